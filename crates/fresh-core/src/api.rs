@@ -253,6 +253,28 @@ pub struct CursorInfo {
     pub selection: Option<Range<usize>>,
 }
 
+/// A byte range within a buffer (end is exclusive)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TextRange {
+    /// Start byte offset
+    pub start: usize,
+    /// End byte offset (exclusive)
+    pub end: usize,
+}
+
+/// A cursor selection expressed as anchor and head byte offsets
+///
+/// When `anchor == position` the selection is empty and only the cursor is placed.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TextSelection {
+    /// Byte offset where the selection started
+    pub anchor: usize,
+    /// Byte offset of the cursor (the moving end of the selection)
+    pub position: usize,
+}
+
 /// Specification for an action to execute, with optional repeat count
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(deny_unknown_fields)]
@@ -758,6 +780,13 @@ pub enum PluginCommand {
         range: Range<usize>,
     },
 
+    /// Replace a range of text in a buffer as a single undoable edit
+    ReplaceRange {
+        buffer_id: BufferId,
+        range: Range<usize>,
+        text: String,
+    },
+
     /// Add an overlay to a buffer, returns handle via response channel
     ///
     /// Colors can be specified as RGB tuples or theme keys. When theme keys
@@ -779,9 +808,39 @@ pub enum PluginCommand {
     /// Set status message
     SetStatus { message: String },
 
+    /// Set the text of a plugin-provided status line segment (`plugin:<id>`).
+    /// Empty text hides the segment.
+    SetStatusSegment { id: String, text: String },
+
+    /// Add a quickfix list, e.g. the results of a project search, as the
+    /// newest in the quickfix history. `selected` is the index of the entry
+    /// the user is at, which `quickfix_next` continues from.
+    SetQuickfixList {
+        title: String,
+        entries: Vec<QuickfixEntry>,
+        selected: Option<usize>,
+    },
+
+    /// Set mark `name` at the cursor: `a`-`z` for the active buffer,
+    /// `A`-`Z` for a file mark
+    SetMark { name: String },
+
+    /// Move the cursor to mark `name`; with `line_start`, to the first
+    /// non-blank character of its line
+    JumpToMark { name: String, line_start: bool },
+
     /// Apply a theme by name
     ApplyTheme { theme_name: String },
 
+    /// Change one color of the active theme (e.g. for a live preview).
+    /// `key` is a theme key such as "editor.bg"; `color` uses the theme file
+    /// format. Lasts until the theme is changed or reloaded.
+    SetThemeColor {
+        key: String,
+        #[ts(type = "[number, number, number] | string")]
+        color: JsonValue,
+    },
+
     /// Reload configuration from file
     /// After a plugin saves config changes, it should call this to reload the config
     ReloadConfig,
@@ -812,6 +871,29 @@ pub enum PluginCommand {
         duration_ms: u64,
     },
 
+    /// A plugin started a background task
+    /// The task id doubles as the callback resolved with the task's result.
+    StartTask {
+        task_id: JsCallbackId,
+        plugin_name: String,
+        name: String,
+    },
+
+    /// Progress report for a running background task
+    UpdateTaskProgress {
+        task_id: JsCallbackId,
+        message: Option<String>,
+        percentage: Option<u32>,
+    },
+
+    /// A background task's job finished, with its result or error
+    FinishTask {
+        task_id: JsCallbackId,
+        #[ts(type = "any")]
+        result: Option<JsonValue>,
+        error: Option<String>,
+    },
+
     /// Spawn a long-running background process
     /// Unlike SpawnProcess, this returns immediately with a process handle
     /// and provides streaming output via hooks
@@ -997,6 +1079,10 @@ pub enum PluginCommand {
         column: Option<usize>, // 1-indexed, None = go to line start
     },
 
+    /// Claim a URI scheme (e.g. "pr" for `pr://owner/repo/123/file`): opening
+    /// such a URI fires the `uri_open_requested` hook instead of reading a file
+    RegisterUriScheme { scheme: String },
+
     /// Start a prompt (minibuffer) with a custom type identifier
     /// This allows plugins to create interactive prompts
     StartPrompt {
@@ -1019,6 +1105,15 @@ pub enum PluginCommand {
         callback_id: JsCallbackId,
     },
 
+    /// Start an async quick pick over a fixed list of items
+    /// Resolves with the picked item's value, or null if cancelled
+    StartQuickPickAsync {
+        title: String,
+        items: Vec<QuickPickItem>,
+        match_on_description: bool,
+        callback_id: JsCallbackId,
+    },
+
     /// Update the suggestions list for the current prompt
     /// Uses the editor's Suggestion type
     SetPromptSuggestions { suggestions: Vec<Suggestion> },
@@ -1073,6 +1168,8 @@ pub enum PluginCommand {
         editing_disabled: bool,
         /// Whether this buffer should be hidden from tabs (for composite source buffers)
         hidden_from_tabs: bool,
+        /// URI the buffer stands for, in a scheme registered with `RegisterUriScheme`
+        uri: Option<String>,
         /// Optional request ID for async response
         request_id: Option<u64>,
     },
@@ -1106,6 +1203,24 @@ pub enum PluginCommand {
         request_id: Option<u64>,
     },
 
+    /// Open (or switch to) a read-only output buffer named `*name*`
+    /// Resolves with the buffer id
+    CreateOutputBuffer { name: String, request_id: u64 },
+
+    /// Append text to the end of an output buffer
+    /// Splits whose cursor was at the end keep following the output
+    AppendOutput { buffer_id: BufferId, text: String },
+
+    /// Remove all text from an output buffer
+    ClearOutput { buffer_id: BufferId },
+
+    /// Ask the user whether a sandboxed plugin may use a permission
+    /// The answer is sent back to the plugin runtime, not to a JS callback.
+    RequestPluginPermission {
+        plugin_name: String,
+        permission: crate::config::PluginPermission,
+    },
+
     /// Set the content of a virtual buffer with text properties
     SetVirtualBufferContent {
         buffer_id: BufferId,
@@ -1222,6 +1337,13 @@ pub enum PluginCommand {
         position: usize,
     },
 
+    /// Replace all cursors in a buffer with the given selections
+    /// The first selection becomes the primary cursor
+    SetSelections {
+        buffer_id: BufferId,
+        selections: Vec<TextSelection>,
+    },
+
     /// Send an arbitrary LSP request and return the raw JSON response
     SendLspRequest {
         language: String,
@@ -1258,6 +1380,10 @@ pub enum PluginCommand {
         action_name: String,
     },
 
+    /// Set the status the editor process exits with (for tools such as git
+    /// that read it, e.g. a mergetool reporting an unresolved merge)
+    SetExitCode { code: i32 },
+
     /// Execute multiple actions in sequence, each with an optional repeat count
     /// Used by vi mode for count prefix (e.g., "3dw" = delete 3 words)
     /// All actions execute atomically with no plugin roundtrips between them
@@ -1500,6 +1626,18 @@ pub struct LanguagePackConfig {
     #[serde(default)]
     pub auto_indent: Option<bool>,
 
+    /// Regex for lines after which the next line is indented one level more
+    #[serde(default)]
+    pub increase_indent_pattern: Option<String>,
+
+    /// Regex for lines that move out one level as they are typed
+    #[serde(default)]
+    pub decrease_indent_pattern: Option<String>,
+
+    /// Path to a tree-sitter indents.scm query replacing the built-in one
+    #[serde(default)]
+    pub indent_query: Option<String>,
+
     /// Whether to show whitespace tab indicators (→) for this language
     /// Defaults to true. Set to false for languages like Go/Hare that use tabs for indentation.
     #[serde(default)]
@@ -1543,6 +1681,11 @@ pub struct LspServerPackConfig {
     #[serde(default)]
     #[ts(type = "Record<string, unknown> | null")]
     pub initialization_options: Option<JsonValue>,
+
+    /// Server name for an additional server of the language (e.g. "tailwindcss").
+    /// When unset, the server becomes the language's primary server.
+    #[serde(default)]
+    pub name: Option<String>,
 }
 
 /// Hunk status for Review Diff
@@ -1594,6 +1737,72 @@ pub struct ActionPopupOptions {
     pub actions: Vec<ActionPopupAction>,
 }
 
+/// Item shown in a quick pick list
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct QuickPickItem {
+    /// Text shown in the list and matched against the filter
+    pub label: String,
+    /// Secondary text shown next to the label
+    #[serde(default)]
+    #[ts(optional)]
+    pub description: Option<String>,
+    /// Value returned when the item is picked (defaults to the label)
+    #[serde(default)]
+    #[ts(optional)]
+    pub value: Option<String>,
+}
+
+/// Entry of a quickfix list
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct QuickfixEntry {
+    /// File path (absolute, or relative to the working directory)
+    pub file: String,
+    /// Line number (1-indexed)
+    pub line: u32,
+    /// Column number (1-indexed, default: 1)
+    #[serde(default)]
+    #[ts(optional)]
+    pub column: Option<u32>,
+    /// Text shown for the entry, such as the matched line or a message
+    #[serde(default)]
+    #[ts(optional)]
+    pub text: Option<String>,
+}
+
+/// Options for showQuickPick
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct QuickPickOptions {
+    /// Prompt label shown before the filter input
+    #[serde(default)]
+    #[ts(optional)]
+    pub title: Option<String>,
+    /// Also match the filter against item descriptions (default: false)
+    #[serde(default, rename = "matchOnDescription")]
+    #[ts(optional, rename = "matchOnDescription")]
+    pub match_on_description: Option<bool>,
+}
+
+/// Options for showInputBox
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct InputBoxOptions {
+    /// Prompt label shown before the input
+    #[serde(default)]
+    #[ts(optional)]
+    pub title: Option<String>,
+    /// Initial text in the input
+    #[serde(default)]
+    #[ts(optional)]
+    pub value: Option<String>,
+}
+
 /// Syntax highlight span for a buffer range
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -1727,6 +1936,11 @@ pub struct CreateVirtualBufferOptions {
     #[serde(default)]
     #[ts(optional)]
     pub entries: Option<Vec<JsTextPropertyEntry>>,
+    /// URI the buffer stands for (e.g. "pr://owner/repo/123/src/main.rs"),
+    /// reported as its path and matched when the URI is opened again
+    #[serde(default)]
+    #[ts(optional)]
+    pub uri: Option<String>,
 }
 
 /// Options for createVirtualBufferInSplit
@@ -1892,6 +2106,66 @@ mod fromjs_impls {
         }
     }
 
+    impl<'js> FromJs<'js> for TextRange {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "TextRange",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
+    impl<'js> FromJs<'js> for TextSelection {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "TextSelection",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
+    impl<'js> FromJs<'js> for QuickPickItem {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "QuickPickItem",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
+    impl<'js> FromJs<'js> for QuickfixEntry {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "QuickfixEntry",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
+    impl<'js> FromJs<'js> for QuickPickOptions {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "QuickPickOptions",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
+    impl<'js> FromJs<'js> for InputBoxOptions {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "InputBoxOptions",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
     impl<'js> FromJs<'js> for ActionPopupAction {
         fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
             rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
@@ -2243,6 +2517,7 @@ impl PluginApi {
             show_cursors: true,
             editing_disabled: false,
             hidden_from_tabs: false,
+            uri: None,
             request_id: None,
         })
     }
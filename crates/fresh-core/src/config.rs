@@ -3,6 +3,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use ts_rs::TS;
 
 fn default_true() -> bool {
     true
@@ -22,6 +23,11 @@ pub struct PluginConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[schemars(extend("readOnly" = true))]
     pub path: Option<PathBuf>,
+
+    /// Permissions the user has permanently granted to this plugin
+    /// Only used for plugins that declare permissions in their package.json.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub granted_permissions: Vec<PluginPermission>,
 }
 
 impl Default for PluginConfig {
@@ -29,6 +35,7 @@ impl Default for PluginConfig {
         Self {
             enabled: true,
             path: None,
+            granted_permissions: Vec::new(),
         }
     }
 }
@@ -38,6 +45,44 @@ impl PluginConfig {
         Self {
             enabled: true,
             path: Some(path),
+            granted_permissions: Vec::new(),
+        }
+    }
+}
+
+/// A capability that a packaged plugin must declare before using it
+///
+/// Plugins shipped with a `package.json` are sandboxed: each sensitive API
+/// call requires the matching permission to be listed in the manifest's
+/// `permissions` array and granted by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export, rename_all = "kebab-case")]
+pub enum PluginPermission {
+    /// Read files and directories
+    FsRead,
+    /// Write files
+    FsWrite,
+    /// Spawn external processes
+    Process,
+    /// Network access
+    Network,
+}
+
+impl PluginPermission {
+    /// Name used in package.json and config files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FsRead => "fs-read",
+            Self::FsWrite => "fs-write",
+            Self::Process => "process",
+            Self::Network => "network",
         }
     }
 }
+
+impl std::fmt::Display for PluginPermission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -112,6 +112,26 @@ pub enum HookArgs {
     /// Editor is initializing
     EditorInitialized,
 
+    /// Two directory trees should be compared (`fresh --diff OLD NEW`)
+    DirectoryDiffRequested { old: PathBuf, new: PathBuf },
+
+    /// A three-way merge should be resolved into `merged`
+    /// (`fresh --merge LOCAL BASE REMOTE MERGED`)
+    MergeRequested {
+        local: PathBuf,
+        base: PathBuf,
+        remote: PathBuf,
+        merged: PathBuf,
+    },
+
+    /// A URI in a scheme registered by a plugin is being opened and no buffer
+    /// shows it yet (line and column are 1-indexed)
+    UriOpenRequested {
+        uri: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+
     /// Rendering is starting for a buffer (called once per buffer before render_line hooks)
     RenderStart { buffer_id: BufferId },
 
@@ -256,6 +276,34 @@ pub enum HookArgs {
         /// The output data
         data: String,
     },
+
+    /// Buffer content changed (fired once per edit, batches included)
+    DidChange {
+        buffer_id: BufferId,
+        /// Changes in the order they were applied; each range refers to the
+        /// buffer as it was after the preceding change
+        changes: Vec<TextChange>,
+    },
+
+    /// The plugin-defined editor mode changed
+    ModeChanged {
+        old_mode: Option<String>,
+        new_mode: Option<String>,
+    },
+
+    /// A saved workspace was restored for the working directory
+    WorkspaceOpened { path: PathBuf },
+}
+
+/// A single edit reported by the DidChange hook
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TextChange {
+    /// Byte offset where the replaced range starts
+    pub start: usize,
+    /// Byte offset where the replaced range ends (exclusive)
+    pub end: usize,
+    /// Text inserted in place of the range (empty for deletions)
+    pub text: String,
 }
 
 /// Information about a single line for the LinesChanged hook
@@ -488,6 +536,32 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
         HookArgs::EditorInitialized => {
             serde_json::json!({})
         }
+        HookArgs::DirectoryDiffRequested { old, new } => {
+            serde_json::json!({
+                "old": old.to_string_lossy(),
+                "new": new.to_string_lossy(),
+            })
+        }
+        HookArgs::MergeRequested {
+            local,
+            base,
+            remote,
+            merged,
+        } => {
+            serde_json::json!({
+                "local": local.to_string_lossy(),
+                "base": base.to_string_lossy(),
+                "remote": remote.to_string_lossy(),
+                "merged": merged.to_string_lossy(),
+            })
+        }
+        HookArgs::UriOpenRequested { uri, line, column } => {
+            serde_json::json!({
+                "uri": uri,
+                "line": line,
+                "column": column,
+            })
+        }
         HookArgs::PromptChanged { prompt_type, input } => {
             serde_json::json!({
                 "prompt_type": prompt_type,
@@ -683,6 +757,21 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
                 "data": data,
             })
         }
+        HookArgs::DidChange { buffer_id, changes } => {
+            serde_json::json!({
+                "buffer_id": buffer_id.0,
+                "changes": changes,
+            })
+        }
+        HookArgs::ModeChanged { old_mode, new_mode } => {
+            serde_json::json!({
+                "old_mode": old_mode,
+                "new_mode": new_mode,
+            })
+        }
+        HookArgs::WorkspaceOpened { path } => {
+            serde_json::json!({ "path": path.to_string_lossy() })
+        }
     };
 
     serde_json::to_string(&json_value)
@@ -12,6 +12,21 @@ pub trait PluginServiceBridge: Send + Sync + 'static {
     /// Get the current locale
     fn current_locale(&self) -> String;
 
+    /// Format a number with the current locale's separators
+    fn format_number(&self, value: f64, decimals: Option<usize>) -> String {
+        match decimals {
+            Some(decimals) => format!("{:.*}", decimals, value),
+            None => value.to_string(),
+        }
+    }
+
+    /// Format a date in the current locale. `format` is a style name
+    /// (e.g. "date", "long_date", "iso_date") or a strftime pattern;
+    /// `timestamp_ms` defaults to now. Returns `None` for an invalid pattern.
+    fn format_date(&self, _format: &str, _timestamp_ms: Option<i64>) -> Option<String> {
+        None
+    }
+
     /// Update the current JavaScript execution state (for debugging/signal handlers)
     fn set_js_execution_state(&self, state: String);
 
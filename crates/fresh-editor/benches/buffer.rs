@@ -0,0 +1,107 @@
+//! Benchmarks for the text buffer: typing, searching and line lookups
+//!
+//!   cargo bench --bench buffer
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use fresh::model::buffer::TextBuffer;
+use fresh::model::filesystem::StdFileSystem;
+use std::hint::black_box;
+use std::sync::Arc;
+
+const TYPED_CHARS: usize = 10_000;
+const LOOKUPS: usize = 1_000;
+
+fn source_text(lines: usize) -> String {
+    (0..lines)
+        .map(|i| format!("    let value_{} = compute({}, \"text\");\n", i, i))
+        .collect()
+}
+
+fn bench_typing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("typing");
+    group.throughput(Throughput::Elements(TYPED_CHARS as u64));
+
+    // One character after another, like typing at the end of a file
+    group.bench_function("append_10k_chars", |b| {
+        b.iter_batched(
+            || TextBuffer::from_bytes(source_text(1_000).into_bytes(), Arc::new(StdFileSystem)),
+            |mut buffer| {
+                for _ in 0..TYPED_CHARS {
+                    let end = buffer.len();
+                    buffer.insert(end, "x");
+                }
+                buffer
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    // Typing in the middle of a file, with a newline every 40 characters
+    group.bench_function("insert_10k_chars_mid_file", |b| {
+        b.iter_batched(
+            || TextBuffer::from_bytes(source_text(100_000).into_bytes(), Arc::new(StdFileSystem)),
+            |mut buffer| {
+                let mut offset = buffer.len() / 2;
+                for i in 0..TYPED_CHARS {
+                    let text = if i % 40 == 39 { "\n" } else { "x" };
+                    buffer.insert(offset, text);
+                    offset += 1;
+                }
+                buffer
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn bench_search(c: &mut Criterion) {
+    let text = source_text(500_000);
+    let len = text.len();
+    let buffer = TextBuffer::from_bytes(text.into_bytes(), Arc::new(StdFileSystem));
+    let mut group = c.benchmark_group("search");
+    group.throughput(Throughput::Bytes(len as u64));
+    group.bench_function("find_next_missing", |b| {
+        b.iter(|| buffer.find_next(black_box("not_in_the_buffer"), 0))
+    });
+    group.bench_function("find_next_near_end", |b| {
+        b.iter(|| buffer.find_next(black_box("value_499999 "), 0))
+    });
+    group.finish();
+}
+
+/// Deterministic spread of `LOOKUPS` positions below `len`
+fn scattered(len: usize) -> impl Iterator<Item = usize> {
+    (0..LOOKUPS).map(move |i| (i * 7_919_993 + 13) % len)
+}
+
+fn bench_line_lookup(c: &mut Criterion) {
+    let lines = 2_000_000;
+    let mut buffer =
+        TextBuffer::from_bytes(source_text(lines).into_bytes(), Arc::new(StdFileSystem));
+    // Split the file into pieces, as editing does
+    for offset in scattered(buffer.len()) {
+        buffer.insert(offset, "y");
+    }
+    let len = buffer.len();
+    let mut group = c.benchmark_group("line_lookup");
+    group.throughput(Throughput::Elements(LOOKUPS as u64));
+    group.bench_function("offset_to_position", |b| {
+        b.iter(|| {
+            for offset in scattered(len) {
+                black_box(buffer.offset_to_position(offset));
+            }
+        })
+    });
+    group.bench_function("line_start_offset", |b| {
+        b.iter(|| {
+            for line in scattered(lines) {
+                black_box(buffer.line_start_offset(line));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_typing, bench_search, bench_line_lookup);
+criterion_main!(benches);
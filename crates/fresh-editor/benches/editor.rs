@@ -0,0 +1,200 @@
+//! Benchmarks for the editor as a whole: opening a large file, typing,
+//! rendering a highlighted file and searching workspace symbols
+//!
+//!   cargo bench --bench editor
+//!
+//! The large file is generated once in the system temp directory. Its size
+//! is `FRESH_BENCH_FILE_MB` megabytes: 1024 by default, and 1 when the
+//! benchmarks only run once as tests (`cargo test --all-targets`).
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::services::workspace_index::WorkspaceIndex;
+use fresh::testing::EditorTestHarness;
+use std::hint::black_box;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const TYPED_CHARS: usize = 10_000;
+
+fn large_file_mb() -> usize {
+    std::env::var("FRESH_BENCH_FILE_MB")
+        .ok()
+        .and_then(|mb| mb.parse().ok())
+        // `cargo bench` passes --bench; `cargo test` doesn't
+        .unwrap_or(if std::env::args().any(|arg| arg == "--bench") {
+            1024
+        } else {
+            1
+        })
+}
+
+/// A file of numbered lines, reused between runs when the size matches
+fn large_file(mb: usize) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("fresh-bench-{}mb.txt", mb));
+    let size = (mb * 1024 * 1024) as u64;
+    if std::fs::metadata(&path).map(|m| m.len()) == Ok(size) {
+        return path;
+    }
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&path).unwrap());
+    let mut written = 0u64;
+    let mut line = 0u64;
+    while written < size {
+        let text = format!(
+            "line {:010} the quick brown fox jumps over the lazy dog\n",
+            line
+        );
+        let text = &text.as_bytes()[..text.len().min((size - written) as usize)];
+        file.write_all(text).unwrap();
+        written += text.len() as u64;
+        line += 1;
+    }
+    file.flush().unwrap();
+    path
+}
+
+/// Rust source with strings, comments, generics and macros on every screen
+fn rust_source(functions: usize) -> String {
+    let mut source = String::new();
+    for i in 0..functions {
+        source.push_str(&format!(
+            r#"/// Parses entry {i} from the "config" section
+pub fn parse_entry_{i}<'a, T: AsRef<str> + 'a>(input: &'a [T]) -> Result<Vec<u64>, String> {{
+    // Skip blank lines and comments
+    let values = input
+        .iter()
+        .filter(|line| !line.as_ref().trim_start().starts_with('#'))
+        .map(|line| line.as_ref().parse::<u64>().map_err(|e| format!("line {{}}: {{e}}", {i})))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(values.len() <= {i} * 0x10, "too many values: {{:?}}", values);
+    Ok(values)
+}}
+
+"#
+        ));
+    }
+    source
+}
+
+/// A workspace of `files` Rust files with twenty functions each
+fn workspace(dir: &Path, files: usize) {
+    for i in 0..files {
+        let module = dir.join(format!("src/module_{:03}", i / 100));
+        std::fs::create_dir_all(&module).unwrap();
+        let source: String = (0..20)
+            .map(|j| {
+                format!(
+                    "pub fn handle_request_{}_{}() {{}}\nstruct Config{}x{};\n",
+                    i, j, i, j
+                )
+            })
+            .collect();
+        std::fs::write(module.join(format!("file_{}.rs", i)), source).unwrap();
+    }
+}
+
+fn wait_until_ready(index: &WorkspaceIndex) {
+    while !index.is_ready() {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn bench_open(c: &mut Criterion) {
+    let mb = large_file_mb();
+    let path = large_file(mb);
+    let mut group = c.benchmark_group("open");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes((mb * 1024 * 1024) as u64));
+    group.bench_function(format!("{}mb_file", mb), |b| {
+        b.iter_batched(
+            || EditorTestHarness::new(120, 40).unwrap(),
+            |mut harness| {
+                harness.open_file(&path).unwrap();
+                harness
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+fn bench_typing(c: &mut Criterion) {
+    let text: String = (0..TYPED_CHARS)
+        .map(|i| if i % 40 == 39 { '\n' } else { 'x' })
+        .collect();
+    let mut group = c.benchmark_group("editor_typing");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(TYPED_CHARS as u64));
+    group.bench_function("type_10k_chars", |b| {
+        b.iter_batched(
+            || {
+                let mut harness = EditorTestHarness::new(120, 40).unwrap();
+                harness.open_text("main.rs", &rust_source(200)).unwrap();
+                harness
+            },
+            |mut harness| {
+                harness.type_text(&text).unwrap();
+                harness
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+fn bench_render(c: &mut Criterion) {
+    let mut harness = EditorTestHarness::new(200, 60).unwrap();
+    harness.open_text("main.rs", &rust_source(2_000)).unwrap();
+
+    let mut group = c.benchmark_group("render");
+    group.bench_function("full_screen_rust", |b| b.iter(|| harness.render().unwrap()));
+
+    // A new screenful every frame, so highlighting isn't served from cache
+    let mut pages = 0;
+    group.bench_function("page_down_rust", |b| {
+        b.iter(|| {
+            pages += 1;
+            if pages % 300 == 0 {
+                harness
+                    .send_key(KeyCode::Home, KeyModifiers::CONTROL)
+                    .unwrap();
+            }
+            harness
+                .send_key(KeyCode::PageDown, KeyModifiers::NONE)
+                .unwrap();
+        })
+    });
+    group.finish();
+}
+
+fn bench_workspace_search(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    workspace(dir.path(), 2_000);
+
+    let mut group = c.benchmark_group("workspace_search");
+    group.sample_size(10);
+    group.bench_function("index_2000_files", |b| {
+        b.iter(|| {
+            let index = WorkspaceIndex::start(dir.path().to_path_buf(), None);
+            wait_until_ready(&index);
+            index
+        })
+    });
+
+    let index = WorkspaceIndex::start(dir.path().to_path_buf(), None);
+    wait_until_ready(&index);
+    group.bench_function("symbols_40k", |b| {
+        b.iter(|| index.search_symbols(black_box("hreq1999"), 50).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_open,
+    bench_typing,
+    bench_render,
+    bench_workspace_search
+);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+//! Run edit scripts decoded from fuzzer input against a buffer
+//!
+//! Every step is checked against a plain byte string, and the whole script
+//! is undone and redone at the end (see `fresh::model::op_script`).
+//!
+//! Run with `cargo +nightly fuzz run buffer_ops` from `crates/fresh-editor`.
+
+#![no_main]
+
+use fresh::model::buffer::TextBuffer;
+use fresh::model::filesystem::StdFileSystem;
+use fresh::model::op_script::OpScript;
+use libfuzzer_sys::fuzz_target;
+use std::sync::Arc;
+
+fuzz_target!(|data: &[u8]| {
+    // The first byte picks the starting text so edits also hit a non-empty
+    // buffer with several lines
+    let (initial, script) = match data.split_first() {
+        Some((&pick, rest)) if pick % 2 == 1 => ("fn main() {\n    let x = 1;\n}\n", rest),
+        Some((_, rest)) => ("", rest),
+        None => ("", data),
+    };
+    let mut buffer = TextBuffer::from_bytes(initial.as_bytes().to_vec(), Arc::new(StdFileSystem));
+    if let Err(violation) = OpScript::from_bytes(script).run(&mut buffer) {
+        panic!("{}", violation);
+    }
+});
@@ -0,0 +1,562 @@
+/// <reference path="./lib/fresh.d.ts" />
+// CSV/TSV Table Mode Plugin
+// Provides a table view for delimited files with:
+// - Columns padded to a common width and separated by │
+// - Tab / Shift+Tab navigation between cells
+// - Sorting rows by the column under the cursor (header row stays in place)
+// - Hiding and showing columns
+//
+// The buffer itself is never reformatted: padding and separators are injected
+// through a view transform, so saving writes the raw delimited text back.
+const editor = getEditor();
+
+
+interface TableConfig {
+  maxColumnWidth: number;
+  hasHeader: boolean;
+}
+
+const config: TableConfig = {
+  maxColumnWidth: 40,
+  hasHeader: true,
+};
+
+const TABLE_MODE = "csv-table";
+
+interface TableState {
+  delimiter: string;
+  // Width of each column in display columns, computed over the whole buffer
+  widths: number[];
+  // Column indices hidden from the view
+  hidden: Set<number>;
+  // Byte offsets of lines that start inside a quoted field
+  quotedLineStarts: Set<number>;
+}
+
+// Buffers currently shown as tables
+const tableBuffers = new Map<number, TableState>();
+
+// =============================================================================
+// Parsing
+// =============================================================================
+
+interface Cell {
+  start: number;               // Byte offset of the first char in the cell
+  end: number;                 // Byte offset after the last char in the cell
+  text: string;                // Raw cell text (quotes included)
+  delimiter: number | null;    // Byte offset of the delimiter after the cell
+}
+
+interface Row {
+  start: number;
+  end: number;                 // Byte offset of the record end (before its line ending)
+  cells: Cell[];
+  raw: string;                 // Record text without its line ending
+  lineEnding: string;          // "\r\n", "\n", or "" for a last line without one
+  quotedLineStarts: number[];  // Byte offsets of the lines after the first
+}
+
+function utf8Length(ch: string): number {
+  const code = ch.codePointAt(0) ?? 0;
+  if (code < 0x80) return 1;
+  if (code < 0x800) return 2;
+  if (code < 0x10000) return 3;
+  return 4;
+}
+
+function displayWidth(text: string): number {
+  return [...text].length;
+}
+
+/**
+ * Split a record into cells. `chars` holds each character with its byte
+ * offset; quoted fields may contain the delimiter and line breaks. Pass
+ * `inQuotes` when `chars` starts inside a quoted field.
+ */
+function splitCells(
+  chars: { ch: string; offset: number }[],
+  lineEnd: number,
+  delimiter: string,
+  inQuotes = false,
+): Cell[] {
+  const cells: Cell[] = [];
+  let cellStart = chars.length > 0 ? chars[0].offset : lineEnd;
+  let text = "";
+
+  for (const { ch, offset } of chars) {
+    if (ch === '"') {
+      inQuotes = !inQuotes;
+    } else if (ch === delimiter && !inQuotes) {
+      cells.push({ start: cellStart, end: offset, text, delimiter: offset });
+      cellStart = offset + utf8Length(ch);
+      text = "";
+      continue;
+    }
+    text += ch;
+  }
+  cells.push({ start: cellStart, end: lineEnd, text, delimiter: null });
+  return cells;
+}
+
+/**
+ * Parse the full buffer text into RFC 4180 records with byte offsets. A line
+ * break inside a quoted field belongs to the record; each record keeps its
+ * own line ending, so files mixing "\n" and "\r\n" parse line by line.
+ */
+function parseTable(text: string, delimiter: string): Row[] {
+  const rows: Row[] = [];
+  let chars: { ch: string; offset: number }[] = [];
+  let raw = "";
+  let quotedLineStarts: number[] = [];
+  let recordStart = 0;
+  let offset = 0;
+  let inQuotes = false;
+
+  const endRecord = (end: number, lineEnding: string) => {
+    rows.push({
+      start: recordStart,
+      end,
+      cells: splitCells(chars, end, delimiter),
+      raw,
+      lineEnding,
+      quotedLineStarts,
+    });
+    chars = [];
+    raw = "";
+    quotedLineStarts = [];
+  };
+
+  for (const ch of text) {
+    if (ch === '"') {
+      inQuotes = !inQuotes;
+    } else if (ch === "\n") {
+      if (!inQuotes) {
+        // Drop the carriage return of CRLF line endings
+        let end = offset;
+        let lineEnding = "\n";
+        if (chars.length > 0 && chars[chars.length - 1].ch === "\r") {
+          end = chars.pop()!.offset;
+          raw = raw.slice(0, -1);
+          lineEnding = "\r\n";
+        }
+        endRecord(end, lineEnding);
+        offset += 1;
+        recordStart = offset;
+        continue;
+      }
+      quotedLineStarts.push(offset + 1);
+    }
+    chars.push({ ch, offset });
+    raw += ch;
+    offset += utf8Length(ch);
+  }
+  if (chars.length > 0) {
+    endRecord(offset, "");
+  }
+  return rows;
+}
+
+function columnWidths(rows: Row[]): number[] {
+  const widths: number[] = [];
+  for (const row of rows) {
+    row.cells.forEach((cell, i) => {
+      // A quoted field with line breaks is as wide as its longest line
+      const longest = Math.max(...cell.text.split("\n").map(displayWidth));
+      const width = Math.min(longest, config.maxColumnWidth);
+      widths[i] = Math.max(widths[i] ?? 0, width);
+    });
+  }
+  return widths;
+}
+
+function delimiterForPath(path: string): string | null {
+  const lower = path.toLowerCase();
+  if (lower.endsWith(".csv")) return ",";
+  if (lower.endsWith(".tsv") || lower.endsWith(".tab")) return "\t";
+  return null;
+}
+
+async function readRows(bufferId: number, state: TableState): Promise<Row[]> {
+  const length = editor.getBufferLength(bufferId);
+  const text = await editor.getBufferText(bufferId, 0, length);
+  return parseTable(text, state.delimiter);
+}
+
+// Recompute column widths from the whole buffer and re-render
+async function refreshTable(bufferId: number): Promise<void> {
+  const state = tableBuffers.get(bufferId);
+  if (!state) return;
+  const rows = await readRows(bufferId, state);
+  // The buffer may have left table mode while the text was being read
+  if (tableBuffers.get(bufferId) !== state) return;
+  state.widths = columnWidths(rows);
+  state.quotedLineStarts = new Set(rows.flatMap((row) => row.quotedLineStarts));
+  editor.refreshLines(bufferId);
+}
+
+// Locate the row and cell containing `position`
+function cellAt(rows: Row[], position: number): { row: number; cell: number } | null {
+  for (let r = 0; r < rows.length; r++) {
+    const row = rows[r];
+    if (position < row.start || position > row.end) continue;
+    for (let c = 0; c < row.cells.length; c++) {
+      const cell = row.cells[c];
+      const cellEnd = cell.delimiter ?? row.end;
+      if (position <= cellEnd) return { row: r, cell: c };
+    }
+  }
+  return null;
+}
+
+// =============================================================================
+// Mode management
+// =============================================================================
+
+function syncEditorMode(bufferId: number): void {
+  if (tableBuffers.has(bufferId)) {
+    editor.setEditorMode(TABLE_MODE);
+  } else if (editor.getEditorMode() === TABLE_MODE) {
+    editor.setEditorMode(null);
+  }
+}
+
+function enableTable(bufferId: number, delimiter: string): void {
+  tableBuffers.set(bufferId, {
+    delimiter,
+    widths: [],
+    hidden: new Set(),
+    quotedLineStarts: new Set(),
+  });
+  // Aligned rows only make sense unwrapped
+  editor.setLineWrap(bufferId, null, false);
+  syncEditorMode(bufferId);
+  refreshTable(bufferId);
+}
+
+function disableTable(bufferId: number): void {
+  tableBuffers.delete(bufferId);
+  editor.clearViewTransform(bufferId, null);
+  syncEditorMode(bufferId);
+  editor.refreshLines(bufferId);
+}
+
+globalThis.csvToggleTable = function(): void {
+  const bufferId = editor.getActiveBufferId();
+  const info = editor.getBufferInfo(bufferId);
+  if (!info) return;
+
+  if (tableBuffers.has(bufferId)) {
+    disableTable(bufferId);
+    editor.setStatus(editor.t("status.table_off"));
+    return;
+  }
+
+  const delimiter = delimiterForPath(info.path);
+  if (delimiter === null) {
+    editor.setStatus(editor.t("status.not_table_file"));
+    return;
+  }
+  enableTable(bufferId, delimiter);
+  editor.setStatus(editor.t("status.table_on"));
+};
+
+// =============================================================================
+// Cell navigation
+// =============================================================================
+
+async function moveToCell(step: 1 | -1): Promise<void> {
+  const bufferId = editor.getActiveBufferId();
+  const state = tableBuffers.get(bufferId);
+  if (!state) return;
+
+  const rows = await readRows(bufferId, state);
+  const here = cellAt(rows, editor.getCursorPosition());
+  if (!here) return;
+
+  // Walk cells in reading order, skipping hidden columns
+  let { row, cell } = here;
+  for (;;) {
+    cell += step;
+    if (cell < 0) {
+      row -= 1;
+      if (row < 0) return;
+      cell = rows[row].cells.length - 1;
+    } else if (cell >= rows[row].cells.length) {
+      row += 1;
+      if (row >= rows.length) return;
+      cell = 0;
+    }
+    if (!state.hidden.has(cell)) break;
+  }
+  editor.setBufferCursor(bufferId, rows[row].cells[cell].start);
+}
+
+globalThis.csv_next_cell = function(): Promise<void> {
+  return moveToCell(1);
+};
+
+globalThis.csv_prev_cell = function(): Promise<void> {
+  return moveToCell(-1);
+};
+
+// =============================================================================
+// Sorting and column visibility
+// =============================================================================
+
+function compareCells(a: string, b: string): number {
+  const unquote = (s: string) => s.trim().replace(/^"(.*)"$/, "$1");
+  const x = unquote(a);
+  const y = unquote(b);
+  const nx = Number(x);
+  const ny = Number(y);
+  if (x !== "" && y !== "" && !isNaN(nx) && !isNaN(ny)) {
+    return nx - ny;
+  }
+  return x.localeCompare(y);
+}
+
+async function sortByColumn(descending: boolean): Promise<void> {
+  const bufferId = editor.getActiveBufferId();
+  const state = tableBuffers.get(bufferId);
+  if (!state) {
+    editor.setStatus(editor.t("status.table_required"));
+    return;
+  }
+
+  const length = editor.getBufferLength(bufferId);
+  const text = await editor.getBufferText(bufferId, 0, length);
+  const rows = parseTable(text, state.delimiter);
+  const here = cellAt(rows, editor.getCursorPosition());
+  if (!here) return;
+  const column = here.cell;
+
+  const headerCount = config.hasHeader ? 1 : 0;
+  const body = rows.slice(headerCount);
+  if (body.length === 0) return;
+
+  const sorted = body
+    .map((row) => ({ row, key: row.cells[column]?.text ?? "" }))
+    .sort((a, b) => {
+      const order = compareCells(a.key, b.key);
+      return descending ? -order : order;
+    })
+    .map((entry) => entry.row);
+  if (sorted.every((row, i) => row === body[i])) return;
+
+  // Whole records move with their own line endings. The record that ends up
+  // last swaps endings with the old last one, so a missing final newline stays
+  // missing.
+  const last = body[body.length - 1];
+  const endings = sorted.map((row) => row.lineEnding);
+  const lastIndex = sorted.indexOf(last);
+  endings[lastIndex] = sorted[sorted.length - 1].lineEnding;
+  endings[sorted.length - 1] = last.lineEnding;
+  const newBody = sorted.map((row, i) => row.raw + endings[i]).join("");
+  const bodyStart = body[0].start;
+  const bodyEnd = last.end + last.lineEnding.length;
+
+  editor.deleteRange(bufferId, bodyStart, bodyEnd);
+  editor.insertText(bufferId, bodyStart, newBody);
+  editor.setBufferCursor(bufferId, 0);
+  editor.setStatus(editor.t(descending ? "status.sorted_desc" : "status.sorted_asc", {
+    column: String(column + 1),
+  }));
+}
+
+globalThis.csvSortAscending = function(): Promise<void> {
+  return sortByColumn(false);
+};
+
+globalThis.csvSortDescending = function(): Promise<void> {
+  return sortByColumn(true);
+};
+
+globalThis.csvHideColumn = async function(): Promise<void> {
+  const bufferId = editor.getActiveBufferId();
+  const state = tableBuffers.get(bufferId);
+  if (!state) {
+    editor.setStatus(editor.t("status.table_required"));
+    return;
+  }
+
+  const rows = await readRows(bufferId, state);
+  const here = cellAt(rows, editor.getCursorPosition());
+  if (!here) return;
+  state.hidden.add(here.cell);
+  editor.refreshLines(bufferId);
+  editor.setStatus(editor.t("status.column_hidden", { column: String(here.cell + 1) }));
+
+  // Keep the cursor out of the hidden column
+  await moveToCell(1);
+};
+
+globalThis.csvShowAllColumns = function(): void {
+  const bufferId = editor.getActiveBufferId();
+  const state = tableBuffers.get(bufferId);
+  if (!state) {
+    editor.setStatus(editor.t("status.table_required"));
+    return;
+  }
+  state.hidden.clear();
+  editor.refreshLines(bufferId);
+  editor.setStatus(editor.t("status.columns_shown"));
+};
+
+// =============================================================================
+// View transform
+// =============================================================================
+
+/**
+ * Lay out the incoming tokens as a table. Cell text keeps its source offsets
+ * so the cursor and mouse still map onto the raw buffer; padding is injected
+ * and each delimiter is drawn as │ at its own offset.
+ */
+function transformTableTokens(tokens: ViewTokenWire[], state: TableState): ViewTokenWire[] {
+  const output: ViewTokenWire[] = [];
+  let chars: { ch: string; offset: number }[] = [];
+
+  const flushLine = (lineEnd: number) => {
+    const lineStart = chars.length > 0 ? chars[0].offset : lineEnd;
+    const cells = splitCells(chars, lineEnd, state.delimiter, state.quotedLineStarts.has(lineStart));
+    chars = [];
+    const visible = cells
+      .map((cell, index) => ({ cell, index }))
+      .filter(({ index }) => !state.hidden.has(index));
+
+    visible.forEach(({ cell, index }, i) => {
+      if (cell.text.length > 0) {
+        output.push({ source_offset: cell.start, kind: { Text: cell.text } });
+      }
+      const isLast = i === visible.length - 1;
+      if (isLast) return;
+
+      const padding = (state.widths[index] ?? 0) - displayWidth(cell.text);
+      for (let p = 0; p < padding; p++) {
+        output.push({ source_offset: null, kind: "Space" });
+      }
+      output.push({ source_offset: null, kind: "Space" });
+      output.push({ source_offset: cell.delimiter, kind: { Text: "│" } });
+      output.push({ source_offset: null, kind: "Space" });
+    });
+  };
+
+  for (const token of tokens) {
+    const kind = token.kind;
+    const offset = token.source_offset;
+    if (kind === "Newline") {
+      flushLine(offset ?? 0);
+      output.push(token);
+    } else if (offset === null) {
+      // Injected content from the core is passed through unchanged
+      output.push(token);
+    } else if (kind === "Space") {
+      chars.push({ ch: " ", offset });
+    } else if (typeof kind === "object" && "Text" in kind) {
+      let byte = offset;
+      for (const ch of kind.Text) {
+        chars.push({ ch, offset: byte });
+        byte += utf8Length(ch);
+      }
+    }
+    // Soft breaks and binary bytes are dropped; tables are never wrapped
+  }
+  if (chars.length > 0) {
+    const last = chars[chars.length - 1];
+    flushLine(last.offset + utf8Length(last.ch));
+  }
+  return output;
+}
+
+globalThis.onCsvViewTransform = function(data: {
+  buffer_id: number;
+  split_id: number;
+  viewport_start: number;
+  viewport_end: number;
+  tokens: ViewTokenWire[];
+}): void {
+  const state = tableBuffers.get(data.buffer_id);
+  if (!state) return;
+
+  const layoutHints: LayoutHints = {
+    composeWidth: null,
+    columnGuides: null,
+  };
+
+  editor.submitViewTransform(
+    data.buffer_id,
+    data.split_id,
+    data.viewport_start,
+    data.viewport_end,
+    transformTableTokens(data.tokens, state),
+    layoutHints
+  );
+};
+
+// =============================================================================
+// Event handlers
+// =============================================================================
+
+globalThis.onCsvBufferChanged = function(data: { buffer_id: number }): void {
+  if (tableBuffers.has(data.buffer_id)) {
+    refreshTable(data.buffer_id);
+  }
+};
+
+globalThis.onCsvBufferActivated = function(data: { buffer_id: number }): void {
+  syncEditorMode(data.buffer_id);
+};
+
+globalThis.onCsvBufferClosed = function(data: { buffer_id: number }): void {
+  tableBuffers.delete(data.buffer_id);
+};
+
+editor.on("view_transform_request", "onCsvViewTransform");
+editor.on("after_insert", "onCsvBufferChanged");
+editor.on("after_delete", "onCsvBufferChanged");
+editor.on("buffer_activated", "onCsvBufferActivated");
+editor.on("buffer_closed", "onCsvBufferClosed");
+
+// Table mode passes typing through and only claims the cell navigation keys
+editor.defineMode(TABLE_MODE, null, [
+  ["Tab", "csv_next_cell"],
+  ["BackTab", "csv_prev_cell"],
+  ["S-BackTab", "csv_prev_cell"],
+], false);
+
+// Register commands
+editor.registerCommand(
+  "%cmd.toggle_table",
+  "%cmd.toggle_table_desc",
+  "csvToggleTable",
+  null
+);
+
+editor.registerCommand(
+  "%cmd.sort_ascending",
+  "%cmd.sort_ascending_desc",
+  "csvSortAscending",
+  null
+);
+
+editor.registerCommand(
+  "%cmd.sort_descending",
+  "%cmd.sort_descending_desc",
+  "csvSortDescending",
+  null
+);
+
+editor.registerCommand(
+  "%cmd.hide_column",
+  "%cmd.hide_column_desc",
+  "csvHideColumn",
+  null
+);
+
+editor.registerCommand(
+  "%cmd.show_columns",
+  "%cmd.show_columns_desc",
+  "csvShowAllColumns",
+  null
+);
+
+editor.debug("CSV table plugin loaded - use 'CSV: Toggle Table Mode' command");
@@ -0,0 +1,429 @@
+/// <reference path="./lib/fresh.d.ts" />
+
+/**
+ * Directory Diff Plugin
+ *
+ * Compares two directory trees, such as two extracted releases or a backup
+ * and the tree it was taken from. `fresh --diff OLD NEW` opens the
+ * comparison at startup, and "Compare Directories..." asks for the two
+ * directories. The listing has the old tree on the left and the new tree on
+ * the right, with a row for each file that differs: changed files appear on
+ * both sides, removed files only on the left and added files only on the
+ * right. Enter on a row opens the file in the diff view, `r` compares again
+ * and `q` closes the listing. `.git` directories and symlinks are skipped.
+ */
+
+import { diffLines } from "./lib/line-diff.ts";
+
+const editor = getEditor();
+
+const DIR_DIFF_MODE = "dir-diff";
+const NAMESPACE = "dir-diff";
+
+/** Files per tree past which the walk stops */
+const MAX_FILES = 20000;
+
+/** Widest the left column gets before long paths push the separator out */
+const MAX_COLUMN_WIDTH = 60;
+
+const SKIPPED_DIRS = new Set([".git"]);
+
+const colors = {
+  header: [255, 200, 100] as [number, number, number],
+  changed: [220, 190, 90] as [number, number, number],
+  added: [110, 200, 110] as [number, number, number],
+  removed: [220, 100, 100] as [number, number, number],
+};
+
+type FileStatus = "changed" | "added" | "removed";
+
+interface DiffEntry {
+  /** Path from the roots of both trees, with forward slashes */
+  path: string;
+  status: FileStatus;
+}
+
+interface Comparison {
+  entries: DiffEntry[];
+  identical: number;
+  truncated: boolean;
+}
+
+interface ListingState {
+  bufferId: number;
+  oldDir: string;
+  newDir: string;
+}
+
+interface FileDiffState {
+  compositeBufferId: number;
+  oldBufferId: number;
+  newBufferId: number;
+}
+
+let listing: ListingState | null = null;
+let activeDiff: FileDiffState | null = null;
+
+// =============================================================================
+// Comparison
+// =============================================================================
+
+function isDirectory(path: string): boolean {
+  const stat = editor.fileStat(path) as { isDir: boolean } | null;
+  return stat?.isDir ?? false;
+}
+
+function fileSize(path: string): number {
+  const stat = editor.fileStat(path) as { size: number } | null;
+  return stat?.size ?? -1;
+}
+
+/**
+ * Files under `root` as paths from it, sorted; stops at `MAX_FILES` and
+ * reports whether it did
+ */
+function listFiles(root: string): { files: string[]; truncated: boolean } {
+  const files: string[] = [];
+  const pending = [""];
+  while (pending.length > 0) {
+    const dir = pending.pop()!;
+    for (const entry of editor.readDir(dir ? editor.pathJoin(root, dir) : root)) {
+      const path = dir ? `${dir}/${entry.name}` : entry.name;
+      if (entry.is_dir && !SKIPPED_DIRS.has(entry.name)) {
+        pending.push(path);
+      } else if (entry.is_file) {
+        if (files.length >= MAX_FILES) {
+          return { files: files.sort(), truncated: true };
+        }
+        files.push(path);
+      }
+    }
+  }
+  return { files: files.sort(), truncated: false };
+}
+
+function sameContent(oldPath: string, newPath: string): boolean {
+  if (fileSize(oldPath) !== fileSize(newPath)) {
+    return false;
+  }
+  return editor.readFile(oldPath) === editor.readFile(newPath);
+}
+
+function compareTrees(oldDir: string, newDir: string): Comparison {
+  const oldFiles = listFiles(oldDir);
+  const newFiles = listFiles(newDir);
+  const inNew = new Set(newFiles.files);
+  const inOld = new Set(oldFiles.files);
+
+  const entries: DiffEntry[] = [];
+  let identical = 0;
+  for (const path of oldFiles.files) {
+    if (!inNew.has(path)) {
+      entries.push({ path, status: "removed" });
+    } else if (sameContent(editor.pathJoin(oldDir, path), editor.pathJoin(newDir, path))) {
+      identical++;
+    } else {
+      entries.push({ path, status: "changed" });
+    }
+  }
+  for (const path of newFiles.files) {
+    if (!inOld.has(path)) {
+      entries.push({ path, status: "added" });
+    }
+  }
+  entries.sort((a, b) => (a.path < b.path ? -1 : a.path > b.path ? 1 : 0));
+  return { entries, identical, truncated: oldFiles.truncated || newFiles.truncated };
+}
+
+// =============================================================================
+// Listing
+// =============================================================================
+
+function utf8Length(text: string): number {
+  let length = 0;
+  for (const ch of text) {
+    const code = ch.codePointAt(0)!;
+    length += code < 0x80 ? 1 : code < 0x800 ? 2 : code < 0x10000 ? 3 : 4;
+  }
+  return length;
+}
+
+const MARKS: Record<FileStatus, string> = { changed: "M", added: "+", removed: "-" };
+
+/** Listing rows and the overlays that color them */
+function buildListing(
+  oldDir: string,
+  newDir: string,
+  comparison: Comparison,
+): { entries: TextPropertyEntry[]; styles: Array<[number, number, [number, number, number]]> } {
+  const width = Math.min(
+    MAX_COLUMN_WIDTH,
+    Math.max(oldDir.length, ...comparison.entries.map((e) => e.path.length + 2)),
+  );
+  const pad = (text: string) => text + " ".repeat(Math.max(0, width - text.length));
+
+  const entries: TextPropertyEntry[] = [];
+  const styles: Array<[number, number, [number, number, number]]> = [];
+  let offset = 0;
+  const push = (text: string, properties: Record<string, unknown>, color?: [number, number, number]) => {
+    const line = text + "\n";
+    if (color) {
+      styles.push([offset, offset + utf8Length(text), color]);
+    }
+    entries.push({ text: line, properties });
+    offset += utf8Length(line);
+  };
+
+  const counts = { changed: 0, added: 0, removed: 0 };
+  for (const entry of comparison.entries) {
+    counts[entry.status]++;
+  }
+  push(editor.t("listing.summary", {
+    changed: String(counts.changed),
+    added: String(counts.added),
+    removed: String(counts.removed),
+    identical: String(comparison.identical),
+  }), { type: "summary" });
+  if (comparison.truncated) {
+    push(editor.t("listing.truncated", { max: String(MAX_FILES) }), { type: "summary" });
+  }
+  push("", { type: "blank" });
+  push(` ${pad(oldDir)} │ ${newDir}`, { type: "header" }, colors.header);
+  push(`─${"─".repeat(width)}─┼─${"─".repeat(Math.max(width, newDir.length))}`, { type: "header" });
+
+  if (comparison.entries.length === 0) {
+    push(editor.t("listing.identical"), { type: "blank" });
+  }
+  for (const entry of comparison.entries) {
+    const cell = `${MARKS[entry.status]} ${entry.path}`;
+    const left = entry.status === "added" ? "" : cell;
+    const right = entry.status === "removed" ? "" : cell;
+    push(` ${pad(left)} │ ${right}`, { type: "file", path: entry.path, status: entry.status }, colors[entry.status]);
+  }
+  return { entries, styles };
+}
+
+async function showListing(oldDir: string, newDir: string): Promise<void> {
+  editor.setStatus(editor.t("status.comparing"));
+  const comparison = compareTrees(oldDir, newDir);
+  const { entries, styles } = buildListing(oldDir, newDir, comparison);
+
+  let bufferId: number;
+  if (listing) {
+    bufferId = listing.bufferId;
+    editor.setVirtualBufferContent(bufferId, entries);
+    editor.showBuffer(bufferId);
+  } else {
+    const result = await editor.createVirtualBuffer({
+      name: "*Directory Diff*",
+      mode: DIR_DIFF_MODE,
+      readOnly: true,
+      entries,
+      showLineNumbers: false,
+      editingDisabled: true,
+    });
+    bufferId = result.bufferId;
+  }
+  listing = { bufferId, oldDir, newDir };
+
+  editor.clearNamespace(bufferId, NAMESPACE);
+  for (const [start, end, color] of styles) {
+    editor.addOverlay(bufferId, NAMESPACE, start, end, { fg: color });
+  }
+  editor.setStatus(editor.t("status.compared", { count: String(comparison.entries.length) }));
+}
+
+/** Start a comparison after checking both paths are directories */
+async function compareDirectories(oldDir: string, newDir: string): Promise<void> {
+  for (const dir of [oldDir, newDir]) {
+    if (!isDirectory(dir)) {
+      editor.setStatus(editor.t("status.not_a_directory", { path: dir }));
+      return;
+    }
+  }
+  await showListing(oldDir, newDir);
+}
+
+// =============================================================================
+// File diff
+// =============================================================================
+
+function closeFileDiff(): void {
+  if (!activeDiff) {
+    return;
+  }
+  try {
+    editor.closeCompositeBuffer(activeDiff.compositeBufferId);
+    editor.closeBuffer(activeDiff.oldBufferId);
+    editor.closeBuffer(activeDiff.newBufferId);
+  } catch {}
+  activeDiff = null;
+}
+
+function lineEntries(lines: string[]): TextPropertyEntry[] {
+  return lines.map((line, idx) => ({
+    text: line + "\n",
+    properties: { type: "line", lineNum: idx + 1 },
+  }));
+}
+
+/** Lines of `path`, or none for a file that side doesn't have */
+function fileLines(path: string, present: boolean): string[] {
+  if (!present) {
+    return [];
+  }
+  return (editor.readFile(path) ?? "").split("\n");
+}
+
+async function openFileDiff(entry: DiffEntry, oldDir: string, newDir: string): Promise<void> {
+  const oldLines = fileLines(editor.pathJoin(oldDir, entry.path), entry.status !== "added");
+  const newLines = fileLines(editor.pathJoin(newDir, entry.path), entry.status !== "removed");
+  const hunks = diffLines(oldLines, newLines);
+
+  closeFileDiff();
+  const oldResult = await editor.createVirtualBuffer({
+    name: `*Old:${entry.path}*`,
+    mode: "normal",
+    readOnly: true,
+    entries: lineEntries(oldLines),
+    showLineNumbers: true,
+    editingDisabled: true,
+    hiddenFromTabs: true,
+  });
+  const newResult = await editor.createVirtualBuffer({
+    name: `*New:${entry.path}*`,
+    mode: "normal",
+    readOnly: true,
+    entries: lineEntries(newLines),
+    showLineNumbers: true,
+    editingDisabled: true,
+    hiddenFromTabs: true,
+  });
+
+  const compositeBufferId = await editor.createCompositeBuffer({
+    name: `*Diff: ${entry.path}*`,
+    mode: "diff-view",
+    layout: {
+      type: "side-by-side",
+      ratios: [0.5, 0.5],
+      showSeparator: true,
+    },
+    sources: [
+      {
+        bufferId: oldResult.bufferId,
+        label: oldDir,
+        editable: false,
+        style: { removeBg: [80, 40, 40], gutterStyle: "diff-markers" },
+      },
+      {
+        bufferId: newResult.bufferId,
+        label: newDir,
+        editable: false,
+        style: { addBg: [40, 80, 40], gutterStyle: "diff-markers" },
+      },
+    ],
+    hunks: hunks.length > 0 ? hunks : null,
+  });
+
+  activeDiff = {
+    compositeBufferId,
+    oldBufferId: oldResult.bufferId,
+    newBufferId: newResult.bufferId,
+  };
+  editor.showBuffer(compositeBufferId);
+  editor.setStatus(editor.t("status.file_diff", { path: entry.path, count: String(hunks.length) }));
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+function absolutePath(path: string): string {
+  return editor.pathIsAbsolute(path) ? path : editor.pathJoin(editor.getCwd(), path);
+}
+
+globalThis.dir_diff_compare = async function (): Promise<void> {
+  const oldDir = await editor.showInputBox({
+    title: editor.t("prompt.old_dir"),
+    value: listing?.oldDir ?? editor.getCwd(),
+  });
+  if (!oldDir?.trim()) {
+    return;
+  }
+  const newDir = await editor.showInputBox({
+    title: editor.t("prompt.new_dir"),
+    value: listing?.newDir ?? editor.getCwd(),
+  });
+  if (!newDir?.trim()) {
+    return;
+  }
+  await compareDirectories(absolutePath(oldDir.trim()), absolutePath(newDir.trim()));
+};
+
+globalThis.dir_diff_open = async function (): Promise<void> {
+  if (!listing) {
+    return;
+  }
+  const props = editor.getTextPropertiesAtCursor(listing.bufferId);
+  const file = props.find((p) => p.type === "file");
+  if (!file) {
+    return;
+  }
+  const entry = { path: file.path as string, status: file.status as FileStatus };
+  await openFileDiff(entry, listing.oldDir, listing.newDir);
+};
+
+globalThis.dir_diff_refresh = async function (): Promise<void> {
+  if (listing) {
+    await compareDirectories(listing.oldDir, listing.newDir);
+  }
+};
+
+globalThis.dir_diff_close = function (): void {
+  if (!listing) {
+    return;
+  }
+  closeFileDiff();
+  editor.closeBuffer(listing.bufferId);
+  listing = null;
+};
+
+// =============================================================================
+// Event handlers
+// =============================================================================
+
+/** `fresh --diff OLD NEW` */
+globalThis.onDirDiffRequested = async function (data: { old: string; new: string }): Promise<void> {
+  await compareDirectories(data.old, data.new);
+};
+
+globalThis.onDirDiffBufferClosed = function (data: { buffer_id: number }): void {
+  if (listing && data.buffer_id === listing.bufferId) {
+    listing = null;
+  }
+  if (activeDiff && data.buffer_id === activeDiff.compositeBufferId) {
+    try {
+      editor.closeBuffer(activeDiff.oldBufferId);
+      editor.closeBuffer(activeDiff.newBufferId);
+    } catch {}
+    activeDiff = null;
+  }
+};
+
+editor.on("directory_diff_requested", "onDirDiffRequested");
+editor.on("buffer_closed", "onDirDiffBufferClosed");
+
+editor.defineMode(DIR_DIFF_MODE, "normal", [
+  ["RET", "dir_diff_open"],
+  ["r", "dir_diff_refresh"],
+  ["q", "dir_diff_close"],
+], true);
+
+editor.registerCommand(
+  "%cmd.compare_directories",
+  "%cmd.compare_directories_desc",
+  "dir_diff_compare",
+  null
+);
+
+editor.debug("Directory Diff plugin loaded");
@@ -0,0 +1,257 @@
+/// <reference path="./lib/fresh.d.ts" />
+
+/**
+ * Git Compare Plugin
+ *
+ * "Compare With Revision..." diffs the active buffer against its file as it
+ * was at a git revision. The revision is picked from the branches, tags and
+ * the commits that touched the file, or typed as any ref git understands
+ * (`HEAD~3`, `origin/main`, a hash). The old version is read with `git show`
+ * through the editor's process spawner, so remote files work too. Both
+ * versions open side by side in the diff view, where `n`/`p` (or `]`/`[`)
+ * move between hunks. The right side is the buffer, unsaved changes
+ * included.
+ */
+
+import { diffLines } from "./lib/line-diff.ts";
+
+const editor = getEditor();
+
+/** Quick pick value that asks for a ref to type */
+const OTHER_REF = "\u0000other";
+
+/** Commits of the file offered in the picker, newest first */
+const MAX_COMMITS = 200;
+
+interface FileLocation {
+  gitRoot: string;
+  /** Path from the repository root, with forward slashes */
+  relativePath: string;
+}
+
+interface CompareState {
+  compositeBufferId: number;
+  oldBufferId: number;
+  newBufferId: number;
+}
+
+let activeCompare: CompareState | null = null;
+
+// =============================================================================
+// Git
+// =============================================================================
+
+/** Repository root and repository path of `path`, or null outside a repo */
+async function locateFile(path: string): Promise<FileLocation | null> {
+  const dir = editor.pathDirname(path);
+  const root = await editor.spawnProcess("git", ["-C", dir, "rev-parse", "--show-toplevel"]);
+  if (root.exit_code !== 0) {
+    return null;
+  }
+  // The prefix is the directory's path from the root ("" at the root)
+  const prefix = await editor.spawnProcess("git", ["-C", dir, "rev-parse", "--show-prefix"]);
+  if (prefix.exit_code !== 0) {
+    return null;
+  }
+  return {
+    gitRoot: root.stdout.trim(),
+    relativePath: prefix.stdout.trim() + editor.pathBasename(path),
+  };
+}
+
+/** Branches, tags and recent commits of the file for the picker */
+async function revisionItems(location: FileLocation): Promise<QuickPickItem[]> {
+  const items: QuickPickItem[] = [
+    { label: editor.t("pick.other"), description: editor.t("pick.other_desc"), value: OTHER_REF },
+  ];
+
+  const refs = await editor.spawnProcess("git", [
+    "-C", location.gitRoot,
+    "for-each-ref", "--format=%(refname:short)%09%(refname)",
+    "refs/heads", "refs/tags", "refs/remotes",
+  ]);
+  if (refs.exit_code === 0) {
+    for (const line of refs.stdout.split("\n")) {
+      const [name, full] = line.trim().split("\t");
+      if (!name || !full || full.endsWith("/HEAD")) {
+        continue;
+      }
+      const kind = full.startsWith("refs/tags/")
+        ? "pick.tag"
+        : full.startsWith("refs/remotes/")
+          ? "pick.remote_branch"
+          : "pick.branch";
+      items.push({ label: name, description: editor.t(kind) });
+    }
+  }
+
+  const log = await editor.spawnProcess("git", [
+    "-C", location.gitRoot,
+    "log", `-n${MAX_COMMITS}`, "--date=short", "--format=%h%x09%ad%x09%an%x09%s",
+    "--", location.relativePath,
+  ]);
+  if (log.exit_code === 0) {
+    for (const line of log.stdout.split("\n")) {
+      const [hash, date, author, ...subject] = line.trim().split("\t");
+      if (!hash) {
+        continue;
+      }
+      items.push({ label: hash, description: `${date} ${author}: ${subject.join("\t")}` });
+    }
+  }
+  return items;
+}
+
+// =============================================================================
+// Diff view
+// =============================================================================
+
+function closeCompare(): void {
+  if (!activeCompare) {
+    return;
+  }
+  try {
+    editor.closeCompositeBuffer(activeCompare.compositeBufferId);
+    editor.closeBuffer(activeCompare.oldBufferId);
+    editor.closeBuffer(activeCompare.newBufferId);
+  } catch {}
+  activeCompare = null;
+}
+
+function lineEntries(lines: string[]): TextPropertyEntry[] {
+  return lines.map((line, idx) => ({
+    text: line + "\n",
+    properties: { type: "line", lineNum: idx + 1 },
+  }));
+}
+
+/** Open the diff view between `rev`'s version of the file and the buffer */
+async function compareWithRevision(
+  bufferId: number,
+  location: FileLocation,
+  rev: string,
+): Promise<void> {
+  const show = await editor.spawnProcess("git", [
+    "-C", location.gitRoot, "show", `${rev}:${location.relativePath}`,
+  ]);
+  if (show.exit_code !== 0) {
+    editor.setStatus(editor.t("status.not_at_revision", { file: location.relativePath, rev }));
+    return;
+  }
+  const oldLines = show.stdout.split("\n");
+  const text = await editor.getBufferText(bufferId, 0, editor.getBufferLength(bufferId));
+  const newLines = text.split("\n");
+
+  const hunks = diffLines(oldLines, newLines);
+  if (hunks.length === 0) {
+    editor.setStatus(editor.t("status.identical", { rev }));
+    return;
+  }
+
+  closeCompare();
+  const name = location.relativePath;
+  const oldResult = await editor.createVirtualBuffer({
+    name: `*${rev}:${name}*`,
+    mode: "normal",
+    readOnly: true,
+    entries: lineEntries(oldLines),
+    showLineNumbers: true,
+    editingDisabled: true,
+    hiddenFromTabs: true,
+  });
+  const newResult = await editor.createVirtualBuffer({
+    name: `*Buffer:${name}*`,
+    mode: "normal",
+    readOnly: true,
+    entries: lineEntries(newLines),
+    showLineNumbers: true,
+    editingDisabled: true,
+    hiddenFromTabs: true,
+  });
+
+  const compositeBufferId = await editor.createCompositeBuffer({
+    name: `*Compare: ${name} @ ${rev}*`,
+    mode: "diff-view",
+    layout: {
+      type: "side-by-side",
+      ratios: [0.5, 0.5],
+      showSeparator: true,
+    },
+    sources: [
+      {
+        bufferId: oldResult.bufferId,
+        label: rev,
+        editable: false,
+        style: { removeBg: [80, 40, 40], gutterStyle: "diff-markers" },
+      },
+      {
+        bufferId: newResult.bufferId,
+        label: editor.t("label.buffer"),
+        editable: false,
+        style: { addBg: [40, 80, 40], gutterStyle: "diff-markers" },
+      },
+    ],
+    hunks,
+  });
+
+  activeCompare = {
+    compositeBufferId,
+    oldBufferId: oldResult.bufferId,
+    newBufferId: newResult.bufferId,
+  };
+  editor.showBuffer(compositeBufferId);
+  editor.setStatus(editor.t("status.compared", { rev, count: String(hunks.length) }));
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+globalThis.git_compare_with_revision = async function (): Promise<void> {
+  const bufferId = editor.getActiveBufferId();
+  const path = editor.getBufferPath(bufferId);
+  if (!path) {
+    editor.setStatus(editor.t("status.no_file"));
+    return;
+  }
+  const location = await locateFile(path);
+  if (!location) {
+    editor.setStatus(editor.t("status.not_in_repo"));
+    return;
+  }
+
+  const items = await revisionItems(location);
+  let rev = await editor.showQuickPick(items, {
+    title: editor.t("prompt.revision"),
+    matchOnDescription: true,
+  });
+  if (rev === OTHER_REF) {
+    rev = await editor.showInputBox({ title: editor.t("prompt.ref"), value: "HEAD~1" });
+  }
+  rev = rev?.trim() ?? "";
+  if (!rev) {
+    return;
+  }
+  await compareWithRevision(bufferId, location, rev);
+};
+
+globalThis.git_compare_on_buffer_closed = (data: { buffer_id: number }) => {
+  if (activeCompare && data.buffer_id === activeCompare.compositeBufferId) {
+    try {
+      editor.closeBuffer(activeCompare.oldBufferId);
+      editor.closeBuffer(activeCompare.newBufferId);
+    } catch {}
+    activeCompare = null;
+  }
+};
+
+editor.on("buffer_closed", "git_compare_on_buffer_closed");
+
+editor.registerCommand(
+  "%cmd.compare_with_revision",
+  "%cmd.compare_with_revision_desc",
+  "git_compare_with_revision",
+  null
+);
+
+editor.debug("Git Compare plugin loaded");
@@ -36,6 +36,7 @@ const finder = new Finder<GrepMatch>(editor, {
   }),
   preview: true,
   maxResults: 100,
+  quickfixTitle: (query) => `git grep ${query}`,
 });
 
 // Search function using git grep
@@ -112,6 +112,13 @@ export interface FinderConfig<T> {
 
   /** Panel-specific: sync cursor with editor */
   syncWithEditor?: boolean;
+
+  /** Panel-specific: extra [key, handler name] bindings besides Enter and Escape */
+  panelKeys?: Array<[string, string]>;
+
+  /** Prompt-specific: when a result is picked, the results become a quickfix
+   * list with this title (built from the query) */
+  quickfixTitle?: (query: string) => string;
 }
 
 /**
@@ -433,6 +440,19 @@ export class Finder<T> {
     return this.isPromptMode || this.isPanelMode;
   }
 
+  /**
+   * Item under the cursor in the panel, if any
+   */
+  get selectedItem(): T | undefined {
+    if (!this.isPanelMode) return undefined;
+    const itemIndex = this.panelState.lineToItemIndex.get(
+      this.panelState.cursorLine
+    );
+    return itemIndex === undefined
+      ? undefined
+      : this.panelState.items[itemIndex];
+  }
+
   /**
    * Start interactive prompt mode
    */
@@ -803,6 +823,10 @@ export class Finder<T> {
       const item = this.promptState.results[selectedIndex];
       const entry = this.promptState.entries[selectedIndex];
 
+      if (this.config.quickfixTitle) {
+        this.setQuickfixList(selectedIndex);
+      }
+
       if (this.config.onSelect) {
         this.config.onSelect(item, entry);
       } else if (entry.location) {
@@ -827,6 +851,36 @@ export class Finder<T> {
     this.promptState.originalSplitId = null;
   }
 
+  /**
+   * Make the prompt results with a location the newest quickfix list, so
+   * Quickfix: Next Item continues from the picked one
+   */
+  private setQuickfixList(selectedIndex: number): void {
+    const entries: QuickfixEntry[] = [];
+    let selected: number | undefined;
+    this.promptState.entries.forEach((entry, i) => {
+      if (!entry.location) {
+        return;
+      }
+      if (i === selectedIndex) {
+        selected = entries.length;
+      }
+      entries.push({
+        file: entry.location.file,
+        line: entry.location.line,
+        column: entry.location.column,
+        text: entry.description,
+      });
+    });
+    if (entries.length > 0 && this.config.quickfixTitle) {
+      this.editor.setQuickfixList(
+        this.config.quickfixTitle(this.promptState.lastQuery),
+        entries,
+        selected
+      );
+    }
+  }
+
   private onPromptCancelled(): void {
     // Kill any running search
     if (this.promptState.currentSearch) {
@@ -977,6 +1031,7 @@ export class Finder<T> {
       [
         ["Return", `${this.handlerPrefix}_panel_select`],
         ["Escape", `${this.handlerPrefix}_panel_close`],
+        ...(this.config.panelKeys ?? []),
       ],
       true
     );
@@ -23,6 +23,19 @@ interface ProcessHandle<T> extends PromiseLike<T> {
 	/** Cancel/kill the operation. Returns true if cancelled, false if already completed */
 	kill(): Promise<boolean>;
 }
+/** Handle passed to a `runTask` job */
+interface TaskHandle {
+	/** Task identifier */
+	readonly id: number;
+	/** True once the user has cancelled the task */
+	readonly cancelled: boolean;
+	/** Show progress in the status bar (percentage is 0-100) */
+	progress(message?: string | null, percentage?: number | null): void;
+}
+/** Job run by `runTask`; its return value (or resolved value) is the task result */
+type TaskJob = (task: TaskHandle) => unknown;
+/** Theme color: `[r, g, b]` or a named color such as "Red" or "Default" */
+type ThemeColor = [number, number, number] | string;
 /** Buffer identifier */
 type BufferId = number;
 /** Split identifier */
@@ -532,6 +545,21 @@ type CreateVirtualBufferOptions = {
 	* Initial content entries with optional properties
 	*/
 	entries?: Array<TextPropertyEntry>;
+	/**
+	* URI the buffer stands for (e.g. "pr://owner/repo/123/src/main.rs"),
+	* reported as its path and matched when the URI is opened again
+	*/
+	uri?: string;
+};
+type InputBoxOptions = {
+	/**
+	* Prompt label shown before the input
+	*/
+	title?: string;
+	/**
+	* Initial text in the input
+	*/
+	value?: string;
 };
 type LanguagePackConfig = {
 	/**
@@ -559,6 +587,18 @@ type LanguagePackConfig = {
 	*/
 	autoIndent: boolean | null;
 	/**
+	* Regex for lines after which the next line is indented one level more
+	*/
+	increaseIndentPattern: string | null;
+	/**
+	* Regex for lines that move out one level as they are typed
+	*/
+	decreaseIndentPattern: string | null;
+	/**
+	* Path to a tree-sitter indents.scm query replacing the built-in one
+	*/
+	indentQuery: string | null;
+	/**
 	* Whether to show whitespace tab indicators (→) for this language
 	* Defaults to true. Set to false for languages like Go/Hare that use tabs for indentation.
 	*/
@@ -585,6 +625,53 @@ type LspServerPackConfig = {
 	* LSP initialization options
 	*/
 	initializationOptions: Record<string, unknown> | null;
+	/**
+	* Server name for an additional server of the language (e.g. "tailwindcss").
+	* When unset, the server becomes the language's primary server.
+	*/
+	name: string | null;
+};
+type QuickPickItem = {
+	/**
+	* Text shown in the list and matched against the filter
+	*/
+	label: string;
+	/**
+	* Secondary text shown next to the label
+	*/
+	description?: string;
+	/**
+	* Value returned when the item is picked (defaults to the label)
+	*/
+	value?: string;
+};
+type QuickPickOptions = {
+	/**
+	* Prompt label shown before the filter input
+	*/
+	title?: string;
+	/**
+	* Also match the filter against item descriptions (default: false)
+	*/
+	matchOnDescription?: boolean;
+};
+type QuickfixEntry = {
+	/**
+	* File path (absolute, or relative to the working directory)
+	*/
+	file: string;
+	/**
+	* Line number (1-indexed)
+	*/
+	line: number;
+	/**
+	* Column number (1-indexed, default: 1)
+	*/
+	column?: number;
+	/**
+	* Text shown for the entry, such as the matched line or a message
+	*/
+	text?: string;
 };
 type SpawnResult = {
 	/**
@@ -623,6 +710,26 @@ type PromptSuggestion = {
 	keybinding?: string;
 };
 type TextPropertiesAtCursor = Array<Record<string, unknown>>;
+type TextRange = {
+	/**
+	* Start byte offset
+	*/
+	start: number;
+	/**
+	* End byte offset (exclusive)
+	*/
+	end: number;
+};
+type TextSelection = {
+	/**
+	* Byte offset where the selection started
+	*/
+	anchor: number;
+	/**
+	* Byte offset of the cursor (the moving end of the selection)
+	*/
+	position: number;
+};
 type TsHighlightSpan = {
 	start: number;
 	end: number;
@@ -661,6 +768,26 @@ interface EditorAPI {
 	warn(msg: string): void;
 	error(msg: string): void;
 	setStatus(msg: string): void;
+	/**
+	* Set the text shown by the `plugin:<id>` status line segment
+	* (empty text hides it). Segments are placed via the `status_line` config.
+	*/
+	setStatusSegment(id: string, text: string): void;
+	/**
+	* Add a quickfix list (e.g. search results) as the newest in the
+	* quickfix history; `selected` is the index of the entry the user is at
+	*/
+	setQuickfixList(title: string, entries: QuickfixEntry[], selected?: number): boolean;
+	/**
+	* Set a mark at the cursor (`a`-`z` for this buffer, `A`-`Z` for a
+	* file mark)
+	*/
+	setMark(name: string): boolean;
+	/**
+	* Move the cursor to a mark; with `lineStart`, to the first non-blank
+	* character of its line
+	*/
+	jumpToMark(name: string, lineStart?: boolean): boolean;
 	copyToClipboard(text: string): void;
 	setClipboard(text: string): void;
 	/**
@@ -678,14 +805,33 @@ interface EditorAPI {
 	setContext(name: string, active: boolean): boolean;
 	/**
 	* Execute a built-in action
+	* 
+	* Saving requires the `fs-write` permission for packaged plugins.
 	*/
 	executeAction(actionName: string): boolean;
 	/**
+	* Set the status the editor process exits with
+	*/
+	setExitCode(code: number): boolean;
+	/**
 	* Translate a string - reads plugin name from __pluginName__ global
 	* Args is optional - can be omitted, undefined, null, or an object
 	*/
 	t(key: string, ...args: unknown[]): string;
 	/**
+	* Format a number with the current locale's decimal and grouping
+	* separators, optionally rounded to `decimals` fractional digits
+	*/
+	formatNumber(value: number, decimals?: number): string;
+	/**
+	* Format a date in the current locale
+	* `format` is "date", "time", "date_time", "long_date", "iso_date",
+	* "iso_date_time", "unix" or a strftime pattern; `timestamp` is in
+	* milliseconds like `Date.now()` and defaults to now.
+	* Returns null for an invalid pattern
+	*/
+	formatDate(format: string, timestamp?: number): string | null;
+	/**
 	* Get cursor position in active buffer
 	*/
 	getCursorPosition(): number;
@@ -723,6 +869,10 @@ interface EditorAPI {
 	*/
 	getAllCursorPositions(): unknown;
 	/**
+	* Get all selections in the active buffer, primary first and the rest by position
+	*/
+	getSelections(): TextSelection[];
+	/**
 	* Get viewport info for active buffer
 	*/
 	getViewport(): ViewportInfo | null;
@@ -768,18 +918,41 @@ interface EditorAPI {
 	*/
 	deleteRange(bufferId: number, start: number, end: number): boolean;
 	/**
+	* Replace a range of a buffer with new text as a single undo step
+	*/
+	replaceRange(bufferId: number, range: TextRange, text: string): boolean;
+	/**
+	* Insert text at a byte offset in a buffer as a single undo step
+	*/
+	insertAt(bufferId: number, position: number, text: string): boolean;
+	/**
+	* Replace all cursors in a buffer; the first selection becomes primary
+	*/
+	setSelections(bufferId: number, selections: TextSelection[]): boolean;
+	/**
 	* Insert text at cursor position in active buffer
 	*/
 	insertAtCursor(text: string): boolean;
 	/**
 	* Open a file, optionally at a specific line/column
+	* 
+	* Requires the `fs-read` permission for packaged plugins, unless the
+	* file is already open.
 	*/
 	openFile(path: string, line: number | null, column: number | null): boolean;
 	/**
 	* Open a file in a specific split
+	* 
+	* Requires the `fs-read` permission for packaged plugins, unless the
+	* file is already open.
 	*/
 	openFileInSplit(splitId: number, path: string, line: number, column: number): boolean;
 	/**
+	* Claim a URI scheme such as "pr": opening `pr://...` fires the
+	* `uri_open_requested` hook instead of reading a file
+	*/
+	registerUriScheme(scheme: string): boolean;
+	/**
 	* Show a buffer in the current split
 	*/
 	showBuffer(bufferId: number): boolean;
@@ -789,13 +962,22 @@ interface EditorAPI {
 	closeBuffer(bufferId: number): boolean;
 	/**
 	* Subscribe to an editor event
+	* 
+	* Subscribing the same handler twice is a no-op.
 	*/
 	on(eventName: string, handlerName: string): void;
 	/**
 	* Unsubscribe from an event
+	* 
+	* Only removes this plugin's subscription; other plugins using the same
+	* handler name keep theirs.
 	*/
 	off(eventName: string, handlerName: string): void;
 	/**
+	* Unsubscribe this plugin from every event it listens to
+	*/
+	offAll(): void;
+	/**
 	* Get an environment variable
 	*/
 	getEnv(name: string): string | null;
@@ -826,18 +1008,26 @@ interface EditorAPI {
 	pathIsAbsolute(path: string): boolean;
 	/**
 	* Check if file exists
+	* 
+	* Requires the `fs-read` permission for packaged plugins.
 	*/
 	fileExists(path: string): boolean;
 	/**
 	* Read file contents
+	* 
+	* Requires the `fs-read` permission for packaged plugins.
 	*/
 	readFile(path: string): string | null;
 	/**
 	* Write file contents
+	* 
+	* Requires the `fs-write` permission for packaged plugins.
 	*/
 	writeFile(path: string, content: string): boolean;
 	/**
 	* Read directory contents (returns array of {name, is_file, is_dir})
+	* 
+	* Requires the `fs-read` permission for packaged plugins.
 	*/
 	readDir(path: string): DirEntry[];
 	/**
@@ -888,6 +1078,13 @@ interface EditorAPI {
 	*/
 	applyTheme(themeName: string): boolean;
 	/**
+	* Change one color of the active theme, e.g. `setThemeColor("editor.bg", [30, 30, 30])`
+	* 
+	* Colors use the theme file format (`[r, g, b]` or a named color). The
+	* change is a preview: it lasts until the theme is changed or reloaded.
+	*/
+	setThemeColor(key: string, color: ThemeColor): boolean;
+	/**
 	* Get theme schema as JS object
 	*/
 	getThemeSchema(): unknown;
@@ -1022,6 +1219,16 @@ interface EditorAPI {
 	*/
 	prompt(label: string, initialValue: string): Promise<string | null>;
 	/**
+	* Show a filterable list of items and wait for a pick (async)
+	* Returns the picked item's value (or label), or null if cancelled
+	*/
+	showQuickPick(items: QuickPickItem[], opts?: QuickPickOptions): Promise<string | null>;
+	/**
+	* Show a free-text input box and wait for input (async)
+	* Returns the entered text, or null if cancelled
+	*/
+	showInputBox(opts?: InputBoxOptions): Promise<string | null>;
+	/**
 	* Start an interactive prompt
 	*/
 	startPrompt(label: string, promptType: string): boolean;
@@ -1106,7 +1313,8 @@ interface EditorAPI {
 	/**
 	* Execute multiple actions in sequence
 	* 
-	* Takes typed ActionSpec array - serde validates field names at runtime
+	* Takes typed ActionSpec array - serde validates field names at runtime.
+	* Saving requires the `fs-write` permission for packaged plugins.
 	*/
 	executeActions(actions: ActionSpec[]): boolean;
 	/**
@@ -1151,6 +1359,20 @@ interface EditorAPI {
 	*/
 	setVirtualBufferContent(bufferId: number, entriesArr: Record<string, unknown>[]): boolean;
 	/**
+	* Open a read-only output buffer named `*name*` (async, resolves to the buffer ID)
+	* 
+	* Reuses the existing buffer if one with the same name is already open.
+	*/
+	createOutputBuffer(name: string): Promise<number>;
+	/**
+	* Append text to the end of an output buffer
+	*/
+	appendOutput(bufferId: number, text: string): boolean;
+	/**
+	* Remove all text from an output buffer
+	*/
+	clearOutput(bufferId: number): boolean;
+	/**
 	* Get text properties at cursor position (returns JS array)
 	*/
 	getTextPropertiesAtCursor(bufferId: number): TextPropertiesAtCursor;
@@ -1167,10 +1389,31 @@ interface EditorAPI {
 	*/
 	getBufferText(bufferId: number, start: number, end: number): Promise<string>;
 	/**
+	* Get the text of a byte range in a buffer (async, returns request_id)
+	*/
+	getText(bufferId: number, range: TextRange): Promise<string>;
+	/**
 	* Delay/sleep (async, returns request_id)
 	*/
 	delay(durationMs: number): Promise<void>;
 	/**
+	* Run a long job as a background task (async, resolves with the job's result)
+	* 
+	* `job` is called with a `TaskHandle` and may return a value or a promise.
+	* While it runs, the status bar shows a spinner with the task's progress.
+	* If the user cancels the task the promise rejects, and `task.cancelled`
+	* becomes true so the job can stop early. The result is passed back as JSON.
+	*/
+	runTask(name: string, job: TaskJob): Promise<unknown>;
+	/**
+	* Report progress for a background task (percentage is 0-100)
+	*/
+	reportTaskProgress(taskId: number, message: string | null, percentage: number | null): boolean;
+	/**
+	* Check whether the user cancelled a background task
+	*/
+	isTaskCancelled(taskId: number): boolean;
+	/**
 	* Send LSP request (async, returns request_id)
 	*/
 	sendLspRequest(language: string, method: string, params: Record<string, unknown> | null): Promise<unknown>;
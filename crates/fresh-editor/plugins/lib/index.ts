@@ -31,6 +31,9 @@ export { NavigationController } from "./navigation-controller.ts";
 export { createVirtualBufferFactory } from "./virtual-buffer-factory.ts";
 export type { VirtualBufferOptions, SplitBufferOptions } from "./virtual-buffer-factory.ts";
 
+// Line Diff
+export { diffLines } from "./line-diff.ts";
+
 // Finder Abstraction
 export { Finder, defaultFuzzyFilter, parseGrepLine, parseGrepOutput, getRelativePath, createLiveProvider } from "./finder.ts";
 export type {
@@ -0,0 +1,114 @@
+/// <reference path="./fresh.d.ts" />
+
+/**
+ * Line diff for the composite diff view
+ *
+ * Plugins that open two versions of a file side by side use this to find the
+ * hunks to align.
+ */
+
+/** Edit distance past which changed lines are shown as one hunk */
+const MAX_EDIT_DISTANCE = 4000;
+
+/**
+ * Hunks turning lines `a` into lines `b`, with 0-indexed starts as the diff
+ * view expects
+ *
+ * Myers' algorithm on what's left after the common prefix and suffix.
+ */
+export function diffLines(a: string[], b: string[]): TsCompositeHunk[] {
+  let start = 0;
+  while (start < a.length && start < b.length && a[start] === b[start]) {
+    start++;
+  }
+  let endA = a.length;
+  let endB = b.length;
+  while (endA > start && endB > start && a[endA - 1] === b[endB - 1]) {
+    endA--;
+    endB--;
+  }
+  const x = a.slice(start, endA);
+  const y = b.slice(start, endB);
+  const n = x.length;
+  const m = y.length;
+  if (n === 0 && m === 0) {
+    return [];
+  }
+  const whole = [{ oldStart: start, oldCount: n, newStart: start, newCount: m }];
+  if (n === 0 || m === 0) {
+    return whole;
+  }
+
+  // v[offset + k] is the furthest x index reached on diagonal k; trace[d]
+  // keeps diagonals -d..d after d edits for walking back
+  const max = Math.min(n + m, MAX_EDIT_DISTANCE);
+  const offset = max + 1;
+  const v = new Int32Array(2 * max + 3);
+  const trace: Int32Array[] = [];
+  let found = false;
+  for (let d = 0; d <= max && !found; d++) {
+    for (let k = -d; k <= d; k += 2) {
+      let i = k === -d || (k !== d && v[offset + k - 1] < v[offset + k + 1])
+        ? v[offset + k + 1]
+        : v[offset + k - 1] + 1;
+      let j = i - k;
+      while (i < n && j < m && x[i] === y[j]) {
+        i++;
+        j++;
+      }
+      v[offset + k] = i;
+      if (i >= n && j >= m) {
+        found = true;
+        break;
+      }
+    }
+    trace.push(v.slice(offset - d, offset + d + 1));
+  }
+  if (!found) {
+    return whole;
+  }
+
+  // Walk back through the edits, collecting the lines both sides share
+  const common: Array<[number, number]> = [];
+  let i = n;
+  let j = m;
+  for (let d = trace.length - 1; d > 0; d--) {
+    const prev = trace[d - 1];
+    const at = (k: number) => prev[k + d - 1];
+    const k = i - j;
+    const prevK = k === -d || (k !== d && at(k - 1) < at(k + 1)) ? k + 1 : k - 1;
+    const prevI = at(prevK);
+    const prevJ = prevI - prevK;
+    const snakeStart = prevK === k + 1 ? prevI : prevI + 1;
+    while (i > snakeStart) {
+      i--;
+      j--;
+      common.push([i, j]);
+    }
+    i = prevI;
+    j = prevJ;
+  }
+  while (i > 0 && j > 0) {
+    i--;
+    j--;
+    common.push([i, j]);
+  }
+  common.reverse();
+
+  const hunks: TsCompositeHunk[] = [];
+  let oldLine = 0;
+  let newLine = 0;
+  for (const [ci, cj] of [...common, [n, m] as [number, number]]) {
+    if (ci > oldLine || cj > newLine) {
+      hunks.push({
+        oldStart: start + oldLine,
+        oldCount: ci - oldLine,
+        newStart: start + newLine,
+        newCount: cj - newLine,
+      });
+    }
+    oldLine = ci + 1;
+    newLine = cj + 1;
+  }
+  return hunks;
+}
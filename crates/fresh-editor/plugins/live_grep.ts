@@ -40,6 +40,7 @@ const finder = new Finder<GrepMatch>(editor, {
   }),
   preview: true,
   maxResults: 100,
+  quickfixTitle: (query) => `rg ${query}`,
 });
 
 // Search function that parses ripgrep output
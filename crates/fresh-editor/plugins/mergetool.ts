@@ -0,0 +1,478 @@
+/// <reference path="./lib/fresh.d.ts" />
+
+/**
+ * Mergetool Plugin
+ *
+ * The UI behind `fresh --merge LOCAL BASE REMOTE MERGED`, meant to be run by
+ * `git mergetool`. LOCAL, BASE and REMOTE are shown read-only side by side,
+ * above an editable RESULT pane. RESULT starts as git's three-way merge of
+ * the files (`git merge-file --diff3`), so only the conflicting regions are
+ * left to resolve, either by hand or a whole region at a time:
+ *
+ * - Alt+N / Alt+P: next / previous conflict
+ * - Alt+1 / Alt+2 / Alt+3: take the LOCAL / BASE / REMOTE side
+ * - Alt+B: take LOCAL followed by REMOTE
+ * - Ctrl+S: write RESULT to MERGED and exit with status 0
+ * - Ctrl+Q: exit with status 1, leaving MERGED alone
+ *
+ * Quitting any other way also exits with status 1, so git only records the
+ * file as resolved when RESULT was written.
+ */
+
+const editor = getEditor();
+
+const MERGE_MODE = "mergetool";
+const SOURCE_MODE = "mergetool-source";
+const CONTEXT = "mergetool-active";
+const NAMESPACE = "mergetool";
+
+const colors = {
+  marker: [200, 100, 100] as [number, number, number],
+  local: [40, 60, 90] as [number, number, number],
+  base: [60, 60, 60] as [number, number, number],
+  remote: [90, 60, 40] as [number, number, number],
+};
+
+type Side = "local" | "base" | "remote";
+
+interface Conflict {
+  /** Line indices of the `<<<<<<<`, `|||||||` (-1 if absent), `=======` and `>>>>>>>` lines */
+  startLine: number;
+  baseLine: number;
+  separatorLine: number;
+  endLine: number;
+  local: string[];
+  base: string[];
+  remote: string[];
+}
+
+interface ResultText {
+  lines: string[];
+  /** Byte offset of each line's start, plus one past the end */
+  lineStarts: number[];
+  conflicts: Conflict[];
+}
+
+interface MergeSession {
+  merged: string;
+  resultBufferId: number;
+  panes: { side: Side; bufferId: number; content: string }[];
+  /** Cursor of the RESULT pane, kept while another pane has focus */
+  resultCursor: number;
+  /** Set when saving was refused once because conflicts remain */
+  confirmUnresolved: boolean;
+}
+
+let session: MergeSession | null = null;
+
+// =============================================================================
+// Conflicts
+// =============================================================================
+
+function utf8Length(text: string): number {
+  let length = 0;
+  for (const ch of text) {
+    const code = ch.codePointAt(0)!;
+    length += code < 0x80 ? 1 : code < 0x800 ? 2 : code < 0x10000 ? 3 : 4;
+  }
+  return length;
+}
+
+function parseResult(text: string): ResultText {
+  const lines = text.split("\n");
+  const lineStarts = [0];
+  for (const line of lines) {
+    lineStarts.push(lineStarts[lineStarts.length - 1] + utf8Length(line) + 1);
+  }
+
+  const conflicts: Conflict[] = [];
+  let open: Partial<Conflict> | null = null;
+  for (let i = 0; i < lines.length; i++) {
+    const line = lines[i];
+    if (line.startsWith("<<<<<<<")) {
+      open = { startLine: i, baseLine: -1 };
+    } else if (open && line.startsWith("|||||||") && open.separatorLine === undefined) {
+      open.baseLine = i;
+    } else if (open && line.startsWith("=======") && open.separatorLine === undefined) {
+      open.separatorLine = i;
+    } else if (open && open.separatorLine !== undefined && line.startsWith(">>>>>>>")) {
+      const start = open.startLine!;
+      const base = open.baseLine!;
+      const separator = open.separatorLine;
+      conflicts.push({
+        startLine: start,
+        baseLine: base,
+        separatorLine: separator,
+        endLine: i,
+        local: lines.slice(start + 1, base >= 0 ? base : separator),
+        base: base >= 0 ? lines.slice(base + 1, separator) : [],
+        remote: lines.slice(separator + 1, i),
+      });
+      open = null;
+    }
+  }
+  return { lines, lineStarts, conflicts };
+}
+
+async function readResult(s: MergeSession): Promise<ResultText> {
+  const length = editor.getBufferLength(s.resultBufferId);
+  return parseResult(await editor.getBufferText(s.resultBufferId, 0, length));
+}
+
+function lineAt(result: ResultText, offset: number): number {
+  let line = 0;
+  while (line + 1 < result.lines.length && result.lineStarts[line + 1] <= offset) {
+    line++;
+  }
+  return line;
+}
+
+/** The conflict holding the RESULT cursor, else the next one, else the last */
+function currentConflict(s: MergeSession, result: ResultText): Conflict | null {
+  const line = lineAt(result, s.resultCursor);
+  return result.conflicts.find((c) => c.endLine >= line) ??
+    result.conflicts[result.conflicts.length - 1] ??
+    null;
+}
+
+/** The three-way merge of the files, with conflicts left as diff3 markers */
+async function initialResult(local: string, base: string, remote: string, merged: string): Promise<string> {
+  const run = await editor.spawnProcess("git", [
+    "merge-file", "-p", "--diff3",
+    "-L", "LOCAL", "-L", "BASE", "-L", "REMOTE",
+    local, base, remote,
+  ], editor.pathDirname(merged));
+  // The status is the number of conflicts, or negative on error
+  if (run.exit_code >= 0 && run.exit_code < 128) {
+    return run.stdout;
+  }
+
+  // Without git, use what git left in MERGED, or conflict the whole file
+  const existing = editor.readFile(merged);
+  if (existing !== null && existing.includes("<<<<<<<")) {
+    return existing;
+  }
+  const section = (text: string) => (text === "" || text.endsWith("\n") ? text : text + "\n");
+  return "<<<<<<< LOCAL\n" + section(editor.readFile(local) ?? "") +
+    "||||||| BASE\n" + section(editor.readFile(base) ?? "") +
+    "=======\n" + section(editor.readFile(remote) ?? "") +
+    ">>>>>>> REMOTE\n";
+}
+
+// =============================================================================
+// Panes
+// =============================================================================
+
+function lineEntries(text: string): TextPropertyEntry[] {
+  return text.split("\n").map((line, idx, all) => ({
+    text: idx < all.length - 1 ? line + "\n" : line,
+    properties: { type: "line", lineNum: idx + 1 },
+  }));
+}
+
+async function refreshHighlights(s: MergeSession): Promise<void> {
+  const result = await readResult(s);
+  const bufferId = s.resultBufferId;
+  editor.clearNamespace(bufferId, NAMESPACE);
+
+  const mark = (line: number) => {
+    editor.addOverlay(bufferId, NAMESPACE, result.lineStarts[line], result.lineStarts[line + 1] - 1, {
+      fg: colors.marker,
+      bold: true,
+    });
+  };
+  const shade = (from: number, to: number, bg: [number, number, number]) => {
+    if (to > from) {
+      editor.addOverlay(bufferId, NAMESPACE, result.lineStarts[from], result.lineStarts[to] - 1, {
+        bg,
+        extendToLineEnd: true,
+      });
+    }
+  };
+  for (const c of result.conflicts) {
+    const localEnd = c.baseLine >= 0 ? c.baseLine : c.separatorLine;
+    shade(c.startLine + 1, localEnd, colors.local);
+    if (c.baseLine >= 0) {
+      shade(c.baseLine + 1, c.separatorLine, colors.base);
+      mark(c.baseLine);
+    }
+    shade(c.separatorLine + 1, c.endLine, colors.remote);
+    mark(c.startLine);
+    mark(c.separatorLine);
+    mark(c.endLine);
+  }
+}
+
+/** Put each source pane's cursor on the conflict's text in that file */
+function revealInSources(s: MergeSession, conflict: Conflict): void {
+  for (const pane of s.panes) {
+    const text = conflict[pane.side].join("\n");
+    const at = text.trim() === "" ? -1 : pane.content.indexOf(text);
+    if (at >= 0) {
+      editor.setBufferCursor(pane.bufferId, utf8Length(pane.content.slice(0, at)));
+    }
+  }
+}
+
+function reportProgress(result: ResultText, conflict: Conflict | null): void {
+  const total = result.conflicts.length;
+  if (total === 0) {
+    editor.setStatus(editor.t("status.resolved"));
+  } else if (conflict) {
+    const current = result.conflicts.indexOf(conflict) + 1;
+    editor.setStatus(editor.t("status.conflict", { current: String(current), total: String(total) }));
+  }
+}
+
+async function startMerge(local: string, base: string, remote: string, merged: string): Promise<void> {
+  const name = editor.pathBasename(merged);
+  const dot = name.lastIndexOf(".");
+  // Keep the extension so the panes are highlighted like the file
+  const ext = dot > 0 ? name.slice(dot) : "";
+
+  const contents: Record<Side, string> = {
+    local: editor.readFile(local) ?? "",
+    base: editor.readFile(base) ?? "",
+    remote: editor.readFile(remote) ?? "",
+  };
+  const resultText = await initialResult(local, base, remote, merged);
+
+  // LOCAL | BASE | REMOTE across the top, RESULT below
+  const sourceOptions = (side: Side) => ({
+    name: `*${side.toUpperCase()}*${ext}`,
+    mode: SOURCE_MODE,
+    readOnly: true,
+    entries: lineEntries(contents[side]),
+    showLineNumbers: true,
+    editingDisabled: true,
+  });
+  const localPane = await editor.createVirtualBuffer(sourceOptions("local"));
+  const localSplit = editor.getActiveSplitId();
+  const resultPane = await editor.createVirtualBufferInSplit({
+    name: `*RESULT*${ext}`,
+    mode: MERGE_MODE,
+    readOnly: false,
+    entries: lineEntries(resultText),
+    ratio: 0.5,
+    direction: "horizontal",
+    showLineNumbers: true,
+    editingDisabled: false,
+  });
+  const resultSplit = resultPane.splitId ?? editor.getActiveSplitId();
+  editor.focusSplit(localSplit);
+  // Each split keeps `ratio` of its pane, so a third and then a half
+  const basePane = await editor.createVirtualBufferInSplit({
+    ...sourceOptions("base"),
+    ratio: 1 / 3,
+    direction: "vertical",
+  });
+  const remotePane = await editor.createVirtualBufferInSplit({
+    ...sourceOptions("remote"),
+    ratio: 0.5,
+    direction: "vertical",
+  });
+  editor.focusSplit(resultSplit);
+
+  session = {
+    merged,
+    resultBufferId: resultPane.bufferId,
+    panes: [
+      { side: "local", bufferId: localPane.bufferId, content: contents.local },
+      { side: "base", bufferId: basePane.bufferId, content: contents.base },
+      { side: "remote", bufferId: remotePane.bufferId, content: contents.remote },
+    ],
+    resultCursor: 0,
+    confirmUnresolved: false,
+  };
+  // Anything but writing the result tells git the merge wasn't resolved
+  editor.setExitCode(1);
+  editor.setContext(CONTEXT, true);
+
+  await refreshHighlights(session);
+  const result = parseResult(resultText);
+  const first = result.conflicts[0] ?? null;
+  if (first) {
+    session.resultCursor = result.lineStarts[first.startLine];
+    editor.setBufferCursor(session.resultBufferId, session.resultCursor);
+    revealInSources(session, first);
+  }
+  reportProgress(result, first);
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+async function moveToConflict(direction: 1 | -1): Promise<void> {
+  const s = session;
+  if (!s) {
+    return;
+  }
+  const result = await readResult(s);
+  const line = lineAt(result, s.resultCursor);
+  const target = direction > 0
+    ? result.conflicts.find((c) => c.startLine > line)
+    : [...result.conflicts].reverse().find((c) => c.startLine < line);
+  if (!target) {
+    editor.setStatus(editor.t(direction > 0 ? "status.no_next" : "status.no_prev"));
+    return;
+  }
+  s.resultCursor = result.lineStarts[target.startLine];
+  editor.setBufferCursor(s.resultBufferId, s.resultCursor);
+  revealInSources(s, target);
+  reportProgress(result, target);
+}
+
+/** Replace the current conflict with the given sides' lines, in order */
+async function takeSides(sides: Side[]): Promise<void> {
+  const s = session;
+  if (!s) {
+    return;
+  }
+  const result = await readResult(s);
+  const conflict = currentConflict(s, result);
+  if (!conflict) {
+    editor.setStatus(editor.t("status.resolved"));
+    return;
+  }
+  if (sides.includes("base") && conflict.baseLine < 0) {
+    editor.setStatus(editor.t("status.no_base"));
+    return;
+  }
+
+  const lines = sides.flatMap((side) => conflict[side]);
+  const text = lines.map((line) => line + "\n").join("");
+  const start = result.lineStarts[conflict.startLine];
+  const end = result.lineStarts[conflict.endLine + 1];
+  // The last line of the buffer has no newline to take with it
+  const trailing = conflict.endLine === result.lines.length - 1;
+  editor.deleteRange(s.resultBufferId, start, trailing ? end - 1 : end);
+  editor.insertText(s.resultBufferId, start, trailing ? text.replace(/\n$/, "") : text);
+  s.resultCursor = start;
+  editor.setBufferCursor(s.resultBufferId, start);
+  s.confirmUnresolved = false;
+
+  const remaining = result.conflicts.length - 1;
+  if (remaining === 0) {
+    editor.setStatus(editor.t("status.resolved"));
+  } else {
+    editor.setStatus(editor.t("status.remaining", { count: String(remaining) }));
+  }
+}
+
+globalThis.mergetool_next_conflict = async function (): Promise<void> {
+  await moveToConflict(1);
+};
+
+globalThis.mergetool_prev_conflict = async function (): Promise<void> {
+  await moveToConflict(-1);
+};
+
+globalThis.mergetool_take_local = async function (): Promise<void> {
+  await takeSides(["local"]);
+};
+
+globalThis.mergetool_take_base = async function (): Promise<void> {
+  await takeSides(["base"]);
+};
+
+globalThis.mergetool_take_remote = async function (): Promise<void> {
+  await takeSides(["remote"]);
+};
+
+globalThis.mergetool_take_both = async function (): Promise<void> {
+  await takeSides(["local", "remote"]);
+};
+
+globalThis.mergetool_save = async function (): Promise<void> {
+  const s = session;
+  if (!s) {
+    return;
+  }
+  const length = editor.getBufferLength(s.resultBufferId);
+  const text = await editor.getBufferText(s.resultBufferId, 0, length);
+  const remaining = parseResult(text).conflicts.length;
+  if (remaining > 0 && !s.confirmUnresolved) {
+    s.confirmUnresolved = true;
+    editor.setStatus(editor.t("status.confirm_unresolved", { count: String(remaining) }));
+    return;
+  }
+  if (!editor.writeFile(s.merged, text)) {
+    editor.setStatus(editor.t("status.write_failed", { file: s.merged }));
+    return;
+  }
+  editor.setExitCode(0);
+  editor.executeAction("force_quit");
+};
+
+globalThis.mergetool_abort = function (): void {
+  if (!session) {
+    return;
+  }
+  editor.setExitCode(1);
+  editor.executeAction("force_quit");
+};
+
+// =============================================================================
+// Event handlers
+// =============================================================================
+
+globalThis.onMergeRequested = async function (data: {
+  local: string;
+  base: string;
+  remote: string;
+  merged: string;
+}): Promise<void> {
+  await startMerge(data.local, data.base, data.remote, data.merged);
+};
+
+globalThis.onMergetoolCursorMoved = function (data: { buffer_id: number; new_position: number }): void {
+  if (session && data.buffer_id === session.resultBufferId) {
+    session.resultCursor = data.new_position;
+  }
+};
+
+globalThis.onMergetoolBufferChanged = function (data: { buffer_id: number }): void {
+  if (session && data.buffer_id === session.resultBufferId) {
+    refreshHighlights(session);
+  }
+};
+
+globalThis.onMergetoolBufferClosed = function (data: { buffer_id: number }): void {
+  if (session && data.buffer_id === session.resultBufferId) {
+    session = null;
+    editor.setContext(CONTEXT, false);
+  }
+};
+
+editor.on("merge_requested", "onMergeRequested");
+editor.on("cursor_moved", "onMergetoolCursorMoved");
+editor.on("after_insert", "onMergetoolBufferChanged");
+editor.on("after_delete", "onMergetoolBufferChanged");
+editor.on("buffer_closed", "onMergetoolBufferClosed");
+
+const bindings: [string, string][] = [
+  ["M-n", "mergetool_next_conflict"],
+  ["M-p", "mergetool_prev_conflict"],
+  ["M-1", "mergetool_take_local"],
+  ["M-2", "mergetool_take_base"],
+  ["M-3", "mergetool_take_remote"],
+  ["M-b", "mergetool_take_both"],
+  ["C-s", "mergetool_save"],
+  ["C-q", "mergetool_abort"],
+];
+
+// RESULT is edited as usual; the source panes only navigate
+editor.defineMode(MERGE_MODE, "normal", bindings, false);
+editor.defineMode(SOURCE_MODE, "normal", bindings, true);
+
+editor.registerCommand("%cmd.next_conflict", "%cmd.next_conflict_desc", "mergetool_next_conflict", CONTEXT);
+editor.registerCommand("%cmd.prev_conflict", "%cmd.prev_conflict_desc", "mergetool_prev_conflict", CONTEXT);
+editor.registerCommand("%cmd.take_local", "%cmd.take_local_desc", "mergetool_take_local", CONTEXT);
+editor.registerCommand("%cmd.take_base", "%cmd.take_base_desc", "mergetool_take_base", CONTEXT);
+editor.registerCommand("%cmd.take_remote", "%cmd.take_remote_desc", "mergetool_take_remote", CONTEXT);
+editor.registerCommand("%cmd.take_both", "%cmd.take_both_desc", "mergetool_take_both", CONTEXT);
+editor.registerCommand("%cmd.save", "%cmd.save_desc", "mergetool_save", CONTEXT);
+editor.registerCommand("%cmd.abort", "%cmd.abort_desc", "mergetool_abort", CONTEXT);
+
+editor.debug("Mergetool plugin loaded");
@@ -0,0 +1,470 @@
+/// <reference path="./lib/fresh.d.ts" />
+
+/**
+ * Patch Mode Plugin
+ *
+ * Turns on for `.patch` and `.diff` files. File headers are set off and the
+ * hunk under the cursor is highlighted. The buffer stays editable, and the
+ * mode only claims Alt keys:
+ *
+ * - Alt+N / Alt+P: next / previous hunk
+ * - Alt+Enter: open the file the hunk changes, at the cursor's line
+ * - Alt+A: apply the hunk under the cursor to the working tree
+ * - Alt+R: reverse the hunk under the cursor in the working tree
+ *
+ * Paths in the patch are taken from the root of the git repository holding
+ * it, or from its directory outside a repository. A hunk is applied where
+ * its lines are found nearest to the line it names, so it still applies
+ * after the file has moved around a little.
+ */
+
+const editor = getEditor();
+
+const PATCH_MODE = "patch";
+const NAMESPACE = "patch";
+
+const colors = {
+  fileHeader: [255, 200, 100] as [number, number, number],
+  hunkHeader: [120, 170, 255] as [number, number, number],
+  currentHunk: [45, 45, 60] as [number, number, number],
+};
+
+interface PatchFile {
+  /** Path before the change, null for a new file */
+  oldPath: string | null;
+  /** Path after the change, null for a deleted file */
+  newPath: string | null;
+  /** Line index of the first header line (`diff --git` or `---`) */
+  headerLine: number;
+}
+
+interface Hunk {
+  file: PatchFile;
+  /** Line index of the `@@` line */
+  headerLine: number;
+  /** Line index just past the hunk's last line */
+  endLine: number;
+  /** 1-indexed start lines from the `@@` line */
+  oldStart: number;
+  newStart: number;
+  /** The hunk's lines, each starting with ` `, `-` or `+` */
+  body: string[];
+}
+
+interface Patch {
+  lines: string[];
+  /** Byte offset of each line's start, plus one past the end */
+  lineStarts: number[];
+  files: PatchFile[];
+  hunks: Hunk[];
+}
+
+const patchBuffers = new Set<number>();
+
+// =============================================================================
+// Parsing
+// =============================================================================
+
+function utf8Length(text: string): number {
+  let length = 0;
+  for (const ch of text) {
+    const code = ch.codePointAt(0)!;
+    length += code < 0x80 ? 1 : code < 0x800 ? 2 : code < 0x10000 ? 3 : 4;
+  }
+  return length;
+}
+
+/** Path from a `---`/`+++` line, without its `a/`/`b/` prefix or timestamp */
+function headerPath(line: string): string | null {
+  const path = line.slice(4).split("\t")[0].trim();
+  if (path === "/dev/null") {
+    return null;
+  }
+  return path.startsWith("a/") || path.startsWith("b/") ? path.slice(2) : path;
+}
+
+function parsePatch(text: string): Patch {
+  const lines = text.split("\n");
+  const lineStarts: number[] = [];
+  let offset = 0;
+  for (const line of lines) {
+    lineStarts.push(offset);
+    offset += utf8Length(line) + 1;
+  }
+  lineStarts.push(offset);
+
+  const files: PatchFile[] = [];
+  const hunks: Hunk[] = [];
+  let file: PatchFile | null = null;
+  let gitHeaderLine = -1;
+  let i = 0;
+  while (i < lines.length) {
+    const line = lines[i];
+    if (line.startsWith("diff ")) {
+      gitHeaderLine = i;
+      i++;
+      continue;
+    }
+    if (line.startsWith("--- ") && i + 1 < lines.length && lines[i + 1].startsWith("+++ ")) {
+      file = {
+        oldPath: headerPath(line),
+        newPath: headerPath(lines[i + 1]),
+        headerLine: gitHeaderLine >= 0 ? gitHeaderLine : i,
+      };
+      files.push(file);
+      gitHeaderLine = -1;
+      i += 2;
+      continue;
+    }
+    const header = file ? line.match(/^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@/) : null;
+    if (!file || !header) {
+      i++;
+      continue;
+    }
+
+    // The counts say where the hunk ends; lines past them belong to whatever
+    // follows, such as the next file's header
+    let oldLeft = header[2] === undefined ? 1 : parseInt(header[2], 10);
+    let newLeft = header[4] === undefined ? 1 : parseInt(header[4], 10);
+    const body: string[] = [];
+    const headerLine = i;
+    i++;
+    while (i < lines.length && (oldLeft > 0 || newLeft > 0)) {
+      const bodyLine = lines[i];
+      const kind = bodyLine[0] ?? " ";
+      if (kind === " ") {
+        oldLeft--;
+        newLeft--;
+      } else if (kind === "-") {
+        oldLeft--;
+      } else if (kind === "+") {
+        newLeft--;
+      } else if (kind !== "\\") {
+        break;
+      }
+      if (kind !== "\\") {
+        body.push(bodyLine.length === 0 ? " " : bodyLine);
+      }
+      i++;
+    }
+    // "\ No newline at end of file" after the last line
+    while (i < lines.length && lines[i].startsWith("\\")) {
+      i++;
+    }
+    hunks.push({
+      file,
+      headerLine,
+      endLine: i,
+      oldStart: parseInt(header[1], 10),
+      newStart: parseInt(header[3], 10),
+      body,
+    });
+  }
+  return { lines, lineStarts, files, hunks };
+}
+
+async function readPatch(bufferId: number): Promise<Patch> {
+  const text = await editor.getBufferText(bufferId, 0, editor.getBufferLength(bufferId));
+  return parsePatch(text);
+}
+
+/** Line index holding byte `offset` */
+function lineAt(patch: Patch, offset: number): number {
+  let line = 0;
+  while (line + 1 < patch.lines.length && patch.lineStarts[line + 1] <= offset) {
+    line++;
+  }
+  return line;
+}
+
+function hunkAt(patch: Patch, line: number): Hunk | null {
+  return patch.hunks.find((h) => h.headerLine <= line && line < h.endLine) ?? null;
+}
+
+/** Hunk under the cursor of the active buffer, or null after saying so */
+async function cursorHunk(bufferId: number): Promise<{ patch: Patch; hunk: Hunk; line: number } | null> {
+  const patch = await readPatch(bufferId);
+  const line = lineAt(patch, editor.getCursorPosition());
+  const hunk = hunkAt(patch, line);
+  if (!hunk) {
+    editor.setStatus(editor.t("status.no_hunk"));
+    return null;
+  }
+  return { patch, hunk, line };
+}
+
+// =============================================================================
+// Working tree
+// =============================================================================
+
+/** Directory the patch's paths are relative to */
+async function patchRoot(bufferId: number): Promise<string> {
+  const dir = editor.pathDirname(editor.getBufferPath(bufferId));
+  const root = await editor.spawnProcess("git", ["-C", dir, "rev-parse", "--show-toplevel"]);
+  return root.exit_code === 0 ? root.stdout.trim() : dir;
+}
+
+function resolvePath(root: string, path: string): string {
+  return editor.pathIsAbsolute(path) ? path : editor.pathJoin(root, path);
+}
+
+/** Index where `needle` occurs in `haystack`, nearest to `near`, or -1 */
+function findLines(haystack: string[], needle: string[], near: number): number {
+  const last = haystack.length - needle.length;
+  if (last < 0) {
+    return -1;
+  }
+  const matchesAt = (at: number) => needle.every((line, k) => haystack[at + k] === line);
+  const start = Math.max(0, Math.min(near, last));
+  for (let distance = 0; distance <= last; distance++) {
+    if (start - distance >= 0 && matchesAt(start - distance)) {
+      return start - distance;
+    }
+    if (start + distance <= last && matchesAt(start + distance)) {
+      return start + distance;
+    }
+    if (start - distance < 0 && start + distance > last) {
+      break;
+    }
+  }
+  return -1;
+}
+
+/** Apply `hunk` to its file, or undo it when `reverse` is set */
+async function applyHunk(bufferId: number, hunk: Hunk, reverse: boolean): Promise<void> {
+  const path = hunk.file.newPath ?? hunk.file.oldPath;
+  if (!path) {
+    return;
+  }
+  const before = hunk.body.filter((l) => l[0] !== (reverse ? "-" : "+")).map((l) => l.slice(1));
+  const after = hunk.body.filter((l) => l[0] !== (reverse ? "+" : "-")).map((l) => l.slice(1));
+
+  const fullPath = resolvePath(await patchRoot(bufferId), path);
+  const content = editor.readFile(fullPath);
+  if (content === null && before.length > 0) {
+    editor.setStatus(editor.t("status.file_missing", { file: path }));
+    return;
+  }
+  const lines = content ? content.split("\n") : [];
+  // A final newline leaves an empty last element that isn't a line
+  const finalNewline = content === null || content === "" || content.endsWith("\n");
+  if (content && finalNewline) {
+    lines.pop();
+  }
+
+  // A hunk adding to an empty range names the line before it
+  const start = reverse ? hunk.newStart : hunk.oldStart;
+  const near = before.length === 0 ? start : start - 1;
+  const at = before.length === 0 ? Math.min(near, lines.length) : findLines(lines, before, near);
+  if (at < 0) {
+    const done = after.length > 0 && findLines(lines, after, near) >= 0;
+    const key = done
+      ? (reverse ? "status.already_reversed" : "status.already_applied")
+      : "status.does_not_apply";
+    editor.setStatus(editor.t(key, { file: path }));
+    return;
+  }
+
+  lines.splice(at, before.length, ...after);
+  const text = lines.join("\n") + (finalNewline && lines.length > 0 ? "\n" : "");
+  if (!editor.writeFile(fullPath, text)) {
+    editor.setStatus(editor.t("status.write_failed", { file: path }));
+    return;
+  }
+  const key = reverse ? "status.reversed" : "status.applied";
+  editor.setStatus(editor.t(key, { file: path, line: String(at + 1) }));
+}
+
+// =============================================================================
+// Highlighting
+// =============================================================================
+
+async function refreshHighlights(bufferId: number): Promise<void> {
+  const patch = await readPatch(bufferId);
+  editor.clearNamespace(bufferId, NAMESPACE);
+
+  for (const file of patch.files) {
+    let line = file.headerLine;
+    while (line < patch.lines.length && !patch.lines[line].startsWith("@@")) {
+      editor.addOverlay(bufferId, NAMESPACE, patch.lineStarts[line], patch.lineStarts[line + 1] - 1, {
+        fg: colors.fileHeader,
+        bold: true,
+      });
+      line++;
+    }
+  }
+  for (const hunk of patch.hunks) {
+    editor.addOverlay(
+      bufferId,
+      NAMESPACE,
+      patch.lineStarts[hunk.headerLine],
+      patch.lineStarts[hunk.headerLine + 1] - 1,
+      { fg: colors.hunkHeader, bold: true },
+    );
+  }
+
+  const current = hunkAt(patch, lineAt(patch, editor.getCursorPosition()));
+  if (current) {
+    editor.addOverlay(
+      bufferId,
+      NAMESPACE,
+      patch.lineStarts[current.headerLine],
+      patch.lineStarts[current.endLine] - 1,
+      { bg: colors.currentHunk, extendToLineEnd: true },
+    );
+  }
+}
+
+// =============================================================================
+// Mode management
+// =============================================================================
+
+function isPatchPath(path: string): boolean {
+  const lower = path.toLowerCase();
+  return lower.endsWith(".patch") || lower.endsWith(".diff");
+}
+
+function syncEditorMode(bufferId: number): void {
+  if (patchBuffers.has(bufferId)) {
+    editor.setEditorMode(PATCH_MODE);
+  } else if (editor.getEditorMode() === PATCH_MODE) {
+    editor.setEditorMode(null);
+  }
+}
+
+function trackBuffer(bufferId: number): void {
+  if (!patchBuffers.has(bufferId) && isPatchPath(editor.getBufferPath(bufferId))) {
+    patchBuffers.add(bufferId);
+    refreshHighlights(bufferId);
+  }
+  syncEditorMode(bufferId);
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+async function moveToHunk(direction: 1 | -1): Promise<void> {
+  const bufferId = editor.getActiveBufferId();
+  if (!patchBuffers.has(bufferId)) {
+    return;
+  }
+  const patch = await readPatch(bufferId);
+  const line = lineAt(patch, editor.getCursorPosition());
+  const target = direction > 0
+    ? patch.hunks.find((h) => h.headerLine > line)
+    : [...patch.hunks].reverse().find((h) => h.headerLine < line);
+  if (!target) {
+    editor.setStatus(editor.t(direction > 0 ? "status.no_next" : "status.no_prev"));
+    return;
+  }
+  editor.setBufferCursor(bufferId, patch.lineStarts[target.headerLine]);
+  const index = patch.hunks.indexOf(target) + 1;
+  editor.setStatus(editor.t("status.hunk", { current: String(index), total: String(patch.hunks.length) }));
+}
+
+globalThis.patch_next_hunk = async function (): Promise<void> {
+  await moveToHunk(1);
+};
+
+globalThis.patch_prev_hunk = async function (): Promise<void> {
+  await moveToHunk(-1);
+};
+
+globalThis.patch_goto_source = async function (): Promise<void> {
+  const bufferId = editor.getActiveBufferId();
+  const patch = await readPatch(bufferId);
+  const cursorLine = lineAt(patch, editor.getCursorPosition());
+  const hunk = hunkAt(patch, cursorLine);
+  const file = hunk?.file ?? [...patch.files].reverse().find((f) => f.headerLine <= cursorLine);
+  if (!file) {
+    editor.setStatus(editor.t("status.no_hunk"));
+    return;
+  }
+
+  // Count through the hunk to the cursor's line, in the new file unless the
+  // patch deletes it
+  const useNew = file.newPath !== null;
+  const path = (useNew ? file.newPath : file.oldPath)!;
+  let line = 1;
+  if (hunk) {
+    line = useNew ? hunk.newStart : hunk.oldStart;
+    const skip = useNew ? "-" : "+";
+    for (let i = hunk.headerLine + 1; i < Math.min(cursorLine, hunk.endLine); i++) {
+      const kind = patch.lines[i][0] ?? " ";
+      if (kind !== skip && kind !== "\\") {
+        line++;
+      }
+    }
+    line = Math.max(line, 1);
+  }
+
+  const fullPath = resolvePath(await patchRoot(bufferId), path);
+  if (!editor.fileExists(fullPath)) {
+    editor.setStatus(editor.t("status.file_missing", { file: path }));
+    return;
+  }
+  editor.openFile(fullPath, line, 1);
+};
+
+globalThis.patch_apply_hunk = async function (): Promise<void> {
+  const bufferId = editor.getActiveBufferId();
+  const found = await cursorHunk(bufferId);
+  if (found) {
+    await applyHunk(bufferId, found.hunk, false);
+  }
+};
+
+globalThis.patch_reverse_hunk = async function (): Promise<void> {
+  const bufferId = editor.getActiveBufferId();
+  const found = await cursorHunk(bufferId);
+  if (found) {
+    await applyHunk(bufferId, found.hunk, true);
+  }
+};
+
+// =============================================================================
+// Event handlers
+// =============================================================================
+
+globalThis.onPatchAfterFileOpen = function (data: { buffer_id: number; path: string }): void {
+  trackBuffer(data.buffer_id);
+};
+
+globalThis.onPatchBufferActivated = function (data: { buffer_id: number }): void {
+  trackBuffer(data.buffer_id);
+};
+
+globalThis.onPatchBufferChanged = function (data: { buffer_id: number }): void {
+  if (patchBuffers.has(data.buffer_id)) {
+    refreshHighlights(data.buffer_id);
+  }
+};
+
+globalThis.onPatchBufferClosed = function (data: { buffer_id: number }): void {
+  patchBuffers.delete(data.buffer_id);
+};
+
+editor.on("after_file_open", "onPatchAfterFileOpen");
+editor.on("buffer_activated", "onPatchBufferActivated");
+editor.on("cursor_moved", "onPatchBufferChanged");
+editor.on("after_insert", "onPatchBufferChanged");
+editor.on("after_delete", "onPatchBufferChanged");
+editor.on("buffer_closed", "onPatchBufferClosed");
+
+// Typing goes through to the buffer; only Alt keys are claimed
+editor.defineMode(PATCH_MODE, null, [
+  ["M-n", "patch_next_hunk"],
+  ["M-p", "patch_prev_hunk"],
+  ["M-RET", "patch_goto_source"],
+  ["M-a", "patch_apply_hunk"],
+  ["M-r", "patch_reverse_hunk"],
+], false);
+
+editor.registerCommand("%cmd.next_hunk", "%cmd.next_hunk_desc", "patch_next_hunk", PATCH_MODE);
+editor.registerCommand("%cmd.prev_hunk", "%cmd.prev_hunk_desc", "patch_prev_hunk", PATCH_MODE);
+editor.registerCommand("%cmd.goto_source", "%cmd.goto_source_desc", "patch_goto_source", PATCH_MODE);
+editor.registerCommand("%cmd.apply_hunk", "%cmd.apply_hunk_desc", "patch_apply_hunk", PATCH_MODE);
+editor.registerCommand("%cmd.reverse_hunk", "%cmd.reverse_hunk_desc", "patch_reverse_hunk", PATCH_MODE);
+
+editor.debug("Patch Mode plugin loaded");
@@ -43,7 +43,7 @@ const LANGUAGES_PACKAGES_DIR = editor.pathJoin(CONFIG_DIR, "languages", "package
 const BUNDLES_PACKAGES_DIR = editor.pathJoin(CONFIG_DIR, "bundles", "packages");
 const INDEX_DIR = editor.pathJoin(PACKAGES_DIR, ".index");
 const CACHE_DIR = editor.pathJoin(PACKAGES_DIR, ".cache");
-const LOCKFILE_PATH = editor.pathJoin(CONFIG_DIR, "fresh.lock");
+const LOCKFILE_PATH = editor.pathJoin(CONFIG_DIR, "packages.lock.json");
 
 // Default registry source
 const DEFAULT_REGISTRY = "https://github.com/sinelaw/fresh-plugins-registry";
@@ -59,6 +59,16 @@ const DEFAULT_REGISTRY = "https://github.com/sinelaw/fresh-plugins-registry";
 //   - docs/internal/package-index-template/schemas/package.schema.json
 //   - crates/fresh-editor/plugins/schemas/package.schema.json
 
+// LSP server definition (fresh.lsp, or a list of them for several servers)
+interface LspServerManifest {
+  /** Server name; required for additional servers (e.g., 'tailwindcss') */
+  name?: string;
+  command: string;
+  args?: string[];
+  autoStart?: boolean;
+  initializationOptions?: Record<string, unknown>;
+}
+
 // Bundle language definition (used in fresh.languages[])
 interface BundleLanguage {
   /** Language identifier (e.g., 'elixir', 'heex') */
@@ -77,19 +87,18 @@ interface BundleLanguage {
     useTabs?: boolean;
     tabSize?: number;
     autoIndent?: boolean;
+    increaseIndentPattern?: string;
+    decreaseIndentPattern?: string;
+    /** Tree-sitter indents.scm query, relative to the package */
+    indentQuery?: string;
     showWhitespaceTabs?: boolean;
     formatter?: {
       command: string;
       args?: string[];
     };
   };
-  /** LSP server configuration */
-  lsp?: {
-    command: string;
-    args?: string[];
-    autoStart?: boolean;
-    initializationOptions?: Record<string, unknown>;
-  };
+  /** LSP server configuration (the first server of a list is the primary one) */
+  lsp?: LspServerManifest | LspServerManifest[];
 }
 
 // Bundle plugin definition (used in fresh.plugins[])
@@ -119,6 +128,7 @@ interface PackageManifest {
   fresh?: {
     min_version?: string;
     entry?: string;
+    runtime?: "typescript" | "lua";
     themes?: Array<{
       file: string;
       name: string;
@@ -142,18 +152,17 @@ interface PackageManifest {
       useTabs?: boolean;
       tabSize?: number;
       autoIndent?: boolean;
+      increaseIndentPattern?: string;
+      decreaseIndentPattern?: string;
+      /** Tree-sitter indents.scm query, relative to the package */
+      indentQuery?: string;
       showWhitespaceTabs?: boolean;
       formatter?: {
         command: string;
         args?: string[];
       };
     };
-    lsp?: {
-      command: string;
-      args?: string[];
-      autoStart?: boolean;
-      initializationOptions?: Record<string, unknown>;
-    };
+    lsp?: LspServerManifest | LspServerManifest[];
 
     // Bundle fields
     /** Languages included in this bundle */
@@ -194,10 +203,15 @@ interface InstalledPackage {
 }
 
 interface LockfileEntry {
+  type: "plugin" | "theme" | "language" | "bundle";
+  /** Git URL (with `#subpath` for monorepos) or local path it was installed from */
   source: string;
+  /** Installed commit (empty for local paths) */
   commit: string;
+  /** Package version from its manifest */
   version: string;
-  integrity?: string;
+  /** Version spec the package is pinned to (e.g. `1.2`, `^2.0.0`) */
+  spec?: string;
 }
 
 interface Lockfile {
@@ -380,6 +394,119 @@ async function writeJsonFile(path: string, data: unknown): Promise<boolean> {
   }
 }
 
+// =============================================================================
+// Version Resolution
+// =============================================================================
+
+interface SemVer {
+  major: number;
+  minor: number;
+  patch: number;
+  prerelease: string | null;
+}
+
+/**
+ * Parse a version like `1.2.3`, `v1.2.3` or `1.2.3-beta.1`
+ */
+function parseSemver(version: string): SemVer | null {
+  const match = version.trim().match(/^v?(\d+)\.(\d+)\.(\d+)(?:-([0-9A-Za-z.-]+))?(?:\+[0-9A-Za-z.-]+)?$/);
+  if (!match) return null;
+  return {
+    major: parseInt(match[1], 10),
+    minor: parseInt(match[2], 10),
+    patch: parseInt(match[3], 10),
+    prerelease: match[4] ?? null
+  };
+}
+
+function compareSemver(a: SemVer, b: SemVer): number {
+  if (a.major !== b.major) return a.major - b.major;
+  if (a.minor !== b.minor) return a.minor - b.minor;
+  if (a.patch !== b.patch) return a.patch - b.patch;
+  // A release sorts after its prereleases
+  if (a.prerelease === b.prerelease) return 0;
+  if (a.prerelease === null) return 1;
+  if (b.prerelease === null) return -1;
+  return a.prerelease < b.prerelease ? -1 : 1;
+}
+
+function isCommitHash(version: string): boolean {
+  return /^[0-9a-f]{7,40}$/.test(version);
+}
+
+/**
+ * Check whether a string is a version spec accepted by `name@spec`
+ */
+function isVersionSpec(spec: string): boolean {
+  return spec === "latest"
+    || parseSemver(spec) !== null
+    || /^[\^~]?v?\d+(\.\d+){0,2}$/.test(spec)
+    || isCommitHash(spec);
+}
+
+/**
+ * Check whether a version satisfies a spec:
+ * - `latest`: any release
+ * - `1.2.3`: exactly that version
+ * - `1.2` / `1`: any release with that prefix
+ * - `^1.2.3`: same major (same minor for 0.x), at least 1.2.3
+ * - `~1.2.3`: same major and minor, at least 1.2.3
+ */
+function satisfiesVersionSpec(version: SemVer, spec: string): boolean {
+  if (spec === "latest") return version.prerelease === null;
+  const exact = parseSemver(spec);
+  if (exact) return compareSemver(version, exact) === 0;
+  if (version.prerelease !== null) return false;
+
+  const match = spec.match(/^([\^~]?)v?(\d+)(?:\.(\d+))?(?:\.(\d+))?$/);
+  if (!match) return false;
+  const [, op, majorStr, minorStr, patchStr] = match;
+  const base: SemVer = {
+    major: parseInt(majorStr, 10),
+    minor: minorStr ? parseInt(minorStr, 10) : 0,
+    patch: patchStr ? parseInt(patchStr, 10) : 0,
+    prerelease: null
+  };
+  if (version.major !== base.major || compareSemver(version, base) < 0) return false;
+
+  if (op === "^") {
+    return base.major > 0 || minorStr === undefined || version.minor === base.minor;
+  }
+  // `~` and partial versions both fix the minor version when given
+  return minorStr === undefined || version.minor === base.minor;
+}
+
+/**
+ * Pick the highest tag satisfying a spec, or null if none does
+ */
+function resolveVersionTag(tags: string[], spec: string): string | null {
+  let best: { tag: string; version: SemVer } | null = null;
+  for (const tag of tags) {
+    const version = parseSemver(tag);
+    if (!version || !satisfiesVersionSpec(version, spec)) continue;
+    if (!best || compareSemver(version, best.version) > 0) {
+      best = { tag, version };
+    }
+  }
+  return best?.tag ?? null;
+}
+
+/**
+ * Split `target@spec` into the package (registry name, URL or path) and its
+ * version spec. `git@host:repo` URLs are left alone: only a trailing `@`
+ * followed by a version spec pins the version.
+ */
+function parseInstallSpec(input: string): { target: string; version?: string } {
+  const at = input.lastIndexOf("@");
+  if (at > 0) {
+    const version = input.slice(at + 1);
+    if (isVersionSpec(version)) {
+      return { target: input.slice(0, at), version };
+    }
+  }
+  return { target: input };
+}
+
 // =============================================================================
 // Registry Operations
 // =============================================================================
@@ -505,6 +632,19 @@ async function cacheRegistry(): Promise<void> {
   }
 }
 
+/**
+ * Find a package by name in the plugin, theme and language registries
+ */
+function findRegistryPackage(name: string): RegistryEntry | null {
+  for (const type of ["plugins", "themes", "languages"] as const) {
+    const entry = loadRegistry(type).packages[name];
+    if (entry) {
+      return entry;
+    }
+  }
+  return null;
+}
+
 /**
  * Check if registry data is available (from index or cache)
  */
@@ -643,7 +783,8 @@ function validatePackage(packageDir: string, packageName: string): ValidationRes
 
   // For plugins, validate entry file exists
   if (manifest.type === "plugin") {
-    const entryFile = manifest.fresh?.entry || `${manifest.name}.ts`;
+    const defaultExt = manifest.fresh?.runtime === "lua" ? "lua" : "ts";
+    const entryFile = manifest.fresh?.entry || `${manifest.name}.${defaultExt}`;
     const entryPath = editor.pathJoin(packageDir, entryFile);
 
     if (!editor.fileExists(entryPath)) {
@@ -816,9 +957,11 @@ async function installFromRepo(
 
   // Checkout specific version if requested
   if (version && version !== "latest") {
-    const checkoutResult = await checkoutVersion(tempDir, version);
-    if (!checkoutResult) {
-      editor.setStatus(`Installed ${packageName} but failed to checkout version ${version}`);
+    const checkedOut = await checkoutVersion(tempDir, version);
+    if (!checkedOut) {
+      editor.setStatus(`Failed to install ${packageName}: no version matches ${version}`);
+      await editor.spawnProcess("rm", ["-rf", tempDir]);
+      return false;
     }
   }
 
@@ -861,6 +1004,13 @@ async function installFromRepo(
     return false;
   }
 
+  await recordLockEntry(packageName, {
+    type: actualType,
+    source: repoUrl,
+    commit: await gitHead(correctTargetDir),
+    version: manifest?.version || "unknown"
+  }, version);
+
   // Dynamically load plugins, reload themes, load language packs, or load bundles
   if (manifest?.type === "plugin" && validation.entryPath) {
     // Update entry path to new location
@@ -975,6 +1125,13 @@ async function installFromLocalPath(
   };
   await writeJsonFile(editor.pathJoin(correctTargetDir, ".fresh-source.json"), sourceInfo);
 
+  await recordLockEntry(packageName, {
+    type: actualType,
+    source: sourceInfo.original_url,
+    commit: "",
+    version: manifest.version || "unknown"
+  });
+
   // Dynamically load plugins, reload themes, load language packs, or load bundles
   if (manifest.type === "plugin" && validation.entryPath) {
     await editor.loadPlugin(validation.entryPath);
@@ -1032,7 +1189,10 @@ async function installFromMonorepo(
 
     // Checkout specific version if requested
     if (version && version !== "latest") {
-      await checkoutVersion(tempDir, version);
+      if (!await checkoutVersion(tempDir, version)) {
+        editor.setStatus(`Failed to install ${packageName}: no version matches ${version}`);
+        return false;
+      }
     }
 
     // Verify subpath exists
@@ -1093,6 +1253,13 @@ async function installFromMonorepo(
     };
     await writeJsonFile(editor.pathJoin(correctTargetDir, ".fresh-source.json"), sourceInfo);
 
+    await recordLockEntry(packageName, {
+      type: actualType,
+      source: sourceInfo.installed_from,
+      commit: await gitHead(tempDir),
+      version: manifest?.version || "unknown"
+    }, version);
+
     // Dynamically load plugins, reload themes, load language packs, or load bundles
     if (manifest?.type === "plugin" && validation.entryPath) {
       // Update entry path to new location
@@ -1118,6 +1285,24 @@ async function installFromMonorepo(
   }
 }
 
+/**
+ * Register a language's LSP servers.
+ * The first server is the language's primary server; the others run
+ * alongside it as additional servers, named by `name` (or their command).
+ */
+function registerLspServers(langId: string, lsp: LspServerManifest | LspServerManifest[]): void {
+  const servers = Array.isArray(lsp) ? lsp : [lsp];
+  servers.forEach((server, index) => {
+    editor.registerLspServer(langId, {
+      command: server.command,
+      args: server.args ?? [],
+      autoStart: server.autoStart ?? null,
+      initializationOptions: server.initializationOptions ?? null,
+      name: index === 0 ? null : (server.name ?? server.command),
+    });
+  });
+}
+
 /**
  * Load a language pack (register grammar, language config, and LSP server)
  */
@@ -1141,6 +1326,9 @@ async function loadLanguagePack(packageDir: string, manifest: PackageManifest):
       useTabs: lang.useTabs ?? null,
       tabSize: lang.tabSize ?? null,
       autoIndent: lang.autoIndent ?? null,
+      increaseIndentPattern: lang.increaseIndentPattern ?? null,
+      decreaseIndentPattern: lang.decreaseIndentPattern ?? null,
+      indentQuery: lang.indentQuery ? editor.pathJoin(packageDir, lang.indentQuery) : null,
       showWhitespaceTabs: lang.showWhitespaceTabs ?? null,
       formatter: lang.formatter ? {
         command: lang.formatter.command,
@@ -1149,15 +1337,9 @@ async function loadLanguagePack(packageDir: string, manifest: PackageManifest):
     });
   }
 
-  // Register LSP server if present
+  // Register LSP servers if present
   if (manifest.fresh?.lsp) {
-    const lsp = manifest.fresh.lsp;
-    editor.registerLspServer(langId, {
-      command: lsp.command,
-      args: lsp.args ?? [],
-      autoStart: lsp.autoStart ?? null,
-      initializationOptions: lsp.initializationOptions ?? null,
-    });
+    registerLspServers(langId, manifest.fresh.lsp);
   }
 
   // Apply changes
@@ -1194,6 +1376,9 @@ async function loadBundle(packageDir: string, manifest: PackageManifest): Promis
           useTabs: langConfig.useTabs ?? null,
           tabSize: langConfig.tabSize ?? null,
           autoIndent: langConfig.autoIndent ?? null,
+          increaseIndentPattern: langConfig.increaseIndentPattern ?? null,
+          decreaseIndentPattern: langConfig.decreaseIndentPattern ?? null,
+          indentQuery: langConfig.indentQuery ? editor.pathJoin(packageDir, langConfig.indentQuery) : null,
           showWhitespaceTabs: langConfig.showWhitespaceTabs ?? null,
           formatter: langConfig.formatter ? {
             command: langConfig.formatter.command,
@@ -1202,15 +1387,9 @@ async function loadBundle(packageDir: string, manifest: PackageManifest): Promis
         });
       }
 
-      // Register LSP server if present
+      // Register LSP servers if present
       if (lang.lsp) {
-        const lsp = lang.lsp;
-        editor.registerLspServer(langId, {
-          command: lsp.command,
-          args: lsp.args ?? [],
-          autoStart: lsp.autoStart ?? null,
-          initializationOptions: lsp.initializationOptions ?? null,
-        });
+        registerLspServers(langId, lang.lsp);
       }
     }
   }
@@ -1249,66 +1428,121 @@ async function loadBundle(packageDir: string, manifest: PackageManifest): Promis
 }
 
 /**
- * Checkout a specific version in a package directory
+ * Checkout the version matching a spec (or a commit) in a package directory
+ *
+ * Returns the checked out tag or commit, or null if no version matches.
  */
-async function checkoutVersion(pkgPath: string, version: string): Promise<boolean> {
-  let target: string;
+async function checkoutVersion(pkgPath: string, version: string): Promise<string | null> {
+  await gitCommand(["-C", `${pkgPath}`, "fetch", "--tags"]);
 
-  if (version === "latest") {
-    // Get latest tag
-    const tagsResult = await gitCommand(["-C", `${pkgPath}`, "tag", "--sort=-v:refname"]);
-    const tags = tagsResult.stdout.split("\n").filter(t => t.trim());
-    target = tags[0] || "HEAD";
-  } else if (version.startsWith("^") || version.startsWith("~")) {
-    // Semver matching - find best matching tag
-    target = await findMatchingSemver(pkgPath, version);
-  } else if (version.match(/^[0-9a-f]{7,40}$/)) {
-    // Commit hash
+  let target: string | null;
+  if (isCommitHash(version)) {
     target = version;
   } else {
-    // Exact version or branch
-    target = version.startsWith("v") ? version : `v${version}`;
+    const tagsResult = await gitCommand(["-C", `${pkgPath}`, "tag", "--list"]);
+    const tags = tagsResult.stdout.split("\n").map(t => t.trim()).filter(t => t);
+    target = resolveVersionTag(tags, version);
+  }
+  if (!target) {
+    return null;
   }
 
-  // Fetch if needed
-  await gitCommand(["-C", `${pkgPath}`, "fetch", "--tags"]);
+  const result = await gitCommand(["-C", `${pkgPath}`, "checkout", "--quiet", target]);
+  return result.exit_code === 0 ? target : null;
+}
 
-  // Checkout
-  const result = await gitCommand(["-C", `${pkgPath}`, "checkout", target]);
-  return result.exit_code === 0;
+/**
+ * Commit a package checkout is at (empty if it isn't a git checkout)
+ */
+async function gitHead(pkgPath: string): Promise<string> {
+  const result = await gitCommand(["-C", `${pkgPath}`, "rev-parse", "HEAD"]);
+  return result.exit_code === 0 ? result.stdout.trim() : "";
 }
 
 /**
- * Find best semver matching version
+ * Outcome of updating one package
  */
-async function findMatchingSemver(pkgPath: string, spec: string): Promise<string> {
-  const tagsResult = await gitCommand(["-C", `${pkgPath}`, "tag", "--sort=-v:refname"]);
-  const tags = tagsResult.stdout.split("\n").filter(t => t.trim());
+interface UpdateResult {
+  pkg: InstalledPackage;
+  status: "updated" | "unchanged" | "failed";
+  /** Manifest version after the update */
+  version?: string;
+  /** One line per new commit (`<hash> <subject>`) */
+  changelog: string[];
+  error?: string;
+}
 
-  // Simple semver matching (^ means compatible, ~ means patch only)
-  const prefix = spec.startsWith("^") ? "^" : "~";
-  const baseVersion = spec.slice(1);
-  const [major, minor] = baseVersion.split(".").map(n => parseInt(n, 10));
+/**
+ * Describe a failed git fetch/pull
+ */
+function describeGitError(stderr: string, fallback: string): string {
+  return stderr.includes("Could not resolve host")
+    ? "Network error"
+    : stderr.includes("Authentication") || stderr.includes("403")
+    ? "Authentication failed"
+    : stderr.split("\n")[0] || fallback;
+}
 
-  for (const tag of tags) {
-    const version = tag.replace(/^v/, "");
-    const [tagMajor, tagMinor] = version.split(".").map(n => parseInt(n, 10));
+/**
+ * Move a package checkout to the newest version its pin allows
+ *
+ * Pinned packages move to the highest tag matching their spec; unpinned ones
+ * follow their upstream branch. Updated plugins and themes are reloaded.
+ */
+async function upgradePackage(pkg: InstalledPackage): Promise<UpdateResult> {
+  const result: UpdateResult = { pkg, status: "unchanged", changelog: [] };
+  const lockEntry = readLockfile().packages[pkg.name];
+  const spec = lockEntry?.spec;
+  const before = await gitHead(pkg.path);
 
-    if (prefix === "^") {
-      // Compatible: same major
-      if (tagMajor === major && !isNaN(tagMinor)) {
-        return tag;
-      }
-    } else {
-      // Patch: same major.minor
-      if (tagMajor === major && tagMinor === minor) {
-        return tag;
-      }
+  if (spec) {
+    if (!await checkoutVersion(pkg.path, spec)) {
+      return { ...result, status: "failed", error: `no version matches ${spec}` };
     }
-  }
+  } else {
+    // Checkouts restored from the lockfile are detached; follow the remote's default branch
+    const onBranch = await gitCommand(["-C", `${pkg.path}`, "symbolic-ref", "-q", "HEAD"]);
+    const update = onBranch.exit_code === 0
+      ? await gitCommand(["-C", `${pkg.path}`, "pull", "--ff-only"])
+      : await gitCommand(["-C", `${pkg.path}`, "fetch"]).then(fetch => fetch.exit_code === 0
+          ? gitCommand(["-C", `${pkg.path}`, "checkout", "--quiet", "origin/HEAD"])
+          : fetch);
+    if (update.exit_code !== 0) {
+      return { ...result, status: "failed", error: describeGitError(update.stderr, "Update failed") };
+    }
+  }
+
+  const after = await gitHead(pkg.path);
+  if (after === before) {
+    return result;
+  }
+
+  const log = await gitCommand(["-C", `${pkg.path}`, "log", "--format=%h %s", `${before}..${after}`]);
+  const manifest = readJsonFile<PackageManifest>(editor.pathJoin(pkg.path, "package.json"));
+  result.status = "updated";
+  result.version = manifest?.version || "unknown";
+  result.changelog = log.stdout.split("\n").filter(line => line.trim());
+
+  await recordLockEntry(pkg.name, {
+    type: pkg.type,
+    source: lockEntry?.source || pkg.source,
+    commit: after,
+    version: result.version,
+    spec
+  });
 
-  // Fallback to latest
-  return tags[0] || "HEAD";
+  // Reload the plugin to apply changes
+  // Use listPlugins to find the correct runtime plugin name
+  if (pkg.type === "plugin") {
+    const loadedPlugins = await editor.listPlugins();
+    const plugin = loadedPlugins.find((p: { path: string }) => p.path.startsWith(pkg.path));
+    if (plugin) {
+      await editor.reloadPlugin(plugin.name);
+    }
+  } else if (pkg.type === "theme") {
+    editor.reloadThemes();
+  }
+  return result;
 }
 
 /**
@@ -1317,35 +1551,17 @@ async function findMatchingSemver(pkgPath: string, spec: string): Promise<string
 async function updatePackage(pkg: InstalledPackage): Promise<boolean> {
   editor.setStatus(`Updating ${pkg.name}...`);
 
-  const result = await gitCommand(["-C", `${pkg.path}`, "pull", "--ff-only"]);
-
-  if (result.exit_code === 0) {
-    if (result.stdout.includes("Already up to date")) {
-      editor.setStatus(`${pkg.name} is already up to date`);
-    } else {
-      // Reload the plugin to apply changes
-      // Use listPlugins to find the correct runtime plugin name
-      if (pkg.type === "plugin") {
-        const loadedPlugins = await editor.listPlugins();
-        const plugin = loadedPlugins.find((p: { path: string }) => p.path.startsWith(pkg.path));
-        if (plugin) {
-          await editor.reloadPlugin(plugin.name);
-        }
-      } else if (pkg.type === "theme") {
-        editor.reloadThemes();
-      }
-      editor.setStatus(`Updated and reloaded ${pkg.name}`);
-    }
-    return true;
-  } else {
-    const errorMsg = result.stderr.includes("Could not resolve host")
-      ? "Network error"
-      : result.stderr.includes("Authentication") || result.stderr.includes("403")
-      ? "Authentication failed"
-      : result.stderr.split("\n")[0] || "Update failed";
-    editor.setStatus(`Failed to update ${pkg.name}: ${errorMsg}`);
+  const result = await upgradePackage(pkg);
+  if (result.status === "failed") {
+    editor.setStatus(`Failed to update ${pkg.name}: ${result.error}`);
     return false;
   }
+  if (result.status === "unchanged") {
+    editor.setStatus(`${pkg.name} is already up to date`);
+  } else {
+    editor.setStatus(`Updated and reloaded ${pkg.name} (v${pkg.version} -> v${result.version})`);
+  }
+  return true;
 }
 
 /**
@@ -1371,6 +1587,7 @@ async function removePackage(pkg: InstalledPackage): Promise<boolean> {
   }
 
   if (result.exit_code === 0) {
+    await removeLockEntry(pkg.name);
     // Reload themes if we removed a theme so Select Theme list is updated
     if (pkg.type === "theme") {
       editor.reloadThemes();
@@ -1384,70 +1601,136 @@ async function removePackage(pkg: InstalledPackage): Promise<boolean> {
 }
 
 /**
- * Update all packages
+ * Installed packages of every type that are git checkouts (and can be updated)
+ */
+function getUpdatablePackages(): InstalledPackage[] {
+  const types = ["plugin", "theme", "language", "bundle"] as const;
+  return types
+    .flatMap(type => getInstalledPackages(type))
+    .filter(pkg => editor.fileExists(editor.pathJoin(pkg.path, ".git")));
+}
+
+/**
+ * Update all packages and show what changed in each one
  */
 async function updateAllPackages(): Promise<void> {
-  const plugins = getInstalledPackages("plugin");
-  const themes = getInstalledPackages("theme");
-  const all = [...plugins, ...themes];
+  const all = getUpdatablePackages();
 
   if (all.length === 0) {
     editor.setStatus("No packages installed");
     return;
   }
 
-  let updated = 0;
-  let failed = 0;
-
+  const results: UpdateResult[] = [];
   for (const pkg of all) {
-    editor.setStatus(`Updating ${pkg.name} (${updated + failed + 1}/${all.length})...`);
-    const result = await gitCommand(["-C", `${pkg.path}`, "pull", "--ff-only"]);
-
-    if (result.exit_code === 0) {
-      if (!result.stdout.includes("Already up to date")) {
-        updated++;
+    editor.setStatus(`Updating ${pkg.name} (${results.length + 1}/${all.length})...`);
+    results.push(await upgradePackage(pkg));
+  }
+
+  const updated = results.filter(r => r.status === "updated");
+  const failed = results.filter(r => r.status === "failed");
+  const unchanged = results.length - updated.length - failed.length;
+  const summary = `Update complete: ${updated.length} updated, ${unchanged} unchanged, ${failed.length} failed`;
+
+  if (updated.length > 0 || failed.length > 0) {
+    const lines = [summary, ""];
+    for (const r of updated) {
+      const pin = readLockfile().packages[r.pkg.name]?.spec;
+      lines.push(`${r.pkg.name} v${r.pkg.version} -> v${r.version}${pin ? ` (pinned to ${pin})` : ""}`);
+      for (const entry of r.changelog) {
+        lines.push(`  ${entry}`);
       }
-    } else {
-      failed++;
+      lines.push("");
     }
+    for (const r of failed) {
+      lines.push(`${r.pkg.name}: failed (${r.error})`);
+    }
+    const output = await editor.createOutputBuffer("Package Updates");
+    editor.clearOutput(output);
+    editor.appendOutput(output, lines.join("\n").trimEnd() + "\n");
   }
 
-  editor.setStatus(`Update complete: ${updated} updated, ${all.length - updated - failed} unchanged, ${failed} failed`);
+  editor.setStatus(summary);
 }
 
 // =============================================================================
 // Lockfile Operations
 // =============================================================================
 
+function readLockfile(): Lockfile {
+  return readJsonFile<Lockfile>(LOCKFILE_PATH) || {
+    lockfile_version: 1,
+    generated: "",
+    packages: {}
+  };
+}
+
+async function writeLockfile(lockfile: Lockfile): Promise<boolean> {
+  lockfile.generated = new Date().toISOString();
+  return await writeJsonFile(LOCKFILE_PATH, lockfile);
+}
+
+/**
+ * Record an installed package in the lockfile
+ *
+ * `version` is what the install asked for: a version spec pins the package,
+ * while a commit (restoring from the lockfile) keeps the recorded pin.
+ */
+async function recordLockEntry(name: string, entry: LockfileEntry, version?: string): Promise<void> {
+  const lockfile = readLockfile();
+  if (version && isCommitHash(version)) {
+    entry.spec = lockfile.packages[name]?.spec;
+  } else if (version && version !== "latest") {
+    entry.spec = version;
+  }
+  if (!entry.spec) {
+    delete entry.spec;
+  }
+  lockfile.packages[name] = entry;
+  await writeLockfile(lockfile);
+}
+
+async function removeLockEntry(name: string): Promise<void> {
+  const lockfile = readLockfile();
+  if (lockfile.packages[name]) {
+    delete lockfile.packages[name];
+    await writeLockfile(lockfile);
+  }
+}
+
 /**
- * Generate lockfile from current state
+ * Rewrite the lockfile from the installed packages
+ *
+ * Installs and updates keep the lockfile current; this picks up packages that
+ * were changed by hand (e.g. `git pull` in a package directory).
  */
 async function generateLockfile(): Promise<void> {
   editor.setStatus("Generating lockfile...");
 
-  const plugins = getInstalledPackages("plugin");
-  const themes = getInstalledPackages("theme");
-  const all = [...plugins, ...themes];
-
+  const previous = readLockfile();
   const lockfile: Lockfile = {
     lockfile_version: 1,
-    generated: new Date().toISOString(),
+    generated: "",
     packages: {}
   };
 
+  const types = ["plugin", "theme", "language", "bundle"] as const;
+  const all = types.flatMap(type => getInstalledPackages(type));
   for (const pkg of all) {
-    // Get current commit
-    const commitResult = await gitCommand(["-C", `${pkg.path}`, "rev-parse", "HEAD"]);
-    const commit = commitResult.stdout.trim();
-
-    lockfile.packages[pkg.name] = {
-      source: pkg.source,
-      commit,
+    const recorded = previous.packages[pkg.name];
+    const entry: LockfileEntry = {
+      type: pkg.type,
+      source: pkg.source || recorded?.source || "",
+      commit: (await gitHead(pkg.path)) || recorded?.commit || "",
       version: pkg.version
     };
+    if (recorded?.spec) {
+      entry.spec = recorded.spec;
+    }
+    lockfile.packages[pkg.name] = entry;
   }
 
-  if (await writeJsonFile(LOCKFILE_PATH, lockfile)) {
+  if (await writeLockfile(lockfile)) {
     editor.setStatus(`Lockfile generated with ${all.length} packages`);
   } else {
     editor.setStatus("Failed to write lockfile");
@@ -1455,7 +1738,7 @@ async function generateLockfile(): Promise<void> {
 }
 
 /**
- * Install packages from lockfile
+ * Install the packages recorded in the lockfile at their recorded commits
  */
 async function installFromLockfile(): Promise<void> {
   const lockfile = readJsonFile<Lockfile>(LOCKFILE_PATH);
@@ -1466,41 +1749,38 @@ async function installFromLockfile(): Promise<void> {
 
   editor.setStatus("Installing from lockfile...");
 
-  let installed = 0;
+  const entries = Object.entries(lockfile.packages);
+  const types = ["plugin", "theme", "language", "bundle"] as const;
+  const installed = types.flatMap(type => getInstalledPackages(type));
+  let done = 0;
   let failed = 0;
 
-  for (const [name, entry] of Object.entries(lockfile.packages)) {
-    editor.setStatus(`Installing ${name} (${installed + failed + 1}/${Object.keys(lockfile.packages).length})...`);
+  for (const [name, entry] of entries) {
+    editor.setStatus(`Installing ${name} (${done + failed + 1}/${entries.length})...`);
 
-    // Check if already installed
-    const pluginPath = editor.pathJoin(PACKAGES_DIR, name);
-    const themePath = editor.pathJoin(THEMES_PACKAGES_DIR, name);
-
-    if (editor.fileExists(pluginPath) || editor.fileExists(themePath)) {
-      // Already installed, just checkout the commit
-      const path = editor.fileExists(pluginPath) ? pluginPath : themePath;
-      await gitCommand(["-C", `${path}`, "fetch"]);
-      const result = await gitCommand(["-C", `${path}`, "checkout", entry.commit]);
-      if (result.exit_code === 0) {
-        installed++;
+    const existing = installed.find(pkg => pkg.name === name);
+    if (!existing) {
+      if (await installPackage(entry.source, name, entry.type, entry.commit || entry.spec)) {
+        done++;
       } else {
         failed++;
       }
-    } else {
-      // Need to clone
-      await ensureDir(PACKAGES_DIR);
-      const result = await gitCommand(["clone", `${entry.source}`, `${pluginPath}`]);
+      continue;
+    }
 
-      if (result.exit_code === 0) {
-        await gitCommand(["-C", `${pluginPath}`, "checkout", entry.commit]);
-        installed++;
+    // Already installed: move git checkouts to the recorded commit
+    if (entry.commit && editor.fileExists(editor.pathJoin(existing.path, ".git"))) {
+      if (await checkoutVersion(existing.path, entry.commit)) {
+        done++;
       } else {
         failed++;
       }
+    } else {
+      done++;
     }
   }
 
-  editor.setStatus(`Lockfile install complete: ${installed} installed, ${failed} failed`);
+  editor.setStatus(`Lockfile install complete: ${done} installed, ${failed} failed`);
 }
 
 // =============================================================================
@@ -2695,6 +2975,48 @@ globalThis.pkg_install_theme = async function(): Promise<void> {
   }
 };
 
+/**
+ * Install a registry package by name, or a git URL / local path,
+ * optionally pinned with `@version` (e.g. `rainbow-brackets@1.2`)
+ */
+globalThis.pkg_install = function(): void {
+  editor.startPrompt("Package (name[@version], git URL or path):", "pkg-install");
+};
+
+globalThis.onPkgInstallConfirmed = async function(args: {
+  prompt_type: string;
+  selected_index: number | null;
+  input: string;
+}): Promise<boolean> {
+  if (args.prompt_type !== "pkg-install") return true;
+
+  const input = args.input.trim();
+  if (!input) {
+    editor.setStatus("No package provided");
+    return true;
+  }
+
+  const { target, version } = parseInstallSpec(input);
+  if (!/^[a-z0-9-]+$/.test(target)) {
+    await installPackage(target, undefined, undefined, version);
+    return true;
+  }
+
+  if (!isRegistrySynced()) {
+    await syncRegistry();
+  }
+  const entry = findRegistryPackage(target);
+  if (entry) {
+    await installPackage(entry.repository, target, undefined, version);
+  } else {
+    editor.setStatus(`Package '${target}' not found in registry`);
+  }
+
+  return true;
+};
+
+editor.on("prompt_confirmed", "onPkgInstallConfirmed");
+
 /**
  * Install from git URL or local path
  */
@@ -2709,9 +3031,10 @@ globalThis.onPkgInstallUrlConfirmed = async function(args: {
 }): Promise<boolean> {
   if (args.prompt_type !== "pkg-install-url") return true;
 
-  const url = args.input.trim();
-  if (url) {
-    await installPackage(url);
+  const input = args.input.trim();
+  if (input) {
+    const { target, version } = parseInstallSpec(input);
+    await installPackage(target, undefined, undefined, version);
   } else {
     editor.setStatus("No URL or path provided");
   }
@@ -2892,9 +3215,18 @@ globalThis.pkg_install_lock = async function(): Promise<void> {
 // Main entry point - opens the package manager UI
 editor.registerCommand("%cmd.list", "%cmd.list_desc", "pkg_list", null);
 
+// Install by name (optionally `name@version`), URL or path
+editor.registerCommand("%cmd.install", "%cmd.install_desc", "pkg_install", null);
+
 // Install from URL - for packages not in registry
 editor.registerCommand("%cmd.install_url", "%cmd.install_url_desc", "pkg_install_url", null);
 
+// Update everything and report per-package changelogs
+editor.registerCommand("%cmd.update_all", "%cmd.update_all_desc", "pkg_update_all", null);
+
+// Restore the exact versions recorded in packages.lock.json
+editor.registerCommand("%cmd.install_lock", "%cmd.install_lock_desc", "pkg_install_lock", null);
+
 // Note: Other commands (install_plugin, install_theme, update, remove, sync, etc.)
 // are available via the package manager UI and don't need global command palette entries.
 
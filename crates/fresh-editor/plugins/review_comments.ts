@@ -0,0 +1,392 @@
+/// <reference path="./lib/fresh.d.ts" />
+
+/**
+ * Review Comments Plugin
+ *
+ * Attaches review comments to line ranges of files. "Review Comments: Add"
+ * comments on the selected lines (or the cursor's line); commented lines
+ * are marked in the gutter, and the panel lists every comment by file. In the
+ * panel, Enter jumps to a comment, `r` marks it resolved (or open again),
+ * `e` edits it, `d` deletes it and `x` exports the open comments.
+ *
+ * Comments are kept in `.review/comments.json` in the working directory,
+ * so they can be shared or kept out of version control like any other
+ * file. A comment remembers the text of its first line, and follows that
+ * line when the file is opened or saved with lines added or removed above
+ * it.
+ *
+ * "Export as Markdown" writes `.review/comments.md` and copies it to the
+ * clipboard, ready to paste into a pull request description.
+ */
+
+import { Finder, createLiveProvider, type FinderProvider } from "./lib/finder.ts";
+
+const editor = getEditor();
+
+const NAMESPACE = "review-comments";
+const PRIORITY = 12;
+
+const COLORS = {
+  open: [230, 200, 80] as [number, number, number],
+  resolved: [120, 120, 120] as [number, number, number],
+};
+
+const SYMBOLS = {
+  open: "◆",
+  resolved: "◇",
+  /** Lines of a range after its first */
+  continued: "│",
+};
+
+/** Lines searched above and below a comment's line for its text */
+const REANCHOR_DISTANCE = 200;
+
+interface ReviewComment {
+  id: string;
+  /** Path relative to the working directory, with forward slashes */
+  file: string;
+  /** 1-indexed, inclusive */
+  startLine: number;
+  endLine: number;
+  text: string;
+  resolved: boolean;
+  created: string;
+  /** Text of `startLine` when the comment was made */
+  anchor: string;
+}
+
+interface CommentStore {
+  version: number;
+  comments: ReviewComment[];
+}
+
+// =============================================================================
+// Storage
+// =============================================================================
+
+let comments: ReviewComment[] = [];
+let loaded = false;
+
+function storePath(): string {
+  return editor.pathJoin(editor.getCwd(), ".review", "comments.json");
+}
+
+function load(): void {
+  if (loaded) return;
+  loaded = true;
+  const text = editor.readFile(storePath());
+  if (!text) return;
+  try {
+    const store = JSON.parse(text) as CommentStore;
+    comments = Array.isArray(store.comments) ? store.comments : [];
+  } catch (e) {
+    editor.setStatus(editor.t("status.bad_store", { path: storePath() }));
+    editor.debug(`[review_comments] ${e}`);
+  }
+}
+
+function save(): void {
+  const store: CommentStore = { version: 1, comments };
+  if (!editor.writeFile(storePath(), JSON.stringify(store, null, 2) + "\n")) {
+    editor.setStatus(editor.t("status.write_failed", { path: storePath() }));
+  }
+}
+
+function relativePath(path: string): string {
+  const cwd = editor.getCwd().replace(/\\/g, "/").replace(/\/$/, "");
+  const normalized = path.replace(/\\/g, "/");
+  return normalized.startsWith(cwd + "/") ? normalized.slice(cwd.length + 1) : normalized;
+}
+
+function absolutePath(file: string): string {
+  return editor.pathIsAbsolute(file) ? file : editor.pathJoin(editor.getCwd(), file);
+}
+
+function lineLabel(c: ReviewComment): string {
+  return c.startLine === c.endLine ? `L${c.startLine}` : `L${c.startLine}-${c.endLine}`;
+}
+
+// =============================================================================
+// Gutter
+// =============================================================================
+
+function updateIndicators(bufferId: number): void {
+  const path = editor.getBufferPath(bufferId);
+  if (!path) return;
+  const file = relativePath(path);
+  editor.clearLineIndicators(bufferId, NAMESPACE);
+  for (const c of comments) {
+    if (c.file !== file) continue;
+    const color = c.resolved ? COLORS.resolved : COLORS.open;
+    for (let line = c.startLine; line <= c.endLine; line++) {
+      const symbol = line === c.startLine
+        ? (c.resolved ? SYMBOLS.resolved : SYMBOLS.open)
+        : SYMBOLS.continued;
+      editor.setLineIndicator(bufferId, line - 1, NAMESPACE, symbol, color[0], color[1], color[2], PRIORITY);
+    }
+  }
+}
+
+function updateFileIndicators(file: string): void {
+  const bufferId = editor.findBufferByPath(absolutePath(file));
+  if (bufferId) updateIndicators(bufferId);
+}
+
+/**
+ * Move the file's comments to where their first line is now, for lines
+ * added or removed above them since they were made
+ */
+async function reanchor(bufferId: number): Promise<void> {
+  const path = editor.getBufferPath(bufferId);
+  if (!path) return;
+  const file = relativePath(path);
+  const fileComments = comments.filter((c) => c.file === file);
+  if (fileComments.length === 0) return;
+
+  const text = await editor.getBufferText(bufferId, 0, editor.getBufferLength(bufferId));
+  const lines = text.split("\n");
+  let moved = false;
+  for (const c of fileComments) {
+    if (lines[c.startLine - 1] === c.anchor) continue;
+    for (let distance = 1; distance <= REANCHOR_DISTANCE; distance++) {
+      const found = [c.startLine - distance, c.startLine + distance].find(
+        (line) => line >= 1 && lines[line - 1] === c.anchor
+      );
+      if (found !== undefined) {
+        c.endLine += found - c.startLine;
+        c.startLine = found;
+        moved = true;
+        break;
+      }
+    }
+  }
+  if (moved) {
+    save();
+    provider.notify();
+  }
+  updateIndicators(bufferId);
+}
+
+// =============================================================================
+// Panel
+// =============================================================================
+
+const provider = createLiveProvider(() => comments);
+
+const finder = new Finder<ReviewComment>(editor, {
+  id: "review-comments",
+  format: (c) => ({
+    label: `${c.resolved ? SYMBOLS.resolved : SYMBOLS.open} ${c.text.split("\n")[0]}`,
+    description: lineLabel(c),
+    location: { file: absolutePath(c.file), line: c.startLine, column: 1 },
+  }),
+  groupBy: "file",
+  panelKeys: [
+    ["r", "review_comments_toggle_resolved"],
+    ["e", "review_comments_edit"],
+    ["d", "review_comments_delete"],
+    ["x", "review_comments_export"],
+  ],
+});
+
+function panelTitle(): string {
+  const open = comments.filter((c) => !c.resolved).length;
+  return editor.t("panel.title", { open: String(open), total: String(comments.length) });
+}
+
+function commentsChanged(file: string): void {
+  save();
+  updateFileIndicators(file);
+  if (finder.isOpen) {
+    finder.updateTitle(panelTitle());
+    provider.notify();
+  }
+}
+
+/** The comment on the cursor's line in a file, or selected in the panel */
+function currentComment(): ReviewComment | undefined {
+  load();
+  const path = editor.getBufferPath(editor.getActiveBufferId());
+  if (!path) return finder.selectedItem;
+  const file = relativePath(path);
+  const line = editor.getCursorLine() + 1;
+  return comments.find((c) => c.file === file && c.startLine <= line && line <= c.endLine);
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+/** 1-indexed line of the byte at `offset` in `text` */
+function lineOfOffset(text: string, offset: number): number {
+  let line = 1;
+  let bytes = 0;
+  for (const ch of text) {
+    if (bytes >= offset) break;
+    const code = ch.codePointAt(0)!;
+    bytes += code < 0x80 ? 1 : code < 0x800 ? 2 : code < 0x10000 ? 3 : 4;
+    if (ch === "\n") line++;
+  }
+  return line;
+}
+
+globalThis.review_comments_add = async function (): Promise<void> {
+  load();
+  const bufferId = editor.getActiveBufferId();
+  const path = editor.getBufferPath(bufferId);
+  if (!path) {
+    editor.setStatus(editor.t("status.no_file"));
+    return;
+  }
+
+  const text = await editor.getBufferText(bufferId, 0, editor.getBufferLength(bufferId));
+  const selection = editor.getSelections()[0];
+  let startLine = editor.getCursorLine() + 1;
+  let endLine = startLine;
+  if (selection && selection.anchor !== selection.position) {
+    const start = Math.min(selection.anchor, selection.position);
+    const end = Math.max(selection.anchor, selection.position);
+    startLine = lineOfOffset(text, start);
+    // A selection ending at the start of a line doesn't take that line
+    endLine = Math.max(startLine, lineOfOffset(text, end - 1));
+  }
+
+  const file = relativePath(path);
+  const range = startLine === endLine ? `${startLine}` : `${startLine}-${endLine}`;
+  const body = await editor.showInputBox({
+    title: editor.t("prompt.comment", { file: editor.pathBasename(path), lines: range }),
+  });
+  if (!body || !body.trim()) return;
+
+  comments.push({
+    id: `c${Date.now().toString(36)}${Math.floor(Math.random() * 1e6).toString(36)}`,
+    file,
+    startLine,
+    endLine,
+    text: body.trim(),
+    resolved: false,
+    created: new Date().toISOString(),
+    anchor: text.split("\n")[startLine - 1] ?? "",
+  });
+  commentsChanged(file);
+  editor.setStatus(editor.t("status.added", { file, lines: range }));
+};
+
+globalThis.review_comments_show = async function (): Promise<void> {
+  load();
+  if (comments.length === 0) {
+    editor.setStatus(editor.t("status.no_comments"));
+    return;
+  }
+  if (finder.isOpen) {
+    finder.updateTitle(panelTitle());
+    provider.notify();
+    return;
+  }
+  await finder.livePanel({
+    title: panelTitle(),
+    provider: provider as FinderProvider<ReviewComment>,
+    ratio: 0.3,
+  });
+};
+
+globalThis.review_comments_toggle_resolved = function (): void {
+  const comment = currentComment();
+  if (!comment) {
+    editor.setStatus(editor.t("status.no_comment_here"));
+    return;
+  }
+  comment.resolved = !comment.resolved;
+  commentsChanged(comment.file);
+  editor.setStatus(editor.t(comment.resolved ? "status.resolved" : "status.reopened"));
+};
+
+globalThis.review_comments_edit = async function (): Promise<void> {
+  const comment = currentComment();
+  if (!comment) {
+    editor.setStatus(editor.t("status.no_comment_here"));
+    return;
+  }
+  const body = await editor.showInputBox({
+    title: editor.t("prompt.edit", { file: comment.file, lines: lineLabel(comment) }),
+    value: comment.text,
+  });
+  if (!body || !body.trim()) return;
+  comment.text = body.trim();
+  commentsChanged(comment.file);
+};
+
+globalThis.review_comments_delete = function (): void {
+  const comment = currentComment();
+  if (!comment) {
+    editor.setStatus(editor.t("status.no_comment_here"));
+    return;
+  }
+  comments = comments.filter((c) => c !== comment);
+  commentsChanged(comment.file);
+  editor.setStatus(editor.t("status.deleted"));
+};
+
+globalThis.review_comments_export = function (): void {
+  load();
+  const open = comments.filter((c) => !c.resolved);
+  if (open.length === 0) {
+    editor.setStatus(editor.t("status.nothing_to_export"));
+    return;
+  }
+
+  const files = [...new Set(open.map((c) => c.file))].sort();
+  let md = "## Review comments\n";
+  for (const file of files) {
+    md += `\n### \`${file}\`\n\n`;
+    const fileComments = open
+      .filter((c) => c.file === file)
+      .sort((a, b) => a.startLine - b.startLine);
+    for (const c of fileComments) {
+      const [first, ...rest] = c.text.split("\n");
+      md += `- **${lineLabel(c)}**: ${first}\n`;
+      for (const line of rest) {
+        md += `  ${line}\n`;
+      }
+    }
+  }
+
+  const path = editor.pathJoin(editor.getCwd(), ".review", "comments.md");
+  if (!editor.writeFile(path, md)) {
+    editor.setStatus(editor.t("status.write_failed", { path }));
+    return;
+  }
+  editor.setClipboard(md);
+  editor.setStatus(editor.t("status.exported", { count: String(open.length), path }));
+};
+
+// =============================================================================
+// Events
+// =============================================================================
+
+globalThis.on_review_comments_buffer_shown = function (data: { buffer_id: number }): void {
+  load();
+  updateIndicators(data.buffer_id);
+};
+
+globalThis.on_review_comments_after_open = async function (data: { buffer_id: number }): Promise<void> {
+  load();
+  await reanchor(data.buffer_id);
+};
+
+globalThis.on_review_comments_after_save = async function (data: { buffer_id: number }): Promise<void> {
+  await reanchor(data.buffer_id);
+};
+
+editor.on("after_file_open", "on_review_comments_after_open");
+editor.on("buffer_activated", "on_review_comments_buffer_shown");
+editor.on("after_file_save", "on_review_comments_after_save");
+
+editor.registerCommand("%cmd.add", "%cmd.add_desc", "review_comments_add", null);
+editor.registerCommand("%cmd.show", "%cmd.show_desc", "review_comments_show", null);
+editor.registerCommand("%cmd.toggle_resolved", "%cmd.toggle_resolved_desc", "review_comments_toggle_resolved", null);
+editor.registerCommand("%cmd.edit", "%cmd.edit_desc", "review_comments_edit", null);
+editor.registerCommand("%cmd.delete", "%cmd.delete_desc", "review_comments_delete", null);
+editor.registerCommand("%cmd.export", "%cmd.export_desc", "review_comments_export", null);
+
+editor.debug("Review Comments plugin loaded");
@@ -0,0 +1,674 @@
+/// <reference path="./lib/fresh.d.ts" />
+
+/**
+ * Test Explorer Plugin
+ *
+ * Finds the tests of the project's test frameworks and lists them in a
+ * panel grouped by file. Tests can be run one at a time, by file, all at
+ * once, or just the ones that failed last time. The runner's output is kept
+ * in an output buffer, and pass/fail marks are shown in the gutter next to
+ * each test function.
+ *
+ * cargo test, pytest and jest are built in. Language packages add more
+ * frameworks with a `test` entry in their `fresh` section.
+ */
+
+import { Finder, createLiveProvider, type FinderProvider } from "./lib/finder.ts";
+
+const editor = getEditor();
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/** A marker file that shows a project uses a framework */
+interface DetectRule {
+  /** File at the project root */
+  file: string;
+  /** Text the file must contain */
+  contains?: string;
+}
+
+/**
+ * How to find and run the tests of one framework
+ *
+ * Regexes are given as strings. Command arguments may use these
+ * placeholders for the tests being run:
+ * - `${files}`: their files (one argument each, relative to the project)
+ * - `${names}`: their names (one argument each)
+ * - `${pattern}`: their names joined with " or " (pytest `-k`)
+ * - `${regex}`: a regex matching the end of any of their names
+ */
+interface TestFramework {
+  name: string;
+  /** Any of these marks the project as using the framework */
+  detect: Array<string | DetectRule>;
+  /** Regex for test files, matched against the project-relative path */
+  files: string;
+  /** Regex for a test's line; group 1 is the test name */
+  test: string;
+  /** Regex one of the attribute lines above the test must match */
+  marker?: string;
+  /** Command that runs every test */
+  runAll: string[];
+  /** Command that runs the given tests */
+  runTests: string[];
+  /** Regex for an output line of a passed test; group 1 is the name */
+  passed: string;
+  /** Regex for an output line of a failed test; group 1 is the name */
+  failed: string;
+}
+
+type TestStatus = "passed" | "failed";
+
+interface TestItem {
+  framework: TestFramework;
+  /** Absolute path of the test file */
+  file: string;
+  /** Path relative to the project root */
+  relativePath: string;
+  name: string;
+  /** 1-indexed line of the test */
+  line: number;
+}
+
+// =============================================================================
+// Frameworks
+// =============================================================================
+
+const BUILTIN_FRAMEWORKS: TestFramework[] = [
+  {
+    name: "cargo",
+    detect: ["Cargo.toml"],
+    files: "\\.rs$",
+    test: "^\\s*(?:pub(?:\\([^)]*\\))?\\s+)?(?:async\\s+)?fn\\s+(\\w+)",
+    marker: "#\\[(?:[\\w:]+::)?test\\b",
+    runAll: ["cargo", "test"],
+    runTests: ["cargo", "test", "--", "${names}"],
+    passed: "^test (?:\\S+::)?(\\w+) \\.\\.\\. ok",
+    failed: "^test (?:\\S+::)?(\\w+) \\.\\.\\. FAILED",
+  },
+  {
+    name: "pytest",
+    detect: [
+      "pytest.ini",
+      "conftest.py",
+      { file: "pyproject.toml", contains: "pytest" },
+      { file: "setup.cfg", contains: "pytest" },
+      { file: "tox.ini", contains: "pytest" },
+    ],
+    files: "(?:^|/)(?:test_[^/]*|[^/]*_test)\\.py$",
+    test: "^\\s*(?:async\\s+)?def\\s+(test\\w*)\\s*\\(",
+    runAll: ["python3", "-m", "pytest", "-v"],
+    runTests: ["python3", "-m", "pytest", "-v", "${files}", "-k", "${pattern}"],
+    passed: "::(\\w+)(?:\\[[^\\]]*\\])? PASSED",
+    failed: "::(\\w+)(?:\\[[^\\]]*\\])? FAILED",
+  },
+  {
+    name: "jest",
+    detect: [
+      "jest.config.js",
+      "jest.config.ts",
+      { file: "package.json", contains: "jest" },
+    ],
+    files: "\\.(?:test|spec)\\.[jt]sx?$",
+    test: "^\\s*(?:it|test)\\(\\s*[\"'`](.+?)[\"'`]",
+    runAll: ["npx", "jest", "--verbose"],
+    runTests: ["npx", "jest", "--verbose", "${files}", "-t", "${regex}"],
+    passed: "^\\s*[✓√] (.+?)(?: \\(\\d+ ?m?s\\))?$",
+    failed: "^\\s*[✕×] (.+?)(?: \\(\\d+ ?m?s\\))?$",
+  },
+];
+
+/** Directories never searched for tests */
+const SKIPPED_DIRS = new Set([
+  "node_modules",
+  "target",
+  "venv",
+  "__pycache__",
+  "dist",
+  "build",
+]);
+
+/** Most files searched for tests */
+const MAX_FILES = 5000;
+
+/** Attribute or comment lines looked at above a test for its marker */
+const MARKER_LOOKBACK = 5;
+
+const NAMESPACE = "test-explorer";
+const PRIORITY = 15;
+const OUTPUT_NAME = "Test Output";
+
+const COLORS = {
+  passed: [80, 250, 123] as [number, number, number],
+  failed: [255, 85, 85] as [number, number, number],
+};
+
+const SYMBOLS = {
+  passed: "✓",
+  failed: "✗",
+};
+
+// =============================================================================
+// State
+// =============================================================================
+
+let tests: TestItem[] = [];
+/** Results of the last run, keyed by testKey */
+const statuses = new Map<string, TestStatus>();
+/** Output of the last run */
+let lastOutput = "";
+let running = false;
+/** Split the panel was opened from, where files and output are shown */
+let sourceSplitId: number | null = null;
+/** Last known cursor line (1-indexed) of each buffer */
+const cursorLines = new Map<number, number>();
+
+function testKey(file: string, name: string): string {
+  return `${file}::${name}`;
+}
+
+function projectRoot(): string {
+  return editor.getCwd();
+}
+
+// =============================================================================
+// Discovery
+// =============================================================================
+
+/** Test definitions contributed by installed language packages and bundles */
+function packageFrameworks(): TestFramework[] {
+  const frameworks: TestFramework[] = [];
+  const configDir = editor.getConfigDir();
+  const add = (test: unknown) => {
+    const list = Array.isArray(test) ? test : test ? [test] : [];
+    for (const framework of list) {
+      if (isFramework(framework)) frameworks.push(framework);
+    }
+  };
+
+  for (const kind of ["languages", "bundles"]) {
+    const packagesDir = editor.pathJoin(configDir, kind, "packages");
+    if (!editor.fileExists(packagesDir)) continue;
+    for (const entry of editor.readDir(packagesDir)) {
+      if (!entry.is_dir) continue;
+      const manifest = editor.readFile(
+        editor.pathJoin(packagesDir, entry.name, "package.json")
+      );
+      if (!manifest) continue;
+      try {
+        const fresh = JSON.parse(manifest).fresh ?? {};
+        add(fresh.test);
+        for (const language of fresh.languages ?? []) {
+          add(language.test);
+        }
+      } catch (e) {
+        editor.debug(`[test_explorer] Bad package.json in ${entry.name}: ${e}`);
+      }
+    }
+  }
+  return frameworks;
+}
+
+function isFramework(value: unknown): value is TestFramework {
+  const f = value as TestFramework;
+  return (
+    !!f &&
+    typeof f.name === "string" &&
+    Array.isArray(f.detect) &&
+    typeof f.files === "string" &&
+    typeof f.test === "string" &&
+    Array.isArray(f.runAll) &&
+    Array.isArray(f.runTests) &&
+    typeof f.passed === "string" &&
+    typeof f.failed === "string"
+  );
+}
+
+/** Frameworks the project at `root` uses */
+function detectFrameworks(root: string): TestFramework[] {
+  return [...BUILTIN_FRAMEWORKS, ...packageFrameworks()].filter((framework) =>
+    framework.detect.some((rule) => {
+      const { file, contains } =
+        typeof rule === "string" ? { file: rule, contains: undefined } : rule;
+      const path = editor.pathJoin(root, file);
+      if (!editor.fileExists(path)) return false;
+      if (contains === undefined) return true;
+      return (editor.readFile(path) ?? "").includes(contains);
+    })
+  );
+}
+
+/** Project-relative paths of the files under `root` */
+function listFiles(root: string): string[] {
+  const files: string[] = [];
+  const walk = (dir: string, relative: string) => {
+    for (const entry of editor.readDir(dir)) {
+      if (files.length >= MAX_FILES) return;
+      if (entry.name.startsWith(".")) continue;
+      const relativePath = relative ? `${relative}/${entry.name}` : entry.name;
+      if (entry.is_dir) {
+        if (!SKIPPED_DIRS.has(entry.name)) {
+          walk(editor.pathJoin(dir, entry.name), relativePath);
+        }
+      } else if (entry.is_file) {
+        files.push(relativePath);
+      }
+    }
+  };
+  walk(root, "");
+  return files;
+}
+
+/** Whether a line above a test carries the framework's marker */
+function hasMarker(lines: string[], index: number, marker: RegExp): boolean {
+  for (let i = index - 1; i >= 0 && i >= index - MARKER_LOOKBACK; i--) {
+    const line = lines[i].trim();
+    if (marker.test(line)) return true;
+    if (line !== "" && !line.startsWith("#[") && !line.startsWith("//")) {
+      return false;
+    }
+  }
+  return false;
+}
+
+/** Tests declared in the text of one file */
+function parseTests(
+  framework: TestFramework,
+  file: string,
+  relativePath: string,
+  text: string
+): TestItem[] {
+  const testRe = new RegExp(framework.test);
+  const marker = framework.marker ? new RegExp(framework.marker) : null;
+  const lines = text.split("\n");
+  const found: TestItem[] = [];
+  lines.forEach((line, index) => {
+    const match = line.match(testRe);
+    if (!match || !match[1]) return;
+    if (marker && !hasMarker(lines, index, marker)) return;
+    found.push({ framework, file, relativePath, name: match[1], line: index + 1 });
+  });
+  return found;
+}
+
+/** Find the tests of every framework the project uses */
+function discoverTests(): TestItem[] {
+  const root = projectRoot();
+  const frameworks = detectFrameworks(root);
+  if (frameworks.length === 0) return [];
+
+  const found: TestItem[] = [];
+  const files = listFiles(root);
+  for (const framework of frameworks) {
+    const filesRe = new RegExp(framework.files);
+    for (const relativePath of files) {
+      if (!filesRe.test(relativePath)) continue;
+      const file = editor.pathJoin(root, relativePath);
+      const text = editor.readFile(file);
+      if (text) found.push(...parseTests(framework, file, relativePath, text));
+    }
+  }
+  return found;
+}
+
+// =============================================================================
+// Running
+// =============================================================================
+
+function escapeRegex(text: string): string {
+  return text.replace(/[.*+?^${}()|[\]\\]/g, "\\$&");
+}
+
+/** Fill in the placeholders of a command template for `selected` */
+function expandCommand(template: string[], selected: TestItem[]): string[] {
+  const names = [...new Set(selected.map((t) => t.name))];
+  const files = [...new Set(selected.map((t) => t.relativePath))];
+  const args: string[] = [];
+  for (const arg of template) {
+    if (arg === "${files}") {
+      args.push(...files);
+    } else if (arg === "${names}") {
+      args.push(...names);
+    } else {
+      args.push(
+        arg
+          .replace("${pattern}", names.join(" or "))
+          .replace("${regex}", `(?:${names.map(escapeRegex).join("|")})$`)
+      );
+    }
+  }
+  return args;
+}
+
+/** Record the results in `output` for the tests of `framework` */
+function recordResults(framework: TestFramework, output: string, selected: TestItem[]) {
+  const passedRe = new RegExp(framework.passed);
+  const failedRe = new RegExp(framework.failed);
+  const results = new Map<string, TestStatus>();
+  for (const line of output.split("\n")) {
+    const passed = line.match(passedRe);
+    if (passed) results.set(passed[1], "passed");
+    const failed = line.match(failedRe);
+    if (failed) results.set(failed[1], "failed");
+  }
+  for (const test of selected) {
+    const status = results.get(test.name);
+    if (status) statuses.set(testKey(test.file, test.name), status);
+  }
+}
+
+/** Run a framework's command and wait for it, killing it if the task is cancelled */
+async function runCommand(task: TaskHandle, args: string[]): Promise<SpawnResult> {
+  const handle = editor.spawnProcess(args[0], args.slice(1), projectRoot());
+  let done = false;
+  handle.result.then(
+    () => (done = true),
+    () => (done = true)
+  );
+  while (!done) {
+    if (task.cancelled) {
+      await handle.kill();
+      break;
+    }
+    await editor.delay(200);
+  }
+  return await handle.result;
+}
+
+/**
+ * Run `selected` tests, or every test when `selected` is null, and record
+ * the results
+ */
+async function runTests(selected: TestItem[] | null): Promise<void> {
+  if (running) {
+    editor.setStatus(editor.t("status.already_running"));
+    return;
+  }
+  if (tests.length === 0) refresh();
+  const toRun = selected ?? tests;
+  if (toRun.length === 0) {
+    editor.setStatus(editor.t("status.no_tests"));
+    return;
+  }
+
+  const frameworks = [...new Set(toRun.map((t) => t.framework))];
+  const outputs: string[] = [];
+  running = true;
+  try {
+    await editor.runTask(editor.t("task.running"), async (task: TaskHandle) => {
+      for (const framework of frameworks) {
+        if (task.cancelled) break;
+        const frameworkTests = toRun.filter((t) => t.framework === framework);
+        const args = expandCommand(
+          selected ? framework.runTests : framework.runAll,
+          frameworkTests
+        );
+        task.progress(args.join(" "));
+        const result = await runCommand(task, args);
+        const output = result.stdout + result.stderr;
+        outputs.push(`$ ${args.join(" ")}\n${output.trimEnd()}\n`);
+        recordResults(framework, output, frameworkTests);
+      }
+    });
+  } catch (e) {
+    editor.debug(`[test_explorer] Test run stopped: ${e}`);
+  } finally {
+    running = false;
+  }
+
+  lastOutput = outputs.join("\n");
+  const passed = toRun.filter((t) => statuses.get(testKey(t.file, t.name)) === "passed").length;
+  const failed = toRun.filter((t) => statuses.get(testKey(t.file, t.name)) === "failed").length;
+  editor.setStatus(
+    editor.t("status.results", { passed: String(passed), failed: String(failed) })
+  );
+  provider.notify();
+  updateAllIndicators();
+}
+
+// =============================================================================
+// Gutter
+// =============================================================================
+
+/** Show the pass/fail marks of the tests in `bufferId`'s file */
+function updateIndicators(bufferId: number): void {
+  const path = editor.getBufferPath(bufferId);
+  if (!path) return;
+  editor.clearLineIndicators(bufferId, NAMESPACE);
+  for (const test of tests) {
+    if (test.file !== path) continue;
+    const status = statuses.get(testKey(test.file, test.name));
+    if (!status) continue;
+    const color = COLORS[status];
+    editor.setLineIndicator(
+      bufferId,
+      test.line - 1,
+      NAMESPACE,
+      SYMBOLS[status],
+      color[0],
+      color[1],
+      color[2],
+      PRIORITY
+    );
+  }
+}
+
+function updateAllIndicators(): void {
+  const files = new Set(tests.map((t) => t.file));
+  for (const file of files) {
+    const bufferId = editor.findBufferByPath(file);
+    if (bufferId) updateIndicators(bufferId);
+  }
+}
+
+// =============================================================================
+// Panel
+// =============================================================================
+
+function refresh(): void {
+  tests = discoverTests();
+  // Forget results of tests that no longer exist
+  const keys = new Set(tests.map((t) => testKey(t.file, t.name)));
+  for (const key of [...statuses.keys()]) {
+    if (!keys.has(key)) statuses.delete(key);
+  }
+}
+
+const provider = createLiveProvider(() => tests);
+
+const finder = new Finder<TestItem>(editor, {
+  id: "test-explorer",
+  format: (t) => {
+    const status = statuses.get(testKey(t.file, t.name));
+    const icon = status ? SYMBOLS[status] : "·";
+    return {
+      label: `${icon} ${t.name}`,
+      description: `${t.framework.name}:${t.line}`,
+      location: { file: t.file, line: t.line, column: 1 },
+    };
+  },
+  groupBy: "file",
+  panelKeys: [
+    ["r", "test_explorer_run_test"],
+    ["f", "test_explorer_run_file"],
+    ["a", "test_explorer_run_all"],
+    ["F", "test_explorer_rerun_failed"],
+    ["o", "test_explorer_show_output"],
+    ["g", "test_explorer_refresh"],
+  ],
+});
+
+function panelTitle(): string {
+  return editor.t("panel.title", { count: String(tests.length) });
+}
+
+/** The test under the cursor: in the panel, or in the active file */
+function currentTest(): TestItem | undefined {
+  const selected = finder.selectedItem;
+  if (selected) return selected;
+
+  const bufferId = editor.getActiveBufferId();
+  const path = editor.getBufferPath(bufferId);
+  const line = cursorLines.get(bufferId) ?? 1;
+  if (tests.length === 0) refresh();
+  // The nearest test at or above the cursor
+  let nearest: TestItem | undefined;
+  for (const test of tests) {
+    if (test.file === path && test.line <= line) {
+      if (!nearest || test.line > nearest.line) nearest = test;
+    }
+  }
+  return nearest;
+}
+
+/** The file of the test under the cursor, or the active file */
+function currentFile(): string | undefined {
+  const selected = finder.selectedItem;
+  if (selected) return selected.file;
+  return editor.getBufferPath(editor.getActiveBufferId()) || undefined;
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+globalThis.test_explorer_show = async function (): Promise<void> {
+  refresh();
+  if (tests.length === 0) {
+    editor.setStatus(editor.t("status.no_tests"));
+  }
+  if (finder.isOpen) {
+    finder.updateTitle(panelTitle());
+    provider.notify();
+    return;
+  }
+  sourceSplitId = editor.getActiveSplitId();
+  await finder.livePanel({
+    title: panelTitle(),
+    provider: provider as FinderProvider<TestItem>,
+    ratio: 0.35,
+  });
+};
+
+globalThis.test_explorer_refresh = function (): void {
+  refresh();
+  finder.updateTitle(panelTitle());
+  provider.notify();
+  updateAllIndicators();
+  editor.setStatus(editor.t("status.found", { count: String(tests.length) }));
+};
+
+globalThis.test_explorer_run_test = async function (): Promise<void> {
+  const test = currentTest();
+  if (!test) {
+    editor.setStatus(editor.t("status.no_test_at_cursor"));
+    return;
+  }
+  await runTests([test]);
+};
+
+globalThis.test_explorer_run_file = async function (): Promise<void> {
+  if (tests.length === 0) refresh();
+  const file = currentFile();
+  const fileTests = tests.filter((t) => t.file === file);
+  if (fileTests.length === 0) {
+    editor.setStatus(editor.t("status.no_tests_in_file"));
+    return;
+  }
+  await runTests(fileTests);
+};
+
+globalThis.test_explorer_run_all = async function (): Promise<void> {
+  refresh();
+  await runTests(null);
+};
+
+globalThis.test_explorer_rerun_failed = async function (): Promise<void> {
+  const failed = tests.filter(
+    (t) => statuses.get(testKey(t.file, t.name)) === "failed"
+  );
+  if (failed.length === 0) {
+    editor.setStatus(editor.t("status.no_failed"));
+    return;
+  }
+  await runTests(failed);
+};
+
+globalThis.test_explorer_show_output = async function (): Promise<void> {
+  if (!lastOutput) {
+    editor.setStatus(editor.t("status.no_output"));
+    return;
+  }
+  // Keep the panel in its split
+  if (finder.isOpen && sourceSplitId !== null) {
+    editor.focusSplit(sourceSplitId);
+  }
+  const output = await editor.createOutputBuffer(OUTPUT_NAME);
+  editor.clearOutput(output);
+  editor.appendOutput(output, lastOutput);
+};
+
+// =============================================================================
+// Events
+// =============================================================================
+
+globalThis.on_test_explorer_buffer_shown = function (data: { buffer_id: number }): void {
+  if (statuses.size > 0) updateIndicators(data.buffer_id);
+};
+
+globalThis.on_test_explorer_cursor_moved = function (data: {
+  buffer_id: number;
+  line: number;
+}): void {
+  cursorLines.set(data.buffer_id, data.line);
+};
+
+globalThis.on_test_explorer_buffer_closed = function (data: { buffer_id: number }): void {
+  cursorLines.delete(data.buffer_id);
+};
+
+editor.on("after_file_open", "on_test_explorer_buffer_shown");
+editor.on("buffer_activated", "on_test_explorer_buffer_shown");
+editor.on("cursor_moved", "on_test_explorer_cursor_moved");
+editor.on("buffer_closed", "on_test_explorer_buffer_closed");
+
+editor.registerCommand(
+  "%cmd.show",
+  "%cmd.show_desc",
+  "test_explorer_show",
+  null
+);
+editor.registerCommand(
+  "%cmd.run_test",
+  "%cmd.run_test_desc",
+  "test_explorer_run_test",
+  null
+);
+editor.registerCommand(
+  "%cmd.run_file",
+  "%cmd.run_file_desc",
+  "test_explorer_run_file",
+  null
+);
+editor.registerCommand(
+  "%cmd.run_all",
+  "%cmd.run_all_desc",
+  "test_explorer_run_all",
+  null
+);
+editor.registerCommand(
+  "%cmd.rerun_failed",
+  "%cmd.rerun_failed_desc",
+  "test_explorer_rerun_failed",
+  null
+);
+editor.registerCommand(
+  "%cmd.show_output",
+  "%cmd.show_output_desc",
+  "test_explorer_show_output",
+  null
+);
+
+editor.debug("Test Explorer plugin loaded");
@@ -133,8 +133,8 @@ function loadThemeSections(): ThemeSection[] {
   const sectionOrder = ["editor", "ui", "search", "diagnostic", "syntax"];
 
   for (const [sectionName, sectionSchema] of Object.entries(properties)) {
-    // Skip "name" field - it's not a color section
-    if (sectionName === "name") continue;
+    // Skip "name", "fallbacks" and "semantic" - they're not fixed color sections
+    if (sectionName === "name" || sectionName === "fallbacks" || sectionName === "semantic") continue;
 
     const sectionObj = sectionSchema as Record<string, unknown>;
     const sectionDesc = (sectionObj.description as string) || "";
@@ -221,6 +221,8 @@ interface ThemeEditorState {
   selectedIndex: number;
   /** Whether there are unsaved changes */
   hasChanges: boolean;
+  /** Whether edits are being previewed on the running editor */
+  previewing: boolean;
   /** Available built-in themes */
   builtinThemes: string[];
   /** Pending save name for overwrite confirmation */
@@ -247,6 +249,7 @@ function isThemeEditorOpen(): boolean {
   // If buffer doesn't exist, reset our stale state
   if (!exists) {
     editor.debug(`Theme editor buffer ${state.bufferId} no longer exists, resetting state`);
+    endPreview();
     state.bufferId = null;
     state.splitId = null;
     state.themeData = {};
@@ -270,6 +273,7 @@ const state: ThemeEditorState = {
   visibleFields: [],
   selectedIndex: 0,
   hasChanges: false,
+  previewing: false,
   builtinThemes: [],
   pendingSaveName: null,
   isBuiltin: false,
@@ -1036,6 +1040,7 @@ globalThis.onThemeColorPromptConfirmed = function(args: {
     // Valid color - apply it
     setNestedValue(state.themeData, path, result.value);
     state.hasChanges = !deepEqual(state.themeData, state.originalThemeData);
+    previewColor(path, result.value);
 
     const entries = buildDisplayEntries();
     if (state.bufferId !== null) {
@@ -1086,6 +1091,9 @@ globalThis.onThemeOpenPromptConfirmed = async function(args: {
 }): Promise<boolean> {
   if (args.prompt_type !== "theme-open") return true;
 
+  // Unsaved edits of the previous theme stop being previewed
+  endPreview();
+
   const value = args.input.trim();
 
   // Parse the value to determine if it's user or builtin
@@ -1320,6 +1328,8 @@ async function saveTheme(name?: string, restorePath?: string | null): Promise<bo
     state.isBuiltin = false; // After saving, it's now a user theme
     state.originalThemeData = deepClone(state.themeData);
     state.hasChanges = false;
+    // The saved theme is applied below, replacing the preview
+    state.previewing = false;
 
     // Update display
     const entries = buildDisplayEntries();
@@ -1344,6 +1354,37 @@ async function saveTheme(name?: string, restorePath?: string | null): Promise<bo
   }
 }
 
+/**
+ * Preview an edited color on the running editor. The first edit previews the
+ * whole theme being edited, so its other colors show as well.
+ */
+function previewColor(path: string, value: ColorValue): void {
+  if (!state.previewing) {
+    state.previewing = true;
+    for (const section of getThemeSections()) {
+      const sectionData = state.themeData[section.name] as Record<string, unknown> | undefined;
+      if (!sectionData) continue;
+      for (const field of section.fields) {
+        const fieldValue = sectionData[field.key];
+        if (fieldValue !== undefined) {
+          editor.setThemeColor(`${section.name}.${field.key}`, fieldValue as ThemeColor);
+        }
+      }
+    }
+  }
+  editor.setThemeColor(path, value);
+}
+
+/**
+ * Stop previewing unsaved edits and go back to the configured theme
+ */
+function endPreview(): void {
+  if (state.previewing) {
+    state.previewing = false;
+    editor.reloadThemes();
+  }
+}
+
 /**
  * Create a default/empty theme
  */
@@ -1787,6 +1828,8 @@ globalThis.theme_editor_close = function(): void {
  * Actually close the editor (called after confirmation or when no changes)
  */
 function doCloseEditor(): void {
+  endPreview();
+
   // Close the buffer (this will switch to another buffer in the same split)
   if (state.bufferId !== null) {
     editor.closeBuffer(state.bufferId);
@@ -0,0 +1,746 @@
+/// <reference path="./lib/fresh.d.ts" />
+
+/**
+ * Unicode Picker Plugin
+ *
+ * "Insert Unicode Character" opens a picker over accented letters, Greek,
+ * punctuation, currency, math, arrows, box drawing, emoji and special
+ * spaces. Type part of a character's name ("e acute", "arrow right"), its
+ * category, its code point ("U+00E9" or "e9") or the character itself;
+ * Enter inserts it at the cursor. A code point that is not in the list can
+ * still be inserted by typing it.
+ *
+ * The characters picked last come first when the query is empty. They are
+ * remembered in `unicode_picker_recent.json` in the config directory.
+ */
+
+import { Finder } from "./lib/finder.ts";
+
+const editor = getEditor();
+
+const MAX_RECENT = 20;
+const MAX_RESULTS = 200;
+const RECENT_FILE = "unicode_picker_recent.json";
+
+interface UnicodeChar {
+  codepoint: number;
+  name: string;
+  category: string;
+}
+
+/** Characters by category, each "HEX NAME" with its Unicode name */
+const CHARACTERS: Array<[string, string[]]> = [
+  ["Latin", [
+    "00C0 LATIN CAPITAL LETTER A WITH GRAVE",
+    "00C1 LATIN CAPITAL LETTER A WITH ACUTE",
+    "00C2 LATIN CAPITAL LETTER A WITH CIRCUMFLEX",
+    "00C3 LATIN CAPITAL LETTER A WITH TILDE",
+    "00C4 LATIN CAPITAL LETTER A WITH DIAERESIS",
+    "00C5 LATIN CAPITAL LETTER A WITH RING ABOVE",
+    "00C6 LATIN CAPITAL LETTER AE",
+    "00C7 LATIN CAPITAL LETTER C WITH CEDILLA",
+    "00C8 LATIN CAPITAL LETTER E WITH GRAVE",
+    "00C9 LATIN CAPITAL LETTER E WITH ACUTE",
+    "00CA LATIN CAPITAL LETTER E WITH CIRCUMFLEX",
+    "00CB LATIN CAPITAL LETTER E WITH DIAERESIS",
+    "00CC LATIN CAPITAL LETTER I WITH GRAVE",
+    "00CD LATIN CAPITAL LETTER I WITH ACUTE",
+    "00CE LATIN CAPITAL LETTER I WITH CIRCUMFLEX",
+    "00CF LATIN CAPITAL LETTER I WITH DIAERESIS",
+    "00D0 LATIN CAPITAL LETTER ETH",
+    "00D1 LATIN CAPITAL LETTER N WITH TILDE",
+    "00D2 LATIN CAPITAL LETTER O WITH GRAVE",
+    "00D3 LATIN CAPITAL LETTER O WITH ACUTE",
+    "00D4 LATIN CAPITAL LETTER O WITH CIRCUMFLEX",
+    "00D5 LATIN CAPITAL LETTER O WITH TILDE",
+    "00D6 LATIN CAPITAL LETTER O WITH DIAERESIS",
+    "00D7 MULTIPLICATION SIGN",
+    "00D8 LATIN CAPITAL LETTER O WITH STROKE",
+    "00D9 LATIN CAPITAL LETTER U WITH GRAVE",
+    "00DA LATIN CAPITAL LETTER U WITH ACUTE",
+    "00DB LATIN CAPITAL LETTER U WITH CIRCUMFLEX",
+    "00DC LATIN CAPITAL LETTER U WITH DIAERESIS",
+    "00DD LATIN CAPITAL LETTER Y WITH ACUTE",
+    "00DE LATIN CAPITAL LETTER THORN",
+    "00DF LATIN SMALL LETTER SHARP S",
+    "00E0 LATIN SMALL LETTER A WITH GRAVE",
+    "00E1 LATIN SMALL LETTER A WITH ACUTE",
+    "00E2 LATIN SMALL LETTER A WITH CIRCUMFLEX",
+    "00E3 LATIN SMALL LETTER A WITH TILDE",
+    "00E4 LATIN SMALL LETTER A WITH DIAERESIS",
+    "00E5 LATIN SMALL LETTER A WITH RING ABOVE",
+    "00E6 LATIN SMALL LETTER AE",
+    "00E7 LATIN SMALL LETTER C WITH CEDILLA",
+    "00E8 LATIN SMALL LETTER E WITH GRAVE",
+    "00E9 LATIN SMALL LETTER E WITH ACUTE",
+    "00EA LATIN SMALL LETTER E WITH CIRCUMFLEX",
+    "00EB LATIN SMALL LETTER E WITH DIAERESIS",
+    "00EC LATIN SMALL LETTER I WITH GRAVE",
+    "00ED LATIN SMALL LETTER I WITH ACUTE",
+    "00EE LATIN SMALL LETTER I WITH CIRCUMFLEX",
+    "00EF LATIN SMALL LETTER I WITH DIAERESIS",
+    "00F0 LATIN SMALL LETTER ETH",
+    "00F1 LATIN SMALL LETTER N WITH TILDE",
+    "00F2 LATIN SMALL LETTER O WITH GRAVE",
+    "00F3 LATIN SMALL LETTER O WITH ACUTE",
+    "00F4 LATIN SMALL LETTER O WITH CIRCUMFLEX",
+    "00F5 LATIN SMALL LETTER O WITH TILDE",
+    "00F6 LATIN SMALL LETTER O WITH DIAERESIS",
+    "00F7 DIVISION SIGN",
+    "00F8 LATIN SMALL LETTER O WITH STROKE",
+    "00F9 LATIN SMALL LETTER U WITH GRAVE",
+    "00FA LATIN SMALL LETTER U WITH ACUTE",
+    "00FB LATIN SMALL LETTER U WITH CIRCUMFLEX",
+    "00FC LATIN SMALL LETTER U WITH DIAERESIS",
+    "00FD LATIN SMALL LETTER Y WITH ACUTE",
+    "00FE LATIN SMALL LETTER THORN",
+    "00FF LATIN SMALL LETTER Y WITH DIAERESIS",
+    "0100 LATIN CAPITAL LETTER A WITH MACRON",
+    "0101 LATIN SMALL LETTER A WITH MACRON",
+    "0102 LATIN CAPITAL LETTER A WITH BREVE",
+    "0103 LATIN SMALL LETTER A WITH BREVE",
+    "0104 LATIN CAPITAL LETTER A WITH OGONEK",
+    "0105 LATIN SMALL LETTER A WITH OGONEK",
+    "0106 LATIN CAPITAL LETTER C WITH ACUTE",
+    "0107 LATIN SMALL LETTER C WITH ACUTE",
+    "0108 LATIN CAPITAL LETTER C WITH CIRCUMFLEX",
+    "0109 LATIN SMALL LETTER C WITH CIRCUMFLEX",
+    "010A LATIN CAPITAL LETTER C WITH DOT ABOVE",
+    "010B LATIN SMALL LETTER C WITH DOT ABOVE",
+    "010C LATIN CAPITAL LETTER C WITH CARON",
+    "010D LATIN SMALL LETTER C WITH CARON",
+    "010E LATIN CAPITAL LETTER D WITH CARON",
+    "010F LATIN SMALL LETTER D WITH CARON",
+    "0110 LATIN CAPITAL LETTER D WITH STROKE",
+    "0111 LATIN SMALL LETTER D WITH STROKE",
+    "0112 LATIN CAPITAL LETTER E WITH MACRON",
+    "0113 LATIN SMALL LETTER E WITH MACRON",
+    "0114 LATIN CAPITAL LETTER E WITH BREVE",
+    "0115 LATIN SMALL LETTER E WITH BREVE",
+    "0116 LATIN CAPITAL LETTER E WITH DOT ABOVE",
+    "0117 LATIN SMALL LETTER E WITH DOT ABOVE",
+    "0118 LATIN CAPITAL LETTER E WITH OGONEK",
+    "0119 LATIN SMALL LETTER E WITH OGONEK",
+    "011A LATIN CAPITAL LETTER E WITH CARON",
+    "011B LATIN SMALL LETTER E WITH CARON",
+    "011C LATIN CAPITAL LETTER G WITH CIRCUMFLEX",
+    "011D LATIN SMALL LETTER G WITH CIRCUMFLEX",
+    "011E LATIN CAPITAL LETTER G WITH BREVE",
+    "011F LATIN SMALL LETTER G WITH BREVE",
+    "0120 LATIN CAPITAL LETTER G WITH DOT ABOVE",
+    "0121 LATIN SMALL LETTER G WITH DOT ABOVE",
+    "0122 LATIN CAPITAL LETTER G WITH CEDILLA",
+    "0123 LATIN SMALL LETTER G WITH CEDILLA",
+    "0124 LATIN CAPITAL LETTER H WITH CIRCUMFLEX",
+    "0125 LATIN SMALL LETTER H WITH CIRCUMFLEX",
+    "0126 LATIN CAPITAL LETTER H WITH STROKE",
+    "0127 LATIN SMALL LETTER H WITH STROKE",
+    "0128 LATIN CAPITAL LETTER I WITH TILDE",
+    "0129 LATIN SMALL LETTER I WITH TILDE",
+    "012A LATIN CAPITAL LETTER I WITH MACRON",
+    "012B LATIN SMALL LETTER I WITH MACRON",
+    "012C LATIN CAPITAL LETTER I WITH BREVE",
+    "012D LATIN SMALL LETTER I WITH BREVE",
+    "012E LATIN CAPITAL LETTER I WITH OGONEK",
+    "012F LATIN SMALL LETTER I WITH OGONEK",
+    "0130 LATIN CAPITAL LETTER I WITH DOT ABOVE",
+    "0131 LATIN SMALL LETTER DOTLESS I",
+    "0132 LATIN CAPITAL LIGATURE IJ",
+    "0133 LATIN SMALL LIGATURE IJ",
+    "0134 LATIN CAPITAL LETTER J WITH CIRCUMFLEX",
+    "0135 LATIN SMALL LETTER J WITH CIRCUMFLEX",
+    "0136 LATIN CAPITAL LETTER K WITH CEDILLA",
+    "0137 LATIN SMALL LETTER K WITH CEDILLA",
+    "0138 LATIN SMALL LETTER KRA",
+    "0139 LATIN CAPITAL LETTER L WITH ACUTE",
+    "013A LATIN SMALL LETTER L WITH ACUTE",
+    "013B LATIN CAPITAL LETTER L WITH CEDILLA",
+    "013C LATIN SMALL LETTER L WITH CEDILLA",
+    "013D LATIN CAPITAL LETTER L WITH CARON",
+    "013E LATIN SMALL LETTER L WITH CARON",
+    "013F LATIN CAPITAL LETTER L WITH MIDDLE DOT",
+    "0140 LATIN SMALL LETTER L WITH MIDDLE DOT",
+    "0141 LATIN CAPITAL LETTER L WITH STROKE",
+    "0142 LATIN SMALL LETTER L WITH STROKE",
+    "0143 LATIN CAPITAL LETTER N WITH ACUTE",
+    "0144 LATIN SMALL LETTER N WITH ACUTE",
+    "0145 LATIN CAPITAL LETTER N WITH CEDILLA",
+    "0146 LATIN SMALL LETTER N WITH CEDILLA",
+    "0147 LATIN CAPITAL LETTER N WITH CARON",
+    "0148 LATIN SMALL LETTER N WITH CARON",
+    "0149 LATIN SMALL LETTER N PRECEDED BY APOSTROPHE",
+    "014A LATIN CAPITAL LETTER ENG",
+    "014B LATIN SMALL LETTER ENG",
+    "014C LATIN CAPITAL LETTER O WITH MACRON",
+    "014D LATIN SMALL LETTER O WITH MACRON",
+    "014E LATIN CAPITAL LETTER O WITH BREVE",
+    "014F LATIN SMALL LETTER O WITH BREVE",
+    "0150 LATIN CAPITAL LETTER O WITH DOUBLE ACUTE",
+    "0151 LATIN SMALL LETTER O WITH DOUBLE ACUTE",
+    "0152 LATIN CAPITAL LIGATURE OE",
+    "0153 LATIN SMALL LIGATURE OE",
+    "0154 LATIN CAPITAL LETTER R WITH ACUTE",
+    "0155 LATIN SMALL LETTER R WITH ACUTE",
+    "0156 LATIN CAPITAL LETTER R WITH CEDILLA",
+    "0157 LATIN SMALL LETTER R WITH CEDILLA",
+    "0158 LATIN CAPITAL LETTER R WITH CARON",
+    "0159 LATIN SMALL LETTER R WITH CARON",
+    "015A LATIN CAPITAL LETTER S WITH ACUTE",
+    "015B LATIN SMALL LETTER S WITH ACUTE",
+    "015C LATIN CAPITAL LETTER S WITH CIRCUMFLEX",
+    "015D LATIN SMALL LETTER S WITH CIRCUMFLEX",
+    "015E LATIN CAPITAL LETTER S WITH CEDILLA",
+    "015F LATIN SMALL LETTER S WITH CEDILLA",
+    "0160 LATIN CAPITAL LETTER S WITH CARON",
+    "0161 LATIN SMALL LETTER S WITH CARON",
+    "0162 LATIN CAPITAL LETTER T WITH CEDILLA",
+    "0163 LATIN SMALL LETTER T WITH CEDILLA",
+    "0164 LATIN CAPITAL LETTER T WITH CARON",
+    "0165 LATIN SMALL LETTER T WITH CARON",
+    "0166 LATIN CAPITAL LETTER T WITH STROKE",
+    "0167 LATIN SMALL LETTER T WITH STROKE",
+    "0168 LATIN CAPITAL LETTER U WITH TILDE",
+    "0169 LATIN SMALL LETTER U WITH TILDE",
+    "016A LATIN CAPITAL LETTER U WITH MACRON",
+    "016B LATIN SMALL LETTER U WITH MACRON",
+    "016C LATIN CAPITAL LETTER U WITH BREVE",
+    "016D LATIN SMALL LETTER U WITH BREVE",
+    "016E LATIN CAPITAL LETTER U WITH RING ABOVE",
+    "016F LATIN SMALL LETTER U WITH RING ABOVE",
+    "0170 LATIN CAPITAL LETTER U WITH DOUBLE ACUTE",
+    "0171 LATIN SMALL LETTER U WITH DOUBLE ACUTE",
+    "0172 LATIN CAPITAL LETTER U WITH OGONEK",
+    "0173 LATIN SMALL LETTER U WITH OGONEK",
+    "0174 LATIN CAPITAL LETTER W WITH CIRCUMFLEX",
+    "0175 LATIN SMALL LETTER W WITH CIRCUMFLEX",
+    "0176 LATIN CAPITAL LETTER Y WITH CIRCUMFLEX",
+    "0177 LATIN SMALL LETTER Y WITH CIRCUMFLEX",
+    "0178 LATIN CAPITAL LETTER Y WITH DIAERESIS",
+    "0179 LATIN CAPITAL LETTER Z WITH ACUTE",
+    "017A LATIN SMALL LETTER Z WITH ACUTE",
+    "017B LATIN CAPITAL LETTER Z WITH DOT ABOVE",
+    "017C LATIN SMALL LETTER Z WITH DOT ABOVE",
+    "017D LATIN CAPITAL LETTER Z WITH CARON",
+    "017E LATIN SMALL LETTER Z WITH CARON",
+    "017F LATIN SMALL LETTER LONG S",
+  ]],
+  ["Greek", [
+    "0391 GREEK CAPITAL LETTER ALPHA",
+    "0392 GREEK CAPITAL LETTER BETA",
+    "0393 GREEK CAPITAL LETTER GAMMA",
+    "0394 GREEK CAPITAL LETTER DELTA",
+    "0395 GREEK CAPITAL LETTER EPSILON",
+    "0396 GREEK CAPITAL LETTER ZETA",
+    "0397 GREEK CAPITAL LETTER ETA",
+    "0398 GREEK CAPITAL LETTER THETA",
+    "0399 GREEK CAPITAL LETTER IOTA",
+    "039A GREEK CAPITAL LETTER KAPPA",
+    "039B GREEK CAPITAL LETTER LAMDA",
+    "039C GREEK CAPITAL LETTER MU",
+    "039D GREEK CAPITAL LETTER NU",
+    "039E GREEK CAPITAL LETTER XI",
+    "039F GREEK CAPITAL LETTER OMICRON",
+    "03A0 GREEK CAPITAL LETTER PI",
+    "03A1 GREEK CAPITAL LETTER RHO",
+    "03A3 GREEK CAPITAL LETTER SIGMA",
+    "03A4 GREEK CAPITAL LETTER TAU",
+    "03A5 GREEK CAPITAL LETTER UPSILON",
+    "03A6 GREEK CAPITAL LETTER PHI",
+    "03A7 GREEK CAPITAL LETTER CHI",
+    "03A8 GREEK CAPITAL LETTER PSI",
+    "03A9 GREEK CAPITAL LETTER OMEGA",
+    "03AA GREEK CAPITAL LETTER IOTA WITH DIALYTIKA",
+    "03AB GREEK CAPITAL LETTER UPSILON WITH DIALYTIKA",
+    "03AC GREEK SMALL LETTER ALPHA WITH TONOS",
+    "03AD GREEK SMALL LETTER EPSILON WITH TONOS",
+    "03AE GREEK SMALL LETTER ETA WITH TONOS",
+    "03AF GREEK SMALL LETTER IOTA WITH TONOS",
+    "03B0 GREEK SMALL LETTER UPSILON WITH DIALYTIKA AND TONOS",
+    "03B1 GREEK SMALL LETTER ALPHA",
+    "03B2 GREEK SMALL LETTER BETA",
+    "03B3 GREEK SMALL LETTER GAMMA",
+    "03B4 GREEK SMALL LETTER DELTA",
+    "03B5 GREEK SMALL LETTER EPSILON",
+    "03B6 GREEK SMALL LETTER ZETA",
+    "03B7 GREEK SMALL LETTER ETA",
+    "03B8 GREEK SMALL LETTER THETA",
+    "03B9 GREEK SMALL LETTER IOTA",
+    "03BA GREEK SMALL LETTER KAPPA",
+    "03BB GREEK SMALL LETTER LAMDA",
+    "03BC GREEK SMALL LETTER MU",
+    "03BD GREEK SMALL LETTER NU",
+    "03BE GREEK SMALL LETTER XI",
+    "03BF GREEK SMALL LETTER OMICRON",
+    "03C0 GREEK SMALL LETTER PI",
+    "03C1 GREEK SMALL LETTER RHO",
+    "03C2 GREEK SMALL LETTER FINAL SIGMA",
+    "03C3 GREEK SMALL LETTER SIGMA",
+    "03C4 GREEK SMALL LETTER TAU",
+    "03C5 GREEK SMALL LETTER UPSILON",
+    "03C6 GREEK SMALL LETTER PHI",
+    "03C7 GREEK SMALL LETTER CHI",
+    "03C8 GREEK SMALL LETTER PSI",
+    "03C9 GREEK SMALL LETTER OMEGA",
+  ]],
+  ["Punctuation", [
+    "00A1 INVERTED EXCLAMATION MARK",
+    "00BF INVERTED QUESTION MARK",
+    "00AB LEFT-POINTING DOUBLE ANGLE QUOTATION MARK",
+    "00BB RIGHT-POINTING DOUBLE ANGLE QUOTATION MARK",
+    "2039 SINGLE LEFT-POINTING ANGLE QUOTATION MARK",
+    "203A SINGLE RIGHT-POINTING ANGLE QUOTATION MARK",
+    "00A7 SECTION SIGN",
+    "00B6 PILCROW SIGN",
+    "00B7 MIDDLE DOT",
+    "2022 BULLET",
+    "2026 HORIZONTAL ELLIPSIS",
+    "2013 EN DASH",
+    "2014 EM DASH",
+    "2018 LEFT SINGLE QUOTATION MARK",
+    "2019 RIGHT SINGLE QUOTATION MARK",
+    "201A SINGLE LOW-9 QUOTATION MARK",
+    "201C LEFT DOUBLE QUOTATION MARK",
+    "201D RIGHT DOUBLE QUOTATION MARK",
+    "201E DOUBLE LOW-9 QUOTATION MARK",
+    "2020 DAGGER",
+    "2021 DOUBLE DAGGER",
+    "2030 PER MILLE SIGN",
+    "2032 PRIME",
+    "2033 DOUBLE PRIME",
+    "203D INTERROBANG",
+  ]],
+  ["Currency", [
+    "00A2 CENT SIGN",
+    "00A3 POUND SIGN",
+    "00A4 CURRENCY SIGN",
+    "00A5 YEN SIGN",
+    "20AC EURO SIGN",
+    "20A9 WON SIGN",
+    "20B9 INDIAN RUPEE SIGN",
+    "20BD RUBLE SIGN",
+    "20BA TURKISH LIRA SIGN",
+    "20BF BITCOIN SIGN",
+    "20A3 FRENCH FRANC SIGN",
+    "20AA NEW SHEQEL SIGN",
+    "20AB DONG SIGN",
+    "0E3F THAI CURRENCY SYMBOL BAHT",
+  ]],
+  ["Math", [
+    "00B1 PLUS-MINUS SIGN",
+    "2200 FOR ALL",
+    "2202 PARTIAL DIFFERENTIAL",
+    "2203 THERE EXISTS",
+    "2205 EMPTY SET",
+    "2206 INCREMENT",
+    "2207 NABLA",
+    "2208 ELEMENT OF",
+    "2209 NOT AN ELEMENT OF",
+    "220B CONTAINS AS MEMBER",
+    "220F N-ARY PRODUCT",
+    "2211 N-ARY SUMMATION",
+    "2212 MINUS SIGN",
+    "221A SQUARE ROOT",
+    "221D PROPORTIONAL TO",
+    "221E INFINITY",
+    "2227 LOGICAL AND",
+    "2228 LOGICAL OR",
+    "2229 INTERSECTION",
+    "222A UNION",
+    "222B INTEGRAL",
+    "2234 THEREFORE",
+    "223C TILDE OPERATOR",
+    "2245 APPROXIMATELY EQUAL TO",
+    "2248 ALMOST EQUAL TO",
+    "2260 NOT EQUAL TO",
+    "2261 IDENTICAL TO",
+    "2264 LESS-THAN OR EQUAL TO",
+    "2265 GREATER-THAN OR EQUAL TO",
+    "2282 SUBSET OF",
+    "2283 SUPERSET OF",
+    "2286 SUBSET OF OR EQUAL TO",
+    "2287 SUPERSET OF OR EQUAL TO",
+    "2218 RING OPERATOR",
+    "2219 BULLET OPERATOR",
+    "22C5 DOT OPERATOR",
+    "2295 CIRCLED PLUS",
+    "2297 CIRCLED TIMES",
+    "22A5 UP TACK",
+    "27E8 MATHEMATICAL LEFT ANGLE BRACKET",
+    "27E9 MATHEMATICAL RIGHT ANGLE BRACKET",
+    "2113 SCRIPT SMALL L",
+    "2115 DOUBLE-STRUCK CAPITAL N",
+    "2124 DOUBLE-STRUCK CAPITAL Z",
+    "211A DOUBLE-STRUCK CAPITAL Q",
+    "211D DOUBLE-STRUCK CAPITAL R",
+    "2102 DOUBLE-STRUCK CAPITAL C",
+  ]],
+  ["Arrows", [
+    "2190 LEFTWARDS ARROW",
+    "2191 UPWARDS ARROW",
+    "2192 RIGHTWARDS ARROW",
+    "2193 DOWNWARDS ARROW",
+    "2194 LEFT RIGHT ARROW",
+    "2195 UP DOWN ARROW",
+    "2196 NORTH WEST ARROW",
+    "2197 NORTH EAST ARROW",
+    "2198 SOUTH EAST ARROW",
+    "2199 SOUTH WEST ARROW",
+    "21A9 LEFTWARDS ARROW WITH HOOK",
+    "21AA RIGHTWARDS ARROW WITH HOOK",
+    "21B5 DOWNWARDS ARROW WITH CORNER LEFTWARDS",
+    "21BB CLOCKWISE OPEN CIRCLE ARROW",
+    "21D0 LEFTWARDS DOUBLE ARROW",
+    "21D1 UPWARDS DOUBLE ARROW",
+    "21D2 RIGHTWARDS DOUBLE ARROW",
+    "21D3 DOWNWARDS DOUBLE ARROW",
+    "21D4 LEFT RIGHT DOUBLE ARROW",
+    "21E7 UPWARDS WHITE ARROW",
+    "27F5 LONG LEFTWARDS ARROW",
+    "27F6 LONG RIGHTWARDS ARROW",
+    "27F7 LONG LEFT RIGHT ARROW",
+  ]],
+  ["Numbers", [
+    "00BC VULGAR FRACTION ONE QUARTER",
+    "00BD VULGAR FRACTION ONE HALF",
+    "00BE VULGAR FRACTION THREE QUARTERS",
+    "2153 VULGAR FRACTION ONE THIRD",
+    "2154 VULGAR FRACTION TWO THIRDS",
+    "215B VULGAR FRACTION ONE EIGHTH",
+    "2070 SUPERSCRIPT ZERO",
+    "00B9 SUPERSCRIPT ONE",
+    "00B2 SUPERSCRIPT TWO",
+    "00B3 SUPERSCRIPT THREE",
+    "2074 SUPERSCRIPT FOUR",
+    "2075 SUPERSCRIPT FIVE",
+    "2076 SUPERSCRIPT SIX",
+    "2077 SUPERSCRIPT SEVEN",
+    "2078 SUPERSCRIPT EIGHT",
+    "2079 SUPERSCRIPT NINE",
+    "2080 SUBSCRIPT ZERO",
+    "2081 SUBSCRIPT ONE",
+    "2082 SUBSCRIPT TWO",
+    "2083 SUBSCRIPT THREE",
+    "2084 SUBSCRIPT FOUR",
+    "2085 SUBSCRIPT FIVE",
+    "2086 SUBSCRIPT SIX",
+    "2087 SUBSCRIPT SEVEN",
+    "2088 SUBSCRIPT EIGHT",
+    "2089 SUBSCRIPT NINE",
+    "2160 ROMAN NUMERAL ONE",
+    "2161 ROMAN NUMERAL TWO",
+    "2162 ROMAN NUMERAL THREE",
+    "2163 ROMAN NUMERAL FOUR",
+    "2164 ROMAN NUMERAL FIVE",
+    "2165 ROMAN NUMERAL SIX",
+    "2166 ROMAN NUMERAL SEVEN",
+    "2167 ROMAN NUMERAL EIGHT",
+    "2168 ROMAN NUMERAL NINE",
+    "2169 ROMAN NUMERAL TEN",
+    "216A ROMAN NUMERAL ELEVEN",
+    "216B ROMAN NUMERAL TWELVE",
+    "2460 CIRCLED DIGIT ONE",
+    "2461 CIRCLED DIGIT TWO",
+    "2462 CIRCLED DIGIT THREE",
+    "2463 CIRCLED DIGIT FOUR",
+    "2464 CIRCLED DIGIT FIVE",
+    "2465 CIRCLED DIGIT SIX",
+    "2466 CIRCLED DIGIT SEVEN",
+    "2467 CIRCLED DIGIT EIGHT",
+    "2468 CIRCLED DIGIT NINE",
+    "2469 CIRCLED NUMBER TEN",
+  ]],
+  ["Symbols", [
+    "00A9 COPYRIGHT SIGN",
+    "00AE REGISTERED SIGN",
+    "2122 TRADE MARK SIGN",
+    "00B0 DEGREE SIGN",
+    "00B5 MICRO SIGN",
+    "00AA FEMININE ORDINAL INDICATOR",
+    "00BA MASCULINE ORDINAL INDICATOR",
+    "00AC NOT SIGN",
+    "00A6 BROKEN BAR",
+    "2116 NUMERO SIGN",
+    "2103 DEGREE CELSIUS",
+    "2109 DEGREE FAHRENHEIT",
+    "2713 CHECK MARK",
+    "2714 HEAVY CHECK MARK",
+    "2717 BALLOT X",
+    "2718 HEAVY BALLOT X",
+    "2605 BLACK STAR",
+    "2606 WHITE STAR",
+    "2665 BLACK HEART SUIT",
+    "2660 BLACK SPADE SUIT",
+    "2663 BLACK CLUB SUIT",
+    "2666 BLACK DIAMOND SUIT",
+    "266A EIGHTH NOTE",
+    "266B BEAMED EIGHTH NOTES",
+    "263A WHITE SMILING FACE",
+    "2639 WHITE FROWNING FACE",
+    "2600 BLACK SUN WITH RAYS",
+    "2601 CLOUD",
+    "2602 UMBRELLA",
+    "2603 SNOWMAN",
+    "2620 SKULL AND CROSSBONES",
+    "2622 RADIOACTIVE SIGN",
+    "262F YIN YANG",
+    "2691 BLACK FLAG",
+    "26A0 WARNING SIGN",
+    "2318 PLACE OF INTEREST SIGN",
+    "2325 OPTION KEY",
+    "21E5 RIGHTWARDS ARROW TO BAR",
+    "238B BROKEN CIRCLE WITH NORTHWEST ARROW",
+    "232B ERASE TO THE LEFT",
+    "2326 ERASE TO THE RIGHT",
+    "23CE RETURN SYMBOL",
+    "25A0 BLACK SQUARE",
+    "25A1 WHITE SQUARE",
+    "25B2 BLACK UP-POINTING TRIANGLE",
+    "25B6 BLACK RIGHT-POINTING TRIANGLE",
+    "25BC BLACK DOWN-POINTING TRIANGLE",
+    "25C0 BLACK LEFT-POINTING TRIANGLE",
+    "25C6 BLACK DIAMOND",
+    "25CB WHITE CIRCLE",
+    "25CF BLACK CIRCLE",
+    "25EF LARGE CIRCLE",
+  ]],
+  ["Box Drawing", [
+    "2500 BOX DRAWINGS LIGHT HORIZONTAL",
+    "2502 BOX DRAWINGS LIGHT VERTICAL",
+    "250C BOX DRAWINGS LIGHT DOWN AND RIGHT",
+    "2510 BOX DRAWINGS LIGHT DOWN AND LEFT",
+    "2514 BOX DRAWINGS LIGHT UP AND RIGHT",
+    "2518 BOX DRAWINGS LIGHT UP AND LEFT",
+    "251C BOX DRAWINGS LIGHT VERTICAL AND RIGHT",
+    "2524 BOX DRAWINGS LIGHT VERTICAL AND LEFT",
+    "252C BOX DRAWINGS LIGHT DOWN AND HORIZONTAL",
+    "2534 BOX DRAWINGS LIGHT UP AND HORIZONTAL",
+    "253C BOX DRAWINGS LIGHT VERTICAL AND HORIZONTAL",
+    "2550 BOX DRAWINGS DOUBLE HORIZONTAL",
+    "2551 BOX DRAWINGS DOUBLE VERTICAL",
+    "2554 BOX DRAWINGS DOUBLE DOWN AND RIGHT",
+    "2557 BOX DRAWINGS DOUBLE DOWN AND LEFT",
+    "255A BOX DRAWINGS DOUBLE UP AND RIGHT",
+    "255D BOX DRAWINGS DOUBLE UP AND LEFT",
+    "2560 BOX DRAWINGS DOUBLE VERTICAL AND RIGHT",
+    "2563 BOX DRAWINGS DOUBLE VERTICAL AND LEFT",
+    "2566 BOX DRAWINGS DOUBLE DOWN AND HORIZONTAL",
+    "2569 BOX DRAWINGS DOUBLE UP AND HORIZONTAL",
+    "256C BOX DRAWINGS DOUBLE VERTICAL AND HORIZONTAL",
+    "256D BOX DRAWINGS LIGHT ARC DOWN AND RIGHT",
+    "256E BOX DRAWINGS LIGHT ARC DOWN AND LEFT",
+    "256F BOX DRAWINGS LIGHT ARC UP AND LEFT",
+    "2570 BOX DRAWINGS LIGHT ARC UP AND RIGHT",
+    "2580 UPPER HALF BLOCK",
+    "2584 LOWER HALF BLOCK",
+    "2588 FULL BLOCK",
+    "258C LEFT HALF BLOCK",
+    "2590 RIGHT HALF BLOCK",
+    "2591 LIGHT SHADE",
+    "2592 MEDIUM SHADE",
+    "2593 DARK SHADE",
+  ]],
+  ["Emoji", [
+    "1F600 GRINNING FACE",
+    "1F602 FACE WITH TEARS OF JOY",
+    "1F603 SMILING FACE WITH OPEN MOUTH",
+    "1F609 WINKING FACE",
+    "1F60A SMILING FACE WITH SMILING EYES",
+    "1F60D SMILING FACE WITH HEART-SHAPED EYES",
+    "1F60E SMILING FACE WITH SUNGLASSES",
+    "1F610 NEUTRAL FACE",
+    "1F622 CRYING FACE",
+    "1F62D LOUDLY CRYING FACE",
+    "1F631 FACE SCREAMING IN FEAR",
+    "1F914 THINKING FACE",
+    "1F644 FACE WITH ROLLING EYES",
+    "1F44D THUMBS UP SIGN",
+    "1F44E THUMBS DOWN SIGN",
+    "1F44B WAVING HAND SIGN",
+    "1F44F CLAPPING HANDS SIGN",
+    "1F64F PERSON WITH FOLDED HANDS",
+    "1F4AA FLEXED BICEPS",
+    "1F440 EYES",
+    "2764 HEAVY BLACK HEART",
+    "1F525 FIRE",
+    "2728 SPARKLES",
+    "1F389 PARTY POPPER",
+    "1F680 ROCKET",
+    "1F41B BUG",
+    "1F4A1 ELECTRIC LIGHT BULB",
+    "1F4DD MEMO",
+    "1F4E6 PACKAGE",
+    "1F512 LOCK",
+    "1F513 OPEN LOCK",
+    "1F527 WRENCH",
+    "1F6A7 CONSTRUCTION SIGN",
+    "1F6A8 POLICE CARS REVOLVING LIGHT",
+    "2705 WHITE HEAVY CHECK MARK",
+    "274C CROSS MARK",
+    "26A1 HIGH VOLTAGE SIGN",
+    "2B50 WHITE MEDIUM STAR",
+    "1F3AF DIRECT HIT",
+    "1F4AF HUNDRED POINTS SYMBOL",
+  ]],
+  ["Spaces", [
+    "00A0 NO-BREAK SPACE",
+    "2002 EN SPACE",
+    "2003 EM SPACE",
+    "2009 THIN SPACE",
+    "200A HAIR SPACE",
+    "200B ZERO WIDTH SPACE",
+    "200C ZERO WIDTH NON-JOINER",
+    "200D ZERO WIDTH JOINER",
+    "2060 WORD JOINER",
+    "202F NARROW NO-BREAK SPACE",
+    "3000 IDEOGRAPHIC SPACE",
+    "00AD SOFT HYPHEN",
+    "FEFF ZERO WIDTH NO-BREAK SPACE",
+  ]],
+];
+
+const ALL: UnicodeChar[] = CHARACTERS.flatMap(([category, entries]) =>
+  entries.map((entry) => {
+    const space = entry.indexOf(" ");
+    return {
+      codepoint: parseInt(entry.slice(0, space), 16),
+      name: entry.slice(space + 1),
+      category,
+    };
+  })
+);
+
+function codepointLabel(codepoint: number): string {
+  return "U+" + codepoint.toString(16).toUpperCase().padStart(4, "0");
+}
+
+/** Spaces and joiners are invisible: show a placeholder in the list */
+function displayChar(item: UnicodeChar): string {
+  return item.category === "Spaces" ? "␣" : String.fromCodePoint(item.codepoint);
+}
+
+/** The code point typed as "U+00E9", "0xe9" or plain hex, if valid */
+function parseCodepoint(query: string): number | null {
+  const hex = query.replace(/^(u\+|0x)/i, "");
+  if (!/^[0-9a-f]{2,6}$/i.test(hex)) {
+    return null;
+  }
+  const codepoint = parseInt(hex, 16);
+  const surrogate = codepoint >= 0xd800 && codepoint <= 0xdfff;
+  return codepoint <= 0x10ffff && !surrogate ? codepoint : null;
+}
+
+// =============================================================================
+// Recently used
+// =============================================================================
+
+function recentPath(): string {
+  return editor.pathJoin(editor.getConfigDir(), RECENT_FILE);
+}
+
+function loadRecent(): number[] {
+  const content = editor.readFile(recentPath());
+  if (!content) {
+    return [];
+  }
+  try {
+    const parsed = JSON.parse(content);
+    return Array.isArray(parsed)
+      ? parsed.filter((c) => typeof c === "number")
+      : [];
+  } catch {
+    return [];
+  }
+}
+
+function rememberRecent(codepoint: number): void {
+  const recent = [codepoint, ...loadRecent().filter((c) => c !== codepoint)];
+  editor.writeFile(recentPath(), JSON.stringify(recent.slice(0, MAX_RECENT)));
+}
+
+function lookup(codepoint: number): UnicodeChar {
+  return (
+    ALL.find((item) => item.codepoint === codepoint) ?? {
+      codepoint,
+      name: codepointLabel(codepoint),
+      category: editor.t("category.codepoint"),
+    }
+  );
+}
+
+// =============================================================================
+// Picker
+// =============================================================================
+
+/** Every word of the query must appear in the name or category */
+function matches(item: UnicodeChar, words: string[]): boolean {
+  const text = `${item.name} ${item.category}`.toLowerCase();
+  return words.every((word) => text.includes(word));
+}
+
+function filterCharacters(items: UnicodeChar[], query: string): UnicodeChar[] {
+  const trimmed = query.trim();
+  if (trimmed === "") {
+    const recent = loadRecent().map(lookup);
+    const recentSet = new Set(recent.map((item) => item.codepoint));
+    return [
+      ...recent,
+      ...items.filter((item) => !recentSet.has(item.codepoint)),
+    ].slice(0, MAX_RESULTS);
+  }
+
+  const results: UnicodeChar[] = [];
+  const typed = parseCodepoint(trimmed);
+  if (typed !== null) {
+    results.push(lookup(typed));
+  }
+  const words = trimmed.toLowerCase().split(/\s+/);
+  for (const item of items) {
+    if (item.codepoint === typed) {
+      continue;
+    }
+    const char = String.fromCodePoint(item.codepoint);
+    if (char === trimmed || matches(item, words)) {
+      results.push(item);
+    }
+  }
+  return results.slice(0, MAX_RESULTS);
+}
+
+const finder = new Finder<UnicodeChar>(editor, {
+  id: "unicode-picker",
+  format: (item) => ({
+    label: `${displayChar(item)}  ${item.name.toLowerCase()}`,
+    description: `${codepointLabel(item.codepoint)}  ${item.category}`,
+  }),
+  preview: false,
+  maxResults: MAX_RESULTS,
+  onSelect: (item) => {
+    const char = String.fromCodePoint(item.codepoint);
+    editor.insertAtCursor(char);
+    rememberRecent(item.codepoint);
+    editor.setStatus(
+      editor.t("status.inserted", {
+        char: displayChar(item),
+        code: codepointLabel(item.codepoint),
+      })
+    );
+  },
+});
+
+globalThis.start_unicode_picker = function (): void {
+  finder.prompt({
+    title: editor.t("prompt.insert_unicode"),
+    source: {
+      mode: "filter",
+      load: async () => ALL,
+      filter: filterCharacters,
+    },
+  });
+};
+
+editor.registerCommand(
+  "%cmd.insert_unicode",
+  "%cmd.insert_unicode_desc",
+  "start_unicode_picker",
+  null
+);
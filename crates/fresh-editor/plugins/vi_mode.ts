@@ -19,8 +19,9 @@ const editor = getEditor();
  */
 
 // Vi mode state
-type ViMode = "normal" | "insert" | "operator-pending" | "find-char" | "visual" | "visual-line" | "visual-block" | "text-object";
+type ViMode = "normal" | "insert" | "replace" | "operator-pending" | "find-char" | "visual" | "visual-line" | "visual-block" | "text-object";
 type FindCharType = "f" | "t" | "F" | "T" | null;
+type MarkCommand = "m" | "`" | "'" | null;
 type TextObjectType = "inner" | "around" | null;
 
 // Types for tracking repeatable changes
@@ -40,6 +41,8 @@ interface ViState {
   mode: ViMode;
   pendingOperator: string | null;
   pendingFindChar: FindCharType; // For f/t/F/T motions
+  pendingMark: MarkCommand; // For m, ` and ' (mark name pending)
+  pendingReplace: number | null; // Count for r (replacement char pending)
   pendingTextObject: TextObjectType; // For i/a text objects
   lastFindChar: { type: FindCharType; char: string } | null; // For ; and , repeat
   count: number | null;
@@ -54,6 +57,8 @@ const state: ViState = {
   mode: "normal",
   pendingOperator: null,
   pendingFindChar: null,
+  pendingMark: null,
+  pendingReplace: null,
   pendingTextObject: null,
   lastFindChar: null,
   count: null,
@@ -72,9 +77,14 @@ function getModeIndicator(mode: ViMode): string {
       return `-- ${editor.t("mode.normal")} --${countPrefix ? ` (${state.count})` : ""}`;
     case "insert":
       return `-- ${editor.t("mode.insert")} --`;
+    case "replace":
+      return `-- ${editor.t("mode.replace")} --`;
     case "operator-pending":
       return `-- ${editor.t("mode.operator")} (${state.pendingOperator}) --${countPrefix ? ` (${state.count})` : ""}`;
     case "find-char":
+      if (state.pendingMark) {
+        return `-- ${editor.t("mode.mark")} (${state.pendingMark}) --`;
+      }
       return `-- ${editor.t("mode.find")} (${state.pendingFindChar}) --`;
     case "visual":
       return `-- ${editor.t("mode.visual")} --${countPrefix ? ` (${state.count})` : ""}`;
@@ -504,10 +514,46 @@ globalThis.vi_delete_char_before = function (): void {
   executeWithCount("delete_backward", count);
 };
 
+// Wait for the replacement character, reusing the find-char key bindings
 globalThis.vi_replace_char = function (): void {
-  // TODO: implement character replacement (need to read next char)
-  editor.setStatus(editor.t("status.replace_not_implemented"));
-};
+  state.pendingReplace = consumeCount();
+  state.mode = "replace";
+  editor.setEditorMode("vi-replace");
+  editor.setStatus(getModeIndicator("replace"));
+};
+
+// UTF-8 byte length of a string (TextEncoder is not available)
+function byteLength(str: string): number {
+  let length = 0;
+  for (let i = 0; i < str.length; i++) {
+    const code = str.charCodeAt(i);
+    if (code <= 0x7f) length += 1;
+    else if (code <= 0x7ff) length += 2;
+    else if (code >= 0xd800 && code <= 0xdfff) {
+      length += 4;
+      i++;
+    } else length += 3;
+  }
+  return length;
+}
+
+// Replace `count` characters from the cursor with `char`, leaving the cursor
+// on the last one. Like vim, does nothing if the line is too short.
+async function replaceChars(count: number, char: string): Promise<void> {
+  const bufferId = editor.getActiveBufferId();
+  const cursorPos = editor.getCursorPosition();
+  if (cursorPos === null) return;
+
+  const bufLen = editor.getBufferLength(bufferId);
+  const text = await editor.getBufferText(bufferId, cursorPos, Math.min(bufLen, cursorPos + count * 4 + 4));
+  const chars = Array.from(text.split("\n")[0]).slice(0, count);
+  if (chars.length < count) return;
+
+  const end = cursorPos + byteLength(chars.join(""));
+  editor.deleteRange(bufferId, cursorPos, end);
+  editor.insertText(bufferId, cursorPos, char.repeat(count));
+  editor.setBufferCursor(bufferId, cursorPos + byteLength(char) * (count - 1));
+}
 
 // Substitute (delete char and enter insert mode)
 globalThis.vi_substitute = function (): void {
@@ -1364,6 +1410,17 @@ async function executeFindChar(findType: FindCharType, char: string): Promise<vo
 
 // Handler for when a character is typed in find-char mode (async)
 globalThis.vi_find_char_handler = async function (char: string): Promise<void> {
+  if (state.pendingReplace !== null) {
+    const count = state.pendingReplace;
+    state.pendingReplace = null;
+    await replaceChars(count, char);
+    switchMode("normal");
+    return;
+  }
+  if (state.pendingMark) {
+    runMarkCommand(state.pendingMark, char);
+    return;
+  }
   if (state.pendingFindChar) {
     await executeFindChar(state.pendingFindChar, char);
   }
@@ -1410,9 +1467,47 @@ globalThis.vi_find_char_repeat_reverse = async function (): Promise<void> {
 // Cancel find-char mode
 globalThis.vi_find_char_cancel = function (): void {
   state.pendingFindChar = null;
+  state.pendingMark = null;
+  state.pendingReplace = null;
   switchMode("normal");
 };
 
+// ============================================================================
+// Marks (m, ` and ')
+// ============================================================================
+
+// Wait for the mark name, reusing the find-char key bindings
+function enterMarkMode(command: MarkCommand): void {
+  state.pendingMark = command;
+  state.mode = "find-char";
+  editor.setEditorMode("vi-find-char");
+  editor.setStatus(getModeIndicator("find-char"));
+}
+
+// Set or jump to mark `name`. Back in normal mode first, so a message from
+// the editor (such as an unset mark) isn't replaced by the mode indicator.
+function runMarkCommand(command: MarkCommand, name: string): void {
+  state.pendingMark = null;
+  switchMode("normal");
+  if (command === "m") {
+    editor.setMark(name);
+  } else {
+    editor.jumpToMark(name, command === "'");
+  }
+}
+
+globalThis.vi_set_mark = function (): void {
+  enterMarkMode("m");
+};
+
+globalThis.vi_jump_to_mark = function (): void {
+  enterMarkMode("`");
+};
+
+globalThis.vi_jump_to_mark_line = function (): void {
+  enterMarkMode("'");
+};
+
 // ============================================================================
 // Operator-Pending Mode Commands
 // ============================================================================
@@ -1516,6 +1611,11 @@ editor.defineMode("vi-normal", null, [
   [";", "vi_find_char_repeat"],
   [",", "vi_find_char_repeat_reverse"],
 
+  // Marks
+  ["m", "vi_set_mark"],
+  ["`", "vi_jump_to_mark"],
+  ["'", "vi_jump_to_mark_line"],
+
   // Mode switching
   ["i", "vi_insert_before"],
   ["a", "vi_insert_after"],
@@ -1570,6 +1670,8 @@ editor.defineMode("vi-normal", null, [
 // Define vi-insert mode - only Escape is special, other keys insert text
 editor.defineMode("vi-insert", null, [
   ["Escape", "vi_escape"],
+  // Ctrl+K {char1} {char2}: insert a digraph
+  ["C-k", "insert_digraph"],
   // Pass through to standard editor shortcuts
   ["C-p", "command_palette"],
   ["C-q", "quit"],
@@ -1644,8 +1746,8 @@ globalThis.vi_fc_8 = async function(): Promise<void> { return globalThis.vi_find
 globalThis.vi_fc_9 = async function(): Promise<void> { return globalThis.vi_find_char_handler("9"); };
 globalThis.vi_fc_space = async function(): Promise<void> { return globalThis.vi_find_char_handler(" "); };
 
-// Define vi-find-char mode with all the character bindings
-editor.defineMode("vi-find-char", null, [
+// Bindings of vi-find-char and vi-replace: every character goes to the handler
+const charBindings: [string, string][] = [
   ["Escape", "vi_find_char_cancel"],
   // Letters
   ["a", "vi_fc_a"], ["b", "vi_fc_b"], ["c", "vi_fc_c"], ["d", "vi_fc_d"],
@@ -1668,7 +1770,10 @@ editor.defineMode("vi-find-char", null, [
   ["8", "vi_fc_8"], ["9", "vi_fc_9"],
   // Common punctuation
   ["Space", "vi_fc_space"],
-], true);
+];
+
+editor.defineMode("vi-find-char", null, charBindings, true);
+editor.defineMode("vi-replace", null, charBindings, true);
 
 // Define vi-operator-pending mode
 editor.defineMode("vi-operator-pending", null, [
@@ -1894,6 +1999,9 @@ const navCommands = [
   ["vi_find_char_T", "find_char_T"],
   ["vi_find_char_repeat", "find_char_repeat"],
   ["vi_find_char_repeat_reverse", "find_char_repeat_reverse"],
+  ["vi_set_mark", "set_mark"],
+  ["vi_jump_to_mark", "jump_to_mark"],
+  ["vi_jump_to_mark_line", "jump_to_mark_line"],
 ];
 
 for (const [name, key] of navCommands) {
@@ -2026,13 +2134,16 @@ const commandTable: CommandDef[] = [
   { name: "tabnext", minAbbrev: 5, allowBang: false, hasArgs: false }, // :tabne, :tabnext (note: different from :tabn)
   { name: "tabprevious", minAbbrev: 4, allowBang: false, hasArgs: false }, // :tabp, :tabprevious
 
-  // Quickfix (mapped to diagnostics in Fresh)
+  // Quickfix lists
   { name: "copen", minAbbrev: 3, allowBang: false, hasArgs: false },   // :cop, :copen
   { name: "cclose", minAbbrev: 3, allowBang: false, hasArgs: false },  // :ccl, :cclose
   { name: "cnext", minAbbrev: 2, allowBang: true, hasArgs: false },    // :cn, :cnext
   { name: "cprevious", minAbbrev: 2, allowBang: true, hasArgs: false },// :cp, :cprev, :cprevious
   { name: "cfirst", minAbbrev: 3, allowBang: true, hasArgs: false },   // :cfir, :cfirst
   { name: "clast", minAbbrev: 3, allowBang: true, hasArgs: false },    // :cla, :clast
+  { name: "colder", minAbbrev: 3, allowBang: false, hasArgs: false },  // :col, :colder
+  { name: "cnewer", minAbbrev: 4, allowBang: false, hasArgs: false },  // :cnew, :cnewer
+  { name: "chistory", minAbbrev: 3, allowBang: false, hasArgs: false },// :chi, :chistory
 
   // Search and replace
   { name: "nohlsearch", minAbbrev: 3, allowBang: false, hasArgs: false }, // :noh, :nohlsearch
@@ -2508,8 +2619,8 @@ async function executeCommand(
     }
 
     case "copen": {
-      // :copen - open diagnostics panel (Fresh equivalent)
-      editor.executeAction("show_diagnostics");
+      // :copen - pick an item of the current quickfix list
+      editor.executeAction("quickfix_show");
       return {};
     }
 
@@ -2519,26 +2630,44 @@ async function executeCommand(
     }
 
     case "cnext": {
-      // :cnext - next diagnostic
-      editor.executeAction("goto_next_diagnostic");
+      // :cnext - next quickfix item
+      editor.executeAction("quickfix_next");
       return {};
     }
 
     case "cprevious": {
-      // :cprev - previous diagnostic
-      editor.executeAction("goto_prev_diagnostic");
+      // :cprev - previous quickfix item
+      editor.executeAction("quickfix_prev");
       return {};
     }
 
     case "cfirst": {
-      // :cfirst - first diagnostic
-      editor.executeAction("goto_first_diagnostic");
+      // :cfirst - first quickfix item
+      editor.executeAction("quickfix_first");
       return {};
     }
 
     case "clast": {
-      // :clast - last diagnostic
-      editor.executeAction("goto_last_diagnostic");
+      // :clast - last quickfix item
+      editor.executeAction("quickfix_last");
+      return {};
+    }
+
+    case "colder": {
+      // :colder - older quickfix list
+      editor.executeAction("quickfix_older");
+      return {};
+    }
+
+    case "cnewer": {
+      // :cnewer - newer quickfix list
+      editor.executeAction("quickfix_newer");
+      return {};
+    }
+
+    case "chistory": {
+      // :chistory - pick a quickfix list from the history
+      editor.executeAction("quickfix_history");
       return {};
     }
 
@@ -2627,8 +2756,9 @@ async function executeCommand(
     }
 
     case "marks": {
-      // :marks - show marks (not implemented)
-      return { error: editor.t("error.marks_not_implemented") };
+      // :marks - pick a mark to jump to
+      editor.executeAction("prompt_jump_to_named_mark");
+      return {};
     }
 
     case "registers": {
@@ -0,0 +1,435 @@
+//! Accessibility mode: describe what changed on screen after every input and
+//! speak it through the configured command
+//!
+//! Each frame takes a [`ScreenSummary`] of what a screen reader user needs to
+//! follow along (the focused buffer, the cursor's line and character, the
+//! status message, and the highlighted entry of a prompt, menu, popup or the
+//! file explorer) and announces how it differs from the previous one.
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::config::MenuItem;
+use crate::input::keybindings::KeyContext;
+use crate::services::speech::Announcer;
+use crate::view::ui::menu::{is_checkbox_checked, is_menu_item_enabled, MenuContext};
+
+/// Open menu, submenu path and highlighted item
+type MenuPosition = (usize, Vec<usize>, Option<usize>);
+
+/// The speech command and what was last announced
+pub(crate) struct AccessibilityState {
+    announcer: Announcer,
+    last: ScreenSummary,
+    /// The menu summary and the position it was taken at, so the menus are
+    /// only expanded again when the highlight moves
+    menu_cache: Option<(MenuPosition, MenuSummary)>,
+    /// Whether a failure to run the speech command was reported already
+    reported_failure: bool,
+}
+
+impl AccessibilityState {
+    pub(crate) fn new(command: Vec<String>) -> Self {
+        Self {
+            announcer: Announcer::new(command),
+            last: ScreenSummary::default(),
+            menu_cache: None,
+            reported_failure: false,
+        }
+    }
+}
+
+/// Label of the open menu and description of its highlighted item
+type MenuSummary = (String, Option<String>);
+
+/// What accessibility mode tracks on screen
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ScreenSummary {
+    /// Name of the active buffer
+    pub buffer: Option<String>,
+    /// The primary cursor, unless a terminal has focus
+    pub cursor: Option<CursorSummary>,
+    pub status: Option<String>,
+    /// Message and input of the open prompt
+    pub prompt: Option<(String, String)>,
+    /// Highlighted prompt suggestion
+    pub suggestion: Option<String>,
+    pub menu: Option<MenuSummary>,
+    /// Selected item of a popup list, like completions
+    pub popup_item: Option<String>,
+    /// Selected file explorer entry, while the explorer has focus
+    pub explorer_item: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct CursorSummary {
+    /// 1-based line and column (in characters)
+    pub line: usize,
+    pub column: usize,
+    pub line_text: String,
+    /// Character under the cursor; None at the end of the line
+    pub char: Option<char>,
+    pub selection: Option<SelectionSize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SelectionSize {
+    /// Characters, for a selection within the cursor's line
+    Chars(usize),
+    /// Lines touched by a longer selection
+    Lines(usize),
+}
+
+/// Phrases describing the change from `old` to `new`, most specific first
+pub(crate) fn describe_changes(old: &ScreenSummary, new: &ScreenSummary) -> Vec<String> {
+    let mut parts = Vec::new();
+    if let Some((label, item)) = &new.menu {
+        match &old.menu {
+            Some((old_label, old_item)) if old_label == label => {
+                if old_item != item {
+                    parts.extend(item.clone());
+                }
+            }
+            _ => {
+                parts.push(t!("accessibility.menu", label = label).to_string());
+                parts.extend(item.clone());
+            }
+        }
+    }
+    if let Some((message, input)) = &new.prompt {
+        if old.prompt.as_ref().map(|(m, _)| m) != Some(message) {
+            parts.push(
+                format!("{} {}", message.trim_end(), input)
+                    .trim()
+                    .to_string(),
+            );
+        }
+    }
+    for (old_item, new_item) in [
+        (&old.suggestion, &new.suggestion),
+        (&old.popup_item, &new.popup_item),
+        (&old.explorer_item, &new.explorer_item),
+    ] {
+        if new_item != old_item {
+            parts.extend(new_item.clone());
+        }
+    }
+
+    if new.buffer != old.buffer {
+        parts.extend(new.buffer.clone());
+    }
+    if let Some(cursor) = &new.cursor {
+        match &old.cursor {
+            Some(old_cursor) if new.buffer == old.buffer && old_cursor.line == cursor.line => {
+                // Typing changes the line; the user knows what they typed
+                if old_cursor.line_text == cursor.line_text && old_cursor.column != cursor.column {
+                    parts.push(describe_char(cursor.char));
+                }
+            }
+            _ => parts.push(describe_line(cursor)),
+        }
+        if cursor.selection != old.cursor.as_ref().and_then(|c| c.selection) {
+            match cursor.selection {
+                Some(SelectionSize::Chars(count)) => {
+                    parts.push(t!("accessibility.selected_chars", count = count).to_string())
+                }
+                Some(SelectionSize::Lines(count)) => {
+                    parts.push(t!("accessibility.selected_lines", count = count).to_string())
+                }
+                None => {}
+            }
+        }
+    }
+
+    if new.status != old.status {
+        parts.extend(new.status.clone());
+    }
+    parts
+}
+
+fn describe_line(cursor: &CursorSummary) -> String {
+    let text = cursor.line_text.trim();
+    if text.is_empty() {
+        t!("accessibility.line_blank", line = cursor.line).to_string()
+    } else {
+        t!("accessibility.line", line = cursor.line, text = text).to_string()
+    }
+}
+
+fn describe_char(c: Option<char>) -> String {
+    match c {
+        None => t!("accessibility.end_of_line").to_string(),
+        Some(' ') => t!("accessibility.space").to_string(),
+        Some('\t') => t!("accessibility.tab").to_string(),
+        Some(c) => c.to_string(),
+    }
+}
+
+fn describe_menu_item(item: &MenuItem, context: &MenuContext) -> Option<String> {
+    match item {
+        MenuItem::Action {
+            label, checkbox, ..
+        } => {
+            let mut text = match checkbox {
+                Some(_) if is_checkbox_checked(checkbox, context) => {
+                    t!("accessibility.checked", label = label).to_string()
+                }
+                Some(_) => t!("accessibility.unchecked", label = label).to_string(),
+                None => label.clone(),
+            };
+            if !is_menu_item_enabled(item, context) {
+                text = t!("accessibility.unavailable", label = text).to_string();
+            }
+            Some(text)
+        }
+        MenuItem::Submenu { label, .. } | MenuItem::DynamicSubmenu { label, .. } => {
+            Some(t!("accessibility.submenu", label = label).to_string())
+        }
+        MenuItem::Label { info } => Some(info.clone()),
+        MenuItem::Separator { .. } => None,
+    }
+}
+
+impl Editor {
+    /// Speak what changed on screen since the last frame
+    pub(crate) fn announce_screen_changes(&mut self) {
+        let Some(mut state) = self.accessibility.take() else {
+            return;
+        };
+        let summary = self.screen_summary(&mut state.menu_cache);
+        let parts = describe_changes(&state.last, &summary);
+        state.last = summary;
+        if !parts.is_empty() {
+            self.speak(&mut state, &parts.join(". "));
+        }
+        self.accessibility = Some(state);
+    }
+
+    /// Turn accessibility mode on or off
+    pub fn toggle_accessibility(&mut self) {
+        let enabled = self.accessibility.is_none();
+        self.config.accessibility.enabled = enabled;
+        if enabled {
+            // Nothing was announced yet, so the next frame describes the
+            // whole context
+            self.accessibility = Some(AccessibilityState::new(
+                self.config.accessibility.speech_command(),
+            ));
+            self.set_status_message(t!("accessibility.on").to_string());
+        } else {
+            self.accessibility = None;
+            self.set_status_message(t!("accessibility.off").to_string());
+        }
+    }
+
+    /// Describe the focused buffer, the cursor's line and column and what is
+    /// highlighted: spoken in accessibility mode, else shown in the status bar
+    pub fn announce_context(&mut self) {
+        let mut menu_cache = None;
+        let summary = ScreenSummary {
+            status: None,
+            ..self.screen_summary(&mut menu_cache)
+        };
+        let mut parts = describe_changes(&ScreenSummary::default(), &summary);
+        if let Some(cursor) = &summary.cursor {
+            parts.push(t!("accessibility.column", column = cursor.column).to_string());
+        }
+        let text = parts.join(". ");
+        match self.accessibility.take() {
+            Some(mut state) => {
+                self.speak(&mut state, &text);
+                self.accessibility = Some(state);
+            }
+            None => self.set_status_message(text),
+        }
+    }
+
+    /// Announcements made in accessibility mode, oldest first (for tests)
+    pub fn accessibility_announcements(&self) -> Vec<String> {
+        self.accessibility
+            .as_ref()
+            .map(|state| state.announcer.history().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    fn speak(&mut self, state: &mut AccessibilityState, text: &str) {
+        if let Err(e) = state.announcer.announce(text) {
+            if !state.reported_failure {
+                state.reported_failure = true;
+                tracing::warn!("Accessibility speech command failed: {}", e);
+                self.set_status_message(
+                    t!("accessibility.speech_failed", error = e.to_string()).to_string(),
+                );
+            }
+        }
+    }
+
+    fn screen_summary(
+        &self,
+        menu_cache: &mut Option<(MenuPosition, MenuSummary)>,
+    ) -> ScreenSummary {
+        let buffer_id = self.active_buffer();
+        let state = self.active_state();
+
+        let cursor = (!self.is_terminal_buffer(buffer_id)).then(|| {
+            let cursor = state.cursors.primary();
+            let line = state.buffer.get_line_number(cursor.position);
+            let line_start = state.buffer.line_start_offset(line).unwrap_or(0);
+            let line_text = state
+                .buffer
+                .get_line(line)
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                .unwrap_or_default();
+            let line_text = line_text.trim_end_matches(['\n', '\r']).to_string();
+            let before = line_text
+                .get(..cursor.position.saturating_sub(line_start))
+                .unwrap_or(&line_text);
+            CursorSummary {
+                line: line + 1,
+                column: before.chars().count() + 1,
+                char: line_text[before.len()..].chars().next(),
+                selection: cursor
+                    .selection_range()
+                    .filter(|range| !range.is_empty())
+                    .map(|range| {
+                        let first = state.buffer.get_line_number(range.start);
+                        let last = state.buffer.get_line_number(range.end);
+                        let within_line =
+                            |offset: usize| line_text.get(..offset.checked_sub(line_start)?);
+                        match (within_line(range.start), within_line(range.end)) {
+                            (Some(start), Some(end)) if first == line && last == line => {
+                                SelectionSize::Chars(end.chars().count() - start.chars().count())
+                            }
+                            _ => SelectionSize::Lines(last - first + 1),
+                        }
+                    }),
+                line_text,
+            }
+        });
+
+        let prompt = self.prompt.as_ref();
+        let suggestion = prompt.and_then(|prompt| {
+            let suggestion = prompt.suggestions.get(prompt.selected_suggestion?)?;
+            Some(match &suggestion.keybinding {
+                Some(key) => format!("{}, {}", suggestion.text, key),
+                None => suggestion.text.clone(),
+            })
+        });
+
+        let menu = self.menu_state.active_menu.and_then(|active| {
+            let position = (
+                active,
+                self.menu_state.submenu_path.clone(),
+                self.menu_state.highlighted_item,
+            );
+            if let Some((cached, summary)) = menu_cache.as_ref() {
+                if *cached == position {
+                    return Some(summary.clone());
+                }
+            }
+            let menus = self.all_menus();
+            let label = menus.get(active)?.label.clone();
+            let item = self.menu_state.highlighted_item.and_then(|index| {
+                let items = self.menu_state.get_current_items(&menus, active)?;
+                describe_menu_item(items.get(index)?, &self.menu_state.context)
+            });
+            *menu_cache = Some((position, (label.clone(), item.clone())));
+            Some((label, item))
+        });
+
+        let popup_item = state
+            .popups
+            .top()
+            .and_then(|popup| popup.selected_item())
+            .map(|item| match &item.detail {
+                Some(detail) => format!("{}, {}", item.text, detail),
+                None => item.text.clone(),
+            });
+
+        let explorer_item = self
+            .file_explorer
+            .as_ref()
+            .filter(|_| self.key_context == KeyContext::FileExplorer)
+            .and_then(|explorer| explorer.get_selected_entry())
+            .map(|entry| {
+                if entry.is_dir() {
+                    t!("accessibility.folder", name = entry.name).to_string()
+                } else {
+                    entry.name.clone()
+                }
+            });
+
+        ScreenSummary {
+            buffer: Some(self.get_buffer_display_name(buffer_id)),
+            cursor,
+            status: self.status_message.clone(),
+            prompt: prompt.map(|prompt| (prompt.message.clone(), prompt.input.clone())),
+            suggestion,
+            menu,
+            popup_item,
+            explorer_item,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(line: usize, column: usize, line_text: &str) -> ScreenSummary {
+        ScreenSummary {
+            buffer: Some("main.rs".to_string()),
+            cursor: Some(CursorSummary {
+                line,
+                column,
+                line_text: line_text.to_string(),
+                char: line_text.chars().nth(column - 1),
+                selection: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_first_summary_describes_everything() {
+        let parts = describe_changes(&ScreenSummary::default(), &at(3, 1, "fn main() {"));
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0], "main.rs");
+        assert!(parts[1].contains('3') && parts[1].contains("fn main() {"));
+    }
+
+    #[test]
+    fn test_cursor_moves() {
+        let line = "let x = 1;";
+        // Along the line: the character under the cursor
+        assert_eq!(describe_changes(&at(1, 1, line), &at(1, 5, line)), ["x"]);
+        // To another line: that line
+        let parts = describe_changes(&at(1, 1, line), &at(2, 1, "return x;"));
+        assert_eq!(parts.len(), 1);
+        assert!(parts[0].contains("return x;"));
+        // Typing: nothing
+        assert!(describe_changes(&at(1, 10, line), &at(1, 11, "let x = 12;")).is_empty());
+        assert!(describe_changes(&at(1, 1, line), &at(1, 1, line)).is_empty());
+    }
+
+    #[test]
+    fn test_menu_and_status() {
+        let editing = at(1, 1, "a");
+        let menu = |item: &str| ScreenSummary {
+            menu: Some(("File".to_string(), Some(item.to_string()))),
+            ..editing.clone()
+        };
+        let opened = describe_changes(&editing, &menu("New File"));
+        assert_eq!(opened.len(), 2);
+        assert!(opened[0].contains("File"));
+        assert_eq!(opened[1], "New File");
+        assert_eq!(describe_changes(&menu("New File"), &menu("Save")), ["Save"]);
+
+        let saved = ScreenSummary {
+            status: Some("Saved main.rs".to_string()),
+            ..editing.clone()
+        };
+        assert_eq!(describe_changes(&editing, &saved), ["Saved main.rs"]);
+        assert!(describe_changes(&saved, &editing).is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+//! **Align By...**: line up the selected lines on a delimiter typed into a
+//! prompt. Each selection is aligned on its own; the lines of cursors
+//! without a selection are aligned together, so a cursor on each line of a
+//! block works like selecting it.
+
+use std::collections::HashSet;
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::model::event::{CursorId, Event};
+use crate::primitives::align::{self, AlignSpec};
+use crate::view::prompt::PromptType;
+
+impl Editor {
+    /// Ask for the delimiter to align on, offering the last one used
+    pub(crate) fn start_align_prompt(&mut self) {
+        let initial = self.last_align_input.clone();
+        self.start_prompt_with_initial_text(
+            t!("align.prompt").to_string(),
+            PromptType::AlignSelection,
+            initial,
+        );
+    }
+
+    /// Handle the Align By prompt
+    pub(crate) fn handle_align_selection(&mut self, input: &str) {
+        let spec = match AlignSpec::parse(input) {
+            Ok(spec) => spec,
+            Err(e) => {
+                self.set_status_message(t!("align.invalid", error = e).to_string());
+                return;
+            }
+        };
+        self.last_align_input = input.to_string();
+        if self.is_editing_disabled() {
+            self.set_status_message(self.editing_disabled_message());
+            return;
+        }
+
+        let estimated_line_length = self.config.editor.estimated_line_length;
+        let state = self.active_state_mut();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut cursor_lines = Vec::new();
+        for (_, cursor) in state.cursors.iter() {
+            match cursor.selection_range().filter(|range| !range.is_empty()) {
+                Some(range) => {
+                    let first = state.buffer.get_line_number(range.start);
+                    let mut last = state.buffer.get_line_number(range.end);
+                    // A selection ending at the start of a line doesn't include it
+                    if last > first && state.buffer.line_start_offset(last) == Some(range.end) {
+                        last -= 1;
+                    }
+                    groups.push((first..=last).collect());
+                }
+                None => cursor_lines.push(state.buffer.get_line_number(cursor.position)),
+            }
+        }
+        cursor_lines.sort_unstable();
+        cursor_lines.dedup();
+        groups.push(cursor_lines);
+
+        // (start, end, new text) of each changed line, in any order
+        let mut edits: Vec<(usize, usize, String)> = Vec::new();
+        let mut seen = HashSet::new();
+        for group in groups {
+            let lines: Vec<(usize, String)> = group
+                .into_iter()
+                .filter(|line| seen.insert(*line))
+                .filter_map(|line| {
+                    let start = state.buffer.line_start_offset(line)?;
+                    let (_, content) = state
+                        .buffer
+                        .line_iterator(start, estimated_line_length)
+                        .next_line()?;
+                    Some((start, content.trim_end_matches(['\n', '\r']).to_string()))
+                })
+                .collect();
+            if lines.len() < 2 {
+                continue;
+            }
+            let texts: Vec<&str> = lines.iter().map(|(_, text)| text.as_str()).collect();
+            for ((start, old), new) in lines.iter().zip(align::align_lines(&texts, &spec)) {
+                if *old != new {
+                    edits.push((*start, start + old.len(), new));
+                }
+            }
+        }
+        if edits.is_empty() {
+            self.set_status_message(t!("align.nothing").to_string());
+            return;
+        }
+        edits.sort_by_key(|(start, _, _)| *start);
+
+        let mut events = Vec::new();
+        for (start, end, text) in edits.iter().rev() {
+            events.push(Event::Delete {
+                range: *start..*end,
+                deleted_text: state.get_text_range(*start, *end),
+                cursor_id: CursorId::UNDO_SENTINEL,
+            });
+            events.push(Event::Insert {
+                position: *start,
+                text: text.clone(),
+                cursor_id: CursorId::UNDO_SENTINEL,
+            });
+        }
+        // Positions inside a realigned line keep their column, as far as
+        // the new line reaches; the end of a line stays at its end
+        let map_position = |pos: usize| -> usize {
+            let mut shift = 0isize;
+            for (start, end, text) in &edits {
+                if pos >= *end {
+                    shift += text.len() as isize - (end - start) as isize;
+                } else if pos >= *start {
+                    return (*start as isize + shift) as usize + (pos - start).min(text.len());
+                } else {
+                    break;
+                }
+            }
+            (pos as isize + shift) as usize
+        };
+        for (cursor_id, cursor) in state.cursors.iter() {
+            events.push(Event::MoveCursor {
+                cursor_id,
+                old_position: cursor.position,
+                new_position: map_position(cursor.position),
+                old_anchor: cursor.anchor,
+                new_anchor: cursor.anchor.map(map_position),
+                old_sticky_column: cursor.sticky_column,
+                new_sticky_column: 0,
+            });
+        }
+
+        let count = edits.len();
+        if let Some(bulk_edit) = self.apply_events_as_bulk_edit(events, "Align lines".into()) {
+            self.active_event_log_mut().append(bulk_edit);
+        }
+        self.set_status_message(t!("align.aligned", count = count).to_string());
+    }
+}
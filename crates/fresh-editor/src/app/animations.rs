@@ -0,0 +1,240 @@
+//! Smooth scrolling, cursor jump flashes and panel transitions.
+//!
+//! Controlled by the `animations` setting. Animations are started by the
+//! actions that cause them and advanced from `process_async_messages`, which
+//! keeps requesting renders while any is running:
+//! - page jumps scroll the viewport line by line towards the new top instead
+//!   of snapping (the viewport skips `ensure_visible` until the scroll ends)
+//! - jumps further than a screen height flash the cursor line
+//! - the file explorer slides open and collapses
+//!
+//! Any key press or mouse click finishes a running scroll immediately so the
+//! next action starts from the final position.
+
+use ratatui::Frame;
+
+use super::types::{CursorFlash, PanelAnimation, ScrollAnimation};
+use super::Editor;
+use crate::input::keybindings::Action;
+use crate::model::event::{BufferId, SplitId};
+use crate::view::animation::{
+    blend_color, Animation, Easing, FLASH_DURATION, PANEL_DURATION, SCROLL_DURATION,
+};
+
+/// Viewport and cursor of the active split before an action ran
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ActionOrigin {
+    split: SplitId,
+    buffer: BufferId,
+    top_byte: usize,
+    top_view_line_offset: usize,
+    cursor: usize,
+    page_jump: bool,
+}
+
+impl Editor {
+    /// Snapshot the active split before `action` runs, or None when
+    /// animations are disabled
+    pub(super) fn action_animation_origin(&self, action: &Action) -> Option<ActionOrigin> {
+        if !self.config.editor.animations {
+            return None;
+        }
+        let split = self.split_manager.active_split();
+        let viewport = &self.split_view_states.get(&split)?.viewport;
+        Some(ActionOrigin {
+            split,
+            buffer: self.active_buffer(),
+            top_byte: viewport.top_byte,
+            top_view_line_offset: viewport.top_view_line_offset,
+            cursor: self.active_state().cursors.primary().position,
+            page_jump: matches!(
+                action,
+                Action::MovePageUp
+                    | Action::MovePageDown
+                    | Action::SelectPageUp
+                    | Action::SelectPageDown
+            ),
+        })
+    }
+
+    /// Start the animations for how an action moved the cursor
+    ///
+    /// Page jumps scroll smoothly to where the viewport would have snapped;
+    /// other moves of more than a screen height flash the new cursor line.
+    pub(super) fn start_action_animations(&mut self, origin: ActionOrigin) {
+        let split = self.split_manager.active_split();
+        if split != origin.split || self.active_buffer() != origin.buffer {
+            return;
+        }
+        let now = self.time_source.now();
+        let (Some(state), Some(view_state)) = (
+            self.buffers.get_mut(&origin.buffer),
+            self.split_view_states.get_mut(&split),
+        ) else {
+            return;
+        };
+        let cursor = *state.cursors.primary();
+        // Large files have no line index to measure jumps or step through
+        if cursor.position == origin.cursor || state.buffer.line_count().is_none() {
+            return;
+        }
+        let viewport = &mut view_state.viewport;
+
+        if origin.page_jump {
+            if viewport.should_skip_ensure_visible() {
+                return;
+            }
+            viewport.ensure_visible(&mut state.buffer, &cursor);
+            let (target_top_byte, target_top_view_line_offset) =
+                (viewport.top_byte, viewport.top_view_line_offset);
+            if target_top_byte == origin.top_byte {
+                return;
+            }
+            let from_line = state.buffer.get_line_number(origin.top_byte);
+            let to_line = state.buffer.get_line_number(target_top_byte);
+
+            // Start from the old position; polling moves the viewport along
+            viewport.top_byte = origin.top_byte;
+            viewport.top_view_line_offset = origin.top_view_line_offset;
+            viewport.set_skip_ensure_visible();
+            self.animations.scroll = Some(ScrollAnimation {
+                split,
+                buffer: origin.buffer,
+                from_line,
+                to_line,
+                target_top_byte,
+                target_top_view_line_offset,
+                animation: Animation::new(now, SCROLL_DURATION, Easing::EaseOutCubic),
+            });
+        } else {
+            let from_line = state.buffer.get_line_number(origin.cursor);
+            let to_line = state.buffer.get_line_number(cursor.position);
+            if from_line.abs_diff(to_line) < viewport.visible_line_count().max(1) {
+                return;
+            }
+            self.animations.cursor_flash = Some(CursorFlash {
+                split,
+                position: cursor.position,
+                animation: Animation::new(now, FLASH_DURATION, Easing::Linear),
+            });
+        }
+    }
+
+    /// Start sliding the file explorer open or closed
+    pub(super) fn start_file_explorer_animation(&mut self, opening: bool) {
+        if !self.config.editor.animations {
+            return;
+        }
+        self.animations.file_explorer = Some(PanelAnimation {
+            opening,
+            animation: Animation::new(self.time_source.now(), PANEL_DURATION, Easing::EaseOutCubic),
+        });
+    }
+
+    /// Fraction of its width the file explorer takes while it is opening or
+    /// closing, or None when it isn't animating
+    pub(super) fn file_explorer_animation_scale(&self) -> Option<f32> {
+        let panel = self.animations.file_explorer?;
+        let progress = panel.animation.progress(self.time_source.now()) as f32;
+        Some(if panel.opening {
+            progress
+        } else {
+            1.0 - progress
+        })
+    }
+
+    /// Jump a running scroll animation to its end
+    pub(super) fn finish_scroll_animation(&mut self) {
+        let Some(scroll) = self.animations.scroll.take() else {
+            return;
+        };
+        if let Some(view_state) = self.split_view_states.get_mut(&scroll.split) {
+            view_state.viewport.top_byte = scroll.target_top_byte;
+            view_state.viewport.top_view_line_offset = scroll.target_top_view_line_offset;
+            view_state.viewport.clear_skip_ensure_visible();
+        }
+    }
+
+    /// Advance running animations and drop finished ones
+    ///
+    /// Called on every iteration of the event loop. Returns true while any
+    /// animation is running (so the next frame is rendered).
+    pub(crate) fn poll_animations(&mut self) -> bool {
+        let now = self.time_source.now();
+        let mut changed = false;
+
+        if let Some(scroll) = self.animations.scroll {
+            changed = true;
+            let still_shown =
+                self.split_manager.buffer_for_split(scroll.split) == Some(scroll.buffer);
+            if !still_shown {
+                self.animations.scroll = None;
+            } else if scroll.animation.is_finished(now) {
+                self.finish_scroll_animation();
+            } else if let (Some(state), Some(view_state)) = (
+                self.buffers.get(&scroll.buffer),
+                self.split_view_states.get_mut(&scroll.split),
+            ) {
+                let line = scroll
+                    .animation
+                    .interpolate(scroll.from_line as f64, scroll.to_line as f64, now)
+                    .round() as usize;
+                if let Some(top_byte) = state.buffer.line_start_offset(line) {
+                    view_state.viewport.top_byte = top_byte;
+                    view_state.viewport.top_view_line_offset = 0;
+                }
+                view_state.viewport.set_skip_ensure_visible();
+            }
+        }
+
+        if let Some(flash) = self.animations.cursor_flash {
+            changed = true;
+            if flash.animation.is_finished(now) {
+                self.animations.cursor_flash = None;
+            }
+        }
+
+        if let Some(panel) = self.animations.file_explorer {
+            changed = true;
+            if panel.animation.is_finished(now) {
+                self.animations.file_explorer = None;
+            }
+        }
+
+        changed
+    }
+
+    /// Paint the fading highlight on the line the cursor jumped to
+    pub(super) fn render_cursor_flash(&self, frame: &mut Frame) {
+        let Some(flash) = self.animations.cursor_flash else {
+            return;
+        };
+        let Some(row) = self
+            .cached_layout
+            .find_visual_row(flash.split, flash.position)
+        else {
+            return;
+        };
+        let Some(&(_, _, content_rect, ..)) = self
+            .cached_layout
+            .split_areas
+            .iter()
+            .find(|(split, ..)| *split == flash.split)
+        else {
+            return;
+        };
+        let y = content_rect.y.saturating_add(row as u16);
+        if y >= content_rect.bottom() {
+            return;
+        }
+
+        let progress = flash.animation.progress(self.time_source.now());
+        let buf = frame.buffer_mut();
+        for x in content_rect.left()..content_rect.right() {
+            if let Some(cell) = buf.cell_mut((x, y)) {
+                let bg = blend_color(self.theme.selection_bg, cell.bg, progress);
+                cell.set_bg(bg);
+            }
+        }
+    }
+}
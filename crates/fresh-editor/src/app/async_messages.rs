@@ -2,7 +2,7 @@
 //!
 //! This module contains handlers for AsyncMessage variants, grouped by domain:
 //! - LSP diagnostics (push and pull models)
-//! - LSP feature responses (inlay hints, progress, status)
+//! - LSP feature responses (inlay hints, document highlights, code lenses, progress, status)
 //! - File system events
 //! - File explorer events
 //! - Plugin events
@@ -15,8 +15,9 @@ use crate::services::async_bridge::{
 use crate::state::{SemanticTokenSpan, SemanticTokenStore};
 use crate::view::file_tree::{FileTreeView, NodeId};
 use lsp_types::{
-    Diagnostic, InlayHint, SemanticToken, SemanticTokensEdit, SemanticTokensFullDeltaResult,
-    SemanticTokensLegend, SemanticTokensRangeResult, SemanticTokensResult,
+    CodeLens, Diagnostic, DocumentHighlight, InlayHint, SemanticToken, SemanticTokensEdit,
+    SemanticTokensFullDeltaResult, SemanticTokensLegend, SemanticTokensRangeResult,
+    SemanticTokensResult,
 };
 use rust_i18n::t;
 use serde_json::Value;
@@ -66,7 +67,11 @@ impl Editor {
 
 impl Editor {
     /// Store and apply diagnostics, emit hook for plugins
-    fn store_and_apply_diagnostics(&mut self, uri: String, diagnostics: Vec<Diagnostic>) {
+    pub(super) fn store_and_apply_diagnostics(
+        &mut self,
+        uri: String,
+        diagnostics: Vec<Diagnostic>,
+    ) {
         // Store diagnostics for later retrieval by plugins
         if diagnostics.is_empty() {
             self.stored_diagnostics.remove(&uri);
@@ -95,19 +100,72 @@ impl Editor {
         );
     }
 
+    /// Record one server's diagnostics for a URI and return the diagnostics of
+    /// all servers for that URI.
+    ///
+    /// When several servers report diagnostics for the same file, entries
+    /// without a `source` are labelled with the server they came from.
+    pub(super) fn merge_server_diagnostics(
+        &mut self,
+        server_id: &str,
+        uri: &str,
+        diagnostics: Vec<Diagnostic>,
+    ) -> Vec<Diagnostic> {
+        let by_server = self.server_diagnostics.entry(uri.to_string()).or_default();
+        if diagnostics.is_empty() {
+            by_server.remove(server_id);
+        } else {
+            by_server.insert(server_id.to_string(), diagnostics);
+        }
+
+        if by_server.len() <= 1 {
+            let merged = by_server.values().next().cloned().unwrap_or_default();
+            if by_server.is_empty() {
+                self.server_diagnostics.remove(uri);
+            }
+            return merged;
+        }
+
+        let mut server_ids: Vec<&String> = by_server.keys().collect();
+        server_ids.sort();
+        let mut merged = Vec::new();
+        for id in server_ids {
+            let label = self
+                .lsp
+                .as_ref()
+                .map(|lsp| lsp.server_label(id))
+                .unwrap_or_else(|| id.clone());
+            merged.extend(by_server[id].iter().cloned().map(|mut diagnostic| {
+                diagnostic.source.get_or_insert_with(|| label.clone());
+                diagnostic
+            }));
+        }
+        merged
+    }
+
     /// Handle LSP diagnostics (push model)
-    pub(super) fn handle_lsp_diagnostics(&mut self, uri: String, diagnostics: Vec<Diagnostic>) {
+    pub(super) fn handle_lsp_diagnostics(
+        &mut self,
+        server_id: String,
+        uri: String,
+        diagnostics: Vec<Diagnostic>,
+    ) {
         tracing::debug!(
-            "Processing {} LSP diagnostics for {}",
+            "Processing {} LSP diagnostics for {} from {}",
             diagnostics.len(),
-            uri
+            uri,
+            server_id
         );
+        let diagnostics = self.merge_server_diagnostics(&server_id, &uri, diagnostics);
         self.store_and_apply_diagnostics(uri, diagnostics);
     }
 
     /// Handle LSP pulled diagnostics (pull model - LSP 3.17+)
+    ///
+    /// `server_id` is the server that answered the request.
     pub(super) fn handle_lsp_pulled_diagnostics(
         &mut self,
+        server_id: String,
         uri: String,
         result_id: Option<String>,
         diagnostics: Vec<Diagnostic>,
@@ -134,6 +192,7 @@ impl Editor {
             self.diagnostic_result_ids.insert(uri.clone(), result_id);
         }
 
+        let diagnostics = self.merge_server_diagnostics(&server_id, &uri, diagnostics);
         self.store_and_apply_diagnostics(uri, diagnostics);
     }
 }
@@ -181,6 +240,90 @@ impl Editor {
         }
     }
 
+    /// Handle LSP document highlight response
+    ///
+    /// An empty response (server unsupported or nothing found) leaves the
+    /// word-based occurrence highlight in place.
+    pub(super) fn handle_lsp_document_highlight(
+        &mut self,
+        request_id: u64,
+        uri: String,
+        highlights: Vec<DocumentHighlight>,
+    ) {
+        let Some((pending_id, buffer_id)) = self.pending_document_highlight_request else {
+            return;
+        };
+        if pending_id != request_id {
+            tracing::debug!(
+                "Ignoring stale document highlight response (request_id={})",
+                request_id
+            );
+            return;
+        }
+        self.pending_document_highlight_request = None;
+
+        if highlights.is_empty() {
+            return;
+        }
+
+        let color = self.theme.semantic_highlight_bg;
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let ranges: Vec<_> = highlights
+            .iter()
+            .map(|highlight| {
+                let start = state.buffer.lsp_position_to_byte(
+                    highlight.range.start.line as usize,
+                    highlight.range.start.character as usize,
+                );
+                let end = state.buffer.lsp_position_to_byte(
+                    highlight.range.end.line as usize,
+                    highlight.range.end.character as usize,
+                );
+                start..end
+            })
+            .filter(|range| range.start < range.end)
+            .collect();
+
+        if state.reference_highlight_overlay.apply_lsp_highlights(
+            &mut state.overlays,
+            &mut state.marker_list,
+            &ranges,
+            color,
+        ) {
+            tracing::debug!("Applied {} document highlights for {}", ranges.len(), uri);
+        }
+    }
+
+    /// Handle LSP code lens response
+    pub(super) fn handle_lsp_code_lens(
+        &mut self,
+        request_id: u64,
+        uri: String,
+        lenses: Vec<CodeLens>,
+    ) {
+        let Some((buffer_id, version)) = self.pending_code_lens_requests.remove(&request_id) else {
+            return;
+        };
+        if !self.config.editor.enable_code_lens {
+            return;
+        }
+
+        let style = ratatui::style::Style::default().fg(self.theme.line_number_fg);
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        if state.buffer.version() != version {
+            // Lens ranges are stale; a fresh request follows once edits settle
+            tracing::debug!("Ignoring outdated code lens response for {}", uri);
+            return;
+        }
+
+        Self::apply_code_lenses_to_state(state, &lenses, style);
+        tracing::debug!("Applied {} code lenses to {}", lenses.len(), uri);
+    }
+
     /// Handle LSP semantic tokens response
     pub(super) fn handle_lsp_semantic_tokens(
         &mut self,
@@ -1062,14 +1205,22 @@ impl Editor {
         }
     }
 
-    /// Re-send didOpen notifications for all buffers of a given language
-    pub(super) fn resend_did_open_for_language(&mut self, language: &str) {
-        // Find all open buffers for this language using stored buffer language
+    /// Re-send didOpen notifications for all buffers served by a server
+    ///
+    /// `server_id` is a language ID for primary servers.
+    pub(super) fn resend_did_open_for_language(&mut self, server_id: &str) {
+        let languages = self
+            .lsp
+            .as_ref()
+            .map(|lsp| lsp.languages_for_server(server_id))
+            .unwrap_or_default();
+
+        // Find all open buffers for these languages using stored buffer language
         let buffers_for_language: Vec<_> = self
             .buffers
             .iter()
             .filter_map(|(buf_id, state)| {
-                if state.language == language {
+                if languages.contains(&state.language) {
                     self.buffer_metadata
                         .get(buf_id)
                         .and_then(|meta| meta.file_path().map(|p| (*buf_id, p.clone())))
@@ -1094,7 +1245,7 @@ impl Editor {
                     let lang_id = state.language.clone();
                     if let Some(lsp) = self.lsp.as_mut() {
                         // LSP should already be running since we just restarted it
-                        if let Some(handle) = lsp.get_handle_mut(&lang_id) {
+                        if let Some(handle) = lsp.get_handle_mut(server_id) {
                             let _ = handle.did_open(uri, content, lang_id);
                         }
                     }
@@ -0,0 +1,195 @@
+//! Automatic saving of modified buffers to their files.
+//!
+//! Controlled by the `auto_save` setting:
+//! - `afterDelay`: a buffer is saved once it has gone `auto_save_delay_ms`
+//!   without edits (each buffer is debounced on its own)
+//! - `onFocusChange`: the buffer being left is saved when switching to another
+//!   buffer or split
+//!
+//! This is unrelated to recovery auto-save (see `recovery_actions`), which
+//! writes recovery files instead of the buffer's own file. Auto-saves skip
+//! on-save actions such as formatters so text doesn't move while typing.
+
+use rust_i18n::t;
+
+use crate::config::AutoSaveMode;
+use crate::model::buffer::SudoSaveRequired;
+use crate::model::event::BufferId;
+use crate::primitives::path_utils::path_matches_glob;
+
+use super::Editor;
+
+impl Editor {
+    /// Advance the `afterDelay` debounce timers and save buffers that are due.
+    ///
+    /// Called on every iteration of the event loop. Returns true if the set of
+    /// pending auto-saves changed (so the status bar needs a re-render).
+    pub(crate) fn poll_auto_save(&mut self) -> bool {
+        if self.config.editor.auto_save != AutoSaveMode::AfterDelay {
+            let had_pending = !self.pending_auto_saves.is_empty();
+            self.pending_auto_saves.clear();
+            return had_pending;
+        }
+
+        let now = self.time_source.now();
+        let before = self.pending_auto_save_count();
+
+        // Track buffer versions so every edit restarts that buffer's timer
+        let candidates: Vec<(BufferId, u64)> = self
+            .buffers
+            .iter()
+            .filter(|(id, _)| self.is_auto_save_candidate(**id))
+            .map(|(id, state)| (*id, state.buffer.version()))
+            .collect();
+        self.pending_auto_saves
+            .retain(|id, _| candidates.iter().any(|(c, _)| c == id));
+        for (id, version) in candidates {
+            let entry = self
+                .pending_auto_saves
+                .entry(id)
+                .or_insert((version, Some(now)));
+            if entry.0 != version {
+                *entry = (version, Some(now));
+            }
+        }
+
+        let delay = std::time::Duration::from_millis(self.config.editor.auto_save_delay_ms);
+        let due: Vec<BufferId> = self
+            .pending_auto_saves
+            .iter()
+            .filter(|(_, (_, edited_at))| {
+                edited_at.is_some_and(|t| self.time_source.elapsed_since(t) >= delay)
+            })
+            .map(|(id, _)| *id)
+            // A dropped SSH link keeps the save pending until it is back
+            .filter(|id| self.buffer_filesystem(*id).is_remote_connected())
+            .collect();
+        let saved_any = !due.is_empty();
+        for id in due {
+            if self.auto_save_buffer(id) {
+                self.pending_auto_saves.remove(&id);
+            } else if let Some(entry) = self.pending_auto_saves.get_mut(&id) {
+                // Don't retry until the buffer is edited again
+                entry.1 = None;
+            }
+        }
+
+        saved_any || self.pending_auto_save_count() != before
+    }
+
+    /// Save `buffer_id` if auto-save is `onFocusChange` and it has unsaved edits.
+    ///
+    /// Called right before focus moves to another buffer or split.
+    pub(crate) fn auto_save_on_focus_change(&mut self, buffer_id: BufferId) {
+        if self.config.editor.auto_save == AutoSaveMode::OnFocusChange
+            && self.is_auto_save_candidate(buffer_id)
+        {
+            self.auto_save_buffer(buffer_id);
+        }
+    }
+
+    /// Number of buffers waiting for their `afterDelay` auto-save
+    pub fn pending_auto_save_count(&self) -> usize {
+        self.pending_auto_saves
+            .values()
+            .filter(|(_, edited_at)| edited_at.is_some())
+            .count()
+    }
+
+    /// Whether a buffer has unsaved edits and may be saved automatically.
+    ///
+    /// Files matching one of the `auto_save_exclude` globs never are.
+    fn is_auto_save_candidate(&self, buffer_id: BufferId) -> bool {
+        self.can_save_without_prompt(buffer_id)
+            && self
+                .buffers
+                .get(&buffer_id)
+                .and_then(|state| state.buffer.file_path())
+                .is_some_and(|path| {
+                    !self
+                        .config
+                        .editor
+                        .auto_save_exclude
+                        .iter()
+                        .any(|pattern| path_matches_glob(pattern, path))
+                })
+    }
+
+    /// Whether a buffer has unsaved edits that can be written to its file
+    /// without asking anything.
+    ///
+    /// Unnamed, virtual, read-only and terminal buffers can't.
+    pub(super) fn can_save_without_prompt(&self, buffer_id: BufferId) -> bool {
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return false;
+        };
+        if !state.buffer.is_modified() || state.is_composite_buffer || state.editing_disabled {
+            return false;
+        }
+        if state.buffer.file_path().is_none() {
+            return false;
+        }
+        self.buffer_metadata
+            .get(&buffer_id)
+            .is_some_and(|m| !m.is_virtual() && !m.read_only)
+            && !self.is_terminal_buffer(buffer_id)
+    }
+
+    /// Write a buffer to its file without prompting.
+    ///
+    /// Unlike an explicit save this never asks the user anything: if the file
+    /// changed on disk or needs elevated permissions, the buffer is left
+    /// modified and a status message explains why. Returns true on success.
+    pub(super) fn auto_save_buffer(&mut self, buffer_id: BufferId) -> bool {
+        let Some(path) = self
+            .buffers
+            .get(&buffer_id)
+            .and_then(|state| state.buffer.file_path())
+            .map(|p| p.to_path_buf())
+        else {
+            return false;
+        };
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+
+        if self.check_buffer_save_conflict(buffer_id).is_some() {
+            self.status_message =
+                Some(t!("status.auto_save_skipped_changed_on_disk", name = name).to_string());
+            return false;
+        }
+
+        self.run_will_save_hook(buffer_id, &path);
+        let result = match self.buffers.get_mut(&buffer_id) {
+            Some(state) => state.buffer.save(),
+            None => return false,
+        };
+        if let Err(e) = result {
+            let message = if e.downcast_ref::<SudoSaveRequired>().is_some() {
+                t!("status.auto_save_needs_sudo", name = name)
+            } else {
+                t!(
+                    "status.auto_save_failed",
+                    name = name,
+                    error = e.to_string()
+                )
+            };
+            self.status_message = Some(message.to_string());
+            return false;
+        }
+
+        if let Err(e) = self.finalize_buffer_save(buffer_id, Some(path), false) {
+            self.status_message = Some(
+                t!(
+                    "status.auto_save_failed",
+                    name = name,
+                    error = e.to_string()
+                )
+                .to_string(),
+            );
+            return false;
+        }
+        true
+    }
+}
@@ -0,0 +1,397 @@
+//! Opening and saving large files on worker threads
+//!
+//! Reading or writing a file of hundreds of megabytes takes long enough to
+//! freeze the frame loop, so files of at least `large_file_threshold_bytes`
+//! are read and written in the background (`editor.background_file_io`).
+//! Results come back through `process_async_messages`, running operations
+//! show in the status bar like plugin tasks, and an open can be cancelled
+//! from **Show Background Tasks**.
+//!
+//! A save writes the content the buffer had when it started. A large buffer
+//! reads its unloaded regions from the file the save replaces, so until the
+//! write finishes nothing is loaded from that file, movement and editing
+//! actions on the buffer are queued, and other edits (plugins, LSP) are
+//! recorded by the buffer and replayed onto the saved file.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rust_i18n::t;
+
+use super::file_open::format_size;
+use super::types::{BackgroundOpen, BackgroundSave};
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::input::keybindings::{Action, KeyContext};
+use crate::model::buffer::{LargeFileEncodingConfirmation, TextBuffer};
+use crate::model::event::BufferId;
+use crate::services::async_bridge::AsyncMessage;
+
+/// Prefix of the background task list values that refer to a file being opened
+pub(super) const OPEN_TASK_PREFIX: &str = "open:";
+
+impl Editor {
+    /// Whether a file of `size` bytes is read and written in the background
+    fn use_background_io(&self, size: u64) -> bool {
+        self.config.editor.background_file_io
+            && size >= self.config.editor.large_file_threshold_bytes
+            && self.tokio_runtime.is_some()
+            && self.async_bridge.is_some()
+    }
+
+    /// Start reading `path` on a worker thread if it is a large file
+    ///
+    /// Returns false if the file should be opened the usual way instead: it
+    /// is small, missing or already open, or files open in an external editor.
+    pub(super) fn start_background_open(&mut self, path: &Path) -> bool {
+        if self.config.terminal.use_external_editor {
+            return false;
+        }
+        let path = if path.is_relative() {
+            self.working_dir.join(path)
+        } else {
+            path.to_path_buf()
+        };
+        let filesystem = Arc::clone(&self.filesystem);
+        if filesystem.is_dir(&path).unwrap_or(true) {
+            return false;
+        }
+        let Ok(metadata) = filesystem.metadata(&path) else {
+            return false;
+        };
+        if !self.use_background_io(metadata.size) {
+            return false;
+        }
+        // A link whose path the buffer keeps is opened right away, since the
+        // background read only knows the resolved path
+        if self.config.editor.open_symlinks == crate::config::OpenSymlinks::Link
+            && filesystem.read_link(&path).is_ok()
+        {
+            return false;
+        }
+        let path = filesystem.canonicalize(&path).unwrap_or(path);
+        let already_open = self
+            .buffers
+            .values()
+            .any(|state| state.buffer.file_path() == Some(path.as_path()));
+        if already_open {
+            return false;
+        }
+        if self.background_opens.iter().any(|open| open.path == path) {
+            return true;
+        }
+
+        let (Some(runtime), Some(bridge)) = (&self.tokio_runtime, &self.async_bridge) else {
+            return false;
+        };
+        let id = self.next_background_open_id;
+        self.next_background_open_id += 1;
+        let sender = bridge.sender();
+        let threshold = self.config.editor.large_file_threshold_bytes as usize;
+        let load_path = path.clone();
+        runtime.spawn_blocking(move || {
+            let result = TextBuffer::load_from_file(&load_path, threshold, filesystem);
+            let _ = sender.send(AsyncMessage::FileLoaded {
+                request_id: id,
+                result,
+            });
+        });
+
+        self.set_status_message(
+            t!(
+                "file.opening",
+                name = display_file_name(&path),
+                size = format_size(metadata.size)
+            )
+            .to_string(),
+        );
+        self.background_opens.push(BackgroundOpen {
+            id,
+            path,
+            size: metadata.size,
+            started_at: self.time_source.now(),
+        });
+        true
+    }
+
+    /// Show a file read in the background, unless the open was cancelled
+    pub(super) fn handle_file_loaded(
+        &mut self,
+        request_id: u64,
+        result: anyhow::Result<TextBuffer>,
+    ) {
+        let Some(index) = self
+            .background_opens
+            .iter()
+            .position(|open| open.id == request_id)
+        else {
+            return;
+        };
+        let open = self.background_opens.remove(index);
+
+        let result = result.and_then(|buffer| self.open_loaded_file(&open.path, buffer));
+        if let Err(e) = result {
+            if let Some(confirmation) = e.downcast_ref::<LargeFileEncodingConfirmation>() {
+                self.start_large_file_encoding_confirmation(confirmation);
+            } else {
+                self.set_status_message(
+                    t!("file.error_opening", error = e.to_string()).to_string(),
+                );
+            }
+        }
+    }
+
+    /// Stop waiting for the background open with `id`
+    ///
+    /// The read itself can't be interrupted; its result is dropped.
+    pub(super) fn cancel_background_open(&mut self, id: u64) {
+        let Some(index) = self.background_opens.iter().position(|open| open.id == id) else {
+            return;
+        };
+        let open = self.background_opens.remove(index);
+        self.set_status_message(
+            t!(
+                "file.background_open_cancelled",
+                name = display_file_name(&open.path)
+            )
+            .to_string(),
+        );
+    }
+
+    /// Start writing the active buffer to `path` on a worker thread if it is large
+    ///
+    /// Returns false if the buffer should be saved the usual way instead.
+    pub(super) fn start_background_save(&mut self, path: &Path) -> anyhow::Result<bool> {
+        let buffer_id = self.active_buffer();
+        if self
+            .background_saves
+            .iter()
+            .any(|s| s.buffer_id == buffer_id)
+        {
+            self.set_status_message(
+                t!("file.still_saving", name = display_file_name(path)).to_string(),
+            );
+            return Ok(true);
+        }
+        let buffer = &self.active_state().buffer;
+        let size = buffer.len() as u64;
+        if !self.use_background_io(size) {
+            return Ok(false);
+        }
+        let (Some(runtime), Some(bridge)) = (&self.tokio_runtime, &self.async_bridge) else {
+            return Ok(false);
+        };
+
+        let job = buffer.prepare_save(path)?;
+        let version = buffer.version();
+        self.active_state_mut().buffer.begin_external_save(path);
+        let sender = bridge.sender();
+        runtime.spawn_blocking(move || {
+            let result = job.write();
+            let _ = sender.send(AsyncMessage::FileSaved {
+                buffer_id,
+                path: job.dest_path().to_path_buf(),
+                result,
+            });
+        });
+
+        self.set_status_message(
+            t!(
+                "file.saving",
+                name = display_file_name(path),
+                size = format_size(size)
+            )
+            .to_string(),
+        );
+        self.background_saves.push(BackgroundSave {
+            buffer_id,
+            path: path.to_path_buf(),
+            size,
+            version,
+            queued_actions: Vec::new(),
+            started_at: self.time_source.now(),
+        });
+        Ok(true)
+    }
+
+    /// Finish a background save and replay the actions queued meanwhile
+    pub(super) fn handle_file_saved(
+        &mut self,
+        buffer_id: BufferId,
+        path: PathBuf,
+        result: anyhow::Result<()>,
+    ) {
+        let Some(index) = self
+            .background_saves
+            .iter()
+            .position(|save| save.buffer_id == buffer_id)
+        else {
+            return;
+        };
+        let save = self.background_saves.remove(index);
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            // Closed while saving; the file is written either way
+            return;
+        };
+
+        match result {
+            Ok(()) => {
+                // Edits that bypass the action queue (plugins, LSP) are replayed
+                // onto the saved file and the buffer stays modified
+                let changed = state.buffer.version() != save.version;
+                let finished = state.buffer.finalize_external_save(path.clone());
+                let finished = match finished {
+                    Ok(()) if changed => {
+                        if let Ok(metadata) = self.buffer_filesystem(buffer_id).metadata(&path) {
+                            if let Some(mtime) = metadata.modified {
+                                self.file_mod_times.insert(path.clone(), mtime);
+                            }
+                        }
+                        self.set_status_message(
+                            t!("file.changed_while_saving", name = display_file_name(&path))
+                                .to_string(),
+                        );
+                        Ok(())
+                    }
+                    Ok(()) => self.finalize_buffer_save(buffer_id, Some(path), true),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = finished {
+                    self.set_status_message(
+                        t!("file.error_saving", error = e.to_string()).to_string(),
+                    );
+                }
+            }
+            Err(e) => {
+                state.buffer.abort_external_save();
+                if let Err(e) = self.handle_save_error(buffer_id, e) {
+                    self.set_status_message(
+                        t!("file.error_saving", error = e.to_string()).to_string(),
+                    );
+                }
+            }
+        }
+
+        if save.queued_actions.is_empty() {
+            return;
+        }
+        if buffer_id != self.active_buffer() {
+            self.set_active_buffer(buffer_id);
+        }
+        for action in save.queued_actions {
+            if let Err(e) = self.dispatch_action(action) {
+                tracing::warn!("Queued action failed after save: {}", e);
+            }
+        }
+    }
+
+    /// Hold back `action` if it moves or edits in a buffer being saved
+    ///
+    /// Returns true if the action was queued.
+    pub(super) fn queue_action_while_saving(&mut self, action: &Action) -> bool {
+        // Undo can swap in a whole piece tree, which can't be replayed onto
+        // the saved file
+        if self.background_saves.is_empty()
+            || !(action.is_movement_or_editing() || matches!(action, Action::Undo | Action::Redo))
+            || self.prompt.is_some()
+            || self.key_context != KeyContext::Normal
+        {
+            return false;
+        }
+        let buffer_id = self.active_buffer();
+        let Some(save) = self
+            .background_saves
+            .iter_mut()
+            .find(|save| save.buffer_id == buffer_id)
+        else {
+            return false;
+        };
+        save.queued_actions.push(action.clone());
+        true
+    }
+
+    /// Status bar text for the oldest background open or save, e.g.
+    /// `⠹ Saving big.log (200.0 MB) 3s`
+    pub(super) fn file_io_status_text(&self) -> Option<String> {
+        let (label, path, size, started_at) = self
+            .background_saves
+            .iter()
+            .map(|save| ("tasks.saving", &save.path, save.size, save.started_at))
+            .chain(
+                self.background_opens
+                    .iter()
+                    .map(|open| ("tasks.opening", &open.path, open.size, open.started_at)),
+            )
+            .min_by_key(|(_, _, _, started_at)| *started_at)?;
+
+        let spinner = super::plugin_tasks::spinner_frame(self.task_spinner_frame);
+        let elapsed = self.time_source.elapsed_since(started_at).as_secs();
+        let mut status = format!(
+            "{} {} ({}) {}s",
+            spinner,
+            t!(label, name = display_file_name(path)),
+            format_size(size),
+            elapsed
+        );
+        let count = self.background_saves.len() + self.background_opens.len();
+        if count > 1 {
+            status.push_str(&format!(" +{}", count - 1));
+        }
+        Some(status)
+    }
+
+    /// Advance the spinner while files are read or written and no plugin
+    /// task drives it
+    ///
+    /// Returns true when the status bar needs a re-render.
+    pub(crate) fn poll_background_io(&mut self) -> bool {
+        if !self.plugin_tasks.is_empty() {
+            return false;
+        }
+        let oldest = self
+            .background_saves
+            .iter()
+            .map(|save| save.started_at)
+            .chain(self.background_opens.iter().map(|open| open.started_at))
+            .min();
+        let Some(started_at) = oldest else {
+            return false;
+        };
+        let frame =
+            super::plugin_tasks::spinner_frame_at(self.time_source.elapsed_since(started_at));
+        if frame == self.task_spinner_frame {
+            return false;
+        }
+        self.task_spinner_frame = frame;
+        true
+    }
+
+    /// Entries for the background task list: opens can be cancelled, saves
+    /// are listed for information
+    pub(super) fn file_io_task_suggestions(&self) -> Vec<Suggestion> {
+        let saves = self.background_saves.iter().map(|save| Suggestion {
+            text: t!("tasks.saving", name = display_file_name(&save.path)).to_string(),
+            description: Some(format_size(save.size)),
+            value: None,
+            disabled: true,
+            keybinding: None,
+            source: None,
+        });
+        let opens = self.background_opens.iter().map(|open| Suggestion {
+            text: t!("tasks.opening", name = display_file_name(&open.path)).to_string(),
+            description: Some(format_size(open.size)),
+            value: Some(format!("{}{}", OPEN_TASK_PREFIX, open.id)),
+            disabled: false,
+            keybinding: None,
+            source: None,
+        });
+        saves.chain(opens).collect()
+    }
+}
+
+/// File name shown in status messages
+fn display_file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
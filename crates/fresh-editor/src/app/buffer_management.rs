@@ -14,14 +14,23 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::app::warning_domains::WarningDomain;
+use crate::config::{BufferConfig, OpenSymlinks};
+use crate::input::commands::Suggestion;
+use crate::input::keybindings::KeyContext;
 use crate::model::event::{BufferId, Event, SplitId};
+use crate::model::filesystem::FileSystem;
+use crate::primitives::indent_rules::IndentRules;
+use crate::primitives::indentation::{self, IndentStyle};
 use crate::state::EditorState;
-use crate::view::prompt::PromptType;
+use crate::view::prompt::{Prompt, PromptType};
 use crate::view::split::SplitViewState;
 
 use super::help;
 use super::Editor;
 
+/// Bytes at the start of a buffer read to detect its indentation
+const INDENT_DETECTION_SAMPLE_BYTES: usize = 64 * 1024;
+
 impl Editor {
     /// Open a file and return its buffer ID
     ///
@@ -41,8 +50,115 @@ impl Editor {
             }
         }
 
-        let buffer_id = self.open_file_no_focus(path)?;
+        let filesystem = Arc::clone(&self.filesystem);
+        self.open_file_with_filesystem(path, filesystem)
+    }
+
+    /// Open a file from a specific filesystem and switch focus to it
+    ///
+    /// The buffer keeps using `filesystem` for saving and reloading, which lets
+    /// local and remote files (or files from several hosts) be open at once.
+    pub fn open_file_with_filesystem(
+        &mut self,
+        path: &Path,
+        filesystem: Arc<dyn FileSystem + Send + Sync>,
+    ) -> anyhow::Result<BufferId> {
+        let buffer_id = self.open_file_no_focus_with_filesystem(path, filesystem)?;
+        self.focus_opened_file(buffer_id, path);
+        Ok(buffer_id)
+    }
+
+    /// Open `path` from a buffer that was already loaded (in the background)
+    /// and switch focus to it
+    pub(crate) fn open_loaded_file(
+        &mut self,
+        path: &Path,
+        buffer: crate::model::buffer::Buffer,
+    ) -> anyhow::Result<BufferId> {
+        let filesystem = Arc::clone(buffer.filesystem());
+        let keep_link = self.config.editor.open_symlinks == OpenSymlinks::Link;
+        let buffer_id = self.open_file_no_focus_inner(path, filesystem, Some(buffer), keep_link)?;
+        self.focus_opened_file(buffer_id, path);
+        Ok(buffer_id)
+    }
+
+    /// Open the symlink `path` and switch focus to it
+    ///
+    /// With `keep_link` the buffer keeps the link's path, otherwise it gets
+    /// the path of the file the link points to, whatever `open_symlinks` says.
+    pub(crate) fn open_symlink(
+        &mut self,
+        path: &Path,
+        keep_link: bool,
+    ) -> anyhow::Result<BufferId> {
+        let filesystem = Arc::clone(&self.filesystem);
+        let buffer_id = self.open_file_no_focus_inner(path, filesystem, None, keep_link)?;
+        self.focus_opened_file(buffer_id, path);
+        Ok(buffer_id)
+    }
+
+    /// Whether opening `path` should ask whether to edit the link or its
+    /// target (`open_symlinks` is "ask" and `path` is a symlink)
+    pub(crate) fn should_ask_open_symlink(&self, path: &Path) -> bool {
+        self.config.editor.open_symlinks == OpenSymlinks::Ask
+            && self.filesystem.read_link(path).is_ok()
+    }
+
+    /// Ask whether to edit the symlink `path` or the file it points to
+    pub(crate) fn start_open_symlink_prompt(&mut self, path: PathBuf) {
+        let target = self
+            .filesystem
+            .read_link(&path)
+            .map(|t| t.display().to_string())
+            .unwrap_or_default();
+        let option = |label: String, value: &str| Suggestion {
+            text: label,
+            description: None,
+            value: Some(value.to_string()),
+            disabled: false,
+            keybinding: None,
+            source: None,
+        };
+        let suggestions = vec![
+            option(
+                t!("symlink.edit_target", target = &target).to_string(),
+                "target",
+            ),
+            option(
+                t!("symlink.edit_link", name = path.display().to_string()).to_string(),
+                "link",
+            ),
+        ];
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut prompt = Prompt::with_suggestions(
+            t!("symlink.open_prompt", name = name).to_string(),
+            PromptType::OpenSymlink { path },
+            suggestions,
+        );
+        prompt.selected_suggestion = Some(0);
+        self.prompt = Some(prompt);
+    }
+
+    /// Open the symlink `path` as picked in the prompt ("target" or "link")
+    pub(super) fn open_symlink_answered(&mut self, path: &Path, input: &str) {
+        let keep_link = match input.trim() {
+            "target" => false,
+            "link" => true,
+            _ => return,
+        };
+        match self.open_symlink(path, keep_link) {
+            Ok(_) => self.key_context = KeyContext::Normal,
+            Err(e) => {
+                self.set_status_message(t!("file.error_opening", error = e.to_string()).to_string())
+            }
+        }
+    }
 
+    /// Switch to a buffer that was just opened for `path`
+    fn focus_opened_file(&mut self, buffer_id: BufferId, path: &Path) {
         // Check if this was an already-open buffer or a new one
         // For already-open buffers, just switch to them
         // For new buffers, record position history before switching
@@ -50,15 +166,7 @@ impl Editor {
 
         if is_new_buffer {
             // Save current position before switching to new buffer
-            self.position_history.commit_pending_movement();
-
-            // Explicitly record current position before switching
-            let current_state = self.active_state();
-            let position = current_state.cursors.primary().position;
-            let anchor = current_state.cursors.primary().anchor;
-            self.position_history
-                .record_movement(self.active_buffer(), position, anchor);
-            self.position_history.commit_pending_movement();
+            self.record_position_before_switch();
         }
 
         self.set_active_buffer(buffer_id);
@@ -76,15 +184,41 @@ impl Editor {
             .get(&buffer_id)
             .map(|s| s.buffer.is_binary())
             .unwrap_or(false);
+        let read_only_reason = self
+            .buffer_metadata
+            .get(&buffer_id)
+            .and_then(|m| m.read_only_reason.clone());
 
         // Show appropriate status message for binary vs regular files
-        if is_binary {
+        if self.is_hard_link_alias(buffer_id, path) {
+            self.status_message = Some(
+                t!(
+                    "buffer.opened_hard_link",
+                    path = path
+                        .strip_prefix(&self.working_dir)
+                        .unwrap_or(path)
+                        .display()
+                        .to_string(),
+                    name = display_name
+                )
+                .to_string(),
+            );
+        } else if self.image_previews.contains_key(&buffer_id) {
+            self.status_message = Some(t!("image_preview.opened", name = display_name).to_string());
+        } else if is_binary {
             self.status_message = Some(t!("buffer.opened_binary", name = display_name).to_string());
+        } else if let Some(reason) = read_only_reason {
+            self.status_message = Some(
+                t!(
+                    "buffer.opened_read_only",
+                    name = display_name,
+                    reason = reason
+                )
+                .to_string(),
+            );
         } else {
             self.status_message = Some(t!("buffer.opened", name = display_name).to_string());
         }
-
-        Ok(buffer_id)
     }
 
     /// Open a file without switching focus to it
@@ -94,10 +228,36 @@ impl Editor {
     ///
     /// If the file doesn't exist, creates an unsaved buffer with that filename.
     pub fn open_file_no_focus(&mut self, path: &Path) -> anyhow::Result<BufferId> {
+        let filesystem = Arc::clone(&self.filesystem);
+        self.open_file_no_focus_with_filesystem(path, filesystem)
+    }
+
+    /// Open a file from a specific filesystem without switching focus to it
+    pub fn open_file_no_focus_with_filesystem(
+        &mut self,
+        path: &Path,
+        filesystem: Arc<dyn FileSystem + Send + Sync>,
+    ) -> anyhow::Result<BufferId> {
+        let keep_link = self.config.editor.open_symlinks == OpenSymlinks::Link;
+        self.open_file_no_focus_inner(path, filesystem, None, keep_link)
+    }
+
+    /// Open a file without switching focus, using `loaded` as its content
+    /// if it was already read
+    ///
+    /// With `keep_link` a symlink isn't resolved, so the buffer keeps the
+    /// link's path.
+    fn open_file_no_focus_inner(
+        &mut self,
+        path: &Path,
+        filesystem: Arc<dyn FileSystem + Send + Sync>,
+        loaded: Option<crate::model::buffer::Buffer>,
+        keep_link: bool,
+    ) -> anyhow::Result<BufferId> {
         // Resolve relative paths against appropriate base directory
         // For remote mode, use the remote home directory; for local, use working_dir
-        let base_dir = if self.filesystem.remote_connection_info().is_some() {
-            self.filesystem
+        let base_dir = if filesystem.remote_connection_info().is_some() {
+            filesystem
                 .home_dir()
                 .unwrap_or_else(|_| self.working_dir.clone())
         } else {
@@ -112,13 +272,15 @@ impl Editor {
 
         // Determine if we're opening a non-existent file (for creating new files)
         // Use filesystem trait method to support remote files
-        let file_exists = self.filesystem.exists(&resolved_path);
+        let file_exists = filesystem.exists(&resolved_path);
+        let resolve_link = !(keep_link && filesystem.read_link(&resolved_path).is_ok());
 
         // Canonicalize the path to resolve symlinks and normalize path components
         // This ensures consistent path representation throughout the editor
-        // For non-existent files, we need to canonicalize the parent directory and append the filename
-        let canonical_path = if file_exists {
-            self.filesystem
+        // For non-existent files and kept links, we need to canonicalize the parent
+        // directory and append the filename
+        let canonical_path = if file_exists && resolve_link {
+            filesystem
                 .canonicalize(&resolved_path)
                 .unwrap_or_else(|_| resolved_path.clone())
         } else {
@@ -128,7 +290,7 @@ impl Editor {
                     // No parent means just a filename, use base dir
                     base_dir.clone()
                 } else {
-                    self.filesystem
+                    filesystem
                         .canonicalize(parent)
                         .unwrap_or_else(|_| parent.to_path_buf())
                 };
@@ -146,16 +308,22 @@ impl Editor {
         // Check if the path is a directory (after following symlinks via canonicalize)
         // Directories cannot be opened as files in the editor
         // Use filesystem trait method to support remote files
-        if self.filesystem.is_dir(path).unwrap_or(false) {
+        if filesystem.is_dir(path).unwrap_or(false) {
             anyhow::bail!(t!("buffer.cannot_open_directory"));
         }
 
-        // Check if file is already open - return existing buffer without switching
+        // Check if file is already open - return existing buffer without switching.
+        // The same path on a different host is a different file.
+        let connection = filesystem.remote_connection_info();
         let already_open = self
             .buffers
             .iter()
-            .find(|(_, state)| state.buffer.file_path() == Some(path))
-            .map(|(id, _)| *id);
+            .find(|(_, state)| {
+                state.buffer.file_path() == Some(path)
+                    && state.buffer.filesystem().remote_connection_info() == connection
+            })
+            .map(|(id, _)| *id)
+            .or_else(|| self.find_aliased_buffer(path, &filesystem));
 
         if let Some(id) = already_open {
             return Ok(id);
@@ -189,7 +357,14 @@ impl Editor {
             self.grammar_registry.available_syntaxes().len(),
             self.grammar_registry.user_extensions_debug()
         );
-        let mut state = if file_exists {
+        let mut state = if let Some(buffer) = loaded {
+            EditorState::from_buffer_with_languages(
+                buffer,
+                path,
+                &self.grammar_registry,
+                &self.config.languages,
+            )
+        } else if file_exists {
             EditorState::from_file_with_languages(
                 path,
                 self.terminal_width,
@@ -197,7 +372,7 @@ impl Editor {
                 self.config.editor.large_file_threshold_bytes as usize,
                 &self.grammar_registry,
                 &self.config.languages,
-                Arc::clone(&self.filesystem),
+                Arc::clone(&filesystem),
             )?
         } else {
             // File doesn't exist - create empty buffer with the file path set
@@ -205,7 +380,7 @@ impl Editor {
                 self.terminal_width,
                 self.terminal_height,
                 self.config.editor.large_file_threshold_bytes as usize,
-                Arc::clone(&self.filesystem),
+                Arc::clone(&filesystem),
             );
             // Set the file path so saving will create the file
             new_state.buffer.set_file_path(path.to_path_buf());
@@ -221,18 +396,6 @@ impl Editor {
             tracing::info!("Detected binary file: {}", path.display());
         }
 
-        // Set show_whitespace_tabs, use_tabs, and tab_size based on language config
-        // with fallback to global editor config for tab_size
-        // Use the buffer's stored language (already set by from_file_with_languages)
-        if let Some(lang_config) = self.config.languages.get(&state.language) {
-            state.show_whitespace_tabs = lang_config.show_whitespace_tabs;
-            state.use_tabs = lang_config.use_tabs;
-            // Use language-specific tab_size if set, otherwise fall back to global
-            state.tab_size = lang_config.tab_size.unwrap_or(self.config.editor.tab_size);
-        } else {
-            state.tab_size = self.config.editor.tab_size;
-        }
-
         // Apply line_numbers default from config
         state
             .margins
@@ -242,6 +405,9 @@ impl Editor {
         self.event_logs
             .insert(buffer_id, crate::model::event::EventLog::new());
 
+        // Set tab, ruler and wrap settings from the language and glob scopes
+        let line_wrap = self.apply_buffer_config(buffer_id);
+
         // Create metadata for this buffer
         let mut metadata =
             super::types::BufferMetadata::with_file(path.to_path_buf(), &self.working_dir);
@@ -260,13 +426,21 @@ impl Editor {
 
         // Store metadata for this buffer
         self.buffer_metadata.insert(buffer_id, metadata);
+        if file_exists {
+            self.apply_auto_read_only(buffer_id);
+        }
+
+        // PNG and JPEG files show a preview rather than their bytes
+        if is_binary {
+            self.open_image_preview(buffer_id, path, filesystem.as_ref());
+        }
 
         // Add buffer to the active split's tabs (but don't switch to it)
         let active_split = self.split_manager.active_split();
         if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
             view_state.add_buffer(buffer_id);
-            // Apply line_wrap default from config (per-view setting, applies to split)
-            view_state.viewport.line_wrap_enabled = self.config.editor.line_wrap;
+            // Apply line_wrap from config (per-view setting, applies to split)
+            view_state.viewport.line_wrap_enabled = line_wrap;
         }
 
         // Restore global file state (scroll/cursor position) if available
@@ -297,6 +471,155 @@ impl Editor {
         Ok(buffer_id)
     }
 
+    /// An open buffer whose file is the same file as `path` under another
+    /// name (a hard link, or a symlink opened with `open_symlinks` "link")
+    ///
+    /// Only local files are compared: on a remote host this would cost a
+    /// round trip per open buffer.
+    fn find_aliased_buffer(
+        &self,
+        path: &Path,
+        filesystem: &Arc<dyn FileSystem + Send + Sync>,
+    ) -> Option<BufferId> {
+        if filesystem.remote_connection_info().is_some() {
+            return None;
+        }
+        let file_id = filesystem.metadata(path).ok()?.file_id?;
+        self.buffers
+            .iter()
+            .find(|(_, state)| {
+                let buffer_fs = state.buffer.filesystem();
+                buffer_fs.remote_connection_info().is_none()
+                    && state.buffer.file_path().is_some_and(|other| {
+                        buffer_fs
+                            .metadata(other)
+                            .is_ok_and(|meta| meta.file_id == Some(file_id))
+                    })
+            })
+            .map(|(id, _)| *id)
+    }
+
+    /// Whether opening `requested` landed on `buffer_id` because both are
+    /// hard links to the same file
+    pub(crate) fn is_hard_link_alias(&self, buffer_id: BufferId, requested: &Path) -> bool {
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return false;
+        };
+        let filesystem = state.buffer.filesystem();
+        if filesystem.remote_connection_info().is_some() {
+            return false;
+        }
+        let Some(opened) = state.buffer.file_path() else {
+            return false;
+        };
+        let requested = self.working_dir.join(requested);
+        match (
+            filesystem.canonicalize(opened),
+            filesystem.canonicalize(&requested),
+        ) {
+            (Ok(opened), Ok(requested)) => opened != requested,
+            _ => false,
+        }
+    }
+
+    /// Apply the buffer's resolved config to its state
+    ///
+    /// Sets tab size, tabs vs spaces, whitespace indicators and rulers from the
+    /// editor config, the language config and any `"[<language>]"` or
+    /// `"files:<glob>"` scopes. With `detect_indentation` on, tabs vs spaces
+    /// and the indent width detected from the buffer's content replace the
+    /// editor and language settings, but not the scopes'. Returns the line
+    /// wrap setting for a view that starts showing the buffer.
+    pub(crate) fn apply_buffer_config(&mut self, buffer_id: BufferId) -> bool {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return self.config.editor.line_wrap;
+        };
+        let buffer_config = BufferConfig::resolve_for_file(
+            &self.config,
+            Some(&state.language),
+            state.buffer.file_path(),
+            Some(&self.working_dir),
+        );
+        let scoped = self.config.overrides.for_buffer(
+            Some(&state.language),
+            state.buffer.file_path(),
+            Some(&self.working_dir),
+        );
+
+        state.tab_size = buffer_config.tab_size;
+        state.use_tabs = buffer_config.use_tabs;
+        state.show_whitespace_tabs = buffer_config.show_whitespace_tabs;
+        state.tab_size_override = scoped.tab_size;
+        state.rulers = buffer_config.rulers;
+        state.wrap_guide_shading = buffer_config.wrap_guide_shading;
+        state.render_whitespace = self.config.editor.render_whitespace;
+
+        if self.config.editor.detect_indentation && !state.buffer.is_binary() {
+            let comment_prefix = self
+                .config
+                .languages
+                .get(&state.language)
+                .and_then(|lang| lang.comment_prefix.as_deref());
+            let sample_len = state.buffer.len().min(INDENT_DETECTION_SAMPLE_BYTES);
+            let sample = state
+                .buffer
+                .get_text_range_mut(0, sample_len)
+                .unwrap_or_default();
+            match indentation::detect(&String::from_utf8_lossy(&sample), comment_prefix) {
+                Some(IndentStyle::Tabs) => {
+                    state.use_tabs = scoped.use_tabs.unwrap_or(true);
+                }
+                Some(IndentStyle::Spaces(width)) => {
+                    state.use_tabs = scoped.use_tabs.unwrap_or(false);
+                    state.tab_size_override = scoped.tab_size.or(Some(width));
+                }
+                None => {}
+            }
+        }
+
+        state.line_wrap_override = buffer_config.line_wrap;
+        let line_wrap = state
+            .line_wrap_override
+            .unwrap_or(self.config.editor.line_wrap);
+        self.apply_indent_rules(buffer_id);
+        line_wrap
+    }
+
+    /// Compile the indentation rules of a buffer's language, and load the
+    /// indents query it declares
+    pub(crate) fn apply_indent_rules(&mut self, buffer_id: BufferId) {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let indent_rules = self
+            .config
+            .languages
+            .get(&state.language)
+            .and_then(|lang| lang.indent_rules.as_ref());
+        state.indent_rules = indent_rules.and_then(|rules| {
+            IndentRules::new(
+                rules.increase_pattern.as_deref(),
+                rules.decrease_pattern.as_deref(),
+            )
+            .unwrap_or_else(|e| {
+                tracing::warn!("Invalid indent pattern for {}: {}", state.language, e);
+                None
+            })
+        });
+        let indent_query = indent_rules
+            .and_then(|rules| rules.query.as_ref())
+            .and_then(|path| {
+                let path = self.dir_context.config_dir.join(path);
+                std::fs::read_to_string(&path)
+                    .map_err(|e| tracing::warn!("Can't read indents query {:?}: {}", path, e))
+                    .ok()
+            });
+        state
+            .indent_calculator
+            .borrow_mut()
+            .set_query_override(indent_query);
+    }
+
     /// Open a local file (always uses local filesystem, not remote)
     ///
     /// This is used for opening local files like log files when in remote mode.
@@ -445,6 +768,7 @@ impl Editor {
         self.buffers.insert(buffer_id, state);
         self.event_logs
             .insert(buffer_id, crate::model::event::EventLog::new());
+        let line_wrap = self.apply_buffer_config(buffer_id);
 
         let metadata =
             super::types::BufferMetadata::with_file(path.to_path_buf(), &self.working_dir);
@@ -454,7 +778,7 @@ impl Editor {
         let active_split = self.split_manager.active_split();
         if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
             view_state.add_buffer(buffer_id);
-            view_state.viewport.line_wrap_enabled = self.config.editor.line_wrap;
+            view_state.viewport.line_wrap_enabled = line_wrap;
         }
 
         self.set_active_buffer(buffer_id);
@@ -573,6 +897,7 @@ impl Editor {
         self.buffers.insert(buffer_id, state);
         self.event_logs
             .insert(buffer_id, crate::model::event::EventLog::new());
+        let line_wrap = self.apply_buffer_config(buffer_id);
 
         let metadata =
             super::types::BufferMetadata::with_file(path.to_path_buf(), &self.working_dir);
@@ -582,7 +907,7 @@ impl Editor {
         let active_split = self.split_manager.active_split();
         if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
             view_state.add_buffer(buffer_id);
-            view_state.viewport.line_wrap_enabled = self.config.editor.line_wrap;
+            view_state.viewport.line_wrap_enabled = line_wrap;
         }
 
         self.set_active_buffer(buffer_id);
@@ -765,15 +1090,7 @@ impl Editor {
     /// Create a new empty buffer
     pub fn new_buffer(&mut self) -> BufferId {
         // Save current position before switching to new buffer
-        self.position_history.commit_pending_movement();
-
-        // Explicitly record current position before switching
-        let current_state = self.active_state();
-        let position = current_state.cursors.primary().position;
-        let anchor = current_state.cursors.primary().anchor;
-        self.position_history
-            .record_movement(self.active_buffer(), position, anchor);
-        self.position_history.commit_pending_movement();
+        self.record_position_before_switch();
 
         let buffer_id = BufferId(self.next_buffer_id);
         self.next_buffer_id += 1;
@@ -797,11 +1114,12 @@ impl Editor {
             .insert(buffer_id, crate::model::event::EventLog::new());
         self.buffer_metadata
             .insert(buffer_id, crate::app::types::BufferMetadata::new());
+        let line_wrap = self.apply_buffer_config(buffer_id);
 
-        // Apply line_wrap default from config to the active split
+        // Apply line_wrap from config to the active split
         let active_split = self.split_manager.active_split();
         if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
-            view_state.viewport.line_wrap_enabled = self.config.editor.line_wrap;
+            view_state.viewport.line_wrap_enabled = line_wrap;
         }
 
         self.set_active_buffer(buffer_id);
@@ -825,15 +1143,7 @@ impl Editor {
         thread_handle: Option<std::thread::JoinHandle<anyhow::Result<()>>>,
     ) -> AnyhowResult<BufferId> {
         // Save current position before switching to new buffer
-        self.position_history.commit_pending_movement();
-
-        // Explicitly record current position before switching
-        let current_state = self.active_state();
-        let position = current_state.cursors.primary().position;
-        let anchor = current_state.cursors.primary().anchor;
-        self.position_history
-            .record_movement(self.active_buffer(), position, anchor);
-        self.position_history.commit_pending_movement();
+        self.record_position_before_switch();
 
         // If the current buffer is empty and unmodified, replace it instead of creating a new one
         // Note: Don't replace composite buffers (they appear empty but are special views)
@@ -1236,13 +1546,19 @@ impl Editor {
     /// Show warnings by opening the warning log file directly
     ///
     /// If there are no warnings, shows a brief status message.
-    /// Otherwise, opens the warning log file for the user to view.
+    /// If config files have problems, lists them so they can be opened at the
+    /// offending line. Otherwise, opens the warning log file for the user to view.
     pub fn show_warnings_popup(&mut self) {
         if !self.warning_domains.has_any_warnings() {
             self.status_message = Some(t!("warnings.none").to_string());
             return;
         }
 
+        if self.warning_domains.config.has_warnings() {
+            self.show_config_issues_panel();
+            return;
+        }
+
         // Open the warning log file directly
         self.open_warning_log();
     }
@@ -1423,8 +1739,10 @@ impl Editor {
                 .set_split_buffer(split_id, replacement_buffer);
         }
 
+        self.forget_buffer_marks(id);
         self.buffers.remove(&id);
         self.event_logs.remove(&id);
+        self.image_previews.remove(&id);
         self.seen_byte_ranges.remove(&id);
         self.buffer_metadata.remove(&id);
         if let Some((request_id, _, _)) = self.semantic_tokens_in_flight.remove(&id) {
@@ -1437,6 +1755,9 @@ impl Editor {
         self.semantic_tokens_range_last_request.remove(&id);
         self.semantic_tokens_range_applied.remove(&id);
         self.semantic_tokens_full_debounce.remove(&id);
+        self.pending_code_lens_requests
+            .retain(|_, (buffer_id, _)| *buffer_id != id);
+        self.code_lens_last_request.remove(&id);
 
         // Remove buffer from panel_ids mapping if it was a panel buffer
         // This prevents stale entries when the same panel_id is reused later
@@ -1460,15 +1781,7 @@ impl Editor {
     pub fn switch_buffer(&mut self, id: BufferId) {
         if self.buffers.contains_key(&id) && id != self.active_buffer() {
             // Save current position before switching buffers
-            self.position_history.commit_pending_movement();
-
-            // Also explicitly record current position (in case there was no pending movement)
-            let current_state = self.active_state();
-            let position = current_state.cursors.primary().position;
-            let anchor = current_state.cursors.primary().anchor;
-            self.position_history
-                .record_movement(self.active_buffer(), position, anchor);
-            self.position_history.commit_pending_movement();
+            self.record_position_before_switch();
 
             self.set_active_buffer(id);
         }
@@ -1689,10 +2002,10 @@ impl Editor {
             .map(|vs| vs.open_buffers.clone())
             .unwrap_or_default();
 
-        // Close all tabs except the one we want to keep
+        // Close all tabs except the one we want to keep and pinned tabs
         let tabs_to_close: Vec<_> = split_tabs
             .iter()
-            .filter(|&&id| id != keep_buffer_id)
+            .filter(|&&id| id != keep_buffer_id && !self.is_tab_pinned(id, split_id))
             .copied()
             .collect();
 
@@ -1728,8 +2041,13 @@ impl Editor {
             return;
         };
 
-        // Close all tabs after the target
-        let tabs_to_close: Vec<_> = split_tabs.iter().skip(target_idx + 1).copied().collect();
+        // Close all unpinned tabs after the target
+        let tabs_to_close: Vec<_> = split_tabs
+            .iter()
+            .skip(target_idx + 1)
+            .filter(|&&id| !self.is_tab_pinned(id, split_id))
+            .copied()
+            .collect();
 
         let mut closed = 0;
         let mut skipped_modified = 0;
@@ -1758,8 +2076,13 @@ impl Editor {
             return;
         };
 
-        // Close all tabs before the target
-        let tabs_to_close: Vec<_> = split_tabs.iter().take(target_idx).copied().collect();
+        // Close all unpinned tabs before the target
+        let tabs_to_close: Vec<_> = split_tabs
+            .iter()
+            .take(target_idx)
+            .filter(|&&id| !self.is_tab_pinned(id, split_id))
+            .copied()
+            .collect();
 
         let mut closed = 0;
         let mut skipped_modified = 0;
@@ -1798,6 +2121,50 @@ impl Editor {
         self.set_batch_close_status_message(closed, skipped_modified);
     }
 
+    /// Check if a tab is pinned in a split
+    pub(crate) fn is_tab_pinned(&self, buffer_id: BufferId, split_id: SplitId) -> bool {
+        self.split_view_states
+            .get(&split_id)
+            .is_some_and(|vs| vs.is_pinned(buffer_id))
+    }
+
+    /// Pin a tab to the start of a split's tab bar, or unpin it
+    pub fn toggle_pin_tab_in_split(&mut self, buffer_id: BufferId, split_id: SplitId) {
+        let Some(view_state) = self.split_view_states.get_mut(&split_id) else {
+            return;
+        };
+        let pinned = !view_state.is_pinned(buffer_id);
+        view_state.set_pinned(buffer_id, pinned);
+        self.keep_split_active_tab_visible(split_id);
+        self.set_status_message(if pinned {
+            t!("tab.pinned").to_string()
+        } else {
+            t!("tab.unpinned").to_string()
+        });
+    }
+
+    /// Assign a tab to a tab group, or remove it from its group
+    pub fn set_tab_group_in_split(
+        &mut self,
+        buffer_id: BufferId,
+        split_id: SplitId,
+        group: Option<crate::view::split::TabGroup>,
+    ) {
+        let Some(view_state) = self.split_view_states.get_mut(&split_id) else {
+            return;
+        };
+        view_state.set_tab_group(buffer_id, group);
+        self.keep_split_active_tab_visible(split_id);
+    }
+
+    /// Scroll a split's tab bar so its displayed tab stays visible after the
+    /// tabs were reordered
+    fn keep_split_active_tab_visible(&mut self, split_id: SplitId) {
+        if let Some(active) = self.split_manager.get_buffer_id(split_id) {
+            self.ensure_active_tab_visible(split_id, active, self.effective_tabs_width());
+        }
+    }
+
     /// Set status message for batch close operations
     fn set_batch_close_status_message(&mut self, closed: usize, skipped_modified: usize) {
         let message = match (closed, skipped_modified) {
@@ -1923,15 +2290,7 @@ impl Editor {
             let next_idx = (idx + 1) % ids.len();
             if ids[next_idx] != self.active_buffer() {
                 // Save current position before switching
-                self.position_history.commit_pending_movement();
-
-                // Also explicitly record current position
-                let current_state = self.active_state();
-                let position = current_state.cursors.primary().position;
-                let anchor = current_state.cursors.primary().anchor;
-                self.position_history
-                    .record_movement(self.active_buffer(), position, anchor);
-                self.position_history.commit_pending_movement();
+                self.record_position_before_switch();
 
                 self.set_active_buffer(ids[next_idx]);
             }
@@ -1950,42 +2309,44 @@ impl Editor {
             let prev_idx = if idx == 0 { ids.len() - 1 } else { idx - 1 };
             if ids[prev_idx] != self.active_buffer() {
                 // Save current position before switching
-                self.position_history.commit_pending_movement();
-
-                // Also explicitly record current position
-                let current_state = self.active_state();
-                let position = current_state.cursors.primary().position;
-                let anchor = current_state.cursors.primary().anchor;
-                self.position_history
-                    .record_movement(self.active_buffer(), position, anchor);
-                self.position_history.commit_pending_movement();
+                self.record_position_before_switch();
 
                 self.set_active_buffer(ids[prev_idx]);
             }
         }
     }
 
+    /// Record the active split's cursor in its jump list, e.g. before
+    /// switching to another buffer
+    pub(super) fn record_position_before_switch(&mut self) {
+        let buffer_id = self.active_buffer();
+        let cursor = *self.active_state().cursors.primary();
+        let active_split = self.split_manager.active_split();
+        if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
+            let history = &mut view_state.position_history;
+            history.commit_pending_movement();
+            history.record_movement(buffer_id, cursor.position, cursor.anchor);
+            history.commit_pending_movement();
+        }
+    }
+
     /// Navigate back in position history
     pub fn navigate_back(&mut self) {
         // Set flag to prevent recording this navigation movement
         self.in_navigation = true;
 
         // Commit any pending movement
-        self.position_history.commit_pending_movement();
+        self.active_position_history_mut().commit_pending_movement();
 
         // If we're at the end of history (haven't used back yet), save current position
         // so we can navigate forward to it later
-        if self.position_history.can_go_back() && !self.position_history.can_go_forward() {
-            let current_state = self.active_state();
-            let position = current_state.cursors.primary().position;
-            let anchor = current_state.cursors.primary().anchor;
-            self.position_history
-                .record_movement(self.active_buffer(), position, anchor);
-            self.position_history.commit_pending_movement();
+        let history = self.active_position_history();
+        if history.can_go_back() && !history.can_go_forward() {
+            self.record_position_before_switch();
         }
 
         // Navigate to the previous position
-        if let Some(entry) = self.position_history.back() {
+        if let Some(entry) = self.active_position_history_mut().back().cloned() {
             let target_buffer = entry.buffer_id;
             let target_position = entry.position;
             let target_anchor = entry.anchor;
@@ -2022,7 +2383,7 @@ impl Editor {
         // Set flag to prevent recording this navigation movement
         self.in_navigation = true;
 
-        if let Some(entry) = self.position_history.forward() {
+        if let Some(entry) = self.active_position_history_mut().forward().cloned() {
             let target_buffer = entry.buffer_id;
             let target_position = entry.position;
             let target_anchor = entry.anchor;
@@ -2094,8 +2455,113 @@ impl Editor {
     /// code path as interactive file opens, providing consistent error handling
     /// (e.g., encoding confirmation prompts are shown in the UI instead of crashing).
     pub fn queue_file_open(&mut self, path: PathBuf, line: Option<usize>, column: Option<usize>) {
-        self.pending_file_opens
-            .push(super::PendingFileOpen { path, line, column });
+        self.pending_file_opens.push(super::PendingFileOpen {
+            path,
+            line,
+            column,
+            remote_connection: None,
+            read_only: false,
+        });
+    }
+
+    /// Open the files queued so far read-only (`fresh -R`)
+    pub fn queue_opens_read_only(&mut self) {
+        for pending in &mut self.pending_file_opens {
+            pending.read_only = true;
+        }
+    }
+
+    /// Queue a file on a mounted remote filesystem to be opened after the TUI starts.
+    ///
+    /// `remote_connection` is the `user@host` label of a filesystem previously
+    /// passed to `mount_filesystem` (or of the primary filesystem).
+    pub fn queue_remote_file_open(
+        &mut self,
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+        remote_connection: String,
+    ) {
+        self.pending_file_opens.push(super::PendingFileOpen {
+            path,
+            line,
+            column,
+            remote_connection: Some(remote_connection),
+            read_only: false,
+        });
+    }
+
+    /// Queue a comparison of two directory trees to be shown after the TUI
+    /// starts (`fresh --diff OLD NEW`).
+    ///
+    /// The comparison itself is done by the directory diff plugin, which
+    /// listens for the `directory_diff_requested` hook.
+    pub fn queue_directory_diff(&mut self, old: PathBuf, new: PathBuf) {
+        self.pending_startup_hooks.push((
+            "directory_diff_requested",
+            crate::services::plugins::hooks::HookArgs::DirectoryDiffRequested { old, new },
+        ));
+    }
+
+    /// Queue a three-way merge to be shown after the TUI starts
+    /// (`fresh --merge LOCAL BASE REMOTE MERGED`).
+    ///
+    /// The merge UI is provided by the mergetool plugin, which listens for
+    /// the `merge_requested` hook.
+    pub fn queue_merge(&mut self, local: PathBuf, base: PathBuf, remote: PathBuf, merged: PathBuf) {
+        self.pending_startup_hooks.push((
+            "merge_requested",
+            crate::services::plugins::hooks::HookArgs::MergeRequested {
+                local,
+                base,
+                remote,
+                merged,
+            },
+        ));
+    }
+
+    /// Open `path` if it is a URI in a scheme claimed by a plugin
+    /// (`editor.registerUriScheme`), returning false for ordinary paths.
+    ///
+    /// A buffer already showing the URI is focused; otherwise the owning
+    /// plugin is asked to create one through the `uri_open_requested` hook.
+    /// Line and column are 1-indexed.
+    pub(crate) fn open_plugin_uri(
+        &mut self,
+        path: &Path,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> bool {
+        let Some(uri) = path.to_str() else {
+            return false;
+        };
+        match uri.split_once("://") {
+            Some((scheme, _)) if self.uri_schemes.contains(scheme) => {}
+            _ => return false,
+        }
+
+        let existing = self
+            .buffer_metadata
+            .iter()
+            .find(|(_, meta)| meta.virtual_uri() == Some(uri))
+            .map(|(id, _)| *id);
+        match existing {
+            Some(buffer_id) => {
+                self.set_active_buffer(buffer_id);
+                if let Some(line) = line {
+                    self.goto_line_col(line, column);
+                }
+            }
+            None => self.plugin_manager.run_hook(
+                "uri_open_requested",
+                crate::services::plugins::hooks::HookArgs::UriOpenRequested {
+                    uri: uri.to_string(),
+                    line,
+                    column,
+                },
+            ),
+        }
+        true
     }
 
     /// Process pending file opens (called from the event loop).
@@ -2103,6 +2569,12 @@ impl Editor {
     /// Opens files that were queued during startup, using the same error handling
     /// as interactive file opens. Returns true if any files were processed.
     pub fn process_pending_file_opens(&mut self) -> bool {
+        if !self.pending_startup_hooks.is_empty() {
+            for (name, args) in std::mem::take(&mut self.pending_startup_hooks) {
+                self.plugin_manager.run_hook(name, args);
+            }
+            return true;
+        }
         if self.pending_file_opens.is_empty() {
             return false;
         }
@@ -2117,8 +2589,34 @@ impl Editor {
                 pending_file.path
             );
 
-            match self.open_file(&pending_file.path) {
-                Ok(_) => {
+            if pending_file.remote_connection.is_none()
+                && self.open_plugin_uri(&pending_file.path, pending_file.line, pending_file.column)
+            {
+                processed_any = true;
+                continue;
+            }
+
+            let result = match &pending_file.remote_connection {
+                None => self.open_file(&pending_file.path),
+                Some(connection) => match self
+                    .filesystem_roots()
+                    .into_iter()
+                    .find(|fs| fs.remote_connection_info() == Some(connection.as_str()))
+                {
+                    Some(filesystem) => {
+                        self.open_file_with_filesystem(&pending_file.path, filesystem)
+                    }
+                    None => Err(anyhow::anyhow!(t!(
+                        "file.remote_not_mounted",
+                        host = connection
+                    ))),
+                },
+            };
+            match result {
+                Ok(buffer_id) => {
+                    if pending_file.read_only {
+                        self.set_buffer_read_only(buffer_id, true);
+                    }
                     // Navigate to line/column if specified
                     if let Some(line) = pending_file.line {
                         self.goto_line_col(line, pending_file.column);
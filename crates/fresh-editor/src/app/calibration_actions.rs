@@ -11,7 +11,9 @@ use rust_i18n::t;
 impl Editor {
     /// Open the calibration wizard
     pub fn open_calibration_wizard(&mut self) {
-        self.calibration_wizard = Some(CalibrationWizard::new());
+        let mut wizard = CalibrationWizard::new();
+        wizard.keyboard_protocol = self.keyboard_enhancement_active;
+        self.calibration_wizard = Some(wizard);
         self.set_status_message(t!("calibration.started").to_string());
     }
 
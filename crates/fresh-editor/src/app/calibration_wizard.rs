@@ -190,6 +190,64 @@ pub fn calibration_groups() -> Vec<CalibrationGroup> {
                 },
             ],
         },
+        // Group 6: Enter Variants (3 keys)
+        CalibrationGroup {
+            name: "Enter Variants",
+            targets: vec![
+                CalibrationTarget {
+                    name: "SHIFT+ENTER",
+                    expected: ExpectedKey::new(KeyCode::Enter, KeyModifiers::SHIFT),
+                },
+                CalibrationTarget {
+                    name: "CTRL+ENTER",
+                    expected: ExpectedKey::new(KeyCode::Enter, KeyModifiers::CONTROL),
+                },
+                CalibrationTarget {
+                    name: "ALT+ENTER",
+                    expected: ExpectedKey::new(KeyCode::Enter, KeyModifiers::ALT),
+                },
+            ],
+        },
+        // Group 7: Vertical Arrows (4 keys)
+        CalibrationGroup {
+            name: "Vertical Arrows",
+            targets: vec![
+                CalibrationTarget {
+                    name: "CTRL+UP",
+                    expected: ExpectedKey::new(KeyCode::Up, KeyModifiers::CONTROL),
+                },
+                CalibrationTarget {
+                    name: "CTRL+DOWN",
+                    expected: ExpectedKey::new(KeyCode::Down, KeyModifiers::CONTROL),
+                },
+                CalibrationTarget {
+                    name: "ALT+UP",
+                    expected: ExpectedKey::new(KeyCode::Up, KeyModifiers::ALT),
+                },
+                CalibrationTarget {
+                    name: "ALT+DOWN",
+                    expected: ExpectedKey::new(KeyCode::Down, KeyModifiers::ALT),
+                },
+            ],
+        },
+        // Group 8: Alt Combinations (3 keys)
+        CalibrationGroup {
+            name: "Alt Combinations",
+            targets: vec![
+                CalibrationTarget {
+                    name: "ALT+B",
+                    expected: ExpectedKey::new(KeyCode::Char('b'), KeyModifiers::ALT),
+                },
+                CalibrationTarget {
+                    name: "ALT+F",
+                    expected: ExpectedKey::new(KeyCode::Char('f'), KeyModifiers::ALT),
+                },
+                CalibrationTarget {
+                    name: "ALT+BACKSPACE",
+                    expected: ExpectedKey::new(KeyCode::Backspace, KeyModifiers::ALT),
+                },
+            ],
+        },
     ]
 }
 
@@ -277,6 +335,9 @@ pub struct CalibrationWizard {
     pub status_message: Option<String>,
     /// Pending confirmation dialog
     pub pending_confirmation: PendingConfirmation,
+    /// Whether the terminal accepted the kitty keyboard protocol, in which
+    /// case modified keys arrive unambiguously and most need no mapping
+    pub keyboard_protocol: bool,
 }
 
 impl CalibrationWizard {
@@ -298,6 +359,7 @@ impl CalibrationWizard {
             verified: HashSet::new(),
             status_message: None,
             pending_confirmation: PendingConfirmation::None,
+            keyboard_protocol: false,
         }
     }
 
@@ -460,6 +522,18 @@ impl CalibrationWizard {
             self.key_statuses[flat_idx] = KeyStatus::Skipped;
             self.captured_raw_keys.remove(&flat_idx);
             self.status_message = Some(t!("calibration.key_works").to_string());
+        } else if self.is_indistinguishable(&key, flat_idx) {
+            // Mapping this would break the key the terminal actually sent
+            self.key_statuses[flat_idx] = KeyStatus::Skipped;
+            self.captured_raw_keys.remove(&flat_idx);
+            self.status_message = Some(
+                t!(
+                    "calibration.key_indistinguishable",
+                    key = crate::input::keybindings::format_keybinding(&key.code, &key.modifiers),
+                    target = target.name
+                )
+                .to_string(),
+            );
         } else {
             // Record the translation: raw -> expected
             let raw_key = KeyEventKey::from_key_event(&key);
@@ -482,6 +556,27 @@ impl CalibrationWizard {
         WizardAction::KeyCaptured
     }
 
+    /// Whether `key` is something the user needs as-is, so it can't be
+    /// translated to the target at `flat_idx`: the expected key of another
+    /// target, a plain Enter/Escape, or an unmodified printable ASCII key.
+    /// This is how Shift+Enter looks in terminals that send a bare CR.
+    fn is_indistinguishable(&self, key: &KeyEvent, flat_idx: usize) -> bool {
+        let other_target = self
+            .groups
+            .iter()
+            .flat_map(|g| g.targets.iter())
+            .enumerate()
+            .any(|(idx, t)| {
+                idx != flat_idx
+                    && t.expected.code == key.code
+                    && t.expected.modifiers == key.modifiers
+            });
+        let plain = key.modifiers.difference(KeyModifiers::SHIFT).is_empty();
+        other_target
+            || (plain && matches!(key.code, KeyCode::Enter | KeyCode::Esc))
+            || (plain && matches!(key.code, KeyCode::Char(c) if c.is_ascii_graphic() || c == ' '))
+    }
+
     /// Handle a key event during verification phase
     pub fn handle_verify_key(&mut self, key: KeyEvent) -> WizardAction {
         // Check for control keys
@@ -749,7 +844,7 @@ mod tests {
         let wizard = CalibrationWizard::new();
         let (step, total) = wizard.current_step_info();
         assert_eq!(step, 1);
-        assert_eq!(total, 24); // 4 + 4 + 8 + 4 + 4 = 24 keys
+        assert_eq!(total, 34); // 4 + 4 + 8 + 4 + 4 + 3 + 4 + 3 = 34 keys
     }
 
     #[test]
@@ -928,6 +1023,36 @@ mod tests {
         assert_eq!(wizard.translation_count(), 0);
     }
 
+    #[test]
+    fn test_indistinguishable_key_not_mapped() {
+        let mut wizard = CalibrationWizard::new();
+        let enter_variants = wizard
+            .groups()
+            .iter()
+            .position(|g| g.name == "Enter Variants")
+            .unwrap();
+        wizard.step = CalibrationStep::Capture {
+            group_idx: enter_variants,
+            key_idx: 0,
+        };
+
+        // Terminal sends a bare Enter for Shift+Enter: mapping it would break Enter
+        let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        let action = wizard.handle_capture_key(key);
+        assert!(matches!(action, WizardAction::KeyCaptured));
+        assert_eq!(wizard.translation_count(), 0);
+
+        // Ctrl+Enter arriving as Ctrl+J is a distinct sequence and can be mapped
+        let key = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL);
+        wizard.handle_capture_key(key);
+        assert_eq!(wizard.translation_count(), 1);
+
+        // Alt+Enter arriving as Shift+Enter collides with another target
+        let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT);
+        wizard.handle_capture_key(key);
+        assert_eq!(wizard.translation_count(), 1);
+    }
+
     #[test]
     fn test_restart() {
         let mut wizard = CalibrationWizard::new();
@@ -956,7 +1081,7 @@ mod tests {
         let mut wizard = CalibrationWizard::new();
 
         // Skip all keys to get to verify phase
-        for _ in 0..24 {
+        for _ in 0..34 {
             let key = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
             wizard.handle_capture_key(key);
         }
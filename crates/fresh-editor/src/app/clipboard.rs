@@ -3,17 +3,20 @@
 //! This module contains clipboard operations and multi-cursor actions:
 //! - Copy/cut/paste operations
 //! - Copy with formatting (HTML with syntax highlighting)
-//! - Multi-cursor add above/below/at next match
+//! - Multi-cursor add above/below/at next match, skip occurrence and select
+//!   all occurrences
 
 use rust_i18n::t;
 
 use crate::input::multi_cursor::{
-    add_cursor_above, add_cursor_at_next_match, add_cursor_below, AddCursorResult,
+    add_cursor_above, add_cursor_at_next_match, add_cursor_below, select_all_matches,
+    skip_to_next_match, AddCursorResult, MatchOptions,
 };
 use crate::model::buffer::Buffer;
 use crate::model::cursor::Position2D;
 use crate::model::event::{CursorId, Event};
 use crate::primitives::word_navigation::{find_word_start_left, find_word_start_right};
+use crate::services::render_profile::{self, Phase};
 
 use super::Editor;
 
@@ -541,6 +544,7 @@ impl Editor {
         if paste_text.is_empty() {
             return;
         }
+        let _profile = render_profile::scope(Phase::Events);
 
         // Normalize line endings: first convert all to LF, then to buffer's format
         // This handles Windows clipboard (CRLF), old Mac (CR), and Unix (LF)
@@ -654,11 +658,47 @@ impl Editor {
         self.clipboard.get_internal().to_string()
     }
 
+    /// Match options for the occurrence commands, from the search options
+    fn occurrence_match_options(&self) -> MatchOptions {
+        MatchOptions {
+            case_sensitive: self.search_case_sensitive,
+            whole_word: self.search_whole_word,
+        }
+    }
+
+    /// Id for a cursor added after the existing ones
+    fn next_cursor_id(&self) -> CursorId {
+        let cursors = &self.active_state().cursors;
+        CursorId(cursors.iter().map(|(id, _)| id.0 + 1).max().unwrap_or(0))
+    }
+
+    /// Event moving the primary cursor to `position` with `anchor`
+    fn move_primary_event(&self, position: usize, anchor: Option<usize>) -> Event {
+        let cursors = &self.active_state().cursors;
+        let primary = cursors.primary();
+        Event::MoveCursor {
+            cursor_id: cursors.primary_id(),
+            old_position: primary.position,
+            new_position: position,
+            old_anchor: primary.anchor,
+            new_anchor: anchor,
+            old_sticky_column: primary.sticky_column,
+            new_sticky_column: 0,
+        }
+    }
+
+    /// Log and apply a cursor event
+    fn apply_cursor_event(&mut self, event: Event) {
+        self.active_event_log_mut().append(event.clone());
+        self.apply_event_to_active_buffer(&event);
+    }
+
     /// Add a cursor at the next occurrence of the selected text
     /// If no selection, first selects the entire word at cursor position
     pub fn add_cursor_at_next_match(&mut self) {
+        let options = self.occurrence_match_options();
         let state = self.active_state_mut();
-        match add_cursor_at_next_match(state) {
+        match add_cursor_at_next_match(state, options) {
             AddCursorResult::Success {
                 cursor,
                 total_cursors,
@@ -670,10 +710,7 @@ impl Editor {
                     position: cursor.position,
                     anchor: cursor.anchor,
                 };
-
-                // Log and apply the event
-                self.active_event_log_mut().append(event.clone());
-                self.apply_event_to_active_buffer(&event);
+                self.apply_cursor_event(event);
 
                 self.status_message =
                     Some(t!("clipboard.added_cursor_match", count = total_cursors).to_string());
@@ -683,21 +720,59 @@ impl Editor {
                 word_end,
             } => {
                 // Select the word by updating the primary cursor
-                let primary_id = self.active_state().cursors.primary_id();
-                let primary = self.active_state().cursors.primary();
-                let event = Event::MoveCursor {
-                    cursor_id: primary_id,
-                    old_position: primary.position,
-                    new_position: word_end,
-                    old_anchor: primary.anchor,
-                    new_anchor: Some(word_start),
-                    old_sticky_column: primary.sticky_column,
-                    new_sticky_column: 0,
+                let event = self.move_primary_event(word_end, Some(word_start));
+                self.apply_cursor_event(event);
+            }
+            AddCursorResult::Failed { message } => {
+                self.status_message = Some(message);
+            }
+        }
+    }
+
+    /// Move the last added cursor to the next occurrence of its selection,
+    /// leaving the occurrence it selected
+    /// If no selection, first selects the entire word at cursor position
+    pub fn skip_to_next_match(&mut self) {
+        let options = self.occurrence_match_options();
+        let state = self.active_state_mut();
+        match skip_to_next_match(state, options) {
+            AddCursorResult::Success {
+                cursor,
+                total_cursors,
+            } => {
+                let cursors = &self.active_state().cursors;
+                let event = if cursors.count() == 1 {
+                    self.move_primary_event(cursor.position, cursor.anchor)
+                } else {
+                    // Replace the primary (last added) cursor in one undo step
+                    let primary = *cursors.primary();
+                    Event::Batch {
+                        events: vec![
+                            Event::RemoveCursor {
+                                cursor_id: cursors.primary_id(),
+                                position: primary.position,
+                                anchor: primary.anchor,
+                            },
+                            Event::AddCursor {
+                                cursor_id: self.next_cursor_id(),
+                                position: cursor.position,
+                                anchor: cursor.anchor,
+                            },
+                        ],
+                        description: "Skip occurrence".to_string(),
+                    }
                 };
+                self.apply_cursor_event(event);
 
-                // Log and apply the event
-                self.active_event_log_mut().append(event.clone());
-                self.apply_event_to_active_buffer(&event);
+                self.status_message =
+                    Some(t!("clipboard.skipped_cursor_match", count = total_cursors).to_string());
+            }
+            AddCursorResult::WordSelected {
+                word_start,
+                word_end,
+            } => {
+                let event = self.move_primary_event(word_end, Some(word_start));
+                self.apply_cursor_event(event);
             }
             AddCursorResult::Failed { message } => {
                 self.status_message = Some(message);
@@ -705,6 +780,45 @@ impl Editor {
         }
     }
 
+    /// Add cursors at every occurrence of the selected text
+    /// If no selection, selects the word at cursor position and its occurrences
+    pub fn select_all_matches(&mut self) {
+        let options = self.occurrence_match_options();
+        let state = self.active_state_mut();
+        let matches = match select_all_matches(state, options) {
+            Ok(matches) => matches,
+            Err(message) => {
+                self.status_message = Some(message);
+                return;
+            }
+        };
+
+        let mut events = Vec::new();
+        if let Some(word) = matches.word {
+            events.push(self.move_primary_event(word.end, Some(word.start)));
+        }
+        let first_id = self.next_cursor_id().0;
+        events.extend(
+            matches
+                .cursors
+                .iter()
+                .enumerate()
+                .map(|(i, cursor)| Event::AddCursor {
+                    cursor_id: CursorId(first_id + i),
+                    position: cursor.position,
+                    anchor: cursor.anchor,
+                }),
+        );
+        // One undo step for all the new cursors
+        self.apply_cursor_event(Event::Batch {
+            events,
+            description: "Select all occurrences".to_string(),
+        });
+
+        let count = self.active_state().cursors.count();
+        self.status_message = Some(t!("clipboard.selected_all_matches", count = count).to_string());
+    }
+
     /// Add a cursor above the primary cursor at the same column
     pub fn add_cursor_above(&mut self) {
         let state = self.active_state_mut();
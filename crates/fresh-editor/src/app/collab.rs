@@ -0,0 +1,359 @@
+//! Shared cursors for sessions with several attached clients
+//!
+//! All clients of a session server edit through one `Editor`, and the server
+//! handles their input one event at a time, in the order it arrives, so edits
+//! never interleave. The buffer's cursors belong to the client whose input is
+//! being handled (the owner). Every other client's cursors are parked as
+//! markers, which move with edits like any other marker, and are drawn as
+//! overlays in that client's color. When a parked client sends input, its
+//! cursors are restored and the previous owner's are parked instead.
+//!
+//! Since every client sees the same screen, **Follow User** pins that screen
+//! to one client: after anyone else's input, the view returns to the
+//! followed client's buffer and cursors.
+
+use ratatui::style::{Color, Style};
+use rust_i18n::t;
+
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::model::cursor::{Cursor, Cursors};
+use crate::model::event::BufferId;
+use crate::model::marker::MarkerId;
+use crate::view::overlay::{Overlay, OverlayFace, OverlayNamespace, UnderlineStyle};
+use crate::view::prompt::{Prompt, PromptType};
+
+/// Colors given to clients in the order they attach
+const PEER_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::Red,
+];
+
+/// Above selection and search highlights
+const PEER_CURSOR_PRIORITY: i32 = 100;
+
+fn collab_namespace() -> OverlayNamespace {
+    OverlayNamespace::from_string("collab-cursors".to_string())
+}
+
+/// Clients sharing the editor and where their cursors are
+#[derive(Debug, Default)]
+pub(crate) struct CollabState {
+    /// Client whose cursors are the buffer's cursors
+    owner: Option<u64>,
+    /// Attached clients, in attach order
+    peers: Vec<Peer>,
+    /// Client whose buffer and cursors the view returns to (Follow User)
+    followed: Option<u64>,
+}
+
+#[derive(Debug)]
+struct Peer {
+    id: u64,
+    color: Color,
+    parked: Option<ParkedCursors>,
+}
+
+/// A client's cursors while another client owns the buffer's cursors
+#[derive(Debug)]
+struct ParkedCursors {
+    buffer: BufferId,
+    /// (position, anchor) markers, primary first
+    cursors: Vec<(MarkerId, Option<MarkerId>)>,
+}
+
+impl Editor {
+    /// Register an attached client; the first one owns the cursors
+    pub fn collab_join(&mut self, client: u64) {
+        let color = PEER_COLORS
+            .iter()
+            .copied()
+            .find(|color| !self.collab.peers.iter().any(|peer| peer.color == *color))
+            .unwrap_or(PEER_COLORS[self.collab.peers.len() % PEER_COLORS.len()]);
+        self.collab.peers.push(Peer {
+            id: client,
+            color,
+            parked: None,
+        });
+        if self.collab.owner.is_none() {
+            self.collab.owner = Some(client);
+        }
+    }
+
+    /// Forget a client that detached or disconnected
+    pub fn collab_leave(&mut self, client: u64) {
+        let Some(index) = self.collab.peers.iter().position(|peer| peer.id == client) else {
+            return;
+        };
+        let peer = self.collab.peers.remove(index);
+        if let Some(parked) = peer.parked {
+            self.release_parked(parked);
+        }
+        if self.collab.owner == Some(client) {
+            self.collab.owner = None;
+        }
+        if self.collab.followed == Some(client) {
+            self.collab.followed = None;
+            self.set_status_message(t!("collab.follow_left", id = client).to_string());
+        }
+        self.refresh_collab_cursors();
+    }
+
+    /// The client whose cursors are the buffer's cursors
+    pub fn collab_owner(&self) -> Option<u64> {
+        self.collab.owner
+    }
+
+    /// Give the buffer's cursors to `client` before handling its input
+    ///
+    /// The current owner's cursors are parked. If `client` has cursors parked
+    /// in the active buffer they are restored; otherwise it continues from
+    /// the current cursors.
+    pub fn collab_activate(&mut self, client: u64) {
+        self.activate_peer(client, false);
+    }
+
+    /// Return the view to the followed client after other clients' input
+    ///
+    /// Its cursors become the buffer's cursors again, in the buffer it was
+    /// last in, so the view scrolls back to them.
+    pub fn collab_return_to_followed(&mut self) {
+        if let Some(client) = self.collab.followed {
+            self.activate_peer(client, true);
+        }
+    }
+
+    /// The client the view follows, if any
+    pub fn collab_followed(&self) -> Option<u64> {
+        self.collab.followed
+    }
+
+    /// Follow `client`, or stop following with `None`
+    pub fn collab_follow(&mut self, client: Option<u64>) {
+        let message = match (client, self.collab.followed) {
+            (Some(id), _) if !self.collab.peers.iter().any(|peer| peer.id == id) => {
+                self.set_status_message(t!("collab.follow_unknown", id = id).to_string());
+                return;
+            }
+            (Some(id), _) => t!("collab.following", id = id),
+            (None, Some(id)) => t!("collab.follow_stopped", id = id),
+            (None, None) => return,
+        };
+        self.collab.followed = client;
+        self.set_status_message(message.to_string());
+    }
+
+    /// Stop following, or ask which client to follow
+    pub(crate) fn toggle_follow_user(&mut self) {
+        if self.collab.followed.is_some() {
+            self.collab_follow(None);
+            return;
+        }
+        let suggestions: Vec<Suggestion> = self
+            .collab
+            .peers
+            .iter()
+            .filter(|peer| Some(peer.id) != self.collab.owner)
+            .map(|peer| Suggestion {
+                text: t!("collab.user", id = peer.id).to_string(),
+                description: peer
+                    .parked
+                    .as_ref()
+                    .map(|parked| self.get_buffer_display_name(parked.buffer)),
+                value: Some(peer.id.to_string()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+        if suggestions.is_empty() {
+            self.set_status_message(t!("collab.follow_no_users").to_string());
+            return;
+        }
+        self.prompt = Some(Prompt::with_suggestions(
+            t!("collab.follow_prompt").to_string(),
+            PromptType::FollowUser,
+            suggestions,
+        ));
+    }
+
+    /// Handle the Follow User prompt; `input` is the client id
+    pub(crate) fn handle_follow_user(&mut self, input: &str) {
+        match input.trim().parse::<u64>() {
+            Ok(id) => self.collab_follow(Some(id)),
+            Err(_) => {
+                self.set_status_message(t!("collab.follow_unknown", id = input.trim()).to_string())
+            }
+        }
+    }
+
+    /// Make `client` the owner of the buffer's cursors
+    ///
+    /// With `follow_buffer`, a client whose cursors are parked in another
+    /// buffer brings that buffer into the active split first.
+    fn activate_peer(&mut self, client: u64, follow_buffer: bool) {
+        if self.collab.owner == Some(client) {
+            return;
+        }
+        let Some(index) = self.collab.peers.iter().position(|peer| peer.id == client) else {
+            return;
+        };
+
+        self.save_current_split_view_state();
+        let buffer = self.active_buffer();
+
+        if let Some(owner) = self.collab.owner {
+            let parked = self.park_cursors(buffer);
+            let replaced = match self.collab.peers.iter_mut().find(|peer| peer.id == owner) {
+                Some(peer) => peer.parked.replace(parked),
+                None => Some(parked),
+            };
+            if let Some(old) = replaced {
+                self.release_parked(old);
+            }
+        }
+
+        if let Some(parked) = self.collab.peers[index].parked.take() {
+            if follow_buffer && parked.buffer != buffer && self.buffers.contains_key(&parked.buffer)
+            {
+                self.set_active_buffer(parked.buffer);
+            }
+            if parked.buffer == self.active_buffer() {
+                let state = self.active_state();
+                let resolved: Vec<(usize, Option<usize>)> = parked
+                    .cursors
+                    .iter()
+                    .filter_map(|(position, anchor)| {
+                        let position = state.marker_list.get_position(*position)?;
+                        let anchor = anchor.and_then(|a| state.marker_list.get_position(a));
+                        Some((position, anchor))
+                    })
+                    .collect();
+                if let Some(cursors) = cursors_from(&resolved) {
+                    self.active_state_mut().cursors = cursors;
+                    self.save_current_split_view_state();
+                }
+            }
+            self.release_parked(parked);
+        }
+
+        self.collab.owner = Some(client);
+        self.refresh_collab_cursors();
+    }
+
+    /// Redraw the overlays that show the parked clients' cursors
+    pub fn refresh_collab_cursors(&mut self) {
+        let namespace = collab_namespace();
+        for state in self.buffers.values_mut() {
+            state
+                .overlays
+                .clear_namespace(&namespace, &mut state.marker_list);
+        }
+
+        for peer in &self.collab.peers {
+            if Some(peer.id) == self.collab.owner {
+                continue;
+            }
+            let Some(parked) = &peer.parked else {
+                continue;
+            };
+            let Some(state) = self.buffers.get_mut(&parked.buffer) else {
+                continue;
+            };
+            let len = state.buffer.len();
+            for (position, anchor) in &parked.cursors {
+                let Some(position) = state.marker_list.get_position(*position) else {
+                    continue;
+                };
+                if let Some(anchor) = anchor.and_then(|a| state.marker_list.get_position(a)) {
+                    let range = position.min(anchor)..position.max(anchor);
+                    if !range.is_empty() {
+                        let face = OverlayFace::Underline {
+                            color: peer.color,
+                            style: UnderlineStyle::Straight,
+                        };
+                        let overlay = Overlay::with_namespace(
+                            &mut state.marker_list,
+                            range,
+                            face,
+                            namespace.clone(),
+                        )
+                        .with_priority_value(PEER_CURSOR_PRIORITY);
+                        state.overlays.add(overlay);
+                    }
+                }
+                if position < len {
+                    let face = OverlayFace::Style {
+                        style: Style::default().fg(Color::Black).bg(peer.color),
+                    };
+                    let overlay = Overlay::with_namespace(
+                        &mut state.marker_list,
+                        position..position + 1,
+                        face,
+                        namespace.clone(),
+                    )
+                    .with_priority_value(PEER_CURSOR_PRIORITY + 1);
+                    state.overlays.add(overlay);
+                }
+            }
+        }
+    }
+
+    /// Turn the active buffer's cursors into markers
+    fn park_cursors(&mut self, buffer: BufferId) -> ParkedCursors {
+        let state = self.active_state_mut();
+        let primary = state.cursors.primary_id();
+        let mut cursors: Vec<_> = state
+            .cursors
+            .iter()
+            .map(|(id, cursor)| (id != primary, cursor.position, cursor.anchor))
+            .collect();
+        cursors.sort();
+        let cursors = cursors
+            .into_iter()
+            .map(|(_, position, anchor)| {
+                // Text inserted at a parked cursor ends up before it
+                let position = state.marker_list.create(position, false);
+                let anchor = anchor.map(|a| state.marker_list.create(a, false));
+                (position, anchor)
+            })
+            .collect();
+        ParkedCursors { buffer, cursors }
+    }
+
+    fn release_parked(&mut self, parked: ParkedCursors) {
+        if let Some(state) = self.buffers.get_mut(&parked.buffer) {
+            for (position, anchor) in parked.cursors {
+                state.marker_list.delete(position);
+                if let Some(anchor) = anchor {
+                    state.marker_list.delete(anchor);
+                }
+            }
+        }
+    }
+}
+
+/// Build a cursor set from (position, anchor) pairs, primary first
+fn cursors_from(resolved: &[(usize, Option<usize>)]) -> Option<Cursors> {
+    let ((position, anchor), rest) = resolved.split_first()?;
+    let mut cursors = Cursors::new();
+    let primary = cursors.primary_id();
+    for (position, anchor) in rest {
+        cursors.add(cursor_at(*position, *anchor));
+    }
+    // `add` makes each new cursor primary; this makes the first one primary again
+    cursors.insert_with_id(primary, cursor_at(*position, *anchor));
+    Some(cursors)
+}
+
+fn cursor_at(position: usize, anchor: Option<usize>) -> Cursor {
+    let mut cursor = Cursor::new(position);
+    if let Some(anchor) = anchor {
+        cursor.set_anchor(anchor);
+    }
+    cursor
+}
@@ -0,0 +1,305 @@
+//! Sharing a buffer with editors on other machines
+//!
+//! **Collab: Share Buffer** listens on an address and **Collab: Join**
+//! connects to one (see `services::collab`). Each editor keeps a CRDT copy
+//! of the buffer. Local edits are found by comparing the buffer with that
+//! copy whenever the buffer's version changes, so every kind of edit is
+//! picked up: typing, undo, formatting and plugin edits alike. Remote edits
+//! are applied and logged like any other edit, which means undo can revert
+//! them too.
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::model::event::{BufferId, CursorId, Event};
+use crate::services::collab::crdt::{Document, Edit};
+use crate::services::collab::{self, Link, LinkEvent, Message, Target, HOST_PEER};
+use crate::view::prompt::PromptType;
+
+/// Address suggested by the share and join prompts
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7707";
+
+/// A shared buffer and its link
+pub(crate) struct ShareSession {
+    link: Link,
+    /// Whether this editor shares the buffer (rather than having joined)
+    host: bool,
+    /// Unset while a join waits for the snapshot
+    buffer: Option<BufferId>,
+    document: Document,
+    /// Buffer version the document last matched
+    synced_version: u64,
+    name: String,
+    peers: usize,
+}
+
+impl Editor {
+    /// Ask for the address to share the active buffer on
+    pub fn start_collab_share_prompt(&mut self) {
+        if self.collab_share.is_some() {
+            self.set_status_message(t!("collab.already_active").to_string());
+            return;
+        }
+        self.start_prompt_with_initial_text(
+            t!("collab.share_prompt").to_string(),
+            PromptType::CollabShare,
+            DEFAULT_ADDRESS.to_string(),
+        );
+    }
+
+    /// Ask for the address of a shared buffer to join
+    pub fn start_collab_join_prompt(&mut self) {
+        if self.collab_share.is_some() {
+            self.set_status_message(t!("collab.already_active").to_string());
+            return;
+        }
+        self.start_prompt_with_initial_text(
+            t!("collab.join_prompt").to_string(),
+            PromptType::CollabJoin,
+            DEFAULT_ADDRESS.to_string(),
+        );
+    }
+
+    /// Share the active buffer on `address`
+    pub(super) fn share_active_buffer(&mut self, address: &str) {
+        let buffer = self.active_buffer();
+        let name = self.get_buffer_display_name(buffer);
+        let state = self.active_state();
+        let Some(text) = state.buffer.to_string() else {
+            self.set_status_message(t!("collab.not_loaded", name = &name).to_string());
+            return;
+        };
+        let version = state.buffer.version();
+
+        let link = match Link::share(address) {
+            Ok(link) => link,
+            Err(e) => {
+                self.set_status_message(
+                    t!(
+                        "collab.share_failed",
+                        address = address,
+                        error = e.to_string()
+                    )
+                    .to_string(),
+                );
+                return;
+            }
+        };
+        let address = link
+            .local_addr()
+            .map(|a| a.to_string())
+            .unwrap_or_else(|| address.to_string());
+        self.set_status_message(t!("collab.sharing", name = &name, address = address).to_string());
+        self.collab_share = Some(ShareSession {
+            link,
+            host: true,
+            buffer: Some(buffer),
+            document: Document::new(collab::new_site_id(), &text),
+            synced_version: version,
+            name,
+            peers: 0,
+        });
+    }
+
+    /// Join the buffer shared at `address`
+    ///
+    /// The buffer opens once the sharing editor sends it.
+    pub(super) fn join_shared_buffer(&mut self, address: &str) {
+        match Link::join(address) {
+            Ok(link) => {
+                self.set_status_message(t!("collab.joining", address = address).to_string());
+                self.collab_share = Some(ShareSession {
+                    link,
+                    host: false,
+                    buffer: None,
+                    document: Document::new(collab::new_site_id(), ""),
+                    synced_version: 0,
+                    name: String::new(),
+                    peers: 0,
+                });
+            }
+            Err(e) => self.set_status_message(
+                t!(
+                    "collab.join_failed",
+                    address = address,
+                    error = e.to_string()
+                )
+                .to_string(),
+            ),
+        }
+    }
+
+    /// Stop sharing, or leave the shared buffer; the buffer stays open
+    pub fn stop_collab_share(&mut self) {
+        if self.collab_share.take().is_some() {
+            self.set_status_message(t!("collab.stopped").to_string());
+        } else {
+            self.set_status_message(t!("collab.not_active").to_string());
+        }
+    }
+
+    /// Exchange edits with the other editors
+    ///
+    /// Local edits are sent before remote ones are applied, so that the
+    /// document and the buffer agree when remote positions are resolved.
+    /// Returns true if anything changed that needs a render.
+    pub(super) fn poll_collab_share(&mut self) -> bool {
+        let Some(mut session) = self.collab_share.take() else {
+            return false;
+        };
+
+        if let Some(buffer) = session.buffer {
+            if !self.buffers.contains_key(&buffer) {
+                self.set_status_message(t!("collab.buffer_closed").to_string());
+                return true;
+            }
+            self.send_local_edits(&mut session, buffer);
+        }
+
+        let mut changed = false;
+        for event in session.link.poll() {
+            changed = true;
+            match event {
+                LinkEvent::Connected { peer, address } => {
+                    if session.host {
+                        session.peers += 1;
+                        session.link.send(
+                            Target::Peer(peer),
+                            &Message::Welcome {
+                                name: session.name.clone(),
+                                snapshot: session.document.snapshot(),
+                            },
+                        );
+                        self.set_status_message(
+                            t!(
+                                "collab.peer_joined",
+                                address = address,
+                                count = session.peers
+                            )
+                            .to_string(),
+                        );
+                    }
+                }
+                LinkEvent::Message {
+                    message: Message::Welcome { name, snapshot },
+                    ..
+                } if !session.host && session.buffer.is_none() => {
+                    session.document = Document::from_snapshot(session.document.site(), &snapshot);
+                    let buffer = self.open_shared_buffer(&name, &session.document.text());
+                    session.synced_version = self.buffers[&buffer].buffer.version();
+                    session.buffer = Some(buffer);
+                    session.name = name;
+                    self.set_status_message(t!("collab.joined", name = &session.name).to_string());
+                }
+                LinkEvent::Message {
+                    peer,
+                    message: Message::Ops { ops },
+                } => {
+                    let Some(buffer) = session.buffer else {
+                        continue;
+                    };
+                    for op in &ops {
+                        let edits = session.document.apply(op);
+                        self.apply_remote_edits(buffer, edits);
+                    }
+                    if let Some(state) = self.buffers.get(&buffer) {
+                        session.synced_version = state.buffer.version();
+                    }
+                    if session.host {
+                        session
+                            .link
+                            .send(Target::AllExcept(peer), &Message::Ops { ops });
+                    }
+                }
+                LinkEvent::Message { .. } => {}
+                LinkEvent::Disconnected { peer, error } => {
+                    if session.host {
+                        session.peers = session.peers.saturating_sub(1);
+                        self.set_status_message(
+                            t!("collab.peer_left", count = session.peers).to_string(),
+                        );
+                    } else if peer == HOST_PEER {
+                        let error = error.unwrap_or_else(|| "closed".to_string());
+                        self.set_status_message(
+                            t!("collab.disconnected", error = error).to_string(),
+                        );
+                        return true;
+                    }
+                }
+            }
+        }
+
+        self.collab_share = Some(session);
+        changed
+    }
+
+    /// Send the edits made to the shared buffer since the last sync
+    fn send_local_edits(&mut self, session: &mut ShareSession, buffer: BufferId) {
+        let Some(state) = self.buffers.get(&buffer) else {
+            return;
+        };
+        let version = state.buffer.version();
+        if version == session.synced_version {
+            return;
+        }
+        let Some(text) = state.buffer.to_string() else {
+            return;
+        };
+        session.synced_version = version;
+        let ops = session.document.update(&text);
+        if !ops.is_empty() {
+            session.link.send(Target::All, &Message::Ops { ops });
+        }
+    }
+
+    /// Apply edits from another editor without moving local cursors
+    fn apply_remote_edits(&mut self, buffer: BufferId, edits: Vec<Edit>) {
+        for edit in edits {
+            let event = match edit {
+                Edit::Insert { position, text } => Event::Insert {
+                    position,
+                    text,
+                    cursor_id: CursorId::UNDO_SENTINEL,
+                },
+                Edit::Delete { range } => {
+                    let Some(state) = self.buffers.get_mut(&buffer) else {
+                        return;
+                    };
+                    let deleted_text = state.get_text_range(range.start, range.end);
+                    Event::Delete {
+                        range,
+                        deleted_text,
+                        cursor_id: CursorId::UNDO_SENTINEL,
+                    }
+                }
+            };
+            if buffer == self.active_buffer() {
+                self.apply_event_to_active_buffer(&event);
+            } else if let Some(state) = self.buffers.get_mut(&buffer) {
+                state.apply(&event);
+            }
+            if let Some(log) = self.event_logs.get_mut(&buffer) {
+                log.append(event);
+            }
+        }
+    }
+
+    /// Open a new buffer holding the text received from the sharing editor
+    fn open_shared_buffer(&mut self, name: &str, text: &str) -> BufferId {
+        let buffer = self.new_buffer();
+        if let Some(metadata) = self.buffer_metadata.get_mut(&buffer) {
+            metadata.display_name = format!("{} (shared)", name);
+        }
+        if let Some(state) = self.buffers.get_mut(&buffer) {
+            if !text.is_empty() {
+                state.apply(&Event::Insert {
+                    position: 0,
+                    text: text.to_string(),
+                    cursor_id: CursorId::UNDO_SENTINEL,
+                });
+            }
+            state.buffer.set_modified(false);
+        }
+        buffer
+    }
+}
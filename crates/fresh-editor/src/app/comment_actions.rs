@@ -0,0 +1,213 @@
+//! **Toggle Line Comment** and **Toggle Block Comment**, for every cursor
+//!
+//! The comment markers come from the buffer's language configuration, which
+//! language packs fill in. A language without block comments falls back to
+//! line comments and the other way around.
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::model::event::{CursorId, Event};
+use crate::primitives::comment_toggle::{self, CommentEdit};
+
+impl Editor {
+    /// Toggle line comments on the lines touched by each cursor or selection
+    pub(super) fn toggle_line_comment(&mut self) {
+        self.toggle_comments(false);
+    }
+
+    /// Toggle a block comment around each selection, or around the current
+    /// line of each cursor without one
+    pub(super) fn toggle_block_comment(&mut self) {
+        self.toggle_comments(true);
+    }
+
+    fn toggle_comments(&mut self, block: bool) {
+        let lang = self.config.languages.get(&self.active_state().language);
+        let line_prefix = lang.and_then(|lang| lang.comment_prefix.clone());
+        let block_markers = lang.and_then(|lang| {
+            Some((
+                lang.block_comment_start.clone()?,
+                lang.block_comment_end.clone()?,
+            ))
+        });
+        // Prefer the style asked for, then whatever the language has
+        let markers = match (block, line_prefix, block_markers) {
+            (true, _, Some((open, close))) | (false, None, Some((open, close))) => {
+                Markers::Block(open, close)
+            }
+            (_, Some(prefix), _) => Markers::Line(prefix),
+            (_, None, None) => return,
+        };
+        let whole_lines = !block || matches!(markers, Markers::Line(_));
+
+        let estimated_line_length = self.config.editor.estimated_line_length;
+        let state = self.active_state_mut();
+        let mut cursors: Vec<_> = state.cursors.iter().map(|(id, c)| (id, *c)).collect();
+        cursors.sort_by_key(|(_, cursor)| cursor.position.min(cursor.anchor.unwrap_or(usize::MAX)));
+
+        // The span each cursor toggles; spans of whole lines that touch merge,
+        // block comment spans that overlap an earlier one are skipped
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        for (_, cursor) in &cursors {
+            let range = cursor
+                .selection_range()
+                .filter(|range| !range.is_empty())
+                .unwrap_or(cursor.position..cursor.position);
+            let span = if whole_lines || range.is_empty() {
+                let last = if range.end > range.start
+                    && line_start(state, range.end, estimated_line_length) == range.end
+                {
+                    range.end - 1
+                } else {
+                    range.end
+                };
+                (
+                    line_start(state, range.start, estimated_line_length),
+                    line_end(state, last, estimated_line_length),
+                )
+            } else {
+                (range.start, range.end)
+            };
+            match spans.last_mut() {
+                Some(previous) if whole_lines && span.0 <= previous.1 + 1 => {
+                    previous.1 = previous.1.max(span.1);
+                }
+                Some(previous) if span.0 < previous.1 => {}
+                _ => spans.push(span),
+            }
+        }
+
+        let mut edits: Vec<CommentEdit> = Vec::new();
+        let mut commented = true;
+        let mut line_count = 0;
+        for (start, end) in spans {
+            let text = state.get_text_range(start, end);
+            let toggle = match &markers {
+                Markers::Block(open, close) => {
+                    comment_toggle::toggle_block_comment(&text, open, close)
+                }
+                Markers::Line(prefix) => {
+                    line_count += text.split('\n').count();
+                    comment_toggle::toggle_line_comments(&text, prefix)
+                }
+            };
+            commented &= toggle.commented;
+            edits.extend(toggle.edits.into_iter().map(|edit| CommentEdit {
+                offset: start + edit.offset,
+                ..edit
+            }));
+        }
+        if edits.is_empty() {
+            return;
+        }
+
+        let mut events = Vec::new();
+        for edit in &edits {
+            if edit.delete > 0 {
+                let range = edit.offset..edit.offset + edit.delete;
+                events.push(Event::Delete {
+                    deleted_text: state.get_text_range(range.start, range.end),
+                    range,
+                    cursor_id: CursorId::UNDO_SENTINEL,
+                });
+            }
+            if !edit.insert.is_empty() {
+                events.push(Event::Insert {
+                    position: edit.offset,
+                    text: edit.insert.clone(),
+                    cursor_id: CursorId::UNDO_SENTINEL,
+                });
+            }
+        }
+        // Selections keep covering the text they covered, comment markers
+        // included, so toggling again undoes the change
+        for (cursor_id, cursor) in &cursors {
+            let (new_position, new_anchor) = match cursor.anchor {
+                Some(anchor) => {
+                    let forward = anchor <= cursor.position;
+                    (
+                        comment_toggle::map_position(&edits, cursor.position, forward),
+                        Some(comment_toggle::map_position(&edits, anchor, !forward)),
+                    )
+                }
+                None => (
+                    comment_toggle::map_position(&edits, cursor.position, true),
+                    None,
+                ),
+            };
+            events.push(Event::MoveCursor {
+                cursor_id: *cursor_id,
+                old_position: cursor.position,
+                new_position,
+                old_anchor: cursor.anchor,
+                new_anchor,
+                old_sticky_column: cursor.sticky_column,
+                new_sticky_column: 0,
+            });
+        }
+
+        let description = if commented {
+            "Comment lines"
+        } else {
+            "Uncomment lines"
+        };
+        if let Some(bulk_edit) = self.apply_events_as_bulk_edit(events, description.to_string()) {
+            self.active_event_log_mut().append(bulk_edit);
+        }
+
+        let message = match (&markers, commented) {
+            (Markers::Block(..), true) => t!("comment.block_added"),
+            (Markers::Block(..), false) => t!("comment.block_removed"),
+            (Markers::Line(_), true) => {
+                t!(
+                    "lines.action",
+                    action = t!("lines.comment"),
+                    count = line_count
+                )
+            }
+            (Markers::Line(_), false) => {
+                t!(
+                    "lines.action",
+                    action = t!("lines.uncomment"),
+                    count = line_count
+                )
+            }
+        };
+        self.set_status_message(message.to_string());
+    }
+}
+
+/// The comment markers a toggle uses
+enum Markers {
+    /// Line comment prefix
+    Line(String),
+    /// Block comment start and end
+    Block(String, String),
+}
+
+/// Start of the line containing `pos`
+fn line_start(
+    state: &mut crate::state::EditorState,
+    pos: usize,
+    estimated_line_length: usize,
+) -> usize {
+    state
+        .buffer
+        .line_iterator(pos, estimated_line_length)
+        .current_position()
+}
+
+/// End of the line containing `pos`, before its line ending
+fn line_end(
+    state: &mut crate::state::EditorState,
+    pos: usize,
+    estimated_line_length: usize,
+) -> usize {
+    let mut iter = state.buffer.line_iterator(pos, estimated_line_length);
+    let start = iter.current_position();
+    match iter.next_line() {
+        Some((_, content)) => start + content.trim_end_matches(['\n', '\r']).len(),
+        None => start,
+    }
+}
@@ -0,0 +1,96 @@
+//! Config problems panel.
+//!
+//! Lists the problems found when validating the config files and opens the
+//! offending file at the reported line when an entry is confirmed.
+
+use super::Editor;
+use crate::config_io::ConfigResolver;
+use crate::model::event::{PopupContentData, PopupData, PopupListItemData, PopupPositionData};
+use rust_i18n::t;
+
+/// Prefix of the popup item data used by the config problems panel
+///
+/// Items carry `config-issue:<index>` (or `config-issue:log` for the warning
+/// log entry), indexing into the config warning domain.
+pub(super) const CONFIG_ISSUE_DATA_PREFIX: &str = "config-issue:";
+
+impl Editor {
+    /// Validate the config files and update the config warning domain.
+    pub(super) fn refresh_config_issues(&mut self) {
+        let resolver = ConfigResolver::new(self.dir_context.clone(), self.working_dir.clone());
+        self.warning_domains
+            .config
+            .set_issues(resolver.validate_layers());
+    }
+
+    /// Show the config problems panel.
+    pub(super) fn show_config_issues_panel(&mut self) {
+        let issues = &self.warning_domains.config.issues;
+        let title = t!("config_issue.title", count = issues.len()).to_string();
+        let mut items: Vec<PopupListItemData> = issues
+            .iter()
+            .enumerate()
+            .map(|(index, issue)| {
+                let file_name = issue
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                PopupListItemData {
+                    text: format!("{}:{}:{}", file_name, issue.line, issue.column),
+                    detail: Some(issue.message()),
+                    icon: None,
+                    data: Some(format!("{}{}", CONFIG_ISSUE_DATA_PREFIX, index)),
+                }
+            })
+            .collect();
+
+        if self.warning_domains.general.log_path.is_some() {
+            items.push(PopupListItemData {
+                text: t!("warning.view_log").to_string(),
+                detail: None,
+                icon: None,
+                data: Some(format!("{}log", CONFIG_ISSUE_DATA_PREFIX)),
+            });
+        }
+
+        let max_height = (items.len() as u16 + 2).min(20);
+        self.show_popup(PopupData {
+            title: Some(title),
+            description: Some(t!("config_issue.open_at_error").to_string()),
+            transient: false,
+            content: PopupContentData::List { items, selected: 0 },
+            position: PopupPositionData::Centered,
+            width: 80,
+            max_height,
+            bordered: true,
+        });
+    }
+
+    /// Handle a confirmed config problems panel item (data without the prefix).
+    pub(super) fn handle_config_issue_confirm(&mut self, data: &str) {
+        if data == "log" {
+            self.open_warning_log();
+            return;
+        }
+
+        let Some(issue) = data
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| self.warning_domains.config.issues.get(index))
+            .cloned()
+        else {
+            return;
+        };
+
+        // Config files are always local
+        match self.open_local_file(&issue.path) {
+            Ok(_) => self.goto_line_col(issue.line, Some(issue.column)),
+            Err(e) => {
+                self.set_status_message(
+                    t!("file.error_opening", error = e.to_string()).to_string(),
+                );
+            }
+        }
+    }
+}
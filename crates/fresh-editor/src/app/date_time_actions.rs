@@ -0,0 +1,68 @@
+//! **Insert Date/Time**: pick one of the locale-aware or ISO formats from
+//! [`crate::services::locale_format`], or type a strftime pattern, and insert
+//! the current local time at each cursor.
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::i18n;
+use crate::input::commands::Suggestion;
+use crate::services::locale_format::{self, DateStyle};
+use crate::view::prompt::{Prompt, PromptType};
+
+impl Editor {
+    /// Offer each format with a preview of the current time
+    pub(crate) fn start_insert_date_time_prompt(&mut self) {
+        let now = chrono::Local::now();
+        let locale = i18n::current_locale();
+        let suggestions = DateStyle::ALL
+            .iter()
+            .map(|style| Suggestion {
+                text: locale_format::format_date(&now, *style, &locale),
+                description: Some(style.label()),
+                value: Some(style.id().to_string()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+
+        self.prompt = Some(Prompt::with_suggestions(
+            t!("date_time.prompt").to_string(),
+            PromptType::InsertDateTime,
+            suggestions,
+        ));
+    }
+
+    /// Handle the Insert Date/Time prompt
+    ///
+    /// `input` is a format id when a suggestion was picked, otherwise a
+    /// strftime pattern. Empty input means the selected suggestion.
+    pub(crate) fn handle_insert_date_time(&mut self, input: &str, selected_index: Option<usize>) {
+        let now = chrono::Local::now();
+        let locale = i18n::current_locale();
+        let input = input.trim();
+        let style = if input.is_empty() {
+            selected_index.and_then(|i| DateStyle::ALL.get(i).copied())
+        } else {
+            DateStyle::from_id(input)
+        };
+        let text = match style {
+            Some(style) => locale_format::format_date(&now, style, &locale),
+            None => match locale_format::format_pattern(&now, input, &locale) {
+                Some(text) if !text.is_empty() => text,
+                _ => {
+                    self.set_status_message(
+                        t!("date_time.invalid_format", format = input).to_string(),
+                    );
+                    return;
+                }
+            },
+        };
+        if self.is_editing_disabled() {
+            self.set_status_message(self.editing_disabled_message());
+            return;
+        }
+        self.paste_text(text);
+    }
+}
@@ -0,0 +1,62 @@
+//! Digraph input: `insert_digraph` followed by a two-character code such as
+//! `e:` inserts the character it names (ë), as Vim's Ctrl+K does.
+
+use super::Editor;
+use crate::input::keybindings::Action;
+use crate::primitives::digraphs;
+use anyhow::Result as AnyhowResult;
+use crossterm::event::{KeyCode, KeyModifiers};
+use rust_i18n::t;
+
+impl Editor {
+    /// Wait for the two characters of a digraph
+    pub(crate) fn start_digraph(&mut self) {
+        self.pending_digraph = Some(None);
+        self.set_status_message(t!("digraph.prompt").to_string());
+    }
+
+    /// Handle a key while a digraph is being typed. Returns whether the key
+    /// was consumed; any key other than a plain character ends the digraph
+    /// (Escape cancels it) and is handled as usual.
+    pub(crate) fn handle_digraph_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> AnyhowResult<bool> {
+        let Some(first) = self.pending_digraph.take() else {
+            return Ok(false);
+        };
+        let c = match code {
+            KeyCode::Char(c) if modifiers.difference(KeyModifiers::SHIFT).is_empty() => c,
+            KeyCode::Esc => {
+                self.clear_status_message();
+                return Ok(true);
+            }
+            _ => {
+                self.clear_status_message();
+                return Ok(false);
+            }
+        };
+
+        let Some(first) = first else {
+            self.pending_digraph = Some(Some(c));
+            self.set_status_message(t!("digraph.first", first = c).to_string());
+            return Ok(true);
+        };
+
+        // Like Vim, an unknown digraph inserts its second character
+        let inserted = match digraphs::lookup(first, c) {
+            Some(digraph) => {
+                self.clear_status_message();
+                digraph
+            }
+            None => {
+                let digraph = format!("{first}{c}");
+                self.set_status_message(t!("digraph.unknown", digraph = digraph).to_string());
+                c
+            }
+        };
+        self.handle_action(Action::InsertChar(inserted))?;
+        Ok(true)
+    }
+}
@@ -54,6 +54,7 @@ impl Editor {
 
     pub fn toggle_file_explorer(&mut self) {
         self.file_explorer_visible = !self.file_explorer_visible;
+        self.start_file_explorer_animation(self.file_explorer_visible);
 
         if self.file_explorer_visible {
             if self.file_explorer.is_none() {
@@ -87,9 +88,18 @@ impl Editor {
         if let Some(metadata) = self.buffer_metadata.get(&self.active_buffer()) {
             if let Some(file_path) = metadata.file_path() {
                 let target_path = file_path.clone();
-                let working_dir = self.working_dir.clone();
-
-                if target_path.starts_with(&working_dir) {
+                // Remote explorers are rooted at the remote home, not the working dir
+                let root_path = self
+                    .file_explorer
+                    .as_ref()
+                    .map(|explorer| explorer.tree().root_path().to_path_buf())
+                    .unwrap_or_else(|| self.working_dir.clone());
+                let same_root = self
+                    .buffer_filesystem(self.active_buffer())
+                    .remote_connection_info()
+                    == self.explorer_filesystem().remote_connection_info();
+
+                if same_root && target_path.starts_with(&root_path) {
                     if let Some(mut view) = self.file_explorer.take() {
                         tracing::trace!(
                             "sync_file_explorer_to_active_file: taking file_explorer for async expand to {:?}",
@@ -138,8 +148,9 @@ impl Editor {
 
     pub(crate) fn init_file_explorer(&mut self) {
         // Use remote home directory if in remote mode, otherwise local working directory
-        let root_path = if self.filesystem.remote_connection_info().is_some() {
-            match self.filesystem.home_dir() {
+        let filesystem = self.explorer_filesystem();
+        let root_path = if filesystem.remote_connection_info().is_some() {
+            match filesystem.home_dir() {
                 Ok(home) => home,
                 Err(e) => {
                     tracing::error!("Failed to get remote home directory: {}", e);
@@ -152,7 +163,11 @@ impl Editor {
         };
 
         if let (Some(runtime), Some(bridge)) = (&self.tokio_runtime, &self.async_bridge) {
-            let fs_manager = Arc::clone(&self.fs_manager);
+            let fs_manager = if self.explorer_root == 0 {
+                Arc::clone(&self.fs_manager)
+            } else {
+                Arc::new(FsManager::new(filesystem))
+            };
             let sender = bridge.sender();
 
             runtime.spawn(async move {
@@ -176,6 +191,36 @@ impl Editor {
         }
     }
 
+    /// Open a file from the filesystem currently shown in the explorer
+    fn open_explorer_file(&mut self, path: &Path) -> AnyhowResult<BufferId> {
+        if self.explorer_root == 0 {
+            self.open_file(path)
+        } else {
+            let filesystem = self.explorer_filesystem();
+            self.open_file_with_filesystem(path, filesystem)
+        }
+    }
+
+    /// Switch the file explorer to the next mounted root (local or remote host)
+    pub fn file_explorer_next_root(&mut self) {
+        let roots = self.filesystem_roots();
+        if roots.len() < 2 {
+            self.set_status_message(t!("explorer.single_root").to_string());
+            return;
+        }
+
+        self.explorer_root = (self.explorer_root + 1) % roots.len();
+        self.file_explorer = None;
+        self.dir_mod_times.clear();
+        self.init_file_explorer();
+
+        let root = roots[self.explorer_root]
+            .remote_connection_info()
+            .map(|info| info.to_string())
+            .unwrap_or_else(|| t!("explorer.local_root").to_string());
+        self.set_status_message(t!("explorer.root_switched", root = root).to_string());
+    }
+
     pub fn file_explorer_navigate_up(&mut self) {
         if let Some(explorer) = &mut self.file_explorer {
             explorer.select_prev_match();
@@ -347,9 +392,14 @@ impl Editor {
             if is_dir {
                 self.file_explorer_toggle_expand();
             } else {
+                if self.explorer_root == 0 && self.should_ask_open_symlink(&path) {
+                    self.start_open_symlink_prompt(path);
+                    return Ok(());
+                }
                 tracing::info!("[SYNTAX DEBUG] file_explorer opening file: {:?}", path);
-                match self.open_file(&path) {
-                    Ok(_) => {
+                match self.open_explorer_file(&path) {
+                    Ok(buffer_id) => {
+                        self.keep_preview_tab(buffer_id);
                         self.set_status_message(
                             t!("explorer.opened_file", name = &name).to_string(),
                         );
@@ -418,6 +468,7 @@ impl Editor {
     }
 
     pub fn file_explorer_new_file(&mut self) {
+        let filesystem = self.explorer_filesystem();
         if let Some(explorer) = &mut self.file_explorer {
             if let Some(selected_id) = explorer.get_selected() {
                 let node = explorer.tree().get_node(selected_id);
@@ -428,7 +479,7 @@ impl Editor {
 
                     if let Some(runtime) = &self.tokio_runtime {
                         let path_clone = file_path.clone();
-                        let result = self.filesystem.create_file(&path_clone).map(|_| ());
+                        let result = filesystem.create_file(&path_clone).map(|_| ());
 
                         match result {
                             Ok(_) => {
@@ -441,7 +492,7 @@ impl Editor {
                                 );
 
                                 // Open the file in the buffer
-                                let _ = self.open_file(&path_clone);
+                                let _ = self.open_explorer_file(&path_clone);
 
                                 // Enter rename mode for the new file with empty prompt
                                 // so user can type the desired filename from scratch
@@ -469,6 +520,7 @@ impl Editor {
     }
 
     pub fn file_explorer_new_directory(&mut self) {
+        let filesystem = self.explorer_filesystem();
         if let Some(explorer) = &mut self.file_explorer {
             if let Some(selected_id) = explorer.get_selected() {
                 let node = explorer.tree().get_node(selected_id);
@@ -480,7 +532,7 @@ impl Editor {
                     if let Some(runtime) = &self.tokio_runtime {
                         let path_clone = dir_path.clone();
                         let dirname_clone = dirname.clone();
-                        let result = self.filesystem.create_dir(&path_clone);
+                        let result = filesystem.create_dir(&path_clone);
 
                         match result {
                             Ok(_) => {
@@ -545,22 +597,16 @@ impl Editor {
     /// Perform the actual file explorer delete operation (called after prompt confirmation)
     /// For local files: moves to system trash/recycle bin
     /// For remote files: moves to ~/.local/share/fresh/trash/ on remote
+    /// The last delete can be undone with `file_explorer_undo_delete`.
     pub fn perform_file_explorer_delete(&mut self, path: std::path::PathBuf, _is_dir: bool) {
         let name = path
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_default();
 
-        // For remote files, move to remote trash directory
-        // For local files, use system trash
-        let delete_result = if self.filesystem.remote_connection_info().is_some() {
-            self.move_to_remote_trash(&path)
-        } else {
-            trash::delete(&path).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
-        };
-
-        match delete_result {
-            Ok(_) => {
+        let filesystem = self.explorer_filesystem();
+        match filesystem.move_to_trash(&path) {
+            Ok(trashed) => {
                 // Refresh the parent directory in the file explorer
                 if let Some(explorer) = &mut self.file_explorer {
                     if let Some(runtime) = &self.tokio_runtime {
@@ -591,7 +637,17 @@ impl Editor {
                         }
                     }
                 }
-                self.set_status_message(t!("explorer.moved_to_trash", name = &name).to_string());
+                if trashed.can_restore() {
+                    self.set_status_message(
+                        t!("explorer.moved_to_trash_undo", name = &name).to_string(),
+                    );
+                    self.last_trashed = Some((filesystem, trashed));
+                } else {
+                    self.set_status_message(
+                        t!("explorer.moved_to_trash", name = &name).to_string(),
+                    );
+                    self.last_trashed = None;
+                }
 
                 // Ensure focus remains on file explorer
                 self.key_context = KeyContext::FileExplorer;
@@ -604,30 +660,43 @@ impl Editor {
         }
     }
 
-    /// Move a file/directory to the remote trash directory (~/.local/share/fresh/trash/)
-    fn move_to_remote_trash(&self, path: &std::path::Path) -> std::io::Result<()> {
-        // Get remote home directory
-        let home = self.filesystem.home_dir()?;
-        let trash_dir = home.join(".local/share/fresh/trash");
+    /// Restore what the last file explorer delete moved to the trash
+    pub fn file_explorer_undo_delete(&mut self) {
+        let Some((filesystem, trashed)) = self.last_trashed.take() else {
+            self.set_status_message(t!("explorer.nothing_to_restore").to_string());
+            return;
+        };
+        let name = trashed
+            .original
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
 
-        // Create trash directory if it doesn't exist
-        if !self.filesystem.exists(&trash_dir) {
-            self.filesystem.create_dir_all(&trash_dir)?;
+        if let Err(e) = filesystem.restore_from_trash(&trashed) {
+            self.set_status_message(
+                t!(
+                    "explorer.error_restore",
+                    name = &name,
+                    error = e.to_string()
+                )
+                .to_string(),
+            );
+            return;
         }
 
-        // Generate unique name with timestamp to avoid collisions
-        let file_name = path
-            .file_name()
-            .unwrap_or_else(|| std::ffi::OsStr::new("unnamed"));
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
-        let trash_name = format!("{}.{}", file_name.to_string_lossy(), timestamp);
-        let trash_path = trash_dir.join(trash_name);
-
-        // Move to trash
-        self.filesystem.rename(path, &trash_path)
+        // Show the restored entry in its parent directory
+        if let (Some(explorer), Some(runtime)) = (&mut self.file_explorer, &self.tokio_runtime) {
+            if let Some(parent) = trashed.original.parent() {
+                if let Some(node) = explorer.tree().get_node_by_path(parent) {
+                    let parent_id = node.id;
+                    let _ = runtime.block_on(explorer.tree_mut().refresh_node(parent_id));
+                }
+            }
+            if let Some(node) = explorer.tree().get_node_by_path(&trashed.original) {
+                explorer.set_selected(Some(node.id));
+            }
+        }
+        self.set_status_message(t!("explorer.restored_from_trash", name = &name).to_string());
     }
 
     pub fn file_explorer_rename(&mut self) {
@@ -679,7 +748,7 @@ impl Editor {
             .unwrap_or_else(|| original_path.clone());
 
         if let Some(runtime) = &self.tokio_runtime {
-            let result = self.filesystem.rename(&original_path, &new_path);
+            let result = self.explorer_filesystem().rename(&original_path, &new_path);
 
             match result {
                 Ok(_) => {
@@ -314,6 +314,9 @@ impl Editor {
 
         // Open the file with auto-detected encoding
         tracing::info!("[SYNTAX DEBUG] file_open_dialog opening file: {:?}", path);
+        if self.start_background_open(&path) {
+            return;
+        }
         if let Err(e) = self.open_file(&path) {
             // Check if this is a large file encoding confirmation error
             if let Some(confirmation) =
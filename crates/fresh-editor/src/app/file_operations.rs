@@ -21,6 +21,9 @@ use crate::state::EditorState;
 
 use super::{BufferMetadata, Editor};
 
+/// How long a save waits for `will_save` plugin handlers before writing anyway
+const WILL_SAVE_TIMEOUT_MS: u64 = 1000;
+
 impl Editor {
     /// Save the active buffer
     pub fn save(&mut self) -> anyhow::Result<()> {
@@ -30,28 +33,146 @@ impl Editor {
             .file_path()
             .map(|p| p.to_path_buf());
 
+        if let Some(ref p) = path {
+            self.run_will_save_hook(self.active_buffer(), p);
+            if self.start_background_save(p)? {
+                return Ok(());
+            }
+        }
+
         match self.active_state_mut().buffer.save() {
             Ok(()) => self.finalize_save(path),
-            Err(e) => {
-                if let Some(sudo_info) = e.downcast_ref::<SudoSaveRequired>() {
-                    let info = sudo_info.clone();
-                    self.start_prompt(
-                        t!("prompt.sudo_save_confirm").to_string(),
-                        PromptType::ConfirmSudoSave { info },
-                    );
-                    Ok(())
-                } else {
-                    Err(e)
-                }
+            Err(e) => self.handle_save_error(self.active_buffer(), e),
+        }
+    }
+
+    /// Save `buffer_id` without switching to it
+    ///
+    /// The active buffer goes through [`Self::save`]; other buffers are written
+    /// directly, and their on-save actions are skipped.
+    pub(crate) fn save_buffer(&mut self, buffer_id: BufferId) -> anyhow::Result<()> {
+        if buffer_id == self.active_buffer() {
+            return self.save();
+        }
+        let Some(path) = self
+            .buffers
+            .get(&buffer_id)
+            .and_then(|state| state.buffer.file_path())
+            .map(|p| p.to_path_buf())
+        else {
+            return Ok(());
+        };
+
+        self.run_will_save_hook(buffer_id, &path);
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return Ok(());
+        };
+        match state.buffer.save() {
+            Ok(()) => self.finalize_buffer_save(buffer_id, Some(path), true),
+            Err(e) => self.handle_save_error(buffer_id, e),
+        }
+    }
+
+    /// Handle a failed save of `buffer_id`
+    ///
+    /// Saves that need sudo or a remote connection that is down are picked up
+    /// again; other errors are returned.
+    pub(crate) fn handle_save_error(
+        &mut self,
+        buffer_id: BufferId,
+        e: anyhow::Error,
+    ) -> anyhow::Result<()> {
+        if let Some(sudo_info) = e.downcast_ref::<SudoSaveRequired>() {
+            let info = sudo_info.clone();
+            let remote = self
+                .buffer_filesystem(buffer_id)
+                .remote_connection_info()
+                .is_some();
+            self.start_prompt(
+                t!(
+                    "prompt.sudo_save_confirm",
+                    program = super::sudo_save::elevation_program(remote)
+                )
+                .to_string(),
+                PromptType::ConfirmSudoSave { buffer_id, info },
+            );
+            Ok(())
+        } else if is_disconnected_error(&e) {
+            // Remote link is down: keep the edits and retry after reconnect
+            if !self.queued_remote_saves.contains(&buffer_id) {
+                self.queued_remote_saves.push(buffer_id);
             }
+            self.status_message = Some(
+                t!(
+                    "status.remote_save_queued",
+                    count = self.queued_remote_saves.len()
+                )
+                .to_string(),
+            );
+            Ok(())
+        } else {
+            Err(e)
         }
     }
 
+    /// Let `will_save` handlers edit the buffer before it is written.
+    ///
+    /// The hook runs synchronously (bounded by a timeout) and any commands the
+    /// handlers queued are applied immediately, so edits made by the handlers
+    /// end up in the saved file.
+    pub(super) fn run_will_save_hook(&mut self, buffer_id: BufferId, path: &Path) {
+        if !self.plugin_manager.has_hook_handlers("will_save") {
+            return;
+        }
+        #[cfg(feature = "plugins")]
+        self.update_plugin_state_snapshot();
+        let finished = self.plugin_manager.run_hook_blocking(
+            "will_save",
+            crate::services::plugins::hooks::HookArgs::BeforeFileSave {
+                buffer_id,
+                path: path.to_path_buf(),
+            },
+            std::time::Duration::from_millis(WILL_SAVE_TIMEOUT_MS),
+        );
+        if !finished {
+            tracing::warn!("will_save handlers timed out for {:?}", path);
+        }
+        self.process_plugin_commands();
+    }
+
+    /// Fire the post-save hooks for plugins
+    pub(crate) fn run_did_save_hooks(&mut self, buffer_id: BufferId, path: PathBuf) {
+        self.plugin_manager.run_hook(
+            "after_file_save",
+            crate::services::plugins::hooks::HookArgs::AfterFileSave {
+                buffer_id,
+                path: path.clone(),
+            },
+        );
+        self.plugin_manager.run_hook(
+            "did_save",
+            crate::services::plugins::hooks::HookArgs::AfterFileSave { buffer_id, path },
+        );
+    }
+
     /// Internal helper to finalize save state (mark as saved, notify LSP, etc.)
     pub(crate) fn finalize_save(&mut self, path: Option<PathBuf>) -> anyhow::Result<()> {
+        self.finalize_buffer_save(self.active_buffer(), path, true)
+    }
+
+    /// Update editor state after `buffer_id` was written to `path`
+    ///
+    /// On-save actions such as formatters work on the active buffer, so they
+    /// only run if `buffer_id` is active and `run_on_save_actions` is set.
+    /// Auto-saves leave it unset, which also keeps the status bar as it is.
+    pub(crate) fn finalize_buffer_save(
+        &mut self,
+        buffer_id: BufferId,
+        path: Option<PathBuf>,
+        run_on_save_actions: bool,
+    ) -> anyhow::Result<()> {
         // Auto-detect language if it's currently "text" and we have a path
         if let Some(ref p) = path {
-            let buffer_id = self.active_buffer();
             if let Some(state) = self.buffers.get_mut(&buffer_id) {
                 if state.language == "text" {
                     if let Some(filename) = p.file_name().and_then(|n| n.to_str()) {
@@ -61,25 +182,30 @@ impl Editor {
             }
         }
 
-        self.status_message = Some(t!("status.file_saved").to_string());
+        if run_on_save_actions {
+            self.status_message = Some(t!("status.file_saved").to_string());
+        }
 
         // Mark the event log position as saved (for undo modified tracking)
-        self.active_event_log_mut().mark_saved();
+        if let Some(event_log) = self.event_logs.get_mut(&buffer_id) {
+            event_log.mark_saved();
+        }
 
         // Update file modification time after save
         if let Some(ref p) = path {
-            if let Ok(metadata) = self.filesystem.metadata(p) {
+            if let Ok(metadata) = self.buffer_filesystem(buffer_id).metadata(p) {
                 if let Some(mtime) = metadata.modified {
                     self.file_mod_times.insert(p.clone(), mtime);
                 }
             }
+            self.update_workspace_index(p);
         }
 
         // Notify LSP of save
-        self.notify_lsp_save();
+        self.notify_lsp_save(buffer_id);
 
         // Delete recovery file (buffer is now saved)
-        let _ = self.delete_buffer_recovery(self.active_buffer());
+        let _ = self.delete_buffer_recovery(buffer_id);
 
         // Emit control event
         if let Some(ref p) = path {
@@ -91,37 +217,40 @@ impl Editor {
             );
         }
 
-        // Fire AfterFileSave hook for plugins
+        // Fire after_file_save / did_save hooks for plugins
         if let Some(ref p) = path {
-            let buffer_id = self.active_buffer();
-            self.plugin_manager.run_hook(
-                "after_file_save",
-                crate::services::plugins::hooks::HookArgs::AfterFileSave {
-                    buffer_id,
-                    path: p.clone(),
-                },
-            );
+            self.run_did_save_hooks(buffer_id, p.clone());
         }
 
         // Run on-save actions (formatters, linters, etc.)
-        match self.run_on_save_actions() {
-            Ok(true) => {
-                // Actions ran successfully - if status_message was set by run_on_save_actions
-                // (e.g., for missing optional formatters), keep it. Otherwise update status.
-                if self.status_message.as_deref() == Some(&t!("status.file_saved")) {
-                    self.status_message = Some(t!("status.file_saved_with_actions").to_string());
+        if run_on_save_actions && buffer_id == self.active_buffer() {
+            match self.run_on_save_actions() {
+                Ok(true) => {
+                    // Actions ran successfully - if status_message was set by run_on_save_actions
+                    // (e.g., for missing optional formatters), keep it. Otherwise update status.
+                    if self.status_message.as_deref() == Some(&t!("status.file_saved")) {
+                        self.status_message =
+                            Some(t!("status.file_saved_with_actions").to_string());
+                    }
+                    // else: keep the message set by run_on_save_actions (e.g., missing formatter)
+                }
+                Ok(false) => {
+                    // No actions configured, keep original status
+                }
+                Err(e) => {
+                    // Action failed, show error but don't fail the save
+                    self.status_message = Some(e);
                 }
-                // else: keep the message set by run_on_save_actions (e.g., missing formatter)
-            }
-            Ok(false) => {
-                // No actions configured, keep original status
-            }
-            Err(e) => {
-                // Action failed, show error but don't fail the save
-                self.status_message = Some(e);
             }
         }
 
+        // Keep what was written, after formatting, in the local history
+        if let Some(ref p) = path {
+            self.record_local_history(buffer_id, p);
+        }
+
+        self.on_save_watch_task();
+
         Ok(())
     }
 
@@ -136,7 +265,7 @@ impl Editor {
             }
         };
 
-        if !path.exists() {
+        if !self.buffer_filesystem(self.active_buffer()).exists(&path) {
             self.status_message =
                 Some(t!("status.file_not_exists", path = path.display().to_string()).to_string());
             return Ok(false);
@@ -159,7 +288,7 @@ impl Editor {
             self.config.editor.large_file_threshold_bytes as usize,
             &self.grammar_registry,
             &self.config.languages,
-            std::sync::Arc::clone(&self.filesystem),
+            self.buffer_filesystem(self.active_buffer()),
         )?;
 
         // Restore cursor positions (clamped to valid range for new file size)
@@ -175,6 +304,8 @@ impl Editor {
         // Replace the current buffer with the new state
         let buffer_id = self.active_buffer();
         if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            // Read-only survives reloading the content
+            new_state.editing_disabled = state.editing_disabled;
             *state = new_state;
             // Note: line_wrap_enabled is now in SplitViewState.viewport
         }
@@ -195,11 +326,12 @@ impl Editor {
         self.seen_byte_ranges.remove(&buffer_id);
 
         // Update the file modification time
-        if let Ok(metadata) = self.filesystem.metadata(&path) {
+        if let Ok(metadata) = self.buffer_filesystem(buffer_id).metadata(&path) {
             if let Some(mtime) = metadata.modified {
                 self.file_mod_times.insert(path.clone(), mtime);
             }
         }
+        self.update_workspace_index(&path);
 
         // Notify LSP that the file was changed
         self.notify_lsp_file_changed(&path);
@@ -254,7 +386,7 @@ impl Editor {
 
         for path in files_to_check {
             // Get current mtime
-            let current_mtime = match self.filesystem.metadata(&path) {
+            let current_mtime = match self.filesystem_for_path(&path).metadata(&path) {
                 Ok(meta) => match meta.modified {
                     Some(mtime) => mtime,
                     None => continue,
@@ -314,7 +446,7 @@ impl Editor {
 
         for (node_id, path) in expanded_dirs {
             // Get current mtime
-            let current_mtime = match self.filesystem.metadata(&path) {
+            let current_mtime = match self.explorer_filesystem().metadata(&path) {
                 Ok(meta) => match meta.modified {
                     Some(mtime) => mtime,
                     None => continue,
@@ -341,6 +473,11 @@ impl Editor {
             return false;
         }
 
+        // Files were added or removed, which the workspace index finds by walking
+        if let Some(index) = &self.workspace_index {
+            index.rescan();
+        }
+
         // Refresh each changed directory
         if let (Some(runtime), Some(explorer)) = (&self.tokio_runtime, &mut self.file_explorer) {
             for node_id in dirs_to_refresh {
@@ -354,6 +491,37 @@ impl Editor {
         true
     }
 
+    /// Refresh the cached git branch shown by the `git_branch` status segment
+    ///
+    /// Only reads the repository while a `git_branch` segment is configured,
+    /// at the auto-revert poll interval. Returns true if the branch changed.
+    pub fn poll_git_branch(&mut self) -> bool {
+        if !self
+            .config
+            .status_line
+            .segments()
+            .any(|segment| segment.split('(').next().map(str::trim) == Some("git_branch"))
+        {
+            return false;
+        }
+
+        let poll_interval =
+            std::time::Duration::from_millis(self.config.editor.auto_revert_poll_interval_ms);
+        if let Some(last_poll) = self.last_git_branch_poll {
+            if self.time_source.elapsed_since(last_poll) < poll_interval {
+                return false;
+            }
+        }
+        self.last_git_branch_poll = Some(self.time_source.now());
+
+        let branch = crate::services::git::current_branch(&*self.filesystem, &self.working_dir);
+        if branch == self.git_branch {
+            return false;
+        }
+        self.git_branch = branch;
+        true
+    }
+
     /// Notify LSP server about a newly opened file
     /// Handles language detection, spawning LSP clients, and sending didOpen notifications
     pub(crate) fn notify_lsp_file_opened(
@@ -437,6 +605,22 @@ impl Editor {
 
         match lsp.try_spawn(&language) {
             LspSpawnResult::Spawned => {
+                // Additional servers of the language only get didOpen; pull
+                // diagnostics and inlay hints come from the primary server
+                for server_id in lsp.server_ids(&language).into_iter().skip(1) {
+                    if lsp.try_spawn(&server_id) != LspSpawnResult::Spawned {
+                        continue;
+                    }
+                    if let Some(client) = lsp.get_handle_mut(&server_id) {
+                        if let Err(e) = client.did_open(uri.clone(), text.clone(), language.clone())
+                        {
+                            tracing::warn!("Failed to send didOpen to {}: {}", server_id, e);
+                        } else {
+                            metadata.lsp_opened_with.insert(client.id());
+                        }
+                    }
+                }
+
                 if let Some(client) = lsp.get_handle_mut(&language) {
                     // Send didOpen
                     tracing::info!("Sending didOpen to LSP for: {}", uri.as_str());
@@ -507,13 +691,20 @@ impl Editor {
     /// This is used by the polling-based auto-revert to detect external changes
     pub(crate) fn watch_file(&mut self, path: &Path) {
         // Record current modification time for polling
-        if let Ok(metadata) = self.filesystem.metadata(path) {
+        if let Ok(metadata) = self.filesystem_for_path(path).metadata(path) {
             if let Some(mtime) = metadata.modified {
                 self.file_mod_times.insert(path.to_path_buf(), mtime);
             }
         }
     }
 
+    /// Tell the workspace index that a file changed on disk
+    pub(crate) fn update_workspace_index(&self, path: &Path) {
+        if let Some(index) = &self.workspace_index {
+            index.update(vec![path.to_path_buf()]);
+        }
+    }
+
     /// Notify LSP that a file's contents changed (e.g., after revert)
     pub(crate) fn notify_lsp_file_changed(&mut self, path: &Path) {
         use crate::services::lsp::manager::LspSpawnResult;
@@ -553,58 +744,37 @@ impl Editor {
             return;
         }
 
-        // Get handle ID (handle should exist now since try_spawn succeeded)
-        let handle_id = {
-            let Some(lsp) = self.lsp.as_mut() else {
-                return;
-            };
-            let Some(handle) = lsp.get_handle_mut(&language) else {
-                return;
-            };
-            handle.id()
-        };
-
-        // Check if didOpen needs to be sent first
-        let needs_open = {
-            let Some(metadata) = self.buffer_metadata.get(&buffer_id) else {
-                return;
-            };
-            !metadata.lsp_opened_with.contains(&handle_id)
+        let server_ids = match self.lsp.as_ref() {
+            Some(lsp) => lsp.server_ids(&language),
+            None => return,
         };
-
-        if needs_open {
-            // Send didOpen first
-            if let Some(lsp) = self.lsp.as_mut() {
-                if let Some(handle) = lsp.get_handle_mut(&language) {
-                    if let Err(e) =
-                        handle.did_open(lsp_uri.clone(), content.clone(), language.clone())
-                    {
-                        tracing::warn!("Failed to send didOpen before didChange: {}", e);
-                        return;
-                    }
-                    tracing::debug!(
-                        "Sent didOpen for {} to LSP handle {} before file change notification",
-                        lsp_uri.as_str(),
-                        handle_id
-                    );
-                }
+        for server_id in server_ids {
+            let Some(lsp) = self.lsp.as_mut() else { return };
+            if lsp.try_spawn(&server_id) != LspSpawnResult::Spawned {
+                continue;
             }
 
-            // Mark as opened
-            if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
-                metadata.lsp_opened_with.insert(handle_id);
+            // Send didOpen first if this server hasn't seen the buffer yet
+            if self.ensure_lsp_did_open(buffer_id, &server_id).is_none() {
+                tracing::warn!(
+                    "Failed to send didOpen to {} before file change notification",
+                    server_id
+                );
+                continue;
             }
-        }
 
-        // Use full document sync - send the entire new content
-        if let Some(lsp) = &mut self.lsp {
-            if let Some(client) = lsp.get_handle_mut(&language) {
+            // Use full document sync - send the entire new content
+            if let Some(client) = self
+                .lsp
+                .as_mut()
+                .and_then(|lsp| lsp.get_handle_mut(&server_id))
+            {
                 let content_change = TextDocumentContentChangeEvent {
                     range: None, // None means full document replacement
                     range_length: None,
-                    text: content,
+                    text: content.clone(),
                 };
-                if let Err(e) = client.did_change(lsp_uri, vec![content_change]) {
+                if let Err(e) = client.did_change(lsp_uri.clone(), vec![content_change]) {
                     tracing::warn!("Failed to notify LSP of file change: {}", e);
                 }
             }
@@ -622,14 +792,14 @@ impl Editor {
         path: &Path,
     ) -> anyhow::Result<()> {
         // Load the file content fresh from disk
-        let new_state = EditorState::from_file_with_languages(
+        let mut new_state = EditorState::from_file_with_languages(
             path,
             self.terminal_width,
             self.terminal_height,
             self.config.editor.large_file_threshold_bytes as usize,
             &self.grammar_registry,
             &self.config.languages,
-            std::sync::Arc::clone(&self.filesystem),
+            self.buffer_filesystem(buffer_id),
         )?;
 
         // Get the new file size for clamping
@@ -644,6 +814,8 @@ impl Editor {
 
         // Replace the buffer content
         if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            // Read-only survives reloading the content
+            new_state.editing_disabled = state.editing_disabled;
             *state = new_state;
 
             // Restore cursor positions (clamped to valid range for new file size)
@@ -664,11 +836,12 @@ impl Editor {
         self.seen_byte_ranges.remove(&buffer_id);
 
         // Update the file modification time
-        if let Ok(metadata) = self.filesystem.metadata(path) {
+        if let Ok(metadata) = self.buffer_filesystem(buffer_id).metadata(path) {
             if let Some(mtime) = metadata.modified {
                 self.file_mod_times.insert(path.to_path_buf(), mtime);
             }
         }
+        self.update_workspace_index(path);
 
         // Notify LSP that the file was changed
         self.notify_lsp_file_changed(path);
@@ -780,10 +953,23 @@ impl Editor {
     /// Check if saving would overwrite changes made by another process
     /// Returns Some(current_mtime) if there's a conflict, None otherwise
     pub fn check_save_conflict(&self) -> Option<std::time::SystemTime> {
-        let path = self.active_state().buffer.file_path()?;
+        self.check_buffer_save_conflict(self.active_buffer())
+    }
+
+    /// Like [`Self::check_save_conflict`], for any buffer
+    pub(crate) fn check_buffer_save_conflict(
+        &self,
+        buffer_id: BufferId,
+    ) -> Option<std::time::SystemTime> {
+        let path = self.buffers.get(&buffer_id)?.buffer.file_path()?;
 
         // Get current file modification time
-        let current_mtime = match self.filesystem.metadata(path).ok().and_then(|m| m.modified) {
+        let current_mtime = match self
+            .buffer_filesystem(buffer_id)
+            .metadata(path)
+            .ok()
+            .and_then(|m| m.modified)
+        {
             Some(mtime) => mtime,
             None => return None, // File doesn't exist or can't read metadata
         };
@@ -797,4 +983,109 @@ impl Editor {
             _ => None,
         }
     }
+
+    /// Check if saving the active buffer would break a link to its file
+    ///
+    /// Returns the question to ask first: a dangling symlink would be replaced
+    /// by a regular file, and on a remote host a file with several hard links
+    /// would be split from its other names (local saves write those in place).
+    pub(crate) fn check_save_breaks_link(&self) -> Option<String> {
+        let buffer_id = self.active_buffer();
+        let path = self.buffers.get(&buffer_id)?.buffer.file_path()?;
+        let filesystem = self.buffer_filesystem(buffer_id);
+
+        if filesystem.remote_connection_info().is_some() {
+            let links = filesystem.metadata(path).ok()?.hard_links?;
+            return (links > 1)
+                .then(|| t!("file.save_breaks_hard_link_prompt", count = links).to_string());
+        }
+        let target = filesystem.read_link(path).ok()?;
+        (!filesystem.exists(path)).then(|| {
+            t!(
+                "file.save_breaks_symlink_prompt",
+                target = target.display().to_string()
+            )
+            .to_string()
+        })
+    }
+
+    /// Track remote connection drops/reconnects and flush queued saves.
+    ///
+    /// Saves attempted while the SSH link was down are retried one at a time
+    /// once it is back. If the remote file changed during the outage, the user
+    /// is asked before overwriting it. Returns true if a re-render is needed.
+    pub(crate) fn poll_remote_connection(&mut self) -> bool {
+        let remotes: Vec<_> = self
+            .filesystem_roots()
+            .into_iter()
+            .filter(|fs| fs.remote_connection_info().is_some())
+            .collect();
+        if remotes.is_empty() {
+            return false;
+        }
+
+        let connected = remotes.iter().all(|fs| fs.is_remote_connected());
+        let changed = connected != self.remote_connected;
+        self.remote_connected = connected;
+        if changed {
+            let message = if connected {
+                t!("status.remote_reconnected")
+            } else {
+                t!("status.remote_connection_lost")
+            };
+            self.status_message = Some(message.to_string());
+        }
+
+        if self.prompt.is_some() {
+            return changed;
+        }
+        // With several hosts mounted, one may come back before the others
+        let ready = self
+            .queued_remote_saves
+            .iter()
+            .position(|&id| self.buffer_filesystem(id).is_remote_connected());
+        if let Some(index) = ready {
+            self.flush_queued_remote_save(index);
+            return true;
+        }
+        changed
+    }
+
+    /// Retry a save that was queued while the remote link was down
+    fn flush_queued_remote_save(&mut self, index: usize) {
+        let buffer_id = self.queued_remote_saves.remove(index);
+        let still_modified = self
+            .buffers
+            .get(&buffer_id)
+            .is_some_and(|state| state.buffer.is_modified());
+        if !still_modified {
+            return;
+        }
+
+        // The user may be working in another buffer: save this one in place
+        if self.check_buffer_save_conflict(buffer_id).is_some() {
+            let name = self
+                .buffers
+                .get(&buffer_id)
+                .and_then(|state| state.buffer.file_path())
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            self.start_prompt(
+                t!("file.remote_changed_while_offline_prompt", name = name).to_string(),
+                PromptType::ConfirmSaveConflict { buffer_id },
+            );
+        } else if let Err(e) = self.save_buffer(buffer_id) {
+            self.status_message = Some(t!("file.save_failed", error = e.to_string()).to_string());
+        }
+    }
+}
+
+/// Whether a save error was caused by the remote connection being down
+fn is_disconnected_error(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|io| io.kind() == std::io::ErrorKind::NotConnected)
+    })
 }
@@ -0,0 +1,169 @@
+//! **Image previews**: PNG and JPEG files open as a preview instead of their
+//! bytes, and "Toggle Image Preview" switches any image buffer, SVGs
+//! included, between the preview and its text. See
+//! [`crate::view::image_preview`] for the layout and
+//! [`crate::view::graphics_protocol`] for drawing the image.
+
+use std::path::Path;
+
+use ratatui::layout::Rect;
+use rust_i18n::t;
+
+use super::Editor;
+use crate::model::event::{BufferId, SplitId};
+use crate::model::filesystem::FileSystem;
+use crate::view::graphics_protocol::GraphicsProtocol;
+use crate::view::image_preview::{ImageFormat, ImagePreview};
+
+impl Editor {
+    /// Set how images are drawn: the terminal's graphics protocol and its
+    /// cell size in pixels
+    pub fn set_graphics_protocol(&mut self, protocol: GraphicsProtocol, cell_size: (u16, u16)) {
+        self.graphics_protocol = protocol;
+        self.image_cell_size = cell_size;
+        let cell_size = self.preview_cell_size();
+        for preview in self.image_previews.values_mut() {
+            preview.cell_size = cell_size;
+        }
+        self.drawn_images.clear();
+    }
+
+    pub fn graphics_protocol(&self) -> GraphicsProtocol {
+        self.graphics_protocol
+    }
+
+    /// The cell size previews lay out images with, or `None` when images
+    /// cannot be shown
+    fn preview_cell_size(&self) -> Option<(u16, u16)> {
+        (self.graphics_protocol != GraphicsProtocol::None && !self.session_mode)
+            .then_some(self.image_cell_size)
+    }
+
+    /// Show a preview for `buffer_id` if `path` is an image.
+    /// Returns false for other files and images that fail to load.
+    pub(crate) fn open_image_preview(
+        &mut self,
+        buffer_id: BufferId,
+        path: &Path,
+        filesystem: &dyn FileSystem,
+    ) -> bool {
+        let Some(format) = ImageFormat::from_path(path) else {
+            return false;
+        };
+        let name = self
+            .buffer_metadata
+            .get(&buffer_id)
+            .map(|m| m.display_name.clone())
+            .unwrap_or_else(|| path.display().to_string());
+        let preview = filesystem
+            .read_file(path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| ImagePreview::load(name, format, data));
+        match preview {
+            Ok(mut preview) => {
+                preview.cell_size = self.preview_cell_size();
+                self.image_previews.insert(buffer_id, preview);
+                // The text is hidden, so it must not be edited blindly
+                if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                    state.editing_disabled = true;
+                }
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Cannot preview image {}: {}", path.display(), e);
+                false
+            }
+        }
+    }
+
+    /// Switch the active buffer between its image preview and its text
+    pub fn toggle_image_preview(&mut self) {
+        let buffer_id = self.active_buffer();
+        if self.image_previews.remove(&buffer_id).is_some() {
+            let read_only = self
+                .buffer_metadata
+                .get(&buffer_id)
+                .is_some_and(|m| m.read_only);
+            if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                state.editing_disabled = read_only;
+            }
+            self.set_status_message(t!("image_preview.hidden").to_string());
+            return;
+        }
+        let Some(path) = self
+            .buffer_metadata
+            .get(&buffer_id)
+            .and_then(|m| m.file_path())
+            .cloned()
+        else {
+            self.set_status_message(t!("image_preview.not_an_image").to_string());
+            return;
+        };
+        let filesystem = self.filesystem.clone();
+        if self.open_image_preview(buffer_id, &path, filesystem.as_ref()) {
+            self.set_status_message(t!("image_preview.shown").to_string());
+        } else {
+            self.set_status_message(t!("image_preview.not_an_image").to_string());
+        }
+    }
+
+    /// Whether `buffer_id` is shown as an image preview
+    pub fn has_image_preview(&self, buffer_id: BufferId) -> bool {
+        self.image_previews.contains_key(&buffer_id)
+    }
+
+    /// Queue the escape sequences that draw the images of the previews in
+    /// `split_areas`, if they moved since the last frame
+    pub(crate) fn update_image_placements(
+        &mut self,
+        split_areas: &[(SplitId, BufferId, Rect, Rect, usize, usize)],
+    ) {
+        if self.preview_cell_size().is_none() {
+            return;
+        }
+
+        let placements: Vec<(BufferId, Rect, u32, u32)> = split_areas
+            .iter()
+            .filter_map(|(_, buffer_id, content_rect, _, _, _)| {
+                let preview = self.image_previews.get(buffer_id)?;
+                let (area, width, height) = preview.placement(*content_rect)?;
+                Some((*buffer_id, area, width, height))
+            })
+            .collect();
+        let drawn: Vec<(BufferId, Rect)> = placements
+            .iter()
+            .map(|(buffer_id, area, _, _)| (*buffer_id, *area))
+            .collect();
+        if drawn == self.drawn_images {
+            return;
+        }
+
+        // Sixel pixels stay until their cells are redrawn; the images are
+        // drawn again after the full redraw
+        if self.graphics_protocol == GraphicsProtocol::Sixel && !self.drawn_images.is_empty() {
+            self.drawn_images.clear();
+            self.request_full_redraw();
+            return;
+        }
+
+        let protocol = self.graphics_protocol;
+        let mut sequences = protocol.clear_sequence();
+        for (buffer_id, area, width, height) in placements {
+            if let Some(pixels) = self
+                .image_previews
+                .get_mut(&buffer_id)
+                .and_then(|preview| preview.pixels(width, height))
+            {
+                sequences.extend(protocol.draw_sequence(pixels, width, height, area));
+            }
+        }
+        self.pending_image_sequences = sequences;
+        self.drawn_images = drawn;
+    }
+
+    /// Take the image escape sequences for the frame just rendered.
+    /// They are written after the frame, over the cells left blank for them.
+    pub fn take_image_sequences(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.pending_image_sequences)
+    }
+}
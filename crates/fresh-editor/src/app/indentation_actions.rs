@@ -0,0 +1,127 @@
+//! Indentation commands: **Convert Indentation to Spaces/Tabs** rewrite the
+//! active buffer's leading whitespace keeping each line's width, and
+//! **Reindent Buffer** changes the indent width. Lines inside block comments
+//! and multi-line strings are left alone.
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::primitives::indentation::{self, IndentStyle, Reindent};
+use crate::view::prompt::PromptType;
+
+impl Editor {
+    /// Rewrite the active buffer's indentation with tabs or spaces, one tab
+    /// per indent level, and indent with them from now on
+    pub(crate) fn convert_indentation(&mut self, to_tabs: bool) {
+        let state = self.active_state();
+        let width = state.indent_width();
+        let reindent = Reindent {
+            tab_size: state.tab_size,
+            from_width: width,
+            to: if to_tabs {
+                IndentStyle::Tabs
+            } else {
+                IndentStyle::Spaces(width)
+            },
+        };
+        let message = if to_tabs {
+            t!("indentation.converted_to_tabs")
+        } else {
+            t!("indentation.converted_to_spaces")
+        };
+        if !self.reindent_active_buffer(reindent, &message) {
+            return;
+        }
+        let state = self.active_state_mut();
+        state.use_tabs = to_tabs;
+        if to_tabs {
+            // A tab is now one level wide, so lines keep their width
+            state.tab_size = width;
+        }
+        state.tab_size_override = Some(width);
+        self.set_status_message(message.to_string());
+    }
+
+    /// Ask for the indent width to reindent the active buffer to
+    pub(crate) fn start_reindent_prompt(&mut self) {
+        let current = self.active_state().indent_width().to_string();
+        self.start_prompt_with_initial_text(
+            t!("indentation.prompt").to_string(),
+            PromptType::ReindentBuffer,
+            current,
+        );
+    }
+
+    /// Handle the Reindent Buffer prompt: rewrite every indent level of the
+    /// active buffer, as detected from its content, to `input` columns
+    pub(crate) fn handle_reindent_buffer(&mut self, input: &str) {
+        let width = match input.trim().parse::<usize>() {
+            Ok(width) if width > 0 => width,
+            _ => {
+                self.set_status_message(t!("indentation.invalid_width", input = input).to_string());
+                return;
+            }
+        };
+        let state = self.active_state();
+        let from_width = match self.detect_active_indentation() {
+            Some(IndentStyle::Spaces(detected)) => detected,
+            Some(IndentStyle::Tabs) => state.tab_size,
+            None => state.indent_width(),
+        };
+        let reindent = Reindent {
+            tab_size: state.tab_size,
+            from_width,
+            to: if state.use_tabs {
+                IndentStyle::Tabs
+            } else {
+                IndentStyle::Spaces(width)
+            },
+        };
+        let message = t!("indentation.reindented", width = width);
+        if !self.reindent_active_buffer(reindent, &message) {
+            return;
+        }
+        let state = self.active_state_mut();
+        if state.use_tabs {
+            state.tab_size = width;
+        }
+        state.tab_size_override = Some(width);
+        self.set_status_message(message.to_string());
+    }
+
+    /// Comment prefix of the active buffer's language
+    fn active_comment_prefix(&self) -> Option<String> {
+        self.config
+            .languages
+            .get(&self.active_state().language)
+            .and_then(|lang| lang.comment_prefix.clone())
+    }
+
+    /// Indentation of the active buffer as detected from its content
+    fn detect_active_indentation(&self) -> Option<IndentStyle> {
+        let text = self.active_state().buffer.to_string()?;
+        indentation::detect(&text, self.active_comment_prefix().as_deref())
+    }
+
+    /// Apply `reindent` to the active buffer as one undo step
+    ///
+    /// Returns false, with a status message, when the buffer can't be edited
+    /// or isn't fully loaded.
+    fn reindent_active_buffer(&mut self, reindent: Reindent, description: &str) -> bool {
+        if self.is_editing_disabled() {
+            self.set_status_message(self.editing_disabled_message());
+            return false;
+        }
+        let Some(text) = self.active_state().buffer.to_string() else {
+            self.set_status_message(t!("indentation.not_loaded").to_string());
+            return false;
+        };
+        let comment_prefix = self.active_comment_prefix();
+        let result = indentation::reindent(&text, comment_prefix.as_deref(), reindent);
+        if let Err(e) = self.replace_buffer_with_output(&result, description) {
+            self.set_status_message(e);
+            return false;
+        }
+        true
+    }
+}
@@ -1,5 +1,7 @@
+use super::quickfix::QuickfixStep;
 use super::*;
 use crate::services::plugins::hooks::HookArgs;
+use crate::services::render_profile::{self, Phase};
 use anyhow::Result as AnyhowResult;
 use rust_i18n::t;
 impl Editor {
@@ -32,6 +34,7 @@ impl Editor {
         use crate::input::keybindings::Action;
 
         let _t_total = std::time::Instant::now();
+        let _profile = render_profile::scope(Phase::Events);
 
         tracing::trace!(
             "Editor.handle_key: code={:?}, modifiers={:?}",
@@ -42,6 +45,10 @@ impl Editor {
         // Create key event for dispatch methods
         let key_event = crossterm::event::KeyEvent::new(code, modifiers);
 
+        if self.pending_digraph.is_some() && self.handle_digraph_key(code, modifiers)? {
+            return Ok(());
+        }
+
         // Try terminal input dispatch first (handles terminal mode and re-entry)
         if self.dispatch_terminal_input(&key_event).is_some() {
             return Ok(());
@@ -49,6 +56,7 @@ impl Editor {
 
         // Clear skip_ensure_visible flag so cursor becomes visible after key press
         // (scroll actions will set it again if needed)
+        self.finish_scroll_animation();
         let active_split = self.split_manager.active_split();
         if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
             view_state.viewport.clear_skip_ensure_visible();
@@ -84,6 +92,48 @@ impl Editor {
             }
         }
 
+        // Bindings with `when` expressions take precedence over everything below,
+        // so they can override modal, mode and context bindings alike
+        if self.keybindings.has_conditional_bindings() {
+            let resolution = {
+                let flags = |name: &str| {
+                    self.menu_state.context.get(name) || self.active_custom_contexts.contains(name)
+                };
+                let when = crate::input::when_clause::WhenContext {
+                    key_context: context,
+                    popup_visible: self.active_state().popups.is_visible(),
+                    buffer_mode: self
+                        .editor_mode
+                        .as_deref()
+                        .or_else(|| self.active_buffer_mode()),
+                    flags: &flags,
+                };
+                self.keybindings
+                    .resolve_conditional(&self.chord_state, &key_event, &when)
+            };
+            match resolution {
+                crate::input::keybindings::ChordResolution::Complete(action) => {
+                    tracing::debug!("Conditional binding matched -> Action: {:?}", action);
+                    self.chord_state.clear();
+                    return self.handle_action(action);
+                }
+                crate::input::keybindings::ChordResolution::Partial => {
+                    self.chord_state.push((code, modifiers));
+                    return Ok(());
+                }
+                crate::input::keybindings::ChordResolution::NoMatch => {
+                    // Modal contexts have no chords of their own; drop an abandoned prefix
+                    if !matches!(
+                        context,
+                        crate::input::keybindings::KeyContext::Normal
+                            | crate::input::keybindings::KeyContext::FileExplorer
+                    ) {
+                        self.chord_state.clear();
+                    }
+                }
+            }
+        }
+
         // Try hierarchical modal input dispatch first (Settings, Menu, Prompt, Popup)
         if self.dispatch_modal_input(&key_event).is_some() {
             return Ok(());
@@ -203,6 +253,8 @@ impl Editor {
             Action::LspCompletion
             | Action::LspGotoDefinition
             | Action::LspReferences
+            | Action::LspPeekDefinition
+            | Action::LspPeekReferences
             | Action::LspHover
             | Action::None => {
                 // Don't cancel for LSP actions or no-op
@@ -221,11 +273,26 @@ impl Editor {
 
     /// Handle an action (for normal mode and command execution)
     pub(super) fn handle_action(&mut self, action: Action) -> AnyhowResult<()> {
-        use crate::input::keybindings::Action;
-
         // Record action to macro if recording
         self.record_macro_action(&action);
 
+        // Replayed by handle_file_saved once the buffer's background save finishes
+        if self.queue_action_while_saving(&action) {
+            return Ok(());
+        }
+
+        let origin = self.action_animation_origin(&action);
+        let result = self.dispatch_action(action);
+        if let Some(origin) = origin {
+            self.start_action_animations(origin);
+        }
+        result
+    }
+
+    /// Run an action
+    pub(super) fn dispatch_action(&mut self, action: Action) -> AnyhowResult<()> {
+        use crate::input::keybindings::Action;
+
         match action {
             Action::Quit => self.quit(),
             Action::ForceQuit => {
@@ -234,6 +301,10 @@ impl Editor {
             Action::Detach => {
                 self.should_detach = true;
             }
+            Action::CollabShare => self.start_collab_share_prompt(),
+            Action::CollabJoin => self.start_collab_join_prompt(),
+            Action::CollabStop => self.stop_collab_share(),
+            Action::FollowUser => self.toggle_follow_user(),
             Action::Save => {
                 // Check if buffer has a file path - if not, redirect to SaveAs
                 if self.active_state().buffer.file_path().is_none() {
@@ -247,8 +318,12 @@ impl Editor {
                     // Check if file was modified externally since we opened/saved it
                     self.start_prompt(
                         t!("file.file_changed_prompt").to_string(),
-                        PromptType::ConfirmSaveConflict,
+                        PromptType::ConfirmSaveConflict {
+                            buffer_id: self.active_buffer(),
+                        },
                     );
+                } else if let Some(question) = self.check_save_breaks_link() {
+                    self.start_prompt(question, PromptType::ConfirmSaveBreaksLink);
                 } else {
                     self.save()?;
                 }
@@ -286,6 +361,7 @@ impl Editor {
                 );
                 self.init_folder_open_state();
             }
+            Action::SwitchProfile => self.start_switch_profile_prompt(),
             Action::GotoLine => self.start_prompt(
                 t!("file.goto_line_prompt").to_string(),
                 PromptType::GotoLine,
@@ -326,6 +402,9 @@ impl Editor {
             Action::ToggleAutoRevert => {
                 self.toggle_auto_revert();
             }
+            Action::LocalHistory => {
+                self.show_local_history();
+            }
             Action::FormatBuffer => {
                 if let Err(e) = self.format_buffer() {
                     self.set_status_message(
@@ -383,14 +462,14 @@ impl Editor {
             Action::CopyWithTheme(theme) => self.copy_selection_with_theme(&theme),
             Action::Cut => {
                 if self.is_editing_disabled() {
-                    self.set_status_message(t!("buffer.editing_disabled").to_string());
+                    self.set_status_message(self.editing_disabled_message());
                     return Ok(());
                 }
                 self.cut_selection()
             }
             Action::Paste => {
                 if self.is_editing_disabled() {
-                    self.set_status_message(t!("buffer.editing_disabled").to_string());
+                    self.set_status_message(self.editing_disabled_message());
                     return Ok(());
                 }
                 self.paste()
@@ -420,6 +499,27 @@ impl Editor {
             Action::ShowLspStatus => {
                 self.show_lsp_status_popup();
             }
+            Action::LspStatusPanel => {
+                self.show_lsp_status_panel();
+            }
+            Action::ShowBackgroundTasks => {
+                self.show_background_tasks();
+            }
+            Action::ReloadPlugin => {
+                self.show_reload_plugin_prompt();
+            }
+            Action::MemoryReport => {
+                self.show_memory_report();
+            }
+            Action::ShowTelemetryData => {
+                self.show_telemetry_data();
+            }
+            Action::UpdateFreshNow => {
+                self.update_fresh_now();
+            }
+            Action::ManageWorkspaceTrust => {
+                self.show_workspace_trust_prompt();
+            }
             Action::ClearWarnings => {
                 self.clear_warnings();
             }
@@ -481,6 +581,12 @@ impl Editor {
             Action::ToggleComposeMode => {
                 self.handle_toggle_compose_mode();
             }
+            Action::ToggleFocusMode => {
+                self.handle_toggle_focus_mode();
+            }
+            Action::InsertDigraph => {
+                self.start_digraph();
+            }
             Action::SetComposeWidth => {
                 let active_split = self.split_manager.active_split();
                 let current = self
@@ -519,6 +625,7 @@ impl Editor {
                     default_amount,
                 );
             }
+            Action::OpenLinkUnderCursor => self.open_link_under_cursor(),
             Action::LspCompletion => {
                 self.request_completion()?;
             }
@@ -534,6 +641,12 @@ impl Editor {
             Action::LspReferences => {
                 self.request_references()?;
             }
+            Action::LspPeekDefinition => {
+                self.request_peek_definition()?;
+            }
+            Action::LspPeekReferences => {
+                self.request_peek_references()?;
+            }
             Action::LspSignatureHelp => {
                 self.request_signature_help()?;
             }
@@ -602,6 +715,8 @@ impl Editor {
                     false,
                 );
             }
+            Action::StructuralSearch => self.start_structural_search_prompt(),
+            Action::StructuralReplace => self.start_structural_replace_prompt(),
             Action::FindInSelection => {
                 self.start_search_prompt(
                     t!("file.search_prompt").to_string(),
@@ -622,12 +737,38 @@ impl Editor {
                 self.find_selection_previous();
             }
             Action::AddCursorNextMatch => self.add_cursor_at_next_match(),
+            Action::SkipOccurrence => self.skip_to_next_match(),
+            Action::SelectAllOccurrences => self.select_all_matches(),
             Action::AddCursorAbove => self.add_cursor_above(),
             Action::AddCursorBelow => self.add_cursor_below(),
             Action::NextBuffer => self.next_buffer(),
             Action::PrevBuffer => self.prev_buffer(),
             Action::SwitchToPreviousTab => self.switch_to_previous_tab(),
             Action::SwitchToTabByName => self.start_switch_to_tab_prompt(),
+            Action::TogglePinTab => {
+                let split_id = self.split_manager.active_split();
+                self.toggle_pin_tab_in_split(self.active_buffer(), split_id);
+            }
+            Action::SetTabGroup => {
+                let split_id = self.split_manager.active_split();
+                self.start_set_tab_group_prompt(self.active_buffer(), split_id);
+            }
+            Action::CloseOtherTabs => {
+                let split_id = self.split_manager.active_split();
+                self.close_other_tabs_in_split(self.active_buffer(), split_id);
+            }
+            Action::CloseTabsToRight => {
+                let split_id = self.split_manager.active_split();
+                self.close_tabs_to_right_in_split(self.active_buffer(), split_id);
+            }
+            Action::CloseTabsToLeft => {
+                let split_id = self.split_manager.active_split();
+                self.close_tabs_to_left_in_split(self.active_buffer(), split_id);
+            }
+            Action::CloseAllTabs => {
+                let split_id = self.split_manager.active_split();
+                self.close_all_tabs_in_split(split_id);
+            }
 
             // Tab scrolling (manual scroll - don't auto-adjust)
             Action::ScrollTabsLeft => {
@@ -654,13 +795,20 @@ impl Editor {
             Action::IncreaseSplitSize => self.adjust_split_size(0.05),
             Action::DecreaseSplitSize => self.adjust_split_size(-0.05),
             Action::ToggleMaximizeSplit => self.toggle_maximize_split(),
+            Action::ToggleScrollLock => self.toggle_scroll_lock(),
             Action::ToggleFileExplorer => self.toggle_file_explorer(),
             Action::ToggleMenuBar => self.toggle_menu_bar(),
             Action::ToggleTabBar => self.toggle_tab_bar(),
+            Action::TogglePreviewTabs => self.toggle_preview_tabs(),
             Action::ToggleLineNumbers => self.toggle_line_numbers(),
+            Action::ToggleReadOnly => self.toggle_read_only(),
+            Action::ToggleImagePreview => self.toggle_image_preview(),
             Action::ToggleMouseCapture => self.toggle_mouse_capture(),
+            Action::ToggleAccessibility => self.toggle_accessibility(),
+            Action::AnnounceContext => self.announce_context(),
             Action::ToggleMouseHover => self.toggle_mouse_hover(),
             Action::ToggleDebugHighlights => self.toggle_debug_highlights(),
+            Action::ToggleRenderProfiler => self.toggle_render_profiler(),
             // Buffer settings
             Action::SetTabSize => {
                 let current = self
@@ -697,6 +845,12 @@ impl Editor {
                     self.set_status_message(status.to_string());
                 }
             }
+            Action::ConvertIndentationToSpaces => self.convert_indentation(false),
+            Action::ConvertIndentationToTabs => self.convert_indentation(true),
+            Action::ReindentBuffer => self.start_reindent_prompt(),
+            Action::InsertSequence => self.start_insert_sequence_prompt(),
+            Action::InsertDateTime => self.start_insert_date_time_prompt(),
+            Action::AlignSelection => self.start_align_prompt(),
             Action::ToggleTabIndicators => {
                 if let Some(state) = self.buffers.get_mut(&self.active_buffer()) {
                     state.show_whitespace_tabs = !state.show_whitespace_tabs;
@@ -708,6 +862,7 @@ impl Editor {
                     self.set_status_message(status.to_string());
                 }
             }
+            Action::CycleRenderWhitespace => self.cycle_render_whitespace(),
             Action::ResetBufferSettings => self.reset_buffer_settings(),
             Action::FocusFileExplorer => self.focus_file_explorer(),
             Action::FocusEditor => self.focus_editor(),
@@ -722,9 +877,11 @@ impl Editor {
             Action::FileExplorerNewFile => self.file_explorer_new_file(),
             Action::FileExplorerNewDirectory => self.file_explorer_new_directory(),
             Action::FileExplorerDelete => self.file_explorer_delete(),
+            Action::FileExplorerUndoDelete => self.file_explorer_undo_delete(),
             Action::FileExplorerRename => self.file_explorer_rename(),
             Action::FileExplorerToggleHidden => self.file_explorer_toggle_hidden(),
             Action::FileExplorerToggleGitignored => self.file_explorer_toggle_gitignored(),
+            Action::FileExplorerNextRoot => self.file_explorer_next_root(),
             Action::FileExplorerSearchClear => self.file_explorer_search_clear(),
             Action::FileExplorerSearchBackspace => self.file_explorer_search_pop_char(),
             Action::RemoveSecondaryCursors => {
@@ -807,7 +964,10 @@ impl Editor {
                 self.smart_home();
             }
             Action::ToggleComment => {
-                self.toggle_comment();
+                self.toggle_line_comment();
+            }
+            Action::ToggleBlockComment => {
+                self.toggle_block_comment();
             }
             Action::GoToMatchingBracket => {
                 self.goto_matching_bracket();
@@ -824,6 +984,12 @@ impl Editor {
             Action::JumpToBookmark(key) => {
                 self.jump_to_bookmark(key);
             }
+            Action::SetNamedMark(name) => {
+                self.set_mark(name);
+            }
+            Action::JumpToNamedMark(name) => {
+                self.jump_to_mark(name, false);
+            }
             Action::ClearBookmark(key) => {
                 self.clear_bookmark(key);
             }
@@ -856,6 +1022,7 @@ impl Editor {
                     let query = search_state.query.clone();
                     self.perform_search(&query);
                 }
+                self.refresh_replace_preview();
             }
             Action::ToggleSearchWholeWord => {
                 self.search_whole_word = !self.search_whole_word;
@@ -881,6 +1048,7 @@ impl Editor {
                     let query = search_state.query.clone();
                     self.perform_search(&query);
                 }
+                self.refresh_replace_preview();
             }
             Action::ToggleSearchRegex => {
                 self.search_use_regex = !self.search_use_regex;
@@ -906,6 +1074,7 @@ impl Editor {
                     let query = search_state.query.clone();
                     self.perform_search(&query);
                 }
+                self.refresh_replace_preview();
             }
             Action::ToggleSearchConfirmEach => {
                 self.search_confirm_each = !self.search_confirm_each;
@@ -963,10 +1132,16 @@ impl Editor {
                     PromptType::JumpToBookmark,
                 );
             }
+            Action::PromptSetNamedMark => {
+                self.start_set_mark_prompt();
+            }
+            Action::PromptJumpToNamedMark => {
+                self.start_jump_to_mark_prompt();
+            }
             Action::None => {}
             Action::DeleteBackward => {
                 if self.is_editing_disabled() {
-                    self.set_status_message(t!("buffer.editing_disabled").to_string());
+                    self.set_status_message(self.editing_disabled_message());
                     return Ok(());
                 }
                 // Normal backspace handling
@@ -1070,6 +1245,22 @@ impl Editor {
                 // Run shell command on buffer/selection, replace content
                 self.start_shell_command_prompt(true);
             }
+            Action::FilterThroughCommand => {
+                self.start_prompt(
+                    t!("shell.filter_prompt").to_string(),
+                    PromptType::FilterCommand,
+                );
+            }
+            Action::ToggleWatchTask => self.toggle_watch_task(),
+            Action::ShowWatchOutput => self.show_watch_output(),
+            Action::QuickfixNext => self.quickfix_step(QuickfixStep::Next),
+            Action::QuickfixPrev => self.quickfix_step(QuickfixStep::Prev),
+            Action::QuickfixFirst => self.quickfix_step(QuickfixStep::First),
+            Action::QuickfixLast => self.quickfix_step(QuickfixStep::Last),
+            Action::QuickfixOlder => self.quickfix_switch_list(true),
+            Action::QuickfixNewer => self.quickfix_switch_list(false),
+            Action::QuickfixShow => self.start_quickfix_items_prompt(),
+            Action::QuickfixHistory => self.start_quickfix_history_prompt(),
             Action::OpenSettings => {
                 self.open_settings();
             }
@@ -1304,16 +1495,27 @@ impl Editor {
             return Ok(());
         }
 
+        self.scroll_split_lines(active_split, buffer_id, delta);
+        Ok(())
+    }
+
+    /// Scroll a split's viewport by `delta` view lines without moving the cursor
+    pub(super) fn scroll_split_lines(
+        &mut self,
+        split_id: SplitId,
+        buffer_id: BufferId,
+        delta: i32,
+    ) {
         // Get view_transform tokens from SplitViewState (if any)
         let view_transform_tokens = self
             .split_view_states
-            .get(&active_split)
+            .get(&split_id)
             .and_then(|vs| vs.view_transform.as_ref())
             .map(|vt| vt.tokens.clone());
 
         // Get mutable references to both buffer and view state
         let buffer = self.buffers.get_mut(&buffer_id).map(|s| &mut s.buffer);
-        let view_state = self.split_view_states.get_mut(&active_split);
+        let view_state = self.split_view_states.get_mut(&split_id);
 
         if let (Some(buffer), Some(view_state)) = (buffer, view_state) {
             let top_byte_before = view_state.viewport.top_byte;
@@ -1341,14 +1543,12 @@ impl Editor {
             // Skip ensure_visible so the scroll position isn't undone during render
             view_state.viewport.set_skip_ensure_visible();
             tracing::trace!(
-                "handle_mouse_scroll: delta={}, top_byte {} -> {}",
+                "scroll_split_lines: delta={}, top_byte {} -> {}",
                 delta,
                 top_byte_before,
                 view_state.viewport.top_byte
             );
         }
-
-        Ok(())
     }
 
     /// Handle scrollbar drag with relative movement (when dragging from thumb)
@@ -2121,6 +2321,35 @@ impl Editor {
                 return Ok(());
             }
 
+            // Ctrl+click adds a cursor, or removes one already at this position
+            if modifiers.contains(KeyModifiers::CONTROL) {
+                let existing = state
+                    .cursors
+                    .iter()
+                    .find(|(_, cursor)| cursor.position == target_position)
+                    .map(|(id, cursor)| (id, *cursor));
+                let event = match existing {
+                    Some((cursor_id, cursor)) if state.cursors.count() > 1 => Event::RemoveCursor {
+                        cursor_id,
+                        position: cursor.position,
+                        anchor: cursor.anchor,
+                    },
+                    Some(_) => return Ok(()),
+                    None => Event::AddCursor {
+                        cursor_id: crate::model::event::CursorId(
+                            state.cursors.iter().map(|(id, _)| id.0).max().unwrap_or(0) + 1,
+                        ),
+                        position: target_position,
+                        anchor: None,
+                    },
+                };
+                if let Some(event_log) = self.event_logs.get_mut(&buffer_id) {
+                    event_log.append(event.clone());
+                }
+                state.apply(&event);
+                return Ok(());
+            }
+
             // Move the primary cursor to this position
             // If shift is held, extend selection; otherwise clear it
             let primary_cursor_id = state.cursors.primary_id();
@@ -2128,10 +2357,8 @@ impl Editor {
             let old_position = primary_cursor.position;
             let old_anchor = primary_cursor.anchor;
 
-            // For shift+click or ctrl+click: extend selection from current anchor (or position if no anchor) to click
-            // Both modifiers supported since some terminals intercept shift+click
-            let extend_selection = modifiers.contains(KeyModifiers::SHIFT)
-                || modifiers.contains(KeyModifiers::CONTROL);
+            // For shift+click: extend selection from current anchor (or position if no anchor) to click
+            let extend_selection = modifiers.contains(KeyModifiers::SHIFT);
             let new_anchor = if extend_selection {
                 // If already selecting, keep the existing anchor; otherwise anchor at current position
                 Some(old_anchor.unwrap_or(old_position))
@@ -2157,8 +2384,11 @@ impl Editor {
 
             // Track position history
             if !self.in_navigation {
-                self.position_history
-                    .record_movement(buffer_id, target_position, None);
+                if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+                    view_state
+                        .position_history
+                        .record_movement(buffer_id, target_position, None);
+                }
             }
 
             // Set up drag selection state for potential text selection
@@ -2218,7 +2448,7 @@ impl Editor {
                         // Double-click or Enter will focus the editor
                         let path = node.entry.path.clone();
                         let name = node.entry.name.clone();
-                        match self.open_file(&path) {
+                        match self.open_file_preview(&path) {
                             Ok(_) => {
                                 self.set_status_message(
                                     rust_i18n::t!("explorer.opened_file", name = &name).to_string(),
@@ -2541,14 +2771,16 @@ impl Editor {
     /// Apply a theme by name and persist it to config
     pub(super) fn apply_theme(&mut self, theme_name: &str) {
         if !theme_name.is_empty() {
-            if let Some(theme) = self.theme_registry.get_cloned(theme_name) {
+            if let Some(theme) = self.load_theme(theme_name) {
                 self.theme = theme;
 
                 // Set terminal cursor color to match theme
                 self.theme.set_terminal_cursor_color();
 
                 // Update the config in memory
-                self.config.theme = self.theme.name.clone().into();
+                self.config
+                    .theme
+                    .select(self.theme.name.clone().into(), self.appearance);
 
                 // Persist to config file
                 self.save_theme_to_config();
@@ -2566,7 +2798,7 @@ impl Editor {
     /// Used for live preview when navigating theme selection
     pub(super) fn preview_theme(&mut self, theme_name: &str) {
         if !theme_name.is_empty() && theme_name != self.theme.name {
-            if let Some(theme) = self.theme_registry.get_cloned(theme_name) {
+            if let Some(theme) = self.load_theme(theme_name) {
                 self.theme = theme;
                 self.theme.set_terminal_cursor_color();
             }
@@ -2588,15 +2820,12 @@ impl Editor {
         let config_path = resolver.user_config_path();
         tracing::info!(
             "Saving theme '{}' to user config at {}",
-            self.config.theme.0,
+            self.config.theme,
             config_path.display()
         );
 
         let mut changes = std::collections::HashMap::new();
-        changes.insert(
-            "/theme".to_string(),
-            serde_json::Value::String(self.config.theme.0.clone()),
-        );
+        changes.insert("/theme".to_string(), serde_json::json!(self.config.theme));
 
         match resolver.save_changes_to_layer(
             &changes,
@@ -2773,15 +3002,9 @@ impl Editor {
             // Update the config in memory
             self.config.editor.cursor_style = style;
 
-            // Apply the cursor style to the terminal
-            if self.session_mode {
-                // In session mode, queue the escape sequence to be sent to the client
-                self.queue_escape_sequences(style.to_escape_sequence());
-            } else {
-                // In normal mode, write directly to stdout
-                use std::io::stdout;
-                let _ = crossterm::execute!(stdout(), style.to_crossterm_style());
-            }
+            // Apply the cursor style to the terminal, unless the current
+            // mode has its own
+            self.sync_cursor_style();
 
             // Persist to config file
             self.save_cursor_style_to_config();
@@ -2943,14 +3166,7 @@ impl Editor {
 
             if is_valid && prev_id != self.active_buffer() {
                 // Save current position before switching
-                self.position_history.commit_pending_movement();
-
-                let current_state = self.active_state();
-                let position = current_state.cursors.primary().position;
-                let anchor = current_state.cursors.primary().anchor;
-                self.position_history
-                    .record_movement(self.active_buffer(), position, anchor);
-                self.position_history.commit_pending_movement();
+                self.record_position_before_switch();
 
                 self.set_active_buffer(prev_id);
             } else if !is_valid {
@@ -3027,6 +3243,67 @@ impl Editor {
         }
     }
 
+    /// Start the prompt for choosing the tab group of a tab
+    pub(crate) fn start_set_tab_group_prompt(&mut self, buffer_id: BufferId, split_id: SplitId) {
+        use crate::view::split::TabGroup;
+
+        let current = self
+            .split_view_states
+            .get(&split_id)
+            .and_then(|vs| vs.tab_groups.get(&buffer_id).copied());
+
+        let label = |group: TabGroup| match group {
+            TabGroup::Red => t!("tab_group.red"),
+            TabGroup::Orange => t!("tab_group.orange"),
+            TabGroup::Yellow => t!("tab_group.yellow"),
+            TabGroup::Green => t!("tab_group.green"),
+            TabGroup::Blue => t!("tab_group.blue"),
+            TabGroup::Purple => t!("tab_group.purple"),
+        };
+        let mut options: Vec<(String, &str, bool)> = TabGroup::ALL
+            .iter()
+            .map(|&group| {
+                (
+                    label(group).to_string(),
+                    group.name(),
+                    current == Some(group),
+                )
+            })
+            .collect();
+        options.push((t!("tab_group.none").to_string(), "none", current.is_none()));
+
+        let current_index = options
+            .iter()
+            .position(|(_, _, is_current)| *is_current)
+            .unwrap_or(0);
+        let suggestions: Vec<crate::input::commands::Suggestion> = options
+            .into_iter()
+            .map(
+                |(label, value, is_current)| crate::input::commands::Suggestion {
+                    text: label,
+                    description: is_current.then(|| t!("tab_group.current").to_string()),
+                    value: Some(value.to_string()),
+                    disabled: false,
+                    keybinding: None,
+                    source: None,
+                },
+            )
+            .collect();
+
+        self.prompt = Some(crate::view::prompt::Prompt::with_suggestions(
+            t!("tab_group.prompt").to_string(),
+            PromptType::SetTabGroup {
+                buffer_id,
+                split_id,
+            },
+            suggestions,
+        ));
+
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.selected_suggestion = Some(current_index);
+        }
+    }
+
     /// Switch to a tab by its BufferId
     pub(crate) fn switch_to_tab(&mut self, buffer_id: BufferId) {
         // Verify the buffer exists and is open in the current split
@@ -3043,14 +3320,7 @@ impl Editor {
 
         if buffer_id != self.active_buffer() {
             // Save current position before switching
-            self.position_history.commit_pending_movement();
-
-            let current_state = self.active_state();
-            let position = current_state.cursors.primary().position;
-            let anchor = current_state.cursors.primary().anchor;
-            self.position_history
-                .record_movement(self.active_buffer(), position, anchor);
-            self.position_history.commit_pending_movement();
+            self.record_position_before_switch();
 
             self.set_active_buffer(buffer_id);
         }
@@ -3089,7 +3359,7 @@ impl Editor {
     fn handle_insert_char_editor(&mut self, c: char) -> AnyhowResult<()> {
         // Check if editing is disabled (show_cursors = false)
         if self.is_editing_disabled() {
-            self.set_status_message(t!("buffer.editing_disabled").to_string());
+            self.set_status_message(self.editing_disabled_message());
             return Ok(());
         }
 
@@ -3151,14 +3421,17 @@ impl Editor {
                 | Action::DeleteWordForward
                 | Action::DeleteLine
                 | Action::DuplicateLine
+                | Action::DuplicateSelection
+                | Action::JoinLines
                 | Action::MoveLineUp
                 | Action::MoveLineDown
                 | Action::DedentSelection
                 | Action::ToggleComment
+                | Action::ToggleBlockComment
         );
 
         if is_editing_action && self.is_editing_disabled() {
-            self.set_status_message(t!("buffer.editing_disabled").to_string());
+            self.set_status_message(self.editing_disabled_message());
             return Ok(());
         }
 
@@ -3215,8 +3488,12 @@ impl Editor {
             ..
         } = event
         {
-            self.position_history
-                .record_movement(self.active_buffer(), *new_position, *new_anchor);
+            let buffer_id = self.active_buffer();
+            self.active_position_history_mut().record_movement(
+                buffer_id,
+                *new_position,
+                *new_anchor,
+            );
         }
     }
 }
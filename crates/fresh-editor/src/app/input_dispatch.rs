@@ -84,6 +84,11 @@ impl Editor {
             return Some(result);
         }
 
+        // Peek window is next (modal while open)
+        if self.peek_window.is_some() {
+            return Some(self.handle_peek_window_input(event));
+        }
+
         // Menu is next
         if self.menu_state.active_menu.is_some() {
             let all_menus: Vec<crate::config::Menu> = self
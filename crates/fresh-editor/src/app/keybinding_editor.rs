@@ -4,7 +4,7 @@
 //! Provides a table view of all resolved bindings with search, filter,
 //! key recording, conflict detection, and keymap management.
 
-use crate::config::{Config, KeyPress, Keybinding};
+use crate::config::{Config, Keybinding};
 use crate::input::keybindings::{format_keybinding, Action, KeybindingResolver};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::Rect;
@@ -18,6 +18,8 @@ pub enum BindingSource {
     Keymap,
     /// User custom override (in config keybindings array)
     Custom,
+    /// Action with no binding in any context (listed so it can be bound)
+    Unbound,
 }
 
 /// A single resolved keybinding entry for display
@@ -139,15 +141,21 @@ impl EditBindingState {
             .iter()
             .position(|c| c == &binding.context)
             .unwrap_or(1);
+        // Keep when expressions that aren't one of the plain contexts
+        let context = if context_options.contains(&binding.context) || binding.context.is_empty() {
+            context_options[context_option_index].clone()
+        } else {
+            binding.context.clone()
+        };
 
         Self {
             mode: EditMode::RecordingKey,
-            key_code: Some(binding.key_code),
+            key_code: (binding.source != BindingSource::Unbound).then_some(binding.key_code),
             modifiers: binding.modifiers,
             key_display: binding.key_display.clone(),
             action_text: binding.action.clone(),
             action_cursor: binding.action.len(),
-            context: binding.context.clone(),
+            context,
             editing_index: Some(index),
             conflicts: Vec::new(),
             context_options,
@@ -185,6 +193,7 @@ pub enum SourceFilter {
     All,
     KeymapOnly,
     CustomOnly,
+    UnboundOnly,
 }
 
 /// Layout information for mouse hit testing
@@ -361,6 +370,18 @@ impl KeybindingEditor {
                 .then(a.action_display.cmp(&b.action_display))
         });
 
+        // List every remaining action after the bound ones so it can be bound
+        // from the editor
+        let bound: std::collections::HashSet<&str> =
+            bindings.iter().map(|b| b.action.as_str()).collect();
+        let mut unbound: Vec<ResolvedBinding> = Self::collect_action_names()
+            .into_iter()
+            .filter(|action| !bound.contains(action.as_str()))
+            .map(Self::unbound_entry)
+            .collect();
+        unbound.sort_by(|a, b| a.action_display.cmp(&b.action_display));
+        bindings.extend(unbound);
+
         bindings
     }
 
@@ -372,10 +393,15 @@ impl KeybindingEditor {
     ) -> Option<ResolvedBinding> {
         let context = kb.when.as_deref().unwrap_or("normal").to_string();
 
-        if !kb.keys.is_empty() {
+        let sequence = KeybindingResolver::binding_sequence(kb)?;
+        let action_display = KeybindingResolver::format_action_from_str(&kb.action);
+        if sequence.len() > 1 {
             // Chord binding
-            let key_display = format_chord_keys(&kb.keys);
-            let action_display = KeybindingResolver::format_action_from_str(&kb.action);
+            let key_display = sequence
+                .iter()
+                .map(|(key_code, modifiers)| format_keybinding(key_code, modifiers))
+                .collect::<Vec<_>>()
+                .join(" ");
             Some(ResolvedBinding {
                 key_display,
                 action: kb.action.clone(),
@@ -386,12 +412,10 @@ impl KeybindingEditor {
                 modifiers: KeyModifiers::NONE,
                 is_chord: true,
             })
-        } else if !kb.key.is_empty() {
+        } else {
             // Single key binding
-            let key_code = KeybindingResolver::parse_key_public(&kb.key)?;
-            let modifiers = KeybindingResolver::parse_modifiers_public(&kb.modifiers);
+            let (key_code, modifiers) = sequence[0];
             let key_display = format_keybinding(&key_code, &modifiers);
-            let action_display = KeybindingResolver::format_action_from_str(&kb.action);
             Some(ResolvedBinding {
                 key_display,
                 action: kb.action.clone(),
@@ -402,8 +426,20 @@ impl KeybindingEditor {
                 modifiers,
                 is_chord: false,
             })
-        } else {
-            None
+        }
+    }
+
+    /// Placeholder row for an action without a binding
+    fn unbound_entry(action: String) -> ResolvedBinding {
+        ResolvedBinding {
+            key_display: String::new(),
+            action_display: KeybindingResolver::format_action_from_str(&action),
+            action,
+            context: String::new(),
+            source: BindingSource::Unbound,
+            key_code: KeyCode::Null,
+            modifiers: KeyModifiers::NONE,
+            is_chord: false,
         }
     }
 
@@ -475,6 +511,7 @@ impl KeybindingEditor {
             match self.source_filter {
                 SourceFilter::KeymapOnly if binding.source != BindingSource::Keymap => continue,
                 SourceFilter::CustomOnly if binding.source != BindingSource::Custom => continue,
+                SourceFilter::UnboundOnly if binding.source != BindingSource::Unbound => continue,
                 _ => {}
             }
 
@@ -638,7 +675,8 @@ impl KeybindingEditor {
         self.source_filter = match self.source_filter {
             SourceFilter::All => SourceFilter::CustomOnly,
             SourceFilter::CustomOnly => SourceFilter::KeymapOnly,
-            SourceFilter::KeymapOnly => SourceFilter::All,
+            SourceFilter::KeymapOnly => SourceFilter::UnboundOnly,
+            SourceFilter::UnboundOnly => SourceFilter::All,
         };
         self.apply_filters();
     }
@@ -766,6 +804,7 @@ impl KeybindingEditor {
 
         for binding in &self.bindings {
             if !binding.is_chord
+                && binding.source != BindingSource::Unbound
                 && binding.key_code == key_code
                 && binding.modifiers == modifiers
                 && (binding.context == context
@@ -807,22 +846,11 @@ impl KeybindingEditor {
             SourceFilter::All => "All",
             SourceFilter::KeymapOnly => "Keymap",
             SourceFilter::CustomOnly => "Custom",
+            SourceFilter::UnboundOnly => "Unbound",
         }
     }
 }
 
-/// Format chord keys for display
-fn format_chord_keys(keys: &[KeyPress]) -> String {
-    keys.iter()
-        .filter_map(|kp| {
-            let key_code = KeybindingResolver::parse_key_public(&kp.key)?;
-            let modifiers = KeybindingResolver::parse_modifiers_public(&kp.modifiers);
-            Some(format_keybinding(&key_code, &modifiers))
-        })
-        .collect::<Vec<_>>()
-        .join(" ")
-}
-
 /// Convert a KeyCode back to a config-friendly name
 fn key_code_to_config_name(key_code: KeyCode) -> String {
     match key_code {
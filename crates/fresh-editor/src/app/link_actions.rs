@@ -0,0 +1,98 @@
+//! **Open Link Under Cursor**: open the URL under the cursor in the browser,
+//! or the file path under it in the editor, at its `:line:col` if it has
+//! one. Links are found by [`crate::services::link_detection`].
+
+use std::path::PathBuf;
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::primitives::path_utils::expand_tilde;
+use crate::services::link_detection::LinkTarget;
+
+impl Editor {
+    /// Turn OSC 8 hyperlinks for URLs on screen on or off
+    pub fn set_hyperlinks(&mut self, enabled: bool) {
+        self.hyperlinks = enabled;
+    }
+
+    /// Whether URLs on screen are drawn as OSC 8 hyperlinks
+    pub(crate) fn hyperlinks_enabled(&self) -> bool {
+        self.hyperlinks && !self.session_mode
+    }
+
+    /// Open the link at the primary cursor
+    pub fn open_link_under_cursor(&mut self) {
+        let state = self.active_state();
+        let position = state.cursors.primary().position;
+        let line = state.buffer.get_line_number(position);
+        let line_start = state.buffer.line_start_offset(line).unwrap_or(0);
+        let line_text = state
+            .buffer
+            .get_line(line)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default();
+        let line_text = line_text.trim_end_matches(['\n', '\r']);
+
+        let Some(link) = self
+            .link_detector
+            .link_at(line_text, position.saturating_sub(line_start))
+        else {
+            self.set_status_message(t!("link.none").to_string());
+            return;
+        };
+
+        match link.target {
+            LinkTarget::Url(url) => self.open_url(&url),
+            LinkTarget::File { path, line, column } => {
+                let Some(resolved) = self.resolve_link_path(&path) else {
+                    self.set_status_message(t!("link.file_not_found", path = path).to_string());
+                    return;
+                };
+                if let Err(e) = self.open_file(&resolved) {
+                    self.set_status_message(
+                        t!("file.error_opening", error = e.to_string()).to_string(),
+                    );
+                    return;
+                }
+                if let Some(line) = line {
+                    self.goto_line_col(line, column);
+                }
+            }
+        }
+    }
+
+    /// Open `url` with the system's default handler
+    fn open_url(&mut self, url: &str) {
+        match open::that(url) {
+            Ok(()) => self.set_status_message(t!("link.opening", url = url).to_string()),
+            Err(e) => self.set_status_message(
+                t!("link.open_failed", url = url, error = e.to_string()).to_string(),
+            ),
+        }
+    }
+
+    /// An existing file for a path found in the active buffer: relative
+    /// paths are looked up next to the buffer's file, then in the working
+    /// directory
+    fn resolve_link_path(&self, path: &str) -> Option<PathBuf> {
+        let path = expand_tilde(path);
+        let candidates = if path.is_absolute() {
+            vec![path]
+        } else {
+            let buffer_dir = self
+                .buffer_metadata
+                .get(&self.active_buffer())
+                .and_then(|m| m.file_path())
+                .and_then(|p| p.parent())
+                .map(|dir| dir.join(&path));
+            buffer_dir
+                .into_iter()
+                .chain(std::iter::once(self.working_dir.join(&path)))
+                .collect()
+        };
+        candidates
+            .into_iter()
+            .find(|candidate| self.filesystem.is_file(candidate).unwrap_or(false))
+    }
+}
@@ -0,0 +1,211 @@
+//! Local history of saved files (**Local History**)
+//!
+//! Saves go through [`Editor::record_local_history`], which keeps the
+//! buffer's content in [`LocalHistory`]. The command lists the versions of
+//! the active buffer's file; picking one offers to compare it with the
+//! buffer in a diff buffer or to restore it. A restore replaces the buffer's
+//! content as a single undoable edit and leaves saving to the user.
+
+use std::path::Path;
+
+use rust_i18n::t;
+
+use super::file_open::format_size;
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::model::event::BufferId;
+use crate::model::line_diff::unified_diff;
+use crate::primitives::text_property::TextPropertyEntry;
+use crate::services::local_history::{HistoryLimits, Version};
+use crate::view::prompt::{Prompt, PromptType};
+
+/// Unchanged lines shown around each change when comparing
+const DIFF_CONTEXT_LINES: usize = 3;
+
+const COMPARE: &str = "compare";
+const RESTORE: &str = "restore";
+
+/// Local time a version was saved, e.g. `2026-10-16 14:03:22`
+fn format_saved_at(version: &Version) -> String {
+    chrono::DateTime::<chrono::Local>::from(version.saved_at())
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+impl Editor {
+    /// Keep the content `buffer_id` was saved with as a version of `path`
+    pub(super) fn record_local_history(&mut self, buffer_id: BufferId, path: &Path) {
+        let editor = &self.config.editor;
+        if !editor.local_history {
+            return;
+        }
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        if state.buffer.is_binary()
+            || state.buffer.len() as u64 >= editor.large_file_threshold_bytes
+            || state.buffer.filesystem().remote_connection_info().is_some()
+        {
+            return;
+        }
+        let Some(content) = state.buffer.to_string() else {
+            return;
+        };
+        let limits = HistoryLimits {
+            max_age: std::time::Duration::from_secs(editor.local_history_days * 24 * 60 * 60),
+            max_bytes: editor.local_history_max_mb * 1024 * 1024,
+        };
+        if let Err(e) = self.local_history.record(path, content.as_bytes(), limits) {
+            tracing::warn!("Failed to keep local history of {:?}: {}", path, e);
+        }
+    }
+
+    /// List the saved versions of the active buffer's file
+    pub fn show_local_history(&mut self) {
+        let buffer_id = self.active_buffer();
+        let Some(path) = self
+            .active_state()
+            .buffer
+            .file_path()
+            .map(Path::to_path_buf)
+        else {
+            self.set_status_message(t!("local_history.no_file").to_string());
+            return;
+        };
+        let versions = self.local_history.versions(&path);
+        if versions.is_empty() {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            self.set_status_message(t!("local_history.none", name = name).to_string());
+            return;
+        }
+
+        let suggestions: Vec<Suggestion> = versions
+            .iter()
+            .map(|version| Suggestion {
+                text: format_saved_at(version),
+                description: Some(format_size(version.size)),
+                value: Some(version.id.to_string()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+        let mut prompt = Prompt::with_suggestions(
+            t!("local_history.prompt").to_string(),
+            PromptType::LocalHistory { buffer_id },
+            suggestions,
+        );
+        prompt.selected_suggestion = Some(0);
+        self.prompt = Some(prompt);
+    }
+
+    /// Ask whether to compare or restore the picked version
+    pub(super) fn local_history_version_selected(&mut self, buffer_id: BufferId, input: &str) {
+        let Ok(version) = input.trim().parse::<u64>() else {
+            return;
+        };
+        let time = format_saved_at(&Version {
+            id: version,
+            size: 0,
+        });
+        let option = |label: String, value: &str| Suggestion {
+            text: label,
+            description: None,
+            value: Some(value.to_string()),
+            disabled: false,
+            keybinding: None,
+            source: None,
+        };
+        let suggestions = vec![
+            option(t!("local_history.compare").to_string(), COMPARE),
+            option(t!("local_history.restore").to_string(), RESTORE),
+        ];
+        let mut prompt = Prompt::with_suggestions(
+            t!("local_history.version_prompt", time = time).to_string(),
+            PromptType::LocalHistoryVersion { buffer_id, version },
+            suggestions,
+        );
+        prompt.selected_suggestion = Some(0);
+        self.prompt = Some(prompt);
+    }
+
+    /// Compare or restore the version `version` of `buffer_id`'s file
+    pub(super) fn local_history_action(&mut self, buffer_id: BufferId, version: u64, input: &str) {
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        let Some(path) = state.buffer.file_path().map(Path::to_path_buf) else {
+            return;
+        };
+        let content = match self.local_history.read(&path, version) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => {
+                self.set_status_message(
+                    t!("local_history.read_failed", error = e.to_string()).to_string(),
+                );
+                return;
+            }
+        };
+        let time = format_saved_at(&Version {
+            id: version,
+            size: content.len() as u64,
+        });
+
+        match input.trim() {
+            COMPARE => self.compare_local_history(buffer_id, &path, &time, &content),
+            RESTORE => {
+                self.set_active_buffer(buffer_id);
+                match self.replace_buffer_with_output(&content, "Restore local history") {
+                    Ok(()) => self
+                        .set_status_message(t!("local_history.restored", time = time).to_string()),
+                    Err(e) => self.set_status_message(e),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Open a diff from the version saved at `time` to the buffer's content
+    fn compare_local_history(&mut self, buffer_id: BufferId, path: &Path, time: &str, old: &str) {
+        let current = self
+            .buffers
+            .get(&buffer_id)
+            .and_then(|state| state.buffer.to_string())
+            .unwrap_or_default();
+        let diff = unified_diff(old, &current, DIFF_CONTEXT_LINES);
+        if diff.is_empty() {
+            self.set_status_message(t!("local_history.identical", time = time).to_string());
+            return;
+        }
+
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        let buffer_name = format!("*History: {} @ {}*", name, time);
+        let content = format!("--- {} @ {}\n+++ {}\n{}", name, time, name, diff);
+
+        let existing_buffer = self
+            .buffer_metadata
+            .iter()
+            .find(|(_, m)| m.display_name == buffer_name)
+            .map(|(id, _)| *id);
+        let diff_buffer = existing_buffer.unwrap_or_else(|| {
+            self.create_virtual_buffer(buffer_name, "special".to_string(), true)
+        });
+        let entries = vec![TextPropertyEntry::text(content)];
+        if let Err(e) = self.set_virtual_buffer_content(diff_buffer, entries) {
+            tracing::warn!("Failed to fill local history diff: {}", e);
+            return;
+        }
+        if let Some(state) = self.buffers.get_mut(&diff_buffer) {
+            state.set_language_from_name("history.diff", &self.grammar_registry);
+            state.editing_disabled = true;
+            state.margins.set_line_numbers(false);
+        }
+        self.set_active_buffer(diff_buffer);
+    }
+}
@@ -5,9 +5,18 @@
 
 use super::Editor;
 use crate::input::commands::Suggestion;
+use crate::model::event::{PopupContentData, PopupData, PopupListItemData, PopupPositionData};
+use crate::services::async_bridge::LspServerStatus;
+use crate::services::process_limits::SystemResources;
 use crate::view::prompt::{Prompt, PromptType};
 use rust_i18n::t;
 
+/// Prefix of the popup item data used by the LSP status panel
+///
+/// Items carry `lsp-panel:<command>:<language>` so confirming an item needs
+/// no extra editor state.
+pub(super) const LSP_PANEL_DATA_PREFIX: &str = "lsp-panel:";
+
 impl Editor {
     /// Handle the LspRestart action.
     ///
@@ -20,14 +29,17 @@ impl Editor {
             return;
         };
         let language = state.language.clone();
+        self.restart_lsp_server(&language);
+    }
 
-        // Attempt restart
+    /// Restart (or start) the LSP server for `language` and re-open its buffers.
+    fn restart_lsp_server(&mut self, language: &str) {
         let Some(lsp) = self.lsp.as_mut() else {
             self.set_status_message(t!("lsp.no_manager").to_string());
             return;
         };
 
-        let (success, message) = lsp.manual_restart(&language);
+        let (success, message) = lsp.manual_restart(language);
         self.status_message = Some(message);
 
         if !success {
@@ -35,20 +47,27 @@ impl Editor {
         }
 
         // Re-send didOpen for all buffers of this language
-        self.reopen_buffers_for_language(&language);
+        self.reopen_buffers_for_language(language);
     }
 
-    /// Re-send didOpen notifications for all buffers of a given language.
+    /// Re-send didOpen notifications for all buffers served by a server.
     ///
-    /// Called after LSP server restart to re-register open files.
-    fn reopen_buffers_for_language(&mut self, language: &str) {
+    /// Called after LSP server restart to re-register open files. `server_id`
+    /// is a language ID for primary servers.
+    fn reopen_buffers_for_language(&mut self, server_id: &str) {
+        let languages = self
+            .lsp
+            .as_ref()
+            .map(|lsp| lsp.languages_for_server(server_id))
+            .unwrap_or_default();
+
         // Collect buffer info first to avoid borrow conflicts
         // Use buffer's stored language rather than detecting from path
         let buffers_for_language: Vec<_> = self
             .buffers
             .iter()
             .filter_map(|(buf_id, state)| {
-                if state.language == language {
+                if languages.contains(&state.language) {
                     self.buffer_metadata
                         .get(buf_id)
                         .and_then(|meta| meta.file_path().map(|p| (*buf_id, p.clone())))
@@ -79,8 +98,8 @@ impl Editor {
             if let Some(lsp) = self.lsp.as_mut() {
                 // Respect auto_start setting for this user action
                 use crate::services::lsp::manager::LspSpawnResult;
-                if lsp.try_spawn(&lang_id) == LspSpawnResult::Spawned {
-                    if let Some(handle) = lsp.get_handle_mut(&lang_id) {
+                if lsp.try_spawn(server_id) == LspSpawnResult::Spawned {
+                    if let Some(handle) = lsp.get_handle_mut(server_id) {
                         let _ = handle.did_open(uri, content, lang_id);
                     }
                 }
@@ -146,4 +165,177 @@ impl Editor {
             }
         }
     }
+
+    /// Show the LSP status panel.
+    ///
+    /// Lists every known language server with its state, open buffers,
+    /// memory and CPU usage (where available) and pending requests.
+    /// Selecting a server offers to restart it, stop it or view its stderr log.
+    pub fn show_lsp_status_panel(&mut self) {
+        let Some(mut languages) = self.lsp.as_ref().map(|lsp| lsp.running_servers()) else {
+            self.set_status_message(t!("lsp.no_manager").to_string());
+            return;
+        };
+        languages.extend(self.lsp_server_statuses.keys().cloned());
+        languages.sort();
+        languages.dedup();
+
+        if languages.is_empty() {
+            self.set_status_message(t!("lsp.no_servers_running").to_string());
+            return;
+        }
+
+        let Some(lsp) = self.lsp.as_ref() else {
+            return;
+        };
+        let items = languages
+            .iter()
+            .map(|language| {
+                let state = if lsp.is_in_cooldown(language) {
+                    t!("lsp.panel.state_crashed")
+                } else if lsp.has_pending_restart(language) {
+                    t!("lsp.panel.state_restarting")
+                } else {
+                    match self.lsp_server_statuses.get(language) {
+                        Some(LspServerStatus::Starting) => t!("lsp.panel.state_starting"),
+                        Some(LspServerStatus::Initializing) => {
+                            t!("lsp.panel.state_initializing")
+                        }
+                        Some(LspServerStatus::Running) => t!("lsp.panel.state_running"),
+                        Some(LspServerStatus::Error) => t!("lsp.panel.state_error"),
+                        Some(LspServerStatus::Shutdown) | None => t!("lsp.panel.state_stopped"),
+                    }
+                };
+
+                let buffer_count = self
+                    .buffers
+                    .values()
+                    .filter(|state| &state.language == language)
+                    .count();
+                let mut details = vec![t!("lsp.panel.buffers", count = buffer_count).to_string()];
+
+                if let Some(handle) = lsp.get_handle(language) {
+                    if let Some(usage) = handle
+                        .process_id()
+                        .and_then(|pid| SystemResources::process_usage(pid).ok())
+                    {
+                        details.push(super::file_open::format_size(usage.rss_bytes));
+                        details.push(
+                            t!(
+                                "lsp.panel.cpu",
+                                seconds = format!("{:.1}", usage.cpu_time.as_secs_f64())
+                            )
+                            .to_string(),
+                        );
+                    }
+                    details.push(
+                        t!("lsp.panel.pending", count = handle.pending_request_count()).to_string(),
+                    );
+                }
+
+                let command = lsp
+                    .get_config(language)
+                    .map(|c| c.command.clone())
+                    .filter(|c| !c.is_empty())
+                    .unwrap_or_else(|| language.clone());
+
+                PopupListItemData {
+                    text: format!("{} ({}) \u{2014} {}", language, command, state),
+                    detail: Some(details.join(" \u{00b7} ")),
+                    icon: None,
+                    data: Some(format!("{}server:{}", LSP_PANEL_DATA_PREFIX, language)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let max_height = (items.len() as u16 + 2).min(20);
+        self.show_popup(PopupData {
+            title: Some(t!("lsp.panel.title").to_string()),
+            description: None,
+            transient: false,
+            content: PopupContentData::List { items, selected: 0 },
+            position: PopupPositionData::Centered,
+            width: 70,
+            max_height,
+            bordered: true,
+        });
+    }
+
+    /// Show the actions available for one server in the LSP status panel.
+    fn show_lsp_server_actions(&mut self, language: &str) {
+        let item = |text: String, detail: String, command: &str| PopupListItemData {
+            text,
+            detail: Some(detail),
+            icon: None,
+            data: Some(format!("{}{}:{}", LSP_PANEL_DATA_PREFIX, command, language)),
+        };
+
+        let items = vec![
+            item(
+                t!("lsp.panel.restart").to_string(),
+                t!("lsp.panel.restart_desc").to_string(),
+                "restart",
+            ),
+            item(
+                t!("lsp.panel.stop").to_string(),
+                t!("lsp.panel.stop_desc").to_string(),
+                "stop",
+            ),
+            item(
+                t!("lsp.panel.view_log").to_string(),
+                t!("lsp.panel.view_log_desc").to_string(),
+                "log",
+            ),
+        ];
+
+        self.show_popup(PopupData {
+            title: Some(t!("lsp.panel.actions_title", language = language).to_string()),
+            description: None,
+            transient: false,
+            content: PopupContentData::List { items, selected: 0 },
+            position: PopupPositionData::Centered,
+            width: 50,
+            max_height: 5,
+            bordered: true,
+        });
+    }
+
+    /// Handle a confirmed LSP status panel item (data without the prefix).
+    pub(super) fn handle_lsp_status_panel_confirm(&mut self, data: &str) {
+        let Some((command, language)) = data.split_once(':') else {
+            return;
+        };
+
+        match command {
+            "server" => self.show_lsp_server_actions(language),
+            "restart" => self.restart_lsp_server(language),
+            "stop" => self.handle_stop_lsp_server(language),
+            "log" => self.open_lsp_stderr_log(language),
+            _ => {}
+        }
+    }
+
+    /// Open the stderr log of a language server as a read-only buffer.
+    fn open_lsp_stderr_log(&mut self, language: &str) {
+        let log_path = crate::services::log_dirs::lsp_log_path(language);
+        if !log_path.exists() {
+            self.set_status_message(t!("lsp.panel.no_log", language = language).to_string());
+            return;
+        }
+
+        // Use open_local_file since log files are always local
+        match self.open_local_file(&log_path) {
+            Ok(buffer_id) => {
+                if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                    state.editing_disabled = true;
+                }
+                if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
+                    metadata.read_only = true;
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to open LSP stderr log: {}", e);
+            }
+        }
+    }
 }
@@ -9,9 +9,11 @@
 //! - Code actions
 //! - Rename operations
 //! - Inlay hints
+//! - Document highlights and code lenses
 
 use anyhow::Result as AnyhowResult;
 use rust_i18n::t;
+use std::collections::HashMap;
 use std::io;
 use std::time::{Duration, Instant};
 
@@ -19,13 +21,17 @@ use lsp_types::TextDocumentContentChangeEvent;
 
 use crate::model::event::{BufferId, Event};
 use crate::primitives::word_navigation::{find_word_end, find_word_start};
+use crate::services::lsp::manager::LspFeature;
 use crate::view::prompt::{Prompt, PromptType};
 
+use super::quickfix::QuickfixItem;
 use super::{uri_to_path, Editor, SemanticTokenRangeRequest};
 
 const SEMANTIC_TOKENS_FULL_DEBOUNCE_MS: u64 = 500;
 const SEMANTIC_TOKENS_RANGE_DEBOUNCE_MS: u64 = 50;
 const SEMANTIC_TOKENS_RANGE_PADDING_LINES: usize = 10;
+const CODE_LENS_DEBOUNCE_MS: u64 = 500;
+const CODE_LENS_NAMESPACE: &str = "lsp-code-lens";
 
 impl Editor {
     /// Handle LSP completion response
@@ -34,23 +40,39 @@ impl Editor {
         request_id: u64,
         items: Vec<lsp_types::CompletionItem>,
     ) -> AnyhowResult<()> {
-        // Check if this is the pending completion request
-        if self.pending_completion_request != Some(request_id) {
+        // Check if this belongs to the pending completion round
+        let Some(server_id) = self.pending_completion_sources.remove(&request_id) else {
             tracing::debug!(
                 "Ignoring completion response for outdated request {}",
                 request_id
             );
             return Ok(());
+        };
+        self.pending_completion_items
+            .extend(items.into_iter().map(|item| (server_id.clone(), item)));
+
+        // Wait until every server of the round has answered
+        if !self.pending_completion_sources.is_empty() {
+            return Ok(());
         }
 
         self.pending_completion_request = None;
         self.lsp_status.clear();
+        let items = self.merge_completion_items();
 
         if items.is_empty() {
             tracing::debug!("No completion items received");
             return Ok(());
         }
+        self.show_completion_popup(items)
+    }
 
+    /// Show completion items in a popup below the cursor, filtered by the
+    /// partial word before the cursor
+    pub(crate) fn show_completion_popup(
+        &mut self,
+        items: Vec<lsp_types::CompletionItem>,
+    ) -> AnyhowResult<()> {
         // Get the partial word at cursor to filter completions
         use crate::primitives::word_navigation::find_completion_word_start;
         let (word_start, cursor_pos) = {
@@ -175,6 +197,11 @@ impl Editor {
         request_id: u64,
         locations: Vec<lsp_types::Location>,
     ) -> AnyhowResult<()> {
+        if let Some(kind) = self.take_pending_peek_request(request_id) {
+            self.show_peek_window(kind, locations);
+            return Ok(());
+        }
+
         // Check if this is the pending request
         if self.pending_goto_definition_request != Some(request_id) {
             tracing::debug!(
@@ -196,77 +223,89 @@ impl Editor {
 
         // Convert URI to file path
         if let Ok(path) = uri_to_path(&location.uri) {
-            // Open the file
-            let buffer_id = match self.open_file(&path) {
-                Ok(id) => id,
-                Err(e) => {
-                    // Check if this is a large file encoding confirmation error
-                    if let Some(confirmation) =
-                        e.downcast_ref::<crate::model::buffer::LargeFileEncodingConfirmation>()
-                    {
-                        self.start_large_file_encoding_confirmation(confirmation);
-                    } else {
-                        self.set_status_message(
-                            t!("file.error_opening", error = e.to_string()).to_string(),
-                        );
-                    }
-                    return Ok(());
-                }
-            };
+            self.open_definition_location(
+                &path,
+                location.range.start.line as usize,
+                location.range.start.character as usize,
+            );
+        } else {
+            self.status_message = Some(t!("lsp.cannot_open_definition").to_string());
+        }
 
-            // Check if file is outside project root (library file)
-            let is_library_file = self.is_library_file(&path);
-            if is_library_file {
-                // Mark as read-only
-                if let Some(state) = self.buffers.get_mut(&buffer_id) {
-                    state.editing_disabled = true;
-                }
-                if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
-                    metadata.read_only = true;
+        Ok(())
+    }
+
+    /// Open a file and move the cursor to a definition/reference position.
+    ///
+    /// Files outside the project (libraries) are opened read-only.
+    pub(crate) fn open_definition_location(
+        &mut self,
+        path: &std::path::Path,
+        line: usize,
+        character: usize,
+    ) {
+        // Open the file
+        let buffer_id = match self.open_file(path) {
+            Ok(id) => id,
+            Err(e) => {
+                // Check if this is a large file encoding confirmation error
+                if let Some(confirmation) =
+                    e.downcast_ref::<crate::model::buffer::LargeFileEncodingConfirmation>()
+                {
+                    self.start_large_file_encoding_confirmation(confirmation);
+                } else {
+                    self.set_status_message(
+                        t!("file.error_opening", error = e.to_string()).to_string(),
+                    );
                 }
+                return;
             }
+        };
 
-            // Move cursor to the definition position
-            let line = location.range.start.line as usize;
-            let character = location.range.start.character as usize;
-
-            // Calculate byte position from line and character
-            if let Some(state) = self.buffers.get(&buffer_id) {
-                let position = state.buffer.line_col_to_position(line, character);
+        // Check if file is outside project root (library file)
+        let is_library_file = self.is_library_file(path);
+        if is_library_file {
+            // Mark as read-only
+            if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                state.editing_disabled = true;
+            }
+            if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
+                metadata.read_only = true;
+            }
+        }
 
-                // Move cursor
-                let cursor_id = state.cursors.primary_id();
-                let old_position = state.cursors.primary().position;
-                let old_anchor = state.cursors.primary().anchor;
-                let old_sticky_column = state.cursors.primary().sticky_column;
-                let event = crate::model::event::Event::MoveCursor {
-                    cursor_id,
-                    old_position,
-                    new_position: position,
-                    old_anchor,
-                    new_anchor: None,
-                    old_sticky_column,
-                    new_sticky_column: 0, // Reset sticky column for goto definition
-                };
+        // Calculate byte position from line and character
+        if let Some(state) = self.buffers.get(&buffer_id) {
+            let position = state.buffer.line_col_to_position(line, character);
+
+            // Move cursor
+            let cursor_id = state.cursors.primary_id();
+            let old_position = state.cursors.primary().position;
+            let old_anchor = state.cursors.primary().anchor;
+            let old_sticky_column = state.cursors.primary().sticky_column;
+            let event = crate::model::event::Event::MoveCursor {
+                cursor_id,
+                old_position,
+                new_position: position,
+                old_anchor,
+                new_anchor: None,
+                old_sticky_column,
+                new_sticky_column: 0, // Reset sticky column for goto definition
+            };
 
-                if let Some(state) = self.buffers.get_mut(&buffer_id) {
-                    state.apply(&event);
-                }
+            if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                state.apply(&event);
             }
-
-            self.status_message = Some(
-                t!(
-                    "lsp.jumped_to_definition",
-                    path = path.display().to_string(),
-                    line = line + 1
-                )
-                .to_string(),
-            );
-        } else {
-            self.status_message = Some(t!("lsp.cannot_open_definition").to_string());
         }
 
-        Ok(())
+        self.status_message = Some(
+            t!(
+                "lsp.jumped_to_definition",
+                path = path.display().to_string(),
+                line = line + 1
+            )
+            .to_string(),
+        );
     }
 
     /// Check if a file path is a library file (outside project root or in common library directories).
@@ -275,6 +314,39 @@ impl Editor {
         super::types::BufferMetadata::is_library_path(path, &self.working_dir)
     }
 
+    /// Take the completion items of the finished round
+    ///
+    /// When more than one server contributed, each item's detail is prefixed
+    /// with the server it came from.
+    fn merge_completion_items(&mut self) -> Vec<lsp_types::CompletionItem> {
+        let collected = std::mem::take(&mut self.pending_completion_items);
+        let first_source = collected.first().map(|(id, _)| id.clone());
+        let multiple_sources = collected
+            .iter()
+            .any(|(id, _)| Some(id) != first_source.as_ref());
+        if !multiple_sources {
+            return collected.into_iter().map(|(_, item)| item).collect();
+        }
+
+        let mut labels: HashMap<String, String> = HashMap::new();
+        collected
+            .into_iter()
+            .map(|(server_id, mut item)| {
+                let label = labels.entry(server_id).or_insert_with_key(|id| {
+                    self.lsp
+                        .as_ref()
+                        .map(|lsp| lsp.server_label(id))
+                        .unwrap_or_else(|| id.clone())
+                });
+                item.detail = Some(match item.detail {
+                    Some(detail) => format!("[{}] {}", label, detail),
+                    None => format!("[{}]", label),
+                });
+                item
+            })
+            .collect()
+    }
+
     /// Check if there are any pending LSP requests
     pub fn has_pending_lsp_requests(&self) -> bool {
         self.pending_completion_request.is_some() || self.pending_goto_definition_request.is_some()
@@ -286,8 +358,11 @@ impl Editor {
     pub(crate) fn cancel_pending_lsp_requests(&mut self) {
         if let Some(request_id) = self.pending_completion_request.take() {
             tracing::debug!("Canceling pending LSP completion request {}", request_id);
-            // Send cancellation to the LSP server
-            self.send_lsp_cancel_request(request_id);
+            // Send cancellation to every server of the round
+            for (request_id, server_id) in std::mem::take(&mut self.pending_completion_sources) {
+                self.send_lsp_cancel_request_to(&server_id, request_id);
+            }
+            self.pending_completion_items.clear();
             self.lsp_status.clear();
         }
         if let Some(request_id) = self.pending_goto_definition_request.take() {
@@ -299,6 +374,11 @@ impl Editor {
             self.send_lsp_cancel_request(request_id);
             self.lsp_status.clear();
         }
+        if let Some((request_id, _)) = self.pending_peek_request.take() {
+            tracing::debug!("Canceling pending LSP peek request {}", request_id);
+            self.send_lsp_cancel_request(request_id);
+            self.lsp_status.clear();
+        }
     }
 
     /// Send a cancel request to the LSP server for a specific request ID
@@ -309,9 +389,14 @@ impl Editor {
             return;
         };
 
+        self.send_lsp_cancel_request_to(&language, request_id);
+    }
+
+    /// Send a cancel request to a specific server
+    fn send_lsp_cancel_request_to(&mut self, server_id: &str, request_id: u64) {
         if let Some(lsp) = self.lsp.as_mut() {
             // Only send cancel if LSP is already running (no need to spawn just to cancel)
-            if let Some(handle) = lsp.get_handle_mut(&language) {
+            if let Some(handle) = lsp.get_handle_mut(server_id) {
                 if let Err(e) = handle.cancel_request(request_id) {
                     tracing::warn!("Failed to send LSP cancel request: {}", e);
                 } else {
@@ -326,28 +411,51 @@ impl Editor {
     /// This helper centralizes the logic for:
     /// 1. Getting buffer metadata, URI, and language
     /// 2. Checking if LSP can be spawned (respects auto_start setting)
-    /// 3. Ensuring didOpen was sent to this server instance (lazy - only gets text if needed)
-    /// 4. Calling the provided closure with the handle
+    /// 3. Picking the server of the language that supports `feature`
+    /// 4. Ensuring didOpen was sent to this server instance (lazy - only gets text if needed)
+    /// 5. Calling the provided closure with the handle
     ///
     /// Returns None if any step fails (no file, no language, LSP disabled, auto_start=false, etc.)
     /// Note: This respects the auto_start setting. If auto_start is false and the server
     /// hasn't been manually started, this will return None without spawning the server.
-    pub(crate) fn with_lsp_for_buffer<F, R>(&mut self, buffer_id: BufferId, f: F) -> Option<R>
+    pub(crate) fn with_lsp_for_buffer<F, R>(
+        &mut self,
+        buffer_id: BufferId,
+        feature: LspFeature,
+        f: F,
+    ) -> Option<R>
     where
         F: FnOnce(&crate::services::lsp::async_handler::LspHandle, &lsp_types::Uri, &str) -> R,
     {
+        let server_id = self
+            .lsp_servers_for_buffer(buffer_id, feature)?
+            .into_iter()
+            .next()?;
+        let (uri, language) = self.ensure_lsp_did_open(buffer_id, &server_id)?;
+
+        // Call the closure with the handle
+        let lsp = self.lsp.as_mut()?;
+        let handle = lsp.get_handle_mut(&server_id)?;
+        Some(f(handle, &uri, &language))
+    }
+
+    /// Spawn the servers of a buffer's language and return the IDs of those
+    /// supporting `feature`, primary server first.
+    ///
+    /// Returns None if the buffer has no LSP or the primary server can't be
+    /// started (respects the auto_start setting).
+    pub(crate) fn lsp_servers_for_buffer(
+        &mut self,
+        buffer_id: BufferId,
+        feature: LspFeature,
+    ) -> Option<Vec<String>> {
         use crate::services::lsp::manager::LspSpawnResult;
 
-        // Get metadata and language from buffer state
-        let (uri, language) = {
-            let metadata = self.buffer_metadata.get(&buffer_id)?;
-            if !metadata.lsp_enabled {
-                return None;
-            }
-            let uri = metadata.file_uri()?.clone();
-            let language = self.buffers.get(&buffer_id)?.language.clone();
-            (uri, language)
-        };
+        let metadata = self.buffer_metadata.get(&buffer_id)?;
+        if !metadata.lsp_enabled || metadata.file_uri().is_none() {
+            return None;
+        }
+        let language = self.buffers.get(&buffer_id)?.language.clone();
 
         // Try to spawn LSP (respects auto_start setting)
         // This will only spawn if auto_start=true or the language was manually allowed
@@ -355,9 +463,31 @@ impl Editor {
         if lsp.try_spawn(&language) != LspSpawnResult::Spawned {
             return None;
         }
+        for server_id in lsp.server_ids(&language).into_iter().skip(1) {
+            lsp.try_spawn(&server_id);
+        }
+
+        Some(lsp.servers_for_feature(&language, feature))
+    }
+
+    /// Ensure didOpen was sent for a buffer to a running server
+    ///
+    /// Returns the buffer's URI and language. The language (not the server
+    /// ID) is always used as the document's languageId.
+    pub(crate) fn ensure_lsp_did_open(
+        &mut self,
+        buffer_id: BufferId,
+        server_id: &str,
+    ) -> Option<(lsp_types::Uri, String)> {
+        let (uri, language) = {
+            let metadata = self.buffer_metadata.get(&buffer_id)?;
+            let uri = metadata.file_uri()?.clone();
+            let language = self.buffers.get(&buffer_id)?.language.clone();
+            (uri, language)
+        };
 
-        // Get handle ID (handle exists since try_spawn succeeded)
-        let handle_id = lsp.get_handle_mut(&language)?.id();
+        // Get handle ID (the server must already be running)
+        let handle_id = self.lsp.as_mut()?.get_handle_mut(server_id)?.id();
 
         // Check if didOpen is needed
         let needs_open = {
@@ -371,7 +501,7 @@ impl Editor {
 
             // Send didOpen
             let lsp = self.lsp.as_mut()?;
-            let handle = lsp.get_handle_mut(&language)?;
+            let handle = lsp.get_handle_mut(server_id)?;
             if let Err(e) = handle.did_open(uri.clone(), text, language.clone()) {
                 tracing::warn!("Failed to send didOpen: {}", e);
                 return None;
@@ -382,20 +512,21 @@ impl Editor {
             metadata.lsp_opened_with.insert(handle_id);
 
             tracing::debug!(
-                "Sent didOpen for {} to LSP handle {} (language: {})",
+                "Sent didOpen for {} to LSP handle {} (server: {}, language: {})",
                 uri.as_str(),
                 handle_id,
+                server_id,
                 language
             );
         }
 
-        // Call the closure with the handle
-        let lsp = self.lsp.as_mut()?;
-        let handle = lsp.get_handle_mut(&language)?;
-        Some(f(handle, &uri, &language))
+        Some((uri, language))
     }
 
     /// Request LSP completion at current cursor position
+    ///
+    /// Without a language server providing completions, words from the open
+    /// buffers are offered instead (`editor.word_completion`).
     pub(crate) fn request_completion(&mut self) -> AnyhowResult<()> {
         // Get the current buffer and cursor position
         let state = self.active_state();
@@ -404,29 +535,49 @@ impl Editor {
         // Convert byte position to LSP position (line, UTF-16 code units)
         let (line, character) = state.buffer.position_to_lsp_position(cursor_pos);
         let buffer_id = self.active_buffer();
-        let request_id = self.next_lsp_request_id;
 
-        // Use helper to ensure didOpen is sent before the request
-        let sent = self
-            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
-                let result =
-                    handle.completion(request_id, uri.clone(), line as u32, character as u32);
-                if result.is_ok() {
-                    tracing::info!(
-                        "Requested completion at {}:{}:{}",
-                        uri.as_str(),
-                        line,
-                        character
-                    );
-                }
-                result.is_ok()
-            })
-            .unwrap_or(false);
+        // Ask every server of the language that provides completions
+        let server_ids = self
+            .lsp_servers_for_buffer(buffer_id, LspFeature::Completion)
+            .unwrap_or_default();
+        let mut sources = HashMap::new();
+        for server_id in server_ids {
+            let request_id = self.next_lsp_request_id;
+            // Ensure didOpen is sent before the request
+            let Some((uri, _language)) = self.ensure_lsp_did_open(buffer_id, &server_id) else {
+                continue;
+            };
+            let Some(handle) = self
+                .lsp
+                .as_mut()
+                .and_then(|lsp| lsp.get_handle_mut(&server_id))
+            else {
+                continue;
+            };
+            if handle
+                .completion(request_id, uri.clone(), line as u32, character as u32)
+                .is_ok()
+            {
+                tracing::info!(
+                    "Requested completion at {}:{}:{} from {}",
+                    uri.as_str(),
+                    line,
+                    character,
+                    server_id
+                );
+                self.next_lsp_request_id += 1;
+                sources.insert(request_id, server_id);
+            }
+        }
 
-        if sent {
-            self.next_lsp_request_id += 1;
+        if let Some(&request_id) = sources.keys().min() {
             self.pending_completion_request = Some(request_id);
+            self.pending_completion_sources = sources;
+            self.pending_completion_items.clear();
             self.lsp_status = "LSP: completion...".to_string();
+        } else if self.config.editor.word_completion {
+            // No language server to ask: complete words instead
+            return self.show_word_completion();
         }
 
         Ok(())
@@ -501,19 +652,27 @@ impl Editor {
 
         // Use helper to ensure didOpen is sent before the request
         let sent = self
-            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
-                let result =
-                    handle.goto_definition(request_id, uri.clone(), line as u32, character as u32);
-                if result.is_ok() {
-                    tracing::info!(
-                        "Requested go-to-definition at {}:{}:{}",
-                        uri.as_str(),
-                        line,
-                        character
+            .with_lsp_for_buffer(
+                buffer_id,
+                LspFeature::Definition,
+                |handle, uri, _language| {
+                    let result = handle.goto_definition(
+                        request_id,
+                        uri.clone(),
+                        line as u32,
+                        character as u32,
                     );
-                }
-                result.is_ok()
-            })
+                    if result.is_ok() {
+                        tracing::info!(
+                            "Requested go-to-definition at {}:{}:{}",
+                            uri.as_str(),
+                            line,
+                            character
+                        );
+                    }
+                    result.is_ok()
+                },
+            )
             .unwrap_or(false);
 
         if sent {
@@ -549,7 +708,7 @@ impl Editor {
 
         // Use helper to ensure didOpen is sent before the request
         let sent = self
-            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
+            .with_lsp_for_buffer(buffer_id, LspFeature::Hover, |handle, uri, _language| {
                 let result = handle.hover(request_id, uri.clone(), line as u32, character as u32);
                 if result.is_ok() {
                     tracing::info!(
@@ -598,7 +757,7 @@ impl Editor {
 
         // Use helper to ensure didOpen is sent before the request
         let sent = self
-            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
+            .with_lsp_for_buffer(buffer_id, LspFeature::Hover, |handle, uri, _language| {
                 let result = handle.hover(request_id, uri.clone(), line as u32, character as u32);
                 if result.is_ok() {
                     tracing::trace!(
@@ -766,8 +925,8 @@ impl Editor {
         use crate::view::virtual_text::VirtualTextPosition;
         use ratatui::style::{Color, Style};
 
-        // Clear existing inlay hints
-        state.virtual_texts.clear(&mut state.marker_list);
+        // Clear existing inlay hints (virtual lines such as code lenses stay)
+        state.virtual_texts.clear_inline(&mut state.marker_list);
 
         if hints.is_empty() {
             return;
@@ -826,6 +985,62 @@ impl Editor {
         tracing::debug!("Applied {} inlay hints as virtual text", hints.len());
     }
 
+    /// Apply resolved code lenses to editor state as virtual lines
+    ///
+    /// Lenses on the same line are joined ("3 references | Run | Debug") and
+    /// rendered above it, indented to match the code.
+    pub(crate) fn apply_code_lenses_to_state(
+        state: &mut crate::state::EditorState,
+        lenses: &[lsp_types::CodeLens],
+        style: ratatui::style::Style,
+    ) {
+        use crate::view::virtual_text::{VirtualTextNamespace, VirtualTextPosition};
+
+        let ns = VirtualTextNamespace::from_string(CODE_LENS_NAMESPACE.to_string());
+        state
+            .virtual_texts
+            .clear_namespace(&mut state.marker_list, &ns);
+
+        let mut titles_by_line: std::collections::BTreeMap<u32, Vec<&str>> =
+            std::collections::BTreeMap::new();
+        for lens in lenses {
+            let Some(command) = lens.command.as_ref() else {
+                continue;
+            };
+            let title = command.title.trim();
+            if !title.is_empty() {
+                titles_by_line
+                    .entry(lens.range.start.line)
+                    .or_default()
+                    .push(title);
+            }
+        }
+
+        for (line, titles) in titles_by_line {
+            let Some(line_start) = state.buffer.line_start_offset(line as usize) else {
+                continue;
+            };
+            let head = state
+                .buffer
+                .slice_bytes(line_start..state.buffer.len().min(line_start + 256));
+            let indent_len = head
+                .iter()
+                .take_while(|&&b| b == b' ' || b == b'\t')
+                .count();
+            let indent = String::from_utf8_lossy(&head[..indent_len]);
+
+            state.virtual_texts.add_line(
+                &mut state.marker_list,
+                line_start,
+                format!("{}{}", indent, titles.join(" | ")),
+                style,
+                VirtualTextPosition::LineAbove,
+                ns.clone(),
+                0,
+            );
+        }
+    }
+
     /// Request LSP find references at current cursor position
     pub(crate) fn request_references(&mut self) -> AnyhowResult<()> {
         // Get the current buffer and cursor position
@@ -899,20 +1114,24 @@ impl Editor {
 
         // Use helper to ensure didOpen is sent before the request
         let sent = self
-            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
-                let result =
-                    handle.references(request_id, uri.clone(), line as u32, character as u32);
-                if result.is_ok() {
-                    tracing::info!(
-                        "Requested find references at {}:{}:{} (byte_pos={})",
-                        uri.as_str(),
-                        line,
-                        character,
-                        cursor_pos
-                    );
-                }
-                result.is_ok()
-            })
+            .with_lsp_for_buffer(
+                buffer_id,
+                LspFeature::References,
+                |handle, uri, _language| {
+                    let result =
+                        handle.references(request_id, uri.clone(), line as u32, character as u32);
+                    if result.is_ok() {
+                        tracing::info!(
+                            "Requested find references at {}:{}:{} (byte_pos={})",
+                            uri.as_str(),
+                            line,
+                            character,
+                            cursor_pos
+                        );
+                    }
+                    result.is_ok()
+                },
+            )
             .unwrap_or(false);
 
         if sent {
@@ -938,20 +1157,28 @@ impl Editor {
 
         // Use helper to ensure didOpen is sent before the request
         let sent = self
-            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
-                let result =
-                    handle.signature_help(request_id, uri.clone(), line as u32, character as u32);
-                if result.is_ok() {
-                    tracing::info!(
-                        "Requested signature help at {}:{}:{} (byte_pos={})",
-                        uri.as_str(),
-                        line,
-                        character,
-                        cursor_pos
+            .with_lsp_for_buffer(
+                buffer_id,
+                LspFeature::SignatureHelp,
+                |handle, uri, _language| {
+                    let result = handle.signature_help(
+                        request_id,
+                        uri.clone(),
+                        line as u32,
+                        character as u32,
                     );
-                }
-                result.is_ok()
-            })
+                    if result.is_ok() {
+                        tracing::info!(
+                            "Requested signature help at {}:{}:{} (byte_pos={})",
+                            uri.as_str(),
+                            line,
+                            character,
+                            cursor_pos
+                        );
+                    }
+                    result.is_ok()
+                },
+            )
             .unwrap_or(false);
 
         if sent {
@@ -1106,29 +1333,33 @@ impl Editor {
 
         // Use helper to ensure didOpen is sent before the request
         let sent = self
-            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
-                let result = handle.code_actions(
-                    request_id,
-                    uri.clone(),
-                    start_line,
-                    start_char,
-                    end_line,
-                    end_char,
-                    diagnostics,
-                );
-                if result.is_ok() {
-                    tracing::info!(
-                        "Requested code actions at {}:{}:{}-{}:{} (byte_pos={})",
-                        uri.as_str(),
+            .with_lsp_for_buffer(
+                buffer_id,
+                LspFeature::CodeAction,
+                |handle, uri, _language| {
+                    let result = handle.code_actions(
+                        request_id,
+                        uri.clone(),
                         start_line,
                         start_char,
                         end_line,
                         end_char,
-                        cursor_pos
+                        diagnostics,
                     );
-                }
-                result.is_ok()
-            })
+                    if result.is_ok() {
+                        tracing::info!(
+                            "Requested code actions at {}:{}:{}-{}:{} (byte_pos={})",
+                            uri.as_str(),
+                            start_line,
+                            start_char,
+                            end_line,
+                            end_char,
+                            cursor_pos
+                        );
+                    }
+                    result.is_ok()
+                },
+            )
             .unwrap_or(false);
 
         if sent {
@@ -1213,6 +1444,11 @@ impl Editor {
             request_id
         );
 
+        if let Some(kind) = self.take_pending_peek_request(request_id) {
+            self.show_peek_window(kind, locations);
+            return Ok(());
+        }
+
         // Check if this response is for the current pending request
         if self.pending_references_request != Some(request_id) {
             tracing::debug!("Ignoring stale references response: {}", request_id);
@@ -1253,6 +1489,24 @@ impl Editor {
             t!("lsp.found_references", count = count, symbol = &symbol).to_string(),
         );
 
+        // The references also become the newest quickfix list
+        let items = locations
+            .iter()
+            .filter_map(|loc| {
+                Some(QuickfixItem {
+                    path: uri_to_path(&loc.uri).ok()?,
+                    line: loc.range.start.line as usize + 1,
+                    column: loc.range.start.character as usize + 1,
+                    text: String::new(),
+                })
+            })
+            .collect();
+        self.set_quickfix_list(
+            t!("quickfix.references_title", symbol = &symbol).to_string(),
+            items,
+            None,
+        );
+
         // Fire the lsp_references hook so plugins can display the results
         self.plugin_manager.run_hook(
             "lsp_references",
@@ -1725,66 +1979,34 @@ impl Editor {
             return;
         }
 
-        // Get handle ID (handle exists since try_spawn succeeded)
-        let Some(handle) = lsp.get_handle_mut(&language) else {
-            return;
-        };
-        let handle_id = handle.id();
-
-        // Check if didOpen needs to be sent first
-        let needs_open = {
-            let Some(metadata) = self.buffer_metadata.get(&buffer_id) else {
-                return;
-            };
-            !metadata.lsp_opened_with.contains(&handle_id)
-        };
-
-        if needs_open {
-            // Get text for didOpen
-            let text = match self
-                .buffers
-                .get(&buffer_id)
-                .and_then(|s| s.buffer.to_string())
-            {
-                Some(t) => t,
-                None => {
-                    tracing::debug!(
-                        "send_lsp_changes_for_buffer: buffer text not available for didOpen"
-                    );
-                    return;
-                }
-            };
-
-            // Send didOpen first
+        // Every server of the language gets the change, not just the primary
+        let server_ids = lsp.server_ids(&language);
+        for server_id in server_ids {
             let Some(lsp) = self.lsp.as_mut() else { return };
-            let Some(handle) = lsp.get_handle_mut(&language) else {
-                return;
-            };
-            if let Err(e) = handle.did_open(uri.clone(), text, language.clone()) {
-                tracing::warn!("Failed to send didOpen before didChange: {}", e);
-                return;
+            if lsp.try_spawn(&server_id) != LspSpawnResult::Spawned {
+                continue;
             }
-            tracing::debug!(
-                "Sent didOpen for {} to LSP handle {} before didChange",
-                uri.as_str(),
-                handle_id
-            );
 
-            // Mark as opened
-            if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
-                metadata.lsp_opened_with.insert(handle_id);
+            // Send didOpen first if this server hasn't seen the buffer yet
+            if self.ensure_lsp_did_open(buffer_id, &server_id).is_none() {
+                tracing::warn!(
+                    "Failed to send didOpen to {} before didChange for {}",
+                    server_id,
+                    uri.as_str()
+                );
+                continue;
             }
-        }
 
-        // Now send didChange
-        let Some(lsp) = self.lsp.as_mut() else { return };
-        let Some(client) = lsp.get_handle_mut(&language) else {
-            return;
-        };
-        if let Err(e) = client.did_change(uri, changes) {
-            tracing::warn!("Failed to send didChange to LSP: {}", e);
-        } else {
-            tracing::trace!("Successfully sent batched didChange to LSP");
+            // Now send didChange
+            let Some(lsp) = self.lsp.as_mut() else { return };
+            let Some(client) = lsp.get_handle_mut(&server_id) else {
+                continue;
+            };
+            if let Err(e) = client.did_change(uri.clone(), changes.clone()) {
+                tracing::warn!("Failed to send didChange to LSP {}: {}", server_id, e);
+            } else {
+                tracing::trace!("Successfully sent batched didChange to LSP {}", server_id);
+            }
         }
     }
 
@@ -1877,7 +2099,7 @@ impl Editor {
 
         // Use helper to ensure didOpen is sent before the request
         let sent = self
-            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
+            .with_lsp_for_buffer(buffer_id, LspFeature::Rename, |handle, uri, _language| {
                 let result = handle.rename(
                     request_id,
                     uri.clone(),
@@ -1930,16 +2152,115 @@ impl Editor {
 
         // Use helper to ensure didOpen is sent before the request
         let sent = self
-            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
-                let result = handle.inlay_hints(request_id, uri.clone(), 0, 0, last_line, 10000);
-                if result.is_ok() {
-                    tracing::info!(
-                        "Requested inlay hints for {} (request_id={})",
-                        uri.as_str(),
-                        request_id
+            .with_lsp_for_buffer(
+                buffer_id,
+                LspFeature::InlayHints,
+                |handle, uri, _language| {
+                    let result =
+                        handle.inlay_hints(request_id, uri.clone(), 0, 0, last_line, 10000);
+                    if result.is_ok() {
+                        tracing::info!(
+                            "Requested inlay hints for {} (request_id={})",
+                            uri.as_str(),
+                            request_id
+                        );
+                    } else if let Err(e) = &result {
+                        tracing::debug!("Failed to request inlay hints: {}", e);
+                    }
+                    result.is_ok()
+                },
+            )
+            .unwrap_or(false);
+
+        if sent {
+            self.next_lsp_request_id += 1;
+            self.pending_inlay_hints_request = Some(request_id);
+        }
+    }
+
+    /// Request LSP document highlights once the word-based occurrence
+    /// highlight in the active buffer has settled.
+    pub(crate) fn maybe_request_document_highlight(&mut self) {
+        let buffer_id = self.active_buffer();
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let Some(position) = state
+            .reference_highlight_overlay
+            .take_document_highlight_request()
+        else {
+            return;
+        };
+        if !self.config.editor.enable_document_highlight {
+            return;
+        }
+
+        let (line, character) = state.buffer.position_to_lsp_position(position);
+        let request_id = self.next_lsp_request_id;
+
+        let sent = self
+            .with_lsp_for_buffer(
+                buffer_id,
+                LspFeature::DocumentHighlight,
+                |handle, uri, _language| {
+                    let result = handle.document_highlight(
+                        request_id,
+                        uri.clone(),
+                        line as u32,
+                        character as u32,
                     );
-                } else if let Err(e) = &result {
-                    tracing::debug!("Failed to request inlay hints: {}", e);
+                    if let Err(e) = &result {
+                        tracing::debug!("Failed to request document highlight: {}", e);
+                    }
+                    result.is_ok()
+                },
+            )
+            .unwrap_or(false);
+
+        if sent {
+            self.next_lsp_request_id += 1;
+            self.pending_document_highlight_request = Some((request_id, buffer_id));
+        }
+    }
+
+    /// Request code lenses for a buffer if enabled and its content changed
+    /// since the last request (debounced while typing).
+    pub(crate) fn maybe_request_code_lens(&mut self, buffer_id: BufferId) {
+        if !self.config.editor.enable_code_lens {
+            // Drop lenses left over from before the setting was turned off
+            if self.code_lens_last_request.remove(&buffer_id).is_some() {
+                if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                    Self::apply_code_lenses_to_state(state, &[], ratatui::style::Style::default());
+                }
+            }
+            return;
+        }
+
+        if self
+            .pending_code_lens_requests
+            .values()
+            .any(|(pending_buffer, _)| *pending_buffer == buffer_id)
+        {
+            return;
+        }
+
+        let Some(version) = self.buffers.get(&buffer_id).map(|s| s.buffer.version()) else {
+            return;
+        };
+        if let Some((last_version, last_time)) = self.code_lens_last_request.get(&buffer_id) {
+            if *last_version == version
+                || last_time.elapsed() < Duration::from_millis(CODE_LENS_DEBOUNCE_MS)
+            {
+                return;
+            }
+        }
+
+        let request_id = self.next_lsp_request_id;
+        let sent = self
+            .with_lsp_for_buffer(buffer_id, LspFeature::CodeLens, |handle, uri, _language| {
+                let result = handle.code_lens(request_id, uri.clone());
+                if let Err(e) = &result {
+                    tracing::debug!("Failed to request code lens: {}", e);
                 }
                 result.is_ok()
             })
@@ -1947,7 +2268,10 @@ impl Editor {
 
         if sent {
             self.next_lsp_request_id += 1;
-            self.pending_inlay_hints_request = Some(request_id);
+            self.pending_code_lens_requests
+                .insert(request_id, (buffer_id, version));
+            self.code_lens_last_request
+                .insert(buffer_id, (version, Instant::now()));
         }
     }
 
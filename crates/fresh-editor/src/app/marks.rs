@@ -0,0 +1,354 @@
+//! Named marks (**Set Named Mark**, **Jump to Mark**)
+//!
+//! Vim-style named positions. A lowercase mark (`a`–`z`) belongs to the
+//! buffer it was set in, so each buffer has its own `a`. An uppercase mark
+//! (`A`–`Z`) is a file mark: there is one of each, and jumping to it opens
+//! its file. Marks are kept as markers in the buffer, so they follow edits
+//! above them. A file mark whose buffer is closed keeps its file and offset,
+//! and file marks are saved with the workspace.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::model::event::{BufferId, Event};
+use crate::model::marker::MarkerId;
+use crate::view::prompt::{Prompt, PromptType};
+use crate::workspace::SerializedBookmark;
+
+/// Where a file mark points
+#[derive(Debug, Clone)]
+pub enum MarkLocation {
+    /// A marker in an open buffer
+    Buffer {
+        buffer_id: BufferId,
+        marker: MarkerId,
+    },
+    /// A byte offset in a file that isn't open
+    File { path: PathBuf, position: usize },
+}
+
+/// The marks of all buffers
+#[derive(Debug, Default)]
+pub struct Marks {
+    /// Lowercase marks of each buffer
+    local: HashMap<BufferId, HashMap<char, MarkerId>>,
+    /// Uppercase file marks
+    global: HashMap<char, MarkLocation>,
+}
+
+/// Whether `name` can name a mark
+fn is_mark_name(name: char) -> bool {
+    name.is_ascii_alphabetic()
+}
+
+impl Editor {
+    /// Mark the primary cursor position as `name`
+    pub fn set_mark(&mut self, name: char) {
+        if !is_mark_name(name) {
+            self.set_status_message(t!("mark.invalid", name = name).to_string());
+            return;
+        }
+        let buffer_id = self.active_buffer();
+        let state = self.active_state_mut();
+        let position = state.cursors.primary().position;
+        let marker = state.marker_list.create(position, true);
+
+        let old = if name.is_ascii_lowercase() {
+            self.marks
+                .local
+                .entry(buffer_id)
+                .or_default()
+                .insert(name, marker)
+                .map(|marker| MarkLocation::Buffer { buffer_id, marker })
+        } else {
+            self.marks
+                .global
+                .insert(name, MarkLocation::Buffer { buffer_id, marker })
+        };
+        if let Some(MarkLocation::Buffer { buffer_id, marker }) = old {
+            if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                state.marker_list.delete(marker);
+            }
+        }
+        self.set_status_message(t!("mark.set", name = name).to_string());
+    }
+
+    /// Move the cursor to mark `name`
+    ///
+    /// With `line_start` the cursor goes to the first non-blank character of
+    /// the mark's line, as Vim's `'a` does.
+    pub fn jump_to_mark(&mut self, name: char, line_start: bool) {
+        if !is_mark_name(name) {
+            self.set_status_message(t!("mark.invalid", name = name).to_string());
+            return;
+        }
+        let location = if name.is_ascii_lowercase() {
+            let buffer_id = self.active_buffer();
+            self.marks
+                .local
+                .get(&buffer_id)
+                .and_then(|marks| marks.get(&name))
+                .map(|&marker| MarkLocation::Buffer { buffer_id, marker })
+        } else {
+            self.marks.global.get(&name).cloned()
+        };
+        let Some(location) = location else {
+            self.set_status_message(t!("mark.not_set", name = name).to_string());
+            return;
+        };
+
+        self.record_position_before_switch();
+        let (buffer_id, position) = match location {
+            MarkLocation::Buffer { buffer_id, marker } => {
+                let Some(position) = self
+                    .buffers
+                    .get(&buffer_id)
+                    .and_then(|state| state.marker_list.get_position(marker))
+                else {
+                    self.set_status_message(t!("mark.not_set", name = name).to_string());
+                    return;
+                };
+                (buffer_id, position)
+            }
+            MarkLocation::File { path, position } => {
+                let buffer_id = match self.open_file(&path) {
+                    Ok(id) => id,
+                    Err(e) => {
+                        let message = t!("file.error_opening", error = e.to_string()).to_string();
+                        self.set_status_message(message);
+                        return;
+                    }
+                };
+                // Follow edits again now that the file is open
+                let state = self.active_state_mut();
+                let position = position.min(state.buffer.len());
+                let marker = state.marker_list.create(position, true);
+                self.marks
+                    .global
+                    .insert(name, MarkLocation::Buffer { buffer_id, marker });
+                (buffer_id, position)
+            }
+        };
+        if buffer_id != self.active_buffer() {
+            self.set_active_buffer(buffer_id);
+        }
+
+        let state = self.active_state_mut();
+        let mut new_pos = position.min(state.buffer.len());
+        if line_start {
+            let line = state.buffer.get_line_number(new_pos);
+            let start = state.buffer.line_start_offset(line).unwrap_or(new_pos);
+            let indent = state
+                .buffer
+                .get_line(line)
+                .map(|text| {
+                    text.iter()
+                        .take_while(|&&b| b == b' ' || b == b'\t')
+                        .count()
+                })
+                .unwrap_or(0);
+            new_pos = start + indent;
+        }
+        let cursor = *state.cursors.primary();
+        let event = Event::MoveCursor {
+            cursor_id: state.cursors.primary_id(),
+            old_position: cursor.position,
+            new_position: new_pos,
+            old_anchor: cursor.anchor,
+            new_anchor: None,
+            old_sticky_column: cursor.sticky_column,
+            new_sticky_column: 0,
+        };
+        self.active_event_log_mut().append(event.clone());
+        self.apply_event_to_active_buffer(&event);
+    }
+
+    /// Drop the marks of a buffer that is being closed
+    ///
+    /// File marks in it keep their file and offset, so they can reopen it.
+    pub(super) fn forget_buffer_marks(&mut self, buffer_id: BufferId) {
+        self.marks.local.remove(&buffer_id);
+        let path = self
+            .buffer_metadata
+            .get(&buffer_id)
+            .and_then(|meta| meta.file_path())
+            .cloned();
+        let state = self.buffers.get(&buffer_id);
+        self.marks.global.retain(|_, location| {
+            let MarkLocation::Buffer {
+                buffer_id: id,
+                marker,
+            } = location
+            else {
+                return true;
+            };
+            if *id != buffer_id {
+                return true;
+            }
+            let position = state.and_then(|s| s.marker_list.get_position(*marker));
+            match (&path, position) {
+                (Some(path), Some(position)) => {
+                    *location = MarkLocation::File {
+                        path: path.clone(),
+                        position,
+                    };
+                    true
+                }
+                _ => false,
+            }
+        });
+    }
+
+    /// Path and byte offset of file mark `location`
+    fn mark_file_position(&self, location: &MarkLocation) -> Option<(PathBuf, usize)> {
+        match location {
+            MarkLocation::Buffer { buffer_id, marker } => {
+                let path = self.buffer_metadata.get(buffer_id)?.file_path()?.clone();
+                let position = self
+                    .buffers
+                    .get(buffer_id)?
+                    .marker_list
+                    .get_position(*marker)?;
+                Some((path, position))
+            }
+            MarkLocation::File { path, position } => Some((path.clone(), *position)),
+        }
+    }
+
+    /// File marks to save with the workspace, with paths relative to the
+    /// working directory where possible
+    pub(super) fn file_marks_for_workspace(&self) -> HashMap<char, SerializedBookmark> {
+        self.marks
+            .global
+            .iter()
+            .filter_map(|(&name, location)| {
+                let (path, position) = self.mark_file_position(location)?;
+                let file_path = path
+                    .strip_prefix(&self.working_dir)
+                    .map(|p| p.to_path_buf())
+                    .unwrap_or(path);
+                Some((
+                    name,
+                    SerializedBookmark {
+                        file_path,
+                        position,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Restore file marks saved with the workspace
+    pub(super) fn restore_file_marks(
+        &mut self,
+        marks: &HashMap<char, SerializedBookmark>,
+        path_to_buffer: &HashMap<PathBuf, BufferId>,
+    ) {
+        for (&name, mark) in marks {
+            if !name.is_ascii_uppercase() {
+                continue;
+            }
+            let bound = path_to_buffer.get(&mark.file_path).and_then(|&buffer_id| {
+                let state = self.buffers.get_mut(&buffer_id)?;
+                let position = mark.position.min(state.buffer.len());
+                let marker = state.marker_list.create(position, true);
+                Some(MarkLocation::Buffer { buffer_id, marker })
+            });
+            let location = bound.unwrap_or_else(|| MarkLocation::File {
+                path: self.working_dir.join(&mark.file_path),
+                position: mark.position,
+            });
+            self.marks.global.insert(name, location);
+        }
+    }
+
+    /// `file:line` of mark `location`
+    fn mark_label(&self, location: &MarkLocation) -> Option<String> {
+        let (name, line) = match location {
+            MarkLocation::Buffer { buffer_id, marker } => {
+                let state = self.buffers.get(buffer_id)?;
+                let position = state.marker_list.get_position(*marker)?;
+                let name = self.buffer_metadata.get(buffer_id)?.display_name.clone();
+                (name, state.buffer.get_line_number(position) + 1)
+            }
+            MarkLocation::File { path, .. } => {
+                let name = path
+                    .strip_prefix(&self.working_dir)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string();
+                // The line isn't known until the file is opened
+                return Some(name);
+            }
+        };
+        Some(format!("{}:{}", name, line))
+    }
+
+    /// Ask for the letter of a mark to set
+    pub fn start_set_mark_prompt(&mut self) {
+        self.start_prompt(t!("mark.set_prompt").to_string(), PromptType::SetNamedMark);
+    }
+
+    /// Pick a mark of the active buffer or a file mark to jump to
+    pub fn start_jump_to_mark_prompt(&mut self) {
+        let buffer_id = self.active_buffer();
+        let mut marks: Vec<(char, MarkLocation)> = self
+            .marks
+            .local
+            .get(&buffer_id)
+            .into_iter()
+            .flatten()
+            .map(|(&name, &marker)| (name, MarkLocation::Buffer { buffer_id, marker }))
+            .chain(
+                self.marks
+                    .global
+                    .iter()
+                    .map(|(&name, location)| (name, location.clone())),
+            )
+            .collect();
+        // Buffer marks first, then file marks
+        marks.sort_by_key(|(name, _)| (name.is_ascii_uppercase(), *name));
+
+        let suggestions: Vec<Suggestion> = marks
+            .iter()
+            .filter_map(|(name, location)| {
+                Some(Suggestion {
+                    text: name.to_string(),
+                    description: Some(self.mark_label(location)?),
+                    value: Some(name.to_string()),
+                    disabled: false,
+                    keybinding: None,
+                    source: None,
+                })
+            })
+            .collect();
+        if suggestions.is_empty() {
+            self.set_status_message(t!("mark.none").to_string());
+            return;
+        }
+        let mut prompt = Prompt::with_suggestions(
+            t!("mark.jump_prompt").to_string(),
+            PromptType::JumpToNamedMark,
+            suggestions,
+        );
+        prompt.selected_suggestion = Some(0);
+        self.prompt = Some(prompt);
+    }
+
+    /// Set or jump to the mark typed or picked in a mark prompt
+    pub(super) fn mark_prompt_confirmed(&mut self, input: &str, jump: bool) {
+        let mut chars = input.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(name), None) if jump => self.jump_to_mark(name, false),
+            (Some(name), None) => self.set_mark(name),
+            _ => {
+                let message = t!("mark.invalid", name = input.trim()).to_string();
+                self.set_status_message(message);
+            }
+        }
+    }
+}
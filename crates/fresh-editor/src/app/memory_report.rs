@@ -0,0 +1,192 @@
+//! Memory usage diagnostics (**Developer: Memory Report**)
+//!
+//! The report lists what each open buffer holds in text, undo history and
+//! syntax highlighting caches, followed by the plugin runtime and the
+//! workspace index. Sizes are estimates from the allocations each part owns,
+//! not what the allocator reports, but they show which part is growing.
+//!
+//! The same total is checked against `editor.memory_warning_threshold_mb`
+//! every [`CHECK_INTERVAL`]; crossing it shows a warning in the status bar
+//! once until use drops below the threshold again.
+
+use std::time::Duration;
+
+use rust_i18n::t;
+
+use super::file_open::format_size;
+use super::Editor;
+use crate::primitives::text_property::TextPropertyEntry;
+
+/// Name of the report buffer
+const MEMORY_REPORT_BUFFER_NAME: &str = "*Memory Report*";
+
+/// How often memory use is compared with the warning threshold
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Estimated memory held by one buffer
+struct BufferUsage {
+    name: String,
+    text: usize,
+    undo: usize,
+    highlight: usize,
+}
+
+impl BufferUsage {
+    fn total(&self) -> usize {
+        self.text + self.undo + self.highlight
+    }
+}
+
+/// Estimated memory held by the editor, per subsystem
+struct MemoryUsage {
+    /// Largest first
+    buffers: Vec<BufferUsage>,
+    /// None if the plugin runtime isn't running
+    plugins: Option<usize>,
+    /// None if the workspace isn't indexed
+    workspace_index: Option<usize>,
+}
+
+impl MemoryUsage {
+    fn total(&self) -> usize {
+        self.buffers.iter().map(BufferUsage::total).sum::<usize>()
+            + self.plugins.unwrap_or(0)
+            + self.workspace_index.unwrap_or(0)
+    }
+
+    fn report(&self) -> String {
+        let size = |bytes: usize| format_size(bytes as u64);
+        let sum = |part: fn(&BufferUsage) -> usize| self.buffers.iter().map(part).sum::<usize>();
+
+        let mut content = String::from("Memory Report\n");
+        content.push_str("=============\n\n");
+        content.push_str("Estimated memory held by each part of the editor.\n");
+        content.push_str("Press 'q' to close this buffer.\n\n");
+
+        content.push_str("── Buffers ──\n\n");
+        content.push_str(&format!(
+            "  {:32} {:>10} {:>10} {:>10}\n",
+            "Buffer", "Text", "Undo", "Highlight"
+        ));
+        for buffer in &self.buffers {
+            content.push_str(&format!(
+                "  {:32} {:>10} {:>10} {:>10}\n",
+                buffer.name,
+                size(buffer.text),
+                size(buffer.undo),
+                size(buffer.highlight)
+            ));
+        }
+
+        let optional = |bytes: Option<usize>, missing: &str| {
+            bytes.map(size).unwrap_or_else(|| missing.to_string())
+        };
+        content.push_str("\n── Subsystems ──\n\n");
+        let rows = [
+            ("Buffer text", size(sum(|b| b.text))),
+            ("Undo history", size(sum(|b| b.undo))),
+            ("Syntax highlighting caches", size(sum(|b| b.highlight))),
+            ("Plugin runtime", optional(self.plugins, "not running")),
+            ("Workspace index", optional(self.workspace_index, "off")),
+        ];
+        for (label, value) in rows {
+            content.push_str(&format!("  {:32} {:>10}\n", label, value));
+        }
+        content.push_str(&format!("\n  {:32} {:>10}\n", "Total", size(self.total())));
+        content
+    }
+}
+
+impl Editor {
+    /// Estimate the memory held by buffers, plugins and the workspace index
+    fn measure_memory(&self) -> MemoryUsage {
+        let mut buffers: Vec<BufferUsage> = self
+            .buffers
+            .iter()
+            .map(|(id, state)| BufferUsage {
+                name: self
+                    .buffer_metadata
+                    .get(id)
+                    .map(|m| m.display_name.clone())
+                    .unwrap_or_else(|| format!("Buffer {}", id.0)),
+                text: state.buffer.memory_usage(),
+                undo: self.event_logs.get(id).map_or(0, |log| log.memory_usage()),
+                highlight: state.highlighter.cache_memory_usage(),
+            })
+            .collect();
+        buffers.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.name.cmp(&b.name)));
+
+        MemoryUsage {
+            buffers,
+            plugins: self.plugin_manager.memory_usage(),
+            workspace_index: self
+                .workspace_index
+                .as_ref()
+                .map(|index| index.memory_usage()),
+        }
+    }
+
+    /// Open a buffer listing estimated memory use per subsystem
+    pub fn show_memory_report(&mut self) {
+        let content = self.measure_memory().report();
+
+        // Refresh an open report rather than opening a second one
+        let existing_buffer = self
+            .buffer_metadata
+            .iter()
+            .find(|(_, m)| m.display_name == MEMORY_REPORT_BUFFER_NAME)
+            .map(|(id, _)| *id);
+        let buffer_id = existing_buffer.unwrap_or_else(|| {
+            self.create_virtual_buffer(
+                MEMORY_REPORT_BUFFER_NAME.to_string(),
+                "special".to_string(),
+                true,
+            )
+        });
+
+        let entries = vec![TextPropertyEntry::text(content)];
+        if let Err(e) = self.set_virtual_buffer_content(buffer_id, entries) {
+            tracing::warn!("Failed to fill memory report: {}", e);
+            return;
+        }
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.editing_disabled = true;
+            state.margins.set_line_numbers(false);
+        }
+
+        self.set_active_buffer(buffer_id);
+    }
+
+    /// Compare memory use with `memory_warning_threshold_mb` now and then
+    ///
+    /// Returns true when a warning was shown (status bar needs a re-render).
+    pub(crate) fn poll_memory_usage(&mut self) -> bool {
+        let threshold_mb = self.config.editor.memory_warning_threshold_mb;
+        if threshold_mb == 0 {
+            return false;
+        }
+        if self.time_source.elapsed_since(self.last_memory_check) < CHECK_INTERVAL {
+            return false;
+        }
+        self.last_memory_check = self.time_source.now();
+
+        let total = self.measure_memory().total() as u64;
+        if total <= threshold_mb * 1024 * 1024 {
+            self.memory_warning_shown = false;
+            return false;
+        }
+        if self.memory_warning_shown {
+            return false;
+        }
+        self.memory_warning_shown = true;
+        self.set_status_message(
+            t!(
+                "memory.warning",
+                size = format_size(total),
+                threshold = threshold_mb
+            )
+            .to_string(),
+        );
+        true
+    }
+}
@@ -10,7 +10,7 @@ use anyhow::Result as AnyhowResult;
 
 impl Editor {
     /// Get all menus (built-in menus + plugin menus) with DynamicSubmenus expanded.
-    fn all_menus(&self) -> Vec<Menu> {
+    pub(crate) fn all_menus(&self) -> Vec<Menu> {
         self.menus
             .menus
             .iter()
@@ -16,6 +16,8 @@ impl Editor {
         let line_numbers = self.is_line_numbers_visible();
         let line_wrap = self.is_line_wrap_enabled();
         let compose_mode = self.is_compose_mode();
+        let focus_mode = self.is_focus_mode();
+        let scroll_lock = self.is_scroll_locked();
         let file_explorer_visible = self.file_explorer_visible;
         let file_explorer_focused = self.is_file_explorer_focused();
         let mouse_capture = self.mouse_enabled;
@@ -23,6 +25,8 @@ impl Editor {
         let inlay_hints = self.config.editor.enable_inlay_hints;
         let has_selection = self.has_active_selection();
         let menu_bar = self.menu_bar_visible;
+        let search_case_sensitive = self.search_case_sensitive;
+        let search_whole_word = self.search_whole_word;
 
         // File explorer state
         let show_hidden = self.is_file_explorer_showing_hidden();
@@ -34,6 +38,7 @@ impl Editor {
 
         // Session mode (for detach command availability)
         let session_mode = self.session_mode;
+        let following_user = self.collab_followed().is_some();
 
         // Apply all context values
         self.menu_state
@@ -41,6 +46,8 @@ impl Editor {
             .set(context_keys::LINE_NUMBERS, line_numbers)
             .set(context_keys::LINE_WRAP, line_wrap)
             .set(context_keys::COMPOSE_MODE, compose_mode)
+            .set(context_keys::FOCUS_MODE, focus_mode)
+            .set(context_keys::SCROLL_LOCK, scroll_lock)
             .set(context_keys::FILE_EXPLORER, file_explorer_visible)
             .set(context_keys::FILE_EXPLORER_FOCUSED, file_explorer_focused)
             .set(context_keys::MOUSE_CAPTURE, mouse_capture)
@@ -52,7 +59,10 @@ impl Editor {
             .set(context_keys::HAS_SELECTION, has_selection)
             .set(context_keys::MENU_BAR, menu_bar)
             .set(context_keys::FORMATTER_AVAILABLE, formatter_available)
-            .set(context_keys::SESSION_MODE, session_mode);
+            .set(context_keys::SESSION_MODE, session_mode)
+            .set(context_keys::FOLLOWING_USER, following_user)
+            .set(context_keys::SEARCH_CASE_SENSITIVE, search_case_sensitive)
+            .set(context_keys::SEARCH_WHOLE_WORD, search_whole_word);
     }
 
     /// Check if line numbers are visible in the active buffer.
@@ -80,6 +90,14 @@ impl Editor {
             .unwrap_or(false)
     }
 
+    /// Check if focus mode is on in the active split.
+    fn is_focus_mode(&self) -> bool {
+        let active_split = self.split_manager.active_split();
+        self.split_view_states
+            .get(&active_split)
+            .is_some_and(|vs| vs.focus_mode)
+    }
+
     /// Check if the file explorer is currently focused.
     fn is_file_explorer_focused(&self) -> bool {
         self.key_context == crate::input::keybindings::KeyContext::FileExplorer
@@ -1,9 +1,20 @@
+mod accessibility;
+mod align_actions;
+mod animations;
 mod async_messages;
+mod auto_save;
+mod background_io;
 mod buffer_management;
 mod calibration_actions;
 pub mod calibration_wizard;
 mod clipboard;
+mod collab;
+mod collab_share;
+mod comment_actions;
 mod composite_buffer_actions;
+mod config_issue_actions;
+mod date_time_actions;
+mod digraph_actions;
 pub mod event_debug;
 mod event_debug_actions;
 mod file_explorer;
@@ -11,25 +22,44 @@ pub mod file_open;
 mod file_open_input;
 mod file_operations;
 mod help;
+mod image_preview_actions;
+mod indentation_actions;
 mod input;
 mod input_dispatch;
 pub mod keybinding_editor;
 mod keybinding_editor_actions;
+mod link_actions;
+mod local_history_actions;
 mod lsp_actions;
 mod lsp_requests;
+mod marks;
+mod memory_report;
 mod menu_actions;
 mod menu_context;
 mod mouse_input;
 mod on_save_actions;
+mod peek_actions;
 mod plugin_commands;
+mod plugin_reload;
+mod plugin_tasks;
 mod popup_actions;
+mod preview_tabs;
+mod profile_actions;
 mod prompt_actions;
+mod quickfix;
+mod read_only;
 mod recovery_actions;
 mod render;
+mod self_update;
+mod sequence_actions;
+mod session_commands;
 mod settings_actions;
 mod shell_command;
 mod split_actions;
+mod structural_search;
+mod sudo_save;
 mod tab_drag;
+mod telemetry_report;
 mod terminal;
 mod terminal_input;
 mod terminal_mouse;
@@ -38,7 +68,10 @@ pub mod types;
 mod undo_actions;
 mod view_actions;
 pub mod warning_domains;
+mod watch_task;
+mod word_completion;
 pub mod workspace;
+mod workspace_trust_actions;
 
 use anyhow::Result as AnyhowResult;
 use rust_i18n::t;
@@ -76,10 +109,15 @@ pub(crate) fn normalize_path(path: &std::path::Path) -> std::path::PathBuf {
     }
 }
 
+use self::marks::Marks;
+use self::quickfix::QuickfixHistory;
+use self::structural_search::PendingStructuralReplace;
+#[cfg(feature = "plugins")]
+use self::types::WatchedPlugin;
 use self::types::{
-    Bookmark, CachedLayout, EventLineInfo, InteractiveReplaceState, LspMessageEntry,
-    LspProgressInfo, MacroRecordingState, MouseState, SearchState, TabContextMenu,
-    DEFAULT_BACKGROUND_FILE,
+    ActiveAnimations, BackgroundOpen, BackgroundSave, Bookmark, CachedLayout, EventLineInfo,
+    InteractiveReplaceState, LspMessageEntry, LspProgressInfo, MacroRecordingState, MouseState,
+    PluginTask, SearchState, TabContextMenu, WatchTask, DEFAULT_BACKGROUND_FILE,
 };
 use crate::config::Config;
 use crate::config_io::{ConfigLayer, ConfigResolver, DirectoryContext};
@@ -93,18 +131,22 @@ use crate::input::quick_open::{
     FileProvider, GotoLineProvider, QuickOpenContext, QuickOpenProvider, QuickOpenRegistry,
 };
 use crate::model::event::{Event, EventLog, SplitDirection, SplitId};
-use crate::model::filesystem::FileSystem;
+use crate::model::filesystem::{FileSystem, TrashedPath};
 use crate::services::async_bridge::{AsyncBridge, AsyncMessage};
 use crate::services::fs::FsManager;
+use crate::services::local_history::LocalHistory;
 use crate::services::lsp::manager::LspManager;
 use crate::services::plugins::PluginManager;
 use crate::services::recovery::{RecoveryConfig, RecoveryService};
+use crate::services::render_profile::RenderProfiler;
 use crate::services::time_source::{RealTimeSource, SharedTimeSource};
+use crate::services::workspace_index::WorkspaceIndex;
+use crate::services::workspace_trust::{self, WorkspaceTrust};
 use crate::state::EditorState;
 use crate::types::LspServerConfig;
 use crate::view::file_tree::{FileTree, FileTreeView};
 use crate::view::prompt::{Prompt, PromptType};
-use crate::view::scroll_sync::ScrollSyncManager;
+use crate::view::scroll_sync::{ScrollSyncGroupId, ScrollSyncManager};
 use crate::view::split::{SplitManager, SplitViewState};
 use crate::view::ui::{
     FileExplorerRenderer, SplitRenderer, StatusBarRenderer, SuggestionsRenderer,
@@ -129,8 +171,8 @@ use std::time::Instant;
 // Re-export BufferId from event module for backward compatibility
 pub use self::types::{BufferKind, BufferMetadata, HoverTarget};
 pub use self::warning_domains::{
-    GeneralWarningDomain, LspWarningDomain, WarningAction, WarningActionId, WarningDomain,
-    WarningDomainRegistry, WarningLevel, WarningPopupContent,
+    ConfigWarningDomain, GeneralWarningDomain, LspWarningDomain, WarningAction, WarningActionId,
+    WarningDomain, WarningDomainRegistry, WarningLevel, WarningPopupContent,
 };
 pub use crate::model::event::BufferId;
 
@@ -230,6 +272,9 @@ pub struct Editor {
     /// Should the editor quit?
     should_quit: bool,
 
+    /// Status the process exits with once the editor quits (set by plugins)
+    exit_code: i32,
+
     /// Should the client detach (keep server running)?
     should_detach: bool,
 
@@ -239,20 +284,91 @@ pub struct Editor {
     /// Session name for display in status bar (session mode only)
     session_name: Option<String>,
 
-    /// Pending escape sequences to send to client (session mode only)
-    /// These get prepended to the next render output
+    /// Pending escape sequences to send to the terminal or client
+    /// These get written before the next render output
     pending_escape_sequences: Vec<u8>,
 
+    /// Image previews, shown instead of the text of their buffers
+    image_previews: HashMap<BufferId, crate::view::image_preview::ImagePreview>,
+
+    /// How the terminal draws images (none in session mode)
+    graphics_protocol: crate::view::graphics_protocol::GraphicsProtocol,
+
+    /// Terminal cell size in pixels, used to scale images
+    image_cell_size: (u16, u16),
+
+    /// Images on screen after the last frame and where they were drawn
+    drawn_images: Vec<(BufferId, ratatui::layout::Rect)>,
+
+    /// Image escape sequences to write after the next frame
+    pending_image_sequences: Vec<u8>,
+
+    /// Whether the terminal shows OSC 8 hyperlinks for URLs on screen
+    hyperlinks: bool,
+
+    /// Finds URLs and file paths for hyperlinks and Open Link Under Cursor
+    link_detector: crate::services::link_detection::LinkDetector,
+
+    /// Cursor style last sent to the terminal, which the terminal starts
+    /// with from `cursor_style`
+    terminal_cursor_style: crate::config::CursorStyle,
+
+    /// Spoken descriptions of what changes on screen, while accessibility
+    /// mode is on
+    accessibility: Option<accessibility::AccessibilityState>,
+
     /// If set, the editor should restart with this new working directory
     /// This is used by Open Folder to do a clean context switch
     restart_with_dir: Option<PathBuf>,
 
+    /// If set, the editor should restart with this profile
+    /// This is used by Switch Profile ("default" selects the default profile)
+    restart_with_profile: Option<String>,
+
+    /// If set, the process should re-exec this binary on exit
+    /// This is used by Update Fresh Now to start the installed update
+    relaunch_executable: Option<PathBuf>,
+
+    /// Whether Update Fresh Now is downloading or installing a release
+    self_update_running: bool,
+
+    /// Cursors of the other clients attached to this session
+    collab: collab::CollabState,
+
+    /// Buffer shared with editors on other machines (Collab: Share Buffer / Join)
+    collab_share: Option<collab_share::ShareSession>,
+
     /// Status message (shown in status bar)
     status_message: Option<String>,
 
     /// Plugin-provided status message (displayed alongside the core status)
     plugin_status_message: Option<String>,
 
+    /// Text of plugin-provided status line segments, keyed by segment id
+    /// (rendered by `plugin:<id>` entries in `status_line`)
+    plugin_status_segments: HashMap<String, String>,
+
+    /// Branch of the git repository containing the working directory,
+    /// refreshed periodically while a `git_branch` segment is configured
+    git_branch: Option<String>,
+
+    /// Last time the git branch was read
+    last_git_branch_poll: Option<std::time::Instant>,
+
+    /// Whether the terminal/OS is light or dark, picks the theme of a
+    /// light/dark `theme` pair
+    appearance: crate::config::Appearance,
+
+    /// Reports OS dark-mode changes, started by `watch_appearance`
+    appearance_watcher: Option<crate::services::appearance::AppearanceWatcher>,
+
+    /// User theme files as of the last check, to reload themes when one is
+    /// edited (None until the first check)
+    theme_file_stamps: Option<HashMap<PathBuf, crate::view::theme::ThemeFileStamp>>,
+
+    /// Last time the user theme files were checked
+    last_theme_file_poll: Option<std::time::Instant>,
+
     /// Accumulated plugin errors (for test assertions)
     /// These are collected when plugin error messages are received
     plugin_errors: Vec<String>,
@@ -296,6 +412,9 @@ pub struct Editor {
     /// Used for side-by-side diff views where two panes need to scroll together
     scroll_sync_manager: ScrollSyncManager,
 
+    /// Scroll sync groups created by "Lock Scrolling" (the rest belong to plugins)
+    scroll_lock_groups: HashSet<ScrollSyncGroupId>,
+
     /// File explorer view (optional, only when open)
     file_explorer: Option<FileTreeView>,
 
@@ -309,6 +428,14 @@ pub struct Editor {
     /// This is always StdFileSystem, even when filesystem is RemoteFileSystem
     local_filesystem: Arc<dyn FileSystem + Send + Sync>,
 
+    /// Additional filesystems (e.g. other SSH hosts) that buffers can be opened
+    /// from alongside `filesystem`. Each buffer keeps the filesystem it was
+    /// opened with, so local and remote files can be edited side by side.
+    mounted_filesystems: Vec<Arc<dyn FileSystem + Send + Sync>>,
+
+    /// Index into `filesystem_roots()` of the filesystem shown in the file explorer
+    explorer_root: usize,
+
     /// Process spawner for plugin command execution (local or remote)
     process_spawner: Arc<dyn crate::services::remote::ProcessSpawner>,
 
@@ -356,6 +483,9 @@ pub struct Editor {
     /// Whether GPM is being used for mouse input (requires software cursor)
     gpm_active: bool,
 
+    /// Whether the terminal accepted the kitty keyboard protocol
+    keyboard_enhancement_active: bool,
+
     /// Current keybinding context
     key_context: KeyContext,
 
@@ -368,8 +498,9 @@ pub struct Editor {
     /// Working directory for file explorer (set at initialization)
     working_dir: PathBuf,
 
-    /// Position history for back/forward navigation
-    pub position_history: PositionHistory,
+    /// Directory of the user's shell, reported by shell integration (the
+    /// `cd` session command)
+    shell_dir: Option<PathBuf>,
 
     /// Flag to prevent recording movements during navigation
     in_navigation: bool,
@@ -380,10 +511,28 @@ pub struct Editor {
     /// Pending LSP completion request ID (if any)
     pending_completion_request: Option<u64>,
 
+    /// Outstanding completion requests of the pending round, by request ID,
+    /// with the server ID each was sent to (several when a language has more
+    /// than one completion server)
+    pending_completion_sources: HashMap<u64, String>,
+
+    /// Completion items received so far in the pending round, with their server ID
+    pending_completion_items: Vec<(String, lsp_types::CompletionItem)>,
+
     /// Original LSP completion items (for type-to-filter)
     /// Stored when completion popup is shown, used for re-filtering as user types
     completion_items: Option<Vec<lsp_types::CompletionItem>>,
 
+    /// The project's ctags `tags` file as last read for word completion
+    word_completion_tags: Option<word_completion::CachedTagsFile>,
+
+    /// Digraph being typed after `insert_digraph`: `Some(None)` waits for
+    /// the first character, `Some(Some(c))` for the second
+    pending_digraph: Option<Option<char>>,
+
+    /// Delimiter last entered in the Align By prompt, offered next time
+    last_align_input: String,
+
     /// Scheduled completion trigger time (for debounced quick suggestions)
     /// When Some, completion will be triggered when this instant is reached
     scheduled_completion_trigger: Option<Instant>,
@@ -430,6 +579,21 @@ pub struct Editor {
     /// Next time a full semantic token refresh is allowed for a buffer
     semantic_tokens_full_debounce: HashMap<BufferId, Instant>,
 
+    /// Pending Peek Definition/References request (request_id, kind)
+    pending_peek_request: Option<(u64, crate::view::peek_window::PeekKind)>,
+
+    /// Inline peek window (modal while open)
+    pub(crate) peek_window: Option<crate::view::peek_window::PeekWindow>,
+
+    /// Pending LSP document highlight request (request_id, buffer)
+    pending_document_highlight_request: Option<(u64, BufferId)>,
+
+    /// Pending code lens requests keyed by LSP request ID (buffer, buffer version)
+    pending_code_lens_requests: HashMap<u64, (BufferId, u64)>,
+
+    /// Last code lens request per buffer (buffer version, time) for debouncing
+    code_lens_last_request: HashMap<BufferId, (u64, Instant)>,
+
     /// Hover symbol range (byte offsets) - for highlighting the symbol under hover
     /// Format: (start_byte_offset, end_byte_offset)
     hover_symbol_range: Option<(usize, usize)>,
@@ -479,6 +643,10 @@ pub struct Editor {
     /// File provider for Quick Open (stored separately for cache management)
     file_provider: Arc<FileProvider>,
 
+    /// Background index of the workspace's files and symbols
+    /// (`editor.workspace_index`; not used for remote workspaces)
+    workspace_index: Option<Arc<WorkspaceIndex>>,
+
     /// Plugin manager (handles both enabled and disabled cases)
     plugin_manager: PluginManager,
 
@@ -504,6 +672,71 @@ pub struct Editor {
     /// When cancelled, the callback is resolved with null.
     pending_async_prompt_callback: Option<fresh_core::api::JsCallbackId>,
 
+    /// Plugin permission requests waiting for their prompt to be shown
+    pending_permission_requests:
+        std::collections::VecDeque<(String, fresh_core::config::PluginPermission)>,
+
+    /// Whether the working directory's project config and plugins may be used
+    workspace_trusted: bool,
+
+    /// Ask whether to trust the workspace once no other prompt is open
+    /// (first open of a workspace that has something to gate)
+    pending_workspace_trust_prompt: bool,
+
+    /// Running plugin background tasks, oldest first
+    plugin_tasks: Vec<PluginTask>,
+
+    /// Spinner frame last drawn for the background task indicator
+    task_spinner_frame: usize,
+
+    /// Large files being read on worker threads, oldest first
+    background_opens: Vec<BackgroundOpen>,
+
+    /// Id for the next background open
+    next_background_open_id: u64,
+
+    /// Large buffers being written on worker threads, oldest first
+    background_saves: Vec<BackgroundSave>,
+
+    /// Command re-run on every save (**Toggle Watch Task**)
+    watch_task: Option<WatchTask>,
+
+    /// Id for the next run of the watch task
+    next_watch_run_id: u64,
+
+    /// Quickfix lists from searches, the watch task and references
+    quickfix: QuickfixHistory,
+
+    /// Structural replace previewed and waiting for confirmation
+    structural_replace: Option<PendingStructuralReplace>,
+
+    /// Frame timings for the render profiler overlay and `--profile-render`
+    render_profiler: RenderProfiler,
+
+    /// Versions of saved files kept for **Local History**
+    local_history: LocalHistory,
+
+    /// What the last file explorer delete moved to the trash, and where, so
+    /// it can be restored
+    last_trashed: Option<(Arc<dyn FileSystem + Send + Sync>, TrashedPath)>,
+
+    /// Last time memory use was checked against `memory_warning_threshold_mb`
+    last_memory_check: std::time::Instant,
+
+    /// Whether the memory warning was shown since use last went above the threshold
+    memory_warning_shown: bool,
+
+    /// Running scroll, cursor flash and panel animations
+    animations: ActiveAnimations,
+
+    /// Plugins watched for source changes (`editor.plugin_hot_reload`), by name
+    #[cfg(feature = "plugins")]
+    watched_plugins: HashMap<String, WatchedPlugin>,
+
+    /// Last time plugin sources were checked for changes
+    #[cfg(feature = "plugins")]
+    last_plugin_source_poll: std::time::Instant,
+
     /// LSP progress tracking (token -> progress info)
     lsp_progress: std::collections::HashMap<String, LspProgressInfo>,
 
@@ -525,12 +758,19 @@ pub struct Editor {
     /// Maps file URI string to Vec of diagnostics for that file
     stored_diagnostics: HashMap<String, Vec<lsp_types::Diagnostic>>,
 
+    /// Diagnostics per URI and server ID, merged into `stored_diagnostics`
+    /// when a language has more than one server
+    server_diagnostics: HashMap<String, HashMap<String, Vec<lsp_types::Diagnostic>>>,
+
     /// Event broadcaster for control events (observable by external systems)
     event_broadcaster: crate::model::control_event::EventBroadcaster,
 
     /// Bookmarks (character key -> bookmark)
     bookmarks: HashMap<char, Bookmark>,
 
+    /// Vim-style marks of each buffer and file marks
+    marks: Marks,
+
     /// Global search options (persist across searches)
     search_case_sensitive: bool,
     search_whole_word: bool,
@@ -586,6 +826,13 @@ pub struct Editor {
     /// Maps file path to last known modification time
     file_mod_times: HashMap<PathBuf, std::time::SystemTime>,
 
+    /// Last observed state of the remote (SSH) connection, for change notifications
+    remote_connected: bool,
+
+    /// Buffers whose save failed because the remote connection was down.
+    /// Retried in order once the connection is re-established.
+    queued_remote_saves: Vec<BufferId>,
+
     /// Last known modification times for expanded directories (for file tree refresh)
     /// Maps directory path to last known modification time
     dir_mod_times: HashMap<PathBuf, std::time::SystemTime>,
@@ -612,6 +859,11 @@ pub struct Editor {
     /// Last auto-save time for rate limiting
     last_auto_save: std::time::Instant,
 
+    /// Modified buffers tracked by the `afterDelay` auto-save debounce.
+    /// Maps buffer ID to (buffer version, time of the last edit). The time is
+    /// None after a failed attempt, until the buffer is edited again.
+    pending_auto_saves: HashMap<BufferId, (u64, Option<std::time::Instant>)>,
+
     /// Active custom contexts for command visibility
     /// Plugin-defined contexts like "config-editor" that control command availability
     active_custom_contexts: HashSet<String>,
@@ -668,6 +920,13 @@ pub struct Editor {
     /// Click count for multi-click detection (1=single, 2=double, 3=triple)
     click_count: u8,
 
+    /// Timestamp and direction (true = down) of the previous wheel notch
+    /// (for scroll acceleration)
+    previous_scroll: Option<(std::time::Instant, bool)>,
+
+    /// Number of rapid wheel notches in a row in the same direction
+    scroll_streak: u8,
+
     /// Settings UI state (when settings modal is open)
     pub(crate) settings_state: Option<crate::view::settings::SettingsState>,
 
@@ -707,6 +966,14 @@ pub struct Editor {
     /// ensuring consistent error handling (e.g., encoding confirmation prompts).
     pending_file_opens: Vec<PendingFileOpen>,
 
+    /// Plugin hooks to run once the TUI starts, for CLI modes that plugins
+    /// implement (`fresh --diff`, `fresh --merge`)
+    pending_startup_hooks: Vec<(&'static str, crate::services::plugins::hooks::HookArgs)>,
+
+    /// URI schemes claimed by plugins (`editor.registerUriScheme`); opening
+    /// `scheme://...` asks the plugin for the content instead of reading a file
+    uri_schemes: HashSet<String>,
+
     /// Stdin streaming state (if reading from stdin)
     stdin_streaming: Option<StdinStreamingState>,
 }
@@ -720,6 +987,11 @@ pub struct PendingFileOpen {
     pub line: Option<usize>,
     /// Column number to navigate to (1-indexed, optional)
     pub column: Option<usize>,
+    /// Mounted remote connection (`user@host`) the file lives on, or `None`
+    /// for the editor's primary filesystem
+    pub remote_connection: Option<String>,
+    /// Open the buffer read-only (`fresh -R`)
+    pub read_only: bool,
 }
 
 /// State for tracking stdin streaming in background
@@ -843,15 +1115,20 @@ impl Editor {
         // This ensures consistent path comparisons throughout the editor
         let working_dir = working_dir.canonicalize().unwrap_or(working_dir);
 
+        crate::primitives::display_width::set_ambiguous_wide(
+            config.editor.ambiguous_width == crate::config::AmbiguousWidth::Wide,
+        );
+
         // Load all themes into registry
         let theme_loader = crate::view::theme::ThemeLoader::new(dir_context.themes_dir());
         let theme_registry = theme_loader.load_all();
 
         // Get active theme from registry, falling back to default if not found
-        let theme = theme_registry.get_cloned(&config.theme).unwrap_or_else(|| {
+        let theme_name = config.theme.resolve(crate::config::Appearance::default());
+        let theme = theme_registry.get_cloned(theme_name).unwrap_or_else(|| {
             tracing::warn!(
                 "Theme '{}' not found, falling back to default theme",
-                config.theme.0
+                theme_name.0
             );
             theme_registry
                 .get_cloned(&crate::config::ThemeName(
@@ -859,6 +1136,7 @@ impl Editor {
                 ))
                 .expect("Default theme must exist")
         });
+        let theme = crate::view::color_support::quantize_theme(&theme, color_capability);
 
         // Set terminal cursor color to match theme
         theme.set_terminal_cursor_color();
@@ -883,6 +1161,9 @@ impl Editor {
         );
         // Apply line_numbers default from config (fixes #539)
         state.margins.set_line_numbers(config.editor.line_numbers);
+        state.rulers = config.editor.rulers.clone();
+        state.wrap_guide_shading = config.editor.wrap_guide_shading;
+        state.render_whitespace = config.editor.render_whitespace;
         // Note: line_wrap_enabled is now stored in SplitViewState.viewport
         tracing::info!("EditorState created for buffer {:?}", buffer_id);
         buffers.insert(buffer_id, state);
@@ -924,6 +1205,9 @@ impl Editor {
         for (language, lsp_config) in &config.lsp {
             lsp.set_language_config(language.clone(), lsp_config.clone());
         }
+        for (language, lang_config) in &config.languages {
+            lsp.set_additional_servers(language, lang_config.lsp_servers.clone());
+        }
 
         // Initialize split manager with the initial buffer
         let split_manager = SplitManager::new(buffer_id);
@@ -941,8 +1225,24 @@ impl Editor {
         // Initialize command registry (always available, used by both plugins and core)
         let command_registry = Arc::new(RwLock::new(CommandRegistry::new()));
 
+        // Index the workspace's files and symbols in the background
+        let workspace_index = (config.editor.workspace_index
+            && filesystem.remote_connection_info().is_none())
+        .then(|| {
+            Arc::new(WorkspaceIndex::start(
+                working_dir.clone(),
+                Some(WorkspaceIndex::cache_path(
+                    &dir_context.data_dir,
+                    &working_dir,
+                )),
+            ))
+        });
+
         // Initialize file provider for Quick Open (stored separately for cache management)
-        let file_provider = Arc::new(FileProvider::new());
+        let file_provider = Arc::new(match &workspace_index {
+            Some(index) => FileProvider::with_index(Arc::clone(index)),
+            None => FileProvider::new(),
+        });
 
         // Initialize Quick Open registry with providers
         let mut quick_open_registry = QuickOpenRegistry::new();
@@ -965,9 +1265,20 @@ impl Editor {
             snapshot.working_dir = working_dir.clone();
         }
 
+        // Project config and workspace plugins are only used in trusted workspaces.
+        // Ask on first open if the workspace has any.
+        let workspace_trust_decision =
+            WorkspaceTrust::load(&dir_context.data_dir).decision(&working_dir);
+        let workspace_trusted =
+            workspace_trust_decision == Some(workspace_trust::TrustDecision::Trusted);
+        let pending_workspace_trust_prompt = workspace_trust_decision.is_none()
+            && (workspace_trust::workspace_plugins_dir(&working_dir).exists()
+                || ConfigResolver::new(dir_context.clone(), working_dir.clone())
+                    .has_untrusted_settings());
+
         // Load TypeScript plugins from multiple directories:
         // 1. Next to the executable (for cargo-dist installations)
-        // 2. In the working directory (for development/local usage)
+        // 2. In the working directory (for development/local usage, trusted workspaces only)
         // 3. From embedded plugins (for cargo-binstall, when embed-plugins feature is enabled)
         // 4. User plugins directory (~/.config/fresh/plugins)
         // 5. Package manager installed plugins (~/.config/fresh/plugins/packages/*)
@@ -984,9 +1295,12 @@ impl Editor {
                 }
             }
 
-            // Then check working directory (for development)
-            let working_plugin_dir = working_dir.join("plugins");
-            if working_plugin_dir.exists() && !plugin_dirs.contains(&working_plugin_dir) {
+            // Then check working directory (for development), if the workspace is trusted
+            let working_plugin_dir = workspace_trust::workspace_plugins_dir(&working_dir);
+            if workspace_trusted
+                && working_plugin_dir.exists()
+                && !plugin_dirs.contains(&working_plugin_dir)
+            {
                 plugin_dirs.push(working_plugin_dir);
             }
 
@@ -1068,15 +1382,25 @@ impl Editor {
         let check_for_updates = config.check_for_updates;
         let show_menu_bar = config.editor.show_menu_bar;
         let show_tab_bar = config.editor.show_tab_bar;
+        let cursor_style = config.editor.cursor_style;
+        let accessibility = config
+            .accessibility
+            .enabled
+            .then(|| accessibility::AccessibilityState::new(config.accessibility.speech_command()));
 
         // Start periodic update checker if enabled (also sends daily telemetry)
         let update_checker = if check_for_updates {
             tracing::debug!("Update checking enabled, starting periodic checker");
+            if config.telemetry.level.sends_crashes() {
+                crate::services::telemetry::enable_crash_reports(&dir_context.data_dir);
+            }
             Some(
                 crate::services::release_checker::start_periodic_update_check(
                     crate::services::release_checker::DEFAULT_RELEASES_URL,
                     time_source.clone(),
                     dir_context.data_dir.clone(),
+                    config.telemetry.clone(),
+                    config.update_channel,
                 ),
             )
         } else {
@@ -1104,13 +1428,35 @@ impl Editor {
             keybindings,
             clipboard: crate::services::clipboard::Clipboard::new(),
             should_quit: false,
+            exit_code: 0,
             should_detach: false,
             session_mode: false,
             session_name: None,
             pending_escape_sequences: Vec::new(),
+            image_previews: HashMap::new(),
+            graphics_protocol: crate::view::graphics_protocol::GraphicsProtocol::None,
+            image_cell_size: crate::view::graphics_protocol::DEFAULT_CELL_SIZE,
+            drawn_images: Vec::new(),
+            pending_image_sequences: Vec::new(),
+            hyperlinks: false,
+            link_detector: crate::services::link_detection::LinkDetector::new(),
+            terminal_cursor_style: cursor_style,
+            accessibility,
             restart_with_dir: None,
+            restart_with_profile: None,
+            relaunch_executable: None,
+            self_update_running: false,
+            collab: collab::CollabState::default(),
+            collab_share: None,
             status_message: None,
             plugin_status_message: None,
+            plugin_status_segments: HashMap::new(),
+            git_branch: None,
+            last_git_branch_poll: None,
+            appearance: crate::config::Appearance::default(),
+            appearance_watcher: None,
+            theme_file_stamps: None,
+            last_theme_file_poll: None,
             plugin_errors: Vec::new(),
             prompt: None,
             terminal_width: width,
@@ -1124,10 +1470,13 @@ impl Editor {
             split_view_states,
             previous_viewports: HashMap::new(),
             scroll_sync_manager: ScrollSyncManager::new(),
+            scroll_lock_groups: HashSet::new(),
             file_explorer: None,
             fs_manager,
             filesystem,
             local_filesystem: Arc::new(crate::model::filesystem::StdFileSystem),
+            mounted_filesystems: Vec::new(),
+            explorer_root: 0,
             process_spawner: Arc::new(crate::services::remote::LocalProcessSpawner),
             file_explorer_visible: false,
             file_explorer_sync_in_progress: false,
@@ -1143,15 +1492,21 @@ impl Editor {
             mouse_enabled: true,
             mouse_cursor_position: None,
             gpm_active: false,
+            keyboard_enhancement_active: false,
             key_context: KeyContext::Normal,
             menu_state: crate::view::ui::MenuState::new(dir_context.themes_dir()),
             menus: crate::config::MenuConfig::translated(),
             working_dir,
-            position_history: PositionHistory::new(),
+            shell_dir: None,
             in_navigation: false,
             next_lsp_request_id: 0,
             pending_completion_request: None,
+            pending_completion_sources: HashMap::new(),
+            pending_completion_items: Vec::new(),
             completion_items: None,
+            word_completion_tags: None,
+            pending_digraph: None,
+            last_align_input: "=".to_string(),
             scheduled_completion_trigger: None,
             pending_goto_definition_request: None,
             pending_hover_request: None,
@@ -1167,6 +1522,11 @@ impl Editor {
             semantic_tokens_range_last_request: HashMap::new(),
             semantic_tokens_range_applied: HashMap::new(),
             semantic_tokens_full_debounce: HashMap::new(),
+            pending_peek_request: None,
+            peek_window: None,
+            pending_document_highlight_request: None,
+            pending_code_lens_requests: HashMap::new(),
+            code_lens_last_request: HashMap::new(),
             hover_symbol_range: None,
             hover_symbol_overlay: None,
             mouse_hover_screen_position: None,
@@ -1186,6 +1546,7 @@ impl Editor {
             command_registry,
             quick_open_registry,
             file_provider,
+            workspace_index,
             plugin_manager,
             seen_byte_ranges: HashMap::new(),
             panel_ids: HashMap::new(),
@@ -1205,14 +1566,38 @@ impl Editor {
                 histories
             },
             pending_async_prompt_callback: None,
+            pending_permission_requests: std::collections::VecDeque::new(),
+            workspace_trusted,
+            pending_workspace_trust_prompt,
+            plugin_tasks: Vec::new(),
+            task_spinner_frame: 0,
+            background_opens: Vec::new(),
+            next_background_open_id: 0,
+            background_saves: Vec::new(),
+            watch_task: None,
+            next_watch_run_id: 0,
+            quickfix: QuickfixHistory::default(),
+            structural_replace: None,
+            render_profiler: RenderProfiler::new(),
+            local_history: LocalHistory::new(&dir_context.data_dir),
+            last_trashed: None,
+            last_memory_check: time_source.now(),
+            memory_warning_shown: false,
+            animations: ActiveAnimations::default(),
+            #[cfg(feature = "plugins")]
+            watched_plugins: HashMap::new(),
+            #[cfg(feature = "plugins")]
+            last_plugin_source_poll: time_source.now(),
             lsp_progress: std::collections::HashMap::new(),
             lsp_server_statuses: std::collections::HashMap::new(),
             lsp_window_messages: Vec::new(),
             lsp_log_messages: Vec::new(),
             diagnostic_result_ids: HashMap::new(),
             stored_diagnostics: HashMap::new(),
+            server_diagnostics: HashMap::new(),
             event_broadcaster: crate::model::control_event::EventBroadcaster::default(),
             bookmarks: HashMap::new(),
+            marks: Marks::default(),
             search_case_sensitive: true,
             search_whole_word: false,
             search_use_regex: false,
@@ -1232,6 +1617,8 @@ impl Editor {
             last_auto_revert_poll: time_source.now(),
             last_file_tree_poll: time_source.now(),
             file_mod_times: HashMap::new(),
+            remote_connected: true,
+            queued_remote_saves: Vec::new(),
             dir_mod_times: HashMap::new(),
             file_rapid_change_counts: HashMap::new(),
             file_open_state: None,
@@ -1247,6 +1634,7 @@ impl Editor {
             full_redraw_requested: false,
             time_source: time_source.clone(),
             last_auto_save: time_source.now(),
+            pending_auto_saves: HashMap::new(),
             active_custom_contexts: HashSet::new(),
             editor_mode: None,
             warning_log: None,
@@ -1263,6 +1651,8 @@ impl Editor {
             previous_click_time: None,
             previous_click_position: None,
             click_count: 0,
+            previous_scroll: None,
+            scroll_streak: 0,
             settings_state: None,
             calibration_wizard: None,
             event_debug: None,
@@ -1273,6 +1663,8 @@ impl Editor {
             .unwrap_or_default(),
             color_capability,
             pending_file_opens: Vec::new(),
+            pending_startup_hooks: Vec::new(),
+            uri_schemes: HashSet::new(),
             stdin_streaming: None,
             review_hunks: Vec::new(),
             active_action_popup: None,
@@ -1280,6 +1672,8 @@ impl Editor {
             composite_view_states: HashMap::new(),
         };
 
+        editor.refresh_config_issues();
+
         #[cfg(feature = "plugins")]
         {
             editor.update_plugin_state_snapshot();
@@ -1289,15 +1683,19 @@ impl Editor {
                     crate::services::plugins::hooks::HookArgs::EditorInitialized,
                 );
             }
-            
+
             // Auto-enable modal editing mode if configured
             let auto_modal = &editor.config.auto_modal_mode;
             if auto_modal == "vi" || auto_modal == "vim" {
                 tracing::info!("Auto-enabling vi mode on startup");
-                let _ = editor.handle_action(crate::input::keybindings::Action::PluginAction("vi_mode_toggle".to_string()));
+                let _ = editor.handle_action(crate::input::keybindings::Action::PluginAction(
+                    "vi_mode_toggle".to_string(),
+                ));
             } else if auto_modal == "helix" || auto_modal == "hx" {
                 tracing::info!("Auto-enabling helix mode on startup");
-                let _ = editor.handle_action(crate::input::keybindings::Action::PluginAction("hx_mode_toggle".to_string()));
+                let _ = editor.handle_action(crate::input::keybindings::Action::PluginAction(
+                    "hx_mode_toggle".to_string(),
+                ));
             }
         }
 
@@ -1560,6 +1958,12 @@ impl Editor {
         Ok(())
     }
 
+    /// Write the timings of every rendered frame to `path` as a Chrome trace
+    pub fn start_render_trace<P: AsRef<Path>>(&mut self, path: P) -> AnyhowResult<()> {
+        self.render_profiler.start_trace(path.as_ref())?;
+        Ok(())
+    }
+
     /// Log keystroke for debugging
     pub fn log_keystroke(&mut self, key_code: &str, modifiers: &str) {
         if let Some(event_log) = self.event_logs.get_mut(&self.active_buffer()) {
@@ -1589,11 +1993,82 @@ impl Editor {
         self.process_spawner = spawner;
     }
 
-    /// Get remote connection info if editing remote files
+    /// Make an additional filesystem available for opening files
+    ///
+    /// Used to open remote files next to local ones (or files from several
+    /// hosts) in one session. Mounting the same connection twice is a no-op.
+    pub fn mount_filesystem(&mut self, fs: Arc<dyn FileSystem + Send + Sync>) {
+        let info = fs.remote_connection_info();
+        let already_mounted = self
+            .filesystem_roots()
+            .iter()
+            .any(|mounted| mounted.remote_connection_info() == info);
+        if !already_mounted {
+            self.mounted_filesystems.push(fs);
+        }
+    }
+
+    /// The primary filesystem followed by all mounted ones
+    pub(crate) fn filesystem_roots(&self) -> Vec<Arc<dyn FileSystem + Send + Sync>> {
+        std::iter::once(Arc::clone(&self.filesystem))
+            .chain(self.mounted_filesystems.iter().cloned())
+            .collect()
+    }
+
+    /// Filesystem a buffer was opened with (falls back to the primary one)
+    pub(crate) fn buffer_filesystem(
+        &self,
+        buffer_id: BufferId,
+    ) -> Arc<dyn FileSystem + Send + Sync> {
+        self.buffers
+            .get(&buffer_id)
+            .map(|state| Arc::clone(state.buffer.filesystem()))
+            .unwrap_or_else(|| Arc::clone(&self.filesystem))
+    }
+
+    /// Filesystem of the open buffer backed by `path` (falls back to the primary one)
+    pub(crate) fn filesystem_for_path(&self, path: &Path) -> Arc<dyn FileSystem + Send + Sync> {
+        self.buffers
+            .values()
+            .find(|state| state.buffer.file_path() == Some(path))
+            .map(|state| Arc::clone(state.buffer.filesystem()))
+            .unwrap_or_else(|| Arc::clone(&self.filesystem))
+    }
+
+    /// Filesystem currently shown in the file explorer
+    pub(crate) fn explorer_filesystem(&self) -> Arc<dyn FileSystem + Send + Sync> {
+        if self.explorer_root == 0 {
+            return Arc::clone(&self.filesystem);
+        }
+        self.mounted_filesystems
+            .get(self.explorer_root - 1)
+            .cloned()
+            .unwrap_or_else(|| Arc::clone(&self.filesystem))
+    }
+
+    /// Get remote connection info if the active buffer is a remote file
     ///
     /// Returns `Some("user@host")` for remote editing, `None` for local.
     pub fn remote_connection_info(&self) -> Option<&str> {
-        self.filesystem.remote_connection_info()
+        self.active_state()
+            .buffer
+            .filesystem()
+            .remote_connection_info()
+    }
+
+    /// Remote connection label for the UI, marked while a reconnect is in progress
+    pub fn remote_connection_display(&self) -> Option<String> {
+        let info = self.remote_connection_info()?;
+        if self
+            .active_state()
+            .buffer
+            .filesystem()
+            .is_remote_connected()
+        {
+            Some(info.to_string())
+        } else {
+            Some(t!("status.remote_reconnecting", host = info).to_string())
+        }
     }
 
     /// Get the status log path
@@ -1666,6 +2141,7 @@ impl Editor {
     pub fn clear_warnings(&mut self) {
         self.warning_domains.general.clear();
         self.warning_domains.lsp.clear();
+        self.warning_domains.config.clear();
         self.status_message = Some("Warnings cleared".to_string());
     }
 
@@ -1864,6 +2340,7 @@ impl Editor {
 
         // Track the previous buffer for "Switch to Previous Tab" command
         let previous = self.active_buffer();
+        self.auto_save_on_focus_change(previous);
 
         // If leaving a terminal buffer while in terminal mode, remember it should resume
         if self.terminal_mode && self.is_terminal_buffer(previous) {
@@ -1887,10 +2364,18 @@ impl Editor {
 
         // Add buffer to the active split's open_buffers (tabs) if not already there
         let active_split = self.split_manager.active_split();
+        let line_wrap_override = self
+            .buffers
+            .get(&buffer_id)
+            .and_then(|state| state.line_wrap_override);
         if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
             view_state.add_buffer(buffer_id);
             // Update the focus history (push the previous buffer we're leaving)
             view_state.push_focus(previous);
+            // Line wrap set by a language or glob scope follows the buffer
+            if let Some(line_wrap) = line_wrap_override {
+                view_state.viewport.line_wrap_enabled = line_wrap;
+            }
         }
 
         // Ensure the newly active tab is visible
@@ -1926,6 +2411,8 @@ impl Editor {
         let split_changed = previous_split != split_id;
 
         if split_changed {
+            self.auto_save_on_focus_change(previous_buffer);
+
             // Switching to a different split - exit terminal mode if active
             if self.terminal_mode && self.is_terminal_buffer(previous_buffer) {
                 self.terminal_mode = false;
@@ -1950,7 +2437,9 @@ impl Editor {
 
             // Handle buffer change side effects
             if previous_buffer != buffer_id {
-                self.position_history.commit_pending_movement();
+                if let Some(view_state) = self.split_view_states.get_mut(&previous_split) {
+                    view_state.position_history.commit_pending_movement();
+                }
                 if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
                     view_state.add_buffer(buffer_id);
                     view_state.push_focus(previous_buffer);
@@ -1995,6 +2484,26 @@ impl Editor {
             .viewport
     }
 
+    /// Get the jump list (back/forward position history) of the active split
+    pub fn active_position_history(&self) -> &PositionHistory {
+        let active_split = self.split_manager.active_split();
+        &self
+            .split_view_states
+            .get(&active_split)
+            .unwrap()
+            .position_history
+    }
+
+    /// Get the jump list of the active split (mutable)
+    pub fn active_position_history_mut(&mut self) -> &mut PositionHistory {
+        let active_split = self.split_manager.active_split();
+        &mut self
+            .split_view_states
+            .get_mut(&active_split)
+            .unwrap()
+            .position_history
+    }
+
     /// Get the display name for a buffer (filename or virtual buffer name)
     pub fn get_buffer_display_name(&self, buffer_id: BufferId) -> String {
         // Check composite buffers first
@@ -2066,6 +2575,7 @@ impl Editor {
             Event::Insert { .. } | Event::Delete { .. } | Event::BulkEdit { .. } => {
                 self.invalidate_layouts_for_buffer(self.active_buffer());
                 self.schedule_semantic_tokens_full_refresh(self.active_buffer());
+                self.keep_preview_tab(self.active_buffer());
             }
             Event::Batch { events, .. } => {
                 let has_edits = events
@@ -2074,6 +2584,7 @@ impl Editor {
                 if has_edits {
                     self.invalidate_layouts_for_buffer(self.active_buffer());
                     self.schedule_semantic_tokens_full_refresh(self.active_buffer());
+                    self.keep_preview_tab(self.active_buffer());
                 }
             }
             _ => {}
@@ -2096,6 +2607,7 @@ impl Editor {
 
         // 3. Trigger plugin hooks for this event (with pre-calculated line info)
         self.trigger_plugin_hooks_for_event(event, line_info);
+        self.trigger_did_change_hook(event);
 
         // 4. Notify LSP of the change using pre-calculated positions
         self.send_lsp_changes_for_buffer(self.active_buffer(), lsp_changes);
@@ -2299,9 +2811,70 @@ impl Editor {
         self.adjust_other_split_cursors_for_event(&bulk_edit);
         // Note: Do NOT clear search overlays - markers track through edits for F3/Shift+F3
 
+        // Edits were applied in descending position order, so each range is
+        // still valid after the previous ones
+        let changes = edits
+            .into_iter()
+            .map(
+                |(start, del_len, text)| crate::services::plugins::hooks::TextChange {
+                    start,
+                    end: start + del_len,
+                    text,
+                },
+            )
+            .collect();
+        #[cfg(feature = "plugins")]
+        self.update_plugin_state_snapshot();
+        self.fire_did_change_hook(changes);
+
         Some(bulk_edit)
     }
 
+    /// Fire the `did_change` hook with the text changes made by an event
+    fn trigger_did_change_hook(&mut self, event: &Event) {
+        fn collect(event: &Event, changes: &mut Vec<crate::services::plugins::hooks::TextChange>) {
+            match event {
+                Event::Insert { position, text, .. } => {
+                    changes.push(crate::services::plugins::hooks::TextChange {
+                        start: *position,
+                        end: *position,
+                        text: text.clone(),
+                    });
+                }
+                Event::Delete { range, .. } => {
+                    changes.push(crate::services::plugins::hooks::TextChange {
+                        start: range.start,
+                        end: range.end,
+                        text: String::new(),
+                    });
+                }
+                Event::Batch { events, .. } => {
+                    for e in events {
+                        collect(e, changes);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut changes = Vec::new();
+        collect(event, &mut changes);
+        self.fire_did_change_hook(changes);
+    }
+
+    fn fire_did_change_hook(&mut self, changes: Vec<crate::services::plugins::hooks::TextChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        self.plugin_manager.run_hook(
+            "did_change",
+            crate::services::plugins::hooks::HookArgs::DidChange {
+                buffer_id: self.active_buffer(),
+                changes,
+            },
+        );
+    }
+
     /// Trigger plugin hooks for an event (if any)
     /// line_info contains pre-calculated line numbers from BEFORE buffer modification
     fn trigger_plugin_hooks_for_event(&mut self, event: &Event, line_info: EventLineInfo) {
@@ -2689,6 +3262,11 @@ impl Editor {
         self.should_quit
     }
 
+    /// Status the process should exit with
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code
+    }
+
     /// Check if the client should detach (keep server running)
     pub fn should_detach(&self) -> bool {
         self.should_detach
@@ -2727,19 +3305,37 @@ impl Editor {
         self.session_name.as_deref()
     }
 
-    /// Queue escape sequences to be sent to the client (session mode only)
+    /// Queue escape sequences to be written to the terminal, or sent to the
+    /// client in session mode, before the next render
     pub fn queue_escape_sequences(&mut self, sequences: &[u8]) {
         self.pending_escape_sequences.extend_from_slice(sequences);
     }
 
+    /// The terminal cursor style for the current editor mode
+    pub fn cursor_style(&self) -> crate::config::CursorStyle {
+        self.config
+            .editor
+            .cursor_style_for_mode(self.editor_mode.as_deref())
+    }
+
+    /// Queue the cursor style for the current editor mode if the terminal
+    /// shows a different one
+    pub(crate) fn sync_cursor_style(&mut self) {
+        let style = self.cursor_style();
+        if style != self.terminal_cursor_style {
+            self.terminal_cursor_style = style;
+            self.queue_escape_sequences(style.to_escape_sequence());
+        }
+    }
+
     /// Take pending escape sequences, clearing the queue
     pub fn take_pending_escape_sequences(&mut self) -> Vec<u8> {
         std::mem::take(&mut self.pending_escape_sequences)
     }
 
-    /// Check if the editor should restart with a new working directory
+    /// Check if the editor should restart (new working directory or profile)
     pub fn should_restart(&self) -> bool {
-        self.restart_with_dir.is_some()
+        self.restart_with_dir.is_some() || self.restart_with_profile.is_some()
     }
 
     /// Take the restart directory, clearing the restart request
@@ -2748,6 +3344,18 @@ impl Editor {
         self.restart_with_dir.take()
     }
 
+    /// Take the requested profile, clearing the request
+    /// Returns the profile name if a profile switch was requested
+    pub fn take_restart_profile(&mut self) -> Option<String> {
+        self.restart_with_profile.take()
+    }
+
+    /// Take the binary to re-exec after the editor exits
+    /// Returns the executable if an installed update should be started
+    pub fn take_relaunch_executable(&mut self) -> Option<PathBuf> {
+        self.relaunch_executable.take()
+    }
+
     /// Request the editor to restart with a new working directory
     /// This triggers a clean shutdown and restart with the new project root
     /// Request a full hardware terminal clear and redraw on the next frame.
@@ -2760,6 +3368,10 @@ impl Editor {
     pub fn take_full_redraw_request(&mut self) -> bool {
         let requested = self.full_redraw_requested;
         self.full_redraw_requested = false;
+        if requested {
+            // Clearing the terminal removes the images too
+            self.drawn_images.clear();
+        }
         requested
     }
 
@@ -2821,6 +3433,34 @@ impl Editor {
             .count()
     }
 
+    /// Display name, file path and modified flag of each buffer shown in tabs,
+    /// in the order the buffers were created
+    pub fn listed_buffers(&self) -> Vec<(String, Option<PathBuf>, bool)> {
+        let mut ids: Vec<BufferId> = self
+            .buffers
+            .keys()
+            .copied()
+            .filter(|id| {
+                !self
+                    .buffer_metadata
+                    .get(id)
+                    .map(|m| m.hidden_from_tabs)
+                    .unwrap_or(false)
+            })
+            .collect();
+        ids.sort_by_key(|id| id.0);
+        ids.into_iter()
+            .map(|id| {
+                let state = &self.buffers[&id];
+                (
+                    self.get_buffer_display_name(id),
+                    state.buffer.file_path().map(Path::to_path_buf),
+                    state.buffer.is_modified(),
+                )
+            })
+            .collect()
+    }
+
     /// Resize all buffers to match new terminal size
     pub fn resize(&mut self, width: u16, height: u16) {
         // Update terminal dimensions for future buffer creation
@@ -2834,6 +3474,9 @@ impl Editor {
 
         // Resize visible terminal PTYs to match new dimensions
         self.resize_visible_terminals();
+
+        // The terminal is cleared on resize, so images are drawn again
+        self.drawn_images.clear();
     }
 
     // Prompt/Minibuffer control methods
@@ -2993,6 +3636,10 @@ impl Editor {
             // Buffer mode
             let query = &input[1..];
             self.get_buffer_suggestions(query)
+        } else if input.starts_with('@') {
+            // Symbol mode
+            let query = &input[1..];
+            self.get_symbol_suggestions(query)
         } else if input.starts_with(':') {
             // Go to line mode
             let line_str = &input[1..];
@@ -3065,6 +3712,59 @@ impl Editor {
         suggestions.into_iter().map(|(s, _)| s).collect()
     }
 
+    /// Symbols from the workspace index matching `query`, best first
+    ///
+    /// `None` if there is no index or it isn't ready yet.
+    fn search_workspace_symbols(
+        &self,
+        query: &str,
+    ) -> Option<Vec<crate::services::workspace_index::Symbol>> {
+        const MAX_SYMBOL_RESULTS: usize = 100;
+        self.workspace_index
+            .as_ref()?
+            .search_symbols(query, MAX_SYMBOL_RESULTS)
+    }
+
+    /// Get symbol suggestions for Quick Open
+    fn get_symbol_suggestions(&self, query: &str) -> Vec<Suggestion> {
+        let message = |text: String| {
+            vec![Suggestion {
+                text,
+                description: None,
+                value: None,
+                disabled: true,
+                keybinding: None,
+                source: None,
+            }]
+        };
+        if self.workspace_index.is_none() {
+            return message(t!("quick_open.symbols_disabled").to_string());
+        }
+        let Some(symbols) = self.search_workspace_symbols(query) else {
+            return message(t!("quick_open.indexing").to_string());
+        };
+        if symbols.is_empty() {
+            return message(t!("quick_open.no_symbols").to_string());
+        }
+
+        symbols
+            .into_iter()
+            .map(|symbol| Suggestion {
+                description: Some(format!(
+                    "{}  {}:{}",
+                    symbol.kind.label(),
+                    symbol.path,
+                    symbol.line
+                )),
+                text: symbol.name.clone(),
+                value: Some(symbol.name),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect()
+    }
+
     /// Get go-to-line suggestions for Quick Open
     fn get_goto_line_suggestions(&self, line_str: &str) -> Vec<Suggestion> {
         if line_str.is_empty() {
@@ -3144,9 +3844,7 @@ impl Editor {
                 // Also cancel interactive replace if active
                 self.interactive_replace_state = None;
                 // Clear search highlights from current buffer
-                let ns = self.search_namespace.clone();
-                let state = self.active_state_mut();
-                state.overlays.clear_namespace(&ns, &mut state.marker_list);
+                self.clear_search_overlays();
             }
         }
     }
@@ -3187,7 +3885,7 @@ impl Editor {
                 .file_path()
                 .and_then(|path| path.parent())
                 .map(|p| p.to_path_buf())
-                .unwrap_or_else(|| self.working_dir.clone())
+                .unwrap_or_else(|| self.shell_or_working_dir())
         };
 
         // Create the file open state with config-based show_hidden setting
@@ -3359,6 +4057,9 @@ impl Editor {
                 PromptType::Search | PromptType::ReplaceSearch | PromptType::QueryReplaceSearch => {
                     self.clear_search_highlights();
                 }
+                PromptType::Replace { .. } | PromptType::QueryReplace { .. } => {
+                    self.clear_replace_preview();
+                }
                 PromptType::Plugin { custom_type } => {
                     // Fire plugin hook for prompt cancellation
                     use crate::services::plugins::hooks::HookArgs;
@@ -3377,18 +4078,30 @@ impl Editor {
                     };
                     self.apply_event_to_active_buffer(&remove_overlay_event);
                 }
+                PromptType::ConfirmQuitWithModified => {
+                    // Staying in the editor also drops a pending restart into an update
+                    self.relaunch_executable = None;
+                }
                 PromptType::OpenFile | PromptType::SwitchProject | PromptType::SaveFileAs => {
                     // Clear file browser state
                     self.file_open_state = None;
                     self.file_browser_layout = None;
                 }
-                PromptType::AsyncPrompt => {
+                PromptType::AsyncPrompt | PromptType::AsyncQuickPick { .. } => {
                     // Resolve the pending async prompt callback with null (cancelled)
                     if let Some(callback_id) = self.pending_async_prompt_callback.take() {
                         self.plugin_manager
                             .resolve_callback(callback_id, "null".to_string());
                     }
                 }
+                PromptType::PluginPermission {
+                    plugin_name,
+                    permission,
+                } => {
+                    // Dismissing the prompt denies the permission for this session
+                    self.plugin_manager
+                        .resolve_permission(plugin_name.clone(), *permission, false);
+                }
                 _ => {}
             }
         }
@@ -3454,13 +4167,24 @@ impl Editor {
                     | PromptType::SwitchProject
                     | PromptType::SaveFileAs
                     | PromptType::StopLspServer
+                    | PromptType::CancelBackgroundTask
+                    | PromptType::ReloadPlugin
+                    | PromptType::LocalHistory { .. }
+                    | PromptType::LocalHistoryVersion { .. }
+                    | PromptType::JumpToNamedMark
+                    | PromptType::QuickfixItem
+                    | PromptType::QuickfixHistory
+                    | PromptType::OpenSymlink { .. }
                     | PromptType::SelectTheme { .. }
                     | PromptType::SelectLocale
                     | PromptType::SwitchToTab
+                    | PromptType::FollowUser
+                    | PromptType::SetTabGroup { .. }
                     | PromptType::SetLanguage
                     | PromptType::SetEncoding
                     | PromptType::SetLineEnding
                     | PromptType::Plugin { .. }
+                    | PromptType::AsyncQuickPick { .. }
             ) {
                 // Use the selected suggestion if any
                 if let Some(selected_idx) = prompt.selected_suggestion {
@@ -3610,6 +4334,11 @@ impl Editor {
         self.status_message = Some(message);
     }
 
+    /// Clear the status message set by `set_status_message`
+    pub fn clear_status_message(&mut self) {
+        self.status_message = None;
+    }
+
     /// Get the current status message
     pub fn get_status_message(&self) -> Option<&String> {
         self.plugin_status_message
@@ -3673,7 +4402,9 @@ impl Editor {
                     history.reset_navigation();
                 }
             }
-            PromptType::Replace { .. } | PromptType::QueryReplace { .. } => {
+            PromptType::Replace { search } | PromptType::QueryReplace { search } => {
+                // Preview the replacement of each visible match as user types
+                self.update_replace_preview(&search, &input);
                 // Reset history navigation when user types - allows Up to navigate history
                 if let Some(history) = self.prompt_histories.get_mut("replace") {
                     history.reset_navigation();
@@ -3713,8 +4444,17 @@ impl Editor {
                 }
             }
             PromptType::SwitchToTab
+            | PromptType::SetTabGroup { .. }
             | PromptType::SelectTheme { .. }
             | PromptType::StopLspServer
+            | PromptType::CancelBackgroundTask
+            | PromptType::ReloadPlugin
+            | PromptType::LocalHistory { .. }
+            | PromptType::LocalHistoryVersion { .. }
+            | PromptType::JumpToNamedMark
+            | PromptType::QuickfixItem
+            | PromptType::QuickfixHistory
+            | PromptType::OpenSymlink { .. }
             | PromptType::SetLanguage
             | PromptType::SetEncoding
             | PromptType::SetLineEnding => {
@@ -3728,6 +4468,13 @@ impl Editor {
                     prompt.filter_suggestions(true);
                 }
             }
+            PromptType::AsyncQuickPick {
+                match_on_description,
+            } => {
+                if let Some(prompt) = &mut self.prompt {
+                    prompt.filter_suggestions(match_on_description);
+                }
+            }
             _ => {}
         }
     }
@@ -3753,8 +4500,12 @@ impl Editor {
 
         for message in messages {
             match message {
-                AsyncMessage::LspDiagnostics { uri, diagnostics } => {
-                    self.handle_lsp_diagnostics(uri, diagnostics);
+                AsyncMessage::LspDiagnostics {
+                    language,
+                    uri,
+                    diagnostics,
+                } => {
+                    self.handle_lsp_diagnostics(language, uri, diagnostics);
                 }
                 AsyncMessage::LspInitialized {
                     language,
@@ -3906,12 +4657,19 @@ impl Editor {
                 }
                 AsyncMessage::LspPulledDiagnostics {
                     request_id: _,
+                    language,
                     uri,
                     result_id,
                     diagnostics,
                     unchanged,
                 } => {
-                    self.handle_lsp_pulled_diagnostics(uri, result_id, diagnostics, unchanged);
+                    self.handle_lsp_pulled_diagnostics(
+                        language,
+                        uri,
+                        result_id,
+                        diagnostics,
+                        unchanged,
+                    );
                 }
                 AsyncMessage::LspInlayHints {
                     request_id,
@@ -3920,6 +4678,20 @@ impl Editor {
                 } => {
                     self.handle_lsp_inlay_hints(request_id, uri, hints);
                 }
+                AsyncMessage::LspDocumentHighlight {
+                    request_id,
+                    uri,
+                    highlights,
+                } => {
+                    self.handle_lsp_document_highlight(request_id, uri, highlights);
+                }
+                AsyncMessage::LspCodeLens {
+                    request_id,
+                    uri,
+                    lenses,
+                } => {
+                    self.handle_lsp_code_lens(request_id, uri, lenses);
+                }
                 AsyncMessage::LspSemanticTokens {
                     request_id,
                     uri,
@@ -4050,6 +4822,22 @@ impl Editor {
                 AsyncMessage::FileOpenShortcutsLoaded(shortcuts) => {
                     self.handle_file_open_shortcuts_loaded(shortcuts);
                 }
+                AsyncMessage::FileLoaded { request_id, result } => {
+                    self.handle_file_loaded(request_id, result);
+                }
+                AsyncMessage::FileSaved {
+                    buffer_id,
+                    path,
+                    result,
+                } => {
+                    self.handle_file_saved(buffer_id, path, result);
+                }
+                AsyncMessage::SelfUpdateFinished(result) => {
+                    self.handle_self_update_finished(result);
+                }
+                AsyncMessage::WatchTaskFinished { run_id, result } => {
+                    self.handle_watch_task_finished(run_id, result);
+                }
                 AsyncMessage::TerminalOutput { terminal_id } => {
                     // Terminal output received - check if we should auto-jump back to terminal mode
                     tracing::trace!("Terminal output received for {:?}", terminal_id);
@@ -4166,6 +4954,10 @@ impl Editor {
         // Process TypeScript plugin commands
         let processed_any_commands = self.process_plugin_commands();
 
+        // Permission requests that arrived while another prompt was open
+        let shown_permission_prompt =
+            self.show_next_permission_prompt() || self.show_pending_workspace_trust_prompt();
+
         // Process pending plugin action completions
         #[cfg(feature = "plugins")]
         self.process_pending_plugin_actions();
@@ -4193,8 +4985,53 @@ impl Editor {
         let file_changes = self.poll_file_changes();
         let tree_changes = self.poll_file_tree_changes();
 
+        // Track SSH connection drops/reconnects and flush saves queued while offline
+        let remote_changes = self.poll_remote_connection();
+
+        // Save buffers whose auto-save delay has elapsed
+        let auto_save_changes = self.poll_auto_save();
+
+        // Refresh the git branch for the status line
+        let git_branch_changes = self.poll_git_branch();
+
+        // Follow OS dark-mode changes for a light/dark theme pair
+        let appearance_changes = self.poll_appearance();
+
+        // Re-apply themes whose files were edited
+        let theme_file_changes = self.poll_theme_files();
+
+        // Animate the background task spinner
+        let task_changes = self.poll_plugin_tasks() | self.poll_background_io();
+
+        // Advance smooth scrolling, cursor flashes and panel transitions
+        let animation_changes = self.poll_animations();
+
+        // Exchange edits with editors sharing a buffer
+        let collab_changes = self.poll_collab_share();
+
+        // Reload plugins whose sources changed (hot reload)
+        let plugin_reloads = self.poll_plugin_sources();
+
+        // Warn when estimated memory use goes above the configured threshold
+        let memory_warning = self.poll_memory_usage();
+
         // Trigger render if any async messages, plugin commands were processed, or plugin requested render
-        needs_render || processed_any_commands || plugin_render || file_changes || tree_changes
+        needs_render
+            || processed_any_commands
+            || shown_permission_prompt
+            || plugin_render
+            || file_changes
+            || tree_changes
+            || remote_changes
+            || auto_save_changes
+            || git_branch_changes
+            || appearance_changes
+            || theme_file_changes
+            || task_changes
+            || animation_changes
+            || collab_changes
+            || plugin_reloads
+            || memory_warning
     }
 
     /// Update LSP status bar string from active progress operations
@@ -4278,7 +5115,17 @@ impl Editor {
             for (buffer_id, state) in &self.buffers {
                 let buffer_info = BufferInfo {
                     id: *buffer_id,
-                    path: state.buffer.file_path().map(|p| p.to_path_buf()),
+                    // Plugin virtual buffers report their URI as their path
+                    path: state
+                        .buffer
+                        .file_path()
+                        .map(|p| p.to_path_buf())
+                        .or_else(|| {
+                            self.buffer_metadata
+                                .get(buffer_id)
+                                .and_then(|meta| meta.virtual_uri())
+                                .map(std::path::PathBuf::from)
+                        }),
                     modified: state.buffer.is_modified(),
                     length: state.buffer.len(),
                 };
@@ -4397,6 +5244,13 @@ impl Editor {
             PluginCommand::DeleteRange { buffer_id, range } => {
                 self.handle_delete_range(buffer_id, range);
             }
+            PluginCommand::ReplaceRange {
+                buffer_id,
+                range,
+                text,
+            } => {
+                self.handle_replace_range(buffer_id, range, text);
+            }
             PluginCommand::InsertAtCursor { text } => {
                 self.handle_insert_at_cursor(text);
             }
@@ -4542,6 +5396,12 @@ impl Editor {
             } => {
                 self.handle_set_buffer_cursor(buffer_id, position);
             }
+            PluginCommand::SetSelections {
+                buffer_id,
+                selections,
+            } => {
+                self.handle_set_selections(buffer_id, selections);
+            }
 
             // ==================== View/Layout Commands ====================
             PluginCommand::SetLayoutHints {
@@ -4608,9 +5468,28 @@ impl Editor {
             PluginCommand::SetStatus { message } => {
                 self.handle_set_status(message);
             }
+            PluginCommand::SetStatusSegment { id, text } => {
+                self.handle_set_status_segment(id, text);
+            }
+            PluginCommand::SetQuickfixList {
+                title,
+                entries,
+                selected,
+            } => {
+                self.handle_set_quickfix_list(title, entries, selected);
+            }
+            PluginCommand::SetMark { name } => {
+                self.handle_set_mark(&name);
+            }
+            PluginCommand::JumpToMark { name, line_start } => {
+                self.handle_jump_to_mark(&name, line_start);
+            }
             PluginCommand::ApplyTheme { theme_name } => {
                 self.apply_theme(&theme_name);
             }
+            PluginCommand::SetThemeColor { key, color } => {
+                self.handle_set_theme_color(key, color);
+            }
             PluginCommand::ReloadConfig => {
                 self.reload_config();
             }
@@ -4650,6 +5529,14 @@ impl Editor {
             } => {
                 self.handle_start_prompt_async(label, initial_value, callback_id);
             }
+            PluginCommand::StartQuickPickAsync {
+                title,
+                items,
+                match_on_description,
+                callback_id,
+            } => {
+                self.handle_start_quick_pick_async(title, items, match_on_description, callback_id);
+            }
             PluginCommand::SetPromptSuggestions { suggestions } => {
                 self.handle_set_prompt_suggestions(suggestions);
             }
@@ -4685,6 +5572,9 @@ impl Editor {
             } => {
                 return self.handle_open_file_in_split(split_id, path, line, column);
             }
+            PluginCommand::RegisterUriScheme { scheme } => {
+                self.uri_schemes.insert(scheme);
+            }
             PluginCommand::ShowBuffer { buffer_id } => {
                 self.handle_show_buffer(buffer_id);
             }
@@ -4795,6 +5685,29 @@ impl Editor {
                 }
             }
 
+            // ==================== Background Tasks ====================
+            PluginCommand::StartTask {
+                task_id,
+                plugin_name,
+                name,
+            } => {
+                self.handle_start_task(task_id, plugin_name, name);
+            }
+            PluginCommand::UpdateTaskProgress {
+                task_id,
+                message,
+                percentage,
+            } => {
+                self.handle_update_task_progress(task_id, message, percentage);
+            }
+            PluginCommand::FinishTask {
+                task_id,
+                result,
+                error,
+            } => {
+                self.handle_finish_task(task_id, result, error);
+            }
+
             PluginCommand::SpawnBackgroundProcess {
                 process_id,
                 command,
@@ -4941,6 +5854,7 @@ impl Editor {
                 show_cursors,
                 editing_disabled,
                 hidden_from_tabs,
+                uri,
                 request_id,
             } => {
                 let buffer_id = self.create_virtual_buffer(name.clone(), mode.clone(), read_only);
@@ -4965,10 +5879,11 @@ impl Editor {
                     );
                 }
 
-                // Apply hidden_from_tabs to buffer metadata
-                if hidden_from_tabs {
-                    if let Some(meta) = self.buffer_metadata.get_mut(&buffer_id) {
-                        meta.hidden_from_tabs = true;
+                // Apply hidden_from_tabs and the plugin URI to buffer metadata
+                if let Some(meta) = self.buffer_metadata.get_mut(&buffer_id) {
+                    meta.hidden_from_tabs |= hidden_from_tabs;
+                    if let BufferKind::Virtual { uri: slot, .. } = &mut meta.kind {
+                        *slot = uri;
                     }
                 }
 
@@ -5160,6 +6075,21 @@ impl Editor {
                     );
                 }
             }
+            PluginCommand::CreateOutputBuffer { name, request_id } => {
+                self.handle_create_output_buffer(name, request_id);
+            }
+            PluginCommand::AppendOutput { buffer_id, text } => {
+                self.handle_append_output(buffer_id, text);
+            }
+            PluginCommand::ClearOutput { buffer_id } => {
+                self.handle_clear_output(buffer_id);
+            }
+            PluginCommand::RequestPluginPermission {
+                plugin_name,
+                permission,
+            } => {
+                self.handle_request_plugin_permission(plugin_name, permission);
+            }
             PluginCommand::SetVirtualBufferContent { buffer_id, entries } => {
                 match self.set_virtual_buffer_content(buffer_id, entries) {
                     Ok(()) => {
@@ -5276,6 +6206,9 @@ impl Editor {
             PluginCommand::ExecuteAction { action_name } => {
                 self.handle_execute_action(action_name);
             }
+            PluginCommand::SetExitCode { code } => {
+                self.exit_code = code;
+            }
             PluginCommand::ExecuteActions { actions } => {
                 self.handle_execute_actions(actions);
             }
@@ -5573,6 +6506,7 @@ impl Editor {
         match self.plugin_manager.unload_plugin(&name) {
             Ok(()) => {
                 tracing::info!("Unloaded plugin: {}", name);
+                self.plugin_tasks.retain(|t| t.plugin_name != name);
                 self.plugin_manager
                     .resolve_callback(callback_id, "true".to_string());
             }
@@ -5587,7 +6521,7 @@ impl Editor {
     /// Reload a plugin by name
     #[cfg(feature = "plugins")]
     fn handle_reload_plugin(&mut self, name: String, callback_id: JsCallbackId) {
-        match self.plugin_manager.reload_plugin(&name) {
+        match self.reload_plugin_sources(&name) {
             Ok(()) => {
                 tracing::info!("Reloaded plugin: {}", name);
                 self.plugin_manager
@@ -5704,8 +6638,19 @@ impl Editor {
 
     /// Set the global editor mode (for vi mode)
     fn handle_set_editor_mode(&mut self, mode: Option<String>) {
-        self.editor_mode = mode.clone();
         tracing::debug!("Set editor mode: {:?}", mode);
+        if self.editor_mode == mode {
+            return;
+        }
+        let old_mode = std::mem::replace(&mut self.editor_mode, mode.clone());
+        self.sync_cursor_style();
+        self.plugin_manager.run_hook(
+            "mode_changed",
+            crate::services::plugins::hooks::HookArgs::ModeChanged {
+                old_mode,
+                new_mode: mode,
+            },
+        );
     }
 
     /// Get the byte offset of the start of a line in the active buffer
@@ -5885,7 +6830,7 @@ impl Editor {
 /// Supports:
 /// - Single characters: "a", "q", etc.
 /// - Function keys: "F1", "F2", etc.
-/// - Special keys: "RET", "TAB", "ESC", "SPC", "DEL", "BS"
+/// - Special keys: "RET", "TAB", "BACKTAB", "ESC", "SPC", "DEL", "BS"
 /// - Modifiers: "C-" (Control), "M-" (Alt/Meta), "S-" (Shift)
 /// - Combinations: "C-n", "M-x", "C-M-s", etc.
 fn parse_key_string(key_str: &str) -> Option<(KeyCode, KeyModifiers)> {
@@ -5916,6 +6861,7 @@ fn parse_key_string(key_str: &str) -> Option<(KeyCode, KeyModifiers)> {
     let code = match upper.as_str() {
         "RET" | "RETURN" | "ENTER" => KeyCode::Enter,
         "TAB" => KeyCode::Tab,
+        "BACKTAB" => KeyCode::BackTab,
         "ESC" | "ESCAPE" => KeyCode::Esc,
         "SPC" | "SPACE" => KeyCode::Char(' '),
         "DEL" | "DELETE" => KeyCode::Delete,
@@ -11,6 +11,7 @@ use super::*;
 use crate::input::keybindings::Action;
 use crate::model::event::{SplitDirection, SplitId};
 use crate::services::plugins::hooks::HookArgs;
+use crate::services::render_profile::{self, Phase};
 use crate::view::popup_mouse::{popup_areas_to_layout_info, PopupHitTester};
 use crate::view::prompt::PromptType;
 use crate::view::ui::tabs::TabHit;
@@ -26,9 +27,14 @@ impl Editor {
     ) -> AnyhowResult<bool> {
         use crossterm::event::{MouseButton, MouseEventKind};
 
+        let _profile = render_profile::scope(Phase::Events);
         let col = mouse_event.column;
         let row = mouse_event.row;
 
+        if !matches!(mouse_event.kind, MouseEventKind::Moved) {
+            self.finish_scroll_animation();
+        }
+
         // Detect multi-click (double/triple) for left button down events
         let (is_double_click, is_triple_click) =
             if matches!(mouse_event.kind, MouseEventKind::Down(MouseButton::Left)) {
@@ -216,15 +222,16 @@ impl Editor {
                 self.update_lsp_hover_state(col, row);
             }
             MouseEventKind::ScrollUp => {
+                let delta = -self.mouse_wheel_lines(false);
                 // Check if prompt with suggestions is active and should handle scroll
-                if self.handle_prompt_scroll(-3) {
+                if self.handle_prompt_scroll(delta) {
                     needs_render = true;
-                } else if self.is_file_open_active() && self.handle_file_open_scroll(-3) {
+                } else if self.is_file_open_active() && self.handle_file_open_scroll(delta) {
                     // Check if file browser is active and should handle scroll
                     needs_render = true;
                 } else if self.is_mouse_over_any_popup(col, row) {
                     // Scroll the popup content (works for all popups including completion)
-                    self.scroll_popup(-3);
+                    self.scroll_popup(delta);
                     needs_render = true;
                 } else {
                     // If in terminal mode, exit to scrollback mode first so scrolling works
@@ -235,21 +242,22 @@ impl Editor {
                     }
                     // Dismiss hover/signature help popups on scroll
                     self.dismiss_transient_popups();
-                    self.handle_mouse_scroll(col, row, -3)?;
+                    self.handle_mouse_scroll(col, row, delta)?;
                     // Sync viewport from SplitViewState to EditorState so rendering sees the scroll
                     self.sync_split_view_state_to_editor_state();
                     needs_render = true;
                 }
             }
             MouseEventKind::ScrollDown => {
+                let delta = self.mouse_wheel_lines(true);
                 // Check if prompt with suggestions is active and should handle scroll
-                if self.handle_prompt_scroll(3) {
+                if self.handle_prompt_scroll(delta) {
                     needs_render = true;
-                } else if self.is_file_open_active() && self.handle_file_open_scroll(3) {
+                } else if self.is_file_open_active() && self.handle_file_open_scroll(delta) {
                     needs_render = true;
                 } else if self.is_mouse_over_any_popup(col, row) {
                     // Scroll the popup content (works for all popups including completion)
-                    self.scroll_popup(3);
+                    self.scroll_popup(delta);
                     needs_render = true;
                 } else {
                     // If in terminal mode, exit to scrollback mode first so scrolling works
@@ -260,7 +268,7 @@ impl Editor {
                     }
                     // Dismiss hover/signature help popups on scroll
                     self.dismiss_transient_popups();
-                    self.handle_mouse_scroll(col, row, 3)?;
+                    self.handle_mouse_scroll(col, row, delta)?;
                     // Sync viewport from SplitViewState to EditorState so rendering sees the scroll
                     self.sync_split_view_state_to_editor_state();
                     needs_render = true;
@@ -280,6 +288,65 @@ impl Editor {
         Ok(needs_render)
     }
 
+    /// Lines to scroll for one wheel notch, applying `mouse_scroll_acceleration`
+    /// when notches in the same direction arrive within 100ms of each other.
+    fn mouse_wheel_lines(&mut self, down: bool) -> i32 {
+        let base = self.config.editor.mouse_scroll_lines.max(1) as i32;
+        if !self.config.editor.mouse_scroll_acceleration {
+            return base;
+        }
+
+        let now = self.time_source.now();
+        let rapid = self.previous_scroll.is_some_and(|(time, was_down)| {
+            was_down == down && now.duration_since(time) < std::time::Duration::from_millis(100)
+        });
+        self.scroll_streak = if rapid {
+            self.scroll_streak.saturating_add(1)
+        } else {
+            0
+        };
+        self.previous_scroll = Some((now, down));
+
+        // Every two rapid notches add another multiple of the base, up to 4x
+        base * (1 + (self.scroll_streak as i32 / 2).min(3))
+    }
+
+    /// Buffer position of the scrollbar mark on a screen row, if there is one
+    fn scrollbar_mark_position(
+        &self,
+        buffer_id: BufferId,
+        scrollbar_rect: ratatui::layout::Rect,
+        row: u16,
+    ) -> Option<usize> {
+        let state = self.buffers.get(&buffer_id)?;
+        let marks = crate::view::scrollbar_marks::scrollbar_marks(
+            state,
+            &self.theme,
+            scrollbar_rect.height as usize,
+            self.config.editor.large_file_threshold_bytes,
+        );
+        let relative_row = row.saturating_sub(scrollbar_rect.y) as usize;
+        crate::view::scrollbar_marks::mark_at_row(&marks, relative_row).map(|mark| mark.position)
+    }
+
+    /// Move the primary cursor to a clicked scrollbar mark
+    fn jump_to_scrollbar_mark(&mut self, position: usize) {
+        let state = self.active_state();
+        let cursor_id = state.cursors.primary_id();
+        let cursor = *state.cursors.primary();
+        let event = Event::MoveCursor {
+            cursor_id,
+            old_position: cursor.position,
+            new_position: position,
+            old_anchor: cursor.anchor,
+            new_anchor: None,
+            old_sticky_column: cursor.sticky_column,
+            new_sticky_column: 0,
+        };
+        self.active_event_log_mut().append(event.clone());
+        self.apply_event_to_active_buffer(&event);
+    }
+
     /// Update the current hover target based on mouse position
     /// Returns true if the hover target changed (requiring a re-render)
     pub(super) fn update_hover_target(&mut self, col: u16, row: u16) -> bool {
@@ -448,6 +515,16 @@ impl Editor {
             }
         }
 
+        // Handle tab name hover - show where a linked file leads
+        if old_target != new_target {
+            if matches!(old_target, Some(HoverTarget::TabName(..))) {
+                self.dismiss_file_explorer_status_tooltip();
+            }
+            if let Some(HoverTarget::TabName(buffer_id, _)) = new_target {
+                return self.show_tab_link_tooltip(buffer_id, col, row) || changed;
+            }
+        }
+
         changed
     }
 
@@ -1394,6 +1471,11 @@ impl Editor {
                     self.mouse_state.drag_start_view_line_offset =
                         Some(view_state.viewport.top_view_line_offset);
                 }
+            } else if let Some(position) =
+                self.scrollbar_mark_position(buffer_id, scrollbar_rect, row)
+            {
+                // Click on a mark - jump to what it marks
+                self.jump_to_scrollbar_mark(position);
             } else {
                 // Click on track - jump to position
                 self.mouse_state.dragging_scrollbar = Some(split_id);
@@ -1538,6 +1620,7 @@ impl Editor {
                     t!("error.cannot_close_split", error = e.to_string()).to_string(),
                 );
             } else {
+                self.remove_scroll_lock(split_id);
                 // Update active buffer to match the new active split
                 let new_active_split = self.split_manager.active_split();
                 if let Some(buffer_id) = self.split_manager.buffer_for_split(new_active_split) {
@@ -1836,6 +1919,18 @@ impl Editor {
             return Ok(());
         };
 
+        // Dragging past the top or bottom edge scrolls the split, faster the
+        // further out the mouse is; the selection extends to the edge row
+        let content_bottom = content_rect.y + content_rect.height;
+        let edge_scroll = if row < content_rect.y {
+            -((content_rect.y - row) as i32)
+        } else if row >= content_bottom {
+            (row - content_bottom + 1) as i32
+        } else {
+            0
+        };
+        let row = row.clamp(content_rect.y, content_bottom.saturating_sub(1));
+
         // Get cached view line mappings for this split
         let cached_mappings = self
             .cached_layout
@@ -1884,6 +1979,10 @@ impl Editor {
             state.apply(&event);
         }
 
+        if edge_scroll != 0 {
+            self.scroll_split_lines(split_id, buffer_id, edge_scroll);
+        }
+
         Ok(())
     }
 
@@ -2069,6 +2168,12 @@ impl Editor {
             TabContextMenuItem::CloseAll => {
                 self.close_all_tabs_in_split(split_id);
             }
+            TabContextMenuItem::TogglePin => {
+                self.toggle_pin_tab_in_split(buffer_id, split_id);
+            }
+            TabContextMenuItem::SetGroup => {
+                self.start_set_tab_group_prompt(buffer_id, split_id);
+            }
         }
 
         Ok(())
@@ -2203,6 +2308,60 @@ impl Editor {
         }
     }
 
+    /// Show a tooltip for a tab whose file is a symlink or has hard links
+    ///
+    /// Returns true if a tooltip was shown.
+    fn show_tab_link_tooltip(&mut self, buffer_id: BufferId, col: u16, row: u16) -> bool {
+        use crate::primitives::display_width::str_width;
+        use crate::view::popup::{Popup, PopupPosition};
+        use ratatui::style::Style;
+
+        let Some(path) = self
+            .buffers
+            .get(&buffer_id)
+            .and_then(|state| state.buffer.file_path())
+            .map(|p| p.to_path_buf())
+        else {
+            return false;
+        };
+        // Hovering shouldn't wait on a remote host
+        let filesystem = self.buffer_filesystem(buffer_id);
+        if filesystem.remote_connection_info().is_some() {
+            return false;
+        }
+
+        let mut lines = Vec::new();
+        if let Ok(target) = filesystem.read_link(&path) {
+            lines.push(t!("symlink.link_to", target = target.display().to_string()).to_string());
+        }
+        if let Some(links) = filesystem
+            .metadata(&path)
+            .ok()
+            .and_then(|meta| meta.hard_links)
+            .filter(|&links| links > 1)
+        {
+            lines.push(t!("symlink.hard_links", count = links).to_string());
+        }
+        if lines.is_empty() {
+            return false;
+        }
+        lines.insert(0, path.display().to_string());
+
+        let width = lines.iter().map(|line| str_width(line)).max().unwrap_or(0) as u16 + 4;
+        let mut popup = Popup::text(lines, &self.theme);
+        popup.transient = true;
+        popup.position = PopupPosition::Fixed { x: col, y: row + 1 };
+        popup.width = width.min(self.terminal_width.saturating_sub(col)).max(10);
+        popup.max_height = 5;
+        popup.border_style = Style::default().fg(self.theme.popup_border_fg);
+        popup.background_style = Style::default().bg(self.theme.popup_bg);
+
+        if let Some(state) = self.buffers.get_mut(&self.active_buffer()) {
+            state.popups.show(popup);
+        }
+        true
+    }
+
     /// Dismiss the file explorer status tooltip
     fn dismiss_file_explorer_status_tooltip(&mut self) {
         // Dismiss any transient popups
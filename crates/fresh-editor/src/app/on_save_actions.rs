@@ -9,7 +9,7 @@ use std::process::{Command, Stdio};
 use std::time::Duration;
 
 use super::Editor;
-use crate::config::{FormatterConfig, OnSaveAction};
+use crate::config::{BufferConfig, FormatterConfig, OnSaveAction};
 use crate::model::event::Event;
 use rust_i18n::t;
 
@@ -34,16 +34,24 @@ impl Editor {
             None => return Ok(false),
         };
 
+        // Resolve settings for the buffer's language and any matching glob scopes
+        let buffer_config = BufferConfig::resolve_for_file(
+            &self.config,
+            Some(&self.active_state().language),
+            Some(&path),
+            Some(&self.working_dir),
+        );
+
         let mut ran_any_action = false;
 
         // Run whitespace cleanup actions first (before formatter)
-        if self.config.editor.trim_trailing_whitespace_on_save {
+        if buffer_config.trim_trailing_whitespace_on_save {
             if self.trim_trailing_whitespace()? {
                 ran_any_action = true;
             }
         }
 
-        if self.config.editor.ensure_final_newline_on_save {
+        if buffer_config.ensure_final_newline_on_save {
             if self.ensure_final_newline()? {
                 ran_any_action = true;
             }
@@ -57,20 +65,12 @@ impl Editor {
             self.active_event_log_mut().mark_saved();
         }
 
-        // Get language from buffer's stored state
-        let language = self.active_state().language.clone();
-
-        let lang_config = match self.config.languages.get(&language) {
-            Some(lc) => lc.clone(),
-            None => return Ok(ran_any_action),
-        };
-
         // Run formatter if format_on_save is enabled
-        if lang_config.format_on_save {
-            if let Some(ref formatter) = lang_config.formatter {
+        if buffer_config.format_on_save {
+            if let Some(ref formatter) = buffer_config.formatter {
                 match self.run_formatter(formatter, &path) {
                     ActionResult::Success(output) => {
-                        self.replace_buffer_with_output(&output)?;
+                        self.replace_buffer_with_output(&output, "On-save format")?;
                         // Re-save after formatting
                         if let Err(e) = self.active_state_mut().buffer.save() {
                             return Err(format!("Failed to re-save after format: {}", e));
@@ -95,7 +95,7 @@ impl Editor {
         let project_root = std::env::current_dir()
             .unwrap_or_else(|_| path.parent().unwrap_or(Path::new(".")).to_path_buf());
 
-        for action in &lang_config.on_save {
+        for action in &buffer_config.on_save {
             if !action.enabled {
                 continue;
             }
@@ -145,7 +145,7 @@ impl Editor {
 
         match self.run_formatter(&formatter, &path) {
             ActionResult::Success(output) => {
-                self.replace_buffer_with_output(&output)?;
+                self.replace_buffer_with_output(&output, "On-save format")?;
                 self.set_status_message(
                     t!(
                         "format.formatted_with",
@@ -403,8 +403,12 @@ impl Editor {
         }
     }
 
-    /// Replace the active buffer's content with new output.
-    fn replace_buffer_with_output(&mut self, output: &str) -> Result<(), String> {
+    /// Replace the active buffer's content with new output as one undo step.
+    pub(super) fn replace_buffer_with_output(
+        &mut self,
+        output: &str,
+        description: &str,
+    ) -> Result<(), String> {
         let cursor_id = self.active_state().cursors.primary_id();
 
         // Get current buffer content
@@ -457,7 +461,7 @@ impl Editor {
         // Apply as a batch for atomic undo
         let batch = Event::Batch {
             events,
-            description: "On-save format".to_string(),
+            description: description.to_string(),
         };
         self.active_event_log_mut().append(batch.clone());
         self.apply_event_to_active_buffer(&batch);
@@ -488,7 +492,7 @@ impl Editor {
             return Ok(false);
         }
 
-        self.replace_buffer_with_output(&trimmed)?;
+        self.replace_buffer_with_output(&trimmed, "On-save format")?;
         Ok(true)
     }
 
@@ -507,13 +511,13 @@ impl Editor {
         }
 
         let with_newline = format!("{}\n", content);
-        self.replace_buffer_with_output(&with_newline)?;
+        self.replace_buffer_with_output(&with_newline, "On-save format")?;
         Ok(true)
     }
 }
 
 /// Check if a command exists in the system PATH.
-fn command_exists(command: &str) -> bool {
+pub(super) fn command_exists(command: &str) -> bool {
     // Use 'which' on Unix or 'where' on Windows to check if command exists
     #[cfg(unix)]
     {
@@ -0,0 +1,139 @@
+//! Peek Definition / Peek References
+//!
+//! Reuses the go-to-definition and find-references requests, but shows the
+//! result in an inline peek window instead of switching buffers.
+
+use super::{uri_to_path, Editor};
+use crate::input::handler::InputResult;
+use crate::view::peek_window::{PeekAction, PeekKind, PeekLocation, PeekWindow};
+use anyhow::Result as AnyhowResult;
+use crossterm::event::KeyEvent;
+use rust_i18n::t;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Lines of the target file kept above and below the target line
+const PEEK_EXCERPT_LINES: usize = 200;
+
+impl Editor {
+    /// Request the definition under the cursor and show it in a peek window
+    pub(crate) fn request_peek_definition(&mut self) -> AnyhowResult<()> {
+        self.request_goto_definition()?;
+        if let Some(request_id) = self.pending_goto_definition_request.take() {
+            self.pending_peek_request = Some((request_id, PeekKind::Definition));
+        }
+        Ok(())
+    }
+
+    /// Request references to the symbol under the cursor and show them in a peek window
+    pub(crate) fn request_peek_references(&mut self) -> AnyhowResult<()> {
+        self.request_references()?;
+        if let Some(request_id) = self.pending_references_request.take() {
+            self.pending_references_symbol.clear();
+            self.pending_peek_request = Some((request_id, PeekKind::References));
+        }
+        Ok(())
+    }
+
+    /// Take the pending peek request if `request_id` belongs to it
+    pub(crate) fn take_pending_peek_request(&mut self, request_id: u64) -> Option<PeekKind> {
+        match self.pending_peek_request {
+            Some((pending_id, kind)) if pending_id == request_id => {
+                self.pending_peek_request = None;
+                Some(kind)
+            }
+            _ => None,
+        }
+    }
+
+    /// Open the peek window for the locations returned by the server
+    pub(crate) fn show_peek_window(&mut self, kind: PeekKind, locations: Vec<lsp_types::Location>) {
+        self.lsp_status.clear();
+
+        // Read each file once, preferring open buffers so unsaved edits show
+        let mut file_lines: HashMap<PathBuf, Vec<String>> = HashMap::new();
+        let mut peek_locations = Vec::new();
+        for location in &locations {
+            let Ok(path) = uri_to_path(&location.uri) else {
+                continue;
+            };
+            if !file_lines.contains_key(&path) {
+                let Some(lines) = self.read_peek_lines(&location.uri, &path) else {
+                    continue;
+                };
+                file_lines.insert(path.clone(), lines);
+            }
+            let lines = &file_lines[&path];
+
+            let line = location.range.start.line as usize;
+            let first_line = line.saturating_sub(PEEK_EXCERPT_LINES);
+            let last_line = line.saturating_add(PEEK_EXCERPT_LINES + 1).min(lines.len());
+            let display_path = path
+                .strip_prefix(&self.working_dir)
+                .unwrap_or(&path)
+                .display()
+                .to_string();
+
+            peek_locations.push(PeekLocation {
+                path: path.clone(),
+                display_path,
+                line,
+                character: location.range.start.character as usize,
+                first_line,
+                lines: lines
+                    .get(first_line..last_line)
+                    .unwrap_or_default()
+                    .to_vec(),
+            });
+        }
+
+        match PeekWindow::new(kind, peek_locations) {
+            Some(peek) => self.peek_window = Some(peek),
+            None => {
+                let message = match kind {
+                    PeekKind::Definition => t!("lsp.no_definition"),
+                    PeekKind::References => t!("lsp.no_references"),
+                };
+                self.set_status_message(message.to_string());
+            }
+        }
+    }
+
+    /// Lines of a peek target, from its open buffer or from disk
+    fn read_peek_lines(&self, uri: &lsp_types::Uri, path: &Path) -> Option<Vec<String>> {
+        let text = match self
+            .find_buffer_by_uri(uri.as_str())
+            .and_then(|id| self.buffers.get(&id))
+        {
+            Some(state) => state.buffer.to_string()?,
+            None => {
+                let bytes = self.filesystem_for_path(path).read_file(path).ok()?;
+                String::from_utf8_lossy(&bytes).into_owned()
+            }
+        };
+        Some(text.lines().map(str::to_string).collect())
+    }
+
+    /// Handle input while the peek window is open (modal)
+    pub(crate) fn handle_peek_window_input(&mut self, event: &KeyEvent) -> InputResult {
+        let Some(peek) = self.peek_window.as_mut() else {
+            return InputResult::Ignored;
+        };
+
+        match peek.handle_key(event) {
+            PeekAction::None => {}
+            PeekAction::Close => {
+                self.peek_window = None;
+            }
+            PeekAction::Open {
+                path,
+                line,
+                character,
+            } => {
+                self.peek_window = None;
+                self.open_definition_location(&path, line, character);
+            }
+        }
+        InputResult::Consumed
+    }
+}
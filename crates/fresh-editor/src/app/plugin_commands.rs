@@ -2,14 +2,19 @@
 //!
 //! This module groups plugin commands by domain for better maintainability.
 
+use crate::model::cursor::{Cursor, Cursors};
 use crate::model::event::{BufferId, CursorId, Event, OverlayFace, SplitId};
 use crate::view::overlay::{OverlayHandle, OverlayNamespace};
 use crate::view::split::SplitViewState;
 use anyhow::Result as AnyhowResult;
 use fresh_core::api::{
-    LayoutHints, MenuPosition, OverlayOptions, PluginResponse, ViewTransformPayload,
+    LayoutHints, MenuPosition, OverlayOptions, PluginResponse, QuickfixEntry, TextSelection,
+    ViewTransformPayload,
 };
+use fresh_core::config::PluginPermission;
+use rust_i18n::t;
 
+use super::quickfix::QuickfixItem;
 use super::Editor;
 
 impl Editor {
@@ -442,6 +447,7 @@ impl Editor {
             Ok(()) => {
                 // Clean up the view state for the closed split
                 self.split_view_states.remove(&split_id);
+                self.remove_scroll_lock(split_id);
                 // Restore cursor and viewport state for the new active split
                 self.restore_current_split_view_state();
                 tracing::info!("Closed split {:?}", split_id);
@@ -528,6 +534,141 @@ impl Editor {
         }
     }
 
+    /// Handle CreateOutputBuffer command
+    pub(super) fn handle_create_output_buffer(&mut self, name: String, request_id: u64) {
+        let display_name = format!("*{}*", name);
+        let existing = self
+            .buffer_metadata
+            .iter()
+            .find(|(id, m)| {
+                m.is_virtual() && m.display_name == display_name && self.buffers.contains_key(id)
+            })
+            .map(|(id, _)| *id);
+
+        let buffer_id = match existing {
+            Some(id) => id,
+            None => {
+                let id = self.create_virtual_buffer(display_name, String::new(), true);
+                if let Some(state) = self.buffers.get_mut(&id) {
+                    state.margins.set_line_numbers(false);
+                    state.editing_disabled = true;
+                }
+                id
+            }
+        };
+        self.set_active_buffer(buffer_id);
+
+        self.plugin_manager.resolve_callback(
+            fresh_core::api::JsCallbackId::from(request_id),
+            buffer_id.0.to_string(),
+        );
+    }
+
+    /// Handle AppendOutput command
+    pub(super) fn handle_append_output(&mut self, buffer_id: BufferId, text: String) {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            tracing::warn!("Buffer {:?} not found for AppendOutput", buffer_id);
+            return;
+        };
+        if text.is_empty() {
+            return;
+        }
+
+        // Output is not user content: bypass the undo log and keep the buffer unmodified
+        let old_len = state.buffer.len();
+        state.buffer.insert(old_len, &text);
+        state.buffer.clear_modified();
+        let new_len = state.buffer.len();
+
+        // Cursors sitting at the old end follow the output
+        if state.cursors.primary().position == old_len {
+            state.cursors.primary_mut().move_to(new_len, false);
+        }
+        for split_id in self.split_manager.splits_for_buffer(buffer_id) {
+            if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+                if view_state.cursors.primary().position == old_len {
+                    view_state.cursors.primary_mut().move_to(new_len, false);
+                    let cursor = *view_state.cursors.primary();
+                    view_state
+                        .viewport
+                        .ensure_visible(&mut state.buffer, &cursor);
+                }
+            }
+        }
+    }
+
+    /// Handle ClearOutput command
+    pub(super) fn handle_clear_output(&mut self, buffer_id: BufferId) {
+        if let Err(e) = self.set_virtual_buffer_content(buffer_id, Vec::new()) {
+            tracing::warn!("Failed to clear output buffer {:?}: {}", buffer_id, e);
+            return;
+        }
+        for split_id in self.split_manager.splits_for_buffer(buffer_id) {
+            if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+                view_state.cursors.primary_mut().move_to(0, false);
+                view_state.viewport.top_byte = 0;
+                view_state.viewport.top_view_line_offset = 0;
+            }
+        }
+    }
+
+    /// Handle SetSelections command
+    pub(super) fn handle_set_selections(
+        &mut self,
+        buffer_id: BufferId,
+        selections: Vec<TextSelection>,
+    ) {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            tracing::warn!("Buffer {:?} not found for SetSelections", buffer_id);
+            return;
+        };
+        if selections.is_empty() {
+            return;
+        }
+
+        let len = state.buffer.len();
+        let to_cursor = |sel: &TextSelection| {
+            let anchor = sel.anchor.min(len);
+            let position = sel.position.min(len);
+            let mut cursor = Cursor::new(position);
+            if anchor != position {
+                cursor.set_anchor(anchor);
+            }
+            cursor
+        };
+        let apply = |cursors: &mut Cursors| {
+            cursors.remove_secondary();
+            // Reuse the remaining cursor for a secondary selection (or the primary
+            // if there is only one), then add the rest. `add` makes the new cursor
+            // primary, so the first selection is added last.
+            let (primary, rest) = selections.split_first().expect("selections is non-empty");
+            match rest.split_first() {
+                Some((first, others)) => {
+                    *cursors.primary_mut() = to_cursor(first);
+                    for sel in others {
+                        cursors.add(to_cursor(sel));
+                    }
+                    cursors.add(to_cursor(primary));
+                }
+                None => *cursors.primary_mut() = to_cursor(primary),
+            }
+        };
+
+        let active_split = self.split_manager.active_split();
+        for split_id in self.split_manager.splits_for_buffer(buffer_id) {
+            if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+                apply(&mut view_state.cursors);
+                let cursor = *view_state.cursors.primary();
+                view_state
+                    .viewport
+                    .ensure_visible(&mut state.buffer, &cursor);
+            }
+            if split_id == active_split {
+                apply(&mut state.cursors);
+            }
+        }
+    }
+
     /// Handle SetSplitScroll command
     pub(super) fn handle_set_split_scroll(&mut self, split_id: SplitId, top_byte: usize) {
         if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
@@ -652,6 +793,57 @@ impl Editor {
         }
     }
 
+    /// Handle ReplaceRange command
+    pub(super) fn handle_replace_range(
+        &mut self,
+        buffer_id: BufferId,
+        range: std::ops::Range<usize>,
+        text: String,
+    ) {
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            let len = state.buffer.len();
+            if range.start > range.end || range.end > len {
+                tracing::warn!(
+                    "ReplaceRange: invalid range {}..{} for buffer of length {}",
+                    range.start,
+                    range.end,
+                    len
+                );
+                return;
+            }
+
+            let mut events = Vec::new();
+            if !range.is_empty() {
+                let deleted_text = state.get_text_range(range.start, range.end);
+                events.push(Event::Delete {
+                    range: range.clone(),
+                    deleted_text,
+                    cursor_id: CursorId(0),
+                });
+            }
+            if !text.is_empty() {
+                events.push(Event::Insert {
+                    position: range.start,
+                    text,
+                    cursor_id: CursorId(0),
+                });
+            }
+
+            let event = match events.len() {
+                0 => return,
+                1 => events.remove(0),
+                _ => Event::Batch {
+                    events,
+                    description: "Plugin replace range".to_string(),
+                },
+            };
+            state.apply(&event);
+            if let Some(log) = self.event_logs.get_mut(&buffer_id) {
+                log.append(event);
+            }
+        }
+    }
+
     /// Handle InsertAtCursor command
     pub(super) fn handle_insert_at_cursor(&mut self, text: String) {
         // Insert text at current cursor position in active buffer
@@ -755,6 +947,11 @@ impl Editor {
         line: Option<usize>,
         column: Option<usize>,
     ) -> AnyhowResult<()> {
+        // URIs in plugin schemes are opened by the plugin that owns them
+        if self.open_plugin_uri(&path, line, column) {
+            return Ok(());
+        }
+
         // Open the file
         if let Err(e) = self.open_file(&path) {
             tracing::error!("Failed to open file from plugin: {}", e);
@@ -788,6 +985,9 @@ impl Editor {
         self.restore_current_split_view_state();
 
         // Open the file in the now-active split
+        if self.open_plugin_uri(&path, line, column) {
+            return Ok(());
+        }
         if let Err(e) = self.open_file(&path) {
             tracing::error!("Failed to open file from plugin: {}", e);
             return Ok(());
@@ -967,6 +1167,66 @@ impl Editor {
         }
     }
 
+    /// Handle SetStatusSegment command
+    pub(super) fn handle_set_status_segment(&mut self, id: String, text: String) {
+        if text.trim().is_empty() {
+            self.plugin_status_segments.remove(&id);
+        } else {
+            self.plugin_status_segments.insert(id, text);
+        }
+    }
+
+    /// Handle SetQuickfixList command
+    pub(super) fn handle_set_quickfix_list(
+        &mut self,
+        title: String,
+        entries: Vec<QuickfixEntry>,
+        selected: Option<usize>,
+    ) {
+        let items = entries
+            .into_iter()
+            .map(|entry| QuickfixItem {
+                path: std::path::PathBuf::from(entry.file),
+                line: entry.line.max(1) as usize,
+                column: entry.column.unwrap_or(1).max(1) as usize,
+                text: entry.text.unwrap_or_default(),
+            })
+            .collect();
+        self.set_quickfix_list(title, items, selected);
+    }
+
+    /// Handle SetMark command
+    pub(super) fn handle_set_mark(&mut self, name: &str) {
+        match name.chars().next() {
+            Some(name) => self.set_mark(name),
+            None => tracing::warn!("setMark: empty mark name"),
+        }
+    }
+
+    /// Handle JumpToMark command
+    pub(super) fn handle_jump_to_mark(&mut self, name: &str, line_start: bool) {
+        match name.chars().next() {
+            Some(name) => self.jump_to_mark(name, line_start),
+            None => tracing::warn!("jumpToMark: empty mark name"),
+        }
+    }
+
+    /// Handle SetThemeColor command
+    pub(super) fn handle_set_theme_color(&mut self, key: String, color: serde_json::Value) {
+        let Ok(color) = serde_json::from_value::<crate::view::theme::ColorDef>(color) else {
+            tracing::warn!("setThemeColor: invalid color for '{}'", key);
+            return;
+        };
+        let color = crate::view::color_support::convert_color(color.into(), self.color_capability);
+        if !self.theme.set_color(&key, color) {
+            tracing::warn!("setThemeColor: unknown theme key '{}'", key);
+            return;
+        }
+        if key == "editor.cursor" {
+            self.theme.set_terminal_cursor_color();
+        }
+    }
+
     /// Handle StartPrompt command
     pub(super) fn handle_start_prompt(&mut self, label: String, prompt_type: String) {
         // Create a plugin-controlled prompt
@@ -1047,6 +1307,39 @@ impl Editor {
         );
     }
 
+    /// Handle StartQuickPickAsync command (for editor.showQuickPick() API)
+    pub(super) fn handle_start_quick_pick_async(
+        &mut self,
+        title: String,
+        items: Vec<fresh_core::api::QuickPickItem>,
+        match_on_description: bool,
+        callback_id: fresh_core::api::JsCallbackId,
+    ) {
+        use crate::input::commands::Suggestion;
+        use crate::view::prompt::{Prompt, PromptType};
+
+        self.pending_async_prompt_callback = Some(callback_id);
+
+        let suggestions = items
+            .into_iter()
+            .map(|item| Suggestion {
+                value: Some(item.value.unwrap_or_else(|| item.label.clone())),
+                text: item.label,
+                description: item.description,
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+        self.prompt = Some(Prompt::with_suggestions(
+            title,
+            PromptType::AsyncQuickPick {
+                match_on_description,
+            },
+            suggestions,
+        ));
+    }
+
     /// Handle SetPromptSuggestions command
     pub(super) fn handle_set_prompt_suggestions(
         &mut self,
@@ -1091,6 +1384,85 @@ impl Editor {
         }
     }
 
+    /// Handle RequestPluginPermission command
+    ///
+    /// Requests are queued so only one permission prompt is open at a time.
+    pub(super) fn handle_request_plugin_permission(
+        &mut self,
+        plugin_name: String,
+        permission: PluginPermission,
+    ) {
+        self.pending_permission_requests
+            .push_back((plugin_name, permission));
+        self.show_next_permission_prompt();
+    }
+
+    /// Open the next queued permission prompt unless another prompt is active
+    ///
+    /// Returns true if a prompt was opened.
+    pub(super) fn show_next_permission_prompt(&mut self) -> bool {
+        if self.prompt.is_some() {
+            return false;
+        }
+        let Some((plugin_name, permission)) = self.pending_permission_requests.pop_front() else {
+            return false;
+        };
+        self.start_prompt(
+            t!(
+                "prompt.plugin_permission_confirm",
+                plugin = &plugin_name,
+                permission = permission.as_str()
+            )
+            .to_string(),
+            crate::view::prompt::PromptType::PluginPermission {
+                plugin_name,
+                permission,
+            },
+        );
+        true
+    }
+
+    /// Apply the answer to a permission prompt: (y)es, (a)lways or anything else for no
+    ///
+    /// "Always" is persisted in the plugin's config entry so later sessions don't ask.
+    pub(super) fn answer_plugin_permission(
+        &mut self,
+        plugin_name: String,
+        permission: PluginPermission,
+        answer: &str,
+    ) {
+        let answer = answer.trim().to_lowercase();
+        let always = answer == "a" || answer == "always";
+        let granted = always || answer == "y" || answer == "yes";
+
+        if always {
+            let entry = self.config.plugins.entry(plugin_name.clone()).or_default();
+            if !entry.granted_permissions.contains(&permission) {
+                entry.granted_permissions.push(permission);
+            }
+            if let Err(e) = self.save_config() {
+                tracing::warn!("Failed to persist plugin permission: {}", e);
+            }
+        }
+
+        let message = if granted {
+            t!(
+                "prompt.plugin_permission_granted",
+                plugin = &plugin_name,
+                permission = permission.as_str()
+            )
+        } else {
+            t!(
+                "prompt.plugin_permission_denied",
+                plugin = &plugin_name,
+                permission = permission.as_str()
+            )
+        };
+        self.set_status_message(message.to_string());
+        self.plugin_manager
+            .resolve_permission(plugin_name, permission, granted);
+    }
+
     // ==================== Command/Mode Registration ====================
 
     /// Handle RegisterCommand command
@@ -1256,7 +1628,17 @@ impl Editor {
         // Convert LanguagePackConfig to the internal LanguageConfig format
         let lang_config = crate::config::LanguageConfig {
             comment_prefix: config.comment_prefix,
+            block_comment_start: config.block_comment_start,
+            block_comment_end: config.block_comment_end,
             auto_indent: config.auto_indent.unwrap_or(true),
+            indent_rules: (config.increase_indent_pattern.is_some()
+                || config.decrease_indent_pattern.is_some()
+                || config.indent_query.is_some())
+            .then(|| crate::config::IndentRulesConfig {
+                increase_pattern: config.increase_indent_pattern,
+                decrease_pattern: config.decrease_indent_pattern,
+                query: config.indent_query.map(Into::into),
+            }),
             use_tabs: config.use_tabs.unwrap_or(false),
             tab_size: config.tab_size,
             show_whitespace_tabs: config.show_whitespace_tabs.unwrap_or(true),
@@ -1274,11 +1656,15 @@ impl Editor {
 
     /// Handle RegisterLspServer command
     /// Applies LSP server configuration immediately
+    ///
+    /// A named server is registered under its name and attached to the
+    /// language as an additional server.
     pub(super) fn handle_register_lsp_server(
         &mut self,
         language: String,
         config: fresh_core::api::LspServerPackConfig,
     ) {
+        let server_id = config.name.clone().unwrap_or_else(|| language.clone());
         // Convert LspServerPackConfig to the internal LspServerConfig format
         let lsp_config = crate::types::LspServerConfig {
             command: config.command,
@@ -1288,13 +1674,27 @@ impl Editor {
             initialization_options: config.initialization_options,
             ..Default::default()
         };
+        if server_id != language {
+            let lsp_servers = &mut self
+                .config
+                .languages
+                .entry(language.clone())
+                .or_default()
+                .lsp_servers;
+            if !lsp_servers.contains(&server_id) {
+                lsp_servers.push(server_id.clone());
+            }
+        }
         // Update LSP manager if available
         if let Some(ref mut lsp) = self.lsp {
-            lsp.set_language_config(language.clone(), lsp_config.clone());
+            lsp.set_language_config(server_id.clone(), lsp_config.clone());
+            if let Some(lang_config) = self.config.languages.get(&language) {
+                lsp.set_additional_servers(&language, lang_config.lsp_servers.clone());
+            }
         }
         // Also update runtime config
-        self.config.lsp.insert(language.clone(), lsp_config);
-        tracing::info!("LSP server registered for '{}'", language);
+        self.config.lsp.insert(server_id.clone(), lsp_config);
+        tracing::info!("LSP server '{}' registered for '{}'", server_id, language);
     }
 
     /// Handle ReloadGrammars command
@@ -0,0 +1,196 @@
+//! Reloading plugins without restarting the editor.
+//!
+//! "Developer: Reload Plugin" reloads one plugin on demand. With
+//! `editor.plugin_hot_reload` enabled, plugin sources (the entry file, the
+//! local modules it imports, its i18n strings and package manifest) are polled
+//! for changes and the plugin is reloaded when any of them is modified.
+
+use rust_i18n::t;
+
+#[cfg(feature = "plugins")]
+use super::types::WatchedPlugin;
+use super::Editor;
+#[cfg(feature = "plugins")]
+use crate::input::commands::Suggestion;
+#[cfg(feature = "plugins")]
+use crate::view::prompt::{Prompt, PromptType};
+#[cfg(feature = "plugins")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "plugins")]
+use std::time::SystemTime;
+
+#[cfg(feature = "plugins")]
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Files a plugin is built from, with their current modification times
+#[cfg(feature = "plugins")]
+fn plugin_sources(plugin_path: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut files = fresh_parser_js::module_source_files(plugin_path);
+    if files.is_empty() {
+        // Unreadable entry file: still watch it so it's picked up once it exists
+        files.push(plugin_path.to_path_buf());
+    }
+    for extra in [
+        plugin_path.with_extension("i18n.json"),
+        plugin_path.with_file_name("package.json"),
+    ] {
+        if extra.exists() {
+            files.push(extra);
+        }
+    }
+    files
+        .into_iter()
+        .map(|path| {
+            let mtime = modified_time(&path);
+            (path, mtime)
+        })
+        .collect()
+}
+
+impl Editor {
+    /// Show the loaded plugins; confirming one reloads it
+    pub fn show_reload_plugin_prompt(&mut self) {
+        #[cfg(feature = "plugins")]
+        {
+            let mut plugins = self.plugin_manager.list_plugins();
+            if plugins.is_empty() {
+                self.set_status_message(t!("plugins.none_loaded").to_string());
+                return;
+            }
+            plugins.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let suggestions: Vec<Suggestion> = plugins
+                .into_iter()
+                .map(|plugin| Suggestion {
+                    text: plugin.name.clone(),
+                    description: Some(plugin.path.display().to_string()),
+                    value: Some(plugin.name),
+                    disabled: false,
+                    keybinding: None,
+                    source: None,
+                })
+                .collect();
+
+            let mut prompt = Prompt::with_suggestions(
+                t!("plugins.reload_prompt").to_string(),
+                PromptType::ReloadPlugin,
+                suggestions,
+            );
+            prompt.selected_suggestion = Some(0);
+            self.prompt = Some(prompt);
+        }
+        #[cfg(not(feature = "plugins"))]
+        self.set_status_message(t!("status.plugins_not_available").to_string());
+    }
+
+    /// Reload the plugin `name` (from the plugin list) and report the outcome
+    pub(super) fn reload_plugin_by_name(&mut self, name: &str) {
+        #[cfg(feature = "plugins")]
+        {
+            if name.is_empty() {
+                return;
+            }
+            let result = self.reload_plugin_sources(name);
+            self.report_plugin_reload(name, result);
+        }
+        #[cfg(not(feature = "plugins"))]
+        let _ = name;
+    }
+
+    /// Reload a plugin and drop the editor-side state tied to its old instance
+    #[cfg(feature = "plugins")]
+    pub(super) fn reload_plugin_sources(&mut self, name: &str) -> anyhow::Result<()> {
+        self.plugin_tasks.retain(|t| t.plugin_name != name);
+        // Re-read the source list on the next poll; imports may have changed
+        self.watched_plugins.remove(name);
+        self.plugin_manager.reload_plugin(name)
+    }
+
+    #[cfg(feature = "plugins")]
+    fn report_plugin_reload(&mut self, name: &str, result: anyhow::Result<()>) {
+        match result {
+            Ok(()) => {
+                tracing::info!("Reloaded plugin: {}", name);
+                self.set_status_message(t!("plugins.reloaded", name = name).to_string());
+            }
+            Err(e) => {
+                tracing::error!("Failed to reload plugin '{}': {}", name, e);
+                self.set_status_message(
+                    t!("plugins.reload_failed", name = name, error = e.to_string()).to_string(),
+                );
+            }
+        }
+    }
+
+    /// Reload plugins whose source files changed (called from main loop)
+    ///
+    /// Only active with `editor.plugin_hot_reload`. Returns true if any plugin
+    /// was reloaded (requires re-render).
+    pub(crate) fn poll_plugin_sources(&mut self) -> bool {
+        #[cfg(feature = "plugins")]
+        {
+            if !self.config.editor.plugin_hot_reload {
+                return false;
+            }
+
+            let poll_interval =
+                std::time::Duration::from_millis(self.config.editor.auto_revert_poll_interval_ms);
+            if self.time_source.elapsed_since(self.last_plugin_source_poll) < poll_interval {
+                return false;
+            }
+            self.last_plugin_source_poll = self.time_source.now();
+
+            // Forget unloaded plugins, but keep watching ones whose reload failed
+            let plugins = self.plugin_manager.list_plugins();
+            self.watched_plugins
+                .retain(|name, w| !w.loaded || plugins.iter().any(|p| &p.name == name));
+            for plugin in plugins {
+                self.watched_plugins
+                    .entry(plugin.name)
+                    .or_insert_with(|| WatchedPlugin {
+                        sources: plugin_sources(&plugin.path),
+                        path: plugin.path,
+                        loaded: true,
+                    });
+            }
+
+            let changed: Vec<(String, WatchedPlugin)> = self
+                .watched_plugins
+                .iter()
+                .filter(|(_, w)| {
+                    w.sources
+                        .iter()
+                        .any(|(path, mtime)| modified_time(path) != *mtime)
+                })
+                .map(|(name, w)| (name.clone(), w.clone()))
+                .collect();
+
+            for (name, watched) in &changed {
+                tracing::info!("Plugin '{}' changed on disk, reloading", name);
+                let result = if watched.loaded {
+                    self.reload_plugin_sources(name)
+                } else {
+                    self.watched_plugins.remove(name);
+                    self.plugin_manager.load_plugin(&watched.path)
+                };
+                if result.is_err() {
+                    // The old instance is gone; retry when the sources change again
+                    self.watched_plugins.insert(
+                        name.clone(),
+                        WatchedPlugin {
+                            sources: plugin_sources(&watched.path),
+                            path: watched.path.clone(),
+                            loaded: false,
+                        },
+                    );
+                }
+                self.report_plugin_reload(name, result);
+            }
+            !changed.is_empty()
+        }
+        #[cfg(not(feature = "plugins"))]
+        false
+    }
+}
@@ -0,0 +1,197 @@
+//! Background tasks started by plugins with `editor.runTask`.
+//!
+//! The job itself runs in the plugin runtime; the editor only tracks the task
+//! list, shows a spinner with the oldest task's progress in the status bar,
+//! and relays the result (or a cancellation) back to the waiting promise.
+//! Files opened or saved in the background share the spinner and task list.
+
+use rust_i18n::t;
+
+use fresh_core::api::JsCallbackId;
+
+use super::background_io::OPEN_TASK_PREFIX;
+use super::types::PluginTask;
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::view::prompt::{Prompt, PromptType};
+
+/// Spinner frames for the status bar task indicator
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// How long each spinner frame is shown
+const SPINNER_FRAME_MS: u128 = 100;
+
+/// Spinner character for frame `frame`
+pub(super) fn spinner_frame(frame: usize) -> char {
+    SPINNER_FRAMES[frame % SPINNER_FRAMES.len()]
+}
+
+/// Spinner frame to show `elapsed` after a task started
+pub(super) fn spinner_frame_at(elapsed: std::time::Duration) -> usize {
+    (elapsed.as_millis() / SPINNER_FRAME_MS) as usize % SPINNER_FRAMES.len()
+}
+
+impl Editor {
+    /// Handle StartTask command
+    pub(super) fn handle_start_task(
+        &mut self,
+        task_id: JsCallbackId,
+        plugin_name: String,
+        name: String,
+    ) {
+        self.plugin_tasks.push(PluginTask {
+            id: task_id,
+            plugin_name,
+            name,
+            message: None,
+            percentage: None,
+            started_at: self.time_source.now(),
+        });
+    }
+
+    /// Handle UpdateTaskProgress command
+    pub(super) fn handle_update_task_progress(
+        &mut self,
+        task_id: JsCallbackId,
+        message: Option<String>,
+        percentage: Option<u32>,
+    ) {
+        if let Some(task) = self.plugin_tasks.iter_mut().find(|t| t.id == task_id) {
+            task.message = message;
+            task.percentage = percentage;
+        }
+    }
+
+    /// Handle FinishTask command
+    ///
+    /// Tasks the user already cancelled are no longer listed; their promise was
+    /// rejected at cancellation, so the late result is dropped.
+    pub(super) fn handle_finish_task(
+        &mut self,
+        task_id: JsCallbackId,
+        result: Option<serde_json::Value>,
+        error: Option<String>,
+    ) {
+        let Some(index) = self.plugin_tasks.iter().position(|t| t.id == task_id) else {
+            return;
+        };
+        self.plugin_tasks.remove(index);
+
+        match error {
+            Some(error) => self.plugin_manager.reject_callback(task_id, error),
+            None => {
+                let json = serde_json::to_string(&result.unwrap_or(serde_json::Value::Null))
+                    .unwrap_or_else(|_| "null".to_string());
+                self.plugin_manager.resolve_callback(task_id, json);
+            }
+        }
+    }
+
+    /// Status bar text for background tasks, e.g. `⠹ Indexing: 3/10 (30%) +1 | watch ✓`
+    pub(super) fn task_status_text(&self) -> Option<String> {
+        let running = self.running_task_status_text();
+        match (running, self.watch_status_text()) {
+            (Some(running), Some(watch)) => Some(format!("{} | {}", running, watch)),
+            (running, watch) => running.or(watch),
+        }
+    }
+
+    /// Status bar text for running background tasks, e.g. `⠹ Indexing: 3/10 (30%) +1`
+    fn running_task_status_text(&self) -> Option<String> {
+        let Some(task) = self.plugin_tasks.first() else {
+            return self.file_io_status_text();
+        };
+        let spinner = spinner_frame(self.task_spinner_frame);
+
+        let mut status = format!("{} {}", spinner, task.name);
+        if let Some(ref message) = task.message {
+            status.push_str(&format!(": {}", message));
+        }
+        if let Some(percentage) = task.percentage {
+            status.push_str(&format!(" ({}%)", percentage));
+        }
+        if self.plugin_tasks.len() > 1 {
+            status.push_str(&format!(" +{}", self.plugin_tasks.len() - 1));
+        }
+        Some(status)
+    }
+
+    /// Advance the task spinner
+    ///
+    /// Called on every iteration of the event loop. Returns true when the
+    /// spinner moved to a new frame (so the status bar needs a re-render).
+    pub(crate) fn poll_plugin_tasks(&mut self) -> bool {
+        let Some(task) = self.plugin_tasks.first() else {
+            return false;
+        };
+        let frame = spinner_frame_at(self.time_source.elapsed_since(task.started_at));
+        if frame == self.task_spinner_frame {
+            return false;
+        }
+        self.task_spinner_frame = frame;
+        true
+    }
+
+    /// Show the running background tasks; confirming one cancels it
+    pub fn show_background_tasks(&mut self) {
+        if self.plugin_tasks.is_empty()
+            && self.background_opens.is_empty()
+            && self.background_saves.is_empty()
+        {
+            self.set_status_message(t!("tasks.none_running").to_string());
+            return;
+        }
+
+        let mut suggestions: Vec<Suggestion> = self
+            .plugin_tasks
+            .iter()
+            .map(|task| {
+                let mut description = task.plugin_name.clone();
+                if let Some(ref message) = task.message {
+                    description.push_str(&format!(" - {}", message));
+                }
+                if let Some(percentage) = task.percentage {
+                    description.push_str(&format!(" ({}%)", percentage));
+                }
+                Suggestion {
+                    text: task.name.clone(),
+                    description: Some(description),
+                    value: Some(task.id.as_u64().to_string()),
+                    disabled: false,
+                    keybinding: None,
+                    source: None,
+                }
+            })
+            .collect();
+        suggestions.extend(self.file_io_task_suggestions());
+
+        let mut prompt = Prompt::with_suggestions(
+            t!("tasks.cancel_prompt").to_string(),
+            PromptType::CancelBackgroundTask,
+            suggestions,
+        );
+        prompt.selected_suggestion = Some(0);
+        self.prompt = Some(prompt);
+    }
+
+    /// Cancel the background task whose id is `input` (from the task list)
+    pub(super) fn cancel_background_task(&mut self, input: &str) {
+        let input = input.trim();
+        if let Some(id) = input.strip_prefix(OPEN_TASK_PREFIX) {
+            if let Ok(id) = id.parse::<u64>() {
+                self.cancel_background_open(id);
+            }
+            return;
+        }
+        let Ok(id) = input.parse::<u64>() else {
+            return;
+        };
+        let Some(index) = self.plugin_tasks.iter().position(|t| t.id.as_u64() == id) else {
+            return;
+        };
+
+        let task = self.plugin_tasks.remove(index);
+        self.plugin_manager.cancel_task(task.id);
+        self.set_status_message(t!("tasks.cancelled", name = &task.name).to_string());
+    }
+}
@@ -47,6 +47,38 @@ impl Editor {
             return PopupConfirmResult::EarlyReturn;
         }
 
+        // Check if this is an item of the LSP status panel
+        let lsp_panel_data = self
+            .active_state()
+            .popups
+            .top()
+            .and_then(|p| p.selected_item())
+            .and_then(|item| item.data.as_deref())
+            .and_then(|data| data.strip_prefix(super::lsp_actions::LSP_PANEL_DATA_PREFIX))
+            .map(str::to_string);
+        if let Some(data) = lsp_panel_data {
+            self.hide_popup();
+            self.handle_lsp_status_panel_confirm(&data);
+            return PopupConfirmResult::EarlyReturn;
+        }
+
+        // Check if this is an item of the config problems panel
+        let config_issue_data = self
+            .active_state()
+            .popups
+            .top()
+            .and_then(|p| p.selected_item())
+            .and_then(|item| item.data.as_deref())
+            .and_then(|data| {
+                data.strip_prefix(super::config_issue_actions::CONFIG_ISSUE_DATA_PREFIX)
+            })
+            .map(str::to_string);
+        if let Some(data) = config_issue_data {
+            self.hide_popup();
+            self.handle_config_issue_confirm(&data);
+            return PopupConfirmResult::EarlyReturn;
+        }
+
         // Check if this is an LSP confirmation popup
         let lsp_confirmation_action = if let Some(popup) = self.active_state().popups.top() {
             if let Some(title) = &popup.title {
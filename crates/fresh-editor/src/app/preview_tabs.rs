@@ -0,0 +1,77 @@
+//! Preview tabs (**Toggle Preview Tabs**)
+//!
+//! With `editor.preview_tabs` on, a file single-clicked in the file explorer
+//! opens in a preview tab, drawn in italics. The next file previewed replaces
+//! it, so browsing a tree doesn't leave a tab per file behind. The tab is
+//! kept as a normal one once the buffer is edited, or when the file is opened
+//! with Enter or a double-click. Files that are already open stay as they
+//! are.
+
+use std::path::Path;
+
+use anyhow::Result as AnyhowResult;
+use rust_i18n::t;
+
+use super::Editor;
+use crate::model::event::BufferId;
+
+impl Editor {
+    /// The current preview tab's buffer, if any
+    fn preview_buffer(&self) -> Option<BufferId> {
+        self.buffer_metadata
+            .iter()
+            .find(|(_, m)| m.preview)
+            .map(|(id, _)| *id)
+    }
+
+    /// Open `path` from a single click in the file explorer
+    pub(super) fn open_file_preview(&mut self, path: &Path) -> AnyhowResult<BufferId> {
+        if !self.config.editor.preview_tabs {
+            return self.open_file(path);
+        }
+        let previous = self.preview_buffer();
+        let next_buffer_id = self.next_buffer_id;
+        // Opening into the empty unnamed buffer reuses its id
+        let unnamed = self.active_buffer();
+        let unnamed_reused = self.active_state().buffer.file_path().is_none();
+
+        let buffer_id = self.open_file(path)?;
+        let opened_new = buffer_id.0 >= next_buffer_id || (unnamed_reused && buffer_id == unnamed);
+        if !opened_new {
+            return Ok(buffer_id);
+        }
+        if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
+            metadata.preview = true;
+        }
+        if let Some(previous) = previous.filter(|&id| id != buffer_id) {
+            // An edited preview has been kept already; this only fails if the
+            // buffer was modified some other way, and then it stays open
+            if let Err(e) = self.close_buffer(previous) {
+                tracing::debug!("Keeping previous preview tab {:?}: {}", previous, e);
+                self.keep_preview_tab(previous);
+            }
+        }
+        Ok(buffer_id)
+    }
+
+    /// Turn the preview tab `buffer_id` into a normal tab
+    pub(super) fn keep_preview_tab(&mut self, buffer_id: BufferId) {
+        if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
+            metadata.preview = false;
+        }
+    }
+
+    /// Toggle opening single-clicked explorer files in a preview tab
+    pub fn toggle_preview_tabs(&mut self) {
+        self.config.editor.preview_tabs = !self.config.editor.preview_tabs;
+        if self.config.editor.preview_tabs {
+            self.set_status_message(t!("toggle.preview_tabs_enabled").to_string());
+        } else {
+            // The current preview tab stays open as a normal tab
+            if let Some(buffer_id) = self.preview_buffer() {
+                self.keep_preview_tab(buffer_id);
+            }
+            self.set_status_message(t!("toggle.preview_tabs_disabled").to_string());
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Profile switching
+//!
+//! A profile is a separate set of config, themes, plugins and workspace state
+//! (see `DirectoryContext::with_profile`). Switching restarts the editor the
+//! same way switching projects does.
+
+use super::Editor;
+use crate::config_io::DirectoryContext;
+use crate::input::commands::Suggestion;
+use crate::view::prompt::{Prompt, PromptType};
+use rust_i18n::t;
+
+impl Editor {
+    /// Ask which profile to switch to
+    ///
+    /// Lists the existing profiles; typing a new name creates that profile.
+    pub fn start_switch_profile_prompt(&mut self) {
+        let current = self.dir_context.profile_name().to_string();
+        let profiles = self.dir_context.list_profiles();
+        let current_index = profiles
+            .iter()
+            .position(|name| *name == current)
+            .unwrap_or(0);
+
+        let suggestions: Vec<Suggestion> = profiles
+            .iter()
+            .map(|name| Suggestion {
+                text: name.clone(),
+                description: (*name == current).then(|| t!("file.profile_current").to_string()),
+                value: Some(name.clone()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+
+        let mut prompt = Prompt::with_suggestions(
+            t!("file.switch_profile_prompt").to_string(),
+            PromptType::SwitchProfile,
+            suggestions,
+        );
+        prompt.selected_suggestion = Some(current_index);
+        prompt.input = current;
+        prompt.cursor_pos = prompt.input.len();
+        self.prompt = Some(prompt);
+    }
+
+    /// Restart the editor with the named profile
+    pub(super) fn switch_profile(&mut self, name: &str) {
+        if !DirectoryContext::is_valid_profile_name(name) {
+            self.set_status_message(t!("file.profile_invalid_name", name = name).to_string());
+            return;
+        }
+        if name == self.dir_context.profile_name() {
+            self.set_status_message(t!("file.profile_already_active", name = name).to_string());
+            return;
+        }
+
+        tracing::info!("Restart requested with profile: {}", name);
+        self.restart_with_profile = Some(name.to_string());
+        // Also signal quit so the event loop exits
+        self.should_quit = true;
+    }
+}
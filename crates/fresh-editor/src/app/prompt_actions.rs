@@ -43,22 +43,45 @@ impl Editor {
                     normalize_path(&self.working_dir.join(&expanded_path))
                 };
 
-                if let Err(e) = self.open_file(&resolved_path) {
-                    self.set_status_message(
-                        t!("file.error_opening", error = e.to_string()).to_string(),
-                    );
+                if self.should_ask_open_symlink(&resolved_path) {
+                    self.start_open_symlink_prompt(resolved_path);
+                } else if self.start_background_open(&resolved_path) {
+                    // Shown once the read finishes
                 } else {
-                    self.set_status_message(
-                        t!("buffer.opened", name = resolved_path.display().to_string()).to_string(),
-                    );
+                    match self.open_file(&resolved_path) {
+                        Err(e) => self.set_status_message(
+                            t!("file.error_opening", error = e.to_string()).to_string(),
+                        ),
+                        // Keep the message naming the other link
+                        Ok(id) if self.is_hard_link_alias(id, &resolved_path) => {}
+                        Ok(_) => self.set_status_message(
+                            t!("buffer.opened", name = resolved_path.display().to_string())
+                                .to_string(),
+                        ),
+                    }
                 }
             }
+            PromptType::OpenSymlink { path } => {
+                self.open_symlink_answered(&path, &input);
+            }
             PromptType::OpenFileWithEncoding { path } => {
                 self.handle_open_file_with_encoding(&path, &input);
             }
             PromptType::ReloadWithEncoding => {
                 self.handle_reload_with_encoding(&input);
             }
+            PromptType::SwitchProfile => {
+                self.switch_profile(input.trim());
+            }
+            PromptType::CollabShare => {
+                self.share_active_buffer(input.trim());
+            }
+            PromptType::CollabJoin => {
+                self.join_shared_buffer(input.trim());
+            }
+            PromptType::FollowUser => {
+                self.handle_follow_user(&input);
+            }
             PromptType::SwitchProject => {
                 // Expand tilde to home directory first
                 let expanded_path = expand_tilde(&input);
@@ -96,6 +119,7 @@ impl Editor {
                 );
             }
             PromptType::Replace { search } => {
+                self.clear_replace_preview();
                 if self.search_confirm_each {
                     self.start_interactive_replace(&search, &input);
                 } else {
@@ -112,12 +136,30 @@ impl Editor {
                 );
             }
             PromptType::QueryReplace { search } => {
+                self.clear_replace_preview();
                 if self.search_confirm_each {
                     self.start_interactive_replace(&search, &input);
                 } else {
                     self.perform_replace(&search, &input);
                 }
             }
+            PromptType::StructuralSearch => {
+                self.structural_search(&input);
+            }
+            PromptType::StructuralReplaceSearch => {
+                self.start_prompt(
+                    t!("structural.replace_prompt", pattern = &input).to_string(),
+                    PromptType::StructuralReplace {
+                        pattern: input.clone(),
+                    },
+                );
+            }
+            PromptType::StructuralReplace { pattern } => {
+                self.structural_replace(&pattern, &input);
+            }
+            PromptType::ConfirmStructuralReplace => {
+                self.structural_replace_confirmed(&input);
+            }
             PromptType::Command => {
                 let commands = self.command_registry.read().unwrap().get_all();
                 if let Some(cmd) = commands.iter().find(|c| c.get_localized_name() == input) {
@@ -227,10 +269,10 @@ impl Editor {
                     self.set_status_message(t!("buffer.revert_cancelled").to_string());
                 }
             }
-            PromptType::ConfirmSaveConflict => {
+            PromptType::ConfirmSaveConflict { buffer_id } => {
                 let input_lower = input.trim().to_lowercase();
                 if input_lower == "o" || input_lower == "overwrite" {
-                    if let Err(e) = self.save() {
+                    if let Err(e) = self.save_buffer(buffer_id) {
                         self.set_status_message(
                             t!("file.save_failed", error = e.to_string()).to_string(),
                         );
@@ -239,62 +281,30 @@ impl Editor {
                     self.set_status_message(t!("buffer.save_cancelled").to_string());
                 }
             }
-            PromptType::ConfirmSudoSave { info } => {
+            PromptType::ConfirmSaveBreaksLink => {
                 let input_lower = input.trim().to_lowercase();
                 if input_lower == "y" || input_lower == "yes" {
-                    // Hide prompt before starting blocking command to clear the line
-                    self.cancel_prompt();
-
-                    // Read temp file and write via sudo (works for both local and remote)
-                    let result = (|| -> anyhow::Result<()> {
-                        let data = self.filesystem.read_file(&info.temp_path)?;
-                        self.filesystem.sudo_write(
-                            &info.dest_path,
-                            &data,
-                            info.mode,
-                            info.uid,
-                            info.gid,
-                        )?;
-                        // Clean up temp file on success
-                        let _ = self.filesystem.remove_file(&info.temp_path);
-                        Ok(())
-                    })();
-
-                    match result {
-                        Ok(_) => {
-                            if let Err(e) = self
-                                .active_state_mut()
-                                .buffer
-                                .finalize_external_save(info.dest_path.clone())
-                            {
-                                tracing::warn!("Failed to finalize sudo save: {}", e);
-                                self.set_status_message(
-                                    t!("prompt.sudo_save_failed", error = e.to_string())
-                                        .to_string(),
-                                );
-                            } else if let Err(e) = self.finalize_save(Some(info.dest_path)) {
-                                tracing::warn!("Failed to finalize save after sudo: {}", e);
-                                self.set_status_message(
-                                    t!("prompt.sudo_save_failed", error = e.to_string())
-                                        .to_string(),
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            tracing::warn!("Sudo save failed: {}", e);
-                            self.set_status_message(
-                                t!("prompt.sudo_save_failed", error = e.to_string()).to_string(),
-                            );
-                            // Clean up temp file on failure
-                            let _ = self.filesystem.remove_file(&info.temp_path);
-                        }
+                    if let Err(e) = self.save() {
+                        self.set_status_message(
+                            t!("file.save_failed", error = e.to_string()).to_string(),
+                        );
                     }
                 } else {
                     self.set_status_message(t!("buffer.save_cancelled").to_string());
-                    // Clean up temp file
-                    let _ = self.filesystem.remove_file(&info.temp_path);
                 }
             }
+            PromptType::ConfirmSudoSave { buffer_id, info } => {
+                self.confirm_sudo_save(buffer_id, info, &input);
+            }
+            PromptType::PluginPermission {
+                plugin_name,
+                permission,
+            } => {
+                self.answer_plugin_permission(plugin_name, permission, &input);
+            }
+            PromptType::WorkspaceTrust => {
+                self.answer_workspace_trust(&input);
+            }
             PromptType::ConfirmOverwriteFile { path } => {
                 let input_lower = input.trim().to_lowercase();
                 if input_lower == "o" || input_lower == "overwrite" {
@@ -314,6 +324,7 @@ impl Editor {
                 if input_lower == discard_key || input_lower == "discard" {
                     self.should_quit = true;
                 } else {
+                    self.relaunch_executable = None;
                     self.set_status_message(t!("buffer.close_cancelled").to_string());
                 }
             }
@@ -332,6 +343,12 @@ impl Editor {
             } => {
                 self.perform_file_explorer_rename(original_path, original_name, input, is_new_file);
             }
+            PromptType::ConfirmRestartAfterUpdate {
+                version,
+                executable,
+            } => {
+                self.confirm_restart_after_update(&input, version, executable);
+            }
             PromptType::ConfirmDeleteFile { path, is_dir } => {
                 let input_lower = input.trim().to_lowercase();
                 if input_lower == "y" || input_lower == "yes" {
@@ -375,6 +392,30 @@ impl Editor {
             PromptType::StopLspServer => {
                 self.handle_stop_lsp_server(&input);
             }
+            PromptType::CancelBackgroundTask => {
+                self.cancel_background_task(&input);
+            }
+            PromptType::ReloadPlugin => {
+                self.reload_plugin_by_name(input.trim());
+            }
+            PromptType::LocalHistory { buffer_id } => {
+                self.local_history_version_selected(buffer_id, &input);
+            }
+            PromptType::LocalHistoryVersion { buffer_id, version } => {
+                self.local_history_action(buffer_id, version, &input);
+            }
+            PromptType::SetNamedMark => {
+                self.mark_prompt_confirmed(&input, false);
+            }
+            PromptType::JumpToNamedMark => {
+                self.mark_prompt_confirmed(&input, true);
+            }
+            PromptType::QuickfixItem => {
+                self.quickfix_item_selected(&input);
+            }
+            PromptType::QuickfixHistory => {
+                self.quickfix_list_selected(&input);
+            }
             PromptType::SelectTheme { .. } => {
                 self.apply_theme(input.trim());
             }
@@ -395,6 +436,13 @@ impl Editor {
                     self.switch_to_tab(BufferId(id));
                 }
             }
+            PromptType::SetTabGroup {
+                buffer_id,
+                split_id,
+            } => {
+                let group = crate::view::split::TabGroup::from_name(input.trim());
+                self.set_tab_group_in_split(buffer_id, split_id, group);
+            }
             PromptType::QueryReplaceConfirm => {
                 // This is handled by InsertChar, not PromptConfirm
                 // But if somehow Enter is pressed, treat it as skip (n)
@@ -405,6 +453,18 @@ impl Editor {
             PromptType::SetTabSize => {
                 self.handle_set_tab_size(&input);
             }
+            PromptType::ReindentBuffer => {
+                self.handle_reindent_buffer(&input);
+            }
+            PromptType::InsertSequence => {
+                self.handle_insert_sequence(&input);
+            }
+            PromptType::InsertDateTime => {
+                self.handle_insert_date_time(&input, selected_index);
+            }
+            PromptType::AlignSelection => {
+                self.handle_align_selection(&input);
+            }
             PromptType::SetLineEnding => {
                 self.handle_set_line_ending(&input);
             }
@@ -417,6 +477,12 @@ impl Editor {
             PromptType::ShellCommand { replace } => {
                 self.handle_shell_command(&input, replace);
             }
+            PromptType::FilterCommand => {
+                self.filter_through_command(&input);
+            }
+            PromptType::WatchCommand => {
+                self.start_watch_task(&input);
+            }
             PromptType::AsyncPrompt => {
                 // Resolve the pending async prompt callback with the input text
                 if let Some(callback_id) = self.pending_async_prompt_callback.take() {
@@ -425,6 +491,17 @@ impl Editor {
                     self.plugin_manager.resolve_callback(callback_id, json);
                 }
             }
+            PromptType::AsyncQuickPick { .. } => {
+                // Only a picked item counts; free text that matched nothing resolves to null
+                if let Some(callback_id) = self.pending_async_prompt_callback.take() {
+                    let json = if selected_index.is_some() {
+                        serde_json::to_string(&input).unwrap_or_else(|_| "null".to_string())
+                    } else {
+                        "null".to_string()
+                    };
+                    self.plugin_manager.resolve_callback(callback_id, json);
+                }
+            }
         }
         PromptResult::Done
     }
@@ -510,20 +587,14 @@ impl Editor {
                     }
                 }
 
-                self.notify_lsp_save();
+                self.notify_lsp_save(self.active_buffer());
 
                 self.emit_event(
                     crate::model::control_event::events::FILE_SAVED.name,
                     serde_json::json!({"path": full_path.display().to_string()}),
                 );
 
-                self.plugin_manager.run_hook(
-                    "after_file_save",
-                    crate::services::plugins::hooks::HookArgs::AfterFileSave {
-                        buffer_id: self.active_buffer(),
-                        path: full_path.clone(),
-                    },
-                );
+                self.run_did_save_hooks(self.active_buffer(), full_path.clone());
 
                 if let Some(buffer_to_close) = self.pending_close_buffer.take() {
                     if let Err(e) = self.force_close_buffer(buffer_to_close) {
@@ -817,6 +888,7 @@ impl Editor {
                 if let Some(lang) = ts_language {
                     state.reference_highlighter.set_language(&lang);
                 }
+                self.apply_indent_rules(buffer_id);
                 self.set_status_message(format!("Language set to {}", trimmed));
             }
         } else {
@@ -900,7 +972,7 @@ impl Editor {
     }
 
     /// Handle StopLspServer prompt confirmation.
-    fn handle_stop_lsp_server(&mut self, input: &str) {
+    pub(super) fn handle_stop_lsp_server(&mut self, input: &str) {
         let language = input.trim();
         if language.is_empty() {
             return;
@@ -953,6 +1025,12 @@ impl Editor {
             return self.handle_quick_open_buffer(query, selected_index);
         }
 
+        if input.starts_with('@') {
+            // Symbol mode - open the file defining the selected symbol
+            let query = &input[1..];
+            return self.handle_quick_open_symbol(query, selected_index);
+        }
+
         if input.starts_with(':') {
             // Go to line mode
             let line_str = &input[1..];
@@ -1058,6 +1136,37 @@ impl Editor {
         PromptResult::Done
     }
 
+    /// Handle Quick Open symbol selection
+    fn handle_quick_open_symbol(
+        &mut self,
+        query: &str,
+        selected_index: Option<usize>,
+    ) -> PromptResult {
+        let symbol = selected_index.and_then(|idx| {
+            self.search_workspace_symbols(query)
+                .and_then(|symbols| symbols.into_iter().nth(idx))
+        });
+        let Some(symbol) = symbol else {
+            self.set_status_message(t!("status.no_selection").to_string());
+            return PromptResult::Done;
+        };
+
+        let full_path = self.working_dir.join(&symbol.path);
+        self.file_provider.record_access(&symbol.path);
+        match self.open_file(&full_path) {
+            Ok(_) => {
+                self.goto_line_col(symbol.line, None);
+                self.set_status_message(
+                    t!("buffer.opened", name = full_path.display().to_string()).to_string(),
+                );
+            }
+            Err(e) => {
+                self.set_status_message(t!("file.error_opening", error = e.to_string()).to_string())
+            }
+        }
+        PromptResult::Done
+    }
+
     /// Handle Quick Open file selection
     fn handle_quick_open_file(
         &mut self,
@@ -1080,6 +1189,9 @@ impl Editor {
                     // Record file access for frecency
                     self.file_provider.record_access(path_str);
 
+                    if self.start_background_open(&full_path) {
+                        return PromptResult::Done;
+                    }
                     match self.open_file(&full_path) {
                         Ok(_) => {
                             self.set_status_message(
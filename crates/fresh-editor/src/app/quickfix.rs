@@ -0,0 +1,407 @@
+//! Quickfix lists (**Quickfix: Next Item**, **Quickfix: History**, ...)
+//!
+//! A quickfix list is a titled list of locations to step through: the
+//! results of a project search, the problems found by the watch task, or the
+//! references of a symbol. Each source adds its list as the newest of a short
+//! history, as Vim's `:grep` and `:make` do, and the commands step through the
+//! current list or go back to an older one.
+
+use std::path::PathBuf;
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::view::prompt::{Prompt, PromptType};
+
+/// Lists kept in the history; older ones are dropped
+const MAX_LISTS: usize = 10;
+
+/// A location in a quickfix list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuickfixItem {
+    pub path: PathBuf,
+    /// 1-indexed line
+    pub line: usize,
+    /// 1-indexed column
+    pub column: usize,
+    /// Text shown for the item, such as the matched line or a message
+    pub text: String,
+}
+
+/// A titled list of locations
+#[derive(Debug, Clone)]
+pub struct QuickfixList {
+    pub title: String,
+    pub items: Vec<QuickfixItem>,
+    /// Index of the item last jumped to
+    pub selected: Option<usize>,
+}
+
+/// Where to move in the current quickfix list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickfixStep {
+    Next,
+    Prev,
+    First,
+    Last,
+}
+
+impl QuickfixList {
+    /// Select the item `step` leads to; false past either end
+    fn step(&mut self, step: QuickfixStep) -> bool {
+        let target = match (step, self.selected) {
+            (_, _) if self.items.is_empty() => None,
+            (QuickfixStep::First, _) | (QuickfixStep::Next, None) => Some(0),
+            (QuickfixStep::Last, _) => Some(self.items.len() - 1),
+            (QuickfixStep::Next, Some(i)) => Some(i + 1).filter(|&i| i < self.items.len()),
+            (QuickfixStep::Prev, Some(i)) => i.checked_sub(1),
+            (QuickfixStep::Prev, None) => None,
+        };
+        match target {
+            Some(i) => {
+                self.selected = Some(i);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// The quickfix lists, oldest first, and which one is current
+#[derive(Debug, Default)]
+pub struct QuickfixHistory {
+    lists: Vec<QuickfixList>,
+    current: usize,
+}
+
+impl QuickfixHistory {
+    /// Add `list` as the newest list and make it current
+    ///
+    /// Lists newer than the current one are dropped. A list with the same
+    /// title as the one it would follow replaces it, so re-running a search
+    /// or a build doesn't fill the history.
+    pub fn push(&mut self, list: QuickfixList) {
+        self.lists.truncate(self.current + 1);
+        if self.lists.last().is_some_and(|l| l.title == list.title) {
+            self.lists.pop();
+        }
+        self.lists.push(list);
+        if self.lists.len() > MAX_LISTS {
+            self.lists.remove(0);
+        }
+        self.current = self.lists.len() - 1;
+    }
+
+    pub fn lists(&self) -> &[QuickfixList] {
+        &self.lists
+    }
+
+    pub fn current(&self) -> Option<&QuickfixList> {
+        self.lists.get(self.current)
+    }
+
+    fn current_mut(&mut self) -> Option<&mut QuickfixList> {
+        self.lists.get_mut(self.current)
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Make the list at `index` current; false if there is none
+    pub fn select(&mut self, index: usize) -> bool {
+        if index < self.lists.len() {
+            self.current = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Make the previous list current; false if there is none
+    pub fn older(&mut self) -> bool {
+        self.current > 0 && self.select(self.current - 1)
+    }
+
+    /// Make the next list current; false if there is none
+    pub fn newer(&mut self) -> bool {
+        self.select(self.current + 1)
+    }
+}
+
+impl Editor {
+    /// Add a quickfix list as the newest in the history
+    ///
+    /// Relative paths are taken from the working directory.
+    pub(crate) fn set_quickfix_list(
+        &mut self,
+        title: String,
+        mut items: Vec<QuickfixItem>,
+        selected: Option<usize>,
+    ) {
+        for item in &mut items {
+            if item.path.is_relative() {
+                item.path = self.working_dir.join(&item.path);
+            }
+        }
+        let selected = selected.filter(|&i| i < items.len());
+        self.quickfix.push(QuickfixList {
+            title,
+            items,
+            selected,
+        });
+    }
+
+    /// Jump to the item `step` leads to in the current quickfix list
+    pub fn quickfix_step(&mut self, step: QuickfixStep) {
+        let Some(list) = self.quickfix.current_mut() else {
+            self.set_status_message(t!("quickfix.none").to_string());
+            return;
+        };
+        if list.items.is_empty() {
+            let message = t!("quickfix.empty", title = &list.title).to_string();
+            self.set_status_message(message);
+            return;
+        }
+        if !list.step(step) {
+            let message = if step == QuickfixStep::Prev {
+                t!("quickfix.at_first")
+            } else {
+                t!("quickfix.at_last")
+            };
+            self.set_status_message(message.to_string());
+            return;
+        }
+        self.jump_to_selected_quickfix_item();
+    }
+
+    /// Make the older (`older`) or newer quickfix list current
+    pub fn quickfix_switch_list(&mut self, older: bool) {
+        let switched = if older {
+            self.quickfix.older()
+        } else {
+            self.quickfix.newer()
+        };
+        let message = match self.quickfix.current() {
+            Some(list) if switched => t!(
+                "quickfix.list",
+                title = &list.title,
+                count = list.items.len()
+            )
+            .to_string(),
+            None => t!("quickfix.none").to_string(),
+            Some(_) if older => t!("quickfix.no_older").to_string(),
+            Some(_) => t!("quickfix.no_newer").to_string(),
+        };
+        self.set_status_message(message);
+    }
+
+    /// Open the selected item of the current list
+    fn jump_to_selected_quickfix_item(&mut self) {
+        let Some(list) = self.quickfix.current() else {
+            return;
+        };
+        let Some(index) = list.selected else {
+            return;
+        };
+        let item = list.items[index].clone();
+        let count = list.items.len();
+
+        if let Err(e) = self.open_file(&item.path) {
+            self.set_status_message(t!("file.error_opening", error = e.to_string()).to_string());
+            return;
+        }
+        self.goto_line_col(item.line, Some(item.column));
+        self.set_status_message(
+            t!(
+                "quickfix.item",
+                index = index + 1,
+                count = count,
+                text = item.text.trim()
+            )
+            .to_string(),
+        );
+    }
+
+    /// `path:line:col` for an item, relative to the working directory
+    fn quickfix_item_label(&self, item: &QuickfixItem) -> String {
+        let path = item
+            .path
+            .strip_prefix(&self.working_dir)
+            .unwrap_or(&item.path);
+        format!("{}:{}:{}", path.display(), item.line, item.column)
+    }
+
+    /// Pick an item of the current quickfix list to jump to
+    pub fn start_quickfix_items_prompt(&mut self) {
+        let Some(list) = self.quickfix.current() else {
+            self.set_status_message(t!("quickfix.none").to_string());
+            return;
+        };
+        if list.items.is_empty() {
+            let message = t!("quickfix.empty", title = &list.title).to_string();
+            self.set_status_message(message);
+            return;
+        }
+        let suggestions: Vec<Suggestion> = list
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| Suggestion {
+                text: self.quickfix_item_label(item),
+                description: Some(item.text.trim().to_string()).filter(|t| !t.is_empty()),
+                value: Some(i.to_string()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+        let selected = list.selected.unwrap_or(0);
+        let mut prompt = Prompt::with_suggestions(
+            t!("quickfix.items_prompt", title = &list.title).to_string(),
+            PromptType::QuickfixItem,
+            suggestions,
+        );
+        prompt.selected_suggestion = Some(selected);
+        self.prompt = Some(prompt);
+    }
+
+    /// Jump to the quickfix item picked in the items prompt
+    pub(super) fn quickfix_item_selected(&mut self, input: &str) {
+        let Ok(index) = input.trim().parse::<usize>() else {
+            return;
+        };
+        let Some(list) = self.quickfix.current_mut() else {
+            return;
+        };
+        if index < list.items.len() {
+            list.selected = Some(index);
+            self.jump_to_selected_quickfix_item();
+        }
+    }
+
+    /// Pick a list from the quickfix history to make current
+    pub fn start_quickfix_history_prompt(&mut self) {
+        if self.quickfix.lists().is_empty() {
+            self.set_status_message(t!("quickfix.none").to_string());
+            return;
+        }
+        let current = self.quickfix.current_index();
+        // Newest first
+        let suggestions: Vec<Suggestion> = self
+            .quickfix
+            .lists()
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, list)| {
+                let mut description =
+                    t!("quickfix.item_count", count = list.items.len()).to_string();
+                if i == current {
+                    description.push_str(&format!(" {}", t!("quickfix.current")));
+                }
+                Suggestion {
+                    text: list.title.clone(),
+                    description: Some(description),
+                    value: Some(i.to_string()),
+                    disabled: false,
+                    keybinding: None,
+                    source: None,
+                }
+            })
+            .collect();
+        let selected = self.quickfix.lists().len() - 1 - current;
+        let mut prompt = Prompt::with_suggestions(
+            t!("quickfix.history_prompt").to_string(),
+            PromptType::QuickfixHistory,
+            suggestions,
+        );
+        prompt.selected_suggestion = Some(selected);
+        self.prompt = Some(prompt);
+    }
+
+    /// Make the list picked in the history prompt current and pick an item
+    pub(super) fn quickfix_list_selected(&mut self, input: &str) {
+        let Ok(index) = input.trim().parse::<usize>() else {
+            return;
+        };
+        if self.quickfix.select(index) {
+            self.start_quickfix_items_prompt();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(title: &str, items: usize) -> QuickfixList {
+        QuickfixList {
+            title: title.to_string(),
+            items: (1..=items)
+                .map(|line| QuickfixItem {
+                    path: PathBuf::from("/p/a.rs"),
+                    line,
+                    column: 1,
+                    text: String::new(),
+                })
+                .collect(),
+            selected: None,
+        }
+    }
+
+    #[test]
+    fn test_step_stops_at_either_end() {
+        let mut l = list("grep", 2);
+        assert!(!l.step(QuickfixStep::Prev));
+        assert!(l.step(QuickfixStep::Next));
+        assert_eq!(l.selected, Some(0));
+        assert!(l.step(QuickfixStep::Next));
+        assert!(!l.step(QuickfixStep::Next));
+        assert_eq!(l.selected, Some(1));
+        assert!(l.step(QuickfixStep::First));
+        assert_eq!(l.selected, Some(0));
+        assert!(l.step(QuickfixStep::Last));
+        assert_eq!(l.selected, Some(1));
+        assert!(!list("empty", 0).step(QuickfixStep::First));
+    }
+
+    #[test]
+    fn test_history_push_older_newer() {
+        let mut history = QuickfixHistory::default();
+        history.push(list("a", 1));
+        history.push(list("b", 1));
+        history.push(list("c", 1));
+        assert!(history.older());
+        assert!(history.older());
+        assert!(!history.older());
+        assert_eq!(history.current().unwrap().title, "a");
+        assert!(history.newer());
+        assert_eq!(history.current().unwrap().title, "b");
+
+        // A new list drops the newer ones, as in Vim
+        history.push(list("d", 1));
+        let titles: Vec<_> = history.lists().iter().map(|l| l.title.as_str()).collect();
+        assert_eq!(titles, ["a", "b", "d"]);
+        assert!(!history.newer());
+    }
+
+    #[test]
+    fn test_history_replaces_same_title_and_caps_length() {
+        let mut history = QuickfixHistory::default();
+        history.push(list("watch: make", 3));
+        history.push(list("watch: make", 1));
+        assert_eq!(history.lists().len(), 1);
+        assert_eq!(history.current().unwrap().items.len(), 1);
+
+        for i in 0..MAX_LISTS + 2 {
+            history.push(list(&i.to_string(), 1));
+        }
+        assert_eq!(history.lists().len(), MAX_LISTS);
+        assert_eq!(
+            history.current().unwrap().title,
+            (MAX_LISTS + 1).to_string()
+        );
+    }
+}
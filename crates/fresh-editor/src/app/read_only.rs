@@ -0,0 +1,145 @@
+//! Read-only file buffers (**Toggle Read-Only**)
+//!
+//! A file buffer is opened read-only when `editor.auto_read_only` is on and
+//! the user has no permission to write the file, or the file says near its
+//! top that it is generated (`@generated`, "DO NOT EDIT"). Files given on the
+//! command line with `fresh -R` are opened read-only too.
+//!
+//! Read-only sets both `BufferMetadata::read_only` and the state's
+//! `editing_disabled`, so edits are refused and auto-save skips the buffer.
+//! The command flips both for the active buffer; binary files and virtual
+//! buffers stay read-only.
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::model::event::BufferId;
+
+/// Bytes at the start of a file searched for a generated-file marker
+const GENERATED_MARKER_SCAN_BYTES: usize = 1024;
+
+/// Lines at the start of a file searched for a generated-file marker
+const GENERATED_MARKER_SCAN_LINES: usize = 5;
+
+/// Whether `head`, the start of a file, marks the file as generated
+fn is_generated(head: &[u8]) -> bool {
+    String::from_utf8_lossy(head)
+        .lines()
+        .take(GENERATED_MARKER_SCAN_LINES)
+        .map(str::to_lowercase)
+        .any(|line| {
+            line.contains("@generated")
+                || line.contains("do not edit")
+                || line.contains("<auto-generated")
+        })
+}
+
+impl Editor {
+    /// Open a freshly loaded file buffer read-only if it isn't writable or
+    /// is generated
+    pub(super) fn apply_auto_read_only(&mut self, buffer_id: BufferId) {
+        if !self.config.editor.auto_read_only {
+            return;
+        }
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        if state.buffer.is_binary() {
+            return;
+        }
+        let Some(path) = state.buffer.file_path().map(|p| p.to_path_buf()) else {
+            return;
+        };
+        let filesystem = state.buffer.filesystem().clone();
+        if !filesystem.exists(&path) {
+            return;
+        }
+
+        let reason = if !filesystem.is_writable(&path) {
+            t!("read_only.no_permission")
+        } else {
+            let len = state.buffer.len().min(GENERATED_MARKER_SCAN_BYTES);
+            match state.buffer.get_text_range_mut(0, len) {
+                Ok(head) if is_generated(&head) => t!("read_only.generated"),
+                _ => return,
+            }
+        };
+        tracing::info!("Opening {} read-only: {}", path.display(), reason);
+        self.set_buffer_read_only(buffer_id, true);
+        if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
+            metadata.read_only_reason = Some(reason.to_string());
+        }
+    }
+
+    /// Make a file buffer read-only or editable
+    ///
+    /// Returns false for buffers that can't be made editable (binary files,
+    /// virtual buffers and image previews).
+    pub(crate) fn set_buffer_read_only(&mut self, buffer_id: BufferId, read_only: bool) -> bool {
+        let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) else {
+            return false;
+        };
+        if metadata.binary
+            || metadata.is_virtual()
+            || self.terminal_buffers.contains_key(&buffer_id)
+            || self.image_previews.contains_key(&buffer_id)
+        {
+            return false;
+        }
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return false;
+        };
+        if state.is_composite_buffer {
+            return false;
+        }
+        metadata.read_only = read_only;
+        metadata.read_only_reason = None;
+        state.editing_disabled = read_only;
+        true
+    }
+
+    /// Toggle read-only on the active buffer
+    pub fn toggle_read_only(&mut self) {
+        let buffer_id = self.active_buffer();
+        let read_only = !self.active_state().editing_disabled;
+        if !self.set_buffer_read_only(buffer_id, read_only) {
+            self.set_status_message(t!("read_only.cannot_toggle").to_string());
+            return;
+        }
+        let status = if read_only {
+            t!("read_only.on")
+        } else {
+            t!("read_only.off")
+        };
+        self.set_status_message(status.to_string());
+    }
+
+    /// Status message for an edit refused in the active buffer
+    pub(super) fn editing_disabled_message(&self) -> String {
+        let read_only_file = self
+            .buffer_metadata
+            .get(&self.active_buffer())
+            .is_some_and(|m| m.is_read_only_file());
+        if read_only_file {
+            t!("read_only.blocked").to_string()
+        } else {
+            t!("buffer.editing_disabled").to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_generated_finds_markers_near_the_top() {
+        assert!(is_generated(b"// @generated by protoc\nfn main() {}\n"));
+        assert!(is_generated(b"#!/bin/sh\n# DO NOT EDIT - built by make\n"));
+        assert!(is_generated(b"<!-- <auto-generated> -->\n"));
+        assert!(!is_generated(b"fn main() {}\n"));
+        assert!(!is_generated(
+            b"1\n2\n3\n4\n5\n// Code generated. DO NOT EDIT.\n"
+        ));
+    }
+}
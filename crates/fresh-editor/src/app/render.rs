@@ -1,11 +1,19 @@
 use super::*;
+use crate::model::event::CursorId;
+use crate::primitives::search_replace::{Replacement, Replacer, SearchOptions};
+use crate::view::virtual_text::VirtualTextPosition;
 use anyhow::Result as AnyhowResult;
 use rust_i18n::t;
 
+/// Virtual text id of the replacement preview shown in the replace prompt
+const REPLACE_PREVIEW_ID: &str = "replace-preview";
+
 impl Editor {
     /// Render the editor to the terminal
     pub fn render(&mut self, frame: &mut Frame) {
         let _span = tracing::trace_span!("render").entered();
+        self.render_profiler.begin_frame();
+        self.announce_screen_changes();
         let size = frame.area();
 
         // For scroll sync groups, we need to update the active split's viewport position BEFORE
@@ -45,11 +53,16 @@ impl Editor {
         for (buffer_id, (start_line, end_line)) in semantic_ranges {
             self.maybe_request_semantic_tokens_range(buffer_id, start_line, end_line);
             self.maybe_request_semantic_tokens_full_debounced(buffer_id);
+            self.maybe_request_code_lens(buffer_id);
         }
 
         for (split_id, view_state) in &self.split_view_states {
             if let Some(buffer_id) = self.split_manager.get_buffer_id(*split_id) {
                 if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                    // Nothing is loaded from a file a background save is replacing
+                    if state.buffer.is_saving() {
+                        continue;
+                    }
                     let top_byte = view_state.viewport.top_byte;
                     let height = view_state.viewport.height;
                     if let Err(e) = state.prepare_for_render(top_byte, height) {
@@ -128,7 +141,13 @@ impl Editor {
         // Split main content area based on file explorer visibility
         // Also keep the layout split if a sync is in progress (to avoid flicker)
         let editor_content_area;
-        let file_explorer_should_show = self.file_explorer_visible
+        // While sliding open or closed the explorer takes part of its width
+        let explorer_scale = self.file_explorer_animation_scale();
+        // Convert f32 percentage (0.0-1.0) to u16 percentage (0-100)
+        let explorer_percent =
+            (self.file_explorer_width_percent * explorer_scale.unwrap_or(1.0) * 100.0) as u16;
+        let file_explorer_should_show = (self.file_explorer_visible || explorer_scale.is_some())
+            && explorer_percent > 0
             && (self.file_explorer.is_some() || self.file_explorer_sync_in_progress);
 
         if file_explorer_should_show {
@@ -138,8 +157,6 @@ impl Editor {
                 self.file_explorer.is_some(),
                 self.file_explorer_sync_in_progress
             );
-            // Convert f32 percentage (0.0-1.0) to u16 percentage (0-100)
-            let explorer_percent = (self.file_explorer_width_percent * 100.0) as u16;
             let editor_percent = 100 - explorer_percent;
             let horizontal_chunks = Layout::default()
                 .direction(Direction::Horizontal)
@@ -153,7 +170,10 @@ impl Editor {
             editor_content_area = horizontal_chunks[1];
 
             // Get remote connection info before mutable borrow of file_explorer
-            let remote_connection = self.remote_connection_info().map(|s| s.to_string());
+            let remote_connection = self
+                .explorer_filesystem()
+                .remote_connection_info()
+                .map(|info| info.to_string());
 
             // Render file explorer (only if we have it - during sync we just keep the area reserved)
             if let Some(ref mut explorer) = self.file_explorer {
@@ -387,6 +407,9 @@ impl Editor {
                 self.tab_bar_visible,
                 self.config.editor.use_terminal_bg,
                 self.session_mode,
+                self.config.editor.focus_mode_dim_paragraphs,
+                self.config.editor.max_content_width,
+                &self.image_previews,
             );
 
         // Detect viewport changes and fire hooks
@@ -453,6 +476,21 @@ impl Editor {
         // Render terminal content on top of split content for terminal buffers
         self.render_terminal_splits(frame, &split_areas);
 
+        // Images of previews are drawn after the frame, over their blank cells
+        self.update_image_placements(&split_areas);
+
+        if self.hyperlinks_enabled() {
+            for (_, buffer_id, content_rect, _, _, _) in &split_areas {
+                if !self.image_previews.contains_key(buffer_id) {
+                    crate::view::hyperlinks::apply(
+                        frame.buffer_mut(),
+                        *content_rect,
+                        &self.link_detector,
+                    );
+                }
+            }
+        }
+
         self.cached_layout.split_areas = split_areas;
         self.cached_layout.tab_layouts = tab_layouts;
         self.cached_layout.close_split_areas = close_split_areas;
@@ -466,6 +504,8 @@ impl Editor {
         // Render hover highlights for separators and scrollbars
         self.render_hover_highlights(frame);
 
+        self.render_cursor_flash(frame);
+
         // Render file browser popup for OpenFile prompt, or suggestions for other prompts
         self.cached_layout.suggestions_area = None;
         self.file_browser_layout = None;
@@ -541,7 +581,13 @@ impl Editor {
         let display_name = self
             .buffer_metadata
             .get(&self.active_buffer())
-            .map(|m| m.display_name.clone())
+            .map(|m| {
+                if m.is_read_only_file() {
+                    format!("{} [RO]", m.display_name)
+                } else {
+                    m.display_name.clone()
+                }
+            })
             .unwrap_or_else(|| "[No Name]".to_string());
         let status_message = self.status_message.clone();
         let plugin_status_message = self.plugin_status_message.clone();
@@ -581,29 +627,69 @@ impl Editor {
             };
 
             // Get remote connection info if editing remote files
-            let remote_connection = self.remote_connection_info().map(|s| s.to_string());
+            let remote_connection = self.remote_connection_display();
+
+            // Number of buffers waiting for a delayed auto-save
+            let pending_auto_saves = self.pending_auto_save_count();
+            let task_status = self.task_status_text();
 
             // Get session name for display (only in session mode)
             let session_name = self.session_name().map(|s| s.to_string());
 
-            let status_bar_layout = StatusBarRenderer::render_status_bar(
-                frame,
-                main_chunks[status_bar_idx],
-                self.active_state_mut(), // Use the mutable reference
-                &status_message,
-                &plugin_status_message,
-                &lsp_status,
-                &theme,
-                &display_name,
-                &keybindings_cloned,          // Pass the cloned keybindings
-                &chord_state_cloned,          // Pass the cloned chord state
-                update_available.as_deref(),  // Pass update availability
-                warning_level,                // Pass warning level for colored indicator
-                general_warning_count,        // Pass general warning count for badge
-                status_bar_hover,             // Pass hover state for indicator styling
-                remote_connection.as_deref(), // Pass remote connection info
-                session_name.as_deref(),      // Pass session name for status bar display
-            );
+            let status_bar_layout = if self.config.status_line.is_custom() {
+                let status_line_config = self.config.status_line.clone();
+                let editor_mode = self.editor_mode.clone();
+                let git_branch = self.git_branch.clone();
+                let plugin_segments = self.plugin_status_segments.clone();
+                let context = crate::view::ui::status_line::StatusLineContext {
+                    display_name: &display_name,
+                    status_message: status_message.as_deref(),
+                    plugin_status_message: plugin_status_message.as_deref(),
+                    lsp_status: &lsp_status,
+                    warning_level,
+                    general_warning_count,
+                    hover: status_bar_hover,
+                    remote_connection: remote_connection.as_deref(),
+                    session_name: session_name.as_deref(),
+                    update_available: update_available.as_deref(),
+                    keybindings: &keybindings_cloned,
+                    chord_state: &chord_state_cloned,
+                    pending_auto_saves,
+                    task_status: task_status.as_deref(),
+                    editor_mode: editor_mode.as_deref(),
+                    git_branch: git_branch.as_deref(),
+                    plugin_segments: &plugin_segments,
+                };
+                crate::view::ui::status_line::render_status_line(
+                    frame,
+                    main_chunks[status_bar_idx],
+                    self.active_state_mut(),
+                    &context,
+                    &status_line_config,
+                    &theme,
+                )
+            } else {
+                StatusBarRenderer::render_status_bar(
+                    frame,
+                    main_chunks[status_bar_idx],
+                    self.active_state_mut(), // Use the mutable reference
+                    &status_message,
+                    &plugin_status_message,
+                    &lsp_status,
+                    &theme,
+                    &display_name,
+                    &keybindings_cloned,          // Pass the cloned keybindings
+                    &chord_state_cloned,          // Pass the cloned chord state
+                    update_available.as_deref(),  // Pass update availability
+                    warning_level,                // Pass warning level for colored indicator
+                    general_warning_count,        // Pass general warning count for badge
+                    status_bar_hover,             // Pass hover state for indicator styling
+                    remote_connection.as_deref(), // Pass remote connection info
+                    pending_auto_saves,           // Pass pending auto-save count
+                    task_status.as_deref(),       // Pass background task progress
+                    session_name.as_deref(),      // Pass session name for status bar display
+                )
+            };
 
             // Store status bar layout for click detection
             let status_bar_area = main_chunks[status_bar_idx];
@@ -817,6 +903,30 @@ impl Editor {
             }
         }
 
+        // Render the peek window embedded below the cursor line of the active split
+        if self.peek_window.is_some() {
+            let active_split = self.split_manager.active_split();
+            let cursor_pos = self.active_state().cursors.primary().position;
+            let content_rect = self
+                .cached_layout
+                .split_areas
+                .iter()
+                .find(|(split_id, ..)| *split_id == active_split)
+                .map(|(_, _, content_rect, ..)| *content_rect);
+            let cursor_row = self
+                .cached_layout
+                .view_line_mappings
+                .get(&active_split)
+                .and_then(|mappings| mappings.iter().position(|m| m.contains_byte(cursor_pos)));
+            if let (Some(content_rect), Some(cursor_row), Some(peek)) =
+                (content_rect, cursor_row, self.peek_window.as_mut())
+            {
+                let area =
+                    crate::view::peek_window::peek_window_area(content_rect, cursor_row as u16);
+                crate::view::peek_window::render_peek_window(frame, area, peek, &theme_clone);
+            }
+        }
+
         // Render menu bar last so dropdown appears on top of all other content
         // Update menu context with current editor state
         self.update_menu_context();
@@ -938,11 +1048,25 @@ impl Editor {
             }
         }
 
+        if self.render_profiler.overlay_visible() {
+            crate::view::render_profile::render_profile_overlay(
+                frame,
+                size,
+                self.render_profiler.stats().as_ref(),
+                &self.theme,
+            );
+        }
+
         // Convert all colors for terminal capability (256/16 color fallback)
         crate::view::color_support::convert_buffer_colors(
             frame.buffer_mut(),
             self.color_capability,
         );
+
+        // Word-based occurrence highlights settle during split rendering above;
+        // follow up with the server's symbol-aware ranges
+        self.maybe_request_document_highlight();
+        self.render_profiler.end_frame();
     }
 
     /// Render the Quick Open hints line showing available mode prefixes
@@ -1855,16 +1979,13 @@ impl Editor {
         }
     }
 
-    /// Notify LSP of a file save
-    pub(super) fn notify_lsp_save(&mut self) {
+    /// Notify LSP that a buffer was saved
+    pub(super) fn notify_lsp_save(&mut self, buffer_id: BufferId) {
         // Check if LSP is enabled for this buffer
-        let metadata = match self.buffer_metadata.get(&self.active_buffer()) {
+        let metadata = match self.buffer_metadata.get(&buffer_id) {
             Some(m) => m,
             None => {
-                tracing::debug!(
-                    "notify_lsp_save: no metadata for buffer {:?}",
-                    self.active_buffer()
-                );
+                tracing::debug!("notify_lsp_save: no metadata for buffer {:?}", buffer_id);
                 return;
             }
         };
@@ -1885,11 +2006,7 @@ impl Editor {
 
         // Get the file path for language detection
         // Use buffer's stored language
-        let language = match self
-            .buffers
-            .get(&self.active_buffer())
-            .map(|s| s.language.clone())
-        {
+        let language = match self.buffers.get(&buffer_id).map(|s| s.language.clone()) {
             Some(l) => l,
             None => {
                 tracing::debug!("notify_lsp_save: no buffer state");
@@ -1898,7 +2015,11 @@ impl Editor {
         };
 
         // Get the full text to send with didSave
-        let full_text = match self.active_state().buffer.to_string() {
+        let full_text = match self
+            .buffers
+            .get(&buffer_id)
+            .and_then(|s| s.buffer.to_string())
+        {
             Some(t) => t,
             None => {
                 tracing::debug!("notify_lsp_save: buffer not fully loaded");
@@ -1921,15 +2042,21 @@ impl Editor {
                 );
                 return;
             }
-            if let Some(client) = lsp.get_handle_mut(&language) {
+            if lsp.get_handle_mut(&language).is_none() {
+                tracing::warn!("notify_lsp_save: failed to get LSP client for {}", language);
+                return;
+            }
+            // Additional servers of the language are notified if they're running
+            for server_id in lsp.server_ids(&language) {
+                let Some(client) = lsp.get_handle_mut(&server_id) else {
+                    continue;
+                };
                 // Send didSave with the full text content
-                if let Err(e) = client.did_save(uri, Some(full_text)) {
-                    tracing::warn!("Failed to send didSave to LSP: {}", e);
+                if let Err(e) = client.did_save(uri.clone(), Some(full_text.clone())) {
+                    tracing::warn!("Failed to send didSave to LSP {}: {}", server_id, e);
                 } else {
-                    tracing::info!("Successfully sent didSave to LSP");
+                    tracing::info!("Successfully sent didSave to LSP {}", server_id);
                 }
-            } else {
-                tracing::warn!("notify_lsp_save: failed to get LSP client for {}", language);
             }
         } else {
             tracing::debug!("notify_lsp_save: no LSP manager available");
@@ -1939,7 +2066,10 @@ impl Editor {
     /// Convert an action into a list of events to apply to the active buffer
     /// Returns None for actions that don't generate events (like Quit)
     pub fn action_to_events(&mut self, action: Action) -> Option<Vec<Event>> {
-        let tab_size = self.config.editor.tab_size;
+        let tab_size = self
+            .active_state()
+            .tab_size_override
+            .unwrap_or(self.config.editor.tab_size);
         let auto_indent = self.config.editor.auto_indent;
         let estimated_line_length = self.config.editor.estimated_line_length;
 
@@ -2082,6 +2212,126 @@ impl Editor {
         let ns = self.search_namespace.clone();
         let state = self.active_state_mut();
         state.overlays.clear_namespace(&ns, &mut state.marker_list);
+        self.clear_replace_preview();
+    }
+
+    /// The current search options (case, whole word, regex)
+    pub(super) fn search_options(&self) -> SearchOptions {
+        SearchOptions {
+            case_sensitive: self.search_case_sensitive,
+            whole_word: self.search_whole_word,
+            use_regex: self.search_use_regex,
+        }
+    }
+
+    /// Byte range of the lines visible in the active split
+    fn visible_search_range(&mut self) -> Range<usize> {
+        let active_split = self.split_manager.active_split();
+        let (top_byte, visible_height) = self
+            .split_view_states
+            .get(&active_split)
+            .map(|vs| (vs.viewport.top_byte, vs.viewport.height.saturating_sub(2)))
+            .unwrap_or((0, 20));
+
+        let state = self.active_state_mut();
+        let mut visible_end = top_byte;
+        {
+            let mut line_iter = state.buffer.line_iterator(top_byte, 80);
+            for _ in 0..visible_height {
+                if let Some((line_start, line_content)) = line_iter.next_line() {
+                    visible_end = line_start + line_content.len();
+                } else {
+                    break;
+                }
+            }
+        }
+
+        // Ensure we don't go past buffer end
+        top_byte..visible_end.min(state.buffer.len())
+    }
+
+    /// The whole text of the active buffer, loading it first if it's large
+    fn load_buffer_text_for_search(&mut self) -> Option<String> {
+        let state = self.active_state_mut();
+        let total_bytes = state.buffer.len();
+
+        // Force-load the entire buffer if not already loaded
+        // get_text_range_mut() handles lazy loading and returns the content
+        match state.buffer.get_text_range_mut(0, total_bytes) {
+            Ok(bytes) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+            Err(e) => {
+                tracing::warn!("Failed to load buffer for search: {}", e);
+                self.set_status_message(t!("error.buffer_not_loaded").to_string());
+                None
+            }
+        }
+    }
+
+    /// Remove the replacement preview shown while typing the replacement
+    pub(super) fn clear_replace_preview(&mut self) {
+        let state = self.active_state_mut();
+        state
+            .virtual_texts
+            .remove_by_id(&mut state.marker_list, REPLACE_PREVIEW_ID);
+    }
+
+    /// Show what each visible match of `search` would be replaced with
+    /// This is called as the user types in the replace prompt
+    pub(super) fn update_replace_preview(&mut self, search: &str, replacement: &str) {
+        self.clear_replace_preview();
+        let options = self.search_options();
+        let Ok(regex) = options.regex(search) else {
+            return;
+        };
+        let replacer = Replacer::new(replacement, options);
+        let visible = self.visible_search_range();
+        let visible_text = self
+            .active_state_mut()
+            .get_text_range(visible.start, visible.end);
+        for found in replacer.find_all(&regex, &visible_text) {
+            self.add_replace_preview(visible.start + found.range.end, &found.text);
+        }
+    }
+
+    /// Show `text` as the replacement of the match ending at `end`
+    fn add_replace_preview(&mut self, end: usize, text: &str) {
+        let style = ratatui::style::Style::default()
+            .fg(self.theme.search_match_fg)
+            .bg(self.theme.diff_add_bg);
+        let state = self.active_state_mut();
+        let buffer_len = state.buffer.len();
+        // Text at the very end of the buffer goes after the last character
+        let (position, placement) = if end < buffer_len {
+            (end, VirtualTextPosition::BeforeChar)
+        } else if buffer_len > 0 {
+            (buffer_len - 1, VirtualTextPosition::AfterChar)
+        } else {
+            return;
+        };
+        state.virtual_texts.add_with_id(
+            &mut state.marker_list,
+            position,
+            format!("\u{2192}{}", text.replace('\n', "\u{23ce}")),
+            style,
+            placement,
+            10,
+            REPLACE_PREVIEW_ID.to_string(),
+        );
+    }
+
+    /// Refresh the replacement preview if the replace prompt is open,
+    /// e.g. after a search option was toggled
+    pub(super) fn refresh_replace_preview(&mut self) {
+        let Some(prompt) = &self.prompt else {
+            return;
+        };
+        if let PromptType::Replace { search } | PromptType::QueryReplace { search } =
+            &prompt.prompt_type
+        {
+            let (search, input) = (search.clone(), prompt.input.clone());
+            self.update_search_highlights(&search);
+            self.update_replace_preview(&search, &input);
+        }
     }
 
     /// Update search highlights in visible viewport only (for incremental search)
@@ -2093,36 +2343,12 @@ impl Editor {
             return;
         }
 
-        // Get theme colors and search settings before borrowing state
+        // Get theme colors before borrowing state
         let search_bg = self.theme.search_match_bg;
         let search_fg = self.theme.search_match_fg;
-        let case_sensitive = self.search_case_sensitive;
-        let whole_word = self.search_whole_word;
-        let use_regex = self.search_use_regex;
         let ns = self.search_namespace.clone();
 
-        // Build regex pattern if regex mode is enabled, or escape for literal search
-        let regex_pattern = if use_regex {
-            if whole_word {
-                format!(r"\b{}\b", query)
-            } else {
-                query.to_string()
-            }
-        } else {
-            let escaped = regex::escape(query);
-            if whole_word {
-                format!(r"\b{}\b", escaped)
-            } else {
-                escaped
-            }
-        };
-
-        // Build regex with case sensitivity
-        let regex = regex::RegexBuilder::new(&regex_pattern)
-            .case_insensitive(!case_sensitive)
-            .build();
-
-        let regex = match regex {
+        let regex = match self.search_options().regex(query) {
             Ok(r) => r,
             Err(_) => {
                 // Invalid regex, clear highlights and return
@@ -2131,39 +2357,15 @@ impl Editor {
             }
         };
 
-        // Get viewport from active split's SplitViewState
-        let active_split = self.split_manager.active_split();
-        let (top_byte, visible_height) = self
-            .split_view_states
-            .get(&active_split)
-            .map(|vs| (vs.viewport.top_byte, vs.viewport.height.saturating_sub(2)))
-            .unwrap_or((0, 20));
-
+        let visible = self.visible_search_range();
+        let visible_start = visible.start;
         let state = self.active_state_mut();
 
         // Clear any existing search highlights
         state.overlays.clear_namespace(&ns, &mut state.marker_list);
 
-        // Get the visible content by iterating through visible lines
-        let visible_start = top_byte;
-        let mut visible_end = top_byte;
-
-        {
-            let mut line_iter = state.buffer.line_iterator(top_byte, 80);
-            for _ in 0..visible_height {
-                if let Some((line_start, line_content)) = line_iter.next_line() {
-                    visible_end = line_start + line_content.len();
-                } else {
-                    break;
-                }
-            }
-        }
-
-        // Ensure we don't go past buffer end
-        visible_end = visible_end.min(state.buffer.len());
-
         // Get the visible text
-        let visible_text = state.get_text_range(visible_start, visible_end);
+        let visible_text = state.get_text_range(visible.start, visible.end);
 
         // Find all matches using regex
         for mat in regex.find_iter(&visible_text) {
@@ -2205,27 +2407,10 @@ impl Editor {
         // For large files with lazy loading, we need to load the entire buffer
         // before searching. This ensures the search can access all content.
         // (Issue #657: Search on large plain text files)
-        let buffer_content = {
-            let state = self.active_state_mut();
-            let total_bytes = state.buffer.len();
-
-            // Force-load the entire buffer if not already loaded
-            // get_text_range_mut() handles lazy loading and returns the content
-            match state.buffer.get_text_range_mut(0, total_bytes) {
-                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
-                Err(e) => {
-                    tracing::warn!("Failed to load buffer for search: {}", e);
-                    self.set_status_message(t!("error.buffer_not_loaded").to_string());
-                    return;
-                }
-            }
+        let Some(buffer_content) = self.load_buffer_text_for_search() else {
+            return;
         };
 
-        // Get search settings
-        let case_sensitive = self.search_case_sensitive;
-        let whole_word = self.search_whole_word;
-        let use_regex = self.search_use_regex;
-
         // Determine search boundaries
         let (search_start, search_end) = if let Some(ref range) = search_range {
             (range.start, range.end)
@@ -2233,27 +2418,8 @@ impl Editor {
             (0, buffer_content.len())
         };
 
-        // Build regex pattern
-        let regex_pattern = if use_regex {
-            if whole_word {
-                format!(r"\b{}\b", query)
-            } else {
-                query.to_string()
-            }
-        } else {
-            let escaped = regex::escape(query);
-            if whole_word {
-                format!(r"\b{}\b", escaped)
-            } else {
-                escaped
-            }
-        };
-
-        // Build regex with case sensitivity
-        let regex = match regex::RegexBuilder::new(&regex_pattern)
-            .case_insensitive(!case_sensitive)
-            .build()
-        {
+        // Build regex from the search options
+        let regex = match self.search_options().regex(query) {
             Ok(r) => r,
             Err(e) => {
                 self.search_state = None;
@@ -2688,38 +2854,32 @@ impl Editor {
     }
 
     /// Perform a replace-all operation
-    /// Replaces all occurrences of the search query with the replacement text
+    /// Replaces all matches of the search query, using the search options,
+    /// with the replacement expanded for each match
     ///
     /// OPTIMIZATION: Uses BulkEdit for O(n) tree operations instead of O(n²)
-    /// This directly edits the piece tree without loading the entire buffer into memory
     pub(super) fn perform_replace(&mut self, search: &str, replacement: &str) {
         if search.is_empty() {
             self.set_status_message(t!("replace.empty_query").to_string());
             return;
         }
 
-        // Find all matches first (before making any modifications)
-        let matches = {
-            let state = self.active_state();
-            let buffer_len = state.buffer.len();
-            let mut matches = Vec::new();
-            let mut current_pos = 0;
-
-            while current_pos < buffer_len {
-                if let Some(offset) = state.buffer.find_next_in_range(
-                    search,
-                    current_pos,
-                    Some(current_pos..buffer_len),
-                ) {
-                    matches.push(offset);
-                    current_pos = offset + search.len();
-                } else {
-                    break;
-                }
+        let options = self.search_options();
+        let regex = match options.regex(search) {
+            Ok(r) => r,
+            Err(e) => {
+                self.set_status_message(
+                    t!("error.invalid_regex", error = e.to_string()).to_string(),
+                );
+                return;
             }
-            matches
+        };
+        let Some(buffer_content) = self.load_buffer_text_for_search() else {
+            return;
         };
 
+        // Find all matches first (before making any modifications)
+        let matches = Replacer::new(replacement, options).find_all(&regex, &buffer_content);
         let count = matches.len();
 
         if count == 0 {
@@ -2732,21 +2892,7 @@ impl Editor {
 
         // Create Delete+Insert events for each match
         // Events will be processed in reverse order by apply_events_as_bulk_edit
-        let mut events = Vec::with_capacity(count * 2);
-        for &match_pos in &matches {
-            // Delete the matched text
-            events.push(Event::Delete {
-                range: match_pos..match_pos + search.len(),
-                deleted_text: search.to_string(), // We know what text is being deleted
-                cursor_id,
-            });
-            // Insert the replacement
-            events.push(Event::Insert {
-                position: match_pos,
-                text: replacement.to_string(),
-                cursor_id,
-            });
-        }
+        let events = replacement_events(&buffer_content, &matches, cursor_id);
 
         // Apply all replacements using BulkEdit for O(n) performance
         let description = format!("Replace all '{}' with '{}'", search, replacement);
@@ -2758,9 +2904,7 @@ impl Editor {
         self.search_state = None;
 
         // Clear any search highlight overlays
-        let ns = self.search_namespace.clone();
-        let state = self.active_state_mut();
-        state.overlays.clear_namespace(&ns, &mut state.marker_list);
+        self.clear_search_overlays();
 
         // Set status message
         self.set_status_message(
@@ -2781,47 +2925,48 @@ impl Editor {
             return;
         }
 
-        // Find the first match lazily (don't find all matches upfront)
-        let state = self.active_state();
-        let start_pos = state.cursors.primary().position;
-        let first_match = state.buffer.find_next(search, start_pos);
+        let options = self.search_options();
+        let regex = match options.regex(search) {
+            Ok(r) => r,
+            Err(e) => {
+                self.set_status_message(
+                    t!("error.invalid_regex", error = e.to_string()).to_string(),
+                );
+                return;
+            }
+        };
+        let replacer = Replacer::new(replacement, options);
 
-        let Some(first_match_pos) = first_match else {
+        // Find the first match at or after the cursor, wrapping around
+        let start_pos = self.active_state().cursors.primary().position;
+        let Some(buffer_content) = self.load_buffer_text_for_search() else {
+            return;
+        };
+        let first_match = replacer
+            .find_at(&regex, &buffer_content, start_pos.min(buffer_content.len()))
+            .or_else(|| replacer.find_at(&regex, &buffer_content, 0));
+
+        let Some(first_match) = first_match else {
             self.set_status_message(t!("search.no_occurrences", search = search).to_string());
             return;
         };
 
         // Initialize interactive replace state with just the current match
-        self.interactive_replace_state = Some(InteractiveReplaceState {
+        let ir_state = InteractiveReplaceState {
             search: search.to_string(),
             replacement: replacement.to_string(),
-            current_match_pos: first_match_pos,
-            start_pos: first_match_pos,
+            regex,
+            replacer,
+            start_pos: first_match.range.start,
+            current: first_match,
             has_wrapped: false,
             replacements_made: 0,
-        });
-
-        // Move cursor to first match
-        let active_split = self.split_manager.active_split();
-        let active_buffer = self.active_buffer();
-        {
-            let state = self.active_state_mut();
-            state.cursors.primary_mut().position = first_match_pos;
-            state.cursors.primary_mut().anchor = None;
-        }
-        // Ensure cursor is visible - get viewport from SplitViewState
-        if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
-            let state = self.buffers.get_mut(&active_buffer).unwrap();
-            view_state
-                .viewport
-                .ensure_visible(&mut state.buffer, state.cursors.primary());
-        }
+        };
 
-        // Show the query-replace prompt
-        self.prompt = Some(Prompt::new(
-            "Replace? (y)es (n)o (a)ll (c)ancel: ".to_string(),
-            PromptType::QueryReplaceConfirm,
-        ));
+        // Show the query-replace prompt, then move the cursor to the first match
+        self.prompt = Some(Prompt::new(String::new(), PromptType::QueryReplaceConfirm));
+        self.move_to_current_match(&ir_state);
+        self.interactive_replace_state = Some(ir_state);
     }
 
     /// Handle interactive replace key press (y/n/a/c)
@@ -2832,38 +2977,41 @@ impl Editor {
         };
 
         match c {
-            'y' | 'Y' => {
-                // Replace current match
-                self.replace_current_match(&ir_state)?;
-                ir_state.replacements_made += 1;
-
-                // Find next match lazily (after the replacement)
-                let search_pos = ir_state.current_match_pos + ir_state.replacement.len();
-                if let Some((next_match, wrapped)) =
-                    self.find_next_match_for_replace(&ir_state, search_pos)
-                {
-                    ir_state.current_match_pos = next_match;
-                    if wrapped {
-                        ir_state.has_wrapped = true;
-                    }
-                    self.interactive_replace_state = Some(ir_state.clone());
-                    self.move_to_current_match(&ir_state);
+            'y' | 'Y' | 'n' | 'N' => {
+                let replace = matches!(c, 'y' | 'Y');
+                let current = ir_state.current.clone();
+                // Step past an empty match so it isn't found again
+                let step = if current.range.is_empty() {
+                    self.char_len_at(current.range.end)
                 } else {
-                    self.finish_interactive_replace(ir_state.replacements_made);
+                    0
+                };
+                let search_pos = if replace {
+                    // Replace current match, then search after the replacement
+                    self.replace_current_match(&ir_state)?;
+                    ir_state.replacements_made += 1;
+                    current.range.start + current.text.len() + step
+                } else {
+                    // Skip current match
+                    current.range.end + step
+                };
+                // After wrapping, the starting position is after the current
+                // match, so it shifts when the match is replaced
+                if replace && ir_state.has_wrapped {
+                    ir_state.start_pos = (ir_state.start_pos + current.text.len())
+                        .saturating_sub(current.range.len());
                 }
-            }
-            'n' | 'N' => {
-                // Skip current match and find next
-                let search_pos = ir_state.current_match_pos + ir_state.search.len();
+
+                // Find next match lazily
                 if let Some((next_match, wrapped)) =
                     self.find_next_match_for_replace(&ir_state, search_pos)
                 {
-                    ir_state.current_match_pos = next_match;
+                    ir_state.current = next_match;
                     if wrapped {
                         ir_state.has_wrapped = true;
                     }
-                    self.interactive_replace_state = Some(ir_state.clone());
                     self.move_to_current_match(&ir_state);
+                    self.interactive_replace_state = Some(ir_state);
                 } else {
                     self.finish_interactive_replace(ir_state.replacements_made);
                 }
@@ -2873,29 +3021,32 @@ impl Editor {
                 // Undo behavior: ONE undo step undoes ALL remaining replacements
                 //
                 // OPTIMIZATION: Uses BulkEdit for O(n) tree operations instead of O(n²)
-                // This directly edits the piece tree without loading the entire buffer
-
-                // Collect ALL match positions including the current match
-                // Start from the current match position
-                let all_matches = {
-                    let mut matches = Vec::new();
-                    let mut temp_state = ir_state.clone();
-                    temp_state.has_wrapped = false; // Reset wrap state to find current match
-
-                    // First, include the current match
-                    matches.push(ir_state.current_match_pos);
-                    let mut current_pos = ir_state.current_match_pos + ir_state.search.len();
-
-                    // Find all remaining matches
-                    while let Some((next_match, wrapped)) =
-                        self.find_next_match_for_replace(&temp_state, current_pos)
-                    {
-                        matches.push(next_match);
-                        current_pos = next_match + temp_state.search.len();
-                        if wrapped {
-                            temp_state.has_wrapped = true;
-                        }
-                    }
+                let Some(buffer_content) = self.load_buffer_text_for_search() else {
+                    return Ok(());
+                };
+
+                // Collect ALL remaining matches, starting with the current match
+                let current_start = ir_state.current.range.start;
+                let all_matches = if ir_state.has_wrapped {
+                    matches_between(
+                        &ir_state,
+                        &buffer_content,
+                        current_start,
+                        ir_state.start_pos,
+                    )
+                } else {
+                    let mut matches = matches_between(
+                        &ir_state,
+                        &buffer_content,
+                        current_start,
+                        buffer_content.len() + 1,
+                    );
+                    // Then wrap around to the matches before the starting position
+                    matches.extend(
+                        matches_between(&ir_state, &buffer_content, 0, ir_state.start_pos)
+                            .into_iter()
+                            .filter(|m| m.range.end <= current_start),
+                    );
                     matches
                 };
 
@@ -2906,19 +3057,7 @@ impl Editor {
                     let cursor_id = self.active_state().cursors.primary_id();
 
                     // Create Delete+Insert events for each match
-                    let mut events = Vec::with_capacity(total_count * 2);
-                    for &match_pos in &all_matches {
-                        events.push(Event::Delete {
-                            range: match_pos..match_pos + ir_state.search.len(),
-                            deleted_text: ir_state.search.clone(),
-                            cursor_id,
-                        });
-                        events.push(Event::Insert {
-                            position: match_pos,
-                            text: ir_state.replacement.clone(),
-                            cursor_id,
-                        });
-                    }
+                    let events = replacement_events(&buffer_content, &all_matches, cursor_id);
 
                     // Apply all replacements using BulkEdit for O(n) performance
                     let description = format!(
@@ -2946,51 +3085,48 @@ impl Editor {
         Ok(())
     }
 
+    /// Length in bytes of the character at `pos`, or 1 at the end of the buffer
+    fn char_len_at(&mut self, pos: usize) -> usize {
+        let state = self.active_state_mut();
+        let end = (pos + 4).min(state.buffer.len());
+        state
+            .get_text_range(pos, end)
+            .chars()
+            .next()
+            .map_or(1, char::len_utf8)
+    }
+
     /// Find the next match for interactive replace (lazy search with wrap-around)
     pub(super) fn find_next_match_for_replace(
-        &self,
+        &mut self,
         ir_state: &InteractiveReplaceState,
         start_pos: usize,
-    ) -> Option<(usize, bool)> {
-        let state = self.active_state();
+    ) -> Option<(Replacement, bool)> {
+        let buffer_content = self.load_buffer_text_for_search()?;
+        let find = |from: usize| {
+            if from > buffer_content.len() {
+                return None;
+            }
+            ir_state
+                .replacer
+                .find_at(&ir_state.regex, &buffer_content, from)
+        };
 
         if ir_state.has_wrapped {
-            // We've already wrapped - only search from start_pos up to (but not including) the original start position
-            // Use find_next_in_range to avoid wrapping again
-            let search_range = Some(start_pos..ir_state.start_pos);
-            if let Some(match_pos) =
-                state
-                    .buffer
-                    .find_next_in_range(&ir_state.search, start_pos, search_range)
-            {
-                return Some((match_pos, true));
-            }
-            None // No more matches before original start position
+            // We've already wrapped - only accept matches before the original start position
+            find(start_pos)
+                .filter(|m| m.range.start < ir_state.start_pos)
+                .map(|m| (m, true))
         } else {
-            // Haven't wrapped yet - search normally from start_pos
-            // First try from start_pos to end of buffer
-            let buffer_len = state.buffer.len();
-            let search_range = Some(start_pos..buffer_len);
-            if let Some(match_pos) =
-                state
-                    .buffer
-                    .find_next_in_range(&ir_state.search, start_pos, search_range)
-            {
-                return Some((match_pos, false));
+            // Haven't wrapped yet - search from start_pos to end of buffer
+            if let Some(found) = find(start_pos) {
+                return Some((found, false));
             }
 
             // No match from start_pos to end - wrap to beginning
-            // Search from 0 to start_pos (original position)
-            let wrap_range = Some(0..ir_state.start_pos);
-            if let Some(match_pos) =
-                state
-                    .buffer
-                    .find_next_in_range(&ir_state.search, 0, wrap_range)
-            {
-                return Some((match_pos, true)); // Found match after wrapping
-            }
-
-            None // No matches found anywhere
+            find(0)
+                .filter(|m| m.range.start < ir_state.start_pos)
+                .map(|m| (m, true))
         }
     }
 
@@ -2999,9 +3135,8 @@ impl Editor {
         &mut self,
         ir_state: &InteractiveReplaceState,
     ) -> AnyhowResult<()> {
-        let match_pos = ir_state.current_match_pos;
-        let search_len = ir_state.search.len();
-        let range = match_pos..(match_pos + search_len);
+        let range = ir_state.current.range.clone();
+        let match_pos = range.start;
 
         // Get the deleted text for the event
         let deleted_text = self
@@ -3017,27 +3152,29 @@ impl Editor {
 
         // Create events: MoveCursor, Delete, Insert
         // The MoveCursor saves the cursor position so undo can restore it
-        let events = vec![
-            Event::MoveCursor {
-                cursor_id,
-                old_position,
-                new_position: match_pos,
-                old_anchor,
-                new_anchor: None,
-                old_sticky_column,
-                new_sticky_column: 0,
-            },
-            Event::Delete {
+        let mut events = vec![Event::MoveCursor {
+            cursor_id,
+            old_position,
+            new_position: match_pos,
+            old_anchor,
+            new_anchor: None,
+            old_sticky_column,
+            new_sticky_column: 0,
+        }];
+        if !range.is_empty() {
+            events.push(Event::Delete {
                 range: range.clone(),
                 deleted_text,
                 cursor_id,
-            },
-            Event::Insert {
+            });
+        }
+        if !ir_state.current.text.is_empty() {
+            events.push(Event::Insert {
                 position: match_pos,
-                text: ir_state.replacement.clone(),
+                text: ir_state.current.text.clone(),
                 cursor_id,
-            },
-        ];
+            });
+        }
 
         // Wrap in batch for atomic undo
         let batch = Event::Batch {
@@ -3057,7 +3194,7 @@ impl Editor {
 
     /// Move cursor to the current match in interactive replace
     pub(super) fn move_to_current_match(&mut self, ir_state: &InteractiveReplaceState) {
-        let match_pos = ir_state.current_match_pos;
+        let match_pos = ir_state.current.range.start;
         let active_split = self.split_manager.active_split();
         let active_buffer = self.active_buffer();
         {
@@ -3073,6 +3210,10 @@ impl Editor {
                 .ensure_visible(&mut state.buffer, state.cursors.primary());
         }
 
+        // Show what this match becomes
+        self.clear_replace_preview();
+        self.add_replace_preview(ir_state.current.range.end, &ir_state.current.text);
+
         // Update the prompt message (show [Wrapped] if we've wrapped around)
         let msg = if ir_state.has_wrapped {
             "[Wrapped] Replace? (y)es (n)o (a)ll (c)ancel: ".to_string()
@@ -3094,9 +3235,7 @@ impl Editor {
         self.prompt = None; // Clear the query-replace prompt
 
         // Clear search highlights
-        let ns = self.search_namespace.clone();
-        let state = self.active_state_mut();
-        state.overlays.clear_namespace(&ns, &mut state.marker_list);
+        self.clear_search_overlays();
 
         self.set_status_message(t!("search.replaced_count", count = replacements_made).to_string());
     }
@@ -3143,196 +3282,6 @@ impl Editor {
         }
     }
 
-    /// Toggle comment on the current line or selection
-    pub(super) fn toggle_comment(&mut self) {
-        // Determine comment prefix from language config
-        // If no language detected or no comment prefix configured, do nothing
-        let language = &self.active_state().language;
-        let comment_prefix = self
-            .config
-            .languages
-            .get(language)
-            .and_then(|lang_config| lang_config.comment_prefix.clone());
-
-        let comment_prefix: String = match comment_prefix {
-            Some(prefix) => {
-                // Ensure there's a trailing space for consistent formatting
-                if prefix.ends_with(' ') {
-                    prefix
-                } else {
-                    format!("{} ", prefix)
-                }
-            }
-            None => return, // No comment prefix for this language, do nothing
-        };
-
-        let estimated_line_length = self.config.editor.estimated_line_length;
-
-        let state = self.active_state_mut();
-        let cursor = *state.cursors.primary();
-        let cursor_id = state.cursors.primary_id();
-
-        // Save original selection info to restore after edit
-        let original_anchor = cursor.anchor;
-        let original_position = cursor.position;
-        let had_selection = original_anchor.is_some();
-
-        let (start_pos, end_pos) = if let Some(range) = cursor.selection_range() {
-            (range.start, range.end)
-        } else {
-            let iter = state
-                .buffer
-                .line_iterator(cursor.position, estimated_line_length);
-            let line_start = iter.current_position();
-            (line_start, cursor.position)
-        };
-
-        // Find all line starts in the range
-        let buffer_len = state.buffer.len();
-        let mut line_starts = Vec::new();
-        let mut iter = state.buffer.line_iterator(start_pos, estimated_line_length);
-        let mut current_pos = iter.current_position();
-        line_starts.push(current_pos);
-
-        while let Some((_, content)) = iter.next_line() {
-            current_pos += content.len();
-            if current_pos >= end_pos || current_pos >= buffer_len {
-                break;
-            }
-            let next_iter = state
-                .buffer
-                .line_iterator(current_pos, estimated_line_length);
-            let next_start = next_iter.current_position();
-            if next_start != *line_starts.last().unwrap() {
-                line_starts.push(next_start);
-            }
-            iter = state
-                .buffer
-                .line_iterator(current_pos, estimated_line_length);
-        }
-
-        // Determine if we should comment or uncomment
-        // If all lines are commented, uncomment; otherwise comment
-        let all_commented = line_starts.iter().all(|&line_start| {
-            let line_bytes = state
-                .buffer
-                .slice_bytes(line_start..buffer_len.min(line_start + comment_prefix.len() + 10));
-            let line_str = String::from_utf8_lossy(&line_bytes);
-            let trimmed = line_str.trim_start();
-            trimmed.starts_with(comment_prefix.trim())
-        });
-
-        let mut events = Vec::new();
-        // Track (edit_position, byte_delta) for calculating new cursor positions
-        // delta is positive for insertions, negative for deletions
-        let mut position_deltas: Vec<(usize, isize)> = Vec::new();
-
-        if all_commented {
-            // Uncomment: remove comment prefix from each line
-            for &line_start in line_starts.iter().rev() {
-                let line_bytes = state
-                    .buffer
-                    .slice_bytes(line_start..buffer_len.min(line_start + 100));
-                let line_str = String::from_utf8_lossy(&line_bytes);
-
-                // Find where the comment prefix starts (after leading whitespace)
-                let leading_ws: usize = line_str
-                    .chars()
-                    .take_while(|c| c.is_whitespace() && *c != '\n')
-                    .map(|c| c.len_utf8())
-                    .sum();
-                let rest = &line_str[leading_ws..];
-
-                if rest.starts_with(comment_prefix.trim()) {
-                    let remove_len = if rest.starts_with(&comment_prefix) {
-                        comment_prefix.len()
-                    } else {
-                        comment_prefix.trim().len()
-                    };
-                    let deleted_text = String::from_utf8_lossy(&state.buffer.slice_bytes(
-                        line_start + leading_ws..line_start + leading_ws + remove_len,
-                    ))
-                    .to_string();
-                    events.push(Event::Delete {
-                        range: (line_start + leading_ws)..(line_start + leading_ws + remove_len),
-                        deleted_text,
-                        cursor_id,
-                    });
-                    position_deltas.push((line_start, -(remove_len as isize)));
-                }
-            }
-        } else {
-            // Comment: add comment prefix to each line
-            let prefix_len = comment_prefix.len();
-            for &line_start in line_starts.iter().rev() {
-                events.push(Event::Insert {
-                    position: line_start,
-                    text: comment_prefix.to_string(),
-                    cursor_id,
-                });
-                position_deltas.push((line_start, prefix_len as isize));
-            }
-        }
-
-        if events.is_empty() {
-            return;
-        }
-
-        let action_desc = if all_commented {
-            "Uncomment"
-        } else {
-            "Comment"
-        };
-
-        // If there was a selection, add a MoveCursor event to restore it
-        if had_selection {
-            // Sort deltas by position ascending for calculation
-            position_deltas.sort_by_key(|(pos, _)| *pos);
-
-            // Calculate cumulative shift for a position based on edits at or before that position
-            let calc_shift = |original_pos: usize| -> isize {
-                let mut shift: isize = 0;
-                for (edit_pos, delta) in &position_deltas {
-                    if *edit_pos < original_pos {
-                        shift += delta;
-                    }
-                }
-                shift
-            };
-
-            let anchor_shift = calc_shift(original_anchor.unwrap_or(0));
-            let position_shift = calc_shift(original_position);
-
-            let new_anchor = (original_anchor.unwrap_or(0) as isize + anchor_shift).max(0) as usize;
-            let new_position = (original_position as isize + position_shift).max(0) as usize;
-
-            events.push(Event::MoveCursor {
-                cursor_id,
-                old_position: original_position,
-                new_position,
-                old_anchor: original_anchor,
-                new_anchor: Some(new_anchor),
-                old_sticky_column: 0,
-                new_sticky_column: 0,
-            });
-        }
-
-        // Use optimized bulk edit for multi-line comment toggle
-        let description = format!("{} lines", action_desc);
-        if let Some(bulk_edit) = self.apply_events_as_bulk_edit(events, description) {
-            self.active_event_log_mut().append(bulk_edit);
-        }
-
-        self.set_status_message(
-            t!(
-                "lines.action",
-                action = action_desc,
-                count = line_starts.len()
-            )
-            .to_string(),
-        );
-    }
-
     /// Go to matching bracket
     pub(super) fn goto_matching_bracket(&mut self) {
         let state = self.active_state_mut();
@@ -3784,11 +3733,13 @@ impl Editor {
         let metadata = BufferMetadata {
             kind: BufferKind::Virtual {
                 mode: "macro-view".to_string(),
+                uri: None,
             },
             display_name: format!("*Macro {}*", key),
             lsp_enabled: false,
             lsp_disabled_reason: Some("Virtual macro buffer".to_string()),
             read_only: false, // Allow editing for saving
+            read_only_reason: None,
             binary: false,
             lsp_opened_with: std::collections::HashSet::new(),
             hidden_from_tabs: false,
@@ -3796,6 +3747,7 @@ impl Editor {
             is_external_editor: false,
             external_editor_command: None,
             external_editor_file: None,
+            preview: false,
         };
         self.buffer_metadata.insert(buffer_id, metadata);
 
@@ -3862,11 +3814,13 @@ impl Editor {
         let metadata = BufferMetadata {
             kind: BufferKind::Virtual {
                 mode: "macro-list".to_string(),
+                uri: None,
             },
             display_name: "*Macros*".to_string(),
             lsp_enabled: false,
             lsp_disabled_reason: Some("Virtual macro list buffer".to_string()),
             read_only: true,
+            read_only_reason: None,
             binary: false,
             lsp_opened_with: std::collections::HashSet::new(),
             hidden_from_tabs: false,
@@ -3874,6 +3828,7 @@ impl Editor {
             is_external_editor: false,
             external_editor_command: None,
             external_editor_file: None,
+            preview: false,
         };
         self.buffer_metadata.insert(buffer_id, metadata);
 
@@ -4224,3 +4179,53 @@ impl Editor {
         }
     }
 }
+
+/// Delete+Insert events replacing each of `matches` in `text`
+pub(super) fn replacement_events(
+    text: &str,
+    matches: &[Replacement],
+    cursor_id: CursorId,
+) -> Vec<Event> {
+    let mut events = Vec::with_capacity(matches.len() * 2);
+    for found in matches {
+        if !found.range.is_empty() {
+            events.push(Event::Delete {
+                range: found.range.clone(),
+                deleted_text: text[found.range.clone()].to_string(),
+                cursor_id,
+            });
+        }
+        events.push(Event::Insert {
+            position: found.range.start,
+            text: found.text.clone(),
+            cursor_id,
+        });
+    }
+    events
+}
+
+/// Matches for interactive replace starting in `from..until`, in order
+fn matches_between(
+    ir_state: &InteractiveReplaceState,
+    text: &str,
+    from: usize,
+    until: usize,
+) -> Vec<Replacement> {
+    let mut matches = Vec::new();
+    let mut pos = from;
+    while pos <= text.len() {
+        let Some(found) = ir_state.replacer.find_at(&ir_state.regex, text, pos) else {
+            break;
+        };
+        if found.range.start >= until {
+            break;
+        }
+        pos = found.range.end;
+        if found.range.is_empty() {
+            // Step past an empty match so it isn't found again
+            pos += text[pos..].chars().next().map_or(1, char::len_utf8);
+        }
+        matches.push(found);
+    }
+    matches
+}
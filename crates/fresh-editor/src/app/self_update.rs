@@ -0,0 +1,92 @@
+//! **Update Fresh Now**
+//!
+//! Installs the newest release on the configured `update_channel` in the
+//! background (see [`crate::services::self_update`]) and then offers to
+//! restart into it. Restarting goes through the normal quit path, so modified
+//! buffers still prompt first; the new binary is started by `main` once the
+//! terminal has been restored.
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::services::async_bridge::AsyncMessage;
+use crate::services::release_checker::DEFAULT_RELEASES_URL;
+use crate::services::self_update::{self, InstalledUpdate};
+use crate::view::prompt::PromptType;
+
+impl Editor {
+    /// Download and install the latest release on the configured channel
+    pub fn update_fresh_now(&mut self) {
+        if self.self_update_running {
+            self.set_status_message(t!("update.already_running").to_string());
+            return;
+        }
+        let (Some(runtime), Some(bridge)) = (&self.tokio_runtime, &self.async_bridge) else {
+            return;
+        };
+        let channel = self.config.update_channel;
+        let allow_unverified = self.config.allow_unverified_updates;
+        let sender = bridge.sender();
+        runtime.spawn_blocking(move || {
+            let result =
+                self_update::update_to_latest(DEFAULT_RELEASES_URL, channel, allow_unverified)
+                    .map_err(|e| e.to_string());
+            let _ = sender.send(AsyncMessage::SelfUpdateFinished(result));
+        });
+
+        self.self_update_running = true;
+        self.set_status_message(t!("update.in_progress", channel = channel.as_str()).to_string());
+    }
+
+    /// Report a finished update and offer to restart into it
+    pub(super) fn handle_self_update_finished(&mut self, result: Result<InstalledUpdate, String>) {
+        self.self_update_running = false;
+        let update = match result {
+            Ok(update) => update,
+            Err(error) => {
+                tracing::warn!("Self-update failed: {}", error);
+                self.set_status_message(t!("update.failed", error = error).to_string());
+                return;
+            }
+        };
+
+        let message = if update.signature_checked {
+            tracing::info!(
+                version = %update.version,
+                "Installed update to {} (checksum and signature verified)",
+                update.executable.display()
+            );
+            t!("update.restart_prompt_signed", version = update.version)
+        } else {
+            tracing::warn!(
+                version = %update.version,
+                "Installed update to {} without a signature check (allow_unverified_updates)",
+                update.executable.display()
+            );
+            t!("update.restart_prompt", version = update.version)
+        };
+        self.start_prompt(
+            message.to_string(),
+            PromptType::ConfirmRestartAfterUpdate {
+                version: update.version,
+                executable: update.executable,
+            },
+        );
+    }
+
+    /// Handle the answer to the restart prompt shown after an update
+    pub(super) fn confirm_restart_after_update(
+        &mut self,
+        input: &str,
+        version: String,
+        executable: std::path::PathBuf,
+    ) {
+        let input_lower = input.trim().to_lowercase();
+        if input_lower == "y" || input_lower == "yes" {
+            self.relaunch_executable = Some(executable);
+            self.quit();
+        } else {
+            self.set_status_message(t!("update.restart_later", version = version).to_string());
+        }
+    }
+}
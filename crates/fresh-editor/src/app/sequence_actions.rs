@@ -0,0 +1,91 @@
+//! **Insert Sequence**: number the cursors, in document order, from a start
+//! and step entered in a prompt (`1`, `10, 10`, `001`). Each number replaces
+//! its cursor's selection.
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::model::event::{CursorId, Event};
+use crate::primitives::text_transform::Sequence;
+use crate::view::prompt::PromptType;
+
+impl Editor {
+    /// Ask for the start and step of the sequence
+    pub(crate) fn start_insert_sequence_prompt(&mut self) {
+        self.start_prompt_with_initial_text(
+            t!("sequence.prompt").to_string(),
+            PromptType::InsertSequence,
+            "1".to_string(),
+        );
+    }
+
+    /// Handle the Insert Sequence prompt
+    pub(crate) fn handle_insert_sequence(&mut self, input: &str) {
+        let Some(sequence) = Sequence::parse(input) else {
+            self.set_status_message(t!("sequence.invalid", input = input).to_string());
+            return;
+        };
+        if self.is_editing_disabled() {
+            self.set_status_message(self.editing_disabled_message());
+            return;
+        }
+
+        let state = self.active_state_mut();
+        let mut cursors: Vec<_> = state.cursors.iter().map(|(id, c)| (id, *c)).collect();
+        cursors.sort_by_key(|(_, cursor)| {
+            cursor
+                .selection_range()
+                .map_or(cursor.position, |range| range.start)
+        });
+
+        // (range, number) per cursor, skipping selections that overlap
+        let mut edits: Vec<(std::ops::Range<usize>, String)> = Vec::new();
+        let mut numbered = Vec::new();
+        for (cursor_id, cursor) in &cursors {
+            let range = cursor
+                .selection_range()
+                .unwrap_or(cursor.position..cursor.position);
+            if edits.last().is_some_and(|(last, _)| range.start < last.end) {
+                continue;
+            }
+            edits.push((range, sequence.nth(edits.len())));
+            numbered.push((*cursor_id, *cursor));
+        }
+
+        let mut events = Vec::new();
+        for (range, text) in edits.iter().rev() {
+            if !range.is_empty() {
+                events.push(Event::Delete {
+                    range: range.clone(),
+                    deleted_text: state.get_text_range(range.start, range.end),
+                    cursor_id: CursorId::UNDO_SENTINEL,
+                });
+            }
+            events.push(Event::Insert {
+                position: range.start,
+                text: text.clone(),
+                cursor_id: CursorId::UNDO_SENTINEL,
+            });
+        }
+        // Each cursor ends up after its number
+        let mut shift = 0isize;
+        for ((cursor_id, cursor), (range, text)) in numbered.iter().zip(&edits) {
+            shift += text.len() as isize - range.len() as isize;
+            events.push(Event::MoveCursor {
+                cursor_id: *cursor_id,
+                old_position: cursor.position,
+                new_position: (range.end as isize + shift) as usize,
+                old_anchor: cursor.anchor,
+                new_anchor: None,
+                old_sticky_column: cursor.sticky_column,
+                new_sticky_column: 0,
+            });
+        }
+
+        let count = edits.len();
+        if let Some(bulk_edit) = self.apply_events_as_bulk_edit(events, "Insert sequence".into()) {
+            self.active_event_log_mut().append(bulk_edit);
+        }
+        self.set_status_message(t!("sequence.inserted", count = count).to_string());
+    }
+}
@@ -0,0 +1,190 @@
+//! Commands sent to a running session (`fresh --cmd session exec`)
+//!
+//! Each command is a single line: a verb followed by its argument, for
+//! example `open src/main.rs:10` or `command Save File`. The result is plain
+//! text that the CLI prints, so scripts can both drive the editor and query it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::Editor;
+use crate::input::keybindings::Action;
+
+/// Verbs understood by `run_session_command`, for the error on unknown input
+const SESSION_COMMAND_VERBS: &str =
+    "open, goto, save, save-all, action, command, file, cursor, buffers, cd, pipe";
+
+impl Editor {
+    /// Run one session command and return its textual result
+    ///
+    /// - `open PATH[:LINE[:COL]]` opens a file (relative to the shell directory)
+    /// - `goto LINE[:COL]` moves the cursor in the active buffer
+    /// - `save` saves the active buffer, `save-all` every modified file
+    /// - `action NAME` runs an editor action, as named in keybindings
+    /// - `command NAME` runs a command palette entry, including plugin commands
+    /// - `file`, `cursor` and `buffers` print the active file, the cursor
+    ///   position and the open buffers
+    /// - `cd DIR` sets the shell directory, used for relative paths, new
+    ///   terminals and the Open File prompt
+    /// - `pipe PATH` opens the content of PATH, a temp file of piped output,
+    ///   in a new unnamed buffer
+    pub fn run_session_command(&mut self, command: &str) -> Result<String, String> {
+        let command = command.trim();
+        let (verb, arg) = command
+            .split_once(char::is_whitespace)
+            .map(|(verb, arg)| (verb, arg.trim()))
+            .unwrap_or((command, ""));
+
+        match (verb, arg) {
+            ("open", path) if !path.is_empty() => self.session_open(path),
+            ("goto", position) if !position.is_empty() => {
+                let (line, column) = parse_line_col(position)
+                    .ok_or_else(|| format!("invalid position '{}'", position))?;
+                self.goto_line_col(line, column);
+                Ok(self.session_cursor())
+            }
+            ("save", "") => {
+                self.save().map_err(|e| e.to_string())?;
+                Ok(self.session_file())
+            }
+            ("save-all", "") => self.session_save_all(),
+            ("action", name) if !name.is_empty() => {
+                let action = Action::from_str(name, &HashMap::new())
+                    .ok_or_else(|| format!("unknown action '{}'", name))?;
+                self.handle_action(action).map_err(|e| e.to_string())?;
+                Ok(String::new())
+            }
+            ("command", name) if !name.is_empty() => {
+                let action = self
+                    .command_registry
+                    .read()
+                    .unwrap()
+                    .get_all()
+                    .into_iter()
+                    .find(|c| c.name == name || c.get_localized_name() == name)
+                    .map(|c| c.action)
+                    .ok_or_else(|| format!("unknown command '{}'", name))?;
+                self.handle_action(action).map_err(|e| e.to_string())?;
+                Ok(String::new())
+            }
+            ("cd", dir) if !dir.is_empty() => self.session_cd(dir),
+            ("pipe", path) if !path.is_empty() => {
+                self.open_stdin_buffer(Path::new(path), None)
+                    .map_err(|e| e.to_string())?;
+                self.complete_stdin_streaming();
+                Ok(self.session_file())
+            }
+            ("file", "") => Ok(self.session_file()),
+            ("cursor", "") => Ok(self.session_cursor()),
+            ("buffers", "") => Ok(self
+                .listed_buffers()
+                .into_iter()
+                .map(|(name, path, modified)| {
+                    let name = path.map(|p| p.display().to_string()).unwrap_or(name);
+                    format!("{}{}", if modified { "* " } else { "  " }, name)
+                })
+                .collect::<Vec<_>>()
+                .join("\n")),
+            _ => Err(format!(
+                "unknown command '{}' (expected one of: {})",
+                command, SESSION_COMMAND_VERBS
+            )),
+        }
+    }
+
+    fn session_open(&mut self, location: &str) -> Result<String, String> {
+        // A path that exists wins over reading a `:LINE` suffix
+        let dir = self.shell_or_working_dir();
+        let (path, line, column) = match location.split_once(':') {
+            Some((path, position)) if !dir.join(location).exists() => {
+                match parse_line_col(position) {
+                    Some((line, column)) => (path, Some(line), column),
+                    None => (location, None, None),
+                }
+            }
+            _ => (location, None, None),
+        };
+        let path = dir.join(PathBuf::from(path));
+        self.open_file(&path).map_err(|e| e.to_string())?;
+        if let Some(line) = line {
+            self.goto_line_col(line, column);
+        }
+        Ok(path.display().to_string())
+    }
+
+    fn session_cd(&mut self, dir: &str) -> Result<String, String> {
+        let dir = self.shell_or_working_dir().join(dir);
+        if !dir.is_dir() {
+            return Err(format!("not a directory: {}", dir.display()));
+        }
+        let dir = dir.canonicalize().unwrap_or(dir);
+        let shown = dir.display().to_string();
+        self.shell_dir = Some(dir);
+        Ok(shown)
+    }
+
+    /// The shell directory if shell integration reported one, otherwise the
+    /// working directory
+    pub(super) fn shell_or_working_dir(&self) -> PathBuf {
+        self.shell_dir
+            .clone()
+            .unwrap_or_else(|| self.working_dir.clone())
+    }
+
+    fn session_save_all(&mut self) -> Result<String, String> {
+        let mut ids: Vec<_> = self
+            .buffers
+            .keys()
+            .copied()
+            .filter(|id| self.can_save_without_prompt(*id))
+            .collect();
+        ids.sort_by_key(|id| id.0);
+
+        let mut saved = Vec::new();
+        let mut failed = Vec::new();
+        for id in ids {
+            let path = self.buffers[&id]
+                .buffer
+                .file_path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            if self.auto_save_buffer(id) {
+                saved.push(path);
+            } else {
+                failed.push(path);
+            }
+        }
+        if failed.is_empty() {
+            Ok(saved.join("\n"))
+        } else {
+            Err(format!("failed to save: {}", failed.join(", ")))
+        }
+    }
+
+    /// Path (or name) of the active buffer
+    fn session_file(&self) -> String {
+        let id = self.active_buffer();
+        self.active_state()
+            .buffer
+            .file_path()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| self.get_buffer_display_name(id))
+    }
+
+    /// Primary cursor as 1-indexed `LINE:COL`
+    fn session_cursor(&self) -> String {
+        let state = self.active_state();
+        let (line, column) = state
+            .buffer
+            .position_to_line_col(state.cursors.primary().position);
+        format!("{}:{}", line + 1, column + 1)
+    }
+}
+
+/// Parse `LINE` or `LINE:COL` (1-indexed)
+fn parse_line_col(position: &str) -> Option<(usize, Option<usize>)> {
+    match position.split_once(':') {
+        Some((line, column)) => Some((line.parse().ok()?, Some(column.parse().ok()?))),
+        None => Some((position.parse().ok()?, None)),
+    }
+}
@@ -94,19 +94,19 @@ impl Editor {
 
         // Apply the new config
         self.config = new_config.clone();
+        crate::primitives::display_width::set_ambiguous_wide(
+            self.config.editor.ambiguous_width == crate::config::AmbiguousWidth::Wide,
+        );
+        self.apply_render_whitespace();
+        self.sync_cursor_style();
 
         // Refresh cached raw user config for plugins
         self.user_config_raw = Config::read_user_config_raw(&self.working_dir);
 
         // Apply runtime changes
-        if old_theme != self.config.theme {
-            if let Some(theme) = self.theme_registry.get_cloned(&self.config.theme) {
-                self.theme = theme;
-                tracing::info!("Theme changed to '{}'", self.config.theme.0);
-            } else {
-                tracing::error!("Theme '{}' not found", self.config.theme.0);
-                self.set_status_message(format!("Theme '{}' not found", self.config.theme.0));
-            }
+        if old_theme != self.config.theme && !self.apply_configured_theme() {
+            let name = self.config.theme.resolve(self.appearance).0.clone();
+            self.set_status_message(format!("Theme '{}' not found", name));
         }
 
         // Apply locale change at runtime
@@ -139,6 +139,9 @@ impl Editor {
             for (language, lsp_config) in &self.config.lsp {
                 lsp.set_language_config(language.clone(), lsp_config.clone());
             }
+            for (language, lang_config) in &self.config.languages {
+                lsp.set_additional_servers(language, lang_config.lsp_servers.clone());
+            }
         }
 
         // Save ONLY the changes to disk (preserves external edits to the config file)
@@ -3,12 +3,17 @@
 //! This module provides functionality to:
 //! - Run shell commands with buffer or selection content as stdin
 //! - Output results to a new buffer or replace the input content
+//! - Filter the selection through a command (**Filter Through Command**),
+//!   run by the editor's [`ProcessSpawner`] so remote buffers are filtered
+//!   on the remote host
+//!
+//! [`ProcessSpawner`]: crate::services::remote::ProcessSpawner
 
 use std::io::Write;
 use std::process::{Command, Stdio};
 
 use super::Editor;
-use crate::model::event::Event;
+use crate::model::event::{Event, PopupContentData, PopupData, PopupPositionData};
 use crate::view::prompt::PromptType;
 use rust_i18n::t;
 
@@ -94,28 +99,21 @@ impl Editor {
         }
     }
 
+    /// The primary selection as (start, end, text), if there is one
+    fn shell_selection(&mut self) -> Option<(usize, usize, String)> {
+        let (start, end) = {
+            let sel = self.active_state().cursors.primary().selection_range()?;
+            (sel.start.min(sel.end), sel.start.max(sel.end))
+        };
+        let text = self.active_state_mut().get_text_range(start, end);
+        Some((start, end, text))
+    }
+
     /// Handle shell command execution after prompt confirmation.
     /// If `replace` is true, replaces the selection/buffer with output.
     /// If `replace` is false, creates a new buffer with the output.
     pub fn handle_shell_command(&mut self, command: &str, replace: bool) {
-        // Capture selection range first
-        let selection_range = {
-            let state = self.active_state();
-            let primary = state.cursors.primary();
-            primary.selection_range().map(|sel| {
-                let start = sel.start.min(sel.end);
-                let end = sel.start.max(sel.end);
-                (start, end)
-            })
-        };
-
-        // Now get the deleted text if there's a selection
-        let selection_info = if let Some((start, end)) = selection_range {
-            let deleted_text = self.active_state_mut().get_text_range(start, end);
-            Some((start, end, deleted_text))
-        } else {
-            None
-        };
+        let selection_info = self.shell_selection();
         let has_selection = selection_info.is_some();
 
         match self.execute_shell_command(command) {
@@ -132,6 +130,94 @@ impl Editor {
         }
     }
 
+    /// Replace the selection (or the whole buffer) with the output of
+    /// `command`, run with it as stdin
+    ///
+    /// On failure the command's stderr is shown in a popup and the buffer is
+    /// left as it was.
+    pub fn filter_through_command(&mut self, command: &str) {
+        let command = command.trim();
+        if command.is_empty() {
+            return;
+        }
+        if self.active_state().editing_disabled {
+            let message = self.editing_disabled_message();
+            self.set_status_message(message);
+            return;
+        }
+
+        let selection_info = self.shell_selection();
+        let input = match &selection_info {
+            Some((_, _, text)) => text.clone(),
+            None => self.active_state().buffer.to_string().unwrap_or_default(),
+        };
+        let shell = self.command_shell();
+        let cwd = self
+            .active_state()
+            .buffer
+            .file_path()
+            .and_then(|path| path.parent())
+            .map(|dir| dir.to_string_lossy().to_string());
+
+        let result = match self.tokio_runtime.as_ref() {
+            Some(runtime) => runtime
+                .block_on(self.process_spawner.spawn_with_input(
+                    shell,
+                    vec!["-c".to_string(), command.to_string()],
+                    cwd,
+                    input.into_bytes(),
+                ))
+                .map_err(|e| e.to_string()),
+            None => Err("Async runtime not available".to_string()),
+        };
+        match result {
+            Ok(output) if output.exit_code == 0 => {
+                let has_selection = selection_info.is_some();
+                self.replace_with_shell_output(&output.stdout, has_selection, selection_info);
+            }
+            Ok(output) => self.show_filter_error(command, output.exit_code, &output.stderr),
+            Err(error) => {
+                self.set_status_message(t!("shell.command_failed", error = error).to_string())
+            }
+        }
+    }
+
+    /// Shell that runs commands through the process spawner
+    pub(super) fn command_shell(&self) -> String {
+        // $SHELL is the local user's shell and may not exist on a remote host
+        if self.filesystem.remote_connection_info().is_some() {
+            "sh".to_string()
+        } else {
+            detect_shell()
+        }
+    }
+
+    /// Show the stderr of a failed filter command in a popup
+    fn show_filter_error(&mut self, command: &str, exit_code: i32, stderr: &str) {
+        let mut lines: Vec<String> = stderr.trim_end().lines().map(str::to_string).collect();
+        if lines.is_empty() {
+            lines.push(t!("shell.no_error_output").to_string());
+        }
+        let max_height = (lines.len() as u16 + 2).min(20);
+        self.show_popup(PopupData {
+            title: Some(
+                t!(
+                    "shell.filter_failed",
+                    command = truncate_command(command, 30),
+                    code = exit_code
+                )
+                .to_string(),
+            ),
+            description: None,
+            transient: false,
+            content: PopupContentData::Text(lines),
+            position: PopupPositionData::Centered,
+            width: 80,
+            max_height,
+            bordered: true,
+        });
+    }
+
     /// Replace the current selection or buffer with shell output.
     fn replace_with_shell_output(
         &mut self,
@@ -250,6 +336,28 @@ impl Editor {
     /// This is used for commands like `sudo` where we might need to wait for completion.
     #[allow(dead_code)]
     pub(crate) fn run_shell_command_blocking(&mut self, command: &str) -> anyhow::Result<()> {
+        let status = self.with_terminal_released(|_| {
+            let shell = detect_shell();
+            let mut child = Command::new(&shell)
+                .args(["-c", command])
+                .spawn()
+                .map_err(|e| anyhow::anyhow!("Failed to spawn shell: {}", e))?;
+
+            child
+                .wait()
+                .map_err(|e| anyhow::anyhow!("Failed to wait for command: {}", e))
+        })?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            anyhow::bail!("Command failed with exit code: {:?}", status.code())
+        }
+    }
+
+    /// Hand the terminal to an external command (e.g. a sudo password
+    /// prompt) while `run` runs, then take it back.
+    pub(crate) fn with_terminal_released<T>(&mut self, run: impl FnOnce(&mut Self) -> T) -> T {
         use crossterm::terminal::{
             disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
         };
@@ -260,15 +368,7 @@ impl Editor {
         let _ = disable_raw_mode();
         let _ = stdout().execute(LeaveAlternateScreen);
 
-        let shell = detect_shell();
-        let mut child = Command::new(&shell)
-            .args(["-c", command])
-            .spawn()
-            .map_err(|e| anyhow::anyhow!("Failed to spawn shell: {}", e))?;
-
-        let status = child
-            .wait()
-            .map_err(|e| anyhow::anyhow!("Failed to wait for command: {}", e))?;
+        let result = run(self);
 
         // Resume TUI
         let _ = stdout().execute(EnterAlternateScreen);
@@ -277,11 +377,7 @@ impl Editor {
         // Request a full hard redraw to clear any ghost text from the external command
         self.request_full_redraw();
 
-        if status.success() {
-            Ok(())
-        } else {
-            anyhow::bail!("Command failed with exit code: {:?}", status.code())
-        }
+        result
     }
 }
 
@@ -10,6 +10,7 @@
 use rust_i18n::t;
 
 use crate::model::event::{BufferId, Event, SplitDirection, SplitId};
+use crate::view::scroll_sync::SyncAnchor;
 use crate::view::split::SplitViewState;
 
 use super::Editor;
@@ -22,6 +23,8 @@ impl Editor {
 
         // Share the current buffer with the new split (Emacs-style)
         let current_buffer_id = self.active_buffer();
+        // The new split starts with a copy of the jump list, as in Vim
+        let position_history = self.active_position_history().clone();
 
         // Split the pane
         match self.split_manager.split_active(
@@ -37,6 +40,7 @@ impl Editor {
                     current_buffer_id,
                 );
                 view_state.viewport.line_wrap_enabled = self.config.editor.line_wrap;
+                view_state.position_history = position_history;
                 self.split_view_states.insert(new_split_id, view_state);
                 // Restore the new split's view state to the buffer
                 self.restore_current_split_view_state();
@@ -55,6 +59,8 @@ impl Editor {
 
         // Share the current buffer with the new split (Emacs-style)
         let current_buffer_id = self.active_buffer();
+        // The new split starts with a copy of the jump list, as in Vim
+        let position_history = self.active_position_history().clone();
 
         // Split the pane
         match self.split_manager.split_active(
@@ -70,6 +76,7 @@ impl Editor {
                     current_buffer_id,
                 );
                 view_state.viewport.line_wrap_enabled = self.config.editor.line_wrap;
+                view_state.position_history = position_history;
                 self.split_view_states.insert(new_split_id, view_state);
                 // Restore the new split's view state to the buffer
                 self.restore_current_split_view_state();
@@ -96,6 +103,7 @@ impl Editor {
             Ok(_) => {
                 // Clean up the view state for the closed split
                 self.split_view_states.remove(&closing_split);
+                self.remove_scroll_lock(closing_split);
 
                 // Get the new active split after closing
                 let new_active_split = self.split_manager.active_split();
@@ -139,6 +147,7 @@ impl Editor {
 
     /// Common split switching logic
     fn switch_split(&mut self, next: bool) {
+        self.auto_save_on_focus_change(self.active_buffer());
         self.save_current_split_view_state();
         if next {
             self.split_manager.next_split();
@@ -311,6 +320,93 @@ impl Editor {
         }
     }
 
+    /// Lock or unlock scrolling of the active split together with the next
+    /// split. The two keep the distance between their top lines, so two
+    /// sections of one file can be compared side by side.
+    pub fn toggle_scroll_lock(&mut self) {
+        let active_split = self.split_manager.active_split();
+        if self.remove_scroll_lock(active_split) {
+            self.set_status_message(t!("split.scroll_unlocked").to_string());
+            return;
+        }
+        if self.scroll_sync_manager.is_split_synced(active_split) {
+            self.set_status_message(t!("split.scroll_lock_synced").to_string());
+            return;
+        }
+
+        // The split "Next Split" would go to, skipping terminals and splits
+        // that already scroll with another
+        let leaves = self.split_manager.root().leaf_split_ids();
+        let start = leaves
+            .iter()
+            .position(|id| *id == active_split)
+            .unwrap_or(0);
+        let other_split = leaves
+            .iter()
+            .cycle()
+            .skip(start + 1)
+            .take(leaves.len().saturating_sub(1))
+            .copied()
+            .find(|id| {
+                !self.scroll_sync_manager.is_split_synced(*id)
+                    && !self
+                        .split_manager
+                        .buffer_for_split(*id)
+                        .is_some_and(|buffer_id| self.is_terminal_buffer(buffer_id))
+            });
+        let tops = other_split.and_then(|other| {
+            let active_line = self.split_top_line(active_split)?;
+            Some((other, active_line, self.split_top_line(other)?))
+        });
+        let Some((other_split, active_line, other_line)) = tops else {
+            self.set_status_message(t!("split.scroll_lock_no_split").to_string());
+            return;
+        };
+
+        let group_id = self
+            .scroll_sync_manager
+            .create_group(active_split, other_split);
+        self.scroll_sync_manager
+            .set_anchors(group_id, vec![SyncAnchor::offset(active_line, other_line)]);
+        self.scroll_lock_groups.insert(group_id);
+        self.set_status_message(t!("split.scroll_locked").to_string());
+    }
+
+    /// Whether the active split is scroll-locked to another split
+    pub fn is_scroll_locked(&self) -> bool {
+        let active_split = self.split_manager.active_split();
+        self.scroll_sync_manager
+            .find_group_for_split(active_split)
+            .is_some_and(|group| self.scroll_lock_groups.contains(&group.id))
+    }
+
+    /// Remove the scroll lock `split_id` is part of, if any
+    pub(crate) fn remove_scroll_lock(&mut self, split_id: SplitId) -> bool {
+        let Some(group_id) = self
+            .scroll_sync_manager
+            .groups()
+            .iter()
+            .find(|g| g.contains_split(split_id) && self.scroll_lock_groups.contains(&g.id))
+            .map(|g| g.id)
+        else {
+            return false;
+        };
+        self.scroll_lock_groups.remove(&group_id);
+        self.scroll_sync_manager.remove_group(group_id)
+    }
+
+    /// Line number at the top of a split's viewport
+    fn split_top_line(&self, split_id: SplitId) -> Option<usize> {
+        let buffer_id = self.split_manager.buffer_for_split(split_id)?;
+        let top_byte = self.split_view_states.get(&split_id)?.viewport.top_byte;
+        Some(
+            self.buffers
+                .get(&buffer_id)?
+                .buffer
+                .get_line_number(top_byte),
+        )
+    }
+
     /// Get cached separator areas for testing
     /// Returns (split_id, direction, x, y, length) tuples
     pub fn get_separator_areas(&self) -> &[(SplitId, SplitDirection, u16, u16, u16)] {
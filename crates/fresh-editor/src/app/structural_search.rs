@@ -0,0 +1,270 @@
+//! Structural search and replace (**Structural Search**, **Structural Replace**)
+//!
+//! Finds code in the active buffer that matches a pattern with metavariables
+//! (see [`crate::primitives::structural_search`]) and lists the matches in a
+//! results buffer and the quickfix list. A replace shows every change as a
+//! diff and asks before applying them as one undo step. It is refused if the
+//! buffer changed since the preview, or if the result wouldn't parse.
+
+use rust_i18n::t;
+
+use super::quickfix::QuickfixItem;
+use super::render::replacement_events;
+use super::Editor;
+use crate::model::event::BufferId;
+use crate::primitives::search_replace::Replacement;
+use crate::primitives::structural_search::{
+    expand_template, parses_cleanly, PatternError, StructuralMatch, StructuralPattern,
+};
+use crate::primitives::text_property::TextPropertyEntry;
+use crate::view::prompt::PromptType;
+
+const RESULTS_BUFFER_NAME: &str = "*Structural Search*";
+
+/// A structural replace waiting for confirmation
+#[derive(Debug)]
+pub struct PendingStructuralReplace {
+    buffer_id: BufferId,
+    /// Buffer version the replacements were computed for
+    version: u64,
+    pattern: String,
+    template: String,
+    replacements: Vec<Replacement>,
+}
+
+/// 1-indexed line and column of byte `offset` in `text`
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count() + 1;
+    (line, before[line_start..].chars().count() + 1)
+}
+
+impl Editor {
+    /// Ask for a pattern to search for
+    pub fn start_structural_search_prompt(&mut self) {
+        self.start_prompt(
+            t!("structural.search_prompt").to_string(),
+            PromptType::StructuralSearch,
+        );
+    }
+
+    /// Ask for a pattern to replace, then for its replacement
+    pub fn start_structural_replace_prompt(&mut self) {
+        self.start_prompt(
+            t!("structural.replace_search_prompt").to_string(),
+            PromptType::StructuralReplaceSearch,
+        );
+    }
+
+    /// `pattern` parsed in the active buffer's language, or None after
+    /// saying why it can't be used
+    fn structural_pattern(&mut self, pattern: &str) -> Option<StructuralPattern> {
+        let result = match self.active_state().highlighter.language().copied() {
+            Some(language) => StructuralPattern::new(pattern, &language),
+            None => Err(PatternError::UnsupportedLanguage),
+        };
+        let error = match result {
+            Ok(pattern) => return Some(pattern),
+            Err(error) => error,
+        };
+        let message = match error {
+            PatternError::UnsupportedLanguage => t!("structural.no_grammar"),
+            PatternError::Syntax => t!("structural.pattern_syntax"),
+            PatternError::TooGeneral => t!("structural.pattern_too_general"),
+        };
+        self.set_status_message(message.to_string());
+        None
+    }
+
+    /// Matches of `pattern` in the active buffer and the buffer's text, or
+    /// None after reporting that there are none
+    fn structural_matches(
+        &mut self,
+        pattern: &str,
+    ) -> Option<(StructuralPattern, Vec<StructuralMatch>, String)> {
+        let compiled = self.structural_pattern(pattern)?;
+        let text = self.load_buffer_text_for_search()?;
+        let matches = compiled.find_all(&text);
+        if matches.is_empty() {
+            let message = t!("structural.no_matches", pattern = pattern).to_string();
+            self.set_status_message(message);
+            return None;
+        }
+        Some((compiled, matches, text))
+    }
+
+    /// List the matches of `pattern` in the active buffer
+    pub fn structural_search(&mut self, pattern: &str) {
+        let Some((_, matches, text)) = self.structural_matches(pattern) else {
+            return;
+        };
+        let buffer_id = self.active_buffer();
+        let (name, path) = self
+            .buffer_metadata
+            .get(&buffer_id)
+            .map(|meta| (meta.display_name.clone(), meta.file_path().cloned()))
+            .unwrap_or_default();
+
+        let mut content = t!(
+            "structural.search_header",
+            pattern = pattern,
+            count = matches.len(),
+            name = &name
+        )
+        .to_string();
+        content.push('\n');
+        let mut items = Vec::with_capacity(matches.len());
+        for m in &matches {
+            let (line, column) = line_column(&text, m.range.start);
+            content.push_str(&format!("\n{}:{}:{}\n", name, line, column));
+            for code in text[m.range.clone()].lines() {
+                content.push_str(&format!("    {}\n", code));
+            }
+            if let Some(path) = &path {
+                items.push(QuickfixItem {
+                    path: path.clone(),
+                    line,
+                    column,
+                    text: text[m.range.clone()]
+                        .lines()
+                        .next()
+                        .unwrap_or("")
+                        .to_string(),
+                });
+            }
+        }
+        if !items.is_empty() {
+            let title = t!("structural.quickfix_title", pattern = pattern).to_string();
+            self.set_quickfix_list(title, items, Some(0));
+        }
+        self.show_structural_results(content);
+        let message = t!("structural.found", count = matches.len()).to_string();
+        self.set_status_message(message);
+    }
+
+    /// Preview replacing the matches of `pattern` with `template`, then ask
+    /// whether to apply the replacements
+    pub fn structural_replace(&mut self, pattern: &str, template: &str) {
+        let Some((compiled, matches, text)) = self.structural_matches(pattern) else {
+            return;
+        };
+        let replacements: Vec<Replacement> = matches
+            .iter()
+            .map(|m| Replacement {
+                range: m.range.clone(),
+                text: expand_template(template, &text, m),
+            })
+            .collect();
+
+        let mut new_text = text.clone();
+        for r in replacements.iter().rev() {
+            new_text.replace_range(r.range.clone(), &r.text);
+        }
+        let language = compiled.language();
+        if parses_cleanly(&text, &language) && !parses_cleanly(&new_text, &language) {
+            self.set_status_message(t!("structural.breaks_syntax").to_string());
+            return;
+        }
+
+        let buffer_id = self.active_buffer();
+        let name = self
+            .buffer_metadata
+            .get(&buffer_id)
+            .map(|meta| meta.display_name.clone())
+            .unwrap_or_default();
+        let mut content = t!(
+            "structural.replace_header",
+            pattern = pattern,
+            template = template,
+            count = replacements.len(),
+            name = &name
+        )
+        .to_string();
+        content.push('\n');
+        for r in &replacements {
+            let (line, column) = line_column(&text, r.range.start);
+            content.push_str(&format!("\n@@ {}:{}:{} @@\n", name, line, column));
+            for old in text[r.range.clone()].lines() {
+                content.push_str(&format!("-{}\n", old));
+            }
+            for new in r.text.lines() {
+                content.push_str(&format!("+{}\n", new));
+            }
+        }
+
+        let count = replacements.len();
+        self.structural_replace = Some(PendingStructuralReplace {
+            buffer_id,
+            version: self.active_state().buffer.version(),
+            pattern: pattern.to_string(),
+            template: template.to_string(),
+            replacements,
+        });
+        self.show_structural_results(content);
+        self.start_prompt(
+            t!("structural.confirm_prompt", count = count).to_string(),
+            PromptType::ConfirmStructuralReplace,
+        );
+    }
+
+    /// Apply the previewed structural replace if the answer is yes
+    pub(super) fn structural_replace_confirmed(&mut self, input: &str) {
+        let Some(pending) = self.structural_replace.take() else {
+            return;
+        };
+        let answer = input.trim().to_lowercase();
+        if answer != "y" && answer != "yes" {
+            self.set_status_message(t!("structural.replace_cancelled").to_string());
+            return;
+        }
+        let unchanged = self
+            .buffers
+            .get(&pending.buffer_id)
+            .is_some_and(|state| state.buffer.version() == pending.version);
+        if !unchanged {
+            self.set_status_message(t!("structural.buffer_changed").to_string());
+            return;
+        }
+
+        self.set_active_buffer(pending.buffer_id);
+        let Some(text) = self.load_buffer_text_for_search() else {
+            return;
+        };
+        let cursor_id = self.active_state().cursors.primary_id();
+        let events = replacement_events(&text, &pending.replacements, cursor_id);
+        let description = format!(
+            "Structural replace '{}' with '{}'",
+            pending.pattern, pending.template
+        );
+        if let Some(bulk_edit) = self.apply_events_as_bulk_edit(events, description) {
+            self.active_event_log_mut().append(bulk_edit);
+        }
+        let count = pending.replacements.len();
+        self.set_status_message(t!("structural.replaced", count = count).to_string());
+    }
+
+    /// Show `content` in the structural search results buffer
+    fn show_structural_results(&mut self, content: String) {
+        let existing_buffer = self
+            .buffer_metadata
+            .iter()
+            .find(|(_, m)| m.display_name == RESULTS_BUFFER_NAME)
+            .map(|(id, _)| *id);
+        let buffer_id = existing_buffer.unwrap_or_else(|| {
+            self.create_virtual_buffer(RESULTS_BUFFER_NAME.to_string(), "special".to_string(), true)
+        });
+        if let Err(e) =
+            self.set_virtual_buffer_content(buffer_id, vec![TextPropertyEntry::text(content)])
+        {
+            tracing::warn!("Failed to fill structural search results: {}", e);
+            return;
+        }
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.set_language_from_name("structural.diff", &self.grammar_registry);
+            state.editing_disabled = true;
+            state.margins.set_line_numbers(false);
+        }
+        self.set_active_buffer(buffer_id);
+    }
+}
@@ -0,0 +1,90 @@
+//! Saving files the user has no permission to write
+//!
+//! When a save fails with permission denied, the buffer is kept in a temp
+//! file and the user is asked whether to save with elevated rights. On yes,
+//! the temp file's content is piped through `sudo tee` (or `pkexec tee` where
+//! sudo isn't installed) by the buffer's [`FileSystem::sudo_write`], so
+//! remote buffers run `sudo` on their host over the SSH channel. A file that
+//! didn't exist yet gets the mode and owner recorded in [`SudoSaveRequired`].
+//!
+//! Locally the terminal is handed over while the command runs, so sudo can
+//! ask for a password, and the screen is fully redrawn afterwards. Remote
+//! sudo has no terminal to ask on and needs cached or password-less rights.
+//!
+//! [`FileSystem::sudo_write`]: crate::model::filesystem::FileSystem::sudo_write
+
+use rust_i18n::t;
+
+use super::on_save_actions::command_exists;
+use super::Editor;
+use crate::model::buffer::SudoSaveRequired;
+use crate::model::event::BufferId;
+
+/// Program used to gain elevated rights
+pub(super) fn elevation_program(remote: bool) -> &'static str {
+    // pkexec needs a polkit agent, which an SSH session doesn't have
+    if !remote && !command_exists("sudo") && command_exists("pkexec") {
+        "pkexec"
+    } else {
+        "sudo"
+    }
+}
+
+impl Editor {
+    /// Answer to the "save with sudo?" prompt for `buffer_id`
+    pub(super) fn confirm_sudo_save(
+        &mut self,
+        buffer_id: BufferId,
+        info: SudoSaveRequired,
+        input: &str,
+    ) {
+        let filesystem = self.buffer_filesystem(buffer_id);
+        let input_lower = input.trim().to_lowercase();
+        if input_lower != "y" && input_lower != "yes" {
+            self.set_status_message(t!("buffer.save_cancelled").to_string());
+            let _ = filesystem.remove_file(&info.temp_path);
+            return;
+        }
+
+        let remote = filesystem.remote_connection_info().is_some();
+        let program = elevation_program(remote);
+        let write = || -> anyhow::Result<()> {
+            let data = filesystem.read_file(&info.temp_path)?;
+            filesystem.sudo_write(
+                program,
+                &info.dest_path,
+                &data,
+                info.mode,
+                info.uid,
+                info.gid,
+            )?;
+            Ok(())
+        };
+        let result = if remote {
+            write()
+        } else {
+            self.with_terminal_released(|_| write())
+        };
+        let _ = filesystem.remove_file(&info.temp_path);
+
+        let result = result.and_then(|()| {
+            if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                state
+                    .buffer
+                    .finalize_external_save(info.dest_path.clone())?;
+            }
+            self.finalize_buffer_save(buffer_id, Some(info.dest_path.clone()), true)
+        });
+        if let Err(e) = result {
+            tracing::warn!("Save with {} failed: {}", program, e);
+            self.set_status_message(
+                t!(
+                    "prompt.sudo_save_failed",
+                    program = program,
+                    error = e.to_string()
+                )
+                .to_string(),
+            );
+        }
+    }
+}
@@ -233,6 +233,8 @@ impl Editor {
                 };
                 let final_idx = adjusted_idx.min(view_state.open_buffers.len());
                 view_state.open_buffers.insert(final_idx, buffer_id);
+                // Tabs can't be dragged across the pinned/unpinned boundary
+                view_state.normalize_tab_order();
             }
         }
     }
@@ -254,7 +256,7 @@ impl Editor {
 
         // Remove from source split's tab bar
         if let Some(source_view_state) = self.split_view_states.get_mut(&source_split_id) {
-            source_view_state.open_buffers.retain(|&id| id != buffer_id);
+            source_view_state.remove_buffer(buffer_id);
 
             // If the source split was showing this buffer, switch to another
             if self.split_manager.get_buffer_id(source_split_id) == Some(buffer_id) {
@@ -273,6 +275,7 @@ impl Editor {
                 let idx = insert_idx.unwrap_or(target_view_state.open_buffers.len());
                 let final_idx = idx.min(target_view_state.open_buffers.len());
                 target_view_state.open_buffers.insert(final_idx, buffer_id);
+                target_view_state.normalize_tab_order();
             }
         }
 
@@ -286,6 +289,7 @@ impl Editor {
         // If source split is now empty, close it
         if source_becomes_empty {
             self.split_view_states.remove(&source_split_id);
+            self.remove_scroll_lock(source_split_id);
             let _ = self.split_manager.close_split(source_split_id);
             self.set_status_message(t!("status.moved_tab_split_closed").to_string());
         } else {
@@ -300,7 +304,7 @@ impl Editor {
         source_split_id: SplitId,
         target_split_id: SplitId,
         direction: SplitDirection,
-        _new_split_first: bool, // If true, new split is placed first (left/top) - TODO: implement
+        new_split_first: bool,
     ) {
         // Check if source split will be empty after removing this buffer
         let source_becomes_empty = self
@@ -313,7 +317,7 @@ impl Editor {
         let source_had_buffer =
             if let Some(source_view_state) = self.split_view_states.get_mut(&source_split_id) {
                 let had = source_view_state.open_buffers.contains(&buffer_id);
-                source_view_state.open_buffers.retain(|&id| id != buffer_id);
+                source_view_state.remove_buffer(buffer_id);
 
                 // If the source split was showing this buffer, switch to another
                 if self.split_manager.get_buffer_id(source_split_id) == Some(buffer_id) {
@@ -341,7 +345,10 @@ impl Editor {
         let ratio = 0.5;
 
         // Create the split
-        match self.split_manager.split_active(direction, buffer_id, ratio) {
+        match self
+            .split_manager
+            .split_active_placed(direction, buffer_id, ratio, new_split_first)
+        {
             Ok(new_split_id) => {
                 // Initialize the new split's view state
                 let (width, height) = (self.terminal_width, self.terminal_height);
@@ -355,14 +362,10 @@ impl Editor {
 
                 self.split_view_states.insert(new_split_id, new_view_state);
 
-                // If new_split_first is true, we need to swap the children
-                // This requires modifying the split manager's tree structure
-                // For now, we accept the default behavior (new split is second)
-                // TODO: Implement swap_split_children for new_split_first=true
-
                 // If source split is now empty, close it
                 if source_becomes_empty {
                     self.split_view_states.remove(&source_split_id);
+                    self.remove_scroll_lock(source_split_id);
                     let _ = self.split_manager.close_split(source_split_id);
                 }
 
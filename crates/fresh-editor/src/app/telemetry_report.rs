@@ -0,0 +1,117 @@
+//! **Telemetry: Show Collected Data**
+//!
+//! Shows the telemetry settings in effect and the exact events the next daily
+//! check would send, followed by anything already written in local-only mode.
+
+use super::Editor;
+use crate::config::TelemetryLevel;
+use crate::primitives::text_property::TextPropertyEntry;
+use crate::services::telemetry;
+
+/// Name of the report buffer
+const TELEMETRY_REPORT_BUFFER_NAME: &str = "*Telemetry Data*";
+
+/// Most recent local-only events shown
+const LOCAL_EVENTS_SHOWN: usize = 20;
+
+impl Editor {
+    /// Describe what telemetry is collected and where it goes
+    fn telemetry_report(&self) -> String {
+        let config = &self.config.telemetry;
+        let data_dir = &self.dir_context.data_dir;
+        let local_path = telemetry::local_events_path(data_dir);
+
+        let mut content = String::from("Telemetry Data\n");
+        content.push_str("==============\n\n");
+        content.push_str("Exactly what Fresh reports with its daily update check.\n");
+        content.push_str("Press 'q' to close this buffer.\n\n");
+
+        let level = match config.level {
+            TelemetryLevel::None => "none",
+            TelemetryLevel::Crashes => "crashes",
+            TelemetryLevel::Features => "features",
+        };
+        let destination = if config.local_only {
+            format!("written to {} (local only)", local_path.display())
+        } else {
+            format!("sent to {}", telemetry::TELEMETRY_URL)
+        };
+        let update_checks = if self.config.check_for_updates {
+            "on"
+        } else {
+            "off"
+        };
+        let rows = [
+            ("Update checks", update_checks.to_string()),
+            ("Level", level.to_string()),
+            ("Events are", destination),
+        ];
+        for (label, value) in rows {
+            content.push_str(&format!("  {:16} {}\n", label, value));
+        }
+
+        content.push_str("\n── Next daily check ──\n\n");
+        let unique_id = telemetry::unique_id(data_dir)
+            .unwrap_or_else(|| "(created at the first check)".to_string());
+        let events = telemetry::pending_events(config, data_dir, &unique_id);
+        if !self.config.check_for_updates {
+            content.push_str("  Nothing: telemetry is only sent with update checks.\n");
+        } else if events.is_empty() {
+            content.push_str("  Nothing.\n");
+        } else {
+            for event in &events {
+                if let Ok(json) = serde_json::to_string_pretty(event) {
+                    content.push_str(&json);
+                    content.push('\n');
+                }
+            }
+        }
+
+        if let Ok(local) = std::fs::read_to_string(&local_path) {
+            let lines: Vec<&str> = local.lines().collect();
+            content.push_str(&format!(
+                "\n── Written locally ({} events) ──\n\n",
+                lines.len()
+            ));
+            for line in lines
+                .iter()
+                .skip(lines.len().saturating_sub(LOCAL_EVENTS_SHOWN))
+            {
+                content.push_str(line);
+                content.push('\n');
+            }
+        }
+        content
+    }
+
+    /// Open a buffer showing the telemetry that would be collected
+    pub fn show_telemetry_data(&mut self) {
+        let content = self.telemetry_report();
+
+        // Refresh an open report rather than opening a second one
+        let existing_buffer = self
+            .buffer_metadata
+            .iter()
+            .find(|(_, m)| m.display_name == TELEMETRY_REPORT_BUFFER_NAME)
+            .map(|(id, _)| *id);
+        let buffer_id = existing_buffer.unwrap_or_else(|| {
+            self.create_virtual_buffer(
+                TELEMETRY_REPORT_BUFFER_NAME.to_string(),
+                "special".to_string(),
+                true,
+            )
+        });
+
+        let entries = vec![TextPropertyEntry::text(content)];
+        if let Err(e) = self.set_virtual_buffer_content(buffer_id, entries) {
+            tracing::warn!("Failed to fill telemetry report: {}", e);
+            return;
+        }
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.editing_disabled = true;
+            state.margins.set_line_numbers(false);
+        }
+
+        self.set_active_buffer(buffer_id);
+    }
+}
@@ -60,7 +60,7 @@ impl Editor {
         match self.terminal_manager.spawn(
             cols,
             rows,
-            Some(self.working_dir.clone()),
+            Some(self.shell_or_working_dir()),
             Some(log_path.clone()),
             backing_path_for_spawn,
             None,                               // No initial command for regular terminal open
@@ -188,7 +188,7 @@ impl Editor {
         match self.terminal_manager.spawn(
             cols,
             rows,
-            Some(self.working_dir.clone()),
+            Some(self.shell_or_working_dir()),
             Some(log_path.clone()),
             Some(backing_path.clone()),
             Some(initial_command),
@@ -1,16 +1,19 @@
 //! Toggle actions and configuration operations for the Editor.
 //!
 //! This module contains toggle methods and configuration operations:
-//! - Toggle line numbers, debug highlights, menu bar
+//! - Toggle line numbers, debug highlights, render profiler, menu bar
 //! - Toggle mouse capture, mouse hover, inlay hints
 //! - Reset buffer settings
 //! - Config dump, save, and reload
 
 use rust_i18n::t;
 
-use crate::config::Config;
+use crate::config::{Appearance, Config};
 use crate::config_io::{ConfigLayer, ConfigResolver};
 use crate::input::keybindings::KeybindingResolver;
+use crate::services::appearance::{AppearanceWatcher, OS_POLL_INTERVAL};
+use crate::view::color_support::quantize_theme;
+use crate::view::theme::ThemeLoader;
 
 use super::Editor;
 
@@ -44,6 +47,15 @@ impl Editor {
         }
     }
 
+    /// Toggle the render profiler overlay (per-frame timings by phase)
+    pub fn toggle_render_profiler(&mut self) {
+        if self.render_profiler.toggle_overlay() {
+            self.set_status_message(t!("toggle.render_profiler_on").to_string());
+        } else {
+            self.set_status_message(t!("toggle.render_profiler_off").to_string());
+        }
+    }
+
     /// Toggle menu bar visibility
     pub fn toggle_menu_bar(&mut self) {
         self.menu_bar_visible = !self.menu_bar_visible;
@@ -81,29 +93,8 @@ impl Editor {
     pub fn reset_buffer_settings(&mut self) {
         let buffer_id = self.active_buffer();
 
-        // Determine settings from config using buffer's stored language
-        let (tab_size, use_tabs, show_whitespace_tabs) =
-            if let Some(state) = self.buffers.get(&buffer_id) {
-                let language = &state.language;
-                if let Some(lang_config) = self.config.languages.get(language) {
-                    (
-                        lang_config.tab_size.unwrap_or(self.config.editor.tab_size),
-                        lang_config.use_tabs,
-                        lang_config.show_whitespace_tabs,
-                    )
-                } else {
-                    (self.config.editor.tab_size, false, true)
-                }
-            } else {
-                (self.config.editor.tab_size, false, true)
-            };
-
-        // Apply settings to buffer
-        if let Some(state) = self.buffers.get_mut(&buffer_id) {
-            state.tab_size = tab_size;
-            state.use_tabs = use_tabs;
-            state.show_whitespace_tabs = show_whitespace_tabs;
-        }
+        // Re-resolve settings from config using buffer's stored language and path
+        self.apply_buffer_config(buffer_id);
 
         self.set_status_message(t!("toggle.buffer_settings_reset").to_string());
     }
@@ -156,6 +147,14 @@ impl Editor {
         self.gpm_active = active;
     }
 
+    /// Record whether kitty keyboard protocol flags were pushed at startup
+    ///
+    /// The calibration wizard reports this, since with the protocol active
+    /// modified keys arrive unambiguously and rarely need mapping.
+    pub fn set_keyboard_enhancement_active(&mut self, active: bool) {
+        self.keyboard_enhancement_active = active;
+    }
+
     /// Toggle inlay hints visibility
     pub fn toggle_inlay_hints(&mut self) {
         self.config.editor.enable_inlay_hints = !self.config.editor.enable_inlay_hints;
@@ -173,6 +172,22 @@ impl Editor {
         }
     }
 
+    /// Cycle which spaces are drawn: none, boundary, selection, trailing, all
+    pub fn cycle_render_whitespace(&mut self) {
+        let mode = self.config.editor.render_whitespace.next();
+        self.config.editor.render_whitespace = mode;
+        self.apply_render_whitespace();
+        self.set_status_message(t!("toggle.render_whitespace", mode = mode.as_str()).to_string());
+    }
+
+    /// Apply the `render_whitespace` setting to every buffer
+    pub(super) fn apply_render_whitespace(&mut self) {
+        let mode = self.config.editor.render_whitespace;
+        for state in self.buffers.values_mut() {
+            state.render_whitespace = mode;
+        }
+    }
+
     /// Dump the current configuration to the user's config file
     pub fn dump_config(&mut self) {
         // Create the config directory if it doesn't exist
@@ -242,18 +257,18 @@ impl Editor {
     pub fn reload_config(&mut self) {
         let old_theme = self.config.theme.clone();
         self.config = Config::load_with_layers(&self.dir_context, &self.working_dir);
+        self.refresh_config_issues();
+        crate::primitives::display_width::set_ambiguous_wide(
+            self.config.editor.ambiguous_width == crate::config::AmbiguousWidth::Wide,
+        );
+        self.apply_render_whitespace();
 
         // Refresh cached raw user config for plugins
         self.user_config_raw = Config::read_user_config_raw(&self.working_dir);
 
         // Apply theme change if needed
         if old_theme != self.config.theme {
-            if let Some(theme) = self.theme_registry.get_cloned(&self.config.theme) {
-                self.theme = theme;
-                tracing::info!("Theme changed to '{}'", self.config.theme.0);
-            } else {
-                tracing::error!("Theme '{}' not found", self.config.theme.0);
-            }
+            self.apply_configured_theme();
         }
 
         // Always reload keybindings (complex types don't implement PartialEq)
@@ -264,6 +279,9 @@ impl Editor {
             for (language, lsp_config) in &self.config.lsp {
                 lsp.set_language_config(language.clone(), lsp_config.clone());
             }
+            for (language, lang_config) in &self.config.languages {
+                lsp.set_additional_servers(language, lang_config.lsp_servers.clone());
+            }
         }
 
         // Emit event so plugins know config changed
@@ -276,18 +294,98 @@ impl Editor {
         );
     }
 
+    /// Switch to the theme the `theme` setting picks for the current appearance
+    ///
+    /// Returns false, keeping the current theme, when that theme doesn't exist.
+    pub(super) fn apply_configured_theme(&mut self) -> bool {
+        let name = self.config.theme.resolve(self.appearance).clone();
+        match self.load_theme(&name) {
+            Some(theme) => {
+                self.theme = theme;
+                tracing::info!("Theme changed to '{}'", name.0);
+                true
+            }
+            None => {
+                tracing::error!("Theme '{}' not found", name.0);
+                false
+            }
+        }
+    }
+
+    /// Set whether the terminal/OS is light or dark
+    ///
+    /// Switches themes right away when `theme` is a light/dark pair.
+    pub fn set_appearance(&mut self, appearance: Appearance) {
+        if appearance == self.appearance {
+            return;
+        }
+        self.appearance = appearance;
+        if self.config.theme.follows_appearance() && self.apply_configured_theme() {
+            self.theme.set_terminal_cursor_color();
+        }
+    }
+
+    /// Keep following the OS dark-mode setting while the editor runs
+    pub fn watch_appearance(&mut self) {
+        self.appearance_watcher = Some(AppearanceWatcher::spawn(OS_POLL_INTERVAL));
+    }
+
+    /// Apply OS dark-mode changes reported by the appearance watcher
+    ///
+    /// Returns true when the theme changed.
+    pub(crate) fn poll_appearance(&mut self) -> bool {
+        let Some(appearance) = self.appearance_watcher.as_ref().and_then(|w| w.poll()) else {
+            return false;
+        };
+        let old_theme = self.theme.name.clone();
+        self.set_appearance(appearance);
+        self.theme.name != old_theme
+    }
+
+    /// Reload themes when a file in the user themes directory changes
+    ///
+    /// Checked at most every `auto_revert_poll_interval_ms`. Returns true if
+    /// themes were reloaded (requires re-render).
+    pub(crate) fn poll_theme_files(&mut self) -> bool {
+        let poll_interval =
+            std::time::Duration::from_millis(self.config.editor.auto_revert_poll_interval_ms);
+        if let Some(last_poll) = self.last_theme_file_poll {
+            if self.time_source.elapsed_since(last_poll) < poll_interval {
+                return false;
+            }
+        }
+        self.last_theme_file_poll = Some(self.time_source.now());
+
+        let stamps = ThemeLoader::new(self.dir_context.themes_dir()).user_theme_file_stamps();
+        let changed = self
+            .theme_file_stamps
+            .as_ref()
+            .is_some_and(|previous| *previous != stamps);
+        self.theme_file_stamps = Some(stamps);
+        if changed {
+            tracing::info!("Theme files changed on disk, reloading themes");
+            self.reload_themes();
+        }
+        changed
+    }
+
+    /// Look up a theme in the registry, quantized for the terminal's colors
+    pub(super) fn load_theme(&self, name: &str) -> Option<crate::view::theme::Theme> {
+        self.theme_registry
+            .get_cloned(name)
+            .map(|theme| quantize_theme(&theme, self.color_capability))
+    }
+
     /// Reload the theme registry from disk.
     ///
     /// Call this after installing new theme packages or saving new themes.
     /// This rescans all theme directories and updates the available themes list.
     pub fn reload_themes(&mut self) {
-        use crate::view::theme::ThemeLoader;
-
         let theme_loader = ThemeLoader::new(self.dir_context.themes_dir());
         self.theme_registry = theme_loader.load_all();
 
         // Re-apply current theme if it still exists, otherwise it might have been updated
-        if let Some(theme) = self.theme_registry.get_cloned(&self.config.theme) {
+        if let Some(theme) = self.load_theme(self.config.theme.resolve(self.appearance)) {
             self.theme = theme;
         }
 
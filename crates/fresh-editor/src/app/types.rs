@@ -1,8 +1,11 @@
 use crate::app::file_open::SortMode;
 use crate::input::keybindings::Action;
 use crate::model::event::{BufferId, SplitDirection, SplitId};
+use crate::primitives::search_replace::{Replacement, Replacer};
 use crate::services::async_bridge::LspMessageType;
+use crate::view::animation::Animation;
 use ratatui::layout::Rect;
+use regex::Regex;
 use rust_i18n::t;
 use std::collections::{HashMap, HashSet};
 use std::ops::Range;
@@ -53,8 +56,12 @@ pub(super) struct InteractiveReplaceState {
     pub search: String,
     /// The replacement text
     pub replacement: String,
-    /// Current match position (byte offset of the match we're at)
-    pub current_match_pos: usize,
+    /// Regex built from the search pattern and search options
+    pub regex: Regex,
+    /// Expands the replacement text for each match
+    pub replacer: Replacer,
+    /// The match we're at and the text that would replace it
+    pub current: Replacement,
     /// Starting position (to detect when we've wrapped around full circle)
     pub start_pos: usize,
     /// Whether we've wrapped around to the beginning
@@ -78,6 +85,9 @@ pub enum BufferKind {
     Virtual {
         /// The buffer's mode (e.g., "diagnostics-list", "grep-results")
         mode: String,
+        /// URI in a plugin-registered scheme that the buffer stands for
+        /// (e.g., "pr://owner/repo/123/src/main.rs")
+        uri: Option<String>,
     },
 }
 
@@ -99,6 +109,9 @@ pub struct BufferMetadata {
     /// Whether the buffer is read-only (typically true for virtual buffers)
     pub read_only: bool,
 
+    /// Why a file buffer was opened read-only (if it was done automatically)
+    pub read_only_reason: Option<String>,
+
     /// Whether the buffer contains binary content
     /// Binary buffers are automatically read-only and render unprintable chars as code points
     pub binary: bool,
@@ -125,6 +138,10 @@ pub struct BufferMetadata {
 
     /// The file being edited by the external editor
     pub external_editor_file: Option<PathBuf>,
+
+    /// Whether this is the preview tab, replaced by the next file previewed
+    /// from the file explorer
+    pub preview: bool,
 }
 
 impl BufferMetadata {
@@ -149,10 +166,25 @@ impl BufferMetadata {
         matches!(self.kind, BufferKind::Virtual { .. })
     }
 
+    /// Check if this is a file buffer made read-only (automatically, by
+    /// `fresh -R` or by Toggle Read-Only), rather than a binary file or a
+    /// virtual buffer that can't be edited at all
+    pub fn is_read_only_file(&self) -> bool {
+        self.read_only && !self.binary && !self.is_virtual()
+    }
+
     /// Get the mode name for virtual buffers
     pub fn virtual_mode(&self) -> Option<&str> {
         match &self.kind {
-            BufferKind::Virtual { mode } => Some(mode),
+            BufferKind::Virtual { mode, .. } => Some(mode),
+            BufferKind::File { .. } => None,
+        }
+    }
+
+    /// Get the plugin URI of a virtual buffer, if it was given one
+    pub fn virtual_uri(&self) -> Option<&str> {
+        match &self.kind {
+            BufferKind::Virtual { uri, .. } => uri.as_deref(),
             BufferKind::File { .. } => None,
         }
     }
@@ -176,6 +208,7 @@ impl BufferMetadata {
             lsp_enabled: true,
             lsp_disabled_reason: None,
             read_only: false,
+            read_only_reason: None,
             binary: false,
             lsp_opened_with: HashSet::new(),
             hidden_from_tabs: false,
@@ -183,6 +216,7 @@ impl BufferMetadata {
             is_external_editor: false,
             external_editor_command: None,
             external_editor_file: None,
+            preview: false,
         }
     }
 
@@ -198,6 +232,7 @@ impl BufferMetadata {
             lsp_enabled: false, // No file path, so no LSP
             lsp_disabled_reason: Some(t!("lsp.disabled.unnamed").to_string()),
             read_only: false,
+            read_only_reason: None,
             binary: false,
             lsp_opened_with: HashSet::new(),
             hidden_from_tabs: false,
@@ -205,6 +240,7 @@ impl BufferMetadata {
             is_external_editor: false,
             external_editor_command: None,
             external_editor_file: None,
+            preview: false,
         }
     }
 
@@ -239,6 +275,7 @@ impl BufferMetadata {
             lsp_enabled,
             lsp_disabled_reason,
             read_only: false,
+            read_only_reason: None,
             binary: false,
             lsp_opened_with: HashSet::new(),
             hidden_from_tabs: false,
@@ -246,6 +283,7 @@ impl BufferMetadata {
             is_external_editor: false,
             external_editor_command: None,
             external_editor_file: None,
+            preview: false,
         }
     }
 
@@ -343,11 +381,12 @@ impl BufferMetadata {
     /// * `read_only` - Whether the buffer should be read-only
     pub fn virtual_buffer(name: String, mode: String, read_only: bool) -> Self {
         Self {
-            kind: BufferKind::Virtual { mode },
+            kind: BufferKind::Virtual { mode, uri: None },
             display_name: name,
             lsp_enabled: false, // Virtual buffers don't use LSP
             lsp_disabled_reason: Some(t!("lsp.disabled.virtual").to_string()),
             read_only,
+            read_only_reason: None,
             binary: false,
             lsp_opened_with: HashSet::new(),
             hidden_from_tabs: false,
@@ -355,6 +394,7 @@ impl BufferMetadata {
             is_external_editor: false,
             external_editor_command: None,
             external_editor_file: None,
+            preview: false,
         }
     }
 
@@ -363,11 +403,12 @@ impl BufferMetadata {
     /// Hidden buffers are always read-only to prevent accidental edits.
     pub fn hidden_virtual_buffer(name: String, mode: String) -> Self {
         Self {
-            kind: BufferKind::Virtual { mode },
+            kind: BufferKind::Virtual { mode, uri: None },
             display_name: name,
             lsp_enabled: false,
             lsp_disabled_reason: Some(t!("lsp.disabled.virtual").to_string()),
             read_only: true, // Hidden buffers are always read-only
+            read_only_reason: None,
             binary: false,
             lsp_opened_with: HashSet::new(),
             hidden_from_tabs: true,
@@ -375,6 +416,7 @@ impl BufferMetadata {
             is_external_editor: false,
             external_editor_command: None,
             external_editor_file: None,
+            preview: false,
         }
     }
 
@@ -403,6 +445,113 @@ pub(super) struct LspProgressInfo {
     pub percentage: Option<u32>,
 }
 
+/// Background task started by a plugin with `editor.runTask`
+#[derive(Debug, Clone)]
+pub(super) struct PluginTask {
+    pub id: fresh_core::api::JsCallbackId,
+    pub plugin_name: String,
+    pub name: String,
+    pub message: Option<String>,
+    pub percentage: Option<u32>,
+    pub started_at: std::time::Instant,
+}
+
+/// File being read on a worker thread (see `background_io`)
+#[derive(Debug, Clone)]
+pub(super) struct BackgroundOpen {
+    pub id: u64,
+    pub path: PathBuf,
+    pub size: u64,
+    pub started_at: std::time::Instant,
+}
+
+/// Buffer being written on a worker thread (see `background_io`)
+#[derive(Debug, Clone)]
+pub(super) struct BackgroundSave {
+    pub buffer_id: BufferId,
+    pub path: PathBuf,
+    pub size: u64,
+    /// Buffer version being written
+    pub version: u64,
+    /// Movement and editing actions held back until the write finishes
+    pub queued_actions: Vec<Action>,
+    pub started_at: std::time::Instant,
+}
+
+/// Command re-run on every save (see `watch_task`)
+#[derive(Debug, Clone, Default)]
+pub(super) struct WatchTask {
+    pub command: String,
+    /// Id of the run in progress, if any
+    pub running: Option<u64>,
+    /// A file was saved while a run was in progress
+    pub rerun: bool,
+    /// Outcome of the last finished run
+    pub last: Option<WatchOutcome>,
+    /// Output of the last finished run
+    pub output: String,
+    /// URIs of the files the last run reported problems in
+    pub problem_uris: Vec<String>,
+}
+
+/// How the last run of the watch task went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum WatchOutcome {
+    Passed,
+    /// The command failed or reported errors
+    Failed {
+        problems: usize,
+    },
+}
+
+/// Smooth scroll of a split's viewport towards the target of a page jump
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ScrollAnimation {
+    pub split: SplitId,
+    pub buffer: BufferId,
+    pub from_line: usize,
+    pub to_line: usize,
+    /// Exact viewport position restored when the animation ends
+    pub target_top_byte: usize,
+    pub target_top_view_line_offset: usize,
+    pub animation: Animation,
+}
+
+/// Highlight fading out on the cursor line after a long jump
+#[derive(Debug, Clone, Copy)]
+pub(super) struct CursorFlash {
+    pub split: SplitId,
+    pub position: usize,
+    pub animation: Animation,
+}
+
+/// File explorer sliding open or collapsing
+#[derive(Debug, Clone, Copy)]
+pub(super) struct PanelAnimation {
+    pub opening: bool,
+    pub animation: Animation,
+}
+
+/// Animations currently running (`editor.animations`)
+#[derive(Debug, Clone, Default)]
+pub(super) struct ActiveAnimations {
+    pub scroll: Option<ScrollAnimation>,
+    pub cursor_flash: Option<CursorFlash>,
+    pub file_explorer: Option<PanelAnimation>,
+}
+
+/// Plugin whose source files are watched for hot reload
+#[cfg(feature = "plugins")]
+#[derive(Debug, Clone)]
+pub(super) struct WatchedPlugin {
+    /// Plugin entry file
+    pub path: std::path::PathBuf,
+    /// Source files with the modification time seen when the plugin was loaded
+    pub sources: Vec<(std::path::PathBuf, Option<std::time::SystemTime>)>,
+    /// False when the last reload failed; the plugin is loaded again once fixed
+    pub loaded: bool,
+}
+
 /// LSP message entry (for window messages and logs)
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -493,6 +642,10 @@ pub enum TabContextMenuItem {
     CloseToLeft,
     /// Close all tabs
     CloseAll,
+    /// Pin or unpin this tab
+    TogglePin,
+    /// Choose the tab group of this tab
+    SetGroup,
 }
 
 impl TabContextMenuItem {
@@ -504,6 +657,8 @@ impl TabContextMenuItem {
             Self::CloseToRight,
             Self::CloseToLeft,
             Self::CloseAll,
+            Self::TogglePin,
+            Self::SetGroup,
         ]
     }
 
@@ -515,6 +670,22 @@ impl TabContextMenuItem {
             Self::CloseToRight => t!("tab.close_to_right").to_string(),
             Self::CloseToLeft => t!("tab.close_to_left").to_string(),
             Self::CloseAll => t!("tab.close_all").to_string(),
+            Self::TogglePin => t!("tab.toggle_pin").to_string(),
+            Self::SetGroup => t!("tab.set_group").to_string(),
+        }
+    }
+
+    /// The action doing the same for the active tab, so every item can be
+    /// reached from the keyboard
+    pub fn action(&self) -> Action {
+        match self {
+            Self::Close => Action::CloseTab,
+            Self::CloseOthers => Action::CloseOtherTabs,
+            Self::CloseToRight => Action::CloseTabsToRight,
+            Self::CloseToLeft => Action::CloseTabsToLeft,
+            Self::CloseAll => Action::CloseAllTabs,
+            Self::TogglePin => Action::TogglePinTab,
+            Self::SetGroup => Action::SetTabGroup,
         }
     }
 }
@@ -1,13 +1,12 @@
 //! Undo and redo action handlers.
 
 use super::Editor;
-use rust_i18n::t;
 
 impl Editor {
     /// Handle Undo action - revert the last edit operation.
     pub fn handle_undo(&mut self) {
         if self.is_editing_disabled() {
-            self.set_status_message(t!("buffer.editing_disabled").to_string());
+            self.set_status_message(self.editing_disabled_message());
             return;
         }
 
@@ -38,7 +37,7 @@ impl Editor {
     /// Handle Redo action - reapply an undone edit operation.
     pub fn handle_redo(&mut self) {
         if self.is_editing_disabled() {
-            self.set_status_message(t!("buffer.editing_disabled").to_string());
+            self.set_status_message(self.editing_disabled_message());
             return;
         }
 
@@ -1,6 +1,6 @@
 //! View mode action handlers.
 //!
-//! This module contains handlers for view-related actions like compose mode toggling.
+//! This module contains handlers for view-related actions like compose and focus mode toggling.
 
 use super::Editor;
 use crate::state::ViewMode;
@@ -46,6 +46,9 @@ impl Editor {
                     // Clear compose width to remove margins
                     vs.compose_width = None;
                     vs.view_transform = None;
+                    // Focus mode is built on compose mode, so it ends too
+                    vs.focus_mode = false;
+                    vs.viewport.typewriter_scrolling = false;
                     let restore = vs
                         .compose_prev_line_numbers
                         .take()
@@ -73,4 +76,45 @@ impl Editor {
         };
         self.set_status_message(t!("view.mode", mode = mode_label).to_string());
     }
+
+    /// Toggle focus mode in the active split.
+    ///
+    /// Focus mode combines the compose layout (text centered at
+    /// `focus_mode_width` and wrapped there) with typewriter scrolling and
+    /// dimming of every paragraph except the one containing the cursor.
+    pub fn handle_toggle_focus_mode(&mut self) {
+        let active_split = self.split_manager.active_split();
+        let Some(vs) = self.split_view_states.get(&active_split) else {
+            return;
+        };
+        let enable = !vs.focus_mode;
+
+        // Focus mode owns the compose layout: enter it when turning focus mode
+        // on and leave it again when turning focus mode off.
+        if enable != (vs.view_mode == ViewMode::Compose) {
+            self.handle_toggle_compose_mode();
+        }
+
+        let width = self.config.editor.focus_mode_width;
+        let typewriter = self.config.editor.focus_mode_typewriter_scrolling;
+        if let Some(vs) = self.split_view_states.get_mut(&active_split) {
+            vs.focus_mode = enable;
+            vs.viewport.typewriter_scrolling = enable && typewriter;
+            if enable {
+                // No view transform wraps plain text, so use builtin wrapping
+                // at the compose width
+                vs.compose_width = Some(width);
+                vs.viewport.line_wrap_enabled = true;
+            }
+        }
+        if enable {
+            self.active_state_mut().compose_width = Some(width);
+        }
+
+        self.set_status_message(if enable {
+            t!("view.focus_mode_on").to_string()
+        } else {
+            t!("view.focus_mode_off").to_string()
+        });
+    }
 }
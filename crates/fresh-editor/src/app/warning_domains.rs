@@ -3,6 +3,7 @@
 //! This module provides a generic architecture for different subsystems (LSP, plugins, etc.)
 //! to report warnings with custom status bar indicators and popup content.
 
+use crate::config_validation::ConfigIssue;
 use rust_i18n::t;
 use std::path::PathBuf;
 
@@ -46,6 +47,12 @@ pub enum WarningActionId {
     DisableLsp(String),
     /// Copy text to clipboard
     CopyToClipboard(String),
+    /// Open a config file at the location of a problem
+    OpenConfigAt {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+    },
     /// Custom action (for plugins)
     Custom(String),
 }
@@ -313,23 +320,85 @@ impl LspWarningDomain {
     /// Get install command for clipboard
     fn get_install_command(&self) -> Option<String> {
         let cmd = self.server_command.as_deref()?;
+        crate::services::lsp::install_command(cmd).map(str::to_string)
+    }
+}
 
-        match cmd {
-            "pylsp" => Some("pip install python-lsp-server".to_string()),
-            "rust-analyzer" => Some("rustup component add rust-analyzer".to_string()),
-            "typescript-language-server" => {
-                Some("npm install -g typescript-language-server typescript".to_string())
-            }
-            "gopls" => Some("go install golang.org/x/tools/gopls@latest".to_string()),
-            "bash-language-server" => Some("npm install -g bash-language-server".to_string()),
-            "clangd" => Some("sudo apt install clangd".to_string()),
-            "vscode-html-language-server"
-            | "vscode-css-language-server"
-            | "vscode-json-language-server" => {
-                Some("npm install -g vscode-langservers-extracted".to_string())
-            }
-            "csharp-ls" => Some("dotnet tool install --global csharp-ls".to_string()),
-            _ => None,
+/// Config warning domain for problems found when validating config files
+#[derive(Debug, Default)]
+pub struct ConfigWarningDomain {
+    /// Problems found in the config files, in layer order
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ConfigWarningDomain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the reported problems
+    pub fn set_issues(&mut self, issues: Vec<ConfigIssue>) {
+        self.issues = issues;
+    }
+
+    /// Clear all problems
+    pub fn clear(&mut self) {
+        self.issues.clear();
+    }
+}
+
+impl WarningDomain for ConfigWarningDomain {
+    fn id(&self) -> &str {
+        "config"
+    }
+
+    fn label(&self) -> String {
+        // Config problems are also written to the warning log, so they are
+        // already counted by the general badge
+        String::new()
+    }
+
+    fn level(&self) -> WarningLevel {
+        if self.issues.iter().any(ConfigIssue::is_error) {
+            WarningLevel::Error
+        } else if self.issues.is_empty() {
+            WarningLevel::None
+        } else {
+            WarningLevel::Warning
+        }
+    }
+
+    fn popup_content(&self) -> WarningPopupContent {
+        let message = self
+            .issues
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut actions = vec![WarningAction {
+            label: t!("warning.dismiss").to_string(),
+            action_id: WarningActionId::Dismiss,
+        }];
+
+        if let Some(issue) = self.issues.first() {
+            actions.insert(
+                0,
+                WarningAction {
+                    label: t!("config_issue.open_at_error").to_string(),
+                    action_id: WarningActionId::OpenConfigAt {
+                        path: issue.path.clone(),
+                        line: issue.line,
+                        column: issue.column,
+                    },
+                },
+            );
+        }
+
+        WarningPopupContent {
+            title: t!("config_issue.title", count = self.issues.len()).to_string(),
+            message,
+            actions,
         }
     }
 }
@@ -339,6 +408,7 @@ impl LspWarningDomain {
 pub struct WarningDomainRegistry {
     pub general: GeneralWarningDomain,
     pub lsp: LspWarningDomain,
+    pub config: ConfigWarningDomain,
 }
 
 impl WarningDomainRegistry {
@@ -354,6 +424,10 @@ impl WarningDomainRegistry {
             domains.push(&self.lsp);
         }
 
+        if self.config.has_warnings() {
+            domains.push(&self.config);
+        }
+
         if self.general.has_warnings() {
             domains.push(&self.general);
         }
@@ -363,11 +437,10 @@ impl WarningDomainRegistry {
 
     /// Get the highest warning level across all domains
     pub fn highest_level(&self) -> WarningLevel {
-        if self.lsp.level() == WarningLevel::Error || self.general.level() == WarningLevel::Error {
+        let levels = [self.lsp.level(), self.general.level(), self.config.level()];
+        if levels.contains(&WarningLevel::Error) {
             WarningLevel::Error
-        } else if self.lsp.level() == WarningLevel::Warning
-            || self.general.level() == WarningLevel::Warning
-        {
+        } else if levels.contains(&WarningLevel::Warning) {
             WarningLevel::Warning
         } else {
             WarningLevel::None
@@ -376,6 +449,6 @@ impl WarningDomainRegistry {
 
     /// Check if any domain has warnings
     pub fn has_any_warnings(&self) -> bool {
-        self.lsp.has_warnings() || self.general.has_warnings()
+        self.lsp.has_warnings() || self.general.has_warnings() || self.config.has_warnings()
     }
 }
@@ -0,0 +1,261 @@
+//! Watch task (**Toggle Watch Task**)
+//!
+//! Re-runs a command, such as `cargo check --message-format=short`, in the
+//! background after every save. The command runs through the shell in the
+//! working directory, using the editor's [`ProcessSpawner`], so it runs on the
+//! remote host for remote sessions. Problems found in its output by the
+//! [`ProblemMatcher`] are shown as diagnostics from the `watch` source, next
+//! to those of language servers, and the status bar shows how the last run
+//! went. The problems also become the newest quickfix list. A save during a
+//! run queues one more run.
+//!
+//! [`ProcessSpawner`]: crate::services::remote::ProcessSpawner
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use rust_i18n::t;
+
+use super::quickfix::QuickfixItem;
+use super::types::{WatchOutcome, WatchTask};
+use super::Editor;
+use crate::primitives::text_property::TextPropertyEntry;
+use crate::services::async_bridge::AsyncMessage;
+use crate::services::problem_matcher::{Problem, ProblemMatcher, ProblemSeverity};
+use crate::services::remote::SpawnResult;
+use crate::view::prompt::PromptType;
+
+/// Diagnostic source and server id of the watch task's problems
+const WATCH_SOURCE: &str = "watch";
+
+/// Name of the buffer showing the last run's output
+const OUTPUT_BUFFER_NAME: &str = "*Watch Output*";
+
+/// Diagnostic for `problem`
+fn problem_diagnostic(problem: &Problem) -> Diagnostic {
+    let position = Position::new(
+        problem.line.saturating_sub(1),
+        problem.column.saturating_sub(1),
+    );
+    Diagnostic {
+        range: Range::new(position, position),
+        severity: Some(match problem.severity {
+            ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+            ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+            ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+        }),
+        code: problem.code.clone().map(NumberOrString::String),
+        source: Some(WATCH_SOURCE.to_string()),
+        message: problem.message.clone(),
+        ..Default::default()
+    }
+}
+
+impl Editor {
+    /// Stop the watch task, or ask for the command to start one
+    pub fn toggle_watch_task(&mut self) {
+        if let Some(task) = self.watch_task.take() {
+            for uri in task.problem_uris {
+                self.set_watch_diagnostics(uri, Vec::new());
+            }
+            self.set_status_message(t!("watch.stopped", command = task.command).to_string());
+            return;
+        }
+        let command = self.config.editor.watch_command.clone();
+        self.start_prompt_with_initial_text(
+            t!("watch.prompt").to_string(),
+            PromptType::WatchCommand,
+            command,
+        );
+    }
+
+    /// Start re-running `command` on every save, and run it once now
+    pub(super) fn start_watch_task(&mut self, command: &str) {
+        let command = command.trim();
+        if command.is_empty() {
+            return;
+        }
+        self.watch_task = Some(WatchTask {
+            command: command.to_string(),
+            ..Default::default()
+        });
+        self.set_status_message(t!("watch.started", command = command).to_string());
+        self.start_watch_run();
+    }
+
+    /// Run the watch task again after a save
+    pub(super) fn on_save_watch_task(&mut self) {
+        let Some(task) = self.watch_task.as_mut() else {
+            return;
+        };
+        if task.running.is_some() {
+            task.rerun = true;
+        } else {
+            self.start_watch_run();
+        }
+    }
+
+    /// Spawn a run of the watch task's command
+    fn start_watch_run(&mut self) {
+        let shell = self.command_shell();
+        let cwd = Some(self.working_dir.to_string_lossy().to_string());
+        let (Some(runtime), Some(bridge), Some(task)) = (
+            &self.tokio_runtime,
+            &self.async_bridge,
+            self.watch_task.as_mut(),
+        ) else {
+            return;
+        };
+        let run_id = self.next_watch_run_id;
+        self.next_watch_run_id += 1;
+        task.running = Some(run_id);
+        task.rerun = false;
+
+        let spawner = Arc::clone(&self.process_spawner);
+        let sender = bridge.sender();
+        let args = vec!["-c".to_string(), task.command.clone()];
+        runtime.spawn(async move {
+            let result = spawner
+                .spawn(shell, args, cwd)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = sender.send(AsyncMessage::WatchTaskFinished { run_id, result });
+        });
+    }
+
+    /// Show the problems of a finished run and start a queued one
+    pub(super) fn handle_watch_task_finished(
+        &mut self,
+        run_id: u64,
+        result: Result<SpawnResult, String>,
+    ) {
+        // Runs of a stopped task are dropped
+        if self.watch_task.as_ref().and_then(|t| t.running) != Some(run_id) {
+            return;
+        }
+
+        let (output, succeeded) = match result {
+            Ok(result) => {
+                let mut output = result.stdout;
+                output.push_str(&result.stderr);
+                (output, result.exit_code == 0)
+            }
+            Err(e) => (e, false),
+        };
+
+        let mut by_uri: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+        let mut items = Vec::new();
+        let mut errors = 0;
+        for problem in ProblemMatcher::new().parse(&output) {
+            let path = self.working_dir.join(&problem.path);
+            // Lines that only look like locations name files that don't exist
+            if !self.filesystem.exists(&path) {
+                continue;
+            }
+            let Ok(uri) = url::Url::from_file_path(&path) else {
+                continue;
+            };
+            if problem.severity == ProblemSeverity::Error {
+                errors += 1;
+            }
+            by_uri
+                .entry(uri.to_string())
+                .or_default()
+                .push(problem_diagnostic(&problem));
+            items.push(QuickfixItem {
+                path,
+                line: problem.line.max(1) as usize,
+                column: problem.column.max(1) as usize,
+                text: problem.message,
+            });
+        }
+        let outcome = if succeeded && errors == 0 {
+            WatchOutcome::Passed
+        } else {
+            WatchOutcome::Failed {
+                problems: items.len(),
+            }
+        };
+
+        let Some(task) = self.watch_task.as_mut() else {
+            return;
+        };
+        task.running = None;
+        task.last = Some(outcome);
+        task.output = output;
+        let stale = std::mem::replace(&mut task.problem_uris, by_uri.keys().cloned().collect());
+        let rerun = task.rerun;
+        let title = format!("{}: {}", WATCH_SOURCE, task.command);
+
+        for uri in stale {
+            if !by_uri.contains_key(&uri) {
+                self.set_watch_diagnostics(uri, Vec::new());
+            }
+        }
+        for (uri, diagnostics) in by_uri {
+            self.set_watch_diagnostics(uri, diagnostics);
+        }
+        if !items.is_empty() {
+            self.set_quickfix_list(title, items, None);
+        }
+        if rerun {
+            self.start_watch_run();
+        }
+    }
+
+    /// Replace the watch task's diagnostics for `uri`
+    fn set_watch_diagnostics(&mut self, uri: String, diagnostics: Vec<Diagnostic>) {
+        let diagnostics = self.merge_server_diagnostics(WATCH_SOURCE, &uri, diagnostics);
+        self.store_and_apply_diagnostics(uri, diagnostics);
+    }
+
+    /// Status bar text for the watch task, e.g. `watch ✗ 3`
+    pub(super) fn watch_status_text(&self) -> Option<String> {
+        let task = self.watch_task.as_ref()?;
+        let state = if task.running.is_some() {
+            "…".to_string()
+        } else {
+            match task.last {
+                Some(WatchOutcome::Passed) => "✓".to_string(),
+                Some(WatchOutcome::Failed { problems: 0 }) => "✗".to_string(),
+                Some(WatchOutcome::Failed { problems }) => format!("✗ {}", problems),
+                None => "…".to_string(),
+            }
+        };
+        Some(format!("{} {}", WATCH_SOURCE, state))
+    }
+
+    /// Show the output of the watch task's last run
+    pub fn show_watch_output(&mut self) {
+        let Some(task) = self.watch_task.as_ref() else {
+            self.set_status_message(t!("watch.not_running").to_string());
+            return;
+        };
+        if task.last.is_none() {
+            self.set_status_message(t!("watch.no_output").to_string());
+            return;
+        }
+        let content = format!("$ {}\n{}", task.command, task.output);
+
+        let existing_buffer = self
+            .buffer_metadata
+            .iter()
+            .find(|(_, m)| m.display_name == OUTPUT_BUFFER_NAME)
+            .map(|(id, _)| *id);
+        let buffer_id = existing_buffer.unwrap_or_else(|| {
+            self.create_virtual_buffer(OUTPUT_BUFFER_NAME.to_string(), "special".to_string(), true)
+        });
+        if let Err(e) =
+            self.set_virtual_buffer_content(buffer_id, vec![TextPropertyEntry::text(content)])
+        {
+            tracing::warn!("Failed to fill watch output: {}", e);
+            return;
+        }
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.editing_disabled = true;
+            state.margins.set_line_numbers(false);
+        }
+        self.set_active_buffer(buffer_id);
+    }
+}
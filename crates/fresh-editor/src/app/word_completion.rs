@@ -0,0 +1,117 @@
+//! Word completion, the fallback when no language server provides completions
+//!
+//! When `editor.word_completion` is on and the buffer has no language server
+//! to ask, the completion trigger (quick suggestions or Ctrl+Space) offers the
+//! words starting with the typed prefix instead. They come from the buffer
+//! being edited (nearest to the cursor first), the other open files and, when
+//! the project root has one, a ctags `tags` file. The words are shown in the
+//! usual completion popup.
+
+use anyhow::Result as AnyhowResult;
+use std::time::SystemTime;
+
+use super::Editor;
+use crate::primitives::word_completion::WordCompletion;
+use crate::primitives::word_navigation::find_completion_word_start;
+
+/// Bytes on each side of the cursor searched in the buffer being edited
+const ACTIVE_BUFFER_WINDOW_BYTES: usize = 256 * 1024;
+
+/// Other open buffers larger than this are not searched
+const OTHER_BUFFER_MAX_BYTES: usize = 1024 * 1024;
+
+/// `tags` files larger than this are not read
+const TAGS_FILE_MAX_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Words offered at most
+const MAX_WORD_COMPLETIONS: usize = 50;
+
+/// A `tags` file as last read, to skip re-reading it while it is unchanged
+pub(crate) struct CachedTagsFile {
+    modified: Option<SystemTime>,
+    content: String,
+}
+
+impl Editor {
+    /// Show the words matching the partial word before the cursor in the
+    /// completion popup
+    pub(crate) fn show_word_completion(&mut self) -> AnyhowResult<()> {
+        let active_buffer = self.active_buffer();
+        let state = self.active_state();
+        let cursor = state.cursors.primary().position;
+        let word_start = find_completion_word_start(&state.buffer, cursor);
+        let prefix =
+            String::from_utf8_lossy(&state.buffer.slice_bytes(word_start..cursor)).into_owned();
+        let mut completion = WordCompletion::new(&prefix);
+
+        // The window may start inside a multi-byte character: skip its tail
+        let start = cursor.saturating_sub(ACTIVE_BUFFER_WINDOW_BYTES);
+        let end = (cursor + ACTIVE_BUFFER_WINDOW_BYTES).min(state.buffer.len());
+        let bytes = state.buffer.slice_bytes(start..end);
+        let skip = bytes.iter().take_while(|&&b| (b & 0xC0) == 0x80).count();
+        completion.add_active_text(
+            &String::from_utf8_lossy(&bytes[skip..]),
+            cursor - start - skip,
+        );
+
+        for (buffer_id, state) in &self.buffers {
+            let searchable = self
+                .buffer_metadata
+                .get(buffer_id)
+                .is_some_and(|meta| !meta.is_virtual() && !meta.binary);
+            if *buffer_id == active_buffer
+                || !searchable
+                || state.buffer.len() > OTHER_BUFFER_MAX_BYTES
+                || state.buffer.line_count().is_none()
+            {
+                continue;
+            }
+            let bytes = state.buffer.slice_bytes(0..state.buffer.len());
+            completion.add_text(&String::from_utf8_lossy(&bytes));
+        }
+
+        if let Some(tags) = self.read_tags_file() {
+            completion.add_tags(tags);
+        }
+
+        let items: Vec<lsp_types::CompletionItem> = completion
+            .ranked(MAX_WORD_COMPLETIONS)
+            .into_iter()
+            .map(|(word, _)| lsp_types::CompletionItem {
+                label: word,
+                kind: Some(lsp_types::CompletionItemKind::TEXT),
+                ..Default::default()
+            })
+            .collect();
+        if items.is_empty() {
+            return Ok(());
+        }
+        self.show_completion_popup(items)
+    }
+
+    /// The project's ctags `tags` file, read again only when it changed
+    fn read_tags_file(&mut self) -> Option<&str> {
+        let path = self.working_dir.join("tags");
+        let Some(metadata) = self.filesystem.metadata_if_exists(&path) else {
+            self.word_completion_tags = None;
+            return None;
+        };
+        if metadata.size > TAGS_FILE_MAX_BYTES {
+            return None;
+        }
+        let stale = match &self.word_completion_tags {
+            Some(cached) => cached.modified.is_none() || cached.modified != metadata.modified,
+            None => true,
+        };
+        if stale {
+            let content = self.filesystem.read_file(&path).ok()?;
+            self.word_completion_tags = Some(CachedTagsFile {
+                modified: metadata.modified,
+                content: String::from_utf8_lossy(&content).into_owned(),
+            });
+        }
+        self.word_completion_tags
+            .as_ref()
+            .map(|cached| cached.content.as_str())
+    }
+}
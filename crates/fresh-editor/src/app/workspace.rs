@@ -26,6 +26,7 @@ use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+use crate::input::position_history::{PositionEntry, PositionHistory};
 use crate::state::EditorState;
 
 use crate::model::event::{BufferId, SplitDirection, SplitId};
@@ -33,10 +34,11 @@ use crate::services::terminal::TerminalId;
 use crate::state::ViewMode;
 use crate::view::split::{SplitNode, SplitViewState};
 use crate::workspace::{
-    FileExplorerState, PersistedFileWorkspace, SearchOptions, SerializedBookmark, SerializedCursor,
-    SerializedFileState, SerializedScroll, SerializedSplitDirection, SerializedSplitNode,
-    SerializedSplitViewState, SerializedTabRef, SerializedTerminalWorkspace, SerializedViewMode,
-    Workspace, WorkspaceConfigOverrides, WorkspaceError, WorkspaceHistories, WORKSPACE_VERSION,
+    get_workspace_path, FileExplorerState, PersistedFileWorkspace, SearchOptions,
+    SerializedBookmark, SerializedCursor, SerializedFileState, SerializedJump, SerializedJumpList,
+    SerializedScroll, SerializedSplitDirection, SerializedSplitNode, SerializedSplitViewState,
+    SerializedTabRef, SerializedTerminalWorkspace, SerializedViewMode, Workspace,
+    WorkspaceConfigOverrides, WorkspaceError, WorkspaceHistories, WORKSPACE_VERSION,
 };
 
 use super::types::Bookmark;
@@ -286,6 +288,7 @@ impl Editor {
             histories,
             search_options,
             bookmarks,
+            file_marks: self.file_marks_for_workspace(),
             terminals,
             external_files,
             saved_at: std::time::SystemTime::now()
@@ -301,6 +304,12 @@ impl Editor {
     /// backing files before capturing the workspace.
     /// Also saves global file states (scroll/cursor positions per file).
     pub fn save_workspace(&mut self) -> Result<(), WorkspaceError> {
+        self.save_workspace_to(&get_workspace_path(&self.working_dir)?)
+    }
+
+    /// Save the current workspace to the given file instead of the default
+    /// location for the working directory (used for session server snapshots)
+    pub fn save_workspace_to(&mut self, path: &Path) -> Result<(), WorkspaceError> {
         // Ensure all terminal backing files have complete state before saving
         self.sync_all_terminal_backing_files();
 
@@ -308,7 +317,7 @@ impl Editor {
         self.save_all_global_file_states();
 
         let workspace = self.capture_workspace();
-        workspace.save()
+        workspace.save_to(path)
     }
 
     /// Save global file states for all open file buffers
@@ -412,11 +421,24 @@ impl Editor {
     ///
     /// Returns true if a workspace was successfully loaded and applied.
     pub fn try_restore_workspace(&mut self) -> Result<bool, WorkspaceError> {
+        self.try_restore_workspace_from(&get_workspace_path(&self.working_dir)?)
+    }
+
+    /// Try to load and apply a workspace from the given file
+    ///
+    /// Returns true if a workspace was successfully loaded and applied.
+    pub fn try_restore_workspace_from(&mut self, path: &Path) -> Result<bool, WorkspaceError> {
         tracing::debug!("Attempting to restore workspace for {:?}", self.working_dir);
-        match Workspace::load(&self.working_dir)? {
+        match Workspace::load_from(path, &self.working_dir)? {
             Some(workspace) => {
                 tracing::info!("Found workspace, applying...");
                 self.apply_workspace(&workspace)?;
+                self.plugin_manager.run_hook(
+                    "workspace_opened",
+                    crate::services::plugins::hooks::HookArgs::WorkspaceOpened {
+                        path: self.working_dir.clone(),
+                    },
+                );
                 Ok(true)
             }
             None => {
@@ -613,6 +635,9 @@ impl Editor {
             }
         }
 
+        // 8. Restore file marks
+        self.restore_file_marks(&workspace.file_marks, &path_to_buffer);
+
         tracing::debug!(
             "Workspace restore complete: {} splits, {} buffers",
             self.split_view_states.len(),
@@ -910,28 +935,32 @@ impl Editor {
         let mut active_buffer_id: Option<BufferId> = None;
 
         if !split_state.open_tabs.is_empty() {
-            for tab in &split_state.open_tabs {
-                match tab {
-                    SerializedTabRef::File(rel_path) => {
-                        if let Some(&buffer_id) = path_to_buffer.get(rel_path) {
-                            if !view_state.open_buffers.contains(&buffer_id) {
-                                view_state.open_buffers.push(buffer_id);
-                            }
-                            if terminal_buffers.values().any(|&tid| tid == buffer_id) {
-                                view_state.viewport.line_wrap_enabled = false;
-                            }
-                        }
-                    }
-                    SerializedTabRef::Terminal(index) => {
-                        if let Some(&buffer_id) = terminal_buffers.get(index) {
-                            if !view_state.open_buffers.contains(&buffer_id) {
-                                view_state.open_buffers.push(buffer_id);
-                            }
-                            view_state.viewport.line_wrap_enabled = false;
-                        }
-                    }
+            for (tab_index, tab) in split_state.open_tabs.iter().enumerate() {
+                let restored_buffer = match tab {
+                    SerializedTabRef::File(rel_path) => path_to_buffer.get(rel_path).copied(),
+                    SerializedTabRef::Terminal(index) => terminal_buffers.get(index).copied(),
+                };
+                let Some(buffer_id) = restored_buffer else {
+                    continue;
+                };
+                if !view_state.open_buffers.contains(&buffer_id) {
+                    view_state.open_buffers.push(buffer_id);
+                }
+                if matches!(tab, SerializedTabRef::Terminal(_))
+                    || terminal_buffers.values().any(|&tid| tid == buffer_id)
+                {
+                    view_state.viewport.line_wrap_enabled = false;
+                }
+
+                // Restore pinned state and tab group
+                if split_state.pinned_tabs.contains(&tab_index) {
+                    view_state.pinned_buffers.insert(buffer_id);
+                }
+                if let Some(group) = split_state.tab_groups.get(&tab_index) {
+                    view_state.tab_groups.insert(buffer_id, *group);
                 }
             }
+            view_state.normalize_tab_order();
 
             if let Some(active_idx) = split_state.active_tab_index {
                 if let Some(tab) = split_state.open_tabs.get(active_idx) {
@@ -1011,6 +1040,26 @@ impl Editor {
                 .set_split_buffer(current_split_id, active_id);
         }
 
+        // Restore the jump list, keeping positions in the reopened files
+        let mut jumps = Vec::new();
+        let mut current = None;
+        for (index, jump) in split_state.jump_list.entries.iter().enumerate() {
+            let Some(&buffer_id) = path_to_buffer.get(&jump.file_path) else {
+                continue;
+            };
+            let max_pos = self.buffers.get(&buffer_id).map_or(0, |b| b.buffer.len());
+            jumps.push(PositionEntry::new(
+                buffer_id,
+                jump.position.min(max_pos),
+                jump.anchor.map(|a| a.min(max_pos)),
+            ));
+            // The current entry, or the last one kept before it
+            if split_state.jump_list.current.is_some_and(|c| index <= c) {
+                current = Some(jumps.len() - 1);
+            }
+        }
+        view_state.position_history.restore(jumps, current);
+
         // Restore view mode
         view_state.view_mode = match split_state.view_mode {
             SerializedViewMode::Source => ViewMode::Source,
@@ -1018,6 +1067,12 @@ impl Editor {
         };
         view_state.compose_width = split_state.compose_width;
         view_state.tab_scroll_offset = split_state.tab_scroll_offset;
+        if split_state.focus_mode && view_state.view_mode == ViewMode::Compose {
+            view_state.focus_mode = true;
+            view_state.viewport.line_wrap_enabled = true;
+            view_state.viewport.typewriter_scrolling =
+                self.config.editor.focus_mode_typewriter_scrolling;
+        }
     }
 }
 
@@ -1122,12 +1177,24 @@ fn serialize_split_view_state(
     let mut open_tabs = Vec::new();
     let mut open_files = Vec::new();
     let mut active_tab_index = None;
+    let mut pinned_tabs = Vec::new();
+    let mut tab_groups = HashMap::new();
+    // Record pin/group state of a tab once it has been added to open_tabs
+    let mut record_tab_marks = |tab_index: usize, buffer_id: &BufferId| {
+        if view_state.is_pinned(*buffer_id) {
+            pinned_tabs.push(tab_index);
+        }
+        if let Some(group) = view_state.tab_groups.get(buffer_id) {
+            tab_groups.insert(tab_index, *group);
+        }
+    };
 
     for buffer_id in &view_state.open_buffers {
         let tab_index = open_tabs.len();
         if let Some(terminal_id) = terminal_buffers.get(buffer_id) {
             if let Some(idx) = terminal_indices.get(terminal_id) {
                 open_tabs.push(SerializedTabRef::Terminal(*idx));
+                record_tab_marks(tab_index, buffer_id);
                 if Some(*buffer_id) == active_buffer {
                     active_tab_index = Some(tab_index);
                 }
@@ -1142,6 +1209,7 @@ fn serialize_split_view_state(
         {
             open_tabs.push(SerializedTabRef::File(rel_path.to_path_buf()));
             open_files.push(rel_path.to_path_buf());
+            record_tab_marks(tab_index, buffer_id);
             if Some(*buffer_id) == active_buffer {
                 active_tab_index = Some(tab_index);
             }
@@ -1209,7 +1277,41 @@ fn serialize_split_view_state(
             ViewMode::Compose => SerializedViewMode::Compose,
         },
         compose_width: view_state.compose_width,
+        focus_mode: view_state.focus_mode,
+        pinned_tabs,
+        tab_groups,
+        jump_list: serialize_jump_list(&view_state.position_history, buffer_metadata, working_dir),
+    }
+}
+
+/// Serialize a jump list, dropping positions in buffers without a file
+/// under the working directory
+fn serialize_jump_list(
+    history: &PositionHistory,
+    buffer_metadata: &HashMap<BufferId, super::types::BufferMetadata>,
+    working_dir: &Path,
+) -> SerializedJumpList {
+    let mut entries = Vec::new();
+    let mut current = None;
+    for (index, entry) in history.entries().iter().enumerate() {
+        let Some(rel_path) = buffer_metadata
+            .get(&entry.buffer_id)
+            .and_then(|meta| meta.file_path())
+            .and_then(|abs_path| abs_path.strip_prefix(working_dir).ok())
+        else {
+            continue;
+        };
+        entries.push(SerializedJump {
+            file_path: rel_path.to_path_buf(),
+            position: entry.position,
+            anchor: entry.anchor,
+        });
+        // The current entry, or the last one kept before it
+        if history.current_index().is_some_and(|c| index <= c) {
+            current = Some(entries.len() - 1);
+        }
     }
+    SerializedJumpList { entries, current }
 }
 
 fn serialize_bookmarks(
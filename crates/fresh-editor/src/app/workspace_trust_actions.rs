@@ -0,0 +1,92 @@
+//! Workspace trust prompt
+//!
+//! Asks whether the working directory's project config and plugins may be
+//! used, and applies the answer. See `services::workspace_trust` for what is
+//! gated and where decisions are stored.
+
+use super::Editor;
+use crate::services::workspace_trust::{self, TrustDecision, WorkspaceTrust};
+use crate::view::prompt::PromptType;
+use rust_i18n::t;
+
+impl Editor {
+    /// Open the trust prompt queued at startup unless another prompt is active
+    ///
+    /// Returns true if the prompt was opened.
+    pub(super) fn show_pending_workspace_trust_prompt(&mut self) -> bool {
+        if !self.pending_workspace_trust_prompt || self.prompt.is_some() {
+            return false;
+        }
+        self.pending_workspace_trust_prompt = false;
+        self.show_workspace_trust_prompt();
+        true
+    }
+
+    /// Ask whether to trust the working directory
+    pub fn show_workspace_trust_prompt(&mut self) {
+        let dir = self.working_dir.display().to_string();
+        let message = if self.workspace_trusted {
+            t!("prompt.workspace_trust_revoke", dir = &dir)
+        } else {
+            t!("prompt.workspace_trust_confirm", dir = &dir)
+        };
+        self.start_prompt(message.to_string(), PromptType::WorkspaceTrust);
+    }
+
+    /// Apply the answer to the trust prompt: (t)rust, (n)o, anything else keeps
+    /// the current state without recording a decision
+    pub(super) fn answer_workspace_trust(&mut self, answer: &str) {
+        let decision = match answer.trim().to_lowercase().as_str() {
+            "t" | "trust" => TrustDecision::Trusted,
+            "n" | "no" => TrustDecision::Untrusted,
+            _ => {
+                self.set_status_message(t!("workspace_trust.unchanged").to_string());
+                return;
+            }
+        };
+
+        let working_dir = self.working_dir.clone();
+        if let Err(e) =
+            WorkspaceTrust::load(&self.dir_context.data_dir).set_decision(&working_dir, decision)
+        {
+            tracing::warn!("Failed to save workspace trust decision: {}", e);
+        }
+
+        let was_trusted = self.workspace_trusted;
+        self.workspace_trusted = decision == TrustDecision::Trusted;
+        if was_trusted != self.workspace_trusted {
+            // Project config layers are filtered by trust when resolved
+            self.reload_config();
+        }
+
+        let message = match (was_trusted, self.workspace_trusted) {
+            (false, true) => {
+                self.load_workspace_plugins();
+                t!("workspace_trust.trusted")
+            }
+            (true, false) => t!("workspace_trust.revoked"),
+            (true, true) => t!("workspace_trust.trusted"),
+            (false, false) => t!("workspace_trust.untrusted"),
+        };
+        self.set_status_message(message.to_string());
+    }
+
+    /// Load the workspace's plugins after it became trusted
+    fn load_workspace_plugins(&mut self) {
+        let plugins_dir = workspace_trust::workspace_plugins_dir(&self.working_dir);
+        if !plugins_dir.exists() || !self.plugin_manager.is_active() {
+            return;
+        }
+
+        tracing::info!("Loading workspace plugins from: {:?}", plugins_dir);
+        let (errors, discovered_plugins) = self
+            .plugin_manager
+            .load_plugins_from_dir_with_config(&plugins_dir, &self.config.plugins);
+        for (name, plugin_config) in discovered_plugins {
+            self.config.plugins.insert(name, plugin_config);
+        }
+        for err in &errors {
+            tracing::error!("Workspace plugin load error: {}", err);
+        }
+    }
+}
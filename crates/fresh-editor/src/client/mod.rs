@@ -6,6 +6,8 @@
 //! - Set terminal to raw mode
 //! - Relay bytes bidirectionally (stdin↔data socket, data socket↔stdout)
 //! - Forward resize events via control socket
+//! - Decompress server output if the handshake negotiated compression
+//! - Optionally echo typed characters before the server answers (see `prediction`)
 //! - Restore terminal on exit
 //!
 //! All complexity (input parsing, rendering, editor logic) lives server-side.
@@ -14,11 +16,13 @@ use std::io;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
+use crate::server::data_codec::DataDecoder;
 use crate::server::ipc::{ClientConnection, SocketPaths};
 use crate::server::protocol::{
-    ClientControl, ClientHello, ServerControl, TermSize, PROTOCOL_VERSION,
+    ClientControl, ClientHello, Compression, ServerControl, TermSize, PROTOCOL_VERSION,
 };
 
+mod prediction;
 #[cfg(unix)]
 mod relay_unix;
 #[cfg(windows)]
@@ -30,6 +34,10 @@ pub struct ClientConfig {
     pub socket_paths: SocketPaths,
     /// Initial terminal size
     pub term_size: TermSize,
+    /// Echo typed characters locally on slow links
+    pub predictive_echo: bool,
+    /// Ask the server to compress its output
+    pub compress: bool,
 }
 
 /// Reason the client exited
@@ -68,7 +76,10 @@ pub fn run_client_with_connection(
     mut conn: ClientConnection,
 ) -> io::Result<ClientExitReason> {
     // Perform handshake
-    let hello = ClientHello::new(config.term_size);
+    let mut hello = ClientHello::new(config.term_size);
+    if config.compress {
+        hello.compression = vec![Compression::Zstd];
+    }
     let hello_json = serde_json::to_string(&ClientControl::Hello(hello))
         .map_err(|e| io::Error::other(e.to_string()))?;
     conn.write_control(&hello_json)?;
@@ -81,7 +92,7 @@ pub fn run_client_with_connection(
     let server_msg: ServerControl =
         serde_json::from_str(&response).map_err(|e| io::Error::other(e.to_string()))?;
 
-    match server_msg {
+    let compression = match server_msg {
         ServerControl::Hello(server_hello) => {
             if server_hello.protocol_version != PROTOCOL_VERSION {
                 return Ok(ClientExitReason::VersionMismatch {
@@ -93,6 +104,7 @@ pub fn run_client_with_connection(
                 server_hello.session_id,
                 server_hello.server_version
             );
+            server_hello.compression
         }
         ServerControl::VersionMismatch(mismatch) => {
             return Ok(ClientExitReason::VersionMismatch {
@@ -105,17 +117,21 @@ pub fn run_client_with_connection(
         _ => {
             return Err(io::Error::other("Unexpected server response"));
         }
-    }
+    };
 
-    run_client_relay(conn)
+    run_client_relay(conn, compression, config.predictive_echo)
 }
 
 /// Run the relay loop with an already-handshaked connection
 ///
 /// Use this when handshake has already been performed externally.
-/// Caller must have already enabled raw mode.
+/// Caller must have already enabled raw mode. `compression` is what the
+/// server's hello chose. With `predictive_echo`, plain typed characters are
+/// drawn locally while the round trip is slow.
 pub fn run_client_relay(
     #[allow(unused_mut)] mut conn: ClientConnection,
+    compression: Option<Compression>,
+    predictive_echo: bool,
 ) -> io::Result<ClientExitReason> {
     // Set up for relay
     // On Windows, don't set nonblocking here - the relay loop uses try_read() which handles this
@@ -128,12 +144,14 @@ pub fn run_client_relay(
     #[cfg(unix)]
     relay_unix::setup_resize_handler(resize_flag.clone())?;
 
+    let decoder = DataDecoder::new(compression);
+
     // Run the platform-specific relay loop
     #[cfg(unix)]
-    return relay_unix::relay_loop(&mut conn, resize_flag);
+    return relay_unix::relay_loop(&mut conn, resize_flag, decoder, predictive_echo);
 
     #[cfg(windows)]
-    return relay_windows::relay_loop(&mut conn);
+    return relay_windows::relay_loop(&mut conn, decoder, predictive_echo);
 }
 
 /// Get current terminal size
@@ -0,0 +1,370 @@
+//! Predictive local echo for high-latency sessions
+//!
+//! Over a slow link every keystroke takes a round trip before it shows up.
+//! Like mosh, the client draws plain typed characters at the cursor right
+//! away (underlined until confirmed) and checks the guess against the next
+//! frames from the server:
+//! - a frame that writes the same characters into the predicted cells and
+//!   leaves the cursor after them confirms the prediction
+//! - anything else is a misprediction, and the client asks the server for a
+//!   full redraw, which paints over the guessed characters
+//!
+//! A frame ends with the cursor update ratatui emits after drawing: `CSI ?25h`
+//! followed by `CSI row;col H`, or `CSI ?25l` when the cursor is hidden.
+//! Predictions are only drawn at a frame boundary, so they never interleave
+//! with a half-drawn frame, and only while the measured round trip is at least
+//! `PREDICTION_RTT`, so local sessions don't flicker.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Round trip above which typed characters are echoed locally
+const PREDICTION_RTT: Duration = Duration::from_millis(30);
+
+/// What the relay should do with a chunk of server output
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Reconciled {
+    /// Bytes to write to the terminal (the server output, with predictions
+    /// that are still pending drawn again after each frame)
+    pub output: Vec<u8>,
+    /// A prediction was wrong; ask the server for a full redraw
+    pub redraw: bool,
+}
+
+#[derive(Debug)]
+enum ParseState {
+    Ground,
+    Escape,
+    Csi(Vec<u8>),
+}
+
+/// Tracks the server's screen output and the characters echoed ahead of it
+pub struct Predictor {
+    enabled: bool,
+    /// Terminal width; predictions stop before the last column
+    cols: u16,
+    state: ParseState,
+    /// Where the next printed character lands (0-based column, row)
+    pos: (u16, u16),
+    /// The previous sequence was `CSI ?25h`, so a cursor move ends the frame
+    after_show: bool,
+    /// Output arrived since the last frame ended
+    mid_frame: bool,
+    /// Cursor at the end of the last frame, None while hidden or unknown
+    server_cursor: Option<(u16, u16)>,
+    /// Echoed characters the server hasn't confirmed, in typing order
+    predictions: Vec<(u16, u16, u8)>,
+    /// Characters the current frame wrote into predicted cells
+    written: HashMap<(u16, u16), u8>,
+    /// Input that couldn't be predicted is in flight
+    blocked: bool,
+    /// When the oldest input not yet answered by a frame was sent
+    sent_at: Option<Instant>,
+    /// Smoothed round trip from input to the next frame
+    rtt: Duration,
+}
+
+impl Predictor {
+    pub fn new(enabled: bool, cols: u16) -> Self {
+        Self {
+            enabled,
+            cols,
+            state: ParseState::Ground,
+            pos: (0, 0),
+            after_show: false,
+            mid_frame: true,
+            server_cursor: None,
+            predictions: Vec::new(),
+            written: HashMap::new(),
+            blocked: false,
+            sent_at: None,
+            rtt: Duration::ZERO,
+        }
+    }
+
+    /// Update the terminal width after a resize
+    ///
+    /// The server redraws everything after a resize, so pending predictions
+    /// are dropped.
+    pub fn resize(&mut self, cols: u16) {
+        self.cols = cols;
+        self.predictions.clear();
+        self.blocked = true;
+    }
+
+    /// Handle input about to be sent to the server
+    ///
+    /// Returns bytes to write to the terminal right away: the echoed
+    /// characters when `input` is plain text that can be predicted.
+    pub fn on_input(&mut self, input: &[u8]) -> Vec<u8> {
+        self.on_input_at(input, Instant::now())
+    }
+
+    fn on_input_at(&mut self, input: &[u8], now: Instant) -> Vec<u8> {
+        self.sent_at.get_or_insert(now);
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let printable = !input.is_empty() && input.iter().all(|b| (0x20..0x7f).contains(b));
+        let start = match self.predictions.last() {
+            Some(&(col, row, _)) => Some((col + 1, row)),
+            None => self.server_cursor,
+        };
+        let Some((col, row)) = start.filter(|_| {
+            printable && !self.blocked && !self.mid_frame && self.rtt >= PREDICTION_RTT
+        }) else {
+            self.blocked = true;
+            return Vec::new();
+        };
+        if usize::from(col) + input.len() >= usize::from(self.cols) {
+            self.blocked = true;
+            return Vec::new();
+        }
+
+        for (i, &byte) in input.iter().enumerate() {
+            self.predictions.push((col + i as u16, row, byte));
+        }
+        self.draw_predictions(self.predictions.len() - input.len())
+    }
+
+    /// Handle a chunk of server output before it is written to the terminal
+    pub fn on_output(&mut self, output: &[u8]) -> Reconciled {
+        self.on_output_at(output, Instant::now())
+    }
+
+    fn on_output_at(&mut self, output: &[u8], now: Instant) -> Reconciled {
+        if !self.enabled {
+            return Reconciled {
+                output: output.to_vec(),
+                redraw: false,
+            };
+        }
+        let mut result = Reconciled {
+            output: Vec::with_capacity(output.len()),
+            redraw: false,
+        };
+        for &byte in output {
+            result.output.push(byte);
+            if let Some(cursor) = self.parse(byte) {
+                self.end_frame(cursor, now, &mut result);
+            }
+        }
+        result
+    }
+
+    /// Feed one byte of server output; returns the cursor when it ends a frame
+    fn parse(&mut self, byte: u8) -> Option<Option<(u16, u16)>> {
+        self.mid_frame = true;
+        match std::mem::replace(&mut self.state, ParseState::Ground) {
+            ParseState::Ground => match byte {
+                0x1b => self.state = ParseState::Escape,
+                b'\r' => self.pos.0 = 0,
+                b'\n' => self.pos.1 += 1,
+                0x08 => self.pos.0 = self.pos.0.saturating_sub(1),
+                // UTF-8 continuation bytes don't start a new cell
+                0x80..=0xbf => {}
+                0x20..=0xff => {
+                    self.after_show = false;
+                    if self.predictions.iter().any(|&(c, r, _)| (c, r) == self.pos) {
+                        self.written.insert(self.pos, byte);
+                    }
+                    self.pos.0 += 1;
+                }
+                _ => {}
+            },
+            ParseState::Escape => {
+                if byte == b'[' {
+                    self.state = ParseState::Csi(Vec::new());
+                } else {
+                    self.after_show = false;
+                }
+            }
+            ParseState::Csi(mut params) => match byte {
+                0x20..=0x3f => {
+                    params.push(byte);
+                    self.state = ParseState::Csi(params);
+                }
+                _ => return self.csi(&params, byte),
+            },
+        }
+        None
+    }
+
+    fn csi(&mut self, params: &[u8], command: u8) -> Option<Option<(u16, u16)>> {
+        let after_show = std::mem::take(&mut self.after_show);
+        match (params, command) {
+            (b"?25", b'h') => self.after_show = true,
+            (b"?25", b'l') => return Some(None),
+            (_, b'H' | b'f') => {
+                let params = String::from_utf8_lossy(params);
+                let mut numbers = params
+                    .split(';')
+                    .map(|n| n.parse::<u16>().unwrap_or(1).max(1) - 1);
+                let row = numbers.next().unwrap_or(0);
+                let col = numbers.next().unwrap_or(0);
+                self.pos = (col, row);
+                if after_show {
+                    return Some(Some(self.pos));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Check pending predictions against the frame that just ended
+    fn end_frame(&mut self, cursor: Option<(u16, u16)>, now: Instant, result: &mut Reconciled) {
+        self.mid_frame = false;
+        self.blocked = false;
+        self.server_cursor = cursor;
+        if let Some(sent_at) = self.sent_at.take() {
+            let sample = now.saturating_duration_since(sent_at);
+            self.rtt = (self.rtt * 7 + sample) / 8;
+        }
+        let written = std::mem::take(&mut self.written);
+        if self.predictions.is_empty() {
+            return;
+        }
+
+        // The server has handled the first `confirmed` predictions if the
+        // cursor is right after them and it drew the same characters
+        let (col, row, _) = self.predictions[0];
+        let confirmed = cursor
+            .filter(|&(c, r)| r == row && c >= col)
+            .map(|(c, _)| usize::from(c - col))
+            .filter(|&n| n <= self.predictions.len())
+            .filter(|&n| {
+                self.predictions[..n]
+                    .iter()
+                    .all(|&(c, r, ch)| written.get(&(c, r)) == Some(&ch))
+            });
+        match confirmed {
+            Some(n) => {
+                self.predictions.drain(..n);
+                // The frame may have painted over the rest
+                let echo = self.draw_predictions(0);
+                result.output.extend_from_slice(&echo);
+            }
+            None => {
+                self.predictions.clear();
+                result.redraw = true;
+            }
+        }
+    }
+
+    /// Draw the predictions from `from` on, leaving the cursor after the last
+    fn draw_predictions(&self, from: usize) -> Vec<u8> {
+        let Some(&(col, row, _)) = self.predictions.get(from) else {
+            return Vec::new();
+        };
+        let text: Vec<u8> = self.predictions[from..].iter().map(|p| p.2).collect();
+        // Save and restore the cursor so the server's colors stay in effect
+        let mut out = format!("\x1b7\x1b[{};{}H\x1b[0;4m", row + 1, col + 1).into_bytes();
+        out.extend_from_slice(&text);
+        let end = col + text.len() as u16;
+        out.extend_from_slice(format!("\x1b8\x1b[{};{}H", row + 1, end + 1).as_bytes());
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A frame that draws `text` at (col, row) and leaves the cursor after it
+    fn frame(col: u16, row: u16, text: &str) -> Vec<u8> {
+        format!(
+            "\x1b[{};{}H{}\x1b[?25h\x1b[{};{}H",
+            row + 1,
+            col + 1,
+            text,
+            row + 1,
+            col + 1 + text.len() as u16
+        )
+        .into_bytes()
+    }
+
+    /// A predictor on a slow link with the cursor at (4, 2)
+    fn slow_predictor() -> (Predictor, Instant) {
+        let mut predictor = Predictor::new(true, 80);
+        let start = Instant::now();
+        predictor.on_input_at(b"x", start);
+        predictor.on_output_at(&frame(0, 2, "abcd"), start + Duration::from_millis(400));
+        assert!(predictor.rtt >= PREDICTION_RTT);
+        (predictor, start + Duration::from_millis(400))
+    }
+
+    #[test]
+    fn test_no_prediction_on_fast_link() {
+        let mut predictor = Predictor::new(true, 80);
+        let now = Instant::now();
+        predictor.on_input_at(b"x", now);
+        predictor.on_output_at(&frame(0, 0, "x"), now);
+        assert!(predictor.on_input_at(b"y", now).is_empty());
+    }
+
+    #[test]
+    fn test_disabled_never_predicts() {
+        let mut predictor = Predictor::new(false, 80);
+        predictor.rtt = Duration::from_secs(1);
+        predictor.server_cursor = Some((0, 0));
+        predictor.mid_frame = false;
+        assert!(predictor.on_input(b"y").is_empty());
+    }
+
+    #[test]
+    fn test_typed_text_is_echoed_and_confirmed() {
+        let (mut predictor, now) = slow_predictor();
+
+        let echo = predictor.on_input_at(b"ef", now);
+        let echo = String::from_utf8(echo).unwrap();
+        assert!(echo.contains("\x1b[3;5H\x1b[0;4mef"), "echo: {:?}", echo);
+        assert!(echo.ends_with("\x1b8\x1b[3;7H"), "cursor after the echo");
+
+        let result = predictor.on_output_at(&frame(4, 2, "ef"), now);
+        assert!(!result.redraw);
+        assert_eq!(result.output, frame(4, 2, "ef"), "nothing left to re-echo");
+        assert!(predictor.predictions.is_empty());
+    }
+
+    #[test]
+    fn test_partly_confirmed_prediction_is_drawn_again() {
+        let (mut predictor, now) = slow_predictor();
+        predictor.on_input_at(b"ef", now);
+
+        let result = predictor.on_output_at(&frame(4, 2, "e"), now);
+        assert!(!result.redraw);
+        let output = String::from_utf8(result.output).unwrap();
+        assert!(output.ends_with("\x1b7\x1b[3;6H\x1b[0;4mf\x1b8\x1b[3;7H"));
+        assert_eq!(predictor.predictions, vec![(5, 2, b'f')]);
+    }
+
+    #[test]
+    fn test_misprediction_requests_redraw() {
+        let (mut predictor, now) = slow_predictor();
+        predictor.on_input_at(b"j", now);
+
+        // The key moved the cursor down instead of inserting text
+        let result = predictor.on_output_at(b"\x1b[?25h\x1b[4;5H", now);
+        assert!(result.redraw);
+        assert!(predictor.predictions.is_empty());
+    }
+
+    #[test]
+    fn test_control_input_blocks_prediction_until_next_frame() {
+        let (mut predictor, now) = slow_predictor();
+        assert!(predictor.on_input_at(b"\x1b[A", now).is_empty());
+        assert!(predictor.on_input_at(b"e", now).is_empty());
+
+        predictor.on_output_at(&frame(0, 1, "abcd"), now);
+        assert!(!predictor.on_input_at(b"e", now).is_empty());
+    }
+
+    #[test]
+    fn test_no_prediction_mid_frame() {
+        let (mut predictor, now) = slow_predictor();
+        predictor.on_output_at(b"\x1b[1;1Hpartial", now);
+        assert!(predictor.on_input_at(b"e", now).is_empty());
+    }
+}
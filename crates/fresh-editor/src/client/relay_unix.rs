@@ -7,7 +7,9 @@ use std::sync::Arc;
 
 use nix::poll::{poll, PollFd, PollFlags};
 
+use super::prediction::Predictor;
 use super::{get_terminal_size, ClientExitReason};
+use crate::server::data_codec::DataDecoder;
 use crate::server::ipc::ClientConnection;
 use crate::server::protocol::ClientControl;
 
@@ -15,9 +17,13 @@ use crate::server::protocol::ClientControl;
 pub fn relay_loop(
     conn: &mut ClientConnection,
     resize_flag: Arc<AtomicBool>,
+    mut decoder: DataDecoder,
+    predictive_echo: bool,
 ) -> io::Result<ClientExitReason> {
     let mut stdin = io::stdin();
     let mut stdout = io::stdout();
+    let cols = get_terminal_size().map(|size| size.cols).unwrap_or(80);
+    let mut predictor = Predictor::new(predictive_echo, cols);
 
     let mut stdin_buf = [0u8; 4096];
     let mut data_buf = [0u8; 4096];
@@ -29,6 +35,7 @@ pub fn relay_loop(
         // Check for resize
         if resize_flag.swap(false, Ordering::SeqCst) {
             if let Ok(size) = get_terminal_size() {
+                predictor.resize(size.cols);
                 let resize_msg = serde_json::to_string(&ClientControl::Resize {
                     cols: size.cols,
                     rows: size.rows,
@@ -72,6 +79,11 @@ pub fn relay_loop(
                 }
                 Ok(n) => {
                     conn.write_data(&stdin_buf[..n])?;
+                    let echo = predictor.on_input(&stdin_buf[..n]);
+                    if !echo.is_empty() {
+                        stdout.write_all(&echo)?;
+                        stdout.flush()?;
+                    }
                 }
                 Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
                 Err(e) => return Err(e),
@@ -90,8 +102,14 @@ pub fn relay_loop(
                     return Ok(ClientExitReason::ServerQuit);
                 }
                 Ok(n) => {
-                    stdout.write_all(&data_buf[..n])?;
+                    let output = decoder.decode(&data_buf[..n])?;
+                    let reconciled = predictor.on_output(&output);
+                    stdout.write_all(&reconciled.output)?;
                     stdout.flush()?;
+                    if reconciled.redraw {
+                        let redraw_msg = serde_json::to_string(&ClientControl::Redraw).unwrap();
+                        conn.write_control(&redraw_msg)?;
+                    }
                 }
                 Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
                 Err(e) => return Err(e),
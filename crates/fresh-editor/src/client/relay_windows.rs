@@ -5,7 +5,9 @@ use std::time::Duration;
 
 use crossterm::event::{self, Event, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind};
 
+use super::prediction::Predictor;
 use super::{get_terminal_size, ClientExitReason};
+use crate::server::data_codec::DataDecoder;
 use crate::server::ipc::ClientConnection;
 use crate::server::protocol::{ClientControl, ServerControl};
 use crate::services::terminal::pty::key_to_pty_bytes;
@@ -14,7 +16,11 @@ use crate::services::terminal::pty::key_to_pty_bytes;
 ///
 /// Uses crossterm for terminal input events and non-blocking pipe reads.
 /// No threads, no mutex contention - just poll input and pipe in a loop.
-pub fn relay_loop(conn: &mut ClientConnection) -> io::Result<ClientExitReason> {
+pub fn relay_loop(
+    conn: &mut ClientConnection,
+    mut decoder: DataDecoder,
+    predictive_echo: bool,
+) -> io::Result<ClientExitReason> {
     tracing::debug!("[windows_loop] Starting single-threaded event loop");
 
     let mut stdout = io::stdout();
@@ -22,6 +28,10 @@ pub fn relay_loop(conn: &mut ClientConnection) -> io::Result<ClientExitReason> {
     let mut control_buf = Vec::new();
     let mut control_byte = [0u8; 1];
     let mut last_size = get_terminal_size().ok();
+    let mut predictor = Predictor::new(
+        predictive_echo,
+        last_size.map(|size| size.cols).unwrap_or(80),
+    );
 
     loop {
         let mut did_work = false;
@@ -48,6 +58,11 @@ pub fn relay_loop(conn: &mut ClientConnection) -> io::Result<ClientExitReason> {
                                 );
                                 conn.write_data(&bytes)?;
                                 tracing::debug!("[loop] Key write complete");
+                                let echo = predictor.on_input(&bytes);
+                                if !echo.is_empty() {
+                                    stdout.write_all(&echo)?;
+                                    stdout.flush()?;
+                                }
                             }
                         }
                     }
@@ -68,6 +83,7 @@ pub fn relay_loop(conn: &mut ClientConnection) -> io::Result<ClientExitReason> {
                     }
                     Ok(Event::Resize(cols, rows)) => {
                         tracing::debug!("[loop] Resize event: {}x{}", cols, rows);
+                        predictor.resize(cols);
                         let resize_msg =
                             serde_json::to_string(&ClientControl::Resize { cols, rows }).unwrap();
                         conn.write_control(&resize_msg)?;
@@ -108,8 +124,14 @@ pub fn relay_loop(conn: &mut ClientConnection) -> io::Result<ClientExitReason> {
             Ok(n) => {
                 tracing::debug!("[loop] Read {} bytes from data pipe, writing to stdout", n);
                 did_work = true;
-                stdout.write_all(&data_buf[..n])?;
+                let output = decoder.decode(&data_buf[..n])?;
+                let reconciled = predictor.on_output(&output);
+                stdout.write_all(&reconciled.output)?;
                 stdout.flush()?;
+                if reconciled.redraw {
+                    let redraw_msg = serde_json::to_string(&ClientControl::Redraw).unwrap();
+                    let _ = conn.write_control(&redraw_msg);
+                }
                 tracing::debug!("[loop] Stdout write complete");
             }
             Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
@@ -163,6 +185,7 @@ pub fn relay_loop(conn: &mut ClientConnection) -> io::Result<ClientExitReason> {
         if let Ok(new_size) = get_terminal_size() {
             if last_size.as_ref() != Some(&new_size) {
                 last_size = Some(new_size);
+                predictor.resize(new_size.cols);
                 let resize_msg = serde_json::to_string(&ClientControl::Resize {
                     cols: new_size.cols,
                     rows: new_size.rows,
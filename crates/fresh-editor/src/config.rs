@@ -1,10 +1,11 @@
+use crate::primitives::path_utils::path_matches_glob;
 use crate::types::{context_keys, LspServerConfig, ProcessLimits};
 
 use rust_i18n::t;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::Deref;
 use std::path::Path;
 
@@ -64,6 +65,89 @@ impl JsonSchema for ThemeName {
     }
 }
 
+/// Whether the terminal or OS uses a light or dark color scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Appearance {
+    Light,
+    #[default]
+    Dark,
+}
+
+/// The `theme` setting: a theme name, or a light/dark pair picked to match
+/// the terminal background or the OS dark-mode setting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ThemeSetting {
+    Named(ThemeName),
+    ByAppearance { light: ThemeName, dark: ThemeName },
+}
+
+impl ThemeSetting {
+    /// Theme to use for the given appearance
+    pub fn resolve(&self, appearance: Appearance) -> &ThemeName {
+        match self {
+            Self::Named(name) => name,
+            Self::ByAppearance { light, .. } if appearance == Appearance::Light => light,
+            Self::ByAppearance { dark, .. } => dark,
+        }
+    }
+
+    /// Whether the theme changes with the appearance
+    pub fn follows_appearance(&self) -> bool {
+        matches!(self, Self::ByAppearance { .. })
+    }
+
+    /// Use `name` for the given appearance, keeping the other theme of a pair
+    pub fn select(&mut self, name: ThemeName, appearance: Appearance) {
+        match self {
+            Self::Named(current) => *current = name,
+            Self::ByAppearance { light, .. } if appearance == Appearance::Light => *light = name,
+            Self::ByAppearance { dark, .. } => *dark = name,
+        }
+    }
+}
+
+impl From<ThemeName> for ThemeSetting {
+    fn from(name: ThemeName) -> Self {
+        Self::Named(name)
+    }
+}
+
+impl From<String> for ThemeSetting {
+    fn from(s: String) -> Self {
+        Self::Named(s.into())
+    }
+}
+
+impl From<&str> for ThemeSetting {
+    fn from(s: &str) -> Self {
+        Self::Named(s.into())
+    }
+}
+
+impl std::fmt::Display for ThemeSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Named(name) => write!(f, "{}", name.0),
+            Self::ByAppearance { light, dark } => {
+                write!(f, "{} (light) / {} (dark)", light.0, dark.0)
+            }
+        }
+    }
+}
+
+impl JsonSchema for ThemeSetting {
+    fn schema_name() -> Cow<'static, str> {
+        ThemeName::schema_name()
+    }
+
+    // The settings dropdown only offers single themes; pairs are set in
+    // the config file
+    fn json_schema(gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        ThemeName::json_schema(gen)
+    }
+}
+
 /// Newtype for locale name that generates proper JSON Schema with enum options
 /// Wraps Option<String> to allow null for auto-detection from environment
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -327,6 +411,186 @@ impl JsonSchema for AcceptSuggestionOnEnter {
     }
 }
 
+/// Which spaces are drawn as `·` (and non-breaking spaces as `␣`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderWhitespace {
+    /// Spaces are not drawn
+    #[default]
+    None,
+    /// Indentation, trailing spaces and runs of spaces, but not single
+    /// spaces between words
+    Boundary,
+    /// Spaces inside the selection
+    Selection,
+    /// Spaces at the end of lines
+    Trailing,
+    /// Every space
+    All,
+}
+
+impl RenderWhitespace {
+    /// The next mode, cycling back to `None` after `All`
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Boundary,
+            Self::Boundary => Self::Selection,
+            Self::Selection => Self::Trailing,
+            Self::Trailing => Self::All,
+            Self::All => Self::None,
+        }
+    }
+
+    /// The mode's name as written in the config
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Boundary => "boundary",
+            Self::Selection => "selection",
+            Self::Trailing => "trailing",
+            Self::All => "all",
+        }
+    }
+}
+
+impl JsonSchema for RenderWhitespace {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("RenderWhitespace")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Which spaces are drawn as visible indicators",
+            "type": "string",
+            "enum": ["none", "boundary", "selection", "trailing", "all"],
+            "default": "none"
+        })
+    }
+}
+
+/// Display width of East Asian "ambiguous" characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AmbiguousWidth {
+    /// One column, as most Western terminals draw them
+    #[default]
+    Narrow,
+    /// Two columns, as terminals set up for CJK locales draw them
+    Wide,
+}
+
+impl JsonSchema for AmbiguousWidth {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("AmbiguousWidth")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Display width of East Asian \"ambiguous\" characters",
+            "type": "string",
+            "enum": ["narrow", "wide"],
+            "default": "narrow"
+        })
+    }
+}
+
+/// When modified buffers are written to disk automatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AutoSaveMode {
+    /// Buffers are only saved explicitly
+    #[default]
+    Off,
+    /// Save a buffer once it has not been edited for `auto_save_delay_ms`
+    AfterDelay,
+    /// Save a buffer when switching away from it to another buffer or split
+    OnFocusChange,
+}
+
+impl JsonSchema for AutoSaveMode {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("AutoSaveMode")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "When modified buffers are written to disk automatically",
+            "type": "string",
+            "enum": ["off", "afterDelay", "onFocusChange"],
+            "default": "off"
+        })
+    }
+}
+
+/// What opening a symlinked file edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenSymlinks {
+    /// Open the file the link points to
+    #[default]
+    Target,
+    /// Keep the link's path for the buffer
+    Link,
+    /// Ask each time a link is opened from the file explorer or Open File
+    Ask,
+}
+
+impl JsonSchema for OpenSymlinks {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("OpenSymlinks")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "What opening a symlinked file edits",
+            "type": "string",
+            "enum": ["target", "link", "ask"],
+            "default": "target"
+        })
+    }
+}
+
+/// What a session server does when it has been idle for `idle_timeout_secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionIdleAction {
+    /// Shut down and forget the session layout
+    #[default]
+    Exit,
+    /// Save the session to disk and shut down; attaching resumes it
+    Suspend,
+}
+
+impl std::str::FromStr for SessionIdleAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exit" => Ok(Self::Exit),
+            "suspend" => Ok(Self::Suspend),
+            _ => Err(format!(
+                "invalid idle action '{}' (expected 'exit' or 'suspend')",
+                s
+            )),
+        }
+    }
+}
+
+impl JsonSchema for SessionIdleAction {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("SessionIdleAction")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "What a session server does when it has been idle for idle_timeout_secs",
+            "type": "string",
+            "enum": ["exit", "suspend"],
+            "default": "exit"
+        })
+    }
+}
+
 impl PartialEq<KeybindingMapName> for str {
     fn eq(&self, other: &KeybindingMapName) -> bool {
         self == other.0
@@ -357,7 +621,7 @@ pub struct Config {
 
     /// Color theme name
     #[serde(default = "default_theme_name")]
-    pub theme: ThemeName,
+    pub theme: ThemeSetting,
 
     /// UI locale (language) for translations
     /// If not set, auto-detected from environment (LC_ALL, LC_MESSAGES, LANG)
@@ -365,10 +629,27 @@ pub struct Config {
     pub locale: LocaleName,
 
     /// Check for new versions on startup (default: true).
-    /// When enabled, also sends basic anonymous telemetry (version, OS, terminal type).
+    /// When enabled, also sends the anonymous telemetry chosen in `telemetry`.
     #[serde(default = "default_true")]
     pub check_for_updates: bool,
 
+    /// Anonymous telemetry sent with the daily update check
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+
+    /// Releases offered by the update check and **Update Fresh Now**:
+    /// "stable" (default), "beta" (also beta and release candidate builds) or
+    /// "nightly" (every published build)
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+
+    /// Let **Update Fresh Now** install a release whose build attestation
+    /// can't be verified because the GitHub CLI is missing or not logged in
+    /// (default: false). The SHA-256 checksum is published in the same
+    /// release as the archive, so on its own it doesn't show who built it.
+    #[serde(default)]
+    pub allow_unverified_updates: bool,
+
     /// Editor behavior settings (indentation, line numbers, wrapping, etc.)
     #[serde(default)]
     pub editor: EditorConfig,
@@ -410,6 +691,18 @@ pub struct Config {
     #[serde(default)]
     pub warnings: WarningsConfig,
 
+    /// Status line layout (which segments are shown, where, and how they are styled)
+    #[serde(default)]
+    pub status_line: StatusLineConfig,
+
+    /// Session server settings (`fresh -a`)
+    #[serde(default)]
+    pub session_server: SessionServerConfig,
+
+    /// Accessibility mode (spoken descriptions of the cursor, status and menus)
+    #[serde(default)]
+    pub accessibility: AccessibilityConfig,
+
     /// Plugin configurations by plugin name
     /// Plugins are auto-discovered from the plugins directory.
     /// Use this to enable/disable specific plugins.
@@ -421,6 +714,11 @@ pub struct Config {
     #[serde(default)]
     pub packages: PackagesConfig,
 
+    /// Settings scoped to a language (`"[rust]"`) or to files matching a glob
+    /// (`"files:**/*.md"`), applied per buffer on top of `editor` and `languages`
+    #[serde(flatten, default)]
+    pub overrides: ScopedOverrides,
+
     /// Automatically enable modal editing mode on startup
     /// Options: "none" (default), "vi", "helix"
     /// When set to "vi" or "helix", the corresponding modal editing plugin
@@ -443,8 +741,8 @@ fn default_keybinding_map_name() -> KeybindingMapName {
     }
 }
 
-fn default_theme_name() -> ThemeName {
-    ThemeName("high-contrast".to_string())
+fn default_theme_name() -> ThemeSetting {
+    ThemeSetting::from("high-contrast")
 }
 
 /// Editor behavior configuration
@@ -466,6 +764,28 @@ pub struct EditorConfig {
     #[schemars(extend("x-section" = "Display"))]
     pub line_wrap: bool,
 
+    /// Columns at which to draw vertical ruler lines (e.g. [80, 120]).
+    /// Default: []
+    #[serde(default)]
+    #[schemars(extend("x-section" = "Display"))]
+    pub rulers: Vec<usize>,
+
+    /// Shade the text area past the last ruler in the theme's wrap guide
+    /// color, so lines running over it stand out.
+    /// Default: false
+    #[serde(default = "default_false")]
+    #[schemars(extend("x-section" = "Display"))]
+    pub wrap_guide_shading: bool,
+
+    /// Draw spaces as `·` and non-breaking spaces as `␣`, in the theme's
+    /// whitespace color: "none", "boundary" (indentation, trailing spaces and
+    /// runs of spaces), "selection" (inside the selection), "trailing" or
+    /// "all". Control characters are always shown in caret notation (`^M`).
+    /// Default: none
+    #[serde(default)]
+    #[schemars(extend("x-section" = "Display"))]
+    pub render_whitespace: RenderWhitespace,
+
     /// Enable syntax highlighting for code files
     #[serde(default = "default_true")]
     #[schemars(extend("x-section" = "Display"))]
@@ -487,6 +807,15 @@ pub struct EditorConfig {
     #[schemars(extend("x-section" = "Display"))]
     pub show_tab_bar: bool,
 
+    /// Open files single-clicked in the file explorer in a preview tab.
+    /// The preview tab is shown in italics and replaced by the next preview;
+    /// it is kept once edited or opened with Enter or a double-click.
+    /// Can be toggled at runtime via command palette.
+    /// Default: false
+    #[serde(default = "default_false")]
+    #[schemars(extend("x-section" = "Display"))]
+    pub preview_tabs: bool,
+
     /// Use the terminal's default background color instead of the theme's editor background.
     /// When enabled, the editor background inherits from the terminal emulator,
     /// allowing transparency or custom terminal backgrounds to show through.
@@ -502,6 +831,60 @@ pub struct EditorConfig {
     #[schemars(extend("x-section" = "Display"))]
     pub cursor_style: CursorStyle,
 
+    /// Cursor style per editor mode, used instead of `cursor_style` while the
+    /// mode is active. Keys are mode names such as "vi-insert"; a key ending
+    /// in `*` matches every mode starting with the rest, and the longest
+    /// match wins. Entries are added to the defaults; map a mode to
+    /// "default" to use the terminal's own cursor there.
+    /// Default: block in vi modes, bar in vi-insert, underline in vi-replace
+    #[serde(default = "default_mode_cursor_styles")]
+    #[schemars(extend("x-section" = "Display"))]
+    pub mode_cursor_styles: HashMap<String, CursorStyle>,
+
+    /// Maximum width of the text in columns, not counting line numbers.
+    /// Panes wider than this show the text centered between margins, as in
+    /// compose mode. Terminals and other virtual buffers use the full width.
+    /// Default: 0 (use the full width)
+    #[serde(default)]
+    #[schemars(extend("x-section" = "Display"))]
+    pub max_content_width: u16,
+
+    /// Maximum text width in columns while focus mode is on.
+    /// Text is centered in the window and wrapped at this width.
+    /// Default: 80
+    #[serde(default = "default_focus_mode_width")]
+    #[schemars(extend("x-section" = "Display"))]
+    pub focus_mode_width: u16,
+
+    /// Keep the cursor line vertically centered while focus mode is on
+    /// (typewriter scrolling).
+    /// Default: true
+    #[serde(default = "default_true")]
+    #[schemars(extend("x-section" = "Display"))]
+    pub focus_mode_typewriter_scrolling: bool,
+
+    /// Dim every paragraph except the one containing the cursor while focus mode is on.
+    /// Default: true
+    #[serde(default = "default_true")]
+    #[schemars(extend("x-section" = "Display"))]
+    pub focus_mode_dim_paragraphs: bool,
+
+    /// Animate page scrolling, cursor jumps and the file explorer opening and closing.
+    /// Disable for instant updates, e.g. over slow remote connections.
+    /// Default: true
+    #[serde(default = "default_true")]
+    #[schemars(extend("x-section" = "Display"))]
+    pub animations: bool,
+
+    /// Width of East Asian "ambiguous" characters such as `±`, `①`, `Ω` or `…`.
+    /// "narrow" counts them as one column; use "wide" when the terminal draws
+    /// them two columns wide (common with CJK locales and fonts), so the cursor
+    /// and wrapping line up with the text.
+    /// Default: narrow
+    #[serde(default)]
+    #[schemars(extend("x-section" = "Display"))]
+    pub ambiguous_width: AmbiguousWidth,
+
     // ===== Editing =====
     /// Number of spaces per tab character
     #[serde(default = "default_tab_size")]
@@ -513,6 +896,13 @@ pub struct EditorConfig {
     #[schemars(extend("x-section" = "Editing"))]
     pub auto_indent: bool,
 
+    /// Detect whether a file is indented with tabs or spaces, and the indent
+    /// width, when it is opened. Language and glob scopes that set
+    /// `use_tabs` or `tab_size` take precedence.
+    #[serde(default = "default_true")]
+    #[schemars(extend("x-section" = "Editing"))]
+    pub detect_indentation: bool,
+
     /// Minimum lines to keep visible above/below cursor when scrolling
     #[serde(default = "default_scroll_offset")]
     #[schemars(extend("x-section" = "Editing"))]
@@ -538,6 +928,31 @@ pub struct EditorConfig {
     #[schemars(extend("x-section" = "Editing"))]
     pub ensure_final_newline_on_save: bool,
 
+    /// What opening a symlink to a file edits.
+    /// - "target": the file the link points to; the buffer shows its path
+    /// - "link": the file under the link's path; saves still write the target
+    /// - "ask": ask when a link is opened from the file explorer or Open File
+    /// Default: "target"
+    #[serde(default)]
+    #[schemars(extend("x-section" = "Editing"))]
+    pub open_symlinks: OpenSymlinks,
+
+    /// Open files read-only when you have no permission to write them, or
+    /// when they are marked as generated (`@generated`, "DO NOT EDIT").
+    /// Toggle Read-Only makes such a buffer editable again.
+    /// Default: true
+    #[serde(default = "default_true")]
+    #[schemars(extend("x-section" = "Editing"))]
+    pub auto_read_only: bool,
+
+    /// Command Toggle Watch Task offers to re-run on every save, e.g.
+    /// `cargo check --message-format=short`. Problems it reports are shown
+    /// as diagnostics.
+    /// Default: ""
+    #[serde(default)]
+    #[schemars(extend("x-section" = "Editing"))]
+    pub watch_command: String,
+
     // ===== Bracket Matching =====
     /// Highlight matching bracket pairs when cursor is on a bracket.
     /// Default: true
@@ -586,6 +1001,14 @@ pub struct EditorConfig {
     #[schemars(extend("x-section" = "Completion"))]
     pub accept_suggestion_on_enter: AcceptSuggestionOnEnter,
 
+    /// Complete words from the open buffers (and from a ctags `tags` file in
+    /// the project root, if there is one) when no language server provides
+    /// completions. Words near the cursor are offered first.
+    /// Default: true
+    #[serde(default = "default_true")]
+    #[schemars(extend("x-section" = "Completion"))]
+    pub word_completion: bool,
+
     // ===== LSP =====
     /// Whether to enable LSP inlay hints (type hints, parameter hints, etc.)
     #[serde(default = "default_true")]
@@ -599,6 +1022,20 @@ pub struct EditorConfig {
     #[schemars(extend("x-section" = "LSP"))]
     pub enable_semantic_tokens_full: bool,
 
+    /// Whether to ask the LSP server for occurrences of the symbol under the cursor.
+    /// When the server supports it, these replace the word-based occurrence highlight.
+    /// Default: true
+    #[serde(default = "default_true")]
+    #[schemars(extend("x-section" = "LSP"))]
+    pub enable_document_highlight: bool,
+
+    /// Whether to show LSP code lenses (e.g. "3 references | Run | Debug")
+    /// as a line above the code they refer to.
+    /// Default: false
+    #[serde(default = "default_false")]
+    #[schemars(extend("x-section" = "LSP"))]
+    pub enable_code_lens: bool,
+
     // ===== Mouse =====
     /// Whether mouse hover triggers LSP hover requests.
     /// When enabled, hovering over code with the mouse will show documentation.
@@ -621,6 +1058,45 @@ pub struct EditorConfig {
     #[schemars(extend("x-section" = "Mouse"))]
     pub double_click_time_ms: u64,
 
+    /// Number of lines scrolled by one mouse wheel notch.
+    /// Default: 3
+    #[serde(default = "default_mouse_scroll_lines")]
+    #[schemars(extend("x-section" = "Mouse"))]
+    pub mouse_scroll_lines: usize,
+
+    /// Scroll further when wheel notches arrive in quick succession.
+    /// Each rapid notch adds to the scroll distance, up to 4x `mouse_scroll_lines`.
+    /// Default: false
+    #[serde(default)]
+    #[schemars(extend("x-section" = "Mouse"))]
+    pub mouse_scroll_acceleration: bool,
+
+    // ===== Auto Save =====
+    /// When to save modified buffers to their files automatically.
+    /// - "off": only save explicitly
+    /// - "afterDelay": save once a buffer has not been edited for `auto_save_delay_ms`
+    /// - "onFocusChange": save when switching to another buffer or split
+    ///
+    /// Unnamed, read-only and excluded buffers are never auto-saved.
+    /// Default: "off"
+    #[serde(default)]
+    #[schemars(extend("x-section" = "Auto Save"))]
+    pub auto_save: AutoSaveMode,
+
+    /// Idle time in milliseconds after the last edit before a buffer is saved
+    /// when `auto_save` is "afterDelay". Each buffer is debounced separately.
+    /// Default: 1000ms
+    #[serde(default = "default_auto_save_delay")]
+    #[schemars(extend("x-section" = "Auto Save"))]
+    pub auto_save_delay_ms: u64,
+
+    /// Glob patterns for files that are never auto-saved (e.g. "*.env", "/etc/**").
+    /// Patterns without a `/` match the file name, others match the full path.
+    /// Default: []
+    #[serde(default)]
+    #[schemars(extend("x-section" = "Auto Save"))]
+    pub auto_save_exclude: Vec<String>,
+
     // ===== Recovery =====
     /// Whether to enable file recovery (Emacs-style auto-save)
     /// When enabled, buffers are periodically saved to recovery files
@@ -645,6 +1121,27 @@ pub struct EditorConfig {
     #[schemars(extend("x-section" = "Recovery"))]
     pub auto_revert_poll_interval_ms: u64,
 
+    /// Keep a copy of every saved file in the data directory, so earlier
+    /// versions can be compared and restored with **Local History**.
+    /// Files of at least `large_file_threshold_bytes` and remote files are skipped.
+    /// Default: true
+    #[serde(default = "default_true")]
+    #[schemars(extend("x-section" = "Recovery"))]
+    pub local_history: bool,
+
+    /// Days a saved version stays in the local history
+    /// Default: 7
+    #[serde(default = "default_local_history_days")]
+    #[schemars(extend("x-section" = "Recovery"))]
+    pub local_history_days: u64,
+
+    /// Megabytes the local history may take for all files together.
+    /// The oldest versions are deleted first.
+    /// Default: 100
+    #[serde(default = "default_local_history_max_mb")]
+    #[schemars(extend("x-section" = "Recovery"))]
+    pub local_history_max_mb: u64,
+
     // ===== Keyboard =====
     /// Enable keyboard enhancement: disambiguate escape codes using CSI-u sequences.
     /// This allows unambiguous reading of Escape and modified keys.
@@ -708,6 +1205,14 @@ pub struct EditorConfig {
     #[schemars(extend("x-section" = "Performance"))]
     pub large_file_threshold_bytes: u64,
 
+    /// Open and save files of at least `large_file_threshold_bytes` on a
+    /// background thread so the editor stays responsive. Edits made to a
+    /// buffer while it is being saved are applied once the save finishes.
+    /// Default: true
+    #[serde(default = "default_true")]
+    #[schemars(extend("x-section" = "Performance"))]
+    pub background_file_io: bool,
+
     /// Estimated average line length in bytes (used for large file line estimation)
     /// This is used by LineIterator to estimate line positions in large files
     /// without line metadata. Typical values: 80-120 bytes.
@@ -722,6 +1227,31 @@ pub struct EditorConfig {
     #[serde(default = "default_file_tree_poll_interval")]
     #[schemars(extend("x-section" = "Performance"))]
     pub file_tree_poll_interval_ms: u64,
+
+    /// Index the workspace's files and symbols in a background thread.
+    /// Quick Open lists files from the index and searches its symbols with `@`.
+    /// The index is kept in the data directory and updated as files change.
+    /// Default: true
+    #[serde(default = "default_true")]
+    #[schemars(extend("x-section" = "Performance"))]
+    pub workspace_index: bool,
+
+    /// Warn in the status bar when the editor's estimated memory use (as
+    /// listed by **Developer: Memory Report**) goes above this many megabytes.
+    /// 0 disables the warning.
+    /// Default: 2048
+    #[serde(default = "default_memory_warning_threshold")]
+    #[schemars(extend("x-section" = "Performance"))]
+    pub memory_warning_threshold_mb: u64,
+
+    // ===== Plugins =====
+    /// Reload plugins automatically when their source files change.
+    /// Plugin files (and the local modules they import) are checked at the
+    /// auto-revert poll interval. Meant for plugin development.
+    /// Default: false
+    #[serde(default = "default_false")]
+    #[schemars(extend("x-section" = "Plugins"))]
+    pub plugin_hot_reload: bool,
 }
 
 fn default_tab_size() -> usize {
@@ -753,10 +1283,18 @@ fn default_accept_suggestion_on_enter() -> AcceptSuggestionOnEnter {
     AcceptSuggestionOnEnter::On
 }
 
+fn default_auto_save_delay() -> u64 {
+    1000
+}
+
 fn default_scroll_offset() -> usize {
     3
 }
 
+fn default_focus_mode_width() -> u16 {
+    80
+}
+
 fn default_highlight_timeout() -> u64 {
     5
 }
@@ -773,6 +1311,14 @@ fn default_auto_save_interval() -> u32 {
     2 // Auto-save every 2 seconds for fast recovery
 }
 
+fn default_local_history_days() -> u64 {
+    7
+}
+
+fn default_local_history_max_mb() -> u64 {
+    100
+}
+
 fn default_highlight_context_bytes() -> usize {
     10_000 // 10KB context for accurate syntax highlighting
 }
@@ -785,6 +1331,10 @@ fn default_double_click_time() -> u64 {
     500 // 500ms window for detecting double-clicks
 }
 
+fn default_mouse_scroll_lines() -> usize {
+    3
+}
+
 fn default_auto_revert_poll_interval() -> u64 {
     2000 // 2 seconds between file mtime checks
 }
@@ -793,36 +1343,72 @@ fn default_file_tree_poll_interval() -> u64 {
     3000 // 3 seconds between directory mtime checks
 }
 
+fn default_memory_warning_threshold() -> u64 {
+    2048
+}
+
+fn default_mode_cursor_styles() -> HashMap<String, CursorStyle> {
+    HashMap::from([
+        ("vi-*".to_string(), CursorStyle::BlinkingBlock),
+        ("vi-insert".to_string(), CursorStyle::BlinkingBar),
+        ("vi-replace".to_string(), CursorStyle::BlinkingUnderline),
+    ])
+}
+
 impl Default for EditorConfig {
     fn default() -> Self {
         Self {
             tab_size: default_tab_size(),
             auto_indent: true,
+            detect_indentation: true,
             line_numbers: true,
             relative_line_numbers: false,
             scroll_offset: default_scroll_offset(),
             syntax_highlighting: true,
             line_wrap: true,
+            rulers: Vec::new(),
+            wrap_guide_shading: false,
+            render_whitespace: RenderWhitespace::default(),
             highlight_timeout_ms: default_highlight_timeout(),
             snapshot_interval: default_snapshot_interval(),
             large_file_threshold_bytes: default_large_file_threshold(),
             estimated_line_length: default_estimated_line_length(),
             enable_inlay_hints: true,
             enable_semantic_tokens_full: false,
+            enable_document_highlight: true,
+            enable_code_lens: false,
             recovery_enabled: true,
             auto_save_interval_secs: default_auto_save_interval(),
+            local_history: true,
+            local_history_days: default_local_history_days(),
+            local_history_max_mb: default_local_history_max_mb(),
             highlight_context_bytes: default_highlight_context_bytes(),
             mouse_hover_enabled: true,
             mouse_hover_delay_ms: default_mouse_hover_delay(),
             double_click_time_ms: default_double_click_time(),
+            mouse_scroll_lines: default_mouse_scroll_lines(),
+            mouse_scroll_acceleration: false,
             auto_revert_poll_interval_ms: default_auto_revert_poll_interval(),
             file_tree_poll_interval_ms: default_file_tree_poll_interval(),
+            workspace_index: true,
+            memory_warning_threshold_mb: default_memory_warning_threshold(),
+            background_file_io: true,
             default_line_ending: LineEndingOption::default(),
             trim_trailing_whitespace_on_save: false,
             ensure_final_newline_on_save: false,
+            open_symlinks: OpenSymlinks::default(),
+            auto_read_only: true,
+            watch_command: String::new(),
             highlight_matching_brackets: true,
             rainbow_brackets: true,
             cursor_style: CursorStyle::default(),
+            mode_cursor_styles: default_mode_cursor_styles(),
+            max_content_width: 0,
+            focus_mode_width: default_focus_mode_width(),
+            focus_mode_typewriter_scrolling: true,
+            focus_mode_dim_paragraphs: true,
+            animations: true,
+            ambiguous_width: AmbiguousWidth::default(),
             keyboard_disambiguate_escape_codes: true,
             keyboard_report_event_types: false,
             keyboard_report_alternate_keys: true,
@@ -831,10 +1417,36 @@ impl Default for EditorConfig {
             quick_suggestions_delay_ms: default_quick_suggestions_delay(),
             suggest_on_trigger_characters: true,
             accept_suggestion_on_enter: default_accept_suggestion_on_enter(),
+            word_completion: true,
+            auto_save: AutoSaveMode::default(),
+            auto_save_delay_ms: default_auto_save_delay(),
+            auto_save_exclude: Vec::new(),
             show_menu_bar: true,
             show_tab_bar: true,
+            preview_tabs: false,
             use_terminal_bg: false,
+            plugin_hot_reload: false,
+        }
+    }
+}
+
+impl EditorConfig {
+    /// The cursor style while `mode` is the active editor mode
+    pub fn cursor_style_for_mode(&self, mode: Option<&str>) -> CursorStyle {
+        let Some(mode) = mode else {
+            return self.cursor_style;
+        };
+        if let Some(style) = self.mode_cursor_styles.get(mode) {
+            return *style;
         }
+        self.mode_cursor_styles
+            .iter()
+            .filter_map(|(key, style)| {
+                let prefix = key.strip_suffix('*')?;
+                mode.starts_with(prefix).then_some((prefix.len(), *style))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map_or(self.cursor_style, |(_, style)| style)
     }
 }
 
@@ -922,6 +1534,214 @@ impl Default for WarningsConfig {
     }
 }
 
+/// Session server configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SessionServerConfig {
+    /// Seconds without input after which a session server shuts down
+    /// (default: 3600, 0 = never). Attached clients see a countdown first.
+    #[serde(default = "default_session_idle_timeout")]
+    pub idle_timeout_secs: u64,
+
+    /// What to do when the idle timeout is reached: "exit" (default) or
+    /// "suspend", which saves the session to disk so attaching resumes it
+    #[serde(default)]
+    pub idle_action: SessionIdleAction,
+
+    /// Echo typed characters in attached clients before the server answers,
+    /// when the connection is slow (default: true)
+    #[serde(default = "default_true")]
+    pub predictive_echo: bool,
+
+    /// Ask the server to compress screen output with zstd (default: false).
+    /// Worth enabling when the session socket is forwarded over a network.
+    #[serde(default)]
+    pub compress_output: bool,
+}
+
+fn default_session_idle_timeout() -> u64 {
+    3600
+}
+
+impl Default for SessionServerConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout_secs: default_session_idle_timeout(),
+            idle_action: SessionIdleAction::default(),
+            predictive_echo: true,
+            compress_output: false,
+        }
+    }
+}
+
+/// Anonymous telemetry configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TelemetryConfig {
+    /// What is collected: "features" (default) sends the daily usage ping
+    /// (version, OS, terminal type) and crash reports, "crashes" only crash
+    /// reports, "none" nothing. Nothing is sent when `check_for_updates` is off.
+    #[serde(default)]
+    pub level: TelemetryLevel,
+
+    /// Append events to `telemetry.jsonl` in the data directory instead of
+    /// sending them (default: false)
+    #[serde(default)]
+    pub local_only: bool,
+}
+
+/// How much anonymous telemetry is collected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TelemetryLevel {
+    /// Nothing
+    None,
+    /// Crash reports (version, OS and where the crash happened)
+    Crashes,
+    /// Crash reports and the daily usage ping
+    #[default]
+    Features,
+}
+
+impl TelemetryLevel {
+    pub fn sends_crashes(self) -> bool {
+        self != Self::None
+    }
+
+    pub fn sends_usage(self) -> bool {
+        self == Self::Features
+    }
+}
+
+impl JsonSchema for TelemetryLevel {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("TelemetryLevel")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "How much anonymous telemetry is collected",
+            "type": "string",
+            "enum": ["none", "crashes", "features"],
+            "default": "features"
+        })
+    }
+}
+
+/// Which releases the update check offers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// Releases only
+    #[default]
+    Stable,
+    /// Releases, beta and release candidate builds (`-beta.N`, `-rc.N`)
+    Beta,
+    /// Every published build, including nightly builds
+    Nightly,
+}
+
+impl UpdateChannel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+            Self::Nightly => "nightly",
+        }
+    }
+}
+
+impl JsonSchema for UpdateChannel {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("UpdateChannel")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Which releases the update check offers",
+            "type": "string",
+            "enum": ["stable", "beta", "nightly"],
+            "default": "stable"
+        })
+    }
+}
+
+/// Accessibility mode configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AccessibilityConfig {
+    /// Describe the cursor context, status messages and prompt, menu and
+    /// popup navigation through the speech command (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Command that speaks an announcement, which is passed as its last
+    /// argument, e.g. `["espeak-ng", "-s", "220"]`. When unset, uses
+    /// speech-dispatcher (`spd-say`) on Linux and `say` on macOS; an empty
+    /// list turns speech off.
+    #[serde(default)]
+    pub command: Option<Vec<String>>,
+}
+
+impl AccessibilityConfig {
+    /// The speech command to run, with the platform default filled in
+    pub fn speech_command(&self) -> Vec<String> {
+        self.command.clone().unwrap_or_else(default_speech_command)
+    }
+}
+
+fn default_speech_command() -> Vec<String> {
+    if cfg!(target_os = "macos") {
+        vec!["say".to_string()]
+    } else if cfg!(unix) {
+        vec!["spd-say".to_string()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Status line layout configuration
+///
+/// Each list holds segment specs, rendered in order. A spec is a segment name
+/// optionally followed by style options in parentheses, for example
+/// `"position"` or `"git_branch(fg=syntax.keyword, bold)"`. Colors are theme
+/// keys such as `ui.status_bar_fg` or `diagnostic.error_fg`.
+///
+/// Segments: `mode`, `session`, `remote`, `filename`, `position`,
+/// `diagnostics`, `cursors`, `auto_save`, `tasks`, `chord`, `messages`,
+/// `git_branch`, `lsp`, `warnings`, `line_ending`, `encoding`, `indentation`,
+/// `language`, `update`, `palette`, and `plugin:<id>` for text published by
+/// plugins.
+///
+/// When all three lists are empty, the built-in layout is used.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StatusLineConfig {
+    /// Segments aligned to the left edge
+    #[serde(default)]
+    pub left: Vec<String>,
+
+    /// Segments centered in the space between the left and right groups
+    #[serde(default)]
+    pub center: Vec<String>,
+
+    /// Segments aligned to the right edge
+    #[serde(default)]
+    pub right: Vec<String>,
+}
+
+impl StatusLineConfig {
+    /// Whether a custom layout is configured (otherwise the built-in one is used)
+    pub fn is_custom(&self) -> bool {
+        !(self.left.is_empty() && self.center.is_empty() && self.right.is_empty())
+    }
+
+    /// All segment specs, left to right
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.left
+            .iter()
+            .chain(&self.center)
+            .chain(&self.right)
+            .map(String::as_str)
+    }
+}
+
 /// Package manager configuration for plugins and themes
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PackagesConfig {
@@ -980,7 +1800,8 @@ pub struct KeyPress {
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[schemars(extend("x-display-field" = "/action"))]
 pub struct Keybinding {
-    /// Key name (e.g., "a", "Enter", "F1") - for single-key bindings
+    /// Key name (e.g., "a", "Enter", "F1"), optionally with modifiers ("ctrl+shift+k").
+    /// Space-separated keys form a chord (e.g., "ctrl+k ctrl+c")
     #[serde(default, skip_serializing_if = "String::is_empty")]
     pub key: String,
 
@@ -1000,7 +1821,8 @@ pub struct Keybinding {
     #[serde(default)]
     pub args: HashMap<String, serde_json::Value>,
 
-    /// Optional condition (e.g., "mode == insert")
+    /// Context the binding applies in ("normal", "prompt", "popup", ...) or a condition
+    /// (e.g., "editorFocus && !popupVisible && buffer_mode == 'vi-normal'")
     #[serde(default)]
     pub when: Option<String>,
 }
@@ -1040,6 +1862,27 @@ pub struct FormatterConfig {
     pub timeout_ms: u64,
 }
 
+/// Indentation rules for a language
+///
+/// The patterns are regular expressions matched against a whole line.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct IndentRulesConfig {
+    /// The line after a line matching this is indented one more level
+    /// (e.g., "^\\s*(def|class|if|else|do)\\b")
+    #[serde(default)]
+    pub increase_pattern: Option<String>,
+
+    /// A line matching this moves out one level as it is typed
+    /// (e.g., "^\\s*(end|else)\\b")
+    #[serde(default)]
+    pub decrease_pattern: Option<String>,
+
+    /// Tree-sitter indents.scm query (@indent and @dedent captures) to use
+    /// instead of the built-in one for the language's grammar
+    #[serde(default)]
+    pub query: Option<std::path::PathBuf>,
+}
+
 /// Action to run when a file is saved (for linters, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[schemars(extend("x-display-field" = "/command"))]
@@ -1095,10 +1938,22 @@ pub struct LanguageConfig {
     #[serde(default)]
     pub comment_prefix: Option<String>,
 
+    /// Block comment start marker (e.g., "/*")
+    #[serde(default)]
+    pub block_comment_start: Option<String>,
+
+    /// Block comment end marker (e.g., "*/")
+    #[serde(default)]
+    pub block_comment_end: Option<String>,
+
     /// Whether to auto-indent
     #[serde(default = "default_true")]
     pub auto_indent: bool,
 
+    /// Indentation rules used by auto-indent instead of the built-in ones
+    #[serde(default)]
+    pub indent_rules: Option<IndentRulesConfig>,
+
     /// Preferred highlighter backend (auto, tree-sitter, or textmate)
     #[serde(default)]
     pub highlighter: HighlighterPreference,
@@ -1137,6 +1992,182 @@ pub struct LanguageConfig {
     /// Note: Use `formatter` + `format_on_save` for formatting, not on_save
     #[serde(default)]
     pub on_save: Vec<OnSaveAction>,
+
+    /// Additional LSP servers for this language, by their key in `lsp`
+    /// (e.g. a Tailwind CSS server for templates). They run alongside the
+    /// language's own server; requests go to whichever server supports them.
+    #[serde(default)]
+    pub lsp_servers: Vec<String>,
+}
+
+/// Settings that can be scoped to a language or a file glob
+///
+/// Unset fields fall through to the less specific scope.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ScopedSettings {
+    /// Number of spaces per tab character
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tab_size: Option<usize>,
+
+    /// Insert a tab character instead of spaces when pressing Tab
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_tabs: Option<bool>,
+
+    /// Wrap long lines to fit the window width
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_wrap: Option<bool>,
+
+    /// Columns at which to draw vertical ruler lines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rulers: Option<Vec<usize>>,
+
+    /// Shade the text area past the last ruler
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrap_guide_shading: Option<bool>,
+
+    /// Run the language's formatter on save
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format_on_save: Option<bool>,
+
+    /// Remove trailing whitespace from all lines on save
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trim_trailing_whitespace_on_save: Option<bool>,
+
+    /// Ensure the file ends with a newline on save
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ensure_final_newline_on_save: Option<bool>,
+}
+
+/// Language- and glob-scoped settings, keyed by scope
+///
+/// Keys are `"[<language>]"` (e.g. `"[rust]"`) or `"files:<glob>"` (e.g.
+/// `"files:**/*.md"`). The map is flattened into the top level of the config,
+/// so other unknown top-level keys are ignored as before.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct ScopedOverrides(pub BTreeMap<String, ScopedSettings>);
+
+/// Prefix of glob-scoped override keys
+const FILES_SCOPE_PREFIX: &str = "files:";
+
+impl ScopedOverrides {
+    /// Whether `key` names a language or glob scope
+    pub fn is_scope_key(key: &str) -> bool {
+        Self::language_scope(key).is_some() || Self::files_scope(key).is_some()
+    }
+
+    /// Language of a `"[<language>]"` key
+    fn language_scope(key: &str) -> Option<&str> {
+        key.strip_prefix('[')?
+            .strip_suffix(']')
+            .filter(|language| !language.is_empty())
+    }
+
+    /// Glob of a `"files:<glob>"` key
+    fn files_scope(key: &str) -> Option<&str> {
+        key.strip_prefix(FILES_SCOPE_PREFIX)
+            .filter(|glob| !glob.is_empty())
+    }
+
+    /// Combine the scopes that apply to a buffer
+    ///
+    /// Matching globs take precedence over the language scope, and longer
+    /// globs over shorter ones. Globs are matched against the path as given
+    /// and relative to `working_dir`.
+    pub fn for_buffer(
+        &self,
+        language_id: Option<&str>,
+        path: Option<&Path>,
+        working_dir: Option<&Path>,
+    ) -> ScopedSettings {
+        let relative = path
+            .zip(working_dir)
+            .and_then(|(p, dir)| p.strip_prefix(dir).ok());
+        let mut globs: Vec<(&str, &ScopedSettings)> = self
+            .0
+            .iter()
+            .filter_map(|(key, settings)| Some((Self::files_scope(key)?, settings)))
+            .filter(|(glob, _)| {
+                path.into_iter()
+                    .chain(relative)
+                    .any(|p| path_matches_glob(glob, p))
+            })
+            .collect();
+        globs.sort_by_key(|(glob, _)| std::cmp::Reverse(glob.len()));
+
+        let mut result = ScopedSettings::default();
+        for (_, settings) in globs {
+            result.fill_from(settings);
+        }
+        if let Some(settings) =
+            language_id.and_then(|language| self.0.get(&format!("[{}]", language)))
+        {
+            result.fill_from(settings);
+        }
+        result
+    }
+}
+
+impl ScopedSettings {
+    /// Fill the unset fields from a less specific scope
+    pub fn fill_from(&mut self, other: &Self) {
+        fn fill<T: Clone>(target: &mut Option<T>, other: &Option<T>) {
+            if target.is_none() {
+                target.clone_from(other);
+            }
+        }
+        fill(&mut self.tab_size, &other.tab_size);
+        fill(&mut self.use_tabs, &other.use_tabs);
+        fill(&mut self.line_wrap, &other.line_wrap);
+        fill(&mut self.rulers, &other.rulers);
+        fill(&mut self.wrap_guide_shading, &other.wrap_guide_shading);
+        fill(&mut self.format_on_save, &other.format_on_save);
+        fill(
+            &mut self.trim_trailing_whitespace_on_save,
+            &other.trim_trailing_whitespace_on_save,
+        );
+        fill(
+            &mut self.ensure_final_newline_on_save,
+            &other.ensure_final_newline_on_save,
+        );
+    }
+}
+
+impl<'de> Deserialize<'de> for ScopedOverrides {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        // Receives every top-level key the config doesn't know; only scope
+        // keys are kept.
+        let entries = HashMap::<String, serde_json::Value>::deserialize(deserializer)?;
+        let mut scopes = BTreeMap::new();
+        for (key, value) in entries {
+            if Self::is_scope_key(&key) {
+                let settings = ScopedSettings::deserialize(value)
+                    .map_err(|e| D::Error::custom(format!("{}: {}", key, e)))?;
+                scopes.insert(key, settings);
+            }
+        }
+        Ok(Self(scopes))
+    }
+}
+
+impl JsonSchema for ScopedOverrides {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("ScopedOverrides")
+    }
+
+    fn json_schema(gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        let settings = gen.subschema_for::<ScopedSettings>();
+        schemars::json_schema!({
+            "type": "object",
+            "patternProperties": {
+                "^\\[.+\\]$": settings,
+                "^files:.+$": settings
+            }
+        })
+    }
 }
 
 /// Resolved editor configuration for a specific buffer.
@@ -1173,6 +2204,21 @@ pub struct BufferConfig {
 
     /// Path to custom TextMate grammar (if any)
     pub textmate_grammar: Option<std::path::PathBuf>,
+
+    /// Line wrap set by a language or glob scope (None keeps the view's setting)
+    pub line_wrap: Option<bool>,
+
+    /// Columns at which to draw vertical rulers
+    pub rulers: Vec<usize>,
+
+    /// Whether to shade the text area past the last ruler
+    pub wrap_guide_shading: bool,
+
+    /// Whether to remove trailing whitespace on save
+    pub trim_trailing_whitespace_on_save: bool,
+
+    /// Whether to ensure a final newline on save
+    pub ensure_final_newline_on_save: bool,
 }
 
 impl BufferConfig {
@@ -1185,6 +2231,20 @@ impl BufferConfig {
     /// * `global_config` - The resolved global configuration
     /// * `language_id` - Optional language identifier (e.g., "rust", "python")
     pub fn resolve(global_config: &Config, language_id: Option<&str>) -> Self {
+        Self::resolve_for_file(global_config, language_id, None, None)
+    }
+
+    /// Resolve the effective configuration for a buffer given its language and file.
+    ///
+    /// On top of `resolve`, applies the `"[<language>]"` scope and any
+    /// `"files:<glob>"` scopes matching `path` (also tried relative to
+    /// `working_dir`).
+    pub fn resolve_for_file(
+        global_config: &Config,
+        language_id: Option<&str>,
+        path: Option<&Path>,
+        working_dir: Option<&Path>,
+    ) -> Self {
         let editor = &global_config.editor;
 
         // Start with global editor settings
@@ -1198,6 +2258,11 @@ impl BufferConfig {
             on_save: Vec::new(),
             highlighter: HighlighterPreference::Auto,
             textmate_grammar: None,
+            line_wrap: None,
+            rulers: editor.rulers.clone(),
+            wrap_guide_shading: editor.wrap_guide_shading,
+            trim_trailing_whitespace_on_save: editor.trim_trailing_whitespace_on_save,
+            ensure_final_newline_on_save: editor.ensure_final_newline_on_save,
         };
 
         // Apply language-specific overrides if available
@@ -1234,6 +2299,33 @@ impl BufferConfig {
             }
         }
 
+        // Apply language and glob scopes
+        let scoped = global_config
+            .overrides
+            .for_buffer(language_id, path, working_dir);
+        if let Some(tab_size) = scoped.tab_size {
+            config.tab_size = tab_size;
+        }
+        if let Some(use_tabs) = scoped.use_tabs {
+            config.use_tabs = use_tabs;
+        }
+        config.line_wrap = scoped.line_wrap;
+        if let Some(rulers) = scoped.rulers {
+            config.rulers = rulers;
+        }
+        if let Some(shading) = scoped.wrap_guide_shading {
+            config.wrap_guide_shading = shading;
+        }
+        if let Some(format_on_save) = scoped.format_on_save {
+            config.format_on_save = format_on_save;
+        }
+        if let Some(trim) = scoped.trim_trailing_whitespace_on_save {
+            config.trim_trailing_whitespace_on_save = trim;
+        }
+        if let Some(ensure) = scoped.ensure_final_newline_on_save {
+            config.ensure_final_newline_on_save = ensure;
+        }
+
         config
     }
 
@@ -1367,6 +2459,9 @@ impl Default for Config {
             theme: default_theme_name(),
             locale: LocaleName::default(),
             check_for_updates: true,
+            telemetry: TelemetryConfig::default(),
+            update_channel: UpdateChannel::default(),
+            allow_unverified_updates: false,
             editor: EditorConfig::default(),
             file_explorer: FileExplorerConfig::default(),
             file_browser: FileBrowserConfig::default(),
@@ -1377,9 +2472,13 @@ impl Default for Config {
             languages: Self::default_languages(),
             lsp: Self::default_lsp_config(),
             warnings: WarningsConfig::default(),
+            status_line: StatusLineConfig::default(),
+            session_server: SessionServerConfig::default(),
+            accessibility: AccessibilityConfig::default(),
             plugins: HashMap::new(), // Populated when scanning for plugins
             packages: PackagesConfig::default(),
             auto_modal_mode: default_auto_modal_mode(),
+            overrides: ScopedOverrides::default(),
         }
     }
 }
@@ -1461,6 +2560,13 @@ impl MenuConfig {
                         checkbox: None,
                     },
                     MenuItem::Separator { separator: true },
+                    MenuItem::Action {
+                        label: t!("menu.file.follow_user").to_string(),
+                        action: "follow_user".to_string(),
+                        args: HashMap::new(),
+                        when: Some(context_keys::SESSION_MODE.to_string()),
+                        checkbox: Some(context_keys::FOLLOWING_USER.to_string()),
+                    },
                     MenuItem::Action {
                         label: t!("menu.file.detach").to_string(),
                         action: "detach".to_string(),
@@ -1567,6 +2673,20 @@ impl MenuConfig {
                         when: None,
                         checkbox: None,
                     },
+                    MenuItem::Action {
+                        label: t!("menu.edit.structural_search").to_string(),
+                        action: "structural_search".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.edit.structural_replace").to_string(),
+                        action: "structural_replace".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
                     MenuItem::Separator { separator: true },
                     MenuItem::Action {
                         label: t!("menu.edit.delete_line").to_string(),
@@ -1627,6 +2747,13 @@ impl MenuConfig {
                         when: None,
                         checkbox: Some(context_keys::LINE_WRAP.to_string()),
                     },
+                    MenuItem::Action {
+                        label: t!("menu.view.focus_mode").to_string(),
+                        action: "toggle_focus_mode".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: Some(context_keys::FOCUS_MODE.to_string()),
+                    },
                     MenuItem::Action {
                         label: t!("menu.view.mouse_support").to_string(),
                         action: "toggle_mouse_capture".to_string(),
@@ -1728,6 +2855,13 @@ impl MenuConfig {
                         when: None,
                         checkbox: None,
                     },
+                    MenuItem::Action {
+                        label: t!("menu.view.lock_scrolling").to_string(),
+                        action: "toggle_scroll_lock".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: Some(context_keys::SCROLL_LOCK.to_string()),
+                    },
                     MenuItem::Separator { separator: true },
                     MenuItem::Submenu {
                         label: t!("menu.terminal").to_string(),
@@ -1853,6 +2987,34 @@ impl MenuConfig {
                         when: None,
                         checkbox: None,
                     },
+                    MenuItem::Action {
+                        label: t!("menu.selection.skip_occurrence").to_string(),
+                        action: "skip_occurrence".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.selection.select_all_occurrences").to_string(),
+                        action: "select_all_occurrences".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.selection.match_case").to_string(),
+                        action: "toggle_search_case_sensitive".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: Some(context_keys::SEARCH_CASE_SENSITIVE.to_string()),
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.selection.whole_word").to_string(),
+                        action: "toggle_search_whole_word".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: Some(context_keys::SEARCH_WHOLE_WORD.to_string()),
+                    },
                     MenuItem::Action {
                         label: t!("menu.selection.remove_secondary_cursors").to_string(),
                         action: "remove_secondary_cursors".to_string(),
@@ -2208,7 +3370,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "rust".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2222,6 +3387,7 @@ impl Config {
                 }),
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2232,7 +3398,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "javascript".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2246,6 +3415,7 @@ impl Config {
                 }),
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2256,7 +3426,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "typescript".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2270,6 +3443,7 @@ impl Config {
                 }),
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2280,7 +3454,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "python".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2298,6 +3475,7 @@ impl Config {
                 }),
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2308,7 +3486,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "c".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2322,6 +3503,7 @@ impl Config {
                 }),
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2339,7 +3521,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "cpp".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2353,6 +3538,7 @@ impl Config {
                 }),
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2363,7 +3549,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "c_sharp".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2372,6 +3561,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2397,7 +3587,10 @@ impl Config {
                 ],
                 grammar: "bash".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2406,6 +3599,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2420,7 +3614,10 @@ impl Config {
                 ],
                 grammar: "make".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: false,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2429,6 +3626,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2439,7 +3637,10 @@ impl Config {
                 filenames: vec!["Dockerfile".to_string(), "Containerfile".to_string()],
                 grammar: "dockerfile".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2448,6 +3649,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2458,7 +3660,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "json".to_string(),
                 comment_prefix: None,
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2472,6 +3677,7 @@ impl Config {
                 }),
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2482,7 +3688,10 @@ impl Config {
                 filenames: vec!["Cargo.lock".to_string()],
                 grammar: "toml".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2491,6 +3700,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2501,7 +3711,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "yaml".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2515,6 +3728,7 @@ impl Config {
                 }),
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2525,7 +3739,10 @@ impl Config {
                 filenames: vec!["README".to_string()],
                 grammar: "markdown".to_string(),
                 comment_prefix: None,
+                block_comment_start: Some("<!--".to_string()),
+                block_comment_end: Some("-->".to_string()),
                 auto_indent: false,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2534,6 +3751,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2545,7 +3763,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "go".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: false,
@@ -2559,6 +3780,7 @@ impl Config {
                 }),
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2569,7 +3791,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "odin".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: false,
@@ -2578,6 +3803,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2588,7 +3814,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "zig".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2597,6 +3826,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2607,7 +3837,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "java".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2616,6 +3849,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2633,7 +3867,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "latex".to_string(),
                 comment_prefix: Some("%".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2642,6 +3879,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2652,7 +3890,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "go".to_string(), // Templ uses Go-like syntax
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2661,6 +3902,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2672,7 +3914,10 @@ impl Config {
                 filenames: vec!["git-rebase-todo".to_string()],
                 grammar: "Git Rebase Todo".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: false,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2681,6 +3926,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2696,7 +3942,10 @@ impl Config {
                 ],
                 grammar: "Git Commit Message".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: false,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2705,6 +3954,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2720,7 +3970,10 @@ impl Config {
                 ],
                 grammar: "Gitignore".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: false,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2729,6 +3982,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2739,7 +3993,10 @@ impl Config {
                 filenames: vec![".gitconfig".to_string(), ".gitmodules".to_string()],
                 grammar: "Git Config".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2748,6 +4005,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2758,7 +4016,10 @@ impl Config {
                 filenames: vec![".gitattributes".to_string()],
                 grammar: "Git Attributes".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: false,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2767,6 +4028,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -2777,7 +4039,10 @@ impl Config {
                 filenames: vec![],
                 grammar: "Typst".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -2786,6 +4051,7 @@ impl Config {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -3253,6 +4519,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_theme_setting_light_dark_pair() {
+        let config: Config =
+            serde_json::from_str(r#"{"theme": {"light": "light", "dark": "nord"}}"#).unwrap();
+        assert!(config.theme.follows_appearance());
+        assert_eq!(config.theme.resolve(Appearance::Light).0, "light");
+        assert_eq!(config.theme.resolve(Appearance::Dark).0, "nord");
+
+        // Picking a theme replaces the side in use and keeps the other
+        let mut theme = config.theme.clone();
+        theme.select("solarized-dark".into(), Appearance::Dark);
+        assert_eq!(theme.resolve(Appearance::Light).0, "light");
+        assert_eq!(theme.resolve(Appearance::Dark).0, "solarized-dark");
+        assert_eq!(
+            serde_json::to_value(&theme).unwrap(),
+            serde_json::json!({"light": "light", "dark": "solarized-dark"})
+        );
+
+        let named: Config = serde_json::from_str(r#"{"theme": "dark"}"#).unwrap();
+        assert!(!named.theme.follows_appearance());
+        assert_eq!(named.theme.resolve(Appearance::Light).0, "dark");
+        assert_eq!(serde_json::to_value(&named.theme).unwrap(), "dark");
+    }
+
     #[test]
     fn test_config_serialize_deserialize() {
         // Test that Config can be serialized and deserialized correctly
@@ -3452,7 +4742,10 @@ mod tests {
                 filenames: vec![],
                 grammar: "go".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: Some("/*".to_string()),
+                block_comment_end: Some("*/".to_string()),
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: false, // Go hides tab indicators
@@ -3466,6 +4759,7 @@ mod tests {
                 }),
                 format_on_save: true,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -3489,6 +4783,91 @@ mod tests {
         assert!(!buffer_config.use_tabs);
     }
 
+    #[test]
+    fn test_cursor_style_for_mode() {
+        let mut editor = EditorConfig {
+            cursor_style: CursorStyle::SteadyBar,
+            ..Default::default()
+        };
+        assert_eq!(editor.cursor_style_for_mode(None), CursorStyle::SteadyBar);
+        assert_eq!(
+            editor.cursor_style_for_mode(Some("vi-normal")),
+            CursorStyle::BlinkingBlock
+        );
+        assert_eq!(
+            editor.cursor_style_for_mode(Some("vi-insert")),
+            CursorStyle::BlinkingBar
+        );
+        assert_eq!(
+            editor.cursor_style_for_mode(Some("vi-replace")),
+            CursorStyle::BlinkingUnderline
+        );
+        // Other modes keep the base style
+        assert_eq!(
+            editor.cursor_style_for_mode(Some("review")),
+            CursorStyle::SteadyBar
+        );
+
+        // The longest wildcard wins
+        editor
+            .mode_cursor_styles
+            .insert("vi-visual*".to_string(), CursorStyle::SteadyUnderline);
+        assert_eq!(
+            editor.cursor_style_for_mode(Some("vi-visual-line")),
+            CursorStyle::SteadyUnderline
+        );
+        assert_eq!(
+            editor.cursor_style_for_mode(Some("vi-operator-pending")),
+            CursorStyle::BlinkingBlock
+        );
+    }
+
+    #[test]
+    fn test_buffer_config_applies_scoped_overrides() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "editor": { "rulers": [80], "wrap_guide_shading": true },
+                "languages": { "rust": { "tab_size": 4 } },
+                "[rust]": { "tab_size": 2, "format_on_save": true, "wrap_guide_shading": false },
+                "files:docs/*.rs": { "tab_size": 8, "line_wrap": false },
+                "files:*.rs": { "tab_size": 3, "rulers": [100] }
+            }"#,
+        )
+        .unwrap();
+        let working_dir = Path::new("/project");
+
+        // Language scope beats `languages`, glob scopes beat the language scope
+        let main = BufferConfig::resolve_for_file(
+            &config,
+            Some("rust"),
+            Some(Path::new("/project/src/main.rs")),
+            Some(working_dir),
+        );
+        assert_eq!(main.tab_size, 3);
+        assert_eq!(main.rulers, vec![100]);
+        assert!(!main.wrap_guide_shading);
+        assert!(main.format_on_save);
+        assert_eq!(main.line_wrap, None);
+
+        // The longer glob wins; it matches the path relative to the working dir
+        let doc = BufferConfig::resolve_for_file(
+            &config,
+            Some("rust"),
+            Some(Path::new("/project/docs/example.rs")),
+            Some(working_dir),
+        );
+        assert_eq!(doc.tab_size, 8);
+        assert_eq!(doc.line_wrap, Some(false));
+        assert_eq!(doc.rulers, vec![100]);
+
+        let unnamed = BufferConfig::resolve(&config, Some("rust"));
+        assert_eq!(unnamed.tab_size, 2);
+        assert_eq!(unnamed.rulers, vec![80]);
+
+        let python = BufferConfig::resolve(&config, Some("python"));
+        assert!(python.wrap_guide_shading);
+    }
+
     #[test]
     fn test_buffer_config_indent_string() {
         let config = Config::default();
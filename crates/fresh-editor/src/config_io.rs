@@ -5,6 +5,7 @@
 //! These are separated from config.rs to allow schema-only builds.
 
 use crate::config::{Config, ConfigError};
+use crate::migration::{self, Migration, MigrationReport};
 use crate::partial_config::{Merge, PartialConfig, SessionConfig};
 use serde_json::Value;
 use std::path::{Path, PathBuf};
@@ -176,43 +177,114 @@ fn find_changed_paths_recursive(
 // ============================================================================
 
 /// Current config schema version.
-/// Increment this when making breaking changes to config structure.
-pub const CURRENT_CONFIG_VERSION: u32 = 1;
+/// Increment this when making breaking changes to config structure,
+/// and add a migration for it to `CONFIG_MIGRATIONS`.
+pub const CURRENT_CONFIG_VERSION: u32 = 2;
+
+/// Every config format change, oldest first
+pub const CONFIG_MIGRATIONS: &[Migration] = &[
+    Migration {
+        to: 1,
+        description: "rename camelCase editor keys to snake_case",
+        apply: migrate_v0_to_v1,
+    },
+    Migration {
+        to: 2,
+        description: "write keybindings as key strings",
+        apply: migrate_v1_to_v2,
+    },
+];
 
 /// Apply all necessary migrations to bring a config JSON to the current version.
 pub fn migrate_config(mut value: Value) -> Result<Value, ConfigError> {
-    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+    migrate_config_value(&mut value);
+    Ok(value)
+}
 
-    // Apply migrations sequentially
-    if version < 1 {
-        value = migrate_v0_to_v1(value)?;
+/// Migrate a config document in place. A config written by a newer version of the
+/// editor is left as is and loaded as far as this version understands it.
+fn migrate_config_value(value: &mut Value) -> Option<MigrationReport> {
+    match migration::migrate(value, CONFIG_MIGRATIONS, CURRENT_CONFIG_VERSION) {
+        Ok(report) => Some(report),
+        Err(e) => {
+            tracing::warn!("Not migrating config: {}", e);
+            None
+        }
     }
-    // Future migrations:
-    // if version < 2 { value = migrate_v1_to_v2(value)?; }
-
-    Ok(value)
 }
 
 /// Migration from v0 (implicit/missing version) to v1.
 /// This is the initial migration that establishes the version field.
-fn migrate_v0_to_v1(mut value: Value) -> Result<Value, ConfigError> {
-    if let Value::Object(ref mut map) = value {
-        // Set version to 1
-        map.insert("version".to_string(), Value::Number(1.into()));
-
-        // Example: rename camelCase keys to snake_case if they exist
-        if let Some(Value::Object(ref mut editor_map)) = map.get_mut("editor") {
-            // tabSize -> tab_size (hypothetical legacy format)
-            if let Some(val) = editor_map.remove("tabSize") {
-                editor_map.entry("tab_size").or_insert(val);
-            }
-            // lineNumbers -> line_numbers
-            if let Some(val) = editor_map.remove("lineNumbers") {
-                editor_map.entry("line_numbers").or_insert(val);
+fn migrate_v0_to_v1(value: &mut Value) {
+    // Example: rename camelCase keys to snake_case if they exist
+    // tabSize -> tab_size (hypothetical legacy format)
+    migration::rename_key(value, "editor.tabSize", "editor.tab_size");
+    // lineNumbers -> line_numbers
+    migration::rename_key(value, "editor.lineNumbers", "editor.line_numbers");
+}
+
+/// Migration from v1 to v2: keybindings written with a separate `modifiers` list
+/// or a `keys` array become a single key string (`"ctrl+k ctrl+c"`).
+fn migrate_v1_to_v2(value: &mut Value) {
+    if let Some(Value::Array(bindings)) = value.get_mut("keybindings") {
+        bindings.iter_mut().for_each(keybinding_to_key_string);
+    }
+    if let Some(Value::Object(maps)) = value.get_mut("keybinding_maps") {
+        for map in maps.values_mut() {
+            if let Some(Value::Array(bindings)) = map.get_mut("bindings") {
+                bindings.iter_mut().for_each(keybinding_to_key_string);
             }
         }
     }
-    Ok(value)
+}
+
+/// Rewrite one keybinding as a key string. Bindings that can't be written that
+/// way, such as the space key with modifiers, keep their old form, which is still
+/// accepted.
+fn keybinding_to_key_string(binding: &mut Value) {
+    let presses: Vec<&Value> = match binding.get("keys") {
+        Some(Value::Array(keys)) if !keys.is_empty() => keys.iter().collect(),
+        _ if binding.get("modifiers").is_some() => vec![&*binding],
+        _ => return,
+    };
+    let Some(tokens) = presses
+        .into_iter()
+        .map(key_press_token)
+        .collect::<Option<Vec<_>>>()
+    else {
+        return;
+    };
+
+    let Some(map) = binding.as_object_mut() else {
+        return;
+    };
+    map.remove("keys");
+    map.remove("modifiers");
+    map.insert("key".to_string(), Value::String(tokens.join(" ")));
+}
+
+/// `{"key": "k", "modifiers": ["ctrl"]}` as `ctrl+k`
+fn key_press_token(press: &Value) -> Option<String> {
+    let key = press.get("key")?.as_str()?;
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return None;
+    }
+    let mut token = String::new();
+    for modifier in press
+        .get("modifiers")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let modifier = modifier.as_str()?.to_lowercase();
+        if !matches!(modifier.as_str(), "ctrl" | "control" | "shift" | "alt") {
+            return None;
+        }
+        token.push_str(&modifier);
+        token.push('+');
+    }
+    token.push_str(key);
+    Some(token)
 }
 
 /// Represents a configuration layer in the 4-level hierarchy.
@@ -264,13 +336,25 @@ impl ConfigResolver {
     /// Session > Project > UserPlatform > User > System
     ///
     /// Each layer fills in values missing from higher precedence layers.
+    /// Session and Project layers live in the working directory, so settings
+    /// that run commands are dropped from them unless the workspace is trusted.
     pub fn resolve(&self) -> Result<Config, ConfigError> {
+        let trusted = self.is_workspace_trusted();
+
         // Start with highest precedence layer (Session)
         let mut merged = self.load_session_layer()?.unwrap_or_default();
+        if !trusted && merged.strip_untrusted() {
+            tracing::info!("Ignoring command settings in session config of untrusted workspace");
+        }
 
         // Merge in Project layer (fills missing values)
-        if let Some(project_partial) = self.load_project_layer()? {
+        if let Some(mut project_partial) = self.load_project_layer()? {
             tracing::debug!("Loaded project config layer");
+            if !trusted && project_partial.strip_untrusted() {
+                tracing::info!(
+                    "Ignoring command settings in project config of untrusted workspace"
+                );
+            }
             merged.merge_from(&project_partial);
         }
 
@@ -290,6 +374,22 @@ impl ConfigResolver {
         Ok(merged.resolve())
     }
 
+    /// Whether the working directory is a trusted workspace
+    pub fn is_workspace_trusted(&self) -> bool {
+        crate::services::workspace_trust::is_workspace_trusted(
+            &self.dir_context.data_dir,
+            &self.working_dir,
+        )
+    }
+
+    /// Whether the Session or Project layer has settings that are ignored
+    /// until the workspace is trusted
+    pub fn has_untrusted_settings(&self) -> bool {
+        [self.load_session_layer(), self.load_project_layer()]
+            .into_iter()
+            .any(|layer| matches!(layer, Ok(Some(mut partial)) if partial.strip_untrusted()))
+    }
+
     /// Get the path to user config file.
     pub fn user_config_path(&self) -> PathBuf {
         self.dir_context.config_path()
@@ -363,6 +463,30 @@ impl ConfigResolver {
         self.load_layer_from_path(&self.session_config_path())
     }
 
+    /// Validate every config file that takes part in `resolve()` against the
+    /// config schema.
+    ///
+    /// Returns the problems found, in layer order (User, UserPlatform,
+    /// Project, Session).
+    pub fn validate_layers(&self) -> Vec<crate::config_validation::ConfigIssue> {
+        let paths = [
+            Some(self.user_config_path()),
+            self.user_platform_config_path(),
+            Some(self.project_config_path()),
+            Some(self.session_config_path()),
+        ];
+
+        paths
+            .into_iter()
+            .flatten()
+            .filter_map(|path| {
+                let content = std::fs::read_to_string(&path).ok()?;
+                Some(crate::config_validation::validate_config(&path, &content))
+            })
+            .flatten()
+            .collect()
+    }
+
     /// Load a layer from a specific path, applying migrations if needed.
     fn load_layer_from_path(&self, path: &Path) -> Result<Option<PartialConfig>, ConfigError> {
         if !path.exists() {
@@ -373,14 +497,26 @@ impl ConfigResolver {
             .map_err(|e| ConfigError::IoError(format!("{}: {}", path.display(), e)))?;
 
         // Parse as raw JSON first
-        let value: Value = serde_json::from_str(&content)
+        let mut value: Value = serde_json::from_str(&content)
             .map_err(|e| ConfigError::ParseError(format!("{}: {}", path.display(), e)))?;
 
-        // Apply migrations
-        let migrated = migrate_config(value)?;
+        // Apply migrations, rewriting the file (with a backup) when they changed it
+        if let Some(report) = migrate_config_value(&mut value).filter(|r| r.changed) {
+            match migration::write_migrated(path, &value, report.from) {
+                Ok(backup) => tracing::info!(
+                    "Migrated {} from version {} to {} ({}), backup at {}",
+                    path.display(),
+                    report.from,
+                    report.to,
+                    report.applied.join(", "),
+                    backup.display()
+                ),
+                Err(e) => tracing::warn!("Failed to save migrated config: {}", e),
+            }
+        }
 
         // Now deserialize to PartialConfig
-        let partial: PartialConfig = serde_json::from_value(migrated)
+        let partial: PartialConfig = serde_json::from_value(value)
             .map_err(|e| ConfigError::ParseError(format!("{}: {}", path.display(), e)))?;
 
         Ok(Some(partial))
@@ -422,12 +558,17 @@ impl ConfigResolver {
                 .map_err(|e| ConfigError::IoError(format!("{}: {}", parent_dir.display(), e)))?;
         }
 
-        // Read existing file content (if any) as PartialConfig.
+        // Read existing file content (if any) as PartialConfig, migrated so that
+        // legacy keys are carried over rather than dropped.
         // This preserves any manual edits made externally while the editor was running.
         let existing: PartialConfig = if path.exists() {
             let content = std::fs::read_to_string(&path)
                 .map_err(|e| ConfigError::IoError(format!("{}: {}", path.display(), e)))?;
-            serde_json::from_str(&content).unwrap_or_default()
+            serde_json::from_str(&content)
+                .ok()
+                .and_then(|value| migrate_config(value).ok())
+                .and_then(|value| serde_json::from_value(value).ok())
+                .unwrap_or_default()
         } else {
             PartialConfig::default()
         };
@@ -501,11 +642,14 @@ impl ConfigResolver {
                 .map_err(|e| ConfigError::IoError(format!("{}: {}", parent_dir.display(), e)))?;
         }
 
-        // Read existing file content as JSON
+        // Read existing file content as JSON, migrated to the current version
         let mut result: Value = if path.exists() {
             let content = std::fs::read_to_string(&path)
                 .map_err(|e| ConfigError::IoError(format!("{}: {}", path.display(), e)))?;
-            serde_json::from_str(&content).unwrap_or(Value::Object(Default::default()))
+            serde_json::from_str(&content)
+                .ok()
+                .and_then(|value| migrate_config(value).ok())
+                .unwrap_or(Value::Object(Default::default()))
         } else {
             Value::Object(Default::default())
         };
@@ -568,11 +712,14 @@ impl ConfigResolver {
                 .map_err(|e| ConfigError::IoError(format!("{}: {}", parent_dir.display(), e)))?;
         }
 
-        // Read existing file content as JSON
+        // Read existing file content as JSON, migrated to the current version
         let mut config_value: Value = if path.exists() {
             let content = std::fs::read_to_string(&path)
                 .map_err(|e| ConfigError::IoError(format!("{}: {}", path.display(), e)))?;
-            serde_json::from_str(&content).unwrap_or(Value::Object(Default::default()))
+            serde_json::from_str(&content)
+                .ok()
+                .and_then(|value| migrate_config(value).ok())
+                .unwrap_or(Value::Object(Default::default()))
         } else {
             Value::Object(Default::default())
         };
@@ -812,8 +959,14 @@ impl Config {
     ///
     /// Merges layers in precedence order: Session > Project > User > System
     /// Falls back to defaults for any unspecified values.
+    ///
+    /// Each config file is also validated against the schema; problems are
+    /// logged as warnings with their file, line and key.
     pub fn load_with_layers(dir_context: &DirectoryContext, working_dir: &Path) -> Self {
         let resolver = ConfigResolver::new(dir_context.clone(), working_dir.to_path_buf());
+        for issue in resolver.validate_layers() {
+            tracing::warn!("Config problem: {}", issue);
+        }
         match resolver.resolve() {
             Ok(config) => {
                 tracing::info!("Loaded layered config for {}", working_dir.display());
@@ -929,8 +1082,19 @@ pub struct DirectoryContext {
 
     /// User's downloads directory (for file open dialog shortcuts)
     pub downloads_dir: Option<std::path::PathBuf>,
+
+    /// Active profile name (None for the default profile)
+    /// Named profiles keep their config and data in `profiles/<name>` below
+    /// the default directories.
+    pub profile: Option<String>,
 }
 
+/// Name of the profile that uses the top-level config and data directories
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Subdirectory (of both the config and data directories) holding named profiles
+pub(crate) const PROFILES_DIR: &str = "profiles";
+
 impl DirectoryContext {
     /// Create a DirectoryContext from the system directories
     /// This should ONLY be called from main()
@@ -957,6 +1121,7 @@ impl DirectoryContext {
             home_dir: dirs::home_dir(),
             documents_dir: dirs::document_dir(),
             downloads_dir: dirs::download_dir(),
+            profile: None,
         })
     }
 
@@ -969,9 +1134,72 @@ impl DirectoryContext {
             home_dir: Some(temp_dir.join("home")),
             documents_dir: Some(temp_dir.join("documents")),
             downloads_dir: Some(temp_dir.join("downloads")),
+            profile: None,
         }
     }
 
+    /// Switch to a named profile
+    ///
+    /// `None` or "default" selects the default directories. Other profiles use
+    /// `profiles/<name>` below the default config and data directories, so
+    /// each has its own config, themes, plugins, workspaces and history.
+    pub fn with_profile(&self, profile: Option<&str>) -> Self {
+        let base = |dir: &std::path::Path| {
+            if self.profile.is_some() {
+                // <base>/profiles/<name>
+                dir.parent()
+                    .and_then(std::path::Path::parent)
+                    .map(std::path::Path::to_path_buf)
+                    .unwrap_or_else(|| dir.to_path_buf())
+            } else {
+                dir.to_path_buf()
+            }
+        };
+        let profile = profile.filter(|name| *name != DEFAULT_PROFILE);
+        let in_profile = |dir: std::path::PathBuf| match profile {
+            Some(name) => dir.join(PROFILES_DIR).join(name),
+            None => dir,
+        };
+        Self {
+            data_dir: in_profile(base(&self.data_dir)),
+            config_dir: in_profile(base(&self.config_dir)),
+            profile: profile.map(str::to_string),
+            ..self.clone()
+        }
+    }
+
+    /// Name of the active profile ("default" for the default profile)
+    pub fn profile_name(&self) -> &str {
+        self.profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// Whether `name` can be used as a profile name
+    ///
+    /// Names are used as directory names, so only letters, digits, `-` and `_`
+    /// are allowed.
+    pub fn is_valid_profile_name(name: &str) -> bool {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    /// List the default profile and the named profiles that have a config directory
+    pub fn list_profiles(&self) -> Vec<String> {
+        let profiles_dir = self.with_profile(None).config_dir.join(PROFILES_DIR);
+        let mut names: Vec<String> = std::fs::read_dir(profiles_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name != DEFAULT_PROFILE && Self::is_valid_profile_name(name))
+            .collect();
+        names.sort();
+        names.insert(0, DEFAULT_PROFILE.to_string());
+        names
+    }
+
     /// Get the recovery directory path
     pub fn recovery_dir(&self) -> std::path::PathBuf {
         self.data_dir.join("recovery")
@@ -1070,6 +1298,42 @@ mod tests {
         (temp_dir, resolver)
     }
 
+    #[test]
+    fn profiles_use_separate_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let default = DirectoryContext::for_testing(temp_dir.path());
+
+        let writing = default.with_profile(Some("writing"));
+        assert_eq!(writing.profile_name(), "writing");
+        assert_eq!(
+            writing.config_dir,
+            temp_dir.path().join("config/profiles/writing")
+        );
+        assert_eq!(
+            writing.data_dir,
+            temp_dir.path().join("data/profiles/writing")
+        );
+
+        // Switching from a named profile starts from the default directories
+        let code = writing.with_profile(Some("code"));
+        assert_eq!(
+            code.config_dir,
+            temp_dir.path().join("config/profiles/code")
+        );
+        let back = code.with_profile(Some(DEFAULT_PROFILE));
+        assert_eq!(back.profile, None);
+        assert_eq!(back.config_dir, default.config_dir);
+        assert_eq!(back.data_dir, default.data_dir);
+
+        std::fs::create_dir_all(&writing.config_dir).unwrap();
+        std::fs::create_dir_all(&code.config_dir).unwrap();
+        assert_eq!(back.list_profiles(), vec!["default", "code", "writing"]);
+
+        assert!(DirectoryContext::is_valid_profile_name("my_profile-2"));
+        assert!(!DirectoryContext::is_valid_profile_name("../escape"));
+        assert!(!DirectoryContext::is_valid_profile_name(""));
+    }
+
     #[test]
     fn resolver_returns_defaults_when_no_config_files() {
         let (_temp, resolver) = create_test_resolver();
@@ -1157,6 +1421,64 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn resolver_ignores_project_commands_until_trusted() {
+        let (temp, resolver) = create_test_resolver();
+
+        let project_config_path = resolver.project_config_write_path();
+        std::fs::create_dir_all(project_config_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &project_config_path,
+            r#"{"editor": {"tab_size": 8}, "lsp": {"rust": {"command": "/tmp/evil.sh"}}}"#,
+        )
+        .unwrap();
+
+        assert!(resolver.has_untrusted_settings());
+        let config = resolver.resolve().unwrap();
+        assert_eq!(config.editor.tab_size, 8);
+        assert_eq!(config.lsp["rust"].command, "rust-analyzer");
+
+        crate::services::workspace_trust::WorkspaceTrust::load(&resolver.dir_context.data_dir)
+            .set_decision(
+                &resolver.working_dir,
+                crate::services::workspace_trust::TrustDecision::Trusted,
+            )
+            .unwrap();
+        let config = resolver.resolve().unwrap();
+        assert_eq!(config.lsp["rust"].command, "/tmp/evil.sh");
+        drop(temp);
+    }
+
+    #[test]
+    fn resolver_validates_each_layer() {
+        let (_temp, resolver) = create_test_resolver();
+
+        let user_config_path = resolver.user_config_path();
+        std::fs::create_dir_all(user_config_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &user_config_path,
+            "{\n  \"editor\": { \"tab_size\": true }\n}",
+        )
+        .unwrap();
+
+        let project_config_path = resolver.project_config_write_path();
+        std::fs::create_dir_all(project_config_path.parent().unwrap()).unwrap();
+        std::fs::write(&project_config_path, r#"{"thme": "dark"}"#).unwrap();
+
+        let issues = resolver.validate_layers();
+        let found: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.path.clone(), issue.line, issue.key.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (user_config_path, 2, "editor.tab_size"),
+                (project_config_path, 1, "thme"),
+            ]
+        );
+    }
+
     #[test]
     fn resolver_loads_legacy_project_config() {
         let (temp, resolver) = create_test_resolver();
@@ -1258,7 +1580,10 @@ mod tests {
 
         let migrated = migrate_config(input).unwrap();
 
-        assert_eq!(migrated.get("version"), Some(&serde_json::json!(1)));
+        assert_eq!(
+            migrated.get("version"),
+            Some(&serde_json::json!(CURRENT_CONFIG_VERSION))
+        );
     }
 
     #[test]
@@ -1311,12 +1636,108 @@ mod tests {
         drop(temp);
     }
 
+    #[test]
+    fn migration_writes_keybindings_as_key_strings() {
+        let input = serde_json::json!({
+            "version": 1,
+            "keybindings": [
+                {"key": "s", "modifiers": ["ctrl", "shift"], "action": "save_as"},
+                {"keys": [
+                    {"key": "k", "modifiers": ["ctrl"]},
+                    {"key": "c", "modifiers": ["ctrl"]}
+                ], "action": "toggle_comment"},
+                {"key": " ", "modifiers": ["ctrl"], "action": "completion"},
+                {"key": "ctrl+p", "action": "quick_open"}
+            ],
+            "keybinding_maps": {
+                "mine": {"bindings": [{"key": "F5", "modifiers": ["alt"], "action": "run"}]}
+            }
+        });
+
+        let migrated = migrate_config(input).unwrap();
+
+        let bindings = &migrated["keybindings"];
+        assert_eq!(
+            bindings[0],
+            serde_json::json!({"key": "ctrl+shift+s", "action": "save_as"})
+        );
+        assert_eq!(
+            bindings[1],
+            serde_json::json!({"key": "ctrl+k ctrl+c", "action": "toggle_comment"})
+        );
+        // The space key can't be part of a key string, so it keeps its modifiers
+        assert_eq!(bindings[2]["modifiers"], serde_json::json!(["ctrl"]));
+        assert_eq!(bindings[3]["key"], "ctrl+p");
+        assert_eq!(
+            migrated["keybinding_maps"]["mine"]["bindings"][0]["key"],
+            "alt+F5"
+        );
+    }
+
+    #[test]
+    fn resolver_rewrites_migrated_config_with_backup() {
+        let (_temp, resolver) = create_test_resolver();
+
+        let user_config_path = resolver.user_config_path();
+        std::fs::create_dir_all(user_config_path.parent().unwrap()).unwrap();
+        let legacy = r#"{"editor": {"tabSize": 3}}"#;
+        std::fs::write(&user_config_path, legacy).unwrap();
+
+        let config = resolver.resolve().unwrap();
+        assert_eq!(config.editor.tab_size, 3);
+
+        let backup = migration::backup_path(&user_config_path, 0);
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), legacy);
+        let rewritten: Value =
+            serde_json::from_str(&std::fs::read_to_string(&user_config_path).unwrap()).unwrap();
+        assert_eq!(
+            rewritten,
+            serde_json::json!({"version": CURRENT_CONFIG_VERSION, "editor": {"tab_size": 3}})
+        );
+    }
+
+    #[test]
+    fn resolver_leaves_current_config_file_alone() {
+        let (_temp, resolver) = create_test_resolver();
+
+        let user_config_path = resolver.user_config_path();
+        std::fs::create_dir_all(user_config_path.parent().unwrap()).unwrap();
+        let content = r#"{"editor": {"tab_size": 3}}"#;
+        std::fs::write(&user_config_path, content).unwrap();
+
+        resolver.resolve().unwrap();
+        assert_eq!(std::fs::read_to_string(&user_config_path).unwrap(), content);
+        assert!(!migration::backup_path(&user_config_path, 0).exists());
+    }
+
+    #[test]
+    fn save_changes_carries_legacy_keys_forward() {
+        let (_temp, resolver) = create_test_resolver();
+
+        let user_config_path = resolver.user_config_path();
+        std::fs::create_dir_all(user_config_path.parent().unwrap()).unwrap();
+        std::fs::write(&user_config_path, r#"{"editor": {"lineNumbers": false}}"#).unwrap();
+
+        let changes = std::collections::HashMap::from([(
+            "/editor/tab_size".to_string(),
+            serde_json::json!(2),
+        )]);
+        resolver
+            .save_changes_to_layer(&changes, &Default::default(), ConfigLayer::User)
+            .unwrap();
+
+        let saved: Value =
+            serde_json::from_str(&std::fs::read_to_string(&user_config_path).unwrap()).unwrap();
+        assert_eq!(saved["editor"]["line_numbers"], serde_json::json!(false));
+        assert!(saved["editor"].get("lineNumbers").is_none());
+    }
+
     #[test]
     fn save_and_load_session() {
         let (_temp, resolver) = create_test_resolver();
 
         let mut session = SessionConfig::new();
-        session.set_theme(crate::config::ThemeName::from("dark"));
+        session.set_theme(crate::config::ThemeSetting::from("dark"));
         session.set_editor_option(|e| e.tab_size = Some(2));
 
         // Save session
@@ -1324,7 +1745,10 @@ mod tests {
 
         // Load session
         let loaded = resolver.load_session().unwrap();
-        assert_eq!(loaded.theme, Some(crate::config::ThemeName::from("dark")));
+        assert_eq!(
+            loaded.theme,
+            Some(crate::config::ThemeSetting::from("dark"))
+        );
         assert_eq!(loaded.editor.as_ref().unwrap().tab_size, Some(2));
     }
 
@@ -1333,7 +1757,7 @@ mod tests {
         let (_temp, resolver) = create_test_resolver();
 
         let mut session = SessionConfig::new();
-        session.set_theme(crate::config::ThemeName::from("dark"));
+        session.set_theme(crate::config::ThemeSetting::from("dark"));
 
         // Save then clear
         resolver.save_session(&session).unwrap();
@@ -1494,7 +1918,7 @@ mod tests {
 
         // Load the config
         let mut config = resolver.resolve().unwrap();
-        assert_eq!(config.theme.0, "dracula");
+        assert_eq!(config.theme, crate::config::ThemeSetting::from("dracula"));
         assert_eq!(config.editor.tab_size, 2);
 
         // User disables LSP via UI
@@ -1538,7 +1962,7 @@ mod tests {
 
         // Reload and verify the full config is correct
         let reloaded = resolver.resolve().unwrap();
-        assert_eq!(reloaded.theme.0, "dracula");
+        assert_eq!(reloaded.theme, crate::config::ThemeSetting::from("dracula"));
         assert_eq!(reloaded.editor.tab_size, 2);
         assert!(!reloaded.lsp["python"].enabled);
         // Command should come from defaults
@@ -1646,7 +2070,8 @@ mod tests {
         // Verify the theme was loaded (config parsed correctly)
         let config = result.unwrap();
         assert_eq!(
-            config.theme.0, "dracula",
+            config.theme,
+            crate::config::ThemeSetting::from("dracula"),
             "Theme should be 'dracula' from config file"
         );
     }
@@ -2014,7 +2439,10 @@ mod tests {
 
         // Step 2: Fresh loads the config (simulating startup)
         let config_at_startup = resolver.resolve().unwrap();
-        assert_eq!(config_at_startup.theme.0, "monokai");
+        assert_eq!(
+            config_at_startup.theme,
+            crate::config::ThemeSetting::from("monokai")
+        );
         assert!(
             !config_at_startup.lsp.contains_key("rust-analyzer"),
             "No custom LSP at startup"
@@ -2177,13 +2605,14 @@ mod tests {
         // Step 2: Load config - theme should be "dracula" from file
         let baseline = resolver.resolve().unwrap();
         assert_eq!(
-            baseline.theme.0, "dracula",
+            baseline.theme,
+            crate::config::ThemeSetting::from("dracula"),
             "Theme should be 'dracula' from file"
         );
 
         // Step 3: User changes theme to the DEFAULT value ("high-contrast")
         let mut config = baseline.clone();
-        config.theme = crate::config::ThemeName::from("high-contrast");
+        config.theme = crate::config::ThemeSetting::from("high-contrast");
 
         // Step 4: Save the change using baseline tracking
         resolver
@@ -2202,7 +2631,8 @@ mod tests {
 
         // The theme should be "high-contrast" (either explicitly in file, or absent so default applies)
         assert_eq!(
-            reloaded.theme.0, "high-contrast",
+            reloaded.theme,
+            crate::config::ThemeSetting::from("high-contrast"),
             "Theme should be 'high-contrast' after changing to default and saving. \
              With save_to_layer_with_baseline, the theme field should be removed from file \
              so the default applies. File content: {}",
@@ -0,0 +1,582 @@
+//! Schema-driven validation of config files.
+//!
+//! Config layers are deserialized leniently: unknown keys are dropped and a
+//! value of the wrong type makes the whole layer fail to load. This module
+//! checks each config file against the bundled JSON schema
+//! (`plugins/config-schema.json`) and reports what is wrong and where, so the
+//! editor can point the user at the exact line.
+
+use rust_i18n::t;
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Schema definitions that only describe what the Settings UI offers
+///
+/// Themes and keybinding maps can also be user-defined (and a theme can be a
+/// light/dark pair), so their values are not checked here.
+const UNCHECKED_DEFS: &[&str] = &["ThemeOptions", "KeybindingMapOptions"];
+
+/// What is wrong with a config value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigIssueKind {
+    /// The file is not valid JSON
+    Syntax(String),
+    /// The key is not a known setting
+    UnknownKey,
+    /// The value has the wrong JSON type (`expected` is e.g. "integer or null")
+    WrongType { expected: String },
+    /// The value is not one of the allowed values
+    InvalidValue { allowed: String },
+}
+
+/// A problem found in a config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Config file containing the problem
+    pub path: PathBuf,
+    /// 1-based line of the offending key or value
+    pub line: usize,
+    /// 1-based column of the offending key or value
+    pub column: usize,
+    /// Dotted path of the setting (e.g. `editor.tab_size`), empty for the whole file
+    pub key: String,
+    pub kind: ConfigIssueKind,
+}
+
+impl ConfigIssue {
+    /// Human-readable description of the problem
+    pub fn message(&self) -> String {
+        match &self.kind {
+            ConfigIssueKind::Syntax(error) => t!("config_issue.syntax", error = error).to_string(),
+            ConfigIssueKind::UnknownKey => {
+                t!("config_issue.unknown_key", key = &self.key).to_string()
+            }
+            ConfigIssueKind::WrongType { expected } => t!(
+                "config_issue.wrong_type",
+                key = &self.key,
+                expected = expected
+            )
+            .to_string(),
+            ConfigIssueKind::InvalidValue { allowed } => t!(
+                "config_issue.invalid_value",
+                key = &self.key,
+                allowed = allowed
+            )
+            .to_string(),
+        }
+    }
+
+    /// Whether the problem keeps the whole file from loading
+    ///
+    /// Unknown keys are only ignored, but syntax errors and wrong types make
+    /// the layer fail to deserialize.
+    pub fn is_error(&self) -> bool {
+        !matches!(self.kind, ConfigIssueKind::UnknownKey)
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.message()
+        )
+    }
+}
+
+/// The bundled config schema
+fn config_schema() -> &'static Value {
+    static SCHEMA: OnceLock<Value> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        serde_json::from_str(include_str!("../plugins/config-schema.json"))
+            .expect("bundled config schema is valid JSON")
+    })
+}
+
+/// Validate the contents of a config file against the config schema
+pub fn validate_config(path: &Path, content: &str) -> Vec<ConfigIssue> {
+    let value: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(e) => {
+            return vec![ConfigIssue {
+                path: path.to_path_buf(),
+                line: e.line().max(1),
+                column: e.column().max(1),
+                key: String::new(),
+                kind: ConfigIssueKind::Syntax(e.to_string()),
+            }];
+        }
+    };
+
+    // Validate what will actually be loaded, so legacy keys renamed by a
+    // migration are not reported
+    let value = crate::config_io::migrate_config(value.clone()).unwrap_or(value);
+
+    let schema = config_schema();
+    let mut validator = Validator {
+        root: schema,
+        issues: Vec::new(),
+    };
+    validator.check(&value, schema, &mut Vec::new());
+
+    let mut issues: Vec<ConfigIssue> = validator
+        .issues
+        .into_iter()
+        .map(|(pointer, kind)| {
+            let (line, column) = locate(content, &pointer);
+            ConfigIssue {
+                path: path.to_path_buf(),
+                line,
+                column,
+                key: pointer.join("."),
+                kind,
+            }
+        })
+        .collect();
+    issues.sort_by_key(|issue| (issue.line, issue.column));
+    issues
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    issues: Vec<(Vec<String>, ConfigIssueKind)>,
+}
+
+impl<'a> Validator<'a> {
+    /// Follow `$ref`s, returning the target schema and its definition name
+    fn resolve(&self, mut schema: &'a Value) -> (&'a Value, Option<&'a str>) {
+        let mut name = None;
+        while let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let Some(def) = reference.strip_prefix("#/$defs/") else {
+                break;
+            };
+            match self.root.get("$defs").and_then(|defs| defs.get(def)) {
+                Some(target) => {
+                    schema = target;
+                    name = Some(def);
+                }
+                None => break,
+            }
+        }
+        (schema, name)
+    }
+
+    fn check(&mut self, value: &Value, schema: &'a Value, pointer: &mut Vec<String>) {
+        let (schema, def_name) = self.resolve(schema);
+        if def_name.is_some_and(|name| UNCHECKED_DEFS.contains(&name)) {
+            return;
+        }
+
+        if let Some(branches) = schema
+            .get("anyOf")
+            .or_else(|| schema.get("oneOf"))
+            .and_then(Value::as_array)
+        {
+            self.check_branches(value, branches, pointer);
+            return;
+        }
+
+        if let Some(types) = schema.get("type") {
+            if !type_matches(value, types) {
+                self.issues.push((
+                    pointer.clone(),
+                    ConfigIssueKind::WrongType {
+                        expected: describe_types(types),
+                    },
+                ));
+                return;
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+            if !allowed.contains(value) {
+                self.issues.push((
+                    pointer.clone(),
+                    ConfigIssueKind::InvalidValue {
+                        allowed: describe_values(allowed.iter()),
+                    },
+                ));
+            }
+            return;
+        }
+
+        match value {
+            Value::Object(map) => {
+                let properties = schema.get("properties").and_then(Value::as_object);
+                let patterns = schema.get("patternProperties").and_then(Value::as_object);
+                let additional = schema.get("additionalProperties");
+                for (key, child) in map {
+                    pointer.push(key.clone());
+                    let child_schema = properties
+                        .and_then(|p| p.get(key))
+                        .or_else(|| patterns.and_then(|p| pattern_property(p, key)));
+                    match (child_schema, additional) {
+                        (Some(child_schema), _) => self.check(child, child_schema, pointer),
+                        (None, Some(child_schema @ Value::Object(_))) => {
+                            self.check(child, child_schema, pointer)
+                        }
+                        (None, Some(Value::Bool(true))) => {}
+                        (None, _) if properties.is_some() => {
+                            self.issues
+                                .push((pointer.clone(), ConfigIssueKind::UnknownKey));
+                        }
+                        (None, _) => {}
+                    }
+                    pointer.pop();
+                }
+            }
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        pointer.push(index.to_string());
+                        self.check(item, item_schema, pointer);
+                        pointer.pop();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Check a value against `anyOf`/`oneOf` branches
+    ///
+    /// Reports the problems of the branch whose type matches the value, so
+    /// e.g. a typo inside a formatter object is reported as an unknown key
+    /// rather than "expected object or null".
+    fn check_branches(&mut self, value: &Value, branches: &'a [Value], pointer: &mut Vec<String>) {
+        let mut matching = Vec::new();
+        for branch in branches {
+            let mut branch_validator = Validator {
+                root: self.root,
+                issues: Vec::new(),
+            };
+            branch_validator.check(value, branch, pointer);
+            if branch_validator.issues.is_empty() {
+                return;
+            }
+            let (resolved, _) = self.resolve(branch);
+            if resolved.get("type").is_some_and(|t| type_matches(value, t)) {
+                matching.push((resolved, branch_validator.issues));
+            }
+        }
+
+        let consts: Vec<&Value> = branches
+            .iter()
+            .filter_map(|branch| self.resolve(branch).0.get("const"))
+            .collect();
+        if !matching.is_empty() && matching.iter().all(|(s, _)| s.get("const").is_some()) {
+            self.issues.push((
+                pointer.clone(),
+                ConfigIssueKind::InvalidValue {
+                    allowed: describe_values(consts.into_iter()),
+                },
+            ));
+        } else if let Some((_, issues)) = matching.into_iter().next() {
+            self.issues.extend(issues);
+        } else {
+            let mut expected: Vec<String> = Vec::new();
+            for branch in branches {
+                if let Some(types) = self.resolve(branch).0.get("type") {
+                    let description = describe_types(types);
+                    if !expected.contains(&description) {
+                        expected.push(description);
+                    }
+                }
+            }
+            self.issues.push((
+                pointer.clone(),
+                ConfigIssueKind::WrongType {
+                    expected: expected.join(" or "),
+                },
+            ));
+        }
+    }
+}
+
+/// Schema of the first `patternProperties` entry whose pattern matches `key`
+fn pattern_property<'a>(
+    patterns: &'a serde_json::Map<String, Value>,
+    key: &str,
+) -> Option<&'a Value> {
+    patterns.iter().find_map(|(pattern, schema)| {
+        regex::Regex::new(pattern)
+            .is_ok_and(|re| re.is_match(key))
+            .then_some(schema)
+    })
+}
+
+fn type_matches(value: &Value, types: &Value) -> bool {
+    let matches_one = |name: &str| match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    };
+    match types {
+        Value::String(name) => matches_one(name),
+        Value::Array(names) => names.iter().filter_map(Value::as_str).any(matches_one),
+        _ => true,
+    }
+}
+
+fn describe_types(types: &Value) -> String {
+    match types {
+        Value::String(name) => name.clone(),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        other => other.to_string(),
+    }
+}
+
+fn describe_values<'v>(values: impl Iterator<Item = &'v Value>) -> String {
+    values.map(Value::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// Find the 1-based line and column of the value at `pointer` in `content`
+///
+/// Points at the key for object members. Falls back to the closest ancestor
+/// that can be found (e.g. for keys renamed by a migration).
+fn locate(content: &str, pointer: &[String]) -> (usize, usize) {
+    let mut scanner = Scanner {
+        bytes: content.as_bytes(),
+        pos: 0,
+    };
+    let offset = scanner.find(pointer).unwrap_or(0);
+
+    let before = &content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let column = content[line_start..offset].chars().count() + 1;
+    (line, column)
+}
+
+/// Minimal JSON scanner that tracks byte offsets of keys and values
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    /// Offset of the value at `pointer`, or of the deepest ancestor found
+    fn find(&mut self, pointer: &[String]) -> Option<usize> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let Some((segment, rest)) = pointer.split_first() else {
+            return Some(start);
+        };
+
+        match self.bytes.get(self.pos) {
+            Some(b'{') => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    if self.bytes.get(self.pos) != Some(&b'"') {
+                        return Some(start);
+                    }
+                    let key_start = self.pos;
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    if self.bytes.get(self.pos) != Some(&b':') {
+                        return Some(start);
+                    }
+                    self.pos += 1;
+                    if &key == segment {
+                        if rest.is_empty() {
+                            return Some(key_start);
+                        }
+                        return self.find(rest).or(Some(key_start));
+                    }
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    if self.bytes.get(self.pos) != Some(&b',') {
+                        return Some(start);
+                    }
+                    self.pos += 1;
+                }
+            }
+            Some(b'[') => {
+                let Ok(index) = segment.parse::<usize>() else {
+                    return Some(start);
+                };
+                self.pos += 1;
+                for _ in 0..index {
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    if self.bytes.get(self.pos) != Some(&b',') {
+                        return Some(start);
+                    }
+                    self.pos += 1;
+                }
+                self.find(rest).or(Some(start))
+            }
+            _ => Some(start),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|b| b.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Read a string literal, returning its decoded value
+    fn string(&mut self) -> Option<String> {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            self.pos += 1;
+            match byte {
+                b'\\' => self.pos += 1,
+                b'"' => {
+                    let literal = std::str::from_utf8(&self.bytes[start..self.pos]).ok()?;
+                    return serde_json::from_str(literal).ok();
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        self.skip_whitespace();
+        match self.bytes.get(self.pos)? {
+            b'"' => {
+                self.string()?;
+            }
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                while let Some(&byte) = self.bytes.get(self.pos) {
+                    match byte {
+                        b'"' => {
+                            self.string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                return Some(());
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+                return None;
+            }
+            _ => {
+                while self
+                    .bytes
+                    .get(self.pos)
+                    .is_some_and(|b| !matches!(b, b',' | b'}' | b']') && !b.is_ascii_whitespace())
+                {
+                    self.pos += 1;
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(content: &str) -> Vec<ConfigIssue> {
+        validate_config(Path::new("config.json"), content)
+    }
+
+    #[test]
+    fn valid_config_has_no_issues() {
+        let issues = validate(
+            r#"{
+  "theme": { "light": "my-light", "dark": "my-dark" },
+  "editor": { "tab_size": 2, "line_wrap": false },
+  "languages": {
+    "rust": { "formatter": { "command": "rustfmt", "args": [] } }
+  },
+  "[rust]": { "tab_size": 4 },
+  "files:**/*.md": { "line_wrap": true, "rulers": [80] }
+}"#,
+        );
+        assert_eq!(issues, Vec::new());
+    }
+
+    #[test]
+    fn reports_unknown_key_with_location() {
+        let issues = validate("{\n  \"editor\": {\n    \"tab_sise\": 2\n  }\n}");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, ConfigIssueKind::UnknownKey);
+        assert_eq!(issues[0].key, "editor.tab_sise");
+        assert_eq!((issues[0].line, issues[0].column), (3, 5));
+    }
+
+    #[test]
+    fn reports_wrong_type_inside_maps_and_options() {
+        let issues = validate(
+            r#"{
+  "editor": { "tab_size": "4" },
+  "languages": { "rust": { "formatter": { "comand": "rustfmt" } } },
+  "editor2": 1
+}"#,
+        );
+        let found: Vec<_> = issues
+            .iter()
+            .map(|i| (i.key.as_str(), i.line, &i.kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "editor.tab_size",
+                    2,
+                    &ConfigIssueKind::WrongType {
+                        expected: "integer".to_string()
+                    }
+                ),
+                (
+                    "languages.rust.formatter.comand",
+                    3,
+                    &ConfigIssueKind::UnknownKey
+                ),
+                ("editor2", 4, &ConfigIssueKind::UnknownKey),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_enum_value() {
+        let issues = validate(r#"{"editor": {"cursor_style": "blinking"}}"#);
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0].kind,
+            ConfigIssueKind::InvalidValue { .. }
+        ));
+    }
+
+    #[test]
+    fn reports_syntax_error_position() {
+        let issues = validate("{\n  \"theme\": \"dark\",\n}");
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0].kind, ConfigIssueKind::Syntax(_)));
+        assert_eq!(issues[0].line, 3);
+    }
+}
@@ -36,51 +36,70 @@ pub fn register_plugin_strings(
 }
 
 /// Translate a string for a plugin using the current locale.
+///
+/// Looks in the plugin's own strings first and then in the core catalog, so
+/// plugins can reuse core messages (e.g. `format.date`). Returns the key if
+/// neither has it.
 pub fn translate_plugin_string(
     plugin_name: &str,
     key: &str,
     args: &HashMap<String, String>,
 ) -> String {
     let locale = current_locale();
+    let template = match plugin_template(plugin_name, key, &locale) {
+        Some(t) => t,
+        None => match core_template(key, &locale) {
+            Some(t) => t,
+            None => return key.to_string(),
+        },
+    };
+
+    // Simple interpolation: %{variable}
+    let mut result = template;
+    for (k, v) in args {
+        result = result.replace(&format!("%{{{}}}", k), v);
+    }
+    result
+}
+
+/// Look up `key` in a plugin's registered strings, falling back to English
+fn plugin_template(plugin_name: &str, key: &str, locale: &str) -> Option<String> {
     let all_strings = PLUGIN_STRINGS.read().unwrap();
 
     let plugin_map: &HashMap<String, HashMap<String, String>> = match all_strings.get(plugin_name) {
         Some(m) => m,
         None => {
             tracing::debug!(
-                "translate_plugin_string: plugin '{}' not found (available: {:?}), returning key '{}'",
+                "translate_plugin_string: plugin '{}' not found (available: {:?}), trying core key '{}'",
                 plugin_name,
                 all_strings.keys().collect::<Vec<_>>(),
                 key
             );
-            return key.to_string();
+            return None;
         }
     };
 
     // Try current locale, then fallback to English
     let lang_map: Option<&HashMap<String, String>> =
-        plugin_map.get(&locale).or_else(|| plugin_map.get("en"));
-
-    let template: &String = match lang_map.and_then(|m| m.get(key)) {
-        Some(t) => t,
-        None => {
-            tracing::debug!(
-                "translate_plugin_string: key '{}' not found for plugin '{}' (locale='{}', available keys: {:?})",
-                key,
-                plugin_name,
-                locale,
-                plugin_map.get(&locale).or_else(|| plugin_map.get("en")).map(|m| m.keys().take(5).collect::<Vec<_>>())
-            );
-            return key.to_string();
-        }
-    };
-
-    // Simple interpolation: %{variable}
-    let mut result = template.clone();
-    for (k, v) in args {
-        result = result.replace(&format!("%{{{}}}", k), v);
+        plugin_map.get(locale).or_else(|| plugin_map.get("en"));
+
+    let template = lang_map.and_then(|m| m.get(key)).cloned();
+    if template.is_none() {
+        tracing::debug!(
+            "translate_plugin_string: key '{}' not found for plugin '{}' (locale='{}', available keys: {:?}), trying core",
+            key,
+            plugin_name,
+            locale,
+            lang_map.map(|m| m.keys().take(5).collect::<Vec<_>>())
+        );
     }
-    result
+    template
+}
+
+/// Look up `key` in the core catalog without interpolating it
+fn core_template(key: &str, locale: &str) -> Option<String> {
+    let template = rust_i18n::t!(key, locale = locale);
+    (template != key).then(|| template.to_string())
 }
 
 /// Unregister strings for a plugin.
@@ -221,6 +240,11 @@ pub fn switched_to_project_message(path: &str) -> String {
     rust_i18n::t!("file.switched_to_project", path = path).to_string()
 }
 
+/// Get the "switched to profile" message
+pub fn switched_to_profile_message(name: &str) -> String {
+    rust_i18n::t!("file.switched_to_profile", name = name).to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,6 +278,39 @@ mod tests {
         assert_eq!(msg, "Locale changed to es");
     }
 
+    #[test]
+    fn test_plugin_translation_falls_back_to_core() {
+        set_locale("en");
+        let mut en = HashMap::new();
+        en.insert(
+            "locale.changed".to_string(),
+            "Plugin: %{locale_name}".to_string(),
+        );
+        let mut strings = HashMap::new();
+        strings.insert("en".to_string(), en);
+        register_plugin_strings("i18n_fallback_test", strings);
+
+        let mut args = HashMap::new();
+        args.insert("locale_name".to_string(), "de".to_string());
+        assert_eq!(
+            translate_plugin_string("i18n_fallback_test", "locale.changed", &args),
+            "Plugin: de"
+        );
+        assert_eq!(
+            translate_plugin_string("i18n_fallback_test", "format.month_3", &args),
+            "March"
+        );
+        assert_eq!(
+            translate_plugin_string("unregistered_plugin", "locale.changed", &args),
+            "Locale changed to de"
+        );
+        assert_eq!(
+            translate_plugin_string("i18n_fallback_test", "no.such.key", &args),
+            "no.such.key"
+        );
+        unregister_plugin_strings("i18n_fallback_test");
+    }
+
     #[test]
     fn test_available_locales_includes_en() {
         let locales = available_locales();
@@ -6,6 +6,8 @@ use crate::model::buffer::{Buffer, LineEnding};
 use crate::model::cursor::{Position2D, SelectionMode};
 use crate::model::event::{CursorId, Event};
 use crate::primitives::display_width::{byte_offset_at_visual_column, str_width};
+use crate::primitives::indent_rules;
+use crate::primitives::text_transform;
 use crate::primitives::word_navigation::{
     find_word_end, find_word_end_right, find_word_start, find_word_start_left,
     find_word_start_right,
@@ -437,7 +439,7 @@ fn calculate_closing_delimiter_indent(
 /// When `use_tabs` is true, uses tab characters; otherwise uses spaces.
 /// The `indent_width` is the visual width in columns, and `tab_size` is
 /// how many columns a tab character represents.
-fn indent_to_string(indent_width: usize, use_tabs: bool, tab_size: usize) -> String {
+pub(crate) fn indent_to_string(indent_width: usize, use_tabs: bool, tab_size: usize) -> String {
     if use_tabs && tab_size > 0 {
         let num_tabs = indent_width / tab_size;
         let remaining_spaces = indent_width % tab_size;
@@ -546,6 +548,119 @@ fn handle_auto_dedent(
     });
 }
 
+/// Indent for a new line broken at `position` from a bracket left open on
+/// the line with text after it, or from the language's increase pattern
+fn declared_indent(state: &EditorState, position: usize, tab_size: usize) -> Option<usize> {
+    let line_start = line_start_at(&state.buffer, position);
+    let line =
+        String::from_utf8_lossy(&state.buffer.slice_bytes(line_start..position)).into_owned();
+    if let Some(column) = indent_rules::hanging_indent(&line, tab_size) {
+        return Some(column);
+    }
+    let rules = state.indent_rules.as_ref()?;
+    rules
+        .increases(&line)
+        .then(|| rules.indent_after(&line, tab_size))
+}
+
+/// Indent for a new line from tree-sitter, or from the surrounding lines in
+/// buffers without a grammar
+fn builtin_indent(state: &EditorState, position: usize, tab_size: usize) -> Option<usize> {
+    match state.highlighter.language() {
+        Some(language) => state.indent_calculator.borrow_mut().calculate_indent(
+            &state.buffer,
+            position,
+            language,
+            tab_size,
+        ),
+        None => Some(
+            crate::primitives::indent::IndentCalculator::calculate_indent_no_language(
+                &state.buffer,
+                position,
+                tab_size,
+            ),
+        ),
+    }
+}
+
+/// Handle a line the language's decrease pattern matches once `ch` is typed,
+/// such as `end`: move it out one level from the block it closes, then
+/// insert the character. Returns false when the line keeps its indent.
+fn handle_rule_dedent(
+    state: &mut EditorState,
+    events: &mut Vec<Event>,
+    data: &InsertCursorData,
+    ch: char,
+    tab_size: usize,
+) -> bool {
+    if state.indent_rules.is_none() || ch.is_whitespace() {
+        return false;
+    }
+    let before = state.get_text_range(data.line_start, data.insert_position);
+    let indent_len = before.len() - before.trim_start_matches([' ', '\t']).len();
+    if indent_len == before.len() {
+        return false;
+    }
+    let after_end = (data.insert_position + MAX_DEDENT_LINE_BYTES).min(state.buffer.len());
+    let after = state.get_text_range(data.insert_position, after_end);
+    let after = after.split(['\r', '\n']).next().unwrap_or_default();
+    let line = format!("{}{}{}", before, ch, after);
+    let previous = previous_nonblank_line(&state.buffer, data.line_start);
+    let Some(indent) = state
+        .indent_rules
+        .as_ref()
+        .and_then(|rules| rules.dedent(&line, &previous, tab_size))
+    else {
+        return false;
+    };
+
+    // Positions are all before the edits, as the events are applied as one
+    events.push(Event::Delete {
+        range: data.line_start..data.line_start + indent_len,
+        deleted_text: before[..indent_len].to_string(),
+        cursor_id: data.cursor_id,
+    });
+    if indent > 0 {
+        events.push(Event::Insert {
+            position: data.line_start,
+            text: indent_to_string(indent, state.use_tabs, tab_size),
+            cursor_id: data.cursor_id,
+        });
+    }
+    events.push(Event::Insert {
+        position: data.insert_position,
+        text: ch.to_string(),
+        cursor_id: data.cursor_id,
+    });
+    true
+}
+
+/// Most bytes after the cursor looked at when matching a decrease pattern
+const MAX_DEDENT_LINE_BYTES: usize = 1024;
+
+/// Start of the line containing `position`
+fn line_start_at(buffer: &Buffer, position: usize) -> usize {
+    let mut line_start = position;
+    while line_start > 0 && buffer.slice_bytes(line_start - 1..line_start).first() != Some(&b'\n') {
+        line_start -= 1;
+    }
+    line_start
+}
+
+/// The nearest non-blank line above the one starting at `line_start`
+fn previous_nonblank_line(buffer: &Buffer, line_start: usize) -> String {
+    let mut end = line_start;
+    while end > 0 {
+        let start = line_start_at(buffer, end - 1);
+        let line = String::from_utf8_lossy(&buffer.slice_bytes(start..end - 1)).into_owned();
+        if !line.trim().is_empty() {
+            return line;
+        }
+        end = start;
+    }
+    String::new()
+}
+
 /// Check if auto-close should happen based on character after cursor.
 fn should_auto_close(char_after: Option<u8>) -> bool {
     let is_alphanumeric_after = char_after
@@ -728,6 +843,14 @@ fn insert_char_events(
             continue;
         }
 
+        // Try dedenting a line the language's rules say closes a block
+        if auto_indent
+            && data.selection.is_none()
+            && handle_rule_dedent(state, events, &data, ch, tab_size)
+        {
+            continue;
+        }
+
         // Try auto-close
         if let Some(close_char) = auto_close_char {
             if should_auto_close(data.char_after) {
@@ -795,6 +918,281 @@ where
     }
 }
 
+/// Rewrite the lines of each selection with `transform`, keeping the line
+/// ending style and a trailing line ending
+fn transform_lines<F>(state: &mut EditorState, events: &mut Vec<Event>, transform: F)
+where
+    F: Fn(&mut Vec<&str>),
+{
+    // Process cursors in reverse order to avoid position shifts
+    let line_ending = state.buffer.line_ending().as_str();
+    let mut selections: Vec<_> = state
+        .cursors
+        .iter()
+        .filter_map(|(cursor_id, cursor)| cursor.selection_range().map(|range| (cursor_id, range)))
+        .collect();
+    selections.sort_by_key(|(_, range)| std::cmp::Reverse(range.start));
+
+    for (cursor_id, range) in selections {
+        let text = state.get_text_range(range.start, range.end);
+        let mut lines: Vec<&str> = text.lines().collect();
+        let ends_with_newline = text.ends_with('\n') || text.ends_with("\r\n");
+        if lines.len() < 2 {
+            continue;
+        }
+
+        transform(&mut lines);
+        let mut new_text = lines.join(line_ending);
+        if ends_with_newline {
+            new_text.push_str(line_ending);
+        }
+
+        if new_text != text {
+            events.push(Event::Delete {
+                range: range.clone(),
+                deleted_text: text,
+                cursor_id,
+            });
+            events.push(Event::Insert {
+                position: range.start,
+                text: new_text,
+                cursor_id,
+            });
+        }
+    }
+}
+
+/// Whether any cursor has a selection that isn't empty
+fn has_nonempty_selection(state: &EditorState) -> bool {
+    state.cursors.iter().any(|(_, cursor)| {
+        cursor
+            .selection_range()
+            .is_some_and(|range| !range.is_empty())
+    })
+}
+
+/// Insert a copy of each selection right after it and select the copy
+///
+/// Cursors without a selection are left where they are.
+fn duplicate_selections(state: &mut EditorState, events: &mut Vec<Event>) {
+    let mut selections: Vec<_> = state
+        .cursors
+        .iter()
+        .filter_map(|(cursor_id, cursor)| {
+            let range = cursor.selection_range().filter(|range| !range.is_empty())?;
+            Some((cursor_id, *cursor, range))
+        })
+        .collect();
+    selections.sort_by_key(|(_, _, range)| (range.start, range.end));
+    selections.dedup_by_key(|(_, _, range)| range.clone());
+
+    let copies: Vec<(usize, String)> = selections
+        .iter()
+        .map(|(_, _, range)| (range.end, state.get_text_range(range.start, range.end)))
+        .collect();
+    // The edits carry no cursor, so each cursor lands where its MoveCursor says
+    for (position, text) in copies.iter().rev() {
+        events.push(Event::Insert {
+            position: *position,
+            text: text.clone(),
+            cursor_id: CursorId::UNDO_SENTINEL,
+        });
+    }
+
+    let mut shift = 0;
+    for ((cursor_id, cursor, range), (_, text)) in selections.iter().zip(&copies) {
+        let copy_start = range.end + shift;
+        let copy_end = copy_start + text.len();
+        shift += text.len();
+        // Keep the direction of the original selection
+        let (new_position, new_anchor) = if cursor.position < range.end {
+            (copy_start, copy_end)
+        } else {
+            (copy_end, copy_start)
+        };
+        events.push(Event::MoveCursor {
+            cursor_id: *cursor_id,
+            old_position: cursor.position,
+            new_position,
+            old_anchor: cursor.anchor,
+            new_anchor: Some(new_anchor),
+            old_sticky_column: cursor.sticky_column,
+            new_sticky_column: 0,
+        });
+    }
+}
+
+/// Join each cursor's line with the next, or the lines of each selection
+/// with one another
+///
+/// Each join drops the trailing whitespace of the upper line, the line
+/// ending and the indentation of the lower line, and puts a single space
+/// between them unless either side is empty or the join is just inside a
+/// bracket. The cursor ends up at the last join.
+fn join_lines(state: &mut EditorState, events: &mut Vec<Event>, estimated_line_length: usize) {
+    // Join edits by start, as (end, separator); cursors on the same lines
+    // share them
+    let mut joins: std::collections::BTreeMap<usize, (usize, &'static str)> =
+        std::collections::BTreeMap::new();
+    let mut cursor_joins = Vec::new();
+
+    let cursors: Vec<_> = state.cursors.iter().map(|(id, c)| (id, *c)).collect();
+    for (cursor_id, cursor) in cursors {
+        let range = cursor
+            .selection_range()
+            .unwrap_or(cursor.position..cursor.position);
+        // A selection ending at the start of a line doesn't include that line
+        let last =
+            if range.end > range.start && line_start_at(&state.buffer, range.end) == range.end {
+                range.end - 1
+            } else {
+                range.end
+            };
+
+        let mut iter = state
+            .buffer
+            .line_iterator(range.start, estimated_line_length);
+        let mut lines = Vec::new();
+        while let Some((start, content)) = iter.next_line() {
+            // A single line joins with the next
+            if start > last && lines.len() > 1 {
+                break;
+            }
+            lines.push((start, content));
+            if start > last {
+                break;
+            }
+        }
+
+        let mut last_join = None;
+        for pair in lines.windows(2) {
+            let (upper_start, upper) = &pair[0];
+            let (lower_start, lower) = &pair[1];
+            if !upper.ends_with('\n') {
+                break;
+            }
+            let upper_text = upper.trim_end();
+            let lower_indent = lower.len() - lower.trim_start_matches([' ', '\t']).len();
+            let lower_text = lower.trim();
+            let separator = if upper_text.trim_start().is_empty()
+                || lower_text.is_empty()
+                || upper_text.ends_with(['(', '['])
+                || lower_text.starts_with([')', ']'])
+            {
+                ""
+            } else {
+                " "
+            };
+            // A blank line's join starts after its whitespace, where the
+            // previous join ends
+            let upper_indent = upper.len() - upper.trim_start_matches([' ', '\t']).len();
+            let join_start = upper_start + upper_text.len().max(upper_indent);
+            joins.insert(join_start, (lower_start + lower_indent, separator));
+            last_join = Some(join_start);
+        }
+        if let Some(join_start) = last_join {
+            cursor_joins.push((cursor_id, cursor, join_start));
+        }
+    }
+
+    for (&start, &(end, separator)) in joins.iter().rev() {
+        events.push(Event::Delete {
+            range: start..end,
+            deleted_text: state.get_text_range(start, end),
+            cursor_id: CursorId::UNDO_SENTINEL,
+        });
+        if !separator.is_empty() {
+            events.push(Event::Insert {
+                position: start,
+                text: separator.to_string(),
+                cursor_id: CursorId::UNDO_SENTINEL,
+            });
+        }
+    }
+
+    for (cursor_id, cursor, join_start) in cursor_joins {
+        let shift: isize = joins
+            .range(..join_start)
+            .map(|(start, (end, separator))| separator.len() as isize - (end - start) as isize)
+            .sum();
+        let separator_len = joins.get(&join_start).map_or(0, |(_, sep)| sep.len());
+        let new_position = (join_start as isize + shift) as usize + separator_len;
+        events.push(Event::MoveCursor {
+            cursor_id,
+            old_position: cursor.position,
+            new_position,
+            old_anchor: cursor.anchor,
+            new_anchor: None,
+            old_sticky_column: cursor.sticky_column,
+            new_sticky_column: 0,
+        });
+    }
+}
+
+/// Add `delta` to the number at or after each cursor on its line, leaving
+/// the cursor on the number's last digit
+fn increment_numbers(
+    state: &mut EditorState,
+    events: &mut Vec<Event>,
+    delta: i64,
+    estimated_line_length: usize,
+) {
+    // Edits by start, as (end, new text); cursors on one number share it
+    let mut edits: std::collections::BTreeMap<usize, (usize, String)> =
+        std::collections::BTreeMap::new();
+    let mut cursor_edits = Vec::new();
+
+    let cursors: Vec<_> = state.cursors.iter().map(|(id, c)| (id, *c)).collect();
+    for (cursor_id, cursor) in cursors {
+        let mut iter = state
+            .buffer
+            .line_iterator(cursor.position, estimated_line_length);
+        let line_start = iter.current_position();
+        let Some((_, content)) = iter.next_line() else {
+            continue;
+        };
+        let line = content.trim_end_matches(['\n', '\r']);
+        let offset = cursor.position - line_start;
+        let Some(edit) = text_transform::increment_number(line, offset, delta) else {
+            continue;
+        };
+        let start = line_start + edit.start;
+        edits.insert(start, (line_start + edit.end, edit.text));
+        cursor_edits.push((cursor_id, cursor, start));
+    }
+
+    for (&start, (end, text)) in edits.iter().rev() {
+        events.push(Event::Delete {
+            range: start..*end,
+            deleted_text: state.get_text_range(start, *end),
+            cursor_id: CursorId::UNDO_SENTINEL,
+        });
+        events.push(Event::Insert {
+            position: start,
+            text: text.clone(),
+            cursor_id: CursorId::UNDO_SENTINEL,
+        });
+    }
+
+    for (cursor_id, cursor, start) in cursor_edits {
+        let shift: isize = edits
+            .range(..start)
+            .map(|(edit_start, (end, text))| text.len() as isize - (end - edit_start) as isize)
+            .sum();
+        let new_len = edits.get(&start).map_or(1, |(_, text)| text.len());
+        let new_position = (start as isize + shift) as usize + new_len - 1;
+        events.push(Event::MoveCursor {
+            cursor_id,
+            old_position: cursor.position,
+            new_position,
+            old_anchor: cursor.anchor,
+            new_anchor: None,
+            old_sticky_column: cursor.sticky_column,
+            new_sticky_column: 0,
+        });
+    }
+}
+
 /// Convert an action into a sequence of events that can be applied to the editor state
 ///
 /// # Parameters
@@ -916,55 +1314,22 @@ pub fn action_to_events(
 
                 if auto_indent {
                     let use_tabs = state.use_tabs;
-                    if let Some(language) = state.highlighter.language() {
-                        // Use tree-sitter-based indent when we have a highlighter
-                        if let Some(indent_width) = state
-                            .indent_calculator
-                            .borrow_mut()
-                            .calculate_indent(&state.buffer, indent_position, language, tab_size)
-                        {
-                            let indent_str = indent_to_string(indent_width, use_tabs, tab_size);
-                            text.push_str(&indent_str);
-
-                            // For bracket expansion, add another newline with dedented closing bracket
-                            if bracket_expansion {
-                                // Record where cursor should end up (end of cursor line)
-                                cursor_line_end_position =
-                                    Some(indent_position + line_ending.len() + indent_str.len());
-
-                                // Calculate the dedent for the closing bracket line
-                                // It should match the indent of the line containing the opening bracket
-                                let opening_bracket_indent =
-                                    crate::primitives::indent::IndentCalculator::get_line_indent_at_position(
-                                        &state.buffer,
-                                        indent_position.saturating_sub(1),
-                                        tab_size,
-                                    );
-                                text.push_str(line_ending);
-                                text.push_str(&indent_to_string(
-                                    opening_bracket_indent,
-                                    use_tabs,
-                                    tab_size,
-                                ));
-                            }
-                        }
-                    } else {
-                        // Fallback for files without syntax highlighting (e.g., .txt)
-                        let indent_width =
-                            crate::primitives::indent::IndentCalculator::calculate_indent_no_language(
-                                &state.buffer,
-                                indent_position,
-                                tab_size,
-                            );
+                    // A hanging bracket or the language's declared rules come
+                    // first, then tree-sitter, then the language-less fallback
+                    let indent_width = declared_indent(state, indent_position, tab_size)
+                        .or_else(|| builtin_indent(state, indent_position, tab_size));
+                    if let Some(indent_width) = indent_width {
                         let indent_str = indent_to_string(indent_width, use_tabs, tab_size);
                         text.push_str(&indent_str);
 
-                        // For bracket expansion in non-language files
+                        // For bracket expansion, add another newline with dedented closing bracket
                         if bracket_expansion {
                             // Record where cursor should end up (end of cursor line)
                             cursor_line_end_position =
                                 Some(indent_position + line_ending.len() + indent_str.len());
 
+                            // Calculate the dedent for the closing bracket line
+                            // It should match the indent of the line containing the opening bracket
                             let opening_bracket_indent =
                                 crate::primitives::indent::IndentCalculator::get_line_indent_at_position(
                                     &state.buffer,
@@ -2279,6 +2644,7 @@ pub fn action_to_events(
                 &mut events,
                 LineMoveDirection::Up,
                 estimated_line_length,
+                auto_indent.then_some(tab_size),
             );
         }
 
@@ -2288,6 +2654,7 @@ pub fn action_to_events(
                 &mut events,
                 LineMoveDirection::Down,
                 estimated_line_length,
+                auto_indent.then_some(tab_size),
             );
         }
 
@@ -2332,47 +2699,39 @@ pub fn action_to_events(
             transform_case(state, &mut events, |s| s.to_lowercase());
         }
 
+        Action::ToTitleCase => {
+            transform_case(state, &mut events, text_transform::title_case);
+        }
+
+        Action::ToCamelCase => {
+            transform_case(state, &mut events, text_transform::camel_case);
+        }
+
+        Action::ToSnakeCase => {
+            transform_case(state, &mut events, text_transform::snake_case);
+        }
+
+        Action::ToKebabCase => {
+            transform_case(state, &mut events, text_transform::kebab_case);
+        }
+
+        Action::IncrementNumber => {
+            increment_numbers(state, &mut events, 1, estimated_line_length);
+        }
+
+        Action::DecrementNumber => {
+            increment_numbers(state, &mut events, -1, estimated_line_length);
+        }
+
         Action::SortLines => {
-            // Sort selected lines alphabetically
-            // Process cursors in reverse order to avoid position shifts
-            let line_ending = state.buffer.line_ending().as_str();
-            let mut selections: Vec<_> = state
-                .cursors
-                .iter()
-                .filter_map(|(cursor_id, cursor)| {
-                    cursor.selection_range().map(|range| (cursor_id, range))
-                })
-                .collect();
-            selections.sort_by_key(|(_, range)| std::cmp::Reverse(range.start));
-
-            for (cursor_id, range) in selections {
-                let text = state.get_text_range(range.start, range.end);
-                // Split into lines, preserving the original line ending style
-                let mut lines: Vec<&str> = text.lines().collect();
-                // Check if original text ends with a newline
-                let ends_with_newline = text.ends_with('\n') || text.ends_with("\r\n");
-
-                if lines.len() > 1 {
-                    lines.sort();
-                    let mut sorted_text = lines.join(line_ending);
-                    if ends_with_newline {
-                        sorted_text.push_str(line_ending);
-                    }
+            transform_lines(state, &mut events, |lines| lines.sort());
+        }
 
-                    if sorted_text != text {
-                        events.push(Event::Delete {
-                            range: range.clone(),
-                            deleted_text: text,
-                            cursor_id,
-                        });
-                        events.push(Event::Insert {
-                            position: range.start,
-                            text: sorted_text,
-                            cursor_id,
-                        });
-                    }
-                }
-            }
+        Action::UniqueLines => {
+            transform_lines(state, &mut events, |lines| {
+                let mut seen = std::collections::HashSet::new();
+                lines.retain(|line| seen.insert(*line));
+            });
         }
 
         Action::OpenLine => {
@@ -2388,7 +2747,16 @@ pub fn action_to_events(
             }
         }
 
-        Action::DuplicateLine => {
+        Action::DuplicateSelection if has_nonempty_selection(state) => {
+            duplicate_selections(state, &mut events);
+        }
+
+        Action::JoinLines => {
+            join_lines(state, &mut events, estimated_line_length);
+        }
+
+        // Without a selection, Duplicate Selection duplicates the line
+        Action::DuplicateLine | Action::DuplicateSelection => {
             // Duplicate the current line (or selected lines) below
             // Process cursors in reverse order to avoid position shifts
             let mut cursor_data: Vec<_> = state
@@ -2520,10 +2888,15 @@ pub fn action_to_events(
         Action::Quit
         | Action::ForceQuit
         | Action::Detach
+        | Action::CollabShare
+        | Action::CollabJoin
+        | Action::CollabStop
+        | Action::FollowUser
         | Action::Save
         | Action::SaveAs
         | Action::Open
         | Action::SwitchProject
+        | Action::SwitchProfile
         | Action::New
         | Action::Close
         | Action::CloseTab
@@ -2532,6 +2905,12 @@ pub fn action_to_events(
         | Action::PrevBuffer
         | Action::SwitchToPreviousTab
         | Action::SwitchToTabByName
+        | Action::TogglePinTab
+        | Action::SetTabGroup
+        | Action::CloseOtherTabs
+        | Action::CloseTabsToRight
+        | Action::CloseTabsToLeft
+        | Action::CloseAllTabs
         | Action::NavigateBack
         | Action::NavigateForward
         | Action::SplitHorizontal
@@ -2548,6 +2927,8 @@ pub fn action_to_events(
         | Action::YankToLineEnd
         | Action::YankToLineStart
         | Action::AddCursorNextMatch
+        | Action::SkipOccurrence
+        | Action::SelectAllOccurrences
         | Action::AddCursorAbove
         | Action::AddCursorBelow
         | Action::CommandPalette
@@ -2555,10 +2936,13 @@ pub fn action_to_events(
         | Action::ShowHelp
         | Action::ToggleLineWrap
         | Action::ToggleComposeMode
+        | Action::ToggleFocusMode
+        | Action::InsertDigraph
         | Action::SetComposeWidth
         | Action::IncreaseSplitSize
         | Action::DecreaseSplitSize
         | Action::ToggleMaximizeSplit
+        | Action::ToggleScrollLock
         | Action::Undo
         | Action::Redo
         | Action::GoToMatchingBracket
@@ -2568,11 +2952,21 @@ pub fn action_to_events(
         | Action::ShowWarnings
         | Action::ShowStatusLog
         | Action::ShowLspStatus
+        | Action::LspStatusPanel
+        | Action::ShowBackgroundTasks
+        | Action::ReloadPlugin
+        | Action::MemoryReport
+        | Action::ShowTelemetryData
+        | Action::UpdateFreshNow
+        | Action::ManageWorkspaceTrust
         | Action::ClearWarnings
         | Action::SmartHome
         | Action::ToggleComment
+        | Action::ToggleBlockComment
         | Action::SetBookmark(_)
         | Action::JumpToBookmark(_)
+        | Action::SetNamedMark(_)
+        | Action::JumpToNamedMark(_)
         | Action::ClearBookmark(_)
         | Action::ListBookmarks
         | Action::ToggleSearchCaseSensitive
@@ -2590,6 +2984,8 @@ pub fn action_to_events(
         | Action::PlayLastMacro
         | Action::PromptSetBookmark
         | Action::PromptJumpToBookmark
+        | Action::PromptSetNamedMark
+        | Action::PromptJumpToNamedMark
         | Action::PromptConfirm
         | Action::PromptConfirmWithText(_)
         | Action::PromptCancel
@@ -2630,6 +3026,7 @@ pub fn action_to_events(
         | Action::ToggleFileExplorer
         | Action::ToggleMenuBar
         | Action::ToggleTabBar
+        | Action::TogglePreviewTabs
         | Action::FocusFileExplorer
         | Action::FocusEditor
         | Action::SetBackground
@@ -2645,14 +3042,19 @@ pub fn action_to_events(
         | Action::FileExplorerNewFile
         | Action::FileExplorerNewDirectory
         | Action::FileExplorerDelete
+        | Action::FileExplorerUndoDelete
         | Action::FileExplorerRename
         | Action::FileExplorerToggleHidden
         | Action::FileExplorerToggleGitignored
+        | Action::FileExplorerNextRoot
         | Action::FileExplorerSearchClear
         | Action::FileExplorerSearchBackspace
+        | Action::OpenLinkUnderCursor
         | Action::LspCompletion
         | Action::LspGotoDefinition
         | Action::LspReferences
+        | Action::LspPeekDefinition
+        | Action::LspPeekReferences
         | Action::LspRename
         | Action::LspHover
         | Action::LspSignatureHelp
@@ -2662,7 +3064,11 @@ pub fn action_to_events(
         | Action::ToggleInlayHints
         | Action::ToggleMouseHover
         | Action::ToggleLineNumbers
+        | Action::ToggleReadOnly
+        | Action::ToggleImagePreview
         | Action::ToggleMouseCapture
+        | Action::ToggleAccessibility
+        | Action::AnnounceContext
         | Action::DumpConfig
         | Action::Search
         | Action::FindInSelection
@@ -2672,6 +3078,8 @@ pub fn action_to_events(
         | Action::FindSelectionPrevious
         | Action::Replace
         | Action::QueryReplace
+        | Action::StructuralSearch
+        | Action::StructuralReplace
         | Action::MenuActivate
         | Action::MenuClose
         | Action::MenuLeft
@@ -2691,6 +3099,7 @@ pub fn action_to_events(
         | Action::SelectLocale
         | Action::Revert
         | Action::ToggleAutoRevert
+        | Action::LocalHistory
         | Action::FormatBuffer
         | Action::TrimTrailingWhitespace
         | Action::EnsureFinalNewline
@@ -2716,11 +3125,30 @@ pub fn action_to_events(
         | Action::ReloadWithEncoding
         | Action::SetLanguage
         | Action::ToggleIndentationStyle
+        | Action::ConvertIndentationToSpaces
+        | Action::ConvertIndentationToTabs
+        | Action::ReindentBuffer
+        | Action::InsertSequence
+        | Action::InsertDateTime
+        | Action::AlignSelection
         | Action::ToggleTabIndicators
+        | Action::CycleRenderWhitespace
         | Action::ToggleDebugHighlights
+        | Action::ToggleRenderProfiler
         | Action::ResetBufferSettings
         | Action::ShellCommand
         | Action::ShellCommandReplace
+        | Action::FilterThroughCommand
+        | Action::ToggleWatchTask
+        | Action::ShowWatchOutput
+        | Action::QuickfixNext
+        | Action::QuickfixPrev
+        | Action::QuickfixFirst
+        | Action::QuickfixLast
+        | Action::QuickfixOlder
+        | Action::QuickfixNewer
+        | Action::QuickfixShow
+        | Action::QuickfixHistory
         | Action::CalibrateInput
         | Action::EventDebug
         | Action::OpenKeybindingEditor => return None,
@@ -3028,6 +3456,110 @@ mod tests {
         assert_eq!(state.cursors.primary().position, 0);
     }
 
+    /// A state holding `text`, with the cursor at `position` and the anchor
+    /// at `anchor`
+    fn state_with(text: &str, position: usize, anchor: Option<usize>) -> EditorState {
+        let mut state = EditorState::new(
+            80,
+            24,
+            crate::config::LARGE_FILE_THRESHOLD_BYTES as usize,
+            test_fs(),
+        );
+        state.apply(&Event::Insert {
+            position: 0,
+            text: text.to_string(),
+            cursor_id: CursorId(0),
+        });
+        state.apply(&Event::MoveCursor {
+            cursor_id: CursorId(0),
+            old_position: state.cursors.primary().position,
+            new_position: position,
+            old_anchor: None,
+            new_anchor: anchor,
+            old_sticky_column: 0,
+            new_sticky_column: 0,
+        });
+        state
+    }
+
+    fn run(state: &mut EditorState, action: Action) {
+        let events = action_to_events(state, action, 4, false, 80, 24).unwrap();
+        for event in events {
+            state.apply(&event);
+        }
+    }
+
+    #[test]
+    fn test_join_lines_with_next() {
+        let mut state = state_with("  let a =  \n      1;\nnext", 0, None);
+        run(&mut state, Action::JoinLines);
+        assert_eq!(state.buffer.to_string().unwrap(), "  let a = 1;\nnext");
+        assert_eq!(state.cursors.primary().position, 10);
+    }
+
+    #[test]
+    fn test_join_selected_lines_inside_brackets() {
+        let text = "call(\n    a,\n    b\n)\nrest";
+        let mut state = state_with(text, 0, Some(20));
+        run(&mut state, Action::JoinLines);
+        assert_eq!(state.buffer.to_string().unwrap(), "call(a, b)\nrest");
+        assert_eq!(state.cursors.primary().position, 9);
+        assert_eq!(state.cursors.primary().anchor, None);
+    }
+
+    #[test]
+    fn test_join_lines_on_last_line_noop() {
+        let mut state = state_with("a\nb", 2, None);
+        run(&mut state, Action::JoinLines);
+        assert_eq!(state.buffer.to_string().unwrap(), "a\nb");
+    }
+
+    #[test]
+    fn test_unique_lines() {
+        let mut state = state_with("b\na\nb\na\nc\n", 0, Some(10));
+        run(&mut state, Action::UniqueLines);
+        assert_eq!(state.buffer.to_string().unwrap(), "b\na\nc\n");
+    }
+
+    #[test]
+    fn test_duplicate_selection_selects_copy() {
+        let mut state = state_with("let value = 1;", 9, Some(4));
+        run(&mut state, Action::DuplicateSelection);
+        assert_eq!(state.buffer.to_string().unwrap(), "let valuevalue = 1;");
+        assert_eq!(state.cursors.primary().position, 14);
+        assert_eq!(state.cursors.primary().anchor, Some(9));
+    }
+
+    #[test]
+    fn test_increment_number_at_or_after_cursor() {
+        let mut state = state_with("x = 9;\ny = -1;", 0, None);
+        run(&mut state, Action::IncrementNumber);
+        assert_eq!(state.buffer.to_string().unwrap(), "x = 10;\ny = -1;");
+        // On the last digit
+        assert_eq!(state.cursors.primary().position, 5);
+
+        let mut state = state_with("x = 9;\ny = -1;", 8, None);
+        run(&mut state, Action::DecrementNumber);
+        assert_eq!(state.buffer.to_string().unwrap(), "x = 9;\ny = -2;");
+    }
+
+    #[test]
+    fn test_to_snake_case_selection() {
+        let mut state = state_with("let parseHttpResponse = 1;", 4, Some(21));
+        run(&mut state, Action::ToSnakeCase);
+        assert_eq!(
+            state.buffer.to_string().unwrap(),
+            "let parse_http_response = 1;"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_selection_without_selection_duplicates_line() {
+        let mut state = state_with("a\nb\n", 0, None);
+        run(&mut state, Action::DuplicateSelection);
+        assert_eq!(state.buffer.to_string().unwrap(), "a\na\nb\n");
+    }
+
     #[test]
     fn test_move_line_down_last_line_noop() {
         let mut state = EditorState::new(
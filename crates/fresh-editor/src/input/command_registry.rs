@@ -782,4 +782,33 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_mouse_targets_have_commands() {
+        use crate::app::types::TabContextMenuItem;
+
+        // Everything a click on the tab context menu or the status bar does
+        // must be reachable from the keyboard too
+        let registry = CommandRegistry::new();
+        let commands = registry.get_all();
+        let mut actions: Vec<Action> = TabContextMenuItem::all()
+            .iter()
+            .map(TabContextMenuItem::action)
+            .collect();
+        actions.extend([
+            Action::SetLineEnding,
+            Action::SetEncoding,
+            Action::SetLanguage,
+            Action::ShowLspStatus,
+            Action::ShowWarnings,
+            Action::ShowStatusLog,
+        ]);
+        for action in actions {
+            assert!(
+                commands.iter().any(|cmd| cmd.action == action),
+                "{:?} should have a command palette entry",
+                action
+            );
+        }
+    }
 }
@@ -174,6 +174,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.switch_profile").to_string(),
+            description: t!("cmd.switch_profile_desc").to_string(),
+            action: Action::SwitchProfile,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.save_file").to_string(),
             description: t!("cmd.save_file_desc").to_string(),
@@ -230,6 +238,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.local_history").to_string(),
+            description: t!("cmd.local_history_desc").to_string(),
+            action: Action::LocalHistory,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.format_buffer").to_string(),
             description: t!("cmd.format_buffer_desc").to_string(),
@@ -270,6 +286,38 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![context_keys::SESSION_MODE.to_string()],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.collab_share").to_string(),
+            description: t!("cmd.collab_share_desc").to_string(),
+            action: Action::CollabShare,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.collab_join").to_string(),
+            description: t!("cmd.collab_join_desc").to_string(),
+            action: Action::CollabJoin,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.collab_stop").to_string(),
+            description: t!("cmd.collab_stop_desc").to_string(),
+            action: Action::CollabStop,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.follow_user").to_string(),
+            description: t!("cmd.follow_user_desc").to_string(),
+            action: Action::FollowUser,
+            contexts: vec![],
+            custom_contexts: vec![context_keys::SESSION_MODE.to_string()],
+            source: CommandSource::Builtin,
+        },
         // Edit operations
         Command {
             name: t!("cmd.undo").to_string(),
@@ -375,6 +423,38 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.transform_titlecase").to_string(),
+            description: t!("cmd.transform_titlecase_desc").to_string(),
+            action: Action::ToTitleCase,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.transform_camelcase").to_string(),
+            description: t!("cmd.transform_camelcase_desc").to_string(),
+            action: Action::ToCamelCase,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.transform_snakecase").to_string(),
+            description: t!("cmd.transform_snakecase_desc").to_string(),
+            action: Action::ToSnakeCase,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.transform_kebabcase").to_string(),
+            description: t!("cmd.transform_kebabcase_desc").to_string(),
+            action: Action::ToKebabCase,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.sort_lines").to_string(),
             description: t!("cmd.sort_lines_desc").to_string(),
@@ -383,6 +463,54 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.unique_lines").to_string(),
+            description: t!("cmd.unique_lines_desc").to_string(),
+            action: Action::UniqueLines,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.increment_number").to_string(),
+            description: t!("cmd.increment_number_desc").to_string(),
+            action: Action::IncrementNumber,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.decrement_number").to_string(),
+            description: t!("cmd.decrement_number_desc").to_string(),
+            action: Action::DecrementNumber,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.insert_sequence").to_string(),
+            description: t!("cmd.insert_sequence_desc").to_string(),
+            action: Action::InsertSequence,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.insert_date_time").to_string(),
+            description: t!("cmd.insert_date_time_desc").to_string(),
+            action: Action::InsertDateTime,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.align_selection").to_string(),
+            description: t!("cmd.align_selection_desc").to_string(),
+            action: Action::AlignSelection,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.open_line").to_string(),
             description: t!("cmd.open_line_desc").to_string(),
@@ -399,6 +527,46 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.duplicate_selection").to_string(),
+            description: t!("cmd.duplicate_selection_desc").to_string(),
+            action: Action::DuplicateSelection,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.move_line_up").to_string(),
+            description: t!("cmd.move_line_up_desc").to_string(),
+            action: Action::MoveLineUp,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.move_line_down").to_string(),
+            description: t!("cmd.move_line_down_desc").to_string(),
+            action: Action::MoveLineDown,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.join_lines").to_string(),
+            description: t!("cmd.join_lines_desc").to_string(),
+            action: Action::JoinLines,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.insert_digraph").to_string(),
+            description: t!("cmd.insert_digraph_desc").to_string(),
+            action: Action::InsertDigraph,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.recenter").to_string(),
             description: t!("cmd.recenter_desc").to_string(),
@@ -473,6 +641,38 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.skip_occurrence").to_string(),
+            description: t!("cmd.skip_occurrence_desc").to_string(),
+            action: Action::SkipOccurrence,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.select_all_occurrences").to_string(),
+            description: t!("cmd.select_all_occurrences_desc").to_string(),
+            action: Action::SelectAllOccurrences,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.toggle_search_case_sensitive").to_string(),
+            description: t!("cmd.toggle_search_case_sensitive_desc").to_string(),
+            action: Action::ToggleSearchCaseSensitive,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.toggle_search_whole_word").to_string(),
+            description: t!("cmd.toggle_search_whole_word_desc").to_string(),
+            action: Action::ToggleSearchWholeWord,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.remove_secondary_cursors").to_string(),
             description: t!("cmd.remove_secondary_cursors_desc").to_string(),
@@ -514,6 +714,54 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.toggle_pin_tab").to_string(),
+            description: t!("cmd.toggle_pin_tab_desc").to_string(),
+            action: Action::TogglePinTab,
+            contexts: vec![KeyContext::Normal, KeyContext::Terminal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.set_tab_group").to_string(),
+            description: t!("cmd.set_tab_group_desc").to_string(),
+            action: Action::SetTabGroup,
+            contexts: vec![KeyContext::Normal, KeyContext::Terminal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.close_other_tabs").to_string(),
+            description: t!("cmd.close_other_tabs_desc").to_string(),
+            action: Action::CloseOtherTabs,
+            contexts: vec![KeyContext::Normal, KeyContext::Terminal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.close_tabs_to_right").to_string(),
+            description: t!("cmd.close_tabs_to_right_desc").to_string(),
+            action: Action::CloseTabsToRight,
+            contexts: vec![KeyContext::Normal, KeyContext::Terminal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.close_tabs_to_left").to_string(),
+            description: t!("cmd.close_tabs_to_left_desc").to_string(),
+            action: Action::CloseTabsToLeft,
+            contexts: vec![KeyContext::Normal, KeyContext::Terminal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.close_all_tabs").to_string(),
+            description: t!("cmd.close_all_tabs_desc").to_string(),
+            action: Action::CloseAllTabs,
+            contexts: vec![KeyContext::Normal, KeyContext::Terminal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // Split operations
         Command {
             name: t!("cmd.split_horizontal").to_string(),
@@ -579,6 +827,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.toggle_scroll_lock").to_string(),
+            description: t!("cmd.toggle_scroll_lock_desc").to_string(),
+            action: Action::ToggleScrollLock,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // View toggles
         Command {
             name: t!("cmd.toggle_line_numbers").to_string(),
@@ -588,6 +844,22 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.toggle_read_only").to_string(),
+            description: t!("cmd.toggle_read_only_desc").to_string(),
+            action: Action::ToggleReadOnly,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.toggle_image_preview").to_string(),
+            description: t!("cmd.toggle_image_preview_desc").to_string(),
+            action: Action::ToggleImagePreview,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.debug_toggle_highlight").to_string(),
             description: t!("cmd.debug_toggle_highlight_desc").to_string(),
@@ -596,6 +868,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.debug_toggle_render_profiler").to_string(),
+            description: t!("cmd.debug_toggle_render_profiler_desc").to_string(),
+            action: Action::ToggleRenderProfiler,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // Buffer settings commands
         Command {
             name: t!("cmd.set_tab_size").to_string(),
@@ -645,6 +925,30 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.convert_indentation_to_spaces").to_string(),
+            description: t!("cmd.convert_indentation_to_spaces_desc").to_string(),
+            action: Action::ConvertIndentationToSpaces,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.convert_indentation_to_tabs").to_string(),
+            description: t!("cmd.convert_indentation_to_tabs_desc").to_string(),
+            action: Action::ConvertIndentationToTabs,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.reindent_buffer").to_string(),
+            description: t!("cmd.reindent_buffer_desc").to_string(),
+            action: Action::ReindentBuffer,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.toggle_tab_indicators").to_string(),
             description: t!("cmd.toggle_tab_indicators_desc").to_string(),
@@ -653,6 +957,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.cycle_render_whitespace").to_string(),
+            description: t!("cmd.cycle_render_whitespace_desc").to_string(),
+            action: Action::CycleRenderWhitespace,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.reset_buffer_settings").to_string(),
             description: t!("cmd.reset_buffer_settings_desc").to_string(),
@@ -701,6 +1013,22 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.toggle_accessibility").to_string(),
+            description: t!("cmd.toggle_accessibility_desc").to_string(),
+            action: Action::ToggleAccessibility,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.announce_context").to_string(),
+            description: t!("cmd.announce_context_desc").to_string(),
+            action: Action::AnnounceContext,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // File explorer
         Command {
             name: t!("cmd.toggle_file_explorer").to_string(),
@@ -738,6 +1066,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.toggle_preview_tabs").to_string(),
+            description: t!("cmd.toggle_preview_tabs_desc").to_string(),
+            action: Action::TogglePreviewTabs,
+            contexts: vec![KeyContext::Normal, KeyContext::FileExplorer],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.focus_file_explorer").to_string(),
             description: t!("cmd.focus_file_explorer_desc").to_string(),
@@ -786,6 +1122,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.explorer_undo_delete").to_string(),
+            description: t!("cmd.explorer_undo_delete_desc").to_string(),
+            action: Action::FileExplorerUndoDelete,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.explorer_rename").to_string(),
             description: t!("cmd.explorer_rename_desc").to_string(),
@@ -810,6 +1154,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.file_explorer_next_root").to_string(),
+            description: t!("cmd.file_explorer_next_root_desc").to_string(),
+            action: Action::FileExplorerNextRoot,
+            contexts: vec![KeyContext::FileExplorer],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // View
         Command {
             name: t!("cmd.toggle_line_wrap").to_string(),
@@ -820,6 +1172,14 @@ pub fn get_all_commands() -> Vec<Command> {
             source: CommandSource::Builtin,
         },
         // Note: Compose mode commands removed - markdown_compose plugin provides these
+        Command {
+            name: t!("cmd.toggle_focus_mode").to_string(),
+            description: t!("cmd.toggle_focus_mode_desc").to_string(),
+            action: Action::ToggleFocusMode,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.set_background").to_string(),
             description: t!("cmd.set_background_desc").to_string(),
@@ -903,6 +1263,22 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.structural_search").to_string(),
+            description: t!("cmd.structural_search_desc").to_string(),
+            action: Action::StructuralSearch,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.structural_replace").to_string(),
+            description: t!("cmd.structural_replace_desc").to_string(),
+            action: Action::StructuralReplace,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // Navigation
         Command {
             name: t!("cmd.goto_line").to_string(),
@@ -928,6 +1304,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.open_link_under_cursor").to_string(),
+            description: t!("cmd.open_link_under_cursor_desc").to_string(),
+            action: Action::OpenLinkUnderCursor,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.goto_definition").to_string(),
             description: t!("cmd.goto_definition_desc").to_string(),
@@ -952,6 +1336,22 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.peek_definition").to_string(),
+            description: t!("cmd.peek_definition_desc").to_string(),
+            action: Action::LspPeekDefinition,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.peek_references").to_string(),
+            description: t!("cmd.peek_references_desc").to_string(),
+            action: Action::LspPeekReferences,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.show_signature_help").to_string(),
             description: t!("cmd.show_signature_help_desc").to_string(),
@@ -1017,6 +1417,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.toggle_block_comment").to_string(),
+            description: t!("cmd.toggle_block_comment_desc").to_string(),
+            action: Action::ToggleBlockComment,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.dedent_selection").to_string(),
             description: t!("cmd.dedent_selection_desc").to_string(),
@@ -1124,6 +1532,22 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.set_named_mark").to_string(),
+            description: t!("cmd.set_named_mark_desc").to_string(),
+            action: Action::PromptSetNamedMark,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.jump_to_mark").to_string(),
+            description: t!("cmd.jump_to_mark_desc").to_string(),
+            action: Action::PromptJumpToNamedMark,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // Help
         Command {
             name: t!("cmd.show_manual").to_string(),
@@ -1149,6 +1573,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.show_status_log").to_string(),
+            description: t!("cmd.show_status_log_desc").to_string(),
+            action: Action::ShowStatusLog,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.show_lsp_status").to_string(),
             description: t!("cmd.show_lsp_status_desc").to_string(),
@@ -1157,6 +1589,62 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.lsp_status_panel").to_string(),
+            description: t!("cmd.lsp_status_panel_desc").to_string(),
+            action: Action::LspStatusPanel,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.show_background_tasks").to_string(),
+            description: t!("cmd.show_background_tasks_desc").to_string(),
+            action: Action::ShowBackgroundTasks,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.reload_plugin").to_string(),
+            description: t!("cmd.reload_plugin_desc").to_string(),
+            action: Action::ReloadPlugin,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.memory_report").to_string(),
+            description: t!("cmd.memory_report_desc").to_string(),
+            action: Action::MemoryReport,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.show_telemetry_data").to_string(),
+            description: t!("cmd.show_telemetry_data_desc").to_string(),
+            action: Action::ShowTelemetryData,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.update_fresh_now").to_string(),
+            description: t!("cmd.update_fresh_now_desc").to_string(),
+            action: Action::UpdateFreshNow,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.manage_workspace_trust").to_string(),
+            description: t!("cmd.manage_workspace_trust_desc").to_string(),
+            action: Action::ManageWorkspaceTrust,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.clear_warnings").to_string(),
             description: t!("cmd.clear_warnings_desc").to_string(),
@@ -1236,6 +1724,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.preferences_keyboard_shortcuts").to_string(),
+            description: t!("cmd.preferences_keyboard_shortcuts_desc").to_string(),
+            action: Action::OpenKeybindingEditor,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // Input calibration
         Command {
             name: t!("cmd.calibrate_input").to_string(),
@@ -1295,6 +1791,94 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.filter_through_command").to_string(),
+            description: t!("cmd.filter_through_command_desc").to_string(),
+            action: Action::FilterThroughCommand,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.toggle_watch_task").to_string(),
+            description: t!("cmd.toggle_watch_task_desc").to_string(),
+            action: Action::ToggleWatchTask,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.show_watch_output").to_string(),
+            description: t!("cmd.show_watch_output_desc").to_string(),
+            action: Action::ShowWatchOutput,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.quickfix_next").to_string(),
+            description: t!("cmd.quickfix_next_desc").to_string(),
+            action: Action::QuickfixNext,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.quickfix_prev").to_string(),
+            description: t!("cmd.quickfix_prev_desc").to_string(),
+            action: Action::QuickfixPrev,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.quickfix_first").to_string(),
+            description: t!("cmd.quickfix_first_desc").to_string(),
+            action: Action::QuickfixFirst,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.quickfix_last").to_string(),
+            description: t!("cmd.quickfix_last_desc").to_string(),
+            action: Action::QuickfixLast,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.quickfix_older").to_string(),
+            description: t!("cmd.quickfix_older_desc").to_string(),
+            action: Action::QuickfixOlder,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.quickfix_newer").to_string(),
+            description: t!("cmd.quickfix_newer_desc").to_string(),
+            action: Action::QuickfixNewer,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.quickfix_show").to_string(),
+            description: t!("cmd.quickfix_show_desc").to_string(),
+            action: Action::QuickfixShow,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.quickfix_history").to_string(),
+            description: t!("cmd.quickfix_history_desc").to_string(),
+            action: Action::QuickfixHistory,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
     ]
 }
 
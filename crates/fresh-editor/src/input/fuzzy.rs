@@ -131,13 +131,20 @@ pub fn fuzzy_match(query: &str, target: &str) -> FuzzyMatch {
 
 /// Match multiple space-separated terms against a target.
 /// All terms must match for the overall match to succeed.
+///
+/// Each term is treated as a word fragment: a term that reaches into a later
+/// word must begin at the start of a word, so "focus ed" matches
+/// "Focus Editor" but not "Toggle Focus Mode".
 fn fuzzy_match_multi_term(original_query: &str, terms: &[&str], target: &str) -> FuzzyMatch {
+    let target_chars: Vec<char> = target.chars().collect();
     let mut total_score = 0;
     let mut all_positions = Vec::new();
 
     for term in terms {
         let result = fuzzy_match_single_term(term, target);
-        if !result.matched {
+        if !result.matched
+            || starts_mid_word_and_spans_words(&result.match_positions, &target_chars)
+        {
             return FuzzyMatch::no_match();
         }
         total_score += result.score;
@@ -179,6 +186,31 @@ fn fuzzy_match_multi_term(original_query: &str, terms: &[&str], target: &str) ->
     }
 }
 
+/// Whether `positions` start inside a word and continue into a later word
+fn starts_mid_word_and_spans_words(positions: &[usize], target_chars: &[char]) -> bool {
+    match (positions.first(), positions.last()) {
+        (Some(&first), Some(&last)) => {
+            !is_word_start(target_chars, first)
+                && (first + 1..=last).any(|i| is_word_start(target_chars, i))
+        }
+        _ => false,
+    }
+}
+
+/// Whether the character at `index` begins a word: the start of the string,
+/// after a separator, or a camelCase hump
+fn is_word_start(target_chars: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+    let prev_char = target_chars[index - 1];
+    is_word_separator(prev_char) || (prev_char.is_lowercase() && target_chars[index].is_uppercase())
+}
+
+fn is_word_separator(c: char) -> bool {
+    matches!(c, ' ' | '_' | '-' | '/' | '.')
+}
+
 /// Match a single term (no spaces) against a target string.
 fn fuzzy_match_single_term(query: &str, target: &str) -> FuzzyMatch {
     let query_lower: Vec<char> = query.to_lowercase().chars().collect();
@@ -307,12 +339,7 @@ fn find_best_match(
             // Word boundary bonus
             if ti > 0 {
                 let prev_char = target_chars[ti - 1];
-                if prev_char == ' '
-                    || prev_char == '_'
-                    || prev_char == '-'
-                    || prev_char == '/'
-                    || prev_char == '.'
-                {
+                if is_word_separator(prev_char) {
                     match_score += score::WORD_BOUNDARY;
                 } else if prev_char.is_lowercase() && target_chars[ti].is_uppercase() {
                     match_score += score::CAMEL_CASE;
@@ -541,6 +568,16 @@ mod tests {
         assert!(!result.matched);
     }
 
+    #[test]
+    fn test_multi_term_does_not_start_mid_word() {
+        // "ed" would otherwise match the "e" of "Toggle" and the "d" of "Mode"
+        assert!(fuzzy_match("focus ed", "Focus Editor").matched);
+        assert!(!fuzzy_match("focus ed", "Toggle Focus Mode").matched);
+
+        // A term may still span words when it starts at a word boundary
+        assert!(fuzzy_match("src mainrs", "src/main.rs").matched);
+    }
+
     #[test]
     fn test_multi_term_query_all_must_match() {
         // All terms must match
@@ -381,9 +381,22 @@ impl InputHistory {
     }
 }
 
+/// Named profile whose data directory `get_data_dir` returns (None = default)
+static DATA_DIR_PROFILE: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+/// Select the profile whose data directory `get_data_dir` returns
+///
+/// Keeps workspaces, file states, histories and recovery files in step with
+/// `DirectoryContext::with_profile`.
+pub fn set_data_dir_profile(profile: Option<&str>) {
+    let profile = profile.filter(|name| *name != crate::config_io::DEFAULT_PROFILE);
+    *DATA_DIR_PROFILE.lock().unwrap() = profile.map(str::to_string);
+}
+
 /// Get the data directory for Fresh editor state
 /// Returns $XDG_DATA_HOME/fresh or ~/.local/share/fresh on Linux
 /// Returns ~/Library/Application Support/fresh on macOS
+/// Named profiles use `profiles/<name>` below it.
 pub fn get_data_dir() -> std::io::Result<std::path::PathBuf> {
     let data_dir = dirs::data_dir().ok_or_else(|| {
         std::io::Error::new(
@@ -391,7 +404,11 @@ pub fn get_data_dir() -> std::io::Result<std::path::PathBuf> {
             "Could not determine data directory",
         )
     })?;
-    Ok(data_dir.join("fresh"))
+    let data_dir = data_dir.join("fresh");
+    Ok(match DATA_DIR_PROFILE.lock().unwrap().as_deref() {
+        Some(name) => data_dir.join(crate::config_io::PROFILES_DIR).join(name),
+        None => data_dir,
+    })
 }
 
 /// Get the path for search history file
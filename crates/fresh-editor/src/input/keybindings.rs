@@ -1,7 +1,9 @@
+use super::when_clause::{WhenContext, WhenExpr};
 use crate::config::Config;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use rust_i18n::t;
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Global flag to force Linux-style keybinding display (Alt/Shift instead of ⌥/⇧)
@@ -299,6 +301,12 @@ pub enum Action {
     TransposeChars,
     OpenLine,
     DuplicateLine,
+    /// Insert a copy of each selection after it, or of the line without one
+    DuplicateSelection,
+    /// Join each line with the next, or the lines of each selection
+    JoinLines,
+    /// Combine the next two typed characters into the digraph they name
+    InsertDigraph,
 
     // View
     Recenter,
@@ -322,6 +330,8 @@ pub enum Action {
     AddCursorAbove,
     AddCursorBelow,
     AddCursorNextMatch,
+    SkipOccurrence,
+    SelectAllOccurrences,
     RemoveSecondaryCursors,
 
     // File operations
@@ -329,14 +339,20 @@ pub enum Action {
     SaveAs,
     Open,
     SwitchProject,
+    SwitchProfile,
     New,
     Close,
     CloseTab,
     Quit,
     ForceQuit,
     Detach,
+    CollabShare,
+    CollabJoin,
+    CollabStop,
+    FollowUser,
     Revert,
     ToggleAutoRevert,
+    LocalHistory,
     FormatBuffer,
     TrimTrailingWhitespace,
     EnsureFinalNewline,
@@ -351,6 +367,7 @@ pub enum Action {
     SmartHome,
     DedentSelection,
     ToggleComment,
+    ToggleBlockComment,
 
     // Bookmarks
     SetBookmark(char),
@@ -358,6 +375,10 @@ pub enum Action {
     ClearBookmark(char),
     ListBookmarks,
 
+    // Marks (a-z per buffer, A-Z across files)
+    SetNamedMark(char),
+    JumpToNamedMark(char),
+
     // Search options
     ToggleSearchCaseSensitive,
     ToggleSearchWholeWord,
@@ -378,6 +399,8 @@ pub enum Action {
     // Bookmarks (prompt-based)
     PromptSetBookmark,
     PromptJumpToBookmark,
+    PromptSetNamedMark,
+    PromptJumpToNamedMark,
 
     // Undo/redo
     Undo,
@@ -391,12 +414,20 @@ pub enum Action {
     ShowWarnings,
     ShowStatusLog,
     ShowLspStatus,
+    LspStatusPanel,
+    ShowBackgroundTasks,
+    ReloadPlugin,
+    MemoryReport,
+    ShowTelemetryData,
+    UpdateFreshNow,
+    ManageWorkspaceTrust,
     ClearWarnings,
     CommandPalette, // TODO: Consider dropping this now that we have QuickOpen
     /// Quick Open - unified prompt with prefix-based provider routing
     QuickOpen,
     ToggleLineWrap,
     ToggleComposeMode,
+    ToggleFocusMode,
     SetComposeWidth,
     SelectTheme,
     SelectKeybindingMap,
@@ -408,6 +439,12 @@ pub enum Action {
     PrevBuffer,
     SwitchToPreviousTab,
     SwitchToTabByName,
+    TogglePinTab,
+    SetTabGroup,
+    CloseOtherTabs,
+    CloseTabsToRight,
+    CloseTabsToLeft,
+    CloseAllTabs,
 
     // Tab scrolling
     ScrollTabsLeft,
@@ -426,6 +463,7 @@ pub enum Action {
     IncreaseSplitSize,
     DecreaseSplitSize,
     ToggleMaximizeSplit,
+    ToggleScrollLock,
 
     // Prompt mode actions
     PromptConfirm,
@@ -479,6 +517,7 @@ pub enum Action {
     ToggleMenuBar,
     // Tab bar visibility
     ToggleTabBar,
+    TogglePreviewTabs,
     FocusFileExplorer,
     FocusEditor,
     FileExplorerUp,
@@ -492,16 +531,23 @@ pub enum Action {
     FileExplorerNewFile,
     FileExplorerNewDirectory,
     FileExplorerDelete,
+    FileExplorerUndoDelete,
     FileExplorerRename,
     FileExplorerToggleHidden,
     FileExplorerToggleGitignored,
+    FileExplorerNextRoot,
     FileExplorerSearchClear,
     FileExplorerSearchBackspace,
 
+    // Links
+    OpenLinkUnderCursor, // URL in the browser, or file:line in the editor
+
     // LSP operations
     LspCompletion,
     LspGotoDefinition,
     LspReferences,
+    LspPeekDefinition,
+    LspPeekReferences,
     LspRename,
     LspHover,
     LspSignatureHelp,
@@ -513,8 +559,13 @@ pub enum Action {
 
     // View toggles
     ToggleLineNumbers,
+    ToggleReadOnly,
+    ToggleImagePreview,
     ToggleMouseCapture,
+    ToggleAccessibility,   // Speak the cursor context, status and menus
+    AnnounceContext,       // Describe where the cursor is ("Where Am I")
     ToggleDebugHighlights, // Debug mode: show highlight/overlay byte ranges
+    ToggleRenderProfiler,  // Debug mode: show per-frame render timings
     SetBackground,
     SetBackgroundBlend,
 
@@ -525,7 +576,11 @@ pub enum Action {
     ReloadWithEncoding,
     SetLanguage,
     ToggleIndentationStyle,
+    ConvertIndentationToSpaces,
+    ConvertIndentationToTabs,
+    ReindentBuffer,
     ToggleTabIndicators,
+    CycleRenderWhitespace,
     ResetBufferSettings,
 
     // Config operations
@@ -540,6 +595,8 @@ pub enum Action {
     FindSelectionPrevious, // Quick find previous occurrence of selection (Ctrl+Shift+F3)
     Replace,
     QueryReplace, // Interactive replace (y/n/!/q for each match)
+    StructuralSearch,
+    StructuralReplace,
 
     // Menu navigation
     MenuActivate,     // Open menu bar (Alt or F10)
@@ -578,13 +635,42 @@ pub enum Action {
     TerminalPaste,         // Paste clipboard contents into terminal as a single batch
 
     // Shell command operations
-    ShellCommand,        // Run shell command on buffer/selection, output to new buffer
-    ShellCommandReplace, // Run shell command on buffer/selection, replace content
+    ShellCommand,         // Run shell command on buffer/selection, output to new buffer
+    ShellCommandReplace,  // Run shell command on buffer/selection, replace content
+    FilterThroughCommand, // Pipe selection/buffer through a command (local or remote), replace it
+
+    // Watch task
+    ToggleWatchTask, // Start/stop re-running a command on every save
+    ShowWatchOutput, // Show the output of the watch task's last run
+
+    // Quickfix lists
+    QuickfixNext,    // Jump to the next item of the current quickfix list
+    QuickfixPrev,    // Jump to the previous item of the current quickfix list
+    QuickfixFirst,   // Jump to the first item of the current quickfix list
+    QuickfixLast,    // Jump to the last item of the current quickfix list
+    QuickfixOlder,   // Make the older quickfix list current
+    QuickfixNewer,   // Make the newer quickfix list current
+    QuickfixShow,    // Pick an item of the current quickfix list
+    QuickfixHistory, // Pick a list from the quickfix history
 
     // Case conversion
     ToUpperCase, // Convert selection to uppercase
     ToLowerCase, // Convert selection to lowercase
+    ToTitleCase, // Capitalize each word of the selection
+    ToCamelCase, // Convert identifiers to camelCase
+    ToSnakeCase, // Convert identifiers to snake_case
+    ToKebabCase, // Convert identifiers to kebab-case
     SortLines,   // Sort selected lines alphabetically
+    UniqueLines, // Remove repeated selected lines
+
+    // Numbers
+    IncrementNumber, // Add one to the number at or after each cursor
+    DecrementNumber, // Subtract one from the number at or after each cursor
+    InsertSequence,  // Prompt for a start and step, and number the cursors
+    InsertDateTime,  // Prompt for a date/time format and insert the current time
+
+    // Alignment
+    AlignSelection, // Prompt for a delimiter and align the selected lines on it
 
     // Input calibration
     CalibrateInput, // Open the input calibration wizard
@@ -710,6 +796,9 @@ impl Action {
             "transpose_chars" => TransposeChars,
             "open_line" => OpenLine,
             "duplicate_line" => DuplicateLine,
+            "duplicate_selection" => DuplicateSelection,
+            "join_lines" => JoinLines,
+            "insert_digraph" => InsertDigraph,
             "recenter" => Recenter,
             "set_mark" => SetMark,
 
@@ -725,20 +814,28 @@ impl Action {
             "add_cursor_above" => AddCursorAbove,
             "add_cursor_below" => AddCursorBelow,
             "add_cursor_next_match" => AddCursorNextMatch,
+            "skip_occurrence" => SkipOccurrence,
+            "select_all_occurrences" => SelectAllOccurrences,
             "remove_secondary_cursors" => RemoveSecondaryCursors,
 
             "save" => Save,
             "save_as" => SaveAs,
             "open" => Open,
             "switch_project" => SwitchProject,
+            "switch_profile" => SwitchProfile,
             "new" => New,
             "close" => Close,
             "close_tab" => CloseTab,
             "quit" => Quit,
             "force_quit" => ForceQuit,
             "detach" => Detach,
+            "collab_share" => CollabShare,
+            "collab_join" => CollabJoin,
+            "collab_stop" => CollabStop,
+            "follow_user" => FollowUser,
             "revert" => Revert,
             "toggle_auto_revert" => ToggleAutoRevert,
+            "local_history" => LocalHistory,
             "format_buffer" => FormatBuffer,
             "goto_line" => GotoLine,
             "goto_matching_bracket" => GoToMatchingBracket,
@@ -748,6 +845,7 @@ impl Action {
             "smart_home" => SmartHome,
             "dedent_selection" => DedentSelection,
             "toggle_comment" => ToggleComment,
+            "toggle_block_comment" => ToggleBlockComment,
 
             "list_bookmarks" => ListBookmarks,
 
@@ -765,6 +863,8 @@ impl Action {
             "play_last_macro" => PlayLastMacro,
             "prompt_set_bookmark" => PromptSetBookmark,
             "prompt_jump_to_bookmark" => PromptJumpToBookmark,
+            "prompt_set_named_mark" => PromptSetNamedMark,
+            "prompt_jump_to_named_mark" => PromptJumpToNamedMark,
 
             "undo" => Undo,
             "redo" => Redo,
@@ -776,15 +876,29 @@ impl Action {
             "show_warnings" => ShowWarnings,
             "show_status_log" => ShowStatusLog,
             "show_lsp_status" => ShowLspStatus,
+            "lsp_status_panel" => LspStatusPanel,
+            "show_background_tasks" => ShowBackgroundTasks,
+            "reload_plugin" => ReloadPlugin,
+            "memory_report" => MemoryReport,
+            "show_telemetry_data" => ShowTelemetryData,
+            "update_fresh_now" => UpdateFreshNow,
+            "manage_workspace_trust" => ManageWorkspaceTrust,
             "clear_warnings" => ClearWarnings,
             "command_palette" => CommandPalette,
             "quick_open" => QuickOpen,
             "toggle_line_wrap" => ToggleLineWrap,
             "toggle_compose_mode" => ToggleComposeMode,
+            "toggle_focus_mode" => ToggleFocusMode,
             "set_compose_width" => SetComposeWidth,
 
             "next_buffer" => NextBuffer,
             "prev_buffer" => PrevBuffer,
+            "toggle_pin_tab" => TogglePinTab,
+            "set_tab_group" => SetTabGroup,
+            "close_other_tabs" => CloseOtherTabs,
+            "close_tabs_to_right" => CloseTabsToRight,
+            "close_tabs_to_left" => CloseTabsToLeft,
+            "close_all_tabs" => CloseAllTabs,
 
             "navigate_back" => NavigateBack,
             "navigate_forward" => NavigateForward,
@@ -797,6 +911,7 @@ impl Action {
             "increase_split_size" => IncreaseSplitSize,
             "decrease_split_size" => DecreaseSplitSize,
             "toggle_maximize_split" => ToggleMaximizeSplit,
+            "toggle_scroll_lock" => ToggleScrollLock,
 
             "prompt_confirm" => PromptConfirm,
             "prompt_cancel" => PromptCancel,
@@ -839,6 +954,7 @@ impl Action {
             "toggle_file_explorer" => ToggleFileExplorer,
             "toggle_menu_bar" => ToggleMenuBar,
             "toggle_tab_bar" => ToggleTabBar,
+            "toggle_preview_tabs" => TogglePreviewTabs,
             "focus_file_explorer" => FocusFileExplorer,
             "focus_editor" => FocusEditor,
             "file_explorer_up" => FileExplorerUp,
@@ -852,15 +968,20 @@ impl Action {
             "file_explorer_new_file" => FileExplorerNewFile,
             "file_explorer_new_directory" => FileExplorerNewDirectory,
             "file_explorer_delete" => FileExplorerDelete,
+            "file_explorer_undo_delete" => FileExplorerUndoDelete,
             "file_explorer_rename" => FileExplorerRename,
             "file_explorer_toggle_hidden" => FileExplorerToggleHidden,
             "file_explorer_toggle_gitignored" => FileExplorerToggleGitignored,
+            "file_explorer_next_root" => FileExplorerNextRoot,
             "file_explorer_search_clear" => FileExplorerSearchClear,
             "file_explorer_search_backspace" => FileExplorerSearchBackspace,
 
             "lsp_completion" => LspCompletion,
+            "open_link_under_cursor" => OpenLinkUnderCursor,
             "lsp_goto_definition" => LspGotoDefinition,
             "lsp_references" => LspReferences,
+            "lsp_peek_definition" => LspPeekDefinition,
+            "lsp_peek_references" => LspPeekReferences,
             "lsp_rename" => LspRename,
             "lsp_hover" => LspHover,
             "lsp_signature_help" => LspSignatureHelp,
@@ -871,8 +992,13 @@ impl Action {
             "toggle_mouse_hover" => ToggleMouseHover,
 
             "toggle_line_numbers" => ToggleLineNumbers,
+            "toggle_read_only" => ToggleReadOnly,
+            "toggle_image_preview" => ToggleImagePreview,
             "toggle_mouse_capture" => ToggleMouseCapture,
+            "toggle_accessibility" => ToggleAccessibility,
+            "announce_context" => AnnounceContext,
             "toggle_debug_highlights" => ToggleDebugHighlights,
+            "toggle_render_profiler" => ToggleRenderProfiler,
             "set_background" => SetBackground,
             "set_background_blend" => SetBackgroundBlend,
             "select_theme" => SelectTheme,
@@ -884,7 +1010,11 @@ impl Action {
             "set_encoding" => SetEncoding,
             "reload_with_encoding" => ReloadWithEncoding,
             "toggle_indentation_style" => ToggleIndentationStyle,
+            "convert_indentation_to_spaces" => ConvertIndentationToSpaces,
+            "convert_indentation_to_tabs" => ConvertIndentationToTabs,
+            "reindent_buffer" => ReindentBuffer,
             "toggle_tab_indicators" => ToggleTabIndicators,
+            "cycle_render_whitespace" => CycleRenderWhitespace,
             "reset_buffer_settings" => ResetBufferSettings,
 
             "dump_config" => DumpConfig,
@@ -897,6 +1027,8 @@ impl Action {
             "find_selection_previous" => FindSelectionPrevious,
             "replace" => Replace,
             "query_replace" => QueryReplace,
+            "structural_search" => StructuralSearch,
+            "structural_replace" => StructuralReplace,
 
             "menu_activate" => MenuActivate,
             "menu_close" => MenuClose,
@@ -915,10 +1047,31 @@ impl Action {
 
             "shell_command" => ShellCommand,
             "shell_command_replace" => ShellCommandReplace,
+            "filter_through_command" => FilterThroughCommand,
+            "toggle_watch_task" => ToggleWatchTask,
+            "show_watch_output" => ShowWatchOutput,
+            "quickfix_next" => QuickfixNext,
+            "quickfix_prev" => QuickfixPrev,
+            "quickfix_first" => QuickfixFirst,
+            "quickfix_last" => QuickfixLast,
+            "quickfix_older" => QuickfixOlder,
+            "quickfix_newer" => QuickfixNewer,
+            "quickfix_show" => QuickfixShow,
+            "quickfix_history" => QuickfixHistory,
 
             "to_upper_case" => ToUpperCase,
             "to_lower_case" => ToLowerCase,
+            "to_title_case" => ToTitleCase,
+            "to_camel_case" => ToCamelCase,
+            "to_snake_case" => ToSnakeCase,
+            "to_kebab_case" => ToKebabCase,
             "sort_lines" => SortLines,
+            "unique_lines" => UniqueLines,
+            "increment_number" => IncrementNumber,
+            "decrement_number" => DecrementNumber,
+            "insert_sequence" => InsertSequence,
+            "insert_date_time" => InsertDateTime,
+            "align_selection" => AlignSelection,
 
             "calibrate_input" => CalibrateInput,
             "event_debug" => EventDebug,
@@ -940,6 +1093,8 @@ impl Action {
             "set_bookmark" => SetBookmark,
             "jump_to_bookmark" => JumpToBookmark,
             "clear_bookmark" => ClearBookmark,
+            "set_named_mark" => SetNamedMark,
+            "jump_to_named_mark" => JumpToNamedMark,
             "play_macro" => PlayMacro,
             "toggle_macro_recording" => ToggleMacroRecording,
             "show_macro" => ShowMacro,
@@ -1019,6 +1174,8 @@ impl Action {
                 | Action::TransposeChars
                 | Action::OpenLine
                 | Action::DuplicateLine
+                | Action::DuplicateSelection
+                | Action::JoinLines
                 | Action::MoveLineUp
                 | Action::MoveLineDown
                 // Clipboard editing (but not Copy)
@@ -1048,6 +1205,8 @@ impl Action {
                 | Action::TransposeChars
                 | Action::OpenLine
                 | Action::DuplicateLine
+                | Action::DuplicateSelection
+                | Action::JoinLines
                 | Action::MoveLineUp
                 | Action::MoveLineDown
                 | Action::Cut
@@ -1067,6 +1226,78 @@ pub enum ChordResolution {
     NoMatch,
 }
 
+/// A binding whose `when` is an expression rather than a plain context name
+#[derive(Clone)]
+struct ConditionalBinding {
+    sequence: Vec<(KeyCode, KeyModifiers)>,
+    when: WhenExpr,
+    action: Action,
+}
+
+/// Where a binding applies
+enum BindingScope {
+    /// Plain context name (or no `when` at all)
+    Context(KeyContext),
+    /// `when` expression, evaluated on each key press
+    Conditional(WhenExpr),
+}
+
+/// Two keybindings that can't both take effect
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeybindingConflict {
+    /// The same keys are bound more than once in the same context; the last one wins
+    Duplicate {
+        when: String,
+        keys: String,
+        actions: Vec<String>,
+    },
+    /// A single key is also the first key of a chord, so it only ever starts the chord
+    ShadowedByChord {
+        when: String,
+        key: String,
+        action: String,
+        chord: String,
+    },
+}
+
+impl fmt::Display for KeybindingConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate {
+                when,
+                keys,
+                actions,
+            } => write!(
+                f,
+                "{} is bound to {} in '{}'; only {} takes effect",
+                keys,
+                actions.join(", "),
+                when,
+                actions.last().map(String::as_str).unwrap_or_default()
+            ),
+            Self::ShadowedByChord {
+                when,
+                key,
+                action,
+                chord,
+            } => write!(
+                f,
+                "{} ({}) in '{}' is unreachable: it starts the chord {}",
+                key, action, when, chord
+            ),
+        }
+    }
+}
+
+/// Format a key sequence like "Ctrl+K Ctrl+C"
+fn format_sequence(sequence: &[(KeyCode, KeyModifiers)]) -> String {
+    sequence
+        .iter()
+        .map(|(code, modifiers)| format_keybinding(code, modifiers))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Resolves key events to actions based on configuration
 #[derive(Clone)]
 pub struct KeybindingResolver {
@@ -1083,6 +1314,13 @@ pub struct KeybindingResolver {
 
     /// Default chord bindings for each context
     default_chord_bindings: HashMap<KeyContext, HashMap<Vec<(KeyCode, KeyModifiers)>, Action>>,
+
+    /// Bindings with a `when` expression, highest priority first
+    /// (custom before default, later custom bindings before earlier ones)
+    conditional_bindings: Vec<ConditionalBinding>,
+
+    /// Conflicts found among the custom keybindings
+    conflicts: Vec<KeybindingConflict>,
 }
 
 impl KeybindingResolver {
@@ -1093,6 +1331,8 @@ impl KeybindingResolver {
             default_bindings: HashMap::new(),
             chord_bindings: HashMap::new(),
             default_chord_bindings: HashMap::new(),
+            conditional_bindings: Vec::new(),
+            conflicts: Vec::new(),
         };
 
         // Load bindings from the active keymap (with inheritance resolution) into default_bindings
@@ -1105,43 +1345,109 @@ impl KeybindingResolver {
         resolver
     }
 
+    /// Parse one key press: a key name, optionally prefixed with modifiers
+    /// (`ctrl+shift+k`), plus any modifiers given separately
+    fn parse_key_press(token: &str, extra_modifiers: &[String]) -> Option<(KeyCode, KeyModifiers)> {
+        let mut modifiers = Self::parse_modifiers(extra_modifiers);
+        if let Some(key_code) = Self::parse_key(token) {
+            return Some((key_code, modifiers));
+        }
+
+        // "ctrl++" binds the plus key itself
+        let (prefix, key) = match token.strip_suffix("++") {
+            Some(prefix) => (prefix, "+"),
+            None => token.rsplit_once('+')?,
+        };
+        for name in prefix.split('+') {
+            let modifier = Self::parse_modifiers(&[name.to_string()]);
+            if modifier.is_empty() {
+                return None;
+            }
+            modifiers |= modifier;
+        }
+        Some((Self::parse_key(key)?, modifiers))
+    }
+
+    /// Key sequence of a binding: `keys`, or `key` holding one key press or a
+    /// space-separated chord (`"ctrl+k ctrl+c"`). None if any key is invalid.
+    pub fn binding_sequence(
+        binding: &crate::config::Keybinding,
+    ) -> Option<Vec<(KeyCode, KeyModifiers)>> {
+        let sequence: Option<Vec<_>> = if !binding.keys.is_empty() {
+            binding
+                .keys
+                .iter()
+                .map(|key_press| Self::parse_key_press(&key_press.key, &key_press.modifiers))
+                .collect()
+        } else {
+            let tokens: Vec<&str> = binding.key.split_whitespace().collect();
+            match tokens.as_slice() {
+                // A literal space (" ") is the space key
+                [] if !binding.key.is_empty() => {
+                    Self::parse_key_press(&binding.key, &binding.modifiers).map(|key| vec![key])
+                }
+                [] => None,
+                [token] => Self::parse_key_press(token, &binding.modifiers).map(|key| vec![key]),
+                tokens => tokens
+                    .iter()
+                    .map(|token| Self::parse_key_press(token, &[]))
+                    .collect(),
+            }
+        };
+        sequence.filter(|s| !s.is_empty())
+    }
+
+    /// Determine where a binding applies from its `when` clause
+    fn binding_scope(binding: &crate::config::Keybinding) -> Option<BindingScope> {
+        let Some(when) = binding.when.as_deref() else {
+            return Some(BindingScope::Context(KeyContext::Normal));
+        };
+        if let Some(context) = KeyContext::from_when_clause(when) {
+            return Some(BindingScope::Context(context));
+        }
+        match WhenExpr::parse(when) {
+            Ok(expr) => Some(BindingScope::Conditional(expr)),
+            Err(e) => {
+                tracing::warn!(
+                    "Ignoring keybinding for '{}': invalid when clause '{}': {}",
+                    binding.action,
+                    when,
+                    e
+                );
+                None
+            }
+        }
+    }
+
     /// Load default bindings from a vector of keybinding definitions (into default_bindings/default_chord_bindings)
     fn load_default_bindings_from_vec(&mut self, bindings: &[crate::config::Keybinding]) {
         for binding in bindings {
-            // Determine context from "when" clause
-            let context = if let Some(ref when) = binding.when {
-                KeyContext::from_when_clause(when).unwrap_or(KeyContext::Normal)
-            } else {
-                KeyContext::Normal
+            let Some(scope) = Self::binding_scope(binding) else {
+                continue;
+            };
+            let Some(action) = Action::from_str(&binding.action, &binding.args) else {
+                continue;
+            };
+            let Some(sequence) = Self::binding_sequence(binding) else {
+                continue;
             };
 
-            if let Some(action) = Action::from_str(&binding.action, &binding.args) {
-                // Check if this is a chord binding (has keys field)
-                if !binding.keys.is_empty() {
-                    // Parse the chord sequence
-                    let mut sequence = Vec::new();
-                    for key_press in &binding.keys {
-                        if let Some(key_code) = Self::parse_key(&key_press.key) {
-                            let modifiers = Self::parse_modifiers(&key_press.modifiers);
-                            sequence.push((key_code, modifiers));
-                        } else {
-                            // Invalid key in sequence, skip this binding
-                            break;
-                        }
-                    }
-
-                    // Only add if all keys in sequence were valid
-                    if sequence.len() == binding.keys.len() && !sequence.is_empty() {
-                        self.default_chord_bindings
-                            .entry(context)
-                            .or_default()
-                            .insert(sequence, action);
-                    }
-                } else if let Some(key_code) = Self::parse_key(&binding.key) {
-                    // Single key binding (legacy format)
-                    let modifiers = Self::parse_modifiers(&binding.modifiers);
-
-                    // Insert the primary binding
+            match scope {
+                BindingScope::Conditional(when) => {
+                    self.conditional_bindings.push(ConditionalBinding {
+                        sequence,
+                        when,
+                        action,
+                    });
+                }
+                BindingScope::Context(context) if sequence.len() > 1 => {
+                    self.default_chord_bindings
+                        .entry(context)
+                        .or_default()
+                        .insert(sequence, action);
+                }
+                BindingScope::Context(context) => {
+                    let (key_code, modifiers) = sequence[0];
                     self.insert_binding_with_equivalents(
                         context,
                         key_code,
@@ -1199,46 +1505,171 @@ impl KeybindingResolver {
 
     /// Load custom bindings from a vector of keybinding definitions (into bindings/chord_bindings)
     fn load_bindings_from_vec(&mut self, bindings: &[crate::config::Keybinding]) {
+        // Custom conditional bindings go ahead of the keymap's, latest first
+        let mut conditional = Vec::new();
+        // (when, keys) -> actions bound to them, in config order
+        let mut seen: Vec<((String, Vec<(KeyCode, KeyModifiers)>), Vec<String>)> = Vec::new();
+
         for binding in bindings {
-            // Determine context from "when" clause
-            let context = if let Some(ref when) = binding.when {
-                KeyContext::from_when_clause(when).unwrap_or(KeyContext::Normal)
-            } else {
-                KeyContext::Normal
+            let Some(scope) = Self::binding_scope(binding) else {
+                continue;
+            };
+            let Some(action) = Action::from_str(&binding.action, &binding.args) else {
+                tracing::warn!(
+                    "Ignoring keybinding with unknown action '{}'",
+                    binding.action
+                );
+                continue;
+            };
+            let Some(sequence) = Self::binding_sequence(binding) else {
+                tracing::warn!(
+                    "Ignoring keybinding for '{}': invalid key '{}'",
+                    binding.action,
+                    binding.key
+                );
+                continue;
             };
 
-            if let Some(action) = Action::from_str(&binding.action, &binding.args) {
-                // Check if this is a chord binding (has keys field)
-                if !binding.keys.is_empty() {
-                    // Parse the chord sequence
-                    let mut sequence = Vec::new();
-                    for key_press in &binding.keys {
-                        if let Some(key_code) = Self::parse_key(&key_press.key) {
-                            let modifiers = Self::parse_modifiers(&key_press.modifiers);
-                            sequence.push((key_code, modifiers));
-                        } else {
-                            // Invalid key in sequence, skip this binding
-                            break;
-                        }
-                    }
+            let when = match &scope {
+                BindingScope::Context(context) => context.to_when_clause().to_string(),
+                BindingScope::Conditional(_) => binding.when.clone().unwrap_or_default(),
+            };
+            let id = (when, sequence.clone());
+            match seen.iter_mut().find(|(key, _)| *key == id) {
+                Some((_, actions)) => actions.push(binding.action.clone()),
+                None => seen.push((id, vec![binding.action.clone()])),
+            }
 
-                    // Only add if all keys in sequence were valid
-                    if sequence.len() == binding.keys.len() && !sequence.is_empty() {
-                        self.chord_bindings
-                            .entry(context)
-                            .or_default()
-                            .insert(sequence, action);
-                    }
-                } else if let Some(key_code) = Self::parse_key(&binding.key) {
-                    // Single key binding (legacy format)
-                    let modifiers = Self::parse_modifiers(&binding.modifiers);
+            match scope {
+                BindingScope::Conditional(when) => {
+                    conditional.push(ConditionalBinding {
+                        sequence,
+                        when,
+                        action,
+                    });
+                }
+                BindingScope::Context(context) if sequence.len() > 1 => {
+                    self.chord_bindings
+                        .entry(context)
+                        .or_default()
+                        .insert(sequence, action);
+                }
+                BindingScope::Context(context) => {
                     self.bindings
                         .entry(context)
                         .or_default()
-                        .insert((key_code, modifiers), action);
+                        .insert(sequence[0], action);
+                }
+            }
+        }
+
+        conditional.reverse();
+        conditional.append(&mut self.conditional_bindings);
+        self.conditional_bindings = conditional;
+
+        self.conflicts = seen
+            .into_iter()
+            .filter(|(_, actions)| actions.iter().any(|a| *a != actions[0]))
+            .map(
+                |((when, sequence), actions)| KeybindingConflict::Duplicate {
+                    when,
+                    keys: format_sequence(&sequence),
+                    actions,
+                },
+            )
+            .collect();
+        self.conflicts.extend(self.find_chord_shadowing());
+
+        for conflict in &self.conflicts {
+            tracing::warn!("Keybinding conflict: {}", conflict);
+        }
+    }
+
+    /// Single keys that also start a chord in the same context, where at least
+    /// one of the two is a custom binding
+    fn find_chord_shadowing(&self) -> Vec<KeybindingConflict> {
+        let mut conflicts = Vec::new();
+        let chord_sources = [
+            (&self.chord_bindings, true),
+            (&self.default_chord_bindings, false),
+        ];
+        let key_sources = [(&self.bindings, true), (&self.default_bindings, false)];
+
+        for (chords, chord_is_custom) in chord_sources {
+            for (context, context_chords) in chords {
+                for (chord, _) in context_chords {
+                    for (keys, key_is_custom) in key_sources {
+                        if !chord_is_custom && !key_is_custom {
+                            continue;
+                        }
+                        // A custom single key overrides the keymap's, so only the
+                        // effective binding for this key matters
+                        if !key_is_custom
+                            && self
+                                .bindings
+                                .get(context)
+                                .is_some_and(|b| b.contains_key(&chord[0]))
+                        {
+                            continue;
+                        }
+                        if let Some(action) = keys.get(context).and_then(|b| b.get(&chord[0])) {
+                            conflicts.push(KeybindingConflict::ShadowedByChord {
+                                when: context.to_when_clause().to_string(),
+                                key: format_sequence(&chord[..1]),
+                                action: Self::format_action(action),
+                                chord: format_sequence(chord),
+                            });
+                        }
+                    }
                 }
             }
         }
+        conflicts.sort_by_key(|c| c.to_string());
+        conflicts
+    }
+
+    /// Conflicts found among the custom keybindings
+    pub fn conflicts(&self) -> &[KeybindingConflict] {
+        &self.conflicts
+    }
+
+    /// Whether any binding uses a `when` expression
+    pub fn has_conditional_bindings(&self) -> bool {
+        !self.conditional_bindings.is_empty()
+    }
+
+    /// Resolve a key against the bindings with `when` expressions
+    ///
+    /// Like [`Self::resolve_chord`], but only bindings whose expression holds
+    /// in `when` are considered.
+    pub fn resolve_conditional(
+        &self,
+        chord_state: &[(KeyCode, KeyModifiers)],
+        event: &KeyEvent,
+        when: &WhenContext,
+    ) -> ChordResolution {
+        let mut full_sequence = chord_state.to_vec();
+        full_sequence.push((event.code, event.modifiers));
+
+        let mut has_partial_match = false;
+        for binding in &self.conditional_bindings {
+            let is_match = binding.sequence == full_sequence;
+            let is_prefix = binding.sequence.len() > full_sequence.len()
+                && binding.sequence[..full_sequence.len()] == full_sequence[..];
+            if !(is_match || is_prefix) || !binding.when.eval(when) {
+                continue;
+            }
+            if is_match {
+                return ChordResolution::Complete(binding.action.clone());
+            }
+            has_partial_match = true;
+        }
+
+        if has_partial_match {
+            ChordResolution::Partial
+        } else {
+            ChordResolution::NoMatch
+        }
     }
 
     /// Check if an action is application-wide (should be accessible in all contexts)
@@ -1781,6 +2212,9 @@ impl KeybindingResolver {
             Action::TransposeChars => t!("action.transpose_chars"),
             Action::OpenLine => t!("action.open_line"),
             Action::DuplicateLine => t!("action.duplicate_line"),
+            Action::DuplicateSelection => t!("action.duplicate_selection"),
+            Action::JoinLines => t!("action.join_lines"),
+            Action::InsertDigraph => t!("action.insert_digraph"),
             Action::Recenter => t!("action.recenter"),
             Action::SetMark => t!("action.set_mark"),
             Action::Copy => t!("action.copy"),
@@ -1795,19 +2229,27 @@ impl KeybindingResolver {
             Action::AddCursorAbove => t!("action.add_cursor_above"),
             Action::AddCursorBelow => t!("action.add_cursor_below"),
             Action::AddCursorNextMatch => t!("action.add_cursor_next_match"),
+            Action::SkipOccurrence => t!("action.skip_occurrence"),
+            Action::SelectAllOccurrences => t!("action.select_all_occurrences"),
             Action::RemoveSecondaryCursors => t!("action.remove_secondary_cursors"),
             Action::Save => t!("action.save"),
             Action::SaveAs => t!("action.save_as"),
             Action::Open => t!("action.open"),
             Action::SwitchProject => t!("action.switch_project"),
+            Action::SwitchProfile => t!("action.switch_profile"),
             Action::New => t!("action.new"),
             Action::Close => t!("action.close"),
             Action::CloseTab => t!("action.close_tab"),
             Action::Quit => t!("action.quit"),
             Action::ForceQuit => t!("action.force_quit"),
             Action::Detach => t!("action.detach"),
+            Action::CollabShare => t!("action.collab_share"),
+            Action::CollabJoin => t!("action.collab_join"),
+            Action::CollabStop => t!("action.collab_stop"),
+            Action::FollowUser => t!("action.follow_user"),
             Action::Revert => t!("action.revert"),
             Action::ToggleAutoRevert => t!("action.toggle_auto_revert"),
+            Action::LocalHistory => t!("action.local_history"),
             Action::FormatBuffer => t!("action.format_buffer"),
             Action::TrimTrailingWhitespace => t!("action.trim_trailing_whitespace"),
             Action::EnsureFinalNewline => t!("action.ensure_final_newline"),
@@ -1818,8 +2260,11 @@ impl KeybindingResolver {
             Action::SmartHome => t!("action.smart_home"),
             Action::DedentSelection => t!("action.dedent_selection"),
             Action::ToggleComment => t!("action.toggle_comment"),
+            Action::ToggleBlockComment => t!("action.toggle_block_comment"),
             Action::SetBookmark(c) => t!("action.set_bookmark", key = c),
             Action::JumpToBookmark(c) => t!("action.jump_to_bookmark", key = c),
+            Action::SetNamedMark(c) => t!("action.set_named_mark", key = c),
+            Action::JumpToNamedMark(c) => t!("action.jump_to_named_mark", key = c),
             Action::ClearBookmark(c) => t!("action.clear_bookmark", key = c),
             Action::ListBookmarks => t!("action.list_bookmarks"),
             Action::ToggleSearchCaseSensitive => t!("action.toggle_search_case_sensitive"),
@@ -1837,6 +2282,8 @@ impl KeybindingResolver {
             Action::PlayLastMacro => t!("action.play_last_macro"),
             Action::PromptSetBookmark => t!("action.prompt_set_bookmark"),
             Action::PromptJumpToBookmark => t!("action.prompt_jump_to_bookmark"),
+            Action::PromptSetNamedMark => t!("action.prompt_set_named_mark"),
+            Action::PromptJumpToNamedMark => t!("action.prompt_jump_to_named_mark"),
             Action::Undo => t!("action.undo"),
             Action::Redo => t!("action.redo"),
             Action::ScrollUp => t!("action.scroll_up"),
@@ -1846,11 +2293,19 @@ impl KeybindingResolver {
             Action::ShowWarnings => t!("action.show_warnings"),
             Action::ShowStatusLog => t!("action.show_status_log"),
             Action::ShowLspStatus => t!("action.show_lsp_status"),
+            Action::LspStatusPanel => t!("action.lsp_status_panel"),
+            Action::ShowBackgroundTasks => t!("action.show_background_tasks"),
+            Action::ReloadPlugin => t!("action.reload_plugin"),
+            Action::MemoryReport => t!("action.memory_report"),
+            Action::ShowTelemetryData => t!("action.show_telemetry_data"),
+            Action::UpdateFreshNow => t!("action.update_fresh_now"),
+            Action::ManageWorkspaceTrust => t!("action.manage_workspace_trust"),
             Action::ClearWarnings => t!("action.clear_warnings"),
             Action::CommandPalette => t!("action.command_palette"),
             Action::QuickOpen => t!("action.quick_open"),
             Action::ToggleLineWrap => t!("action.toggle_line_wrap"),
             Action::ToggleComposeMode => t!("action.toggle_compose_mode"),
+            Action::ToggleFocusMode => t!("action.toggle_focus_mode"),
             Action::SetComposeWidth => t!("action.set_compose_width"),
             Action::NextBuffer => t!("action.next_buffer"),
             Action::PrevBuffer => t!("action.prev_buffer"),
@@ -1864,6 +2319,7 @@ impl KeybindingResolver {
             Action::IncreaseSplitSize => t!("action.increase_split_size"),
             Action::DecreaseSplitSize => t!("action.decrease_split_size"),
             Action::ToggleMaximizeSplit => t!("action.toggle_maximize_split"),
+            Action::ToggleScrollLock => t!("action.toggle_scroll_lock"),
             Action::PromptConfirm => t!("action.prompt_confirm"),
             Action::PromptConfirmWithText(ref text) => {
                 format!("{} ({})", t!("action.prompt_confirm"), text).into()
@@ -1908,6 +2364,7 @@ impl KeybindingResolver {
             Action::ToggleFileExplorer => t!("action.toggle_file_explorer"),
             Action::ToggleMenuBar => t!("action.toggle_menu_bar"),
             Action::ToggleTabBar => t!("action.toggle_tab_bar"),
+            Action::TogglePreviewTabs => t!("action.toggle_preview_tabs"),
             Action::FocusFileExplorer => t!("action.focus_file_explorer"),
             Action::FocusEditor => t!("action.focus_editor"),
             Action::FileExplorerUp => t!("action.file_explorer_up"),
@@ -1921,14 +2378,19 @@ impl KeybindingResolver {
             Action::FileExplorerNewFile => t!("action.file_explorer_new_file"),
             Action::FileExplorerNewDirectory => t!("action.file_explorer_new_directory"),
             Action::FileExplorerDelete => t!("action.file_explorer_delete"),
+            Action::FileExplorerUndoDelete => t!("action.file_explorer_undo_delete"),
             Action::FileExplorerRename => t!("action.file_explorer_rename"),
             Action::FileExplorerToggleHidden => t!("action.file_explorer_toggle_hidden"),
             Action::FileExplorerToggleGitignored => t!("action.file_explorer_toggle_gitignored"),
+            Action::FileExplorerNextRoot => t!("action.file_explorer_next_root"),
             Action::FileExplorerSearchClear => t!("action.file_explorer_search_clear"),
             Action::FileExplorerSearchBackspace => t!("action.file_explorer_search_backspace"),
             Action::LspCompletion => t!("action.lsp_completion"),
+            Action::OpenLinkUnderCursor => t!("action.open_link_under_cursor"),
             Action::LspGotoDefinition => t!("action.lsp_goto_definition"),
             Action::LspReferences => t!("action.lsp_references"),
+            Action::LspPeekDefinition => t!("action.lsp_peek_definition"),
+            Action::LspPeekReferences => t!("action.lsp_peek_references"),
             Action::LspRename => t!("action.lsp_rename"),
             Action::LspHover => t!("action.lsp_hover"),
             Action::LspSignatureHelp => t!("action.lsp_signature_help"),
@@ -1938,8 +2400,13 @@ impl KeybindingResolver {
             Action::ToggleInlayHints => t!("action.toggle_inlay_hints"),
             Action::ToggleMouseHover => t!("action.toggle_mouse_hover"),
             Action::ToggleLineNumbers => t!("action.toggle_line_numbers"),
+            Action::ToggleReadOnly => t!("action.toggle_read_only"),
+            Action::ToggleImagePreview => t!("action.toggle_image_preview"),
             Action::ToggleMouseCapture => t!("action.toggle_mouse_capture"),
+            Action::ToggleAccessibility => t!("action.toggle_accessibility"),
+            Action::AnnounceContext => t!("action.announce_context"),
             Action::ToggleDebugHighlights => t!("action.toggle_debug_highlights"),
+            Action::ToggleRenderProfiler => t!("action.toggle_render_profiler"),
             Action::SetBackground => t!("action.set_background"),
             Action::SetBackgroundBlend => t!("action.set_background_blend"),
             Action::SetTabSize => t!("action.set_tab_size"),
@@ -1948,7 +2415,11 @@ impl KeybindingResolver {
             Action::ReloadWithEncoding => t!("action.reload_with_encoding"),
             Action::SetLanguage => t!("action.set_language"),
             Action::ToggleIndentationStyle => t!("action.toggle_indentation_style"),
+            Action::ConvertIndentationToSpaces => t!("action.convert_indentation_to_spaces"),
+            Action::ConvertIndentationToTabs => t!("action.convert_indentation_to_tabs"),
+            Action::ReindentBuffer => t!("action.reindent_buffer"),
             Action::ToggleTabIndicators => t!("action.toggle_tab_indicators"),
+            Action::CycleRenderWhitespace => t!("action.cycle_render_whitespace"),
             Action::ResetBufferSettings => t!("action.reset_buffer_settings"),
             Action::DumpConfig => t!("action.dump_config"),
             Action::Search => t!("action.search"),
@@ -1959,6 +2430,8 @@ impl KeybindingResolver {
             Action::FindSelectionPrevious => t!("action.find_selection_previous"),
             Action::Replace => t!("action.replace"),
             Action::QueryReplace => t!("action.query_replace"),
+            Action::StructuralSearch => t!("action.structural_search"),
+            Action::StructuralReplace => t!("action.structural_replace"),
             Action::MenuActivate => t!("action.menu_activate"),
             Action::MenuClose => t!("action.menu_close"),
             Action::MenuLeft => t!("action.menu_left"),
@@ -1977,6 +2450,12 @@ impl KeybindingResolver {
             Action::SelectLocale => t!("action.select_locale"),
             Action::SwitchToPreviousTab => t!("action.switch_to_previous_tab"),
             Action::SwitchToTabByName => t!("action.switch_to_tab_by_name"),
+            Action::TogglePinTab => t!("action.toggle_pin_tab"),
+            Action::SetTabGroup => t!("action.set_tab_group"),
+            Action::CloseOtherTabs => t!("action.close_other_tabs"),
+            Action::CloseTabsToRight => t!("action.close_tabs_to_right"),
+            Action::CloseTabsToLeft => t!("action.close_tabs_to_left"),
+            Action::CloseAllTabs => t!("action.close_all_tabs"),
             Action::OpenTerminal => t!("action.open_terminal"),
             Action::CloseTerminal => t!("action.close_terminal"),
             Action::FocusTerminal => t!("action.focus_terminal"),
@@ -1995,9 +2474,30 @@ impl KeybindingResolver {
             Action::SettingsDecrement => t!("action.settings_decrement"),
             Action::ShellCommand => t!("action.shell_command"),
             Action::ShellCommandReplace => t!("action.shell_command_replace"),
+            Action::FilterThroughCommand => t!("action.filter_through_command"),
+            Action::ToggleWatchTask => t!("action.toggle_watch_task"),
+            Action::ShowWatchOutput => t!("action.show_watch_output"),
+            Action::QuickfixNext => t!("action.quickfix_next"),
+            Action::QuickfixPrev => t!("action.quickfix_prev"),
+            Action::QuickfixFirst => t!("action.quickfix_first"),
+            Action::QuickfixLast => t!("action.quickfix_last"),
+            Action::QuickfixOlder => t!("action.quickfix_older"),
+            Action::QuickfixNewer => t!("action.quickfix_newer"),
+            Action::QuickfixShow => t!("action.quickfix_show"),
+            Action::QuickfixHistory => t!("action.quickfix_history"),
             Action::ToUpperCase => t!("action.to_uppercase"),
             Action::ToLowerCase => t!("action.to_lowercase"),
+            Action::ToTitleCase => t!("action.to_title_case"),
+            Action::ToCamelCase => t!("action.to_camel_case"),
+            Action::ToSnakeCase => t!("action.to_snake_case"),
+            Action::ToKebabCase => t!("action.to_kebab_case"),
             Action::SortLines => t!("action.sort_lines"),
+            Action::UniqueLines => t!("action.unique_lines"),
+            Action::IncrementNumber => t!("action.increment_number"),
+            Action::DecrementNumber => t!("action.decrement_number"),
+            Action::InsertSequence => t!("action.insert_sequence"),
+            Action::InsertDateTime => t!("action.insert_date_time"),
+            Action::AlignSelection => t!("action.align_selection"),
             Action::CalibrateInput => t!("action.calibrate_input"),
             Action::EventDebug => t!("action.event_debug"),
             Action::OpenKeybindingEditor => "Keybinding Editor".into(),
@@ -2132,24 +2632,15 @@ impl KeybindingResolver {
     /// Reload bindings from config (for hot reload)
     pub fn reload(&mut self, config: &Config) {
         self.bindings.clear();
-        for binding in &config.keybindings {
-            if let Some(key_code) = Self::parse_key(&binding.key) {
-                let modifiers = Self::parse_modifiers(&binding.modifiers);
-                if let Some(action) = Action::from_str(&binding.action, &binding.args) {
-                    // Determine context from "when" clause
-                    let context = if let Some(ref when) = binding.when {
-                        KeyContext::from_when_clause(when).unwrap_or(KeyContext::Normal)
-                    } else {
-                        KeyContext::Normal
-                    };
+        self.chord_bindings.clear();
+        self.conditional_bindings.clear();
+        self.conflicts.clear();
 
-                    self.bindings
-                        .entry(context)
-                        .or_default()
-                        .insert((key_code, modifiers), action);
-                }
-            }
-        }
+        let map_bindings = config.resolve_keymap(&config.active_keybinding_map);
+        self.default_bindings.clear();
+        self.default_chord_bindings.clear();
+        self.load_default_bindings_from_vec(&map_bindings);
+        self.load_bindings_from_vec(&config.keybindings);
     }
 }
 
@@ -2402,6 +2893,176 @@ mod tests {
         );
     }
 
+    fn custom_binding(key: &str, action: &str, when: Option<&str>) -> crate::config::Keybinding {
+        crate::config::Keybinding {
+            key: key.to_string(),
+            modifiers: vec![],
+            keys: vec![],
+            action: action.to_string(),
+            args: HashMap::new(),
+            when: when.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_key_string_chords() {
+        let ctrl = KeyModifiers::CONTROL;
+        let sequence =
+            |key: &str| KeybindingResolver::binding_sequence(&custom_binding(key, "save", None));
+        assert_eq!(
+            sequence("ctrl+k ctrl+c"),
+            Some(vec![(KeyCode::Char('k'), ctrl), (KeyCode::Char('c'), ctrl)])
+        );
+        assert_eq!(
+            sequence("ctrl+shift+Enter"),
+            Some(vec![(KeyCode::Enter, ctrl | KeyModifiers::SHIFT)])
+        );
+        assert_eq!(sequence("ctrl++"), Some(vec![(KeyCode::Char('+'), ctrl)]));
+        assert_eq!(
+            sequence("+"),
+            Some(vec![(KeyCode::Char('+'), KeyModifiers::NONE)])
+        );
+        assert_eq!(
+            sequence("g g"),
+            Some(vec![(KeyCode::Char('g'), KeyModifiers::NONE); 2])
+        );
+        assert_eq!(sequence("hyper+k"), None);
+        assert_eq!(sequence("ctrl+k nope"), None);
+
+        let mut config = Config::default();
+        config
+            .keybindings
+            .push(custom_binding("ctrl+k ctrl+c", "command_palette", None));
+        let resolver = KeybindingResolver::new(&config);
+        let ctrl_k = KeyEvent::new(KeyCode::Char('k'), ctrl);
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), ctrl);
+        assert_eq!(
+            resolver.resolve_chord(&[], &ctrl_k, KeyContext::Normal),
+            ChordResolution::Partial
+        );
+        assert_eq!(
+            resolver.resolve_chord(&[(KeyCode::Char('k'), ctrl)], &ctrl_c, KeyContext::Normal),
+            ChordResolution::Complete(Action::CommandPalette)
+        );
+    }
+
+    #[test]
+    fn test_conditional_bindings() {
+        let mut config = Config::default();
+        config.keybindings.push(custom_binding(
+            "x",
+            "save",
+            Some("editorFocus && buffer_mode == 'vi-normal'"),
+        ));
+        config.keybindings.push(custom_binding(
+            "ctrl+k ctrl+s",
+            "quit",
+            Some("has_selection || promptFocus"),
+        ));
+        config
+            .keybindings
+            .push(custom_binding("y", "undo", Some("editorFocus &&")));
+        let resolver = KeybindingResolver::new(&config);
+        assert!(resolver.has_conditional_bindings());
+
+        let no_flags = |_: &str| false;
+        let selection = |name: &str| name == "has_selection";
+        fn when<'a>(
+            key_context: KeyContext,
+            buffer_mode: Option<&'a str>,
+            flags: &'a dyn Fn(&str) -> bool,
+        ) -> WhenContext<'a> {
+            WhenContext {
+                key_context,
+                popup_visible: false,
+                buffer_mode,
+                flags,
+            }
+        }
+        let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+        assert_eq!(
+            resolver.resolve_conditional(
+                &[],
+                &x,
+                &when(KeyContext::Normal, Some("vi-normal"), &no_flags)
+            ),
+            ChordResolution::Complete(Action::Save)
+        );
+        assert_eq!(
+            resolver.resolve_conditional(&[], &x, &when(KeyContext::Normal, None, &no_flags)),
+            ChordResolution::NoMatch
+        );
+        assert_eq!(
+            resolver.resolve_conditional(
+                &[],
+                &x,
+                &when(KeyContext::Prompt, Some("vi-normal"), &no_flags)
+            ),
+            ChordResolution::NoMatch
+        );
+
+        let ctrl = KeyModifiers::CONTROL;
+        let ctrl_k = KeyEvent::new(KeyCode::Char('k'), ctrl);
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), ctrl);
+        assert_eq!(
+            resolver.resolve_conditional(&[], &ctrl_k, &when(KeyContext::Normal, None, &no_flags)),
+            ChordResolution::NoMatch
+        );
+        assert_eq!(
+            resolver.resolve_conditional(&[], &ctrl_k, &when(KeyContext::Normal, None, &selection)),
+            ChordResolution::Partial
+        );
+        assert_eq!(
+            resolver.resolve_conditional(
+                &[(KeyCode::Char('k'), ctrl)],
+                &ctrl_s,
+                &when(KeyContext::Prompt, None, &no_flags)
+            ),
+            ChordResolution::Complete(Action::Quit)
+        );
+
+        // The invalid expression was skipped
+        let y = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+        assert_eq!(
+            resolver.resolve_conditional(&[], &y, &when(KeyContext::Normal, None, &no_flags)),
+            ChordResolution::NoMatch
+        );
+    }
+
+    #[test]
+    fn test_keybinding_conflicts() {
+        let mut config = Config::default();
+        assert!(KeybindingResolver::new(&config).conflicts().is_empty());
+
+        config
+            .keybindings
+            .push(custom_binding("ctrl+t", "save", None));
+        config
+            .keybindings
+            .push(custom_binding("ctrl+t", "quit", None));
+        // Same key in another context is fine
+        config
+            .keybindings
+            .push(custom_binding("ctrl+t", "undo", Some("prompt")));
+        // The chord makes the default Ctrl+K binding unreachable in normal context
+        config
+            .keybindings
+            .push(custom_binding("ctrl+k ctrl+c", "command_palette", None));
+
+        let resolver = KeybindingResolver::new(&config);
+        let conflicts = resolver.conflicts();
+        assert_eq!(conflicts.len(), 2, "{:?}", conflicts);
+        assert!(conflicts.contains(&KeybindingConflict::Duplicate {
+            when: "normal".to_string(),
+            keys: format_keybinding(&KeyCode::Char('t'), &KeyModifiers::CONTROL),
+            actions: vec!["save".to_string(), "quit".to_string()],
+        }));
+        assert!(conflicts.iter().any(|c| matches!(
+            c,
+            KeybindingConflict::ShadowedByChord { when, .. } if when == "normal"
+        )));
+    }
+
     #[test]
     fn test_all_context_default_bindings_exist() {
         let config = Config::default();
@@ -1,8 +1,10 @@
 //! Line/selection move helpers for editor actions.
 
+use crate::input::actions::indent_to_string;
 use crate::model::buffer::Buffer;
 use crate::model::cursor::Cursor;
 use crate::model::event::{CursorId, Event};
+use crate::primitives::indent_rules::{self, IndentRules};
 use crate::state::EditorState;
 use std::ops::Range;
 
@@ -140,6 +142,8 @@ struct LinePiece {
     start: usize,
     end: usize,
     content: String,
+    /// Bytes added to (or removed from) the line's indentation
+    indent_shift: isize,
 }
 
 #[derive(Debug, Clone)]
@@ -150,12 +154,15 @@ struct LineMapping {
     new_start: usize,
     new_len: usize,
     new_has_newline: bool,
+    indent_shift: isize,
 }
 
 #[derive(Debug, Clone)]
 struct AppliedRegion {
     region: MoveRegion,
     mappings: Vec<LineMapping>,
+    /// Change in length from reindenting the moved lines
+    len_delta: isize,
 }
 
 fn strip_line_ending(line: &str) -> &str {
@@ -197,7 +204,8 @@ fn map_position_in_region(
         pos >= mapping.old_start
             && (pos < mapping.old_end || (!mapping.old_has_newline && pos == mapping.old_end))
     }) {
-        let column = pos.saturating_sub(mapping.old_start);
+        let column =
+            (pos.saturating_sub(mapping.old_start) as isize + mapping.indent_shift).max(0) as usize;
         let max_offset = if mapping.new_has_newline {
             mapping.new_len.saturating_sub(1)
         } else {
@@ -210,12 +218,74 @@ fn map_position_in_region(
     None
 }
 
+/// The nearest non-blank line above `pos`
+fn nonblank_line_above(
+    buffer: &mut Buffer,
+    pos: usize,
+    estimated_line_length: usize,
+) -> Option<String> {
+    let mut iter = buffer.line_iterator(pos, estimated_line_length);
+    (0..MAX_REINDENT_LOOKBACK)
+        .map_while(|_| iter.prev())
+        .map(|(_, line)| strip_line_ending(&line).to_string())
+        .find(|line| !line.trim().is_empty())
+}
+
+/// Lines looked back over for the line a moved block lands under
+const MAX_REINDENT_LOOKBACK: usize = 100;
+
+/// Shift the indentation of `block` so its first non-blank line is indented
+/// as it should be below `previous`
+///
+/// Blank lines are left alone, and nothing changes if moving out would cut
+/// into a line's text.
+fn reindent_block(
+    block: &mut [LinePiece],
+    previous: &str,
+    rules: Option<&IndentRules>,
+    use_tabs: bool,
+    tab_size: usize,
+) {
+    let Some(first) = block.iter().find(|line| !line.content.trim().is_empty()) else {
+        return;
+    };
+    let expected = indent_rules::indent_below(rules, previous, &first.content, tab_size);
+    let delta = expected as isize - indent_rules::indent_columns(&first.content, tab_size) as isize;
+    let fits = block.iter().all(|line| {
+        line.content.trim().is_empty()
+            || indent_rules::indent_columns(&line.content, tab_size) as isize + delta >= 0
+    });
+    if delta == 0 || !fits {
+        return;
+    }
+    for line in block.iter_mut() {
+        if line.content.trim().is_empty() {
+            continue;
+        }
+        let columns = indent_rules::indent_columns(&line.content, tab_size) as isize + delta;
+        let text = line.content.trim_start_matches([' ', '\t']);
+        let indent = indent_to_string(columns as usize, use_tabs, tab_size);
+        let old_indent_len = line.content.len() - text.len();
+        line.indent_shift = indent.len() as isize - old_indent_len as isize;
+        line.content = format!("{}{}", indent, text);
+    }
+}
+
+/// Move the lines of every cursor and selection up or down past the line next
+/// to them
+///
+/// With `reindent` (the tab size, when auto-indent is on) the moved lines are
+/// reindented for the block they land in, in buffers whose language has a
+/// grammar or indentation rules to go by.
 pub(crate) fn move_lines(
     state: &mut EditorState,
     events: &mut Vec<Event>,
     direction: LineMoveDirection,
     estimated_line_length: usize,
+    reindent: Option<usize>,
 ) {
+    let reindent =
+        reindent.filter(|_| state.indent_rules.is_some() || state.highlighter.language().is_some());
     let buffer_len = state.buffer.len();
     if buffer_len == 0 {
         return;
@@ -318,6 +388,7 @@ pub(crate) fn move_lines(
     };
 
     let mut applied_regions = Vec::new();
+    let mut edits = Vec::new();
     let line_ending = state.buffer.line_ending().as_str();
     let line_ending_len = line_ending.len();
     for region in &move_regions {
@@ -343,6 +414,7 @@ pub(crate) fn move_lines(
                 start: line_start,
                 end: line_end,
                 content: strip_line_ending(&line).to_string(),
+                indent_shift: 0,
             });
             if line_end >= region.end {
                 break;
@@ -393,6 +465,23 @@ pub(crate) fn move_lines(
             }
         };
 
+        if let Some(tab_size) = reindent {
+            // The line the block lands under
+            let previous = match region.direction {
+                LineMoveDirection::Down if !adjacent.content.trim().is_empty() => {
+                    Some(adjacent.content.clone())
+                }
+                _ => nonblank_line_above(&mut state.buffer, region.start, estimated_line_length),
+            };
+            reindent_block(
+                &mut block_lines,
+                previous.as_deref().unwrap_or_default(),
+                state.indent_rules.as_ref(),
+                state.use_tabs,
+                tab_size,
+            );
+        }
+
         let mut new_text = String::new();
         let mut ordered = Vec::new();
         match region.direction {
@@ -444,6 +533,7 @@ pub(crate) fn move_lines(
                 new_start,
                 new_len,
                 new_has_newline: append_line_ending,
+                indent_shift: line.indent_shift,
             });
 
             if append_line_ending {
@@ -456,39 +546,58 @@ pub(crate) fn move_lines(
         applied_regions.push(AppliedRegion {
             region: *region,
             mappings,
+            len_delta: new_text.len() as isize - old_text.len() as isize,
         });
 
         if new_text == old_text {
             continue;
         }
 
-        events.push(Event::Delete {
-            range: region.start..region.end,
-            deleted_text: old_text,
-            cursor_id: primary_cursor_id,
-        });
-        events.push(Event::Insert {
-            position: region.start,
-            text: new_text,
-            cursor_id: primary_cursor_id,
-        });
+        edits.push((
+            Event::Delete {
+                range: region.start..region.end,
+                deleted_text: old_text,
+                cursor_id: primary_cursor_id,
+            },
+            Event::Insert {
+                position: region.start,
+                text: new_text,
+                cursor_id: primary_cursor_id,
+            },
+        ));
     }
 
     if applied_regions.is_empty() {
         return;
     }
 
-    for (cursor_id, selection, position, anchor, sticky_column) in cursor_snapshots {
-        let new_position = applied_regions
+    // Last region first, so reindented regions don't shift the ones after them
+    for (delete, insert) in edits.into_iter().rev() {
+        events.push(delete);
+        events.push(insert);
+    }
+
+    // How far reindenting the regions before `pos` moved it
+    let delta_before = |pos: usize| -> isize {
+        applied_regions
             .iter()
-            .find_map(|region| map_position_in_region(position, selection.as_ref(), region))
-            .unwrap_or(position);
-        let new_anchor = anchor.map(|anchor_pos| {
-            applied_regions
-                .iter()
-                .find_map(|region| map_position_in_region(anchor_pos, selection.as_ref(), region))
-                .unwrap_or(anchor_pos)
-        });
+            .filter(|applied| applied.region.end <= pos)
+            .map(|applied| applied.len_delta)
+            .sum()
+    };
+    let map_position = |pos: usize, selection: Option<&Range<usize>>| -> usize {
+        applied_regions
+            .iter()
+            .find_map(|applied| {
+                map_position_in_region(pos, selection, applied)
+                    .map(|new_pos| new_pos as isize + delta_before(applied.region.start))
+            })
+            .unwrap_or_else(|| pos as isize + delta_before(pos)) as usize
+    };
+
+    for (cursor_id, selection, position, anchor, sticky_column) in cursor_snapshots {
+        let new_position = map_position(position, selection.as_ref());
+        let new_anchor = anchor.map(|anchor_pos| map_position(anchor_pos, selection.as_ref()));
 
         if new_position != position || new_anchor != anchor {
             events.push(Event::MoveCursor {
@@ -1,5 +1,9 @@
 //! Multi-cursor operations for adding cursors at various positions
 
+use std::ops::Range;
+
+use regex::bytes::{Regex, RegexBuilder};
+
 use crate::model::cursor::Cursor;
 use crate::primitives::word_navigation::{find_word_end, find_word_start};
 use crate::state::EditorState;
@@ -63,140 +67,249 @@ fn adjust_position_for_newline(state: &mut EditorState, position: usize) -> usiz
     position
 }
 
+/// How occurrences of the selected text are matched, from the search options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: true,
+            whole_word: false,
+        }
+    }
+}
+
+/// Regex for occurrences of the literal `text`
+///
+/// With `whole_word`, ends of `text` that are word characters must be at a
+/// word boundary, so `foo(` still matches in `foo(x)`.
+fn occurrence_regex(text: &str, options: MatchOptions) -> Option<Regex> {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let mut pattern = regex::escape(text);
+    if options.whole_word && is_word(text.chars().next()) {
+        pattern.insert_str(0, r"\b");
+    }
+    if options.whole_word && is_word(text.chars().last()) {
+        pattern.push_str(r"\b");
+    }
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .ok()
+}
+
+/// Range of the first occurrence at or after `start`
+///
+/// With `wrap`, the search continues from the start of the buffer. `text_len`
+/// is the length of the selected text; a case-insensitive match can differ
+/// from it in length.
+fn find_occurrence(
+    state: &mut EditorState,
+    regex: &Regex,
+    start: usize,
+    text_len: usize,
+    wrap: bool,
+) -> Option<Range<usize>> {
+    let len = state.buffer.len();
+    let pos = if wrap {
+        state.buffer.find_next_regex(regex, start)?
+    } else {
+        state
+            .buffer
+            .find_next_regex_in_range(regex, start, Some(0..len))?
+    };
+    let end = (pos + text_len * 4).min(len);
+    let text = state.buffer.get_text_range_mut(pos, end - pos).ok()?;
+    let found = regex.find(&text).filter(|m| m.start() == 0)?;
+    Some(pos..pos + found.end())
+}
+
+/// Range of the word at the cursor, or None on whitespace or punctuation
+fn word_at_cursor(state: &mut EditorState) -> Option<Range<usize>> {
+    let cursor_pos = state.cursors.primary().position;
+    let word_start = find_word_start(&state.buffer, cursor_pos);
+
+    // Determine word_end: if we're just past a word (at a non-word char but
+    // word_start < cursor_pos), use cursor_pos as the end. This handles the
+    // case where cursor is at the space right after a word.
+    let word_end = if word_start < cursor_pos {
+        // Check if we're at a word character
+        let at_word_char = if cursor_pos < state.buffer.len() {
+            if let Ok(bytes) = state.buffer.get_text_range_mut(cursor_pos, 1) {
+                bytes
+                    .first()
+                    .map(|&b| crate::primitives::word_navigation::is_word_char(b))
+                    .unwrap_or(false)
+            } else {
+                false
+            }
+        } else {
+            false
+        };
+
+        if at_word_char {
+            // We're in the middle of a word, find the actual end
+            find_word_end(&state.buffer, cursor_pos)
+        } else {
+            // We're just past a word, use cursor position as end
+            cursor_pos
+        }
+    } else {
+        // word_start == cursor_pos, find the end normally
+        find_word_end(&state.buffer, cursor_pos)
+    };
+
+    (word_start < word_end).then_some(word_start..word_end)
+}
+
+/// Cursor selecting `range`, facing the same way as the primary cursor
+fn cursor_for_match(range: Range<usize>, cursor_at_start: bool) -> Cursor {
+    if cursor_at_start {
+        let mut cursor = Cursor::new(range.start);
+        cursor.set_anchor(range.end);
+        cursor
+    } else {
+        Cursor::with_selection(range.start, range.end)
+    }
+}
+
 /// Add a cursor at the next occurrence of the selected text
 /// If no selection, selects the entire word at cursor position first
-pub fn add_cursor_at_next_match(state: &mut EditorState) -> AddCursorResult {
-    // Get the selected text from the primary cursor
-    let primary = state.cursors.primary();
-    let selection_range = match primary.selection_range() {
-        Some(range) => range,
-        None => {
-            // No selection - select the entire word at cursor position
-            let cursor_pos = primary.position;
-            let word_start = find_word_start(&state.buffer, cursor_pos);
-
-            // Determine word_end: if we're just past a word (at a non-word char but
-            // word_start < cursor_pos), use cursor_pos as the end. This handles the
-            // case where cursor is at the space right after a word.
-            let word_end = if word_start < cursor_pos {
-                // Check if we're at a word character
-                let at_word_char = if cursor_pos < state.buffer.len() {
-                    if let Ok(bytes) = state.buffer.get_text_range_mut(cursor_pos, 1) {
-                        bytes
-                            .first()
-                            .map(|&b| crate::primitives::word_navigation::is_word_char(b))
-                            .unwrap_or(false)
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                };
-
-                if at_word_char {
-                    // We're in the middle of a word, find the actual end
-                    find_word_end(&state.buffer, cursor_pos)
-                } else {
-                    // We're just past a word, use cursor position as end
-                    cursor_pos
-                }
-            } else {
-                // word_start == cursor_pos, find the end normally
-                find_word_end(&state.buffer, cursor_pos)
-            };
+pub fn add_cursor_at_next_match(state: &mut EditorState, options: MatchOptions) -> AddCursorResult {
+    next_free_match(state, options, false)
+}
 
-            // If cursor is on whitespace or punctuation (word_start == word_end), fail
-            if word_start == word_end {
-                return AddCursorResult::Failed {
-                    message: "No word at cursor position".to_string(),
-                };
-            }
+/// Find the next occurrence for the primary cursor to move to, skipping the
+/// one it selects (the last one added)
+/// If no selection, selects the entire word at cursor position first
+pub fn skip_to_next_match(state: &mut EditorState, options: MatchOptions) -> AddCursorResult {
+    next_free_match(state, options, true)
+}
 
+/// The next occurrence of the primary cursor's selection that no cursor
+/// selects yet; with `skip`, the primary cursor's own occurrence counts as
+/// free since it is about to move
+fn next_free_match(state: &mut EditorState, options: MatchOptions, skip: bool) -> AddCursorResult {
+    // Get the selected text from the primary cursor
+    let primary_id = state.cursors.primary_id();
+    let primary = *state.cursors.primary();
+    let Some(selection_range) = primary.selection_range() else {
+        // No selection - select the entire word at cursor position
+        return match word_at_cursor(state) {
             // Return WordSelected so caller can update the cursor's selection
-            return AddCursorResult::WordSelected {
-                word_start,
-                word_end,
-            };
-        }
+            Some(word) => AddCursorResult::WordSelected {
+                word_start: word.start,
+                word_end: word.end,
+            },
+            None => AddCursorResult::Failed {
+                message: "No word at cursor position".to_string(),
+            },
+        };
     };
 
     // Determine if the original selection is "backward" (cursor at start of selection)
     let cursor_at_start = primary.position == selection_range.start;
 
     // Extract the selected text
-    let pattern = state.get_text_range(selection_range.start, selection_range.end);
-    let pattern_len = pattern.len();
+    let text = state.get_text_range(selection_range.start, selection_range.end);
+    let Some(regex) = occurrence_regex(&text, options) else {
+        return AddCursorResult::Failed {
+            message: "No more matches".to_string(),
+        };
+    };
 
-    // Start searching from the end of the current selection
+    // Start searching from the end of the current selection, and stop once
+    // the search comes back around to a match it has seen
     let mut search_start = selection_range.end;
-    let _ign = search_start; // To prevent infinite loops (unused now)
-
-    // Loop until we find a match that isn't already occupied by a cursor
+    let mut first_match = None;
     loop {
-        let match_pos = match state.buffer.find_next(&pattern, search_start) {
-            Some(pos) => pos,
-            None => {
-                // If finding next failed even with wrap-around (implied by buffer.find_next usually),
-                // then truly no matches exist.
-                return AddCursorResult::Failed {
-                    message: "No more matches".to_string(),
-                };
-            }
+        let Some(match_range) = find_occurrence(state, &regex, search_start, text.len(), true)
+        else {
+            return AddCursorResult::Failed {
+                message: "No more matches".to_string(),
+            };
         };
-
-        // Calculate the range of the found match
-        let match_range = match_pos..(match_pos + pattern_len);
-
-        // Check if any existing cursor overlaps with this match
-        let is_occupied = state.cursors.iter().any(|(_, c)| {
-            if let Some(r) = c.selection_range() {
-                r == match_range
+        if first_match == Some(match_range.start) || match_range == selection_range {
+            let message = if skip {
+                "No more matches"
             } else {
-                false
-            }
-        });
+                "All matches are already selected"
+            };
+            return AddCursorResult::Failed {
+                message: message.to_string(),
+            };
+        }
+        first_match.get_or_insert(match_range.start);
 
+        // Check if any existing cursor already selects this match
+        let is_occupied = state.cursors.iter().any(|(id, c)| {
+            !(skip && id == primary_id) && c.selection_range() == Some(match_range.clone())
+        });
         if !is_occupied {
-            // Found a free match!
-            let match_start = match_pos;
-            let match_end = match_pos + pattern_len;
-            let new_cursor = if cursor_at_start {
-                let mut cursor = Cursor::new(match_start);
-                cursor.set_anchor(match_end);
-                cursor
+            let cursor = cursor_for_match(match_range, cursor_at_start);
+            return if skip {
+                AddCursorResult::Success {
+                    cursor,
+                    total_cursors: state.cursors.count(),
+                }
             } else {
-                Cursor::with_selection(match_start, match_end)
+                success_result(cursor, state)
             };
-            return success_result(new_cursor, state);
         }
 
-        // If we wrapped around and came back to where we started searching (or past it), stop to avoid infinite loop
-        // We need to handle the case where find_next wraps around.
-        // Assuming buffer.find_next does wrap around:
-        // If match_pos <= search_start and we haven't wrapped explicitly, it means we wrapped.
-
-        // Let's refine the search start. We want to search *after* this occupied match.
-        // If match_pos is behind us, we wrapped.
-
-        let next_start = match_pos + pattern_len;
+        search_start = match_range.end;
+    }
+}
 
-        // Simple cycle detection: if we are stuck on the same spot or have cycled through the whole buffer
-        // Ideally we check if we've visited this match_pos before, but checking if we passed initial_start again is a decent proxy
-        // provided we handle the wrap-around logic correctly.
+/// Occurrences for Select All Occurrences
+pub struct AllMatches {
+    /// Word at the cursor for the primary cursor to select, when it had no
+    /// selection
+    pub word: Option<Range<usize>>,
+    /// Cursors for the occurrences no cursor selects yet, in buffer order
+    pub cursors: Vec<Cursor>,
+}
 
-        // If find_next scans the whole buffer, it might return the same spot if it's the only match.
-        // If it's occupied, we are done.
+/// Find every occurrence of the selected text (or of the word at the
+/// cursor) that no cursor selects yet
+pub fn select_all_matches(
+    state: &mut EditorState,
+    options: MatchOptions,
+) -> Result<AllMatches, String> {
+    let primary = *state.cursors.primary();
+    let (selection_range, word, cursor_at_start) = match primary.selection_range() {
+        Some(range) => (range.clone(), None, primary.position == range.start),
+        None => {
+            let word = word_at_cursor(state).ok_or("No word at cursor position")?;
+            (word.clone(), Some(word), false)
+        }
+    };
 
-        // To be safe against infinite loops if all matches are occupied:
-        if match_pos == selection_range.start {
-            // We wrapped all the way back to the primary cursor without finding a free spot
-            return AddCursorResult::Failed {
-                message: "All matches are already selected".to_string(),
-            };
+    let text = state.get_text_range(selection_range.start, selection_range.end);
+    let regex = occurrence_regex(&text, options).ok_or("No more matches")?;
+
+    let mut cursors = Vec::new();
+    let mut search_start = 0;
+    while let Some(match_range) = find_occurrence(state, &regex, search_start, text.len(), false) {
+        search_start = match_range.end.max(match_range.start + 1);
+        let is_occupied = match_range == selection_range
+            || state
+                .cursors
+                .iter()
+                .any(|(_, c)| c.selection_range() == Some(match_range.clone()));
+        if !is_occupied {
+            cursors.push(cursor_for_match(match_range, cursor_at_start));
         }
+    }
 
-        search_start = next_start;
+    if cursors.is_empty() && word.is_none() {
+        return Err("All matches are already selected".to_string());
     }
+    Ok(AllMatches { word, cursors })
 }
 
 /// Add a cursor above the primary cursor at the same column
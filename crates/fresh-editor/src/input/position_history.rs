@@ -59,6 +59,10 @@ const LARGE_JUMP_THRESHOLD: usize = 50;
 ///
 /// Movements are coalesced: consecutive MoveCursor events within a short
 /// time period are treated as a single "jump" for navigation purposes.
+///
+/// Each split keeps its own history, so navigating back in one pane doesn't
+/// follow jumps made in another.
+#[derive(Clone, Debug)]
 pub struct PositionHistory {
     /// Stack of position entries
     entries: Vec<PositionEntry>,
@@ -252,6 +256,31 @@ impl PositionHistory {
     pub fn current_index(&self) -> Option<usize> {
         self.current_index
     }
+
+    /// Get all entries, oldest first
+    pub fn entries(&self) -> &[PositionEntry] {
+        &self.entries
+    }
+
+    /// Replace the history with `entries`, e.g. when restoring a workspace
+    ///
+    /// `current_index` is clamped to the entries; the most recent entries are
+    /// kept if there are more than the maximum.
+    pub fn restore(&mut self, mut entries: Vec<PositionEntry>, current_index: Option<usize>) {
+        let excess = entries.len().saturating_sub(self.max_entries);
+        entries.drain(..excess);
+        self.current_index = if entries.is_empty() {
+            None
+        } else {
+            Some(
+                current_index
+                    .map_or(entries.len() - 1, |i| i.saturating_sub(excess))
+                    .min(entries.len() - 1),
+            )
+        };
+        self.entries = entries;
+        self.pending_movement = None;
+    }
 }
 
 impl Default for PositionHistory {
@@ -448,4 +477,25 @@ mod tests {
         assert!(!history.can_go_back());
         assert!(!history.can_go_forward());
     }
+
+    #[test]
+    fn test_restore() {
+        let mut history = PositionHistory::with_capacity(3);
+        history.record_movement(BufferId(1), 500, None);
+
+        let entries: Vec<_> = (0..5).map(|i| make_entry(1, i * 10)).collect();
+        history.restore(entries, Some(3));
+
+        // The oldest entries are dropped and the index follows its entry
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.current(), Some(&make_entry(1, 30)));
+        assert!(history.can_go_forward());
+
+        // The pending movement from before the restore is gone
+        history.commit_pending_movement();
+        assert_eq!(history.len(), 3);
+
+        history.restore(Vec::new(), Some(2));
+        assert_eq!(history.current_index(), None);
+    }
 }
@@ -4,8 +4,8 @@
 //! Inspired by VSCode's Quick Open (Ctrl+P) which supports:
 //! - Empty prefix: file finder
 //! - `>`: command palette
-//! - `#`: symbol finder (future)
-//! - `@`: go to symbol in file (future)
+//! - `#`: buffer switcher
+//! - `@`: symbol finder (from the background workspace index)
 //! - `:`: go to line
 //!
 //! Providers are registered with a prefix and handle suggestion generation
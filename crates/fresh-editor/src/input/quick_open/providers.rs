@@ -9,6 +9,7 @@
 use super::{QuickOpenContext, QuickOpenProvider, QuickOpenResult};
 use crate::input::commands::Suggestion;
 use crate::input::fuzzy::fuzzy_match;
+use crate::services::workspace_index::WorkspaceIndex;
 use rust_i18n::t;
 
 // ============================================================================
@@ -328,6 +329,8 @@ pub struct FileProvider {
     file_cache: std::sync::Arc<std::sync::RwLock<Option<Vec<FileEntry>>>>,
     /// Frecency data for ranking
     frecency: std::sync::Arc<std::sync::RwLock<std::collections::HashMap<String, FrecencyData>>>,
+    /// Background workspace index, preferred over listing files on demand
+    index: Option<std::sync::Arc<WorkspaceIndex>>,
 }
 
 #[derive(Clone)]
@@ -347,6 +350,17 @@ impl FileProvider {
         Self {
             file_cache: std::sync::Arc::new(std::sync::RwLock::new(None)),
             frecency: std::sync::Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
+            index: None,
+        }
+    }
+
+    /// Create a provider that lists files from a workspace index
+    ///
+    /// Until the index is ready, files are listed on demand as usual.
+    pub fn with_index(index: std::sync::Arc<WorkspaceIndex>) -> Self {
+        Self {
+            index: Some(index),
+            ..Self::new()
         }
     }
 
@@ -397,6 +411,17 @@ impl FileProvider {
 
     /// Load files from the project directory
     fn load_files(&self, cwd: &str) -> Vec<FileEntry> {
+        // The index stays current by itself, so its list isn't cached here
+        if let Some(files) = self.index.as_ref().and_then(|index| index.files()) {
+            return files
+                .into_iter()
+                .map(|path| FileEntry {
+                    frecency_score: self.get_frecency_score(&path),
+                    relative_path: path,
+                })
+                .collect();
+        }
+
         // Check cache first
         if let Ok(cache) = self.file_cache.read() {
             if let Some(files) = cache.as_ref() {
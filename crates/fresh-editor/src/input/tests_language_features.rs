@@ -7,7 +7,10 @@ mod tests {
         Arc::new(StdFileSystem)
     }
     use crate::input::actions::get_auto_close_char;
-    use crate::input::multi_cursor::{add_cursor_at_next_match, AddCursorResult};
+    use crate::input::multi_cursor::{
+        add_cursor_at_next_match, select_all_matches, skip_to_next_match, AddCursorResult,
+        MatchOptions,
+    };
     use crate::model::buffer::Buffer;
     use crate::primitives::word_navigation::{find_word_start_left, find_word_start_right};
     use crate::state::EditorState;
@@ -96,7 +99,7 @@ mod tests {
 
     // Helper to apply the result of add_cursor_at_next_match to the state
     fn perform_add_cursor_at_next_match(state: &mut EditorState) -> AddCursorResult {
-        let result = add_cursor_at_next_match(state);
+        let result = add_cursor_at_next_match(state, MatchOptions::default());
         if let AddCursorResult::Success { cursor, .. } = &result {
             // Manually apply the change to the state since add_cursor_at_next_match is pure
             // We use a high ID to avoid conflicts in simple tests
@@ -238,4 +241,79 @@ mod tests {
             ),
         }
     }
+
+    #[test]
+    fn test_ctrl_d_respects_search_options() {
+        let mut state = create_state("foo Foo food FOO");
+        state.cursors.primary_mut().position = 3;
+        state.cursors.primary_mut().set_anchor(0);
+        let options = MatchOptions {
+            case_sensitive: false,
+            whole_word: true,
+        };
+
+        // "food" is skipped as a partial word, other cases match
+        let mut found = Vec::new();
+        while let AddCursorResult::Success { cursor, .. } =
+            add_cursor_at_next_match(&mut state, options)
+        {
+            found.push(cursor.selection_range().unwrap());
+            state.apply(&Event::AddCursor {
+                cursor_id: CursorId(state.cursors.count()),
+                position: cursor.position,
+                anchor: cursor.anchor,
+            });
+        }
+        assert_eq!(found, vec![4..7, 13..16]);
+
+        // Case-sensitive matching finds no other "foo"
+        let mut state = create_state("foo Foo");
+        state.cursors.primary_mut().position = 3;
+        state.cursors.primary_mut().set_anchor(0);
+        match add_cursor_at_next_match(&mut state, MatchOptions::default()) {
+            AddCursorResult::Failed { .. } => {}
+            _ => panic!("Expected no other match"),
+        }
+    }
+
+    #[test]
+    fn test_skip_to_next_match() {
+        let mut state = create_state("foo foo foo");
+        state.cursors.primary_mut().position = 3;
+        state.cursors.primary_mut().set_anchor(0);
+        perform_add_cursor_at_next_match(&mut state); // Primary now on 4..7
+
+        // Skipping moves the primary past the second "foo"
+        match skip_to_next_match(&mut state, MatchOptions::default()) {
+            AddCursorResult::Success {
+                cursor,
+                total_cursors,
+            } => {
+                assert_eq!(cursor.selection_range(), Some(8..11));
+                assert_eq!(total_cursors, 2);
+            }
+            _ => panic!("Failed to skip occurrence"),
+        }
+    }
+
+    #[test]
+    fn test_select_all_matches() {
+        let mut state = create_state("bar foo bar\nbar");
+        state.cursors.primary_mut().position = 1;
+
+        // No selection: the word at the cursor and its other occurrences
+        let matches = select_all_matches(&mut state, MatchOptions::default()).unwrap();
+        assert_eq!(matches.word, Some(0..3));
+        let ranges: Vec<_> = matches
+            .cursors
+            .iter()
+            .map(|c| c.selection_range().unwrap())
+            .collect();
+        assert_eq!(ranges, vec![8..11, 12..15]);
+
+        let mut state = create_state("foo");
+        state.cursors.primary_mut().position = 3;
+        state.cursors.primary_mut().set_anchor(0);
+        assert!(select_all_matches(&mut state, MatchOptions::default()).is_err());
+    }
 }
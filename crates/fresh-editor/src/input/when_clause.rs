@@ -0,0 +1,311 @@
+//! `when` expressions for keybindings
+//!
+//! A keybinding's `when` is either a plain context name (`"prompt"`,
+//! `"file_explorer"`, ...), which selects the context the binding lives in, or
+//! a VSCode-style expression evaluated on every key press:
+//!
+//! ```text
+//! editorFocus && !popupVisible && buffer_mode == 'vi-normal'
+//! ```
+//!
+//! Operators are `!`, `&&`, `||`, `==`, `!=` and parentheses. Identifiers are
+//! focus names (`editorFocus`, `promptFocus`, ... or the plain context names),
+//! `popupVisible`, `buffer_mode`, the menu context keys (`has_selection`,
+//! `lsp_available`, ...) and contexts set by plugins with `setContext`. Unknown
+//! identifiers are false.
+
+use super::keybindings::KeyContext;
+
+/// Parsed `when` expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum WhenExpr {
+    /// Boolean identifier
+    Flag(String),
+    /// `name == value`
+    Equals(String, String),
+    /// `name != value`
+    NotEquals(String, String),
+    Not(Box<WhenExpr>),
+    And(Box<WhenExpr>, Box<WhenExpr>),
+    Or(Box<WhenExpr>, Box<WhenExpr>),
+}
+
+/// Editor state a `when` expression is evaluated against
+pub struct WhenContext<'a> {
+    pub key_context: KeyContext,
+    pub popup_visible: bool,
+    /// Active editor mode (e.g. `vi-normal`) or the buffer's own mode
+    pub buffer_mode: Option<&'a str>,
+    /// Named boolean state: menu context keys and plugin-defined contexts
+    pub flags: &'a dyn Fn(&str) -> bool,
+}
+
+impl WhenContext<'_> {
+    fn flag(&self, name: &str) -> bool {
+        match name {
+            "true" => true,
+            "false" => false,
+            "editorFocus" => self.key_context == KeyContext::Normal,
+            "promptFocus" => self.key_context == KeyContext::Prompt,
+            "fileExplorerFocus" => self.key_context == KeyContext::FileExplorer,
+            "menuFocus" => self.key_context == KeyContext::Menu,
+            "terminalFocus" => self.key_context == KeyContext::Terminal,
+            "settingsFocus" => self.key_context == KeyContext::Settings,
+            "popupVisible" | "suggestWidgetVisible" => self.popup_visible,
+            "buffer_mode" => self.buffer_mode.is_some(),
+            _ => match KeyContext::from_when_clause(name) {
+                Some(KeyContext::Global) => true,
+                Some(context) => context == self.key_context,
+                None => (self.flags)(name),
+            },
+        }
+    }
+
+    fn value(&self, name: &str) -> String {
+        match name {
+            "buffer_mode" => self.buffer_mode.unwrap_or_default().to_string(),
+            "context" => self.key_context.to_when_clause().to_string(),
+            _ => self.flag(name).to_string(),
+        }
+    }
+}
+
+impl WhenExpr {
+    /// Parse an expression, returning a description of the first error
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.or()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+        }
+    }
+
+    pub fn eval(&self, context: &WhenContext) -> bool {
+        match self {
+            Self::Flag(name) => context.flag(name),
+            Self::Equals(name, value) => context.value(name) == *value,
+            Self::NotEquals(name, value) => context.value(name) != *value,
+            Self::Not(inner) => !inner.eval(context),
+            Self::And(a, b) => a.eval(context) && b.eval(context),
+            Self::Or(a, b) => a.eval(context) || b.eval(context),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Not,
+    And,
+    Or,
+    Eq,
+    Ne,
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' {
+                    Token::LParen
+                } else {
+                    Token::RParen
+                });
+            }
+            '!' | '=' => {
+                chars.next();
+                if chars.peek() == Some(&'=') {
+                    chars.next();
+                    tokens.push(if c == '!' { Token::Ne } else { Token::Eq });
+                } else if c == '!' {
+                    tokens.push(Token::Not);
+                } else {
+                    return Err("expected '=='".to_string());
+                }
+            }
+            '&' | '|' => {
+                chars.next();
+                if chars.next() != Some(c) {
+                    return Err(format!("expected '{c}{c}'"));
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+            }
+            '\'' | '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some(ch) if ch == c => break,
+                        Some(ch) => value.push(ch),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':') => {
+                let mut ident = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.' | ':') {
+                        ident.push(ch);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            _ => return Err(format!("unexpected character '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.tokens.get(self.pos) == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Result<WhenExpr, String> {
+        let mut expr = self.and()?;
+        while self.eat(&Token::Or) {
+            expr = WhenExpr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<WhenExpr, String> {
+        let mut expr = self.unary()?;
+        while self.eat(&Token::And) {
+            expr = WhenExpr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<WhenExpr, String> {
+        if self.eat(&Token::Not) {
+            return Ok(WhenExpr::Not(Box::new(self.unary()?)));
+        }
+        match self.next() {
+            Some(Token::LParen) => {
+                let expr = self.or()?;
+                if !self.eat(&Token::RParen) {
+                    return Err("expected ')'".to_string());
+                }
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) => {
+                if self.eat(&Token::Eq) {
+                    Ok(WhenExpr::Equals(name, self.value()?))
+                } else if self.eat(&Token::Ne) {
+                    Ok(WhenExpr::NotEquals(name, self.value()?))
+                } else {
+                    Ok(WhenExpr::Flag(name))
+                }
+            }
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+
+    /// Right-hand side of a comparison: a quoted string or a bare word
+    fn value(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Str(value)) | Some(Token::Ident(value)) => Ok(value),
+            _ => Err("expected a value after comparison".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expr: &str, key_context: KeyContext, buffer_mode: Option<&str>) -> bool {
+        let flags = |name: &str| name == "has_selection";
+        let context = WhenContext {
+            key_context,
+            popup_visible: false,
+            buffer_mode,
+            flags: &flags,
+        };
+        WhenExpr::parse(expr).unwrap().eval(&context)
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        assert_eq!(
+            WhenExpr::parse("a || b && !c").unwrap(),
+            WhenExpr::Or(
+                Box::new(WhenExpr::Flag("a".into())),
+                Box::new(WhenExpr::And(
+                    Box::new(WhenExpr::Flag("b".into())),
+                    Box::new(WhenExpr::Not(Box::new(WhenExpr::Flag("c".into()))))
+                ))
+            )
+        );
+        assert_eq!(
+            WhenExpr::parse("buffer_mode == 'vi-normal'").unwrap(),
+            WhenExpr::Equals("buffer_mode".into(), "vi-normal".into())
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(WhenExpr::parse("").is_err());
+        assert!(WhenExpr::parse("a &&").is_err());
+        assert!(WhenExpr::parse("a & b").is_err());
+        assert!(WhenExpr::parse("(a").is_err());
+        assert!(WhenExpr::parse("a == ").is_err());
+        assert!(WhenExpr::parse("mode = 'x'").is_err());
+        assert!(WhenExpr::parse("'unterminated").is_err());
+    }
+
+    #[test]
+    fn test_eval() {
+        let vi = Some("vi-normal");
+        assert!(eval(
+            "editorFocus && !popupVisible && buffer_mode == 'vi-normal'",
+            KeyContext::Normal,
+            vi
+        ));
+        assert!(!eval(
+            "buffer_mode == 'vi-normal'",
+            KeyContext::Normal,
+            None
+        ));
+        assert!(eval("buffer_mode != vi-insert", KeyContext::Normal, vi));
+        assert!(!eval("editorFocus", KeyContext::Prompt, None));
+        assert!(eval("prompt || file_explorer", KeyContext::Prompt, None));
+        assert!(eval("has_selection && (global)", KeyContext::Menu, None));
+        assert!(!eval("unknown_flag", KeyContext::Normal, None));
+        assert!(eval("context == 'popup'", KeyContext::Popup, None));
+    }
+}
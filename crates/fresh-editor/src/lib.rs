@@ -14,6 +14,10 @@ pub mod types;
 #[cfg(feature = "runtime")]
 pub mod config_io;
 #[cfg(feature = "runtime")]
+pub mod config_validation;
+#[cfg(feature = "runtime")]
+pub mod migration;
+#[cfg(feature = "runtime")]
 pub mod state;
 #[cfg(feature = "runtime")]
 pub mod workspace;
@@ -37,6 +41,10 @@ pub mod client;
 #[cfg(feature = "runtime")]
 pub mod server;
 
+// Headless editor for plugin and integration tests
+#[cfg(feature = "testing")]
+pub mod testing;
+
 // View module - available for runtime, WASM, and dev-bins (schema generation)
 // Most submodules are runtime-only, but theme types are always available
 #[cfg(any(feature = "runtime", feature = "wasm", feature = "dev-bins"))]
@@ -40,28 +40,43 @@ const EXIT_NEW_SESSION: i32 = 2;
     "Commands (use --cmd):\n",
     "  config show               Print effective configuration\n",
     "  config paths              Show directories used by Fresh\n",
+    "  doctor                    Check the terminal, directories and tools, with fixes\n",
     "  init                      Initialize a new plugin/theme/language\n",
+    "  pkg export FILE           Pack installed packages into an offline bundle\n",
+    "  pkg import FILE [SHA256]  Install packages from an offline bundle\n",
+    "  shell-init bash|zsh|fish  Print shell helpers for talking to a session\n",
     "\n",
     "Session commands:\n",
     "  session list              List active sessions\n",
     "  session attach [NAME]     Attach to a session (NAME or current dir)\n",
     "  session new NAME          Start a new named session\n",
     "  session kill [NAME]       Terminate a session\n",
+    "  session info [NAME] [--json]   Show clients, buffers and resource use of a session\n",
+    "  session exec NAME COMMAND      Run a command in a session and print the result\n",
     "  session open-file NAME FILES   Open files in session (starts if needed, exit 2 = new)\n",
+    "  session pipe [NAME]       Open standard input in a new buffer of a session\n",
+    "  open-url URL              Open a fresh://open?file=PATH&line=N&column=N link\n",
     "\n",
     "Examples:\n",
     "  fresh file.txt                               Open a file\n",
+    "  fresh -R /etc/fstab                          Open a file read-only\n",
+    "  fresh ssh://user@host:2222/etc/hosts         Edit a remote file over SSH on port 2222\n",
+    "  fresh notes.md web1:/etc/nginx/nginx.conf    Edit local and remote files together\n",
     "  fresh -a                                     Attach to session (current dir)\n",
     "  fresh -a mysession                           Attach to named session\n",
     "  fresh --cmd session new proj                 Start session named 'proj'\n",
     "  fresh --cmd session open-file . main.rs     Open file in current dir session\n",
     "  fresh --cmd session open-file proj a.rs     Open file in 'proj' session\n",
+    "  fresh --cmd session exec proj \"goto 42\"     Move the cursor in 'proj' session\n",
+    "  cargo test 2>&1 | fresh --cmd session pipe   Show output in current dir session\n",
+    "  eval \"$(fresh --cmd shell-init bash)\"        Add shell helpers (in ~/.bashrc)\n",
     "\n",
     "Documentation: https://getfresh.dev/docs"
 ))]
 struct Cli {
     /// Run a command instead of opening files
-    /// Commands: session (list|attach|new|kill|open-file), config (show|paths), init
+    /// Commands: session (list|attach|new|kill|info|exec|open-file|pipe), open-url,
+    /// config (show|paths), doctor, init, pkg (export|import), shell-init
     #[arg(long, num_args = 1.., value_name = "COMMAND")]
     cmd: Vec<String>,
 
@@ -77,6 +92,19 @@ struct Cli {
     #[arg(long)]
     stdin: bool,
 
+    /// Open the given files read-only
+    #[arg(short = 'R', long)]
+    read_only: bool,
+
+    /// Compare two directories, listing the files added, removed or changed
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"])]
+    diff: Vec<PathBuf>,
+
+    /// Resolve a three-way merge into MERGED (for use as a git mergetool);
+    /// exits with a non-zero status unless the result is saved
+    #[arg(long, num_args = 4, value_names = ["LOCAL", "BASE", "REMOTE", "MERGED"])]
+    merge: Vec<PathBuf>,
+
     /// Disable plugin loading
     #[arg(long)]
     no_plugins: bool,
@@ -93,6 +121,16 @@ struct Cli {
     #[arg(long, value_name = "LOG_FILE")]
     event_log: Option<PathBuf>,
 
+    /// Write per-frame render timings to the specified file as a Chrome
+    /// trace (open it in chrome://tracing or Perfetto)
+    #[arg(long, value_name = "TRACE_FILE")]
+    profile_render: Option<PathBuf>,
+
+    /// Start without a terminal, open FILES and draw the first frame, then
+    /// print how long each step took and exit
+    #[arg(long)]
+    bench_startup: bool,
+
     /// Don't restore previous workspace
     #[arg(long, alias = "no-session")]
     no_restore: bool,
@@ -105,6 +143,28 @@ struct Cli {
     #[arg(long, value_name = "LOCALE")]
     locale: Option<String>,
 
+    /// SSH port for remote files (overrides ~/.ssh/config)
+    #[arg(short = 'p', long, value_name = "PORT")]
+    ssh_port: Option<u16>,
+
+    /// Use a named profile (separate config, themes, plugins and workspaces)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Print machine-readable JSON (for `session info`)
+    #[arg(long)]
+    json: bool,
+
+    /// Seconds without input before a newly started session server shuts
+    /// down (0 = never; default from `session_server.idle_timeout_secs`)
+    #[arg(long, value_name = "SECS")]
+    idle_timeout: Option<u64>,
+
+    /// What a newly started session server does when idle: exit or suspend
+    /// (default from `session_server.idle_action`)
+    #[arg(long, value_name = "ACTION")]
+    idle_action: Option<config::SessionIdleAction>,
+
     // === Hidden internal flags ===
     /// Start as a daemon server (internal)
     #[arg(long, hide = true)]
@@ -138,15 +198,27 @@ struct Cli {
 struct Args {
     files: Vec<String>,
     stdin: bool,
+    read_only: bool,
+    /// Directories to compare (`--diff OLD NEW`)
+    diff: Option<(PathBuf, PathBuf)>,
+    /// Files of a three-way merge (`--merge LOCAL BASE REMOTE MERGED`)
+    merge: Option<MergeFiles>,
     no_plugins: bool,
     config: Option<PathBuf>,
     log_file: Option<PathBuf>,
     event_log: Option<PathBuf>,
+    profile_render: Option<PathBuf>,
+    bench_startup: bool,
     no_session: bool,
     no_upgrade_check: bool,
     dump_config: bool,
     show_paths: bool,
     locale: Option<String>,
+    ssh_port: Option<u16>,
+    profile: Option<String>,
+    json: bool,
+    idle_timeout: Option<u64>,
+    idle_action: Option<config::SessionIdleAction>,
     check_plugin: Option<PathBuf>,
     init: Option<Option<String>>,
     server: bool,
@@ -157,10 +229,82 @@ struct Args {
     kill: Option<Option<String>>,
     /// Open files in a session without attaching (session_name, files)
     open_files_in_session: Option<(Option<String>, Vec<String>)>,
+    /// Offline package bundle operation (`--cmd pkg ...`)
+    pkg: Option<PkgCommand>,
+    /// Describe a running session (`--cmd session info [NAME]`, None = current dir)
+    session_info: Option<Option<String>>,
+    /// Run a command in a session (`--cmd session exec NAME COMMAND`, None = current dir)
+    session_exec: Option<(Option<String>, String)>,
+    /// Open a `fresh://open?...` link in a session (`--cmd open-url URL`)
+    open_url: Option<String>,
+    /// Open stdin in a session (`--cmd session pipe [NAME]`, None = current dir)
+    session_pipe: Option<Option<String>>,
+    /// Print shell integration for a shell (`--cmd shell-init SHELL`)
+    shell_init: Option<String>,
+    /// Diagnose the install (`--cmd doctor`)
+    doctor: bool,
+}
+
+/// `fresh --cmd pkg ...` operations on installed packages
+#[derive(Debug)]
+enum PkgCommand {
+    /// Pack installed packages into a bundle at this path
+    Export(PathBuf),
+    /// Install packages from a bundle, optionally checking its SHA-256
+    Import {
+        archive: PathBuf,
+        sha256: Option<String>,
+    },
+}
+
+impl PkgCommand {
+    fn parse(cmd_args: &[&str]) -> Option<Self> {
+        match cmd_args {
+            ["pkg", "export", path] => Some(Self::Export(PathBuf::from(path))),
+            ["pkg", "import", path] => Some(Self::Import {
+                archive: PathBuf::from(path),
+                sha256: None,
+            }),
+            ["pkg", "import", path, sha256] => Some(Self::Import {
+                archive: PathBuf::from(path),
+                sha256: Some((*sha256).to_string()),
+            }),
+            _ => None,
+        }
+    }
 }
 
 impl From<Cli> for Args {
     fn from(cli: Cli) -> Self {
+        let cmd_args: Vec<&str> = cli.cmd.iter().map(|s| s.as_str()).collect();
+        let pkg = PkgCommand::parse(&cmd_args);
+        let session_info = match cmd_args.as_slice() {
+            ["session" | "s", "info", name, ..] if *name != "." => Some(Some((*name).to_string())),
+            ["session" | "s", "info", ..] => Some(None),
+            _ => None,
+        };
+        let session_exec = match cmd_args.as_slice() {
+            ["session" | "s", "exec", name, command @ ..] if !command.is_empty() => {
+                let session = (*name != ".").then(|| (*name).to_string());
+                Some((session, command.join(" ")))
+            }
+            _ => None,
+        };
+        let open_url = match cmd_args.as_slice() {
+            ["open-url", url] => Some((*url).to_string()),
+            _ => None,
+        };
+        let session_pipe = match cmd_args.as_slice() {
+            ["session" | "s", "pipe", name] if *name != "." => Some(Some((*name).to_string())),
+            ["session" | "s", "pipe"] | ["session" | "s", "pipe", _] => Some(None),
+            _ => None,
+        };
+        let shell_init = match cmd_args.as_slice() {
+            ["shell-init", shell] => Some((*shell).to_string()),
+            _ => None,
+        };
+        let doctor = matches!(cmd_args.as_slice(), ["doctor"]);
+
         // Parse --cmd arguments to determine command
         let (
             list_sessions,
@@ -174,7 +318,6 @@ impl From<Cli> for Args {
             open_files_in_session,
         ) = if !cli.cmd.is_empty() {
             // Parse command from --cmd arguments
-            let cmd_args: Vec<&str> = cli.cmd.iter().map(|s| s.as_str()).collect();
             match cmd_args.as_slice() {
                 // Session commands
                 ["session", "list", ..]
@@ -275,14 +418,30 @@ impl From<Cli> for Args {
                     cli.files,
                     None,
                 ),
-                ["session", "info", name, ..] | ["s", "info", name, ..] => {
-                    // Info not fully implemented, treat as list for now
-                    let _ = name;
-                    (true, None, false, None, false, false, None, cli.files, None)
-                }
-                ["session", "info"] | ["s", "info"] => {
-                    (true, None, false, None, false, false, None, cli.files, None)
-                }
+                // Session info (parsed into `session_info` above)
+                ["session", "info", ..] | ["s", "info", ..] => (
+                    false, None, false, None, false, false, None, cli.files, None,
+                ),
+                // Session exec (parsed into `session_exec` above)
+                ["session" | "s", "exec", ..] if session_exec.is_some() => (
+                    false, None, false, None, false, false, None, cli.files, None,
+                ),
+                // Open URL (parsed into `open_url` above)
+                ["open-url", ..] if open_url.is_some() => (
+                    false, None, false, None, false, false, None, cli.files, None,
+                ),
+                // Session pipe (parsed into `session_pipe` above)
+                ["session" | "s", "pipe", ..] if session_pipe.is_some() => (
+                    false, None, false, None, false, false, None, cli.files, None,
+                ),
+                // Shell integration (parsed into `shell_init` above)
+                ["shell-init", ..] if shell_init.is_some() => (
+                    false, None, false, None, false, false, None, cli.files, None,
+                ),
+                // Install diagnostics (parsed into `doctor` above)
+                ["doctor"] => (
+                    false, None, false, None, false, false, None, cli.files, None,
+                ),
                 // Config commands
                 ["config", "show"] | ["config", "dump"] => {
                     (false, None, false, None, true, false, None, cli.files, None)
@@ -313,10 +472,14 @@ impl From<Cli> for Args {
                     cli.files,
                     None,
                 ),
+                // Package bundle commands (parsed into `pkg` above)
+                ["pkg", ..] if pkg.is_some() => (
+                    false, None, false, None, false, false, None, cli.files, None,
+                ),
                 // Unknown command
                 _ => {
                     eprintln!("Unknown command: {}", cli.cmd.join(" "));
-                    eprintln!("Available commands: session (list|attach|new|kill|info|open-file), config (show|paths), init");
+                    eprintln!("Available commands: session (list|attach|new|kill|info|exec|open-file|pipe), open-url, config (show|paths), doctor, init, pkg (export|import), shell-init");
                     std::process::exit(1);
                 }
             }
@@ -351,15 +514,36 @@ impl From<Cli> for Args {
         Args {
             files,
             stdin: cli.stdin,
+            read_only: cli.read_only,
+            diff: match cli.diff.as_slice() {
+                [old, new] => Some((old.clone(), new.clone())),
+                _ => None,
+            },
+            merge: match cli.merge.as_slice() {
+                [local, base, remote, merged] => Some(MergeFiles {
+                    local: local.clone(),
+                    base: base.clone(),
+                    remote: remote.clone(),
+                    merged: merged.clone(),
+                }),
+                _ => None,
+            },
             no_plugins: cli.no_plugins,
             config: cli.config,
             log_file: cli.log_file,
             event_log: cli.event_log,
+            profile_render: cli.profile_render,
+            bench_startup: cli.bench_startup,
             no_session: cli.no_restore,
             no_upgrade_check: cli.no_upgrade_check,
             dump_config,
             show_paths,
             locale: cli.locale,
+            ssh_port: cli.ssh_port,
+            profile: cli.profile,
+            json: cli.json,
+            idle_timeout: cli.idle_timeout,
+            idle_action: cli.idle_action,
             check_plugin: cli.check_plugin,
             init,
             server: cli.server,
@@ -368,6 +552,13 @@ impl From<Cli> for Args {
             session_name,
             kill,
             open_files_in_session,
+            pkg,
+            session_info,
+            session_exec,
+            open_url,
+            session_pipe,
+            shell_init,
+            doctor,
         }
     }
 }
@@ -378,13 +569,18 @@ struct FileLocation {
     path: PathBuf,
     line: Option<usize>,
     column: Option<usize>,
+    /// Connection (`user@host`) of the mounted remote filesystem the file lives
+    /// on, or `None` for the primary filesystem
+    remote: Option<String>,
 }
 
-/// Parsed remote location from CLI argument in user@host:path format
+/// Parsed remote location from CLI argument in user@host:path or ssh:// format
 #[derive(Debug, Clone)]
 struct RemoteLocation {
+    /// May be empty for SSH config aliases (`alias:path`)
     user: String,
     host: String,
+    port: Option<u16>,
     path: String,
     line: Option<usize>,
     column: Option<usize>,
@@ -397,10 +593,22 @@ enum ParsedLocation {
     Remote(RemoteLocation),
 }
 
+/// The files git passes to a mergetool
+#[derive(Debug)]
+struct MergeFiles {
+    local: PathBuf,
+    base: PathBuf,
+    remote: PathBuf,
+    merged: PathBuf,
+}
+
 struct IterationOutcome {
     loop_result: AnyhowResult<()>,
     update_result: Option<release_checker::ReleaseCheckResult>,
     restart_dir: Option<PathBuf>,
+    restart_profile: Option<String>,
+    relaunch: Option<PathBuf>,
+    exit_code: i32,
 }
 
 struct SetupState {
@@ -421,6 +629,8 @@ struct SetupState {
     process_spawner: std::sync::Arc<dyn remote::ProcessSpawner>,
     /// Remote session resources - must be kept alive for remote editing
     _remote_session: Option<RemoteSession>,
+    /// Additional remote hosts opened next to the primary filesystem
+    mounts: Vec<FilesystemResult>,
     /// Key translator for input calibration
     key_translator: KeyTranslator,
     #[cfg(target_os = "linux")]
@@ -430,6 +640,8 @@ struct SetupState {
     /// Terminal mode state (raw mode, alternate screen, etc.)
     /// Drop impl restores terminal on cleanup
     terminal_modes: TerminalModes,
+    /// Light/dark detected at startup when `theme` is a light/dark pair
+    appearance: Option<fresh::config::Appearance>,
 }
 
 /// State for stdin streaming in background
@@ -680,6 +892,11 @@ fn handle_first_run_setup(
         editor.enable_event_streaming(log_path)?;
     }
 
+    if let Some(trace_path) = &args.profile_render {
+        tracing::info!("Render profiling enabled: {}", trace_path.display());
+        editor.start_render_trace(trace_path)?;
+    }
+
     if let Some(handles) = tracing_handles.take() {
         editor.set_warning_log(handles.warning.receiver, handles.warning.path);
         editor.set_status_log_path(handles.status.path);
@@ -710,12 +927,34 @@ fn handle_first_run_setup(
     // This ensures they go through the same code path as interactive file opens,
     // with consistent error handling (e.g., encoding confirmation prompts in the UI)
     for loc in file_locations {
+        if let Some(remote) = &loc.remote {
+            editor.queue_remote_file_open(loc.path.clone(), loc.line, loc.column, remote.clone());
+            continue;
+        }
         if loc.path.is_dir() {
             continue;
         }
         tracing::info!("[SYNTAX DEBUG] Queueing CLI file for open: {:?}", loc.path);
         editor.queue_file_open(loc.path.clone(), loc.line, loc.column);
     }
+    if args.read_only {
+        editor.queue_opens_read_only();
+    }
+    if let Some((old, new)) = &args.diff {
+        let absolute = |dir: &PathBuf| dir.canonicalize().unwrap_or_else(|_| dir.clone());
+        editor.queue_directory_diff(absolute(old), absolute(new));
+    }
+    if let Some(files) = &args.merge {
+        // MERGED may not exist yet, so the paths are made absolute rather
+        // than canonicalized
+        let absolute = |path: &PathBuf| std::path::absolute(path).unwrap_or_else(|_| path.clone());
+        editor.queue_merge(
+            absolute(&files.local),
+            absolute(&files.base),
+            absolute(&files.remote),
+            absolute(&files.merged),
+        );
+    }
 
     if show_file_explorer {
         editor.show_file_explorer();
@@ -762,6 +1001,7 @@ fn parse_file_location(input: &str) -> FileLocation {
             path: full_path,
             line: None,
             column: None,
+            remote: None,
         };
     }
 
@@ -802,6 +1042,7 @@ fn parse_file_location(input: &str) -> FileLocation {
                     path: PathBuf::from(path_str),
                     line: Some(line),
                     column: Some(col),
+                    remote: None,
                 };
             }
             // Fall through - not valid line:col format
@@ -819,6 +1060,7 @@ fn parse_file_location(input: &str) -> FileLocation {
                     path: PathBuf::from(path_str),
                     line: Some(line),
                     column: None,
+                    remote: None,
                 };
             }
             // Fall through - not valid line format
@@ -831,75 +1073,189 @@ fn parse_file_location(input: &str) -> FileLocation {
         path: full_path,
         line: None,
         column: None,
+        remote: None,
+    }
+}
+
+/// A `fresh://open?file=PATH&line=N&column=N&session=NAME` link
+#[derive(Debug)]
+struct OpenUrl {
+    location: FileLocation,
+    /// Session to open the file in; None picks a running one
+    session: Option<String>,
+}
+
+/// Parse a `fresh://open` link, as passed to `fresh --cmd open-url`
+///
+/// `file` is a path or a `file://` URL and is required; `line`, `column`
+/// (or `col`) and `session` are optional. Values are percent-decoded.
+fn parse_open_url(input: &str) -> Result<OpenUrl, String> {
+    let url = url::Url::parse(input).map_err(|e| format!("invalid URL: {}", e))?;
+    if url.scheme() != "fresh" {
+        return Err(format!("expected a fresh:// URL, got {}://", url.scheme()));
     }
+    // `fresh://open?...` has "open" as its host, `fresh:open?...` as its path
+    let action = url.host_str().unwrap_or_else(|| url.path());
+    if action != "open" {
+        return Err(format!("unknown action '{}', expected 'open'", action));
+    }
+
+    let mut file = None;
+    let mut line = None;
+    let mut column = None;
+    let mut session = None;
+    for (key, value) in url.query_pairs() {
+        let number = || {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("{} must be a number, got '{}'", key, value))
+        };
+        match key.as_ref() {
+            "file" => file = Some(value.to_string()),
+            "line" => line = Some(number()?),
+            "column" | "col" => column = Some(number()?),
+            "session" => session = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    let file = file.ok_or_else(|| "missing 'file' parameter".to_string())?;
+    let path = match url::Url::parse(&file) {
+        Ok(file_url) if file_url.scheme() == "file" => file_url
+            .to_file_path()
+            .map_err(|()| format!("not a local file URL: {}", file))?,
+        _ => PathBuf::from(file),
+    };
+
+    Ok(OpenUrl {
+        location: FileLocation {
+            path,
+            line,
+            column,
+            remote: None,
+        },
+        session,
+    })
 }
 
 /// Parse a location that may be local (file:line:col) or remote (user@host:path:line:col)
 ///
 /// Remote format: user@host:path or user@host:path:line or user@host:path:line:col
 /// The path can be absolute (/path) or relative (path)
+#[cfg(test)]
 fn parse_location(input: &str) -> ParsedLocation {
+    parse_location_with_aliases(input, |_| false)
+}
+
+/// Like [`parse_location`], but also recognizes `alias:path` (no `user@`) when
+/// `is_ssh_alias` reports `alias` as a host declared in the user's SSH config.
+///
+/// An explicit SSH port needs the URL form: `ssh://user@host:2222/path`.
+fn parse_location_with_aliases(input: &str, is_ssh_alias: impl Fn(&str) -> bool) -> ParsedLocation {
+    match parse_remote_location(input, is_ssh_alias) {
+        Some(remote) => ParsedLocation::Remote(remote),
+        // Not a remote path, parse as local
+        None => ParsedLocation::Local(parse_file_location(input)),
+    }
+}
+
+fn parse_remote_location(
+    input: &str,
+    is_ssh_alias: impl Fn(&str) -> bool,
+) -> Option<RemoteLocation> {
+    if let Some(url) = input.strip_prefix("ssh://") {
+        return parse_ssh_url(url);
+    }
+
     // Check for SSH-style syntax: user@host:path
     // Must have @ before the first : to be considered remote
-    // Also skip if it looks like a Windows path (single letter before :)
-    if let Some(at_pos) = input.find('@') {
-        // Everything before @ is the user
-        let user = &input[..at_pos];
-
-        // Everything after @ contains host:path[:line[:col]]
-        let after_at = &input[at_pos + 1..];
-
-        // Find the first : which separates host from path
-        if let Some(colon_pos) = after_at.find(':') {
-            let host = &after_at[..colon_pos];
-            let path_and_rest = &after_at[colon_pos + 1..];
-
-            // Validate: user and host must be non-empty and not contain spaces
-            if !user.is_empty()
-                && !host.is_empty()
-                && !user.contains(' ')
-                && !host.contains(' ')
-                && !path_and_rest.is_empty()
-            {
-                // Now parse path:line:col from path_and_rest
-                // We need to distinguish between path components and line:col suffixes
-                // Strategy: work backwards, try to parse numeric suffixes
-
-                let parts: Vec<&str> = path_and_rest.rsplitn(3, ':').collect();
-
-                let (path, line, column) = match parts.as_slice() {
-                    [maybe_col, maybe_line, rest] => {
-                        if let (Ok(line), Ok(col)) =
-                            (maybe_line.parse::<usize>(), maybe_col.parse::<usize>())
-                        {
-                            (rest.to_string(), Some(line), Some(col))
-                        } else {
-                            (path_and_rest.to_string(), None, None)
-                        }
-                    }
-                    [maybe_line, rest] => {
-                        if let Ok(line) = maybe_line.parse::<usize>() {
-                            (rest.to_string(), Some(line), None)
-                        } else {
-                            (path_and_rest.to_string(), None, None)
-                        }
-                    }
-                    _ => (path_and_rest.to_string(), None, None),
-                };
+    let (user, after_at) = match input.split_once('@') {
+        Some((user, rest)) if !user.contains([':', '/', '\\']) => (user, rest),
+        _ => ("", input),
+    };
+
+    // Find the first : which separates host from path
+    let (host, path_and_rest) = after_at.split_once(':')?;
+
+    // Validate: host and path must be non-empty and names must not contain spaces.
+    // Without a user, the host must be a known SSH config alias (this also keeps
+    // Windows drive letters local).
+    if host.is_empty() || host.contains(' ') || user.contains(' ') || path_and_rest.is_empty() {
+        return None;
+    }
+    if user.is_empty() && !is_ssh_alias(host) {
+        return None;
+    }
+
+    // Everything after the host is the path, so a numeric first segment is a
+    // path too; ports need the ssh:// form or --ssh-port
+    Some(remote_location(user, host, None, path_and_rest))
+}
+
+/// Parse the part of an `ssh://[user@]host[:port]/path` URL after the scheme.
+/// The path keeps its leading `/`.
+fn parse_ssh_url(url: &str) -> Option<RemoteLocation> {
+    let slash = url.find('/')?;
+    let (authority, path_and_rest) = url.split_at(slash);
+
+    let (user, host_port) = match authority.split_once('@') {
+        Some((user, rest)) => (user, rest),
+        None => ("", authority),
+    };
+    let (host, port) = match host_port.split_once(':') {
+        Some((host, port)) => (host, Some(port.parse::<u16>().ok()?)),
+        None => (host_port, None),
+    };
+
+    if host.is_empty() || host.contains(' ') || user.contains(' ') {
+        return None;
+    }
+
+    Some(remote_location(user, host, port, path_and_rest))
+}
 
-                return ParsedLocation::Remote(RemoteLocation {
-                    user: user.to_string(),
-                    host: host.to_string(),
-                    path,
-                    line,
-                    column,
-                });
+/// Build a [`RemoteLocation`], splitting an optional `:line:col` suffix off the path
+fn remote_location(
+    user: &str,
+    host: &str,
+    port: Option<u16>,
+    path_and_rest: &str,
+) -> RemoteLocation {
+    // Now parse path:line:col from path_and_rest
+    // We need to distinguish between path components and line:col suffixes
+    // Strategy: work backwards, try to parse numeric suffixes
+    let parts: Vec<&str> = path_and_rest.rsplitn(3, ':').collect();
+
+    let (path, line, column) = match parts.as_slice() {
+        [maybe_col, maybe_line, rest] => {
+            if let (Ok(line), Ok(col)) = (maybe_line.parse::<usize>(), maybe_col.parse::<usize>()) {
+                (rest.to_string(), Some(line), Some(col))
+            } else {
+                (path_and_rest.to_string(), None, None)
             }
         }
+        [maybe_line, rest] => {
+            if let Ok(line) = maybe_line.parse::<usize>() {
+                (rest.to_string(), Some(line), None)
+            } else {
+                (path_and_rest.to_string(), None, None)
+            }
+        }
+        _ => (path_and_rest.to_string(), None, None),
+    };
+
+    RemoteLocation {
+        user: user.to_string(),
+        host: host.to_string(),
+        port,
+        path,
+        line,
+        column,
     }
+}
 
-    // Not a remote path, parse as local
-    ParsedLocation::Local(parse_file_location(input))
+/// Whether two remote locations are on the same host (and so share a connection)
+fn same_remote_host(a: &RemoteLocation, b: &RemoteLocation) -> bool {
+    a.user == b.user && a.host == b.host && a.port == b.port
 }
 
 /// Holds resources needed for remote editing (kept alive for duration of session)
@@ -920,9 +1276,13 @@ struct FilesystemResult {
 }
 
 /// Create filesystem for local or remote editing
-fn create_filesystem(remote_info: &Option<RemoteLocation>) -> AnyhowResult<FilesystemResult> {
+fn create_filesystem(
+    remote_info: &Option<RemoteLocation>,
+    ssh_port: Option<u16>,
+    ssh_config: &remote::SshConfig,
+) -> AnyhowResult<FilesystemResult> {
     if let Some(remote) = remote_info {
-        connect_remote(remote)
+        connect_remote(remote, ssh_port, ssh_config)
     } else {
         Ok(FilesystemResult {
             filesystem: std::sync::Arc::new(StdFileSystem),
@@ -933,25 +1293,29 @@ fn create_filesystem(remote_info: &Option<RemoteLocation>) -> AnyhowResult<Files
 }
 
 /// Establish SSH connection to remote host and return RemoteFileSystem
-fn connect_remote(remote: &RemoteLocation) -> AnyhowResult<FilesystemResult> {
+fn connect_remote(
+    remote: &RemoteLocation,
+    ssh_port: Option<u16>,
+    ssh_config: &remote::SshConfig,
+) -> AnyhowResult<FilesystemResult> {
     // Create a Tokio runtime for the SSH connection
     let rt = tokio::runtime::Runtime::new()
         .context("Failed to create Tokio runtime for remote connection")?;
 
-    let connection_params = remote::ConnectionParams {
+    // Port from the location wins over --ssh-port, which wins over ~/.ssh/config
+    let mut connection_params = remote::ConnectionParams {
         user: remote.user.clone(),
         host: remote.host.clone(),
-        port: None, // TODO: support port in remote location parsing
-        identity_file: None,
+        port: remote.port.or(ssh_port),
+        ..Default::default()
     };
+    connection_params.apply_ssh_config(&ssh_config.lookup(&remote.host));
+    let display_name = connection_params.to_string();
 
     // Establish SSH connection (this is async, so we block on it)
     let connection = rt
         .block_on(remote::SshConnection::connect(connection_params))
-        .context(format!(
-            "Failed to connect to remote host {}@{}",
-            remote.user, remote.host
-        ))?;
+        .context(format!("Failed to connect to remote host {}", display_name))?;
 
     let connection_string = connection.connection_string();
     let channel = connection.channel();
@@ -992,9 +1356,51 @@ fn initialize_app(args: &Args) -> AnyhowResult<SetupState> {
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic| {
         terminal_modes::emergency_cleanup();
+        fresh::services::telemetry::record_crash(panic.location());
         original_hook(panic);
     }));
 
+    // The directory diff is done by a plugin, so check what it needs while
+    // errors can still be printed
+    if let Some((old, new)) = &args.diff {
+        if args.no_plugins {
+            eprintln!("Error: --diff needs plugins, but --no-plugins was given");
+            anyhow::bail!(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--diff with --no-plugins",
+            ));
+        }
+        for dir in [old, new] {
+            if !dir.is_dir() {
+                eprintln!("Error: --diff: {} is not a directory", dir.display());
+                anyhow::bail!(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--diff needs two directories",
+                ));
+            }
+        }
+    }
+    // Likewise for the merge UI. BASE may be missing (git passes an empty
+    // file when both sides added the file), and MERGED is written on save.
+    if let Some(files) = &args.merge {
+        if args.no_plugins {
+            eprintln!("Error: --merge needs plugins, but --no-plugins was given");
+            anyhow::bail!(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--merge with --no-plugins",
+            ));
+        }
+        for file in [&files.local, &files.remote] {
+            if !file.is_file() {
+                eprintln!("Error: --merge: {} is not a file", file.display());
+                anyhow::bail!(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--merge needs LOCAL and REMOTE files",
+                ));
+            }
+        }
+    }
+
     // Check if we should read from stdin
     // This can be triggered by --stdin flag or by using "-" as a file argument
     let stdin_requested = args.stdin || args.files.iter().any(|f| f == "-");
@@ -1031,58 +1437,67 @@ fn initialize_app(args: &Args) -> AnyhowResult<SetupState> {
 
     // Determine working directory early for config loading
     // Filter out "-" from files list since it's handled via stdin_stream
-    // Parse locations which may be local or remote (user@host:path)
+    // Parse locations which may be local or remote (user@host:path).
+    // Host aliases from ~/.ssh/config are also accepted without a user.
+    let ssh_config = remote::SshConfig::load();
     let parsed_locations: Vec<ParsedLocation> = args
         .files
         .iter()
         .filter(|f| *f != "-")
-        .map(|f| parse_location(f))
-        .collect();
-
-    // Check for remote locations - for now, collect them separately
-    let remote_locations: Vec<&RemoteLocation> = parsed_locations
-        .iter()
-        .filter_map(|loc| match loc {
-            ParsedLocation::Remote(r) => Some(r),
-            ParsedLocation::Local(_) => None,
-        })
+        .map(|f| parse_location_with_aliases(f, |host| ssh_config.has_alias(host)))
         .collect();
 
-    // If there are remote locations, validate they're all on the same host
-    let remote_info: Option<RemoteLocation> = if !remote_locations.is_empty() {
-        let first = remote_locations[0];
-        for r in &remote_locations[1..] {
-            if r.user != first.user || r.host != first.host {
-                anyhow::bail!(
-                    "Cannot open files from multiple remote hosts. \
-                     First: {}@{}, found: {}@{}",
-                    first.user,
-                    first.host,
-                    r.user,
-                    r.host
-                );
+    // Group remote locations by host: each host gets its own connection.
+    // Local and remote files can be mixed; every buffer keeps the filesystem
+    // it was opened from.
+    let mut remote_hosts: Vec<RemoteLocation> = Vec::new();
+    for loc in &parsed_locations {
+        if let ParsedLocation::Remote(r) = loc {
+            if !remote_hosts.iter().any(|h| same_remote_host(h, r)) {
+                remote_hosts.push(r.clone());
             }
         }
-        // Check that there are no local files mixed with remote
-        let has_local = parsed_locations
-            .iter()
-            .any(|loc| matches!(loc, ParsedLocation::Local(_)));
-        if has_local {
-            anyhow::bail!(
-                "Cannot mix local and remote files. Use either local paths or remote paths (user@host:path)."
-            );
-        }
-        Some(first.clone())
-    } else {
+    }
+    let has_local = parsed_locations
+        .iter()
+        .any(|loc| matches!(loc, ParsedLocation::Local(_)));
+
+    // Without local files, the first host is the primary filesystem (working
+    // directory, file explorer, plugin processes); other hosts are mounted
+    let remote_info: Option<RemoteLocation> = if has_local {
         None
+    } else {
+        remote_hosts.first().cloned()
     };
 
+    // Create filesystem early - needed for remote directory detection
+    // For remote editing, this establishes the SSH connection
+    let FilesystemResult {
+        filesystem,
+        process_spawner,
+        remote_session,
+    } = create_filesystem(&remote_info, args.ssh_port, &ssh_config)?;
+
+    let mut mounts = Vec::new();
+    let mut mount_labels: Vec<(&RemoteLocation, String)> = Vec::new();
+    for host in remote_hosts.iter().skip(usize::from(remote_info.is_some())) {
+        let mount = connect_remote(host, args.ssh_port, &ssh_config)?;
+        if let Some(label) = mount.filesystem.remote_connection_info() {
+            mount_labels.push((host, label.to_string()));
+        }
+        mounts.push(mount);
+    }
+
     // Convert to FileLocation for downstream code
     let file_locations: Vec<FileLocation> = parsed_locations
         .into_iter()
         .map(|loc| match loc {
             ParsedLocation::Local(fl) => fl,
             ParsedLocation::Remote(rl) => FileLocation {
+                remote: mount_labels
+                    .iter()
+                    .find(|(host, _)| same_remote_host(host, &rl))
+                    .map(|(_, label)| label.clone()),
                 path: PathBuf::from(&rl.path),
                 line: rl.line,
                 column: rl.column,
@@ -1090,14 +1505,6 @@ fn initialize_app(args: &Args) -> AnyhowResult<SetupState> {
         })
         .collect();
 
-    // Create filesystem early - needed for remote directory detection
-    // For remote editing, this establishes the SSH connection
-    let FilesystemResult {
-        filesystem,
-        process_spawner,
-        remote_session,
-    } = create_filesystem(&remote_info)?;
-
     let mut working_dir = None;
     let mut show_file_explorer = false;
 
@@ -1125,7 +1532,7 @@ fn initialize_app(args: &Args) -> AnyhowResult<SetupState> {
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_default())
     };
 
-    let dir_context = fresh::config_io::DirectoryContext::from_system()?;
+    let dir_context = profile_dir_context(args)?;
 
     let mut config = if let Some(config_path) = &args.config {
         // Explicit config file overrides layered system
@@ -1164,6 +1571,14 @@ fn initialize_app(args: &Args) -> AnyhowResult<SetupState> {
     };
     let terminal_modes = TerminalModes::enable(Some(&keyboard_config))?;
 
+    // Pick the side of a light/dark theme pair. Queries the terminal, so it
+    // has to run before the input reader starts consuming its replies.
+    let appearance = if config.theme.follows_appearance() {
+        fresh::services::appearance::detect_appearance()
+    } else {
+        None
+    };
+
     #[cfg(target_os = "linux")]
     let gpm_client = match GpmClient::connect() {
         Ok(client) => client,
@@ -1191,7 +1606,7 @@ fn initialize_app(args: &Args) -> AnyhowResult<SetupState> {
     let size = terminal.size()?;
     tracing::info!("Terminal size: {}x{}", size.width, size.height);
 
-    let dir_context = DirectoryContext::from_system()?;
+    let dir_context = profile_dir_context(args)?;
     let current_working_dir = working_dir;
 
     // Load key translator for input calibration
@@ -1216,9 +1631,11 @@ fn initialize_app(args: &Args) -> AnyhowResult<SetupState> {
         key_translator,
         gpm_client,
         terminal_modes,
+        appearance,
         filesystem,
         process_spawner,
         _remote_session: remote_session,
+        mounts,
     })
 }
 
@@ -1247,14 +1664,96 @@ fn run_editor_iteration(
 
     let update_result = editor.get_update_result().cloned();
     let restart_dir = editor.take_restart_dir();
+    let restart_profile = editor.take_restart_profile();
+    let relaunch = editor.take_relaunch_executable();
+    let exit_code = editor.exit_code();
 
     Ok(IterationOutcome {
         loop_result,
         update_result,
         restart_dir,
+        restart_profile,
+        relaunch,
+        exit_code,
     })
 }
 
+/// Directory context for the profile selected with `--profile`
+fn profile_dir_context(args: &Args) -> AnyhowResult<DirectoryContext> {
+    let dir_context = DirectoryContext::from_system()?;
+    if let Some(profile) = &args.profile {
+        if !DirectoryContext::is_valid_profile_name(profile) {
+            eprintln!(
+                "Error: Invalid profile name '{}' (use letters, digits, '-' or '_')",
+                profile
+            );
+            anyhow::bail!("Invalid profile name: {}", profile);
+        }
+    }
+    fresh::input::input_history::set_data_dir_profile(args.profile.as_deref());
+    Ok(dir_context.with_profile(args.profile.as_deref()))
+}
+
+/// Time the steps of starting up (`--bench-startup`) without a terminal
+///
+/// Runs the same config loading, editor construction (themes, grammars,
+/// plugins) and file opening as a normal start, draws one frame into an
+/// off-screen buffer, and prints each step's duration.
+fn bench_startup_command(args: &Args) -> AnyhowResult<()> {
+    let start = std::time::Instant::now();
+    let mut steps = Vec::new();
+    let mut last = start;
+    let mut lap = |name: &'static str| {
+        let now = std::time::Instant::now();
+        steps.push((name, now - last));
+        last = now;
+    };
+
+    let dir_context = profile_dir_context(args)?;
+    let working_dir = std::env::current_dir()?;
+    let mut config = match &args.config {
+        Some(path) => config::Config::load_from_file(path)
+            .with_context(|| format!("Failed to load config from {}", path.display()))?,
+        None => config::Config::load_with_layers(&dir_context, &working_dir),
+    };
+    config.check_for_updates = false;
+    fresh::i18n::init_with_config(args.locale.as_deref().or(config.locale.as_option()));
+    lap("config");
+
+    let (width, height) = (120, 40);
+    let mut editor = Editor::with_working_dir(
+        config,
+        width,
+        height,
+        Some(working_dir),
+        dir_context,
+        !args.no_plugins,
+        fresh::view::color_support::ColorCapability::TrueColor,
+        std::sync::Arc::new(StdFileSystem),
+    )
+    .context("Failed to create editor instance")?;
+    lap("editor (themes, grammars, plugins)");
+
+    for file in &args.files {
+        let location = parse_file_location(file);
+        editor
+            .open_file(&location.path)
+            .with_context(|| format!("Failed to open {}", location.path.display()))?;
+    }
+    editor.process_async_messages();
+    lap("open files");
+
+    let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(width, height))?;
+    terminal.draw(|frame| editor.render(frame))?;
+    lap("first frame");
+
+    for (name, duration) in &steps {
+        println!("{:<36} {:>10.2?}", name, duration);
+    }
+    println!("{:<36} {:>10.2?}", "total", start.elapsed());
+    Ok(())
+}
+
 /// Check a plugin by bundling it and printing the output
 #[cfg(feature = "plugins")]
 fn check_plugin_bundle(plugin_path: &std::path::Path) -> AnyhowResult<()> {
@@ -1883,6 +2382,43 @@ MIT
 // === Session persistence commands ===
 
 /// List active sessions
+/// Export or import an offline package bundle
+fn package_bundle_command(command: &PkgCommand, args: &Args) -> AnyhowResult<()> {
+    use fresh::services::package_bundle;
+
+    let dir_context = profile_dir_context(args)?;
+    match command {
+        PkgCommand::Export(output) => {
+            let summary = package_bundle::export_bundle(&dir_context, output)?;
+            println!(
+                "Exported {} packages ({} files) to {}",
+                summary.packages,
+                summary.files,
+                output.display()
+            );
+            println!("SHA-256: {}", summary.sha256);
+            println!(
+                "Install with: fresh --cmd pkg import {} {}",
+                output.display(),
+                summary.sha256
+            );
+        }
+        PkgCommand::Import { archive, sha256 } => {
+            let summary = package_bundle::import_bundle(&dir_context, archive, sha256.as_deref())?;
+            for package in &summary.packages {
+                println!("  {}", package);
+            }
+            println!(
+                "Imported {} packages ({} files) into {}",
+                summary.packages.len(),
+                summary.files,
+                dir_context.config_dir.display()
+            );
+        }
+    }
+    Ok(())
+}
+
 fn list_sessions_command() -> AnyhowResult<()> {
     let socket_dir = SocketPaths::socket_directory()?;
 
@@ -2020,6 +2556,209 @@ fn kill_session_command(session: Option<&str>, args: &Args) -> AnyhowResult<()>
     Ok(())
 }
 
+/// Print information about a running session
+fn session_info_command(session: Option<&str>, json: bool) -> AnyhowResult<()> {
+    use fresh::server::ipc::ClientConnection;
+    use fresh::server::protocol::{ClientControl, ClientHello, ServerControl, TermSize};
+
+    let socket_paths = match session {
+        Some(name) => SocketPaths::for_session_name(name)?,
+        None => SocketPaths::for_working_dir(&std::env::current_dir()?)?,
+    };
+
+    socket_paths.cleanup_if_stale();
+    if !socket_paths.is_server_alive() {
+        eprintln!("No session found.");
+        std::process::exit(1);
+    }
+
+    let conn = ClientConnection::connect(&socket_paths)?;
+    let hello = ClientHello::new(TermSize::new(80, 24));
+    conn.write_control(&serde_json::to_string(&ClientControl::Hello(hello))?)?;
+    conn.write_control(&serde_json::to_string(&ClientControl::GetInfo)?)?;
+
+    // Skip the handshake response and anything else until the info arrives
+    let info = loop {
+        let line = conn
+            .read_control()?
+            .ok_or_else(|| anyhow::anyhow!("Server closed connection"))?;
+        match serde_json::from_str::<ServerControl>(&line) {
+            Ok(ServerControl::Info(info)) => break info,
+            Ok(ServerControl::VersionMismatch(mismatch)) => {
+                anyhow::bail!("Version mismatch: server is v{}", mismatch.server_version);
+            }
+            Ok(ServerControl::Error { message }) => {
+                anyhow::bail!("Server error: {}", message);
+            }
+            _ => continue,
+        }
+    };
+    let _ = conn.write_control(&serde_json::to_string(&ClientControl::Detach)?);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+
+    println!("Session:     {}", info.session_id);
+    println!("Working dir: {}", info.working_dir);
+    println!("Server PID:  {}", info.pid);
+    println!("Version:     {}", info.server_version);
+    println!(
+        "Uptime:      {}h {:02}m {:02}s",
+        info.uptime_secs / 3600,
+        info.uptime_secs / 60 % 60,
+        info.uptime_secs % 60
+    );
+    match info.memory_bytes {
+        Some(bytes) => println!("Memory:      {:.1} MB", bytes as f64 / (1024.0 * 1024.0)),
+        None => println!("Memory:      unknown"),
+    }
+
+    println!();
+    println!("Clients ({}):", info.clients.len());
+    for client in &info.clients {
+        println!(
+            "  #{}  {}x{}  {}",
+            client.id,
+            client.term_size.cols,
+            client.term_size.rows,
+            client.term.as_deref().unwrap_or("-")
+        );
+        let traffic = &client.traffic;
+        println!(
+            "      {} frames ({} full), {:.1} KB rendered, {:.1} KB sent{}",
+            traffic.frames,
+            traffic.full_frames,
+            traffic.raw_bytes as f64 / 1024.0,
+            traffic.sent_bytes as f64 / 1024.0,
+            match client.compression {
+                Some(compression) => format!(" ({:?})", compression).to_lowercase(),
+                None => String::new(),
+            }
+        );
+    }
+
+    println!();
+    println!("Buffers ({}):", info.buffers.len());
+    for buffer in &info.buffers {
+        println!(
+            "  {} {}",
+            if buffer.modified { "*" } else { " " },
+            buffer.path.as_deref().unwrap_or(&buffer.name)
+        );
+    }
+
+    Ok(())
+}
+
+/// Run a command in a running session and print its result
+fn session_exec_command(session: Option<&str>, command: &str) -> AnyhowResult<()> {
+    use fresh::server::ipc::ClientConnection;
+    use fresh::server::protocol::{ClientControl, ClientHello, ServerControl, TermSize};
+
+    let socket_paths = match session {
+        Some(name) => SocketPaths::for_session_name(name)?,
+        None => SocketPaths::for_working_dir(&std::env::current_dir()?)?,
+    };
+
+    socket_paths.cleanup_if_stale();
+    if !socket_paths.is_server_alive() {
+        eprintln!("No session found.");
+        std::process::exit(1);
+    }
+
+    let conn = ClientConnection::connect(&socket_paths)?;
+    let hello = ClientHello::new(TermSize::new(80, 24));
+    conn.write_control(&serde_json::to_string(&ClientControl::Hello(hello))?)?;
+    conn.write_control(&serde_json::to_string(&ClientControl::RunCommand {
+        command: command.to_string(),
+    })?)?;
+
+    // Skip the handshake response and anything else until the result arrives
+    let (ok, output) = loop {
+        let line = conn
+            .read_control()?
+            .ok_or_else(|| anyhow::anyhow!("Server closed connection"))?;
+        match serde_json::from_str::<ServerControl>(&line) {
+            Ok(ServerControl::CommandResult { ok, output }) => break (ok, output),
+            Ok(ServerControl::VersionMismatch(mismatch)) => {
+                anyhow::bail!("Version mismatch: server is v{}", mismatch.server_version);
+            }
+            Ok(ServerControl::Error { message }) => {
+                anyhow::bail!("Server error: {}", message);
+            }
+            _ => continue,
+        }
+    };
+    let _ = conn.write_control(&serde_json::to_string(&ClientControl::Detach)?);
+
+    if !ok {
+        eprintln!("{}", output);
+        std::process::exit(1);
+    }
+    if !output.is_empty() {
+        println!("{}", output);
+    }
+    Ok(())
+}
+
+/// Open standard input in a new buffer of a running session
+fn session_pipe_command(session: Option<&str>) -> AnyhowResult<()> {
+    // Named like stdin read at startup, so leftovers are cleaned up the same way
+    let temp_path = std::env::temp_dir().join(format!("fresh-stdin-{}.tmp", std::process::id()));
+    let mut file = std::fs::File::create(&temp_path)?;
+    io::copy(&mut io::stdin().lock(), &mut file)?;
+    drop(file);
+    session_exec_command(session, &format!("pipe {}", temp_path.display()))
+}
+
+/// Print the shell integration script for `shell`
+fn shell_init_command(shell: &str) -> AnyhowResult<()> {
+    match fresh::server::shell_init::script(shell) {
+        Some(script) => {
+            print!("{}", script);
+            Ok(())
+        }
+        None => {
+            eprintln!(
+                "Unknown shell '{}' (expected one of: {})",
+                shell,
+                fresh::server::shell_init::SHELLS.join(", ")
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Check the terminal, directories, language tools and sessions, printing
+/// a fix for each problem. Exits with status 1 if anything is broken.
+fn doctor_command(args: &Args) -> AnyhowResult<()> {
+    use fresh::services::doctor;
+
+    let dir_context = profile_dir_context(args)?;
+    let config = match &args.config {
+        Some(config_path) => config::Config::load_from_file(config_path).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to load config from {}: {}",
+                config_path.display(),
+                e
+            )
+        })?,
+        None => {
+            let working_dir = std::env::current_dir().unwrap_or_default();
+            config::Config::load_with_layers(&dir_context, &working_dir)
+        }
+    };
+
+    let report = doctor::run(&config, &dir_context);
+    print!("{}", report.render());
+    if report.count(doctor::Status::Error) > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 /// Run as a daemon server
 fn run_server_command(args: &Args) -> AnyhowResult<()> {
     use fresh::server::{EditorServer, EditorServerConfig};
@@ -2041,7 +2780,7 @@ fn run_server_command(args: &Args) -> AnyhowResult<()> {
     let working_dir = std::env::current_dir()?;
     eprintln!("[server] Working directory: {:?}", working_dir);
 
-    let dir_context = fresh::config_io::DirectoryContext::from_system()?;
+    let dir_context = profile_dir_context(args)?;
 
     // Load editor config
     eprintln!("[server] Loading editor config...");
@@ -2052,10 +2791,19 @@ fn run_server_command(args: &Args) -> AnyhowResult<()> {
     };
     eprintln!("[server] Editor config loaded");
 
+    // Per-session flags win over the config
+    let idle_timeout_secs = args
+        .idle_timeout
+        .unwrap_or(editor_config.session_server.idle_timeout_secs);
+    let idle_action = args
+        .idle_action
+        .unwrap_or(editor_config.session_server.idle_action);
+
     let config = EditorServerConfig {
         working_dir: working_dir.clone(),
         session_name: args.session_name.clone(),
-        idle_timeout: Some(std::time::Duration::from_secs(3600)), // 1 hour default
+        idle_timeout: (idle_timeout_secs > 0).then_some(Duration::from_secs(idle_timeout_secs)),
+        idle_action,
         editor_config,
         dir_context,
         plugins_enabled: !args.no_plugins,
@@ -2084,15 +2832,93 @@ fn run_server_command(args: &Args) -> AnyhowResult<()> {
     Ok(())
 }
 
+/// Flags forwarded to a session server started by this client
+fn server_spawn_args(args: &Args) -> Vec<String> {
+    let mut server_args = Vec::new();
+    if let Some(profile) = &args.profile {
+        server_args.extend(["--profile".to_string(), profile.clone()]);
+    }
+    if let Some(config) = &args.config {
+        server_args.extend(["--config".to_string(), config.display().to_string()]);
+    }
+    if let Some(secs) = args.idle_timeout {
+        server_args.extend(["--idle-timeout".to_string(), secs.to_string()]);
+    }
+    if let Some(action) = args.idle_action {
+        let action = match action {
+            config::SessionIdleAction::Exit => "exit",
+            config::SessionIdleAction::Suspend => "suspend",
+        };
+        server_args.extend(["--idle-action".to_string(), action.to_string()]);
+    }
+    server_args
+}
+
+/// Open a `fresh://open` link in a running session
+///
+/// Without a `session` parameter the link goes to the running session whose
+/// working directory holds the file, then to the only running session, and
+/// otherwise to the current directory's session like `session open-file .`.
+fn run_open_url_command(url: &str, server_args: &[String]) -> AnyhowResult<()> {
+    let open_url = match parse_open_url(url) {
+        Ok(open_url) => open_url,
+        Err(e) => {
+            eprintln!("Cannot open {}: {}", url, e);
+            std::process::exit(1);
+        }
+    };
+    let session = open_url
+        .session
+        .or_else(|| session_for_file(&open_url.location.path));
+    run_open_files_command(session.as_deref(), &[open_url.location], server_args)
+}
+
+/// Running session to open `file` in when none is named
+fn session_for_file(file: &std::path::Path) -> Option<String> {
+    let socket_dir = SocketPaths::socket_directory().ok()?;
+    let mut running = Vec::new();
+    for entry in std::fs::read_dir(socket_dir).ok()?.flatten() {
+        let filename = entry.file_name();
+        let Some(name) = filename.to_str().and_then(|f| f.strip_suffix(".ctrl.sock")) else {
+            continue;
+        };
+        if SocketPaths::for_session_name(name).is_ok_and(|paths| paths.is_server_alive()) {
+            running.push(name.to_string());
+        }
+    }
+
+    // Sessions started in a directory are named after it
+    let owner = running
+        .iter()
+        .filter_map(|name| {
+            // Named sessions decode to "/<name>", which is no directory
+            let dir = workspace::decode_filename_to_path(name)
+                .filter(|dir| dir.components().count() > 2)?;
+            file.starts_with(&dir)
+                .then(|| (dir.components().count(), name))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, name)| name.clone());
+    match owner {
+        Some(name) => Some(name),
+        None if running.len() == 1 => running.pop(),
+        None => None,
+    }
+}
+
 /// Open files in a running session without attaching
-fn run_open_files_command(session_name: Option<&str>, files: &[String]) -> AnyhowResult<()> {
+fn run_open_files_command(
+    session_name: Option<&str>,
+    locations: &[FileLocation],
+    server_args: &[String],
+) -> AnyhowResult<()> {
     use fresh::server::daemon::is_process_running;
     use fresh::server::protocol::{
         ClientControl, ClientHello, FileRequest, ServerControl, TermSize, PROTOCOL_VERSION,
     };
     use fresh::server::spawn_server_detached;
 
-    if files.is_empty() {
+    if locations.is_empty() {
         eprintln!("No files specified.");
         return Ok(());
     }
@@ -2103,8 +2929,7 @@ fn run_open_files_command(session_name: Option<&str>, files: &[String]) -> Anyho
     let mut file_requests: Vec<FileRequest> = Vec::new();
     let mut skipped_dirs = 0;
 
-    for f in files {
-        let loc = parse_file_location(f);
+    for loc in locations {
         // Resolve relative paths to absolute paths based on client's working directory
         let abs_path = if loc.path.is_relative() {
             working_dir.join(&loc.path)
@@ -2147,7 +2972,7 @@ fn run_open_files_command(session_name: Option<&str>, files: &[String]) -> Anyho
 
     // Start server if not running (like nvr does by default)
     let server_was_started = if !socket_paths.is_server_alive() {
-        let _pid = spawn_server_detached(session_name)?;
+        let _pid = spawn_server_detached(session_name, server_args)?;
 
         // Wait for server to be ready
         loop {
@@ -2223,7 +3048,7 @@ fn run_open_files_command(session_name: Option<&str>, files: &[String]) -> Anyho
 fn run_attach_command(args: &Args) -> AnyhowResult<()> {
     use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
     use fresh::server::protocol::{
-        ClientControl, ClientHello, ServerControl, TermSize, PROTOCOL_VERSION,
+        ClientControl, ClientHello, Compression, ServerControl, TermSize, PROTOCOL_VERSION,
     };
     use fresh::server::spawn_server_detached;
 
@@ -2258,7 +3083,7 @@ fn run_attach_command(args: &Args) -> AnyhowResult<()> {
         eprintln!("Starting server...");
 
         // Spawn server in background
-        let _pid = spawn_server_detached(args.session_name.as_deref())?;
+        let _pid = spawn_server_detached(args.session_name.as_deref(), &server_spawn_args(args))?;
         true
     } else {
         false
@@ -2295,8 +3120,17 @@ fn run_attach_command(args: &Args) -> AnyhowResult<()> {
 
     let term_size = TermSize::new(cols, rows);
 
+    let editor_config = if let Some(config_path) = &args.config {
+        config::Config::load_from_file(config_path)?
+    } else {
+        config::Config::load_with_layers(&profile_dir_context(args)?, &working_dir)
+    };
+
     // Perform handshake
-    let hello = ClientHello::new(term_size);
+    let mut hello = ClientHello::new(term_size);
+    if editor_config.session_server.compress_output {
+        hello.compression = vec![Compression::Zstd];
+    }
     let hello_json = serde_json::to_string(&ClientControl::Hello(hello))?;
     conn.write_control(&hello_json)?;
 
@@ -2307,7 +3141,7 @@ fn run_attach_command(args: &Args) -> AnyhowResult<()> {
 
     let server_msg: ServerControl = serde_json::from_str(&response)?;
 
-    match server_msg {
+    let compression = match server_msg {
         ServerControl::Hello(server_hello) => {
             if server_hello.protocol_version != PROTOCOL_VERSION {
                 eprintln!(
@@ -2318,10 +3152,12 @@ fn run_attach_command(args: &Args) -> AnyhowResult<()> {
                 return Ok(());
             }
             tracing::info!(
-                "Connected to session '{}' (server {})",
+                "Connected to session '{}' (server {}, compression {:?})",
                 server_hello.session_id,
-                server_hello.server_version
+                server_hello.server_version,
+                server_hello.compression
             );
+            server_hello.compression
         }
         ServerControl::VersionMismatch(mismatch) => {
             eprintln!("Version mismatch: server is v{}", mismatch.server_version);
@@ -2334,7 +3170,7 @@ fn run_attach_command(args: &Args) -> AnyhowResult<()> {
         _ => {
             return Err(anyhow::anyhow!("Unexpected server response"));
         }
-    }
+    };
 
     // Continue to relay loop
 
@@ -2343,7 +3179,11 @@ fn run_attach_command(args: &Args) -> AnyhowResult<()> {
     enable_raw_mode()?;
 
     // Run the client relay loop (handshake already done)
-    let result = client::run_client_relay(conn);
+    let result = client::run_client_relay(
+        conn,
+        compression,
+        editor_config.session_server.predictive_echo,
+    );
 
     // Disable raw mode before printing any messages
     let _ = disable_raw_mode();
@@ -2425,14 +3265,14 @@ fn real_main() -> AnyhowResult<()> {
 
     // Handle --show-paths early (no terminal setup needed)
     if args.show_paths {
-        let dir_context = fresh::config_io::DirectoryContext::from_system()?;
+        let dir_context = profile_dir_context(&args)?;
         fresh::services::log_dirs::print_all_paths(&dir_context);
         return Ok(());
     }
 
     // Handle --dump-config early (no terminal setup needed)
     if args.dump_config {
-        let dir_context = fresh::config_io::DirectoryContext::from_system()?;
+        let dir_context = profile_dir_context(&args)?;
         let working_dir = std::env::current_dir().unwrap_or_default();
         let config = if let Some(config_path) = &args.config {
             match config::Config::load_from_file(config_path) {
@@ -2473,16 +3313,51 @@ fn real_main() -> AnyhowResult<()> {
         return check_plugin_bundle(plugin_path);
     }
 
+    // Handle --bench-startup early (renders off-screen)
+    if args.bench_startup {
+        return bench_startup_command(&args);
+    }
+
     // Handle --init early (no terminal setup needed)
     if let Some(ref pkg_type) = args.init {
         return init_package_command(pkg_type.clone());
     }
 
+    // Handle pkg export/import early (no terminal setup needed)
+    if let Some(ref pkg) = args.pkg {
+        return package_bundle_command(pkg, &args);
+    }
+
     // Handle --list-sessions early (no terminal setup needed)
     if args.list_sessions {
         return list_sessions_command();
     }
 
+    // Handle session info: describe a running session
+    if let Some(ref session) = args.session_info {
+        return session_info_command(session.as_deref(), args.json);
+    }
+
+    // Handle session exec: run a command in a running session
+    if let Some((ref session, ref command)) = args.session_exec {
+        return session_exec_command(session.as_deref(), command);
+    }
+
+    // Handle session pipe: open stdin in a running session
+    if let Some(ref session) = args.session_pipe {
+        return session_pipe_command(session.as_deref());
+    }
+
+    // Handle shell-init: print shell integration
+    if let Some(ref shell) = args.shell_init {
+        return shell_init_command(shell);
+    }
+
+    // Handle doctor: diagnose the install
+    if args.doctor {
+        return doctor_command(&args);
+    }
+
     // Handle --kill: terminate a session
     if let Some(ref session) = args.kill {
         return kill_session_command(session.as_deref(), &args);
@@ -2495,7 +3370,17 @@ fn real_main() -> AnyhowResult<()> {
 
     // Handle open-file in session: send files to running session without attaching
     if let Some((session_name, files)) = &args.open_files_in_session {
-        return run_open_files_command(session_name.as_deref(), files);
+        let locations: Vec<FileLocation> = files.iter().map(|f| parse_file_location(f)).collect();
+        return run_open_files_command(
+            session_name.as_deref(),
+            &locations,
+            &server_spawn_args(&args),
+        );
+    }
+
+    // Handle open-url: open a fresh:// link in a session
+    if let Some(url) = &args.open_url {
+        return run_open_url_command(url, &server_spawn_args(&args));
     }
 
     // Handle --attach: connect to existing session
@@ -2504,13 +3389,13 @@ fn real_main() -> AnyhowResult<()> {
     }
 
     let SetupState {
-        config,
+        mut config,
         mut tracing_handles,
         mut terminal,
         terminal_size,
         file_locations,
         show_file_explorer,
-        dir_context,
+        mut dir_context,
         current_working_dir: initial_working_dir,
         mut stdin_stream,
         key_translator,
@@ -2519,9 +3404,11 @@ fn real_main() -> AnyhowResult<()> {
         #[cfg(not(target_os = "linux"))]
         gpm_client,
         mut terminal_modes,
+        appearance,
         filesystem,
         process_spawner,
         _remote_session,
+        mounts,
     } = initialize_app(&args).context("Failed to initialize application")?;
 
     let mut current_working_dir = initial_working_dir;
@@ -2533,11 +3420,17 @@ fn real_main() -> AnyhowResult<()> {
     // Track whether we should restore workspace on restart (for project switching)
     let mut restore_workspace_on_restart = false;
 
+    // Profile switched to on the last restart (shown instead of the project message)
+    let mut switched_profile: Option<String> = None;
+
     // Main editor loop - supports restarting with a new working directory
-    // Returns (loop_result, last_update_result) tuple
-    let (result, last_update_result) = loop {
+    // Returns (loop_result, last_update_result, relaunch, exit_code) tuple
+    let (result, last_update_result, relaunch, exit_code) = loop {
         let first_run = is_first_run;
-        let workspace_enabled = !args.no_session && file_locations.is_empty();
+        let workspace_enabled = !args.no_session
+            && file_locations.is_empty()
+            && args.diff.is_none()
+            && args.merge.is_none();
 
         // Detect terminal color capability
         let color_capability = fresh::view::color_support::ColorCapability::detect();
@@ -2560,10 +3453,28 @@ fn real_main() -> AnyhowResult<()> {
         // Set the process spawner (LocalProcessSpawner for local, RemoteProcessSpawner for remote)
         editor.set_process_spawner(process_spawner.clone());
 
+        // Additional remote hosts opened alongside the primary filesystem
+        for mount in &mounts {
+            editor.mount_filesystem(mount.filesystem.clone());
+        }
+
         #[cfg(target_os = "linux")]
         if gpm_client.is_some() {
             editor.set_gpm_active(true);
         }
+        editor.set_keyboard_enhancement_active(terminal_modes.keyboard_enhancement());
+        editor.set_graphics_protocol(
+            fresh::view::graphics_protocol::GraphicsProtocol::detect(),
+            terminal_cell_size(),
+        );
+        editor.set_hyperlinks(fresh::view::hyperlinks::detect());
+
+        if config.theme.follows_appearance() {
+            if let Some(appearance) = appearance {
+                editor.set_appearance(appearance);
+            }
+            editor.watch_appearance();
+        }
 
         if first_run {
             handle_first_run_setup(
@@ -2592,11 +3503,15 @@ fn real_main() -> AnyhowResult<()> {
             }
 
             editor.show_file_explorer();
-            let path = current_working_dir
-                .as_ref()
-                .map(|p| p.display().to_string())
-                .unwrap_or_else(|| ".".to_string());
-            editor.set_status_message(fresh::i18n::switched_to_project_message(&path));
+            if let Some(profile) = switched_profile.take() {
+                editor.set_status_message(fresh::i18n::switched_to_profile_message(&profile));
+            } else {
+                let path = current_working_dir
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| ".".to_string());
+                editor.set_status_message(fresh::i18n::switched_to_project_message(&path));
+            }
         }
 
         if let Err(e) = editor.start_recovery_session() {
@@ -2615,10 +3530,41 @@ fn real_main() -> AnyhowResult<()> {
 
         let update_result = iteration.update_result;
         let restart_dir = iteration.restart_dir;
+        let restart_profile = iteration.restart_profile;
+        let relaunch = iteration.relaunch;
         let loop_result = iteration.loop_result;
+        let exit_code = iteration.exit_code;
 
         drop(editor);
 
+        if let Some(profile) = restart_profile {
+            tracing::info!("Restarting editor with profile: {}", profile);
+            dir_context = dir_context.with_profile(Some(&profile));
+            fresh::input::input_history::set_data_dir_profile(Some(&profile));
+            if let Err(e) = std::fs::create_dir_all(&dir_context.config_dir) {
+                tracing::warn!("Failed to create profile config directory: {}", e);
+            }
+            // An explicit --config file is used for every profile
+            if args.config.is_none() {
+                let working_dir = current_working_dir
+                    .clone()
+                    .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+                config = config::Config::load_with_layers(&dir_context, &working_dir);
+                if args.no_upgrade_check {
+                    config.check_for_updates = false;
+                }
+                let locale_override = args.locale.as_deref().or(config.locale.as_option());
+                fresh::i18n::init_with_config(locale_override);
+            }
+            switched_profile = Some(dir_context.profile_name().to_string());
+            is_first_run = false;
+            restore_workspace_on_restart = true; // Restore the profile's workspace
+            terminal
+                .clear()
+                .context("Failed to clear terminal for restart")?;
+            continue;
+        }
+
         if let Some(new_dir) = restart_dir {
             tracing::info!(
                 "Restarting editor with new working directory: {}",
@@ -2633,12 +3579,19 @@ fn real_main() -> AnyhowResult<()> {
             continue;
         }
 
-        break (loop_result, update_result);
+        break (loop_result, update_result, relaunch, exit_code);
     };
 
     // Restore terminal state
     terminal_modes.undo();
 
+    // Start the release installed by Update Fresh Now
+    if let Some(executable) = relaunch {
+        if result.is_ok() {
+            return relaunch_editor(&executable);
+        }
+    }
+
     // Check for updates after terminal is restored (using cached result)
     if let Some(update_result) = last_update_result {
         if update_result.update_available {
@@ -2660,9 +3613,32 @@ fn real_main() -> AnyhowResult<()> {
         }
     }
 
+    if result.is_ok() && exit_code != 0 {
+        std::process::exit(exit_code);
+    }
     result.context("Editor loop returned an error")
 }
 
+/// Replace this process with `executable`, passing the original arguments
+#[cfg(unix)]
+fn relaunch_editor(executable: &std::path::Path) -> AnyhowResult<()> {
+    use std::os::unix::process::CommandExt;
+    let err = std::process::Command::new(executable)
+        .args(std::env::args_os().skip(1))
+        .exec();
+    Err(err).with_context(|| format!("Failed to start {}", executable.display()))
+}
+
+/// Run `executable` with the original arguments and exit with its status
+#[cfg(not(unix))]
+fn relaunch_editor(executable: &std::path::Path) -> AnyhowResult<()> {
+    let status = std::process::Command::new(executable)
+        .args(std::env::args_os().skip(1))
+        .status()
+        .with_context(|| format!("Failed to start {}", executable.display()))?;
+    std::process::exit(status.code().unwrap_or(1));
+}
+
 /// Main event loop
 #[cfg(target_os = "linux")]
 fn run_event_loop(
@@ -2781,7 +3757,22 @@ where
         }
 
         if needs_render && last_render.elapsed() >= FRAME_DURATION {
+            // Escape sequences queued by the editor, such as a cursor style
+            // change on a mode switch
+            let sequences = editor.take_pending_escape_sequences();
+            if !sequences.is_empty() {
+                use std::io::Write;
+                terminal.backend_mut().write_all(&sequences)?;
+            }
             terminal.draw(|frame| editor.render(frame))?;
+            // Images go over the cells the frame left blank for them
+            let images = editor.take_image_sequences();
+            if !images.is_empty() {
+                use std::io::Write;
+                let backend = terminal.backend_mut();
+                backend.write_all(&images)?;
+                backend.flush()?;
+            }
             last_render = Instant::now();
             needs_render = false;
         }
@@ -2833,6 +3824,8 @@ where
             }
             CrosstermEvent::Resize(w, h) => {
                 editor.resize(w, h);
+                // A font size change resizes the cells too
+                editor.set_graphics_protocol(editor.graphics_protocol(), terminal_cell_size());
                 needs_render = true;
             }
             CrosstermEvent::Paste(text) => {
@@ -2937,6 +3930,16 @@ fn poll_with_gpm(
 }
 
 /// Handle a keyboard event
+/// The terminal's cell size in pixels, for scaling image previews
+fn terminal_cell_size() -> (u16, u16) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => {
+            (size.width / size.columns, size.height / size.rows)
+        }
+        _ => fresh::view::graphics_protocol::DEFAULT_CELL_SIZE,
+    }
+}
+
 fn handle_key_event(editor: &mut Editor, key_event: KeyEvent) -> AnyhowResult<()> {
     // Trace the full key event
     tracing::trace!(
@@ -3164,6 +4167,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_location_remote_with_port() {
+        let loc = parse_location("ssh://deploy@web1:2222/etc/nginx/nginx.conf:12");
+        match loc {
+            ParsedLocation::Remote(rl) => {
+                assert_eq!(rl.user, "deploy");
+                assert_eq!(rl.host, "web1");
+                assert_eq!(rl.port, Some(2222));
+                assert_eq!(rl.path, "/etc/nginx/nginx.conf");
+                assert_eq!(rl.line, Some(12));
+            }
+            ParsedLocation::Local(_) => panic!("Expected remote, got local"),
+        }
+    }
+
+    #[test]
+    fn test_parse_location_ssh_url_without_user_or_port() {
+        let loc = parse_location("ssh://web1/etc/hosts");
+        match loc {
+            ParsedLocation::Remote(rl) => {
+                assert_eq!(rl.user, "");
+                assert_eq!(rl.host, "web1");
+                assert_eq!(rl.port, None);
+                assert_eq!(rl.path, "/etc/hosts");
+                assert_eq!(rl.line, None);
+            }
+            ParsedLocation::Local(_) => panic!("Expected remote, got local"),
+        }
+
+        // A port that isn't a number, or a missing path, is not a valid URL
+        assert!(parse_remote_location("ssh://web1:ssh/etc/hosts", |_| false).is_none());
+        assert!(parse_remote_location("ssh://web1", |_| false).is_none());
+    }
+
+    #[test]
+    fn test_parse_location_remote_numeric_segment_is_path() {
+        // Without ssh://, a numeric first segment is part of the path, not a port
+        let loc = parse_location("user@host:10:20");
+        match loc {
+            ParsedLocation::Remote(rl) => {
+                assert_eq!(rl.port, None);
+                assert_eq!(rl.path, "10");
+                assert_eq!(rl.line, Some(20));
+                assert_eq!(rl.column, None);
+            }
+            ParsedLocation::Local(_) => panic!("Expected remote, got local"),
+        }
+
+        let loc = parse_location("user@host:2022/path");
+        match loc {
+            ParsedLocation::Remote(rl) => {
+                assert_eq!(rl.port, None);
+                assert_eq!(rl.path, "2022/path");
+                assert_eq!(rl.line, None);
+            }
+            ParsedLocation::Local(_) => panic!("Expected remote, got local"),
+        }
+
+        let loc = parse_location("user@host:2222:/etc/hosts");
+        match loc {
+            ParsedLocation::Remote(rl) => {
+                assert_eq!(rl.port, None);
+                assert_eq!(rl.path, "2222:/etc/hosts");
+            }
+            ParsedLocation::Local(_) => panic!("Expected remote, got local"),
+        }
+    }
+
+    #[test]
+    fn test_parse_location_ssh_alias() {
+        let is_alias = |host: &str| host == "devbox";
+
+        match parse_location_with_aliases("devbox:src/main.rs:3", is_alias) {
+            ParsedLocation::Remote(rl) => {
+                assert_eq!(rl.user, "");
+                assert_eq!(rl.host, "devbox");
+                assert_eq!(rl.port, None);
+                assert_eq!(rl.path, "src/main.rs");
+                assert_eq!(rl.line, Some(3));
+            }
+            ParsedLocation::Local(_) => panic!("Expected remote, got local"),
+        }
+
+        // Unknown hosts without a user stay local
+        match parse_location_with_aliases("other:src/main.rs", is_alias) {
+            ParsedLocation::Local(fl) => assert_eq!(fl.path, PathBuf::from("other:src/main.rs")),
+            ParsedLocation::Remote(_) => panic!("Expected local, got remote"),
+        }
+    }
+
     #[test]
     fn test_parse_location_email_like_not_remote() {
         // An email-like string without a path should be treated as local
@@ -3187,6 +4280,39 @@ mod tests {
             ParsedLocation::Remote(_) => panic!("Expected local, got remote"),
         }
     }
+
+    #[test]
+    fn test_parse_open_url() {
+        let open = parse_open_url("fresh://open?file=/src/main.rs&line=12&column=4").unwrap();
+        assert_eq!(open.location.path, PathBuf::from("/src/main.rs"));
+        assert_eq!(open.location.line, Some(12));
+        assert_eq!(open.location.column, Some(4));
+        assert_eq!(open.session, None);
+
+        let open =
+            parse_open_url("fresh://open?file=/my%20dir/a%26b.rs&col=2&session=work").unwrap();
+        assert_eq!(open.location.path, PathBuf::from("/my dir/a&b.rs"));
+        assert_eq!(open.location.line, None);
+        assert_eq!(open.location.column, Some(2));
+        assert_eq!(open.session.as_deref(), Some("work"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_open_url_file_url_value() {
+        let open = parse_open_url("fresh://open?file=file%3A%2F%2F%2Ftmp%2Fx.rs&line=3").unwrap();
+        assert_eq!(open.location.path, PathBuf::from("/tmp/x.rs"));
+        assert_eq!(open.location.line, Some(3));
+    }
+
+    #[test]
+    fn test_parse_open_url_rejects_bad_links() {
+        assert!(parse_open_url("fresh://open?line=3").is_err());
+        assert!(parse_open_url("https://open?file=/a.rs").is_err());
+        assert!(parse_open_url("fresh://close?file=/a.rs").is_err());
+        assert!(parse_open_url("fresh://open?file=/a.rs&line=top").is_err());
+        assert!(parse_open_url("not a url").is_err());
+    }
 }
 
 // Property tests use Unix-style path generation strategy, skip on Windows
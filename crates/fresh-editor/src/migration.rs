@@ -0,0 +1,264 @@
+//! Versioned migrations for JSON files the editor reads back: the config layers,
+//! workspace files and keybinding definitions.
+//!
+//! Each file format keeps a top-level `"version"` number and a table of
+//! [`Migration`]s. Loading a file runs every migration newer than its version in
+//! order, so renamed keys and restructured sections are carried forward instead of
+//! being dropped by deserialization. Files written by users are rewritten after a
+//! migration, keeping the original next to them as `<name>.v<version>.bak`.
+
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// One step in a file format's history
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Version the file has once this migration has run
+    pub to: u32,
+    /// What the migration changes, for logs
+    pub description: &'static str,
+    /// Transform the whole document in place
+    pub apply: fn(&mut Value),
+}
+
+/// Result of migrating a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Version the document had before migrating (0 when it had none)
+    pub from: u32,
+    /// Version the document has now
+    pub to: u32,
+    /// Descriptions of the migrations that ran, oldest first
+    pub applied: Vec<&'static str>,
+    /// Whether anything other than the version number changed
+    pub changed: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error("version {version} is newer than the supported version {max_supported}")]
+    VersionTooNew { version: u32, max_supported: u32 },
+    #[error("{path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Version recorded in a document; documents without one are version 0
+pub fn version_of(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| v.min(u32::MAX as u64) as u32)
+}
+
+/// Bring `value` up to `current` by running every migration newer than its version.
+///
+/// Documents newer than `current` are left untouched and rejected, since the
+/// editor can't know what a later version changed.
+pub fn migrate(
+    value: &mut Value,
+    migrations: &[Migration],
+    current: u32,
+) -> Result<MigrationReport, MigrationError> {
+    let from = version_of(value);
+    if from > current {
+        return Err(MigrationError::VersionTooNew {
+            version: from,
+            max_supported: current,
+        });
+    }
+
+    let original = value.clone();
+    let mut applied = Vec::new();
+    for migration in migrations.iter().filter(|m| m.to > from && m.to <= current) {
+        (migration.apply)(value);
+        applied.push(migration.description);
+    }
+
+    let Value::Object(map) = value else {
+        return Ok(MigrationReport {
+            from,
+            to: from,
+            applied,
+            changed: *value != original,
+        });
+    };
+    if from < current {
+        map.insert("version".to_string(), Value::from(current));
+    }
+
+    // Compare without the version so that stamping it alone isn't a change
+    let mut stamped = original;
+    if let (Value::Object(original_map), Some(version)) = (&mut stamped, map.get("version")) {
+        original_map.insert("version".to_string(), version.clone());
+    }
+    Ok(MigrationReport {
+        from,
+        to: current,
+        applied,
+        changed: *value != stamped,
+    })
+}
+
+/// Where the pre-migration copy of `path` is kept: `config.json` becomes
+/// `config.json.v1.bak` for a file at version 1
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    path.with_file_name(name)
+}
+
+/// Replace a migrated file with `value`, first copying the original to its
+/// [`backup_path`]. An existing backup is never overwritten, so the oldest copy
+/// of each version survives repeated migrations. Returns the backup path.
+pub fn write_migrated(path: &Path, value: &Value, from: u32) -> Result<PathBuf, MigrationError> {
+    let io_err = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| MigrationError::Io { path, source }
+    };
+
+    let backup = backup_path(path, from);
+    if !backup.exists() {
+        std::fs::copy(path, &backup).map_err(io_err(&backup))?;
+    }
+
+    let json = serde_json::to_string_pretty(value).map_err(|e| MigrationError::Io {
+        path: path.to_path_buf(),
+        source: e.into(),
+    })?;
+    std::fs::write(path, json).map_err(io_err(path))?;
+    Ok(backup)
+}
+
+/// Move the value at dotted path `from` (e.g. `"editor.tabSize"`) to `to`,
+/// creating intermediate objects. A value already at `to` wins and the old key
+/// is dropped. Returns whether a value was found at `from`.
+pub fn rename_key(value: &mut Value, from: &str, to: &str) -> bool {
+    let Some(moved) = take_key(value, from) else {
+        return false;
+    };
+
+    let mut segments: Vec<&str> = to.split('.').collect();
+    let Some(last) = segments.pop() else {
+        return true;
+    };
+    let mut target = value;
+    for segment in segments {
+        let Value::Object(map) = target else {
+            return true;
+        };
+        target = map
+            .entry(segment)
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    if let Value::Object(map) = target {
+        map.entry(last).or_insert(moved);
+    }
+    true
+}
+
+/// Remove and return the value at a dotted path
+fn take_key(value: &mut Value, path: &str) -> Option<Value> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (
+            parent
+                .split('.')
+                .try_fold(&mut *value, |v, segment| v.get_mut(segment))?,
+            key,
+        ),
+        None => (value, path),
+    };
+    parent.as_object_mut()?.remove(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn add_b(value: &mut Value) {
+        value["b"] = json!(true);
+    }
+
+    fn rename_a(value: &mut Value) {
+        rename_key(value, "a", "nested.a");
+    }
+
+    const MIGRATIONS: &[Migration] = &[
+        Migration {
+            to: 1,
+            description: "add b",
+            apply: add_b,
+        },
+        Migration {
+            to: 2,
+            description: "move a into nested",
+            apply: rename_a,
+        },
+    ];
+
+    #[test]
+    fn runs_only_newer_migrations() {
+        let mut value = json!({"version": 1, "a": 5});
+        let report = migrate(&mut value, MIGRATIONS, 2).unwrap();
+
+        assert_eq!(value, json!({"version": 2, "nested": {"a": 5}}));
+        assert_eq!(report.from, 1);
+        assert_eq!(report.to, 2);
+        assert_eq!(report.applied, vec!["move a into nested"]);
+        assert!(report.changed);
+    }
+
+    #[test]
+    fn stamping_the_version_is_not_a_change() {
+        let mut value = json!({"b": true});
+        let report = migrate(&mut value, MIGRATIONS, 2).unwrap();
+
+        assert_eq!(value, json!({"version": 2, "b": true}));
+        assert_eq!(report.applied.len(), 2);
+        assert!(!report.changed);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let mut value = json!({"version": 3, "a": 1});
+        let err = migrate(&mut value, MIGRATIONS, 2).unwrap_err();
+
+        assert!(matches!(
+            err,
+            MigrationError::VersionTooNew {
+                version: 3,
+                max_supported: 2
+            }
+        ));
+        assert_eq!(value, json!({"version": 3, "a": 1}));
+    }
+
+    #[test]
+    fn rename_keeps_existing_destination() {
+        let mut value = json!({"editor": {"tabSize": 8, "tab_size": 2}});
+
+        assert!(rename_key(&mut value, "editor.tabSize", "editor.tab_size"));
+        assert!(!rename_key(&mut value, "editor.missing", "editor.other"));
+        assert_eq!(value, json!({"editor": {"tab_size": 2}}));
+    }
+
+    #[test]
+    fn write_migrated_keeps_the_first_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{"a": 1}"#).unwrap();
+
+        let backup = write_migrated(&path, &json!({"version": 2, "a": 2}), 0).unwrap();
+        assert_eq!(backup, dir.path().join("config.json.v0.bak"));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), r#"{"a": 1}"#);
+
+        write_migrated(&path, &json!({"version": 2, "a": 3}), 0).unwrap();
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), r#"{"a": 1}"#);
+        let written: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written, json!({"version": 2, "a": 3}));
+    }
+}
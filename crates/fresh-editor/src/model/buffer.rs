@@ -176,219 +176,646 @@ impl WriteRecipe {
     }
 }
 
-/// Represents a line number (simplified for new implementation)
-/// Legacy enum kept for backwards compatibility - always Absolute now
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum LineNumber {
-    /// Absolute line number - this is the actual line number in the file
-    Absolute(usize),
-    /// Relative line number (deprecated - now same as Absolute)
-    Relative {
-        line: usize,
-        from_cached_line: usize,
-    },
+/// A save prepared by [`TextBuffer::prepare_save`]
+///
+/// Holds everything needed to write the file, so the write can run on another
+/// thread. Call [`TextBuffer::finalize_external_save`] once it succeeds.
+pub struct SaveJob {
+    fs: Arc<dyn FileSystem + Send + Sync>,
+    dest_path: PathBuf,
+    recipe: WriteRecipe,
 }
 
-impl LineNumber {
-    /// Get the line number value
-    pub fn value(&self) -> usize {
-        match self {
-            Self::Absolute(line) | Self::Relative { line, .. } => *line,
-        }
+impl SaveJob {
+    /// The file being written
+    pub fn dest_path(&self) -> &Path {
+        &self.dest_path
     }
 
-    /// Check if this is an absolute line number
-    pub fn is_absolute(&self) -> bool {
-        matches!(self, LineNumber::Absolute(_))
+    /// Write the file
+    ///
+    /// Uses the write recipe approach for both local and remote filesystems:
+    /// - Copy ops reference unchanged regions in the source file
+    /// - Insert ops contain new/modified data
+    ///
+    /// For remote filesystems, the recipe is sent to the agent which reconstructs
+    /// the file server-side, avoiding transfer of unchanged content.
+    ///
+    /// For local filesystems with ownership concerns (file owned by another user),
+    /// uses in-place writing to preserve ownership. Otherwise uses atomic writes.
+    pub fn write(&self) -> anyhow::Result<()> {
+        let dest_path = self.dest_path.as_path();
+        let recipe = &self.recipe;
+
+        // Empty buffers have no actions
+        if recipe.actions.is_empty() {
+            self.fs.write_file(dest_path, &[])?;
+            return Ok(());
+        }
+
+        let ops = recipe.to_write_ops();
+
+        // Check if we need in-place writing to preserve file ownership (local only)
+        // Remote filesystems handle this differently
+        let is_local = self.fs.remote_connection_info().is_none();
+        let use_inplace = is_local && self.should_use_inplace_write(dest_path);
+
+        if use_inplace {
+            // In-place write: write directly to preserve ownership
+            self.save_with_inplace_write(dest_path, recipe)?;
+        } else if !recipe.has_copy_ops() && !is_local {
+            // Remote with no Copy ops: use write_file directly (more efficient)
+            let data = recipe.flatten_inserts();
+            self.fs.write_file(dest_path, &data)?;
+        } else if is_local {
+            // Local: use write_file or write_patched with sudo fallback
+            let write_result = if !recipe.has_copy_ops() {
+                let data = recipe.flatten_inserts();
+                self.fs.write_file(dest_path, &data)
+            } else {
+                let src_for_patch = recipe.src_path.as_deref().unwrap_or(dest_path);
+                self.fs.write_patched(src_for_patch, dest_path, &ops)
+            };
+
+            if let Err(e) = write_result {
+                if e.kind() == io::ErrorKind::PermissionDenied {
+                    // Create temp file and return sudo error
+                    let original_metadata = self.fs.metadata_if_exists(dest_path);
+                    let (temp_path, mut temp_file) = self.create_temp_file(dest_path)?;
+                    self.write_recipe_to_file(&mut temp_file, recipe)?;
+                    temp_file.sync_all()?;
+                    drop(temp_file);
+                    return Err(self.make_sudo_error(temp_path, dest_path, original_metadata));
+                }
+                return Err(e.into());
+            }
+        } else {
+            // Remote with Copy ops: use write_patched
+            let src_for_patch = recipe.src_path.as_deref().unwrap_or(dest_path);
+            self.fs.write_patched(src_for_patch, dest_path, &ops)?;
+        }
+
+        Ok(())
     }
 
-    /// Check if this is a relative line number
-    pub fn is_relative(&self) -> bool {
-        matches!(self, LineNumber::Relative { .. })
+    /// Check if we should use in-place writing to preserve file ownership.
+    /// Returns true if the file exists and is owned by a different user.
+    /// On Unix, only root or the file owner can change file ownership with chown.
+    /// When the current user is not the file owner, using atomic write (temp file + rename)
+    /// would change the file's ownership to the current user. To preserve ownership,
+    /// we must write directly to the existing file instead.
+    ///
+    /// Files with more than one hard link are also written in place, since
+    /// renaming a new file over one name would split it from the others.
+    fn should_use_inplace_write(&self, dest_path: &Path) -> bool {
+        !self.fs.is_owner(dest_path)
+            || self
+                .fs
+                .metadata(dest_path)
+                .is_ok_and(|meta| meta.hard_links.is_some_and(|links| links > 1))
     }
 
-    /// Format the line number for display
-    pub fn format(&self) -> String {
-        match self {
-            Self::Absolute(line) => format!("{}", line + 1),
-            Self::Relative { line, .. } => format!("~{}", line + 1),
+    /// Create a temporary file for saving.
+    ///
+    /// Tries to create the file in the same directory as the destination file first
+    /// to allow for an atomic rename. If that fails (e.g., due to directory permissions),
+    /// falls back to the system temporary directory.
+    fn create_temp_file(
+        &self,
+        dest_path: &Path,
+    ) -> io::Result<(PathBuf, Box<dyn crate::model::filesystem::FileWriter>)> {
+        // Try creating in same directory first
+        let same_dir_temp = self.fs.temp_path_for(dest_path);
+        match self.fs.create_file(&same_dir_temp) {
+            Ok(file) => Ok((same_dir_temp, file)),
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                // Fallback to system temp directory
+                let temp_path = self.fs.unique_temp_path(dest_path);
+                let file = self.fs.create_file(&temp_path)?;
+                Ok((temp_path, file))
+            }
+            Err(e) => Err(e),
         }
     }
-}
-
-/// A text buffer that manages document content using a piece table
-/// with integrated line tracking
-pub struct TextBuffer {
-    /// Filesystem abstraction for file I/O operations.
-    /// Stored internally so methods can access it without threading through call chains.
-    fs: Arc<dyn FileSystem + Send + Sync>,
 
-    /// The piece tree for efficient text manipulation with integrated line tracking
-    piece_tree: PieceTree,
+    /// Create a temporary file in the recovery directory for in-place writes.
+    /// This allows recovery if a crash occurs during the in-place write operation.
+    fn create_recovery_temp_file(
+        &self,
+        dest_path: &Path,
+    ) -> io::Result<(PathBuf, Box<dyn crate::model::filesystem::FileWriter>)> {
+        // Get recovery directory: $XDG_DATA_HOME/fresh/recovery or ~/.local/share/fresh/recovery
+        let recovery_dir = crate::input::input_history::get_data_dir()
+            .map(|d| d.join("recovery"))
+            .unwrap_or_else(|_| std::env::temp_dir());
 
-    /// Snapshot of the piece tree root at last save (shared via Arc)
-    saved_root: Arc<crate::model::piece_tree::PieceTreeNode>,
+        // Ensure directory exists
+        self.fs.create_dir_all(&recovery_dir)?;
 
-    /// List of string buffers containing chunks of text data
-    /// Index 0 is typically the original/stored buffer
-    /// Additional buffers are added for modifications
-    buffers: Vec<StringBuffer>,
+        // Create unique filename based on destination file and timestamp
+        let file_name = dest_path
+            .file_name()
+            .unwrap_or_else(|| std::ffi::OsStr::new("fresh-save"));
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let pid = std::process::id();
 
-    /// Next buffer ID to assign
-    next_buffer_id: usize,
+        let temp_name = format!(
+            ".inplace-{}-{}-{}.tmp",
+            file_name.to_string_lossy(),
+            pid,
+            timestamp
+        );
+        let temp_path = recovery_dir.join(temp_name);
 
-    /// Optional file path for persistence
-    file_path: Option<PathBuf>,
+        let file = self.fs.create_file(&temp_path)?;
+        Ok((temp_path, file))
+    }
 
-    /// Has the buffer been modified since last save?
-    modified: bool,
+    /// Get the path for in-place write recovery metadata.
+    /// Uses the same recovery directory as temp files.
+    fn inplace_recovery_meta_path(&self, dest_path: &Path) -> PathBuf {
+        let recovery_dir = crate::input::input_history::get_data_dir()
+            .map(|d| d.join("recovery"))
+            .unwrap_or_else(|_| std::env::temp_dir());
 
-    /// Does the buffer have unsaved changes for recovery auto-save?
-    /// This is separate from `modified` because recovery auto-save doesn't
-    /// clear `modified` (buffer still differs from on-disk file).
-    recovery_pending: bool,
+        let hash = crate::services::recovery::path_hash(dest_path);
+        recovery_dir.join(format!("{}.inplace.json", hash))
+    }
 
-    /// Is this a large file (no line indexing, lazy loading enabled)?
-    large_file: bool,
+    /// Write in-place recovery metadata using self.fs.
+    /// This is called before the dangerous streaming step so we can recover on crash.
+    fn write_inplace_recovery_meta(
+        &self,
+        meta_path: &Path,
+        dest_path: &Path,
+        temp_path: &Path,
+        original_metadata: &Option<FileMetadata>,
+    ) -> io::Result<()> {
+        #[cfg(unix)]
+        let (uid, gid, mode) = original_metadata
+            .as_ref()
+            .map(|m| {
+                (
+                    m.uid.unwrap_or(0),
+                    m.gid.unwrap_or(0),
+                    m.permissions.as_ref().map(|p| p.mode()).unwrap_or(0o644),
+                )
+            })
+            .unwrap_or((0, 0, 0o644));
+        #[cfg(not(unix))]
+        let (uid, gid, mode) = (0u32, 0u32, 0o644u32);
 
-    /// Is this a binary file? Binary files are opened read-only and render
-    /// unprintable characters as code points.
-    is_binary: bool,
+        let recovery = crate::services::recovery::InplaceWriteRecovery::new(
+            dest_path.to_path_buf(),
+            temp_path.to_path_buf(),
+            uid,
+            gid,
+            mode,
+        );
 
-    /// Line ending format detected from the file (or default for new files)
-    line_ending: LineEnding,
+        let json = serde_json::to_string_pretty(&recovery)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
-    /// Original line ending format when file was loaded (used for conversion on save)
-    /// This tracks what the file had when loaded, so we can detect if the user
-    /// changed the line ending format and needs conversion on save.
-    original_line_ending: LineEnding,
+        self.fs.write_file(meta_path, json.as_bytes())
+    }
 
-    /// Text encoding format detected from the file (or default for new files)
-    encoding: Encoding,
+    /// Internal helper to create a SudoSaveRequired error.
+    fn make_sudo_error(
+        &self,
+        temp_path: PathBuf,
+        dest_path: &Path,
+        original_metadata: Option<FileMetadata>,
+    ) -> anyhow::Error {
+        #[cfg(unix)]
+        let (uid, gid, mode) = if let Some(ref meta) = original_metadata {
+            (
+                meta.uid.unwrap_or(0),
+                meta.gid.unwrap_or(0),
+                meta.permissions
+                    .as_ref()
+                    .map(|p| p.mode() & 0o7777)
+                    .unwrap_or(0),
+            )
+        } else {
+            // A new file belongs to the directory's owner, readable by all
+            let dir = dest_path.parent().and_then(|p| self.fs.metadata(p).ok());
+            (
+                dir.as_ref().and_then(|m| m.uid).unwrap_or(0),
+                dir.as_ref().and_then(|m| m.gid).unwrap_or(0),
+                0o644,
+            )
+        };
+        #[cfg(not(unix))]
+        let (uid, gid, mode) = (0u32, 0u32, 0u32);
 
-    /// Original encoding when file was loaded (used for conversion on save)
-    /// Similar to original_line_ending, tracks what the file had when loaded.
-    original_encoding: Encoding,
+        let _ = original_metadata; // suppress unused warning on non-Unix
 
-    /// The file size on disk after the last save.
-    /// Used for chunked recovery to know the original file size for reconstruction.
-    /// Updated when loading from file or after saving.
-    saved_file_size: Option<usize>,
+        anyhow::anyhow!(SudoSaveRequired {
+            temp_path,
+            dest_path: dest_path.to_path_buf(),
+            uid,
+            gid,
+            mode,
+        })
+    }
 
-    /// Monotonic version counter for change tracking.
-    version: u64,
-}
+    /// Write using in-place mode to preserve file ownership.
+    ///
+    /// This is used when the file is owned by a different user and we need
+    /// to write directly to the existing file to preserve its ownership.
+    ///
+    /// The approach:
+    /// 1. Write the recipe to a temp file first (reads from original, writes to temp)
+    /// 2. Stream the temp file content to the destination file (truncates and writes)
+    /// 3. Delete the temp file
+    ///
+    /// This avoids the bug where truncating the destination before reading Copy chunks
+    /// would corrupt the file. It also works for huge files since we stream in chunks.
+    fn save_with_inplace_write(
+        &self,
+        dest_path: &Path,
+        recipe: &WriteRecipe,
+    ) -> anyhow::Result<()> {
+        let original_metadata = self.fs.metadata_if_exists(dest_path);
 
-impl TextBuffer {
-    /// Create a new text buffer with the given filesystem implementation.
-    /// Note: large_file_threshold is ignored in the new implementation
-    pub fn new(_large_file_threshold: usize, fs: Arc<dyn FileSystem + Send + Sync>) -> Self {
-        let piece_tree = PieceTree::empty();
-        let line_ending = LineEnding::default();
-        let encoding = Encoding::default();
-        TextBuffer {
-            fs,
-            saved_root: piece_tree.root(),
-            piece_tree,
-            buffers: vec![StringBuffer::new(0, Vec::new())],
-            next_buffer_id: 1,
-            file_path: None,
-            modified: false,
-            recovery_pending: false,
-            large_file: false,
-            is_binary: false,
-            line_ending,
-            original_line_ending: line_ending,
-            encoding,
-            original_encoding: encoding,
-            saved_file_size: None,
-            version: 0,
+        // Optimization: if no Copy ops, we can write directly without a temp file
+        // (same as the non-inplace path for small files)
+        if !recipe.has_copy_ops() {
+            let data = recipe.flatten_inserts();
+            return self.write_data_inplace(dest_path, &data, original_metadata);
         }
-    }
-
-    /// Current buffer version (monotonic, wraps on overflow)
-    pub fn version(&self) -> u64 {
-        self.version
-    }
 
-    /// Get a reference to the filesystem implementation used by this buffer.
-    pub fn filesystem(&self) -> &Arc<dyn FileSystem + Send + Sync> {
-        &self.fs
-    }
+        // Step 1: Write recipe to a temp file in the recovery directory
+        // This reads Copy chunks from the original file (still intact) and writes to temp.
+        // Using the recovery directory allows crash recovery if the operation fails.
+        let (temp_path, mut temp_file) = self.create_recovery_temp_file(dest_path)?;
+        if let Err(e) = self.write_recipe_to_file(&mut temp_file, recipe) {
+            let _ = self.fs.remove_file(&temp_path);
+            return Err(e.into());
+        }
+        temp_file.sync_all()?;
+        drop(temp_file);
 
-    /// Set the filesystem implementation for this buffer.
-    pub fn set_filesystem(&mut self, fs: Arc<dyn FileSystem + Send + Sync>) {
-        self.fs = fs;
-    }
+        // Step 1.5: Save recovery metadata before the dangerous step
+        // If we crash during step 2, this metadata + temp file allows recovery
+        let recovery_meta_path = self.inplace_recovery_meta_path(dest_path);
+        // Best effort - don't fail the save if we can't write recovery metadata
+        let _ = self.write_inplace_recovery_meta(
+            &recovery_meta_path,
+            dest_path,
+            &temp_path,
+            &original_metadata,
+        );
 
-    #[inline]
-    fn bump_version(&mut self) {
-        self.version = self.version.wrapping_add(1);
+        // Step 2: Stream temp file content to destination
+        // Now it's safe to truncate the destination since all data is in temp
+        match self.fs.open_file_for_write(dest_path) {
+            Ok(mut out_file) => {
+                if let Err(e) = self.stream_file_to_writer(&temp_path, &mut out_file) {
+                    // Don't delete temp file or recovery metadata - allow recovery
+                    return Err(e.into());
+                }
+                out_file.sync_all()?;
+                // Success! Clean up temp file and recovery metadata
+                let _ = self.fs.remove_file(&temp_path);
+                let _ = self.fs.remove_file(&recovery_meta_path);
+                Ok(())
+            }
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                // Can't write to destination - trigger sudo fallback
+                // Keep temp file for sudo to use, clean up recovery metadata
+                let _ = self.fs.remove_file(&recovery_meta_path);
+                Err(self.make_sudo_error(temp_path, dest_path, original_metadata))
+            }
+            Err(e) => {
+                // Don't delete temp file or recovery metadata - allow recovery
+                Err(e.into())
+            }
+        }
     }
 
-    #[inline]
-    fn mark_content_modified(&mut self) {
-        self.modified = true;
-        self.recovery_pending = true;
-        self.bump_version();
+    /// Write data directly to a file in-place, with sudo fallback on permission denied.
+    fn write_data_inplace(
+        &self,
+        dest_path: &Path,
+        data: &[u8],
+        original_metadata: Option<FileMetadata>,
+    ) -> anyhow::Result<()> {
+        match self.fs.open_file_for_write(dest_path) {
+            Ok(mut out_file) => {
+                out_file.write_all(data)?;
+                out_file.sync_all()?;
+                Ok(())
+            }
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                // Create temp file for sudo fallback
+                let (temp_path, mut temp_file) = self.create_temp_file(dest_path)?;
+                temp_file.write_all(data)?;
+                temp_file.sync_all()?;
+                drop(temp_file);
+                Err(self.make_sudo_error(temp_path, dest_path, original_metadata))
+            }
+            Err(e) => Err(e.into()),
+        }
     }
 
-    /// Create a text buffer from raw bytes WITHOUT encoding conversion.
-    /// Used for binary files where we want to preserve the exact bytes.
-    fn from_bytes_raw(content: Vec<u8>, fs: Arc<dyn FileSystem + Send + Sync>) -> Self {
-        let bytes = content.len();
-
-        // For binary files, detect line ending but don't convert encoding
-        let line_ending = Self::detect_line_ending(&content);
+    /// Stream a file's content to a writer in chunks to avoid memory issues with large files.
+    fn stream_file_to_writer(
+        &self,
+        src_path: &Path,
+        out_file: &mut Box<dyn crate::model::filesystem::FileWriter>,
+    ) -> io::Result<()> {
+        const CHUNK_SIZE: usize = 1024 * 1024; // 1MB chunks
 
-        // Create initial StringBuffer with ID 0
-        let buffer = StringBuffer::new(0, content);
-        let line_feed_cnt = buffer.line_feed_count();
+        let file_size = self.fs.metadata(src_path)?.size as u64;
+        let mut offset = 0u64;
 
-        let piece_tree = if bytes > 0 {
-            PieceTree::new(BufferLocation::Stored(0), 0, bytes, line_feed_cnt)
-        } else {
-            PieceTree::empty()
-        };
+        while offset < file_size {
+            let remaining = file_size - offset;
+            let chunk_len = std::cmp::min(remaining, CHUNK_SIZE as u64) as usize;
+            let chunk = self.fs.read_range(src_path, offset, chunk_len)?;
+            out_file.write_all(&chunk)?;
+            offset += chunk_len as u64;
+        }
 
-        let saved_root = piece_tree.root();
+        Ok(())
+    }
 
-        TextBuffer {
-            fs,
-            line_ending,
-            original_line_ending: line_ending,
-            encoding: Encoding::Utf8, // Binary files treated as raw bytes (no conversion)
-            original_encoding: Encoding::Utf8,
-            piece_tree,
-            saved_root,
-            buffers: vec![buffer],
-            next_buffer_id: 1,
-            file_path: None,
-            modified: false,
-            recovery_pending: false,
-            large_file: false,
-            is_binary: true,
-            saved_file_size: Some(bytes),
-            version: 0,
+    /// Write the recipe content to a file writer.
+    fn write_recipe_to_file(
+        &self,
+        out_file: &mut Box<dyn crate::model::filesystem::FileWriter>,
+        recipe: &WriteRecipe,
+    ) -> io::Result<()> {
+        for action in &recipe.actions {
+            match action {
+                RecipeAction::Copy { offset, len } => {
+                    // Read from source and write to output
+                    let src_path = recipe.src_path.as_ref().ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidData, "Copy action without source")
+                    })?;
+                    let data = self.fs.read_range(src_path, *offset, *len as usize)?;
+                    out_file.write_all(&data)?;
+                }
+                RecipeAction::Insert { index } => {
+                    out_file.write_all(&recipe.insert_data[*index])?;
+                }
+            }
         }
+        Ok(())
     }
+}
 
-    /// Create a text buffer from initial content with the given filesystem.
-    pub fn from_bytes(content: Vec<u8>, fs: Arc<dyn FileSystem + Send + Sync>) -> Self {
-        // Auto-detect encoding and convert to UTF-8 if needed
-        let (encoding, utf8_content) = Self::detect_and_convert_encoding(&content);
+/// A save whose write runs elsewhere, started by [`TextBuffer::begin_external_save`]
+struct PendingSave {
+    /// The file being written
+    dest_path: PathBuf,
+    /// Edits made since the save started, in order
+    edits: Vec<PendingEdit>,
+    /// False once the piece tree was replaced in a way `edits` can't express
+    replayable: bool,
+}
 
-        let bytes = utf8_content.len();
+/// An edit made while a save is in progress, replayed onto the saved file
+enum PendingEdit {
+    Insert { offset: usize, text: Vec<u8> },
+    Delete { offset: usize, bytes: usize },
+    Bulk(Vec<(usize, usize, String)>),
+    Replace(String),
+}
 
-        // Auto-detect line ending format from content
-        let line_ending = Self::detect_line_ending(&utf8_content);
+/// Represents a line number (simplified for new implementation)
+/// Legacy enum kept for backwards compatibility - always Absolute now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineNumber {
+    /// Absolute line number - this is the actual line number in the file
+    Absolute(usize),
+    /// Relative line number (deprecated - now same as Absolute)
+    Relative {
+        line: usize,
+        from_cached_line: usize,
+    },
+}
 
-        // Create initial StringBuffer with ID 0
-        let buffer = StringBuffer::new(0, utf8_content);
-        let line_feed_cnt = buffer.line_feed_count();
+impl LineNumber {
+    /// Get the line number value
+    pub fn value(&self) -> usize {
+        match self {
+            Self::Absolute(line) | Self::Relative { line, .. } => *line,
+        }
+    }
 
-        let piece_tree = if bytes > 0 {
-            PieceTree::new(BufferLocation::Stored(0), 0, bytes, line_feed_cnt)
+    /// Check if this is an absolute line number
+    pub fn is_absolute(&self) -> bool {
+        matches!(self, LineNumber::Absolute(_))
+    }
+
+    /// Check if this is a relative line number
+    pub fn is_relative(&self) -> bool {
+        matches!(self, LineNumber::Relative { .. })
+    }
+
+    /// Format the line number for display
+    pub fn format(&self) -> String {
+        match self {
+            Self::Absolute(line) => format!("{}", line + 1),
+            Self::Relative { line, .. } => format!("~{}", line + 1),
+        }
+    }
+}
+
+/// A text buffer that manages document content using a piece table
+/// with integrated line tracking
+pub struct TextBuffer {
+    /// Filesystem abstraction for file I/O operations.
+    /// Stored internally so methods can access it without threading through call chains.
+    fs: Arc<dyn FileSystem + Send + Sync>,
+
+    /// The piece tree for efficient text manipulation with integrated line tracking
+    piece_tree: PieceTree,
+
+    /// Snapshot of the piece tree root at last save (shared via Arc)
+    saved_root: Arc<crate::model::piece_tree::PieceTreeNode>,
+
+    /// List of string buffers containing chunks of text data
+    /// Index 0 is typically the original/stored buffer
+    /// Additional buffers are added for modifications
+    buffers: Vec<StringBuffer>,
+
+    /// Next buffer ID to assign
+    next_buffer_id: usize,
+
+    /// Optional file path for persistence
+    file_path: Option<PathBuf>,
+
+    /// Has the buffer been modified since last save?
+    modified: bool,
+
+    /// Does the buffer have unsaved changes for recovery auto-save?
+    /// This is separate from `modified` because recovery auto-save doesn't
+    /// clear `modified` (buffer still differs from on-disk file).
+    recovery_pending: bool,
+
+    /// Is this a large file (no line indexing, lazy loading enabled)?
+    large_file: bool,
+
+    /// Is this a binary file? Binary files are opened read-only and render
+    /// unprintable characters as code points.
+    is_binary: bool,
+
+    /// Line ending format detected from the file (or default for new files)
+    line_ending: LineEnding,
+
+    /// Original line ending format when file was loaded (used for conversion on save)
+    /// This tracks what the file had when loaded, so we can detect if the user
+    /// changed the line ending format and needs conversion on save.
+    original_line_ending: LineEnding,
+
+    /// Text encoding format detected from the file (or default for new files)
+    encoding: Encoding,
+
+    /// Original encoding when file was loaded (used for conversion on save)
+    /// Similar to original_line_ending, tracks what the file had when loaded.
+    original_encoding: Encoding,
+
+    /// The file size on disk after the last save.
+    /// Used for chunked recovery to know the original file size for reconstruction.
+    /// Updated when loading from file or after saving.
+    saved_file_size: Option<usize>,
+
+    /// Monotonic version counter for change tracking.
+    version: u64,
+
+    /// Background save in progress, see [`TextBuffer::begin_external_save`]
+    pending_save: Option<PendingSave>,
+}
+
+impl std::fmt::Debug for TextBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextBuffer")
+            .field("file_path", &self.file_path)
+            .field("bytes", &self.total_bytes())
+            .field("modified", &self.modified)
+            .field("large_file", &self.large_file)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TextBuffer {
+    /// Create a new text buffer with the given filesystem implementation.
+    /// Note: large_file_threshold is ignored in the new implementation
+    pub fn new(_large_file_threshold: usize, fs: Arc<dyn FileSystem + Send + Sync>) -> Self {
+        let piece_tree = PieceTree::empty();
+        let line_ending = LineEnding::default();
+        let encoding = Encoding::default();
+        TextBuffer {
+            fs,
+            saved_root: piece_tree.root(),
+            piece_tree,
+            buffers: vec![StringBuffer::new(0, Vec::new())],
+            next_buffer_id: 1,
+            file_path: None,
+            modified: false,
+            recovery_pending: false,
+            large_file: false,
+            is_binary: false,
+            line_ending,
+            original_line_ending: line_ending,
+            encoding,
+            original_encoding: encoding,
+            saved_file_size: None,
+            version: 0,
+            pending_save: None,
+        }
+    }
+
+    /// Current buffer version (monotonic, wraps on overflow)
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Get a reference to the filesystem implementation used by this buffer.
+    pub fn filesystem(&self) -> &Arc<dyn FileSystem + Send + Sync> {
+        &self.fs
+    }
+
+    /// Set the filesystem implementation for this buffer.
+    pub fn set_filesystem(&mut self, fs: Arc<dyn FileSystem + Send + Sync>) {
+        self.fs = fs;
+    }
+
+    #[inline]
+    fn bump_version(&mut self) {
+        self.version = self.version.wrapping_add(1);
+    }
+
+    #[inline]
+    fn mark_content_modified(&mut self) {
+        self.modified = true;
+        self.recovery_pending = true;
+        self.bump_version();
+    }
+
+    /// Create a text buffer from raw bytes WITHOUT encoding conversion.
+    /// Used for binary files where we want to preserve the exact bytes.
+    fn from_bytes_raw(content: Vec<u8>, fs: Arc<dyn FileSystem + Send + Sync>) -> Self {
+        let bytes = content.len();
+
+        // For binary files, detect line ending but don't convert encoding
+        let line_ending = Self::detect_line_ending(&content);
+
+        // Create initial StringBuffer with ID 0
+        let buffer = StringBuffer::new(0, content);
+        let line_feed_cnt = buffer.line_feed_count();
+
+        let piece_tree = if bytes > 0 {
+            PieceTree::new(BufferLocation::Stored(0), 0, bytes, line_feed_cnt)
+        } else {
+            PieceTree::empty()
+        };
+
+        let saved_root = piece_tree.root();
+
+        TextBuffer {
+            fs,
+            line_ending,
+            original_line_ending: line_ending,
+            encoding: Encoding::Utf8, // Binary files treated as raw bytes (no conversion)
+            original_encoding: Encoding::Utf8,
+            piece_tree,
+            saved_root,
+            buffers: vec![buffer],
+            next_buffer_id: 1,
+            file_path: None,
+            modified: false,
+            recovery_pending: false,
+            large_file: false,
+            is_binary: true,
+            saved_file_size: Some(bytes),
+            version: 0,
+            pending_save: None,
+        }
+    }
+
+    /// Create a text buffer from initial content with the given filesystem.
+    pub fn from_bytes(content: Vec<u8>, fs: Arc<dyn FileSystem + Send + Sync>) -> Self {
+        // Auto-detect encoding and convert to UTF-8 if needed
+        let (encoding, utf8_content) = Self::detect_and_convert_encoding(&content);
+
+        let bytes = utf8_content.len();
+
+        // Auto-detect line ending format from content
+        let line_ending = Self::detect_line_ending(&utf8_content);
+
+        // Create initial StringBuffer with ID 0
+        let buffer = StringBuffer::new(0, utf8_content);
+        let line_feed_cnt = buffer.line_feed_count();
+
+        let piece_tree = if bytes > 0 {
+            PieceTree::new(BufferLocation::Stored(0), 0, bytes, line_feed_cnt)
         } else {
             PieceTree::empty()
         };
@@ -412,6 +839,7 @@ impl TextBuffer {
             is_binary: false,
             saved_file_size: Some(bytes), // Treat initial content as "saved" state
             version: 0,
+            pending_save: None,
         }
     }
 
@@ -458,6 +886,7 @@ impl TextBuffer {
             is_binary: false,
             saved_file_size: Some(bytes),
             version: 0,
+            pending_save: None,
         }
     }
 
@@ -493,6 +922,7 @@ impl TextBuffer {
             original_encoding: encoding,
             saved_file_size: None,
             version: 0,
+            pending_save: None,
         }
     }
 
@@ -737,6 +1167,7 @@ impl TextBuffer {
             original_encoding: encoding,
             saved_file_size: Some(file_size),
             version: 0,
+            pending_save: None,
         })
     }
 
@@ -748,481 +1179,176 @@ impl TextBuffer {
             anyhow::bail!(io::Error::new(
                 io::ErrorKind::NotFound,
                 "No file path associated with buffer",
-            ))
-        }
-    }
-
-    /// Check if we should use in-place writing to preserve file ownership.
-    /// Returns true if the file exists and is owned by a different user.
-    /// On Unix, only root or the file owner can change file ownership with chown.
-    /// When the current user is not the file owner, using atomic write (temp file + rename)
-    /// would change the file's ownership to the current user. To preserve ownership,
-    /// we must write directly to the existing file instead.
-    fn should_use_inplace_write(&self, dest_path: &Path) -> bool {
-        !self.fs.is_owner(dest_path)
-    }
-
-    /// Build a write recipe from the piece tree for saving.
-    ///
-    /// This creates a recipe of Copy and Insert operations that can reconstruct
-    /// the buffer content. Copy operations reference unchanged regions in the
-    /// source file, while Insert operations contain new/modified data.
-    ///
-    /// # Returns
-    /// A WriteRecipe with the source path, insert data, and sequence of actions.
-    fn build_write_recipe(&self) -> io::Result<WriteRecipe> {
-        let total = self.total_bytes();
-
-        // Determine the source file for Copy operations (if any)
-        // We can only use Copy if:
-        // 1. We have a source file path
-        // 2. The source file exists
-        // 3. No line ending conversion is needed
-        // 4. No encoding conversion is needed
-        let needs_line_ending_conversion = self.line_ending != self.original_line_ending;
-        // We need encoding conversion if:
-        // - NOT a binary file (binary files preserve raw bytes), AND
-        // - Either the encoding changed from the original, OR
-        // - The target encoding isn't plain UTF-8/ASCII (since internal storage is UTF-8)
-        // For example: UTF-8 BOM files are stored as UTF-8, so we need to add BOM on save
-        let needs_encoding_conversion = !self.is_binary
-            && (self.encoding != self.original_encoding
-                || !matches!(self.encoding, Encoding::Utf8 | Encoding::Ascii));
-        let needs_conversion = needs_line_ending_conversion || needs_encoding_conversion;
-
-        let src_path_for_copy: Option<&Path> = if needs_conversion {
-            None
-        } else {
-            self.file_path.as_deref().filter(|p| self.fs.exists(p))
-        };
-        let target_ending = self.line_ending;
-        let target_encoding = self.encoding;
-
-        let mut insert_data: Vec<Vec<u8>> = Vec::new();
-        let mut actions: Vec<RecipeAction> = Vec::new();
-
-        // Add BOM as the first piece if the target encoding has one
-        if let Some(bom) = target_encoding.bom_bytes() {
-            insert_data.push(bom.to_vec());
-            actions.push(RecipeAction::Insert { index: 0 });
-        }
-
-        for piece_view in self.piece_tree.iter_pieces_in_range(0, total) {
-            let buffer_id = piece_view.location.buffer_id();
-            let buffer = self.buffers.get(buffer_id).ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    format!("Buffer {} not found", buffer_id),
-                )
-            })?;
-
-            match &buffer.data {
-                // Unloaded buffer: can use Copy if same source file, else load and send
-                BufferData::Unloaded {
-                    file_path,
-                    file_offset,
-                    ..
-                } => {
-                    // Can only use Copy if:
-                    // - This is a Stored piece (original file content)
-                    // - We have a valid source for copying
-                    // - This buffer is from that source
-                    // - No line ending or encoding conversion needed
-                    let can_copy = matches!(piece_view.location, BufferLocation::Stored(_))
-                        && src_path_for_copy.is_some_and(|src| file_path == src);
-
-                    if can_copy {
-                        let src_offset = (*file_offset + piece_view.buffer_offset) as u64;
-                        actions.push(RecipeAction::Copy {
-                            offset: src_offset,
-                            len: piece_view.bytes as u64,
-                        });
-                        continue;
-                    }
-
-                    // Need to load and send this unloaded region
-                    // This happens when: different source file, or conversion needed
-                    let data = self.fs.read_range(
-                        file_path,
-                        (*file_offset + piece_view.buffer_offset) as u64,
-                        piece_view.bytes,
-                    )?;
-
-                    let data = if needs_line_ending_conversion {
-                        Self::convert_line_endings_to(&data, target_ending)
-                    } else {
-                        data
-                    };
-
-                    // Convert encoding if needed
-                    let data = if needs_encoding_conversion {
-                        Self::convert_to_encoding(&data, target_encoding)
-                    } else {
-                        data
-                    };
-
-                    let index = insert_data.len();
-                    insert_data.push(data);
-                    actions.push(RecipeAction::Insert { index });
-                }
-
-                // Loaded data: send as Insert
-                BufferData::Loaded { data, .. } => {
-                    let start = piece_view.buffer_offset;
-                    let end = start + piece_view.bytes;
-                    let chunk = &data[start..end];
-
-                    let chunk = if needs_line_ending_conversion {
-                        Self::convert_line_endings_to(chunk, target_ending)
-                    } else {
-                        chunk.to_vec()
-                    };
-
-                    // Convert encoding if needed
-                    let chunk = if needs_encoding_conversion {
-                        Self::convert_to_encoding(&chunk, target_encoding)
-                    } else {
-                        chunk
-                    };
-
-                    let index = insert_data.len();
-                    insert_data.push(chunk);
-                    actions.push(RecipeAction::Insert { index });
-                }
-            }
-        }
-
-        Ok(WriteRecipe {
-            src_path: src_path_for_copy.map(|p| p.to_path_buf()),
-            insert_data,
-            actions,
-        })
-    }
-
-    /// Create a temporary file for saving.
-    ///
-    /// Tries to create the file in the same directory as the destination file first
-    /// to allow for an atomic rename. If that fails (e.g., due to directory permissions),
-    /// falls back to the system temporary directory.
-    fn create_temp_file(
-        &self,
-        dest_path: &Path,
-    ) -> io::Result<(PathBuf, Box<dyn crate::model::filesystem::FileWriter>)> {
-        // Try creating in same directory first
-        let same_dir_temp = self.fs.temp_path_for(dest_path);
-        match self.fs.create_file(&same_dir_temp) {
-            Ok(file) => Ok((same_dir_temp, file)),
-            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
-                // Fallback to system temp directory
-                let temp_path = self.fs.unique_temp_path(dest_path);
-                let file = self.fs.create_file(&temp_path)?;
-                Ok((temp_path, file))
-            }
-            Err(e) => Err(e),
-        }
-    }
-
-    /// Create a temporary file in the recovery directory for in-place writes.
-    /// This allows recovery if a crash occurs during the in-place write operation.
-    fn create_recovery_temp_file(
-        &self,
-        dest_path: &Path,
-    ) -> io::Result<(PathBuf, Box<dyn crate::model::filesystem::FileWriter>)> {
-        // Get recovery directory: $XDG_DATA_HOME/fresh/recovery or ~/.local/share/fresh/recovery
-        let recovery_dir = crate::input::input_history::get_data_dir()
-            .map(|d| d.join("recovery"))
-            .unwrap_or_else(|_| std::env::temp_dir());
-
-        // Ensure directory exists
-        self.fs.create_dir_all(&recovery_dir)?;
-
-        // Create unique filename based on destination file and timestamp
-        let file_name = dest_path
-            .file_name()
-            .unwrap_or_else(|| std::ffi::OsStr::new("fresh-save"));
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_nanos())
-            .unwrap_or(0);
-        let pid = std::process::id();
-
-        let temp_name = format!(
-            ".inplace-{}-{}-{}.tmp",
-            file_name.to_string_lossy(),
-            pid,
-            timestamp
-        );
-        let temp_path = recovery_dir.join(temp_name);
-
-        let file = self.fs.create_file(&temp_path)?;
-        Ok((temp_path, file))
-    }
-
-    /// Get the path for in-place write recovery metadata.
-    /// Uses the same recovery directory as temp files.
-    fn inplace_recovery_meta_path(&self, dest_path: &Path) -> PathBuf {
-        let recovery_dir = crate::input::input_history::get_data_dir()
-            .map(|d| d.join("recovery"))
-            .unwrap_or_else(|_| std::env::temp_dir());
-
-        let hash = crate::services::recovery::path_hash(dest_path);
-        recovery_dir.join(format!("{}.inplace.json", hash))
-    }
-
-    /// Write in-place recovery metadata using self.fs.
-    /// This is called before the dangerous streaming step so we can recover on crash.
-    fn write_inplace_recovery_meta(
-        &self,
-        meta_path: &Path,
-        dest_path: &Path,
-        temp_path: &Path,
-        original_metadata: &Option<FileMetadata>,
-    ) -> io::Result<()> {
-        #[cfg(unix)]
-        let (uid, gid, mode) = original_metadata
-            .as_ref()
-            .map(|m| {
-                (
-                    m.uid.unwrap_or(0),
-                    m.gid.unwrap_or(0),
-                    m.permissions.as_ref().map(|p| p.mode()).unwrap_or(0o644),
-                )
-            })
-            .unwrap_or((0, 0, 0o644));
-        #[cfg(not(unix))]
-        let (uid, gid, mode) = (0u32, 0u32, 0o644u32);
-
-        let recovery = crate::services::recovery::InplaceWriteRecovery::new(
-            dest_path.to_path_buf(),
-            temp_path.to_path_buf(),
-            uid,
-            gid,
-            mode,
-        );
-
-        let json = serde_json::to_string_pretty(&recovery)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        self.fs.write_file(meta_path, json.as_bytes())
-    }
-
-    /// Save the buffer to a specific file
-    ///
-    /// Uses the write recipe approach for both local and remote filesystems:
-    /// - Copy ops reference unchanged regions in the source file
-    /// - Insert ops contain new/modified data
-    ///
-    /// For remote filesystems, the recipe is sent to the agent which reconstructs
-    /// the file server-side, avoiding transfer of unchanged content.
-    ///
-    /// For local filesystems with ownership concerns (file owned by another user),
-    /// uses in-place writing to preserve ownership. Otherwise uses atomic writes.
-    ///
-    /// If the line ending format has been changed (via set_line_ending), all content
-    /// will be converted to the new format during save.
-    pub fn save_to_file<P: AsRef<Path>>(&mut self, path: P) -> anyhow::Result<()> {
-        let dest_path = path.as_ref();
-        let total = self.total_bytes();
-
-        // Handle empty files
-        if total == 0 {
-            self.fs.write_file(dest_path, &[])?;
-            self.finalize_save(dest_path)?;
-            return Ok(());
-        }
-
-        // Build the write recipe (unified for all filesystem types)
-        let recipe = self.build_write_recipe()?;
-        let ops = recipe.to_write_ops();
-
-        // Check if we need in-place writing to preserve file ownership (local only)
-        // Remote filesystems handle this differently
-        let is_local = self.fs.remote_connection_info().is_none();
-        let use_inplace = is_local && self.should_use_inplace_write(dest_path);
-
-        if use_inplace {
-            // In-place write: write directly to preserve ownership
-            self.save_with_inplace_write(dest_path, &recipe)?;
-        } else if !recipe.has_copy_ops() && !is_local {
-            // Remote with no Copy ops: use write_file directly (more efficient)
-            let data = recipe.flatten_inserts();
-            self.fs.write_file(dest_path, &data)?;
-        } else if is_local {
-            // Local: use write_file or write_patched with sudo fallback
-            let write_result = if !recipe.has_copy_ops() {
-                let data = recipe.flatten_inserts();
-                self.fs.write_file(dest_path, &data)
-            } else {
-                let src_for_patch = recipe.src_path.as_deref().unwrap_or(dest_path);
-                self.fs.write_patched(src_for_patch, dest_path, &ops)
-            };
-
-            if let Err(e) = write_result {
-                if e.kind() == io::ErrorKind::PermissionDenied {
-                    // Create temp file and return sudo error
-                    let original_metadata = self.fs.metadata_if_exists(dest_path);
-                    let (temp_path, mut temp_file) = self.create_temp_file(dest_path)?;
-                    self.write_recipe_to_file(&mut temp_file, &recipe)?;
-                    temp_file.sync_all()?;
-                    drop(temp_file);
-                    return Err(self.make_sudo_error(temp_path, dest_path, original_metadata));
-                }
-                return Err(e.into());
-            }
-        } else {
-            // Remote with Copy ops: use write_patched
-            let src_for_patch = recipe.src_path.as_deref().unwrap_or(dest_path);
-            self.fs.write_patched(src_for_patch, dest_path, &ops)?;
-        }
-
-        self.finalize_save(dest_path)?;
-        Ok(())
-    }
-
-    /// Write using in-place mode to preserve file ownership.
-    ///
-    /// This is used when the file is owned by a different user and we need
-    /// to write directly to the existing file to preserve its ownership.
-    ///
-    /// The approach:
-    /// 1. Write the recipe to a temp file first (reads from original, writes to temp)
-    /// 2. Stream the temp file content to the destination file (truncates and writes)
-    /// 3. Delete the temp file
-    ///
-    /// This avoids the bug where truncating the destination before reading Copy chunks
-    /// would corrupt the file. It also works for huge files since we stream in chunks.
-    fn save_with_inplace_write(
-        &self,
-        dest_path: &Path,
-        recipe: &WriteRecipe,
-    ) -> anyhow::Result<()> {
-        let original_metadata = self.fs.metadata_if_exists(dest_path);
-
-        // Optimization: if no Copy ops, we can write directly without a temp file
-        // (same as the non-inplace path for small files)
-        if !recipe.has_copy_ops() {
-            let data = recipe.flatten_inserts();
-            return self.write_data_inplace(dest_path, &data, original_metadata);
-        }
-
-        // Step 1: Write recipe to a temp file in the recovery directory
-        // This reads Copy chunks from the original file (still intact) and writes to temp.
-        // Using the recovery directory allows crash recovery if the operation fails.
-        let (temp_path, mut temp_file) = self.create_recovery_temp_file(dest_path)?;
-        if let Err(e) = self.write_recipe_to_file(&mut temp_file, recipe) {
-            let _ = self.fs.remove_file(&temp_path);
-            return Err(e.into());
-        }
-        temp_file.sync_all()?;
-        drop(temp_file);
-
-        // Step 1.5: Save recovery metadata before the dangerous step
-        // If we crash during step 2, this metadata + temp file allows recovery
-        let recovery_meta_path = self.inplace_recovery_meta_path(dest_path);
-        // Best effort - don't fail the save if we can't write recovery metadata
-        let _ = self.write_inplace_recovery_meta(
-            &recovery_meta_path,
-            dest_path,
-            &temp_path,
-            &original_metadata,
-        );
-
-        // Step 2: Stream temp file content to destination
-        // Now it's safe to truncate the destination since all data is in temp
-        match self.fs.open_file_for_write(dest_path) {
-            Ok(mut out_file) => {
-                if let Err(e) = self.stream_file_to_writer(&temp_path, &mut out_file) {
-                    // Don't delete temp file or recovery metadata - allow recovery
-                    return Err(e.into());
-                }
-                out_file.sync_all()?;
-                // Success! Clean up temp file and recovery metadata
-                let _ = self.fs.remove_file(&temp_path);
-                let _ = self.fs.remove_file(&recovery_meta_path);
-                Ok(())
-            }
-            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
-                // Can't write to destination - trigger sudo fallback
-                // Keep temp file for sudo to use, clean up recovery metadata
-                let _ = self.fs.remove_file(&recovery_meta_path);
-                Err(self.make_sudo_error(temp_path, dest_path, original_metadata))
-            }
-            Err(e) => {
-                // Don't delete temp file or recovery metadata - allow recovery
-                Err(e.into())
-            }
-        }
-    }
-
-    /// Write data directly to a file in-place, with sudo fallback on permission denied.
-    fn write_data_inplace(
-        &self,
-        dest_path: &Path,
-        data: &[u8],
-        original_metadata: Option<FileMetadata>,
-    ) -> anyhow::Result<()> {
-        match self.fs.open_file_for_write(dest_path) {
-            Ok(mut out_file) => {
-                out_file.write_all(data)?;
-                out_file.sync_all()?;
-                Ok(())
-            }
-            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
-                // Create temp file for sudo fallback
-                let (temp_path, mut temp_file) = self.create_temp_file(dest_path)?;
-                temp_file.write_all(data)?;
-                temp_file.sync_all()?;
-                drop(temp_file);
-                Err(self.make_sudo_error(temp_path, dest_path, original_metadata))
-            }
-            Err(e) => Err(e.into()),
+            ))
         }
     }
 
-    /// Stream a file's content to a writer in chunks to avoid memory issues with large files.
-    fn stream_file_to_writer(
-        &self,
-        src_path: &Path,
-        out_file: &mut Box<dyn crate::model::filesystem::FileWriter>,
-    ) -> io::Result<()> {
-        const CHUNK_SIZE: usize = 1024 * 1024; // 1MB chunks
+    /// Save the buffer to a specific file
+    ///
+    /// If the line ending format has been changed (via set_line_ending), all content
+    /// will be converted to the new format during save.
+    pub fn save_to_file<P: AsRef<Path>>(&mut self, path: P) -> anyhow::Result<()> {
+        let dest_path = path.as_ref();
+        self.prepare_save(dest_path)?.write()?;
+        self.finalize_save(dest_path)
+    }
 
-        let file_size = self.fs.metadata(src_path)?.size as u64;
-        let mut offset = 0u64;
+    /// Prepare saving the buffer to `path` without writing anything yet
+    ///
+    /// The returned job captures the current content, so edits made while it
+    /// is written are not part of the save.
+    pub fn prepare_save(&self, path: impl AsRef<Path>) -> io::Result<SaveJob> {
+        let recipe = if self.total_bytes() == 0 {
+            WriteRecipe {
+                src_path: None,
+                insert_data: Vec::new(),
+                actions: Vec::new(),
+            }
+        } else {
+            self.build_write_recipe()?
+        };
+        Ok(SaveJob {
+            fs: Arc::clone(&self.fs),
+            dest_path: path.as_ref().to_path_buf(),
+            recipe,
+        })
+    }
 
-        while offset < file_size {
-            let remaining = file_size - offset;
-            let chunk_len = std::cmp::min(remaining, CHUNK_SIZE as u64) as usize;
-            let chunk = self.fs.read_range(src_path, offset, chunk_len)?;
-            out_file.write_all(&chunk)?;
-            offset += chunk_len as u64;
+    /// Build a write recipe from the piece tree for saving.
+    ///
+    /// This creates a recipe of Copy and Insert operations that can reconstruct
+    /// the buffer content. Copy operations reference unchanged regions in the
+    /// source file, while Insert operations contain new/modified data.
+    ///
+    /// # Returns
+    /// A WriteRecipe with the source path, insert data, and sequence of actions.
+    fn build_write_recipe(&self) -> io::Result<WriteRecipe> {
+        let total = self.total_bytes();
+
+        // Determine the source file for Copy operations (if any)
+        // We can only use Copy if:
+        // 1. We have a source file path
+        // 2. The source file exists
+        // 3. No line ending conversion is needed
+        // 4. No encoding conversion is needed
+        let needs_line_ending_conversion = self.line_ending != self.original_line_ending;
+        // We need encoding conversion if:
+        // - NOT a binary file (binary files preserve raw bytes), AND
+        // - Either the encoding changed from the original, OR
+        // - The target encoding isn't plain UTF-8/ASCII (since internal storage is UTF-8)
+        // For example: UTF-8 BOM files are stored as UTF-8, so we need to add BOM on save
+        let needs_encoding_conversion = !self.is_binary
+            && (self.encoding != self.original_encoding
+                || !matches!(self.encoding, Encoding::Utf8 | Encoding::Ascii));
+        let needs_conversion = needs_line_ending_conversion || needs_encoding_conversion;
+
+        let src_path_for_copy: Option<&Path> = if needs_conversion {
+            None
+        } else {
+            self.file_path.as_deref().filter(|p| self.fs.exists(p))
+        };
+        let target_ending = self.line_ending;
+        let target_encoding = self.encoding;
+
+        let mut insert_data: Vec<Vec<u8>> = Vec::new();
+        let mut actions: Vec<RecipeAction> = Vec::new();
+
+        // Add BOM as the first piece if the target encoding has one
+        if let Some(bom) = target_encoding.bom_bytes() {
+            insert_data.push(bom.to_vec());
+            actions.push(RecipeAction::Insert { index: 0 });
         }
 
-        Ok(())
-    }
+        for piece_view in self.piece_tree.iter_pieces_in_range(0, total) {
+            let buffer_id = piece_view.location.buffer_id();
+            let buffer = self.buffers.get(buffer_id).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Buffer {} not found", buffer_id),
+                )
+            })?;
 
-    /// Write the recipe content to a file writer.
-    fn write_recipe_to_file(
-        &self,
-        out_file: &mut Box<dyn crate::model::filesystem::FileWriter>,
-        recipe: &WriteRecipe,
-    ) -> io::Result<()> {
-        for action in &recipe.actions {
-            match action {
-                RecipeAction::Copy { offset, len } => {
-                    // Read from source and write to output
-                    let src_path = recipe.src_path.as_ref().ok_or_else(|| {
-                        io::Error::new(io::ErrorKind::InvalidData, "Copy action without source")
-                    })?;
-                    let data = self.fs.read_range(src_path, *offset, *len as usize)?;
-                    out_file.write_all(&data)?;
+            match &buffer.data {
+                // Unloaded buffer: can use Copy if same source file, else load and send
+                BufferData::Unloaded {
+                    file_path,
+                    file_offset,
+                    ..
+                } => {
+                    // Can only use Copy if:
+                    // - This is a Stored piece (original file content)
+                    // - We have a valid source for copying
+                    // - This buffer is from that source
+                    // - No line ending or encoding conversion needed
+                    let can_copy = matches!(piece_view.location, BufferLocation::Stored(_))
+                        && src_path_for_copy.is_some_and(|src| file_path == src);
+
+                    if can_copy {
+                        let src_offset = (*file_offset + piece_view.buffer_offset) as u64;
+                        actions.push(RecipeAction::Copy {
+                            offset: src_offset,
+                            len: piece_view.bytes as u64,
+                        });
+                        continue;
+                    }
+
+                    // Need to load and send this unloaded region
+                    // This happens when: different source file, or conversion needed
+                    let data = self.fs.read_range(
+                        file_path,
+                        (*file_offset + piece_view.buffer_offset) as u64,
+                        piece_view.bytes,
+                    )?;
+
+                    let data = if needs_line_ending_conversion {
+                        Self::convert_line_endings_to(&data, target_ending)
+                    } else {
+                        data
+                    };
+
+                    // Convert encoding if needed
+                    let data = if needs_encoding_conversion {
+                        Self::convert_to_encoding(&data, target_encoding)
+                    } else {
+                        data
+                    };
+
+                    let index = insert_data.len();
+                    insert_data.push(data);
+                    actions.push(RecipeAction::Insert { index });
                 }
-                RecipeAction::Insert { index } => {
-                    out_file.write_all(&recipe.insert_data[*index])?;
+
+                // Loaded data: send as Insert
+                BufferData::Loaded { data, .. } => {
+                    let start = piece_view.buffer_offset;
+                    let end = start + piece_view.bytes;
+                    let chunk = &data[start..end];
+
+                    let chunk = if needs_line_ending_conversion {
+                        Self::convert_line_endings_to(chunk, target_ending)
+                    } else {
+                        chunk.to_vec()
+                    };
+
+                    // Convert encoding if needed
+                    let chunk = if needs_encoding_conversion {
+                        Self::convert_to_encoding(&chunk, target_encoding)
+                    } else {
+                        chunk
+                    };
+
+                    let index = insert_data.len();
+                    insert_data.push(chunk);
+                    actions.push(RecipeAction::Insert { index });
                 }
             }
         }
-        Ok(())
+
+        Ok(WriteRecipe {
+            src_path: src_path_for_copy.map(|p| p.to_path_buf()),
+            insert_data,
+            actions,
+        })
     }
 
     /// Finalize save state after successful write.
@@ -1246,20 +1372,109 @@ impl TextBuffer {
         Ok(())
     }
 
+    /// Start a save whose [`SaveJob`] is written elsewhere, e.g. on another
+    /// thread
+    ///
+    /// Until [`Self::finalize_external_save`] or [`Self::abort_external_save`],
+    /// unloaded regions of `dest_path` can't be loaded, since the file is being
+    /// replaced, and edits are recorded so they can be replayed onto the saved
+    /// file.
+    pub fn begin_external_save(&mut self, dest_path: &Path) {
+        self.pending_save = Some(PendingSave {
+            dest_path: dest_path.to_path_buf(),
+            edits: Vec::new(),
+            replayable: true,
+        });
+    }
+
+    /// Whether a save started with [`Self::begin_external_save`] is in progress
+    pub fn is_saving(&self) -> bool {
+        self.pending_save.is_some()
+    }
+
+    /// End a save started with [`Self::begin_external_save`] that failed.
+    /// The edits made meanwhile are kept as they are.
+    pub fn abort_external_save(&mut self) {
+        self.pending_save = None;
+    }
+
     /// Finalize buffer state after an external save operation (e.g., via sudo).
     ///
     /// This updates the saved snapshot and file size to match the new state on disk.
+    /// Edits made since [`Self::begin_external_save`] are not part of the file:
+    /// they are replayed onto it and the buffer stays modified.
     pub fn finalize_external_save(&mut self, dest_path: PathBuf) -> anyhow::Result<()> {
+        let pending = self.pending_save.take();
         let new_size = self.fs.metadata(&dest_path)?.size as usize;
         self.saved_file_size = Some(new_size);
         self.file_path = Some(dest_path.clone());
+        self.original_line_ending = self.line_ending;
+        self.original_encoding = self.encoding;
+
+        let (edits, replayable) = match pending {
+            Some(save) => (save.edits, save.replayable),
+            None => (Vec::new(), true),
+        };
+        let changed = !edits.is_empty() || !replayable;
+        if changed && !self.large_file {
+            // Everything is in memory, so the buffer stays as it is
+            return Ok(());
+        }
+        anyhow::ensure!(
+            replayable,
+            "the buffer changed during the save in a way that can't be applied to the saved file"
+        );
 
         // Consolidate the piece tree to synchronize with disk or simplify structure.
+        // Unloaded regions of a large file are read from the new file from now on,
+        // so edits made during the save are replayed on top of it.
         self.consolidate_after_save(&dest_path, new_size);
-
         self.mark_saved_snapshot();
-        self.original_line_ending = self.line_ending;
-        self.original_encoding = self.encoding;
+        for edit in edits {
+            self.replay_pending_edit(edit);
+        }
+        Ok(())
+    }
+
+    /// Record an edit if a save is in progress
+    fn record_pending_edit(&mut self, edit: impl FnOnce(&Self) -> PendingEdit) {
+        if self.pending_save.is_some() {
+            let edit = edit(self);
+            if let Some(save) = &mut self.pending_save {
+                save.edits.push(edit);
+            }
+        }
+    }
+
+    fn replay_pending_edit(&mut self, edit: PendingEdit) {
+        match edit {
+            PendingEdit::Insert { offset, text } => {
+                self.insert_bytes(offset, text);
+            }
+            PendingEdit::Delete { offset, bytes } => self.delete_bytes(offset, bytes),
+            PendingEdit::Bulk(edits) => {
+                let edits: Vec<(usize, usize, &str)> = edits
+                    .iter()
+                    .map(|(pos, len, text)| (*pos, *len, text.as_str()))
+                    .collect();
+                self.apply_bulk_edits(&edits);
+            }
+            PendingEdit::Replace(content) => self.replace_content(&content),
+        }
+    }
+
+    /// Fail instead of loading a region of a file a save is replacing
+    fn ensure_loadable(&self, buffer_id: usize) -> Result<()> {
+        let (Some(save), Some(buffer)) = (&self.pending_save, self.buffers.get(buffer_id)) else {
+            return Ok(());
+        };
+        if let BufferData::Unloaded { file_path, .. } = &buffer.data {
+            anyhow::ensure!(
+                file_path != &save.dest_path,
+                "{} is being saved",
+                file_path.display()
+            );
+        }
         Ok(())
     }
 
@@ -1326,40 +1541,6 @@ impl TextBuffer {
         }
     }
 
-    /// Internal helper to create a SudoSaveRequired error.
-    fn make_sudo_error(
-        &self,
-        temp_path: PathBuf,
-        dest_path: &Path,
-        original_metadata: Option<FileMetadata>,
-    ) -> anyhow::Error {
-        #[cfg(unix)]
-        let (uid, gid, mode) = if let Some(ref meta) = original_metadata {
-            (
-                meta.uid.unwrap_or(0),
-                meta.gid.unwrap_or(0),
-                meta.permissions
-                    .as_ref()
-                    .map(|p| p.mode() & 0o7777)
-                    .unwrap_or(0),
-            )
-        } else {
-            (0, 0, 0)
-        };
-        #[cfg(not(unix))]
-        let (uid, gid, mode) = (0u32, 0u32, 0u32);
-
-        let _ = original_metadata; // suppress unused warning on non-Unix
-
-        anyhow::anyhow!(SudoSaveRequired {
-            temp_path,
-            dest_path: dest_path.to_path_buf(),
-            uid,
-            gid,
-            mode,
-        })
-    }
-
     /// Get the total number of bytes in the document
     pub fn total_bytes(&self) -> usize {
         self.piece_tree.total_bytes()
@@ -1605,6 +1786,10 @@ impl TextBuffer {
         if text.is_empty() {
             return self.piece_tree.cursor_at_offset(offset);
         }
+        self.record_pending_edit(|_| PendingEdit::Insert {
+            offset,
+            text: text.clone(),
+        });
 
         // Mark as modified (updates version)
         self.mark_content_modified();
@@ -1694,6 +1879,10 @@ impl TextBuffer {
             let offset = self.position_to_offset(position);
             return self.piece_tree.cursor_at_offset(offset);
         }
+        self.record_pending_edit(|buffer| PendingEdit::Insert {
+            offset: buffer.position_to_offset(position),
+            text: text.clone(),
+        });
 
         self.mark_content_modified();
 
@@ -1723,6 +1912,7 @@ impl TextBuffer {
         if bytes == 0 || offset >= self.total_bytes() {
             return;
         }
+        self.record_pending_edit(|_| PendingEdit::Delete { offset, bytes });
 
         // Update piece tree
         self.piece_tree.delete(offset, bytes, &self.buffers);
@@ -1740,6 +1930,13 @@ impl TextBuffer {
     /// Delete text in a line/column range
     /// This now uses the optimized piece_tree.delete_position_range() for a single traversal
     pub fn delete_range(&mut self, start: Position, end: Position) {
+        self.record_pending_edit(|buffer| {
+            let offset = buffer.position_to_offset(start);
+            PendingEdit::Delete {
+                offset,
+                bytes: buffer.position_to_offset(end).saturating_sub(offset),
+            }
+        });
         // Use the optimized position-based deletion
         self.piece_tree.delete_position_range(
             start.line,
@@ -1758,6 +1955,7 @@ impl TextBuffer {
     /// This is used for bulk operations like "replace all" where applying
     /// individual edits would be prohibitively slow.
     pub fn replace_content(&mut self, new_content: &str) {
+        self.record_pending_edit(|_| PendingEdit::Replace(new_content.to_string()));
         let bytes = new_content.len();
         let content_bytes = new_content.as_bytes().to_vec();
 
@@ -1788,6 +1986,9 @@ impl TextBuffer {
     /// Restore a previously saved piece tree (for undo of BulkEdit)
     /// This is O(1) because PieceTree uses Arc internally
     pub fn restore_piece_tree(&mut self, tree: &Arc<PieceTree>) {
+        if let Some(save) = &mut self.pending_save {
+            save.replayable = false;
+        }
         self.piece_tree = (**tree).clone();
         self.mark_content_modified();
     }
@@ -1801,6 +2002,14 @@ impl TextBuffer {
     /// Apply bulk edits efficiently in a single pass
     /// Returns the net change in bytes
     pub fn apply_bulk_edits(&mut self, edits: &[(usize, usize, &str)]) -> isize {
+        self.record_pending_edit(|_| {
+            PendingEdit::Bulk(
+                edits
+                    .iter()
+                    .map(|(pos, len, text)| (*pos, *len, text.to_string()))
+                    .collect(),
+            )
+        });
         // Pre-allocate buffers for all insert texts (only non-empty texts)
         // This avoids the borrow conflict in the closure
         // IMPORTANT: Only add entries for non-empty texts because the closure
@@ -1923,6 +2132,7 @@ impl TextBuffer {
                     .unwrap_or(false);
 
                 if needs_loading {
+                    self.ensure_loadable(buffer_id)?;
                     // Check if piece is too large for full loading
                     if piece_view.bytes > LOAD_CHUNK_SIZE {
                         // Split large piece into chunks
@@ -2504,6 +2714,19 @@ impl TextBuffer {
         self.piece_tree.stats()
     }
 
+    /// Estimated bytes held by the buffer: loaded text, line indexes and
+    /// piece tree nodes (regions still on disk aren't counted)
+    pub fn memory_usage(&self) -> usize {
+        let leaves = self.piece_tree.stats().leaf_count;
+        let nodes = (2 * leaves).saturating_sub(1);
+        self.buffers
+            .iter()
+            .map(StringBuffer::heap_size)
+            .sum::<usize>()
+            + self.buffers.capacity() * std::mem::size_of::<StringBuffer>()
+            + nodes * std::mem::size_of::<crate::model::piece_tree::PieceTreeNode>()
+    }
+
     // Search and Replace Operations
 
     /// Find the next occurrence of a pattern, with wrap-around
@@ -4861,7 +5084,7 @@ mod tests {
         #[cfg(unix)]
         fn test_save_to_unwritable_directory() -> anyhow::Result<()> {
             use std::fs::Permissions;
-            use std::os::unix::fs::PermissionsExt;
+            use std::os::unix::fs::{MetadataExt, PermissionsExt};
             use tempfile::TempDir;
 
             let temp_dir = TempDir::new().unwrap();
@@ -4881,6 +5104,11 @@ mod tests {
                     if let Some(sudo_err) = e.downcast_ref::<SudoSaveRequired>() {
                         assert_eq!(sudo_err.dest_path, file_path);
                         assert!(sudo_err.temp_path.exists());
+                        // The new file takes the directory's owner
+                        let dir_meta = std::fs::metadata(&unwritable_dir)?;
+                        assert_eq!(sudo_err.uid, dir_meta.uid());
+                        assert_eq!(sudo_err.gid, dir_meta.gid());
+                        assert_eq!(sudo_err.mode, 0o644);
                         // It should be in /tmp because the directory was not writable
                         assert!(sudo_err.temp_path.starts_with(std::env::temp_dir()));
                         // Cleanup
@@ -490,6 +490,32 @@ impl Event {
             _ => None,
         }
     }
+
+    /// Bytes this event owns outside of itself
+    fn heap_size(&self) -> usize {
+        let cursors = |list: &Vec<(CursorId, usize, Option<usize>)>| {
+            list.capacity() * std::mem::size_of::<(CursorId, usize, Option<usize>)>()
+        };
+        match self {
+            Self::Insert { text, .. } => text.capacity(),
+            Self::Delete { deleted_text, .. } => deleted_text.capacity(),
+            Self::Batch {
+                events,
+                description,
+            } => {
+                events.capacity() * std::mem::size_of::<Event>()
+                    + events.iter().map(Event::heap_size).sum::<usize>()
+                    + description.capacity()
+            }
+            Self::BulkEdit {
+                old_cursors,
+                new_cursors,
+                description,
+                ..
+            } => cursors(old_cursors) + cursors(new_cursors) + description.capacity(),
+            _ => 0,
+        }
+    }
 }
 
 /// A log entry containing an event and metadata
@@ -744,6 +770,23 @@ impl EventLog {
         self.entries.is_empty()
     }
 
+    /// Estimated bytes held by the log: its entries plus the text and
+    /// cursor lists they own
+    ///
+    /// Piece trees kept by bulk edits share most of their nodes with the
+    /// buffer, so only the handles are counted.
+    pub fn memory_usage(&self) -> usize {
+        self.entries.capacity() * std::mem::size_of::<LogEntry>()
+            + self.snapshots.capacity() * std::mem::size_of::<Snapshot>()
+            + self
+                .entries
+                .iter()
+                .map(|entry| {
+                    entry.event.heap_size() + entry.description.as_ref().map_or(0, String::capacity)
+                })
+                .sum::<usize>()
+    }
+
     /// Can we undo?
     pub fn can_undo(&self) -> bool {
         self.current_index > 0
@@ -1154,4 +1197,29 @@ mod tests {
             "Should not be at saved position after undo + new edit"
         );
     }
+
+    #[test]
+    fn test_memory_usage_counts_text_held_by_events() {
+        let mut log = EventLog::new();
+        let empty = log.memory_usage();
+
+        let text = "x".repeat(10_000);
+        log.append(Event::Batch {
+            events: vec![
+                Event::Insert {
+                    position: 0,
+                    text: text.clone(),
+                    cursor_id: CursorId(0),
+                },
+                Event::Delete {
+                    range: 0..10_000,
+                    deleted_text: text,
+                    cursor_id: CursorId(1),
+                },
+            ],
+            description: "replace".to_string(),
+        });
+
+        assert!(log.memory_usage() >= empty + 20_000);
+    }
 }
@@ -38,6 +38,8 @@ pub struct DirEntry {
     pub metadata: Option<FileMetadata>,
     /// For symlinks, whether the target is a directory
     pub symlink_target_is_dir: bool,
+    /// For symlinks, the path the link points to, as stored in the link
+    pub symlink_target: Option<PathBuf>,
 }
 
 impl DirEntry {
@@ -49,6 +51,7 @@ impl DirEntry {
             entry_type,
             metadata: None,
             symlink_target_is_dir: false,
+            symlink_target: None,
         }
     }
 
@@ -60,6 +63,7 @@ impl DirEntry {
             entry_type: EntryType::Symlink,
             metadata: None,
             symlink_target_is_dir: target_is_dir,
+            symlink_target: None,
         }
     }
 
@@ -110,6 +114,17 @@ pub struct FileMetadata {
     /// File owner GID (Unix only)
     #[cfg(unix)]
     pub gid: Option<u32>,
+    /// Number of names (hard links) the file has
+    pub hard_links: Option<u64>,
+    /// Identity of the file, shared by all of its names
+    pub file_id: Option<FileId>,
+}
+
+/// Device and inode of a file: two paths with the same id are the same file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileId {
+    pub device: u64,
+    pub inode: u64,
 }
 
 impl FileMetadata {
@@ -125,6 +140,8 @@ impl FileMetadata {
             uid: None,
             #[cfg(unix)]
             gid: None,
+            hard_links: None,
+            file_id: None,
         }
     }
 
@@ -317,6 +334,9 @@ pub trait FileSystem: Send + Sync {
     fn read_range(&self, path: &Path, offset: u64, len: usize) -> io::Result<Vec<u8>>;
 
     /// Write data to file atomically (temp file + rename)
+    ///
+    /// A crash or failed write leaves the file with either its old or its new
+    /// content, never a mix.
     fn write_file(&self, path: &Path, data: &[u8]) -> io::Result<()>;
 
     /// Create a file for writing, returns a writer handle
@@ -381,6 +401,51 @@ pub trait FileSystem: Send + Sync {
     /// Remove an empty directory
     fn remove_dir(&self, path: &Path) -> io::Result<()>;
 
+    /// Move a file or directory to the trash instead of deleting it
+    ///
+    /// The default renames it into `.local/share/fresh/trash/` under
+    /// [`FileSystem::home_dir`], which works for any backend that can rename.
+    fn move_to_trash(&self, path: &Path) -> io::Result<TrashedPath> {
+        let trash_dir = self.home_dir()?.join(".local/share/fresh/trash");
+        self.create_dir_all(&trash_dir)?;
+
+        // Timestamp suffix so repeated deletes of one name don't collide
+        let file_name = path
+            .file_name()
+            .unwrap_or_else(|| std::ffi::OsStr::new("unnamed"));
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let location = trash_dir.join(format!("{}.{}", file_name.to_string_lossy(), timestamp));
+        self.rename(path, &location)?;
+        Ok(TrashedPath {
+            original: path.to_path_buf(),
+            restore: Restore::Rename(location),
+        })
+    }
+
+    /// Put back what [`FileSystem::move_to_trash`] moved
+    ///
+    /// Fails rather than overwrite whatever is at the original path now.
+    fn restore_from_trash(&self, trashed: &TrashedPath) -> io::Result<()> {
+        if self.symlink_metadata(&trashed.original).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", trashed.original.display()),
+            ));
+        }
+        match &trashed.restore {
+            Restore::Rename(location) => self.rename(location, &trashed.original),
+            #[cfg(feature = "runtime")]
+            Restore::System(item) => system_trash::restore(item),
+            Restore::Unavailable => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the system trash can't restore files on this platform",
+            )),
+        }
+    }
+
     // ========================================================================
     // Metadata Operations
     // ========================================================================
@@ -391,6 +456,16 @@ pub trait FileSystem: Send + Sync {
     /// Get symlink metadata (doesn't follow symlinks)
     fn symlink_metadata(&self, path: &Path) -> io::Result<FileMetadata>;
 
+    /// Read the path a symlink points to, as stored in the link
+    ///
+    /// Fails if `path` isn't a symlink.
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("reading links is not supported here: {}", path.display()),
+        ))
+    }
+
     /// Check if path exists
     fn exists(&self, path: &Path) -> bool {
         self.metadata(path).is_ok()
@@ -455,9 +530,53 @@ pub trait FileSystem: Send + Sync {
         }
     }
 
+    /// Check if the current user may write the file
+    ///
+    /// Group membership isn't looked up: a file not owned by the user counts
+    /// as writable when its group or others may write it. Returns true when
+    /// the metadata can't be read, so a failed check never blocks editing.
+    fn is_writable(&self, path: &Path) -> bool {
+        let Ok(meta) = self.metadata(path) else {
+            return true;
+        };
+        #[cfg(unix)]
+        {
+            let Some(mode) = meta.permissions.as_ref().map(FilePermissions::mode) else {
+                return true;
+            };
+            if self.current_uid() == 0 {
+                true
+            } else if self.is_owner(path) {
+                mode & 0o200 != 0
+            } else {
+                mode & 0o022 != 0
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            !meta.is_readonly
+        }
+    }
+
     /// Get a temporary file path for atomic writes
+    ///
+    /// A hidden sibling of `path` with a unique name, so the rename stays on
+    /// one filesystem and no other file (like `notes.tmp` for `notes.txt`)
+    /// is ever overwritten.
     fn temp_path_for(&self, path: &Path) -> PathBuf {
-        path.with_extension("tmp")
+        let file_name = path
+            .file_name()
+            .unwrap_or_else(|| std::ffi::OsStr::new("fresh-save"));
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        path.with_file_name(format!(
+            ".{}.{}-{}.tmp",
+            file_name.to_string_lossy(),
+            std::process::id(),
+            timestamp
+        ))
     }
 
     /// Get a unique temporary file path (using timestamp and PID)
@@ -490,6 +609,14 @@ pub trait FileSystem: Send + Sync {
         None
     }
 
+    /// Whether the connection backing this filesystem is currently up.
+    ///
+    /// Always `true` for local filesystems. Remote filesystems report `false`
+    /// while the SSH link is down and being re-established.
+    fn is_remote_connected(&self) -> bool {
+        true
+    }
+
     /// Get the home directory for this filesystem
     ///
     /// For local filesystems, returns the local home directory.
@@ -499,18 +626,154 @@ pub trait FileSystem: Send + Sync {
             .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "home directory not found"))
     }
 
-    /// Write file using sudo (for root-owned files).
+    /// Write file with elevated rights (for root-owned files).
     ///
-    /// This writes the file with elevated privileges, preserving the specified
-    /// permissions and ownership. Used when normal write fails due to permissions.
+    /// The data is piped through `program tee` (`sudo` or `pkexec`) in a single
+    /// elevated command. An existing file is written in place and keeps its
+    /// permissions and ownership; a new file is given the ones passed here.
+    /// Used when normal write fails due to permissions.
     ///
+    /// - `program`: Elevation program, `sudo` or `pkexec`
     /// - `path`: Destination file path
     /// - `data`: File contents to write
-    /// - `mode`: File permissions (e.g., 0o644)
-    /// - `uid`: Owner user ID
-    /// - `gid`: Owner group ID
-    fn sudo_write(&self, path: &Path, data: &[u8], mode: u32, uid: u32, gid: u32)
-        -> io::Result<()>;
+    /// - `mode`: Permissions for a new file (e.g., 0o644)
+    /// - `uid`: Owner user ID for a new file
+    /// - `gid`: Owner group ID for a new file
+    fn sudo_write(
+        &self,
+        program: &str,
+        path: &Path,
+        data: &[u8],
+        mode: u32,
+        uid: u32,
+        gid: u32,
+    ) -> io::Result<()>;
+}
+
+// ============================================================================
+// Trash
+// ============================================================================
+
+/// A file or directory moved to the trash by [`FileSystem::move_to_trash`]
+#[derive(Debug, Clone)]
+pub struct TrashedPath {
+    /// Where it was before it was trashed
+    pub original: PathBuf,
+    restore: Restore,
+}
+
+impl TrashedPath {
+    /// Whether [`FileSystem::restore_from_trash`] can put it back
+    pub fn can_restore(&self) -> bool {
+        !matches!(self.restore, Restore::Unavailable)
+    }
+}
+
+/// How to get a trashed path back
+#[derive(Debug, Clone)]
+enum Restore {
+    /// Renamed to this path in the editor's own trash directory
+    Rename(PathBuf),
+    /// In the desktop trash
+    #[cfg(feature = "runtime")]
+    System(system_trash::Item),
+    /// In a desktop trash that can't restore it (macOS)
+    Unavailable,
+}
+
+/// The desktop trash (freedesktop.org trash, Recycle Bin, macOS Trash)
+#[cfg(feature = "runtime")]
+mod system_trash {
+    use std::io;
+    use std::path::Path;
+
+    pub use platform::{restore, Item};
+
+    /// Trash `path` (absolute) and return what's needed to restore it
+    pub fn delete(path: &Path) -> io::Result<Option<Item>> {
+        let item = platform::item(path);
+        trash::delete(path).map_err(io::Error::other)?;
+        Ok(item)
+    }
+
+    /// Trashes that can be listed and restored from
+    #[cfg(any(
+        target_os = "windows",
+        all(
+            unix,
+            not(target_os = "macos"),
+            not(target_os = "ios"),
+            not(target_os = "android")
+        )
+    ))]
+    mod platform {
+        use std::io;
+        use std::path::{Path, PathBuf};
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        /// A path about to be trashed
+        ///
+        /// Listing the trash reads every entry in it, so the entry is only
+        /// looked up when the path is restored.
+        #[derive(Debug, Clone)]
+        pub struct Item {
+            original: PathBuf,
+            /// Seconds since the epoch just before the path was trashed
+            deleted_after: i64,
+        }
+
+        pub fn item(path: &Path) -> Option<Item> {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+            Some(Item {
+                original: path.to_path_buf(),
+                deleted_after: now.as_secs() as i64,
+            })
+        }
+
+        pub fn restore(item: &Item) -> io::Result<()> {
+            // The newest entry for the path trashed since then is ours
+            let entry = trash::os_limited::list()
+                .map_err(io::Error::other)?
+                .into_iter()
+                .filter(|entry| {
+                    entry.original_path() == item.original
+                        && entry.time_deleted >= item.deleted_after
+                })
+                .max_by_key(|entry| entry.time_deleted)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("{} is no longer in the trash", item.original.display()),
+                    )
+                })?;
+            trash::os_limited::restore_all([entry]).map_err(io::Error::other)
+        }
+    }
+
+    /// The macOS Trash can't be listed, so nothing in it can be restored
+    #[cfg(not(any(
+        target_os = "windows",
+        all(
+            unix,
+            not(target_os = "macos"),
+            not(target_os = "ios"),
+            not(target_os = "android")
+        )
+    )))]
+    mod platform {
+        use std::io;
+        use std::path::Path;
+
+        pub type Item = std::convert::Infallible;
+
+        pub fn item(_path: &Path) -> Option<Item> {
+            None
+        }
+
+        pub fn restore(item: &Item) -> io::Result<()> {
+            match *item {}
+        }
+    }
 }
 
 // ============================================================================
@@ -632,6 +895,84 @@ impl StdFileSystem {
             .is_some_and(|n| n.starts_with('.'))
     }
 
+    /// Write `data` to a new file at `temp_path` with the permissions and
+    /// ownership of the file it will replace, and flush it to disk
+    fn write_synced(
+        temp_path: &Path,
+        data: &[u8],
+        original: Option<&std::fs::Metadata>,
+    ) -> io::Result<()> {
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(temp_path)?;
+        file.write_all(data)?;
+        if let Some(meta) = original {
+            // Ownership first: chown clears setuid/setgid bits set by chmod.
+            // Only root can give a file away, but the group usually works.
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                let _ = std::os::unix::fs::fchown(&file, Some(meta.uid()), Some(meta.gid()));
+            }
+            let _ = file.set_permissions(meta.permissions());
+        }
+        file.sync_all()
+    }
+
+    /// Move the finished temp file over `path`
+    ///
+    /// A bind-mounted file or one on another device can't be renamed over;
+    /// then the content is copied into the existing file instead, which isn't
+    /// atomic but still ends synced to disk.
+    fn replace_with(
+        temp_path: &Path,
+        path: &Path,
+        original: Option<&std::fs::Metadata>,
+    ) -> io::Result<()> {
+        match std::fs::rename(temp_path, path) {
+            Ok(()) => {
+                Self::sync_parent_dir(path);
+                Ok(())
+            }
+            Err(e)
+                if original.is_some()
+                    && matches!(
+                        e.kind(),
+                        io::ErrorKind::CrossesDevices | io::ErrorKind::ResourceBusy
+                    ) =>
+            {
+                let mut source = std::fs::File::open(temp_path)?;
+                let mut dest = std::fs::OpenOptions::new()
+                    .write(true)
+                    .truncate(true)
+                    .open(path)?;
+                io::copy(&mut source, &mut dest)?;
+                dest.sync_all()?;
+                drop(source);
+                std::fs::remove_file(temp_path)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Flush the directory entry of a renamed file so the rename survives a crash
+    fn sync_parent_dir(path: &Path) {
+        #[cfg(unix)]
+        if let Some(parent) = path.parent() {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            if let Ok(dir) = std::fs::File::open(parent) {
+                let _ = dir.sync_all();
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = path;
+    }
+
     /// Build FileMetadata from std::fs::Metadata
     fn build_metadata(path: &Path, meta: &std::fs::Metadata) -> FileMetadata {
         #[cfg(unix)]
@@ -645,6 +986,11 @@ impl StdFileSystem {
                 is_readonly: meta.permissions().readonly(),
                 uid: Some(meta.uid()),
                 gid: Some(meta.gid()),
+                hard_links: Some(meta.nlink()),
+                file_id: Some(FileId {
+                    device: meta.dev(),
+                    inode: meta.ino(),
+                }),
             }
         }
         #[cfg(not(unix))]
@@ -655,6 +1001,8 @@ impl StdFileSystem {
                 permissions: Some(FilePermissions::from_std(meta.permissions())),
                 is_hidden: Self::is_hidden(path),
                 is_readonly: meta.permissions().readonly(),
+                hard_links: None,
+                file_id: None,
             }
         }
     }
@@ -675,20 +1023,22 @@ impl FileSystem for StdFileSystem {
     }
 
     fn write_file(&self, path: &Path, data: &[u8]) -> io::Result<()> {
-        let original_metadata = self.metadata_if_exists(path);
-        let temp_path = self.temp_path_for(path);
-        {
-            let mut file = self.create_file(&temp_path)?;
-            file.write_all(data)?;
-            file.sync_all()?;
-        }
-        if let Some(ref meta) = original_metadata {
-            if let Some(ref perms) = meta.permissions {
-                let _ = self.set_permissions(&temp_path, perms);
+        // Write through a symlink to its target instead of replacing the link
+        let path = match std::fs::symlink_metadata(path) {
+            Ok(meta) if meta.file_type().is_symlink() => {
+                std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
             }
+            _ => path.to_path_buf(),
+        };
+        let original = std::fs::metadata(&path).ok();
+        let temp_path = self.temp_path_for(&path);
+
+        let result = Self::write_synced(&temp_path, data, original.as_ref())
+            .and_then(|_| Self::replace_with(&temp_path, &path, original.as_ref()));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
         }
-        self.rename(&temp_path, path)?;
-        Ok(())
+        result
     }
 
     fn create_file(&self, path: &Path) -> io::Result<Box<dyn FileWriter>> {
@@ -739,6 +1089,25 @@ impl FileSystem for StdFileSystem {
         std::fs::remove_dir(path)
     }
 
+    #[cfg(feature = "runtime")]
+    fn move_to_trash(&self, path: &Path) -> io::Result<TrashedPath> {
+        // Absolute, but without resolving a symlink: the link is trashed, not its target
+        let absolute = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+                std::fs::canonicalize(parent)?.join(name)
+            }
+            _ => std::env::current_dir()?.join(path),
+        };
+        let restore = match system_trash::delete(&absolute)? {
+            Some(item) => Restore::System(item),
+            None => Restore::Unavailable,
+        };
+        Ok(TrashedPath {
+            original: path.to_path_buf(),
+            restore,
+        })
+    }
+
     // Metadata Operations
     fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
         let meta = std::fs::metadata(path)?;
@@ -750,6 +1119,10 @@ impl FileSystem for StdFileSystem {
         Ok(Self::build_metadata(path, &meta))
     }
 
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::read_link(path)
+    }
+
     fn is_dir(&self, path: &Path) -> io::Result<bool> {
         Ok(std::fs::metadata(path)?.is_dir())
     }
@@ -786,6 +1159,7 @@ impl FileSystem for StdFileSystem {
                 dir_entry.symlink_target_is_dir = std::fs::metadata(&path)
                     .map(|m| m.is_dir())
                     .unwrap_or(false);
+                dir_entry.symlink_target = std::fs::read_link(&path).ok();
             }
 
             entries.push(dir_entry);
@@ -821,6 +1195,7 @@ impl FileSystem for StdFileSystem {
 
     fn sudo_write(
         &self,
+        program: &str,
         path: &Path,
         data: &[u8],
         mode: u32,
@@ -829,20 +1204,37 @@ impl FileSystem for StdFileSystem {
     ) -> io::Result<()> {
         use std::process::{Command, Stdio};
 
-        // Write data via sudo tee
-        let mut child = Command::new("sudo")
-            .args(["tee", &path.to_string_lossy()])
+        // One elevated command, so pkexec (which doesn't cache) asks only once.
+        // Ownership first: chown clears setuid/setgid bits set by chmod.
+        let mut script = String::from(r#"tee -- "$1" > /dev/null"#);
+        if !path.exists() {
+            script.push_str(r#" && chown "$3" "$1" && chmod "$2" "$1""#);
+        }
+        let mut child = Command::new(program)
+            .args([
+                "sh",
+                "-c",
+                &script,
+                "sh",
+                &path.to_string_lossy(),
+                &format!("{:o}", mode),
+                &format!("{}:{}", uid, gid),
+            ])
             .stdin(Stdio::piped())
             .stdout(Stdio::null())
             .stderr(Stdio::piped())
             .spawn()
             .map_err(|e| {
-                io::Error::new(io::ErrorKind::Other, format!("failed to spawn sudo: {}", e))
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("failed to spawn {}: {}", program, e),
+                )
             })?;
 
         if let Some(mut stdin) = child.stdin.take() {
             use std::io::Write;
-            stdin.write_all(data)?;
+            // A program that exits early closes stdin; its exit code says why
+            let _ = stdin.write_all(data);
         }
 
         let output = child.wait_with_output()?;
@@ -850,30 +1242,10 @@ impl FileSystem for StdFileSystem {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(io::Error::new(
                 io::ErrorKind::PermissionDenied,
-                format!("sudo tee failed: {}", stderr.trim()),
+                format!("{} tee failed: {}", program, stderr.trim()),
             ));
         }
 
-        // Set permissions via sudo chmod
-        let status = Command::new("sudo")
-            .args(["chmod", &format!("{:o}", mode), &path.to_string_lossy()])
-            .status()?;
-        if !status.success() {
-            return Err(io::Error::new(io::ErrorKind::Other, "sudo chmod failed"));
-        }
-
-        // Set ownership via sudo chown
-        let status = Command::new("sudo")
-            .args([
-                "chown",
-                &format!("{}:{}", uid, gid),
-                &path.to_string_lossy(),
-            ])
-            .status()?;
-        if !status.success() {
-            return Err(io::Error::new(io::ErrorKind::Other, "sudo chown failed"));
-        }
-
         Ok(())
     }
 }
@@ -989,6 +1361,7 @@ impl FileSystem for NoopFileSystem {
 
     fn sudo_write(
         &self,
+        _program: &str,
         _path: &Path,
         _data: &[u8],
         _mode: u32,
@@ -1117,6 +1490,90 @@ mod tests {
         assert_eq!(fs.read_file(&path).unwrap(), b"updated");
     }
 
+    #[test]
+    fn test_write_file_leaves_siblings_alone() {
+        let fs = StdFileSystem;
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+        let sibling = temp_dir.path().join("notes.tmp");
+        fs.write_file(&sibling, b"keep me").unwrap();
+
+        fs.write_file(&path, b"saved").unwrap();
+
+        assert_eq!(fs.read_file(&sibling).unwrap(), b"keep me");
+        // No temp file is left behind
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_write_file_keeps_permissions_and_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let fs = StdFileSystem;
+        let temp_dir = tempfile::tempdir().unwrap();
+        let target = temp_dir.path().join("script.sh");
+        let link = temp_dir.path().join("link.sh");
+        std::fs::write(&target, b"old").unwrap();
+        std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o750)).unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        fs.write_file(&link, b"new").unwrap();
+
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read(&target).unwrap(), b"new");
+        let mode = std::fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_links_in_metadata_and_listing() {
+        let fs = StdFileSystem;
+        let temp_dir = tempfile::tempdir().unwrap();
+        let target = temp_dir.path().join("target.txt");
+        let hard = temp_dir.path().join("hard.txt");
+        let link = temp_dir.path().join("link.txt");
+        std::fs::write(&target, b"x").unwrap();
+        std::fs::hard_link(&target, &hard).unwrap();
+        std::os::unix::fs::symlink("target.txt", &link).unwrap();
+
+        let meta = fs.metadata(&target).unwrap();
+        assert_eq!(meta.hard_links, Some(2));
+        assert!(meta.file_id.is_some());
+        assert_eq!(meta.file_id, fs.metadata(&hard).unwrap().file_id);
+
+        assert_eq!(fs.read_link(&link).unwrap(), PathBuf::from("target.txt"));
+        assert!(fs.read_link(&target).is_err());
+        let entry = fs
+            .read_dir(temp_dir.path())
+            .unwrap()
+            .into_iter()
+            .find(|e| e.name == "link.txt")
+            .unwrap();
+        assert_eq!(entry.symlink_target, Some(PathBuf::from("target.txt")));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_is_writable_follows_owner_write_bit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let fs = StdFileSystem;
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("file.txt");
+        std::fs::write(&path, b"x").unwrap();
+        assert!(fs.is_writable(&path));
+        assert!(fs.is_writable(&temp_dir.path().join("missing.txt")));
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o444)).unwrap();
+        // root may write any file
+        assert_eq!(fs.is_writable(&path), fs.current_uid() == 0);
+    }
+
     #[test]
     fn test_write_patched_default_impl() {
         // Test that the default write_patched implementation works correctly
@@ -275,6 +275,119 @@ fn classify_change(
     }
 }
 
+/// Largest LCS table `unified_diff` builds; beyond it the differing middle
+/// of the two texts is shown as removed and re-added as a whole
+const MAX_UNIFIED_DIFF_CELLS: usize = 4_000_000;
+
+/// One line of a unified diff, by line index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffOp {
+    Equal(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Unified diff from `old` to `new` with `context` unchanged lines around
+/// each change, like `diff -u` without the file names
+///
+/// Returns an empty string if the texts have the same lines.
+pub fn unified_diff(old: &str, new: &str, context: usize) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // Only the part between the common prefix and suffix needs the LCS
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle: Vec<&[u8]> = old_lines[prefix..old_lines.len() - suffix]
+        .iter()
+        .map(|line| line.as_bytes())
+        .collect();
+    let new_middle: Vec<&[u8]> = new_lines[prefix..new_lines.len() - suffix]
+        .iter()
+        .map(|line| line.as_bytes())
+        .collect();
+    let matches = if old_middle.len().saturating_mul(new_middle.len()) <= MAX_UNIFIED_DIFF_CELLS {
+        longest_common_subsequence(&old_middle, &new_middle)
+    } else {
+        Vec::new()
+    };
+
+    let mut ops: Vec<DiffOp> = (0..prefix).map(|i| DiffOp::Equal(i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    let end = LineMatch {
+        saved_idx: old_middle.len(),
+        current_idx: new_middle.len(),
+    };
+    for m in matches.iter().chain(std::iter::once(&end)) {
+        ops.extend((i..m.saved_idx).map(|i| DiffOp::Removed(prefix + i)));
+        ops.extend((j..m.current_idx).map(|j| DiffOp::Added(prefix + j)));
+        if m.saved_idx < old_middle.len() {
+            ops.push(DiffOp::Equal(prefix + m.saved_idx, prefix + m.current_idx));
+        }
+        i = m.saved_idx + 1;
+        j = m.current_idx + 1;
+    }
+    let (old_end, new_end) = (old_lines.len() - suffix, new_lines.len() - suffix);
+    ops.extend((0..suffix).map(|k| DiffOp::Equal(old_end + k, new_end + k)));
+
+    // Group changes whose context overlaps into hunks
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, DiffOp::Equal(..)))
+        .map(|(index, _)| index)
+        .collect();
+    let mut hunks: Vec<Range<usize>> = Vec::new();
+    for index in changed {
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => hunks.push(start..end),
+        }
+    }
+
+    let is_old = |op: &DiffOp| !matches!(op, DiffOp::Added(_));
+    let is_new = |op: &DiffOp| !matches!(op, DiffOp::Removed(_));
+    let mut out = String::new();
+    for hunk in hunks {
+        let old_before = ops[..hunk.start].iter().filter(|op| is_old(op)).count();
+        let new_before = ops[..hunk.start].iter().filter(|op| is_new(op)).count();
+        let ops = &ops[hunk];
+        let old_count = ops.iter().filter(|op| is_old(op)).count();
+        let new_count = ops.iter().filter(|op| is_new(op)).count();
+        // 1-based first line, or the line before for an empty side
+        let start = |before: usize, count: usize| if count == 0 { before } else { before + 1 };
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            start(old_before, old_count),
+            old_count,
+            start(new_before, new_count),
+            new_count
+        ));
+        for op in ops {
+            let (marker, line) = match *op {
+                DiffOp::Equal(o, _) => (' ', old_lines[o]),
+                DiffOp::Removed(o) => ('-', old_lines[o]),
+                DiffOp::Added(n) => ('+', new_lines[n]),
+            };
+            out.push(marker);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
 /// Merge adjacent or overlapping ranges.
 pub fn merge_ranges(ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
     if ranges.is_empty() {
@@ -311,6 +424,18 @@ mod tests {
         assert!(diff.changed_lines.is_empty());
     }
 
+    #[test]
+    fn test_unified_diff_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let new = "a\nb\nX\nd\ne\nf\ng\n";
+        assert_eq!(
+            unified_diff(old, new, 1),
+            "@@ -2,3 +2,3 @@\n b\n-c\n+X\n d\n@@ -7,2 +7,1 @@\n g\n-h\n"
+        );
+        assert_eq!(unified_diff("", "x\n", 3), "@@ -0,0 +1,1 @@\n+x\n");
+        assert_eq!(unified_diff(old, old, 3), "");
+    }
+
     #[test]
     fn test_empty_files() {
         let diff = diff_lines(b"", b"");
@@ -15,5 +15,6 @@ pub mod filesystem;
 pub mod line_diff;
 pub mod marker;
 pub mod marker_tree;
+pub mod op_script;
 pub mod piece_tree;
 pub mod piece_tree_diff;
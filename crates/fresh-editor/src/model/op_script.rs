@@ -0,0 +1,417 @@
+//! Deterministic edit scripts for fuzzing and property tests
+//!
+//! An `OpScript` is a list of inserts, deletes, undos and redos decoded from
+//! a seed or from raw bytes (as handed out by a fuzzer). Running it against a
+//! `TextBuffer` applies each step through the undo log and checks the buffer
+//! against a plain byte string after every step: content, length and the
+//! line index. At the end everything is undone back to the start and redone
+//! again, which must reproduce the original and final texts exactly.
+//!
+//! Offsets in a script are raw numbers and are wrapped into the buffer when
+//! the step runs, so every script is valid for every buffer.
+
+use crate::model::buffer::TextBuffer;
+use crate::model::cursor::CursorId;
+use crate::model::event::{Event, EventLog};
+use crate::model::piece_tree::Position;
+
+/// Inserted text is drawn from these, including a multi-byte character and
+/// line breaks so line index updates are exercised
+const ALPHABET: &[&str] = &["a", "b", "z", " ", "\t", "\n", "\n", "é", "\r\n"];
+
+/// Most pieces inserted at once
+const MAX_INSERT_PIECES: u8 = 16;
+
+/// Most bytes deleted at once
+const MAX_DELETE_LEN: u8 = 32;
+
+/// One step of a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptOp {
+    /// Insert `text` at `offset` (wrapped into `0..=len`)
+    Insert {
+        offset: u16,
+        text: String,
+    },
+    /// Delete up to `len` bytes at `offset` (wrapped into `0..len`)
+    Delete {
+        offset: u16,
+        len: u8,
+    },
+    Undo,
+    Redo,
+}
+
+/// A step that left the buffer in the wrong state
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("step {step} ({op}): {message}")]
+pub struct InvariantViolation {
+    /// Index of the failing step; `ops.len()` for the final undo/redo round trip
+    pub step: usize,
+    pub op: String,
+    pub message: String,
+}
+
+/// A deterministic sequence of buffer edits
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpScript {
+    pub ops: Vec<ScriptOp>,
+}
+
+impl OpScript {
+    pub fn new(ops: Vec<ScriptOp>) -> Self {
+        Self { ops }
+    }
+
+    /// Generate `len` steps from `seed`; the same seed always gives the same script
+    pub fn from_seed(seed: u64, len: usize) -> Self {
+        let mut rng = SplitMix64(seed);
+        let mut next = || Some(rng.next_u8());
+        Self::decode(&mut next, len)
+    }
+
+    /// Decode as many steps as `data` holds (for fuzz targets)
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let mut bytes = data.iter().copied();
+        Self::decode(&mut || bytes.next(), usize::MAX)
+    }
+
+    fn decode(next: &mut dyn FnMut() -> Option<u8>, max_ops: usize) -> Self {
+        let mut ops = Vec::new();
+        while ops.len() < max_ops {
+            let Some(tag) = next() else { break };
+            let op = match tag % 8 {
+                // Inserts and deletes are three times as likely as undo and redo
+                0..=2 => {
+                    let (Some(hi), Some(lo), Some(count)) = (next(), next(), next()) else {
+                        break;
+                    };
+                    let text = (0..count % MAX_INSERT_PIECES + 1)
+                        .map_while(|_| next())
+                        .map(|b| ALPHABET[b as usize % ALPHABET.len()])
+                        .collect();
+                    ScriptOp::Insert {
+                        offset: u16::from_be_bytes([hi, lo]),
+                        text,
+                    }
+                }
+                3..=5 => {
+                    let (Some(hi), Some(lo), Some(len)) = (next(), next(), next()) else {
+                        break;
+                    };
+                    ScriptOp::Delete {
+                        offset: u16::from_be_bytes([hi, lo]),
+                        len: len % MAX_DELETE_LEN + 1,
+                    }
+                }
+                6 => ScriptOp::Undo,
+                _ => ScriptOp::Redo,
+            };
+            ops.push(op);
+        }
+        Self { ops }
+    }
+
+    /// Run the script against `buffer`, checking invariants after every step
+    pub fn run(&self, buffer: &mut TextBuffer) -> Result<(), InvariantViolation> {
+        let initial = buffer.get_all_text().ok_or_else(|| InvariantViolation {
+            step: 0,
+            op: "start".to_string(),
+            message: "buffer is not fully loaded".to_string(),
+        })?;
+        let mut log = EventLog::new();
+        // Expected text after each entry of the log; `states[log.current_index()]` is current
+        let mut states = vec![initial];
+
+        for (step, op) in self.ops.iter().enumerate() {
+            let fail = |message: String| InvariantViolation {
+                step,
+                op: format!("{:?}", op),
+                message,
+            };
+            let current = &states[log.current_index()];
+            match op {
+                ScriptOp::Insert { offset, text } => {
+                    let offset = *offset as usize % (current.len() + 1);
+                    let mut expected = current.clone();
+                    expected.splice(offset..offset, text.bytes());
+                    let event = Event::Insert {
+                        position: offset,
+                        text: text.clone(),
+                        cursor_id: CursorId(0),
+                    };
+                    apply_event(buffer, &event);
+                    log.append(event);
+                    states.truncate(log.current_index());
+                    states.push(expected);
+                }
+                ScriptOp::Delete { offset, len } => {
+                    if current.is_empty() {
+                        continue;
+                    }
+                    let start = *offset as usize % current.len();
+                    let end = (start + *len as usize).min(current.len());
+                    let mut expected = current.clone();
+                    let deleted = expected.drain(start..end).collect::<Vec<u8>>();
+                    // The log keeps deleted text as a String, so a delete that
+                    // splits a multi-byte character can't be undone; skip it
+                    let Ok(deleted_text) = String::from_utf8(deleted) else {
+                        continue;
+                    };
+                    let event = Event::Delete {
+                        range: start..end,
+                        deleted_text,
+                        cursor_id: CursorId(0),
+                    };
+                    apply_event(buffer, &event);
+                    log.append(event);
+                    states.truncate(log.current_index());
+                    states.push(expected);
+                }
+                ScriptOp::Undo => {
+                    for event in log.undo() {
+                        apply_event(buffer, &event);
+                    }
+                }
+                ScriptOp::Redo => {
+                    for event in log.redo() {
+                        apply_event(buffer, &event);
+                    }
+                }
+            }
+            check_invariants(buffer, &states[log.current_index()]).map_err(fail)?;
+        }
+
+        // Undo everything, then redo everything
+        let round_trip = |message: String| InvariantViolation {
+            step: self.ops.len(),
+            op: "undo/redo round trip".to_string(),
+            message,
+        };
+        let last = log.current_index();
+        while log.can_undo() {
+            for event in log.undo() {
+                apply_event(buffer, &event);
+            }
+        }
+        check_invariants(buffer, &states[0]).map_err(round_trip)?;
+        while log.can_redo() {
+            for event in log.redo() {
+                apply_event(buffer, &event);
+            }
+        }
+        check_invariants(buffer, &states[states.len() - 1]).map_err(round_trip)?;
+        // Redo may go past where the script stopped; undo back to it
+        while log.current_index() > last {
+            for event in log.undo() {
+                apply_event(buffer, &event);
+            }
+        }
+        check_offset_positions(buffer, &states[last]).map_err(round_trip)
+    }
+}
+
+/// Check that `buffer` holds `expected` and that its line index agrees
+///
+/// Line starts are compared for every line; use `check_offset_positions` to
+/// also convert every offset to a position and back.
+pub fn check_invariants(buffer: &TextBuffer, expected: &[u8]) -> Result<(), String> {
+    let actual = buffer
+        .get_all_text()
+        .ok_or_else(|| "buffer is not fully loaded".to_string())?;
+    if actual != expected {
+        return Err(format!(
+            "content mismatch\nexpected: {:?}\nactual:   {:?}",
+            String::from_utf8_lossy(expected),
+            String::from_utf8_lossy(&actual)
+        ));
+    }
+    if buffer.total_bytes() != expected.len() {
+        return Err(format!(
+            "length {} but content has {} bytes",
+            buffer.total_bytes(),
+            expected.len()
+        ));
+    }
+
+    let line_starts = line_starts(expected);
+    if buffer.line_count() != Some(line_starts.len()) {
+        return Err(format!(
+            "line count {:?}, expected {}",
+            buffer.line_count(),
+            line_starts.len()
+        ));
+    }
+    for (line, &start) in line_starts.iter().enumerate() {
+        if buffer.line_start_offset(line) != Some(start) {
+            return Err(format!(
+                "line {} starts at {:?}, expected {}",
+                line,
+                buffer.line_start_offset(line),
+                start
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check that every offset converts to the right line/column and back
+pub fn check_offset_positions(buffer: &TextBuffer, expected: &[u8]) -> Result<(), String> {
+    check_invariants(buffer, expected)?;
+    let line_starts = line_starts(expected);
+    for offset in 0..=expected.len() {
+        let line = line_starts.partition_point(|&start| start <= offset) - 1;
+        let want = Position {
+            line,
+            column: offset - line_starts[line],
+        };
+        let got = buffer.offset_to_position(offset);
+        if got != Some(want) {
+            return Err(format!(
+                "offset {} is at {:?}, expected {:?}",
+                offset, got, want
+            ));
+        }
+        let back = buffer.position_to_offset(want);
+        if back != offset {
+            return Err(format!(
+                "{:?} maps back to {}, expected {}",
+                want, back, offset
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Byte offsets where each line starts
+fn line_starts(text: &[u8]) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(
+            text.iter()
+                .enumerate()
+                .filter(|(_, &b)| b == b'\n')
+                .map(|(i, _)| i + 1),
+        )
+        .collect()
+}
+
+/// Apply an insert or delete (or a batch of them) to the buffer
+fn apply_event(buffer: &mut TextBuffer, event: &Event) {
+    match event {
+        Event::Insert { position, text, .. } => {
+            buffer.insert_bytes(*position, text.as_bytes().to_vec());
+        }
+        Event::Delete { range, .. } => {
+            buffer.delete_bytes(range.start, range.len());
+        }
+        Event::Batch { events, .. } => {
+            for event in events {
+                apply_event(buffer, event);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Small seedable generator so scripts don't depend on an RNG crate
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_u8(&mut self) -> u8 {
+        (self.next_u64() >> 56) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::filesystem::StdFileSystem;
+    use proptest::prelude::*;
+    use std::sync::Arc;
+
+    fn buffer(text: &str) -> TextBuffer {
+        TextBuffer::from_bytes(text.as_bytes().to_vec(), Arc::new(StdFileSystem))
+    }
+
+    #[test]
+    fn test_same_seed_same_script() {
+        assert_eq!(OpScript::from_seed(7, 100), OpScript::from_seed(7, 100));
+        assert_ne!(OpScript::from_seed(7, 100), OpScript::from_seed(8, 100));
+        assert_eq!(OpScript::from_seed(7, 100).ops.len(), 100);
+    }
+
+    #[test]
+    fn test_from_bytes_stops_at_end_of_input() {
+        // Insert "b" at offset 1, then a delete cut short by the end of input
+        let script = OpScript::from_bytes(&[0, 0, 1, 0, 1, 3, 0]);
+        assert_eq!(
+            script.ops,
+            vec![ScriptOp::Insert {
+                offset: 1,
+                text: "b".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_undo_redo_script() {
+        let script = OpScript::new(vec![
+            ScriptOp::Insert {
+                offset: 5,
+                text: "\nworld".to_string(),
+            },
+            ScriptOp::Delete { offset: 0, len: 1 },
+            ScriptOp::Undo,
+            ScriptOp::Undo,
+            ScriptOp::Redo,
+            ScriptOp::Insert {
+                offset: 0,
+                text: "é".to_string(),
+            },
+            // Nothing left to redo after a new edit
+            ScriptOp::Redo,
+        ]);
+        let mut buffer = buffer("hello");
+        script.run(&mut buffer).unwrap();
+        assert_eq!(buffer.get_all_text().unwrap(), "éhello\nworld".as_bytes());
+    }
+
+    #[test]
+    fn test_check_invariants_reports_mismatch() {
+        let buffer = buffer("a\nb");
+        assert!(check_offset_positions(&buffer, b"a\nb").is_ok());
+        let err = check_invariants(&buffer, b"a\nc").unwrap_err();
+        assert!(err.contains("content mismatch"), "{}", err);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_seeded_scripts_keep_invariants(
+            seed in any::<u64>(),
+            len in 1usize..150,
+            initial in "[a-z\n]{0,40}"
+        ) {
+            let mut buffer = buffer(&initial);
+            let script = OpScript::from_seed(seed, len);
+            if let Err(violation) = script.run(&mut buffer) {
+                return Err(TestCaseError::fail(format!("seed {}: {}", seed, violation)));
+            }
+        }
+
+        #[test]
+        fn prop_byte_scripts_keep_invariants(data in prop::collection::vec(any::<u8>(), 0..400)) {
+            let mut buffer = buffer("");
+            OpScript::from_bytes(&data)
+                .run(&mut buffer)
+                .map_err(|violation| TestCaseError::fail(violation.to_string()))?;
+        }
+    }
+}
@@ -74,6 +74,19 @@ impl StringBuffer {
         }
     }
 
+    /// Bytes allocated for this buffer's data and line index
+    pub fn heap_size(&self) -> usize {
+        match &self.data {
+            BufferData::Loaded { data, line_starts } => {
+                data.capacity()
+                    + line_starts
+                        .as_ref()
+                        .map_or(0, |starts| starts.capacity() * std::mem::size_of::<usize>())
+            }
+            BufferData::Unloaded { file_path, .. } => file_path.as_os_str().len(),
+        }
+    }
+
     /// Check if buffer is loaded
     pub fn is_loaded(&self) -> bool {
         matches!(self.data, BufferData::Loaded { .. })
@@ -328,6 +341,16 @@ pub struct TreeStats {
 // The iterator would need access to the actual buffer data (Stored/Added)
 // which is managed externally, so this is deferred until buffer integration.
 
+/// Line starts strictly inside a piece, i.e. in `(piece_start, piece_end)`
+///
+/// `line_starts` is sorted, so this is two binary searches rather than a scan
+/// of every line in the buffer.
+fn line_starts_within(line_starts: &[usize], piece_start: usize, piece_end: usize) -> &[usize] {
+    let first = line_starts.partition_point(|&ls| ls <= piece_start);
+    let last = line_starts.partition_point(|&ls| ls < piece_end);
+    &line_starts[first..last.max(first)]
+}
+
 impl PieceTreeNode {
     /// Find the piece containing the given byte offset
     fn find_by_offset(&self, offset: usize) -> Option<OffsetFindResult> {
@@ -593,25 +616,10 @@ impl PieceTreeNode {
                     // First line starts at piece start
                     piece_start_in_buffer
                 } else {
-                    // Find the Nth newline within this piece
-                    // Count line_starts that fall within [piece_start, piece_end)
-                    let mut lines_seen = 0;
-                    let mut found_line_start = None;
-
-                    for &line_start in line_starts.iter() {
-                        // Line starts are positions of newlines + 1, or beginning of buffer (0)
-                        // We want line_starts that are > piece_start and < piece_end
-                        if line_start > piece_start_in_buffer && line_start < piece_end_in_buffer {
-                            if lines_seen == line_in_piece - 1 {
-                                // This is the start of our target line
-                                found_line_start = Some(line_start);
-                                break;
-                            }
-                            lines_seen += 1;
-                        }
-                    }
-
-                    found_line_start?
+                    // The Nth line start inside this piece
+                    line_starts_within(line_starts, piece_start_in_buffer, piece_end_in_buffer)
+                        .get(line_in_piece - 1)
+                        .copied()?
                 };
 
                 // Add column offset
@@ -942,23 +950,15 @@ impl PieceTree {
                         let line_start_in_buffer = if line_in_piece == 0 {
                             *offset
                         } else {
-                            // Find the Nth newline within this piece
-                            let mut lines_seen = 0;
-                            let mut found_line_start = *offset;
-
-                            if let Some(line_starts) = buffer.get_line_starts() {
-                                for &ls in line_starts.iter() {
-                                    if ls > *offset && ls < *offset + *bytes {
-                                        if lines_seen == line_in_piece - 1 {
-                                            found_line_start = ls;
-                                            break;
-                                        }
-                                        lines_seen += 1;
-                                    }
-                                }
-                            }
-
-                            found_line_start
+                            // The Nth line start inside this piece
+                            buffer
+                                .get_line_starts()
+                                .and_then(|line_starts| {
+                                    line_starts_within(line_starts, *offset, *offset + *bytes)
+                                        .get(line_in_piece - 1)
+                                        .copied()
+                                })
+                                .unwrap_or(*offset)
                         };
 
                         // Calculate split offset within the piece
@@ -1152,7 +1152,15 @@ impl PieceTree {
         if let Some(buffer) = buffers.get(buffer_id) {
             if let Some(data) = buffer.get_data() {
                 let end = (offset + bytes).min(data.len());
-                Some(data[offset..end].iter().filter(|&&b| b == b'\n').count())
+                match buffer.get_line_starts() {
+                    // A line feed at `p` starts a line at `p + 1`, so count the
+                    // line starts in (offset, end] instead of scanning the bytes
+                    Some(line_starts) => Some(
+                        line_starts.partition_point(|&ls| ls <= end)
+                            - line_starts.partition_point(|&ls| ls <= offset),
+                    ),
+                    None => Some(data[offset..end].iter().filter(|&&b| b == b'\n').count()),
+                }
             } else {
                 // Buffer is unloaded - return None
                 None
@@ -1464,23 +1472,15 @@ impl PieceTree {
         let line_start_in_buffer = if line_in_piece == 0 {
             piece_offset
         } else {
-            // Find the Nth newline within this piece
-            let mut lines_seen = 0;
-            let mut found_line_start = piece_offset;
-
-            if let Some(line_starts) = buffer.get_line_starts() {
-                for &ls in line_starts.iter() {
-                    if ls > piece_offset && ls < piece_offset + piece_bytes {
-                        if lines_seen == line_in_piece - 1 {
-                            found_line_start = ls;
-                            break;
-                        }
-                        lines_seen += 1;
-                    }
-                }
-            }
-
-            found_line_start
+            // The Nth line start inside this piece
+            buffer
+                .get_line_starts()
+                .and_then(|line_starts| {
+                    line_starts_within(line_starts, piece_offset, piece_offset + piece_bytes)
+                        .get(line_in_piece - 1)
+                        .copied()
+                })
+                .unwrap_or(piece_offset)
         };
 
         // Calculate offset within the piece
@@ -1669,17 +1669,14 @@ impl PieceTree {
                     } else {
                         // Line starts within this piece
                         // Find where the line starts within the piece
-                        let mut count = 0;
-                        let mut line_start_in_buf = piece_info.offset;
-                        for &ls in line_starts.iter() {
-                            if ls > piece_info.offset && ls < piece_info.offset + piece_info.bytes {
-                                count += 1;
-                                if count == line_in_piece {
-                                    line_start_in_buf = ls;
-                                    break;
-                                }
-                            }
-                        }
+                        let line_start_in_buf = line_starts_within(
+                            line_starts,
+                            piece_info.offset,
+                            piece_info.offset + piece_info.bytes,
+                        )
+                        .get(line_in_piece - 1)
+                        .copied()
+                        .unwrap_or(piece_info.offset);
                         let line_start_offset_in_piece = line_start_in_buf - piece_info.offset;
                         offset_in_piece - line_start_offset_in_piece
                     };
@@ -2571,6 +2568,41 @@ mod tests {
             tree2.total_bytes()
         );
     }
+
+    #[test]
+    fn test_line_starts_within_piece() {
+        let line_starts = [0, 4, 8, 12];
+        assert_eq!(line_starts_within(&line_starts, 4, 12), &[8]);
+        assert_eq!(line_starts_within(&line_starts, 0, 13), &[4, 8, 12]);
+        assert!(line_starts_within(&line_starts, 5, 5).is_empty());
+    }
+
+    #[test]
+    fn test_split_pieces_count_line_feeds_from_line_starts() {
+        let text = "line\n".repeat(1000).into_bytes();
+        let mut buffers = vec![
+            StringBuffer::new(0, text.clone()),
+            StringBuffer::new(1, b"xy".to_vec()),
+        ];
+        let mut tree = PieceTree::new(BufferLocation::Stored(0), 0, text.len(), Some(1000));
+        tree.insert(2502, BufferLocation::Added(1), 0, 2, Some(0), &buffers);
+        tree.delete(10, 7, &buffers);
+        buffers.push(StringBuffer::new(2, b"\n".to_vec()));
+        tree.insert(4000, BufferLocation::Added(2), 0, 1, Some(1), &buffers);
+
+        for leaf in tree.get_leaves() {
+            let data = buffers[leaf.location.buffer_id()].get_data().unwrap();
+            let scanned = data[leaf.offset..leaf.offset + leaf.bytes]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count();
+            assert_eq!(leaf.line_feed_cnt, Some(scanned));
+        }
+        // One line feed deleted, one inserted
+        assert_eq!(tree.line_count(), Some(1001));
+        // "xy" was at column 2 of line 500; one line before it was deleted
+        assert_eq!(tree.offset_to_position(2502 - 7, &buffers), Some((499, 2)));
+    }
 }
 
 #[cfg(test)]
@@ -4,9 +4,12 @@
 //! enabling a 4-level overlay architecture (System → User → Project → Session).
 
 use crate::config::{
-    AcceptSuggestionOnEnter, CursorStyle, FileBrowserConfig, FileExplorerConfig, FormatterConfig,
-    HighlighterPreference, Keybinding, KeybindingMapName, KeymapConfig, LanguageConfig,
-    LineEndingOption, OnSaveAction, PluginConfig, TerminalConfig, ThemeName, WarningsConfig,
+    AcceptSuggestionOnEnter, AccessibilityConfig, AmbiguousWidth, AutoSaveMode, CursorStyle,
+    FileBrowserConfig, FileExplorerConfig, FormatterConfig, HighlighterPreference,
+    IndentRulesConfig, Keybinding, KeybindingMapName, KeymapConfig, LanguageConfig,
+    LineEndingOption, OnSaveAction, OpenSymlinks, PluginConfig, RenderWhitespace, ScopedOverrides,
+    ScopedSettings, SessionIdleAction, SessionServerConfig, StatusLineConfig, TelemetryConfig,
+    TelemetryLevel, TerminalConfig, ThemeSetting, UpdateChannel, WarningsConfig,
 };
 use crate::types::LspServerConfig;
 use serde::{Deserialize, Serialize};
@@ -74,9 +77,12 @@ where
 #[serde(default)]
 pub struct PartialConfig {
     pub version: Option<u32>,
-    pub theme: Option<ThemeName>,
+    pub theme: Option<ThemeSetting>,
     pub locale: Option<String>,
     pub check_for_updates: Option<bool>,
+    pub telemetry: Option<PartialTelemetryConfig>,
+    pub update_channel: Option<UpdateChannel>,
+    pub allow_unverified_updates: Option<bool>,
     pub editor: Option<PartialEditorConfig>,
     pub file_explorer: Option<PartialFileExplorerConfig>,
     pub file_browser: Option<PartialFileBrowserConfig>,
@@ -87,9 +93,14 @@ pub struct PartialConfig {
     pub languages: Option<HashMap<String, PartialLanguageConfig>>,
     pub lsp: Option<HashMap<String, LspServerConfig>>,
     pub warnings: Option<PartialWarningsConfig>,
+    pub status_line: Option<PartialStatusLineConfig>,
+    pub session_server: Option<PartialSessionServerConfig>,
+    pub accessibility: Option<PartialAccessibilityConfig>,
     pub plugins: Option<HashMap<String, PartialPluginConfig>>,
     pub packages: Option<PartialPackagesConfig>,
     pub auto_modal_mode: Option<String>,
+    #[serde(flatten)]
+    pub overrides: ScopedOverrides,
 }
 
 impl Merge for PartialConfig {
@@ -98,13 +109,20 @@ impl Merge for PartialConfig {
         self.theme.merge_from(&other.theme);
         self.locale.merge_from(&other.locale);
         self.check_for_updates.merge_from(&other.check_for_updates);
+        self.update_channel.merge_from(&other.update_channel);
+        self.allow_unverified_updates
+            .merge_from(&other.allow_unverified_updates);
 
         // Nested structs: merge recursively
+        merge_partial(&mut self.telemetry, &other.telemetry);
         merge_partial(&mut self.editor, &other.editor);
         merge_partial(&mut self.file_explorer, &other.file_explorer);
         merge_partial(&mut self.file_browser, &other.file_browser);
         merge_partial(&mut self.terminal, &other.terminal);
         merge_partial(&mut self.warnings, &other.warnings);
+        merge_partial(&mut self.status_line, &other.status_line);
+        merge_partial(&mut self.session_server, &other.session_server);
+        merge_partial(&mut self.accessibility, &other.accessibility);
         merge_partial(&mut self.packages, &other.packages);
 
         // Lists: higher precedence replaces (per design doc)
@@ -119,6 +137,24 @@ impl Merge for PartialConfig {
         self.active_keybinding_map
             .merge_from(&other.active_keybinding_map);
         self.auto_modal_mode.merge_from(&other.auto_modal_mode);
+        self.overrides.merge_from(&other.overrides);
+    }
+}
+
+impl Merge for ScopedSettings {
+    fn merge_from(&mut self, other: &Self) {
+        self.fill_from(other);
+    }
+}
+
+impl Merge for ScopedOverrides {
+    fn merge_from(&mut self, other: &Self) {
+        for (key, value) in &other.0 {
+            self.0
+                .entry(key.clone())
+                .and_modify(|existing| existing.merge_from(value))
+                .or_insert_with(|| value.clone());
+        }
     }
 }
 
@@ -137,31 +173,58 @@ fn merge_partial<T: Merge + Clone>(target: &mut Option<T>, other: &Option<T>) {
 pub struct PartialEditorConfig {
     pub tab_size: Option<usize>,
     pub auto_indent: Option<bool>,
+    pub detect_indentation: Option<bool>,
     pub line_numbers: Option<bool>,
     pub relative_line_numbers: Option<bool>,
     pub scroll_offset: Option<usize>,
     pub syntax_highlighting: Option<bool>,
     pub line_wrap: Option<bool>,
+    pub rulers: Option<Vec<usize>>,
+    pub wrap_guide_shading: Option<bool>,
+    pub render_whitespace: Option<RenderWhitespace>,
     pub highlight_timeout_ms: Option<u64>,
     pub snapshot_interval: Option<usize>,
     pub large_file_threshold_bytes: Option<u64>,
     pub estimated_line_length: Option<usize>,
     pub enable_inlay_hints: Option<bool>,
     pub enable_semantic_tokens_full: Option<bool>,
+    pub enable_document_highlight: Option<bool>,
+    pub enable_code_lens: Option<bool>,
     pub recovery_enabled: Option<bool>,
     pub auto_save_interval_secs: Option<u32>,
+    pub auto_save: Option<AutoSaveMode>,
+    pub auto_save_delay_ms: Option<u64>,
+    pub auto_save_exclude: Option<Vec<String>>,
     pub highlight_context_bytes: Option<usize>,
     pub mouse_hover_enabled: Option<bool>,
     pub mouse_hover_delay_ms: Option<u64>,
     pub double_click_time_ms: Option<u64>,
+    pub mouse_scroll_lines: Option<usize>,
+    pub mouse_scroll_acceleration: Option<bool>,
     pub auto_revert_poll_interval_ms: Option<u64>,
+    pub local_history: Option<bool>,
+    pub local_history_days: Option<u64>,
+    pub local_history_max_mb: Option<u64>,
     pub file_tree_poll_interval_ms: Option<u64>,
+    pub workspace_index: Option<bool>,
+    pub memory_warning_threshold_mb: Option<u64>,
+    pub background_file_io: Option<bool>,
     pub default_line_ending: Option<LineEndingOption>,
     pub trim_trailing_whitespace_on_save: Option<bool>,
     pub ensure_final_newline_on_save: Option<bool>,
+    pub open_symlinks: Option<OpenSymlinks>,
+    pub auto_read_only: Option<bool>,
+    pub watch_command: Option<String>,
     pub highlight_matching_brackets: Option<bool>,
     pub rainbow_brackets: Option<bool>,
     pub cursor_style: Option<CursorStyle>,
+    pub mode_cursor_styles: Option<HashMap<String, CursorStyle>>,
+    pub max_content_width: Option<u16>,
+    pub focus_mode_width: Option<u16>,
+    pub focus_mode_typewriter_scrolling: Option<bool>,
+    pub focus_mode_dim_paragraphs: Option<bool>,
+    pub animations: Option<bool>,
+    pub ambiguous_width: Option<AmbiguousWidth>,
     pub keyboard_disambiguate_escape_codes: Option<bool>,
     pub keyboard_report_event_types: Option<bool>,
     pub keyboard_report_alternate_keys: Option<bool>,
@@ -170,15 +233,20 @@ pub struct PartialEditorConfig {
     pub quick_suggestions_delay_ms: Option<u64>,
     pub suggest_on_trigger_characters: Option<bool>,
     pub accept_suggestion_on_enter: Option<AcceptSuggestionOnEnter>,
+    pub word_completion: Option<bool>,
     pub show_menu_bar: Option<bool>,
     pub show_tab_bar: Option<bool>,
+    pub preview_tabs: Option<bool>,
     pub use_terminal_bg: Option<bool>,
+    pub plugin_hot_reload: Option<bool>,
 }
 
 impl Merge for PartialEditorConfig {
     fn merge_from(&mut self, other: &Self) {
         self.tab_size.merge_from(&other.tab_size);
         self.auto_indent.merge_from(&other.auto_indent);
+        self.detect_indentation
+            .merge_from(&other.detect_indentation);
         self.line_numbers.merge_from(&other.line_numbers);
         self.relative_line_numbers
             .merge_from(&other.relative_line_numbers);
@@ -186,6 +254,10 @@ impl Merge for PartialEditorConfig {
         self.syntax_highlighting
             .merge_from(&other.syntax_highlighting);
         self.line_wrap.merge_from(&other.line_wrap);
+        self.rulers.merge_from(&other.rulers);
+        self.wrap_guide_shading
+            .merge_from(&other.wrap_guide_shading);
+        self.render_whitespace.merge_from(&other.render_whitespace);
         self.highlight_timeout_ms
             .merge_from(&other.highlight_timeout_ms);
         self.snapshot_interval.merge_from(&other.snapshot_interval);
@@ -197,9 +269,16 @@ impl Merge for PartialEditorConfig {
             .merge_from(&other.enable_inlay_hints);
         self.enable_semantic_tokens_full
             .merge_from(&other.enable_semantic_tokens_full);
+        self.enable_document_highlight
+            .merge_from(&other.enable_document_highlight);
+        self.enable_code_lens.merge_from(&other.enable_code_lens);
         self.recovery_enabled.merge_from(&other.recovery_enabled);
         self.auto_save_interval_secs
             .merge_from(&other.auto_save_interval_secs);
+        self.auto_save.merge_from(&other.auto_save);
+        self.auto_save_delay_ms
+            .merge_from(&other.auto_save_delay_ms);
+        self.auto_save_exclude.merge_from(&other.auto_save_exclude);
         self.highlight_context_bytes
             .merge_from(&other.highlight_context_bytes);
         self.mouse_hover_enabled
@@ -208,20 +287,46 @@ impl Merge for PartialEditorConfig {
             .merge_from(&other.mouse_hover_delay_ms);
         self.double_click_time_ms
             .merge_from(&other.double_click_time_ms);
+        self.mouse_scroll_lines
+            .merge_from(&other.mouse_scroll_lines);
+        self.mouse_scroll_acceleration
+            .merge_from(&other.mouse_scroll_acceleration);
         self.auto_revert_poll_interval_ms
             .merge_from(&other.auto_revert_poll_interval_ms);
+        self.local_history.merge_from(&other.local_history);
+        self.local_history_days
+            .merge_from(&other.local_history_days);
+        self.local_history_max_mb
+            .merge_from(&other.local_history_max_mb);
         self.file_tree_poll_interval_ms
             .merge_from(&other.file_tree_poll_interval_ms);
+        self.workspace_index.merge_from(&other.workspace_index);
+        self.memory_warning_threshold_mb
+            .merge_from(&other.memory_warning_threshold_mb);
+        self.background_file_io
+            .merge_from(&other.background_file_io);
         self.default_line_ending
             .merge_from(&other.default_line_ending);
         self.trim_trailing_whitespace_on_save
             .merge_from(&other.trim_trailing_whitespace_on_save);
         self.ensure_final_newline_on_save
             .merge_from(&other.ensure_final_newline_on_save);
+        self.open_symlinks.merge_from(&other.open_symlinks);
+        self.auto_read_only.merge_from(&other.auto_read_only);
+        self.watch_command.merge_from(&other.watch_command);
         self.highlight_matching_brackets
             .merge_from(&other.highlight_matching_brackets);
         self.rainbow_brackets.merge_from(&other.rainbow_brackets);
         self.cursor_style.merge_from(&other.cursor_style);
+        merge_hashmap(&mut self.mode_cursor_styles, &other.mode_cursor_styles);
+        self.max_content_width.merge_from(&other.max_content_width);
+        self.focus_mode_width.merge_from(&other.focus_mode_width);
+        self.focus_mode_typewriter_scrolling
+            .merge_from(&other.focus_mode_typewriter_scrolling);
+        self.focus_mode_dim_paragraphs
+            .merge_from(&other.focus_mode_dim_paragraphs);
+        self.animations.merge_from(&other.animations);
+        self.ambiguous_width.merge_from(&other.ambiguous_width);
         self.keyboard_disambiguate_escape_codes
             .merge_from(&other.keyboard_disambiguate_escape_codes);
         self.keyboard_report_event_types
@@ -237,9 +342,12 @@ impl Merge for PartialEditorConfig {
             .merge_from(&other.suggest_on_trigger_characters);
         self.accept_suggestion_on_enter
             .merge_from(&other.accept_suggestion_on_enter);
+        self.word_completion.merge_from(&other.word_completion);
         self.show_menu_bar.merge_from(&other.show_menu_bar);
         self.show_tab_bar.merge_from(&other.show_tab_bar);
+        self.preview_tabs.merge_from(&other.preview_tabs);
         self.use_terminal_bg.merge_from(&other.use_terminal_bg);
+        self.plugin_hot_reload.merge_from(&other.plugin_hot_reload);
     }
 }
 
@@ -316,6 +424,72 @@ impl Merge for PartialWarningsConfig {
     }
 }
 
+/// Partial status line configuration.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PartialStatusLineConfig {
+    pub left: Option<Vec<String>>,
+    pub center: Option<Vec<String>>,
+    pub right: Option<Vec<String>>,
+}
+
+impl Merge for PartialStatusLineConfig {
+    fn merge_from(&mut self, other: &Self) {
+        self.left.merge_from(&other.left);
+        self.center.merge_from(&other.center);
+        self.right.merge_from(&other.right);
+    }
+}
+
+/// Partial session server configuration.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PartialSessionServerConfig {
+    pub idle_timeout_secs: Option<u64>,
+    pub idle_action: Option<SessionIdleAction>,
+    pub predictive_echo: Option<bool>,
+    pub compress_output: Option<bool>,
+}
+
+impl Merge for PartialSessionServerConfig {
+    fn merge_from(&mut self, other: &Self) {
+        self.idle_timeout_secs.merge_from(&other.idle_timeout_secs);
+        self.idle_action.merge_from(&other.idle_action);
+        self.predictive_echo.merge_from(&other.predictive_echo);
+        self.compress_output.merge_from(&other.compress_output);
+    }
+}
+
+/// Partial telemetry configuration.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PartialTelemetryConfig {
+    pub level: Option<TelemetryLevel>,
+    pub local_only: Option<bool>,
+}
+
+impl Merge for PartialTelemetryConfig {
+    fn merge_from(&mut self, other: &Self) {
+        self.level.merge_from(&other.level);
+        self.local_only.merge_from(&other.local_only);
+    }
+}
+
+/// Partial accessibility configuration.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PartialAccessibilityConfig {
+    pub enabled: Option<bool>,
+    pub command: Option<Vec<String>>,
+}
+
+impl Merge for PartialAccessibilityConfig {
+    fn merge_from(&mut self, other: &Self) {
+        self.enabled.merge_from(&other.enabled);
+        self.command.merge_from(&other.command);
+    }
+}
+
 /// Partial packages configuration for plugin/theme package management.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(default)]
@@ -337,12 +511,16 @@ pub struct PartialPluginConfig {
     pub enabled: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<std::path::PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub granted_permissions: Option<Vec<fresh_core::config::PluginPermission>>,
 }
 
 impl Merge for PartialPluginConfig {
     fn merge_from(&mut self, other: &Self) {
         self.enabled.merge_from(&other.enabled);
         self.path.merge_from(&other.path);
+        self.granted_permissions
+            .merge_from(&other.granted_permissions);
     }
 }
 
@@ -354,7 +532,10 @@ pub struct PartialLanguageConfig {
     pub filenames: Option<Vec<String>>,
     pub grammar: Option<String>,
     pub comment_prefix: Option<String>,
+    pub block_comment_start: Option<String>,
+    pub block_comment_end: Option<String>,
     pub auto_indent: Option<bool>,
+    pub indent_rules: Option<IndentRulesConfig>,
     pub highlighter: Option<HighlighterPreference>,
     pub textmate_grammar: Option<std::path::PathBuf>,
     pub show_whitespace_tabs: Option<bool>,
@@ -363,6 +544,7 @@ pub struct PartialLanguageConfig {
     pub formatter: Option<FormatterConfig>,
     pub format_on_save: Option<bool>,
     pub on_save: Option<Vec<OnSaveAction>>,
+    pub lsp_servers: Option<Vec<String>>,
 }
 
 impl Merge for PartialLanguageConfig {
@@ -371,7 +553,11 @@ impl Merge for PartialLanguageConfig {
         self.filenames.merge_from(&other.filenames);
         self.grammar.merge_from(&other.grammar);
         self.comment_prefix.merge_from(&other.comment_prefix);
+        self.block_comment_start
+            .merge_from(&other.block_comment_start);
+        self.block_comment_end.merge_from(&other.block_comment_end);
         self.auto_indent.merge_from(&other.auto_indent);
+        self.indent_rules.merge_from(&other.indent_rules);
         self.highlighter.merge_from(&other.highlighter);
         self.textmate_grammar.merge_from(&other.textmate_grammar);
         self.show_whitespace_tabs
@@ -381,6 +567,7 @@ impl Merge for PartialLanguageConfig {
         self.formatter.merge_from(&other.formatter);
         self.format_on_save.merge_from(&other.format_on_save);
         self.on_save.merge_from(&other.on_save);
+        self.lsp_servers.merge_from(&other.lsp_servers);
     }
 }
 
@@ -410,31 +597,58 @@ impl From<&crate::config::EditorConfig> for PartialEditorConfig {
         Self {
             tab_size: Some(cfg.tab_size),
             auto_indent: Some(cfg.auto_indent),
+            detect_indentation: Some(cfg.detect_indentation),
             line_numbers: Some(cfg.line_numbers),
             relative_line_numbers: Some(cfg.relative_line_numbers),
             scroll_offset: Some(cfg.scroll_offset),
             syntax_highlighting: Some(cfg.syntax_highlighting),
             line_wrap: Some(cfg.line_wrap),
+            rulers: Some(cfg.rulers.clone()),
+            wrap_guide_shading: Some(cfg.wrap_guide_shading),
+            render_whitespace: Some(cfg.render_whitespace),
             highlight_timeout_ms: Some(cfg.highlight_timeout_ms),
             snapshot_interval: Some(cfg.snapshot_interval),
             large_file_threshold_bytes: Some(cfg.large_file_threshold_bytes),
             estimated_line_length: Some(cfg.estimated_line_length),
             enable_inlay_hints: Some(cfg.enable_inlay_hints),
             enable_semantic_tokens_full: Some(cfg.enable_semantic_tokens_full),
+            enable_document_highlight: Some(cfg.enable_document_highlight),
+            enable_code_lens: Some(cfg.enable_code_lens),
             recovery_enabled: Some(cfg.recovery_enabled),
             auto_save_interval_secs: Some(cfg.auto_save_interval_secs),
+            auto_save: Some(cfg.auto_save),
+            auto_save_delay_ms: Some(cfg.auto_save_delay_ms),
+            auto_save_exclude: Some(cfg.auto_save_exclude.clone()),
             highlight_context_bytes: Some(cfg.highlight_context_bytes),
             mouse_hover_enabled: Some(cfg.mouse_hover_enabled),
             mouse_hover_delay_ms: Some(cfg.mouse_hover_delay_ms),
             double_click_time_ms: Some(cfg.double_click_time_ms),
+            mouse_scroll_lines: Some(cfg.mouse_scroll_lines),
+            mouse_scroll_acceleration: Some(cfg.mouse_scroll_acceleration),
             auto_revert_poll_interval_ms: Some(cfg.auto_revert_poll_interval_ms),
+            local_history: Some(cfg.local_history),
+            local_history_days: Some(cfg.local_history_days),
+            local_history_max_mb: Some(cfg.local_history_max_mb),
             file_tree_poll_interval_ms: Some(cfg.file_tree_poll_interval_ms),
+            workspace_index: Some(cfg.workspace_index),
+            memory_warning_threshold_mb: Some(cfg.memory_warning_threshold_mb),
+            background_file_io: Some(cfg.background_file_io),
             default_line_ending: Some(cfg.default_line_ending.clone()),
             trim_trailing_whitespace_on_save: Some(cfg.trim_trailing_whitespace_on_save),
             ensure_final_newline_on_save: Some(cfg.ensure_final_newline_on_save),
+            open_symlinks: Some(cfg.open_symlinks),
+            auto_read_only: Some(cfg.auto_read_only),
+            watch_command: Some(cfg.watch_command.clone()),
             highlight_matching_brackets: Some(cfg.highlight_matching_brackets),
             rainbow_brackets: Some(cfg.rainbow_brackets),
             cursor_style: Some(cfg.cursor_style),
+            mode_cursor_styles: Some(cfg.mode_cursor_styles.clone()),
+            max_content_width: Some(cfg.max_content_width),
+            focus_mode_width: Some(cfg.focus_mode_width),
+            focus_mode_typewriter_scrolling: Some(cfg.focus_mode_typewriter_scrolling),
+            focus_mode_dim_paragraphs: Some(cfg.focus_mode_dim_paragraphs),
+            animations: Some(cfg.animations),
+            ambiguous_width: Some(cfg.ambiguous_width),
             keyboard_disambiguate_escape_codes: Some(cfg.keyboard_disambiguate_escape_codes),
             keyboard_report_event_types: Some(cfg.keyboard_report_event_types),
             keyboard_report_alternate_keys: Some(cfg.keyboard_report_alternate_keys),
@@ -445,9 +659,12 @@ impl From<&crate::config::EditorConfig> for PartialEditorConfig {
             quick_suggestions_delay_ms: Some(cfg.quick_suggestions_delay_ms),
             suggest_on_trigger_characters: Some(cfg.suggest_on_trigger_characters),
             accept_suggestion_on_enter: Some(cfg.accept_suggestion_on_enter),
+            word_completion: Some(cfg.word_completion),
             show_menu_bar: Some(cfg.show_menu_bar),
             show_tab_bar: Some(cfg.show_tab_bar),
+            preview_tabs: Some(cfg.preview_tabs),
             use_terminal_bg: Some(cfg.use_terminal_bg),
+            plugin_hot_reload: Some(cfg.plugin_hot_reload),
         }
     }
 }
@@ -458,6 +675,9 @@ impl PartialEditorConfig {
         crate::config::EditorConfig {
             tab_size: self.tab_size.unwrap_or(defaults.tab_size),
             auto_indent: self.auto_indent.unwrap_or(defaults.auto_indent),
+            detect_indentation: self
+                .detect_indentation
+                .unwrap_or(defaults.detect_indentation),
             line_numbers: self.line_numbers.unwrap_or(defaults.line_numbers),
             relative_line_numbers: self
                 .relative_line_numbers
@@ -467,6 +687,11 @@ impl PartialEditorConfig {
                 .syntax_highlighting
                 .unwrap_or(defaults.syntax_highlighting),
             line_wrap: self.line_wrap.unwrap_or(defaults.line_wrap),
+            rulers: self.rulers.unwrap_or_else(|| defaults.rulers.clone()),
+            wrap_guide_shading: self
+                .wrap_guide_shading
+                .unwrap_or(defaults.wrap_guide_shading),
+            render_whitespace: self.render_whitespace.unwrap_or(defaults.render_whitespace),
             highlight_timeout_ms: self
                 .highlight_timeout_ms
                 .unwrap_or(defaults.highlight_timeout_ms),
@@ -483,10 +708,22 @@ impl PartialEditorConfig {
             enable_semantic_tokens_full: self
                 .enable_semantic_tokens_full
                 .unwrap_or(defaults.enable_semantic_tokens_full),
+            enable_document_highlight: self
+                .enable_document_highlight
+                .unwrap_or(defaults.enable_document_highlight),
+            enable_code_lens: self.enable_code_lens.unwrap_or(defaults.enable_code_lens),
             recovery_enabled: self.recovery_enabled.unwrap_or(defaults.recovery_enabled),
             auto_save_interval_secs: self
                 .auto_save_interval_secs
                 .unwrap_or(defaults.auto_save_interval_secs),
+            auto_save: self.auto_save.unwrap_or(defaults.auto_save),
+            auto_save_delay_ms: self
+                .auto_save_delay_ms
+                .unwrap_or(defaults.auto_save_delay_ms),
+            auto_save_exclude: self
+                .auto_save_exclude
+                .clone()
+                .unwrap_or_else(|| defaults.auto_save_exclude.clone()),
             highlight_context_bytes: self
                 .highlight_context_bytes
                 .unwrap_or(defaults.highlight_context_bytes),
@@ -499,12 +736,32 @@ impl PartialEditorConfig {
             double_click_time_ms: self
                 .double_click_time_ms
                 .unwrap_or(defaults.double_click_time_ms),
+            mouse_scroll_lines: self
+                .mouse_scroll_lines
+                .unwrap_or(defaults.mouse_scroll_lines),
+            mouse_scroll_acceleration: self
+                .mouse_scroll_acceleration
+                .unwrap_or(defaults.mouse_scroll_acceleration),
             auto_revert_poll_interval_ms: self
                 .auto_revert_poll_interval_ms
                 .unwrap_or(defaults.auto_revert_poll_interval_ms),
+            local_history: self.local_history.unwrap_or(defaults.local_history),
+            local_history_days: self
+                .local_history_days
+                .unwrap_or(defaults.local_history_days),
+            local_history_max_mb: self
+                .local_history_max_mb
+                .unwrap_or(defaults.local_history_max_mb),
             file_tree_poll_interval_ms: self
                 .file_tree_poll_interval_ms
                 .unwrap_or(defaults.file_tree_poll_interval_ms),
+            workspace_index: self.workspace_index.unwrap_or(defaults.workspace_index),
+            memory_warning_threshold_mb: self
+                .memory_warning_threshold_mb
+                .unwrap_or(defaults.memory_warning_threshold_mb),
+            background_file_io: self
+                .background_file_io
+                .unwrap_or(defaults.background_file_io),
             default_line_ending: self
                 .default_line_ending
                 .unwrap_or(defaults.default_line_ending.clone()),
@@ -514,11 +771,32 @@ impl PartialEditorConfig {
             ensure_final_newline_on_save: self
                 .ensure_final_newline_on_save
                 .unwrap_or(defaults.ensure_final_newline_on_save),
+            open_symlinks: self.open_symlinks.unwrap_or(defaults.open_symlinks),
+            auto_read_only: self.auto_read_only.unwrap_or(defaults.auto_read_only),
+            watch_command: self
+                .watch_command
+                .unwrap_or_else(|| defaults.watch_command.clone()),
             highlight_matching_brackets: self
                 .highlight_matching_brackets
                 .unwrap_or(defaults.highlight_matching_brackets),
             rainbow_brackets: self.rainbow_brackets.unwrap_or(defaults.rainbow_brackets),
             cursor_style: self.cursor_style.unwrap_or(defaults.cursor_style),
+            mode_cursor_styles: {
+                // Entries override the default ones rather than replacing the map
+                let mut styles = defaults.mode_cursor_styles.clone();
+                styles.extend(self.mode_cursor_styles.unwrap_or_default());
+                styles
+            },
+            max_content_width: self.max_content_width.unwrap_or(defaults.max_content_width),
+            focus_mode_width: self.focus_mode_width.unwrap_or(defaults.focus_mode_width),
+            focus_mode_typewriter_scrolling: self
+                .focus_mode_typewriter_scrolling
+                .unwrap_or(defaults.focus_mode_typewriter_scrolling),
+            focus_mode_dim_paragraphs: self
+                .focus_mode_dim_paragraphs
+                .unwrap_or(defaults.focus_mode_dim_paragraphs),
+            animations: self.animations.unwrap_or(defaults.animations),
+            ambiguous_width: self.ambiguous_width.unwrap_or(defaults.ambiguous_width),
             keyboard_disambiguate_escape_codes: self
                 .keyboard_disambiguate_escape_codes
                 .unwrap_or(defaults.keyboard_disambiguate_escape_codes),
@@ -541,9 +819,12 @@ impl PartialEditorConfig {
             accept_suggestion_on_enter: self
                 .accept_suggestion_on_enter
                 .unwrap_or(defaults.accept_suggestion_on_enter),
+            word_completion: self.word_completion.unwrap_or(defaults.word_completion),
             show_menu_bar: self.show_menu_bar.unwrap_or(defaults.show_menu_bar),
             show_tab_bar: self.show_tab_bar.unwrap_or(defaults.show_tab_bar),
+            preview_tabs: self.preview_tabs.unwrap_or(defaults.preview_tabs),
             use_terminal_bg: self.use_terminal_bg.unwrap_or(defaults.use_terminal_bg),
+            plugin_hot_reload: self.plugin_hot_reload.unwrap_or(defaults.plugin_hot_reload),
         }
     }
 }
@@ -640,6 +921,84 @@ impl PartialWarningsConfig {
     }
 }
 
+impl From<&StatusLineConfig> for PartialStatusLineConfig {
+    fn from(cfg: &StatusLineConfig) -> Self {
+        Self {
+            left: Some(cfg.left.clone()),
+            center: Some(cfg.center.clone()),
+            right: Some(cfg.right.clone()),
+        }
+    }
+}
+
+impl PartialStatusLineConfig {
+    pub fn resolve(self, defaults: &StatusLineConfig) -> StatusLineConfig {
+        StatusLineConfig {
+            left: self.left.unwrap_or_else(|| defaults.left.clone()),
+            center: self.center.unwrap_or_else(|| defaults.center.clone()),
+            right: self.right.unwrap_or_else(|| defaults.right.clone()),
+        }
+    }
+}
+
+impl From<&SessionServerConfig> for PartialSessionServerConfig {
+    fn from(cfg: &SessionServerConfig) -> Self {
+        Self {
+            idle_timeout_secs: Some(cfg.idle_timeout_secs),
+            idle_action: Some(cfg.idle_action),
+            predictive_echo: Some(cfg.predictive_echo),
+            compress_output: Some(cfg.compress_output),
+        }
+    }
+}
+
+impl PartialSessionServerConfig {
+    pub fn resolve(self, defaults: &SessionServerConfig) -> SessionServerConfig {
+        SessionServerConfig {
+            idle_timeout_secs: self.idle_timeout_secs.unwrap_or(defaults.idle_timeout_secs),
+            idle_action: self.idle_action.unwrap_or(defaults.idle_action),
+            predictive_echo: self.predictive_echo.unwrap_or(defaults.predictive_echo),
+            compress_output: self.compress_output.unwrap_or(defaults.compress_output),
+        }
+    }
+}
+
+impl From<&TelemetryConfig> for PartialTelemetryConfig {
+    fn from(cfg: &TelemetryConfig) -> Self {
+        Self {
+            level: Some(cfg.level),
+            local_only: Some(cfg.local_only),
+        }
+    }
+}
+
+impl PartialTelemetryConfig {
+    pub fn resolve(self, defaults: &TelemetryConfig) -> TelemetryConfig {
+        TelemetryConfig {
+            level: self.level.unwrap_or(defaults.level),
+            local_only: self.local_only.unwrap_or(defaults.local_only),
+        }
+    }
+}
+
+impl From<&AccessibilityConfig> for PartialAccessibilityConfig {
+    fn from(cfg: &AccessibilityConfig) -> Self {
+        Self {
+            enabled: Some(cfg.enabled),
+            command: cfg.command.clone(),
+        }
+    }
+}
+
+impl PartialAccessibilityConfig {
+    pub fn resolve(self, defaults: &AccessibilityConfig) -> AccessibilityConfig {
+        AccessibilityConfig {
+            enabled: self.enabled.unwrap_or(defaults.enabled),
+            command: self.command.or_else(|| defaults.command.clone()),
+        }
+    }
+}
+
 impl From<&crate::config::PackagesConfig> for PartialPackagesConfig {
     fn from(cfg: &crate::config::PackagesConfig) -> Self {
         Self {
@@ -664,6 +1023,7 @@ impl From<&PluginConfig> for PartialPluginConfig {
         Self {
             enabled: Some(cfg.enabled),
             path: cfg.path.clone(),
+            granted_permissions: Some(cfg.granted_permissions.clone()),
         }
     }
 }
@@ -673,6 +1033,9 @@ impl PartialPluginConfig {
         PluginConfig {
             enabled: self.enabled.unwrap_or(defaults.enabled),
             path: self.path.or_else(|| defaults.path.clone()),
+            granted_permissions: self
+                .granted_permissions
+                .unwrap_or_else(|| defaults.granted_permissions.clone()),
         }
     }
 }
@@ -684,7 +1047,10 @@ impl From<&LanguageConfig> for PartialLanguageConfig {
             filenames: Some(cfg.filenames.clone()),
             grammar: Some(cfg.grammar.clone()),
             comment_prefix: cfg.comment_prefix.clone(),
+            block_comment_start: cfg.block_comment_start.clone(),
+            block_comment_end: cfg.block_comment_end.clone(),
             auto_indent: Some(cfg.auto_indent),
+            indent_rules: cfg.indent_rules.clone(),
             highlighter: Some(cfg.highlighter),
             textmate_grammar: cfg.textmate_grammar.clone(),
             show_whitespace_tabs: Some(cfg.show_whitespace_tabs),
@@ -693,6 +1059,7 @@ impl From<&LanguageConfig> for PartialLanguageConfig {
             formatter: cfg.formatter.clone(),
             format_on_save: Some(cfg.format_on_save),
             on_save: Some(cfg.on_save.clone()),
+            lsp_servers: Some(cfg.lsp_servers.clone()),
         }
     }
 }
@@ -708,7 +1075,14 @@ impl PartialLanguageConfig {
             comment_prefix: self
                 .comment_prefix
                 .or_else(|| defaults.comment_prefix.clone()),
+            block_comment_start: self
+                .block_comment_start
+                .or_else(|| defaults.block_comment_start.clone()),
+            block_comment_end: self
+                .block_comment_end
+                .or_else(|| defaults.block_comment_end.clone()),
             auto_indent: self.auto_indent.unwrap_or(defaults.auto_indent),
+            indent_rules: self.indent_rules.or_else(|| defaults.indent_rules.clone()),
             highlighter: self.highlighter.unwrap_or(defaults.highlighter),
             textmate_grammar: self
                 .textmate_grammar
@@ -721,6 +1095,9 @@ impl PartialLanguageConfig {
             formatter: self.formatter.or_else(|| defaults.formatter.clone()),
             format_on_save: self.format_on_save.unwrap_or(defaults.format_on_save),
             on_save: self.on_save.unwrap_or_else(|| defaults.on_save.clone()),
+            lsp_servers: self
+                .lsp_servers
+                .unwrap_or_else(|| defaults.lsp_servers.clone()),
         }
     }
 }
@@ -732,6 +1109,9 @@ impl From<&crate::config::Config> for PartialConfig {
             theme: Some(cfg.theme.clone()),
             locale: cfg.locale.0.clone(),
             check_for_updates: Some(cfg.check_for_updates),
+            telemetry: Some(PartialTelemetryConfig::from(&cfg.telemetry)),
+            update_channel: Some(cfg.update_channel),
+            allow_unverified_updates: Some(cfg.allow_unverified_updates),
             editor: Some(PartialEditorConfig::from(&cfg.editor)),
             file_explorer: Some(PartialFileExplorerConfig::from(&cfg.file_explorer)),
             file_browser: Some(PartialFileBrowserConfig::from(&cfg.file_browser)),
@@ -747,6 +1127,9 @@ impl From<&crate::config::Config> for PartialConfig {
             ),
             lsp: Some(cfg.lsp.clone()),
             warnings: Some(PartialWarningsConfig::from(&cfg.warnings)),
+            status_line: Some(PartialStatusLineConfig::from(&cfg.status_line)),
+            session_server: Some(PartialSessionServerConfig::from(&cfg.session_server)),
+            accessibility: Some(PartialAccessibilityConfig::from(&cfg.accessibility)),
             // Only include plugins that differ from defaults
             // Path is auto-discovered at runtime and should never be saved
             plugins: {
@@ -754,13 +1137,20 @@ impl From<&crate::config::Config> for PartialConfig {
                 let non_default_plugins: HashMap<String, PartialPluginConfig> = cfg
                     .plugins
                     .iter()
-                    .filter(|(_, v)| v.enabled != default_plugin.enabled)
+                    .filter(|(_, v)| {
+                        v.enabled != default_plugin.enabled || !v.granted_permissions.is_empty()
+                    })
                     .map(|(k, v)| {
                         (
                             k.clone(),
                             PartialPluginConfig {
                                 enabled: Some(v.enabled),
                                 path: None, // Don't save path - it's auto-discovered
+                                granted_permissions: if v.granted_permissions.is_empty() {
+                                    None
+                                } else {
+                                    Some(v.granted_permissions.clone())
+                                },
                             },
                         )
                     })
@@ -773,11 +1163,49 @@ impl From<&crate::config::Config> for PartialConfig {
             },
             packages: Some(PartialPackagesConfig::from(&cfg.packages)),
             auto_modal_mode: Some(cfg.auto_modal_mode.clone()),
+            overrides: cfg.overrides.clone(),
         }
     }
 }
 
 impl PartialConfig {
+    /// Remove settings that run commands or load code, for layers that come
+    /// from an untrusted workspace.
+    ///
+    /// Covers LSP servers, per-language formatters, on-save actions and extra
+    /// servers (also format-on-save in language and glob scopes), the terminal
+    /// shell and external editor, the watch command, the screen reader command, plugin settings,
+    /// package sources and self-update settings. Returns true if anything was removed.
+    pub fn strip_untrusted(&mut self) -> bool {
+        let mut stripped = self.lsp.take().is_some();
+        stripped |= self.plugins.take().is_some();
+        stripped |= self.packages.take().is_some();
+        stripped |= self.accessibility.take().is_some();
+        stripped |= self.update_channel.take().is_some();
+        stripped |= self.allow_unverified_updates.take().is_some();
+        if let Some(terminal) = &mut self.terminal {
+            stripped |= terminal.shell.take().is_some();
+            stripped |= terminal.external_editor.take().is_some();
+        }
+        if let Some(editor) = &mut self.editor {
+            stripped |= editor.watch_command.take().is_some();
+        }
+        for language in self
+            .languages
+            .iter_mut()
+            .flat_map(|langs| langs.values_mut())
+        {
+            stripped |= language.formatter.take().is_some();
+            stripped |= language.format_on_save.take().is_some();
+            stripped |= language.on_save.take().is_some();
+            stripped |= language.lsp_servers.take().is_some();
+        }
+        for scope in self.overrides.0.values_mut() {
+            stripped |= scope.format_on_save.take().is_some();
+        }
+        stripped
+    }
+
     /// Resolve this partial config to a concrete Config using system defaults.
     pub fn resolve(self) -> crate::config::Config {
         let defaults = crate::config::Config::default();
@@ -844,6 +1272,14 @@ impl PartialConfig {
                 self.locale.or_else(|| defaults.locale.0.clone()),
             ),
             check_for_updates: self.check_for_updates.unwrap_or(defaults.check_for_updates),
+            telemetry: self
+                .telemetry
+                .map(|e| e.resolve(&defaults.telemetry))
+                .unwrap_or_else(|| defaults.telemetry.clone()),
+            update_channel: self.update_channel.unwrap_or(defaults.update_channel),
+            allow_unverified_updates: self
+                .allow_unverified_updates
+                .unwrap_or(defaults.allow_unverified_updates),
             editor: self
                 .editor
                 .map(|e| e.resolve(&defaults.editor))
@@ -873,6 +1309,18 @@ impl PartialConfig {
                 .warnings
                 .map(|e| e.resolve(&defaults.warnings))
                 .unwrap_or_else(|| defaults.warnings.clone()),
+            status_line: self
+                .status_line
+                .map(|e| e.resolve(&defaults.status_line))
+                .unwrap_or_else(|| defaults.status_line.clone()),
+            session_server: self
+                .session_server
+                .map(|e| e.resolve(&defaults.session_server))
+                .unwrap_or_else(|| defaults.session_server.clone()),
+            accessibility: self
+                .accessibility
+                .map(|e| e.resolve(&defaults.accessibility))
+                .unwrap_or_else(|| defaults.accessibility.clone()),
             plugins,
             packages: self
                 .packages
@@ -881,6 +1329,11 @@ impl PartialConfig {
             auto_modal_mode: self
                 .auto_modal_mode
                 .unwrap_or_else(|| defaults.auto_modal_mode.clone()),
+            overrides: {
+                let mut overrides = self.overrides;
+                overrides.merge_from(&defaults.overrides);
+                overrides
+            },
         }
     }
 }
@@ -893,7 +1346,10 @@ impl Default for LanguageConfig {
             filenames: Vec::new(),
             grammar: String::new(),
             comment_prefix: None,
+            block_comment_start: None,
+            block_comment_end: None,
             auto_indent: true,
+            indent_rules: None,
             highlighter: HighlighterPreference::default(),
             textmate_grammar: None,
             show_whitespace_tabs: true,
@@ -902,6 +1358,7 @@ impl Default for LanguageConfig {
             formatter: None,
             format_on_save: false,
             on_save: Vec::new(),
+            lsp_servers: Vec::new(),
         }
     }
 }
@@ -917,7 +1374,7 @@ impl Default for LanguageConfig {
 #[serde(default)]
 pub struct SessionConfig {
     /// Temporarily override the theme (e.g., for preview)
-    pub theme: Option<ThemeName>,
+    pub theme: Option<ThemeSetting>,
 
     /// Temporary editor overrides (e.g., changing tab_size for current session)
     pub editor: Option<PartialEditorConfig>,
@@ -935,7 +1392,7 @@ impl SessionConfig {
     }
 
     /// Set a temporary theme override.
-    pub fn set_theme(&mut self, theme: ThemeName) {
+    pub fn set_theme(&mut self, theme: ThemeSetting) {
         self.theme = Some(theme);
     }
 
@@ -997,6 +1454,43 @@ impl From<PartialConfig> for SessionConfig {
 mod tests {
     use super::*;
 
+    #[test]
+    fn strip_untrusted_removes_command_settings() {
+        let mut partial: PartialConfig = serde_json::from_value(serde_json::json!({
+            "editor": { "tab_size": 8, "watch_command": "/tmp/evil.sh" },
+            "terminal": { "shell": "/tmp/evil.sh", "jump_to_end_on_output": false },
+            "languages": {
+                "rust": {
+                    "tab_size": 2,
+                    "formatter": { "command": "/tmp/evil.sh" },
+                    "on_save": [{ "command": "/tmp/evil.sh" }]
+                }
+            },
+            "lsp": { "rust": { "command": "/tmp/evil.sh" } },
+            "accessibility": { "enabled": true, "command": ["/tmp/evil.sh"] },
+            "update_channel": "nightly",
+            "allow_unverified_updates": true
+        }))
+        .unwrap();
+
+        assert!(partial.strip_untrusted());
+        assert!(partial.lsp.is_none());
+        assert!(partial.accessibility.is_none());
+        assert!(partial.update_channel.is_none() && partial.allow_unverified_updates.is_none());
+        let terminal = partial.terminal.as_ref().unwrap();
+        assert!(terminal.shell.is_none());
+        assert_eq!(terminal.jump_to_end_on_output, Some(false));
+        let rust = &partial.languages.as_ref().unwrap()["rust"];
+        assert!(rust.formatter.is_none() && rust.on_save.is_none());
+        assert_eq!(rust.tab_size, Some(2));
+        let editor = partial.editor.as_ref().unwrap();
+        assert!(editor.watch_command.is_none());
+        assert_eq!(editor.tab_size, Some(8));
+
+        // Nothing left to strip
+        assert!(!partial.strip_untrusted());
+    }
+
     #[test]
     fn merge_option_higher_precedence_wins() {
         let mut higher: Option<i32> = Some(10);
@@ -1095,16 +1589,34 @@ mod tests {
         assert_eq!(rust.auto_indent, Some(false)); // Filled from lower
     }
 
+    #[test]
+    fn merge_scoped_overrides_field_wise() {
+        let mut higher: PartialConfig = serde_json::from_str(
+            r#"{ "[rust]": { "tab_size": 2 }, "files:**/*.md": { "line_wrap": true }, "stray": 1 }"#,
+        )
+        .unwrap();
+        let lower: PartialConfig =
+            serde_json::from_str(r#"{ "[rust]": { "tab_size": 4, "rulers": [100] } }"#).unwrap();
+
+        higher.merge_from(&lower);
+
+        let rust = &higher.overrides.0["[rust]"];
+        assert_eq!(rust.tab_size, Some(2)); // Higher wins
+        assert_eq!(rust.rulers, Some(vec![100])); // Filled from lower
+        assert_eq!(higher.overrides.0["files:**/*.md"].line_wrap, Some(true));
+        assert!(!higher.overrides.0.contains_key("stray"));
+    }
+
     #[test]
     fn resolve_fills_defaults() {
         let partial = PartialConfig {
-            theme: Some(ThemeName::from("dark")),
+            theme: Some(ThemeSetting::from("dark")),
             ..Default::default()
         };
 
         let resolved = partial.resolve();
 
-        assert_eq!(resolved.theme.0, "dark");
+        assert_eq!(resolved.theme, ThemeSetting::from("dark"));
         assert_eq!(resolved.editor.tab_size, 4); // Default
         assert!(resolved.editor.line_numbers); // Default true
     }
@@ -1146,15 +1658,15 @@ mod tests {
     #[test]
     fn session_config_set_theme() {
         let mut session = SessionConfig::new();
-        session.set_theme(ThemeName::from("dark"));
-        assert_eq!(session.theme, Some(ThemeName::from("dark")));
+        session.set_theme(ThemeSetting::from("dark"));
+        assert_eq!(session.theme, Some(ThemeSetting::from("dark")));
         assert!(!session.is_empty());
     }
 
     #[test]
     fn session_config_clear_theme() {
         let mut session = SessionConfig::new();
-        session.set_theme(ThemeName::from("dark"));
+        session.set_theme(ThemeSetting::from("dark"));
         session.clear_theme();
         assert!(session.theme.is_none());
     }
@@ -1189,11 +1701,11 @@ mod tests {
     #[test]
     fn session_config_to_partial_config() {
         let mut session = SessionConfig::new();
-        session.set_theme(ThemeName::from("dark"));
+        session.set_theme(ThemeSetting::from("dark"));
         session.set_editor_option(|e| e.tab_size = Some(2));
 
         let partial = session.to_partial_config();
-        assert_eq!(partial.theme, Some(ThemeName::from("dark")));
+        assert_eq!(partial.theme, Some(ThemeSetting::from("dark")));
         assert_eq!(partial.editor.as_ref().unwrap().tab_size, Some(2));
     }
 
@@ -1208,6 +1720,7 @@ mod tests {
             PluginConfig {
                 enabled: true, // Default value
                 path: Some(std::path::PathBuf::from("/path/to/plugin.ts")),
+                granted_permissions: Vec::new(),
             },
         );
 
@@ -1229,6 +1742,7 @@ mod tests {
             PluginConfig {
                 enabled: true,
                 path: Some(std::path::PathBuf::from("/path/to/enabled.ts")),
+                granted_permissions: Vec::new(),
             },
         );
         config.plugins.insert(
@@ -1236,6 +1750,7 @@ mod tests {
             PluginConfig {
                 enabled: false, // Not default!
                 path: Some(std::path::PathBuf::from("/path/to/disabled.ts")),
+                granted_permissions: Vec::new(),
             },
         );
 
@@ -1268,6 +1783,7 @@ mod tests {
             PluginConfig {
                 enabled: false,
                 path: Some(std::path::PathBuf::from("/some/path/plugin.ts")),
+                granted_permissions: Vec::new(),
             },
         );
 
@@ -1290,6 +1806,7 @@ mod tests {
                 PartialPluginConfig {
                     enabled: Some(false),
                     path: None,
+                    granted_permissions: None,
                 },
             )])),
             ..Default::default()
@@ -1318,6 +1835,7 @@ mod tests {
                 PartialPluginConfig {
                     enabled: Some(false), // User disabled
                     path: None,
+                    granted_permissions: None,
                 },
             )])),
             ..Default::default()
@@ -1329,6 +1847,7 @@ mod tests {
                 PartialPluginConfig {
                     enabled: Some(true), // Lower layer has it enabled
                     path: None,
+                    granted_permissions: None,
                 },
             )])),
             ..Default::default()
@@ -1355,6 +1874,7 @@ mod tests {
             PluginConfig {
                 enabled: true,
                 path: Some(std::path::PathBuf::from("/a.ts")),
+                granted_permissions: Vec::new(),
             },
         );
         config.plugins.insert(
@@ -1362,6 +1882,7 @@ mod tests {
             PluginConfig {
                 enabled: false,
                 path: Some(std::path::PathBuf::from("/b.ts")),
+                granted_permissions: Vec::new(),
             },
         );
         config.plugins.insert(
@@ -1369,6 +1890,7 @@ mod tests {
             PluginConfig {
                 enabled: true,
                 path: Some(std::path::PathBuf::from("/c.ts")),
+                granted_permissions: Vec::new(),
             },
         );
 
@@ -1401,4 +1923,33 @@ mod tests {
         assert!(plugins.contains_key("plugin_b"));
         assert_eq!(plugins.get("plugin_b").unwrap().enabled, Some(false));
     }
+
+    #[test]
+    fn roundtrip_granted_permissions_are_saved() {
+        use fresh_core::config::PluginPermission;
+
+        let mut config = crate::config::Config::default();
+        config.plugins.insert(
+            "packaged".to_string(),
+            PluginConfig {
+                enabled: true,
+                path: Some(std::path::PathBuf::from("/packaged/main.ts")),
+                granted_permissions: vec![PluginPermission::FsRead, PluginPermission::Process],
+            },
+        );
+
+        let json = serde_json::to_string(&PartialConfig::from(&config)).unwrap();
+        assert!(
+            json.contains("\"granted_permissions\":[\"fs-read\",\"process\"]"),
+            "Granted permissions should be persisted: {}",
+            json
+        );
+
+        let deserialized: PartialConfig = serde_json::from_str(&json).unwrap();
+        let resolved = deserialized.resolve();
+        assert_eq!(
+            resolved.plugins["packaged"].granted_permissions,
+            vec![PluginPermission::FsRead, PluginPermission::Process]
+        );
+    }
 }
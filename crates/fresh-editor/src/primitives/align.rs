@@ -0,0 +1,216 @@
+//! Aligning lines on a delimiter
+//!
+//! [`align_lines`] splits each line at a delimiter (the first occurrence, or
+//! every one for tables) and pads the pieces so the delimiters line up:
+//!
+//! ```text
+//! name = "fresh"            name    = "fresh"
+//! version = "0.1"     ->    version = "0.1"
+//! ```
+//!
+//! The delimiter is literal text or a `/regex/`, as parsed by
+//! [`AlignSpec::parse`]. Lines without it are left alone, and each line keeps
+//! its indentation.
+
+use regex::Regex;
+
+use crate::primitives::display_width::str_width;
+
+/// What to align on and how much space to put around it
+#[derive(Debug, Clone)]
+pub struct AlignSpec {
+    pattern: Regex,
+    /// Align every occurrence, not just the first
+    pub every: bool,
+    /// Spaces before the delimiter, after the longest text in front of it
+    pub pad_before: usize,
+    /// Spaces after the delimiter
+    pub pad_after: usize,
+}
+
+impl AlignSpec {
+    /// Parse the text typed into the Align prompt
+    ///
+    /// - `=` aligns on the first `=`; `/\s+->\s+/` on a regex
+    /// - a leading `*` aligns on every occurrence: `*,` or `*|`
+    /// - spaces typed around the delimiter set the padding: `: ` puts none
+    ///   before and one after. Without any, there is one space on each side,
+    ///   except none before `:` and `,`
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let core = input.trim_matches(' ');
+        let lead = input.len() - input.trim_start_matches(' ').len();
+        let trail = input.len() - input.trim_end_matches(' ').len();
+        let (every, delimiter) = match core.strip_prefix('*') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, core),
+        };
+        if delimiter.is_empty() {
+            return Err("empty delimiter".to_string());
+        }
+
+        let pattern = match delimiter
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            Some(regex) if !regex.is_empty() => Regex::new(regex).map_err(|e| e.to_string())?,
+            _ => Regex::new(&regex::escape(delimiter)).map_err(|e| e.to_string())?,
+        };
+        let (pad_before, pad_after) = if lead == 0 && trail == 0 {
+            (usize::from(!matches!(delimiter, ":" | ",")), 1)
+        } else {
+            (lead, trail)
+        };
+        Ok(Self {
+            pattern,
+            every,
+            pad_before,
+            pad_after,
+        })
+    }
+}
+
+/// `lines` aligned on `spec`'s delimiter
+pub fn align_lines(lines: &[&str], spec: &AlignSpec) -> Vec<String> {
+    // Each line as its pieces and the delimiters between them
+    let split: Vec<Option<(Vec<&str>, Vec<&str>)>> =
+        lines.iter().map(|line| split_line(line, spec)).collect();
+
+    // The width of each column, over the lines that have a delimiter after it
+    let mut widths: Vec<usize> = Vec::new();
+    for (pieces, delimiters) in split.iter().flatten() {
+        for (column, piece) in pieces.iter().take(delimiters.len()).enumerate() {
+            if widths.len() <= column {
+                widths.push(0);
+            }
+            widths[column] = widths[column].max(str_width(piece));
+        }
+    }
+
+    lines
+        .iter()
+        .zip(&split)
+        .map(|(line, split)| {
+            let Some((pieces, delimiters)) = split else {
+                return line.to_string();
+            };
+            let mut out = String::with_capacity(line.len());
+            let spaces = |out: &mut String, count: usize| out.push_str(&" ".repeat(count));
+            for (column, delimiter) in delimiters.iter().enumerate() {
+                let piece = pieces[column];
+                let fill = widths[column] - str_width(piece);
+                // No padding at the end of the line
+                let at_end = column + 1 == delimiters.len() && pieces[column + 1].is_empty();
+                out.push_str(piece);
+                if spec.pad_before == 0 {
+                    // The delimiter sticks to the text before it and the
+                    // text after it lines up: `key:   value`
+                    out.push_str(delimiter);
+                    if !at_end {
+                        spaces(&mut out, fill + spec.pad_after);
+                    }
+                } else {
+                    // A column that is empty on every line, like the start
+                    // of a `| a | b |` table row, gets no padding
+                    if widths[column] > 0 {
+                        spaces(&mut out, fill + spec.pad_before);
+                    }
+                    out.push_str(delimiter);
+                    if !at_end {
+                        spaces(&mut out, spec.pad_after);
+                    }
+                }
+            }
+            out.push_str(pieces[delimiters.len()]);
+            out
+        })
+        .collect()
+}
+
+/// The pieces of `line` around its delimiters, trimmed, the first keeping
+/// the line's indentation; None without a delimiter
+fn split_line<'a>(line: &'a str, spec: &AlignSpec) -> Option<(Vec<&'a str>, Vec<&'a str>)> {
+    let mut pieces = Vec::new();
+    let mut delimiters = Vec::new();
+    let mut rest_start = 0;
+    let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+    for found in spec.pattern.find_iter(line) {
+        // Skip empty matches and any inside the indentation
+        if found.is_empty() || found.start() < indent {
+            continue;
+        }
+        let piece = &line[rest_start..found.start()];
+        pieces.push(if rest_start == 0 {
+            piece.trim_end()
+        } else {
+            piece.trim()
+        });
+        // A regex like `\s*->\s*` brings its own spaces; the padding replaces them
+        delimiters.push(found.as_str().trim_matches(' '));
+        rest_start = found.end();
+        if !spec.every {
+            break;
+        }
+    }
+    if delimiters.is_empty() {
+        return None;
+    }
+    pieces.push(line[rest_start..].trim_start());
+    Some((pieces, delimiters))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn align(text: &str, input: &str) -> String {
+        let lines: Vec<&str> = text.split('\n').collect();
+        align_lines(&lines, &AlignSpec::parse(input).unwrap()).join("\n")
+    }
+
+    #[test]
+    fn test_align_first_delimiter() {
+        assert_eq!(
+            align("name = \"fresh\"\nversion=\"0.1\"\n# note", "="),
+            "name    = \"fresh\"\nversion = \"0.1\"\n# note"
+        );
+        // Only the first `=` counts
+        assert_eq!(align("a = b = c\nlong = d", "="), "a    = b = c\nlong = d");
+    }
+
+    #[test]
+    fn test_align_keeps_indentation() {
+        assert_eq!(
+            align("    x: 1\n    longer: 2", ":"),
+            "    x:      1\n    longer: 2"
+        );
+    }
+
+    #[test]
+    fn test_align_every_occurrence() {
+        assert_eq!(align("a,bb,c\naaa,b,cc", "*,"), "a,   bb, c\naaa, b,  cc");
+    }
+
+    #[test]
+    fn test_padding_from_input() {
+        assert_eq!(align("a=1\nbbb=2", "= "), "a=   1\nbbb= 2");
+        assert_eq!(align("a=1\nbbb=2", "  =  "), "a    =  1\nbbb  =  2");
+    }
+
+    #[test]
+    fn test_align_table() {
+        assert_eq!(
+            align("| a | b |\n|---|---|\n| long | c |", "*|"),
+            "| a    | b   |\n| ---  | --- |\n| long | c   |"
+        );
+    }
+
+    #[test]
+    fn test_align_regex() {
+        assert_eq!(
+            align("x -> 1\nlonger  ->  2", r"/\s*->\s*/"),
+            "x      -> 1\nlonger -> 2"
+        );
+        assert!(AlignSpec::parse("/(/").is_err());
+        assert!(AlignSpec::parse("  ").is_err());
+    }
+}
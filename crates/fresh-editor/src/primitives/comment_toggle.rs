@@ -0,0 +1,261 @@
+//! Line and block comment toggling
+//!
+//! [`toggle_line_comments`] comments a run of lines, or uncomments it when
+//! every non-blank line is already commented. The prefix goes at the
+//! indentation shared by the lines, so commented lines stay aligned with each
+//! other and with their neighbours. A mix of commented and uncommented lines
+//! is commented as a whole, so toggling twice gives back the original text.
+//!
+//! [`toggle_block_comment`] wraps a span in block comment markers, or removes
+//! them when the span already starts and ends with them.
+//!
+//! Both return edits relative to the text they were given, in ascending order
+//! and without overlaps.
+
+/// A replacement of `delete` bytes at `offset` by `insert`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentEdit {
+    pub offset: usize,
+    pub delete: usize,
+    pub insert: String,
+}
+
+/// The edits that toggle a comment, and which way it went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentToggle {
+    /// True when comments were added, false when they were removed
+    pub commented: bool,
+    pub edits: Vec<CommentEdit>,
+}
+
+/// Toggle line comments on each line of `text` (split at `\n`)
+///
+/// `prefix` is the language's line comment prefix, with or without a
+/// trailing space. Blank lines are left alone unless every line is blank.
+pub fn toggle_line_comments(text: &str, prefix: &str) -> CommentToggle {
+    let marker = prefix.trim_end();
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in text.split('\n') {
+        let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+        lines.push((offset, line, indent));
+        offset += line.len() + 1;
+    }
+
+    let mut candidates: Vec<_> = lines
+        .iter()
+        .filter(|(_, line, _)| !line.trim().is_empty())
+        .collect();
+    if candidates.is_empty() {
+        candidates = lines.iter().collect();
+    }
+
+    let commented = !candidates
+        .iter()
+        .all(|(_, line, indent)| !marker.is_empty() && line[*indent..].starts_with(marker));
+
+    let mut edits = Vec::new();
+    if commented {
+        let column = shared_indent(candidates.iter().map(|(_, line, indent)| &line[..*indent]));
+        let insert = format!("{} ", marker);
+        for (offset, _, _) in candidates {
+            edits.push(CommentEdit {
+                offset: offset + column,
+                delete: 0,
+                insert: insert.clone(),
+            });
+        }
+    } else {
+        for (offset, line, indent) in candidates {
+            let rest = &line[indent + marker.len()..];
+            let delete = marker.len() + usize::from(rest.starts_with(' '));
+            edits.push(CommentEdit {
+                offset: offset + indent,
+                delete,
+                insert: String::new(),
+            });
+        }
+    }
+    CommentToggle { commented, edits }
+}
+
+/// Toggle a block comment around `text`, ignoring surrounding whitespace
+///
+/// Adds `start` and `end` with a space inside each, or removes them (and
+/// those spaces) when the trimmed text already begins with `start` and ends
+/// with `end`.
+pub fn toggle_block_comment(text: &str, start: &str, end: &str) -> CommentToggle {
+    let body_start = text.len() - text.trim_start().len();
+    let body_end = text.trim_end().len().max(body_start);
+    let body = &text[body_start..body_end];
+
+    if body.len() >= start.len() + end.len() && body.starts_with(start) && body.ends_with(end) {
+        let inner = &body[start.len()..body.len() - end.len()];
+        let open_space = usize::from(inner.starts_with(' ') && inner.len() > 1);
+        let close_space = usize::from(inner[open_space..].ends_with(' '));
+        return CommentToggle {
+            commented: false,
+            edits: vec![
+                CommentEdit {
+                    offset: body_start,
+                    delete: start.len() + open_space,
+                    insert: String::new(),
+                },
+                CommentEdit {
+                    offset: body_end - end.len() - close_space,
+                    delete: end.len() + close_space,
+                    insert: String::new(),
+                },
+            ],
+        };
+    }
+
+    let edits = if body.is_empty() {
+        vec![CommentEdit {
+            offset: body_start,
+            delete: 0,
+            insert: format!("{}  {}", start, end),
+        }]
+    } else {
+        vec![
+            CommentEdit {
+                offset: body_start,
+                delete: 0,
+                insert: format!("{} ", start),
+            },
+            CommentEdit {
+                offset: body_end,
+                delete: 0,
+                insert: format!(" {}", end),
+            },
+        ]
+    };
+    CommentToggle {
+        commented: true,
+        edits,
+    }
+}
+
+/// Where `position` ends up after `edits` (ascending, relative to the same
+/// text)
+///
+/// An insertion at `position` itself moves it along when `after_insert` is
+/// set, as for a cursor at the start of a line being commented; otherwise it
+/// stays in front, as for the start of a selection.
+pub fn map_position(edits: &[CommentEdit], position: usize, after_insert: bool) -> usize {
+    let mut shift = 0isize;
+    for edit in edits {
+        let end = edit.offset + edit.delete;
+        if end < position || (end == position && (edit.delete > 0 || after_insert)) {
+            shift += edit.insert.len() as isize - edit.delete as isize;
+        } else if edit.offset < position {
+            // Inside a deleted span: move to where it was
+            shift -= (position - edit.offset) as isize;
+        }
+    }
+    (position as isize + shift).max(0) as usize
+}
+
+/// The longest leading whitespace all of `indents` start with
+fn shared_indent<'a>(mut indents: impl Iterator<Item = &'a str>) -> usize {
+    let Some(first) = indents.next() else {
+        return 0;
+    };
+    indents.fold(first.len(), |len, indent| {
+        first
+            .bytes()
+            .zip(indent.bytes())
+            .take(len)
+            .take_while(|(a, b)| a == b)
+            .count()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(text: &str, toggle: &CommentToggle) -> String {
+        let mut result = text.to_string();
+        for edit in toggle.edits.iter().rev() {
+            result.replace_range(edit.offset..edit.offset + edit.delete, &edit.insert);
+        }
+        result
+    }
+
+    #[test]
+    fn test_line_comment_at_shared_indent() {
+        let text = "    if x {\n        y();\n\n    }";
+        let toggle = toggle_line_comments(text, "//");
+        assert!(toggle.commented);
+        assert_eq!(
+            apply(text, &toggle),
+            "    // if x {\n    //     y();\n\n    // }"
+        );
+    }
+
+    #[test]
+    fn test_line_uncomment() {
+        let text = "    // if x {\n    //     y();\n    //}";
+        let toggle = toggle_line_comments(text, "// ");
+        assert!(!toggle.commented);
+        assert_eq!(apply(text, &toggle), "    if x {\n        y();\n    }");
+    }
+
+    #[test]
+    fn test_mixed_lines_are_commented() {
+        let text = "# a\nb";
+        let toggle = toggle_line_comments(text, "#");
+        assert!(toggle.commented);
+        let commented = apply(text, &toggle);
+        assert_eq!(commented, "# # a\n# b");
+        assert_eq!(
+            apply(&commented, &toggle_line_comments(&commented, "#")),
+            text
+        );
+    }
+
+    #[test]
+    fn test_blank_line_is_commented() {
+        let text = "  ";
+        assert_eq!(apply(text, &toggle_line_comments(text, "//")), "  // ");
+    }
+
+    #[test]
+    fn test_block_comment_roundtrip() {
+        let text = "  let x = 1;  ";
+        let toggle = toggle_block_comment(text, "/*", "*/");
+        assert!(toggle.commented);
+        let commented = apply(text, &toggle);
+        assert_eq!(commented, "  /* let x = 1; */  ");
+        let toggle = toggle_block_comment(&commented, "/*", "*/");
+        assert!(!toggle.commented);
+        assert_eq!(apply(&commented, &toggle), text);
+    }
+
+    #[test]
+    fn test_block_uncomment_without_spaces() {
+        let text = "<!--note-->";
+        assert_eq!(
+            apply(text, &toggle_block_comment(text, "<!--", "-->")),
+            "note"
+        );
+        let text = "/**/";
+        assert_eq!(apply(text, &toggle_block_comment(text, "/*", "*/")), "");
+    }
+
+    #[test]
+    fn test_map_position() {
+        let edits = toggle_line_comments("a\nb", "//").edits;
+        // Start of the first line: stays for a selection, moves for a caret
+        assert_eq!(map_position(&edits, 0, false), 0);
+        assert_eq!(map_position(&edits, 0, true), 3);
+        // End of the text moves past both insertions
+        assert_eq!(map_position(&edits, 3, false), 9);
+
+        let edits = toggle_line_comments("// a", "//").edits;
+        // Inside the removed prefix
+        assert_eq!(map_position(&edits, 1, false), 0);
+        assert_eq!(map_position(&edits, 4, false), 1);
+    }
+}
@@ -0,0 +1,366 @@
+//! Digraphs: two-character codes for accented letters and symbols
+//!
+//! The codes follow RFC 1345 as Vim uses them: a letter followed by a mark
+//! for its accent (`e:` is ë, `a'` is á, `n?` is ñ, `c,` is ç), `*` for
+//! Greek (`l*` is λ) and mnemonic pairs for symbols (`Eu` is €, `->` is →,
+//! `!=` is ≠). A pair is also found in reverse order (`:e` is ë too).
+//!
+//! Accent marks: `!` grave, `'` acute, `>` circumflex, `?` tilde,
+//! `:` diaeresis, `,` cedilla, `;` ogonek, `<` caron, `(` breve, `-` macron,
+//! `.` dot, `0` ring, `"` double acute, `/` stroke.
+
+/// The digraph table: code and character
+pub const DIGRAPHS: &[(&str, char)] = &[
+    // Latin-1 letters
+    ("A!", 'À'),
+    ("A'", 'Á'),
+    ("A>", 'Â'),
+    ("A?", 'Ã'),
+    ("A:", 'Ä'),
+    ("AA", 'Å'),
+    ("AE", 'Æ'),
+    ("C,", 'Ç'),
+    ("E!", 'È'),
+    ("E'", 'É'),
+    ("E>", 'Ê'),
+    ("E:", 'Ë'),
+    ("I!", 'Ì'),
+    ("I'", 'Í'),
+    ("I>", 'Î'),
+    ("I:", 'Ï'),
+    ("D-", 'Ð'),
+    ("N?", 'Ñ'),
+    ("O!", 'Ò'),
+    ("O'", 'Ó'),
+    ("O>", 'Ô'),
+    ("O?", 'Õ'),
+    ("O:", 'Ö'),
+    ("O/", 'Ø'),
+    ("U!", 'Ù'),
+    ("U'", 'Ú'),
+    ("U>", 'Û'),
+    ("U:", 'Ü'),
+    ("Y'", 'Ý'),
+    ("TH", 'Þ'),
+    ("ss", 'ß'),
+    ("a!", 'à'),
+    ("a'", 'á'),
+    ("a>", 'â'),
+    ("a?", 'ã'),
+    ("a:", 'ä'),
+    ("aa", 'å'),
+    ("ae", 'æ'),
+    ("c,", 'ç'),
+    ("e!", 'è'),
+    ("e'", 'é'),
+    ("e>", 'ê'),
+    ("e:", 'ë'),
+    ("i!", 'ì'),
+    ("i'", 'í'),
+    ("i>", 'î'),
+    ("i:", 'ï'),
+    ("d-", 'ð'),
+    ("n?", 'ñ'),
+    ("o!", 'ò'),
+    ("o'", 'ó'),
+    ("o>", 'ô'),
+    ("o?", 'õ'),
+    ("o:", 'ö'),
+    ("o/", 'ø'),
+    ("u!", 'ù'),
+    ("u'", 'ú'),
+    ("u>", 'û'),
+    ("u:", 'ü'),
+    ("y'", 'ý'),
+    ("th", 'þ'),
+    ("y:", 'ÿ'),
+    // Latin Extended-A letters
+    ("A-", 'Ā'),
+    ("a-", 'ā'),
+    ("A(", 'Ă'),
+    ("a(", 'ă'),
+    ("A;", 'Ą'),
+    ("a;", 'ą'),
+    ("C'", 'Ć'),
+    ("c'", 'ć'),
+    ("C<", 'Č'),
+    ("c<", 'č'),
+    ("D<", 'Ď'),
+    ("d<", 'ď'),
+    ("D/", 'Đ'),
+    ("d/", 'đ'),
+    ("E-", 'Ē'),
+    ("e-", 'ē'),
+    ("E.", 'Ė'),
+    ("e.", 'ė'),
+    ("E;", 'Ę'),
+    ("e;", 'ę'),
+    ("E<", 'Ě'),
+    ("e<", 'ě'),
+    ("G(", 'Ğ'),
+    ("g(", 'ğ'),
+    ("I-", 'Ī'),
+    ("i-", 'ī'),
+    ("I.", 'İ'),
+    ("i.", 'ı'),
+    ("L/", 'Ł'),
+    ("l/", 'ł'),
+    ("N'", 'Ń'),
+    ("n'", 'ń'),
+    ("N<", 'Ň'),
+    ("n<", 'ň'),
+    ("O-", 'Ō'),
+    ("o-", 'ō'),
+    ("O\"", 'Ő'),
+    ("o\"", 'ő'),
+    ("OE", 'Œ'),
+    ("oe", 'œ'),
+    ("R<", 'Ř'),
+    ("r<", 'ř'),
+    ("S'", 'Ś'),
+    ("s'", 'ś'),
+    ("S,", 'Ş'),
+    ("s,", 'ş'),
+    ("S<", 'Š'),
+    ("s<", 'š'),
+    ("T<", 'Ť'),
+    ("t<", 'ť'),
+    ("U-", 'Ū'),
+    ("u-", 'ū'),
+    ("U0", 'Ů'),
+    ("u0", 'ů'),
+    ("U\"", 'Ű'),
+    ("u\"", 'ű'),
+    ("Z'", 'Ź'),
+    ("z'", 'ź'),
+    ("Z.", 'Ż'),
+    ("z.", 'ż'),
+    ("Z<", 'Ž'),
+    ("z<", 'ž'),
+    // Greek
+    ("A*", 'Α'),
+    ("B*", 'Β'),
+    ("G*", 'Γ'),
+    ("D*", 'Δ'),
+    ("E*", 'Ε'),
+    ("Z*", 'Ζ'),
+    ("Y*", 'Η'),
+    ("H*", 'Θ'),
+    ("I*", 'Ι'),
+    ("K*", 'Κ'),
+    ("L*", 'Λ'),
+    ("M*", 'Μ'),
+    ("N*", 'Ν'),
+    ("C*", 'Ξ'),
+    ("O*", 'Ο'),
+    ("P*", 'Π'),
+    ("R*", 'Ρ'),
+    ("S*", 'Σ'),
+    ("T*", 'Τ'),
+    ("U*", 'Υ'),
+    ("F*", 'Φ'),
+    ("X*", 'Χ'),
+    ("Q*", 'Ψ'),
+    ("W*", 'Ω'),
+    ("a*", 'α'),
+    ("b*", 'β'),
+    ("g*", 'γ'),
+    ("d*", 'δ'),
+    ("e*", 'ε'),
+    ("z*", 'ζ'),
+    ("y*", 'η'),
+    ("h*", 'θ'),
+    ("i*", 'ι'),
+    ("k*", 'κ'),
+    ("l*", 'λ'),
+    ("m*", 'μ'),
+    ("n*", 'ν'),
+    ("c*", 'ξ'),
+    ("o*", 'ο'),
+    ("p*", 'π'),
+    ("r*", 'ρ'),
+    ("*s", 'ς'),
+    ("s*", 'σ'),
+    ("t*", 'τ'),
+    ("u*", 'υ'),
+    ("f*", 'φ'),
+    ("x*", 'χ'),
+    ("q*", 'ψ'),
+    ("w*", 'ω'),
+    // Punctuation
+    ("NS", '\u{A0}'),
+    ("!I", '¡'),
+    ("?I", '¿'),
+    ("<<", '«'),
+    (">>", '»'),
+    ("<1", '‹'),
+    (">1", '›'),
+    ("SE", '§'),
+    ("PI", '¶'),
+    (".M", '·'),
+    ("oo", '•'),
+    (",.", '…'),
+    ("-N", '–'),
+    ("-M", '—'),
+    ("'6", '‘'),
+    ("'9", '’'),
+    (".9", '‚'),
+    ("\"6", '“'),
+    ("\"9", '”'),
+    (":9", '„'),
+    ("/-", '†'),
+    ("/=", '‡'),
+    ("%0", '‰'),
+    // Currency
+    ("Ct", '¢'),
+    ("Pd", '£'),
+    ("Ye", '¥'),
+    ("Eu", '€'),
+    ("=e", '€'),
+    ("W=", '₩'),
+    ("=R", '₽'),
+    // Signs
+    ("Co", '©'),
+    ("Rg", '®'),
+    ("TM", '™'),
+    ("DG", '°'),
+    ("My", 'µ'),
+    ("-a", 'ª'),
+    ("-o", 'º'),
+    ("NO", '¬'),
+    ("BB", '¦'),
+    ("OK", '✓'),
+    ("XX", '✗'),
+    // Fractions, superscripts and subscripts
+    ("14", '¼'),
+    ("12", '½'),
+    ("34", '¾'),
+    ("0S", '⁰'),
+    ("1S", '¹'),
+    ("2S", '²'),
+    ("3S", '³'),
+    ("4S", '⁴'),
+    ("5S", '⁵'),
+    ("6S", '⁶'),
+    ("7S", '⁷'),
+    ("8S", '⁸'),
+    ("9S", '⁹'),
+    ("0s", '₀'),
+    ("1s", '₁'),
+    ("2s", '₂'),
+    ("3s", '₃'),
+    ("4s", '₄'),
+    ("5s", '₅'),
+    ("6s", '₆'),
+    ("7s", '₇'),
+    ("8s", '₈'),
+    ("9s", '₉'),
+    // Mathematics
+    ("+-", '±'),
+    ("*X", '×'),
+    ("-:", '÷'),
+    ("FA", '∀'),
+    ("dP", '∂'),
+    ("TE", '∃'),
+    ("/0", '∅'),
+    ("DE", '∆'),
+    ("NB", '∇'),
+    ("(-", '∈'),
+    ("-)", '∋'),
+    ("*P", '∏'),
+    ("+Z", '∑'),
+    ("-2", '−'),
+    ("RT", '√'),
+    ("0(", '∝'),
+    ("00", '∞'),
+    ("AN", '∧'),
+    ("OR", '∨'),
+    ("(U", '∩'),
+    (")U", '∪'),
+    ("In", '∫'),
+    (".:", '∴'),
+    ("?1", '∼'),
+    ("?=", '≅'),
+    ("?2", '≈'),
+    ("!=", '≠'),
+    ("=3", '≡'),
+    ("=<", '≤'),
+    (">=", '≥'),
+    ("(C", '⊂'),
+    (")C", '⊃'),
+    ("(_", '⊆'),
+    (")_", '⊇'),
+    ("Ob", '∘'),
+    ("Sb", '∙'),
+    // Arrows
+    ("<-", '←'),
+    ("-!", '↑'),
+    ("->", '→'),
+    ("-v", '↓'),
+    ("<>", '↔'),
+    ("UD", '↕'),
+    ("<=", '⇐'),
+    ("=>", '⇒'),
+    ("==", '⇔'),
+    // Box drawing
+    ("hh", '─'),
+    ("vv", '│'),
+    ("dr", '┌'),
+    ("dl", '┐'),
+    ("ur", '└'),
+    ("ul", '┘'),
+    ("vr", '├'),
+    ("vl", '┤'),
+    ("dh", '┬'),
+    ("uh", '┴'),
+    ("vh", '┼'),
+];
+
+/// The character for the digraph `first` `second`, trying the reverse
+/// order when there is none in this order
+pub fn lookup(first: char, second: char) -> Option<char> {
+    let find = |a: char, b: char| {
+        DIGRAPHS.iter().find_map(|(code, c)| {
+            let mut chars = code.chars();
+            (chars.next() == Some(a) && chars.next() == Some(b)).then_some(*c)
+        })
+    };
+    find(first, second).or_else(|| find(second, first))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup('e', ':'), Some('ë'));
+        assert_eq!(lookup('a', '\''), Some('á'));
+        assert_eq!(lookup('E', 'u'), Some('€'));
+        assert_eq!(lookup('l', '*'), Some('λ'));
+        assert_eq!(lookup('-', '>'), Some('→'));
+    }
+
+    #[test]
+    fn test_lookup_reversed() {
+        assert_eq!(lookup(':', 'e'), Some('ë'));
+        assert_eq!(lookup('?', 'n'), Some('ñ'));
+        // The order written takes precedence when both exist
+        assert_eq!(lookup('=', '<'), Some('≤'));
+        assert_eq!(lookup('<', '='), Some('⇐'));
+    }
+
+    #[test]
+    fn test_unknown() {
+        assert_eq!(lookup('q', 'q'), None);
+    }
+
+    #[test]
+    fn test_codes_are_unique_pairs() {
+        let mut seen = std::collections::HashSet::new();
+        for (code, _) in DIGRAPHS {
+            assert_eq!(code.chars().count(), 2, "{code:?} is not two characters");
+            assert!(seen.insert(*code), "{code:?} is listed twice");
+        }
+    }
+}
@@ -4,27 +4,73 @@
 //! of characters and strings on a terminal. This is essential for proper
 //! cursor positioning, line wrapping, and UI layout with CJK characters,
 //! emoji, and other double-width or zero-width characters.
+//!
+//! Widths are those of grapheme clusters, which is how terminals draw text:
+//! an emoji ZWJ sequence (👨‍👩‍👧), a flag (🇯🇵) or a character with an emoji
+//! variation selector (❤️) takes two columns however many code points it
+//! has, and combining marks add nothing to their base. East Asian
+//! "ambiguous" characters take one column unless [`set_ambiguous_wide`]
+//! says the terminal draws them two wide (`editor.ambiguous_width`).
+
+use std::sync::atomic::{AtomicBool, Ordering};
 
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+/// Whether East Asian ambiguous-width characters count as two columns
+static AMBIGUOUS_WIDE: AtomicBool = AtomicBool::new(false);
+
+/// Count East Asian ambiguous-width characters (`±`, `①`, `Ω`, ...) as two
+/// columns instead of one, for terminals that draw them wide.
+pub fn set_ambiguous_wide(wide: bool) {
+    AMBIGUOUS_WIDE.store(wide, Ordering::Relaxed);
+}
+
+/// Whether East Asian ambiguous-width characters count as two columns
+#[inline]
+pub fn ambiguous_wide() -> bool {
+    AMBIGUOUS_WIDE.load(Ordering::Relaxed)
+}
+
 /// Calculate the display width of a single character.
 ///
 /// Returns 0 for control characters and zero-width characters,
 /// 2 for CJK/fullwidth characters and emoji,
 /// 1 for most other characters.
+///
+/// This is the width of the character on its own; text is measured by
+/// grapheme cluster with [`str_width`] or [`ClusterWidth`].
 #[inline]
 pub fn char_width(c: char) -> usize {
     // unicode_width returns None for control characters
-    c.width().unwrap_or(0)
+    if c.is_ascii() {
+        return c.width().unwrap_or(0);
+    }
+    if ambiguous_wide() {
+        c.width_cjk().unwrap_or(0)
+    } else {
+        c.width().unwrap_or(0)
+    }
 }
 
 /// Calculate the display width of a string.
 ///
-/// This is the sum of display widths of all characters in the string.
+/// This is the sum of the widths of its grapheme clusters.
 /// Use this instead of `.chars().count()` when calculating visual layout.
 #[inline]
 pub fn str_width(s: &str) -> usize {
-    s.width()
+    str_width_with(s, ambiguous_wide())
+}
+
+#[inline]
+fn str_width_with(s: &str, ambiguous_wide: bool) -> usize {
+    if s.is_ascii() {
+        s.width()
+    } else if ambiguous_wide {
+        s.width_cjk()
+    } else {
+        s.width()
+    }
 }
 
 /// Extension trait for convenient width calculation on string types.
@@ -47,27 +93,77 @@ impl DisplayWidth for String {
     }
 }
 
+/// Widths of text fed one character at a time, by grapheme cluster
+///
+/// Code that walks text character by character (rendering, wrapping) can't
+/// add up [`char_width`]s: 👨‍👩‍👧 would count as six columns where the
+/// terminal draws two. [`ClusterWidth::push`] returns the columns each
+/// character adds to the grapheme cluster it belongs to, so the first
+/// character of a cluster carries its width and the rest usually add 0.
+#[derive(Debug, Default)]
+pub struct ClusterWidth {
+    /// The grapheme cluster the last character belongs to, so far
+    cluster: String,
+    /// Width of `cluster`
+    width: usize,
+}
+
+impl ClusterWidth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Columns that `c` adds to the text fed so far
+    pub fn push(&mut self, c: char) -> usize {
+        // Only CR LF joins ASCII characters into one cluster, and both are
+        // zero-width
+        if !c.is_ascii() && !self.cluster.is_empty() {
+            self.cluster.push(c);
+            if self.cluster.graphemes(true).nth(1).is_none() {
+                let width = str_width(&self.cluster);
+                let added = width.saturating_sub(self.width);
+                self.width = self.width.max(width);
+                return added;
+            }
+        }
+        self.cluster.clear();
+        self.cluster.push(c);
+        self.width = char_width(c);
+        self.width
+    }
+
+    /// Forget the text fed so far: the next character starts a new cluster
+    pub fn reset(&mut self) {
+        self.cluster.clear();
+        self.width = 0;
+    }
+}
+
 /// Calculate the visual column (display width) at a given byte offset within a string.
 ///
-/// Returns the sum of display widths of all characters before the given byte offset.
+/// Returns the width of the grapheme clusters before the given byte offset.
 #[inline]
 pub fn visual_column_at_byte(s: &str, byte_offset: usize) -> usize {
-    s[..byte_offset.min(s.len())].chars().map(char_width).sum()
+    let mut end = byte_offset.min(s.len());
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    str_width(&s[..end])
 }
 
 /// Convert a visual column to a byte offset within a string.
 ///
-/// Returns the byte offset of the character that starts at or after the given visual column.
+/// Returns the byte offset of the grapheme cluster that starts at or after the given visual column.
 /// If the visual column is beyond the string's width, returns the string's length.
-/// This ensures the result is always at a valid UTF-8 character boundary.
+/// This ensures the result is always at a grapheme cluster boundary.
 #[inline]
 pub fn byte_offset_at_visual_column(s: &str, visual_col: usize) -> usize {
     let mut current_col = 0;
-    for (byte_idx, ch) in s.char_indices() {
+    for (byte_idx, grapheme) in s.grapheme_indices(true) {
         if current_col >= visual_col {
             return byte_idx;
         }
-        current_col += char_width(ch);
+        current_col += str_width(grapheme);
     }
     s.len()
 }
@@ -140,4 +236,61 @@ mod tests {
         let string = String::from("Hello🚀");
         assert_eq!(string.display_width(), 7);
     }
+
+    /// Total width of `s` fed to a `ClusterWidth` one character at a time
+    fn streamed_width(s: &str) -> usize {
+        let mut widths = ClusterWidth::new();
+        s.chars().map(|c| widths.push(c)).sum()
+    }
+
+    #[test]
+    fn test_grapheme_cluster_widths() {
+        let cases = [
+            ("👨\u{200D}👩\u{200D}👧", 2),   // ZWJ family
+            ("👍\u{1F3FD}", 2),              // skin tone modifier
+            ("❤\u{FE0F}", 2),                // emoji presentation selector
+            ("🇯🇵", 2),                       // regional indicator pair
+            ("e\u{301}", 1),                 // combining acute accent
+            ("\u{1100}\u{1161}\u{11A8}", 2), // Hangul jamo L V T
+            ("日本", 4),
+            ("a👨\u{200D}👩\u{200D}👧b", 4),
+        ];
+        for (text, width) in cases {
+            assert_eq!(str_width(text), width, "str_width({text:?})");
+            assert_eq!(streamed_width(text), width, "streamed width of {text:?}");
+        }
+    }
+
+    #[test]
+    fn test_cluster_width_first_char_carries_width() {
+        let mut widths = ClusterWidth::new();
+        let pushed: Vec<usize> = "e\u{301}x".chars().map(|c| widths.push(c)).collect();
+        assert_eq!(pushed, vec![1, 0, 1]);
+
+        widths.reset();
+        let pushed: Vec<usize> = "👨\u{200D}👩".chars().map(|c| widths.push(c)).collect();
+        assert_eq!(pushed, vec![2, 0, 0]);
+    }
+
+    #[test]
+    fn test_columns_and_offsets_by_grapheme() {
+        let text = "a👨\u{200D}👩\u{200D}👧b";
+        let family_end = text.len() - 1;
+        assert_eq!(visual_column_at_byte(text, 1), 1);
+        assert_eq!(visual_column_at_byte(text, family_end), 3);
+        // A column inside the family lands after it, never inside it
+        assert_eq!(byte_offset_at_visual_column(text, 2), family_end);
+        assert_eq!(byte_offset_at_visual_column(text, 3), family_end);
+
+        let accented = "e\u{301}z";
+        assert_eq!(byte_offset_at_visual_column(accented, 1), 3);
+    }
+
+    #[test]
+    fn test_ambiguous_width() {
+        assert_eq!(str_width_with("±Ω", false), 2);
+        assert_eq!(str_width_with("±Ω", true), 4);
+        // Unambiguous characters are unaffected
+        assert_eq!(str_width_with("a你", true), 3);
+    }
 }
@@ -520,7 +520,10 @@ mod tests {
                 filenames: vec!["CUSTOMBUILD".to_string()],
                 grammar: "Bourne Again Shell (bash)".to_string(),
                 comment_prefix: Some("#".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: crate::config::HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: true,
@@ -529,6 +532,7 @@ mod tests {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
 
@@ -25,7 +25,7 @@ use crate::view::theme::Theme;
 use std::ops::Range;
 use std::path::Path;
 use std::sync::Arc;
-use syntect::parsing::SyntaxSet;
+use syntect::parsing::{ParseState, ScopeStack, SyntaxSet};
 
 /// Map TextMate scope to highlight category
 fn scope_to_category(scope: &str) -> Option<HighlightCategory> {
@@ -212,6 +212,10 @@ pub struct TextMateEngine {
 struct TextMateCache {
     range: Range<usize>,
     spans: Vec<CachedSpan>,
+    /// Parser state at the start of every parsed line, in offset order
+    lines: Vec<LineState>,
+    /// Region edited since the last parse, in current buffer offsets
+    damage: Option<Range<usize>>,
 }
 
 #[derive(Debug, Clone)]
@@ -220,6 +224,20 @@ struct CachedSpan {
     category: crate::primitives::highlighter::HighlightCategory,
 }
 
+/// Where the parser was at the start of a line
+///
+/// Parsing can resume from any line that starts before an edit. A line after
+/// the edit whose content hash and start state match the cached ones parses
+/// exactly as it did before, so reparsing can stop there.
+#[derive(Debug, Clone, PartialEq)]
+struct LineState {
+    offset: usize,
+    /// Hash of the line's bytes, including its terminator
+    hash: u64,
+    parse_state: ParseState,
+    scopes: ScopeStack,
+}
+
 /// Maximum bytes to parse in a single operation
 const MAX_PARSE_BYTES: usize = 1024 * 1024;
 
@@ -259,6 +277,9 @@ impl TextMateEngine {
     ///
     /// `context_bytes` controls how far before/after the viewport to parse for accurate
     /// highlighting of multi-line constructs (strings, comments, nested blocks).
+    ///
+    /// If the cache covers the viewport, only the lines affected by edits since the
+    /// last call are parsed again.
     pub fn highlight_viewport(
         &mut self,
         buffer: &Buffer,
@@ -267,25 +288,15 @@ impl TextMateEngine {
         theme: &Theme,
         context_bytes: usize,
     ) -> Vec<HighlightSpan> {
-        use syntect::parsing::{ParseState, ScopeStack};
-
         // Check cache validity
-        if let Some(cache) = &self.cache {
-            if cache.range.start <= viewport_start
+        let covered = self.cache.as_ref().is_some_and(|cache| {
+            cache.range.start <= viewport_start
                 && cache.range.end >= viewport_end
                 && self.last_buffer_len == buffer.len()
-            {
-                return cache
-                    .spans
-                    .iter()
-                    .filter(|span| {
-                        span.range.start < viewport_end && span.range.end > viewport_start
-                    })
-                    .map(|span| HighlightSpan {
-                        range: span.range.clone(),
-                        color: highlight_color(span.category, theme),
-                    })
-                    .collect();
+        });
+        if covered && self.reparse_damage(buffer) {
+            if let Some(cache) = &self.cache {
+                return Self::visible_spans(&cache.spans, viewport_start, viewport_end, theme);
             }
         }
 
@@ -297,23 +308,135 @@ impl TextMateEngine {
             return Vec::new();
         }
 
+        // Get content
+        let content = buffer.slice_bytes(parse_start..parse_end);
+        if std::str::from_utf8(&content).is_err() {
+            return Vec::new();
+        }
+
         let syntax = &self.syntax_set.syntaxes()[self.syntax_index];
-        let mut state = ParseState::new(syntax);
         let mut spans = Vec::new();
+        let mut lines = Vec::new();
+        Self::parse_lines(
+            &self.syntax_set,
+            &content,
+            parse_start,
+            ParseState::new(syntax),
+            ScopeStack::new(),
+            &mut spans,
+            &mut lines,
+            |_| false,
+        );
 
-        // Get content
-        let content = buffer.slice_bytes(parse_start..parse_end);
-        let content_str = match std::str::from_utf8(&content) {
-            Ok(s) => s,
-            Err(_) => return Vec::new(),
+        // Merge adjacent spans
+        Self::merge_adjacent_spans(&mut spans);
+
+        let visible = Self::visible_spans(&spans, viewport_start, viewport_end, theme);
+
+        // Update cache
+        self.cache = Some(TextMateCache {
+            range: parse_start..parse_end,
+            spans,
+            lines,
+            damage: None,
+        });
+        self.last_buffer_len = buffer.len();
+
+        visible
+    }
+
+    /// Bring the cache up to date with the edits made since it was built
+    ///
+    /// Parsing resumes at the last line that starts at or before the damaged
+    /// region and stops at the first line after it that starts in the same
+    /// state with the same content as before; spans from there on are kept.
+    /// Returns false, dropping the cache, if no line to resume from survived
+    /// the edits.
+    fn reparse_damage(&mut self, buffer: &Buffer) -> bool {
+        let Some(cache) = self.cache.as_mut() else {
+            return false;
+        };
+        let Some(damage) = cache.damage.take() else {
+            return true;
         };
 
+        let resume = cache
+            .lines
+            .partition_point(|line| line.offset <= damage.start);
+        if resume == 0 {
+            self.cache = None;
+            return false;
+        }
+        let resume = resume - 1;
+        let resume_offset = cache.lines[resume].offset;
+
+        let content = buffer.slice_bytes(resume_offset..cache.range.end);
+        if std::str::from_utf8(&content).is_err() {
+            self.cache = None;
+            return false;
+        }
+
+        let old_lines = std::mem::take(&mut cache.lines);
+        let old_spans = std::mem::take(&mut cache.spans);
+        let mut lines = old_lines[..resume].to_vec();
+        let mut spans: Vec<CachedSpan> = old_spans
+            .iter()
+            .filter(|span| span.range.start < resume_offset)
+            .cloned()
+            .collect();
+
+        let later = &old_lines[resume..];
+        let converged_at = Self::parse_lines(
+            &self.syntax_set,
+            &content,
+            resume_offset,
+            later[0].parse_state.clone(),
+            later[0].scopes.clone(),
+            &mut spans,
+            &mut lines,
+            |line| {
+                line.offset >= damage.end
+                    && later
+                        .binary_search_by_key(&line.offset, |old| old.offset)
+                        .is_ok_and(|i| later[i] == *line)
+            },
+        );
+
+        if let Some(offset) = converged_at {
+            spans.extend(
+                old_spans
+                    .into_iter()
+                    .filter(|span| span.range.start >= offset),
+            );
+            lines.extend(old_lines.into_iter().filter(|line| line.offset >= offset));
+        }
+        Self::merge_adjacent_spans(&mut spans);
+
+        cache.spans = spans;
+        cache.lines = lines;
+        true
+    }
+
+    /// Parse `content_bytes`, which start at buffer offset `start`, from the given state
+    ///
+    /// Appends the highlighted spans and the state at the start of each line.
+    /// Stops before the first line after the first one for which `converged`
+    /// returns true, and returns that line's offset.
+    #[allow(clippy::too_many_arguments)]
+    fn parse_lines(
+        syntax_set: &SyntaxSet,
+        content_bytes: &[u8],
+        start: usize,
+        mut state: ParseState,
+        mut current_scopes: ScopeStack,
+        spans: &mut Vec<CachedSpan>,
+        lines: &mut Vec<LineState>,
+        mut converged: impl FnMut(&LineState) -> bool,
+    ) -> Option<usize> {
         // Parse line by line - manually track line boundaries to handle CRLF correctly
         // str::lines() strips both \n and \r\n, losing the distinction
-        let content_bytes = content_str.as_bytes();
         let mut pos = 0;
-        let mut current_offset = parse_start;
-        let mut current_scopes = ScopeStack::new();
+        let mut current_offset = start;
 
         while pos < content_bytes.len() {
             let line_start = pos;
@@ -339,6 +462,17 @@ impl TextMateEngine {
             let line_bytes = &content_bytes[line_start..line_end];
             let actual_line_byte_len = line_bytes.len();
 
+            let line_state = LineState {
+                offset: current_offset,
+                hash: hash_line(line_bytes),
+                parse_state: state.clone(),
+                scopes: current_scopes.clone(),
+            };
+            if current_offset > start && converged(&line_state) {
+                return Some(current_offset);
+            }
+            lines.push(line_state);
+
             // Create line string for syntect - strip CR if present, ensure single \n
             let line_str = match std::str::from_utf8(line_bytes) {
                 Ok(s) => s,
@@ -357,7 +491,7 @@ impl TextMateEngine {
                 line_content.to_string()
             };
 
-            let ops = match state.parse_line(&line_for_syntect, &self.syntax_set) {
+            let ops = match state.parse_line(&line_for_syntect, syntax_set) {
                 Ok(ops) => ops,
                 Err(_) => {
                     pos = line_end;
@@ -410,23 +544,21 @@ impl TextMateEngine {
             pos = line_end;
             current_offset += actual_line_byte_len;
         }
+        None
+    }
 
-        // Merge adjacent spans
-        Self::merge_adjacent_spans(&mut spans);
-
-        // Update cache
-        self.cache = Some(TextMateCache {
-            range: parse_start..parse_end,
-            spans: spans.clone(),
-        });
-        self.last_buffer_len = buffer.len();
-
-        // Filter and resolve colors
+    /// Resolve colors for the spans that overlap the viewport
+    fn visible_spans(
+        spans: &[CachedSpan],
+        viewport_start: usize,
+        viewport_end: usize,
+        theme: &Theme,
+    ) -> Vec<HighlightSpan> {
         spans
-            .into_iter()
+            .iter()
             .filter(|span| span.range.start < viewport_end && span.range.end > viewport_start)
             .map(|span| HighlightSpan {
-                range: span.range,
+                range: span.range.clone(),
                 color: highlight_color(span.category, theme),
             })
             .collect()
@@ -479,6 +611,118 @@ impl TextMateEngine {
         self.cache = None;
     }
 
+    /// Bytes held by the span and line state cache (parser states' own
+    /// allocations aren't visible, so only their scope stacks are counted)
+    pub fn cache_memory_usage(&self) -> usize {
+        self.cache.as_ref().map_or(0, |cache| {
+            cache.spans.capacity() * std::mem::size_of::<CachedSpan>()
+                + cache.lines.capacity() * std::mem::size_of::<LineState>()
+                + cache
+                    .lines
+                    .iter()
+                    .map(|line| {
+                        line.scopes.as_slice().len()
+                            * std::mem::size_of::<syntect::parsing::Scope>()
+                    })
+                    .sum::<usize>()
+        })
+    }
+
+    /// Record that `len` bytes were inserted at `position`
+    ///
+    /// Cached spans and line states after the insertion are shifted, and the
+    /// inserted text is marked for reparsing by the next `highlight_viewport`.
+    pub fn notify_insert(&mut self, position: usize, len: usize) {
+        self.last_buffer_len += len;
+        let Some(cache) = &mut self.cache else {
+            return;
+        };
+        if len == 0 || position > cache.range.end {
+            return;
+        }
+
+        let shift = |offset: usize| {
+            if offset > position {
+                offset + len
+            } else {
+                offset
+            }
+        };
+        // Text inserted at the end of a span or of the cache extends it
+        let shift_end = |offset: usize| {
+            if offset >= position {
+                offset + len
+            } else {
+                offset
+            }
+        };
+
+        let damaged = position >= cache.range.start;
+        cache.range = shift(cache.range.start)..shift_end(cache.range.end);
+        for span in &mut cache.spans {
+            if span.range.start >= position {
+                span.range = span.range.start + len..span.range.end + len;
+            } else {
+                span.range.end = shift_end(span.range.end);
+            }
+        }
+        for line in &mut cache.lines {
+            line.offset = shift(line.offset);
+        }
+        let damage = cache
+            .damage
+            .take()
+            .map(|d| shift(d.start)..shift_end(d.end));
+        cache.damage = match (damage, damaged) {
+            (Some(d), true) => Some(d.start.min(position)..d.end.max(position + len)),
+            (None, true) => Some(position..position + len),
+            (d, false) => d,
+        };
+    }
+
+    /// Record that `range` was deleted
+    ///
+    /// Cached spans and line states after the deletion are shifted, line states
+    /// inside it are dropped, and the lines around it are marked for reparsing.
+    pub fn notify_delete(&mut self, range: Range<usize>) {
+        self.last_buffer_len = self.last_buffer_len.saturating_sub(range.len());
+        let Some(cache) = &mut self.cache else {
+            return;
+        };
+        if range.is_empty() || range.start >= cache.range.end {
+            return;
+        }
+
+        let len = range.len();
+        let shift = |offset: usize| {
+            if offset >= range.end {
+                offset - len
+            } else {
+                offset.min(range.start)
+            }
+        };
+
+        let damaged = range.end > cache.range.start;
+        cache.range = shift(cache.range.start)..shift(cache.range.end);
+        cache.spans.retain_mut(|span| {
+            span.range = shift(span.range.start)..shift(span.range.end);
+            !span.range.is_empty()
+        });
+        // A line that started inside the deletion no longer starts a line
+        cache
+            .lines
+            .retain(|line| line.offset <= range.start || line.offset > range.end);
+        for line in &mut cache.lines {
+            line.offset = shift(line.offset);
+        }
+        let damage = cache.damage.take().map(|d| shift(d.start)..shift(d.end));
+        cache.damage = match (damage, damaged) {
+            (Some(d), true) => Some(d.start.min(range.start)..d.end.max(range.start)),
+            (None, true) => Some(range.start..range.start),
+            (d, false) => d,
+        };
+    }
+
     /// Get syntax name
     pub fn syntax_name(&self) -> &str {
         &self.syntax_set.syntaxes()[self.syntax_index].name
@@ -717,6 +961,27 @@ impl HighlightEngine {
         }
     }
 
+    /// Update the cache for `len` bytes inserted at `position`
+    ///
+    /// The TextMate backend reparses only the lines the insertion affects;
+    /// tree-sitter drops its cache if the insertion falls inside it.
+    pub fn notify_insert(&mut self, position: usize, len: usize) {
+        match self {
+            Self::TreeSitter(h) => h.invalidate_range(position..position + len),
+            Self::TextMate(h) => h.notify_insert(position, len),
+            Self::None => {}
+        }
+    }
+
+    /// Update the cache for a deleted range
+    pub fn notify_delete(&mut self, range: Range<usize>) {
+        match self {
+            Self::TreeSitter(h) => h.invalidate_range(range),
+            Self::TextMate(h) => h.notify_delete(range),
+            Self::None => {}
+        }
+    }
+
     /// Invalidate entire cache
     pub fn invalidate_all(&mut self) {
         match self {
@@ -726,6 +991,15 @@ impl HighlightEngine {
         }
     }
 
+    /// Bytes held by the highlighting cache
+    pub fn cache_memory_usage(&self) -> usize {
+        match self {
+            Self::TreeSitter(h) => h.cache_memory_usage(),
+            Self::TextMate(h) => h.cache_memory_usage(),
+            Self::None => 0,
+        }
+    }
+
     /// Check if this engine has highlighting available
     pub fn has_highlighting(&self) -> bool {
         !matches!(self, Self::None)
@@ -771,8 +1045,6 @@ pub fn highlight_string(
     registry: &GrammarRegistry,
     theme: &Theme,
 ) -> Vec<HighlightSpan> {
-    use syntect::parsing::{ParseState, ScopeStack};
-
     // Find syntax by language token (handles aliases like "py" -> Python)
     let syntax = match registry.syntax_set().find_syntax_by_token(lang_hint) {
         Some(s) => s,
@@ -850,6 +1122,14 @@ pub fn highlight_string(
     spans
 }
 
+/// Hash a line's bytes for comparing cached line states
+fn hash_line(line: &[u8]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    line.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// Map scope stack to highlight category (for highlight_string)
 fn scope_stack_to_category(scopes: &syntect::parsing::ScopeStack) -> Option<HighlightCategory> {
     for scope in scopes.as_slice().iter().rev() {
@@ -1130,4 +1410,127 @@ mod tests {
         assert_eq!(engine.backend_name(), "textmate");
         assert!(engine.has_highlighting());
     }
+
+    fn textmate_spans(
+        engine: &mut HighlightEngine,
+        buffer: &Buffer,
+        theme: &Theme,
+    ) -> Vec<(Range<usize>, ratatui::style::Color)> {
+        engine
+            .highlight_viewport(buffer, 0, buffer.len(), theme, 0)
+            .into_iter()
+            .map(|span| (span.range, span.color))
+            .collect()
+    }
+
+    /// Edits reported through notify_insert/notify_delete must give the same
+    /// highlighting as parsing the edited buffer from scratch.
+    #[test]
+    fn test_textmate_incremental_reparse_matches_full_parse() {
+        let registry =
+            GrammarRegistry::load(&crate::primitives::grammar::LocalGrammarLoader::embedded_only());
+        let theme = Theme::load_builtin(theme::THEME_LIGHT).unwrap();
+        let path = Path::new("Test.java");
+
+        let content =
+            "public class Test {\n    int count = 1; // counter\n    String name = \"x\";\n}\n"
+                .repeat(20);
+        let mut buffer = Buffer::from_bytes(content.into_bytes(), test_fs());
+        let mut engine = HighlightEngine::for_file(path, &registry);
+        assert_eq!(engine.backend_name(), "textmate");
+        textmate_spans(&mut engine, &buffer, &theme);
+
+        enum Edit {
+            Insert(usize, &'static str),
+            Delete(Range<usize>),
+        }
+        let edits = [
+            // Opening a block comment changes every following line
+            Edit::Insert(24, "/*"),
+            Edit::Delete(24..26),
+            // Edits within a line
+            Edit::Insert(30, "Total"),
+            Edit::Delete(40..44),
+            // Joining and splitting lines
+            Edit::Delete(70..90),
+            Edit::Insert(100, "\n\"open string\n"),
+            // At the start and end of the buffer
+            Edit::Insert(0, "// header\n"),
+            Edit::Delete(0..3),
+        ];
+
+        for edit in edits {
+            match edit {
+                Edit::Insert(position, text) => {
+                    buffer.insert(position, text);
+                    engine.notify_insert(position, text.len());
+                }
+                Edit::Delete(range) => {
+                    buffer.delete(range.clone());
+                    engine.notify_delete(range);
+                }
+            }
+            let end = buffer.len();
+            buffer.insert(end, "int tail;\n");
+            engine.notify_insert(end, "int tail;\n".len());
+
+            let mut fresh = HighlightEngine::for_file(path, &registry);
+            assert_eq!(
+                textmate_spans(&mut engine, &buffer, &theme),
+                textmate_spans(&mut fresh, &buffer, &theme)
+            );
+        }
+    }
+
+    /// After an edit that leaves the parser state unchanged at the next line,
+    /// the cached spans further down are reused rather than reparsed.
+    #[test]
+    fn test_textmate_reparse_stops_where_lines_converge() {
+        let registry =
+            GrammarRegistry::load(&crate::primitives::grammar::LocalGrammarLoader::embedded_only());
+        let theme = Theme::load_builtin(theme::THEME_LIGHT).unwrap();
+
+        let content = "int value = 1;\n".repeat(200);
+        let mut buffer = Buffer::from_bytes(content.into_bytes(), test_fs());
+        let mut engine = HighlightEngine::for_file(Path::new("Test.java"), &registry);
+        textmate_spans(&mut engine, &buffer, &theme);
+
+        let HighlightEngine::TextMate(ref mut tm) = engine else {
+            panic!("Expected TextMate engine for .java file");
+        };
+        let cache = tm.cache.as_mut().unwrap();
+        assert_eq!(cache.lines.len(), 200);
+        // Mark the last span so we can tell whether it gets reparsed
+        let last = cache.spans.last_mut().unwrap();
+        let marked = if last.category == HighlightCategory::Comment {
+            HighlightCategory::String
+        } else {
+            HighlightCategory::Comment
+        };
+        last.category = marked;
+
+        buffer.insert(16, "float ");
+        tm.notify_insert(16, "float ".len());
+        tm.highlight_viewport(&buffer, 0, buffer.len(), &theme, 0);
+
+        let cache = tm.cache.as_ref().unwrap();
+        assert_eq!(cache.spans.last().unwrap().category, marked);
+
+        // Everything before the marked span matches a full parse
+        let mut fresh = HighlightEngine::for_file(Path::new("Test.java"), &registry);
+        textmate_spans(&mut fresh, &buffer, &theme);
+        let HighlightEngine::TextMate(ref fresh_tm) = fresh else {
+            unreachable!();
+        };
+        let fresh_cache = fresh_tm.cache.as_ref().unwrap();
+        assert_eq!(fresh_cache.spans.len(), cache.spans.len());
+        for (span, fresh_span) in cache.spans.iter().zip(&fresh_cache.spans).rev().skip(1) {
+            assert_eq!(span.range, fresh_span.range);
+            assert_eq!(span.category, fresh_span.category);
+        }
+
+        assert_eq!(cache.lines.len(), 200);
+        assert!(cache.lines.windows(2).all(|w| w[0].offset < w[1].offset));
+        assert_eq!(cache.lines[2].offset, 32 + "float ".len());
+    }
 }
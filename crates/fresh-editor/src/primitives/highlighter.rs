@@ -230,6 +230,13 @@ impl Highlighter {
         self.cache = None;
     }
 
+    /// Bytes held by the span cache
+    pub fn cache_memory_usage(&self) -> usize {
+        self.cache.as_ref().map_or(0, |cache| {
+            cache.spans.capacity() * std::mem::size_of::<CachedSpan>()
+        })
+    }
+
     /// Get the current language
     pub fn language(&self) -> &Language {
         &self.language
@@ -52,6 +52,8 @@ const MAX_PARSE_BYTES: usize = 2000;
 pub struct IndentCalculator {
     /// Map of language to (parser, query)
     configs: HashMap<&'static str, (Parser, Query)>,
+    /// Indents query from the language config, used instead of the built-in one
+    query_override: Option<String>,
 }
 
 impl IndentCalculator {
@@ -59,6 +61,16 @@ impl IndentCalculator {
     pub fn new() -> Self {
         Self {
             configs: HashMap::new(),
+            query_override: None,
+        }
+    }
+
+    /// Use `query` instead of the built-in indents query, or go back to the
+    /// built-in one with None
+    pub fn set_query_override(&mut self, query: Option<String>) {
+        if self.query_override != query {
+            self.query_override = query;
+            self.configs.clear();
         }
     }
 
@@ -166,8 +178,21 @@ impl IndentCalculator {
                 return None;
             }
 
-            // Create query
-            let query = match Query::new(&ts_language, query_str) {
+            // Create query, preferring the configured one
+            let custom = self.query_override.as_deref().and_then(|custom| {
+                match Query::new(&ts_language, custom) {
+                    Ok(q) => Some(q),
+                    Err(e) => {
+                        tracing::warn!(
+                            "Invalid indents query for {}, using the built-in one: {:?}",
+                            lang_name,
+                            e
+                        );
+                        None
+                    }
+                }
+            });
+            let query = match custom.map_or_else(|| Query::new(&ts_language, query_str), Ok) {
                 Ok(q) => q,
                 Err(e) => {
                     tracing::error!("Failed to create query for {}: {:?}", lang_name, e);
@@ -0,0 +1,260 @@
+//! Indentation rules declared by a language, and hanging indents
+//!
+//! A language (or language pack) that the built-in indenters don't handle
+//! well can declare two regular expressions, as in TextMate and VS Code:
+//!
+//! - `increase`: a line matching it indents the next line one level more
+//!   (`def f():`, `do`, `<div>`)
+//! - `decrease`: a line matching it moves one level out as it is typed
+//!   (`end`, `else:`, `</div>`)
+//!
+//! [`hanging_indent`] aligns a wrapped argument list with the first argument
+//! after its unclosed `(` or `[`, for any language.
+
+use regex::Regex;
+
+/// Compiled indentation rules of a language
+#[derive(Debug, Clone)]
+pub struct IndentRules {
+    increase: Option<Regex>,
+    decrease: Option<Regex>,
+}
+
+impl IndentRules {
+    /// Compile the patterns; None when neither is given
+    pub fn new(
+        increase: Option<&str>,
+        decrease: Option<&str>,
+    ) -> Result<Option<Self>, regex::Error> {
+        if increase.is_none() && decrease.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            increase: increase.map(Regex::new).transpose()?,
+            decrease: decrease.map(Regex::new).transpose()?,
+        }))
+    }
+
+    /// Whether the line after `line` is indented one level more
+    pub fn increases(&self, line: &str) -> bool {
+        self.increase.as_ref().is_some_and(|re| re.is_match(line))
+    }
+
+    /// Indent, in columns, of a new line following `line`
+    pub fn indent_after(&self, line: &str, tab_size: usize) -> usize {
+        let indent = indent_columns(line, tab_size);
+        if self.increases(line) {
+            indent + tab_size
+        } else {
+            indent
+        }
+    }
+
+    /// Whether `line` moves out one level from the block it is in
+    pub fn decreases(&self, line: &str) -> bool {
+        self.decrease.as_ref().is_some_and(|re| re.is_match(line))
+    }
+
+    /// The indent `line` should move out to, if it matches the decrease
+    /// pattern and is indented deeper than one level inside `previous`, the
+    /// nearest non-blank line above it
+    pub fn dedent(&self, line: &str, previous: &str, tab_size: usize) -> Option<usize> {
+        if !self.decreases(line) {
+            return None;
+        }
+        let target = self
+            .indent_after(previous, tab_size)
+            .saturating_sub(tab_size);
+        (target < indent_columns(line, tab_size)).then_some(target)
+    }
+}
+
+/// Indent, in columns, `line` gets below `previous`, the nearest non-blank
+/// line above it
+///
+/// Follows the language's rules when it has them. Otherwise a line after an
+/// opening bracket or a `:` is indented one level more, and a line starting
+/// with a closing bracket one level less.
+pub fn indent_below(
+    rules: Option<&IndentRules>,
+    previous: &str,
+    line: &str,
+    tab_size: usize,
+) -> usize {
+    let (increases, decreases) = match rules {
+        Some(rules) => (rules.increases(previous), rules.decreases(line)),
+        None => {
+            let previous = previous.trim();
+            // A comment ending in a colon, like `# Steps:`, opens no block
+            let comment = previous.starts_with(['#', '/', '*', '-']);
+            (
+                previous.ends_with(['{', '[', '(']) || (previous.ends_with(':') && !comment),
+                line.trim_start().starts_with(['}', ']', ')']),
+            )
+        }
+    };
+    let indent = indent_columns(previous, tab_size) + if increases { tab_size } else { 0 };
+    if decreases {
+        indent.saturating_sub(tab_size)
+    } else {
+        indent
+    }
+}
+
+/// Column to continue `line` at when it ends inside a `(` or `[` that has
+/// text after it on the line, e.g. `call(first,` continues under `first`
+///
+/// Brackets inside string and character literals are skipped. Returns None
+/// when the innermost unclosed bracket is the last thing on the line, where
+/// the usual indent applies.
+pub fn hanging_indent(line: &str, tab_size: usize) -> Option<usize> {
+    let mut open: Vec<(char, usize)> = Vec::new();
+    let mut column = 0;
+    let mut chars = line.chars();
+    let mut quote: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        let start = column;
+        column = advance(column, c, tab_size);
+        match quote {
+            Some(q) => {
+                if c == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        column = advance(column, escaped, tab_size);
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '`' => quote = Some(c),
+                // A character literal; a lone quote is an apostrophe or lifetime
+                '\'' if is_char_literal(chars.clone()) => quote = Some(c),
+                '(' | '[' | '{' => open.push((c, start)),
+                ')' | ']' | '}' => {
+                    open.pop();
+                }
+                _ => {}
+            },
+        }
+    }
+
+    let &(bracket, bracket_column) = open.last()?;
+    if bracket == '{' {
+        return None;
+    }
+    // The first non-blank character after the bracket
+    let mut column = 0;
+    for c in line.chars() {
+        if column > bracket_column && !c.is_whitespace() {
+            return Some(column);
+        }
+        column = advance(column, c, tab_size);
+    }
+    None
+}
+
+/// Whether the text after an opening `'` closes it as a character literal
+fn is_char_literal(mut rest: impl Iterator<Item = char>) -> bool {
+    match rest.next() {
+        Some('\\') => true,
+        Some(_) => rest.next() == Some('\''),
+        None => false,
+    }
+}
+
+/// Column after `c` when it starts at `column`
+fn advance(column: usize, c: char, tab_size: usize) -> usize {
+    if c == '\t' {
+        let tab_size = tab_size.max(1);
+        (column / tab_size + 1) * tab_size
+    } else {
+        column + 1
+    }
+}
+
+/// Width in columns of the leading whitespace of `line`
+pub fn indent_columns(line: &str, tab_size: usize) -> usize {
+    line.chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .fold(0, |column, c| advance(column, c, tab_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ruby() -> IndentRules {
+        IndentRules::new(
+            Some(r"^\s*(def|class|if|else|do)\b"),
+            Some(r"^\s*(end|else)\b"),
+        )
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_no_patterns() {
+        assert!(IndentRules::new(None, None).unwrap().is_none());
+        assert!(IndentRules::new(Some("("), None).is_err());
+    }
+
+    #[test]
+    fn test_indent_after() {
+        let rules = ruby();
+        assert_eq!(rules.indent_after("  def f", 2), 4);
+        assert_eq!(rules.indent_after("  x = 1", 2), 2);
+        assert_eq!(rules.indent_after("\tif x", 4), 8);
+    }
+
+    #[test]
+    fn test_dedent() {
+        let rules = ruby();
+        // Closing a block whose body is at the line's level
+        assert_eq!(rules.dedent("    end", "    x = 1", 2), Some(2));
+        // Directly after the opening line
+        assert_eq!(rules.dedent("    else", "  if x", 2), Some(2));
+        // Already in place, or not a closing line
+        assert_eq!(rules.dedent("  end", "    x = 1", 2), None);
+        assert_eq!(rules.dedent("    ending = 1", "    x = 1", 2), None);
+    }
+
+    #[test]
+    fn test_indent_below() {
+        assert_eq!(indent_below(None, "fn main() {", "x();", 4), 4);
+        assert_eq!(indent_below(None, "    x();", "}", 4), 0);
+        assert_eq!(indent_below(None, "  def f():", "pass", 2), 4);
+        assert_eq!(indent_below(None, "# Steps:", "run()", 4), 0);
+        let rules = ruby();
+        assert_eq!(indent_below(Some(&rules), "  do", "end", 2), 2);
+        assert_eq!(indent_below(Some(&rules), "  x {", "y", 2), 2);
+    }
+
+    #[test]
+    fn test_hanging_indent() {
+        assert_eq!(hanging_indent("foo(a, ", 4), Some(4));
+        assert_eq!(hanging_indent("    x = call(first,", 4), Some(13));
+        assert_eq!(hanging_indent("items = [1, 2,", 4), Some(9));
+        // Nested: the innermost open bracket wins
+        assert_eq!(hanging_indent("f(a, g(b,", 4), Some(7));
+    }
+
+    #[test]
+    fn test_no_hanging_indent() {
+        // Bracket at the end of the line
+        assert_eq!(hanging_indent("foo(", 4), None);
+        // Everything closed
+        assert_eq!(hanging_indent("foo(a, b)", 4), None);
+        // Braces open a block
+        assert_eq!(hanging_indent("if (x) { y", 4), None);
+        // Brackets in strings and character literals
+        assert_eq!(hanging_indent("print(\")\", x", 4), Some(6));
+        assert_eq!(hanging_indent("s = \"(a, b", 4), None);
+        assert_eq!(hanging_indent("m(')', x", 4), Some(2));
+    }
+
+    #[test]
+    fn test_hanging_indent_with_lifetimes() {
+        assert_eq!(hanging_indent("fn f<'a>(x: &'a str,", 4), Some(9));
+    }
+}
@@ -0,0 +1,361 @@
+//! Indentation detection and conversion
+//!
+//! [`detect`] guesses whether a file is indented with tabs or spaces, and how
+//! many spaces make one level, from the lines' leading whitespace. The width
+//! is the most common change in indentation between consecutive lines.
+//!
+//! [`reindent`] rewrites the leading indentation of every line from one style
+//! to another. Lines that start inside a block comment or a multi-line string
+//! are left alone, and columns beyond a whole indent level are kept as
+//! spaces, so alignment is preserved.
+
+/// How a file's lines are indented
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    /// One tab per level
+    Tabs,
+    /// The given number of spaces per level
+    Spaces(usize),
+}
+
+/// Lines scanned at most by [`detect`]
+const MAX_DETECT_LINES: usize = 10_000;
+
+/// Largest indent width [`detect`] reports
+const MAX_INDENT_WIDTH: usize = 8;
+
+/// Guess the indentation of `text`
+///
+/// `comment_prefix` is the language's line comment prefix, used to skip
+/// lines inside block comments and multi-line strings. Returns None when the
+/// text has no indented lines, or as many indented with tabs as with spaces.
+pub fn detect(text: &str, comment_prefix: Option<&str>) -> Option<IndentStyle> {
+    let protected = protected_lines(text, comment_prefix);
+    let mut tab_lines = 0;
+    let mut space_lines = 0;
+    let mut deltas = [0usize; MAX_INDENT_WIDTH + 1];
+    let mut previous_spaces = 0;
+
+    for (line, protected) in text.lines().zip(protected).take(MAX_DETECT_LINES) {
+        if protected || line.trim().is_empty() {
+            continue;
+        }
+        let leading = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+        if leading.starts_with('\t') {
+            tab_lines += 1;
+            continue;
+        }
+        if leading.contains('\t') {
+            continue;
+        }
+        let spaces = leading.len();
+        if spaces > 0 {
+            space_lines += 1;
+        }
+        let delta = spaces.abs_diff(previous_spaces);
+        if (2..=MAX_INDENT_WIDTH).contains(&delta) {
+            deltas[delta] += 1;
+        }
+        previous_spaces = spaces;
+    }
+
+    if tab_lines > space_lines {
+        return Some(IndentStyle::Tabs);
+    }
+    if space_lines == 0 || tab_lines == space_lines {
+        return None;
+    }
+    // The most common change wins; the smaller width on a tie
+    let (width, count) = deltas
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|(_, count)| **count)?;
+    (*count > 0).then_some(IndentStyle::Spaces(width))
+}
+
+/// How [`reindent`] rewrites leading indentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reindent {
+    /// Display width of a tab in the existing text
+    pub tab_size: usize,
+    /// Columns per indent level in the existing text
+    pub from_width: usize,
+    /// Indentation of the result
+    pub to: IndentStyle,
+}
+
+/// Rewrite the leading indentation of each line of `text`
+///
+/// Each line's indentation is measured in columns and split into whole
+/// levels of `from_width` columns; the levels are written in the `to` style
+/// and the remaining columns as spaces. Lines starting inside a block comment
+/// or multi-line string are copied unchanged.
+pub fn reindent(text: &str, comment_prefix: Option<&str>, reindent: Reindent) -> String {
+    let protected = protected_lines(text, comment_prefix);
+    let tab_size = reindent.tab_size.max(1);
+    let from_width = reindent.from_width.max(1);
+    let mut result = String::with_capacity(text.len());
+
+    for (index, (line, protected)) in text.split('\n').zip(protected).enumerate() {
+        if index > 0 {
+            result.push('\n');
+        }
+        let body = line.trim_start_matches([' ', '\t']);
+        if protected {
+            result.push_str(line);
+            continue;
+        }
+        let columns = line[..line.len() - body.len()]
+            .chars()
+            .fold(0, |col, c| match c {
+                '\t' => (col / tab_size + 1) * tab_size,
+                _ => col + 1,
+            });
+        let (levels, extra) = (columns / from_width, columns % from_width);
+        match reindent.to {
+            IndentStyle::Tabs => result.push_str(&"\t".repeat(levels)),
+            IndentStyle::Spaces(width) => result.push_str(&" ".repeat(levels * width)),
+        }
+        result.push_str(&" ".repeat(extra));
+        result.push_str(body);
+    }
+    result
+}
+
+/// Scanner state at a point in the text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scan {
+    Code,
+    BlockComment,
+    /// Inside a string closed by `quote`; `multiline` strings continue onto
+    /// the next line when left open
+    Str {
+        quote: &'static str,
+        multiline: bool,
+    },
+}
+
+/// For each line of `text` (split at `\n`), whether it starts inside a block
+/// comment or a multi-line string
+///
+/// The syntax is guessed from the line comment prefix: languages using `//`
+/// get `/* */` comments and double-quoted and backtick strings that may span
+/// lines; languages using `#` get triple-quoted strings.
+fn protected_lines(text: &str, comment_prefix: Option<&str>) -> Vec<bool> {
+    let c_like = comment_prefix == Some("//");
+    let hash_like = comment_prefix == Some("#");
+    let line_comment = comment_prefix.filter(|prefix| !prefix.is_empty());
+    let mut state = Scan::Code;
+    let mut protected = Vec::new();
+
+    for line in text.split('\n') {
+        protected.push(state != Scan::Code);
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            match state {
+                Scan::Code => {
+                    if line_comment.is_some_and(|prefix| rest.starts_with(prefix)) {
+                        break;
+                    }
+                    let opened = if c_like && rest.starts_with("/*") {
+                        Some((Scan::BlockComment, 2))
+                    } else if hash_like && rest.starts_with("\"\"\"") {
+                        Some((
+                            Scan::Str {
+                                quote: "\"\"\"",
+                                multiline: true,
+                            },
+                            3,
+                        ))
+                    } else if hash_like && rest.starts_with("'''") {
+                        Some((
+                            Scan::Str {
+                                quote: "'''",
+                                multiline: true,
+                            },
+                            3,
+                        ))
+                    } else if c == '"' {
+                        Some((
+                            Scan::Str {
+                                quote: "\"",
+                                multiline: c_like,
+                            },
+                            1,
+                        ))
+                    } else if c_like && c == '`' {
+                        Some((
+                            Scan::Str {
+                                quote: "`",
+                                multiline: true,
+                            },
+                            1,
+                        ))
+                    } else {
+                        None
+                    };
+                    if let Some((next, len)) = opened {
+                        state = next;
+                        rest = &rest[len..];
+                    } else if c == '\'' {
+                        // Character literal or single-quoted string; a quote
+                        // with no partner (a lifetime, an apostrophe) is text
+                        rest = &rest[1..];
+                        if let Some(end) = closing_quote(rest, "'") {
+                            rest = &rest[end..];
+                        }
+                    } else {
+                        rest = &rest[c.len_utf8()..];
+                    }
+                }
+                Scan::BlockComment => match rest.find("*/") {
+                    Some(end) => {
+                        state = Scan::Code;
+                        rest = &rest[end + 2..];
+                    }
+                    None => break,
+                },
+                Scan::Str { quote, .. } => match closing_quote(rest, quote) {
+                    Some(end) => {
+                        state = Scan::Code;
+                        rest = &rest[end..];
+                    }
+                    None => break,
+                },
+            }
+        }
+        if let Scan::Str {
+            multiline: false, ..
+        } = state
+        {
+            state = Scan::Code;
+        }
+    }
+    protected
+}
+
+/// Byte offset just past the first unescaped `quote` in `text`
+fn closing_quote(text: &str, quote: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    while let Some((index, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if text[index..].starts_with(quote) {
+            return Some(index + quote.len());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_spaces() {
+        let text = "fn main() {\n  if x {\n    y();\n  }\n}\n";
+        assert_eq!(detect(text, Some("//")), Some(IndentStyle::Spaces(2)));
+        let text = "def f():\n    if x:\n        y()\n    return\n";
+        assert_eq!(detect(text, Some("#")), Some(IndentStyle::Spaces(4)));
+    }
+
+    #[test]
+    fn test_detect_tabs() {
+        let text = "func main() {\n\tif x {\n\t\ty()\n\t}\n}\n";
+        assert_eq!(detect(text, Some("//")), Some(IndentStyle::Tabs));
+    }
+
+    #[test]
+    fn test_detect_nothing_indented() {
+        assert_eq!(detect("one\ntwo\n", None), None);
+        assert_eq!(detect("\tone\n    two\n", None), None);
+    }
+
+    #[test]
+    fn test_detect_ignores_block_comment_alignment() {
+        let text = "/*\n * one\n * two\n * three\n */\nfn f() {\n    x();\n}\n";
+        assert_eq!(detect(text, Some("//")), Some(IndentStyle::Spaces(4)));
+    }
+
+    #[test]
+    fn test_reindent_spaces_to_tabs_keeps_alignment() {
+        let text = "fn f() {\n    call(a,\n         b);\n}\n";
+        let result = reindent(
+            text,
+            Some("//"),
+            Reindent {
+                tab_size: 4,
+                from_width: 4,
+                to: IndentStyle::Tabs,
+            },
+        );
+        assert_eq!(result, "fn f() {\n\tcall(a,\n\t\t b);\n}\n");
+    }
+
+    #[test]
+    fn test_reindent_tabs_to_spaces() {
+        let text = "{\n\tx\n\t\ty\n}";
+        let result = reindent(
+            text,
+            None,
+            Reindent {
+                tab_size: 4,
+                from_width: 4,
+                to: IndentStyle::Spaces(4),
+            },
+        );
+        assert_eq!(result, "{\n    x\n        y\n}");
+    }
+
+    #[test]
+    fn test_reindent_changes_width() {
+        let text = "a:\n  b:\n    c\n";
+        let result = reindent(
+            text,
+            Some("#"),
+            Reindent {
+                tab_size: 4,
+                from_width: 2,
+                to: IndentStyle::Spaces(4),
+            },
+        );
+        assert_eq!(result, "a:\n    b:\n        c\n");
+    }
+
+    #[test]
+    fn test_reindent_skips_strings_and_comments() {
+        let text = "let s = \"one\n  two\";\n/*\n  note\n*/\n  x\n";
+        let result = reindent(
+            text,
+            Some("//"),
+            Reindent {
+                tab_size: 4,
+                from_width: 2,
+                to: IndentStyle::Tabs,
+            },
+        );
+        assert_eq!(result, "let s = \"one\n  two\";\n/*\n  note\n*/\n\tx\n");
+
+        let text = "def f():\n  s = \"\"\"\n    text\n  \"\"\"\n  return s\n";
+        let result = reindent(
+            text,
+            Some("#"),
+            Reindent {
+                tab_size: 4,
+                from_width: 2,
+                to: IndentStyle::Spaces(4),
+            },
+        );
+        assert_eq!(
+            result,
+            "def f():\n    s = \"\"\"\n    text\n  \"\"\"\n    return s\n"
+        );
+    }
+
+    #[test]
+    fn test_quotes_in_comments_and_lifetimes() {
+        let text = "fn f<'a>(x: &'a str) {\n    // don't \"\n    x\n}\n";
+        assert_eq!(protected_lines(text, Some("//")), vec![false; 5]);
+    }
+}
@@ -5,7 +5,7 @@
 //! This module provides a single source of truth for how lines wrap,
 //! ensuring rendering and cursor positioning always agree.
 
-use crate::primitives::display_width::char_width;
+use crate::primitives::display_width::ClusterWidth;
 
 /// Represents a single wrapped segment of a logical line
 #[derive(Debug, Clone)]
@@ -111,10 +111,13 @@ pub fn wrap_line(text: &str, config: &WrapConfig) -> Vec<WrappedSegment> {
         // Take characters until we reach the visual width limit
         let mut segment_visual_width = 0;
         let segment_text_start = pos;
+        // Characters joined to a grapheme cluster add no width, so a segment
+        // never ends inside a cluster
+        let mut widths = ClusterWidth::new();
 
         while pos < chars.len() {
             let c = chars[pos];
-            let c_width = char_width(c);
+            let c_width = widths.push(c);
 
             // Check if adding this character would exceed the width
             // (but always include at least one character per segment to avoid infinite loops)
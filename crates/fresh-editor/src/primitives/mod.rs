@@ -15,12 +15,20 @@
 //! | Reference highlighting | `reference_highlight_text` | `reference_highlighter` |
 
 // Pure modules - available for both runtime and WASM
+pub mod align;
+pub mod comment_toggle;
+pub mod digraphs;
 pub mod display_width;
 pub mod grapheme;
+pub mod indent_rules;
+pub mod indentation;
 pub mod line_wrapping;
 pub mod path_utils;
+pub mod search_replace;
 pub mod snippet;
 pub mod text_property;
+pub mod text_transform;
+pub mod word_completion;
 
 // Modules depending on model::buffer - available for both runtime and WASM
 pub mod line_iterator;
@@ -64,3 +72,5 @@ pub mod highlighter;
 pub mod indent;
 #[cfg(feature = "runtime")]
 pub mod reference_highlighter;
+#[cfg(feature = "runtime")]
+pub mod structural_search;
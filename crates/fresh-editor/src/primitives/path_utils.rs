@@ -1,6 +1,6 @@
 //! Path utilities for path expansion and normalization.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Expand tilde (~) in a path to the user's home directory.
 ///
@@ -23,6 +23,47 @@ pub fn expand_tilde(path: &str) -> PathBuf {
     }
 }
 
+/// Match `text` against a glob pattern supporting `*` and `?`.
+///
+/// `*` matches any run of characters, including `/`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Check a file path against a glob pattern.
+///
+/// Patterns without a `/` (like `*.env`) match the file name; other patterns
+/// (like `~/notes/**` or `**/secrets/*`) match the whole path.
+pub fn path_matches_glob(pattern: &str, path: &Path) -> bool {
+    if pattern.contains('/') {
+        let pattern = expand_tilde(pattern);
+        glob_match(&pattern.to_string_lossy(), &path.to_string_lossy())
+    } else {
+        path.file_name()
+            .is_some_and(|name| glob_match(pattern, &name.to_string_lossy()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,4 +103,32 @@ mod tests {
             assert_eq!(result, home);
         }
     }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*", "host"));
+        assert!(glob_match("web?", "web1"));
+        assert!(!glob_match("web?", "web10"));
+        assert!(glob_match("*.example.com", "a.b.example.com"));
+        assert!(!glob_match("*.example.com", "example.org"));
+    }
+
+    #[test]
+    fn test_path_matches_glob() {
+        assert!(path_matches_glob("*.env", Path::new("/srv/app/.env")));
+        assert!(path_matches_glob("*.env", Path::new("/srv/app/prod.env")));
+        assert!(!path_matches_glob(
+            "*.env",
+            Path::new("/srv/app.env/main.rs")
+        ));
+        assert!(path_matches_glob(
+            "/etc/**",
+            Path::new("/etc/nginx/nginx.conf")
+        ));
+        assert!(path_matches_glob(
+            "**/secrets/*",
+            Path::new("/home/me/secrets/key")
+        ));
+        assert!(!path_matches_glob("/etc/**", Path::new("/home/etc/file")));
+    }
 }
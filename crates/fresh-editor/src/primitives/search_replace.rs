@@ -0,0 +1,319 @@
+//! Search patterns and replacement templates.
+//!
+//! Builds the search regex from the search options and expands replacement
+//! text for each match. In regex mode the replacement may use:
+//! - `$0`, `$1`, `${1}` - capture groups by number (`$0` is the whole match)
+//! - `$name`, `${name}` - named capture groups (`(?P<name>...)`)
+//! - `$$` - a literal dollar sign
+//! - `\u` / `\l` - uppercase / lowercase the next character
+//! - `\U` / `\L` - uppercase / lowercase until `\E`
+//! - `\n`, `\t`, `\\` - newline, tab, backslash
+//!
+//! When the search ignores case and the replacement has no uppercase letters,
+//! the replacement follows the case of each match (smart case): replacing
+//! `Foo` with `bar` gives `Bar`, and replacing `FOO` gives `BAR`.
+
+use std::ops::Range;
+
+use regex::{Captures, Regex, RegexBuilder};
+
+/// How the search query is matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub use_regex: bool,
+}
+
+impl SearchOptions {
+    /// Regex finding `query` with these options
+    pub fn regex(&self, query: &str) -> Result<Regex, regex::Error> {
+        let pattern = if self.use_regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let pattern = if self.whole_word {
+            format!(r"\b(?:{})\b", pattern)
+        } else {
+            pattern
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+    }
+}
+
+/// A match and the text replacing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+/// Expands a replacement template for each match
+#[derive(Debug, Clone)]
+pub struct Replacer {
+    template: String,
+    use_regex: bool,
+    smart_case: bool,
+}
+
+impl Replacer {
+    pub fn new(template: &str, options: SearchOptions) -> Self {
+        Self {
+            template: template.to_string(),
+            use_regex: options.use_regex,
+            smart_case: !options.case_sensitive && !template.chars().any(char::is_uppercase),
+        }
+    }
+
+    /// Text replacing the match in `caps`
+    pub fn replacement(&self, caps: &Captures) -> String {
+        let text = if self.use_regex {
+            expand_template(&self.template, caps)
+        } else {
+            self.template.clone()
+        };
+        if self.smart_case {
+            follow_case(&caps[0], text)
+        } else {
+            text
+        }
+    }
+
+    /// First match of `regex` in `text` at or after `start`
+    pub fn find_at(&self, regex: &Regex, text: &str, start: usize) -> Option<Replacement> {
+        let caps = regex.captures_at(text, start)?;
+        let m = caps.get(0)?;
+        Some(Replacement {
+            range: m.range(),
+            text: self.replacement(&caps),
+        })
+    }
+
+    /// All matches of `regex` in `text`, in order
+    pub fn find_all(&self, regex: &Regex, text: &str) -> Vec<Replacement> {
+        regex
+            .captures_iter(text)
+            .filter_map(|caps| {
+                let m = caps.get(0)?;
+                Some(Replacement {
+                    range: m.range(),
+                    text: self.replacement(&caps),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Case change applied to expanded text
+#[derive(Clone, Copy, PartialEq)]
+enum CaseChange {
+    None,
+    Upper,
+    Lower,
+}
+
+/// Output of a template expansion, applying `\u`, `\l`, `\U` and `\L`
+struct CaseWriter {
+    out: String,
+    span: CaseChange,
+    next: CaseChange,
+}
+
+impl CaseWriter {
+    fn push(&mut self, c: char) {
+        let change = match self.next {
+            CaseChange::None => self.span,
+            next => {
+                self.next = CaseChange::None;
+                next
+            }
+        };
+        match change {
+            CaseChange::None => self.out.push(c),
+            CaseChange::Upper => self.out.extend(c.to_uppercase()),
+            CaseChange::Lower => self.out.extend(c.to_lowercase()),
+        }
+    }
+
+    fn push_str(&mut self, s: &str) {
+        s.chars().for_each(|c| self.push(c));
+    }
+}
+
+/// Expand capture group references and case escapes in `template`
+fn expand_template(template: &str, caps: &Captures) -> String {
+    let mut writer = CaseWriter {
+        out: String::with_capacity(template.len()),
+        span: CaseChange::None,
+        next: CaseChange::None,
+    };
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('u') => writer.next = CaseChange::Upper,
+                Some('l') => writer.next = CaseChange::Lower,
+                Some('U') => writer.span = CaseChange::Upper,
+                Some('L') => writer.span = CaseChange::Lower,
+                Some('E') => writer.span = CaseChange::None,
+                Some('n') => writer.push('\n'),
+                Some('t') => writer.push('\t'),
+                Some('\\') => writer.push('\\'),
+                Some(other) => {
+                    writer.push('\\');
+                    writer.push(other);
+                }
+                None => writer.push('\\'),
+            },
+            '$' => match chars.peek().copied() {
+                Some('$') => {
+                    chars.next();
+                    writer.push('$');
+                }
+                Some('{') => {
+                    chars.next();
+                    let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                    writer.push_str(group(caps, &name));
+                }
+                Some(d) if d.is_ascii_digit() => {
+                    let mut name = String::new();
+                    while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                        name.push(d);
+                    }
+                    writer.push_str(group(caps, &name));
+                }
+                Some(n) if n.is_alphabetic() || n == '_' => {
+                    let mut name = String::new();
+                    while let Some(n) = chars.next_if(|&n| n.is_alphanumeric() || n == '_') {
+                        name.push(n);
+                    }
+                    writer.push_str(group(caps, &name));
+                }
+                _ => writer.push('$'),
+            },
+            c => writer.push(c),
+        }
+    }
+    writer.out
+}
+
+/// Text of capture group `name` (a number or a group name), or "" if it
+/// didn't take part in the match
+fn group<'t>(caps: &Captures<'t>, name: &str) -> &'t str {
+    let m = match name.parse::<usize>() {
+        Ok(index) => caps.get(index),
+        Err(_) => caps.name(name),
+    };
+    m.map_or("", |m| m.as_str())
+}
+
+/// Give `text` the case of `matched`: all caps if it's all caps, capitalized
+/// if it starts with a capital, unchanged otherwise
+fn follow_case(matched: &str, text: String) -> String {
+    let mut letters = matched.chars().filter(|c| c.is_alphabetic()).peekable();
+    let Some(&first) = letters.peek() else {
+        return text;
+    };
+    if !first.is_uppercase() {
+        return text;
+    }
+    let (count, all_upper) =
+        letters.fold((0, true), |(n, all), c| (n + 1, all && c.is_uppercase()));
+    if count > 1 && all_upper {
+        return text.to_uppercase();
+    }
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(case_sensitive: bool, use_regex: bool) -> SearchOptions {
+        SearchOptions {
+            case_sensitive,
+            whole_word: false,
+            use_regex,
+        }
+    }
+
+    fn replace_all(query: &str, template: &str, options: SearchOptions, text: &str) -> String {
+        let regex = options.regex(query).unwrap();
+        let replacer = Replacer::new(template, options);
+        let mut out = text.to_string();
+        for r in replacer.find_all(&regex, text).into_iter().rev() {
+            out.replace_range(r.range, &r.text);
+        }
+        out
+    }
+
+    #[test]
+    fn test_capture_groups() {
+        let opts = options(true, true);
+        assert_eq!(
+            replace_all(r"(\w+)=(\w+)", "$2=$1", opts, "a=1, b=2"),
+            "1=a, 2=b"
+        );
+        assert_eq!(
+            replace_all(r"(?P<key>\w+):", "${key}_x:$$", opts, "k: v"),
+            "k_x:$ v"
+        );
+        assert_eq!(replace_all(r"(\w)(\w)", "${1}0$2", opts, "ab"), "a0b");
+        // Groups that didn't match expand to nothing
+        assert_eq!(replace_all(r"a(x)?", "[$1]", opts, "a"), "[]");
+    }
+
+    #[test]
+    fn test_literal_mode_keeps_template() {
+        let opts = options(true, false);
+        assert_eq!(replace_all("a.b", r"$1\u", opts, "a.b axb"), r"$1\u axb");
+    }
+
+    #[test]
+    fn test_case_escapes() {
+        let opts = options(true, true);
+        assert_eq!(
+            replace_all(r"(\w+) (\w+)", r"\u$1 \U$2\E!", opts, "foo bar"),
+            "Foo BAR!"
+        );
+        assert_eq!(replace_all(r"\w+", r"\L$0", opts, "MiXeD"), "mixed");
+        assert_eq!(replace_all("x", r"a\nb\tc\\", opts, "x"), "a\nb\tc\\");
+    }
+
+    #[test]
+    fn test_smart_case() {
+        let opts = options(false, false);
+        assert_eq!(
+            replace_all("foo", "bar", opts, "foo Foo FOO"),
+            "bar Bar BAR"
+        );
+        // An uppercase letter in the replacement turns smart case off
+        assert_eq!(
+            replace_all("foo", "baR", opts, "foo Foo FOO"),
+            "baR baR baR"
+        );
+        // Case-sensitive searches keep the replacement as typed
+        assert_eq!(
+            replace_all("Foo", "bar", options(true, false), "Foo"),
+            "bar"
+        );
+    }
+
+    #[test]
+    fn test_whole_word_wraps_alternation() {
+        let opts = SearchOptions {
+            case_sensitive: true,
+            whole_word: true,
+            use_regex: true,
+        };
+        assert_eq!(replace_all("a|b", "x", opts, "a ab b"), "x ab x");
+    }
+}
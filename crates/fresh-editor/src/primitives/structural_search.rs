@@ -0,0 +1,444 @@
+//! Structural search and replace
+//!
+//! A pattern is a snippet of code in the buffer's language with
+//! metavariables: `$name` matches any single syntax node and `$$$name`
+//! matches any run of sibling nodes, including none. `$_` and `$$$` match
+//! without binding anything. Both the pattern and the buffer are parsed with
+//! tree-sitter and compared node by node, so layout and comments don't
+//! matter: `if ($a) { return $b; }` finds every `if` whose body is a single
+//! return, however it's formatted. A metavariable used twice must match the
+//! same text both times.
+//!
+//! A replacement template is plain text in which the metavariables stand for
+//! the text they matched. Lines after the first are indented like the line
+//! the match starts on.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use fresh_languages::tree_sitter::{Node, Parser, Tree};
+
+use crate::primitives::highlighter::Language;
+
+/// Identifier standing in for `$name` while the pattern is parsed
+const SINGLE_PREFIX: &str = "__fresh_mv_";
+/// Identifier standing in for `$$$name` while the pattern is parsed
+const MULTI_PREFIX: &str = "__fresh_mvs_";
+/// Metavariable name that matches without binding
+const ANONYMOUS: &str = "_";
+
+/// Why a pattern can't be used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// The language has no tree-sitter grammar
+    UnsupportedLanguage,
+    /// The pattern doesn't parse as code in the language
+    Syntax,
+    /// The pattern is empty or only a metavariable, so it matches everything
+    TooGeneral,
+}
+
+/// A match of a structural pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructuralMatch {
+    pub range: Range<usize>,
+    /// Text range bound to each metavariable
+    pub captures: HashMap<String, Range<usize>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Metavariable<'p> {
+    Single(&'p str),
+    Multi(&'p str),
+}
+
+/// A parsed structural search pattern
+pub struct StructuralPattern {
+    language: Language,
+    /// Pattern text with metavariables replaced by placeholder identifiers
+    source: String,
+    tree: Tree,
+}
+
+impl StructuralPattern {
+    pub fn new(pattern: &str, language: &Language) -> Result<Self, PatternError> {
+        let source = replace_metavariables(pattern.trim());
+        if source.is_empty() {
+            return Err(PatternError::TooGeneral);
+        }
+        let tree = parse(&source, language).ok_or(PatternError::UnsupportedLanguage)?;
+        if tree.root_node().has_error() {
+            return Err(PatternError::Syntax);
+        }
+        let pattern = Self {
+            language: *language,
+            source,
+            tree,
+        };
+        if pattern.metavariable(pattern.root()).is_some() {
+            return Err(PatternError::TooGeneral);
+        }
+        Ok(pattern)
+    }
+
+    /// Language the pattern is written in
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// Non-overlapping matches in `text`, in order
+    pub fn find_all(&self, text: &str) -> Vec<StructuralMatch> {
+        let Some(tree) = parse(text, &self.language) else {
+            return Vec::new();
+        };
+        let root = self.root();
+        let mut matches = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            let mut captures = HashMap::new();
+            if node.kind_id() == root.kind_id() && self.match_node(root, node, text, &mut captures)
+            {
+                matches.push(StructuralMatch {
+                    range: node.byte_range(),
+                    captures,
+                });
+                continue;
+            }
+            let mut children = code_children(node);
+            children.reverse();
+            stack.extend(children);
+        }
+        matches
+    }
+
+    /// Node the pattern stands for: the root, minus wrappers that cover all
+    /// of it (`source_file`, an expression statement, ...)
+    fn root(&self) -> Node<'_> {
+        let mut node = self.tree.root_node();
+        loop {
+            let mut named = code_children(node).into_iter().filter(Node::is_named);
+            match (named.next(), named.next()) {
+                (Some(child), None)
+                    if node.parent().is_none() || child.byte_range() == node.byte_range() =>
+                {
+                    node = child
+                }
+                _ => return node,
+            }
+        }
+    }
+
+    /// The metavariable pattern node `node` stands for, if any
+    fn metavariable(&self, node: Node) -> Option<Metavariable<'_>> {
+        let text = self.source[node.byte_range()].trim();
+        let text = text.strip_suffix(';').unwrap_or(text).trim_end();
+        let (name, multi) = match text.strip_prefix(MULTI_PREFIX) {
+            Some(name) => (name, true),
+            None => (text.strip_prefix(SINGLE_PREFIX)?, false),
+        };
+        if name.is_empty() || !name.chars().all(is_name_char) {
+            return None;
+        }
+        Some(if multi {
+            Metavariable::Multi(name)
+        } else {
+            Metavariable::Single(name)
+        })
+    }
+
+    fn match_node(
+        &self,
+        pattern: Node,
+        node: Node,
+        text: &str,
+        captures: &mut HashMap<String, Range<usize>>,
+    ) -> bool {
+        if let Some(Metavariable::Single(name) | Metavariable::Multi(name)) =
+            self.metavariable(pattern)
+        {
+            return bind(captures, name, node.byte_range(), text);
+        }
+        if pattern.kind_id() != node.kind_id() {
+            return false;
+        }
+        let pattern_children = code_children(pattern);
+        let children = code_children(node);
+        if pattern_children.is_empty() {
+            return children.is_empty()
+                && self.source[pattern.byte_range()] == text[node.byte_range()];
+        }
+        self.match_siblings(
+            &pattern_children,
+            &children,
+            node.start_byte(),
+            text,
+            captures,
+        )
+    }
+
+    /// Match a run of pattern siblings against a run of buffer siblings,
+    /// trying shorter runs for `$$$` metavariables first
+    fn match_siblings(
+        &self,
+        patterns: &[Node],
+        nodes: &[Node],
+        start: usize,
+        text: &str,
+        captures: &mut HashMap<String, Range<usize>>,
+    ) -> bool {
+        let Some((&pattern, rest)) = patterns.split_first() else {
+            return nodes.is_empty();
+        };
+        if let Some(Metavariable::Multi(name)) = self.metavariable(pattern) {
+            for taken in 0..=nodes.len() {
+                let range = match taken {
+                    0 => start..start,
+                    n => nodes[0].start_byte()..nodes[n - 1].end_byte(),
+                };
+                let saved = captures.clone();
+                if bind(captures, name, range.clone(), text)
+                    && self.match_siblings(rest, &nodes[taken..], range.end, text, captures)
+                {
+                    return true;
+                }
+                *captures = saved;
+            }
+            return false;
+        }
+        let Some((&node, remaining)) = nodes.split_first() else {
+            return false;
+        };
+        let saved = captures.clone();
+        if self.match_node(pattern, node, text, captures)
+            && self.match_siblings(rest, remaining, node.end_byte(), text, captures)
+        {
+            return true;
+        }
+        *captures = saved;
+        false
+    }
+}
+
+/// Bind metavariable `name` to `range`, or check that it matches the text it
+/// is already bound to
+fn bind(
+    captures: &mut HashMap<String, Range<usize>>,
+    name: &str,
+    range: Range<usize>,
+    text: &str,
+) -> bool {
+    if name == ANONYMOUS {
+        return true;
+    }
+    match captures.get(name) {
+        Some(bound) => text[bound.clone()] == text[range],
+        None => {
+            captures.insert(name.to_string(), range);
+            true
+        }
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Children of `node` other than comments and other extras
+fn code_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| !child.is_extra())
+        .collect()
+}
+
+/// Replace `$name` and `$$$name` with identifiers the grammar can parse
+fn replace_metavariables(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(index) = rest.find('$') {
+        out.push_str(&rest[..index]);
+        rest = &rest[index..];
+        let (prefix, after) = match rest.strip_prefix("$$$") {
+            Some(after) => (MULTI_PREFIX, after),
+            None => (SINGLE_PREFIX, &rest[1..]),
+        };
+        let name_len = after
+            .find(|c: char| !is_name_char(c))
+            .unwrap_or(after.len());
+        if name_len == 0 && prefix == SINGLE_PREFIX {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        let name = if name_len == 0 {
+            ANONYMOUS
+        } else {
+            &after[..name_len]
+        };
+        out.push_str(prefix);
+        out.push_str(name);
+        rest = &after[name_len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Text replacing `m`: `template` with its metavariables expanded
+pub fn expand_template(template: &str, text: &str, m: &StructuralMatch) -> String {
+    let line_start = text[..m.range.start].rfind('\n').map_or(0, |i| i + 1);
+    let indent: String = text[line_start..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '$' => {
+                let after = &template[index..];
+                let skip = if after.starts_with("$$$") { 3 } else { 1 };
+                let name: String = after[skip..]
+                    .chars()
+                    .take_while(|&c| is_name_char(c))
+                    .collect();
+                match m.captures.get(&name) {
+                    Some(range) if !name.is_empty() => {
+                        out.push_str(&text[range.clone()]);
+                        for _ in 1..skip + name.chars().count() {
+                            chars.next();
+                        }
+                    }
+                    _ => out.push('$'),
+                }
+            }
+            '\n' => {
+                out.push('\n');
+                out.push_str(&indent);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Whether `text` parses without syntax errors
+pub fn parses_cleanly(text: &str, language: &Language) -> bool {
+    parse(text, language).is_some_and(|tree| !tree.root_node().has_error())
+}
+
+fn parse(text: &str, language: &Language) -> Option<Tree> {
+    let ts_language = match language {
+        Language::Rust => fresh_languages::tree_sitter_rust::LANGUAGE.into(),
+        Language::Python => fresh_languages::tree_sitter_python::LANGUAGE.into(),
+        Language::JavaScript => fresh_languages::tree_sitter_javascript::LANGUAGE.into(),
+        Language::TypeScript => fresh_languages::tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        Language::Go => fresh_languages::tree_sitter_go::LANGUAGE.into(),
+        Language::C => fresh_languages::tree_sitter_c::LANGUAGE.into(),
+        Language::Cpp => fresh_languages::tree_sitter_cpp::LANGUAGE.into(),
+        Language::Java => fresh_languages::tree_sitter_java::LANGUAGE.into(),
+        Language::Php => fresh_languages::tree_sitter_php::LANGUAGE_PHP.into(),
+        Language::Ruby => fresh_languages::tree_sitter_ruby::LANGUAGE.into(),
+        Language::Bash => fresh_languages::tree_sitter_bash::LANGUAGE.into(),
+        Language::Lua => fresh_languages::tree_sitter_lua::LANGUAGE.into(),
+        Language::Pascal => fresh_languages::tree_sitter_pascal::LANGUAGE.into(),
+        Language::Json => fresh_languages::tree_sitter_json::LANGUAGE.into(),
+        Language::HTML => fresh_languages::tree_sitter_html::LANGUAGE.into(),
+        Language::CSS => fresh_languages::tree_sitter_css::LANGUAGE.into(),
+        Language::CSharp => fresh_languages::tree_sitter_c_sharp::LANGUAGE.into(),
+        Language::Odin => fresh_languages::tree_sitter_odin::LANGUAGE.into(),
+    };
+    let mut parser = Parser::new();
+    parser.set_language(&ts_language).ok()?;
+    parser.parse(text, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched<'t>(pattern: &str, language: Language, text: &'t str) -> Vec<&'t str> {
+        StructuralPattern::new(pattern, &language)
+            .unwrap()
+            .find_all(text)
+            .into_iter()
+            .map(|m| &text[m.range])
+            .collect()
+    }
+
+    fn replace_all(pattern: &str, template: &str, language: Language, text: &str) -> String {
+        let pattern = StructuralPattern::new(pattern, &language).unwrap();
+        let mut out = text.to_string();
+        for m in pattern.find_all(text).iter().rev() {
+            out.replace_range(m.range.clone(), &expand_template(template, text, m));
+        }
+        out
+    }
+
+    #[test]
+    fn test_matches_ignore_layout_and_comments() {
+        let text = "function f(x) {\n  if (x) { return 1; }\n  if (x > 0)\n  {\n    // done\n    return x;\n  }\n  if (y) { g(); return 2; }\n}\n";
+        assert_eq!(
+            matched("if ($a) { return $b; }", Language::JavaScript, text),
+            vec![
+                "if (x) { return 1; }",
+                "if (x > 0)\n  {\n    // done\n    return x;\n  }"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repeated_metavariable_must_match_same_text() {
+        let text = "a = a + 1\nb = c + 1\n";
+        assert_eq!(
+            matched("$x = $x + 1", Language::Python, text),
+            vec!["a = a + 1"]
+        );
+    }
+
+    #[test]
+    fn test_sequence_metavariable() {
+        let text = "fn main() {\n    foo();\n    foo(1, 2);\n    bar(3);\n}\n";
+        assert_eq!(
+            matched("foo($$$args)", Language::Rust, text),
+            vec!["foo()", "foo(1, 2)"]
+        );
+        assert_eq!(
+            replace_all("foo($$$args)", "baz($$$args)", Language::Rust, text),
+            "fn main() {\n    baz();\n    baz(1, 2);\n    bar(3);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_template_is_reindented() {
+        let text = "fn f() {\n    if a { return b; }\n}\n";
+        assert_eq!(
+            replace_all(
+                "if $c { return $v; }",
+                "if !$c {\n    return None;\n}\nreturn $v;",
+                Language::Rust,
+                text
+            ),
+            "fn f() {\n    if !a {\n        return None;\n    }\n    return b;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_rejected_patterns() {
+        let lang = Language::Rust;
+        assert_eq!(
+            StructuralPattern::new("$x", &lang).err(),
+            Some(PatternError::TooGeneral)
+        );
+        assert_eq!(
+            StructuralPattern::new("  ", &lang).err(),
+            Some(PatternError::TooGeneral)
+        );
+        assert_eq!(
+            StructuralPattern::new("if $a {", &lang).err(),
+            Some(PatternError::Syntax)
+        );
+        assert!(parses_cleanly("fn f() {}", &lang));
+        assert!(!parses_cleanly("fn f( {}", &lang));
+    }
+}
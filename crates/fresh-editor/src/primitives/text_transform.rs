@@ -0,0 +1,342 @@
+//! Case and number transformations of selected text
+//!
+//! [`camel_case`], [`snake_case`] and [`kebab_case`] split identifiers into
+//! words at `_`, `-`, spaces and case changes (`parseHTTPResponse` is
+//! `parse`, `HTTP`, `Response`) and join them again. Other punctuation and
+//! line breaks are kept, so `a.fooBar = b_c` converts each name on its own.
+//!
+//! [`increment_number`] finds the decimal or `0x` hexadecimal number at or
+//! after a column, as Vim's `Ctrl+A` does, and [`Sequence`] numbers a list
+//! of cursors.
+
+/// Capitalize the first letter of every word and lowercase the rest
+pub fn title_case(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_word = false;
+    for c in text.chars() {
+        if in_word {
+            out.extend(c.to_lowercase());
+        } else {
+            out.extend(c.to_uppercase());
+        }
+        // An apostrophe inside a word (`don't`) doesn't start a new one
+        in_word = c.is_alphanumeric() || (c == '\'' && in_word);
+    }
+    out
+}
+
+/// `fooBar`
+pub fn camel_case(text: &str) -> String {
+    convert_identifiers(text, |words| {
+        let mut out = String::new();
+        for (i, word) in words.iter().enumerate() {
+            if i == 0 {
+                out.push_str(&word.to_lowercase());
+            } else {
+                out.push_str(&capitalize(word));
+            }
+        }
+        out
+    })
+}
+
+/// `foo_bar`
+pub fn snake_case(text: &str) -> String {
+    convert_identifiers(text, |words| join_lowercase(words, "_"))
+}
+
+/// `foo-bar`
+pub fn kebab_case(text: &str) -> String {
+    convert_identifiers(text, |words| join_lowercase(words, "-"))
+}
+
+fn join_lowercase(words: &[String], separator: &str) -> String {
+    words
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Characters that separate the words of one identifier
+fn is_word_separator(c: char) -> bool {
+    matches!(c, '_' | '-' | ' ' | '\t')
+}
+
+/// Rewrite each run of words in `text` with `join`, keeping the separators
+/// around a run and everything between runs
+fn convert_identifiers(text: &str, join: impl Fn(&[String]) -> String) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let run_len = rest
+            .find(|c: char| !c.is_alphanumeric() && !is_word_separator(c))
+            .unwrap_or(rest.len());
+        if run_len == 0 {
+            let c = rest.chars().next().unwrap_or_default();
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let run = &rest[..run_len];
+        let core = run.trim_matches(is_word_separator);
+        let lead = run.len() - run.trim_start_matches(is_word_separator).len();
+        let words = split_words(core);
+        if words.is_empty() {
+            out.push_str(run);
+        } else {
+            out.push_str(&run[..lead]);
+            out.push_str(&join(&words));
+            out.push_str(&run[lead + core.len()..]);
+        }
+        rest = &rest[run_len..];
+    }
+    out
+}
+
+/// The words of an identifier, split at separators and case changes
+fn split_words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if !current.is_empty() {
+            let previous = chars[i - 1];
+            // `fooBar`, `v2Beta`, and the `R` of `HTTPResponse`
+            let boundary = c.is_uppercase()
+                && (previous.is_lowercase()
+                    || previous.is_numeric()
+                    || (previous.is_uppercase()
+                        && chars.get(i + 1).is_some_and(|next| next.is_lowercase())));
+            if boundary {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// A replacement of `line[start..end]` by `text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberEdit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// Add `delta` to the first number in `line` that ends after byte `offset`
+///
+/// A `-` right before a decimal number is its sign unless it follows a word
+/// character (`x-1` holds the number 1). Leading zeros keep the number's
+/// width, and hexadecimal numbers keep the case of their digits. Returns None
+/// when there is no such number or the result doesn't fit.
+pub fn increment_number(line: &str, offset: usize, delta: i64) -> Option<NumberEdit> {
+    let bytes = line.as_bytes();
+    let digits_end = |from: usize, hex: bool| {
+        from + bytes[from..]
+            .iter()
+            .take_while(|b| {
+                if hex {
+                    b.is_ascii_hexdigit()
+                } else {
+                    b.is_ascii_digit()
+                }
+            })
+            .count()
+    };
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let hex = bytes[i] == b'0'
+            && matches!(bytes.get(i + 1), Some(b'x' | b'X'))
+            && bytes.get(i + 2).is_some_and(u8::is_ascii_hexdigit);
+        let digits_start = if hex { start + 2 } else { start };
+        let end = digits_end(digits_start, hex);
+        if end <= offset {
+            i = end;
+            continue;
+        }
+
+        let digits = &line[digits_start..end];
+        if hex {
+            let value = i128::from_str_radix(digits, 16).ok()?;
+            let new_value = value.checked_add(delta as i128).filter(|v| *v >= 0)?;
+            let upper = digits.bytes().any(|b| b.is_ascii_uppercase())
+                && !digits.bytes().any(|b| b.is_ascii_lowercase());
+            let new_digits = if upper {
+                format!("{:0width$X}", new_value, width = digits.len())
+            } else {
+                format!("{:0width$x}", new_value, width = digits.len())
+            };
+            return Some(NumberEdit {
+                start: digits_start,
+                end,
+                text: new_digits,
+            });
+        }
+
+        let negative = start > 0
+            && bytes[start - 1] == b'-'
+            && (start == 1 || !is_word_byte(bytes[start - 2]));
+        let magnitude: i128 = digits.parse().ok()?;
+        let value = if negative { -magnitude } else { magnitude };
+        let new_value = value.checked_add(delta as i128)?;
+        let width = if digits.len() > 1 && digits.starts_with('0') {
+            digits.len()
+        } else {
+            0
+        };
+        return Some(NumberEdit {
+            start: if negative { start - 1 } else { start },
+            end,
+            text: format_padded(new_value, width),
+        });
+    }
+    None
+}
+
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// `value` with its digits zero-padded to `width`
+fn format_padded(value: i128, width: usize) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    format!("{}{:0width$}", sign, value.unsigned_abs(), width = width)
+}
+
+/// Numbers inserted at successive cursors: `start`, `start + step`, ...
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sequence {
+    pub start: i64,
+    pub step: i64,
+    /// Digits to zero-pad to, from a start like `001`
+    pub width: usize,
+}
+
+impl Sequence {
+    /// Parse `start` or `start, step` (a space also separates them)
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut parts = input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| !part.is_empty());
+        let start_text = parts.next()?;
+        let start = start_text.parse().ok()?;
+        let step = match parts.next() {
+            Some(step) => step.parse().ok()?,
+            None => 1,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        let digits = start_text.trim_start_matches(['-', '+']);
+        let width = if digits.len() > 1 && digits.starts_with('0') {
+            digits.len()
+        } else {
+            0
+        };
+        Some(Self { start, step, width })
+    }
+
+    /// The number for the `index`th cursor
+    pub fn nth(&self, index: usize) -> String {
+        let value = self.start as i128 + self.step as i128 * index as i128;
+        format_padded(value, self.width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_case() {
+        assert_eq!(title_case("hello WORLD"), "Hello World");
+        assert_eq!(title_case("don't stop-me"), "Don't Stop-Me");
+        assert_eq!(title_case("'quoted' text"), "'Quoted' Text");
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words("parseHTTPResponse"),
+            ["parse", "HTTP", "Response"]
+        );
+        assert_eq!(split_words("foo_bar-baz qux"), ["foo", "bar", "baz", "qux"]);
+        assert_eq!(split_words("v2Beta"), ["v2", "Beta"]);
+    }
+
+    #[test]
+    fn test_identifier_cases() {
+        assert_eq!(camel_case("user_id"), "userId");
+        assert_eq!(camel_case("Hello world"), "helloWorld");
+        assert_eq!(snake_case("parseHTTPResponse"), "parse_http_response");
+        assert_eq!(kebab_case("MyComponent"), "my-component");
+        // Punctuation, surrounding separators and line breaks stay
+        assert_eq!(snake_case("a.fooBar = bazQux;"), "a.foo_bar = baz_qux;");
+        assert_eq!(
+            camel_case("_private_name\nother_name"),
+            "_privateName\notherName"
+        );
+    }
+
+    #[test]
+    fn test_increment_number() {
+        let edit = |line: &str, offset, delta| {
+            let edit = increment_number(line, offset, delta).unwrap();
+            let mut result = line.to_string();
+            result.replace_range(edit.start..edit.end, &edit.text);
+            result
+        };
+        assert_eq!(edit("x = 41;", 0, 1), "x = 42;");
+        // The number under the cursor, not the one before it
+        assert_eq!(edit("1 and 9", 2, 1), "1 and 10");
+        assert_eq!(edit("a = -1", 0, 2), "a = 1");
+        assert_eq!(edit("x-1", 0, 1), "x-2");
+        assert_eq!(edit("id 007", 0, 1), "id 008");
+        assert_eq!(edit("0", 0, -1), "-1");
+        assert_eq!(edit("color 0xFF", 0, 1), "color 0x100");
+        assert_eq!(edit("0x0f", 0, 1), "0x10");
+        assert!(increment_number("no digits", 0, 1).is_none());
+        assert!(increment_number("12 at the start", 5, 1).is_none());
+    }
+
+    #[test]
+    fn test_sequence() {
+        let sequence = Sequence::parse("1").unwrap();
+        assert_eq!(sequence.nth(0), "1");
+        assert_eq!(sequence.nth(2), "3");
+        let sequence = Sequence::parse("10, -5").unwrap();
+        assert_eq!(sequence.nth(3), "-5");
+        let sequence = Sequence::parse("001 2").unwrap();
+        assert_eq!(sequence.nth(5), "011");
+        assert!(Sequence::parse("a").is_none());
+        assert!(Sequence::parse("1,2,3").is_none());
+    }
+}
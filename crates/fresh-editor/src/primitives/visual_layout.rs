@@ -11,11 +11,12 @@
 //! Handles:
 //! - ANSI escape sequences (zero visual width)
 //! - Double-width characters (CJK, emoji)
+//! - Grapheme clusters (emoji sequences, combining marks) as one unit of width
 //! - Tab expansion
 //! - Zero-width Unicode characters
 
 use crate::primitives::ansi::AnsiParser;
-use crate::primitives::display_width::char_width;
+use crate::primitives::display_width::ClusterWidth;
 
 /// Standard tab width for terminal display
 pub const TAB_WIDTH: usize = 8;
@@ -96,6 +97,7 @@ pub struct LineMappingsBuilder {
     mappings: LineMappings,
     current_visual_col: usize,
     ansi_parser: Option<AnsiParser>,
+    widths: ClusterWidth,
 }
 
 impl LineMappingsBuilder {
@@ -109,6 +111,7 @@ impl LineMappingsBuilder {
             } else {
                 None
             },
+            widths: ClusterWidth::new(),
         }
     }
 
@@ -130,9 +133,10 @@ impl LineMappingsBuilder {
 
         // Regular character (possibly zero-width Unicode)
         let width = if ch == '\t' {
+            self.widths.reset();
             tab_expansion_width(self.current_visual_col)
         } else {
-            char_width(ch)
+            self.widths.push(ch)
         };
 
         let char_idx = self.mappings.char_source_bytes.len();
@@ -150,6 +154,7 @@ impl LineMappingsBuilder {
 
     /// Add a tab character with custom expansion
     pub fn add_tab(&mut self, source_byte: Option<usize>) -> usize {
+        self.widths.reset();
         let width = tab_expansion_width(self.current_visual_col);
         let char_idx = self.mappings.char_source_bytes.len();
 
@@ -188,15 +193,17 @@ pub fn visual_width(s: &str, start_col: usize) -> usize {
 
     let mut col = start_col;
     let mut parser = AnsiParser::new();
+    let mut widths = ClusterWidth::new();
 
     for ch in s.chars() {
         if parser.parse_char(ch).is_none() {
             continue; // ANSI escape char, skip
         }
         if ch == '\t' {
+            widths.reset();
             col += tab_expansion_width(col);
         } else {
-            col += char_width(ch);
+            col += widths.push(ch);
         }
     }
 
@@ -217,6 +224,7 @@ pub fn byte_to_visual_col(s: &str, byte_offset: usize) -> usize {
     let mut col = 0;
     let mut current_byte = 0;
     let mut parser = AnsiParser::new();
+    let mut widths = ClusterWidth::new();
 
     for ch in s.chars() {
         if current_byte >= clamped_offset {
@@ -226,9 +234,10 @@ pub fn byte_to_visual_col(s: &str, byte_offset: usize) -> usize {
         if parser.parse_char(ch).is_some() {
             // Visible character
             if ch == '\t' {
+                widths.reset();
                 col += tab_expansion_width(col);
             } else {
-                col += char_width(ch);
+                col += widths.push(ch);
             }
         }
         // ANSI chars don't add to visual column
@@ -247,8 +256,9 @@ pub fn visual_col_to_byte(s: &str, target_visual_col: usize) -> usize {
     if !s.contains('\x1b') && !s.contains('\t') {
         // Fast path: use simple character iteration (no ANSI, no tabs)
         let mut col = 0;
+        let mut widths = ClusterWidth::new();
         for (byte_idx, ch) in s.char_indices() {
-            let width = char_width(ch);
+            let width = widths.push(ch);
             // Check if target falls within this character's visual range [col, col+width)
             if target_visual_col < col + width {
                 return byte_idx;
@@ -260,14 +270,16 @@ pub fn visual_col_to_byte(s: &str, target_visual_col: usize) -> usize {
 
     let mut col = 0;
     let mut parser = AnsiParser::new();
+    let mut widths = ClusterWidth::new();
 
     for (byte_idx, ch) in s.char_indices() {
         if parser.parse_char(ch).is_some() {
             // Visible character - check if target falls within this char's range
             let width = if ch == '\t' {
+                widths.reset();
                 tab_expansion_width(col)
             } else {
-                char_width(ch)
+                widths.push(ch)
             };
 
             // Target is within [col, col+width) range of this character
@@ -0,0 +1,195 @@
+//! Word completion from text, the fallback when no language server provides
+//! completions
+//!
+//! Candidates are the words that start with the typed prefix, gathered from
+//! the buffer being edited, the other open buffers and optionally a ctags
+//! `tags` file. Words of the buffer being edited come first, nearest to the
+//! cursor first; the others are ranked by how often they occur.
+
+use std::collections::HashMap;
+
+/// Words shorter than this are not worth completing
+pub const MIN_WORD_LEN: usize = 3;
+
+/// Where the best occurrence of a candidate was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WordSource {
+    /// The buffer being edited, with the distance in lines from the cursor
+    ActiveBuffer(usize),
+    /// Another open buffer
+    OtherBuffer,
+    /// A ctags `tags` file
+    Tags,
+}
+
+#[derive(Debug)]
+struct Candidate {
+    source: WordSource,
+    count: usize,
+}
+
+/// Collects and ranks the words matching a prefix
+#[derive(Debug)]
+pub struct WordCompletion {
+    prefix: String,
+    prefix_lower: String,
+    candidates: HashMap<String, Candidate>,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Words of `text` with their byte offsets
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| !is_word_char(c))
+        .filter(|word| !word.is_empty())
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+impl WordCompletion {
+    /// Start collecting completions for `prefix` (the partial word before the
+    /// cursor; matching ignores case)
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            prefix_lower: prefix.to_lowercase(),
+            candidates: HashMap::new(),
+        }
+    }
+
+    fn add(&mut self, word: &str, source: WordSource) {
+        if word.chars().count() < MIN_WORD_LEN
+            || word == self.prefix
+            || word.chars().next().is_some_and(|c| c.is_ascii_digit())
+            || !word.to_lowercase().starts_with(&self.prefix_lower)
+        {
+            return;
+        }
+        let candidate = self
+            .candidates
+            .entry(word.to_string())
+            .or_insert(Candidate { source, count: 0 });
+        candidate.source = candidate.source.min(source);
+        candidate.count += 1;
+    }
+
+    /// Add the words of the buffer being edited
+    ///
+    /// `text` is the buffer (or a window of it around the cursor) and
+    /// `cursor` the cursor's byte offset in it. The word under the cursor is
+    /// the one being typed and is skipped.
+    pub fn add_active_text(&mut self, text: &str, cursor: usize) {
+        let cursor = cursor.min(text.len());
+        let cursor_line = text.as_bytes()[..cursor]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        let mut line = 0;
+        let mut scanned = 0;
+        for (offset, word) in words(text) {
+            if offset <= cursor && cursor <= offset + word.len() {
+                continue;
+            }
+            line += text.as_bytes()[scanned..offset]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count();
+            scanned = offset;
+            self.add(word, WordSource::ActiveBuffer(line.abs_diff(cursor_line)));
+        }
+    }
+
+    /// Add the words of another open buffer
+    pub fn add_text(&mut self, text: &str) {
+        for (_, word) in words(text) {
+            self.add(word, WordSource::OtherBuffer);
+        }
+    }
+
+    /// Add the tag names of a ctags `tags` file
+    pub fn add_tags(&mut self, tags_file: &str) {
+        for line in tags_file.lines() {
+            if line.starts_with("!_TAG_") {
+                continue;
+            }
+            if let Some(name) = line.split('\t').next() {
+                if name.chars().all(is_word_char) {
+                    self.add(name, WordSource::Tags);
+                }
+            }
+        }
+    }
+
+    /// The candidates, best first, at most `limit` of them
+    pub fn ranked(self, limit: usize) -> Vec<(String, WordSource)> {
+        let mut ranked: Vec<(String, Candidate)> = self.candidates.into_iter().collect();
+        ranked.sort_by(|(a_word, a), (b_word, b)| {
+            a.source
+                .cmp(&b.source)
+                .then(b.count.cmp(&a.count))
+                .then(a_word.cmp(b_word))
+        });
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(word, candidate)| (word, candidate.source))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words_of(completion: WordCompletion) -> Vec<String> {
+        completion
+            .ranked(100)
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect()
+    }
+
+    #[test]
+    fn test_active_buffer_words_nearest_first() {
+        let text = "counter_far\n\n\n\ncounter_near\ncou\n";
+        let cursor = text.find("cou\n").unwrap() + 3;
+        let mut completion = WordCompletion::new("cou");
+        completion.add_active_text(text, cursor);
+        assert_eq!(words_of(completion), vec!["counter_near", "counter_far"]);
+    }
+
+    #[test]
+    fn test_skips_word_being_typed_and_short_words() {
+        let text = "value va valid\nval";
+        let mut completion = WordCompletion::new("val");
+        completion.add_active_text(text, text.len());
+        assert_eq!(words_of(completion), vec!["valid", "value"]);
+    }
+
+    #[test]
+    fn test_other_buffers_by_frequency_after_active_buffer() {
+        let mut completion = WordCompletion::new("re");
+        completion.add_active_text("result re", 9);
+        completion.add_text("render request request");
+        assert_eq!(words_of(completion), vec!["result", "request", "render"]);
+    }
+
+    #[test]
+    fn test_prefix_ignores_case() {
+        let mut completion = WordCompletion::new("http");
+        completion.add_text("HttpClient parse_http");
+        assert_eq!(words_of(completion), vec!["HttpClient"]);
+    }
+
+    #[test]
+    fn test_tags_file() {
+        let tags = "!_TAG_FILE_FORMAT\t2\t/extended format/\n\
+                    parse_config\tsrc/config.rs\t/^fn parse_config() {$/;\"\tf\n\
+                    ParseError\tsrc/error.rs\t/^pub struct ParseError;$/;\"\ts\n\
+                    operator ()\tsrc/a.cpp\t10;\"\tf\n";
+        let mut completion = WordCompletion::new("par");
+        completion.add_tags(tags);
+        assert_eq!(words_of(completion), vec!["ParseError", "parse_config"]);
+    }
+}
@@ -4,9 +4,9 @@
 //! to a buffer that can be sent to clients.
 
 use ratatui::backend::{Backend, ClearType, WindowSize};
-use ratatui::buffer::Cell;
+use ratatui::buffer::{Buffer, Cell};
 use ratatui::layout::{Position, Size};
-use ratatui::style::{Color, Modifier};
+use ratatui::style::{Color, Modifier, Style};
 use std::io::{self, Write};
 
 /// A backend that captures output to a buffer
@@ -67,6 +67,39 @@ impl CaptureBackend {
         self.current_modifiers = Modifier::empty();
     }
 
+    /// Encode a complete repaint of `buffer`, for a client whose screen
+    /// doesn't match the previous frame (e.g. one that just attached)
+    ///
+    /// The output leaves the client's style and cursor in the same state as
+    /// this backend, so later incremental draws apply on top of it.
+    pub fn full_frame(&self, buffer: &Buffer) -> Vec<u8> {
+        let mut frame = CaptureBackend::new(self.size.width, self.size.height);
+        frame.buffer.extend_from_slice(b"\x1b[0m");
+        let _ = frame.clear();
+        // Cells left blank by the clear don't need to be drawn
+        let blank = Buffer::empty(buffer.area);
+        let _ = frame.draw(blank.diff(buffer).into_iter());
+
+        frame.buffer.extend_from_slice(b"\x1b[0m");
+        frame.reset_style_state();
+        let mut style = Cell::default();
+        style.set_style(
+            Style::default()
+                .fg(self.current_fg)
+                .bg(self.current_bg)
+                .add_modifier(self.current_modifiers),
+        );
+        frame.write_style(&style);
+
+        if self.cursor_visible {
+            frame.buffer.extend_from_slice(b"\x1b[?25h");
+            frame.write_cursor_position(self.cursor.x, self.cursor.y);
+        } else {
+            frame.buffer.extend_from_slice(b"\x1b[?25l");
+        }
+        frame.take_buffer()
+    }
+
     /// Write ANSI escape sequence to move cursor
     fn write_cursor_position(&mut self, x: u16, y: u16) {
         // CSI row ; col H (1-based)
@@ -474,6 +507,24 @@ mod tests {
         assert!(teardown_str.contains("\x1b[0m"));
     }
 
+    #[test]
+    fn test_full_frame_repaints_and_matches_backend_state() {
+        let mut backend = CaptureBackend::new(10, 2);
+        let mut buffer = Buffer::empty(ratatui::layout::Rect::new(0, 0, 10, 2));
+        buffer.set_string(2, 1, "Hi", Style::default().fg(Color::Red));
+        let blank = Buffer::empty(buffer.area);
+        backend.draw(blank.diff(&buffer).into_iter()).unwrap();
+        backend.show_cursor().unwrap();
+        backend.set_cursor_position(Position::new(4, 1)).unwrap();
+        backend.clear_buffer();
+
+        let frame = String::from_utf8(backend.full_frame(&buffer)).unwrap();
+        assert!(frame.starts_with("\x1b[0m\x1b[2J\x1b[H"));
+        assert!(frame.contains("\x1b[2;3H\x1b[31mHi"));
+        // Ends in the backend's style (red) with its cursor shown at (4, 1)
+        assert!(frame.ends_with("\x1b[0m\x1b[31m\x1b[?25h\x1b[2;5H"));
+    }
+
     #[test]
     fn test_clear_region_variants() {
         let mut backend = CaptureBackend::new(80, 24);
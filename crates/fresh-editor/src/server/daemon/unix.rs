@@ -59,8 +59,12 @@ pub fn daemonize() -> io::Result<()> {
 ///
 /// This is used when the client starts and no server is running.
 /// The server inherits the current working directory.
+/// `server_args` are passed through to the server (e.g. `--profile NAME`).
 /// Returns the PID of the spawned server (intermediate, not final daemon PID).
-pub fn spawn_server_detached(session_name: Option<&str>) -> io::Result<u32> {
+pub fn spawn_server_detached(
+    session_name: Option<&str>,
+    server_args: &[String],
+) -> io::Result<u32> {
     let exe = std::env::current_exe()?;
 
     let mut args = vec!["--server".to_string()];
@@ -69,6 +73,7 @@ pub fn spawn_server_detached(session_name: Option<&str>) -> io::Result<u32> {
         args.push("--session-name".to_string());
         args.push(name.to_string());
     }
+    args.extend_from_slice(server_args);
 
     // Use Command to spawn, which properly handles the process
     let child = std::process::Command::new(&exe)
@@ -27,8 +27,12 @@ pub fn daemonize() -> io::Result<()> {
 ///
 /// This is used when the client starts and no server is running.
 /// The server inherits the current working directory.
+/// `server_args` are passed through to the server (e.g. `--profile NAME`).
 /// Returns the PID of the spawned server.
-pub fn spawn_server_detached(session_name: Option<&str>) -> io::Result<u32> {
+pub fn spawn_server_detached(
+    session_name: Option<&str>,
+    server_args: &[String],
+) -> io::Result<u32> {
     let exe = std::env::current_exe()?;
 
     let mut cmd = std::process::Command::new(&exe);
@@ -37,6 +41,7 @@ pub fn spawn_server_detached(session_name: Option<&str>) -> io::Result<u32> {
     if let Some(name) = session_name {
         cmd.arg("--session-name").arg(name);
     }
+    cmd.args(server_args);
 
     cmd.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
     cmd.stdin(std::process::Stdio::null());
@@ -0,0 +1,161 @@
+//! Framing and compression for the data channel
+//!
+//! Without compression the data channel carries raw terminal output. When the
+//! handshake negotiates `Compression::Zstd`, every write from the server is
+//! sent as one frame: a 4-byte little-endian payload length, a kind byte
+//! (`FRAME_RAW` or `FRAME_ZSTD`) and the payload. Small writes stay raw, since
+//! compressing a few bytes of cursor movement only adds overhead.
+//!
+//! Input from the client to the server is never framed.
+
+use super::protocol::Compression;
+use std::io;
+
+/// Payload is uncompressed terminal output
+const FRAME_RAW: u8 = 0;
+/// Payload is a zstd frame
+const FRAME_ZSTD: u8 = 1;
+/// Length prefix plus kind byte
+const HEADER_LEN: usize = 5;
+/// Writes shorter than this are sent uncompressed
+const MIN_COMPRESS_LEN: usize = 256;
+/// zstd level: fast, still shrinks full frames several times over
+const ZSTD_LEVEL: i32 = 3;
+
+/// Server side: turns terminal output into data channel bytes
+#[derive(Debug)]
+pub struct DataEncoder {
+    compression: Option<Compression>,
+}
+
+impl DataEncoder {
+    pub fn new(compression: Option<Compression>) -> Self {
+        Self { compression }
+    }
+
+    /// Encode one write of terminal output
+    pub fn encode(&mut self, data: &[u8]) -> Vec<u8> {
+        match self.compression {
+            None => data.to_vec(),
+            Some(Compression::Zstd) => {
+                let compressed = (data.len() >= MIN_COMPRESS_LEN)
+                    .then(|| zstd::bulk::compress(data, ZSTD_LEVEL).ok())
+                    .flatten()
+                    .filter(|compressed| compressed.len() < data.len());
+                match compressed {
+                    Some(payload) => frame(FRAME_ZSTD, &payload),
+                    None => frame(FRAME_RAW, data),
+                }
+            }
+        }
+    }
+}
+
+fn frame(kind: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(HEADER_LEN + payload.len());
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.push(kind);
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Client side: turns data channel bytes back into terminal output
+///
+/// Reads may split frames anywhere, so incomplete frames are kept until the
+/// rest arrives.
+#[derive(Debug)]
+pub struct DataDecoder {
+    compression: Option<Compression>,
+    pending: Vec<u8>,
+}
+
+impl DataDecoder {
+    pub fn new(compression: Option<Compression>) -> Self {
+        Self {
+            compression,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Decode the bytes of one read, returning the terminal output they complete
+    pub fn decode(&mut self, data: &[u8]) -> io::Result<Vec<u8>> {
+        if self.compression.is_none() {
+            return Ok(data.to_vec());
+        }
+
+        self.pending.extend_from_slice(data);
+        let mut output = Vec::new();
+        let mut consumed = 0;
+        while self.pending.len() - consumed >= HEADER_LEN {
+            let header = &self.pending[consumed..consumed + HEADER_LEN];
+            let len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+            let kind = header[4];
+            let start = consumed + HEADER_LEN;
+            if self.pending.len() - start < len {
+                break;
+            }
+            let payload = &self.pending[start..start + len];
+            match kind {
+                FRAME_RAW => output.extend_from_slice(payload),
+                FRAME_ZSTD => output.extend_from_slice(&zstd::stream::decode_all(payload)?),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unknown data frame kind {}", kind),
+                    ))
+                }
+            }
+            consumed = start + len;
+        }
+        self.pending.drain(..consumed);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uncompressed_channel_passes_bytes_through() {
+        let mut encoder = DataEncoder::new(None);
+        let mut decoder = DataDecoder::new(None);
+        let encoded = encoder.encode(b"\x1b[1;1Hhello");
+        assert_eq!(encoded, b"\x1b[1;1Hhello");
+        assert_eq!(decoder.decode(&encoded).unwrap(), b"\x1b[1;1Hhello");
+    }
+
+    #[test]
+    fn test_large_writes_are_compressed_and_round_trip() {
+        let mut encoder = DataEncoder::new(Some(Compression::Zstd));
+        let mut decoder = DataDecoder::new(Some(Compression::Zstd));
+        let frame = "\x1b[38;5;245m~ ".repeat(500).into_bytes();
+
+        let encoded = encoder.encode(&frame);
+        assert_eq!(encoded[4], FRAME_ZSTD);
+        assert!(encoded.len() < frame.len() / 4);
+        assert_eq!(decoder.decode(&encoded).unwrap(), frame);
+    }
+
+    #[test]
+    fn test_small_writes_are_framed_raw() {
+        let mut encoder = DataEncoder::new(Some(Compression::Zstd));
+        let encoded = encoder.encode(b"x");
+        assert_eq!(encoded, [1, 0, 0, 0, FRAME_RAW, b'x']);
+    }
+
+    #[test]
+    fn test_decoder_waits_for_split_frames() {
+        let mut encoder = DataEncoder::new(Some(Compression::Zstd));
+        let mut decoder = DataDecoder::new(Some(Compression::Zstd));
+        let first = "a".repeat(1000).into_bytes();
+        let mut stream = encoder.encode(&first);
+        stream.extend(encoder.encode(b"tail"));
+
+        let (head, rest) = stream.split_at(3);
+        assert!(decoder.decode(head).unwrap().is_empty());
+        let (middle, end) = rest.split_at(rest.len() - 2);
+        assert_eq!(decoder.decode(middle).unwrap(), first);
+        assert_eq!(decoder.decode(end).unwrap(), b"tail");
+    }
+}
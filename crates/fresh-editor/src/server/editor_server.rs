@@ -13,20 +13,31 @@ use std::time::{Duration, Instant};
 
 use crossterm::event::{Event, KeyEventKind};
 use ratatui::Terminal;
+use rust_i18n::t;
 
 use crate::app::Editor;
-use crate::config::Config;
+use crate::config::{Config, SessionIdleAction};
 use crate::config_io::DirectoryContext;
 use crate::model::filesystem::{FileSystem, StdFileSystem};
 use crate::server::capture_backend::{
     terminal_setup_sequences, terminal_teardown_sequences, CaptureBackend,
 };
+use crate::server::data_codec::DataEncoder;
 use crate::server::input_parser::InputParser;
 use crate::server::ipc::{ServerConnection, ServerListener, SocketPaths};
 use crate::server::protocol::{
-    ClientControl, ServerControl, ServerHello, TermSize, VersionMismatch, PROTOCOL_VERSION,
+    ClientControl, Compression, DataStats, ServerControl, ServerHello, SessionBufferInfo,
+    SessionClientInfo, SessionInfo, TermSize, VersionMismatch, PROTOCOL_VERSION,
 };
+use crate::services::process_limits::SystemResources;
 use crate::view::color_support::ColorCapability;
+use crate::workspace::Workspace;
+
+/// How often the session state is written to disk while it is changing
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long before an idle shutdown attached clients see a countdown
+const IDLE_WARNING: Duration = Duration::from_secs(60);
 
 /// Configuration for the editor server
 #[derive(Debug, Clone)]
@@ -35,8 +46,10 @@ pub struct EditorServerConfig {
     pub working_dir: PathBuf,
     /// Optional session name
     pub session_name: Option<String>,
-    /// Idle timeout before auto-shutdown
+    /// Time without input before auto-shutdown (None = never)
     pub idle_timeout: Option<Duration>,
+    /// What to do with the session state on an idle shutdown
+    pub idle_action: SessionIdleAction,
     /// Editor configuration
     pub editor_config: Config,
     /// Directory context for config/data paths
@@ -53,6 +66,16 @@ pub struct EditorServer {
     editor: Option<Editor>,
     terminal: Option<Terminal<CaptureBackend>>,
     last_client_activity: Instant,
+    /// When the server started (for uptime in session info)
+    started_at: Instant,
+    /// Where the session state is saved so a restarted server can restore it
+    snapshot_path: PathBuf,
+    /// When the session state was last saved
+    last_snapshot: Instant,
+    /// Whether the session state changed since the last snapshot
+    snapshot_dirty: bool,
+    /// Countdown message currently shown before an idle shutdown
+    idle_warning: Option<String>,
     shutdown: Arc<AtomicBool>,
     /// Effective terminal size (from the primary/first client)
     term_size: TermSize,
@@ -69,11 +92,16 @@ struct ConnectedClient {
     input_parser: InputParser,
     /// Whether this client needs a full screen render on next frame
     needs_full_render: bool,
+    /// Negotiated data channel compression
+    compression: Option<Compression>,
+    encoder: DataEncoder,
+    /// Output sent so far (for session info)
+    traffic: DataStats,
 }
 
 impl EditorServer {
     /// Create a new editor server
-    pub fn new(config: EditorServerConfig) -> io::Result<Self> {
+    pub fn new(mut config: EditorServerConfig) -> io::Result<Self> {
         let socket_paths = if let Some(ref name) = config.session_name {
             SocketPaths::for_session_name(name)?
         } else {
@@ -88,6 +116,29 @@ impl EditorServer {
             tracing::warn!("Failed to write PID file: {}", e);
         }
 
+        let session_id = config
+            .session_name
+            .clone()
+            .unwrap_or_else(|| crate::workspace::encode_path_for_filename(&config.working_dir));
+        let snapshot_path = config
+            .dir_context
+            .data_dir
+            .join("sessions")
+            .join(format!("{}.json", session_id));
+
+        // Shells in the session's terminals find the session through this
+        std::env::set_var(super::shell_init::SESSION_ENV_VAR, &session_id);
+
+        // A named session keeps its working directory when it is restarted
+        // from somewhere else (e.g. `fresh -a NAME` after a reboot)
+        if config.session_name.is_some() {
+            if let Ok(Some(snapshot)) = Workspace::read_file(&snapshot_path) {
+                if snapshot.working_dir.is_dir() {
+                    config.working_dir = snapshot.working_dir;
+                }
+            }
+        }
+
         Ok(Self {
             config,
             listener,
@@ -95,6 +146,11 @@ impl EditorServer {
             editor: None,
             terminal: None,
             last_client_activity: Instant::now(),
+            started_at: Instant::now(),
+            snapshot_path,
+            last_snapshot: Instant::now(),
+            snapshot_dirty: false,
+            idle_warning: None,
             shutdown: Arc::new(AtomicBool::new(false)),
             term_size: TermSize::new(80, 24), // Default until first client connects
             last_input_client: None,
@@ -118,6 +174,7 @@ impl EditorServer {
         let mut next_client_id = 1u64;
         let mut needs_render = true;
         let mut last_render = Instant::now();
+        let mut idle_shutdown = false;
         const FRAME_DURATION: Duration = Duration::from_millis(16); // 60fps
 
         loop {
@@ -129,10 +186,18 @@ impl EditorServer {
 
             // Check idle timeout
             if let Some(timeout) = self.config.idle_timeout {
-                if self.clients.is_empty() && self.last_client_activity.elapsed() > timeout {
-                    tracing::info!("Idle timeout reached, shutting down");
+                let idle = self.last_client_activity.elapsed();
+                if idle >= timeout {
+                    tracing::info!(
+                        "Idle timeout reached, shutting down ({:?})",
+                        self.config.idle_action
+                    );
+                    idle_shutdown = true;
                     break;
                 }
+                if !self.clients.is_empty() && timeout - idle <= IDLE_WARNING {
+                    needs_render |= self.show_idle_warning(timeout - idle);
+                }
             }
 
             // Accept new connections
@@ -143,7 +208,7 @@ impl EditorServer {
                     let cursor_style = self
                         .editor
                         .as_ref()
-                        .map(|e| e.config().editor.cursor_style)
+                        .map(|e| e.cursor_style())
                         .unwrap_or(self.config.editor_config.editor.cursor_style);
                     match self.handle_new_connection(conn, next_client_id, cursor_style) {
                         Ok(client) => {
@@ -163,6 +228,9 @@ impl EditorServer {
                             }
                             // Note: full redraw is handled via client.needs_full_render flag
 
+                            if let Some(ref mut editor) = self.editor {
+                                editor.collab_join(client.id);
+                            }
                             self.clients.push(client);
                             self.last_client_activity = Instant::now();
                             next_client_id += 1;
@@ -212,9 +280,12 @@ impl EditorServer {
                 if let Some(idx) = self.last_input_client.take() {
                     if idx < self.clients.len() {
                         tracing::info!("Client {} requested detach", self.clients[idx].id);
-                        let client = self.clients.remove(idx);
+                        let mut client = self.clients.remove(idx);
+                        if let Some(ref mut editor) = self.editor {
+                            editor.collab_leave(client.id);
+                        }
                         let teardown = terminal_teardown_sequences();
-                        let _ = client.conn.write_data(&teardown);
+                        let _ = client.send_data(&teardown);
                         let quit_msg = serde_json::to_string(&ServerControl::Quit {
                             reason: "Detached".to_string(),
                         })
@@ -239,14 +310,26 @@ impl EditorServer {
                 needs_render = true;
             }
 
-            // Process input events
+            // Process input events in arrival order, each with the cursors
+            // of the client that sent it
             if !input_events.is_empty() {
                 self.last_client_activity = Instant::now();
-                for event in input_events {
+                self.snapshot_dirty = true;
+                self.clear_idle_warning();
+                for (client_id, event) in input_events {
+                    if let Some(ref mut editor) = self.editor {
+                        editor.collab_activate(client_id);
+                    }
                     if self.handle_event(event)? {
                         needs_render = true;
                     }
                 }
+                if self.clients.len() > 1 {
+                    if let Some(ref mut editor) = self.editor {
+                        editor.collab_return_to_followed();
+                        editor.refresh_collab_cursors();
+                    }
+                }
             }
 
             // Process async messages from editor
@@ -260,6 +343,13 @@ impl EditorServer {
                 if editor.check_mouse_hover_timer() {
                     needs_render = true;
                 }
+                if let Err(e) = editor.auto_save_dirty_buffers() {
+                    tracing::debug!("Auto-save error: {}", e);
+                }
+            }
+
+            if self.snapshot_dirty && self.last_snapshot.elapsed() >= SNAPSHOT_INTERVAL {
+                self.save_snapshot();
             }
 
             // Render and broadcast if needed
@@ -274,11 +364,86 @@ impl EditorServer {
         }
 
         // Clean shutdown
-        self.disconnect_all_clients("Server shutting down")?;
+        let reason = match (idle_shutdown, self.config.idle_action) {
+            (false, _) => "Server shutting down",
+            (true, SessionIdleAction::Exit) => "Session closed (idle)",
+            (true, SessionIdleAction::Suspend) => "Session suspended (idle)",
+        };
+        self.disconnect_all_clients(reason)?;
+        if idle_shutdown && self.config.idle_action == SessionIdleAction::Exit {
+            // The next server starts with a fresh layout; unsaved changes are
+            // still recovered from the recovery files below
+            if let Err(e) = std::fs::remove_file(&self.snapshot_path) {
+                if e.kind() != io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to remove session snapshot: {}", e);
+                }
+            }
+        } else {
+            self.save_snapshot();
+        }
+        if let Some(ref mut editor) = self.editor {
+            // Unsaved changes are only dropped when the user quit from the
+            // editor (which asks about them). After `session kill` or an idle
+            // timeout they stay in the recovery files for the next server.
+            if editor.should_quit() {
+                if let Err(e) = editor.end_recovery_session() {
+                    tracing::warn!("Failed to end recovery session: {}", e);
+                }
+            }
+        }
 
         Ok(())
     }
 
+    /// Show the idle shutdown countdown to attached clients
+    ///
+    /// Returns true when the message changed and the screen needs a render.
+    fn show_idle_warning(&mut self, remaining: Duration) -> bool {
+        let Some(ref mut editor) = self.editor else {
+            return false;
+        };
+        // Round up so the countdown ends at 1, not 0
+        let seconds = remaining.as_millis().div_ceil(1000).to_string();
+        let message = match self.config.idle_action {
+            SessionIdleAction::Exit => t!("status.session_idle_exit", seconds = seconds),
+            SessionIdleAction::Suspend => t!("status.session_idle_suspend", seconds = seconds),
+        }
+        .to_string();
+        if self.idle_warning.as_ref() == Some(&message) {
+            return false;
+        }
+        editor.set_status_message(message.clone());
+        self.idle_warning = Some(message);
+        true
+    }
+
+    /// Remove the idle countdown once there is input again
+    fn clear_idle_warning(&mut self) {
+        let Some(message) = self.idle_warning.take() else {
+            return;
+        };
+        if let Some(ref mut editor) = self.editor {
+            // Leave messages that replaced the countdown alone
+            if editor.get_status_message() == Some(&message) {
+                editor.clear_status_message();
+            }
+        }
+    }
+
+    /// Save the session state (layout, open files, cursors) for a restarted server
+    ///
+    /// Unsaved buffer content is kept by the recovery service instead.
+    fn save_snapshot(&mut self) {
+        let Some(ref mut editor) = self.editor else {
+            return;
+        };
+        if let Err(e) = editor.save_workspace_to(&self.snapshot_path) {
+            tracing::warn!("Failed to save session snapshot: {}", e);
+        }
+        self.last_snapshot = Instant::now();
+        self.snapshot_dirty = false;
+    }
+
     /// Initialize the editor with the current terminal size
     fn initialize_editor(&mut self) -> io::Result<()> {
         let backend = CaptureBackend::new(self.term_size.cols, self.term_size.rows);
@@ -315,6 +480,22 @@ impl EditorServer {
         });
         editor.set_session_name(Some(session_display_name));
 
+        // Pick up where a previous server for this session left off
+        match editor.try_restore_workspace_from(&self.snapshot_path) {
+            Ok(true) => tracing::info!("Restored session snapshot"),
+            Ok(false) => tracing::debug!("No session snapshot found"),
+            Err(e) => tracing::warn!("Failed to restore session snapshot: {}", e),
+        }
+        if editor.has_recovery_files().unwrap_or(false) {
+            match editor.recover_all_buffers() {
+                Ok(count) => tracing::info!("Recovered {} buffer(s)", count),
+                Err(e) => tracing::warn!("Failed to recover buffers: {}", e),
+            }
+        }
+        if let Err(e) = editor.start_recovery_session() {
+            tracing::warn!("Failed to start recovery session: {}", e);
+        }
+
         self.terminal = Some(terminal);
         self.editor = Some(editor);
 
@@ -376,12 +557,10 @@ impl EditorServer {
             return Err(io::Error::other("Version mismatch"));
         }
 
-        // Send server hello
-        let session_id = self.config.session_name.clone().unwrap_or_else(|| {
-            crate::workspace::encode_path_for_filename(&self.config.working_dir)
-        });
-
-        let server_hello = ServerHello::new(session_id);
+        // Send server hello, accepting the client's preferred compression
+        let compression = hello.compression.first().copied();
+        let mut server_hello = ServerHello::new(self.session_id());
+        server_hello.compression = compression;
         let response = serde_json::to_string(&ServerControl::Hello(server_hello))
             .map_err(|e| io::Error::other(e.to_string()))?;
         conn.write_control(&response)?;
@@ -391,34 +570,96 @@ impl EditorServer {
         #[cfg(not(windows))]
         conn.control.set_nonblocking(true)?;
 
-        // Send terminal setup sequences
-        let setup = terminal_setup_sequences();
-        conn.write_data(&setup)?;
-
-        // Send cursor style (from editor if running, otherwise from config)
-        conn.write_data(cursor_style.to_escape_sequence())?;
-
         tracing::debug!(
-            "Client {} connected: {}x{}, TERM={:?}",
+            "Client {} connected: {}x{}, TERM={:?}, compression={:?}",
             client_id,
             hello.term_size.cols,
             hello.term_size.rows,
-            hello.term()
+            hello.term(),
+            compression
         );
 
-        Ok(ConnectedClient {
+        let mut client = ConnectedClient {
             conn,
             term_size: hello.term_size,
             env: hello.env,
             id: client_id,
             input_parser: InputParser::new(),
             needs_full_render: true,
-        })
+            compression,
+            encoder: DataEncoder::new(compression),
+            traffic: DataStats::default(),
+        };
+
+        // Send terminal setup sequences
+        let setup = terminal_setup_sequences();
+        client.send_data(&setup)?;
+
+        // Send cursor style (from editor if running, otherwise from config)
+        client.send_data(cursor_style.to_escape_sequence())?;
+
+        Ok(client)
+    }
+
+    /// Session identifier (name or encoded working directory)
+    fn session_id(&self) -> String {
+        self.config
+            .session_name
+            .clone()
+            .unwrap_or_else(|| crate::workspace::encode_path_for_filename(&self.config.working_dir))
+    }
+
+    /// Describe the session for the client at `requester` (which is left out of the client list)
+    fn session_info(&self, requester: usize) -> SessionInfo {
+        let pid = std::process::id();
+        let clients = self
+            .clients
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != requester)
+            .map(|(_, client)| SessionClientInfo {
+                id: client.id,
+                term_size: client.term_size,
+                term: client.term().map(str::to_string),
+                compression: client.compression,
+                traffic: client.traffic,
+            })
+            .collect();
+        let buffers = self
+            .editor
+            .as_ref()
+            .map(|editor| {
+                editor
+                    .listed_buffers()
+                    .into_iter()
+                    .map(|(name, path, modified)| SessionBufferInfo {
+                        name,
+                        path: path.map(|p| p.to_string_lossy().to_string()),
+                        modified,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        SessionInfo {
+            session_id: self.session_id(),
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            pid,
+            working_dir: self.config.working_dir.to_string_lossy().to_string(),
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            memory_bytes: SystemResources::process_usage(pid)
+                .ok()
+                .map(|usage| usage.rss_bytes),
+            clients,
+            buffers,
+        }
     }
 
     /// Process messages from connected clients
-    /// Returns (input_events, resize_occurred, index of client that provided input)
-    fn process_clients(&mut self) -> io::Result<(Vec<Event>, bool, Option<usize>)> {
+    /// Returns (input events with the id of the client that sent each,
+    /// resize_occurred, index of client that provided input)
+    #[allow(clippy::type_complexity)]
+    fn process_clients(&mut self) -> io::Result<(Vec<(u64, Event)>, bool, Option<usize>)> {
         let mut disconnected = Vec::new();
         let mut input_source_client: Option<usize> = None;
         let mut input_events = Vec::new();
@@ -452,7 +693,7 @@ impl EditorServer {
                     if !events.is_empty() {
                         input_source_client = Some(idx);
                     }
-                    input_events.extend(events);
+                    input_events.extend(events.into_iter().map(|event| (client.id, event)));
                 }
                 Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
                 Err(e) => {
@@ -566,6 +807,38 @@ impl EditorServer {
                     tracing::info!("Client {} detached", idx);
                     disconnected.push(idx);
                 }
+                ClientControl::GetInfo => {
+                    let info = self.session_info(idx);
+                    if let Some(client) = self.clients.get(idx) {
+                        let msg =
+                            serde_json::to_string(&ServerControl::Info(info)).unwrap_or_default();
+                        let _ = client.conn.write_control(&msg);
+                    }
+                }
+                ClientControl::Redraw => {
+                    if let Some(client) = self.clients.get_mut(idx) {
+                        client.needs_full_render = true;
+                    }
+                    resize_occurred = true; // Force re-render
+                }
+                ClientControl::RunCommand { command } => {
+                    let result = match self.editor {
+                        Some(ref mut editor) => editor.run_session_command(&command),
+                        None => Err("editor is not running".to_string()),
+                    };
+                    let (ok, output) = match result {
+                        Ok(output) => (true, output),
+                        Err(message) => (false, message),
+                    };
+                    if let Some(client) = self.clients.get(idx) {
+                        let msg =
+                            serde_json::to_string(&ServerControl::CommandResult { ok, output })
+                                .unwrap_or_default();
+                        let _ = client.conn.write_control(&msg);
+                    }
+                    self.snapshot_dirty = true;
+                    resize_occurred = true; // Force re-render
+                }
                 ClientControl::OpenFiles { files } => {
                     if let Some(ref mut editor) = self.editor {
                         for file_req in &files {
@@ -578,6 +851,7 @@ impl EditorServer {
                             );
                             editor.queue_file_open(path, file_req.line, file_req.column);
                         }
+                        self.snapshot_dirty = true;
                         resize_occurred = true; // Force re-render
                     }
                 }
@@ -587,10 +861,13 @@ impl EditorServer {
 
         // Remove disconnected clients
         for idx in disconnected.into_iter().rev() {
-            let client = self.clients.remove(idx);
+            let mut client = self.clients.remove(idx);
+            if let Some(ref mut editor) = self.editor {
+                editor.collab_leave(client.id);
+            }
             // Send teardown sequences
             let teardown = terminal_teardown_sequences();
-            let _ = client.conn.write_data(&teardown);
+            let _ = client.send_data(&teardown);
             tracing::info!("Client {} disconnected", client.id);
             // Invalidate input source if that client disconnected
             if input_source_client == Some(idx) {
@@ -664,30 +941,39 @@ impl EditorServer {
                 "Full render requested for {} client(s)",
                 self.clients.iter().filter(|c| c.needs_full_render).count()
             );
-            // Force full redraw by invalidating terminal state
-            terminal.backend_mut().reset_style_state();
-            let _ = terminal.clear();
         }
 
         // Take any pending escape sequences (e.g., cursor style changes)
         let pending_sequences = editor.take_pending_escape_sequences();
 
-        // Render to capture backend
-        terminal
+        // Render to capture backend. Its output only holds the cells that
+        // changed since the previous frame, which is what clients that saw
+        // that frame need.
+        let completed = terminal
             .draw(|frame| editor.render(frame))
             .map_err(|e| io::Error::other(e.to_string()))?;
+        let frame_buffer = any_needs_full.then(|| completed.buffer.clone());
 
         // Get the captured output
         let output = terminal.backend_mut().take_buffer();
 
-        if output.is_empty() && pending_sequences.is_empty() {
+        // Clients that need a full render get the whole frame instead
+        let full_output = frame_buffer
+            .map(|buffer| terminal.backend().full_frame(&buffer))
+            .unwrap_or_default();
+
+        if output.is_empty() && pending_sequences.is_empty() && !any_needs_full {
             return Ok(());
         }
 
-        // Broadcast to all clients (pending sequences first, then rendered output)
+        // The terminal cursor is the cursor of the client that typed last.
+        // Everyone else sees their own cursor as a colored overlay instead.
+        let cursor_owner = editor.collab_owner().filter(|_| self.clients.len() > 1);
+
+        // Send to all clients (pending sequences first, then rendered output)
         for client in &mut self.clients {
             if !pending_sequences.is_empty() {
-                if let Err(e) = client.conn.write_data(&pending_sequences) {
+                if let Err(e) = client.send_data(&pending_sequences) {
                     tracing::warn!(
                         "Failed to send pending sequences to client {}: {}",
                         client.id,
@@ -695,8 +981,19 @@ impl EditorServer {
                     );
                 }
             }
-            if !output.is_empty() {
-                if let Err(e) = client.conn.write_data(&output) {
+            let frame = if client.needs_full_render {
+                client.traffic.full_frames += 1;
+                &full_output
+            } else {
+                &output
+            };
+            if !frame.is_empty() {
+                client.traffic.frames += 1;
+                let mut result = client.send_data(frame);
+                if cursor_owner.is_some_and(|owner| owner != client.id) {
+                    result = result.and_then(|_| client.send_data(b"\x1b[?25l"));
+                }
+                if let Err(e) = result {
                     tracing::warn!("Failed to send to client {}: {}", client.id, e);
                 }
             }
@@ -711,7 +1008,10 @@ impl EditorServer {
     fn disconnect_all_clients(&mut self, reason: &str) -> io::Result<()> {
         let teardown = terminal_teardown_sequences();
         for client in &mut self.clients {
-            let _ = client.conn.write_data(&teardown);
+            if let Some(ref mut editor) = self.editor {
+                editor.collab_leave(client.id);
+            }
+            let _ = client.send_data(&teardown);
             let quit_msg = serde_json::to_string(&ServerControl::Quit {
                 reason: reason.to_string(),
             })
@@ -724,8 +1024,15 @@ impl EditorServer {
 }
 
 impl ConnectedClient {
+    /// Send terminal output over the data channel, compressing it if negotiated
+    fn send_data(&mut self, data: &[u8]) -> io::Result<()> {
+        let encoded = self.encoder.encode(data);
+        self.traffic.raw_bytes += data.len() as u64;
+        self.traffic.sent_bytes += encoded.len() as u64;
+        self.conn.write_data(&encoded)
+    }
+
     /// Get the client's TERM environment variable
-    #[allow(dead_code)]
     pub fn term(&self) -> Option<&str> {
         self.env.get("TERM").and_then(|v| v.as_deref())
     }
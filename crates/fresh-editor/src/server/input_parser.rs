@@ -37,6 +37,15 @@ impl InputParser {
                     events.push(event);
                     self.buffer.clear();
                 }
+                ParseResult::Text(text) => {
+                    events.extend(text.chars().map(|c| {
+                        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::empty()))
+                    }));
+                    self.buffer.clear();
+                }
+                ParseResult::Ignored => {
+                    self.buffer.clear();
+                }
                 ParseResult::Incomplete => {
                     // Need more bytes
                     if self.buffer.len() > self.max_buffer_size {
@@ -81,6 +90,11 @@ impl InputParser {
             return self.parse_escape_sequence();
         }
 
+        // Non-ASCII characters, typed or committed by an input method
+        if bytes[0] >= 0x80 {
+            return self.parse_utf8();
+        }
+
         // Single byte - convert directly
         if let Some(event) = self.byte_to_event(bytes[0]) {
             return ParseResult::Complete(event);
@@ -89,6 +103,34 @@ impl InputParser {
         ParseResult::Invalid
     }
 
+    /// Parse a UTF-8 encoded character
+    fn parse_utf8(&self) -> ParseResult {
+        let bytes = &self.buffer;
+        let len = match bytes[0] {
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            // Stray continuation byte or invalid lead byte
+            _ => return ParseResult::Ignored,
+        };
+        if !bytes[1..].iter().all(|&b| (b & 0xC0) == 0x80) {
+            return ParseResult::Invalid;
+        }
+        if bytes.len() < len {
+            return ParseResult::Incomplete;
+        }
+        match std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|s| s.chars().next())
+        {
+            Some(c) => ParseResult::Complete(Event::Key(KeyEvent::new(
+                KeyCode::Char(c),
+                KeyModifiers::empty(),
+            ))),
+            None => ParseResult::Invalid,
+        }
+    }
+
     /// Parse an escape sequence
     fn parse_escape_sequence(&self) -> ParseResult {
         let bytes = &self.buffer;
@@ -173,6 +215,9 @@ impl InputParser {
             // Special keys with tilde
             b'~' => self.parse_tilde_sequence(params),
 
+            // Kitty keyboard protocol: CSI code ; modifiers ; text u
+            b'u' => self.parse_kitty_key(params),
+
             // Mouse events (SGR format): CSI < Cb ; Cx ; Cy M/m
             b'M' | b'm' => {
                 if !params.is_empty() && params[0] == b'<' {
@@ -222,6 +267,63 @@ impl InputParser {
         ParseResult::Complete(Event::Key(KeyEvent::new(keycode, modifiers)))
     }
 
+    /// Parse kitty keyboard protocol keys: CSI code[:alternates] ;
+    /// modifiers[:event type] ; text u
+    ///
+    /// The optional text field holds the text the key produced as code
+    /// points separated by colons, which is how input methods commit
+    /// composed text when text reporting is enabled.
+    fn parse_kitty_key(&self, params: &[u8]) -> ParseResult {
+        let params_str = std::str::from_utf8(params).unwrap_or("");
+        let mut fields = params_str.split(';');
+
+        let code = fields
+            .next()
+            .and_then(|field| field.split(':').next())
+            .and_then(|code| code.parse::<u32>().ok());
+        let (mods, event_type) = fields
+            .next()
+            .map(|field| {
+                let mut parts = field.split(':');
+                let mods = parts.next().and_then(|m| m.parse().ok()).unwrap_or(1);
+                let event_type = parts.next().and_then(|e| e.parse().ok()).unwrap_or(1);
+                (mods, event_type)
+            })
+            .unwrap_or((1, 1));
+
+        // Key releases (event type 3) aren't editor input
+        if event_type == 3 {
+            return ParseResult::Ignored;
+        }
+
+        let text: String = fields
+            .next()
+            .unwrap_or("")
+            .split(':')
+            .filter_map(|c| c.parse::<u32>().ok())
+            .filter_map(char::from_u32)
+            .collect();
+        if !text.is_empty() {
+            return ParseResult::Text(text);
+        }
+
+        let keycode = match code {
+            Some(9) => KeyCode::Tab,
+            Some(13) => KeyCode::Enter,
+            Some(27) => KeyCode::Esc,
+            Some(127) => KeyCode::Backspace,
+            Some(code) => match char::from_u32(code) {
+                Some(c) if !c.is_control() => KeyCode::Char(c),
+                _ => return ParseResult::Ignored,
+            },
+            None => return ParseResult::Invalid,
+        };
+        ParseResult::Complete(Event::Key(KeyEvent::new(
+            keycode,
+            modifiers_from_param(mods),
+        )))
+    }
+
     /// Parse SGR mouse format: CSI < Cb ; Cx ; Cy M/m
     fn parse_sgr_mouse(&self, params: &[u8], pressed: bool) -> ParseResult {
         // Skip the '<'
@@ -237,6 +339,23 @@ impl InputParser {
         let cy: u16 = parts[2].parse().unwrap_or(1);
 
         let button_bits = cb & 0b11;
+
+        // Buttons 8-11 have no crossterm equivalent. The back and forward
+        // buttons (8 and 9) are sent as Alt+Left and Alt+Right, the Navigate
+        // Back/Forward keys; their release and the other buttons are dropped
+        // rather than read as clicks.
+        if cb & 128 != 0 {
+            if !pressed || cb & 32 != 0 {
+                return ParseResult::Ignored;
+            }
+            let code = match button_bits {
+                0 => KeyCode::Left,
+                1 => KeyCode::Right,
+                _ => return ParseResult::Ignored,
+            };
+            return ParseResult::Complete(Event::Key(KeyEvent::new(code, KeyModifiers::ALT)));
+        }
+
         let button = match button_bits {
             0 => MouseButton::Left,
             1 => MouseButton::Middle,
@@ -401,8 +520,12 @@ enum ParseResult {
     Complete(Event),
     /// Need more bytes to complete the sequence
     Incomplete,
+    /// Text to deliver as one key press per character
+    Text(String),
     /// Invalid sequence
     Invalid,
+    /// A complete sequence with no event
+    Ignored,
 }
 
 /// Convert a byte to a KeyCode
@@ -551,6 +674,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sgr_mouse_back_forward_buttons() {
+        let mut parser = InputParser::new();
+        let events = parser.parse(b"\x1b[<128;10;5M\x1b[<128;10;5m\x1b[<129;10;5M");
+        assert_eq!(
+            events,
+            vec![
+                Event::Key(KeyEvent::new(KeyCode::Left, KeyModifiers::ALT)),
+                Event::Key(KeyEvent::new(KeyCode::Right, KeyModifiers::ALT)),
+            ]
+        );
+
+        // Other extended buttons are dropped
+        assert!(parser.parse(b"\x1b[<130;10;5M").is_empty());
+    }
+
     #[test]
     fn test_focus_events() {
         let mut parser = InputParser::new();
@@ -625,4 +764,53 @@ mod tests {
             _ => panic!("Expected mouse motion event"),
         }
     }
+
+    fn chars_of(events: &[Event]) -> String {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char(c),
+                    ..
+                }) => Some(*c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_utf8_characters() {
+        let mut parser = InputParser::new();
+        let events = parser.parse("日本語é😀".as_bytes());
+        assert_eq!(events.len(), 5);
+        assert_eq!(chars_of(&events), "日本語é😀");
+    }
+
+    #[test]
+    fn test_utf8_split_across_reads() {
+        let mut parser = InputParser::new();
+        let bytes = "語".as_bytes();
+        assert!(parser.parse(&bytes[..1]).is_empty());
+        assert!(parser.parse(&bytes[1..2]).is_empty());
+        assert_eq!(chars_of(&parser.parse(&bytes[2..])), "語");
+    }
+
+    #[test]
+    fn test_kitty_key_with_text() {
+        let mut parser = InputParser::new();
+        // Input method commit reported with associated text: 日本
+        let events = parser.parse(b"\x1b[0;1;26085:26412u");
+        assert_eq!(chars_of(&events), "日本");
+
+        // Plain key without text, and a release that is ignored
+        let events = parser.parse(b"\x1b[97;5u\x1b[97;1:3u");
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            Event::Key(ke) => {
+                assert_eq!(ke.code, KeyCode::Char('a'));
+                assert!(ke.modifiers.contains(KeyModifiers::CONTROL));
+            }
+            _ => panic!("Expected key event"),
+        }
+    }
 }
@@ -13,10 +13,12 @@
 
 pub mod capture_backend;
 pub mod daemon;
+pub mod data_codec;
 pub mod editor_server;
 pub mod input_parser;
 pub mod ipc;
 pub mod protocol;
+pub mod shell_init;
 
 #[cfg(test)]
 mod runner;
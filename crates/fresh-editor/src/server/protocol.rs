@@ -1,7 +1,8 @@
 //! Protocol definitions for client-server communication
 //!
 //! The protocol uses two channels:
-//! - **Data channel**: Raw bytes, no framing (stdin→server, server→stdout)
+//! - **Data channel**: Raw bytes, no framing (stdin→server, server→stdout),
+//!   unless the handshake negotiates compression (see `data_codec`)
 //! - **Control channel**: JSON messages for out-of-band communication
 
 use serde::{Deserialize, Serialize};
@@ -35,6 +36,9 @@ pub struct ClientHello {
     /// Environment variables relevant for rendering
     /// Keys: TERM, COLORTERM, LANG, LC_ALL
     pub env: HashMap<String, Option<String>>,
+    /// Data channel compression the client can decode, in order of preference
+    #[serde(default)]
+    pub compression: Vec<Compression>,
 }
 
 impl ClientHello {
@@ -52,6 +56,7 @@ impl ClientHello {
             client_version: env!("CARGO_PKG_VERSION").to_string(),
             term_size,
             env,
+            compression: Vec::new(),
         }
     }
 
@@ -79,6 +84,10 @@ pub struct ServerHello {
     pub server_version: String,
     /// Session identifier (encoded working directory)
     pub session_id: String,
+    /// Compression the server chose from the client's offer; the data channel
+    /// is framed from here on when set
+    #[serde(default)]
+    pub compression: Option<Compression>,
 }
 
 impl ServerHello {
@@ -87,10 +96,18 @@ impl ServerHello {
             protocol_version: PROTOCOL_VERSION,
             server_version: env!("CARGO_PKG_VERSION").to_string(),
             session_id,
+            compression: None,
         }
     }
 }
 
+/// Compression of server output on the data channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    Zstd,
+}
+
 /// Version mismatch error response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionMismatch {
@@ -117,6 +134,13 @@ pub enum ClientControl {
     Quit,
     /// Request to open files in the editor
     OpenFiles { files: Vec<FileRequest> },
+    /// Request a description of the session (answered with `ServerControl::Info`)
+    GetInfo,
+    /// Run a session command such as `open FILE` or `save-all` (answered
+    /// with `ServerControl::CommandResult`)
+    RunCommand { command: String },
+    /// Repaint the whole screen (sent after a wrong predictive echo)
+    Redraw,
 }
 
 /// A file to open with optional line/column position
@@ -145,6 +169,71 @@ pub enum ServerControl {
     Quit { reason: String },
     /// Error message
     Error { message: String },
+    /// Session description, in response to `ClientControl::GetInfo`
+    Info(SessionInfo),
+    /// Outcome of `ClientControl::RunCommand`: the command's output, or the
+    /// error message when `ok` is false
+    CommandResult { ok: bool, output: String },
+}
+
+/// Description of a running session (`fresh --cmd session info`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionInfo {
+    /// Session identifier (name or encoded working directory)
+    pub session_id: String,
+    /// Server binary version
+    pub server_version: String,
+    /// Process ID of the server
+    pub pid: u32,
+    /// Working directory of the session
+    pub working_dir: String,
+    /// Seconds since the server started
+    pub uptime_secs: u64,
+    /// Resident memory of the server process, if the platform reports it
+    pub memory_bytes: Option<u64>,
+    /// Attached clients, not counting the one that asked
+    pub clients: Vec<SessionClientInfo>,
+    /// Open buffers
+    pub buffers: Vec<SessionBufferInfo>,
+}
+
+/// A client attached to a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionClientInfo {
+    pub id: u64,
+    pub term_size: TermSize,
+    /// The client's TERM value
+    pub term: Option<String>,
+    /// Negotiated data channel compression
+    #[serde(default)]
+    pub compression: Option<Compression>,
+    /// Screen output sent to the client so far
+    #[serde(default)]
+    pub traffic: DataStats,
+}
+
+/// Screen output sent to one client
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataStats {
+    /// Frames sent, including full repaints
+    pub frames: u64,
+    /// Frames that repainted the whole screen
+    pub full_frames: u64,
+    /// Terminal output before compression
+    pub raw_bytes: u64,
+    /// Bytes written to the data channel
+    pub sent_bytes: u64,
+}
+
+/// A buffer open in a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionBufferInfo {
+    /// Display name (file name or virtual buffer name)
+    pub name: String,
+    /// File path, if the buffer is backed by a file
+    pub path: Option<String>,
+    /// Whether the buffer has unsaved changes
+    pub modified: bool,
 }
 
 /// Wrapper for control channel messages (used for JSON serialization)
@@ -202,6 +291,20 @@ mod tests {
         assert!(json.contains("\"type\":\"resize\""));
     }
 
+    #[test]
+    fn test_hello_without_compression_fields_parses() {
+        // Hellos from older binaries lack the compression fields
+        let mut client = serde_json::to_value(ClientHello::new(TermSize::new(80, 24))).unwrap();
+        client.as_object_mut().unwrap().remove("compression");
+        let client: ClientHello = serde_json::from_value(client).unwrap();
+        assert!(client.compression.is_empty());
+
+        let mut server = serde_json::to_value(ServerHello::new("s".to_string())).unwrap();
+        server.as_object_mut().unwrap().remove("compression");
+        let server: ServerHello = serde_json::from_value(server).unwrap();
+        assert_eq!(server.compression, None);
+    }
+
     #[test]
     fn test_server_hello_includes_session_id() {
         let hello = ServerHello::new("my-session".to_string());
@@ -230,6 +333,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_session_info_roundtrip() {
+        let info = SessionInfo {
+            session_id: "proj".to_string(),
+            server_version: "1.0.0".to_string(),
+            pid: 1234,
+            working_dir: "/home/user/proj".to_string(),
+            uptime_secs: 3600,
+            memory_bytes: Some(50 * 1024 * 1024),
+            clients: vec![SessionClientInfo {
+                id: 1,
+                term_size: TermSize::new(120, 40),
+                term: Some("xterm-256color".to_string()),
+                compression: Some(Compression::Zstd),
+                traffic: DataStats {
+                    frames: 12,
+                    full_frames: 1,
+                    raw_bytes: 40_000,
+                    sent_bytes: 9_000,
+                },
+            }],
+            buffers: vec![SessionBufferInfo {
+                name: "main.rs".to_string(),
+                path: Some("/home/user/proj/src/main.rs".to_string()),
+                modified: true,
+            }],
+        };
+        let json = serde_json::to_string(&ServerControl::Info(info.clone())).unwrap();
+        assert!(json.contains("\"type\":\"info\""));
+        match serde_json::from_str::<ServerControl>(&json).unwrap() {
+            ServerControl::Info(parsed) => assert_eq!(parsed, info),
+            other => panic!("Expected Info, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_truecolor_detection() {
         let mut hello = ClientHello::new(TermSize::new(80, 24));
@@ -269,6 +407,11 @@ mod tests {
                     column: Some(5),
                 }],
             },
+            ClientControl::GetInfo,
+            ClientControl::RunCommand {
+                command: "open main.rs:10".to_string(),
+            },
+            ClientControl::Redraw,
         ];
 
         for variant in variants {
@@ -292,6 +435,20 @@ mod tests {
             ServerControl::Error {
                 message: "error".to_string(),
             },
+            ServerControl::Info(SessionInfo {
+                session_id: "test".to_string(),
+                server_version: "1.0.0".to_string(),
+                pid: 42,
+                working_dir: "/tmp".to_string(),
+                uptime_secs: 0,
+                memory_bytes: None,
+                clients: vec![],
+                buffers: vec![],
+            }),
+            ServerControl::CommandResult {
+                ok: true,
+                output: "main.rs".to_string(),
+            },
         ];
 
         for variant in variants {
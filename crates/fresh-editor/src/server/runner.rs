@@ -367,6 +367,21 @@ impl Server {
                     client.id
                 );
             }
+            ClientControl::GetInfo => {
+                // Session info describes the editor, which this runner doesn't have
+                tracing::warn!("Client {} sent GetInfo but no editor is running", client.id);
+            }
+            ClientControl::Redraw => {
+                // Nothing is rendered by this runner
+            }
+            ClientControl::RunCommand { .. } => {
+                let reply = serde_json::to_string(&ServerControl::CommandResult {
+                    ok: false,
+                    output: "no editor is running".to_string(),
+                })
+                .map_err(|e| io::Error::other(e.to_string()))?;
+                client.conn.write_control(&reply)?;
+            }
         }
         Ok(())
     }
@@ -0,0 +1,30 @@
+# Fresh shell integration for bash
+# Add to ~/.bashrc: eval "$(fresh --cmd shell-init bash)"
+
+# Open files in the session: fresh-open src/main.rs:42
+fresh-open() {
+    command fresh --cmd session open-file "${FRESH_SESSION:-.}" "$@"
+}
+
+# Set the session's shell directory: fresh-cd [DIR]
+fresh-cd() {
+    local dir
+    dir=$(cd -- "${1:-.}" && pwd) || return
+    command fresh --cmd session exec "${FRESH_SESSION:-.}" cd "$dir" >/dev/null
+}
+
+# Open a command's output in a new buffer: make 2>&1 | fresh-pipe
+fresh-pipe() {
+    command fresh --cmd session pipe "${FRESH_SESSION:-.}"
+}
+
+# In a session's terminal, follow the shell's directory at each prompt
+__fresh_prompt() {
+    if [ -n "$FRESH_SESSION" ] && [ "$PWD" != "$__fresh_dir" ]; then
+        __fresh_dir=$PWD
+        (command fresh --cmd session exec "$FRESH_SESSION" cd "$PWD" >/dev/null 2>&1 &)
+    fi
+}
+if [[ ";${PROMPT_COMMAND};" != *";__fresh_prompt;"* ]]; then
+    PROMPT_COMMAND="__fresh_prompt${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
@@ -0,0 +1,35 @@
+# Fresh shell integration for fish
+# Add to ~/.config/fish/config.fish: fresh --cmd shell-init fish | source
+
+function __fresh_session
+    if set -q FRESH_SESSION
+        echo $FRESH_SESSION
+    else
+        echo .
+    end
+end
+
+# Open files in the session: fresh-open src/main.rs:42
+function fresh-open --description 'Open files in the Fresh session'
+    command fresh --cmd session open-file (__fresh_session) $argv
+end
+
+# Set the session's shell directory: fresh-cd [DIR]
+function fresh-cd --description "Set the Fresh session's shell directory"
+    set -q argv[1]; or set argv .
+    set -l dir (builtin realpath $argv[1]); or return
+    command fresh --cmd session exec (__fresh_session) cd $dir >/dev/null
+end
+
+# Open a command's output in a new buffer: make 2>&1 | fresh-pipe
+function fresh-pipe --description 'Open standard input in a Fresh buffer'
+    command fresh --cmd session pipe (__fresh_session)
+end
+
+# In a session's terminal, follow the shell's directory at each prompt
+function __fresh_prompt --on-event fish_prompt
+    if set -q FRESH_SESSION; and test "$PWD" != "$__fresh_dir"
+        set -g __fresh_dir $PWD
+        command fresh --cmd session exec $FRESH_SESSION cd $PWD >/dev/null 2>&1 &
+    end
+end
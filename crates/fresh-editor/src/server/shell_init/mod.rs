@@ -0,0 +1,46 @@
+//! Shell integration scripts (`fresh --cmd shell-init SHELL`)
+//!
+//! Each script defines helpers that talk to a running session through the
+//! `fresh` CLI:
+//!
+//! - `fresh-open FILES` opens files, like `session open-file`
+//! - `fresh-cd [DIR]` sets the session's shell directory (`cd` session command)
+//! - `fresh-pipe` opens its standard input in a new buffer (`session pipe`)
+//!
+//! The helpers use the session named by `FRESH_SESSION`, which is set in
+//! terminals opened inside a session, or the session for the current
+//! directory. Inside a session's terminal the prompt hook also keeps the
+//! session's shell directory in step with the shell's.
+
+/// Environment variable naming the session, inherited by the processes a
+/// session server starts
+pub const SESSION_ENV_VAR: &str = "FRESH_SESSION";
+
+/// Shells that have an integration script
+pub const SHELLS: &[&str] = &["bash", "zsh", "fish"];
+
+/// The integration script for `shell`
+pub fn script(shell: &str) -> Option<&'static str> {
+    match shell {
+        "bash" => Some(include_str!("fresh.bash")),
+        "zsh" => Some(include_str!("fresh.zsh")),
+        "fish" => Some(include_str!("fresh.fish")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_shell_has_a_script() {
+        for shell in SHELLS {
+            let script = script(shell).unwrap();
+            for helper in ["fresh-open", "fresh-cd", "fresh-pipe", "FRESH_SESSION"] {
+                assert!(script.contains(helper), "{} script lacks {}", shell, helper);
+            }
+        }
+        assert!(script("powershell").is_none());
+    }
+}
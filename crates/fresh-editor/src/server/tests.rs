@@ -6,6 +6,7 @@ mod integration_tests {
     use std::thread;
     use std::time::Duration;
 
+    use crate::config::SessionIdleAction;
     use crate::server::daemon::is_process_running;
     use crate::server::ipc::{ClientConnection, SocketPaths};
     use crate::server::protocol::{
@@ -567,6 +568,7 @@ mod integration_tests {
             working_dir: temp_dir.clone(),
             session_name: Some(session_name.clone()),
             idle_timeout: Some(Duration::from_secs(30)),
+            idle_action: SessionIdleAction::Exit,
             editor_config: config,
             dir_context,
             plugins_enabled: false, // Faster test without plugins
@@ -752,6 +754,7 @@ mod integration_tests {
             working_dir: temp_dir.clone(),
             session_name: Some(session_name.clone()),
             idle_timeout: Some(Duration::from_secs(30)),
+            idle_action: SessionIdleAction::Exit,
             editor_config: config,
             dir_context,
             plugins_enabled: false,
@@ -904,4 +907,498 @@ mod integration_tests {
         let _ = socket_paths.cleanup();
         std::fs::remove_dir_all(&temp_dir).ok();
     }
+
+    type EditorServerHandles = (
+        SocketPaths,
+        std::sync::Arc<std::sync::atomic::AtomicBool>,
+        thread::JoinHandle<std::io::Result<()>>,
+    );
+
+    /// Start an `EditorServer` for `session_name` in a background thread and
+    /// wait until it is ready
+    fn start_editor_server(temp_dir: &std::path::Path, session_name: &str) -> EditorServerHandles {
+        start_editor_server_with_idle(
+            temp_dir,
+            session_name,
+            Duration::from_secs(30),
+            SessionIdleAction::Exit,
+        )
+    }
+
+    /// Like `start_editor_server`, with the given idle policy
+    fn start_editor_server_with_idle(
+        temp_dir: &std::path::Path,
+        session_name: &str,
+        idle_timeout: Duration,
+        idle_action: SessionIdleAction,
+    ) -> EditorServerHandles {
+        use crate::config::Config;
+        use crate::config_io::DirectoryContext;
+        use crate::server::editor_server::{EditorServer, EditorServerConfig};
+        use std::sync::mpsc;
+
+        let server_config = EditorServerConfig {
+            working_dir: temp_dir.to_path_buf(),
+            session_name: Some(session_name.to_string()),
+            idle_timeout: Some(idle_timeout),
+            idle_action,
+            editor_config: Config::default(),
+            dir_context: DirectoryContext::for_testing(temp_dir),
+            plugins_enabled: false,
+        };
+
+        let (tx, rx) = mpsc::channel();
+        // EditorServer must be created in the thread because Editor is not Send
+        let server_handle = thread::spawn(move || {
+            let mut server = EditorServer::new(server_config).unwrap();
+            tx.send((server.socket_paths().clone(), server.shutdown_handle()))
+                .unwrap();
+            server.run()
+        });
+        let (socket_paths, shutdown_handle) = rx.recv().unwrap();
+
+        let mut attempts = 0;
+        while !socket_paths.pid.exists() || socket_paths.read_pid().ok().flatten().is_none() {
+            thread::sleep(Duration::from_millis(10));
+            attempts += 1;
+            if attempts > 500 {
+                panic!("Server did not become ready in time");
+            }
+        }
+
+        (socket_paths, shutdown_handle, server_handle)
+    }
+
+    /// Connect a client and complete the handshake
+    fn connect_client(socket_paths: &SocketPaths, size: TermSize) -> ClientConnection {
+        let conn = ClientConnection::connect(socket_paths).unwrap();
+        let hello = ClientHello::new(size);
+        conn.write_control(&serde_json::to_string(&ClientControl::Hello(hello)).unwrap())
+            .unwrap();
+        let response = conn.read_control().unwrap().unwrap();
+        assert!(matches!(
+            serde_json::from_str::<ServerControl>(&response).unwrap(),
+            ServerControl::Hello(_)
+        ));
+        conn
+    }
+
+    /// Ask the server for its session info
+    fn request_info(socket_paths: &SocketPaths) -> crate::server::protocol::SessionInfo {
+        let conn = connect_client(socket_paths, TermSize::new(80, 24));
+        conn.write_control(&serde_json::to_string(&ClientControl::GetInfo).unwrap())
+            .unwrap();
+        loop {
+            let line = conn
+                .read_control()
+                .unwrap()
+                .expect("server closed connection");
+            if let ServerControl::Info(info) = serde_json::from_str(&line).unwrap() {
+                return info;
+            }
+        }
+    }
+
+    /// E2E test: `GetInfo` describes the session without counting the asking client
+    #[test]
+    fn test_session_info_lists_clients_and_buffers() {
+        let temp_dir = std::env::temp_dir().join(format!("fresh-e2e-info-{}", std::process::id()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let session_name = unique_session_name("e2e-info");
+        let (socket_paths, shutdown_handle, server_handle) =
+            start_editor_server(&temp_dir, &session_name);
+
+        // An attached client that edits the scratch buffer
+        let conn = connect_client(&socket_paths, TermSize::new(100, 30));
+        thread::sleep(Duration::from_millis(100));
+        conn.write_data(b"typed").unwrap();
+        thread::sleep(Duration::from_millis(200));
+
+        let info = request_info(&socket_paths);
+
+        assert_eq!(info.session_id, session_name);
+        assert_eq!(info.pid, std::process::id());
+        assert_eq!(info.working_dir, temp_dir.to_string_lossy());
+        assert_eq!(info.clients.len(), 1, "asking client is not listed");
+        assert_eq!(info.clients[0].term_size, TermSize::new(100, 30));
+        assert_eq!(info.buffers.len(), 1);
+        assert!(
+            info.buffers[0].modified,
+            "typed text marks the buffer dirty"
+        );
+
+        shutdown_handle.store(true, Ordering::SeqCst);
+        let _ = server_handle.join();
+        let _ = socket_paths.cleanup();
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    /// E2E test: a restarted server restores the files of the previous one
+    #[test]
+    fn test_restarted_server_restores_session_snapshot() {
+        use crate::server::protocol::FileRequest;
+
+        let temp_dir =
+            std::env::temp_dir().join(format!("fresh-e2e-snapshot-{}", std::process::id()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        let file_path = temp_dir.join("notes.txt");
+        std::fs::write(&file_path, "some notes\n").unwrap();
+        let file_path = file_path.canonicalize().unwrap();
+
+        let session_name = unique_session_name("e2e-snapshot");
+
+        // First server: open a file, then shut down
+        let (socket_paths, shutdown_handle, server_handle) =
+            start_editor_server(&temp_dir, &session_name);
+        let conn = connect_client(&socket_paths, TermSize::new(80, 24));
+        let open = ClientControl::OpenFiles {
+            files: vec![FileRequest {
+                path: file_path.to_string_lossy().to_string(),
+                line: None,
+                column: None,
+            }],
+        };
+        conn.write_control(&serde_json::to_string(&open).unwrap())
+            .unwrap();
+        thread::sleep(Duration::from_millis(300));
+        shutdown_handle.store(true, Ordering::SeqCst);
+        server_handle.join().unwrap().unwrap();
+        let _ = socket_paths.cleanup();
+
+        let snapshot = temp_dir
+            .join("data/sessions")
+            .join(format!("{}.json", session_name));
+        assert!(snapshot.exists(), "shutdown should write a snapshot");
+
+        // Second server for the same session: the file is open again
+        let (socket_paths, shutdown_handle, server_handle) =
+            start_editor_server(&temp_dir, &session_name);
+        let _conn = connect_client(&socket_paths, TermSize::new(80, 24));
+        thread::sleep(Duration::from_millis(200));
+        let info = request_info(&socket_paths);
+        let file_str = file_path.to_string_lossy().to_string();
+        assert!(
+            info.buffers
+                .iter()
+                .any(|b| b.path.as_deref() == Some(file_str.as_str())),
+            "restored buffers: {:?}",
+            info.buffers
+        );
+
+        shutdown_handle.store(true, Ordering::SeqCst);
+        let _ = server_handle.join();
+        let _ = socket_paths.cleanup();
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    /// Wait for the server to tell `conn` why it is disconnecting
+    fn read_quit_reason(conn: &ClientConnection) -> String {
+        loop {
+            let line = conn
+                .read_control()
+                .unwrap()
+                .expect("server closed connection");
+            if let ServerControl::Quit { reason } = serde_json::from_str(&line).unwrap() {
+                return reason;
+            }
+        }
+    }
+
+    /// E2E test: an idle server suspends even with a client attached, keeping
+    /// its snapshot; the exit policy discards it
+    #[test]
+    fn test_idle_timeout_suspend_keeps_snapshot_and_exit_discards_it() {
+        let temp_dir = std::env::temp_dir().join(format!("fresh-e2e-idle-{}", std::process::id()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let session_name = unique_session_name("e2e-idle");
+        let snapshot = temp_dir
+            .join("data/sessions")
+            .join(format!("{}.json", session_name));
+
+        let (socket_paths, _shutdown_handle, server_handle) = start_editor_server_with_idle(
+            &temp_dir,
+            &session_name,
+            Duration::from_secs(1),
+            SessionIdleAction::Suspend,
+        );
+        let conn = connect_client(&socket_paths, TermSize::new(80, 24));
+        assert_eq!(read_quit_reason(&conn), "Session suspended (idle)");
+        server_handle.join().unwrap().unwrap();
+        let _ = socket_paths.cleanup();
+        assert!(snapshot.exists(), "suspend should write a snapshot");
+
+        let (socket_paths, _shutdown_handle, server_handle) = start_editor_server_with_idle(
+            &temp_dir,
+            &session_name,
+            Duration::from_secs(1),
+            SessionIdleAction::Exit,
+        );
+        let conn = connect_client(&socket_paths, TermSize::new(80, 24));
+        assert_eq!(read_quit_reason(&conn), "Session closed (idle)");
+        server_handle.join().unwrap().unwrap();
+        let _ = socket_paths.cleanup();
+        assert!(!snapshot.exists(), "exit should discard the snapshot");
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    /// Run a session command on a fresh connection and return (ok, output)
+    fn run_command(socket_paths: &SocketPaths, command: &str) -> (bool, String) {
+        let conn = connect_client(socket_paths, TermSize::new(80, 24));
+        let msg = ClientControl::RunCommand {
+            command: command.to_string(),
+        };
+        conn.write_control(&serde_json::to_string(&msg).unwrap())
+            .unwrap();
+        loop {
+            let line = conn
+                .read_control()
+                .unwrap()
+                .expect("server closed connection");
+            if let ServerControl::CommandResult { ok, output } =
+                serde_json::from_str(&line).unwrap()
+            {
+                return (ok, output);
+            }
+        }
+    }
+
+    /// E2E test: `RunCommand` opens files, moves the cursor and reports state
+    #[test]
+    fn test_run_command_drives_session() {
+        let temp_dir = std::env::temp_dir().join(format!("fresh-e2e-exec-{}", std::process::id()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        std::fs::write(temp_dir.join("notes.txt"), "one\ntwo\nthree\n").unwrap();
+
+        let session_name = unique_session_name("e2e-exec");
+        let (socket_paths, shutdown_handle, server_handle) =
+            start_editor_server(&temp_dir, &session_name);
+
+        let (ok, output) = run_command(&socket_paths, "open notes.txt:2:3");
+        assert!(ok, "open failed: {}", output);
+        assert!(output.ends_with("notes.txt"), "opened: {}", output);
+        assert_eq!(
+            run_command(&socket_paths, "cursor"),
+            (true, "2:3".to_string())
+        );
+        assert_eq!(
+            run_command(&socket_paths, "goto 3"),
+            (true, "3:1".to_string())
+        );
+        let (ok, buffers) = run_command(&socket_paths, "buffers");
+        assert!(ok);
+        assert!(buffers.contains("notes.txt"), "buffers: {}", buffers);
+
+        let (ok, output) = run_command(&socket_paths, "frobnicate");
+        assert!(!ok);
+        assert!(output.contains("unknown command"), "error: {}", output);
+
+        shutdown_handle.store(true, Ordering::SeqCst);
+        let _ = server_handle.join();
+        let _ = socket_paths.cleanup();
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    /// E2E test: `cd` moves where relative paths are opened from, and `pipe`
+    /// opens piped output in a new buffer
+    #[test]
+    fn test_run_command_cd_and_pipe() {
+        let temp_dir = std::env::temp_dir().join(format!("fresh-e2e-cd-{}", std::process::id()));
+        std::fs::create_dir_all(temp_dir.join("sub")).unwrap();
+        std::fs::write(temp_dir.join("sub").join("inner.txt"), "inner\n").unwrap();
+        let piped = temp_dir.join("piped.tmp");
+        std::fs::write(&piped, "test result: ok\n").unwrap();
+
+        let session_name = unique_session_name("e2e-cd");
+        let (socket_paths, shutdown_handle, server_handle) =
+            start_editor_server(&temp_dir, &session_name);
+
+        let (ok, output) = run_command(&socket_paths, "cd sub");
+        assert!(ok, "cd failed: {}", output);
+        assert!(output.ends_with("sub"), "cd: {}", output);
+        let (ok, output) = run_command(&socket_paths, "open inner.txt");
+        assert!(ok, "open failed: {}", output);
+        let (ok, _) = run_command(&socket_paths, "cd missing");
+        assert!(!ok);
+
+        let (ok, output) = run_command(&socket_paths, &format!("pipe {}", piped.display()));
+        assert!(ok, "pipe failed: {}", output);
+        let (ok, buffers) = run_command(&socket_paths, "buffers");
+        assert!(ok);
+        assert!(buffers.contains("inner.txt"), "buffers: {}", buffers);
+        assert!(buffers.contains(&output), "buffers: {}", buffers);
+
+        shutdown_handle.store(true, Ordering::SeqCst);
+        let _ = server_handle.join();
+        let _ = socket_paths.cleanup();
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    /// Read whatever the server has sent on the data channel so far
+    fn drain_data(conn: &ClientConnection) -> Vec<u8> {
+        let mut buf = [0u8; 8192];
+        let mut output = Vec::new();
+        for _ in 0..50 {
+            match conn.data.try_read(&mut buf) {
+                Ok(n) if n > 0 => output.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock && output.is_empty() => {
+                    thread::sleep(Duration::from_millis(20));
+                }
+                _ => break,
+            }
+        }
+        output
+    }
+
+    /// E2E test: a client that offers zstd gets compressed frames, and a
+    /// client joining later doesn't make the server repaint everyone
+    #[test]
+    fn test_compressed_attach_and_traffic_metrics() {
+        use crate::server::data_codec::DataDecoder;
+        use crate::server::protocol::Compression;
+
+        let temp_dir = std::env::temp_dir().join(format!("fresh-e2e-zstd-{}", std::process::id()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let session_name = unique_session_name("e2e-zstd");
+        let (socket_paths, shutdown_handle, server_handle) =
+            start_editor_server(&temp_dir, &session_name);
+
+        let conn = ClientConnection::connect(&socket_paths).unwrap();
+        let mut hello = ClientHello::new(TermSize::new(80, 24));
+        hello.compression = vec![Compression::Zstd];
+        conn.write_control(&serde_json::to_string(&ClientControl::Hello(hello)).unwrap())
+            .unwrap();
+        let response = conn.read_control().unwrap().unwrap();
+        match serde_json::from_str::<ServerControl>(&response).unwrap() {
+            ServerControl::Hello(server_hello) => {
+                assert_eq!(server_hello.compression, Some(Compression::Zstd))
+            }
+            other => panic!("Expected Hello, got {:?}", other),
+        }
+
+        thread::sleep(Duration::from_millis(100));
+        conn.write_data(b"COMPRESSED").unwrap();
+        thread::sleep(Duration::from_millis(200));
+        let mut decoder = DataDecoder::new(Some(Compression::Zstd));
+        let screen = decoder.decode(&drain_data(&conn)).unwrap();
+        let screen = String::from_utf8_lossy(&screen);
+        assert!(screen.contains("\x1b[?1049h"), "setup sequences decoded");
+        assert!(screen.contains("COMPRESSED"), "typed text rendered");
+
+        // A plain second client gets a full frame of its own
+        let other = connect_client(&socket_paths, TermSize::new(80, 24));
+        thread::sleep(Duration::from_millis(200));
+        let other_screen = drain_data(&other);
+        assert!(String::from_utf8_lossy(&other_screen).contains("COMPRESSED"));
+
+        let info = request_info(&socket_paths);
+        let first = &info.clients[0];
+        assert_eq!(first.compression, Some(Compression::Zstd));
+        assert_eq!(
+            first.traffic.full_frames, 1,
+            "joining client caused no repaint"
+        );
+        assert!(first.traffic.sent_bytes < first.traffic.raw_bytes);
+        let second = &info.clients[1];
+        assert_eq!(second.compression, None);
+        assert_eq!(second.traffic.full_frames, 1);
+        assert_eq!(second.traffic.sent_bytes, second.traffic.raw_bytes);
+
+        shutdown_handle.store(true, Ordering::SeqCst);
+        let _ = server_handle.join();
+        let _ = socket_paths.cleanup();
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    /// E2E test: two clients type into the same buffer, each at its own cursor
+    #[test]
+    fn test_clients_edit_with_their_own_cursors() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("fresh-e2e-collab-{}", std::process::id()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        let file = temp_dir.join("notes.txt");
+        std::fs::write(&file, "one\ntwo\n").unwrap();
+
+        let session_name = unique_session_name("e2e-collab");
+        let (socket_paths, shutdown_handle, server_handle) =
+            start_editor_server(&temp_dir, &session_name);
+
+        let first = connect_client(&socket_paths, TermSize::new(80, 24));
+        let second = connect_client(&socket_paths, TermSize::new(80, 24));
+        let (ok, output) = run_command(&socket_paths, "open notes.txt");
+        assert!(ok, "open failed: {}", output);
+
+        let type_keys = |conn: &ClientConnection, keys: &[u8]| {
+            conn.write_data(keys).unwrap();
+            thread::sleep(Duration::from_millis(200));
+        };
+        // The first client types at the start of the file
+        type_keys(&first, b"A");
+        // The second client takes over from there, moves down and types
+        type_keys(&second, b"\x1b[BB");
+        // The first client's cursor was left after its "A"
+        type_keys(&first, b"C");
+        // The second client's cursor moved with the first client's edit
+        type_keys(&second, b"D");
+
+        let (ok, output) = run_command(&socket_paths, "save");
+        assert!(ok, "save failed: {}", output);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "ACone\ntBDwo\n");
+
+        shutdown_handle.store(true, Ordering::SeqCst);
+        let _ = server_handle.join();
+        let _ = socket_paths.cleanup();
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    /// E2E test: the view stays on a followed client while another one types
+    #[test]
+    fn test_follow_user_keeps_view_on_followed_client() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("fresh-e2e-follow-{}", std::process::id()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        std::fs::write(temp_dir.join("notes.txt"), "one\ntwo\nthree\nfour\n").unwrap();
+
+        let session_name = unique_session_name("e2e-follow");
+        let (socket_paths, shutdown_handle, server_handle) =
+            start_editor_server(&temp_dir, &session_name);
+
+        let first = connect_client(&socket_paths, TermSize::new(80, 24));
+        let second = connect_client(&socket_paths, TermSize::new(80, 24));
+        let (ok, output) = run_command(&socket_paths, "open notes.txt");
+        assert!(ok, "open failed: {}", output);
+
+        let type_keys = |conn: &ClientConnection, keys: &[u8]| {
+            conn.write_data(keys).unwrap();
+            thread::sleep(Duration::from_millis(200));
+        };
+        let run_follow_user = |conn: &ClientConnection| {
+            type_keys(conn, b"\x10");
+            type_keys(conn, b">Follow User\r");
+        };
+        // The first client moves to the second line
+        type_keys(&first, b"\x1b[B");
+        // The second client follows it (the only other client)
+        run_follow_user(&second);
+        type_keys(&second, b"\r");
+        // The second client moves its own cursor, but the view returns to
+        // the first client's cursor
+        type_keys(&second, b"\x1b[B\x1b[B");
+        let (_, cursor) = run_command(&socket_paths, "cursor");
+        assert_eq!(cursor, "2:1");
+
+        // Running the command again stops following
+        run_follow_user(&second);
+        type_keys(&second, b"\x1b[B");
+        let (_, cursor) = run_command(&socket_paths, "cursor");
+        assert_eq!(cursor, "5:1");
+
+        shutdown_handle.store(true, Ordering::SeqCst);
+        let _ = server_handle.join();
+        let _ = socket_paths.cleanup();
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
 }
@@ -0,0 +1,316 @@
+//! Light/dark appearance detection
+//!
+//! Picks between the two themes of a `"theme": {"light": ..., "dark": ...}`
+//! setting:
+//! - the terminal's background color, queried once at startup with OSC 11
+//!   and falling back to `COLORFGBG`
+//! - the OS dark-mode setting, polled in the background by
+//!   [`AppearanceWatcher`] so the theme follows it while the editor runs
+
+use crate::config::Appearance;
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait for the terminal to answer the background color query
+pub const TERMINAL_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How often the OS dark-mode setting is checked
+pub const OS_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Light or dark, judged by the perceived brightness of a background color
+pub fn appearance_from_rgb(r: u8, g: u8, b: u8) -> Appearance {
+    let luma = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+    if luma > 127.5 {
+        Appearance::Light
+    } else {
+        Appearance::Dark
+    }
+}
+
+/// Parse a terminal's reply to an OSC 11 query, e.g.
+/// `ESC ] 11 ; rgb:1e1e/1e1e/1e1e ESC \`
+pub fn parse_osc11_response(response: &[u8]) -> Option<Appearance> {
+    let text = String::from_utf8_lossy(response);
+    let start = text.find("]11;")? + "]11;".len();
+    let rest = &text[start..];
+    let end = rest.find(['\x07', '\x1b']).unwrap_or(rest.len());
+    let spec = &rest[..end];
+    let channels = spec
+        .strip_prefix("rgb:")
+        .or_else(|| spec.strip_prefix("rgba:"))?;
+
+    let mut channels = channels.split('/').map(parse_color_channel);
+    let (r, g, b) = (channels.next()??, channels.next()??, channels.next()??);
+    Some(appearance_from_rgb(r, g, b))
+}
+
+/// Scale a 1-4 digit hex color channel to 8 bits
+fn parse_color_channel(hex: &str) -> Option<u8> {
+    if hex.is_empty() || hex.len() > 4 {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let max = (1u32 << (4 * hex.len())) - 1;
+    Some((value * 255 / max) as u8)
+}
+
+/// Light or dark from `COLORFGBG` (`"fg;bg"` or `"fg;default;bg"`, ANSI indices)
+pub fn appearance_from_colorfgbg(value: &str) -> Option<Appearance> {
+    let bg: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+    Some(match bg {
+        7 | 9..=15 => Appearance::Light,
+        _ => Appearance::Dark,
+    })
+}
+
+/// Parse `gsettings get org.gnome.desktop.interface color-scheme`
+pub fn parse_gnome_color_scheme(output: &str) -> Option<Appearance> {
+    match output.trim().trim_matches('\'') {
+        "prefer-dark" => Some(Appearance::Dark),
+        "prefer-light" | "default" => Some(Appearance::Light),
+        _ => None,
+    }
+}
+
+/// Parse `reg query ... /v AppsUseLightTheme`
+pub fn parse_windows_light_theme(output: &str) -> Option<Appearance> {
+    let value = output
+        .lines()
+        .find(|line| line.contains("AppsUseLightTheme"))?
+        .split_whitespace()
+        .last()?;
+    match value {
+        "0x0" => Some(Appearance::Dark),
+        "0x1" => Some(Appearance::Light),
+        _ => None,
+    }
+}
+
+/// Read the OS dark-mode setting, or None when it can't be determined
+pub fn detect_os_appearance() -> Option<Appearance> {
+    if cfg!(target_os = "macos") {
+        // The key only exists while dark mode is on
+        let output = Command::new("defaults")
+            .args(["read", "-g", "AppleInterfaceStyle"])
+            .output()
+            .ok()?;
+        let dark = String::from_utf8_lossy(&output.stdout).trim() == "Dark";
+        Some(if dark {
+            Appearance::Dark
+        } else {
+            Appearance::Light
+        })
+    } else if cfg!(windows) {
+        let output = Command::new("reg")
+            .args([
+                "query",
+                r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+                "/v",
+                "AppsUseLightTheme",
+            ])
+            .output()
+            .ok()?;
+        parse_windows_light_theme(&String::from_utf8_lossy(&output.stdout))
+    } else {
+        let output = Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_gnome_color_scheme(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// Ask the terminal for its background color (OSC 11)
+///
+/// Must run in raw mode before the input reader starts. A primary device
+/// attributes query is sent after it, which every terminal answers, so
+/// terminals that ignore OSC 11 don't cost the whole timeout.
+#[cfg(unix)]
+pub fn query_terminal_background(timeout: Duration) -> Option<Appearance> {
+    use nix::poll::{poll, PollFd, PollFlags};
+    use std::io::{Read, Write};
+    use std::os::fd::AsFd;
+    use std::time::Instant;
+
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    tty.write_all(b"\x1b]11;?\x1b\\\x1b[c").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + timeout;
+    let mut response = Vec::new();
+    let mut buf = [0u8; 256];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let millis = u16::try_from(remaining.as_millis()).unwrap_or(u16::MAX);
+        if millis == 0 {
+            break;
+        }
+        let mut fds = [PollFd::new(tty.as_fd(), PollFlags::POLLIN)];
+        if poll(&mut fds, millis).ok()? == 0 {
+            break;
+        }
+        let n = tty.read(&mut buf).ok()?;
+        if n == 0 {
+            break;
+        }
+        response.extend_from_slice(&buf[..n]);
+        if device_attributes_received(&response) {
+            break;
+        }
+    }
+    parse_osc11_response(&response)
+}
+
+#[cfg(not(unix))]
+pub fn query_terminal_background(_timeout: Duration) -> Option<Appearance> {
+    None
+}
+
+/// Whether the reply to the device attributes query (`ESC [ ? ... c`) arrived
+#[cfg(unix)]
+fn device_attributes_received(response: &[u8]) -> bool {
+    response
+        .windows(3)
+        .position(|w| w == b"\x1b[?")
+        .is_some_and(|start| response[start..].contains(&b'c'))
+}
+
+/// Appearance at startup: terminal background, then the OS setting, then
+/// `COLORFGBG`
+pub fn detect_appearance() -> Option<Appearance> {
+    query_terminal_background(TERMINAL_QUERY_TIMEOUT)
+        .or_else(detect_os_appearance)
+        .or_else(|| {
+            std::env::var("COLORFGBG")
+                .ok()
+                .and_then(|value| appearance_from_colorfgbg(&value))
+        })
+}
+
+/// Background thread reporting changes of the OS dark-mode setting
+pub struct AppearanceWatcher {
+    receiver: Receiver<Appearance>,
+}
+
+impl AppearanceWatcher {
+    /// Start polling the OS setting every `interval`
+    ///
+    /// The thread exits right away when the setting can't be read and once
+    /// the watcher is dropped.
+    pub fn spawn(interval: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name("appearance-watcher".to_string())
+            .spawn(move || {
+                let Some(mut last) = detect_os_appearance() else {
+                    return;
+                };
+                loop {
+                    thread::sleep(interval);
+                    let Some(current) = detect_os_appearance() else {
+                        continue;
+                    };
+                    if current != last {
+                        last = current;
+                        if sender.send(current).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::warn!("Failed to start appearance watcher: {}", e);
+        }
+        Self { receiver }
+    }
+
+    /// The latest appearance the OS switched to since the last poll
+    pub fn poll(&self) -> Option<Appearance> {
+        self.receiver.try_iter().last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_osc11_response() {
+        assert_eq!(
+            parse_osc11_response(b"\x1b]11;rgb:ffff/ffff/ffff\x1b\\"),
+            Some(Appearance::Light)
+        );
+        assert_eq!(
+            parse_osc11_response(b"\x1b]11;rgb:1e1e/1e1e/2e2e\x07\x1b[?62;22c"),
+            Some(Appearance::Dark)
+        );
+        assert_eq!(
+            parse_osc11_response(b"\x1b]11;rgba:fd/f6/e3/ff\x07"),
+            Some(Appearance::Light)
+        );
+        // Only the device attributes reply: OSC 11 unsupported
+        assert_eq!(parse_osc11_response(b"\x1b[?62;22c"), None);
+        assert_eq!(parse_osc11_response(b"\x1b]11;rgb:zz/00/00\x07"), None);
+    }
+
+    #[test]
+    fn test_parse_color_channel_scales_to_8_bits() {
+        assert_eq!(parse_color_channel("f"), Some(255));
+        assert_eq!(parse_color_channel("80"), Some(128));
+        assert_eq!(parse_color_channel("ffff"), Some(255));
+        assert_eq!(parse_color_channel("0000"), Some(0));
+        assert_eq!(parse_color_channel("12345"), None);
+    }
+
+    #[test]
+    fn test_appearance_from_colorfgbg() {
+        assert_eq!(appearance_from_colorfgbg("15;0"), Some(Appearance::Dark));
+        assert_eq!(appearance_from_colorfgbg("0;15"), Some(Appearance::Light));
+        assert_eq!(
+            appearance_from_colorfgbg("0;default;7"),
+            Some(Appearance::Light)
+        );
+        assert_eq!(appearance_from_colorfgbg("default"), None);
+    }
+
+    #[test]
+    fn test_parse_os_settings() {
+        assert_eq!(
+            parse_gnome_color_scheme("'prefer-dark'\n"),
+            Some(Appearance::Dark)
+        );
+        assert_eq!(
+            parse_gnome_color_scheme("'default'\n"),
+            Some(Appearance::Light)
+        );
+        assert_eq!(parse_gnome_color_scheme(""), None);
+
+        let output = "\nHKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize\n    AppsUseLightTheme    REG_DWORD    0x0\n";
+        assert_eq!(parse_windows_light_theme(output), Some(Appearance::Dark));
+        assert_eq!(
+            parse_windows_light_theme(&output.replace("0x0", "0x1")),
+            Some(Appearance::Light)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_device_attributes_received() {
+        assert!(!device_attributes_received(b"\x1b]11;rgb:0/0/0\x07"));
+        assert!(!device_attributes_received(
+            b"\x1b]11;rgb:0/0/0\x07\x1b[?62;"
+        ));
+        assert!(device_attributes_received(
+            b"\x1b]11;rgb:0/0/0\x07\x1b[?62;22c"
+        ));
+    }
+}
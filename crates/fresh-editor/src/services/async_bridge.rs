@@ -13,8 +13,8 @@
 use crate::services::terminal::TerminalId;
 use crate::view::file_tree::{FileTreeView, NodeId};
 use lsp_types::{
-    CodeActionOrCommand, CompletionItem, Diagnostic, InlayHint, Location,
-    SemanticTokensFullDeltaResult, SemanticTokensLegend, SemanticTokensRangeResult,
+    CodeActionOrCommand, CodeLens, CompletionItem, Diagnostic, DocumentHighlight, InlayHint,
+    Location, SemanticTokensFullDeltaResult, SemanticTokensLegend, SemanticTokensRangeResult,
     SemanticTokensResult, SignatureHelp,
 };
 use serde_json::Value;
@@ -33,6 +33,8 @@ pub enum LspSemanticTokensResponse {
 pub enum AsyncMessage {
     /// LSP diagnostics received for a file
     LspDiagnostics {
+        /// Server ID that published the diagnostics
+        language: String,
         uri: String,
         diagnostics: Vec<Diagnostic>,
     },
@@ -111,6 +113,8 @@ pub enum AsyncMessage {
     /// LSP pulled diagnostics response (textDocument/diagnostic)
     LspPulledDiagnostics {
         request_id: u64,
+        /// Server ID that answered the request
+        language: String,
         uri: String,
         /// New result_id for incremental updates (None if server doesn't support)
         result_id: Option<String>,
@@ -128,6 +132,21 @@ pub enum AsyncMessage {
         hints: Vec<InlayHint>,
     },
 
+    /// LSP document highlight response (textDocument/documentHighlight)
+    LspDocumentHighlight {
+        request_id: u64,
+        uri: String,
+        /// Occurrences of the symbol under the cursor (empty if unsupported)
+        highlights: Vec<DocumentHighlight>,
+    },
+
+    /// LSP code lens response (textDocument/codeLens, already resolved)
+    LspCodeLens {
+        request_id: u64,
+        uri: String,
+        lenses: Vec<CodeLens>,
+    },
+
     /// LSP semantic tokens response (full, full/delta, or range)
     LspSemanticTokens {
         request_id: u64,
@@ -167,6 +186,28 @@ pub enum AsyncMessage {
     /// File open dialog: async shortcuts (Windows drive letters) loaded
     FileOpenShortcutsLoaded(Vec<crate::app::file_open::NavigationShortcut>),
 
+    /// A large file read on a worker thread finished loading
+    FileLoaded {
+        request_id: u64,
+        result: anyhow::Result<crate::model::buffer::TextBuffer>,
+    },
+
+    /// A large buffer written on a worker thread finished saving
+    FileSaved {
+        buffer_id: crate::model::event::BufferId,
+        path: std::path::PathBuf,
+        result: anyhow::Result<()>,
+    },
+
+    /// Update Fresh Now finished downloading and installing a release
+    SelfUpdateFinished(Result<crate::services::self_update::InstalledUpdate, String>),
+
+    /// A run of the watch task finished
+    WatchTaskFinished {
+        run_id: u64,
+        result: Result<crate::services::remote::SpawnResult, String>,
+    },
+
     /// Terminal output received (triggers redraw)
     TerminalOutput { terminal_id: TerminalId },
 
@@ -478,6 +519,7 @@ mod tests {
 
         sender
             .send(AsyncMessage::LspDiagnostics {
+                language: "rust".to_string(),
                 uri: "file:///test.rs".to_string(),
                 diagnostics: diagnostics.clone(),
             })
@@ -490,6 +532,7 @@ mod tests {
             AsyncMessage::LspDiagnostics {
                 uri,
                 diagnostics: diags,
+                ..
             } => {
                 assert_eq!(uri, "file:///test.rs");
                 assert_eq!(diags.len(), 1);
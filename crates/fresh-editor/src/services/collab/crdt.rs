@@ -0,0 +1,444 @@
+//! Replicated text for buffer sharing
+//!
+//! The document is an RGA sequence. Every character that was ever inserted
+//! keeps a unique id (a Lamport counter plus the id of the site that inserted
+//! it), and deleted characters stay in the sequence as tombstones. An insert
+//! names the character it follows, and concurrent inserts after the same
+//! character are ordered by id, so sites that apply the same operations end
+//! up with the same text whatever order the operations arrived in.
+//!
+//! Positions used by the editor are byte offsets into the visible text.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Identity of one inserted character
+///
+/// Ordered by counter, then site. Among characters inserted after the same
+/// one, the greater id comes first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CharId {
+    pub counter: u64,
+    pub site: u64,
+}
+
+impl CharId {
+    fn offset(self, n: usize) -> Self {
+        Self {
+            counter: self.counter + n as u64,
+            site: self.site,
+        }
+    }
+}
+
+/// A change made at one site, sent to the others
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Op {
+    /// `text` inserted after `origin` (`None` for the start of the document).
+    /// Its characters have consecutive counters starting at `id`.
+    Insert {
+        id: CharId,
+        origin: Option<CharId>,
+        text: String,
+    },
+    /// Characters removed
+    Delete { ids: Vec<CharId> },
+}
+
+/// A change to the local text caused by a remote operation
+///
+/// Edits are in the order they must be applied; each position is relative
+/// to the text after the previous edits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Edit {
+    Insert { position: usize, text: String },
+    Delete { range: Range<usize> },
+}
+
+/// Consecutive characters of a snapshot, stored like an insert
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Run {
+    pub id: CharId,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deleted: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Element {
+    id: CharId,
+    ch: char,
+    deleted: bool,
+}
+
+/// One site's copy of a shared document
+#[derive(Clone, Debug)]
+pub struct Document {
+    site: u64,
+    /// Highest counter seen, local or remote
+    clock: u64,
+    elements: Vec<Element>,
+}
+
+impl Document {
+    /// Start a document with `text`, inserted by `site`
+    pub fn new(site: u64, text: &str) -> Self {
+        let mut document = Self {
+            site,
+            clock: 0,
+            elements: Vec::new(),
+        };
+        if !text.is_empty() {
+            document.local_insert(0, text);
+        }
+        document
+    }
+
+    /// Rebuild a document received from another site
+    pub fn from_snapshot(site: u64, runs: &[Run]) -> Self {
+        let mut elements = Vec::new();
+        let mut clock = 0;
+        for run in runs {
+            for (i, ch) in run.text.chars().enumerate() {
+                let id = run.id.offset(i);
+                clock = clock.max(id.counter);
+                elements.push(Element {
+                    id,
+                    ch,
+                    deleted: run.deleted,
+                });
+            }
+        }
+        Self {
+            site,
+            clock,
+            elements,
+        }
+    }
+
+    /// The whole sequence, tombstones included, for a site that is joining
+    pub fn snapshot(&self) -> Vec<Run> {
+        let mut runs: Vec<Run> = Vec::new();
+        let mut next = None;
+        for element in &self.elements {
+            match runs.last_mut() {
+                Some(run) if next == Some(element.id) && run.deleted == element.deleted => {
+                    run.text.push(element.ch)
+                }
+                _ => runs.push(Run {
+                    id: element.id,
+                    text: element.ch.to_string(),
+                    deleted: element.deleted,
+                }),
+            }
+            next = Some(element.id.offset(1));
+        }
+        runs
+    }
+
+    pub fn site(&self) -> u64 {
+        self.site
+    }
+
+    /// The visible text
+    pub fn text(&self) -> String {
+        self.elements
+            .iter()
+            .filter(|e| !e.deleted)
+            .map(|e| e.ch)
+            .collect()
+    }
+
+    /// Record `text` inserted at byte `position`
+    pub fn local_insert(&mut self, position: usize, text: &str) -> Op {
+        let index = self.index_after(position);
+        let origin = index.checked_sub(1).map(|i| self.elements[i].id);
+        let id = CharId {
+            counter: self.clock + 1,
+            site: self.site,
+        };
+        let elements: Vec<Element> = text
+            .chars()
+            .enumerate()
+            .map(|(i, ch)| Element {
+                id: id.offset(i),
+                ch,
+                deleted: false,
+            })
+            .collect();
+        self.clock += elements.len() as u64;
+        self.elements.splice(index..index, elements);
+        Op::Insert {
+            id,
+            origin,
+            text: text.to_string(),
+        }
+    }
+
+    /// Record the bytes in `range` deleted; `None` if nothing was there
+    pub fn local_delete(&mut self, range: Range<usize>) -> Option<Op> {
+        let mut ids = Vec::new();
+        let mut offset = 0;
+        for element in self.elements.iter_mut().filter(|e| !e.deleted) {
+            if offset >= range.end {
+                break;
+            }
+            if offset >= range.start {
+                element.deleted = true;
+                ids.push(element.id);
+            }
+            offset += element.ch.len_utf8();
+        }
+        (!ids.is_empty()).then_some(Op::Delete { ids })
+    }
+
+    /// Record the changes that turn the visible text into `text`
+    ///
+    /// The edit is found by trimming the common prefix and suffix, which is
+    /// exact for the single insertion or deletion a keystroke makes and
+    /// still correct, if coarse, for anything else.
+    pub fn update(&mut self, text: &str) -> Vec<Op> {
+        let old = self.text();
+        let prefix = common_prefix(&old, text);
+        let suffix = common_suffix(&old[prefix..], &text[prefix..]);
+        let removed = prefix..old.len() - suffix;
+        let inserted = &text[prefix..text.len() - suffix];
+
+        let mut ops = Vec::new();
+        if let Some(op) = self.local_delete(removed) {
+            ops.push(op);
+        }
+        if !inserted.is_empty() {
+            ops.push(self.local_insert(prefix, inserted));
+        }
+        ops
+    }
+
+    /// Apply an operation from another site
+    pub fn apply(&mut self, op: &Op) -> Vec<Edit> {
+        match op {
+            Op::Insert { id, origin, text } => self.apply_insert(*id, *origin, text),
+            Op::Delete { ids } => self.apply_delete(ids),
+        }
+    }
+
+    fn apply_insert(&mut self, id: CharId, origin: Option<CharId>, text: &str) -> Vec<Edit> {
+        if text.is_empty() || self.elements.iter().any(|e| e.id == id) {
+            return Vec::new();
+        }
+        let mut index = match origin {
+            None => 0,
+            Some(origin) => match self.elements.iter().position(|e| e.id == origin) {
+                Some(i) => i + 1,
+                None => {
+                    tracing::warn!("collab: insert after unknown character {:?}", origin);
+                    return Vec::new();
+                }
+            },
+        };
+        // Concurrent inserts after the same character: greater ids go first
+        while index < self.elements.len() && self.elements[index].id > id {
+            index += 1;
+        }
+
+        let position = self.elements[..index]
+            .iter()
+            .filter(|e| !e.deleted)
+            .map(|e| e.ch.len_utf8())
+            .sum();
+        let elements: Vec<Element> = text
+            .chars()
+            .enumerate()
+            .map(|(i, ch)| Element {
+                id: id.offset(i),
+                ch,
+                deleted: false,
+            })
+            .collect();
+        self.clock = self.clock.max(id.counter + elements.len() as u64 - 1);
+        self.elements.splice(index..index, elements);
+        vec![Edit::Insert {
+            position,
+            text: text.to_string(),
+        }]
+    }
+
+    fn apply_delete(&mut self, ids: &[CharId]) -> Vec<Edit> {
+        let ids: HashSet<CharId> = ids.iter().copied().collect();
+        let mut edits: Vec<Edit> = Vec::new();
+        let mut offset = 0;
+        for element in self.elements.iter_mut().filter(|e| !e.deleted) {
+            let len = element.ch.len_utf8();
+            if !ids.contains(&element.id) {
+                offset += len;
+                continue;
+            }
+            element.deleted = true;
+            // The offset doesn't advance: later text moves up to this position
+            match edits.last_mut() {
+                Some(Edit::Delete { range }) if range.start == offset => range.end += len,
+                _ => edits.push(Edit::Delete {
+                    range: offset..offset + len,
+                }),
+            }
+        }
+        edits
+    }
+
+    /// Index in `elements` just after the visible character that ends at
+    /// byte `position`
+    fn index_after(&self, position: usize) -> usize {
+        if position == 0 {
+            return 0;
+        }
+        let mut offset = 0;
+        for (i, element) in self.elements.iter().enumerate() {
+            if element.deleted {
+                continue;
+            }
+            offset += element.ch.len_utf8();
+            if offset >= position {
+                return i + 1;
+            }
+        }
+        self.elements.len()
+    }
+}
+
+/// Length in bytes of the common prefix, on a character boundary
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map(|((i, _), _)| i)
+        .unwrap_or(a.len().min(b.len()))
+}
+
+/// Length in bytes of the common suffix, on a character boundary
+fn common_suffix(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply `edits` the way the editor applies them to its buffer
+    fn apply_edits(text: &mut String, edits: &[Edit]) {
+        for edit in edits {
+            match edit {
+                Edit::Insert { position, text: t } => text.insert_str(*position, t),
+                Edit::Delete { range } => {
+                    text.replace_range(range.clone(), "");
+                }
+            }
+        }
+    }
+
+    /// A site plus a copy of its buffer, kept in step like the editor does
+    struct Site {
+        document: Document,
+        buffer: String,
+    }
+
+    impl Site {
+        fn join(site: u64, host: &Document) -> Self {
+            let document = Document::from_snapshot(site, &host.snapshot());
+            let buffer = document.text();
+            Self { document, buffer }
+        }
+
+        fn edit(&mut self, f: impl FnOnce(&mut String)) -> Vec<Op> {
+            f(&mut self.buffer);
+            self.document.update(&self.buffer)
+        }
+
+        fn receive(&mut self, ops: &[Op]) {
+            for op in ops {
+                let edits = self.document.apply(op);
+                apply_edits(&mut self.buffer, &edits);
+            }
+            assert_eq!(self.buffer, self.document.text());
+        }
+    }
+
+    #[test]
+    fn test_concurrent_inserts_at_the_same_position_converge() {
+        let host = Document::new(1, "ac");
+        let mut a = Site::join(2, &host);
+        let mut b = Site::join(3, &host);
+
+        let from_a = a.edit(|t| t.insert_str(1, "XX"));
+        let from_b = b.edit(|t| t.insert_str(1, "YY"));
+        a.receive(&from_b);
+        b.receive(&from_a);
+
+        assert_eq!(a.buffer, b.buffer);
+        // Each site's run stays together
+        assert!(a.buffer == "aXXYYc" || a.buffer == "aYYXXc");
+    }
+
+    #[test]
+    fn test_insert_into_concurrently_deleted_text_survives() {
+        let host = Document::new(1, "hello world");
+        let mut a = Site::join(2, &host);
+        let mut b = Site::join(3, &host);
+
+        let from_a = a.edit(|t| t.replace_range(5..11, ""));
+        let from_b = b.edit(|t| t.insert_str(8, "!!"));
+        a.receive(&from_b);
+        b.receive(&from_a);
+
+        assert_eq!(a.buffer, "hello!!");
+        assert_eq!(b.buffer, "hello!!");
+    }
+
+    #[test]
+    fn test_delete_split_by_a_concurrent_insert() {
+        let host = Document::new(1, "abcdef");
+        let mut a = Site::join(2, &host);
+        let mut b = Site::join(3, &host);
+
+        let from_a = a.edit(|t| t.replace_range(1..5, ""));
+        let from_b = b.edit(|t| t.insert_str(3, "Z"));
+        b.receive(&from_a);
+        a.receive(&from_b);
+
+        assert_eq!(a.buffer, "aZf");
+        assert_eq!(b.buffer, "aZf");
+    }
+
+    #[test]
+    fn test_update_finds_edits_in_multibyte_text() {
+        let mut document = Document::new(1, "héllo wörld");
+        let ops = document.update("héllo, wörld");
+        assert_eq!(ops.len(), 1);
+        assert!(matches!(&ops[0], Op::Insert { text, .. } if text == ","));
+
+        let ops = document.update("hé, wörld");
+        assert!(matches!(&ops[0], Op::Delete { ids } if ids.len() == 3));
+        assert_eq!(document.text(), "hé, wörld");
+    }
+
+    #[test]
+    fn test_snapshot_keeps_tombstones_and_ids() {
+        let mut host = Document::new(1, "abc");
+        host.update("ac");
+        let insert = host.local_insert(1, "b");
+
+        let mut joined = Document::from_snapshot(2, &host.snapshot());
+        assert_eq!(joined.text(), "abc");
+        assert_eq!(host.snapshot().len(), 4);
+        // Already applied through the snapshot
+        assert!(joined.apply(&insert).is_empty());
+        // New local ids don't collide with the host's
+        assert!(matches!(joined.local_insert(0, "x"), Op::Insert { id, .. } if id.counter == 5));
+    }
+}
@@ -0,0 +1,359 @@
+//! Peer-to-peer buffer sharing (**Collab: Share Buffer** / **Collab: Join**)
+//!
+//! One editor shares a buffer by listening on a TCP address and others join
+//! by connecting to it. Each connection carries JSON messages, one per line.
+//! The sharing editor sends every joiner a snapshot of the document; after
+//! that every site sends the operations for its own edits. The sharing
+//! editor forwards the operations it receives to its other peers, so joiners
+//! only ever talk to it.
+//!
+//! The link has no authentication or encryption: share on a trusted network
+//! or through an SSH tunnel.
+
+pub mod crdt;
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use crdt::{Op, Run};
+
+/// Peer id a joining editor uses for the editor it joined
+pub const HOST_PEER: u64 = 0;
+
+/// How often the listener checks whether sharing stopped
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long joining waits for the sharing editor to answer
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A message on the link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// First message to a joiner: the shared buffer's name and document
+    Welcome { name: String, snapshot: Vec<Run> },
+    /// Edits made at one site
+    Ops { ops: Vec<Op> },
+}
+
+/// What the network threads report to the editor
+#[derive(Debug)]
+pub enum LinkEvent {
+    /// A joiner connected, or (when joining) the connection was made
+    Connected {
+        peer: u64,
+        address: String,
+    },
+    Message {
+        peer: u64,
+        message: Message,
+    },
+    /// A connection closed; `error` is set if it failed
+    Disconnected {
+        peer: u64,
+        error: Option<String>,
+    },
+}
+
+/// Recipients of an outgoing message
+#[derive(Debug, Clone, Copy)]
+pub enum Target {
+    All,
+    Peer(u64),
+    AllExcept(u64),
+}
+
+enum Outgoing {
+    AddPeer(u64, TcpStream),
+    Send(Target, String),
+}
+
+/// A running share or join
+///
+/// Dropping it closes every connection.
+pub struct Link {
+    outgoing: mpsc::Sender<Outgoing>,
+    events: mpsc::Receiver<LinkEvent>,
+    local_addr: Option<SocketAddr>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl Link {
+    /// Listen on `address` for editors that join
+    pub fn share(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr().ok();
+
+        let (link, events) = Self::start(local_addr);
+        let outgoing = link.outgoing.clone();
+        let stopped = link.stopped.clone();
+        thread::Builder::new()
+            .name("collab-listener".to_string())
+            .spawn(move || {
+                let mut next_peer = HOST_PEER + 1;
+                while !stopped.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, address)) => {
+                            let _ = stream.set_nonblocking(false);
+                            let peer = next_peer;
+                            next_peer += 1;
+                            if !add_peer(peer, stream, &outgoing, &events) {
+                                break;
+                            }
+                            let _ = events.send(LinkEvent::Connected {
+                                peer,
+                                address: address.to_string(),
+                            });
+                        }
+                        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                            thread::sleep(ACCEPT_POLL_INTERVAL)
+                        }
+                        Err(e) => {
+                            tracing::warn!("collab: accept failed: {}", e);
+                            thread::sleep(ACCEPT_POLL_INTERVAL);
+                        }
+                    }
+                }
+            })?;
+        Ok(link)
+    }
+
+    /// Connect to an editor sharing a buffer at `address`
+    ///
+    /// Connecting happens in the background; the result arrives as a
+    /// `Connected` or `Disconnected` event for `HOST_PEER`.
+    pub fn join(address: &str) -> io::Result<Self> {
+        let addresses: Vec<SocketAddr> = address.to_socket_addrs()?.collect();
+        let (link, events) = Self::start(None);
+        let outgoing = link.outgoing.clone();
+        let address = address.to_string();
+        thread::Builder::new()
+            .name("collab-connect".to_string())
+            .spawn(move || {
+                let mut last_error = io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("no address found for {}", address),
+                );
+                for candidate in &addresses {
+                    match TcpStream::connect_timeout(candidate, CONNECT_TIMEOUT) {
+                        Ok(stream) => {
+                            if add_peer(HOST_PEER, stream, &outgoing, &events) {
+                                let _ = events.send(LinkEvent::Connected {
+                                    peer: HOST_PEER,
+                                    address,
+                                });
+                            }
+                            return;
+                        }
+                        Err(e) => last_error = e,
+                    }
+                }
+                let _ = events.send(LinkEvent::Disconnected {
+                    peer: HOST_PEER,
+                    error: Some(last_error.to_string()),
+                });
+            })?;
+        Ok(link)
+    }
+
+    /// Start the writer thread; returns the link and the sender for events
+    fn start(local_addr: Option<SocketAddr>) -> (Self, mpsc::Sender<LinkEvent>) {
+        let (outgoing, outgoing_rx) = mpsc::channel();
+        let (events_tx, events) = mpsc::channel();
+        let _ = thread::Builder::new()
+            .name("collab-writer".to_string())
+            .spawn(move || write_loop(outgoing_rx));
+        let link = Self {
+            outgoing,
+            events,
+            local_addr,
+            stopped: Arc::new(AtomicBool::new(false)),
+        };
+        (link, events_tx)
+    }
+
+    /// Address the share is listening on
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// Queue `message` for `target`
+    pub fn send(&self, target: Target, message: &Message) {
+        match serde_json::to_string(message) {
+            Ok(line) => {
+                let _ = self.outgoing.send(Outgoing::Send(target, line));
+            }
+            Err(e) => tracing::warn!("collab: failed to encode message: {}", e),
+        }
+    }
+
+    /// Events received since the last call
+    pub fn poll(&self) -> Vec<LinkEvent> {
+        self.events.try_iter().collect()
+    }
+}
+
+impl Drop for Link {
+    fn drop(&mut self) {
+        // The writer closes every connection once the sender is gone
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Hand a connection to the writer and start reading from it
+///
+/// Returns false if the link is already gone.
+fn add_peer(
+    peer: u64,
+    stream: TcpStream,
+    outgoing: &mpsc::Sender<Outgoing>,
+    events: &mpsc::Sender<LinkEvent>,
+) -> bool {
+    let reader = match stream.try_clone() {
+        Ok(reader) => reader,
+        Err(e) => {
+            let _ = events.send(LinkEvent::Disconnected {
+                peer,
+                error: Some(e.to_string()),
+            });
+            return true;
+        }
+    };
+    if outgoing.send(Outgoing::AddPeer(peer, stream)).is_err() {
+        return false;
+    }
+    let events = events.clone();
+    let _ = thread::Builder::new()
+        .name(format!("collab-reader-{}", peer))
+        .spawn(move || read_loop(peer, reader, events));
+    true
+}
+
+fn read_loop(peer: u64, stream: TcpStream, events: mpsc::Sender<LinkEvent>) {
+    let mut error = None;
+    for line in BufReader::new(stream).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                error = Some(e.to_string());
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(message) => {
+                if events.send(LinkEvent::Message { peer, message }).is_err() {
+                    return;
+                }
+            }
+            Err(e) => {
+                error = Some(format!("invalid message: {}", e));
+                break;
+            }
+        }
+    }
+    let _ = events.send(LinkEvent::Disconnected { peer, error });
+}
+
+fn write_loop(outgoing: mpsc::Receiver<Outgoing>) {
+    let mut peers: HashMap<u64, TcpStream> = HashMap::new();
+    for message in outgoing {
+        match message {
+            Outgoing::AddPeer(peer, stream) => {
+                peers.insert(peer, stream);
+            }
+            Outgoing::Send(target, line) => {
+                peers.retain(|peer, stream| {
+                    let wanted = match target {
+                        Target::All => true,
+                        Target::Peer(p) => *peer == p,
+                        Target::AllExcept(p) => *peer != p,
+                    };
+                    if !wanted {
+                        return true;
+                    }
+                    // A failed write drops the peer; its reader reports why
+                    let written = stream
+                        .write_all(line.as_bytes())
+                        .and_then(|_| stream.write_all(b"\n"));
+                    if written.is_err() {
+                        let _ = stream.shutdown(Shutdown::Both);
+                    }
+                    written.is_ok()
+                });
+            }
+        }
+    }
+    for stream in peers.values() {
+        let _ = stream.shutdown(Shutdown::Both);
+    }
+}
+
+/// A random id for this site, so ids from different editors don't collide
+pub fn new_site_id() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn wait_for(link: &Link, mut f: impl FnMut(&LinkEvent) -> bool) -> LinkEvent {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            if let Some(event) = link.poll().into_iter().find(|e| f(e)) {
+                return event;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for link event"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_join_receives_messages_from_share() {
+        let host = Link::share("127.0.0.1:0").unwrap();
+        let address = host.local_addr().unwrap().to_string();
+        let guest = Link::join(&address).unwrap();
+
+        wait_for(&guest, |e| matches!(e, LinkEvent::Connected { .. }));
+        let peer = match wait_for(&host, |e| matches!(e, LinkEvent::Connected { .. })) {
+            LinkEvent::Connected { peer, .. } => peer,
+            _ => unreachable!(),
+        };
+
+        host.send(
+            Target::Peer(peer),
+            &Message::Welcome {
+                name: "notes.txt".to_string(),
+                snapshot: Vec::new(),
+            },
+        );
+        let event = wait_for(&guest, |e| matches!(e, LinkEvent::Message { .. }));
+        assert!(matches!(
+            event,
+            LinkEvent::Message {
+                peer: HOST_PEER,
+                message: Message::Welcome { ref name, .. },
+            } if name == "notes.txt"
+        ));
+
+        drop(host);
+        wait_for(&guest, |e| matches!(e, LinkEvent::Disconnected { .. }));
+    }
+}
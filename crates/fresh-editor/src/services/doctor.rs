@@ -0,0 +1,764 @@
+//! Install diagnostics (`fresh --cmd doctor`)
+//!
+//! Checks the things that most often make Fresh behave differently from one
+//! machine to the next and prints a fix for each problem:
+//!
+//! - Terminal: color depth, the kitty keyboard protocol, bracketed paste,
+//!   OSC 52 clipboard support (including tmux and GNU Screen in between),
+//!   image previews and OSC 8 hyperlinks
+//! - Directories: the config, data and log directories are writable and the
+//!   user config file is valid
+//! - Language tools: the configured language servers and formatters are on
+//!   `PATH`
+//! - Sessions: the socket directory is private and free of stale sessions
+//!
+//! Nothing is changed while checking, apart from creating missing directories
+//! the editor would create on startup anyway.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::Config;
+use crate::config_io::DirectoryContext;
+use crate::server::ipc::SocketPaths;
+use crate::view::color_support::ColorCapability;
+use crate::view::graphics_protocol::GraphicsProtocol;
+use crate::view::hyperlinks;
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// Informational, e.g. a tool only needed for a language not in use
+    Info,
+    /// Works, but something is degraded
+    Warning,
+    /// Something is broken
+    Error,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Info => "info",
+            Self::Warning => "warn",
+            Self::Error => "FAIL",
+        }
+    }
+}
+
+/// A single diagnostic with an optional fix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+    /// What to do about it, for warnings and errors
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+/// Checks grouped under a heading
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub title: &'static str,
+    pub checks: Vec<Check>,
+}
+
+/// Everything `fresh --cmd doctor` found
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub sections: Vec<Section>,
+}
+
+impl Report {
+    /// Number of checks with the given status
+    pub fn count(&self, status: Status) -> usize {
+        self.sections
+            .iter()
+            .flat_map(|s| &s.checks)
+            .filter(|c| c.status == status)
+            .count()
+    }
+
+    /// Plain-text report, one line per check with its fix indented below
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for section in &self.sections {
+            let _ = writeln!(out, "{}", section.title);
+            for check in &section.checks {
+                let _ = writeln!(
+                    out,
+                    "  [{:>4}] {}: {}",
+                    check.status.label(),
+                    check.name,
+                    check.detail
+                );
+                if let Some(fix) = &check.fix {
+                    for line in fix.lines() {
+                        let _ = writeln!(out, "         fix: {}", line);
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        let _ = writeln!(
+            out,
+            "{} error(s), {} warning(s)",
+            self.count(Status::Error),
+            self.count(Status::Warning)
+        );
+        out
+    }
+}
+
+/// Run every check against the effective `config`
+pub fn run(config: &Config, dir_context: &DirectoryContext) -> Report {
+    let env = |key: &str| std::env::var(key).ok().filter(|v| !v.is_empty());
+    Report {
+        sections: vec![
+            Section {
+                title: "Terminal",
+                checks: terminal_checks(&env, query_keyboard_enhancement(config)),
+            },
+            Section {
+                title: "Directories",
+                checks: directory_checks(dir_context),
+            },
+            Section {
+                title: "Language tools",
+                checks: language_tool_checks(config, &|command| {
+                    find_in_path(command, env("PATH").as_deref())
+                }),
+            },
+            Section {
+                title: "Sessions",
+                checks: session_checks(),
+            },
+        ],
+    }
+}
+
+/// Ask the terminal whether it speaks the kitty keyboard protocol. `None`
+/// when the protocol is turned off in the config or there's no terminal.
+fn query_keyboard_enhancement(config: &Config) -> Option<Result<bool, String>> {
+    use std::io::IsTerminal;
+
+    let editor = &config.editor;
+    let wanted = editor.keyboard_disambiguate_escape_codes
+        || editor.keyboard_report_event_types
+        || editor.keyboard_report_alternate_keys
+        || editor.keyboard_report_all_keys_as_escape_codes;
+    if !wanted || !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return None;
+    }
+    Some(crossterm::terminal::supports_keyboard_enhancement().map_err(|e| e.to_string()))
+}
+
+fn terminal_checks(
+    env: &dyn Fn(&str) -> Option<String>,
+    keyboard: Option<Result<bool, String>>,
+) -> Vec<Check> {
+    let term = env("TERM").unwrap_or_default().to_lowercase();
+    let in_tmux = env("TMUX").is_some() || term.starts_with("tmux");
+    let in_screen = env("STY").is_some() || term.starts_with("screen");
+
+    vec![
+        color_check(env, &term, in_tmux, in_screen),
+        keyboard_check(keyboard, in_tmux),
+        bracketed_paste_check(&term),
+        clipboard_check(env, in_tmux, in_screen, || tmux_option("set-clipboard")),
+        image_check(env),
+        hyperlink_check(env),
+    ]
+}
+
+fn image_check(env: &dyn Fn(&str) -> Option<String>) -> Check {
+    const NAME: &str = "Image previews";
+    match GraphicsProtocol::detect_from(env) {
+        GraphicsProtocol::None => Check::new(
+            NAME,
+            Status::Info,
+            "no graphics protocol detected; image files show their size only",
+        )
+        .with_fix("If your terminal supports images, force a protocol with FRESH_IMAGE_PROTOCOL=kitty|sixel"),
+        protocol => Check::new(NAME, Status::Ok, protocol.name()),
+    }
+}
+
+fn hyperlink_check(env: &dyn Fn(&str) -> Option<String>) -> Check {
+    const NAME: &str = "Hyperlinks (OSC 8)";
+    if hyperlinks::detect_from(env) {
+        Check::new(NAME, Status::Ok, "URLs on screen are clickable")
+    } else {
+        Check::new(
+            NAME,
+            Status::Info,
+            "not detected; URLs are shown as plain text",
+        )
+        .with_fix(
+            "If your terminal supports OSC 8 hyperlinks, turn them on with FRESH_HYPERLINKS=1",
+        )
+    }
+}
+
+fn color_check(
+    env: &dyn Fn(&str) -> Option<String>,
+    term: &str,
+    in_tmux: bool,
+    in_screen: bool,
+) -> Check {
+    const NAME: &str = "Colors";
+    match ColorCapability::detect_from(env) {
+        ColorCapability::TrueColor => Check::new(NAME, Status::Ok, "24-bit color"),
+        capability => {
+            let depth = if capability == ColorCapability::Color256 {
+                "256 colors"
+            } else {
+                "16 colors"
+            };
+            let check = Check::new(
+                NAME,
+                Status::Warning,
+                format!("{} (TERM={}); themes are approximated", depth, term),
+            );
+            if in_screen {
+                check.with_fix(
+                    "GNU Screen is limited to 256 colors; run Fresh in tmux or directly in the terminal",
+                )
+            } else if in_tmux {
+                check.with_fix(
+                    "Add `set -ga terminal-overrides \",*:RGB\"` to ~/.tmux.conf and restart tmux,\nthen `export COLORTERM=truecolor`",
+                )
+            } else {
+                check.with_fix(
+                    "If your terminal supports 24-bit color, `export COLORTERM=truecolor`\n(or force a mode with FRESH_COLOR_MODE=truecolor|256|16)",
+                )
+            }
+        }
+    }
+}
+
+fn keyboard_check(keyboard: Option<Result<bool, String>>, in_tmux: bool) -> Check {
+    const NAME: &str = "Kitty keyboard protocol";
+    match keyboard {
+        None => Check::new(
+            NAME,
+            Status::Info,
+            "not checked (disabled in config, or not run from a terminal)",
+        ),
+        Some(Ok(true)) => Check::new(NAME, Status::Ok, "supported"),
+        Some(Ok(false)) => {
+            let fix = if in_tmux {
+                "tmux doesn't pass the protocol through; run Fresh directly in a terminal that supports it\n(kitty, WezTerm, foot, Ghostty, Alacritty) for full key support"
+            } else {
+                "Use a terminal that supports it (kitty, WezTerm, foot, Ghostty, Alacritty) for full key support"
+            };
+            Check::new(
+                NAME,
+                Status::Warning,
+                "not supported; some combinations such as Ctrl+Shift+letter can't be told apart",
+            )
+            .with_fix(fix)
+        }
+        Some(Err(e)) => Check::new(NAME, Status::Warning, format!("query failed: {}", e))
+            .with_fix("The terminal didn't answer; key combinations fall back to legacy encoding"),
+    }
+}
+
+fn bracketed_paste_check(term: &str) -> Check {
+    const NAME: &str = "Bracketed paste";
+    match term {
+        "" | "dumb" => Check::new(NAME, Status::Warning, "unknown terminal (TERM is not set)")
+            .with_fix("Set TERM to match your terminal, e.g. `export TERM=xterm-256color`"),
+        "linux" | "cons25" => Check::new(
+            NAME,
+            Status::Warning,
+            "not supported by the console; pastes arrive as typed keys and may be auto-indented",
+        )
+        .with_fix("Use a terminal emulator, or paste inside Fresh with the Paste command"),
+        _ => Check::new(
+            NAME,
+            Status::Ok,
+            format!("expected to work (TERM={})", term),
+        ),
+    }
+}
+
+fn clipboard_check(
+    env: &dyn Fn(&str) -> Option<String>,
+    in_tmux: bool,
+    in_screen: bool,
+    tmux_set_clipboard: impl FnOnce() -> Option<String>,
+) -> Check {
+    const NAME: &str = "Clipboard (OSC 52)";
+    let over_ssh = env("SSH_CONNECTION").is_some() || env("SSH_TTY").is_some();
+    let fallback = if over_ssh {
+        "Over SSH, OSC 52 is the only way copies reach your local clipboard"
+    } else {
+        "Copies still reach the system clipboard directly on this machine"
+    };
+
+    if in_tmux {
+        return match tmux_set_clipboard().as_deref() {
+            Some("on") | Some("external") => {
+                Check::new(NAME, Status::Ok, "passed through by tmux (set-clipboard on)")
+            }
+            Some(value) => Check::new(
+                NAME,
+                Status::Warning,
+                format!("tmux drops it (set-clipboard {})", value),
+            )
+            .with_fix(format!(
+                "Add `set -g set-clipboard on` to ~/.tmux.conf and run `tmux source ~/.tmux.conf`\n{}",
+                fallback
+            )),
+            None => Check::new(NAME, Status::Info, "inside tmux; couldn't read set-clipboard"),
+        };
+    }
+    if in_screen {
+        return Check::new(NAME, Status::Warning, "GNU Screen drops OSC 52").with_fix(format!(
+            "Run Fresh in tmux or directly in the terminal\n{}",
+            fallback
+        ));
+    }
+    if env("TERM_PROGRAM").as_deref() == Some("Apple_Terminal") {
+        return Check::new(NAME, Status::Warning, "Terminal.app ignores OSC 52").with_fix(format!(
+            "Use iTerm2, kitty or WezTerm to copy over SSH\n{}",
+            fallback
+        ));
+    }
+    if env("VTE_VERSION").is_some() {
+        return Check::new(
+            NAME,
+            Status::Warning,
+            "VTE-based terminals (GNOME Terminal, Tilix) ignore OSC 52",
+        )
+        .with_fix(format!(
+            "Use a terminal that supports it (kitty, WezTerm, foot, Alacritty) to copy over SSH\n{}",
+            fallback
+        ));
+    }
+    Check::new(NAME, Status::Ok, "expected to work")
+}
+
+/// Global value of a tmux option, if tmux answers
+fn tmux_option(name: &str) -> Option<String> {
+    let output = Command::new("tmux")
+        .args(["show-options", "-gv", name])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn directory_checks(dir_context: &DirectoryContext) -> Vec<Check> {
+    let config_path = dir_context.config_path();
+    vec![
+        dir_check("Config directory", &dir_context.config_dir),
+        dir_check("Data directory", &dir_context.data_dir),
+        dir_check("Log directory", super::log_dirs::log_dir()),
+        config_file_check(&config_path),
+    ]
+}
+
+/// Check that `dir` is a writable directory, or can be created
+fn dir_check(name: &str, dir: &Path) -> Check {
+    let shown = dir.display().to_string();
+    if dir.exists() && !dir.is_dir() {
+        return Check::new(name, Status::Error, format!("{} is not a directory", shown)).with_fix(
+            format!("Move {} aside so Fresh can create the directory", shown),
+        );
+    }
+    if !dir.exists() {
+        return match std::fs::create_dir_all(dir) {
+            Ok(()) => Check::new(name, Status::Ok, format!("{} (created)", shown)),
+            Err(e) => Check::new(
+                name,
+                Status::Error,
+                format!("{} can't be created: {}", shown, e),
+            )
+            .with_fix(format!("mkdir -p \"{}\"", shown)),
+        };
+    }
+
+    let probe = dir.join(format!(".fresh-doctor-{}", std::process::id()));
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            Check::new(name, Status::Ok, shown)
+        }
+        Err(e) => Check::new(
+            name,
+            Status::Error,
+            format!("{} is not writable: {}", shown, e),
+        )
+        .with_fix(if cfg!(unix) {
+            format!("chmod u+rwx \"{}\" (and check its owner)", shown)
+        } else {
+            format!("Give your user write access to {}", shown)
+        }),
+    }
+}
+
+fn config_file_check(path: &Path) -> Check {
+    const NAME: &str = "User config";
+    let shown = path.display().to_string();
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Check::new(NAME, Status::Ok, format!("{} (not created yet)", shown));
+        }
+        Err(e) => {
+            return Check::new(
+                NAME,
+                Status::Error,
+                format!("{} can't be read: {}", shown, e),
+            )
+            .with_fix(format!("Check the permissions of {}", shown));
+        }
+    };
+
+    let issues = crate::config_validation::validate_config(path, &content);
+    let Some(first) = issues.first() else {
+        return Check::new(NAME, Status::Ok, shown);
+    };
+    let status = if issues.iter().any(|i| i.is_error()) {
+        Status::Error
+    } else {
+        Status::Warning
+    };
+    let more = if issues.len() > 1 {
+        format!(" (and {} more)", issues.len() - 1)
+    } else {
+        String::new()
+    };
+    Check::new(NAME, status, format!("{}{}", first, more)).with_fix(
+        "Fix the setting, or run `fresh` and use Open Settings;\n`fresh --cmd config show` prints the configuration in effect",
+    )
+}
+
+/// Find an executable on `PATH` (or check it directly when it has a path)
+pub fn find_in_path(command: &str, path_var: Option<&str>) -> Option<PathBuf> {
+    let candidates = |base: PathBuf| {
+        let mut names = vec![base.clone()];
+        if cfg!(windows) && base.extension().is_none() {
+            for ext in ["exe", "cmd", "bat"] {
+                names.push(base.with_extension(ext));
+            }
+        }
+        names
+    };
+
+    let command_path = Path::new(command);
+    if command_path.components().count() > 1 {
+        return candidates(command_path.to_path_buf())
+            .into_iter()
+            .find(|p| is_executable(p));
+    }
+    std::env::split_paths(path_var?)
+        .flat_map(|dir| candidates(dir.join(command)))
+        .find(|p| is_executable(p))
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+fn language_tool_checks(config: &Config, find: &dyn Fn(&str) -> Option<PathBuf>) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let mut servers: Vec<_> = config
+        .lsp
+        .iter()
+        .filter(|(_, server)| server.enabled && !server.command.is_empty())
+        .collect();
+    servers.sort_by(|a, b| a.0.cmp(b.0));
+    for (language, server) in servers {
+        let name = format!("{} language server", language);
+        let check = match find(&server.command) {
+            Some(path) => Check::new(name, Status::Ok, path.display().to_string()),
+            // Servers that start on their own fail visibly, the rest only on request
+            None => Check::new(
+                name,
+                if server.auto_start {
+                    Status::Warning
+                } else {
+                    Status::Info
+                },
+                format!("`{}` not found", server.command),
+            )
+            .with_fix(
+                super::lsp::install_command(&server.command)
+                    .map(|cmd| format!("Install it: {}", cmd))
+                    .unwrap_or_else(|| {
+                        format!(
+                            "Install `{}`, or set lsp.{}.enabled to false",
+                            server.command, language
+                        )
+                    }),
+            ),
+        };
+        checks.push(check);
+    }
+
+    let mut formatters: Vec<_> = config
+        .languages
+        .iter()
+        .filter_map(|(language, lang)| Some((language, lang.formatter.as_ref()?, lang)))
+        .filter(|(_, formatter, _)| !formatter.command.is_empty())
+        .collect();
+    formatters.sort_by(|a, b| a.0.cmp(b.0));
+    for (language, formatter, lang) in formatters {
+        let name = format!("{} formatter", language);
+        let check = match find(&formatter.command) {
+            Some(path) => Check::new(name, Status::Ok, path.display().to_string()),
+            None => Check::new(
+                name,
+                if lang.format_on_save {
+                    Status::Warning
+                } else {
+                    Status::Info
+                },
+                format!("`{}` not found", formatter.command),
+            )
+            .with_fix(format!(
+                "Install `{}`, or remove languages.{}.formatter",
+                formatter.command, language
+            )),
+        };
+        checks.push(check);
+    }
+
+    checks
+}
+
+fn session_checks() -> Vec<Check> {
+    const NAME: &str = "Socket directory";
+    let dir = match SocketPaths::socket_directory() {
+        Ok(dir) => dir,
+        Err(e) => {
+            let fix = match std::env::var("XDG_RUNTIME_DIR") {
+                Ok(runtime) => format!(
+                    "Check that $XDG_RUNTIME_DIR ({}) exists and is yours",
+                    runtime
+                ),
+                Err(_) => format!("Check that {} is writable", std::env::temp_dir().display()),
+            };
+            return vec![
+                Check::new(NAME, Status::Error, format!("unavailable: {}", e)).with_fix(fix),
+            ];
+        }
+    };
+
+    let mut checks = vec![socket_dir_check(&dir)];
+
+    let mut running = 0;
+    let mut stale = 0;
+    for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+        let file_name = entry.file_name();
+        let Some(name) = file_name
+            .to_str()
+            .and_then(|n| n.strip_suffix(".ctrl.sock"))
+        else {
+            continue;
+        };
+        if SocketPaths::for_session_name_in_dir(name, &dir).is_server_alive() {
+            running += 1;
+        } else {
+            stale += 1;
+        }
+    }
+    checks.push(if stale > 0 {
+        Check::new(
+            "Sessions",
+            Status::Warning,
+            format!("{} running, {} stale", running, stale),
+        )
+        .with_fix("`fresh --cmd session list` removes the sockets of sessions that are gone")
+    } else {
+        Check::new("Sessions", Status::Ok, format!("{} running", running))
+    });
+    checks
+}
+
+fn socket_dir_check(dir: &Path) -> Check {
+    const NAME: &str = "Socket directory";
+    let check = dir_check(NAME, dir);
+    if check.status != Status::Ok {
+        return check;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = dir.metadata().map(|m| m.permissions().mode()).unwrap_or(0);
+        if mode & 0o077 != 0 {
+            return Check::new(
+                NAME,
+                Status::Warning,
+                format!(
+                    "{} is accessible to other users ({:o})",
+                    dir.display(),
+                    mode & 0o777
+                ),
+            )
+            .with_fix(format!("chmod 700 \"{}\"", dir.display()));
+        }
+    }
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn test_terminal_checks_suggest_tmux_fixes() {
+        let env = env_of(&[
+            ("TERM", "tmux-256color"),
+            ("TMUX", "/tmp/tmux-1000/default,1,0"),
+        ]);
+        let term = "tmux-256color";
+
+        let color = color_check(&env, term, true, false);
+        assert_eq!(color.status, Status::Warning);
+        assert!(color.fix.unwrap().contains("terminal-overrides"));
+
+        let clipboard = clipboard_check(&env, true, false, || Some("off".to_string()));
+        assert_eq!(clipboard.status, Status::Warning);
+        assert!(clipboard.fix.unwrap().contains("set-clipboard on"));
+
+        let clipboard = clipboard_check(&env, true, false, || Some("external".to_string()));
+        assert_eq!(clipboard.status, Status::Ok);
+    }
+
+    #[test]
+    fn test_terminal_checks_in_a_modern_terminal() {
+        let env = env_of(&[("TERM", "xterm-kitty"), ("COLORTERM", "truecolor")]);
+        let checks = terminal_checks(&env, Some(Ok(true)));
+
+        assert!(
+            checks.iter().all(|c| c.status == Status::Ok),
+            "{:#?}",
+            checks
+        );
+    }
+
+    #[test]
+    fn test_linux_console_warnings() {
+        let env = env_of(&[("TERM", "linux")]);
+        let checks = terminal_checks(&env, Some(Ok(false)));
+        let status = |name: &str| checks.iter().find(|c| c.name == name).unwrap().status;
+
+        assert_eq!(status("Colors"), Status::Warning);
+        assert_eq!(status("Kitty keyboard protocol"), Status::Warning);
+        assert_eq!(status("Bracketed paste"), Status::Warning);
+        assert_eq!(status("Image previews"), Status::Info);
+        assert_eq!(status("Hyperlinks (OSC 8)"), Status::Info);
+    }
+
+    #[test]
+    fn test_dir_check() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(dir_check("Data", dir.path()).status, Status::Ok);
+
+        let missing = dir.path().join("a/b");
+        assert_eq!(dir_check("Data", &missing).status, Status::Ok);
+        assert!(missing.is_dir());
+
+        let file = dir.path().join("file");
+        std::fs::write(&file, "").unwrap();
+        let check = dir_check("Data", &file);
+        assert_eq!(check.status, Status::Error);
+        assert!(check.fix.is_some());
+    }
+
+    #[test]
+    fn test_config_file_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        assert_eq!(config_file_check(&path).status, Status::Ok);
+
+        std::fs::write(&path, r#"{"editor": {"tab_size": "four"}}"#).unwrap();
+        assert_eq!(config_file_check(&path).status, Status::Error);
+
+        std::fs::write(&path, r#"{"editor": {"tab_size": 4}}"#).unwrap();
+        assert_eq!(config_file_check(&path).status, Status::Ok);
+    }
+
+    #[test]
+    fn test_language_tool_checks() {
+        let mut config = Config::default();
+        config.lsp.retain(|language, _| language == "rust");
+        let server = config.lsp.get_mut("rust").unwrap();
+        server.enabled = true;
+        server.auto_start = true;
+        config.languages.clear();
+
+        let checks = language_tool_checks(&config, &|_| None);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, Status::Warning);
+        assert_eq!(
+            checks[0].fix.as_deref(),
+            Some("Install it: rustup component add rust-analyzer")
+        );
+
+        let checks = language_tool_checks(&config, &|cmd| Some(PathBuf::from("/bin").join(cmd)));
+        assert_eq!(checks[0].status, Status::Ok);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_in_path() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join("tool");
+        std::fs::write(&tool, "").unwrap();
+        let path_var = dir.path().to_str().unwrap();
+
+        // Not executable yet
+        assert_eq!(find_in_path("tool", Some(path_var)), None);
+        std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(find_in_path("tool", Some(path_var)), Some(tool.clone()));
+        assert_eq!(find_in_path(tool.to_str().unwrap(), None), Some(tool));
+        assert_eq!(find_in_path("missing", Some(path_var)), None);
+    }
+}
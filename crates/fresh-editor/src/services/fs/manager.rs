@@ -4,18 +4,29 @@ use std::fmt;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{oneshot, Mutex};
 
 /// Type alias for pending directory requests map
 type PendingDirRequests =
     Arc<Mutex<HashMap<PathBuf, Vec<oneshot::Sender<io::Result<Vec<DirEntry>>>>>>>;
 
+/// Type alias for cached directory listings (path -> fetch time and entries)
+type DirCache = Arc<Mutex<HashMap<PathBuf, (Instant, Vec<DirEntry>)>>>;
+
+/// How long a remote directory listing is reused before it is fetched again.
+///
+/// Every listing on a remote host costs an SSH round trip, so collapsing and
+/// re-expanding a directory in the file explorer is served from this cache.
+pub const REMOTE_DIR_CACHE_TTL: Duration = Duration::from_secs(30);
+
 /// Manages filesystem operations with request batching and deduplication
 ///
 /// The FsManager sits between the application and the filesystem backend,
 /// providing optimizations like:
 /// - Request deduplication (multiple requests for the same path)
 /// - Batching of metadata requests
+/// - Caching of directory listings for remote filesystems
 /// - Centralized error handling
 ///
 /// This wraps a `FileSystem` trait object and provides async methods
@@ -25,6 +36,10 @@ pub struct FsManager {
     /// Pending directory listing requests
     /// Map of path -> list of channels waiting for the result
     pending_dir_requests: PendingDirRequests,
+    /// Directory listings reused until they are older than `cache_ttl`
+    dir_cache: DirCache,
+    /// Listing cache lifetime (`None` disables caching, the default for local disks)
+    cache_ttl: Option<Duration>,
 }
 
 impl fmt::Debug for FsManager {
@@ -32,25 +47,54 @@ impl fmt::Debug for FsManager {
         f.debug_struct("FsManager")
             .field("fs", &"<dyn FileSystem>")
             .field("pending_dir_requests", &"<mutex>")
+            .field("cache_ttl", &self.cache_ttl)
             .finish()
     }
 }
 
 impl FsManager {
     /// Create a new filesystem manager with the given filesystem implementation
+    ///
+    /// Directory listings are cached for `REMOTE_DIR_CACHE_TTL` when the
+    /// filesystem is remote; local listings are always read fresh.
     pub fn new(fs: Arc<dyn FileSystem + Send + Sync>) -> Self {
+        let cache_ttl = fs
+            .remote_connection_info()
+            .is_some()
+            .then_some(REMOTE_DIR_CACHE_TTL);
         Self {
             fs,
             pending_dir_requests: Arc::new(Mutex::new(HashMap::new())),
+            dir_cache: Arc::new(Mutex::new(HashMap::new())),
+            cache_ttl,
         }
     }
 
+    /// Override how long directory listings are cached (`None` disables caching)
+    pub fn with_cache_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Drop the cached listing for `path` so the next `list_dir` reads it again
+    pub async fn invalidate_dir(&self, path: &Path) {
+        self.dir_cache.lock().await.remove(path);
+    }
+
     /// List directory contents with request deduplication
     ///
     /// If multiple requests for the same directory are made concurrently,
     /// only one filesystem operation will be performed and all requesters
     /// will receive the same result.
     pub async fn list_dir(&self, path: PathBuf) -> io::Result<Vec<DirEntry>> {
+        if let Some(ttl) = self.cache_ttl {
+            if let Some((fetched, entries)) = self.dir_cache.lock().await.get(&path) {
+                if fetched.elapsed() < ttl {
+                    return Ok(entries.clone());
+                }
+            }
+        }
+
         // Check if there's already a pending request for this path
         let (rx, should_execute) = {
             let mut pending = self.pending_dir_requests.lock().await;
@@ -76,6 +120,13 @@ impl FsManager {
                 .await
                 .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
 
+            if let (Some(_), Ok(entries)) = (self.cache_ttl, &result) {
+                self.dir_cache
+                    .lock()
+                    .await
+                    .insert(path.clone(), (Instant::now(), entries.clone()));
+            }
+
             // Notify all waiting requesters
             let mut pending = self.pending_dir_requests.lock().await;
             if let Some(senders) = pending.remove(&path) {
@@ -187,10 +238,11 @@ impl FsManager {
             if is_symlink {
                 // For symlinks, check what they point to
                 let target_is_dir = fs.is_dir(&path_buf).unwrap_or(false);
-                Ok(
-                    DirEntry::new_symlink(path_buf, name, target_is_dir)
-                        .with_metadata(symlink_meta),
-                )
+                let target = fs.read_link(&path_buf).ok();
+                let mut entry = DirEntry::new_symlink(path_buf, name, target_is_dir)
+                    .with_metadata(symlink_meta);
+                entry.symlink_target = target;
+                Ok(entry)
             } else {
                 // Regular file or directory
                 let entry_type = if fs.is_dir(&path_buf).unwrap_or(false) {
@@ -218,19 +270,27 @@ impl FsManager {
     ///
     /// This is a convenience method that combines `list_dir` with
     /// `get_metadata` to get complete information about all entries.
+    /// Entries whose metadata came with the listing (as remote listings do)
+    /// are not queried again.
     pub async fn list_dir_with_metadata(&self, path: PathBuf) -> io::Result<Vec<DirEntry>> {
         let mut entries = self.list_dir(path).await?;
 
         // Collect paths for metadata batch fetch
-        let paths: Vec<_> = entries.iter().map(|e| e.path.clone()).collect();
+        let missing: Vec<usize> = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.metadata.is_none())
+            .map(|(i, _)| i)
+            .collect();
+        let paths: Vec<_> = missing.iter().map(|&i| entries[i].path.clone()).collect();
 
         // Fetch metadata in parallel
         let metadata_results = self.get_metadata(paths).await;
 
         // Attach metadata to entries
-        for (entry, metadata_result) in entries.iter_mut().zip(metadata_results.into_iter()) {
+        for (index, metadata_result) in missing.into_iter().zip(metadata_results.into_iter()) {
             if let Ok(metadata) = metadata_result {
-                entry.metadata = Some(metadata);
+                entries[index].metadata = Some(metadata);
             }
         }
 
@@ -248,6 +308,8 @@ impl Clone for FsManager {
         Self {
             fs: Arc::clone(&self.fs),
             pending_dir_requests: Arc::clone(&self.pending_dir_requests),
+            dir_cache: Arc::clone(&self.dir_cache),
+            cache_ttl: self.cache_ttl,
         }
     }
 }
@@ -449,6 +511,42 @@ mod tests {
         assert_eq!(file1.metadata.as_ref().unwrap().size, 8);
     }
 
+    #[tokio::test]
+    async fn test_list_dir_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().to_path_buf();
+        std_fs::write(temp_path.join("file1.txt"), "content1").unwrap();
+
+        let fs = Arc::new(StdFileSystem);
+        let manager = FsManager::new(fs).with_cache_ttl(Some(Duration::from_secs(60)));
+
+        assert_eq!(manager.list_dir(temp_path.clone()).await.unwrap().len(), 1);
+
+        // A new file is not seen while the cached listing is fresh
+        std_fs::write(temp_path.join("file2.txt"), "content2").unwrap();
+        assert_eq!(manager.list_dir(temp_path.clone()).await.unwrap().len(), 1);
+
+        // ...until the directory is invalidated
+        manager.invalidate_dir(&temp_path).await;
+        assert_eq!(manager.list_dir(temp_path.clone()).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_local_listings_are_not_cached() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().to_path_buf();
+
+        let manager = FsManager::new(Arc::new(StdFileSystem));
+        assert!(manager
+            .list_dir(temp_path.clone())
+            .await
+            .unwrap()
+            .is_empty());
+
+        std_fs::write(temp_path.join("file1.txt"), "content1").unwrap();
+        assert_eq!(manager.list_dir(temp_path).await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_concurrent_different_dirs() {
         let temp_dir = TempDir::new().unwrap();
@@ -6,7 +6,7 @@
 //! drives, slow disks, etc.).
 
 use crate::model::filesystem::{
-    DirEntry, FileMetadata, FilePermissions, FileReader, FileSystem, FileWriter,
+    DirEntry, FileMetadata, FilePermissions, FileReader, FileSystem, FileWriter, TrashedPath,
 };
 use std::io;
 use std::path::{Path, PathBuf};
@@ -234,6 +234,18 @@ impl FileSystem for SlowFileSystem {
         self.inner.remove_dir(path)
     }
 
+    fn move_to_trash(&self, path: &Path) -> io::Result<TrashedPath> {
+        self.add_delay(self.config.other_delay);
+        self.metrics.other_calls.fetch_add(1, Ordering::SeqCst);
+        self.inner.move_to_trash(path)
+    }
+
+    fn restore_from_trash(&self, trashed: &TrashedPath) -> io::Result<()> {
+        self.add_delay(self.config.other_delay);
+        self.metrics.other_calls.fetch_add(1, Ordering::SeqCst);
+        self.inner.restore_from_trash(trashed)
+    }
+
     fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
         self.add_delay(self.config.metadata_delay);
         self.metrics.metadata_calls.fetch_add(1, Ordering::SeqCst);
@@ -246,6 +258,12 @@ impl FileSystem for SlowFileSystem {
         self.inner.symlink_metadata(path)
     }
 
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        self.add_delay(self.config.metadata_delay);
+        self.metrics.metadata_calls.fetch_add(1, Ordering::SeqCst);
+        self.inner.read_link(path)
+    }
+
     fn is_dir(&self, path: &Path) -> io::Result<bool> {
         self.add_delay(self.config.other_delay);
         self.metrics.other_calls.fetch_add(1, Ordering::SeqCst);
@@ -294,6 +312,7 @@ impl FileSystem for SlowFileSystem {
 
     fn sudo_write(
         &self,
+        program: &str,
         path: &Path,
         data: &[u8],
         mode: u32,
@@ -302,7 +321,7 @@ impl FileSystem for SlowFileSystem {
     ) -> io::Result<()> {
         self.add_delay(self.config.write_file_delay);
         self.metrics.write_file_calls.fetch_add(1, Ordering::SeqCst);
-        self.inner.sudo_write(path, data, mode, uid, gid)
+        self.inner.sudo_write(program, path, data, mode, uid, gid)
     }
 }
 
@@ -0,0 +1,105 @@
+//! Lightweight git repository queries
+//!
+//! Reads repository metadata directly from the `.git` directory through the
+//! editor's [`FileSystem`], so it works the same for local and remote
+//! projects and doesn't require a `git` binary.
+
+use std::path::{Path, PathBuf};
+
+use crate::model::filesystem::FileSystem;
+
+/// Name of the checked-out branch for the repository containing `dir`.
+///
+/// Walks up from `dir` to find the repository. Returns the short commit hash
+/// for a detached HEAD, or None when `dir` is not inside a git repository.
+pub fn current_branch(fs: &dyn FileSystem, dir: &Path) -> Option<String> {
+    let git_dir = find_git_dir(fs, dir)?;
+    let head = fs.read_file(&git_dir.join("HEAD")).ok()?;
+    parse_head(&String::from_utf8_lossy(&head))
+}
+
+/// Locate the git directory for `dir`, following `gitdir:` files used by
+/// worktrees and submodules
+fn find_git_dir(fs: &dyn FileSystem, dir: &Path) -> Option<PathBuf> {
+    for ancestor in dir.ancestors() {
+        let candidate = ancestor.join(".git");
+        if fs.is_dir(&candidate).unwrap_or(false) {
+            return Some(candidate);
+        }
+        if fs.is_file(&candidate).unwrap_or(false) {
+            let content = fs.read_file(&candidate).ok()?;
+            let content = String::from_utf8_lossy(&content);
+            let target = content.trim().strip_prefix("gitdir:")?.trim();
+            return Some(ancestor.join(target));
+        }
+    }
+    None
+}
+
+/// Extract the branch name (or short hash when detached) from a HEAD file
+fn parse_head(head: &str) -> Option<String> {
+    let head = head.trim();
+    if let Some(reference) = head.strip_prefix("ref:") {
+        let reference = reference.trim();
+        return Some(
+            reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_string(),
+        );
+    }
+    if head.len() >= 7 && head.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some(head[..7].to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::filesystem::StdFileSystem;
+
+    #[test]
+    fn test_parse_head() {
+        assert_eq!(
+            parse_head("ref: refs/heads/feature/status-line\n").as_deref(),
+            Some("feature/status-line")
+        );
+        assert_eq!(
+            parse_head("3f2a9c1d0e8b7a6f5e4d3c2b1a0f9e8d7c6b5a49\n").as_deref(),
+            Some("3f2a9c1")
+        );
+        assert_eq!(parse_head("garbage"), None);
+    }
+
+    #[test]
+    fn test_current_branch_from_subdirectory_and_worktree() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(repo.join("src/deep")).unwrap();
+        std::fs::write(repo.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        assert_eq!(
+            current_branch(&StdFileSystem, &repo.join("src/deep")).as_deref(),
+            Some("main")
+        );
+
+        // A worktree points at its git dir through a `.git` file
+        let worktree = temp.path().join("wt");
+        let wt_git = repo.join(".git/worktrees/wt");
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::create_dir_all(&wt_git).unwrap();
+        std::fs::write(wt_git.join("HEAD"), "ref: refs/heads/topic\n").unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", wt_git.display()),
+        )
+        .unwrap();
+        assert_eq!(
+            current_branch(&StdFileSystem, &worktree).as_deref(),
+            Some("topic")
+        );
+
+        assert_eq!(current_branch(&StdFileSystem, temp.path()), None);
+    }
+}
@@ -0,0 +1,248 @@
+//! URLs and file paths in text
+//!
+//! [`LinkDetector`] finds the links in a line of a buffer or of terminal
+//! output: URLs (`https://`, `http://`, `ftp://`, `file://`, `mailto:`) and
+//! file paths such as `src/main.rs`, `./build.sh`, `~/notes.md` or
+//! `src/lib.rs:42:7`, optionally followed by a line and column.
+//!
+//! A path is only a candidate: whether the file exists is up to the caller.
+//! To keep prose like "e.g." or "12:30" from matching, a path must contain
+//! a `/` or end in an extension, and a URL never ends in punctuation or in a
+//! closing bracket it didn't open.
+
+use regex::Regex;
+use std::ops::Range;
+
+/// What a link points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkTarget {
+    Url(String),
+    File {
+        /// Path as written
+        path: String,
+        /// 1-indexed line
+        line: Option<usize>,
+        /// 1-indexed column
+        column: Option<usize>,
+    },
+}
+
+/// A link in a line of text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// Byte range of the link in the line, including any `:line:col`
+    pub range: Range<usize>,
+    pub target: LinkTarget,
+}
+
+/// Finder for the links described in the module documentation
+pub struct LinkDetector {
+    url: Regex,
+    /// A path after the start of the line or a delimiter, with an optional
+    /// `:line` or `:line:col`
+    path: Regex,
+}
+
+impl Default for LinkDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkDetector {
+    pub fn new() -> Self {
+        Self {
+            url: Regex::new(r#"(?:(?:https?|ftp|file)://|mailto:)[^\s<>"'`]+"#)
+                .expect("valid regex"),
+            path: Regex::new(
+                r#"(?:^|[\s"'`(\[<=,])((?:~|\.{1,2})?/?[\w.@+\-]+(?:/[\w.@+\-]+)*)(?::(\d+)(?::(\d+))?)?"#,
+            )
+            .expect("valid regex"),
+        }
+    }
+
+    /// URLs in `line`, in order
+    pub fn urls(&self, line: &str) -> Vec<Link> {
+        self.url
+            .find_iter(line)
+            .filter_map(|m| {
+                let end = m.start() + trim_url(m.as_str()).len();
+                let url = &line[m.start()..end];
+                let (_, rest) = url.split_once(':')?;
+                (!rest.trim_start_matches('/').is_empty()).then(|| Link {
+                    range: m.start()..end,
+                    target: LinkTarget::Url(url.to_string()),
+                })
+            })
+            .collect()
+    }
+
+    /// URLs and file paths in `line`, in order
+    pub fn links(&self, line: &str) -> Vec<Link> {
+        let mut links = self.urls(line);
+        let url_ranges: Vec<Range<usize>> = links.iter().map(|l| l.range.clone()).collect();
+
+        for caps in self.path.captures_iter(line) {
+            let Some(path_match) = caps.get(1) else {
+                continue;
+            };
+            let start = path_match.start();
+            if url_ranges.iter().any(|r| r.start <= start && start < r.end) {
+                continue;
+            }
+            let line_number = caps.get(2).and_then(|m| m.as_str().parse().ok());
+            let column = caps.get(3).and_then(|m| m.as_str().parse().ok());
+            // A sentence may end right after the path
+            let path = if line_number.is_none() {
+                path_match.as_str().trim_end_matches('.')
+            } else {
+                path_match.as_str()
+            };
+            if !looks_like_path(path) {
+                continue;
+            }
+            let end = match caps.get(if column.is_some() { 3 } else { 2 }) {
+                Some(m) if line_number.is_some() => m.end(),
+                _ => start + path.len(),
+            };
+            links.push(Link {
+                range: start..end,
+                target: LinkTarget::File {
+                    path: path.to_string(),
+                    line: line_number,
+                    column,
+                },
+            });
+        }
+
+        links.sort_by_key(|link| link.range.start);
+        links
+    }
+
+    /// The link that contains byte `offset` of `line`, or ends right at it
+    pub fn link_at(&self, line: &str, offset: usize) -> Option<Link> {
+        self.links(line)
+            .into_iter()
+            .find(|link| link.range.start <= offset && offset <= link.range.end)
+    }
+}
+
+/// Drop trailing punctuation, and closing brackets without an opening one
+/// in the URL, so `(see https://example.com/a_(b)).` keeps `a_(b)`
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let Some(last) = url.chars().last() else {
+            return url;
+        };
+        let unbalanced = |open: char, close: char| {
+            last == close && url.matches(close).count() > url.matches(open).count()
+        };
+        if matches!(last, '.' | ',' | ';' | ':' | '!' | '?')
+            || unbalanced('(', ')')
+            || unbalanced('[', ']')
+            || unbalanced('{', '}')
+        {
+            url = &url[..url.len() - last.len_utf8()];
+        } else {
+            return url;
+        }
+    }
+}
+
+/// A path contains a directory separator or ends in an extension. A name
+/// and extension of one letter each, as in "e.g", don't count.
+fn looks_like_path(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let has_extension = name.rsplit_once('.').is_some_and(|(stem, ext)| {
+        !stem.is_empty() && stem.len() + ext.len() >= 3 && ext.chars().any(|c| c.is_alphabetic())
+    });
+    let has_separator = path.contains('/') && path.chars().any(|c| c.is_alphanumeric());
+    has_separator || has_extension
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> LinkTarget {
+        LinkTarget::Url(s.to_string())
+    }
+
+    fn file(path: &str, line: Option<usize>, column: Option<usize>) -> LinkTarget {
+        LinkTarget::File {
+            path: path.to_string(),
+            line,
+            column,
+        }
+    }
+
+    fn targets(line: &str) -> Vec<LinkTarget> {
+        LinkDetector::new()
+            .links(line)
+            .into_iter()
+            .map(|link| link.target)
+            .collect()
+    }
+
+    #[test]
+    fn test_urls_drop_trailing_punctuation() {
+        assert_eq!(
+            targets("See https://example.com/docs."),
+            vec![url("https://example.com/docs")]
+        );
+        assert_eq!(
+            targets("(see https://en.wikipedia.org/wiki/Rust_(language))"),
+            vec![url("https://en.wikipedia.org/wiki/Rust_(language)")]
+        );
+        assert_eq!(
+            targets("<mailto:dev@example.com>, file:///tmp/x"),
+            vec![url("mailto:dev@example.com"), url("file:///tmp/x")]
+        );
+        assert_eq!(targets("https:// is a scheme"), vec![]);
+    }
+
+    #[test]
+    fn test_paths_with_locations() {
+        assert_eq!(
+            targets("error: src/main.rs:12:5: expected `;`"),
+            vec![file("src/main.rs", Some(12), Some(5))]
+        );
+        assert_eq!(
+            targets("  --> ./build.sh:3"),
+            vec![file("./build.sh", Some(3), None)]
+        );
+        assert_eq!(
+            targets("edit ~/notes.md or /etc/hosts."),
+            vec![
+                file("~/notes.md", None, None),
+                file("/etc/hosts", None, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prose_is_not_a_path() {
+        assert_eq!(targets("at 12:30 we met, i.e. later"), vec![]);
+        assert_eq!(targets("version 1.2 is out"), vec![]);
+    }
+
+    #[test]
+    fn test_link_ranges() {
+        let detector = LinkDetector::new();
+        let line = "open README.md:4 or https://x.org";
+        let links = detector.links(line);
+        assert_eq!(&line[links[0].range.clone()], "README.md:4");
+        assert_eq!(&line[links[1].range.clone()], "https://x.org");
+
+        assert_eq!(
+            detector.link_at(line, 14).map(|l| l.target),
+            Some(file("README.md", Some(4), None))
+        );
+        assert_eq!(detector.link_at(line, 17), None);
+        assert_eq!(
+            detector.link_at(line, line.len()).map(|l| l.target),
+            Some(url("https://x.org"))
+        );
+    }
+}
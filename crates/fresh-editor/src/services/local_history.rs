@@ -0,0 +1,277 @@
+//! Local history of saved file versions (**Local History**)
+//!
+//! Every save of a local file keeps a copy of what was written under
+//! `<data dir>/history/<encoded path>/<millis>`, named after the time of the
+//! save in milliseconds since the epoch. A save that writes the same content
+//! as the newest copy adds nothing.
+//!
+//! Copies older than `editor.local_history_days` are deleted when the file
+//! is saved again, and when all copies together take more than
+//! `editor.local_history_max_mb` the oldest ones, across all files, go
+//! first. This is independent of git and of recovery files, which hold
+//! unsaved changes instead.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long and how much history is kept
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryLimits {
+    pub max_age: Duration,
+    pub max_bytes: u64,
+}
+
+/// One saved version of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    /// Milliseconds since the epoch when the version was saved
+    pub id: u64,
+    pub size: u64,
+}
+
+impl Version {
+    pub fn saved_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.id)
+    }
+}
+
+/// The local history kept in the data directory
+pub struct LocalHistory {
+    root: PathBuf,
+    /// Bytes taken by all versions; measured on the first save
+    used: Option<u64>,
+}
+
+impl LocalHistory {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            root: data_dir.join("history"),
+            used: None,
+        }
+    }
+
+    fn file_dir(&self, file: &Path) -> PathBuf {
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        self.root
+            .join(crate::workspace::encode_path_for_filename(&file))
+    }
+
+    /// Versions of `file`, newest first
+    pub fn versions(&self, file: &Path) -> Vec<Version> {
+        let mut versions = list_versions(&self.file_dir(file));
+        versions.sort_by(|a, b| b.id.cmp(&a.id));
+        versions
+    }
+
+    /// Content of the version `id` of `file`
+    pub fn read(&self, file: &Path, id: u64) -> io::Result<Vec<u8>> {
+        fs::read(self.file_dir(file).join(id.to_string()))
+    }
+
+    /// Keep `content` as the newest version of `file`
+    ///
+    /// Returns false if it matches the newest version already kept.
+    pub fn record(
+        &mut self,
+        file: &Path,
+        content: &[u8],
+        limits: HistoryLimits,
+    ) -> io::Result<bool> {
+        self.record_at(file, content, limits, SystemTime::now())
+    }
+
+    fn record_at(
+        &mut self,
+        file: &Path,
+        content: &[u8],
+        limits: HistoryLimits,
+        now: SystemTime,
+    ) -> io::Result<bool> {
+        let dir = self.file_dir(file);
+        let versions = self.versions(file);
+        if let Some(newest) = versions.first() {
+            if newest.size == content.len() as u64
+                && fs::read(dir.join(newest.id.to_string()))? == content
+            {
+                return Ok(false);
+            }
+        }
+
+        let now_ms = millis(now);
+        // Two saves within a millisecond still get their own version
+        let id = versions
+            .first()
+            .map_or(now_ms, |newest| now_ms.max(newest.id + 1));
+        fs::create_dir_all(&dir)?;
+        let temp = dir.join(format!("{}.tmp", id));
+        fs::write(&temp, content)?;
+        fs::rename(&temp, dir.join(id.to_string()))?;
+
+        let used = match self.used {
+            Some(used) => used + content.len() as u64,
+            None => self.measure(),
+        };
+        self.used = Some(used);
+
+        let cutoff = now_ms.saturating_sub(limits.max_age.as_millis() as u64);
+        for version in versions.iter().filter(|v| v.id < cutoff) {
+            self.remove(&dir, version);
+        }
+        if self.used.unwrap_or(0) > limits.max_bytes {
+            self.shrink_to(limits.max_bytes, &dir, id);
+        }
+        Ok(true)
+    }
+
+    /// Bytes taken by every version of every file
+    fn measure(&self) -> u64 {
+        history_dirs(&self.root)
+            .iter()
+            .flat_map(|dir| list_versions(dir))
+            .map(|version| version.size)
+            .sum()
+    }
+
+    /// Delete the oldest versions until at most `max_bytes` are left, but
+    /// never the version `keep` of the file in `keep_dir` that was just saved
+    fn shrink_to(&mut self, max_bytes: u64, keep_dir: &Path, keep: u64) {
+        let mut all: Vec<(PathBuf, Version)> = history_dirs(&self.root)
+            .into_iter()
+            .flat_map(|dir| {
+                list_versions(&dir)
+                    .into_iter()
+                    .map(move |version| (dir.clone(), version))
+            })
+            .filter(|(dir, version)| !(dir == keep_dir && version.id == keep))
+            .collect();
+        all.sort_by_key(|(_, version)| version.id);
+
+        for (dir, version) in all {
+            if self.used.unwrap_or(0) <= max_bytes {
+                break;
+            }
+            self.remove(&dir, &version);
+        }
+    }
+
+    fn remove(&mut self, dir: &Path, version: &Version) {
+        if fs::remove_file(dir.join(version.id.to_string())).is_ok() {
+            self.used = self.used.map(|used| used.saturating_sub(version.size));
+        }
+        // Drop the directory once its last version is gone
+        let _ = fs::remove_dir(dir);
+    }
+}
+
+fn millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn history_dirs(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+fn list_versions(dir: &Path) -> Vec<Version> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_str()?.parse().ok()?;
+            let size = entry.metadata().ok()?.len();
+            Some(Version { id, size })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn limits(max_bytes: u64) -> HistoryLimits {
+        HistoryLimits {
+            max_age: 7 * DAY,
+            max_bytes,
+        }
+    }
+
+    #[test]
+    fn test_record_skips_unchanged_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        let mut history = LocalHistory::new(&dir.path().join("data"));
+        let now = SystemTime::now();
+
+        assert!(history.record_at(&file, b"one", limits(1024), now).unwrap());
+        assert!(!history.record_at(&file, b"one", limits(1024), now).unwrap());
+        assert!(history.record_at(&file, b"two", limits(1024), now).unwrap());
+
+        let versions = history.versions(&file);
+        assert_eq!(versions.len(), 2);
+        assert_eq!(history.read(&file, versions[0].id).unwrap(), b"two");
+        assert_eq!(history.read(&file, versions[1].id).unwrap(), b"one");
+    }
+
+    #[test]
+    fn test_old_versions_expire() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        let mut history = LocalHistory::new(&dir.path().join("data"));
+        let now = SystemTime::now();
+
+        history
+            .record_at(&file, b"old", limits(1024), now - 8 * DAY)
+            .unwrap();
+        history
+            .record_at(&file, b"recent", limits(1024), now - DAY)
+            .unwrap();
+        history.record_at(&file, b"new", limits(1024), now).unwrap();
+
+        let kept: Vec<Vec<u8>> = history
+            .versions(&file)
+            .iter()
+            .map(|v| history.read(&file, v.id).unwrap())
+            .collect();
+        assert_eq!(kept, [b"new".to_vec(), b"recent".to_vec()]);
+    }
+
+    #[test]
+    fn test_oldest_versions_of_any_file_go_first_when_over_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.txt");
+        let b = dir.path().join("b.txt");
+        let mut history = LocalHistory::new(&dir.path().join("data"));
+        let now = SystemTime::now();
+
+        history
+            .record_at(&a, &[b'a'; 40], limits(100), now - Duration::from_secs(2))
+            .unwrap();
+        history
+            .record_at(&b, &[b'b'; 40], limits(100), now - Duration::from_secs(1))
+            .unwrap();
+        history
+            .record_at(&a, &[b'c'; 40], limits(100), now)
+            .unwrap();
+
+        assert_eq!(history.versions(&a).len(), 1);
+        assert_eq!(history.versions(&b).len(), 1);
+        assert_eq!(
+            history.read(&a, history.versions(&a)[0].id).unwrap(),
+            [b'c'; 40]
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! Locale-aware number and date formatting
+//!
+//! Date patterns, month names and number separators come from the `format.*`
+//! keys of the core locale files, so they are translated with the same
+//! machinery as the rest of the UI. Patterns use strftime syntax; `%B` is
+//! replaced by the translated month name before chrono sees the pattern.
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, SecondsFormat, TimeZone};
+use rust_i18n::t;
+use std::fmt::Display;
+
+/// The formats offered by "Insert Date/Time" and `editor.formatDate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateStyle {
+    /// `2026-10-16`
+    IsoDate,
+    /// RFC 3339, e.g. `2026-10-16T14:05:09+02:00`
+    IsoDateTime,
+    /// The locale's short date
+    Date,
+    /// The locale's time of day
+    Time,
+    /// The locale's short date and time
+    DateTime,
+    /// The locale's date with the month spelled out
+    LongDate,
+    /// Seconds since the Unix epoch
+    Unix,
+}
+
+impl DateStyle {
+    pub const ALL: [DateStyle; 7] = [
+        DateStyle::Date,
+        DateStyle::Time,
+        DateStyle::DateTime,
+        DateStyle::LongDate,
+        DateStyle::IsoDate,
+        DateStyle::IsoDateTime,
+        DateStyle::Unix,
+    ];
+
+    /// Stable name used by plugins and as the prompt suggestion value
+    pub fn id(self) -> &'static str {
+        match self {
+            DateStyle::IsoDate => "iso_date",
+            DateStyle::IsoDateTime => "iso_date_time",
+            DateStyle::Date => "date",
+            DateStyle::Time => "time",
+            DateStyle::DateTime => "date_time",
+            DateStyle::LongDate => "long_date",
+            DateStyle::Unix => "unix",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|style| style.id() == id)
+    }
+
+    /// Translated name shown next to the preview in the prompt
+    pub fn label(self) -> String {
+        match self {
+            DateStyle::IsoDate => t!("date_time.iso_date"),
+            DateStyle::IsoDateTime => t!("date_time.iso_date_time"),
+            DateStyle::Date => t!("date_time.date"),
+            DateStyle::Time => t!("date_time.time"),
+            DateStyle::DateTime => t!("date_time.date_time"),
+            DateStyle::LongDate => t!("date_time.long_date"),
+            DateStyle::Unix => t!("date_time.unix"),
+        }
+        .to_string()
+    }
+
+    /// The strftime pattern for this style in `locale`, if it has one
+    fn pattern(self, locale: &str) -> Option<String> {
+        let pattern = match self {
+            DateStyle::IsoDate => return Some("%Y-%m-%d".to_string()),
+            DateStyle::IsoDateTime | DateStyle::Unix => return None,
+            DateStyle::Date => t!("format.date", locale = locale),
+            DateStyle::Time => t!("format.time", locale = locale),
+            DateStyle::DateTime => t!("format.date_time", locale = locale),
+            DateStyle::LongDate => t!("format.date_long", locale = locale),
+        };
+        Some(pattern.to_string())
+    }
+}
+
+/// Format `when` in one of the predefined styles
+pub fn format_date<Tz: TimeZone>(when: &DateTime<Tz>, style: DateStyle, locale: &str) -> String
+where
+    Tz::Offset: Display,
+{
+    match style {
+        DateStyle::IsoDateTime => when.to_rfc3339_opts(SecondsFormat::Secs, false),
+        DateStyle::Unix => when.timestamp().to_string(),
+        _ => style
+            .pattern(locale)
+            .and_then(|pattern| format_pattern(when, &pattern, locale))
+            .unwrap_or_else(|| when.format("%Y-%m-%d").to_string()),
+    }
+}
+
+/// Format `when` with a strftime pattern, spelling `%B` in `locale`.
+///
+/// Returns `None` if the pattern is not valid strftime.
+pub fn format_pattern<Tz: TimeZone>(
+    when: &DateTime<Tz>,
+    pattern: &str,
+    locale: &str,
+) -> Option<String>
+where
+    Tz::Offset: Display,
+{
+    let month_key = format!("format.month_{}", when.month());
+    let month = t!(month_key.as_str(), locale = locale).replace('%', "%%");
+
+    let mut resolved = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            resolved.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('B') => resolved.push_str(&month),
+            Some(next) => {
+                resolved.push('%');
+                resolved.push(next);
+            }
+            None => resolved.push('%'),
+        }
+    }
+
+    let items = StrftimeItems::new(&resolved);
+    if items.clone().any(|item| matches!(item, Item::Error)) {
+        return None;
+    }
+    Some(when.format_with_items(items).to_string())
+}
+
+/// Format a number with the decimal and grouping separators of `locale`.
+///
+/// With `decimals`, the value is rounded to that many fractional digits;
+/// otherwise it is written with as many as it needs.
+pub fn format_number(value: f64, decimals: Option<usize>, locale: &str) -> String {
+    format_number_with(
+        value,
+        decimals,
+        &t!("format.decimal_separator", locale = locale),
+        &t!("format.group_separator", locale = locale),
+    )
+}
+
+fn format_number_with(
+    value: f64,
+    decimals: Option<usize>,
+    decimal_separator: &str,
+    group_separator: &str,
+) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let plain = match decimals {
+        Some(decimals) => format!("{:.*}", decimals, value),
+        None => value.to_string(),
+    };
+    let (sign, unsigned) = match plain.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", plain.as_str()),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+
+    let mut result = String::from(sign);
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            result.push_str(group_separator);
+        }
+        result.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        result.push_str(decimal_separator);
+        result.push_str(fraction);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn sample() -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2026, 3, 7, 14, 5, 9)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_iso_styles_ignore_locale() {
+        let when = sample();
+        assert_eq!(format_date(&when, DateStyle::IsoDate, "de"), "2026-03-07");
+        assert_eq!(
+            format_date(&when, DateStyle::IsoDateTime, "ja"),
+            "2026-03-07T14:05:09+02:00"
+        );
+        assert_eq!(format_date(&when, DateStyle::Unix, "en"), "1772885109");
+    }
+
+    #[test]
+    fn test_locale_date_patterns() {
+        let when = sample();
+        assert_eq!(format_date(&when, DateStyle::Date, "en"), "03/07/2026");
+        assert_eq!(format_date(&when, DateStyle::Date, "de"), "07.03.2026");
+        assert_eq!(format_date(&when, DateStyle::Time, "en"), "2:05 PM");
+        assert_eq!(format_date(&when, DateStyle::Time, "fr"), "14:05");
+    }
+
+    #[test]
+    fn test_long_date_uses_translated_month() {
+        let when = sample();
+        assert_eq!(
+            format_date(&when, DateStyle::LongDate, "en"),
+            "March 7, 2026"
+        );
+        assert_eq!(
+            format_date(&when, DateStyle::LongDate, "de"),
+            "7. März 2026"
+        );
+        assert_eq!(
+            format_date(&when, DateStyle::LongDate, "ja"),
+            "2026年3月7日"
+        );
+    }
+
+    #[test]
+    fn test_custom_pattern() {
+        let when = sample();
+        assert_eq!(
+            format_pattern(&when, "%B %%B %H", "es").as_deref(),
+            Some("marzo %B 14")
+        );
+        assert_eq!(format_pattern(&when, "%Q", "en"), None);
+    }
+
+    #[test]
+    fn test_style_ids_round_trip() {
+        for style in DateStyle::ALL {
+            assert_eq!(DateStyle::from_id(style.id()), Some(style));
+        }
+        assert_eq!(DateStyle::from_id("%Y"), None);
+    }
+
+    #[test]
+    fn test_number_grouping() {
+        assert_eq!(format_number_with(1234567.0, None, ".", ","), "1,234,567");
+        assert_eq!(format_number_with(-1234.5, None, ",", "."), "-1.234,5");
+        assert_eq!(format_number_with(999.0, Some(2), ".", ","), "999.00");
+        assert_eq!(format_number_with(0.14, Some(1), ".", ","), "0.1");
+        assert_eq!(format_number_with(f64::NAN, None, ".", ","), "NaN");
+    }
+
+    #[test]
+    fn test_number_uses_locale_separators() {
+        assert_eq!(format_number(1234.5, Some(2), "en"), "1,234.50");
+        assert_eq!(format_number(1234.5, Some(2), "de"), "1.234,50");
+    }
+}
@@ -35,7 +35,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{mpsc as std_mpsc, Arc, Mutex};
 use std::time::Instant;
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
@@ -200,8 +200,9 @@ impl LspClientState {
 /// Create common LSP client capabilities with workDoneProgress support
 fn create_client_capabilities() -> ClientCapabilities {
     use lsp_types::{
-        GeneralClientCapabilities, RenameClientCapabilities, TextDocumentClientCapabilities,
-        WorkspaceClientCapabilities, WorkspaceEditClientCapabilities,
+        CodeLensClientCapabilities, DocumentHighlightClientCapabilities, GeneralClientCapabilities,
+        RenameClientCapabilities, TextDocumentClientCapabilities, WorkspaceClientCapabilities,
+        WorkspaceEditClientCapabilities,
     };
 
     ClientCapabilities {
@@ -224,6 +225,12 @@ fn create_client_capabilities() -> ClientCapabilities {
                 honors_change_annotations: Some(true),
                 ..Default::default()
             }),
+            document_highlight: Some(DocumentHighlightClientCapabilities {
+                dynamic_registration: Some(false),
+            }),
+            code_lens: Some(CodeLensClientCapabilities {
+                dynamic_registration: Some(false),
+            }),
             semantic_tokens: Some(SemanticTokensClientCapabilities {
                 dynamic_registration: Some(true),
                 requests: SemanticTokensClientCapabilitiesRequests {
@@ -393,6 +400,14 @@ enum LspCommand {
         character: u32,
     },
 
+    /// Request document highlights for the symbol at position
+    DocumentHighlight {
+        request_id: u64,
+        uri: Uri,
+        line: u32,
+        character: u32,
+    },
+
     /// Request signature help
     SignatureHelp {
         request_id: u64,
@@ -431,6 +446,9 @@ enum LspCommand {
         end_char: u32,
     },
 
+    /// Request code lenses for the entire document (resolved before delivery)
+    CodeLens { request_id: u64, uri: Uri },
+
     /// Request semantic tokens for the entire document
     SemanticTokensFull { request_id: u64, uri: Uri },
 
@@ -537,6 +555,10 @@ impl LspState {
                     tracing::info!("Replaying DidSave for {}", uri.as_str());
                     let _ = self.handle_did_save(uri, text).await;
                 }
+                LspCommand::CodeLens { request_id, uri } => {
+                    tracing::info!("Replaying code lens request for {}", uri.as_str());
+                    let _ = self.handle_code_lens(request_id, uri, pending).await;
+                }
                 LspCommand::SemanticTokensFull { request_id, uri } => {
                     tracing::info!("Replaying semantic tokens request for {}", uri.as_str());
                     let _ = self
@@ -1286,6 +1308,173 @@ impl LspState {
         }
     }
 
+    /// Handle document highlight request
+    #[allow(clippy::type_complexity)]
+    async fn handle_document_highlight(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        line: u32,
+        character: u32,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            DocumentHighlightParams, PartialResultParams, Position, TextDocumentPositionParams,
+            WorkDoneProgressParams,
+        };
+
+        let supported = self
+            .capabilities
+            .as_ref()
+            .and_then(|caps| caps.document_highlight_provider.as_ref())
+            .is_some_and(|provider| match provider {
+                lsp_types::OneOf::Left(enabled) => *enabled,
+                lsp_types::OneOf::Right(_) => true,
+            });
+        if !supported {
+            // Server doesn't provide highlights - editor keeps its word-based fallback
+            let _ = self.async_tx.send(AsyncMessage::LspDocumentHighlight {
+                request_id,
+                uri: uri.as_str().to_string(),
+                highlights: Vec::new(),
+            });
+            return Ok(());
+        }
+
+        tracing::trace!(
+            "LSP: document highlight request at {}:{}:{}",
+            uri.as_str(),
+            line,
+            character
+        );
+
+        let params = DocumentHighlightParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position: Position { line, character },
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
+        let result = self
+            .send_request_sequential_tracked::<_, Option<Vec<lsp_types::DocumentHighlight>>>(
+                "textDocument/documentHighlight",
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await;
+
+        let (highlights, outcome) = match result {
+            Ok(highlights) => (highlights.unwrap_or_default(), Ok(())),
+            Err(e) => {
+                tracing::debug!("Document highlight request failed: {}", e);
+                (Vec::new(), Err(e))
+            }
+        };
+
+        let _ = self.async_tx.send(AsyncMessage::LspDocumentHighlight {
+            request_id,
+            uri: uri.as_str().to_string(),
+            highlights,
+        });
+        outcome
+    }
+
+    /// Handle code lens request
+    ///
+    /// Lenses that arrive without a command (e.g. rust-analyzer's reference
+    /// counts) are resolved with `codeLens/resolve` before being sent on, so
+    /// the editor only ever sees displayable titles.
+    #[allow(clippy::type_complexity)]
+    async fn handle_code_lens(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{CodeLensParams, PartialResultParams, WorkDoneProgressParams};
+
+        let Some(resolve_provider) = self
+            .capabilities
+            .as_ref()
+            .and_then(|caps| caps.code_lens_provider.as_ref())
+            .map(|options| options.resolve_provider.unwrap_or(false))
+        else {
+            let _ = self.async_tx.send(AsyncMessage::LspCodeLens {
+                request_id,
+                uri: uri.as_str().to_string(),
+                lenses: Vec::new(),
+            });
+            return Ok(());
+        };
+
+        tracing::trace!("LSP: code lens request for {}", uri.as_str());
+
+        let params = CodeLensParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
+        let lenses = match self
+            .send_request_sequential_tracked::<_, Option<Vec<lsp_types::CodeLens>>>(
+                "textDocument/codeLens",
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await
+        {
+            Ok(lenses) => lenses.unwrap_or_default(),
+            Err(e) => {
+                tracing::debug!("Code lens request failed: {}", e);
+                let _ = self.async_tx.send(AsyncMessage::LspCodeLens {
+                    request_id,
+                    uri: uri.as_str().to_string(),
+                    lenses: Vec::new(),
+                });
+                return Err(e);
+            }
+        };
+
+        let mut resolved = Vec::with_capacity(lenses.len());
+        for lens in lenses {
+            if lens.command.is_some() || !resolve_provider {
+                resolved.push(lens);
+                continue;
+            }
+            match self
+                .send_request_sequential::<_, lsp_types::CodeLens>(
+                    "codeLens/resolve",
+                    Some(lens.clone()),
+                    pending,
+                )
+                .await
+            {
+                Ok(lens) => resolved.push(lens),
+                Err(e) => {
+                    tracing::debug!("Code lens resolve failed: {}", e);
+                    resolved.push(lens);
+                }
+            }
+        }
+
+        tracing::trace!(
+            "LSP: received {} code lenses for {}",
+            resolved.len(),
+            uri.as_str()
+        );
+
+        let _ = self.async_tx.send(AsyncMessage::LspCodeLens {
+            request_id,
+            uri: uri.as_str().to_string(),
+            lenses: resolved,
+        });
+        Ok(())
+    }
+
     /// Handle signature help request
     #[allow(clippy::type_complexity)]
     async fn handle_signature_help(
@@ -1514,6 +1703,7 @@ impl LspState {
 
                     let _ = self.async_tx.send(AsyncMessage::LspPulledDiagnostics {
                         request_id,
+                        language: self.language.clone(),
                         uri: uri_string,
                         result_id,
                         diagnostics,
@@ -1535,6 +1725,7 @@ impl LspState {
 
                     let _ = self.async_tx.send(AsyncMessage::LspPulledDiagnostics {
                         request_id,
+                        language: self.language.clone(),
                         uri: uri_string,
                         result_id: Some(result_id),
                         diagnostics: Vec::new(),
@@ -1548,6 +1739,7 @@ impl LspState {
                     );
                     let _ = self.async_tx.send(AsyncMessage::LspPulledDiagnostics {
                         request_id,
+                        language: self.language.clone(),
                         uri: uri_string,
                         result_id: None,
                         diagnostics: Vec::new(),
@@ -1562,6 +1754,7 @@ impl LspState {
                 // Send empty result on error
                 let _ = self.async_tx.send(AsyncMessage::LspPulledDiagnostics {
                     request_id,
+                    language: self.language.clone(),
                     uri: uri.as_str().to_string(),
                     result_id: None,
                     diagnostics: Vec::new(),
@@ -1910,9 +2103,6 @@ struct LspTask {
     /// Next request ID
     next_id: i64,
 
-    /// Pending requests waiting for response
-    pending: HashMap<i64, oneshot::Sender<Result<Value, String>>>,
-
     /// Server capabilities
     capabilities: Option<ServerCapabilities>,
 
@@ -2012,7 +2202,6 @@ impl LspTask {
             stdin,
             stdout,
             next_id: 0,
-            pending: HashMap::new(),
             capabilities: None,
             document_versions: HashMap::new(),
             pending_opens: HashMap::new(),
@@ -2120,8 +2309,21 @@ impl LspTask {
         });
     }
 
+    /// OS process ID of the server, if it is still running
+    fn process_id(&self) -> Option<u32> {
+        self._process.id()
+    }
+
     /// Run the task (processes commands and reads from stdout)
-    async fn run(self, mut command_rx: mpsc::Receiver<LspCommand>) {
+    ///
+    /// `pending` holds requests waiting for a response; it is shared with the
+    /// `LspHandle` so the editor can report how many are outstanding.
+    #[allow(clippy::type_complexity)]
+    async fn run(
+        self,
+        mut command_rx: mpsc::Receiver<LspCommand>,
+        pending: Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) {
         tracing::info!("LspTask::run() started for language: {}", self.language);
 
         // Create shared stdin writer so both command processing and stdout reader can write
@@ -2140,7 +2342,6 @@ impl LspTask {
             active_requests: HashMap::new(),
         };
 
-        let pending = Arc::new(Mutex::new(self.pending));
         let async_tx = state.async_tx.clone();
         let language_clone = state.language.clone();
 
@@ -2351,6 +2552,31 @@ impl LspTask {
                                 });
                             }
                         }
+                        LspCommand::DocumentHighlight {
+                            request_id,
+                            uri,
+                            line,
+                            character,
+                        } => {
+                            if state.initialized {
+                                tracing::trace!(
+                                    "Processing DocumentHighlight request for {}",
+                                    uri.as_str()
+                                );
+                                let _ = state
+                                    .handle_document_highlight(
+                                        request_id, uri, line, character, &pending,
+                                    )
+                                    .await;
+                            } else {
+                                tracing::trace!("LSP not initialized, cannot get document highlights");
+                                let _ = state.async_tx.send(AsyncMessage::LspDocumentHighlight {
+                                    request_id,
+                                    uri: uri.as_str().to_string(),
+                                    highlights: Vec::new(),
+                                });
+                            }
+                        }
                         LspCommand::SignatureHelp {
                             request_id,
                             uri,
@@ -2425,6 +2651,7 @@ impl LspTask {
                                 );
                                 let _ = state.async_tx.send(AsyncMessage::LspPulledDiagnostics {
                                     request_id,
+                                    language: state.language.clone(),
                                     uri: uri.as_str().to_string(),
                                     result_id: None,
                                     diagnostics: Vec::new(),
@@ -2467,6 +2694,18 @@ impl LspTask {
                                 });
                             }
                         }
+                        LspCommand::CodeLens { request_id, uri } => {
+                            if state.initialized {
+                                tracing::info!("Processing CodeLens request for {}", uri.as_str());
+                                let _ = state.handle_code_lens(request_id, uri, &pending).await;
+                            } else {
+                                tracing::trace!(
+                                    "Queueing CodeLens for {} until initialization completes",
+                                    uri.as_str()
+                                );
+                                pending_commands.push(LspCommand::CodeLens { request_id, uri });
+                            }
+                        }
                         LspCommand::SemanticTokensFull { request_id, uri } => {
                             if state.initialized {
                                 tracing::info!(
@@ -2825,6 +3064,7 @@ async fn handle_notification_dispatch(
 
                 // Send to main loop
                 let _ = async_tx.send(AsyncMessage::LspDiagnostics {
+                    language: language.to_string(),
                     uri: params.uri.to_string(),
                     diagnostics: params.diagnostics,
                 });
@@ -3042,6 +3282,15 @@ pub struct LspHandle {
     /// Client state
     state: Arc<Mutex<LspClientState>>,
 
+    /// Requests sent to the server that are still waiting for a response
+    pending: Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+
+    /// OS process ID of the server (0 until the process has been spawned)
+    pid: Arc<AtomicU32>,
+
+    /// Capabilities reported by the server (None until initialized)
+    capabilities: Arc<Mutex<Option<ServerCapabilities>>>,
+
     /// Runtime handle for blocking operations
     runtime: tokio::runtime::Handle,
 }
@@ -3073,7 +3322,12 @@ impl LspHandle {
             message: None,
         });
 
+        let pending = Arc::new(Mutex::new(HashMap::new()));
+        let pid = Arc::new(AtomicU32::new(0));
+
         let state_clone = state.clone();
+        let pending_clone = pending.clone();
+        let pid_clone = pid.clone();
         let stderr_log_path_clone = stderr_log_path.clone();
         runtime.spawn(async move {
             match LspTask::spawn(
@@ -3087,7 +3341,11 @@ impl LspHandle {
             .await
             {
                 Ok(task) => {
-                    task.run(command_rx).await;
+                    if let Some(process_id) = task.process_id() {
+                        pid_clone.store(process_id, Ordering::Relaxed);
+                    }
+                    task.run(command_rx, pending_clone).await;
+                    pid_clone.store(0, Ordering::Relaxed);
                 }
                 Err(e) => {
                     tracing::error!("Failed to spawn LSP task: {}", e);
@@ -3118,6 +3376,9 @@ impl LspHandle {
             language,
             command_tx,
             state,
+            pending,
+            pid,
+            capabilities: Arc::new(Mutex::new(None)),
             runtime: runtime.clone(),
         })
     }
@@ -3132,6 +3393,24 @@ impl LspHandle {
         &self.language
     }
 
+    /// OS process ID of the server, if it has been spawned and is still running
+    pub fn process_id(&self) -> Option<u32> {
+        match self.pid.load(Ordering::Relaxed) {
+            0 => None,
+            pid => Some(pid),
+        }
+    }
+
+    /// Capabilities reported by the server, once it has been initialized
+    pub fn capabilities(&self) -> Option<ServerCapabilities> {
+        self.capabilities.lock().ok().and_then(|c| c.clone())
+    }
+
+    /// Number of requests sent to the server that haven't been answered yet
+    pub fn pending_request_count(&self) -> usize {
+        self.pending.lock().map(|p| p.len()).unwrap_or(0)
+    }
+
     /// Initialize the server (non-blocking)
     ///
     /// This sends the initialize request asynchronously. The server will be ready
@@ -3159,6 +3438,7 @@ impl LspHandle {
         }
 
         let state = self.state.clone();
+        let capabilities = self.capabilities.clone();
 
         // Create a channel for the response, but don't wait for it
         let (tx, rx) = oneshot::channel();
@@ -3175,8 +3455,11 @@ impl LspHandle {
         let runtime = self.runtime.clone();
         runtime.spawn(async move {
             match tokio::time::timeout(std::time::Duration::from_secs(10), rx).await {
-                Ok(Ok(Ok(_))) => {
+                Ok(Ok(Ok(result))) => {
                     // Successfully initialized
+                    if let Ok(mut c) = capabilities.lock() {
+                        *c = Some(result.capabilities);
+                    }
                     if let Ok(mut s) = state.lock() {
                         let _ = s.transition_to(LspClientState::Running);
                     }
@@ -3361,6 +3644,27 @@ impl LspHandle {
             .map_err(|_| "Failed to send references command".to_string())
     }
 
+    /// Request document highlights for the symbol at a position
+    ///
+    /// Unlike find references this is scoped to the current document and is
+    /// cheap enough to send on every cursor settle.
+    pub fn document_highlight(
+        &self,
+        request_id: u64,
+        uri: Uri,
+        line: u32,
+        character: u32,
+    ) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::DocumentHighlight {
+                request_id,
+                uri,
+                line,
+                character,
+            })
+            .map_err(|_| "Failed to send document_highlight command".to_string())
+    }
+
     /// Request signature help
     pub fn signature_help(
         &self,
@@ -3447,6 +3751,13 @@ impl LspHandle {
             .map_err(|_| "Failed to send inlay_hints command".to_string())
     }
 
+    /// Request code lenses for an entire document
+    pub fn code_lens(&self, request_id: u64, uri: Uri) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::CodeLens { request_id, uri })
+            .map_err(|_| "Failed to send code_lens command".to_string())
+    }
+
     /// Request semantic tokens for an entire document
     pub fn semantic_tokens_full(&self, request_id: u64, uri: Uri) -> Result<(), String> {
         self.command_tx
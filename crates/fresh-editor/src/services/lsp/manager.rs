@@ -1,15 +1,16 @@
 //! LSP Manager - manages multiple language servers using async I/O
 //!
 //! This module provides a manager for multiple LSP servers that:
-//! - Spawns one server per language
+//! - Spawns one primary server per language, plus any additional servers
+//!   attached to the language (e.g. a CSS server for templates)
 //! - Uses async LspHandle for non-blocking I/O
-//! - Routes requests to appropriate servers
+//! - Routes requests to appropriate servers based on their capabilities
 //! - Configured via config.json
 
 use crate::services::async_bridge::AsyncBridge;
 use crate::services::lsp::async_handler::LspHandle;
 use crate::types::LspServerConfig;
-use lsp_types::{SemanticTokensLegend, Uri};
+use lsp_types::{OneOf, SemanticTokensLegend, ServerCapabilities, Uri};
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 
@@ -25,6 +26,49 @@ pub enum LspSpawnResult {
     Failed,
 }
 
+/// An LSP feature used to route requests when a language has several servers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LspFeature {
+    Completion,
+    Hover,
+    Definition,
+    References,
+    Rename,
+    SignatureHelp,
+    CodeAction,
+    DocumentHighlight,
+    CodeLens,
+    InlayHints,
+}
+
+impl LspFeature {
+    /// Whether a server with these capabilities supports the feature
+    pub fn is_supported_by(self, capabilities: &ServerCapabilities) -> bool {
+        fn enabled<T>(provider: &Option<OneOf<bool, T>>) -> bool {
+            matches!(provider, Some(OneOf::Left(true)) | Some(OneOf::Right(_)))
+        }
+
+        match self {
+            LspFeature::Completion => capabilities.completion_provider.is_some(),
+            LspFeature::Hover => !matches!(
+                capabilities.hover_provider,
+                None | Some(lsp_types::HoverProviderCapability::Simple(false))
+            ),
+            LspFeature::Definition => enabled(&capabilities.definition_provider),
+            LspFeature::References => enabled(&capabilities.references_provider),
+            LspFeature::Rename => enabled(&capabilities.rename_provider),
+            LspFeature::SignatureHelp => capabilities.signature_help_provider.is_some(),
+            LspFeature::CodeAction => !matches!(
+                capabilities.code_action_provider,
+                None | Some(lsp_types::CodeActionProviderCapability::Simple(false))
+            ),
+            LspFeature::DocumentHighlight => enabled(&capabilities.document_highlight_provider),
+            LspFeature::CodeLens => capabilities.code_lens_provider.is_some(),
+            LspFeature::InlayHints => enabled(&capabilities.inlay_hint_provider),
+        }
+    }
+}
+
 /// Constants for restart behavior
 const MAX_RESTARTS_IN_WINDOW: usize = 5;
 const RESTART_WINDOW_SECS: u64 = 180; // 3 minutes
@@ -32,12 +76,18 @@ const RESTART_BACKOFF_BASE_MS: u64 = 1000; // 1s, 2s, 4s, 8s...
 
 /// Manager for multiple language servers (async version)
 pub struct LspManager {
-    /// Map from language ID to LSP handle
+    /// Map from server ID to LSP handle
+    ///
+    /// The primary server of a language uses the language ID as its server ID;
+    /// additional servers use the key they were configured under.
     handles: HashMap<String, LspHandle>,
 
-    /// Configuration for each language
+    /// Configuration for each server ID
     config: HashMap<String, LspServerConfig>,
 
+    /// Additional server IDs attached to each language, in priority order
+    additional_servers: HashMap<String, Vec<String>>,
+
     /// Default root URI for workspace (used if no per-language root is set)
     root_uri: Option<Uri>,
 
@@ -89,6 +139,7 @@ impl LspManager {
         Self {
             handles: HashMap::new(),
             config: HashMap::new(),
+            additional_servers: HashMap::new(),
             root_uri,
             per_language_root_uris: HashMap::new(),
             runtime: None,
@@ -253,6 +304,83 @@ impl LspManager {
         self.config.insert(language, config);
     }
 
+    /// Attach additional servers (by server ID) to a language
+    ///
+    /// They receive the language's documents alongside its primary server.
+    pub fn set_additional_servers(&mut self, language: &str, server_ids: Vec<String>) {
+        if server_ids.is_empty() {
+            self.additional_servers.remove(language);
+        } else {
+            self.additional_servers
+                .insert(language.to_string(), server_ids);
+        }
+    }
+
+    /// Server IDs serving a language: its primary server first, then any
+    /// additional servers
+    pub fn server_ids(&self, language: &str) -> Vec<String> {
+        let mut ids = vec![language.to_string()];
+        if let Some(additional) = self.additional_servers.get(language) {
+            ids.extend(additional.iter().filter(|id| *id != language).cloned());
+        }
+        ids
+    }
+
+    /// Languages served by a server ID (the inverse of `server_ids`)
+    pub fn languages_for_server(&self, server_id: &str) -> Vec<String> {
+        let mut languages = vec![server_id.to_string()];
+        for (language, additional) in &self.additional_servers {
+            if additional.iter().any(|id| id == server_id) && language != server_id {
+                languages.push(language.clone());
+            }
+        }
+        languages
+    }
+
+    /// Whether a language has more than one server attached
+    pub fn has_additional_servers(&self, language: &str) -> bool {
+        self.additional_servers
+            .get(language)
+            .is_some_and(|ids| !ids.is_empty())
+    }
+
+    /// Short name for a server, used to label its results
+    pub fn server_label(&self, server_id: &str) -> String {
+        self.config
+            .get(server_id)
+            .map(|c| c.command.as_str())
+            .filter(|command| !command.is_empty())
+            .and_then(|command| std::path::Path::new(command).file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| server_id.to_string())
+    }
+
+    /// Pick the server that should answer a request for `feature`
+    ///
+    /// Returns the first running server of the language (primary first) whose
+    /// capabilities include the feature. Until a server has reported its
+    /// capabilities, only the primary server is assumed to support everything.
+    pub fn server_for_feature(&self, language: &str, feature: LspFeature) -> Option<String> {
+        self.servers_for_feature(language, feature)
+            .into_iter()
+            .next()
+    }
+
+    /// All running servers of a language that support `feature`
+    pub fn servers_for_feature(&self, language: &str, feature: LspFeature) -> Vec<String> {
+        self.server_ids(language)
+            .into_iter()
+            .filter(|id| {
+                self.handles
+                    .get(id)
+                    .is_some_and(|handle| match handle.capabilities() {
+                        Some(capabilities) => feature.is_supported_by(&capabilities),
+                        None => id == language,
+                    })
+            })
+            .collect()
+    }
+
     /// Set a new root URI for the workspace
     ///
     /// This should be called after shutting down all servers when switching projects.
@@ -786,6 +914,43 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_lsp_manager_additional_servers() {
+        let mut manager = LspManager::new(None);
+        manager.set_additional_servers("rust", vec!["tailwindcss".to_string()]);
+        manager.set_additional_servers("html", vec!["tailwindcss".to_string()]);
+
+        assert_eq!(manager.server_ids("rust"), vec!["rust", "tailwindcss"]);
+        assert_eq!(manager.server_ids("python"), vec!["python"]);
+        assert!(manager.has_additional_servers("rust"));
+
+        let mut languages = manager.languages_for_server("tailwindcss");
+        languages.sort();
+        assert_eq!(languages, vec!["html", "rust", "tailwindcss"]);
+
+        // Nothing is running, so no server can answer requests
+        assert!(manager
+            .server_for_feature("rust", LspFeature::Completion)
+            .is_none());
+
+        manager.set_additional_servers("rust", vec![]);
+        assert!(!manager.has_additional_servers("rust"));
+    }
+
+    #[test]
+    fn test_lsp_feature_capabilities() {
+        let mut capabilities = ServerCapabilities::default();
+        assert!(!LspFeature::Completion.is_supported_by(&capabilities));
+        assert!(!LspFeature::Hover.is_supported_by(&capabilities));
+
+        capabilities.completion_provider = Some(Default::default());
+        capabilities.hover_provider = Some(lsp_types::HoverProviderCapability::Simple(true));
+        capabilities.definition_provider = Some(OneOf::Left(false));
+        assert!(LspFeature::Completion.is_supported_by(&capabilities));
+        assert!(LspFeature::Hover.is_supported_by(&capabilities));
+        assert!(!LspFeature::Definition.is_supported_by(&capabilities));
+    }
+
     #[test]
     fn test_lsp_manager_force_spawn_disabled_language() {
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -830,7 +995,10 @@ mod tests {
                 filenames: vec![],
                 grammar: "rust".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: crate::config::HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: false,
@@ -839,6 +1007,7 @@ mod tests {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
         languages.insert(
@@ -848,7 +1017,10 @@ mod tests {
                 filenames: vec![],
                 grammar: "javascript".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: crate::config::HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: false,
@@ -857,6 +1029,7 @@ mod tests {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
         languages.insert(
@@ -866,7 +1039,10 @@ mod tests {
                 filenames: vec![],
                 grammar: "c_sharp".to_string(),
                 comment_prefix: Some("//".to_string()),
+                block_comment_start: None,
+                block_comment_end: None,
                 auto_indent: true,
+                indent_rules: None,
                 highlighter: crate::config::HighlighterPreference::Auto,
                 textmate_grammar: None,
                 show_whitespace_tabs: false,
@@ -875,6 +1051,7 @@ mod tests {
                 formatter: None,
                 format_on_save: false,
                 on_save: vec![],
+                lsp_servers: vec![],
             },
         );
         languages
@@ -121,3 +121,22 @@ pub mod semantic_tokens;
 
 // Re-export for public API (used by tests)
 pub use crate::types::LspServerConfig;
+
+/// Command that installs a common language server, shown with "not found" errors
+pub fn install_command(server_command: &str) -> Option<&'static str> {
+    match server_command {
+        "pylsp" => Some("pip install python-lsp-server"),
+        "rust-analyzer" => Some("rustup component add rust-analyzer"),
+        "typescript-language-server" => {
+            Some("npm install -g typescript-language-server typescript")
+        }
+        "gopls" => Some("go install golang.org/x/tools/gopls@latest"),
+        "bash-language-server" => Some("npm install -g bash-language-server"),
+        "clangd" => Some("sudo apt install clangd"),
+        "vscode-html-language-server"
+        | "vscode-css-language-server"
+        | "vscode-json-language-server" => Some("npm install -g vscode-langservers-extracted"),
+        "csharp-ls" => Some("dotnet tool install --global csharp-ls"),
+        _ => None,
+    }
+}
@@ -20,11 +20,19 @@ pub fn is_semantic_token_overlay(overlay: &crate::view::overlay::Overlay) -> boo
 }
 
 /// Map an LSP semantic token type + modifiers to a theme color.
+///
+/// The theme's `semantic` scopes win, most specific first: "type.modifier",
+/// then "*.modifier", then "type". Otherwise the token falls back to the
+/// closest syntax color.
 pub fn semantic_token_color(
     token_type: &str,
     modifiers: &[String],
     theme: &crate::view::theme::Theme,
 ) -> Color {
+    if let Some(color) = themed_semantic_color(token_type, modifiers, theme) {
+        return color;
+    }
+
     if modifiers.iter().any(|m| m == "deprecated") {
         return theme.diagnostic_warning_fg;
     }
@@ -32,9 +40,8 @@ pub fn semantic_token_color(
     match token_type {
         "keyword" | "modifier" => theme.syntax_keyword,
         "function" | "method" | "macro" => theme.syntax_function,
-        "parameter" | "variable" | "property" | "enumMember" | "event" | "label" => {
-            theme.syntax_variable
-        }
+        "parameter" | "variable" | "property" | "event" | "label" => theme.syntax_variable,
+        "enumMember" => theme.syntax_constant,
         "type" | "class" | "interface" | "struct" | "typeParameter" | "namespace" | "enum" => {
             theme.syntax_type
         }
@@ -47,6 +54,23 @@ pub fn semantic_token_color(
     }
 }
 
+/// Look up a token in the theme's `semantic` scopes
+fn themed_semantic_color(
+    token_type: &str,
+    modifiers: &[String],
+    theme: &crate::view::theme::Theme,
+) -> Option<Color> {
+    if theme.semantic.is_empty() {
+        return None;
+    }
+    let lookup = |scope: String| theme.semantic.get(&scope).copied();
+    modifiers
+        .iter()
+        .find_map(|m| lookup(format!("{}.{}", token_type, m)))
+        .or_else(|| modifiers.iter().find_map(|m| lookup(format!("*.{}", m))))
+        .or_else(|| theme.semantic.get(token_type).copied())
+}
+
 /// Apply semantic tokens as overlays so their ranges track edits.
 pub fn apply_semantic_tokens_to_state(
     state: &mut EditorState,
@@ -151,4 +175,42 @@ mod tests {
             .range(&state.marker_list);
         assert_eq!(moved_range, 6..10);
     }
+
+    #[test]
+    fn semantic_token_color_prefers_theme_scopes() {
+        let mut theme = Theme::load_builtin(THEME_DARK).expect("dark theme must exist");
+        theme.semantic.clear();
+        theme
+            .semantic
+            .insert("parameter".into(), Color::Rgb(1, 1, 1));
+        theme
+            .semantic
+            .insert("variable.readonly".into(), Color::Rgb(2, 2, 2));
+        theme
+            .semantic
+            .insert("*.static".into(), Color::Rgb(3, 3, 3));
+        let mods = |m: &[&str]| m.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            semantic_token_color("parameter", &[], &theme),
+            Color::Rgb(1, 1, 1)
+        );
+        assert_eq!(
+            semantic_token_color("variable", &mods(&["static", "readonly"]), &theme),
+            Color::Rgb(2, 2, 2)
+        );
+        assert_eq!(
+            semantic_token_color("property", &mods(&["static"]), &theme),
+            Color::Rgb(3, 3, 3)
+        );
+        // Unscoped tokens fall back to syntax colors
+        assert_eq!(
+            semantic_token_color("property", &[], &theme),
+            theme.syntax_variable
+        );
+        assert_eq!(
+            semantic_token_color("enumMember", &[], &theme),
+            theme.syntax_constant
+        );
+    }
 }
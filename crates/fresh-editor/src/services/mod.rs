@@ -3,19 +3,31 @@
 //! This module contains all code that deals with external processes,
 //! I/O, and async operations.
 
+pub mod appearance;
 pub mod async_bridge;
 pub mod clipboard;
+pub mod collab;
+pub mod doctor;
 pub mod fs;
+pub mod git;
 #[cfg(target_os = "linux")]
 pub mod gpm;
+pub mod link_detection;
+pub mod local_history;
+pub mod locale_format;
 pub mod log_dirs;
 pub mod lsp;
+pub mod package_bundle;
 pub mod plugins;
+pub mod problem_matcher;
 pub mod process_limits;
 pub mod recovery;
 pub mod release_checker;
 pub mod remote;
+pub mod render_profile;
+pub mod self_update;
 pub mod signal_handler;
+pub mod speech;
 pub mod status_log;
 pub mod styled_html;
 pub mod telemetry;
@@ -24,3 +36,5 @@ pub mod terminal_modes;
 pub mod time_source;
 pub mod tracing_setup;
 pub mod warning_log;
+pub mod workspace_index;
+pub mod workspace_trust;
@@ -0,0 +1,452 @@
+//! Offline package bundles
+//!
+//! `fresh --cmd pkg export bundle.tar.gz` packs every installed package
+//! (plugins, themes, language packs and bundles) together with
+//! `packages.lock.json` into one gzipped tarball, so they can be carried to a
+//! machine without network access and restored with `fresh --cmd pkg import`.
+//!
+//! The archive starts with a `fresh-bundle.json` manifest holding the SHA-256
+//! of every file. Import checks the whole archive (against a hash the user
+//! supplies, if any) and every file against the manifest before anything is
+//! written to the config directory.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config_io::DirectoryContext;
+
+/// Name of the manifest entry at the start of the archive
+const MANIFEST_NAME: &str = "fresh-bundle.json";
+
+/// Current bundle format version
+const BUNDLE_VERSION: u32 = 1;
+
+/// Directories (relative to the config dir) holding one package per subdirectory
+const PACKAGE_DIRS: [&str; 4] = [
+    "plugins/packages",
+    "themes/packages",
+    "languages/packages",
+    "bundles/packages",
+];
+
+/// Lockfile written by the package manager, carried along with the packages
+const LOCKFILE_NAME: &str = "packages.lock.json";
+
+/// Package manager state that is not part of any package (registry index and cache)
+fn is_internal_dir(name: &str) -> bool {
+    name.starts_with('.')
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleManifest {
+    bundle_version: u32,
+    /// Packages in the bundle, as paths relative to the config dir
+    packages: Vec<String>,
+    /// SHA-256 (hex) of every file, keyed by path relative to the config dir
+    files: BTreeMap<String, String>,
+}
+
+/// Result of a successful export
+#[derive(Debug)]
+pub struct ExportSummary {
+    pub packages: usize,
+    pub files: usize,
+    /// SHA-256 (hex) of the written archive
+    pub sha256: String,
+}
+
+/// Result of a successful import
+#[derive(Debug)]
+pub struct ImportSummary {
+    /// Installed packages, as paths relative to the config dir
+    pub packages: Vec<String>,
+    pub files: usize,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Archive path (always `/`-separated) for a path relative to the config dir
+fn archive_path(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Collect the regular files below `dir`, relative to `root`
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|e| e.ok())
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if file_type.is_file() {
+            files.push(path.strip_prefix(root)?.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+/// Pack all installed packages and the lockfile into a gzipped tarball at `output`
+pub fn export_bundle(dir_context: &DirectoryContext, output: &Path) -> Result<ExportSummary> {
+    let root = &dir_context.config_dir;
+    let mut packages = Vec::new();
+    let mut files = Vec::new();
+
+    for packages_dir in PACKAGE_DIRS {
+        let dir = root.join(packages_dir);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut package_paths: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .filter(|e| !is_internal_dir(&e.file_name().to_string_lossy()))
+            .map(|e| e.path())
+            .collect();
+        package_paths.sort();
+
+        for package in package_paths {
+            packages.push(archive_path(package.strip_prefix(root)?));
+            collect_files(root, &package, &mut files)?;
+        }
+    }
+
+    if packages.is_empty() {
+        bail!("No installed packages found in {}", root.display());
+    }
+    if root.join(LOCKFILE_NAME).is_file() {
+        files.push(PathBuf::from(LOCKFILE_NAME));
+    }
+
+    let mut manifest = BundleManifest {
+        bundle_version: BUNDLE_VERSION,
+        packages,
+        files: BTreeMap::new(),
+    };
+    let mut contents = Vec::with_capacity(files.len());
+    for relative in &files {
+        let data = fs::read(root.join(relative))
+            .with_context(|| format!("Failed to read {}", relative.display()))?;
+        manifest
+            .files
+            .insert(archive_path(relative), sha256_hex(&data));
+        contents.push(data);
+    }
+
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    append_file(&mut builder, MANIFEST_NAME, &manifest_json, 0o644)?;
+    for (relative, data) in files.iter().zip(&contents) {
+        append_file(
+            &mut builder,
+            &archive_path(relative),
+            data,
+            file_mode(&root.join(relative)),
+        )?;
+    }
+    let archive = builder.into_inner()?.finish()?;
+
+    fs::write(output, &archive).with_context(|| format!("Failed to write {}", output.display()))?;
+
+    Ok(ExportSummary {
+        packages: manifest.packages.len(),
+        files: manifest.files.len(),
+        sha256: sha256_hex(&archive),
+    })
+}
+
+fn append_file<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    data: &[u8],
+    mode: u32,
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(mode);
+    header.set_cksum();
+    builder.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn file_mode(path: &Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|m| m.permissions().mode() & 0o777)
+        .unwrap_or(0o644)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_path: &Path) -> u32 {
+    0o644
+}
+
+/// Reject archive paths that could escape the config dir
+fn safe_relative_path(path: &str) -> Result<PathBuf> {
+    let relative = PathBuf::from(path);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        bail!("Bundle contains an unsafe path: {}", path);
+    }
+    Ok(relative)
+}
+
+/// Whether `path` belongs to one of the bundle's packages (or is the lockfile)
+fn is_bundled_path(path: &str, packages: &[String]) -> bool {
+    path == LOCKFILE_NAME
+        || packages
+            .iter()
+            .any(|p| path.starts_with(p.as_str()) && path[p.len()..].starts_with('/'))
+}
+
+/// Install the packages from a bundle written by [`export_bundle`]
+///
+/// With `expected_sha256`, the archive itself must match that hash. Every file
+/// is verified against the manifest before anything is written; packages that
+/// are already installed are replaced.
+pub fn import_bundle(
+    dir_context: &DirectoryContext,
+    archive_path: &Path,
+    expected_sha256: Option<&str>,
+) -> Result<ImportSummary> {
+    let archive = fs::read(archive_path)
+        .with_context(|| format!("Failed to read {}", archive_path.display()))?;
+
+    if let Some(expected) = expected_sha256 {
+        let actual = sha256_hex(&archive);
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            bail!(
+                "Bundle hash mismatch: expected {}, got {}",
+                expected.trim(),
+                actual
+            );
+        }
+    }
+
+    let mut tar = tar::Archive::new(GzDecoder::new(archive.as_slice()));
+    let mut manifest: Option<BundleManifest> = None;
+    let mut files: Vec<(PathBuf, Vec<u8>, u32)> = Vec::new();
+
+    for entry in tar.entries().context("Failed to read bundle")? {
+        let mut entry = entry.context("Failed to read bundle")?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.to_string_lossy().into_owned();
+        let mode = entry.header().mode().unwrap_or(0o644);
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+
+        if path == MANIFEST_NAME {
+            let parsed: BundleManifest =
+                serde_json::from_slice(&data).context("Invalid bundle manifest")?;
+            if parsed.bundle_version > BUNDLE_VERSION {
+                bail!(
+                    "Bundle format version {} is newer than supported ({})",
+                    parsed.bundle_version,
+                    BUNDLE_VERSION
+                );
+            }
+            manifest = Some(parsed);
+            continue;
+        }
+
+        let Some(manifest) = manifest.as_ref() else {
+            bail!("Bundle has no {} manifest", MANIFEST_NAME);
+        };
+        let Some(hash) = manifest.files.get(&path) else {
+            bail!("Bundle contains a file missing from its manifest: {}", path);
+        };
+        if sha256_hex(&data) != *hash {
+            bail!("Hash mismatch for {}", path);
+        }
+        if !is_bundled_path(&path, &manifest.packages) {
+            bail!("Bundle file is outside its packages: {}", path);
+        }
+        files.push((safe_relative_path(&path)?, data, mode));
+    }
+
+    let Some(manifest) = manifest else {
+        bail!("Bundle has no {} manifest", MANIFEST_NAME);
+    };
+    if files.len() != manifest.files.len() {
+        bail!(
+            "Bundle is incomplete: {} of {} files present",
+            files.len(),
+            manifest.files.len()
+        );
+    }
+
+    let root = &dir_context.config_dir;
+    for package in &manifest.packages {
+        let relative = safe_relative_path(package)?;
+        if !PACKAGE_DIRS
+            .iter()
+            .any(|dir| relative.parent() == Some(Path::new(dir)))
+        {
+            bail!("Bundle contains an unexpected package path: {}", package);
+        }
+        let dest = root.join(&relative);
+        if dest.exists() {
+            fs::remove_dir_all(&dest)
+                .with_context(|| format!("Failed to replace {}", dest.display()))?;
+        }
+    }
+
+    for (relative, data, mode) in &files {
+        let dest = root.join(relative);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest, data).with_context(|| format!("Failed to write {}", dest.display()))?;
+        set_file_mode(&dest, *mode)?;
+    }
+
+    Ok(ImportSummary {
+        packages: manifest.packages,
+        files: files.len(),
+    })
+}
+
+#[cfg(unix)]
+fn set_file_mode(path: &Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o777))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_file_mode(_path: &Path, _mode: u32) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, content: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn installed_context() -> (TempDir, DirectoryContext) {
+        let temp = TempDir::new().unwrap();
+        let context = DirectoryContext::for_testing(temp.path());
+        let root = &context.config_dir;
+        write(root, "plugins/packages/alpha/package.json", "{}");
+        write(root, "plugins/packages/alpha/main.ts", "// alpha");
+        write(root, "plugins/packages/.index/abc/plugins.json", "{}");
+        write(root, "themes/packages/dusk/dusk.json", "{}");
+        write(root, LOCKFILE_NAME, r#"{"packages":{}}"#);
+        (temp, context)
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let (temp, source) = installed_context();
+        let bundle = temp.path().join("bundle.tar.gz");
+        let exported = export_bundle(&source, &bundle).unwrap();
+        assert_eq!(exported.packages, 2);
+        assert_eq!(exported.files, 4);
+
+        let target_dir = TempDir::new().unwrap();
+        let target = DirectoryContext::for_testing(target_dir.path());
+        // A stale file in an installed package is dropped when it's replaced
+        write(&target.config_dir, "plugins/packages/alpha/old.ts", "");
+
+        let imported = import_bundle(&target, &bundle, Some(&exported.sha256)).unwrap();
+        assert_eq!(
+            imported.packages,
+            vec!["plugins/packages/alpha", "themes/packages/dusk"]
+        );
+
+        let root = &target.config_dir;
+        assert_eq!(
+            fs::read_to_string(root.join("plugins/packages/alpha/main.ts")).unwrap(),
+            "// alpha"
+        );
+        assert!(root.join("themes/packages/dusk/dusk.json").exists());
+        assert!(root.join(LOCKFILE_NAME).exists());
+        assert!(!root.join("plugins/packages/alpha/old.ts").exists());
+        assert!(!root.join("plugins/packages/.index").exists());
+    }
+
+    #[test]
+    fn test_import_rejects_wrong_archive_hash() {
+        let (temp, source) = installed_context();
+        let bundle = temp.path().join("bundle.tar.gz");
+        export_bundle(&source, &bundle).unwrap();
+
+        let target_dir = TempDir::new().unwrap();
+        let target = DirectoryContext::for_testing(target_dir.path());
+        let err = import_bundle(&target, &bundle, Some(&"0".repeat(64))).unwrap_err();
+        assert!(err.to_string().contains("hash mismatch"), "{}", err);
+        assert!(!target.config_dir.join("plugins/packages/alpha").exists());
+    }
+
+    #[test]
+    fn test_import_rejects_tampered_file() {
+        let temp = TempDir::new().unwrap();
+        let manifest = BundleManifest {
+            bundle_version: BUNDLE_VERSION,
+            packages: vec!["plugins/packages/alpha".to_string()],
+            files: BTreeMap::from([(
+                "plugins/packages/alpha/main.ts".to_string(),
+                sha256_hex(b"original"),
+            )]),
+        };
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        append_file(
+            &mut builder,
+            MANIFEST_NAME,
+            &serde_json::to_vec(&manifest).unwrap(),
+            0o644,
+        )
+        .unwrap();
+        append_file(
+            &mut builder,
+            "plugins/packages/alpha/main.ts",
+            b"tampered",
+            0o644,
+        )
+        .unwrap();
+        let bundle = temp.path().join("bundle.tar.gz");
+        fs::write(&bundle, builder.into_inner().unwrap().finish().unwrap()).unwrap();
+
+        let target = DirectoryContext::for_testing(temp.path());
+        let err = import_bundle(&target, &bundle, None).unwrap_err();
+        assert!(err.to_string().contains("Hash mismatch"), "{}", err);
+        assert!(!target.config_dir.join("plugins/packages/alpha").exists());
+    }
+
+    #[test]
+    fn test_export_without_packages_fails() {
+        let temp = TempDir::new().unwrap();
+        let context = DirectoryContext::for_testing(temp.path());
+        assert!(export_bundle(&context, &temp.path().join("bundle.tar.gz")).is_err());
+    }
+}
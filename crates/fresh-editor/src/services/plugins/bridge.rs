@@ -1,8 +1,10 @@
 use crate::config_io::DirectoryContext;
 use crate::i18n;
 use crate::input::command_registry::CommandRegistry;
+use crate::services::locale_format::{self, DateStyle};
 use crate::services::signal_handler;
 use crate::view::theme;
+use chrono::TimeZone;
 use fresh_core::services::PluginServiceBridge;
 use std::any::Any;
 use std::collections::HashMap;
@@ -27,6 +29,22 @@ impl PluginServiceBridge for EditorServiceBridge {
         i18n::current_locale()
     }
 
+    fn format_number(&self, value: f64, decimals: Option<usize>) -> String {
+        locale_format::format_number(value, decimals, &i18n::current_locale())
+    }
+
+    fn format_date(&self, format: &str, timestamp_ms: Option<i64>) -> Option<String> {
+        let when = match timestamp_ms {
+            Some(ms) => chrono::Local.timestamp_millis_opt(ms).single()?,
+            None => chrono::Local::now(),
+        };
+        let locale = i18n::current_locale();
+        match DateStyle::from_id(format) {
+            Some(style) => Some(locale_format::format_date(&when, style, &locale)),
+            None => locale_format::format_pattern(&when, format, &locale),
+        }
+    }
+
     fn set_js_execution_state(&self, state: String) {
         signal_handler::set_js_execution_state(state);
     }
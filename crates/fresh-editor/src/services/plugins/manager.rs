@@ -200,6 +200,29 @@ impl PluginManager {
         }
     }
 
+    /// Run a hook and wait for its handlers to return (bounded by `timeout`).
+    ///
+    /// Returns true if the handlers finished in time.
+    pub fn run_hook_blocking(
+        &self,
+        hook_name: &str,
+        args: super::hooks::HookArgs,
+        timeout: std::time::Duration,
+    ) -> bool {
+        #[cfg(feature = "plugins")]
+        {
+            self.inner
+                .as_ref()
+                .map(|m| m.run_hook_blocking(hook_name, args, timeout))
+                .unwrap_or(false)
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            let _ = (hook_name, args, timeout);
+            false
+        }
+    }
+
     /// Deliver a response to a pending async plugin operation.
     pub fn deliver_response(&self, response: super::api::PluginResponse) {
         #[cfg(feature = "plugins")]
@@ -258,6 +281,18 @@ impl PluginManager {
             .unwrap_or_default()
     }
 
+    /// Bytes allocated by the plugin runtime, or None if it isn't running.
+    pub fn memory_usage(&self) -> Option<usize> {
+        #[cfg(feature = "plugins")]
+        {
+            self.inner.as_ref().and_then(|m| m.memory_usage())
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            None
+        }
+    }
+
     /// Reload a plugin by name.
     #[cfg(feature = "plugins")]
     pub fn reload_plugin(&self, name: &str) -> anyhow::Result<()> {
@@ -267,6 +302,25 @@ impl PluginManager {
             .reload_plugin(name)
     }
 
+    /// Deliver the user's answer to a plugin permission prompt.
+    pub fn resolve_permission(
+        &self,
+        plugin_name: String,
+        permission: fresh_core::config::PluginPermission,
+        granted: bool,
+    ) {
+        #[cfg(feature = "plugins")]
+        {
+            if let Some(ref manager) = self.inner {
+                manager.resolve_permission(plugin_name, permission, granted);
+            }
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            let _ = (plugin_name, permission, granted);
+        }
+    }
+
     /// Check if any handlers are registered for a hook.
     pub fn has_hook_handlers(&self, hook_name: &str) -> bool {
         #[cfg(feature = "plugins")]
@@ -314,4 +368,18 @@ impl PluginManager {
     pub fn reject_callback(&self, callback_id: fresh_core::api::JsCallbackId, error: String) {
         let _ = (callback_id, error);
     }
+
+    /// Cancel a plugin background task
+    #[cfg(feature = "plugins")]
+    pub fn cancel_task(&self, task_id: super::api::JsCallbackId) {
+        if let Some(inner) = &self.inner {
+            inner.cancel_task(task_id);
+        }
+    }
+
+    /// Cancel a plugin background task (no-op when plugins disabled)
+    #[cfg(not(feature = "plugins"))]
+    pub fn cancel_task(&self, task_id: fresh_core::api::JsCallbackId) {
+        let _ = task_id;
+    }
 }
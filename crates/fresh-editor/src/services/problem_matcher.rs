@@ -0,0 +1,219 @@
+//! Problems reported in the output of build and lint commands
+//!
+//! [`ProblemMatcher`] finds `file:line:col` locations with their messages in
+//! the output of commands such as `cargo check`, `gcc`, `go vet`, `eslint
+//! --format unix` and `tsc`. These output styles are recognized:
+//!
+//! - `path:line:col: severity: message` and `path:line: severity: message`
+//!   (gcc, clang, rustc `--message-format=short`); a line without a severity
+//!   is an error (`go build`)
+//! - rustc's long format, `error[E0308]: message` followed by
+//!   `  --> path:line:col`
+//! - `path(line,col): error TS2322: message` (tsc)
+//!
+//! Paths are returned as printed; resolving them and dropping lines that
+//! only look like locations is up to the caller.
+
+use regex::Regex;
+
+/// How serious a problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl ProblemSeverity {
+    fn parse(text: &str) -> Self {
+        match text.to_ascii_lowercase().as_str() {
+            "warning" | "warn" => Self::Warning,
+            "note" | "info" | "help" => Self::Info,
+            _ => Self::Error,
+        }
+    }
+}
+
+/// A problem at a location in a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// Path as printed by the command
+    pub path: String,
+    /// 1-indexed line
+    pub line: u32,
+    /// 1-indexed column (1 if the command gave none)
+    pub column: u32,
+    pub severity: ProblemSeverity,
+    pub message: String,
+    /// Error code, such as `E0308` or `TS2322`
+    pub code: Option<String>,
+}
+
+/// Parser for the output styles listed in the module documentation
+pub struct ProblemMatcher {
+    /// `path:line:col: severity: message`
+    gcc: Regex,
+    /// `error[CODE]: message` heading a rustc diagnostic
+    rustc_heading: Regex,
+    /// `  --> path:line:col` below a rustc heading
+    rustc_location: Regex,
+    /// `path(line,col): error TS1234: message`
+    tsc: Regex,
+}
+
+impl Default for ProblemMatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProblemMatcher {
+    pub fn new() -> Self {
+        Self {
+            gcc: Regex::new(
+                r"^([^\s:()][^:()]*?):(\d+):(?:(\d+):)?\s*(?:(error|warning|warn|note|info|help)(?:\[([^\]]+)\])?:\s*)?(.+)$",
+            )
+            .expect("valid regex"),
+            rustc_heading: Regex::new(r"^(error|warning)(?:\[([^\]]+)\])?: (.+)$")
+                .expect("valid regex"),
+            rustc_location: Regex::new(r"^\s*--> (.+?):(\d+):(\d+)$").expect("valid regex"),
+            tsc: Regex::new(r"^(.+?)\((\d+),(\d+)\): (error|warning) (TS\d+): (.+)$")
+                .expect("valid regex"),
+        }
+    }
+
+    /// Problems found in `output`, in the order they were printed
+    pub fn parse(&self, output: &str) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        // A rustc heading waiting for its `-->` location line
+        let mut heading: Option<(ProblemSeverity, Option<String>, String)> = None;
+
+        for line in output.lines() {
+            let line = line.trim_end();
+            if let Some(caps) = self.rustc_heading.captures(line) {
+                heading = Some((
+                    ProblemSeverity::parse(&caps[1]),
+                    caps.get(2).map(|m| m.as_str().to_string()),
+                    caps[3].to_string(),
+                ));
+                continue;
+            }
+            if let Some(caps) = self.rustc_location.captures(line) {
+                if let Some((severity, code, message)) = heading.take() {
+                    problems.push(Problem {
+                        path: caps[1].to_string(),
+                        line: caps[2].parse().unwrap_or(1),
+                        column: caps[3].parse().unwrap_or(1),
+                        severity,
+                        message,
+                        code,
+                    });
+                }
+                continue;
+            }
+            if let Some(caps) = self.tsc.captures(line) {
+                problems.push(Problem {
+                    path: caps[1].to_string(),
+                    line: caps[2].parse().unwrap_or(1),
+                    column: caps[3].parse().unwrap_or(1),
+                    severity: ProblemSeverity::parse(&caps[4]),
+                    message: caps[6].to_string(),
+                    code: Some(caps[5].to_string()),
+                });
+                continue;
+            }
+            if let Some(caps) = self.gcc.captures(line) {
+                problems.push(Problem {
+                    path: caps[1].to_string(),
+                    line: caps[2].parse().unwrap_or(1),
+                    column: caps
+                        .get(3)
+                        .and_then(|m| m.as_str().parse().ok())
+                        .unwrap_or(1),
+                    severity: caps
+                        .get(4)
+                        .map(|m| ProblemSeverity::parse(m.as_str()))
+                        .unwrap_or(ProblemSeverity::Error),
+                    message: caps[6].trim().to_string(),
+                    code: caps.get(5).map(|m| m.as_str().to_string()),
+                });
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(path: &str, line: u32, column: u32, severity: ProblemSeverity) -> Problem {
+        Problem {
+            path: path.to_string(),
+            line,
+            column,
+            severity,
+            message: String::new(),
+            code: None,
+        }
+    }
+
+    fn locations(problems: &[Problem]) -> Vec<Problem> {
+        problems
+            .iter()
+            .map(|p| problem(&p.path, p.line, p.column, p.severity))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_gcc_style() {
+        let output = "src/main.c:12:5: error: expected ';' before '}' token\n\
+                      src/util.c:3: warning: unused variable 'x'\n\
+                      ./main.go:7:2: undefined: foo\n\
+                      make: *** [all] Error 1\n";
+        let problems = ProblemMatcher::new().parse(output);
+        assert_eq!(
+            locations(&problems),
+            vec![
+                problem("src/main.c", 12, 5, ProblemSeverity::Error),
+                problem("src/util.c", 3, 1, ProblemSeverity::Warning),
+                problem("./main.go", 7, 2, ProblemSeverity::Error),
+            ]
+        );
+        assert_eq!(problems[0].message, "expected ';' before '}' token");
+        assert_eq!(problems[2].message, "undefined: foo");
+    }
+
+    #[test]
+    fn test_parse_rustc_short_and_long() {
+        let output = "src/lib.rs:4:9: warning[unused_variables]: unused variable: `x`\n\
+                      error[E0308]: mismatched types\n\
+                      \x20 --> src/main.rs:10:18\n\
+                      \x20  |\n\
+                      10 |     let n: u32 = \"a\";\n\
+                      error: could not compile `demo`\n";
+        let problems = ProblemMatcher::new().parse(output);
+        assert_eq!(
+            locations(&problems),
+            vec![
+                problem("src/lib.rs", 4, 9, ProblemSeverity::Warning),
+                problem("src/main.rs", 10, 18, ProblemSeverity::Error),
+            ]
+        );
+        assert_eq!(problems[0].code.as_deref(), Some("unused_variables"));
+        assert_eq!(problems[1].message, "mismatched types");
+        assert_eq!(problems[1].code.as_deref(), Some("E0308"));
+    }
+
+    #[test]
+    fn test_parse_tsc() {
+        let output =
+            "src/app.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n";
+        let problems = ProblemMatcher::new().parse(output);
+        assert_eq!(
+            locations(&problems),
+            vec![problem("src/app.ts", 3, 7, ProblemSeverity::Error)]
+        );
+        assert_eq!(problems[0].code.as_deref(), Some("TS2322"));
+    }
+}
@@ -307,6 +307,72 @@ impl SystemResources {
             ))
         }
     }
+
+    /// Get the resident memory and CPU time used so far by a running process
+    pub fn process_usage(pid: u32) -> io::Result<ProcessUsage> {
+        #[cfg(target_os = "linux")]
+        {
+            Self::linux_process_usage(pid)
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = pid;
+            // TODO: Implement for other platforms
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Process usage not implemented for this platform",
+            ))
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn linux_process_usage(pid: u32) -> io::Result<ProcessUsage> {
+        let invalid = |what: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Could not parse {} for process {}", what, pid),
+            )
+        };
+
+        // Format: "VmRSS:     12345 kB"
+        let status = fs::read_to_string(format!("/proc/{}/status", pid))?;
+        let rss_kb = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .ok_or_else(|| invalid("VmRSS"))?;
+
+        // The command name may contain spaces, so split after its closing paren.
+        // utime and stime are fields 14 and 15, i.e. 11 and 12 after the paren.
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid))?;
+        let fields: Vec<&str> = stat
+            .rsplit_once(')')
+            .map(|(_, rest)| rest.split_whitespace().collect())
+            .unwrap_or_default();
+        let ticks = |index: usize| fields.get(index).and_then(|f| f.parse::<u64>().ok());
+        let (utime, stime) = ticks(11)
+            .zip(ticks(12))
+            .ok_or_else(|| invalid("CPU time"))?;
+
+        let ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+        let cpu_millis = (utime + stime) * 1000 / ticks_per_sec;
+
+        Ok(ProcessUsage {
+            rss_bytes: rss_kb * 1024,
+            cpu_time: std::time::Duration::from_millis(cpu_millis),
+        })
+    }
+}
+
+/// Resource usage of a running process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessUsage {
+    /// Resident memory in bytes
+    pub rss_bytes: u64,
+    /// Total user + system CPU time consumed
+    pub cpu_time: std::time::Duration,
 }
 
 /// Apply memory limit via setrlimit (fallback method)
@@ -397,6 +463,13 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_system_resources_process_usage() {
+        let usage = SystemResources::process_usage(std::process::id()).unwrap();
+        assert!(usage.rss_bytes > 0);
+    }
+
     #[test]
     fn test_process_limits_apply_to_command_disabled() {
         let limits = ProcessLimits::unlimited();
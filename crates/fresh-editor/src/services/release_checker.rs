@@ -5,8 +5,12 @@
 //! - Detect the installation method (Homebrew, npm, cargo, etc.) based on executable path
 //! - Provide appropriate update commands based on installation method
 //! - Daily update checking (debounced via stamp file)
+//! - Update channels: stable releases only, or also beta / nightly prereleases
 
 use super::time_source::SharedTimeSource;
+use crate::config::{TelemetryConfig, UpdateChannel};
+use serde::Deserialize;
+use std::cmp::Ordering;
 use std::env;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, TryRecvError};
@@ -19,6 +23,9 @@ pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// Default GitHub releases API URL for the fresh editor
 pub const DEFAULT_RELEASES_URL: &str = "https://api.github.com/repos/sinelaw/fresh/releases/latest";
 
+/// How many recent releases the beta and nightly channels look through
+const RELEASE_LIST_LIMIT: u32 = 30;
+
 /// Installation method detection result
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InstallMethod {
@@ -61,6 +68,40 @@ pub struct ReleaseCheckResult {
     pub install_method: InstallMethod,
 }
 
+/// A published release, as far as updating is concerned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// Version without the `v` prefix of the tag
+    pub version: String,
+    /// Files attached to the release
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// A file attached to a release
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    #[serde(rename = "browser_download_url")]
+    pub download_url: String,
+}
+
+impl Release {
+    /// Find an attached file by name
+    pub fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// Release fields of the GitHub API response
+#[derive(Deserialize)]
+struct ApiRelease {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
 /// Handle to a background update check (one-shot)
 ///
 /// Use `try_get_result` to check if the result is ready without blocking.
@@ -162,12 +203,15 @@ impl UpdateChecker {
 /// Start an update checker that runs once at startup.
 ///
 /// The check respects daily debouncing via the stamp file - if already
-/// checked today, no network request is made.
+/// checked today, no network request is made. The same daily check reports
+/// the telemetry allowed by `telemetry`.
 /// Results are available via `poll_result()` on the returned handle.
 pub fn start_periodic_update_check(
     releases_url: &str,
     time_source: SharedTimeSource,
     data_dir: PathBuf,
+    telemetry: TelemetryConfig,
+    channel: UpdateChannel,
 ) -> UpdateChecker {
     tracing::debug!("Starting update checker");
     let url = releases_url.to_string();
@@ -177,8 +221,8 @@ pub fn start_periodic_update_check(
         if let Some(unique_id) =
             super::telemetry::should_run_daily_check(time_source.as_ref(), &data_dir)
         {
-            super::telemetry::track_open(&unique_id);
-            let result = check_for_update(&url);
+            super::telemetry::report(&telemetry, &data_dir, &unique_id);
+            let result = check_for_update(&url, channel);
             let _ = tx.send(result);
         }
     });
@@ -197,9 +241,11 @@ pub fn start_periodic_update_check_with_interval(
     _check_interval: Duration,
     time_source: SharedTimeSource,
     data_dir: PathBuf,
+    telemetry: TelemetryConfig,
+    channel: UpdateChannel,
 ) -> UpdateChecker {
     // check_interval is ignored - debouncing is handled by stamp file
-    start_periodic_update_check(releases_url, time_source, data_dir)
+    start_periodic_update_check(releases_url, time_source, data_dir, telemetry, channel)
 }
 
 /// Start a background update check
@@ -211,6 +257,8 @@ pub fn start_update_check(
     releases_url: &str,
     time_source: SharedTimeSource,
     data_dir: PathBuf,
+    telemetry: TelemetryConfig,
+    channel: UpdateChannel,
 ) -> UpdateCheckHandle {
     tracing::debug!("Starting background update check");
     let url = releases_url.to_string();
@@ -220,8 +268,8 @@ pub fn start_update_check(
         if let Some(unique_id) =
             super::telemetry::should_run_daily_check(time_source.as_ref(), &data_dir)
         {
-            super::telemetry::track_open(&unique_id);
-            let result = check_for_update(&url);
+            super::telemetry::report(&telemetry, &data_dir, &unique_id);
+            let result = check_for_update(&url, channel);
             let _ = tx.send(result);
         }
     });
@@ -235,6 +283,30 @@ pub fn start_update_check(
 /// Fetches release information from the provided URL.
 pub fn fetch_latest_version(url: &str) -> Result<String, String> {
     tracing::debug!("Fetching latest version from {}", url);
+    let body = fetch_releases_json(url)?;
+    let version = parse_version_from_json(&body)?;
+    tracing::debug!("Latest version: {}", version);
+    Ok(version)
+}
+
+/// The releases endpoint a channel reads. GitHub's `latest` endpoint only
+/// returns stable releases, so beta and nightly read the release list instead.
+pub fn releases_url_for_channel(releases_url: &str, channel: UpdateChannel) -> String {
+    match (channel, releases_url.strip_suffix("/latest")) {
+        (UpdateChannel::Stable, _) | (_, None) => releases_url.to_string(),
+        (_, Some(list_url)) => format!("{}?per_page={}", list_url, RELEASE_LIST_LIMIT),
+    }
+}
+
+/// Fetch the newest release on `channel`
+pub fn fetch_latest_release(releases_url: &str, channel: UpdateChannel) -> Result<Release, String> {
+    let url = releases_url_for_channel(releases_url, channel);
+    tracing::debug!("Fetching latest {} release from {}", channel.as_str(), url);
+    let body = fetch_releases_json(&url)?;
+    parse_latest_release(&body, channel)
+}
+
+fn fetch_releases_json(url: &str) -> Result<String, String> {
     let agent = ureq::Agent::config_builder()
         .timeout_global(Some(Duration::from_secs(15)))
         .build()
@@ -249,14 +321,51 @@ pub fn fetch_latest_version(url: &str) -> Result<String, String> {
             format!("HTTP request failed: {}", e)
         })?;
 
-    let body = response
+    response
         .into_body()
         .read_to_string()
-        .map_err(|e| format!("Failed to read response body: {}", e))?;
+        .map_err(|e| format!("Failed to read response body: {}", e))
+}
 
-    let version = parse_version_from_json(&body)?;
-    tracing::debug!("Latest version: {}", version);
-    Ok(version)
+/// Pick the newest release on `channel` from a GitHub API response, which is
+/// either a single release or a list of them. Drafts are never offered.
+fn parse_latest_release(json: &str, channel: UpdateChannel) -> Result<Release, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid release JSON: {}", e))?;
+    let releases: Vec<ApiRelease> = match value {
+        serde_json::Value::Array(_) => serde_json::from_value(value),
+        _ => serde_json::from_value(value).map(|release| vec![release]),
+    }
+    .map_err(|e| format!("Invalid release JSON: {}", e))?;
+
+    releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .map(|release| Release {
+            version: release
+                .tag_name
+                .strip_prefix('v')
+                .unwrap_or(&release.tag_name)
+                .to_string(),
+            assets: release.assets,
+        })
+        .filter(|release| is_on_channel(&release.version, channel))
+        .max_by(|a, b| compare_versions(&a.version, &b.version).unwrap_or(Ordering::Equal))
+        .ok_or_else(|| format!("No release found on the {} channel", channel.as_str()))
+}
+
+/// Whether a version is offered on `channel`: stable versions are on every
+/// channel, `-beta`/`-rc` prereleases on beta and nightly, and any other
+/// prerelease (`-nightly.N`, `-alpha`, ...) on nightly only
+pub fn is_on_channel(version: &str, channel: UpdateChannel) -> bool {
+    let Some((_, pre)) = version.split_once('-') else {
+        return true;
+    };
+    match channel {
+        UpdateChannel::Stable => false,
+        UpdateChannel::Beta => pre.starts_with("beta") || pre.starts_with("rc"),
+        UpdateChannel::Nightly => true,
+    }
 }
 
 /// Parse version from GitHub API JSON response
@@ -344,32 +453,78 @@ fn is_arch_linux() -> bool {
 /// Compare two semantic versions
 /// Returns true if `latest` is newer than `current`
 pub fn is_newer_version(current: &str, latest: &str) -> bool {
-    let parse_version = |v: &str| -> Option<(u32, u32, u32)> {
-        let parts: Vec<&str> = v.split('.').collect();
-        if parts.len() >= 3 {
-            Some((
+    compare_versions(latest, current) == Some(Ordering::Greater)
+}
+
+/// Order two semantic versions, including prerelease precedence:
+/// `0.2.0-beta.1 < 0.2.0-beta.2 < 0.2.0-rc.1 < 0.2.0`.
+/// Returns `None` if either version can't be parsed.
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let parse_version = |v: &str| -> Option<((u32, u32, u32), Option<String>)> {
+        let v = v.split('+').next()?;
+        let (core, pre) = match v.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (v, None),
+        };
+        let parts: Vec<&str> = core.split('.').collect();
+        let numbers = match parts.len() {
+            3 => (
                 parts[0].parse().ok()?,
                 parts[1].parse().ok()?,
-                parts[2].split('-').next()?.parse().ok()?,
-            ))
-        } else if parts.len() == 2 {
-            Some((parts[0].parse().ok()?, parts[1].parse().ok()?, 0))
-        } else {
-            None
-        }
+                parts[2].parse().ok()?,
+            ),
+            2 => (parts[0].parse().ok()?, parts[1].parse().ok()?, 0),
+            _ => return None,
+        };
+        Some((numbers, pre))
     };
 
-    match (parse_version(current), parse_version(latest)) {
-        (Some((c_major, c_minor, c_patch)), Some((l_major, l_minor, l_patch))) => {
-            (l_major, l_minor, l_patch) > (c_major, c_minor, c_patch)
+    let (a_numbers, a_pre) = parse_version(a)?;
+    let (b_numbers, b_pre) = parse_version(b)?;
+    Some(
+        a_numbers
+            .cmp(&b_numbers)
+            .then_with(|| match (a_pre, b_pre) {
+                (None, None) => Ordering::Equal,
+                // A release is newer than any of its prereleases
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_prerelease(&a, &b),
+            }),
+    )
+}
+
+/// Compare dot-separated prerelease identifiers: numeric ones numerically and
+/// below alphanumeric ones, with a shorter prefix first (semver §11)
+fn compare_prerelease(a: &str, b: &str) -> Ordering {
+    let mut a_ids = a.split('.');
+    let mut b_ids = b.split('.');
+    loop {
+        match (a_ids.next(), b_ids.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => {
+                let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+                    (Ok(a), Ok(b)) => a.cmp(&b),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => a.cmp(b),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
         }
-        _ => false,
     }
 }
 
-/// Check for a new release (blocking)
-pub fn check_for_update(releases_url: &str) -> Result<ReleaseCheckResult, String> {
-    let latest_version = fetch_latest_version(releases_url)?;
+/// Check for a new release on `channel` (blocking)
+pub fn check_for_update(
+    releases_url: &str,
+    channel: UpdateChannel,
+) -> Result<ReleaseCheckResult, String> {
+    let latest_version = fetch_latest_release(releases_url, channel)?.version;
     let install_method = detect_install_method();
     let update_available = is_newer_version(CURRENT_VERSION, &latest_version);
 
@@ -406,6 +561,13 @@ mod tests {
             ("1.0.0", "0.1.26", false),       // older major
             ("0.1.26-alpha", "0.1.27", true), // prerelease current
             ("0.1.26", "0.1.27-beta", true),  // prerelease latest
+            ("0.2.0-beta.1", "0.2.0", true),  // release after its prerelease
+            ("0.2.0", "0.2.0-rc.1", false),   // prerelease of the same release
+            ("0.2.0-beta.1", "0.2.0-beta.2", true),
+            ("0.2.0-beta.2", "0.2.0-beta.10", true), // numeric identifiers
+            ("0.2.0-beta.2", "0.2.0-rc.1", true),
+            ("0.2.0-beta", "0.2.0-beta.1", true), // longer prerelease
+            ("0.2.0-rc.1", "0.2.0-beta.5", false),
         ];
         for (current, latest, expected) in cases {
             assert_eq!(
@@ -475,6 +637,62 @@ mod tests {
         assert!(is_newer_version(CURRENT_VERSION, &version));
     }
 
+    #[test]
+    fn test_latest_release_per_channel() {
+        let json = r#"[
+            {"tag_name": "v0.3.0-nightly.20261015", "draft": false},
+            {"tag_name": "v0.3.0-beta.2", "draft": false},
+            {"tag_name": "v0.4.0", "draft": true},
+            {"tag_name": "v0.2.1", "draft": false, "assets": [
+                {"name": "fresh-editor-x86_64-unknown-linux-gnu.tar.xz",
+                 "browser_download_url": "https://example.com/fresh.tar.xz"}
+            ]},
+            {"tag_name": "v0.3.0-beta.10", "draft": false}
+        ]"#;
+        let cases = [
+            (UpdateChannel::Stable, "0.2.1"),
+            (UpdateChannel::Beta, "0.3.0-beta.10"),
+            (UpdateChannel::Nightly, "0.3.0-nightly.20261015"),
+        ];
+        for (channel, expected) in cases {
+            let release = parse_latest_release(json, channel).unwrap();
+            assert_eq!(release.version, expected, "{:?}", channel);
+        }
+
+        let stable = parse_latest_release(json, UpdateChannel::Stable).unwrap();
+        assert_eq!(
+            stable
+                .asset("fresh-editor-x86_64-unknown-linux-gnu.tar.xz")
+                .map(|a| a.download_url.as_str()),
+            Some("https://example.com/fresh.tar.xz")
+        );
+
+        // The `latest` endpoint returns a single object
+        let single = r#"{"tag_name": "v0.2.1"}"#;
+        assert_eq!(
+            parse_latest_release(single, UpdateChannel::Nightly)
+                .unwrap()
+                .version,
+            "0.2.1"
+        );
+        assert!(
+            parse_latest_release(r#"[{"tag_name": "v0.3.0-beta.1"}]"#, UpdateChannel::Stable)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_releases_url_for_channel() {
+        assert_eq!(
+            releases_url_for_channel(DEFAULT_RELEASES_URL, UpdateChannel::Stable),
+            DEFAULT_RELEASES_URL
+        );
+        assert_eq!(
+            releases_url_for_channel(DEFAULT_RELEASES_URL, UpdateChannel::Beta),
+            "https://api.github.com/repos/sinelaw/fresh/releases?per_page=30"
+        );
+    }
+
     #[test]
     fn test_current_version_is_valid() {
         let parts: Vec<&str> = CURRENT_VERSION.split('.').collect();
@@ -530,14 +748,27 @@ mod tests {
         (stop_tx, url)
     }
 
+    /// Telemetry written to the test's data directory rather than sent
+    fn local_telemetry() -> TelemetryConfig {
+        TelemetryConfig {
+            local_only: true,
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_update_checker_detects_new_version() {
         let (stop_tx, url) = start_mock_release_server("99.0.0");
         let time_source = super::super::time_source::TestTimeSource::shared();
         let temp_dir = tempfile::tempdir().unwrap();
 
-        let mut checker =
-            start_periodic_update_check(&url, time_source, temp_dir.path().to_path_buf());
+        let mut checker = start_periodic_update_check(
+            &url,
+            time_source,
+            temp_dir.path().to_path_buf(),
+            local_telemetry(),
+            UpdateChannel::Stable,
+        );
 
         // Wait for result
         let start = std::time::Instant::now();
@@ -560,8 +791,13 @@ mod tests {
         let time_source = super::super::time_source::TestTimeSource::shared();
         let temp_dir = tempfile::tempdir().unwrap();
 
-        let mut checker =
-            start_periodic_update_check(&url, time_source, temp_dir.path().to_path_buf());
+        let mut checker = start_periodic_update_check(
+            &url,
+            time_source,
+            temp_dir.path().to_path_buf(),
+            local_telemetry(),
+            UpdateChannel::Stable,
+        );
 
         // Wait for result
         let start = std::time::Instant::now();
@@ -585,7 +821,13 @@ mod tests {
         let time_source = super::super::time_source::TestTimeSource::shared();
         let temp_dir = tempfile::tempdir().unwrap();
 
-        let checker = start_periodic_update_check(&url, time_source, temp_dir.path().to_path_buf());
+        let checker = start_periodic_update_check(
+            &url,
+            time_source,
+            temp_dir.path().to_path_buf(),
+            local_telemetry(),
+            UpdateChannel::Stable,
+        );
 
         // Immediately check (before result arrives)
         assert!(!checker.is_update_available());
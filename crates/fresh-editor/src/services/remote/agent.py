@@ -106,21 +106,25 @@ def cmd_write(id, p):
 
 
 def cmd_sudo_write(id, p):
-    """Write file contents using sudo (for root-owned files).
+    """Write file contents with elevated rights (for root-owned files).
 
-    Uses sudo tee to write the file. Preserves original permissions and ownership.
+    Pipes the data through `<program> tee` in one elevated command. An existing
+    file keeps its permissions and ownership; a new file gets the given ones.
     """
     path = validate_path(p["path"])
     data = unb64(p["data"])
+    program = p.get("program", "sudo")
 
-    # Get original metadata to preserve permissions
     mode = p.get("mode")
     uid = p.get("uid")
     gid = p.get("gid")
 
-    # Use sudo tee to write the file
+    # Ownership first: chown clears setuid/setgid bits set by chmod
+    script = 'tee -- "$1" > /dev/null'
+    if not os.path.exists(path) and None not in (mode, uid, gid):
+        script += ' && chown "$3" "$1" && chmod "$2" "$1"'
     proc = subprocess.Popen(
-        ["sudo", "tee", path],
+        [program, "sh", "-c", script, "sh", path, f"{mode or 0:o}", f"{uid}:{gid}"],
         stdin=subprocess.PIPE,
         stdout=subprocess.DEVNULL,
         stderr=subprocess.PIPE,
@@ -128,15 +132,7 @@ def cmd_sudo_write(id, p):
     _, stderr = proc.communicate(data)
 
     if proc.returncode != 0:
-        raise RuntimeError(f"sudo tee failed: {stderr.decode().strip()}")
-
-    # Restore permissions and ownership if provided
-    if mode is not None:
-        subprocess.run(["sudo", "chmod", f"{mode:o}", path], check=True,
-                       capture_output=True)
-    if uid is not None and gid is not None:
-        subprocess.run(["sudo", "chown", f"{uid}:{gid}", path], check=True,
-                       capture_output=True)
+        raise RuntimeError(f"{program} tee failed: {stderr.decode().strip()}")
 
     send(id, r={"size": len(data)})
 
@@ -160,6 +156,9 @@ def cmd_stat(id, p):
             "dir": stat.S_ISDIR(st.st_mode),
             "file": stat.S_ISREG(st.st_mode),
             "link": is_link,
+            "nlink": st.st_nlink,
+            "dev": st.st_dev,
+            "ino": st.st_ino,
         },
     )
 
@@ -176,11 +175,16 @@ def cmd_ls(id, p):
 
             # For symlinks, check target type
             target_is_dir = False
+            target = None
             if is_link:
                 try:
                     target_is_dir = entry.is_dir(follow_symlinks=True)
                 except OSError:
                     pass
+                try:
+                    target = os.readlink(entry.path)
+                except OSError:
+                    pass
 
             entries.append(
                 {
@@ -190,6 +194,7 @@ def cmd_ls(id, p):
                     "file": entry.is_file(follow_symlinks=True),
                     "link": is_link,
                     "link_dir": target_is_dir,
+                    "target": target,
                     "size": st.st_size,
                     "mtime": int(st.st_mtime),
                     "mode": st.st_mode,
@@ -245,6 +250,14 @@ def cmd_realpath(id, p):
     send(id, r={"path": validate_path(p["path"])})
 
 
+def cmd_readlink(id, p):
+    """Read the path a symlink points to."""
+    # Resolve the parent directory, but not the link itself
+    path = os.path.abspath(os.path.expanduser(p["path"]))
+    link = os.path.join(validate_path(os.path.dirname(path)), os.path.basename(path))
+    send(id, r={"target": os.readlink(link)})
+
+
 def cmd_chmod(id, p):
     """Change file permissions."""
     os.chmod(validate_path(p["path"]), p["mode"])
@@ -348,11 +361,13 @@ def cmd_exec(id, p):
     cwd = validate_path(p["cwd"]) if p.get("cwd") else None
     cmd = p["cmd"]
     args = p.get("args", [])
+    stdin_data = unb64(p["in"]) if "in" in p else None
 
     try:
         proc = subprocess.Popen(
             [cmd] + args,
             cwd=cwd,
+            stdin=subprocess.PIPE if stdin_data is not None else None,
             stdout=subprocess.PIPE,
             stderr=subprocess.PIPE,
         )
@@ -366,6 +381,23 @@ def cmd_exec(id, p):
     with lock:
         procs[id] = proc
 
+    def feed_input():
+        """Write the request's input to stdin, then close it."""
+        try:
+            proc.stdin.write(stdin_data)
+        except OSError:
+            pass  # Process exited early; its exit code tells why
+        finally:
+            try:
+                proc.stdin.close()
+            except OSError:
+                pass
+
+    feeder = None
+    if stdin_data is not None:
+        feeder = threading.Thread(target=feed_input, daemon=True)
+        feeder.start()
+
     def stream_output():
         """Stream process output in a background thread."""
         try:
@@ -391,6 +423,10 @@ def cmd_exec(id, p):
                         send(id, d={key: b64(data)})
 
             # Drain any remaining output
+            if feeder is not None:
+                # stdin is closed by now; keep communicate() from flushing it
+                feeder.join(timeout=5)
+                proc.stdin = None
             out, err = proc.communicate(timeout=5)
             if out:
                 send(id, d={"out": b64(out)})
@@ -452,6 +488,7 @@ METHODS = {
     "mv": cmd_mv,
     "cp": cmd_cp,
     "realpath": cmd_realpath,
+    "readlink": cmd_readlink,
     "chmod": cmd_chmod,
     "append": cmd_append,
     "truncate": cmd_truncate,
@@ -5,7 +5,7 @@
 use crate::services::remote::protocol::{AgentRequest, AgentResponse};
 use std::collections::HashMap;
 use std::io;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
 use tokio::sync::{mpsc, oneshot};
@@ -40,16 +40,25 @@ struct PendingRequest {
     result_tx: oneshot::Sender<Result<serde_json::Value, String>>,
 }
 
-/// Communication channel with the remote agent
-pub struct AgentChannel {
+/// Write side and liveness flag of one SSH session.
+///
+/// Replaced wholesale when the connection is re-established, so requests
+/// issued after a reconnect go to the new agent.
+struct Transport {
     /// Sender to the write task
     write_tx: mpsc::Sender<String>,
+    /// Whether this transport is still connected
+    connected: Arc<AtomicBool>,
+}
+
+/// Communication channel with the remote agent
+pub struct AgentChannel {
+    /// Current transport (swapped on reconnect)
+    transport: Mutex<Transport>,
     /// Pending requests awaiting responses
     pending: Arc<Mutex<HashMap<u64, PendingRequest>>>,
     /// Next request ID
     next_id: AtomicU64,
-    /// Whether the channel is connected
-    connected: Arc<std::sync::atomic::AtomicBool>,
     /// Runtime handle for blocking operations
     runtime_handle: tokio::runtime::Handle,
 }
@@ -59,14 +68,44 @@ impl AgentChannel {
     ///
     /// Must be called from within a Tokio runtime context.
     pub fn new(
-        mut reader: tokio::io::BufReader<tokio::process::ChildStdout>,
-        mut writer: tokio::process::ChildStdin,
+        reader: tokio::io::BufReader<tokio::process::ChildStdout>,
+        writer: tokio::process::ChildStdin,
     ) -> Self {
         let pending: Arc<Mutex<HashMap<u64, PendingRequest>>> =
             Arc::new(Mutex::new(HashMap::new()));
-        let connected = Arc::new(std::sync::atomic::AtomicBool::new(true));
         // Capture the runtime handle for later use in blocking operations
         let runtime_handle = tokio::runtime::Handle::current();
+        let transport = Self::spawn_transport(reader, writer, pending.clone());
+
+        Self {
+            transport: Mutex::new(transport),
+            pending,
+            next_id: AtomicU64::new(1),
+            runtime_handle,
+        }
+    }
+
+    /// Replace the underlying connection with a freshly bootstrapped agent.
+    ///
+    /// Used after an SSH reconnect; existing `Arc<AgentChannel>` holders
+    /// (filesystem, process spawner) keep working without being rebuilt.
+    pub fn replace_transport(
+        &self,
+        reader: tokio::io::BufReader<tokio::process::ChildStdout>,
+        writer: tokio::process::ChildStdin,
+    ) {
+        let _guard = self.runtime_handle.enter();
+        let transport = Self::spawn_transport(reader, writer, self.pending.clone());
+        *self.transport.lock().unwrap() = transport;
+    }
+
+    /// Spawn the read/write tasks for one SSH session
+    fn spawn_transport(
+        mut reader: tokio::io::BufReader<tokio::process::ChildStdout>,
+        mut writer: tokio::process::ChildStdin,
+        pending: Arc<Mutex<HashMap<u64, PendingRequest>>>,
+    ) -> Transport {
+        let connected = Arc::new(AtomicBool::new(true));
 
         // Channel for outgoing requests
         let (write_tx, mut write_rx) = mpsc::channel::<String>(64);
@@ -87,7 +126,6 @@ impl AgentChannel {
         });
 
         // Spawn read task
-        let pending_read = pending.clone();
         let connected_read = connected.clone();
         tokio::spawn(async move {
             let mut line = String::new();
@@ -101,7 +139,7 @@ impl AgentChannel {
                     }
                     Ok(_) => {
                         if let Ok(resp) = serde_json::from_str::<AgentResponse>(&line) {
-                            Self::handle_response(&pending_read, resp);
+                            Self::handle_response(&pending, resp);
                         }
                     }
                     Err(_) => {
@@ -112,18 +150,15 @@ impl AgentChannel {
             }
 
             // Clean up pending requests on disconnect
-            let mut pending = pending_read.lock().unwrap();
+            let mut pending = pending.lock().unwrap();
             for (_, req) in pending.drain() {
                 let _ = req.result_tx.send(Err("connection closed".to_string()));
             }
         });
 
-        Self {
+        Transport {
             write_tx,
-            pending,
-            next_id: AtomicU64::new(1),
             connected,
-            runtime_handle,
         }
     }
 
@@ -153,7 +188,11 @@ impl AgentChannel {
 
     /// Check if the channel is connected
     pub fn is_connected(&self) -> bool {
-        self.connected.load(Ordering::SeqCst)
+        self.transport
+            .lock()
+            .unwrap()
+            .connected
+            .load(Ordering::SeqCst)
     }
 
     /// Send a request and wait for the final result (ignoring streaming data)
@@ -186,9 +225,13 @@ impl AgentChannel {
         ),
         ChannelError,
     > {
-        if !self.is_connected() {
-            return Err(ChannelError::ChannelClosed);
-        }
+        let write_tx = {
+            let transport = self.transport.lock().unwrap();
+            if !transport.connected.load(Ordering::SeqCst) {
+                return Err(ChannelError::ChannelClosed);
+            }
+            transport.write_tx.clone()
+        };
 
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
 
@@ -204,7 +247,7 @@ impl AgentChannel {
 
         // Build and send request
         let req = AgentRequest::new(id, method, params);
-        self.write_tx
+        write_tx
             .send(req.to_json_line())
             .await
             .map_err(|_| ChannelError::ChannelClosed)?;
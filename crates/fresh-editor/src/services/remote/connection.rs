@@ -4,11 +4,14 @@
 
 use crate::services::remote::channel::AgentChannel;
 use crate::services::remote::protocol::AgentResponse;
+use crate::services::remote::ssh_config::SshHostConfig;
 use crate::services::remote::AGENT_SOURCE;
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, Command};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
 
 /// Error type for SSH connection
 #[derive(Debug, thiserror::Error)]
@@ -30,12 +33,17 @@ pub enum SshError {
 }
 
 /// SSH connection parameters
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ConnectionParams {
+    /// Remote user; empty means "let ssh decide" (SSH config `User` or local login)
     pub user: String,
     pub host: String,
     pub port: Option<u16>,
     pub identity_file: Option<PathBuf>,
+    /// Jump host(s) passed to `ssh -J`
+    pub proxy_jump: Option<String>,
+    /// ssh-agent socket to authenticate with (defaults to `$SSH_AUTH_SOCK`)
+    pub identity_agent: Option<PathBuf>,
 }
 
 impl ConnectionParams {
@@ -60,139 +68,98 @@ impl ConnectionParams {
             user: user.to_string(),
             host: host.to_string(),
             port,
-            identity_file: None,
+            ..Default::default()
         })
     }
 
+    /// Fill in settings from the user's SSH config.
+    ///
+    /// Values given explicitly (e.g. on the command line) take precedence,
+    /// matching OpenSSH's own "first obtained value wins" rule. The host alias
+    /// is kept as-is so ssh resolves `HostName` itself.
+    pub fn apply_ssh_config(&mut self, config: &SshHostConfig) {
+        if self.user.is_empty() {
+            if let Some(ref user) = config.user {
+                self.user = user.clone();
+            }
+        }
+        if self.port.is_none() {
+            self.port = config.port;
+        }
+        if self.identity_file.is_none() {
+            self.identity_file = config.identity_files.iter().find(|p| p.exists()).cloned();
+        }
+        if self.proxy_jump.is_none() {
+            self.proxy_jump = config.proxy_jump.clone();
+        }
+        if self.identity_agent.is_none() {
+            self.identity_agent = config
+                .identity_agent
+                .clone()
+                .or_else(|| std::env::var_os("SSH_AUTH_SOCK").map(PathBuf::from));
+        }
+    }
+
+    /// Destination argument for ssh: `user@host`, or just `host` if no user is known
+    fn destination(&self) -> String {
+        if self.user.is_empty() {
+            self.host.clone()
+        } else {
+            format!("{}@{}", self.user, self.host)
+        }
+    }
+
     /// Format as connection string
     pub fn to_string(&self) -> String {
         if let Some(port) = self.port {
-            format!("{}@{}:{}", self.user, self.host, port)
+            format!("{}:{}", self.destination(), port)
         } else {
-            format!("{}@{}", self.user, self.host)
+            self.destination()
         }
     }
 }
 
 /// Active SSH connection with bootstrapped agent
 pub struct SshConnection {
-    /// SSH child process
-    process: Child,
+    /// SSH child process (replaced on reconnect)
+    process: Arc<Mutex<Child>>,
     /// Communication channel with agent (wrapped in Arc for sharing)
-    channel: std::sync::Arc<AgentChannel>,
+    channel: Arc<AgentChannel>,
     /// Connection parameters
     params: ConnectionParams,
+    /// Background task that re-establishes the connection when it drops
+    reconnect_task: tokio::task::JoinHandle<()>,
 }
 
 impl SshConnection {
     /// Establish a new SSH connection and bootstrap the agent
+    ///
+    /// Must be called from within a Tokio runtime context. If the connection
+    /// later drops, it is re-established in the background with exponential
+    /// backoff; the returned channel stays valid across reconnects.
     pub async fn connect(params: ConnectionParams) -> Result<Self, SshError> {
-        let mut cmd = Command::new("ssh");
-
-        // Don't check host key strictly for ease of use
-        cmd.arg("-o").arg("StrictHostKeyChecking=accept-new");
-        // Allow password prompts - SSH will use the terminal for this
-        // Note: We inherit stderr so SSH can prompt for password if needed
-
-        if let Some(port) = params.port {
-            cmd.arg("-p").arg(port.to_string());
-        }
-
-        if let Some(ref identity) = params.identity_file {
-            cmd.arg("-i").arg(identity);
-        }
-
-        cmd.arg(format!("{}@{}", params.user, params.host));
-
-        // Bootstrap the agent using Python itself to read the exact byte count.
-        // This avoids requiring bash or other shell utilities on the remote.
-        // Python reads exactly N bytes (the agent code), execs it, and the agent
-        // then continues reading from stdin for protocol messages.
-        //
-        // Note: SSH passes the remote command through a shell, so we need to
-        // properly quote the Python code. We use double quotes for the outer
-        // shell and avoid problematic characters in the Python code.
-        let agent_len = AGENT_SOURCE.len();
-        let bootstrap = format!(
-            "python3 -u -c \"import sys;exec(sys.stdin.read({}))\"",
-            agent_len
-        );
-        cmd.arg(bootstrap);
-
-        cmd.stdin(Stdio::piped());
-        cmd.stdout(Stdio::piped());
-        // Inherit stderr so SSH can prompt for password on the terminal
-        cmd.stderr(Stdio::inherit());
-
-        let mut child = cmd.spawn()?;
-
-        // Get handles
-        let mut stdin = child
-            .stdin
-            .take()
-            .ok_or_else(|| SshError::AgentStartFailed("failed to get stdin".to_string()))?;
-        let stdout = child
-            .stdout
-            .take()
-            .ok_or_else(|| SshError::AgentStartFailed("failed to get stdout".to_string()))?;
-        // Note: stderr is inherited so SSH can prompt for password on the terminal
-
-        // Send the agent code (exact byte count)
-        stdin.write_all(AGENT_SOURCE.as_bytes()).await?;
-        stdin.flush().await?;
-
-        // Create buffered reader for stdout
-        let mut reader = BufReader::new(stdout);
-
-        // Wait for ready message from agent
-        // No timeout needed - all failure modes (auth failure, network issues, etc.)
-        // result in SSH exiting and us getting EOF. User can Ctrl+C if needed.
-        let mut ready_line = String::new();
-        match reader.read_line(&mut ready_line).await {
-            Ok(0) => {
-                return Err(SshError::AgentStartFailed(
-                    "connection closed (check terminal for SSH errors)".to_string(),
-                ));
-            }
-            Ok(_) => {}
-            Err(e) => return Err(SshError::AgentStartFailed(format!("read error: {}", e))),
-        }
-
-        let ready: AgentResponse = serde_json::from_str(&ready_line).map_err(|e| {
-            SshError::AgentStartFailed(format!(
-                "invalid ready message '{}': {}",
-                ready_line.trim(),
-                e
-            ))
-        })?;
-
-        if !ready.is_ready() {
-            return Err(SshError::AgentStartFailed(
-                "agent did not send ready message".to_string(),
-            ));
-        }
-
-        // Check protocol version
-        let version = ready.version.unwrap_or(0);
-        if version != crate::services::remote::protocol::PROTOCOL_VERSION {
-            return Err(SshError::VersionMismatch {
-                expected: crate::services::remote::protocol::PROTOCOL_VERSION,
-                got: version,
-            });
-        }
-
-        // Create channel (takes ownership of stdin for writing)
-        let channel = std::sync::Arc::new(AgentChannel::new(reader, stdin));
+        // Interactive auth is allowed for the first connection only: the
+        // terminal is still in normal mode so ssh can prompt for a password.
+        let (child, reader, stdin) = start_agent(&params, false).await?;
+        let channel = Arc::new(AgentChannel::new(reader, stdin));
+        let process = Arc::new(Mutex::new(child));
+
+        let reconnect_task = tokio::spawn(reconnect_loop(
+            params.clone(),
+            channel.clone(),
+            process.clone(),
+        ));
 
         Ok(Self {
-            process: child,
+            process,
             channel,
             params,
+            reconnect_task,
         })
     }
 
     /// Get the communication channel as an Arc for sharing
-    pub fn channel(&self) -> std::sync::Arc<AgentChannel> {
+    pub fn channel(&self) -> Arc<AgentChannel> {
         self.channel.clone()
     }
 
@@ -214,9 +181,216 @@ impl SshConnection {
 
 impl Drop for SshConnection {
     fn drop(&mut self) {
+        self.reconnect_task.abort();
         // Try to kill the SSH process gracefully
-        let _ = self.process.start_kill();
+        if let Ok(mut process) = self.process.lock() {
+            let _ = process.start_kill();
+        }
+    }
+}
+
+/// Exponential backoff schedule for reconnect attempts
+#[derive(Debug, Clone)]
+pub struct ReconnectBackoff {
+    attempt: u32,
+    initial: Duration,
+    max: Duration,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1), Duration::from_secs(30))
+    }
+}
+
+impl ReconnectBackoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            attempt: 0,
+            initial,
+            max,
+        }
+    }
+
+    /// Delay before the next attempt (doubles each time, capped at `max`)
+    pub fn next_delay(&mut self) -> Duration {
+        let factor = 1u32.checked_shl(self.attempt).unwrap_or(u32::MAX);
+        self.attempt = self.attempt.saturating_add(1);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+
+    /// Reset after a successful connection
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+/// Watch the channel and re-bootstrap the agent whenever the link drops
+async fn reconnect_loop(
+    params: ConnectionParams,
+    channel: Arc<AgentChannel>,
+    process: Arc<Mutex<Child>>,
+) {
+    const POLL_INTERVAL: Duration = Duration::from_millis(500);
+    let mut backoff = ReconnectBackoff::default();
+
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        if channel.is_connected() {
+            continue;
+        }
+
+        tracing::warn!("Connection to {} lost, reconnecting", params.to_string());
+        if let Ok(mut old) = process.lock() {
+            let _ = old.start_kill();
+        }
+
+        loop {
+            let delay = backoff.next_delay();
+            tokio::time::sleep(delay).await;
+            // Batch mode: the editor owns the terminal now, so ssh must not prompt
+            match start_agent(&params, true).await {
+                Ok((child, reader, stdin)) => {
+                    if let Ok(mut current) = process.lock() {
+                        *current = child;
+                    }
+                    channel.replace_transport(reader, stdin);
+                    backoff.reset();
+                    tracing::info!("Reconnected to {}", params.to_string());
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Reconnect to {} failed (retrying in up to {:?}): {}",
+                        params.to_string(),
+                        backoff.max,
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Spawn ssh, bootstrap the Python agent and wait for its ready message
+async fn start_agent(
+    params: &ConnectionParams,
+    batch_mode: bool,
+) -> Result<(Child, BufReader<ChildStdout>, ChildStdin), SshError> {
+    let mut cmd = Command::new("ssh");
+
+    // Don't check host key strictly for ease of use
+    cmd.arg("-o").arg("StrictHostKeyChecking=accept-new");
+    // Detect dead links so a dropped network surfaces as EOF instead of a hang
+    cmd.arg("-o").arg("ServerAliveInterval=10");
+    cmd.arg("-o").arg("ServerAliveCountMax=3");
+    if batch_mode {
+        cmd.arg("-o").arg("BatchMode=yes");
+    }
+    // Allow password prompts - SSH will use the terminal for this
+    // Note: We inherit stderr so SSH can prompt for password if needed
+
+    if let Some(port) = params.port {
+        cmd.arg("-p").arg(port.to_string());
+    }
+
+    if let Some(ref identity) = params.identity_file {
+        cmd.arg("-i").arg(identity);
+    }
+
+    if let Some(ref jump) = params.proxy_jump {
+        cmd.arg("-J").arg(jump);
+    }
+
+    if let Some(ref agent) = params.identity_agent {
+        cmd.arg("-o")
+            .arg(format!("IdentityAgent={}", agent.display()));
+    }
+
+    cmd.arg(params.destination());
+
+    // Bootstrap the agent using Python itself to read the exact byte count.
+    // This avoids requiring bash or other shell utilities on the remote.
+    // Python reads exactly N bytes (the agent code), execs it, and the agent
+    // then continues reading from stdin for protocol messages.
+    //
+    // Note: SSH passes the remote command through a shell, so we need to
+    // properly quote the Python code. We use double quotes for the outer
+    // shell and avoid problematic characters in the Python code.
+    let agent_len = AGENT_SOURCE.len();
+    let bootstrap = format!(
+        "python3 -u -c \"import sys;exec(sys.stdin.read({}))\"",
+        agent_len
+    );
+    cmd.arg(bootstrap);
+
+    cmd.stdin(Stdio::piped());
+    cmd.stdout(Stdio::piped());
+    // Inherit stderr so SSH can prompt for password on the terminal.
+    // Reconnects run while the editor owns the terminal, so silence them.
+    cmd.stderr(if batch_mode {
+        Stdio::null()
+    } else {
+        Stdio::inherit()
+    });
+
+    let mut child = cmd.spawn()?;
+
+    // Get handles
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| SshError::AgentStartFailed("failed to get stdin".to_string()))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| SshError::AgentStartFailed("failed to get stdout".to_string()))?;
+
+    // Send the agent code (exact byte count)
+    stdin.write_all(AGENT_SOURCE.as_bytes()).await?;
+    stdin.flush().await?;
+
+    // Create buffered reader for stdout
+    let mut reader = BufReader::new(stdout);
+
+    // Wait for ready message from agent
+    // No timeout needed - all failure modes (auth failure, network issues, etc.)
+    // result in SSH exiting and us getting EOF. User can Ctrl+C if needed.
+    let mut ready_line = String::new();
+    match reader.read_line(&mut ready_line).await {
+        Ok(0) => {
+            return Err(SshError::AgentStartFailed(
+                "connection closed (check terminal for SSH errors)".to_string(),
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => return Err(SshError::AgentStartFailed(format!("read error: {}", e))),
+    }
+
+    let ready: AgentResponse = serde_json::from_str(&ready_line).map_err(|e| {
+        SshError::AgentStartFailed(format!(
+            "invalid ready message '{}': {}",
+            ready_line.trim(),
+            e
+        ))
+    })?;
+
+    if !ready.is_ready() {
+        return Err(SshError::AgentStartFailed(
+            "agent did not send ready message".to_string(),
+        ));
+    }
+
+    // Check protocol version
+    let version = ready.version.unwrap_or(0);
+    if version != crate::services::remote::protocol::PROTOCOL_VERSION {
+        return Err(SshError::VersionMismatch {
+            expected: crate::services::remote::protocol::PROTOCOL_VERSION,
+            got: version,
+        });
     }
+
+    Ok((child, reader, stdin))
 }
 
 /// Spawn a local agent process for testing (no SSH)
@@ -224,7 +398,7 @@ impl Drop for SshConnection {
 /// This is used by integration tests to test the full stack without SSH.
 /// Not intended for production use.
 #[doc(hidden)]
-pub async fn spawn_local_agent() -> Result<std::sync::Arc<AgentChannel>, SshError> {
+pub async fn spawn_local_agent() -> Result<Arc<AgentChannel>, SshError> {
     use tokio::process::Command as TokioCommand;
 
     let mut child = TokioCommand::new("python3")
@@ -260,7 +434,7 @@ pub async fn spawn_local_agent() -> Result<std::sync::Arc<AgentChannel>, SshErro
         ));
     }
 
-    Ok(std::sync::Arc::new(AgentChannel::new(reader, stdin)))
+    Ok(Arc::new(AgentChannel::new(reader, stdin)))
 }
 
 #[cfg(test)]
@@ -289,8 +463,7 @@ mod tests {
         let params = ConnectionParams {
             user: "alice".to_string(),
             host: "example.com".to_string(),
-            port: None,
-            identity_file: None,
+            ..Default::default()
         };
         assert_eq!(params.to_string(), "alice@example.com");
 
@@ -298,8 +471,60 @@ mod tests {
             user: "bob".to_string(),
             host: "server.local".to_string(),
             port: Some(2222),
-            identity_file: None,
+            ..Default::default()
         };
         assert_eq!(params.to_string(), "bob@server.local:2222");
+
+        let params = ConnectionParams {
+            host: "devbox".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(params.to_string(), "devbox");
+    }
+
+    #[test]
+    fn test_apply_ssh_config_keeps_explicit_values() {
+        let mut params = ConnectionParams::parse("alice@web1:2200").unwrap();
+        params.apply_ssh_config(&SshHostConfig {
+            user: Some("deploy".to_string()),
+            port: Some(22),
+            proxy_jump: Some("bastion".to_string()),
+            identity_agent: Some(PathBuf::from("/tmp/agent.sock")),
+            ..Default::default()
+        });
+        assert_eq!(params.user, "alice");
+        assert_eq!(params.port, Some(2200));
+        assert_eq!(params.proxy_jump.as_deref(), Some("bastion"));
+        assert_eq!(
+            params.identity_agent.as_deref(),
+            Some(std::path::Path::new("/tmp/agent.sock"))
+        );
+
+        let mut params = ConnectionParams {
+            host: "web1".to_string(),
+            ..Default::default()
+        };
+        params.apply_ssh_config(&SshHostConfig {
+            user: Some("deploy".to_string()),
+            port: Some(2222),
+            ..Default::default()
+        });
+        assert_eq!(params.to_string(), "deploy@web1:2222");
+    }
+
+    #[test]
+    fn test_reconnect_backoff_doubles_and_caps() {
+        let mut backoff = ReconnectBackoff::new(Duration::from_secs(1), Duration::from_secs(10));
+        let delays: Vec<u64> = (0..6).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 10, 10]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+
+        // Many failures must not overflow
+        for _ in 0..100 {
+            backoff.next_delay();
+        }
+        assert_eq!(backoff.next_delay(), Duration::from_secs(10));
     }
 }
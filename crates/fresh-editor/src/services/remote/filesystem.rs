@@ -3,7 +3,8 @@
 //! Implements the FileSystem trait for remote operations via SSH agent.
 
 use crate::model::filesystem::{
-    DirEntry, EntryType, FileMetadata, FilePermissions, FileReader, FileSystem, FileWriter, WriteOp,
+    DirEntry, EntryType, FileId, FileMetadata, FilePermissions, FileReader, FileSystem, FileWriter,
+    WriteOp,
 };
 use crate::services::remote::channel::{AgentChannel, ChannelError};
 use crate::services::remote::protocol::{
@@ -59,6 +60,9 @@ impl RemoteFileSystem {
                 };
                 io::Error::new(kind, msg)
             }
+            ChannelError::ChannelClosed => {
+                io::Error::new(io::ErrorKind::NotConnected, e.to_string())
+            }
             e => io::Error::new(io::ErrorKind::Other, e.to_string()),
         }
     }
@@ -89,6 +93,11 @@ impl RemoteFileSystem {
             meta.uid = Some(rm.uid);
             meta.gid = Some(rm.gid);
         }
+        meta.hard_links = rm.nlink;
+        meta.file_id = rm
+            .dev
+            .zip(rm.ino)
+            .map(|(device, inode)| FileId { device, inode });
 
         meta
     }
@@ -125,6 +134,7 @@ impl RemoteFileSystem {
         let mut entry = DirEntry::new(PathBuf::from(&re.path), re.name.clone(), entry_type);
         entry.metadata = Some(metadata);
         entry.symlink_target_is_dir = re.link_dir;
+        entry.symlink_target = re.target.as_ref().map(PathBuf::from);
 
         entry
     }
@@ -338,6 +348,23 @@ impl FileSystem for RemoteFileSystem {
         Ok(Self::convert_metadata(&rm, &name))
     }
 
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        let params = serde_json::json!({"path": path.to_string_lossy()});
+        let result = self
+            .channel
+            .request_blocking("readlink", params)
+            .map_err(Self::to_io_error)?;
+
+        let target = result
+            .get("target")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "missing target in response")
+            })?;
+
+        Ok(PathBuf::from(target))
+    }
+
     fn is_dir(&self, path: &Path) -> io::Result<bool> {
         let path_str = path.to_string_lossy();
         let result = self
@@ -437,6 +464,10 @@ impl FileSystem for RemoteFileSystem {
         Some(&self.connection_string)
     }
 
+    fn is_remote_connected(&self) -> bool {
+        self.channel.is_connected()
+    }
+
     fn home_dir(&self) -> io::Result<PathBuf> {
         let result = self
             .channel
@@ -470,6 +501,7 @@ impl FileSystem for RemoteFileSystem {
 
     fn sudo_write(
         &self,
+        program: &str,
         path: &Path,
         data: &[u8],
         mode: u32,
@@ -480,7 +512,7 @@ impl FileSystem for RemoteFileSystem {
         self.channel
             .request_blocking(
                 "sudo_write",
-                sudo_write_params(&path_str, data, mode, uid, gid),
+                sudo_write_params(program, &path_str, data, mode, uid, gid),
             )
             .map_err(Self::to_io_error)?;
         Ok(())
@@ -609,10 +641,21 @@ mod tests {
             dir: false,
             file: true,
             link: false,
+            nlink: Some(2),
+            dev: Some(64768),
+            ino: Some(42),
         };
 
         let meta = RemoteFileSystem::convert_metadata(&rm, "test.txt");
         assert_eq!(meta.size, 1234);
+        assert_eq!(meta.hard_links, Some(2));
+        assert_eq!(
+            meta.file_id,
+            Some(FileId {
+                device: 64768,
+                inode: 42
+            })
+        );
         assert!(!meta.is_hidden);
         assert!(!meta.is_readonly);
 
@@ -632,11 +675,24 @@ mod tests {
             size: 100,
             mtime: 1700000000,
             mode: 0o644,
+            target: None,
         };
 
         let entry = RemoteFileSystem::convert_dir_entry(&re);
         assert_eq!(entry.name, "file.rs");
         assert_eq!(entry.entry_type, EntryType::File);
         assert!(!entry.is_symlink());
+
+        let link = RemoteDirEntry {
+            name: "current".to_string(),
+            path: "/srv/app/current".to_string(),
+            link: true,
+            link_dir: true,
+            target: Some("releases/42".to_string()),
+            ..re
+        };
+        let entry = RemoteFileSystem::convert_dir_entry(&link);
+        assert!(entry.is_symlink() && entry.is_dir());
+        assert_eq!(entry.symlink_target, Some(PathBuf::from("releases/42")));
     }
 }
@@ -8,12 +8,13 @@ mod connection;
 mod filesystem;
 mod protocol;
 mod spawner;
+mod ssh_config;
 
 pub use channel::AgentChannel;
 /// Re-export for integration tests - spawns a local agent without SSH
 #[doc(hidden)]
 pub use connection::spawn_local_agent;
-pub use connection::{ConnectionParams, SshConnection};
+pub use connection::{ConnectionParams, ReconnectBackoff, SshConnection};
 pub use filesystem::RemoteFileSystem;
 pub use protocol::{
     decode_base64, encode_base64, ls_params, read_params, stat_params, sudo_write_params,
@@ -22,6 +23,7 @@ pub use protocol::{
 pub use spawner::{
     LocalProcessSpawner, ProcessSpawner, RemoteProcessSpawner, SpawnError, SpawnResult,
 };
+pub use ssh_config::{SshConfig, SshHostConfig};
 
 /// The Python agent source code, embedded at compile time.
 pub const AGENT_SOURCE: &str = include_str!("agent.py");
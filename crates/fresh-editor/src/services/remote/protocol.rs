@@ -89,6 +89,9 @@ pub struct RemoteDirEntry {
     pub mtime: i64,
     #[serde(default)]
     pub mode: u32,
+    /// For symlinks, the path the link points to
+    #[serde(default)]
+    pub target: Option<String>,
 }
 
 /// File metadata returned by `stat` command
@@ -108,6 +111,12 @@ pub struct RemoteMetadata {
     pub file: bool,
     #[serde(default)]
     pub link: bool,
+    #[serde(default)]
+    pub nlink: Option<u64>,
+    #[serde(default)]
+    pub dev: Option<u64>,
+    #[serde(default)]
+    pub ino: Option<u64>,
 }
 
 /// Process execution result
@@ -159,6 +168,7 @@ pub fn write_params(path: &str, data: &[u8]) -> serde_json::Value {
 
 /// Build params for sudo_write request (write file as root)
 pub fn sudo_write_params(
+    program: &str,
     path: &str,
     data: &[u8],
     mode: u32,
@@ -166,6 +176,7 @@ pub fn sudo_write_params(
     gid: u32,
 ) -> serde_json::Value {
     serde_json::json!({
+        "program": program,
         "path": path,
         "data": encode_base64(data),
         "mode": mode,
@@ -201,6 +212,18 @@ pub fn exec_params(cmd: &str, args: &[String], cwd: Option<&str>) -> serde_json:
     params
 }
 
+/// Build params for exec request that feeds `input` to the process's stdin
+pub fn exec_input_params(
+    cmd: &str,
+    args: &[String],
+    cwd: Option<&str>,
+    input: &[u8],
+) -> serde_json::Value {
+    let mut params = exec_params(cmd, args, cwd);
+    params["in"] = serde_json::json!(encode_base64(input));
+    params
+}
+
 /// Build params for cancel request
 pub fn cancel_params(request_id: u64) -> serde_json::Value {
     serde_json::json!({"id": request_id})
@@ -8,7 +8,7 @@
 //! git_grep, and fuzzy finder to work transparently on remote files.
 
 use crate::services::remote::channel::{AgentChannel, ChannelError};
-use crate::services::remote::protocol::{decode_base64, exec_params};
+use crate::services::remote::protocol::{decode_base64, exec_input_params, exec_params};
 use std::sync::Arc;
 
 /// Result of spawning a process
@@ -51,6 +51,15 @@ pub trait ProcessSpawner: Send + Sync {
         args: Vec<String>,
         cwd: Option<String>,
     ) -> Result<SpawnResult, SpawnError>;
+
+    /// Spawn a process with `input` on its stdin and wait for completion
+    async fn spawn_with_input(
+        &self,
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        input: Vec<u8>,
+    ) -> Result<SpawnResult, SpawnError>;
 }
 
 /// Local process spawner using tokio
@@ -85,6 +94,53 @@ impl ProcessSpawner for LocalProcessSpawner {
             exit_code: output.status.code().unwrap_or(-1),
         })
     }
+
+    async fn spawn_with_input(
+        &self,
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        input: Vec<u8>,
+    ) -> Result<SpawnResult, SpawnError> {
+        use std::process::Stdio;
+        use tokio::io::AsyncWriteExt;
+
+        let mut cmd = tokio::process::Command::new(&command);
+        cmd.args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        if let Some(ref dir) = cwd {
+            cmd.current_dir(dir);
+        }
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| SpawnError::Process(e.to_string()))?;
+
+        // Feed stdin while collecting output, so a process that echoes its
+        // input (like `tee`) can't fill the stdout pipe and stall the write
+        let stdin = child.stdin.take();
+        let write = async move {
+            if let Some(mut stdin) = stdin {
+                stdin.write_all(&input).await?;
+            }
+            Ok::<(), std::io::Error>(())
+        };
+        let (written, output) = tokio::join!(write, child.wait_with_output());
+        let output = output.map_err(|e| SpawnError::Process(e.to_string()))?;
+        // A process that exits early closes stdin; its exit code says why
+        if output.status.success() {
+            written.map_err(|e| SpawnError::Process(e.to_string()))?;
+        }
+
+        Ok(SpawnResult {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            exit_code: output.status.code().unwrap_or(-1),
+        })
+    }
 }
 
 /// Remote process spawner via SSH agent
@@ -108,7 +164,24 @@ impl ProcessSpawner for RemoteProcessSpawner {
         cwd: Option<String>,
     ) -> Result<SpawnResult, SpawnError> {
         let params = exec_params(&command, &args, cwd.as_deref());
+        self.exec(params).await
+    }
+
+    async fn spawn_with_input(
+        &self,
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        input: Vec<u8>,
+    ) -> Result<SpawnResult, SpawnError> {
+        let params = exec_input_params(&command, &args, cwd.as_deref(), &input);
+        self.exec(params).await
+    }
+}
 
+impl RemoteProcessSpawner {
+    /// Run an `exec` request and collect its output
+    async fn exec(&self, params: serde_json::Value) -> Result<SpawnResult, SpawnError> {
         // Use streaming request to get live output
         let (mut data_rx, result_rx) = self.channel.request_streaming("exec", params).await?;
 
@@ -164,4 +237,18 @@ mod tests {
         assert_eq!(result.exit_code, 0);
         assert!(result.stdout.trim() == "hello");
     }
+
+    #[tokio::test]
+    async fn test_local_spawner_with_input() {
+        let spawner = LocalProcessSpawner;
+        // More than a pipe buffer, echoed back while it is being written
+        let input = "line\n".repeat(100_000);
+        let result = spawner
+            .spawn_with_input("cat".to_string(), vec![], None, input.clone().into_bytes())
+            .await
+            .unwrap();
+
+        assert_eq!(result.exit_code, 0);
+        assert_eq!(result.stdout, input);
+    }
 }
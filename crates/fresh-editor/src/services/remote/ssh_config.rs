@@ -0,0 +1,257 @@
+//! OpenSSH client configuration support
+//!
+//! Parses the subset of `~/.ssh/config` that affects how Fresh connects to a
+//! remote host: `HostName`, `User`, `Port`, `IdentityFile`, `ProxyJump` and
+//! `IdentityAgent`. Like OpenSSH, the first value obtained for a directive
+//! wins, so more specific `Host` blocks should appear before generic ones.
+
+use crate::primitives::path_utils::{expand_tilde, glob_match};
+use std::path::{Path, PathBuf};
+
+/// Options resolved for a single host from the SSH config
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SshHostConfig {
+    pub host_name: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    pub identity_files: Vec<PathBuf>,
+    pub proxy_jump: Option<String>,
+    pub identity_agent: Option<PathBuf>,
+}
+
+/// A `Host` block: the patterns it applies to and its directives
+#[derive(Debug, Clone)]
+struct HostBlock {
+    patterns: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+/// Parsed SSH client configuration
+#[derive(Debug, Clone, Default)]
+pub struct SshConfig {
+    blocks: Vec<HostBlock>,
+}
+
+impl SshConfig {
+    /// Load the user's SSH config (`~/.ssh/config`).
+    ///
+    /// Returns an empty config if the file is missing or unreadable.
+    pub fn load() -> Self {
+        match dirs::home_dir() {
+            Some(home) => Self::load_from(&home.join(".ssh").join("config")),
+            None => Self::default(),
+        }
+    }
+
+    /// Load an SSH config from a specific path, or an empty config on error
+    pub fn load_from(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) => {
+                tracing::debug!("Not using SSH config {:?}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Parse SSH config file contents
+    pub fn parse(content: &str) -> Self {
+        // Directives before the first Host line apply to every host
+        let mut blocks = vec![HostBlock {
+            patterns: vec!["*".to_string()],
+            options: Vec::new(),
+        }];
+        // `Match` blocks are not supported; their directives are skipped
+        let mut in_match = false;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = split_directive(line) else {
+                continue;
+            };
+            match key.as_str() {
+                "host" => {
+                    in_match = false;
+                    blocks.push(HostBlock {
+                        patterns: value.split_whitespace().map(unquote).collect(),
+                        options: Vec::new(),
+                    });
+                }
+                "match" => in_match = true,
+                _ if in_match => {}
+                _ => {
+                    if let Some(block) = blocks.last_mut() {
+                        block.options.push((key, unquote(&value)));
+                    }
+                }
+            }
+        }
+
+        Self { blocks }
+    }
+
+    /// Resolve the effective options for `host`
+    pub fn lookup(&self, host: &str) -> SshHostConfig {
+        let mut resolved = SshHostConfig::default();
+        for block in self
+            .blocks
+            .iter()
+            .filter(|b| host_matches(&b.patterns, host))
+        {
+            for (key, value) in &block.options {
+                match key.as_str() {
+                    "hostname" if resolved.host_name.is_none() => {
+                        resolved.host_name = Some(value.clone());
+                    }
+                    "user" if resolved.user.is_none() => {
+                        resolved.user = Some(value.clone());
+                    }
+                    "port" if resolved.port.is_none() => {
+                        resolved.port = value.parse().ok();
+                    }
+                    // IdentityFile is cumulative in OpenSSH
+                    "identityfile" => {
+                        let path = expand_tilde(value);
+                        if !resolved.identity_files.contains(&path) {
+                            resolved.identity_files.push(path);
+                        }
+                    }
+                    "proxyjump" if resolved.proxy_jump.is_none() && value != "none" => {
+                        resolved.proxy_jump = Some(value.clone());
+                    }
+                    "identityagent" if resolved.identity_agent.is_none() && value != "none" => {
+                        resolved.identity_agent = Some(expand_tilde(value));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        resolved
+    }
+
+    /// Whether `host` is named explicitly (without wildcards) by a `Host` line.
+    ///
+    /// Used to recognize `alias:path` locations that carry no `user@` prefix.
+    pub fn has_alias(&self, host: &str) -> bool {
+        self.blocks.iter().any(|b| {
+            b.patterns
+                .iter()
+                .any(|p| p == host && !p.contains(['*', '?', '!']))
+        })
+    }
+}
+
+/// Split a config line into a lowercase keyword and its value.
+///
+/// Keywords and values may be separated by whitespace or a single `=`.
+fn split_directive(line: &str) -> Option<(String, String)> {
+    let split_at = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let (key, rest) = line.split_at(split_at);
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest).trim();
+    if key.is_empty() || rest.is_empty() {
+        return None;
+    }
+    Some((key.to_ascii_lowercase(), rest.to_string()))
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// Check a host against a `Host` pattern list, honoring `!` negation
+fn host_matches(patterns: &[String], host: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if glob_match(negated, host) {
+                return false;
+            }
+        } else if glob_match(pattern, host) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+# Bastion-fronted production hosts
+Host prod-*
+    User deploy
+    ProxyJump bastion.example.com
+    IdentityFile ~/.ssh/prod_ed25519
+
+Host web1 !prod-db
+    HostName 10.0.0.5
+    Port 2222
+
+Host bastion.example.com
+    User jump
+
+Match host foo
+    User ignored
+
+Host *
+    User fallback
+    IdentityFile=~/.ssh/id_rsa
+    IdentityAgent "~/.1password/agent.sock"
+"#;
+
+    #[test]
+    fn test_lookup_first_value_wins() {
+        let config = SshConfig::parse(SAMPLE);
+        let web = config.lookup("web1");
+        assert_eq!(web.host_name.as_deref(), Some("10.0.0.5"));
+        assert_eq!(web.port, Some(2222));
+        assert_eq!(web.user.as_deref(), Some("fallback"));
+        assert_eq!(web.proxy_jump, None);
+    }
+
+    #[test]
+    fn test_lookup_wildcards_and_cumulative_identities() {
+        let config = SshConfig::parse(SAMPLE);
+        let prod = config.lookup("prod-api");
+        assert_eq!(prod.user.as_deref(), Some("deploy"));
+        assert_eq!(prod.proxy_jump.as_deref(), Some("bastion.example.com"));
+        assert_eq!(prod.identity_files.len(), 2);
+        assert!(prod.identity_files[0].ends_with(".ssh/prod_ed25519"));
+        assert!(prod
+            .identity_agent
+            .as_ref()
+            .is_some_and(|p| p.ends_with(".1password/agent.sock")));
+    }
+
+    #[test]
+    fn test_negated_pattern_excludes_host() {
+        let config = SshConfig::parse("Host * !secret\n  Port 2200\n");
+        assert_eq!(config.lookup("anything").port, Some(2200));
+        assert_eq!(config.lookup("secret").port, None);
+    }
+
+    #[test]
+    fn test_match_blocks_are_skipped() {
+        let config = SshConfig::parse(SAMPLE);
+        assert_eq!(config.lookup("foo").user.as_deref(), Some("fallback"));
+    }
+
+    #[test]
+    fn test_has_alias() {
+        let config = SshConfig::parse(SAMPLE);
+        assert!(config.has_alias("web1"));
+        assert!(config.has_alias("bastion.example.com"));
+        assert!(!config.has_alias("prod-api"));
+        assert!(!config.has_alias("prod-db"));
+        assert!(!config.has_alias("unknown"));
+    }
+}
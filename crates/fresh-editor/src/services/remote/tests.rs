@@ -132,6 +132,17 @@ fn test_exec_params() {
     assert!(params.get("cwd").is_none());
 }
 
+#[test]
+fn test_exec_input_params() {
+    let params = exec_input_params("tee", &["/etc/hosts".to_string()], None, b"127.0.0.1\n");
+    assert_eq!(params["cmd"], "tee");
+    assert_eq!(
+        decode_base64(params["in"].as_str().unwrap()).unwrap(),
+        b"127.0.0.1\n"
+    );
+    assert!(exec_params("ls", &[], None).get("in").is_none());
+}
+
 #[test]
 fn test_cancel_params() {
     let params = cancel_params(42);
@@ -210,8 +221,7 @@ fn test_connection_params_to_string() {
     let params = ConnectionParams {
         user: "alice".to_string(),
         host: "server.com".to_string(),
-        port: None,
-        identity_file: None,
+        ..Default::default()
     };
     assert_eq!(params.to_string(), "alice@server.com");
 
@@ -219,7 +229,7 @@ fn test_connection_params_to_string() {
         user: "bob".to_string(),
         host: "example.org".to_string(),
         port: Some(2222),
-        identity_file: None,
+        ..Default::default()
     };
     assert_eq!(params.to_string(), "bob@example.org:2222");
 }
@@ -446,4 +456,41 @@ mod agent_integration {
         assert!(resp.error.is_some());
         assert!(resp.error.unwrap().contains("unknown method"));
     }
+
+    #[test]
+    fn test_agent_exec_with_input() {
+        let Some((mut stdin, mut stdout)) = spawn_agent() else {
+            eprintln!("Skipping test: Python3 not available");
+            return;
+        };
+
+        // Read ready message
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+
+        let req = AgentRequest::new(
+            1,
+            "exec",
+            exec_input_params("cat", &[], None, b"through stdin\n"),
+        );
+        stdin.write_all(req.to_json_line().as_bytes()).unwrap();
+        stdin.flush().unwrap();
+
+        // Output arrives as data messages before the final result
+        let mut output = Vec::new();
+        let resp = loop {
+            let mut line = String::new();
+            stdout.read_line(&mut line).unwrap();
+            let resp: AgentResponse = serde_json::from_str(&line).unwrap();
+            if resp.is_final() {
+                break resp;
+            }
+            if let Some(out) = resp.data.as_ref().and_then(|d| d.get("out")) {
+                output.extend(decode_base64(out.as_str().unwrap()).unwrap());
+            }
+        };
+
+        assert_eq!(resp.result.unwrap()["code"], 0);
+        assert_eq!(output, b"through stdin\n");
+    }
 }
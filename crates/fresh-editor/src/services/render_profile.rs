@@ -0,0 +1,411 @@
+//! Per-frame render timings (**Debug: Toggle Render Profiler**, `--profile-render`)
+//!
+//! Code on the render path marks its phases with [`scope`]; the returned guard
+//! adds the time until it drops to the frame being measured. Nested scopes
+//! only count their own time, so a highlight pass inside layout isn't counted
+//! twice. Widget drawing is whatever remains of the frame. Input handled
+//! between two frames is counted towards the second.
+//!
+//! Timings are kept per thread: the editor handles input and renders on one
+//! thread, and scopes on other threads record nothing. With profiling off a
+//! scope costs one thread-local lookup.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Time a frame may take at 60 frames per second
+pub const FRAME_BUDGET: Duration = Duration::from_millis(16);
+
+/// Number of frames the averages and worst case are taken over
+const RECENT_FRAMES: usize = 120;
+
+/// Measured part of handling input and rendering
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Key, mouse and paste handling
+    Events,
+    /// Syntax highlighting of the visible range
+    Highlight,
+    /// Building view lines (wrapping, virtual text, view transforms)
+    Layout,
+}
+
+impl Phase {
+    /// Name in the overlay and the trace file
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Events => "events",
+            Self::Highlight => "highlight",
+            Self::Layout => "layout",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Where one frame's time went
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameTimings {
+    pub events: Duration,
+    pub highlight: Duration,
+    pub layout: Duration,
+    /// Rendering time not spent highlighting or laying out
+    pub draw: Duration,
+    /// Time spent in `Editor::render`
+    pub render: Duration,
+}
+
+impl FrameTimings {
+    /// Input handling plus rendering
+    pub fn total(&self) -> Duration {
+        self.events + self.render
+    }
+}
+
+/// Summary of the recent frames for the overlay
+#[derive(Debug, Clone, Copy)]
+pub struct FrameStats {
+    pub last: FrameTimings,
+    pub average: FrameTimings,
+    /// Slowest total among the recent frames
+    pub worst: Duration,
+    /// Recent frames whose total exceeded [`FRAME_BUDGET`]
+    pub over_budget: usize,
+    pub frames: usize,
+}
+
+/// A finished scope, kept for the trace file
+struct Span {
+    phase: Phase,
+    start: Instant,
+    duration: Duration,
+}
+
+#[derive(Default)]
+struct Recorder {
+    enabled: bool,
+    keep_spans: bool,
+    totals: [Duration; 3],
+    /// Time spent in nested scopes, one entry per open scope
+    open: Vec<Duration>,
+    spans: Vec<Span>,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Recorder> = RefCell::new(Recorder::default());
+}
+
+/// Guard returned by [`scope`]
+pub struct Scope {
+    phase: Phase,
+    start: Instant,
+}
+
+/// Count the time until the returned guard drops towards `phase`
+///
+/// Returns None when profiling is off on this thread.
+pub fn scope(phase: Phase) -> Option<Scope> {
+    RECORDER.with(|recorder| {
+        let mut recorder = recorder.borrow_mut();
+        if !recorder.enabled {
+            return None;
+        }
+        recorder.open.push(Duration::ZERO);
+        Some(Scope {
+            phase,
+            start: Instant::now(),
+        })
+    })
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let duration = self.start.elapsed();
+        RECORDER.with(|recorder| {
+            let mut recorder = recorder.borrow_mut();
+            // Profiling was switched off (and the recorder reset) inside the scope
+            let Some(nested) = recorder.open.pop() else {
+                return;
+            };
+            recorder.totals[self.phase.index()] += duration.saturating_sub(nested);
+            if let Some(parent) = recorder.open.last_mut() {
+                *parent += duration;
+            }
+            if recorder.keep_spans {
+                recorder.spans.push(Span {
+                    phase: self.phase,
+                    start: self.start,
+                    duration,
+                });
+            }
+        });
+    }
+}
+
+/// Trace in the Chrome trace event format, for `chrome://tracing` or Perfetto
+struct ChromeTrace {
+    out: BufWriter<File>,
+    epoch: Instant,
+    empty: bool,
+}
+
+impl ChromeTrace {
+    fn create(path: &Path) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(b"[")?;
+        Ok(Self {
+            out,
+            epoch: Instant::now(),
+            empty: true,
+        })
+    }
+
+    /// Write a complete ("X") event
+    fn event(&mut self, name: &str, start: Instant, duration: Duration) -> io::Result<()> {
+        let event = serde_json::json!({
+            "name": name,
+            "cat": "render",
+            "ph": "X",
+            "ts": start.saturating_duration_since(self.epoch).as_secs_f64() * 1e6,
+            "dur": duration.as_secs_f64() * 1e6,
+            "pid": 1,
+            "tid": 1,
+        });
+        let separator = if self.empty { "\n" } else { ",\n" };
+        self.empty = false;
+        write!(self.out, "{}{}", separator, event)
+    }
+}
+
+impl Drop for ChromeTrace {
+    fn drop(&mut self) {
+        let _ = self.out.write_all(b"\n]\n");
+        let _ = self.out.flush();
+    }
+}
+
+/// Collects frame timings for the overlay and the trace file
+#[derive(Default)]
+pub struct RenderProfiler {
+    overlay: bool,
+    trace: Option<ChromeTrace>,
+    frame_start: Option<Instant>,
+    recent: VecDeque<FrameTimings>,
+}
+
+impl RenderProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn overlay_visible(&self) -> bool {
+        self.overlay
+    }
+
+    /// Show or hide the overlay; returns whether it is now shown
+    pub fn toggle_overlay(&mut self) -> bool {
+        self.overlay = !self.overlay;
+        if !self.overlay {
+            self.recent.clear();
+        }
+        self.sync_recorder();
+        self.overlay
+    }
+
+    /// Write every frame's phases to `path` as a Chrome trace
+    pub fn start_trace(&mut self, path: &Path) -> io::Result<()> {
+        self.trace = Some(ChromeTrace::create(path)?);
+        self.sync_recorder();
+        Ok(())
+    }
+
+    fn enabled(&self) -> bool {
+        self.overlay || self.trace.is_some()
+    }
+
+    fn sync_recorder(&self) {
+        let enabled = self.enabled();
+        let keep_spans = self.trace.is_some();
+        RECORDER.with(|recorder| {
+            let mut recorder = recorder.borrow_mut();
+            if recorder.enabled != enabled {
+                *recorder = Recorder::default();
+            }
+            recorder.enabled = enabled;
+            recorder.keep_spans = keep_spans;
+            if !keep_spans {
+                recorder.spans.clear();
+            }
+        });
+    }
+
+    /// Mark the start of `Editor::render`
+    pub fn begin_frame(&mut self) {
+        self.frame_start = self.enabled().then(Instant::now);
+    }
+
+    /// Mark the end of `Editor::render` and record the frame
+    pub fn end_frame(&mut self) {
+        let Some(start) = self.frame_start.take() else {
+            return;
+        };
+        let render = start.elapsed();
+        let (totals, spans) = RECORDER.with(|recorder| {
+            let mut recorder = recorder.borrow_mut();
+            (
+                std::mem::take(&mut recorder.totals),
+                std::mem::take(&mut recorder.spans),
+            )
+        });
+
+        let highlight = totals[Phase::Highlight.index()];
+        let layout = totals[Phase::Layout.index()];
+        let timings = FrameTimings {
+            events: totals[Phase::Events.index()],
+            highlight,
+            layout,
+            draw: render.saturating_sub(highlight + layout),
+            render,
+        };
+        if self.recent.len() == RECENT_FRAMES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(timings);
+
+        if let Some(trace) = &mut self.trace {
+            let written = spans
+                .iter()
+                .try_for_each(|span| trace.event(span.phase.label(), span.start, span.duration))
+                .and_then(|_| trace.event("frame", start, render))
+                .and_then(|_| trace.out.flush());
+            if let Err(e) = written {
+                tracing::warn!("Stopped writing render trace: {}", e);
+                self.trace = None;
+                self.sync_recorder();
+            }
+        }
+    }
+
+    /// Timings of the recent frames, if any were recorded
+    pub fn stats(&self) -> Option<FrameStats> {
+        let last = *self.recent.back()?;
+        let frames = self.recent.len();
+        let sum = self
+            .recent
+            .iter()
+            .fold(FrameTimings::default(), |sum, frame| FrameTimings {
+                events: sum.events + frame.events,
+                highlight: sum.highlight + frame.highlight,
+                layout: sum.layout + frame.layout,
+                draw: sum.draw + frame.draw,
+                render: sum.render + frame.render,
+            });
+        let count = frames as u32;
+        Some(FrameStats {
+            last,
+            average: FrameTimings {
+                events: sum.events / count,
+                highlight: sum.highlight / count,
+                layout: sum.layout / count,
+                draw: sum.draw / count,
+                render: sum.render / count,
+            },
+            worst: self.recent.iter().map(FrameTimings::total).max()?,
+            over_budget: self
+                .recent
+                .iter()
+                .filter(|frame| frame.total() > FRAME_BUDGET)
+                .count(),
+            frames,
+        })
+    }
+}
+
+impl Drop for RenderProfiler {
+    fn drop(&mut self) {
+        if self.enabled() {
+            self.overlay = false;
+            self.trace = None;
+            self.sync_recorder();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn busy(duration: Duration) {
+        let start = Instant::now();
+        while start.elapsed() < duration {}
+    }
+
+    #[test]
+    fn test_scopes_record_nothing_while_disabled() {
+        assert!(scope(Phase::Highlight).is_none());
+        let mut profiler = RenderProfiler::new();
+        profiler.begin_frame();
+        profiler.end_frame();
+        assert!(profiler.stats().is_none());
+    }
+
+    #[test]
+    fn test_nested_scopes_count_only_their_own_time() {
+        let mut profiler = RenderProfiler::new();
+        assert!(profiler.toggle_overlay());
+
+        let events = scope(Phase::Events);
+        busy(Duration::from_millis(2));
+        drop(events);
+
+        profiler.begin_frame();
+        {
+            let _layout = scope(Phase::Layout);
+            let highlight = scope(Phase::Highlight);
+            busy(Duration::from_millis(5));
+            drop(highlight);
+        }
+        profiler.end_frame();
+
+        let stats = profiler.stats().unwrap();
+        let frame = stats.last;
+        assert!(frame.events >= Duration::from_millis(2));
+        assert!(frame.highlight >= Duration::from_millis(5));
+        assert!(frame.layout < frame.highlight);
+        assert_eq!(frame.render, frame.highlight + frame.layout + frame.draw);
+        assert_eq!(stats.frames, 1);
+
+        assert!(!profiler.toggle_overlay());
+        assert!(scope(Phase::Events).is_none());
+    }
+
+    #[test]
+    fn test_trace_file_is_a_json_array_of_complete_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("render.json");
+        let mut profiler = RenderProfiler::new();
+        profiler.start_trace(&path).unwrap();
+
+        for _ in 0..2 {
+            profiler.begin_frame();
+            drop(scope(Phase::Highlight));
+            profiler.end_frame();
+        }
+        drop(profiler);
+
+        let trace: Vec<serde_json::Value> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let names: Vec<&str> = trace
+            .iter()
+            .map(|event| event["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["highlight", "frame", "highlight", "frame"]);
+        assert!(trace.iter().all(|event| event["ph"] == "X"));
+    }
+}
@@ -0,0 +1,435 @@
+//! In-editor self-update for standalone installs
+//!
+//! **Update Fresh Now** downloads the release archive built for this platform
+//! (`fresh-editor-<target>.tar.xz`, or `.zip` on Windows), checks it against
+//! the `.sha256` file published next to it and verifies the release's build
+//! attestation with `gh attestation verify`. The new `fresh` binary then
+//! replaces the running one; the previous binary is kept next to it as
+//! `fresh.old`.
+//!
+//! The checksum only catches a corrupted download: it comes from the same
+//! release as the archive, so whoever can replace one can replace the other.
+//! The attestation is what shows the archive was built by the Fresh release
+//! workflow, so the update fails when it can't be checked (the GitHub CLI is
+//! missing or not logged in) unless `allow_unverified_updates` is set.
+//!
+//! Installs managed by a package manager (Homebrew, cargo, npm, ...) are never
+//! touched - the user is told which command updates them instead.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use super::release_checker::{
+    detect_install_method, fetch_latest_release, is_newer_version, Release, CURRENT_VERSION,
+};
+use crate::config::UpdateChannel;
+
+/// Repository whose attestations release archives are verified against
+const ATTESTATION_REPO: &str = "sinelaw/fresh";
+
+/// Name of the editor binary inside the release archive
+#[cfg(windows)]
+const BINARY_NAME: &str = "fresh.exe";
+#[cfg(not(windows))]
+const BINARY_NAME: &str = "fresh";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SelfUpdateError {
+    #[error("Fresh is managed by a package manager; update with: {command}")]
+    ManagedInstall { command: &'static str },
+    #[error("Update check failed: {0}")]
+    Check(String),
+    #[error("Fresh {version} is already the latest {channel} release")]
+    UpToDate {
+        version: String,
+        channel: &'static str,
+    },
+    #[error("No release builds are published for this platform")]
+    UnsupportedPlatform,
+    #[error("Release {version} has no {name}")]
+    MissingAsset { version: String, name: String },
+    #[error("Download of {url} failed: {message}")]
+    Download { url: String, message: String },
+    #[error("Checksum mismatch for {name}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Signature verification failed: {0}")]
+    Signature(String),
+    #[error(
+        "Cannot verify the release signature without a logged-in GitHub CLI (gh); \
+         install it and run `gh auth login`, or set allow_unverified_updates to \
+         install on the checksum alone"
+    )]
+    SignatureUnavailable,
+    #[error("Could not unpack the release: {0}")]
+    Extract(String),
+    #[error("{path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+}
+
+/// A binary swapped in by [`update_to_latest`]
+#[derive(Debug, Clone)]
+pub struct InstalledUpdate {
+    /// Version now installed
+    pub version: String,
+    /// Path of the new binary (the path the editor was started from)
+    pub executable: PathBuf,
+    /// Whether the build attestation was verified in addition to the checksum
+    pub signature_checked: bool,
+}
+
+fn io_err(path: &Path) -> impl FnOnce(io::Error) -> SelfUpdateError {
+    let path = path.to_path_buf();
+    move |source| SelfUpdateError::Io { path, source }
+}
+
+/// Target triple of the release build matching this binary
+pub fn release_target() -> Option<&'static str> {
+    if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        Some("aarch64-apple-darwin")
+    } else if cfg!(all(target_os = "macos", target_arch = "x86_64")) {
+        Some("x86_64-apple-darwin")
+    } else if cfg!(all(
+        target_os = "linux",
+        target_env = "musl",
+        target_arch = "x86_64"
+    )) {
+        Some("x86_64-unknown-linux-musl")
+    } else if cfg!(all(
+        target_os = "linux",
+        target_env = "musl",
+        target_arch = "aarch64"
+    )) {
+        Some("aarch64-unknown-linux-musl")
+    } else if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        Some("x86_64-unknown-linux-gnu")
+    } else if cfg!(all(target_os = "linux", target_arch = "aarch64")) {
+        Some("aarch64-unknown-linux-gnu")
+    } else if cfg!(all(windows, target_arch = "x86_64")) {
+        Some("x86_64-pc-windows-msvc")
+    } else {
+        None
+    }
+}
+
+/// Name of the release archive built for `target`
+pub fn archive_name(target: &str) -> String {
+    let extension = if target.contains("windows") {
+        "zip"
+    } else {
+        "tar.xz"
+    };
+    format!("fresh-editor-{}.{}", target, extension)
+}
+
+/// Download, verify and install the newest release on `channel` in place of
+/// the running binary. Blocks; run it off the UI thread.
+///
+/// Fails if the build attestation can't be checked, unless `allow_unverified`.
+pub fn update_to_latest(
+    releases_url: &str,
+    channel: UpdateChannel,
+    allow_unverified: bool,
+) -> Result<InstalledUpdate, SelfUpdateError> {
+    if let Some(command) = detect_install_method().update_command() {
+        return Err(SelfUpdateError::ManagedInstall { command });
+    }
+    let executable = std::env::current_exe()
+        .and_then(|exe| exe.canonicalize())
+        .map_err(io_err(Path::new(BINARY_NAME)))?;
+
+    let release = fetch_latest_release(releases_url, channel).map_err(SelfUpdateError::Check)?;
+    if !is_newer_version(CURRENT_VERSION, &release.version) {
+        return Err(SelfUpdateError::UpToDate {
+            version: CURRENT_VERSION.to_string(),
+            channel: channel.as_str(),
+        });
+    }
+
+    let target = release_target().ok_or(SelfUpdateError::UnsupportedPlatform)?;
+    install_release(
+        &release,
+        &archive_name(target),
+        &executable,
+        allow_unverified,
+    )
+}
+
+/// Install `release` over `executable` using the archive called `archive`
+fn install_release(
+    release: &Release,
+    archive: &str,
+    executable: &Path,
+    allow_unverified: bool,
+) -> Result<InstalledUpdate, SelfUpdateError> {
+    let missing = |name: String| SelfUpdateError::MissingAsset {
+        version: release.version.clone(),
+        name,
+    };
+    let archive_asset = release
+        .asset(archive)
+        .ok_or_else(|| missing(archive.to_string()))?;
+    let checksum_name = format!("{}.sha256", archive);
+    let checksum_asset = release
+        .asset(&checksum_name)
+        .ok_or_else(|| missing(checksum_name.clone()))?;
+
+    // Stage next to the binary so the final rename stays on one filesystem
+    let install_dir = executable.parent().unwrap_or(Path::new("."));
+    let staging = install_dir.join(format!(".fresh-update-{}", release.version));
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging).map_err(io_err(&staging))?;
+
+    let result = (|| {
+        let archive_path = staging.join(archive);
+        download(&archive_asset.download_url, &archive_path)?;
+        let checksum_path = staging.join(&checksum_name);
+        download(&checksum_asset.download_url, &checksum_path)?;
+
+        let checksum = fs::read_to_string(&checksum_path).map_err(io_err(&checksum_path))?;
+        verify_checksum(&archive_path, &checksum)?;
+        let signature_checked = verify_attestation(&archive_path, allow_unverified)?;
+
+        let new_binary = extract_binary(&archive_path, &staging.join("unpacked"))?;
+        replace_executable(executable, &new_binary)?;
+        Ok(InstalledUpdate {
+            version: release.version.clone(),
+            executable: executable.to_path_buf(),
+            signature_checked,
+        })
+    })();
+
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+fn download(url: &str, dest: &Path) -> Result<(), SelfUpdateError> {
+    tracing::debug!("Downloading {} to {}", url, dest.display());
+    let download_err = |message: String| SelfUpdateError::Download {
+        url: url.to_string(),
+        message,
+    };
+    let agent = ureq::Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(300)))
+        .build()
+        .new_agent();
+    let response = agent
+        .get(url)
+        .header("User-Agent", "fresh-editor-self-update")
+        .call()
+        .map_err(|e| download_err(e.to_string()))?;
+
+    let mut file = File::create(dest).map_err(io_err(dest))?;
+    io::copy(&mut response.into_body().into_reader(), &mut file)
+        .map_err(|e| download_err(e.to_string()))?;
+    Ok(())
+}
+
+/// Check `path` against a cargo-dist `.sha256` file (`<hex>  <file name>`)
+fn verify_checksum(path: &Path, checksum_file: &str) -> Result<(), SelfUpdateError> {
+    let expected = checksum_file
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path).map_err(io_err(path))?, &mut hasher).map_err(io_err(path))?;
+    let actual = format!("{:x}", hasher.finalize());
+
+    if actual != expected {
+        return Err(SelfUpdateError::ChecksumMismatch {
+            name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Verify the archive's build attestation with the GitHub CLI
+///
+/// When `gh` isn't installed or logged in this fails, or returns `Ok(false)`
+/// if `allow_unverified` is set, leaving the checksum as the only check.
+fn verify_attestation(archive: &Path, allow_unverified: bool) -> Result<bool, SelfUpdateError> {
+    let logged_in = Command::new("gh")
+        .args(["auth", "status"])
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if !logged_in {
+        if !allow_unverified {
+            return Err(SelfUpdateError::SignatureUnavailable);
+        }
+        tracing::warn!(
+            "gh not available, installing {} without an attestation check",
+            archive.display()
+        );
+        return Ok(false);
+    }
+
+    let output = Command::new("gh")
+        .args(["attestation", "verify"])
+        .arg(archive)
+        .args(["--repo", ATTESTATION_REPO])
+        .output()
+        .map_err(|e| SelfUpdateError::Signature(e.to_string()))?;
+    if !output.status.success() {
+        return Err(SelfUpdateError::Signature(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(true)
+}
+
+/// Unpack `archive` into `dir` with the system `tar` (which reads `.tar.xz`
+/// everywhere and `.zip` on Windows) and find the editor binary in it
+fn extract_binary(archive: &Path, dir: &Path) -> Result<PathBuf, SelfUpdateError> {
+    fs::create_dir_all(dir).map_err(io_err(dir))?;
+    let output = Command::new("tar")
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dir)
+        .output()
+        .map_err(|e| SelfUpdateError::Extract(format!("could not run tar: {}", e)))?;
+    if !output.status.success() {
+        return Err(SelfUpdateError::Extract(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    find_file(dir, BINARY_NAME)
+        .ok_or_else(|| SelfUpdateError::Extract(format!("no {} in the archive", BINARY_NAME)))
+}
+
+fn find_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    let mut subdirs = Vec::new();
+    for entry in fs::read_dir(dir).ok()?.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            subdirs.push(path);
+        } else if entry.file_name() == name {
+            return Some(path);
+        }
+    }
+    subdirs
+        .into_iter()
+        .find_map(|subdir| find_file(&subdir, name))
+}
+
+/// Path the replaced binary is kept at: `fresh` becomes `fresh.old`
+pub fn previous_binary_path(executable: &Path) -> PathBuf {
+    let mut name = executable.file_name().unwrap_or_default().to_os_string();
+    name.push(".old");
+    executable.with_file_name(name)
+}
+
+/// Move `new_binary` into place at `executable`, keeping the old binary at
+/// [`previous_binary_path`]. The old binary is restored if the swap fails.
+/// Renaming works even while the old binary is running, on Windows too.
+fn replace_executable(executable: &Path, new_binary: &Path) -> Result<(), SelfUpdateError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(new_binary, fs::Permissions::from_mode(0o755))
+            .map_err(io_err(new_binary))?;
+    }
+
+    let previous = previous_binary_path(executable);
+    // A leftover from the last update (Windows can't delete a running binary)
+    let _ = fs::remove_file(&previous);
+    fs::rename(executable, &previous).map_err(io_err(executable))?;
+    if let Err(e) = fs::rename(new_binary, executable) {
+        let _ = fs::rename(&previous, executable);
+        return Err(io_err(executable)(e));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_name() {
+        assert_eq!(
+            archive_name("x86_64-unknown-linux-gnu"),
+            "fresh-editor-x86_64-unknown-linux-gnu.tar.xz"
+        );
+        assert_eq!(
+            archive_name("x86_64-pc-windows-msvc"),
+            "fresh-editor-x86_64-pc-windows-msvc.zip"
+        );
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fresh-editor.tar.xz");
+        fs::write(&path, b"release").unwrap();
+        let digest = format!("{:x}", Sha256::digest(b"release"));
+
+        verify_checksum(&path, &format!("{}  fresh-editor.tar.xz\n", digest)).unwrap();
+        verify_checksum(&path, &digest.to_uppercase()).unwrap();
+
+        let err = verify_checksum(&path, &"0".repeat(64)).unwrap_err();
+        assert!(matches!(err, SelfUpdateError::ChecksumMismatch { .. }));
+    }
+
+    #[test]
+    fn test_replace_executable_keeps_previous_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let executable = dir.path().join("fresh");
+        let new_binary = dir.path().join("staging-fresh");
+        fs::write(&executable, "old").unwrap();
+        fs::write(&new_binary, "new").unwrap();
+
+        replace_executable(&executable, &new_binary).unwrap();
+
+        assert_eq!(fs::read_to_string(&executable).unwrap(), "new");
+        assert_eq!(
+            fs::read_to_string(previous_binary_path(&executable)).unwrap(),
+            "old"
+        );
+        assert!(!new_binary.exists());
+    }
+
+    #[test]
+    fn test_replace_executable_restores_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let executable = dir.path().join("fresh");
+        fs::write(&executable, "old").unwrap();
+
+        assert!(replace_executable(&executable, &dir.path().join("missing")).is_err());
+        assert_eq!(fs::read_to_string(&executable).unwrap(), "old");
+    }
+
+    #[test]
+    fn test_install_release_reports_missing_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let release = Release {
+            version: "99.0.0".to_string(),
+            assets: Vec::new(),
+        };
+
+        let err = install_release(
+            &release,
+            "fresh-editor-x.tar.xz",
+            &dir.path().join("fresh"),
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(err, SelfUpdateError::MissingAsset { .. }));
+    }
+}
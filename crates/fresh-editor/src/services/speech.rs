@@ -0,0 +1,104 @@
+//! Speaking accessibility announcements through an external command
+//!
+//! Each announcement runs the configured command (`accessibility.command`)
+//! with the text as its last argument. A newer announcement stops an older
+//! one that is still being spoken, so moving quickly through a menu doesn't
+//! queue up every item.
+
+use std::collections::VecDeque;
+use std::io;
+use std::process::{Child, Command, Stdio};
+
+/// How many recent announcements are kept
+const HISTORY_LEN: usize = 50;
+
+/// Runs the speech command and remembers recent announcements
+#[derive(Debug)]
+pub struct Announcer {
+    /// Program and arguments; empty only records announcements
+    command: Vec<String>,
+    /// The announcement being spoken
+    child: Option<Child>,
+    history: VecDeque<String>,
+}
+
+impl Announcer {
+    pub fn new(command: Vec<String>) -> Self {
+        Self {
+            command,
+            child: None,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Speak `text`, interrupting the previous announcement
+    ///
+    /// The text is recorded even when the command can't be started.
+    pub fn announce(&mut self, text: &str) -> io::Result<()> {
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(text.to_string());
+        tracing::debug!(target: "accessibility", "{}", text);
+
+        self.stop();
+        let Some((program, args)) = self.command.split_first() else {
+            return Ok(());
+        };
+        let child = Command::new(program)
+            .args(args)
+            .arg(text)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        self.child = Some(child);
+        Ok(())
+    }
+
+    /// Recent announcements, oldest first
+    pub fn history(&self) -> impl Iterator<Item = &str> {
+        self.history.iter().map(String::as_str)
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            if matches!(child.try_wait(), Ok(None)) {
+                let _ = child.kill();
+            }
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for Announcer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut announcer = Announcer::new(Vec::new());
+        for i in 0..HISTORY_LEN + 5 {
+            announcer.announce(&format!("item {}", i)).unwrap();
+        }
+        assert_eq!(announcer.history().count(), HISTORY_LEN);
+        assert_eq!(announcer.history().next(), Some("item 5"));
+        assert_eq!(
+            announcer.history().last(),
+            Some(format!("item {}", HISTORY_LEN + 4).as_str())
+        );
+    }
+
+    #[test]
+    fn test_missing_command_still_records() {
+        let mut announcer = Announcer::new(vec!["fresh-no-such-speech-command".to_string()]);
+        assert!(announcer.announce("hello").is_err());
+        assert_eq!(announcer.history().collect::<Vec<_>>(), ["hello"]);
+    }
+}
@@ -1,4 +1,13 @@
+//! Anonymous telemetry sent with the daily update check.
+//!
+//! What is sent depends on `telemetry.level`: the usage ping (version, OS and
+//! terminal type) and/or crash reports. Crashes are recorded by the panic hook
+//! into the data directory and reported, then forgotten, at the next daily
+//! check. With `telemetry.local_only` the events are appended to
+//! `telemetry.jsonl` in the data directory instead of being sent.
+
 use super::time_source::TimeSource;
+use crate::config::TelemetryConfig;
 use chrono::NaiveDate;
 use serde::Serialize;
 use std::collections::hash_map::RandomState;
@@ -6,15 +15,22 @@ use std::env::consts::{ARCH, OS};
 use std::fs;
 use std::hash::{BuildHasher, Hasher};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::thread;
 use std::time::Duration;
 
-const TELEMETRY_URL: &str = "https://t.getfresh.dev";
+pub const TELEMETRY_URL: &str = "https://t.getfresh.dev";
 const STAMP_FILE_NAME: &str = "telemetry_stamp";
+const CRASHES_FILE_NAME: &str = "telemetry_crashes";
+const EVENTS_FILE_NAME: &str = "telemetry.jsonl";
+
+/// Data directory crashes are recorded in, set when crash reports are enabled
+static CRASH_DATA_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
 
-#[derive(Serialize, Default)]
-struct Event {
+/// One telemetry event, exactly as sent
+#[derive(Serialize, Default, Debug, Clone, PartialEq)]
+pub struct Event {
     #[serde(skip_serializing_if = "Option::is_none")]
     version: Option<&'static str>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -129,16 +145,131 @@ pub fn should_run_daily_check(
     }
 }
 
-/// Track app open with unique ID
-pub fn track_open(unique_id: &str) {
-    let event = Event {
+/// The daily usage ping
+fn open_event(unique_id: &str) -> Event {
+    Event {
         version: Some(env!("CARGO_PKG_VERSION")),
         os: Some(format!("{}-{}", OS, ARCH)),
         command: Some("fresh"),
         value: std::env::var("TERM").ok(),
         uid: Some(unique_id.to_string()),
+    }
+}
+
+/// A crash report: only the source file and line of the panic, never its message
+fn crash_event(unique_id: &str, location: String) -> Event {
+    Event {
+        version: Some(env!("CARGO_PKG_VERSION")),
+        os: Some(format!("{}-{}", OS, ARCH)),
+        command: Some("crash"),
+        value: Some(location),
+        uid: Some(unique_id.to_string()),
+    }
+}
+
+/// The unique ID sent with events, if the first daily check has made one
+pub fn unique_id(data_dir: &Path) -> Option<String> {
+    read_stamp_file(data_dir).map(|data| data.unique_id)
+}
+
+/// Events the next daily check sends with these settings
+pub fn pending_events(config: &TelemetryConfig, data_dir: &Path, unique_id: &str) -> Vec<Event> {
+    let mut events = Vec::new();
+    if config.level.sends_usage() {
+        events.push(open_event(unique_id));
+    }
+    if config.level.sends_crashes() {
+        events.extend(
+            recorded_crashes(data_dir)
+                .into_iter()
+                .map(|location| crash_event(unique_id, location)),
+        );
+    }
+    events
+}
+
+/// Send (or with `local_only`, write) the pending events, then forget the
+/// crashes they reported
+pub fn report(config: &TelemetryConfig, data_dir: &Path, unique_id: &str) {
+    for event in pending_events(config, data_dir, unique_id) {
+        if config.local_only {
+            write_local_event(data_dir, &event);
+        } else {
+            send(event);
+        }
+    }
+    let _ = fs::remove_file(crashes_file_path(data_dir));
+}
+
+/// Where `local_only` events are written
+pub fn local_events_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("fresh").join(EVENTS_FILE_NAME)
+}
+
+fn write_local_event(data_dir: &Path, event: &Event) {
+    let Ok(line) = serde_json::to_string(event) else {
+        return;
+    };
+    let path = local_events_path(data_dir);
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::OpenOptions::new().create(true).append(true).open(&path))
+        .and_then(|mut f| writeln!(f, "{}", line));
+    if let Err(e) = written {
+        tracing::debug!("Failed to write telemetry event to {:?}: {}", path, e);
+    }
+}
+
+fn crashes_file_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("fresh").join(CRASHES_FILE_NAME)
+}
+
+/// Crash locations recorded since the last report, oldest first
+fn recorded_crashes(data_dir: &Path) -> Vec<String> {
+    fs::read_to_string(crashes_file_path(data_dir))
+        .map(|content| content.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Record crashes from now on (called when the settings allow crash reports)
+pub fn enable_crash_reports(data_dir: &Path) {
+    if let Ok(mut dir) = CRASH_DATA_DIR.lock() {
+        *dir = Some(data_dir.to_path_buf());
+    }
+}
+
+/// Record a panic for the next daily check, if crash reports are enabled.
+/// Called from the panic hook, so it only appends a line to a file.
+pub fn record_crash(location: Option<&std::panic::Location<'_>>) {
+    let Some(data_dir) = CRASH_DATA_DIR.lock().ok().and_then(|dir| dir.clone()) else {
+        return;
     };
-    send(event);
+    append_crash(&data_dir, &crash_location(location));
+}
+
+/// `buffer.rs:120` for a panic location; directories are left out since they
+/// can contain the user name of whoever built the binary
+fn crash_location(location: Option<&std::panic::Location<'_>>) -> String {
+    match location {
+        Some(location) => {
+            let file = Path::new(location.file())
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            format!("{}:{}", file, location.line())
+        }
+        None => "unknown".to_string(),
+    }
+}
+
+fn append_crash(data_dir: &Path, location: &str) {
+    let path = crashes_file_path(data_dir);
+    let _ = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::OpenOptions::new().create(true).append(true).open(&path))
+        .and_then(|mut f| writeln!(f, "{}", location));
 }
 
 fn send(event: Event) {
@@ -162,6 +293,7 @@ fn send(event: Event) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::TelemetryLevel;
     use crate::services::time_source::TestTimeSource;
     use std::time::Duration;
 
@@ -217,6 +349,58 @@ mod tests {
         assert!(id3.is_some(), "next-day call should return Some");
         assert_eq!(id1, id3, "unique_id should persist across days");
     }
+
+    fn telemetry(level: TelemetryLevel, local_only: bool) -> TelemetryConfig {
+        TelemetryConfig { level, local_only }
+    }
+
+    #[test]
+    fn pending_events_follow_the_level() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        append_crash(temp_dir.path(), "buffer.rs:12");
+
+        let commands = |level| {
+            pending_events(&telemetry(level, false), temp_dir.path(), "id")
+                .into_iter()
+                .map(|event| event.command.unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(commands(TelemetryLevel::Features), vec!["fresh", "crash"]);
+        assert_eq!(commands(TelemetryLevel::Crashes), vec!["crash"]);
+        assert!(commands(TelemetryLevel::None).is_empty());
+    }
+
+    #[test]
+    fn local_only_report_writes_events_and_forgets_crashes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        append_crash(temp_dir.path(), "buffer.rs:12");
+
+        report(
+            &telemetry(TelemetryLevel::Crashes, true),
+            temp_dir.path(),
+            "id",
+        );
+
+        let written = fs::read_to_string(local_events_path(temp_dir.path())).unwrap();
+        let events: Vec<serde_json::Value> = written
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["command"], "crash");
+        assert_eq!(events[0]["value"], "buffer.rs:12");
+        assert!(recorded_crashes(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn crash_location_leaves_out_directories() {
+        let location = std::panic::Location::caller();
+        assert_eq!(
+            crash_location(Some(location)),
+            format!("telemetry.rs:{}", location.line())
+        );
+        assert_eq!(crash_location(None), "unknown");
+    }
 }
 
 #[cfg(test)]
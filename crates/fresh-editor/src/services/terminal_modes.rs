@@ -177,6 +177,11 @@ impl TerminalModes {
         Ok(modes)
     }
 
+    /// Whether keyboard enhancement (kitty protocol) flags were pushed.
+    pub fn keyboard_enhancement(&self) -> bool {
+        self.keyboard_enhancement
+    }
+
     /// Restore terminal to original state by disabling all enabled modes.
     ///
     /// This is safe to call multiple times - it tracks what was enabled
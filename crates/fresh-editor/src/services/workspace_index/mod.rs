@@ -0,0 +1,422 @@
+//! Background index of the workspace's files and symbols
+//!
+//! A thread walks the working directory, respecting `.gitignore`, keeps the
+//! list of files, and tags each source file with the definitions `tags`
+//! finds in it. Quick Open lists files from the index instead of running
+//! `git ls-files` when it opens, and its `@` mode searches the symbols, which
+//! works without a language server.
+//!
+//! The index is saved in the data directory, so the next start has results
+//! right away while the thread rescans. A rescan only reads files whose
+//! modification time changed. Besides rescanning periodically, the thread
+//! updates files the editor saved or reloaded, and rescans when the file
+//! explorer sees a directory change.
+
+pub mod tags;
+
+pub use tags::{SymbolKind, Tag};
+
+use crate::input::fuzzy::fuzzy_match;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, RwLock};
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Bumped when the saved format changes; older indexes are rebuilt
+const INDEX_VERSION: u32 = 1;
+/// Stop listing files past this many, like Quick Open's other file sources
+const MAX_FILES: usize = 50_000;
+/// Larger files are listed but not tagged
+const MAX_TAGGED_FILE_BYTES: u64 = 1024 * 1024;
+/// How often the whole workspace is checked for changes
+const RESCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A definition somewhere in the workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Path relative to the workspace root
+    pub path: String,
+    /// 1-based line number
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct IndexData {
+    version: u32,
+    /// Indexed files by path relative to the root
+    files: BTreeMap<String, IndexedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedFile {
+    /// Modification time in nanoseconds since the epoch
+    mtime: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<Tag>,
+}
+
+enum Request {
+    Rescan,
+    Update(Vec<PathBuf>),
+}
+
+/// Handle to a running workspace indexer
+///
+/// Dropping it stops the indexer thread.
+pub struct WorkspaceIndex {
+    requests: mpsc::Sender<Request>,
+    /// Unset until the saved index is loaded or the first scan finishes
+    data: Arc<RwLock<Option<Arc<IndexData>>>>,
+}
+
+impl WorkspaceIndex {
+    /// Start indexing `root`, keeping the index at `cache_path` if given
+    pub fn start(root: PathBuf, cache_path: Option<PathBuf>) -> Self {
+        let (requests, receiver) = mpsc::channel();
+        let data = Arc::new(RwLock::new(None));
+        let mut indexer = Indexer {
+            root,
+            cache_path,
+            data: Arc::new(IndexData {
+                version: INDEX_VERSION,
+                files: BTreeMap::new(),
+            }),
+            shared: Arc::clone(&data),
+        };
+        if let Err(e) = thread::Builder::new()
+            .name("workspace-index".to_string())
+            .spawn(move || indexer.run(receiver))
+        {
+            tracing::warn!("Failed to start workspace indexer: {}", e);
+        }
+        Self { requests, data }
+    }
+
+    /// Where the index for `root` is kept under `data_dir`
+    pub fn cache_path(data_dir: &Path, root: &Path) -> PathBuf {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        data_dir.join("index").join(format!(
+            "{}.json",
+            crate::workspace::encode_path_for_filename(&root)
+        ))
+    }
+
+    fn snapshot(&self) -> Option<Arc<IndexData>> {
+        self.data.read().ok()?.clone()
+    }
+
+    /// Whether the index has anything to show yet
+    pub fn is_ready(&self) -> bool {
+        self.snapshot().is_some()
+    }
+
+    /// Every indexed file, relative to the root, in path order
+    pub fn files(&self) -> Option<Vec<String>> {
+        Some(self.snapshot()?.files.keys().cloned().collect())
+    }
+
+    /// Estimated bytes held by the index: paths, symbol names and map nodes
+    pub fn memory_usage(&self) -> usize {
+        let Some(data) = self.snapshot() else {
+            return 0;
+        };
+        data.files
+            .iter()
+            .map(|(path, file)| {
+                path.capacity()
+                    + std::mem::size_of::<(String, IndexedFile)>()
+                    + file.tags.capacity() * std::mem::size_of::<Tag>()
+                    + file
+                        .tags
+                        .iter()
+                        .map(|tag| tag.name.capacity())
+                        .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// The best `limit` symbols whose names fuzzy-match `query`
+    pub fn search_symbols(&self, query: &str, limit: usize) -> Option<Vec<Symbol>> {
+        let data = self.snapshot()?;
+        let mut matches: Vec<(i32, Symbol)> = data
+            .files
+            .iter()
+            .flat_map(|(path, file)| file.tags.iter().map(move |tag| (path, tag)))
+            .filter_map(|(path, tag)| {
+                let result = fuzzy_match(query, &tag.name);
+                result.matched.then(|| {
+                    let symbol = Symbol {
+                        name: tag.name.clone(),
+                        kind: tag.kind,
+                        path: path.clone(),
+                        line: tag.line,
+                    };
+                    (result.score, symbol)
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| a.1.name.len().cmp(&b.1.name.len()))
+        });
+        matches.truncate(limit);
+        Some(matches.into_iter().map(|(_, symbol)| symbol).collect())
+    }
+
+    /// Re-read files that changed on disk
+    pub fn update(&self, paths: Vec<PathBuf>) {
+        let _ = self.requests.send(Request::Update(paths));
+    }
+
+    /// Walk the whole workspace again, e.g. after files were added or removed
+    pub fn rescan(&self) {
+        let _ = self.requests.send(Request::Rescan);
+    }
+}
+
+struct Indexer {
+    root: PathBuf,
+    cache_path: Option<PathBuf>,
+    data: Arc<IndexData>,
+    shared: Arc<RwLock<Option<Arc<IndexData>>>>,
+}
+
+impl Indexer {
+    fn run(&mut self, requests: mpsc::Receiver<Request>) {
+        if let Some(data) = self.load() {
+            self.data = Arc::new(data);
+            self.publish();
+        }
+        if self.rescan() {
+            self.save();
+        }
+        self.publish();
+
+        loop {
+            let mut rescan = false;
+            let mut paths = Vec::new();
+            match requests.recv_timeout(RESCAN_INTERVAL) {
+                Ok(Request::Rescan) | Err(mpsc::RecvTimeoutError::Timeout) => rescan = true,
+                Ok(Request::Update(changed)) => paths.extend(changed),
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+            // Handle everything queued in one pass
+            for request in requests.try_iter() {
+                match request {
+                    Request::Rescan => rescan = true,
+                    Request::Update(changed) => paths.extend(changed),
+                }
+            }
+
+            let changed = if rescan {
+                self.rescan()
+            } else {
+                self.update(&paths)
+            };
+            if changed {
+                self.publish();
+                self.save();
+            }
+        }
+    }
+
+    fn publish(&self) {
+        if let Ok(mut shared) = self.shared.write() {
+            *shared = Some(Arc::clone(&self.data));
+        }
+    }
+
+    /// Walk the workspace, re-tagging files whose modification time changed
+    ///
+    /// Returns true if anything changed.
+    fn rescan(&mut self) -> bool {
+        let mut changed = false;
+        let mut files = BTreeMap::new();
+        for (relative, path, metadata) in walk(&self.root) {
+            let mtime = mtime_of(&metadata);
+            let file = match self.data.files.get(&relative) {
+                Some(file) if file.mtime == mtime => file.clone(),
+                _ => {
+                    changed = true;
+                    IndexedFile {
+                        mtime,
+                        tags: read_tags(&path, metadata.len()),
+                    }
+                }
+            };
+            files.insert(relative, file);
+        }
+        // Every file found was already indexed; any others were removed
+        changed |= files.len() != self.data.files.len();
+        if changed {
+            Arc::make_mut(&mut self.data).files = files;
+        }
+        changed
+    }
+
+    /// Re-tag `paths`, falling back to a rescan for files not yet indexed
+    ///
+    /// A new file may be ignored, which only the walk knows.
+    fn update(&mut self, paths: &[PathBuf]) -> bool {
+        let mut changed = false;
+        for path in paths {
+            let Ok(relative) = path.strip_prefix(&self.root) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().into_owned();
+            let metadata = std::fs::metadata(path).ok().filter(|m| m.is_file());
+            match (self.data.files.get(&relative), metadata) {
+                (Some(file), Some(metadata)) => {
+                    let mtime = mtime_of(&metadata);
+                    if file.mtime != mtime {
+                        let tags = read_tags(path, metadata.len());
+                        Arc::make_mut(&mut self.data)
+                            .files
+                            .insert(relative, IndexedFile { mtime, tags });
+                        changed = true;
+                    }
+                }
+                (Some(_), None) => {
+                    Arc::make_mut(&mut self.data).files.remove(&relative);
+                    changed = true;
+                }
+                (None, Some(_)) => return self.rescan() || changed,
+                (None, None) => {}
+            }
+        }
+        changed
+    }
+
+    fn load(&self) -> Option<IndexData> {
+        let content = std::fs::read_to_string(self.cache_path.as_ref()?).ok()?;
+        let data: IndexData = serde_json::from_str(&content).ok()?;
+        (data.version == INDEX_VERSION).then_some(data)
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.cache_path else {
+            return;
+        };
+        if let Err(e) = write_index(path, &self.data) {
+            tracing::warn!("Failed to save workspace index to {:?}: {}", path, e);
+        }
+    }
+}
+
+/// Write the index atomically, so another editor never reads half of it
+fn write_index(path: &Path, data: &IndexData) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, serde_json::to_string(data)?)?;
+    std::fs::rename(&temp, path)
+}
+
+/// Files under `root` that aren't ignored, with their paths relative to it
+fn walk(root: &Path) -> Vec<(String, PathBuf, std::fs::Metadata)> {
+    let walker = ignore::WalkBuilder::new(root)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+    walker
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let relative = entry.path().strip_prefix(root).ok()?;
+            let relative = relative.to_string_lossy().into_owned();
+            Some((relative, entry.into_path(), metadata))
+        })
+        .take(MAX_FILES)
+        .collect()
+}
+
+fn mtime_of(metadata: &std::fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn read_tags(path: &Path, len: u64) -> Vec<Tag> {
+    if len > MAX_TAGGED_FILE_BYTES || !tags::supports(path) {
+        return Vec::new();
+    }
+    match std::fs::read_to_string(path) {
+        Ok(content) => tags::tags_for(path, &content),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn wait_until(mut done: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !done() {
+            assert!(Instant::now() < deadline, "timed out waiting for the index");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_index_lists_files_and_symbols() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn parse_config() {}\n").unwrap();
+        std::fs::write(root.join("target/out.rs"), "fn generated() {}\n").unwrap();
+
+        let cache = dir.path().join("index.json");
+        let index = WorkspaceIndex::start(root.clone(), Some(cache.clone()));
+        wait_until(|| index.is_ready());
+
+        assert_eq!(
+            index.files().unwrap(),
+            vec![".gitignore".to_string(), "src/lib.rs".to_string()]
+        );
+        let symbols = index.search_symbols("pcfg", 10).unwrap();
+        assert_eq!(
+            symbols,
+            vec![Symbol {
+                name: "parse_config".to_string(),
+                kind: SymbolKind::Function,
+                path: "src/lib.rs".to_string(),
+                line: 1,
+            }]
+        );
+        assert!(index.search_symbols("generated", 10).unwrap().is_empty());
+        wait_until(|| cache.exists());
+
+        // Edits reach the index through update
+        let lib = root.join("src/lib.rs");
+        std::fs::write(&lib, "\npub struct Config;\n").unwrap();
+        let mtime = std::fs::metadata(&lib).unwrap().modified().unwrap();
+        let later = mtime + Duration::from_secs(1);
+        std::fs::File::options()
+            .write(true)
+            .open(&lib)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        index.update(vec![lib]);
+        wait_until(|| {
+            index
+                .search_symbols("Config", 10)
+                .is_some_and(|symbols| symbols.iter().any(|s| s.line == 2))
+        });
+        assert!(index.search_symbols("parse_config", 10).unwrap().is_empty());
+    }
+}
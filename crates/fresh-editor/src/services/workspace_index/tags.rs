@@ -0,0 +1,303 @@
+//! Ctags-style symbol extraction
+//!
+//! Definitions are found with one regex per construct, matched line by line.
+//! That misses what only a parser would see (definitions split over several
+//! lines, nested scopes), but needs no language server and is fast enough to
+//! run over a whole workspace.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// What kind of definition a tag is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    Function,
+    Type,
+    Module,
+    Constant,
+    Macro,
+}
+
+impl SymbolKind {
+    /// Short label shown next to the symbol
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Function => "fn",
+            Self::Type => "type",
+            Self::Module => "mod",
+            Self::Constant => "const",
+            Self::Macro => "macro",
+        }
+    }
+}
+
+/// A definition found in a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tag {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// 1-based line number
+    pub line: usize,
+}
+
+/// Patterns for one language; the first capture group is the name
+struct LanguageTags {
+    extensions: &'static [&'static str],
+    patterns: &'static [(SymbolKind, &'static str)],
+}
+
+const LANGUAGES: &[LanguageTags] = &[
+    LanguageTags {
+        extensions: &["rs"],
+        patterns: &[
+            (
+                SymbolKind::Function,
+                r#"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|async|unsafe)\s+|extern\s+(?:"[^"]*"\s+)?)*fn\s+([A-Za-z_]\w*)"#,
+            ),
+            (
+                SymbolKind::Type,
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:struct|enum|union|trait|type)\s+([A-Za-z_]\w*)",
+            ),
+            (
+                SymbolKind::Module,
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+([A-Za-z_]\w*)",
+            ),
+            (
+                SymbolKind::Constant,
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:const|static)\s+(?:mut\s+)?([A-Za-z_]\w*)\s*:",
+            ),
+            (SymbolKind::Macro, r"^\s*macro_rules!\s*([A-Za-z_]\w*)"),
+        ],
+    },
+    LanguageTags {
+        extensions: &["py", "pyi"],
+        patterns: &[
+            (
+                SymbolKind::Function,
+                r"^\s*(?:async\s+)?def\s+([A-Za-z_]\w*)",
+            ),
+            (SymbolKind::Type, r"^\s*class\s+([A-Za-z_]\w*)"),
+        ],
+    },
+    LanguageTags {
+        extensions: &["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts"],
+        patterns: &[
+            (
+                SymbolKind::Function,
+                r"^\s*(?:export\s+)?(?:default\s+)?(?:async\s+)?function\s*\*?\s*([A-Za-z_$][\w$]*)",
+            ),
+            (
+                SymbolKind::Function,
+                r"^\s*(?:export\s+)?(?:const|let)\s+([A-Za-z_$][\w$]*)\s*=\s*(?:async\s+)?(?:\([^)]*\)|[A-Za-z_$][\w$]*)\s*=>",
+            ),
+            (
+                SymbolKind::Type,
+                r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:class|interface|enum|type)\s+([A-Za-z_$][\w$]*)",
+            ),
+            (
+                SymbolKind::Module,
+                r"^\s*(?:export\s+)?(?:declare\s+)?namespace\s+([A-Za-z_$][\w$]*)",
+            ),
+        ],
+    },
+    LanguageTags {
+        extensions: &["go"],
+        patterns: &[
+            (
+                SymbolKind::Function,
+                r"^func\s+(?:\([^)]*\)\s*)?([A-Za-z_]\w*)",
+            ),
+            (SymbolKind::Type, r"^type\s+([A-Za-z_]\w*)"),
+            (SymbolKind::Constant, r"^const\s+([A-Za-z_]\w*)"),
+        ],
+    },
+    LanguageTags {
+        extensions: &["c", "h", "cc", "cpp", "cxx", "hpp", "hh", "hxx"],
+        patterns: &[
+            (
+                SymbolKind::Type,
+                r"^\s*(?:typedef\s+)?(?:struct|class|enum(?:\s+class)?|union)\s+([A-Za-z_]\w*)\s*(?:[{:]|$)",
+            ),
+            (SymbolKind::Macro, r"^\s*#\s*define\s+([A-Za-z_]\w*)"),
+            // A definition at the start of a line, not a call or declaration
+            (
+                SymbolKind::Function,
+                r"^(?:[A-Za-z_][\w:<>]*[\s*&]+)+([A-Za-z_][\w:~]*)\s*\([^;]*$",
+            ),
+            (SymbolKind::Module, r"^\s*namespace\s+([A-Za-z_]\w*)"),
+        ],
+    },
+    LanguageTags {
+        extensions: &["java", "kt", "cs"],
+        patterns: &[(
+            SymbolKind::Type,
+            r"^\s*(?:(?:public|private|protected|internal|static|final|abstract|sealed|data|open|partial)\s+)*(?:class|interface|enum|record|object|struct)\s+([A-Za-z_]\w*)",
+        )],
+    },
+    LanguageTags {
+        extensions: &["rb"],
+        patterns: &[
+            (
+                SymbolKind::Function,
+                r"^\s*def\s+(?:self\.)?([A-Za-z_]\w*[?!=]?)",
+            ),
+            (SymbolKind::Type, r"^\s*class\s+([A-Z]\w*)"),
+            (SymbolKind::Module, r"^\s*module\s+([A-Z]\w*)"),
+        ],
+    },
+    LanguageTags {
+        extensions: &["sh", "bash", "zsh"],
+        patterns: &[(
+            SymbolKind::Function,
+            r"^\s*(?:function\s+)?([A-Za-z_][\w-]*)\s*\(\)",
+        )],
+    },
+    LanguageTags {
+        extensions: &["lua"],
+        patterns: &[(
+            SymbolKind::Function,
+            r"^\s*(?:local\s+)?function\s+([A-Za-z_][\w.:]*)",
+        )],
+    },
+];
+
+/// Words the loose C function pattern can pick up as names
+const C_KEYWORDS: &[&str] = &["if", "for", "while", "switch", "return", "sizeof", "else"];
+
+struct CompiledLanguage {
+    extensions: &'static [&'static str],
+    patterns: Vec<(SymbolKind, Regex)>,
+}
+
+fn languages() -> &'static [CompiledLanguage] {
+    static COMPILED: OnceLock<Vec<CompiledLanguage>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        LANGUAGES
+            .iter()
+            .map(|language| CompiledLanguage {
+                extensions: language.extensions,
+                patterns: language
+                    .patterns
+                    .iter()
+                    .filter_map(|(kind, pattern)| match Regex::new(pattern) {
+                        Ok(regex) => Some((*kind, regex)),
+                        Err(e) => {
+                            tracing::warn!("Invalid tag pattern {:?}: {}", pattern, e);
+                            None
+                        }
+                    })
+                    .collect(),
+            })
+            .collect()
+    })
+}
+
+fn language_for(path: &Path) -> Option<&'static CompiledLanguage> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    languages()
+        .iter()
+        .find(|language| language.extensions.contains(&extension.as_str()))
+}
+
+/// Whether definitions can be found in files like `path`
+pub fn supports(path: &Path) -> bool {
+    language_for(path).is_some()
+}
+
+/// Find the definitions in `content`, the text of the file at `path`
+pub fn tags_for(path: &Path, content: &str) -> Vec<Tag> {
+    let Some(language) = language_for(path) else {
+        return Vec::new();
+    };
+    let mut tags = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        for (kind, regex) in &language.patterns {
+            let Some(name) = regex.captures(line).and_then(|c| c.get(1)) else {
+                continue;
+            };
+            if C_KEYWORDS.contains(&name.as_str()) {
+                continue;
+            }
+            tags.push(Tag {
+                name: name.as_str().to_string(),
+                kind: *kind,
+                line: index + 1,
+            });
+            break;
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(path: &str, content: &str) -> Vec<(String, SymbolKind, usize)> {
+        tags_for(Path::new(path), content)
+            .into_iter()
+            .map(|tag| (tag.name, tag.kind, tag.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_tags() {
+        let content = "\
+pub struct Editor {
+    buffers: Vec<Buffer>,
+}
+
+impl Editor {
+    pub(crate) async fn open(&mut self) {}
+    fn close(&mut self) {}
+}
+
+pub const MAX_FILES: usize = 10;
+mod tests;
+macro_rules! log {}
+";
+        assert_eq!(
+            names("src/app.rs", content),
+            vec![
+                ("Editor".to_string(), SymbolKind::Type, 1),
+                ("open".to_string(), SymbolKind::Function, 6),
+                ("close".to_string(), SymbolKind::Function, 7),
+                ("MAX_FILES".to_string(), SymbolKind::Constant, 10),
+                ("tests".to_string(), SymbolKind::Module, 11),
+                ("log".to_string(), SymbolKind::Macro, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_c_tags_skip_calls_and_declarations() {
+        let content = "\
+#define MAX 10
+struct point {
+int add(int a, int b)
+{
+    if (a > b)
+        return add(b, a);
+}
+int declared(void);
+";
+        assert_eq!(
+            names("math.c", content),
+            vec![
+                ("MAX".to_string(), SymbolKind::Macro, 1),
+                ("point".to_string(), SymbolKind::Type, 2),
+                ("add".to_string(), SymbolKind::Function, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_files_have_no_tags() {
+        assert!(!supports(Path::new("notes.txt")));
+        assert!(tags_for(Path::new("notes.txt"), "fn main() {}").is_empty());
+        assert!(supports(Path::new("script.PY")));
+    }
+}
@@ -0,0 +1,139 @@
+//! Workspace trust
+//!
+//! Project-local configuration (`.fresh/config.json`, `.fresh/session.json`)
+//! and workspace plugins (`{working_dir}/plugins`) can run arbitrary commands,
+//! e.g. through formatter or LSP server settings. Until the user trusts a
+//! workspace, those parts are ignored.
+//!
+//! ## Storage
+//!
+//! Decisions are stored in `$XDG_DATA_HOME/fresh/workspace_trust.json` as a
+//! map from canonical workspace path to `"trusted"` or `"untrusted"`. A
+//! workspace without a decision is treated as untrusted, and the editor asks
+//! once when it has something to gate.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name of the trust store inside the data directory
+const TRUST_FILE_NAME: &str = "workspace_trust.json";
+
+/// The user's decision for a workspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustDecision {
+    Trusted,
+    Untrusted,
+}
+
+/// Persisted trust decisions for workspaces
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceTrust {
+    path: PathBuf,
+    decisions: BTreeMap<PathBuf, TrustDecision>,
+}
+
+impl WorkspaceTrust {
+    /// Load the trust store from the data directory (empty if missing or unreadable)
+    pub fn load(data_dir: &Path) -> Self {
+        let path = data_dir.join(TRUST_FILE_NAME);
+        let decisions = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| match serde_json::from_str(&contents) {
+                Ok(decisions) => Some(decisions),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid trust store {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self { path, decisions }
+    }
+
+    /// The decision for a workspace, if the user made one
+    ///
+    /// A decision for a parent directory also applies to the workspaces below it;
+    /// the closest one wins.
+    pub fn decision(&self, working_dir: &Path) -> Option<TrustDecision> {
+        let dir = normalize(working_dir);
+        dir.ancestors()
+            .find_map(|ancestor| self.decisions.get(ancestor).copied())
+    }
+
+    /// Whether the workspace's project config and plugins may be used
+    pub fn is_trusted(&self, working_dir: &Path) -> bool {
+        self.decision(working_dir) == Some(TrustDecision::Trusted)
+    }
+
+    /// Record a decision for a workspace and save the store
+    pub fn set_decision(&mut self, working_dir: &Path, decision: TrustDecision) -> io::Result<()> {
+        self.decisions.insert(normalize(working_dir), decision);
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&self.decisions).map_err(io::Error::other)?;
+
+        // Write atomically: temp file + rename
+        let temp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&temp_path, json)?;
+        std::fs::rename(&temp_path, &self.path)
+    }
+}
+
+/// Whether a workspace is trusted, reading the store from the data directory
+pub fn is_workspace_trusted(data_dir: &Path, working_dir: &Path) -> bool {
+    WorkspaceTrust::load(data_dir).is_trusted(working_dir)
+}
+
+/// Directory holding a workspace's plugins
+pub fn workspace_plugins_dir(working_dir: &Path) -> PathBuf {
+    working_dir.join("plugins")
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_workspace_is_untrusted() {
+        let temp = tempfile::tempdir().unwrap();
+        let trust = WorkspaceTrust::load(temp.path());
+        assert_eq!(trust.decision(temp.path()), None);
+        assert!(!trust.is_trusted(temp.path()));
+    }
+
+    #[test]
+    fn test_decisions_persist_and_apply_to_subdirectories() {
+        let temp = tempfile::tempdir().unwrap();
+        let data_dir = temp.path().join("data");
+        let project = temp.path().join("project");
+        let nested = project.join("crates").join("core");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let mut trust = WorkspaceTrust::load(&data_dir);
+        trust
+            .set_decision(&project, TrustDecision::Trusted)
+            .unwrap();
+
+        assert!(is_workspace_trusted(&data_dir, &project));
+        assert!(is_workspace_trusted(&data_dir, &nested));
+
+        // The closest decision wins
+        trust
+            .set_decision(&nested, TrustDecision::Untrusted)
+            .unwrap();
+        let reloaded = WorkspaceTrust::load(&data_dir);
+        assert_eq!(reloaded.decision(&nested), Some(TrustDecision::Untrusted));
+        assert!(reloaded.is_trusted(&project));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config::RenderWhitespace;
 use crate::model::buffer::{Buffer, LineNumber};
 use crate::model::cursor::{Cursor, Cursors};
 use crate::model::document_model::{
@@ -13,6 +14,7 @@ use crate::primitives::grammar::GrammarRegistry;
 use crate::primitives::highlight_engine::HighlightEngine;
 use crate::primitives::highlighter::Language;
 use crate::primitives::indent::IndentCalculator;
+use crate::primitives::indent_rules::IndentRules;
 use crate::primitives::reference_highlighter::ReferenceHighlighter;
 use crate::primitives::text_property::TextPropertyManager;
 use crate::view::bracket_highlight_overlay::BracketHighlightOverlay;
@@ -57,6 +59,9 @@ pub struct EditorState {
     /// Auto-indent calculator for smart indentation (RefCell for interior mutability)
     pub indent_calculator: RefCell<IndentCalculator>,
 
+    /// Indentation rules declared by the buffer's language, if any
+    pub indent_rules: Option<IndentRules>,
+
     /// Overlays for visual decorations (underlines, highlights, etc.)
     pub overlays: OverlayManager,
 
@@ -109,6 +114,27 @@ pub struct EditorState {
     /// Used for visual display of tab characters and indent calculations.
     pub tab_size: usize,
 
+    /// Columns at which to draw vertical rulers
+    /// Set from the editor config and language or glob scopes.
+    pub rulers: Vec<usize>,
+
+    /// Whether to shade the text area past the last ruler
+    /// Set from the editor config and language or glob scopes.
+    pub wrap_guide_shading: bool,
+
+    /// Which spaces are drawn as visible indicators
+    /// Set from the editor config.
+    pub render_whitespace: RenderWhitespace,
+
+    /// Tab size set by a language or glob scope, or detected from the
+    /// buffer's indentation, used for indentation instead of the editor's
+    /// tab_size
+    pub tab_size_override: Option<usize>,
+
+    /// Line wrap set by a language or glob scope, applied to the view
+    /// whenever this buffer is shown (None keeps the view's setting)
+    pub line_wrap_override: Option<bool>,
+
     /// Semantic highlighter for word occurrence highlighting
     pub reference_highlighter: ReferenceHighlighter,
 
@@ -160,6 +186,7 @@ impl EditorState {
             cursors: Cursors::new(),
             highlighter: HighlightEngine::None, // No file path, so no syntax highlighting
             indent_calculator: RefCell::new(IndentCalculator::new()),
+            indent_rules: None,
             overlays: OverlayManager::new(),
             marker_list: MarkerList::new(),
             virtual_texts: VirtualTextManager::new(),
@@ -174,6 +201,11 @@ impl EditorState {
             show_whitespace_tabs: true,
             use_tabs: false,
             tab_size: 4, // Default tab size
+            rulers: Vec::new(),
+            wrap_guide_shading: false,
+            render_whitespace: RenderWhitespace::default(),
+            tab_size_override: None,
+            line_wrap_override: None,
             reference_highlighter: ReferenceHighlighter::new(),
             view_mode: ViewMode::Source,
             debug_highlight_mode: false,
@@ -253,6 +285,7 @@ impl EditorState {
             cursors: Cursors::new(),
             highlighter,
             indent_calculator: RefCell::new(IndentCalculator::new()),
+            indent_rules: None,
             overlays: OverlayManager::new(),
             marker_list,
             virtual_texts: VirtualTextManager::new(),
@@ -267,6 +300,11 @@ impl EditorState {
             show_whitespace_tabs: true,
             use_tabs: false,
             tab_size: 4,
+            rulers: Vec::new(),
+            wrap_guide_shading: false,
+            render_whitespace: RenderWhitespace::default(),
+            tab_size_override: None,
+            line_wrap_override: None,
             reference_highlighter,
             view_mode: ViewMode::Source,
             debug_highlight_mode: false,
@@ -298,7 +336,18 @@ impl EditorState {
         fs: Arc<dyn FileSystem + Send + Sync>,
     ) -> anyhow::Result<Self> {
         let buffer = Buffer::load_from_file(path, large_file_threshold, fs)?;
+        Ok(Self::from_buffer_with_languages(
+            buffer, path, registry, languages,
+        ))
+    }
 
+    /// Create an editor state for `buffer`, already loaded from `path`
+    pub fn from_buffer_with_languages(
+        buffer: Buffer,
+        path: &std::path::Path,
+        registry: &GrammarRegistry,
+        languages: &std::collections::HashMap<String, crate::config::LanguageConfig>,
+    ) -> Self {
         let highlighter = HighlightEngine::for_file_with_languages(path, registry, languages);
 
         let language = Language::from_path(path);
@@ -316,11 +365,12 @@ impl EditorState {
             marker_list.adjust_for_insert(0, buffer.len());
         }
 
-        Ok(Self {
+        Self {
             buffer,
             cursors: Cursors::new(),
             highlighter,
             indent_calculator: RefCell::new(IndentCalculator::new()),
+            indent_rules: None,
             overlays: OverlayManager::new(),
             marker_list,
             virtual_texts: VirtualTextManager::new(),
@@ -335,6 +385,11 @@ impl EditorState {
             show_whitespace_tabs: true,
             use_tabs: false,
             tab_size: 4,
+            rulers: Vec::new(),
+            wrap_guide_shading: false,
+            render_whitespace: RenderWhitespace::default(),
+            tab_size_override: None,
+            line_wrap_override: None,
             reference_highlighter,
             view_mode: ViewMode::Source,
             debug_highlight_mode: false,
@@ -346,7 +401,7 @@ impl EditorState {
             bracket_highlight_overlay: BracketHighlightOverlay::new(),
             semantic_tokens: None,
             language: language_name,
-        })
+        }
     }
 
     /// Create an editor state from a buffer and highlighter
@@ -374,6 +429,7 @@ impl EditorState {
             cursors: Cursors::new(),
             highlighter,
             indent_calculator: RefCell::new(IndentCalculator::new()),
+            indent_rules: None,
             overlays: OverlayManager::new(),
             marker_list,
             virtual_texts: VirtualTextManager::new(),
@@ -388,6 +444,11 @@ impl EditorState {
             show_whitespace_tabs: true,
             use_tabs: false,
             tab_size: 4,
+            rulers: Vec::new(),
+            wrap_guide_shading: false,
+            render_whitespace: RenderWhitespace::default(),
+            tab_size_override: None,
+            line_wrap_override: None,
             reference_highlighter,
             view_mode: ViewMode::Source,
             debug_highlight_mode: false,
@@ -418,9 +479,8 @@ impl EditorState {
         // Insert text into buffer
         self.buffer.insert(position, text);
 
-        // Invalidate highlight cache for edited range
-        self.highlighter
-            .invalidate_range(position..position + text.len());
+        // Update highlight cache for edited range
+        self.highlighter.notify_insert(position, text.len());
 
         // Note: reference_highlight_overlay uses markers that auto-adjust,
         // so no manual invalidation needed
@@ -466,8 +526,8 @@ impl EditorState {
         // Delete from buffer
         self.buffer.delete(range.clone());
 
-        // Invalidate highlight cache for edited range
-        self.highlighter.invalidate_range(range.clone());
+        // Update highlight cache for edited range
+        self.highlighter.notify_delete(range.clone());
 
         // Note: reference_highlight_overlay uses markers that auto-adjust,
         // so no manual invalidation needed
@@ -794,6 +854,12 @@ impl EditorState {
         }
     }
 
+    /// Columns per indent level: the language or glob scope's tab size, or
+    /// the width detected from the buffer, else the tab size
+    pub fn indent_width(&self) -> usize {
+        self.tab_size_override.unwrap_or(self.tab_size)
+    }
+
     /// Get the primary cursor
     pub fn primary_cursor(&self) -> &Cursor {
         self.cursors.primary()
@@ -12,6 +12,8 @@ pub mod context_keys {
     pub const LINE_NUMBERS: &str = "line_numbers";
     pub const LINE_WRAP: &str = "line_wrap";
     pub const COMPOSE_MODE: &str = "compose_mode";
+    pub const FOCUS_MODE: &str = "focus_mode";
+    pub const SCROLL_LOCK: &str = "scroll_lock";
     pub const FILE_EXPLORER: &str = "file_explorer";
     pub const MENU_BAR: &str = "menu_bar";
     pub const FILE_EXPLORER_FOCUSED: &str = "file_explorer_focused";
@@ -24,6 +26,9 @@ pub mod context_keys {
     pub const FORMATTER_AVAILABLE: &str = "formatter_available";
     pub const INLAY_HINTS: &str = "inlay_hints";
     pub const SESSION_MODE: &str = "session_mode";
+    pub const FOLLOWING_USER: &str = "following_user";
+    pub const SEARCH_CASE_SENSITIVE: &str = "search_case_sensitive";
+    pub const SEARCH_WHOLE_WORD: &str = "search_whole_word";
 }
 
 /// Configuration for process resource limits
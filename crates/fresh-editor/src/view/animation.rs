@@ -0,0 +1,139 @@
+//! Time-based animations
+//!
+//! An animation is a plain value sampled with the current time when a frame is
+//! drawn. Owners keep them alongside the state they animate and report whether
+//! any are still running so the frame loop keeps rendering at 60fps until they
+//! finish.
+
+use ratatui::style::Color;
+use std::time::{Duration, Instant};
+
+/// Duration of smooth scrolling for page jumps
+pub const SCROLL_DURATION: Duration = Duration::from_millis(150);
+/// Duration of the highlight flashed on the cursor line after a jump
+pub const FLASH_DURATION: Duration = Duration::from_millis(400);
+/// Duration of panels opening or collapsing
+pub const PANEL_DURATION: Duration = Duration::from_millis(120);
+
+/// Easing curve applied to linear progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// Fast start with a gentle stop
+    EaseOutCubic,
+    /// Slow start and stop
+    EaseInOutQuad,
+}
+
+impl Easing {
+    /// Map linear progress `t` (clamped to 0.0..=1.0) onto the curve
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOutQuad => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// Progress from 0.0 to 1.0 over a fixed duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Animation {
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Animation {
+    pub fn new(start: Instant, duration: Duration, easing: Easing) -> Self {
+        Self {
+            start,
+            duration,
+            easing,
+        }
+    }
+
+    /// Eased progress at `now`
+    pub fn progress(&self, now: Instant) -> f64 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let elapsed = now.saturating_duration_since(self.start);
+        self.easing
+            .apply(elapsed.as_secs_f64() / self.duration.as_secs_f64())
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= self.duration
+    }
+
+    /// Value between `from` and `to` at `now`
+    pub fn interpolate(&self, from: f64, to: f64, now: Instant) -> f64 {
+        from + (to - from) * self.progress(now)
+    }
+}
+
+/// Blend `from` towards `to` by `t`
+///
+/// RGB colors are mixed channel by channel; other colors can't be mixed and
+/// switch over halfway.
+pub fn blend_color(from: Color, to: Color, t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    match (from, to) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+        }
+        _ if t < 0.5 => from,
+        _ => to,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_easing_endpoints() {
+        for easing in [Easing::Linear, Easing::EaseOutCubic, Easing::EaseInOutQuad] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert!(Easing::EaseOutCubic.apply(0.5) > 0.5);
+        assert_eq!(Easing::EaseInOutQuad.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn test_animation_progress() {
+        let start = Instant::now();
+        let animation = Animation::new(start, Duration::from_millis(100), Easing::Linear);
+
+        assert_eq!(animation.interpolate(10.0, 20.0, start), 10.0);
+        let halfway = animation.interpolate(10.0, 20.0, start + Duration::from_millis(50));
+        assert!((halfway - 15.0).abs() < 1e-9);
+        assert!(!animation.is_finished(start + Duration::from_millis(99)));
+        assert!(animation.is_finished(start + Duration::from_millis(100)));
+        assert_eq!(
+            animation.interpolate(10.0, 20.0, start + Duration::from_secs(5)),
+            20.0
+        );
+    }
+
+    #[test]
+    fn test_blend_color() {
+        assert_eq!(
+            blend_color(Color::Rgb(0, 0, 0), Color::Rgb(200, 100, 50), 0.5),
+            Color::Rgb(100, 50, 25)
+        );
+        assert_eq!(blend_color(Color::Red, Color::Reset, 0.4), Color::Red);
+        assert_eq!(blend_color(Color::Red, Color::Reset, 0.6), Color::Reset);
+    }
+}
@@ -176,6 +176,17 @@ fn render_capture_phase(
                 .fg(theme.diagnostic_warning_fg)
                 .add_modifier(Modifier::BOLD),
         )]),
+        Line::from(vec![
+            Span::raw(format!("{}: ", t!("calibration.keyboard_protocol"))),
+            Span::styled(
+                if wizard.keyboard_protocol {
+                    t!("calibration.protocol_kitty").to_string()
+                } else {
+                    t!("calibration.protocol_legacy").to_string()
+                },
+                Style::default().fg(theme.line_number_fg),
+            ),
+        ]),
     ];
 
     let instructions_para = Paragraph::new(instructions)
@@ -12,8 +12,12 @@
 //! ```
 //!
 //! The Editor will automatically convert colors during rendering based on the capability.
+//! Themes are also quantized when they are loaded (see [`quantize_theme`]) so
+//! they can provide explicit fallbacks and keep highlights readable.
 
+use crate::view::theme::{color_to_rgb, Theme, COLOR_CUBE_LEVELS};
 use ratatui::style::Color;
+use std::collections::{HashMap, HashSet};
 
 /// Terminal color capability levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -30,8 +34,13 @@ impl ColorCapability {
     /// Detect the terminal's color capability
     /// Can be overridden with FRESH_COLOR_MODE env var: "truecolor", "256", or "16"
     pub fn detect() -> Self {
+        Self::detect_from(|key| std::env::var(key).ok())
+    }
+
+    /// Detect the color capability from environment variables looked up with `env`
+    pub fn detect_from(env: impl Fn(&str) -> Option<String>) -> Self {
         // Check for manual override first
-        if let Ok(mode) = std::env::var("FRESH_COLOR_MODE") {
+        if let Some(mode) = env("FRESH_COLOR_MODE") {
             match mode.to_lowercase().as_str() {
                 "truecolor" | "24bit" | "true" => return ColorCapability::TrueColor,
                 "256" | "256color" => return ColorCapability::Color256,
@@ -42,7 +51,7 @@ impl ColorCapability {
 
         // Check TERM first for multiplexers that don't support truecolor
         // (they may pass through COLORTERM from the outer terminal)
-        if let Ok(term) = std::env::var("TERM") {
+        if let Some(term) = env("TERM") {
             let t = term.to_lowercase();
 
             // GNU Screen doesn't support truecolor - cap at 256
@@ -56,7 +65,7 @@ impl ColorCapability {
                     return ColorCapability::TrueColor;
                 }
                 // Check COLORTERM - tmux can pass through truecolor if configured
-                if let Ok(colorterm) = std::env::var("COLORTERM") {
+                if let Some(colorterm) = env("COLORTERM") {
                     let ct = colorterm.to_lowercase();
                     if ct == "truecolor" || ct == "24bit" {
                         return ColorCapability::TrueColor;
@@ -67,7 +76,7 @@ impl ColorCapability {
         }
 
         // Check COLORTERM - reliable for truecolor (but not inside Screen/tmux)
-        if let Ok(colorterm) = std::env::var("COLORTERM") {
+        if let Some(colorterm) = env("COLORTERM") {
             let ct = colorterm.to_lowercase();
             if ct == "truecolor" || ct == "24bit" {
                 return ColorCapability::TrueColor;
@@ -75,12 +84,12 @@ impl ColorCapability {
         }
 
         // Windows Terminal sets WT_SESSION and supports truecolor
-        if std::env::var("WT_SESSION").is_ok() {
+        if env("WT_SESSION").is_some() {
             return ColorCapability::TrueColor;
         }
 
         // Check TERM for other indicators
-        if let Ok(term) = std::env::var("TERM") {
+        if let Some(term) = env("TERM") {
             let t = term.to_lowercase();
 
             // Check for truecolor indicators
@@ -144,130 +153,67 @@ fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
         return 232 + ((gray - 8) * 23 / 240) as u8;
     }
 
-    // Map to 6x6x6 color cube (indices 16-231)
-    // Each RGB component maps to 0-5
-    let r_idx = if r < 48 {
-        0
-    } else {
-        ((r as u16 - 35) * 5 / 220).min(5) as u8
-    };
-    let g_idx = if g < 48 {
-        0
-    } else {
-        ((g as u16 - 35) * 5 / 220).min(5) as u8
-    };
-    let b_idx = if b < 48 {
-        0
-    } else {
-        ((b as u16 - 35) * 5 / 220).min(5) as u8
-    };
+    // Map each component to the nearest of the 6 cube levels (indices 16-231)
+    let r_idx = nearest_cube_level(r);
+    let g_idx = nearest_cube_level(g);
+    let b_idx = nearest_cube_level(b);
 
     16 + 36 * r_idx + 6 * g_idx + b_idx
 }
 
-/// Convert an RGB color to the nearest basic 16 ANSI color
-fn rgb_to_16(r: u8, g: u8, b: u8) -> Color {
-    // Calculate luminance and saturation to help with color matching
-    let max = r.max(g).max(b);
-    let min = r.min(g).min(b);
-    let lum = (max as u16 + min as u16) / 2;
-    let is_bright = lum > 127;
-
-    // Check for grayscale
-    let gray_threshold = 30;
-    if max - min < gray_threshold {
-        return if lum < 64 {
-            Color::Black
-        } else if lum < 128 {
-            Color::DarkGray
-        } else if lum < 192 {
-            Color::Gray
-        } else {
-            Color::White
-        };
-    }
-
-    // Determine dominant color(s)
-    let r_dom = r >= g && r >= b;
-    let g_dom = g >= r && g >= b;
-    let b_dom = b >= r && b >= g;
-
-    // Determine if secondary colors are significant
-    let threshold = max / 2;
-    let r_sig = r > threshold;
-    let g_sig = g > threshold;
-    let b_sig = b > threshold;
-
-    // Map to ANSI colors
-    match (r_dom, g_dom, b_dom, r_sig, g_sig, b_sig) {
-        // Pure colors
-        (true, false, false, true, false, false) => {
-            if is_bright {
-                Color::LightRed
-            } else {
-                Color::Red
-            }
-        }
-        (false, true, false, false, true, false) => {
-            if is_bright {
-                Color::LightGreen
-            } else {
-                Color::Green
-            }
-        }
-        (false, false, true, false, false, true) => {
-            if is_bright {
-                Color::LightBlue
-            } else {
-                Color::Blue
-            }
-        }
-
-        // Yellow (red + green)
-        (_, _, false, true, true, false) => {
-            if is_bright {
-                Color::LightYellow
-            } else {
-                Color::Yellow
-            }
-        }
-
-        // Cyan (green + blue)
-        (false, _, _, false, true, true) => {
-            if is_bright {
-                Color::LightCyan
-            } else {
-                Color::Cyan
-            }
-        }
+/// Index of the color cube level nearest to a component value
+fn nearest_cube_level(value: u8) -> u8 {
+    COLOR_CUBE_LEVELS
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, level)| level.abs_diff(value))
+        .map_or(0, |(idx, _)| idx as u8)
+}
 
-        // Magenta (red + blue)
-        (_, false, _, true, false, true) => {
-            if is_bright {
-                Color::LightMagenta
-            } else {
-                Color::Magenta
-            }
-        }
+/// The basic 16 ANSI colors
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// Perceptual distance between two RGB colors ("redmean" approximation)
+fn color_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let r_mean = (a.0 as i32 + b.0 as i32) / 2;
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    (((512 + r_mean) * dr * dr) >> 8) as u32
+        + (4 * dg * dg) as u32
+        + (((767 - r_mean) * db * db) >> 8) as u32
+}
 
-        // White-ish (all colors significant)
-        (_, _, _, true, true, true) => {
-            if is_bright {
-                Color::White
-            } else {
-                Color::Gray
-            }
-        }
+/// Nearest basic 16 ANSI color, optionally skipping one that is already taken
+fn nearest_16(rgb: (u8, u8, u8), exclude: Option<Color>) -> Color {
+    ANSI_COLORS
+        .iter()
+        .copied()
+        .filter(|&color| Some(color) != exclude)
+        .min_by_key(|&color| color_distance(rgb, color_to_rgb(color).unwrap_or_default()))
+        .unwrap_or(Color::White)
+}
 
-        // Fallback
-        _ => {
-            if is_bright {
-                Color::White
-            } else {
-                Color::DarkGray
-            }
-        }
-    }
+/// Convert an RGB color to the nearest basic 16 ANSI color
+fn rgb_to_16(r: u8, g: u8, b: u8) -> Color {
+    nearest_16((r, g, b), None)
 }
 
 /// Convert a Color to the appropriate format for the terminal's capability
@@ -289,46 +235,125 @@ pub fn convert_color(color: Color, capability: ColorCapability) -> Color {
 /// Convert a 256-color index to the nearest 16 color
 fn indexed_to_16(idx: u8) -> Color {
     match idx {
-        // Standard colors (0-7)
-        0 => Color::Black,
-        1 => Color::Red,
-        2 => Color::Green,
-        3 => Color::Yellow,
-        4 => Color::Blue,
-        5 => Color::Magenta,
-        6 => Color::Cyan,
-        7 => Color::Gray,
-        // Bright colors (8-15)
-        8 => Color::DarkGray,
-        9 => Color::LightRed,
-        10 => Color::LightGreen,
-        11 => Color::LightYellow,
-        12 => Color::LightBlue,
-        13 => Color::LightMagenta,
-        14 => Color::LightCyan,
-        15 => Color::White,
-        // Color cube (16-231) - convert back to RGB then to 16
-        16..=231 => {
-            let idx = idx - 16;
-            let r = (idx / 36) * 51;
-            let g = ((idx % 36) / 6) * 51;
-            let b = (idx % 6) * 51;
-            rgb_to_16(r, g, b)
+        0..=15 => ANSI_COLORS[idx as usize],
+        _ => color_to_rgb(Color::Indexed(idx)).map_or(Color::White, |rgb| nearest_16(rgb, None)),
+    }
+}
+
+/// Highlights that must stay distinguishable from the background they are
+/// drawn over: (highlight, background)
+const DISTINCT_BACKGROUNDS: &[(&str, &str)] = &[
+    ("editor.selection_bg", "editor.bg"),
+    ("search.match_bg", "editor.bg"),
+    ("ui.status_bar_bg", "editor.bg"),
+    ("ui.tab_active_bg", "ui.tab_inactive_bg"),
+    ("ui.popup_selection_bg", "ui.popup_bg"),
+    ("ui.suggestion_selected_bg", "ui.suggestion_bg"),
+    ("ui.menu_highlight_bg", "ui.menu_dropdown_bg"),
+    ("ui.prompt_selection_bg", "ui.prompt_bg"),
+];
+
+/// Text colors that must stay readable on their background: (text, background)
+const READABLE_TEXT: &[(&str, &str)] = &[
+    ("editor.fg", "editor.bg"),
+    ("editor.line_number_fg", "editor.line_number_bg"),
+    ("editor.whitespace_fg", "editor.bg"),
+    ("editor.ruler_fg", "editor.bg"),
+    ("search.match_fg", "search.match_bg"),
+    ("ui.status_bar_fg", "ui.status_bar_bg"),
+    ("ui.tab_active_fg", "ui.tab_active_bg"),
+    ("ui.tab_inactive_fg", "ui.tab_inactive_bg"),
+    ("ui.menu_fg", "ui.menu_bg"),
+    ("ui.menu_dropdown_fg", "ui.menu_dropdown_bg"),
+    ("ui.menu_highlight_fg", "ui.menu_highlight_bg"),
+    ("ui.prompt_fg", "ui.prompt_bg"),
+    ("ui.prompt_selection_fg", "ui.prompt_selection_bg"),
+    ("ui.popup_text_fg", "ui.popup_bg"),
+    ("ui.popup_selection_fg", "ui.popup_selection_bg"),
+    ("ui.help_fg", "ui.help_bg"),
+    ("ui.settings_selected_fg", "ui.settings_selected_bg"),
+];
+
+/// Map a theme's colors onto the terminal's palette
+///
+/// Colors listed in the theme's `fallbacks` for this capability are used as
+/// given; the rest are mapped to the nearest palette color. With only 16
+/// colors many shades collapse onto the same one, so the generated colors are
+/// adjusted: highlights that would match the background they are drawn over
+/// take the next nearest color, and text that would match its background
+/// becomes black or white.
+pub fn quantize_theme(theme: &Theme, capability: ColorCapability) -> Theme {
+    let fallbacks = match capability {
+        ColorCapability::TrueColor => return theme.clone(),
+        ColorCapability::Color256 => &theme.fallbacks.color256,
+        ColorCapability::Color16 => &theme.fallbacks.color16,
+    };
+
+    let mut original = theme.clone();
+    let original: HashMap<&str, Color> = original
+        .colors_mut()
+        .into_iter()
+        .map(|(key, color)| (key, *color))
+        .collect();
+
+    let mut quantized = theme.clone();
+    let mut colors: HashMap<&str, &mut Color> = quantized.colors_mut().into_iter().collect();
+    let mut explicit = HashSet::new();
+    for (key, color) in colors.iter_mut() {
+        match fallbacks.get(*key) {
+            Some(fallback) => {
+                **color = fallback.clone().into();
+                explicit.insert(*key);
+            }
+            None => **color = convert_color(**color, capability),
+        }
+    }
+
+    if capability == ColorCapability::Color16 {
+        for &(highlight, background) in DISTINCT_BACKGROUNDS {
+            let (Some(bg), Some(rgb)) = (
+                colors.get(background).map(|c| **c),
+                original.get(highlight).and_then(|c| color_to_rgb(*c)),
+            ) else {
+                continue;
+            };
+            if let Some(color) = colors.get_mut(highlight) {
+                if !explicit.contains(highlight) && bg != Color::Reset && **color == bg {
+                    **color = nearest_16(rgb, Some(bg));
+                }
+            }
         }
-        // Grayscale (232-255)
-        232..=255 => {
-            let gray = (idx - 232) * 10 + 8;
-            if gray < 64 {
-                Color::Black
-            } else if gray < 128 {
-                Color::DarkGray
-            } else if gray < 192 {
-                Color::Gray
-            } else {
-                Color::White
+        for &(text, background) in READABLE_TEXT {
+            let Some(bg) = colors.get(background).map(|c| **c) else {
+                continue;
+            };
+            if let Some(color) = colors.get_mut(text) {
+                if !explicit.contains(text) && bg != Color::Reset && **color == bg {
+                    **color = contrasting_16(bg);
+                }
             }
         }
     }
+
+    for (scope, color) in quantized.semantic.iter_mut() {
+        *color = match fallbacks.get(&format!("semantic.{}", scope)) {
+            Some(fallback) => fallback.clone().into(),
+            None => convert_color(*color, capability),
+        };
+    }
+
+    quantized
+}
+
+/// Black or white, whichever reads better on `background`
+fn contrasting_16(background: Color) -> Color {
+    let (r, g, b) = color_to_rgb(background).unwrap_or_default();
+    let luminance = (299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000;
+    if luminance > 127 {
+        Color::Black
+    } else {
+        Color::White
+    }
 }
 
 /// Convert all colors in a ratatui Buffer for the given color capability
@@ -436,6 +461,94 @@ mod tests {
         assert_eq!(rgb_to_16(255, 255, 255), Color::White);
     }
 
+    #[test]
+    fn test_rgb_to_256_nearest_cube_level() {
+        // 100 -> 95 (1), 150 -> 135 (2), 200 -> 215 (4)
+        assert_eq!(rgb_to_256(100, 150, 200), 16 + 36 + 2 * 6 + 4);
+    }
+
+    #[test]
+    fn test_indexed_to_16() {
+        assert_eq!(indexed_to_16(9), Color::LightRed);
+        assert_eq!(indexed_to_16(196), Color::LightRed);
+        assert_eq!(indexed_to_16(16), Color::Black);
+        assert_eq!(indexed_to_16(255), Color::Gray);
+    }
+
+    fn theme_json(extra: &str) -> Theme {
+        Theme::from_json(&format!(
+            r#"{{"name":"test",
+                "editor":{{"bg":[30,30,30],"fg":[40,40,40],"selection_bg":[45,45,45]}},
+                "ui":{{}},"search":{{}},"diagnostic":{{}},"syntax":{{}}{extra}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_quantize_theme_truecolor_unchanged() {
+        let theme = theme_json("");
+        let quantized = quantize_theme(&theme, ColorCapability::TrueColor);
+        assert_eq!(quantized.editor_bg, Color::Rgb(30, 30, 30));
+    }
+
+    #[test]
+    fn test_quantize_theme_maps_to_palette() {
+        let theme = theme_json("");
+        let quantized = quantize_theme(&theme, ColorCapability::Color256);
+        assert_eq!(quantized.editor_bg, Color::Indexed(rgb_to_256(30, 30, 30)));
+        assert_eq!(
+            quantized.syntax_keyword,
+            convert_color(theme.syntax_keyword, ColorCapability::Color256)
+        );
+    }
+
+    #[test]
+    fn test_quantize_theme_16_keeps_highlights_visible() {
+        let theme = theme_json("");
+        let quantized = quantize_theme(&theme, ColorCapability::Color16);
+        // All three shades are nearest to black
+        assert_eq!(quantized.editor_bg, Color::Black);
+        assert_ne!(quantized.selection_bg, Color::Black);
+        assert_eq!(quantized.editor_fg, Color::White);
+    }
+
+    #[test]
+    fn test_quantize_theme_uses_fallbacks() {
+        let theme = theme_json(
+            r#","fallbacks":{"256":{"editor.bg":234},"16":{"editor.bg":"Blue","editor.fg":"Blue"}}"#,
+        );
+        let quantized = quantize_theme(&theme, ColorCapability::Color256);
+        assert_eq!(quantized.editor_bg, Color::Indexed(234));
+
+        let quantized = quantize_theme(&theme, ColorCapability::Color16);
+        assert_eq!(quantized.editor_bg, Color::Blue);
+        // Explicit fallbacks are not adjusted for contrast
+        assert_eq!(quantized.editor_fg, Color::Blue);
+    }
+
+    #[test]
+    fn test_quantize_theme_semantic_scopes() {
+        let theme = theme_json(
+            r#","semantic":{"parameter":[255,0,0],"enumMember":[0,0,255]},"fallbacks":{"16":{"semantic.enumMember":"Cyan"}}"#,
+        );
+        let quantized = quantize_theme(&theme, ColorCapability::Color16);
+        assert_eq!(
+            quantized.semantic["parameter"],
+            convert_color(Color::Rgb(255, 0, 0), ColorCapability::Color16)
+        );
+        assert_eq!(quantized.semantic["enumMember"], Color::Cyan);
+    }
+
+    #[test]
+    fn test_quantize_pairs_use_theme_keys() {
+        let mut theme = theme_json("");
+        let keys: HashSet<&str> = theme.colors_mut().into_iter().map(|(key, _)| key).collect();
+        for (a, b) in DISTINCT_BACKGROUNDS.iter().chain(READABLE_TEXT) {
+            assert!(keys.contains(a), "unknown theme key {a}");
+            assert!(keys.contains(b), "unknown theme key {b}");
+        }
+    }
+
     #[test]
     fn test_convert_color_truecolor() {
         let color = Color::Rgb(100, 150, 200);
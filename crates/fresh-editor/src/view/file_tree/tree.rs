@@ -222,6 +222,12 @@ impl FileTree {
     ///
     /// This is useful when filesystem contents have changed.
     pub async fn refresh_node(&mut self, id: NodeId) -> io::Result<()> {
+        // Bypass any cached listing (remote filesystems cache directory reads)
+        if let Some(node) = self.get_node(id) {
+            let path = node.entry.path.clone();
+            self.fs_manager.invalidate_dir(&path).await;
+        }
+
         // Collapse and re-expand
         self.collapse_node(id);
         self.expand_node(id).await
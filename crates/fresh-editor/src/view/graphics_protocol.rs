@@ -0,0 +1,330 @@
+//! Terminal graphics protocol detection and image encoding
+//!
+//! Image previews are drawn with the kitty graphics protocol or with sixel,
+//! whichever the terminal speaks. Like [`crate::view::color_support`], support
+//! is detected from the environment at startup and passed to the Editor:
+//! ```ignore
+//! let protocol = GraphicsProtocol::detect();
+//! editor.set_graphics_protocol(protocol, cell_size);
+//! ```
+//!
+//! The escape sequences built here are written straight to the terminal
+//! after a frame is drawn, so they save and restore the cursor around the
+//! image.
+
+use base64::Engine as _;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use ratatui::layout::Rect;
+use std::fmt::Write as _;
+use std::io::Write as _;
+
+/// Largest base64 payload the kitty protocol accepts in one escape sequence
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Cell size in pixels assumed when the terminal does not report it
+pub const DEFAULT_CELL_SIZE: (u16, u16) = (8, 16);
+
+/// Levels per channel of the color cube used for sixel images
+const SIXEL_LEVELS: usize = 6;
+
+/// How the terminal can display images
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// The kitty graphics protocol (kitty, WezTerm, Ghostty)
+    Kitty,
+    /// DEC sixel graphics (foot, mlterm, Windows Terminal, iTerm2, ...)
+    Sixel,
+    /// No image support; previews show the image info only
+    None,
+}
+
+impl GraphicsProtocol {
+    /// Detect the terminal's graphics protocol
+    /// Can be overridden with FRESH_IMAGE_PROTOCOL env var: "kitty", "sixel" or "none"
+    pub fn detect() -> Self {
+        Self::detect_from(|key| std::env::var(key).ok())
+    }
+
+    /// Detect the graphics protocol from environment variables looked up with `env`
+    pub fn detect_from(env: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(protocol) = env("FRESH_IMAGE_PROTOCOL") {
+            match protocol.to_lowercase().as_str() {
+                "kitty" => return GraphicsProtocol::Kitty,
+                "sixel" => return GraphicsProtocol::Sixel,
+                "none" | "off" => return GraphicsProtocol::None,
+                _ => {} // Fall through to auto-detection
+            }
+        }
+
+        let term = env("TERM").unwrap_or_default().to_lowercase();
+
+        // Multiplexers only pass images through when specially configured,
+        // and redraw over them on their own
+        if env("TMUX").is_some() || term.starts_with("tmux") || term.starts_with("screen") {
+            return GraphicsProtocol::None;
+        }
+
+        let term_program = env("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+
+        if env("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || term_program == "wezterm"
+            || term_program == "ghostty"
+        {
+            return GraphicsProtocol::Kitty;
+        }
+
+        if term.starts_with("foot")
+            || term.starts_with("mlterm")
+            || term.starts_with("contour")
+            || term_program == "iterm.app"
+            || env("WT_SESSION").is_some()
+        {
+            return GraphicsProtocol::Sixel;
+        }
+
+        GraphicsProtocol::None
+    }
+
+    /// Short name for diagnostics
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphicsProtocol::Kitty => "kitty graphics",
+            GraphicsProtocol::Sixel => "sixel",
+            GraphicsProtocol::None => "none",
+        }
+    }
+
+    /// Escape sequence drawing an RGBA image of `width`×`height` pixels with
+    /// its top-left corner at the top-left cell of `area`
+    pub fn draw_sequence(self, rgba: &[u8], width: u32, height: u32, area: Rect) -> Vec<u8> {
+        let image = match self {
+            GraphicsProtocol::Kitty => kitty_sequence(rgba, width, height),
+            GraphicsProtocol::Sixel => sixel_sequence(rgba, width, height),
+            GraphicsProtocol::None => return Vec::new(),
+        };
+        let mut out = format!("\x1b7\x1b[{};{}H", area.y + 1, area.x + 1).into_bytes();
+        out.extend_from_slice(&image);
+        out.extend_from_slice(b"\x1b8");
+        out
+    }
+
+    /// Escape sequence removing every image drawn by [`Self::draw_sequence`]
+    ///
+    /// Sixel images are plain pixels in the cells, so they go away only when
+    /// those cells are redrawn; the caller has to force a full redraw.
+    pub fn clear_sequence(self) -> Vec<u8> {
+        match self {
+            GraphicsProtocol::Kitty => b"\x1b_Ga=d,d=A,q=2\x1b\\".to_vec(),
+            GraphicsProtocol::Sixel | GraphicsProtocol::None => Vec::new(),
+        }
+    }
+}
+
+/// Transmit and display an image in one go: zlib-compressed RGBA, base64
+/// encoded and split into chunks. `C=1` keeps the cursor in place and `q=2`
+/// silences the terminal's replies, which would otherwise arrive as input.
+fn kitty_sequence(rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::fast());
+    let compressed = encoder
+        .write_all(rgba)
+        .and_then(|_| encoder.finish())
+        .unwrap_or_default();
+    let payload = base64::engine::general_purpose::STANDARD.encode(compressed);
+
+    let mut out = String::with_capacity(payload.len() + payload.len() / KITTY_CHUNK_SIZE * 16);
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    let last = chunks.len().saturating_sub(1);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i < last);
+        if i == 0 {
+            let _ = write!(
+                out,
+                "\x1b_Ga=T,f=32,o=z,s={},v={},C=1,q=2,m={};",
+                width, height, more
+            );
+        } else {
+            let _ = write!(out, "\x1b_Gm={};", more);
+        }
+        out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        out.push_str("\x1b\\");
+    }
+    out.into_bytes()
+}
+
+/// Index of the nearest color in the sixel color cube, or `None` for a
+/// (mostly) transparent pixel, which keeps the cell background
+fn sixel_color(pixel: &[u8]) -> Option<usize> {
+    if pixel[3] < 128 {
+        return None;
+    }
+    let level = |c: u8| (c as usize * (SIXEL_LEVELS - 1) + 127) / 255;
+    Some(
+        level(pixel[0]) * SIXEL_LEVELS * SIXEL_LEVELS
+            + level(pixel[1]) * SIXEL_LEVELS
+            + level(pixel[2]),
+    )
+}
+
+/// Append a run of `count` identical sixels, using the repeat introducer
+/// for longer runs
+fn push_sixel_run(out: &mut String, sixel: u8, count: usize) {
+    let c = (b'?' + sixel) as char;
+    if count > 3 {
+        let _ = write!(out, "!{}{}", count, c);
+    } else {
+        for _ in 0..count {
+            out.push(c);
+        }
+    }
+}
+
+/// Encode an image as sixel with a fixed 6×6×6 color cube palette
+fn sixel_sequence(rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let (width, height) = (width as usize, height as usize);
+    let colors: Vec<Option<usize>> = rgba.chunks_exact(4).map(sixel_color).collect();
+    let palette_size = SIXEL_LEVELS * SIXEL_LEVELS * SIXEL_LEVELS;
+
+    // P2=1: pixels that are not set keep the background
+    let mut out = String::from("\x1bP0;1;0q");
+    let _ = write!(out, "\"1;1;{};{}", width, height);
+    for i in 0..palette_size {
+        let percent = |level: usize| level * 100 / (SIXEL_LEVELS - 1);
+        let _ = write!(
+            out,
+            "#{};2;{};{};{}",
+            i,
+            percent(i / (SIXEL_LEVELS * SIXEL_LEVELS)),
+            percent(i / SIXEL_LEVELS % SIXEL_LEVELS),
+            percent(i % SIXEL_LEVELS)
+        );
+    }
+
+    for band in (0..height).step_by(6) {
+        let rows = band..(band + 6).min(height);
+        let mut used = vec![false; palette_size];
+        for y in rows.clone() {
+            for color in colors[y * width..(y + 1) * width].iter().flatten() {
+                used[*color] = true;
+            }
+        }
+
+        let mut first = true;
+        for color in (0..palette_size).filter(|&c| used[c]) {
+            if !first {
+                out.push('$');
+            }
+            first = false;
+            let _ = write!(out, "#{}", color);
+
+            let mut run: Option<(u8, usize)> = None;
+            for x in 0..width {
+                let sixel = rows
+                    .clone()
+                    .filter(|y| colors[y * width + x] == Some(color))
+                    .fold(0u8, |bits, y| bits | 1 << (y - band));
+                run = match run {
+                    Some((current, count)) if current == sixel => Some((current, count + 1)),
+                    Some((current, count)) => {
+                        push_sixel_run(&mut out, current, count);
+                        Some((sixel, 1))
+                    }
+                    None => Some((sixel, 1)),
+                };
+            }
+            if let Some((current, count)) = run {
+                push_sixel_run(&mut out, current, count);
+            }
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect(vars: &[(&str, &str)]) -> GraphicsProtocol {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        GraphicsProtocol::detect_from(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn test_detect_terminals() {
+        assert_eq!(detect(&[("TERM", "xterm-kitty")]), GraphicsProtocol::Kitty);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("TERM_PROGRAM", "WezTerm")]),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(detect(&[("TERM", "foot")]), GraphicsProtocol::Sixel);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("WT_SESSION", "1")]),
+            GraphicsProtocol::Sixel
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color")]),
+            GraphicsProtocol::None
+        );
+    }
+
+    #[test]
+    fn test_detect_multiplexer_and_override() {
+        assert_eq!(
+            detect(&[("TERM", "tmux-256color"), ("KITTY_WINDOW_ID", "1")]),
+            GraphicsProtocol::None
+        );
+        assert_eq!(
+            detect(&[("TERM", "screen"), ("FRESH_IMAGE_PROTOCOL", "sixel")]),
+            GraphicsProtocol::Sixel
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("FRESH_IMAGE_PROTOCOL", "none")]),
+            GraphicsProtocol::None
+        );
+    }
+
+    #[test]
+    fn test_kitty_sequence_is_chunked() {
+        // Noise compresses badly, so the payload spans several chunks
+        let rgba: Vec<u8> = (0..64 * 64 * 4u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect();
+        let out = String::from_utf8(kitty_sequence(&rgba, 64, 64)).unwrap();
+        assert!(out.starts_with("\x1b_Ga=T,f=32,o=z,s=64,v=64,C=1,q=2,m=1;"));
+        assert!(out.ends_with("\x1b\\"));
+        assert!(out.contains("\x1b_Gm=0;"));
+        assert_eq!(out.matches("\x1b_G").count(), out.matches("\x1b\\").count());
+    }
+
+    #[test]
+    fn test_sixel_sequence() {
+        // 2×7 image: a red column and a transparent column, two bands
+        let mut rgba = Vec::new();
+        for _ in 0..7 {
+            rgba.extend_from_slice(&[255, 0, 0, 255]);
+            rgba.extend_from_slice(&[0, 0, 0, 0]);
+        }
+        let out = String::from_utf8(sixel_sequence(&rgba, 2, 7)).unwrap();
+        assert!(out.starts_with("\x1bP0;1;0q\"1;1;2;7#0;2;0;0;0"));
+        assert!(out.ends_with("#180~?-#180@?-\x1b\\"));
+    }
+
+    #[test]
+    fn test_draw_sequence_positions_cursor() {
+        let rgba = [0u8, 0, 0, 255];
+        let out = GraphicsProtocol::Sixel.draw_sequence(&rgba, 1, 1, Rect::new(4, 2, 1, 1));
+        assert!(out.starts_with(b"\x1b7\x1b[3;5H\x1bP"));
+        assert!(out.ends_with(b"\x1b\\\x1b8"));
+        assert!(GraphicsProtocol::None
+            .draw_sequence(&rgba, 1, 1, Rect::new(0, 0, 1, 1))
+            .is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+//! OSC 8 hyperlinks for URLs on screen
+//!
+//! After a frame is rendered, the URLs visible in each split, in buffers and
+//! terminal output alike, are turned into OSC 8 hyperlinks, so the terminal
+//! lets you click them. Only URLs are linked: a file path is opened inside
+//! the editor with "Open Link Under Cursor" instead of by the terminal.
+//!
+//! Like [`crate::view::color_support`], support is detected from the
+//! environment at startup and passed to the Editor:
+//! ```ignore
+//! editor.set_hyperlinks(hyperlinks::detect());
+//! ```
+//!
+//! ratatui counts the escape sequence into the width of a cell, so a link is
+//! written two characters per cell, the workaround from ratatui's hyperlink
+//! example.
+
+use crate::services::link_detection::LinkDetector;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+
+/// `TERM` values of terminals known to support OSC 8
+const HYPERLINK_TERMS: &[&str] = &[
+    "kitty",
+    "foot",
+    "alacritty",
+    "ghostty",
+    "wezterm",
+    "contour",
+    "rio",
+];
+
+/// Whether the terminal shows OSC 8 hyperlinks
+/// Can be overridden with FRESH_HYPERLINKS env var: "1"/"on" or "0"/"off"
+pub fn detect() -> bool {
+    detect_from(|key| std::env::var(key).ok())
+}
+
+/// Detect hyperlink support from environment variables looked up with `env`
+pub fn detect_from(env: impl Fn(&str) -> Option<String>) -> bool {
+    if let Some(value) = env("FRESH_HYPERLINKS") {
+        match value.to_lowercase().as_str() {
+            "1" | "on" | "true" | "yes" => return true,
+            "0" | "off" | "false" | "no" => return false,
+            _ => {} // Fall through to auto-detection
+        }
+    }
+
+    let term = env("TERM").unwrap_or_default().to_lowercase();
+
+    // Multiplexers drop the sequences unless configured for them, and the
+    // Linux console prints them
+    if env("TMUX").is_some()
+        || env("STY").is_some()
+        || term.starts_with("tmux")
+        || term.starts_with("screen")
+        || term == "linux"
+        || term == "dumb"
+    {
+        return false;
+    }
+
+    let term_program = env("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+    let vte_version = env("VTE_VERSION")
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(0);
+
+    env("KITTY_WINDOW_ID").is_some()
+        || env("WT_SESSION").is_some()
+        || env("KONSOLE_VERSION").is_some()
+        || vte_version >= 5000
+        || matches!(
+            term_program.as_str(),
+            "iterm.app" | "wezterm" | "vscode" | "ghostty" | "hyper" | "tabby" | "rio"
+        )
+        || HYPERLINK_TERMS.iter().any(|name| term.contains(name))
+}
+
+/// Turn the URLs in `area` of `buffer` into hyperlinks
+pub fn apply(buffer: &mut Buffer, area: Rect, detector: &LinkDetector) {
+    let area = area.intersection(buffer.area);
+    for y in area.top()..area.bottom() {
+        // One byte per cell, so byte offsets are column offsets. Cells
+        // holding anything but ASCII end a URL.
+        let text: String = (area.left()..area.right())
+            .map(|x| {
+                let symbol = buffer[(x, y)].symbol();
+                match symbol.as_bytes() {
+                    [byte] if byte.is_ascii_graphic() => *byte as char,
+                    _ => ' ',
+                }
+            })
+            .collect();
+
+        for link in detector.urls(&text) {
+            let url = &text[link.range.clone()];
+            let start = area.left() + link.range.start as u16;
+            let end = area.left() + link.range.end as u16;
+            for x in (start..end).step_by(2) {
+                let pair = if x + 1 < end {
+                    format!("{}{}", buffer[(x, y)].symbol(), buffer[(x + 1, y)].symbol())
+                } else {
+                    buffer[(x, y)].symbol().to_string()
+                };
+                buffer[(x, y)].set_symbol(&hyperlink(url, &pair));
+            }
+        }
+    }
+}
+
+/// `text` as an OSC 8 hyperlink to `url`
+fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x07{}\x1b]8;;\x07", url, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect(vars: &[(&str, &str)]) -> bool {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        detect_from(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn test_detect() {
+        assert!(detect(&[("TERM", "xterm-kitty")]));
+        assert!(detect(&[
+            ("TERM", "xterm-256color"),
+            ("VTE_VERSION", "7006")
+        ]));
+        assert!(!detect(&[("TERM", "xterm-256color")]));
+        assert!(!detect(&[
+            ("TERM", "tmux-256color"),
+            ("TERM_PROGRAM", "WezTerm")
+        ]));
+        assert!(detect(&[("TERM", "linux"), ("FRESH_HYPERLINKS", "on")]));
+        assert!(!detect(&[("TERM", "foot"), ("FRESH_HYPERLINKS", "0")]));
+    }
+
+    #[test]
+    fn test_apply_links_urls_two_cells_at_a_time() {
+        let area = Rect::new(0, 0, 20, 1);
+        let mut buffer = Buffer::empty(area);
+        buffer.set_string(0, 0, "see http://a.io.", Default::default());
+        apply(&mut buffer, area, &LinkDetector::new());
+
+        let link = |text: &str| hyperlink("http://a.io", text);
+        assert_eq!(buffer[(3, 0)].symbol(), " ");
+        assert_eq!(buffer[(4, 0)].symbol(), link("ht"));
+        assert_eq!(buffer[(5, 0)].symbol(), "t");
+        assert_eq!(buffer[(14, 0)].symbol(), link("o"));
+        assert_eq!(buffer[(15, 0)].symbol(), ".");
+    }
+}
@@ -0,0 +1,293 @@
+//! Image previews for PNG, JPEG and SVG files
+//!
+//! A preview replaces the buffer's text in every split showing it: the image
+//! itself when the terminal has a [`GraphicsProtocol`], and below it (or on
+//! its own) an info line with the format, dimensions and file size.
+//!
+//! The renderer only draws the info text and leaves the image cells blank.
+//! The Editor then writes the image over those cells after the frame, using
+//! [`ImagePreview::placement`] to find them.
+//!
+//! [`GraphicsProtocol`]: crate::view::graphics_protocol::GraphicsProtocol
+
+use crate::app::file_open::format_size;
+use crate::view::theme::Theme;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Wrap};
+use ratatui::Frame;
+use resvg::{tiny_skia, usvg};
+use std::io::Cursor;
+use std::path::Path;
+
+/// Rows taken by the name and info lines
+const INFO_ROWS: u16 = 2;
+
+/// Image formats that can be previewed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Svg,
+}
+
+impl ImageFormat {
+    /// The format of `path`, judged by its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "png" => Some(ImageFormat::Png),
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+            "svg" => Some(ImageFormat::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::Svg => "SVG",
+        }
+    }
+
+    fn raster_format(self) -> Option<image::ImageFormat> {
+        match self {
+            ImageFormat::Png => Some(image::ImageFormat::Png),
+            ImageFormat::Jpeg => Some(image::ImageFormat::Jpeg),
+            ImageFormat::Svg => None,
+        }
+    }
+}
+
+/// A decoded image scaled to fit a preview area
+struct ScaledImage {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+/// The preview of one image buffer
+pub struct ImagePreview {
+    pub name: String,
+    pub format: ImageFormat,
+    /// Image size in pixels (for SVG, the document size)
+    pub width: u32,
+    pub height: u32,
+    /// Size of the file in bytes
+    pub file_size: u64,
+    /// Terminal cell size in pixels, or `None` when the terminal cannot
+    /// display images and only the info is shown
+    pub cell_size: Option<(u16, u16)>,
+    data: Vec<u8>,
+    /// The last scaled rendering, reused while the preview area is unchanged
+    scaled: Option<ScaledImage>,
+}
+
+impl ImagePreview {
+    /// Read the dimensions of an image file; the pixels are decoded only
+    /// when the image is drawn
+    pub fn load(name: String, format: ImageFormat, data: Vec<u8>) -> Result<Self, String> {
+        let (width, height) = match format.raster_format() {
+            Some(raster) => image::ImageReader::with_format(Cursor::new(&data), raster)
+                .into_dimensions()
+                .map_err(|e| e.to_string())?,
+            None => {
+                let tree = usvg::Tree::from_data(&data, &usvg::Options::default())
+                    .map_err(|e| e.to_string())?;
+                let size = tree.size().to_int_size();
+                (size.width(), size.height())
+            }
+        };
+        Ok(Self {
+            name,
+            format,
+            width,
+            height,
+            file_size: data.len() as u64,
+            cell_size: None,
+            data,
+            scaled: None,
+        })
+    }
+
+    /// The info line, e.g. `PNG · 800 × 600 · 12.3 KB`
+    pub fn info(&self) -> String {
+        format!(
+            "{} · {} × {} · {}",
+            self.format.name(),
+            self.width,
+            self.height,
+            format_size(self.file_size)
+        )
+    }
+
+    /// Where the image goes inside `area`: the cells it covers and the pixel
+    /// size it is scaled to. `None` when no image is shown.
+    ///
+    /// Raster images are only scaled down; SVGs are scaled to fit.
+    pub fn placement(&self, area: Rect) -> Option<(Rect, u32, u32)> {
+        let (cell_width, cell_height) = self.cell_size?;
+        let max_cols = area.width.saturating_sub(2);
+        let max_rows = area.height.saturating_sub(INFO_ROWS + 2);
+        if max_cols == 0 || max_rows == 0 || self.width == 0 || self.height == 0 {
+            return None;
+        }
+
+        let max_width = max_cols as f64 * cell_width as f64;
+        let max_height = max_rows as f64 * cell_height as f64;
+        let mut scale = (max_width / self.width as f64).min(max_height / self.height as f64);
+        if self.format != ImageFormat::Svg {
+            scale = scale.min(1.0);
+        }
+        let width = ((self.width as f64 * scale).floor() as u32).max(1);
+        let height = ((self.height as f64 * scale).floor() as u32).max(1);
+        let cols = (width.div_ceil(cell_width as u32) as u16).min(max_cols);
+        let rows = (height.div_ceil(cell_height as u32) as u16).min(max_rows);
+
+        let block_height = rows + 1 + INFO_ROWS;
+        let x = area.x + (area.width - cols) / 2;
+        let y = area.y + area.height.saturating_sub(block_height) / 2;
+        Some((Rect::new(x, y, cols, rows), width, height))
+    }
+
+    /// Draw the name and info lines, centered in `area` or below the image
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let bg = Style::default().bg(theme.editor_bg);
+        frame.render_widget(Paragraph::new("").style(bg), area);
+
+        let info_y = match self.placement(area) {
+            Some((image_area, _, _)) => image_area.bottom() + 1,
+            None => area.y + area.height.saturating_sub(INFO_ROWS) / 2,
+        };
+        let info_area = Rect::new(
+            area.x,
+            info_y,
+            area.width,
+            INFO_ROWS.min(area.bottom().saturating_sub(info_y)),
+        );
+        let lines = vec![
+            Line::styled(
+                self.name.clone(),
+                Style::default()
+                    .fg(theme.editor_fg)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Line::styled(self.info(), Style::default().fg(theme.line_number_fg)),
+        ];
+        frame.render_widget(
+            Paragraph::new(lines)
+                .style(bg)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            info_area,
+        );
+    }
+
+    /// The image as RGBA pixels scaled to `width`×`height`, decoded on first
+    /// use and cached for the next frame
+    pub fn pixels(&mut self, width: u32, height: u32) -> Option<&[u8]> {
+        let cached = self
+            .scaled
+            .as_ref()
+            .is_some_and(|s| s.width == width && s.height == height);
+        if !cached {
+            let rgba = self.rasterize(width, height)?;
+            self.scaled = Some(ScaledImage {
+                width,
+                height,
+                rgba,
+            });
+        }
+        self.scaled.as_ref().map(|s| s.rgba.as_slice())
+    }
+
+    fn rasterize(&self, width: u32, height: u32) -> Option<Vec<u8>> {
+        match self.format.raster_format() {
+            Some(raster) => {
+                let image = image::load_from_memory_with_format(&self.data, raster)
+                    .map_err(|e| tracing::warn!("Failed to decode {}: {}", self.name, e))
+                    .ok()?;
+                Some(
+                    image
+                        .resize_exact(width, height, image::imageops::FilterType::Triangle)
+                        .to_rgba8()
+                        .into_raw(),
+                )
+            }
+            None => {
+                let tree = usvg::Tree::from_data(&self.data, &usvg::Options::default()).ok()?;
+                let mut pixmap = tiny_skia::Pixmap::new(width, height)?;
+                let size = tree.size();
+                let transform = tiny_skia::Transform::from_scale(
+                    width as f32 / size.width(),
+                    height as f32 / size.height(),
+                );
+                resvg::render(&tree, transform, &mut pixmap.as_mut());
+                Some(
+                    pixmap
+                        .pixels()
+                        .iter()
+                        .flat_map(|pixel| {
+                            let color = pixel.demultiply();
+                            [color.red(), color.green(), color.blue(), color.alpha()]
+                        })
+                        .collect(),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20"><rect width="40" height="20" fill="red"/></svg>"#;
+
+    fn svg_preview() -> ImagePreview {
+        ImagePreview::load("dot.svg".into(), ImageFormat::Svg, SVG.as_bytes().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ImageFormat::from_path(Path::new("a/photo.JPG")),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(
+            ImageFormat::from_path(Path::new("logo.svg")),
+            Some(ImageFormat::Svg)
+        );
+        assert_eq!(ImageFormat::from_path(Path::new("notes.txt")), None);
+    }
+
+    #[test]
+    fn test_svg_info() {
+        let preview = svg_preview();
+        assert_eq!(preview.info(), format!("SVG · 40 × 20 · {} B", SVG.len()));
+    }
+
+    #[test]
+    fn test_placement_needs_cell_size() {
+        let mut preview = svg_preview();
+        let area = Rect::new(0, 1, 40, 20);
+        assert_eq!(preview.placement(area), None);
+
+        // The SVG is scaled up to the 38 columns available, keeping its
+        // 2:1 aspect ratio, and centered with the info below
+        preview.cell_size = Some((10, 20));
+        let (cells, width, height) = preview.placement(area).unwrap();
+        assert_eq!((width, height), (380, 190));
+        assert_eq!(cells, Rect::new(1, 4, 38, 10));
+    }
+
+    #[test]
+    fn test_svg_pixels() {
+        let mut preview = svg_preview();
+        let pixels = preview.pixels(4, 2).unwrap();
+        assert_eq!(pixels.len(), 4 * 2 * 4);
+        assert_eq!(&pixels[..4], &[255, 0, 0, 255]);
+    }
+}
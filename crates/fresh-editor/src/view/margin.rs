@@ -501,6 +501,23 @@ impl MarginManager {
         by_line
     }
 
+    /// Get the highest priority indicator at each byte position, ordered by
+    /// position
+    pub fn line_indicator_positions(&self) -> Vec<(usize, &LineIndicator)> {
+        let mut positions: Vec<(usize, &LineIndicator)> = self
+            .line_indicators
+            .iter()
+            .filter_map(|(&marker_id, indicators)| {
+                let byte_pos = self.indicator_markers.get_position(MarkerId(marker_id))?;
+                let indicator = indicators.values().max_by_key(|ind| ind.priority)?;
+                Some((byte_pos, indicator))
+            })
+            .collect();
+        positions.sort_by_key(|(byte_pos, indicator)| (*byte_pos, -indicator.priority));
+        positions.dedup_by_key(|(byte_pos, _)| *byte_pos);
+        positions
+    }
+
     /// Add an annotation to a margin
     pub fn add_annotation(&mut self, annotation: MarginAnnotation) {
         let annotations = match annotation.position {
@@ -860,6 +877,34 @@ mod tests {
         assert_eq!(retrieved.priority, 20);
     }
 
+    #[test]
+    fn test_line_indicator_positions() {
+        let mut manager = MarginManager::new();
+
+        manager.set_line_indicator(
+            line_to_byte(7),
+            "git-gutter".to_string(),
+            LineIndicator::new("│", Color::Green, 10),
+        );
+        manager.set_line_indicator(
+            line_to_byte(2),
+            "git-gutter".to_string(),
+            LineIndicator::new("│", Color::Yellow, 10),
+        );
+        manager.set_line_indicator(
+            line_to_byte(2),
+            "breakpoints".to_string(),
+            LineIndicator::new("●", Color::Red, 20),
+        );
+
+        let positions = manager.line_indicator_positions();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].0, line_to_byte(2));
+        assert_eq!(positions[0].1.color, Color::Red);
+        assert_eq!(positions[1].0, line_to_byte(7));
+        assert_eq!(positions[1].1.color, Color::Green);
+    }
+
     #[test]
     fn test_line_indicator_clear_namespace() {
         let mut manager = MarginManager::new();
@@ -39,6 +39,8 @@ pub mod settings;
 
 // Runtime-only modules (depend on app, state, config_io, input, or tree-sitter)
 #[cfg(feature = "runtime")]
+pub mod animation;
+#[cfg(feature = "runtime")]
 pub mod bracket_highlight_overlay;
 #[cfg(feature = "runtime")]
 pub mod calibration_wizard;
@@ -49,10 +51,18 @@ pub mod file_browser_input;
 #[cfg(feature = "runtime")]
 pub mod file_tree;
 #[cfg(feature = "runtime")]
+pub mod graphics_protocol;
+#[cfg(feature = "runtime")]
+pub mod hyperlinks;
+#[cfg(feature = "runtime")]
+pub mod image_preview;
+#[cfg(feature = "runtime")]
 pub mod keybinding_editor;
 #[cfg(feature = "runtime")]
 pub mod markdown;
 #[cfg(feature = "runtime")]
+pub mod peek_window;
+#[cfg(feature = "runtime")]
 pub mod popup;
 #[cfg(feature = "runtime")]
 pub mod popup_input;
@@ -67,6 +77,10 @@ pub mod query_replace_input;
 #[cfg(feature = "runtime")]
 pub mod reference_highlight_overlay;
 #[cfg(feature = "runtime")]
+pub mod render_profile;
+#[cfg(feature = "runtime")]
+pub mod scrollbar_marks;
+#[cfg(feature = "runtime")]
 pub mod split;
 #[cfg(feature = "runtime")]
 pub mod stream;
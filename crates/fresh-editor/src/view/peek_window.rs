@@ -0,0 +1,343 @@
+//! Peek window: an inline, scrollable view of another location
+//!
+//! Shows the result of Peek Definition / Peek References embedded below the
+//! cursor line, so the target can be read without switching buffers.
+//! Enter opens the selected location for real, Escape dismisses the window.
+
+use crate::view::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use rust_i18n::t;
+use std::path::PathBuf;
+
+/// Total height of the peek window including its border
+pub const PEEK_WINDOW_HEIGHT: u16 = 14;
+
+/// Lines of context shown above the target when a location is selected
+const CONTEXT_ABOVE: usize = 2;
+
+/// Which request produced the peek window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeekKind {
+    Definition,
+    References,
+}
+
+/// One location shown in the peek window, with an excerpt of its file
+#[derive(Debug, Clone)]
+pub struct PeekLocation {
+    pub path: PathBuf,
+    /// Path as shown in the title (relative to the project when possible)
+    pub display_path: String,
+    /// 0-based target line
+    pub line: usize,
+    /// LSP character offset of the target (UTF-16 code units)
+    pub character: usize,
+    /// 0-based line number of `lines[0]`
+    pub first_line: usize,
+    /// Excerpt of the file around the target
+    pub lines: Vec<String>,
+}
+
+impl PeekLocation {
+    /// Index of the target line within `lines`
+    fn target_index(&self) -> usize {
+        self.line.saturating_sub(self.first_line)
+    }
+}
+
+/// Result of a key press in the peek window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeekAction {
+    /// Key handled, window stays open
+    None,
+    /// Dismiss the window
+    Close,
+    /// Dismiss the window and open the location in its buffer
+    Open {
+        path: PathBuf,
+        line: usize,
+        character: usize,
+    },
+}
+
+/// State of an open peek window
+#[derive(Debug, Clone)]
+pub struct PeekWindow {
+    pub kind: PeekKind,
+    locations: Vec<PeekLocation>,
+    selected: usize,
+    /// First visible excerpt line of the selected location
+    scroll: usize,
+    /// Content rows shown in the last render (used for paging)
+    visible_rows: usize,
+}
+
+impl PeekWindow {
+    /// Create a peek window; returns None if there is nothing to show
+    pub fn new(kind: PeekKind, locations: Vec<PeekLocation>) -> Option<Self> {
+        if locations.is_empty() {
+            return None;
+        }
+        let mut peek = Self {
+            kind,
+            locations,
+            selected: 0,
+            scroll: 0,
+            visible_rows: PEEK_WINDOW_HEIGHT.saturating_sub(2) as usize,
+        };
+        peek.select(0);
+        Some(peek)
+    }
+
+    /// The currently selected location
+    pub fn selected(&self) -> &PeekLocation {
+        &self.locations[self.selected]
+    }
+
+    /// Index of the selected location
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// Number of locations
+    pub fn location_count(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// First visible excerpt line
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.scroll = self.selected().target_index().saturating_sub(CONTEXT_ABOVE);
+        self.clamp_scroll();
+    }
+
+    /// Select the next location (wrapping)
+    pub fn select_next(&mut self) {
+        self.select((self.selected + 1) % self.locations.len());
+    }
+
+    /// Select the previous location (wrapping)
+    pub fn select_prev(&mut self) {
+        let count = self.locations.len();
+        self.select((self.selected + count - 1) % count);
+    }
+
+    /// Scroll the excerpt by `delta` lines
+    pub fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self.scroll.saturating_add_signed(delta);
+        self.clamp_scroll();
+    }
+
+    fn clamp_scroll(&mut self) {
+        let max = self
+            .selected()
+            .lines
+            .len()
+            .saturating_sub(self.visible_rows.max(1));
+        self.scroll = self.scroll.min(max);
+    }
+
+    /// Handle a key press while the peek window has focus
+    pub fn handle_key(&mut self, event: &KeyEvent) -> PeekAction {
+        let page = self.visible_rows.saturating_sub(1).max(1) as isize;
+        match (event.code, event.modifiers) {
+            (KeyCode::Esc, _) => return PeekAction::Close,
+            (KeyCode::Enter, _) => {
+                let location = self.selected();
+                return PeekAction::Open {
+                    path: location.path.clone(),
+                    line: location.line,
+                    character: location.character,
+                };
+            }
+            (KeyCode::Tab, KeyModifiers::NONE) => self.select_next(),
+            (KeyCode::BackTab, _) | (KeyCode::Tab, KeyModifiers::SHIFT) => self.select_prev(),
+            (KeyCode::Up, _) => self.scroll_by(-1),
+            (KeyCode::Down, _) => self.scroll_by(1),
+            (KeyCode::PageUp, _) => self.scroll_by(-page),
+            (KeyCode::PageDown, _) => self.scroll_by(page),
+            (KeyCode::Home, _) => self.scroll = 0,
+            (KeyCode::End, _) => self.scroll_by(isize::MAX),
+            _ => {}
+        }
+        PeekAction::None
+    }
+}
+
+/// Compute where the peek window goes inside a split's content area
+///
+/// It sits directly below `cursor_row` (relative to the content area), or
+/// above it when there isn't room for a useful window below.
+pub fn peek_window_area(content: Rect, cursor_row: u16) -> Rect {
+    let below = content.height.saturating_sub(cursor_row + 1);
+    let above = cursor_row.min(content.height);
+    let (y, height) = if below >= PEEK_WINDOW_HEIGHT.min(6) || below >= above {
+        (content.y + cursor_row + 1, below.min(PEEK_WINDOW_HEIGHT))
+    } else {
+        let height = above.min(PEEK_WINDOW_HEIGHT);
+        (content.y + cursor_row - height, height)
+    };
+    Rect {
+        x: content.x,
+        y,
+        width: content.width,
+        height,
+    }
+}
+
+/// Render the peek window into `area`
+pub fn render_peek_window(frame: &mut Frame, area: Rect, peek: &mut PeekWindow, theme: &Theme) {
+    if area.height < 3 || area.width < 10 {
+        return;
+    }
+
+    frame.render_widget(Clear, area);
+
+    let location = peek.selected();
+    let mut title = format!(" {}:{} ", location.display_path, location.line + 1);
+    if peek.location_count() > 1 {
+        title.push_str(&format!(
+            "({}/{}) ",
+            peek.selected_index() + 1,
+            peek.location_count()
+        ));
+    }
+    let hint = if peek.location_count() > 1 {
+        t!("peek.hint_multiple")
+    } else {
+        t!("peek.hint")
+    };
+
+    let block = Block::default()
+        .title(title)
+        .title_bottom(format!(" {} ", hint))
+        .borders(Borders::TOP | Borders::BOTTOM)
+        .border_style(Style::default().fg(theme.popup_border_fg))
+        .style(Style::default().bg(theme.editor_bg).fg(theme.editor_fg));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    peek.visible_rows = inner.height as usize;
+    peek.clamp_scroll();
+
+    let location = peek.selected();
+    let target = location.target_index();
+    let last_line_number = location.first_line + location.lines.len();
+    let gutter_width = last_line_number.to_string().len().max(3);
+
+    let lines: Vec<Line> = location
+        .lines
+        .iter()
+        .enumerate()
+        .skip(peek.scroll)
+        .take(inner.height as usize)
+        .map(|(idx, text)| {
+            let number = format!(
+                "{:>width$} ",
+                location.first_line + idx + 1,
+                width = gutter_width
+            );
+            let text = text.replace('\t', "    ");
+            let line = Line::from(vec![
+                Span::styled(number, Style::default().fg(theme.line_number_fg)),
+                Span::raw(text),
+            ]);
+            if idx == target {
+                line.style(Style::default().bg(theme.current_line_bg))
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(line: usize, line_count: usize) -> PeekLocation {
+        PeekLocation {
+            path: PathBuf::from("/tmp/lib.rs"),
+            display_path: "lib.rs".to_string(),
+            line,
+            character: 4,
+            first_line: 0,
+            lines: (0..line_count).map(|i| format!("line {}", i)).collect(),
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_new_scrolls_target_into_view() {
+        let peek = PeekWindow::new(PeekKind::Definition, vec![location(40, 100)]).unwrap();
+        assert_eq!(peek.scroll(), 38);
+        assert!(PeekWindow::new(PeekKind::Definition, Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_scroll_is_clamped() {
+        let mut peek = PeekWindow::new(PeekKind::Definition, vec![location(1, 20)]).unwrap();
+        peek.handle_key(&key(KeyCode::Up));
+        peek.handle_key(&key(KeyCode::Up));
+        assert_eq!(peek.scroll(), 0);
+        peek.handle_key(&key(KeyCode::End));
+        assert_eq!(peek.scroll(), 20 - 12);
+    }
+
+    #[test]
+    fn test_tab_cycles_locations() {
+        let mut peek = PeekWindow::new(
+            PeekKind::References,
+            vec![location(3, 50), location(30, 50)],
+        )
+        .unwrap();
+        peek.handle_key(&key(KeyCode::Tab));
+        assert_eq!(peek.selected_index(), 1);
+        assert_eq!(peek.scroll(), 28);
+        peek.handle_key(&key(KeyCode::Tab));
+        assert_eq!(peek.selected_index(), 0);
+        peek.handle_key(&key(KeyCode::BackTab));
+        assert_eq!(peek.selected_index(), 1);
+    }
+
+    #[test]
+    fn test_enter_opens_and_escape_closes() {
+        let mut peek = PeekWindow::new(PeekKind::Definition, vec![location(7, 20)]).unwrap();
+        assert_eq!(
+            peek.handle_key(&key(KeyCode::Enter)),
+            PeekAction::Open {
+                path: PathBuf::from("/tmp/lib.rs"),
+                line: 7,
+                character: 4,
+            }
+        );
+        assert_eq!(peek.handle_key(&key(KeyCode::Esc)), PeekAction::Close);
+    }
+
+    #[test]
+    fn test_area_flips_above_near_bottom() {
+        let content = Rect::new(0, 1, 80, 30);
+        let below = peek_window_area(content, 5);
+        assert_eq!((below.y, below.height), (7, PEEK_WINDOW_HEIGHT));
+
+        let above = peek_window_area(content, 28);
+        assert_eq!(above.y + above.height, 1 + 28);
+        assert_eq!(above.height, PEEK_WINDOW_HEIGHT);
+    }
+}
@@ -19,6 +19,14 @@ pub enum PromptType {
     ReloadWithEncoding,
     /// Switch to a different project folder (change working directory)
     SwitchProject,
+    /// Switch to a different config profile (restarts the editor)
+    SwitchProfile,
+    /// Address to share the active buffer on (Collab: Share Buffer)
+    CollabShare,
+    /// Address of a shared buffer to join (Collab: Join)
+    CollabJoin,
+    /// Attached client the view follows (Follow User)
+    FollowUser,
     /// Save current buffer to a new file
     SaveFileAs,
     /// Search for text in buffer
@@ -33,6 +41,14 @@ pub enum PromptType {
     QueryReplace { search: String },
     /// Query replace confirmation prompt (y/n/!/q for each match)
     QueryReplaceConfirm,
+    /// Structural search pattern
+    StructuralSearch,
+    /// Structural search pattern (will prompt for the replacement after)
+    StructuralReplaceSearch,
+    /// Replacement template for a structural pattern
+    StructuralReplace { pattern: String },
+    /// Confirm applying a previewed structural replace
+    ConfirmStructuralReplace,
     /// Execute a command by name (M-x)
     Command,
     /// Quick Open - unified prompt with prefix-based provider routing
@@ -63,10 +79,22 @@ pub enum PromptType {
     SetBookmark,
     /// Jump to a bookmark - prompts for register (0-9)
     JumpToBookmark,
+    /// Set a mark - prompts for a letter
+    SetNamedMark,
+    /// Jump to a mark (select from list)
+    JumpToNamedMark,
     /// Set compose width (empty clears to viewport)
     SetComposeWidth,
     /// Set tab size for current buffer
     SetTabSize,
+    /// Reindent the current buffer to a new indent width
+    ReindentBuffer,
+    /// Number the cursors from a start and step
+    InsertSequence,
+    /// Insert the current date/time (select a format or type a pattern)
+    InsertDateTime,
+    /// Align the selected lines on a delimiter
+    AlignSelection,
     /// Set line ending format for current buffer
     SetLineEnding,
     /// Set text encoding format for current buffer
@@ -75,6 +103,24 @@ pub enum PromptType {
     SetLanguage,
     /// Stop a running LSP server (select from list)
     StopLspServer,
+    /// Cancel a plugin background task (select from list)
+    CancelBackgroundTask,
+    /// Reload a loaded plugin (select from list)
+    ReloadPlugin,
+    /// Pick a saved version of the buffer's file (select from list)
+    LocalHistory {
+        buffer_id: crate::model::event::BufferId,
+    },
+    /// Compare or restore a saved version of the buffer's file
+    /// `version` is the time it was saved, in milliseconds since the epoch
+    LocalHistoryVersion {
+        buffer_id: crate::model::event::BufferId,
+        version: u64,
+    },
+    /// Pick an item of the current quickfix list
+    QuickfixItem,
+    /// Pick a list from the quickfix history
+    QuickfixHistory,
     /// Select a theme (select from list)
     /// Stores the original theme name for restoration on cancel
     SelectTheme { original_theme: String },
@@ -89,11 +135,25 @@ pub enum PromptType {
     /// Confirm reverting a modified file
     ConfirmRevert,
     /// Confirm saving over a file that changed on disk
-    ConfirmSaveConflict,
+    ConfirmSaveConflict {
+        buffer_id: crate::model::event::BufferId,
+    },
+    /// Confirm a save that would break a symlink or hard link
+    ConfirmSaveBreaksLink,
     /// Confirm saving with sudo after permission denied
     ConfirmSudoSave {
+        buffer_id: crate::model::event::BufferId,
         info: crate::model::buffer::SudoSaveRequired,
     },
+    /// Ask whether to edit a symlink or the file it points to
+    OpenSymlink { path: std::path::PathBuf },
+    /// Ask whether a sandboxed plugin may use a permission
+    PluginPermission {
+        plugin_name: String,
+        permission: fresh_core::config::PluginPermission,
+    },
+    /// Ask whether to trust the working directory's project config and plugins
+    WorkspaceTrust,
     /// Confirm overwriting an existing file during SaveAs
     ConfirmOverwriteFile { path: std::path::PathBuf },
     /// Confirm closing a modified buffer (save/discard/cancel)
@@ -112,6 +172,11 @@ pub enum PromptType {
         /// False if renaming an existing file (should keep focus in file explorer)
         is_new_file: bool,
     },
+    /// Confirm restarting into a release installed by Update Fresh Now
+    ConfirmRestartAfterUpdate {
+        version: String,
+        executable: std::path::PathBuf,
+    },
     /// Confirm deleting a file or directory in the file explorer
     ConfirmDeleteFile {
         path: std::path::PathBuf,
@@ -122,13 +187,26 @@ pub enum PromptType {
     ConfirmLargeFileEncoding { path: std::path::PathBuf },
     /// Switch to a tab by name (from the current split's open buffers)
     SwitchToTab,
+    /// Choose the tab group of a tab
+    SetTabGroup {
+        buffer_id: crate::model::event::BufferId,
+        split_id: crate::model::event::SplitId,
+    },
     /// Run shell command on buffer/selection
     /// If replace is true, replace the input with the output
     /// If replace is false, output goes to a new buffer
     ShellCommand { replace: bool },
+    /// Filter Through Command: replace the selection/buffer with the output
+    /// of a command run by the process spawner
+    FilterCommand,
+    /// Toggle Watch Task: command re-run on every save
+    WatchCommand,
     /// Async prompt from plugin (for editor.prompt() API)
     /// The result is returned via callback resolution
     AsyncPrompt,
+    /// Async quick pick from plugin (for editor.showQuickPick() API)
+    /// Only a selected suggestion can be confirmed; the result is its value
+    AsyncQuickPick { match_on_description: bool },
 }
 
 /// Prompt state for the minibuffer
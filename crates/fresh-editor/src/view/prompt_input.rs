@@ -242,7 +242,7 @@ impl InputHandler for Prompt {
                                     .input
                                     .chars()
                                     .next()
-                                    .filter(|c| *c == '>' || *c == '#' || *c == ':');
+                                    .filter(|c| matches!(c, '>' | '#' | '@' | ':'));
                                 if let Some(p) = prefix {
                                     self.input = format!("{}{}", p, value);
                                 } else {
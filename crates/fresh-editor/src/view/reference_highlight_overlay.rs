@@ -3,12 +3,17 @@
 //! This module manages word occurrence highlighting through overlays that
 //! automatically adjust their positions when text is edited. Unlike the
 //! old cache-based approach, overlays use markers that move with the text.
+//!
+//! Word-based occurrences are shown first; once they settle the editor asks
+//! the LSP server for `textDocument/documentHighlight` and, if it answers,
+//! swaps in the server's symbol-aware ranges.
 
 use crate::model::buffer::Buffer;
 use crate::model::marker::MarkerList;
 use crate::primitives::reference_highlighter::ReferenceHighlighter;
 use crate::view::overlay::{Overlay, OverlayFace, OverlayManager, OverlayNamespace};
 use ratatui::style::Color;
+use std::ops::Range;
 use std::time::{Duration, Instant};
 
 /// Default debounce delay for reference highlighting (150ms)
@@ -32,6 +37,9 @@ pub struct ReferenceHighlightOverlay {
     word_changed_at: Option<Instant>,
     /// Debounce delay before updating highlights
     debounce_delay: Duration,
+    /// Cursor position at which LSP document highlights should be requested.
+    /// Set when the word-based highlights settle; taken by the editor.
+    document_highlight_request: Option<usize>,
     /// Whether highlighting is enabled
     pub enabled: bool,
 }
@@ -44,6 +52,7 @@ impl ReferenceHighlightOverlay {
             pending_word: None,
             word_changed_at: None,
             debounce_delay: Duration::from_millis(DEFAULT_DEBOUNCE_MS),
+            document_highlight_request: None,
             enabled: true,
         }
     }
@@ -103,6 +112,8 @@ impl ReferenceHighlightOverlay {
                 // Debounce period elapsed - update overlays
                 self.current_word = self.pending_word.clone();
                 self.word_changed_at = None;
+                self.document_highlight_request =
+                    self.current_word.as_ref().map(|_| cursor_position);
 
                 self.apply_highlights(
                     buffer,
@@ -166,6 +177,43 @@ impl ReferenceHighlightOverlay {
         }
     }
 
+    /// Take the pending LSP document highlight request, if any
+    ///
+    /// Returns the cursor position the word-based highlights were computed for.
+    pub fn take_document_highlight_request(&mut self) -> Option<usize> {
+        self.document_highlight_request.take()
+    }
+
+    /// Replace the word-based highlights with ranges supplied by the LSP server
+    ///
+    /// Ignored if the cursor has since moved to a different word, so a slow
+    /// response can't clobber fresher highlights.
+    pub fn apply_lsp_highlights(
+        &mut self,
+        overlays: &mut OverlayManager,
+        marker_list: &mut MarkerList,
+        ranges: &[Range<usize>],
+        highlight_color: Color,
+    ) -> bool {
+        if self.current_word.is_none() || self.is_debouncing() {
+            return false;
+        }
+
+        let ns = reference_highlight_namespace();
+        overlays.clear_namespace(&ns, marker_list);
+
+        for range in ranges {
+            let face = OverlayFace::Background {
+                color: highlight_color,
+            };
+            let overlay = Overlay::with_namespace(marker_list, range.clone(), face, ns.clone())
+                .with_priority_value(5);
+
+            overlays.add(overlay);
+        }
+        true
+    }
+
     /// Check if a redraw is needed (debounce timer pending)
     pub fn needs_redraw(&self) -> Option<Duration> {
         self.word_changed_at.map(|changed_at| {
@@ -185,6 +233,7 @@ impl ReferenceHighlightOverlay {
         self.current_word = None;
         self.pending_word = None;
         self.word_changed_at = None;
+        self.document_highlight_request = None;
     }
 
     /// Check if currently debouncing
@@ -0,0 +1,105 @@
+//! Render profiler overlay
+//!
+//! A small box in the top-right corner with the last frame's timings per
+//! phase, their average over the recent frames, and how many of those frames
+//! went over the frame budget.
+
+use crate::services::render_profile::{FrameStats, FrameTimings, FRAME_BUDGET};
+use crate::view::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use rust_i18n::t;
+use std::time::Duration;
+
+/// Width of the overlay in characters
+const OVERLAY_WIDTH: u16 = 36;
+/// Height of the overlay: borders, header, five phases and the summary line
+const OVERLAY_HEIGHT: u16 = 9;
+
+fn millis(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Render the profiler overlay for `stats` (None before the first frame)
+pub fn render_profile_overlay(
+    frame: &mut Frame,
+    area: Rect,
+    stats: Option<&FrameStats>,
+    theme: &Theme,
+) {
+    let width = OVERLAY_WIDTH.min(area.width);
+    let height = OVERLAY_HEIGHT.min(area.height);
+    // Below the menu bar, clear of the right edge's scrollbar
+    let overlay_area = Rect {
+        x: area.x + area.width.saturating_sub(width + 2),
+        y: area.y + area.height.saturating_sub(height).min(1),
+        width,
+        height,
+    };
+    frame.render_widget(Clear, overlay_area);
+
+    let block = Block::default()
+        .title(t!("render_profile.title").to_string())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.editor_fg))
+        .style(Style::default().bg(theme.editor_bg).fg(theme.editor_fg));
+    let inner = block.inner(overlay_area);
+    frame.render_widget(block, overlay_area);
+
+    let muted = Style::default().fg(theme.line_number_fg);
+    let Some(stats) = stats else {
+        frame.render_widget(
+            Paragraph::new(t!("render_profile.waiting").to_string()).style(muted),
+            inner,
+        );
+        return;
+    };
+
+    let row = |label: &str, value: fn(&FrameTimings) -> Duration, style: Style| {
+        Line::from(vec![
+            Span::styled(format!("{:<10}", label), style),
+            Span::styled(format!("{:>10}", millis(value(&stats.last))), style),
+            Span::styled(format!("{:>10}", millis(value(&stats.average))), muted),
+        ])
+    };
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let total_style = if stats.last.total() > FRAME_BUDGET {
+        bold.fg(theme.diagnostic_error_fg)
+    } else {
+        bold
+    };
+
+    let lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "{:<10}{:>10}{:>10}",
+                "",
+                t!("render_profile.last"),
+                t!("render_profile.average")
+            ),
+            muted,
+        )),
+        row("events", |f| f.events, Style::default()),
+        row("highlight", |f| f.highlight, Style::default()),
+        row("layout", |f| f.layout, Style::default()),
+        row("draw", |f| f.draw, Style::default()),
+        row("total", FrameTimings::total, total_style),
+        Line::from(Span::styled(
+            t!(
+                "render_profile.budget",
+                worst = millis(stats.worst),
+                over = stats.over_budget,
+                frames = stats.frames,
+                budget = FRAME_BUDGET.as_millis()
+            )
+            .to_string(),
+            muted,
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
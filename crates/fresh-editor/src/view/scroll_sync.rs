@@ -1,7 +1,9 @@
-/// Scroll synchronization for side-by-side diff views
+/// Scroll synchronization for side-by-side diff views and locked splits
 ///
 /// This module implements marker-based sync anchors for synchronized scrolling
 /// between two panes showing different versions of a file (e.g., old vs new in a diff).
+/// "Lock Scrolling" uses the same groups with a single anchor holding the two
+/// panes' offset, so they scroll in parallel.
 ///
 /// Key design principles:
 /// - Single source of truth: `scroll_line` is the authoritative position
@@ -23,6 +25,17 @@ pub struct SyncAnchor {
     pub right_line: usize,
 }
 
+impl SyncAnchor {
+    /// Anchor that keeps two panes `right_line - left_line` lines apart,
+    /// clamping at the top of either buffer
+    pub fn offset(left_line: usize, right_line: usize) -> Self {
+        Self {
+            left_line: left_line.saturating_sub(right_line),
+            right_line: right_line.saturating_sub(left_line),
+        }
+    }
+}
+
 /// A unique identifier for a scroll sync group
 pub type ScrollSyncGroupId = u32;
 
@@ -161,27 +174,37 @@ impl ScrollSyncGroup {
 }
 
 /// Manager for scroll sync groups
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct ScrollSyncManager {
     /// Active scroll sync groups
     groups: Vec<ScrollSyncGroup>,
-    /// Next group ID to assign
+    /// Next group ID to assign. Counts down so editor-created groups never
+    /// take the IDs plugins pick, which count up from 1.
     next_id: ScrollSyncGroupId,
 }
 
+impl Default for ScrollSyncManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ScrollSyncManager {
     /// Create a new scroll sync manager
     pub fn new() -> Self {
         Self {
             groups: Vec::new(),
-            next_id: 1,
+            next_id: ScrollSyncGroupId::MAX,
         }
     }
 
     /// Create a new scroll sync group and return its ID
     pub fn create_group(&mut self, left_split: SplitId, right_split: SplitId) -> ScrollSyncGroupId {
+        while self.groups.iter().any(|g| g.id == self.next_id) {
+            self.next_id -= 1;
+        }
         let id = self.next_id;
-        self.next_id += 1;
+        self.next_id -= 1;
 
         let group = ScrollSyncGroup::new(id, left_split, right_split);
         self.groups.push(group);
@@ -356,4 +379,31 @@ mod tests {
         assert_eq!(group.left_scroll_line(), 55);
         assert_eq!(group.right_scroll_line(), 65); // 60 + 5
     }
+
+    #[test]
+    fn test_offset_anchor_keeps_distance() {
+        // Right pane 30 lines further down
+        let mut group = ScrollSyncGroup::new(1, SplitId(1), SplitId(2));
+        group.set_anchors(vec![SyncAnchor::offset(10, 40)]);
+        assert_eq!(group.left_to_right_line(0), 30);
+        assert_eq!(group.left_to_right_line(12), 42);
+        assert_eq!(group.right_to_left_line(42), 12);
+        assert_eq!(group.right_to_left_line(5), 0);
+
+        // Right pane 30 lines further up
+        group.set_anchors(vec![SyncAnchor::offset(40, 10)]);
+        assert_eq!(group.left_to_right_line(42), 12);
+        assert_eq!(group.left_to_right_line(5), 0);
+        assert_eq!(group.right_to_left_line(0), 30);
+    }
+
+    #[test]
+    fn test_created_groups_avoid_plugin_ids() {
+        let mut manager = ScrollSyncManager::new();
+        assert!(manager.create_group_with_id(1, SplitId(1), SplitId(2)));
+        let id = manager.create_group(SplitId(3), SplitId(4));
+        assert_ne!(id, 1);
+        assert!(manager.create_group_with_id(2, SplitId(5), SplitId(6)));
+        assert_ne!(manager.create_group(SplitId(7), SplitId(8)), id);
+    }
 }
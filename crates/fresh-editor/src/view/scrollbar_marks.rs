@@ -0,0 +1,204 @@
+//! Marks drawn along the editor scrollbar track
+//!
+//! The scrollbar doubles as an overview of the whole buffer: search matches,
+//! diagnostics, line indicators (git hunks and other plugin annotations) and
+//! cursors are drawn at their proportional position. Clicking a mark jumps to it.
+
+use crate::services::lsp::diagnostics::lsp_diagnostic_namespace;
+use crate::state::EditorState;
+use crate::view::overlay::OverlayNamespace;
+use crate::view::theme::Theme;
+use ratatui::style::Color;
+use std::collections::BTreeMap;
+
+/// Symbol drawn for a mark (over the track or thumb background)
+pub const MARK_SYMBOL: &str = "━";
+
+// Priorities decide which mark is shown when several fall on the same row
+const PRIORITY_ERROR: i32 = 100;
+const PRIORITY_WARNING: i32 = 80;
+const PRIORITY_CURSOR: i32 = 60;
+const PRIORITY_SEARCH: i32 = 50;
+const PRIORITY_INFO: i32 = 40;
+const PRIORITY_LINE_INDICATOR: i32 = 30;
+const PRIORITY_HINT: i32 = 20;
+
+/// A mark on one row of the scrollbar track
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollbarMark {
+    /// Row within the track (0-indexed)
+    pub row: usize,
+    /// Byte position a click on the mark jumps to
+    pub position: usize,
+    pub color: Color,
+    priority: i32,
+}
+
+/// Compute the marks for a buffer on a track `height` rows tall, at most one
+/// per row, ordered by row
+///
+/// Small files map positions by line so marks line up with the thumb; large
+/// files fall back to byte ratios since they have no line index.
+pub fn scrollbar_marks(
+    state: &EditorState,
+    theme: &Theme,
+    height: usize,
+    large_file_threshold_bytes: u64,
+) -> Vec<ScrollbarMark> {
+    if height == 0 {
+        return Vec::new();
+    }
+
+    let buffer_len = state.buffer.len();
+    let total_lines = if buffer_len as u64 > large_file_threshold_bytes {
+        None
+    } else {
+        state.buffer.line_count().filter(|&lines| lines > 0)
+    };
+    let row_of = |position: usize| {
+        let row = match total_lines {
+            Some(lines) => state.buffer.get_line_number(position) * height / lines,
+            None => position * height / buffer_len.max(1),
+        };
+        row.min(height - 1)
+    };
+
+    let mut by_row: BTreeMap<usize, ScrollbarMark> = BTreeMap::new();
+    let mut add = |position: usize, color: Color, priority: i32| {
+        let row = row_of(position);
+        let mark = ScrollbarMark {
+            row,
+            position,
+            color,
+            priority,
+        };
+        match by_row.get(&row) {
+            Some(existing)
+                if existing.priority > priority
+                    || (existing.priority == priority && existing.position <= position) => {}
+            _ => {
+                by_row.insert(row, mark);
+            }
+        }
+    };
+
+    let search_ns = OverlayNamespace::from_string("search".to_string());
+    let diagnostic_ns = lsp_diagnostic_namespace();
+    for overlay in state.overlays.all() {
+        let Some(namespace) = overlay.namespace.as_ref() else {
+            continue;
+        };
+        let position = overlay.range(&state.marker_list).start;
+        if *namespace == search_ns {
+            add(position, theme.search_match_bg, PRIORITY_SEARCH);
+        } else if *namespace == diagnostic_ns {
+            // Diagnostic overlays carry their severity as priority
+            let (color, priority) = match overlay.priority {
+                p if p >= 100 => (theme.diagnostic_error_fg, PRIORITY_ERROR),
+                p if p >= 50 => (theme.diagnostic_warning_fg, PRIORITY_WARNING),
+                p if p >= 30 => (theme.diagnostic_info_fg, PRIORITY_INFO),
+                _ => (theme.diagnostic_hint_fg, PRIORITY_HINT),
+            };
+            add(position, color, priority);
+        }
+    }
+
+    for (position, indicator) in state.margins.line_indicator_positions() {
+        add(position, indicator.color, PRIORITY_LINE_INDICATOR);
+    }
+
+    if state.cursors.count() > 1 {
+        for (_, cursor) in state.cursors.iter() {
+            add(cursor.position, theme.cursor, PRIORITY_CURSOR);
+        }
+    }
+
+    by_row.into_values().collect()
+}
+
+/// Find the mark on a track row, if any
+pub fn mark_at_row(marks: &[ScrollbarMark], row: usize) -> Option<&ScrollbarMark> {
+    marks.iter().find(|mark| mark.row == row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LARGE_FILE_THRESHOLD_BYTES;
+    use crate::model::event::{CursorId, Event};
+    use crate::model::filesystem::StdFileSystem;
+    use crate::view::margin::LineIndicator;
+    use crate::view::overlay::{Overlay, OverlayFace};
+    use crate::view::theme::THEME_DARK;
+    use std::sync::Arc;
+
+    fn state_with_lines(lines: usize) -> EditorState {
+        let mut state = EditorState::new(
+            80,
+            24,
+            LARGE_FILE_THRESHOLD_BYTES as usize,
+            Arc::new(StdFileSystem),
+        );
+        let text = (0..lines)
+            .map(|i| format!("line {i:03}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        state.apply(&Event::Insert {
+            position: 0,
+            text,
+            cursor_id: CursorId::UNDO_SENTINEL,
+        });
+        state
+    }
+
+    fn add_overlay(state: &mut EditorState, namespace: &str, position: usize, priority: i32) {
+        let overlay = Overlay::with_namespace(
+            &mut state.marker_list,
+            position..position + 4,
+            OverlayFace::Background { color: Color::Red },
+            OverlayNamespace::from_string(namespace.to_string()),
+        )
+        .with_priority_value(priority);
+        state.overlays.add(overlay);
+    }
+
+    #[test]
+    fn test_marks_map_lines_to_rows() {
+        let theme = Theme::load_builtin(THEME_DARK).unwrap();
+        let mut state = state_with_lines(100);
+        // "line NNN\n" is 9 bytes: line 50 lands halfway down a 10 row track
+        add_overlay(&mut state, "search", 50 * 9, 0);
+        add_overlay(&mut state, "lsp-diagnostic", 99 * 9, 100);
+        state.margins.set_line_indicator(
+            10 * 9,
+            "git-gutter".to_string(),
+            LineIndicator::new("│", Color::Green, 10),
+        );
+
+        let marks = scrollbar_marks(&state, &theme, 10, LARGE_FILE_THRESHOLD_BYTES);
+        let rows: Vec<usize> = marks.iter().map(|m| m.row).collect();
+        assert_eq!(rows, vec![1, 5, 9]);
+        assert_eq!(marks[0].color, Color::Green);
+        assert_eq!(marks[1].color, theme.search_match_bg);
+        assert_eq!(marks[1].position, 50 * 9);
+        assert_eq!(marks[2].color, theme.diagnostic_error_fg);
+        assert_eq!(mark_at_row(&marks, 5).map(|m| m.position), Some(450));
+        assert!(mark_at_row(&marks, 4).is_none());
+    }
+
+    #[test]
+    fn test_higher_priority_mark_wins_row() {
+        let theme = Theme::load_builtin(THEME_DARK).unwrap();
+        let mut state = state_with_lines(100);
+        add_overlay(&mut state, "search", 20 * 9, 0);
+        add_overlay(&mut state, "lsp-diagnostic", 21 * 9, 50);
+        // Overlays outside the known namespaces are not marked
+        add_overlay(&mut state, "plugin", 80 * 9, 0);
+
+        let marks = scrollbar_marks(&state, &theme, 10, LARGE_FILE_THRESHOLD_BYTES);
+        assert_eq!(marks.len(), 1);
+        assert_eq!(marks[0].row, 2);
+        assert_eq!(marks[0].color, theme.diagnostic_warning_fg);
+        assert_eq!(marks[0].position, 21 * 9);
+    }
+}
@@ -23,13 +23,16 @@
 /// └────────────────────┘      └──────────┴─────────┘
 ///  (horizontal split)          (mixed splits)
 /// ```
+use crate::input::position_history::PositionHistory;
 use crate::model::cursor::Cursors;
 use crate::model::event::{BufferId, SplitDirection, SplitId};
 use crate::view::ui::view_pipeline::Layout;
 use crate::view::viewport::Viewport;
 use crate::{services::plugins::api::ViewTransformPayload, state::ViewMode};
 use ratatui::layout::Rect;
+use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 /// A node in the split tree
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -57,6 +60,59 @@ pub enum SplitNode {
     },
 }
 
+/// Color label grouping related tabs in a split's tab bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabGroup {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl TabGroup {
+    /// All groups, in the order they are offered
+    pub const ALL: [Self; 6] = [
+        Self::Red,
+        Self::Orange,
+        Self::Yellow,
+        Self::Green,
+        Self::Blue,
+        Self::Purple,
+    ];
+
+    /// Stable name used in commands and persisted workspaces
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Red => "red",
+            Self::Orange => "orange",
+            Self::Yellow => "yellow",
+            Self::Green => "green",
+            Self::Blue => "blue",
+            Self::Purple => "purple",
+        }
+    }
+
+    /// Parse a group from its name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|group| group.name() == name)
+    }
+
+    /// Color of the group marker in the tab bar
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Red => Color::Red,
+            Self::Orange => Color::Rgb(230, 140, 40),
+            Self::Yellow => Color::Yellow,
+            Self::Green => Color::Green,
+            Self::Blue => Color::Blue,
+            Self::Purple => Color::Magenta,
+        }
+    }
+}
+
 /// Per-split view state (independent of buffer content)
 ///
 /// Following the Emacs model where each window (split) has its own:
@@ -81,6 +137,13 @@ pub struct SplitViewState {
     /// Horizontal scroll offset for the tabs in this split
     pub tab_scroll_offset: usize,
 
+    /// Tabs pinned to the start of the tab bar. Pinned tabs always come
+    /// first in `open_buffers` and are kept by "close others".
+    pub pinned_buffers: HashSet<BufferId>,
+
+    /// Group of each tab assigned to a tab group
+    pub tab_groups: HashMap<BufferId, TabGroup>,
+
     /// View mode (Source/Compose) per split
     pub view_mode: ViewMode,
 
@@ -93,6 +156,10 @@ pub struct SplitViewState {
     /// Previously configured line number visibility (restored when leaving Compose)
     pub compose_prev_line_numbers: Option<bool>,
 
+    /// Whether focus mode (compose layout with typewriter scrolling and
+    /// paragraph dimming) is on in this split
+    pub focus_mode: bool,
+
     /// Optional view transform payload for this split/viewport
     pub view_transform: Option<ViewTransformPayload>,
 
@@ -107,6 +174,9 @@ pub struct SplitViewState {
     /// Used for "Switch to Previous Tab" and for returning to previous buffer when closing
     pub focus_history: Vec<BufferId>,
 
+    /// Jump list for "Navigate Back/Forward" in this split
+    pub position_history: PositionHistory,
+
     /// Sync group ID for synchronized scrolling
     /// Splits with the same sync_group will scroll together
     pub sync_group: Option<u32>,
@@ -126,14 +196,18 @@ impl SplitViewState {
             viewport: Viewport::new(width, height),
             open_buffers: Vec::new(),
             tab_scroll_offset: 0,
+            pinned_buffers: HashSet::new(),
+            tab_groups: HashMap::new(),
             view_mode: ViewMode::Source,
             compose_width: None,
             compose_column_guides: None,
             compose_prev_line_numbers: None,
+            focus_mode: false,
             view_transform: None,
             layout: None,
             layout_dirty: true, // Start dirty so first operation builds layout
             focus_history: Vec::new(),
+            position_history: PositionHistory::new(),
             sync_group: None,
             composite_view: None,
         }
@@ -146,14 +220,18 @@ impl SplitViewState {
             viewport: Viewport::new(width, height),
             open_buffers: vec![buffer_id],
             tab_scroll_offset: 0,
+            pinned_buffers: HashSet::new(),
+            tab_groups: HashMap::new(),
             view_mode: ViewMode::Source,
             compose_width: None,
             compose_column_guides: None,
             compose_prev_line_numbers: None,
+            focus_mode: false,
             view_transform: None,
             layout: None,
             layout_dirty: true, // Start dirty so first operation builds layout
             focus_history: Vec::new(),
+            position_history: PositionHistory::new(),
             sync_group: None,
             composite_view: None,
         }
@@ -203,6 +281,77 @@ impl SplitViewState {
     /// Remove a buffer from this split's tabs
     pub fn remove_buffer(&mut self, buffer_id: BufferId) {
         self.open_buffers.retain(|&id| id != buffer_id);
+        self.pinned_buffers.remove(&buffer_id);
+        self.tab_groups.remove(&buffer_id);
+    }
+
+    /// Check if a tab is pinned
+    pub fn is_pinned(&self, buffer_id: BufferId) -> bool {
+        self.pinned_buffers.contains(&buffer_id)
+    }
+
+    /// Pin or unpin a tab. The tab moves to the boundary between pinned and
+    /// unpinned tabs: last of the pinned tabs, or first of the unpinned ones.
+    pub fn set_pinned(&mut self, buffer_id: BufferId, pinned: bool) {
+        if !self.has_buffer(buffer_id) {
+            return;
+        }
+        if pinned {
+            self.pinned_buffers.insert(buffer_id);
+        } else {
+            self.pinned_buffers.remove(&buffer_id);
+        }
+        self.open_buffers.retain(|&id| id != buffer_id);
+        let boundary = self
+            .open_buffers
+            .iter()
+            .take_while(|id| self.pinned_buffers.contains(id))
+            .count();
+        self.open_buffers.insert(boundary, buffer_id);
+        self.normalize_tab_order();
+    }
+
+    /// Assign a tab to a group, or remove it from its group with `None`.
+    /// The tab moves next to the other tabs of the group so groups stay
+    /// together (without crossing the pinned/unpinned boundary).
+    pub fn set_tab_group(&mut self, buffer_id: BufferId, group: Option<TabGroup>) {
+        if !self.has_buffer(buffer_id) {
+            return;
+        }
+        let Some(group) = group else {
+            self.tab_groups.remove(&buffer_id);
+            return;
+        };
+        self.tab_groups.insert(buffer_id, group);
+
+        let pinned = self.is_pinned(buffer_id);
+        let last_member = self.open_buffers.iter().rposition(|&id| {
+            id != buffer_id
+                && self.tab_groups.get(&id) == Some(&group)
+                && self.is_pinned(id) == pinned
+        });
+        if let Some(last_member) = last_member {
+            if let Some(current) = self.open_buffers.iter().position(|&id| id == buffer_id) {
+                self.open_buffers.remove(current);
+                let insert_at = if current < last_member {
+                    last_member
+                } else {
+                    last_member + 1
+                };
+                self.open_buffers.insert(insert_at, buffer_id);
+            }
+        }
+    }
+
+    /// Move pinned tabs to the front (keeping their relative order) and drop
+    /// pin/group state of tabs that are no longer open
+    pub fn normalize_tab_order(&mut self) {
+        self.pinned_buffers
+            .retain(|id| self.open_buffers.contains(id));
+        self.tab_groups
+            .retain(|id, _| self.open_buffers.contains(id));
+        let pinned = &self.pinned_buffers;
+        self.open_buffers.sort_by_key(|id| !pinned.contains(id));
     }
 
     /// Check if a buffer is open in this split
@@ -578,11 +727,24 @@ impl SplitManager {
         direction: SplitDirection,
         new_buffer_id: BufferId,
         ratio: f32,
+    ) -> Result<SplitId, String> {
+        self.split_active_placed(direction, new_buffer_id, ratio, false)
+    }
+
+    /// Split the currently active pane, placing the new pane before it
+    /// (left/top) when `new_first` is set
+    pub fn split_active_placed(
+        &mut self,
+        direction: SplitDirection,
+        new_buffer_id: BufferId,
+        ratio: f32,
+        new_first: bool,
     ) -> Result<SplitId, String> {
         let active_id = self.active_split;
 
         // Find the parent of the active split
-        let result = self.replace_split_with_split(active_id, direction, new_buffer_id, ratio);
+        let result =
+            self.replace_split_with_split(active_id, direction, new_buffer_id, ratio, new_first);
 
         if let Ok(new_split_id) = result {
             // Set the new split as active
@@ -600,6 +762,7 @@ impl SplitManager {
         direction: SplitDirection,
         new_buffer_id: BufferId,
         ratio: f32,
+        new_first: bool,
     ) -> Result<SplitId, String> {
         // Pre-allocate all IDs before any borrowing
         let temp_id = self.allocate_split_id();
@@ -610,14 +773,14 @@ impl SplitManager {
         if self.root.id() == target_id {
             let old_root =
                 std::mem::replace(&mut self.root, SplitNode::leaf(new_buffer_id, temp_id));
+            let new_leaf = SplitNode::leaf(new_buffer_id, new_leaf_id);
+            let (first, second) = if new_first {
+                (new_leaf, old_root)
+            } else {
+                (old_root, new_leaf)
+            };
 
-            self.root = SplitNode::split(
-                direction,
-                old_root,
-                SplitNode::leaf(new_buffer_id, new_leaf_id),
-                ratio,
-                new_split_id,
-            );
+            self.root = SplitNode::split(direction, first, second, ratio, new_split_id);
 
             return Ok(new_leaf_id);
         }
@@ -625,14 +788,14 @@ impl SplitManager {
         // Find and replace the target node
         if let Some(node) = self.root.find_mut(target_id) {
             let old_node = std::mem::replace(node, SplitNode::leaf(new_buffer_id, temp_id));
+            let new_leaf = SplitNode::leaf(new_buffer_id, new_leaf_id);
+            let (first, second) = if new_first {
+                (new_leaf, old_node)
+            } else {
+                (old_node, new_leaf)
+            };
 
-            *node = SplitNode::split(
-                direction,
-                old_node,
-                SplitNode::leaf(new_buffer_id, new_leaf_id),
-                ratio,
-                new_split_id,
-            );
+            *node = SplitNode::split(direction, first, second, ratio, new_split_id);
 
             Ok(new_leaf_id)
         } else {
@@ -937,6 +1100,61 @@ impl SplitManager {
 mod tests {
     use super::*;
 
+    fn view_state_with_tabs(count: usize) -> SplitViewState {
+        let mut view_state = SplitViewState::new(80, 24);
+        for id in 0..count {
+            view_state.add_buffer(BufferId(id));
+        }
+        view_state
+    }
+
+    #[test]
+    fn test_pinned_tabs_stay_first() {
+        let mut view_state = view_state_with_tabs(4);
+        view_state.set_pinned(BufferId(2), true);
+        view_state.set_pinned(BufferId(3), true);
+        assert_eq!(
+            view_state.open_buffers,
+            vec![BufferId(2), BufferId(3), BufferId(0), BufferId(1)]
+        );
+
+        // New tabs open after the pinned ones
+        view_state.add_buffer(BufferId(4));
+        assert_eq!(view_state.open_buffers.last(), Some(&BufferId(4)));
+
+        // Unpinning moves the tab to the start of the unpinned tabs
+        view_state.set_pinned(BufferId(2), false);
+        assert_eq!(
+            view_state.open_buffers,
+            vec![
+                BufferId(3),
+                BufferId(2),
+                BufferId(0),
+                BufferId(1),
+                BufferId(4)
+            ]
+        );
+
+        view_state.remove_buffer(BufferId(3));
+        assert!(view_state.pinned_buffers.is_empty());
+    }
+
+    #[test]
+    fn test_tab_group_keeps_members_together() {
+        let mut view_state = view_state_with_tabs(4);
+        view_state.set_tab_group(BufferId(0), Some(TabGroup::Green));
+        view_state.set_tab_group(BufferId(3), Some(TabGroup::Green));
+        assert_eq!(
+            view_state.open_buffers,
+            vec![BufferId(0), BufferId(3), BufferId(1), BufferId(2)]
+        );
+
+        view_state.set_tab_group(BufferId(3), None);
+        assert_eq!(view_state.tab_groups.get(&BufferId(3)), None);
+        assert_eq!(TabGroup::from_name("green"), Some(TabGroup::Green));
+        assert_eq!(TabGroup::from_name("none"), None);
+    }
+
     #[test]
     fn test_create_split_manager() {
         let buffer_id = BufferId(0);
@@ -970,6 +1188,24 @@ mod tests {
         assert_eq!(manager.root().count_leaves(), 2);
     }
 
+    #[test]
+    fn test_split_new_pane_first() {
+        let buffer_a = BufferId(0);
+        let buffer_b = BufferId(1);
+
+        let mut manager = SplitManager::new(buffer_a);
+        let new_id = manager
+            .split_active_placed(SplitDirection::Vertical, buffer_b, 0.5, true)
+            .unwrap();
+
+        let rect = Rect::new(0, 0, 80, 24);
+        let leaves = manager.get_visible_buffers(rect);
+        assert_eq!(leaves[0].0, new_id);
+        assert_eq!(leaves[0].1, buffer_b);
+        assert_eq!(leaves[1].1, buffer_a);
+        assert_eq!(manager.active_split(), new_id);
+    }
+
     #[test]
     fn test_nested_splits() {
         let buffer_a = BufferId(0);
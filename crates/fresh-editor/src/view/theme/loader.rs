@@ -6,6 +6,7 @@
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use super::types::{Theme, ThemeFile, ThemeInfo, BUILTIN_THEMES};
 
@@ -60,6 +61,25 @@ impl ThemeRegistry {
     }
 }
 
+/// Modification time and size of a theme file
+pub type ThemeFileStamp = (Option<SystemTime>, u64);
+
+fn collect_theme_file_stamps(dir: &Path, stamps: &mut HashMap<PathBuf, ThemeFileStamp>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_theme_file_stamps(&path, stamps);
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            if let Ok(metadata) = entry.metadata() {
+                stamps.insert(path, (metadata.modified().ok(), metadata.len()));
+            }
+        }
+    }
+}
+
 /// Loads themes and creates a ThemeRegistry.
 pub struct ThemeLoader {
     user_themes_dir: Option<PathBuf>,
@@ -85,6 +105,16 @@ impl ThemeLoader {
         self.user_themes_dir.as_deref()
     }
 
+    /// Modification time and size of every theme file under the user themes
+    /// directory (including packages), to notice when themes are edited.
+    pub fn user_theme_file_stamps(&self) -> HashMap<PathBuf, ThemeFileStamp> {
+        let mut stamps = HashMap::new();
+        if let Some(ref user_dir) = self.user_themes_dir {
+            collect_theme_file_stamps(user_dir, &mut stamps);
+        }
+        stamps
+    }
+
     /// Load all themes (embedded + user + packages) into a registry.
     pub fn load_all(&self) -> ThemeRegistry {
         let mut themes = HashMap::new();
@@ -288,6 +318,28 @@ impl Theme {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_user_theme_file_stamps_track_edits() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let nested = temp.path().join("packages").join("pack");
+        std::fs::create_dir_all(&nested).unwrap();
+        let theme_path = temp.path().join("mine.json");
+        std::fs::write(&theme_path, r#"{"name": "mine"}"#).unwrap();
+        std::fs::write(nested.join("other.json"), "{}").unwrap();
+        std::fs::write(temp.path().join("notes.txt"), "ignored").unwrap();
+
+        let loader = ThemeLoader::new(temp.path().to_path_buf());
+        let before = loader.user_theme_file_stamps();
+        assert_eq!(before.len(), 2);
+        assert!(before.contains_key(&theme_path));
+
+        std::fs::write(&theme_path, r#"{"name": "mine", "editor": {}}"#).unwrap();
+        assert_ne!(loader.user_theme_file_stamps(), before);
+        assert!(ThemeLoader::embedded_only()
+            .user_theme_file_stamps()
+            .is_empty());
+    }
+
     #[test]
     fn test_theme_registry_get() {
         let loader = ThemeLoader::embedded_only();
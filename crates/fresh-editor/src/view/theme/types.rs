@@ -6,6 +6,7 @@
 use ratatui::style::Color;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub const THEME_DARK: &str = "dark";
 pub const THEME_LIGHT: &str = "light";
@@ -55,8 +56,11 @@ impl ThemeInfo {
     }
 }
 
+/// RGB values of the six levels of each channel in the 256-color cube
+pub const COLOR_CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
 /// Convert a ratatui Color to RGB values.
-/// Returns None for Reset or Indexed colors.
+/// Returns None for Reset.
 pub fn color_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
     match color {
         Color::Rgb(r, g, b) => Some((r, g, b)),
@@ -76,7 +80,45 @@ pub fn color_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
         Color::LightYellow => Some((255, 255, 0)),
         Color::LightMagenta => Some((255, 0, 255)),
         Color::LightCyan => Some((0, 255, 255)),
-        Color::Reset | Color::Indexed(_) => None,
+        Color::Indexed(idx) => indexed_to_rgb(idx),
+        Color::Reset => None,
+    }
+}
+
+/// RGB values of a 256-color palette index (xterm defaults)
+fn indexed_to_rgb(idx: u8) -> Option<(u8, u8, u8)> {
+    const ANSI: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::Gray,
+        Color::DarkGray,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::White,
+    ];
+    match idx {
+        0..=15 => color_to_rgb(ANSI[idx as usize]),
+        16..=231 => {
+            let idx = (idx - 16) as usize;
+            Some((
+                COLOR_CUBE_LEVELS[idx / 36],
+                COLOR_CUBE_LEVELS[(idx % 36) / 6],
+                COLOR_CUBE_LEVELS[idx % 6],
+            ))
+        }
+        232..=255 => {
+            let gray = 8 + (idx - 232) * 10;
+            Some((gray, gray, gray))
+        }
     }
 }
 
@@ -102,6 +144,8 @@ pub enum ColorDef {
     Rgb(u8, u8, u8),
     /// Named color
     Named(String),
+    /// 256-color palette index
+    Indexed(u8),
 }
 
 impl From<ColorDef> for Color {
@@ -129,6 +173,7 @@ impl From<ColorDef> for Color {
                 "Default" | "Reset" => Color::Reset,
                 _ => Color::White, // Default fallback
             },
+            ColorDef::Indexed(idx) => Color::Indexed(idx),
         }
     }
 }
@@ -154,14 +199,7 @@ impl From<Color> for ColorDef {
             Color::LightMagenta => ColorDef::Named("LightMagenta".to_string()),
             Color::LightCyan => ColorDef::Named("LightCyan".to_string()),
             Color::Reset => ColorDef::Named("Default".to_string()),
-            Color::Indexed(_) => {
-                // Fallback for indexed colors
-                if let Some((r, g, b)) = color_to_rgb(color) {
-                    ColorDef::Rgb(r, g, b)
-                } else {
-                    ColorDef::Named("Default".to_string())
-                }
-            }
+            Color::Indexed(idx) => ColorDef::Indexed(idx),
         }
     }
 }
@@ -181,6 +219,36 @@ pub struct ThemeFile {
     pub diagnostic: DiagnosticColors,
     /// Syntax highlighting colors
     pub syntax: SyntaxColors,
+    /// Colors for LSP semantic tokens, layered over syntax highlighting
+    ///
+    /// Keys are token types (`"parameter"`, `"enumMember"`), optionally with a
+    /// modifier (`"variable.readonly"`, or `"*.deprecated"` for any type).
+    /// Tokens without a matching key use the syntax colors.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub semantic: BTreeMap<String, ColorDef>,
+    /// Explicit colors for terminals without true color support
+    #[serde(default, skip_serializing_if = "ThemeFallbacks::is_empty")]
+    pub fallbacks: ThemeFallbacks,
+}
+
+/// Colors used instead of the nearest palette match on terminals with limited
+/// colors
+///
+/// Keys are theme keys in "section.field" form, e.g. `"editor.selection_bg"`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ThemeFallbacks {
+    /// Colors for 256-color terminals (palette indices or named colors)
+    #[serde(rename = "256", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub color256: BTreeMap<String, ColorDef>,
+    /// Colors for 16-color terminals (named colors)
+    #[serde(rename = "16", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub color16: BTreeMap<String, ColorDef>,
+}
+
+impl ThemeFallbacks {
+    pub fn is_empty(&self) -> bool {
+        self.color256.is_empty() && self.color16.is_empty()
+    }
 }
 
 /// Editor area colors
@@ -219,6 +287,15 @@ pub struct EditorColors {
     /// Diff modified line background
     #[serde(default = "default_diff_modify_bg")]
     pub diff_modify_bg: ColorDef,
+    /// Whitespace and control character indicators (·, →, ␣, ^M)
+    #[serde(default = "default_whitespace_fg")]
+    pub whitespace_fg: ColorDef,
+    /// Vertical rulers at the configured columns
+    #[serde(default = "default_ruler_fg")]
+    pub ruler_fg: ColorDef,
+    /// Background past the last ruler, when wrap guide shading is on
+    #[serde(default = "default_wrap_guide_bg")]
+    pub wrap_guide_bg: ColorDef,
 }
 
 // Default editor colors (for minimal themes)
@@ -255,6 +332,15 @@ fn default_diff_remove_bg() -> ColorDef {
 fn default_diff_modify_bg() -> ColorDef {
     ColorDef::Rgb(40, 38, 30) // Very subtle yellow tint, close to dark bg
 }
+fn default_whitespace_fg() -> ColorDef {
+    ColorDef::Rgb(80, 80, 80)
+}
+fn default_ruler_fg() -> ColorDef {
+    ColorDef::Rgb(60, 60, 60)
+}
+fn default_wrap_guide_bg() -> ColorDef {
+    ColorDef::Rgb(36, 36, 36)
+}
 
 /// UI element colors (tabs, menus, status bar, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -800,6 +886,12 @@ pub struct Theme {
     pub current_line_bg: Color,
     pub line_number_fg: Color,
     pub line_number_bg: Color,
+    /// Whitespace and control character indicators
+    pub whitespace_fg: Color,
+    /// Vertical rulers
+    pub ruler_fg: Color,
+    /// Background past the last ruler
+    pub wrap_guide_bg: Color,
 
     // Diff highlighting colors
     pub diff_add_bg: Color,
@@ -922,6 +1014,12 @@ pub struct Theme {
     pub syntax_variable: Color,
     pub syntax_constant: Color,
     pub syntax_operator: Color,
+
+    /// Semantic token colors by token type or "type.modifier"
+    pub semantic: BTreeMap<String, Color>,
+
+    /// Explicit colors for terminals without true color support
+    pub fallbacks: ThemeFallbacks,
 }
 
 impl From<ThemeFile> for Theme {
@@ -936,6 +1034,9 @@ impl From<ThemeFile> for Theme {
             current_line_bg: file.editor.current_line_bg.into(),
             line_number_fg: file.editor.line_number_fg.into(),
             line_number_bg: file.editor.line_number_bg.into(),
+            whitespace_fg: file.editor.whitespace_fg.into(),
+            ruler_fg: file.editor.ruler_fg.into(),
+            wrap_guide_bg: file.editor.wrap_guide_bg.into(),
             diff_add_bg: file.editor.diff_add_bg.clone().into(),
             diff_remove_bg: file.editor.diff_remove_bg.clone().into(),
             diff_modify_bg: file.editor.diff_modify_bg.into(),
@@ -1023,6 +1124,12 @@ impl From<ThemeFile> for Theme {
             syntax_variable: file.syntax.variable.into(),
             syntax_constant: file.syntax.constant.into(),
             syntax_operator: file.syntax.operator.into(),
+            semantic: file
+                .semantic
+                .into_iter()
+                .map(|(key, color)| (key, color.into()))
+                .collect(),
+            fallbacks: file.fallbacks,
         }
     }
 }
@@ -1043,6 +1150,9 @@ impl From<Theme> for ThemeFile {
                 diff_add_bg: theme.diff_add_bg.into(),
                 diff_remove_bg: theme.diff_remove_bg.into(),
                 diff_modify_bg: theme.diff_modify_bg.into(),
+                whitespace_fg: theme.whitespace_fg.into(),
+                ruler_fg: theme.ruler_fg.into(),
+                wrap_guide_bg: theme.wrap_guide_bg.into(),
             },
             ui: UiColors {
                 tab_active_fg: theme.tab_active_fg.into(),
@@ -1133,6 +1243,12 @@ impl From<Theme> for ThemeFile {
                 constant: theme.syntax_constant.into(),
                 operator: theme.syntax_operator.into(),
             },
+            semantic: theme
+                .semantic
+                .into_iter()
+                .map(|(key, color)| (key, color.into()))
+                .collect(),
+            fallbacks: theme.fallbacks,
         }
     }
 }
@@ -1154,6 +1270,169 @@ impl Theme {
         Ok(theme_file.into())
     }
 
+    /// All colors with their theme keys ("section.field"), for rewriting
+    /// colors in bulk
+    pub fn colors_mut(&mut self) -> Vec<(&'static str, &mut Color)> {
+        vec![
+            ("editor.bg", &mut self.editor_bg),
+            ("editor.fg", &mut self.editor_fg),
+            ("editor.cursor", &mut self.cursor),
+            ("editor.inactive_cursor", &mut self.inactive_cursor),
+            ("editor.selection_bg", &mut self.selection_bg),
+            ("editor.current_line_bg", &mut self.current_line_bg),
+            ("editor.line_number_fg", &mut self.line_number_fg),
+            ("editor.line_number_bg", &mut self.line_number_bg),
+            ("editor.whitespace_fg", &mut self.whitespace_fg),
+            ("editor.ruler_fg", &mut self.ruler_fg),
+            ("editor.wrap_guide_bg", &mut self.wrap_guide_bg),
+            ("editor.diff_add_bg", &mut self.diff_add_bg),
+            ("editor.diff_remove_bg", &mut self.diff_remove_bg),
+            ("editor.diff_modify_bg", &mut self.diff_modify_bg),
+            (
+                "editor.diff_add_highlight_bg",
+                &mut self.diff_add_highlight_bg,
+            ),
+            (
+                "editor.diff_remove_highlight_bg",
+                &mut self.diff_remove_highlight_bg,
+            ),
+            ("ui.tab_active_fg", &mut self.tab_active_fg),
+            ("ui.tab_active_bg", &mut self.tab_active_bg),
+            ("ui.tab_inactive_fg", &mut self.tab_inactive_fg),
+            ("ui.tab_inactive_bg", &mut self.tab_inactive_bg),
+            ("ui.tab_separator_bg", &mut self.tab_separator_bg),
+            ("ui.tab_close_hover_fg", &mut self.tab_close_hover_fg),
+            ("ui.tab_hover_bg", &mut self.tab_hover_bg),
+            ("ui.menu_bg", &mut self.menu_bg),
+            ("ui.menu_fg", &mut self.menu_fg),
+            ("ui.menu_active_bg", &mut self.menu_active_bg),
+            ("ui.menu_active_fg", &mut self.menu_active_fg),
+            ("ui.menu_dropdown_bg", &mut self.menu_dropdown_bg),
+            ("ui.menu_dropdown_fg", &mut self.menu_dropdown_fg),
+            ("ui.menu_highlight_bg", &mut self.menu_highlight_bg),
+            ("ui.menu_highlight_fg", &mut self.menu_highlight_fg),
+            ("ui.menu_border_fg", &mut self.menu_border_fg),
+            ("ui.menu_separator_fg", &mut self.menu_separator_fg),
+            ("ui.menu_hover_bg", &mut self.menu_hover_bg),
+            ("ui.menu_hover_fg", &mut self.menu_hover_fg),
+            ("ui.menu_disabled_fg", &mut self.menu_disabled_fg),
+            ("ui.menu_disabled_bg", &mut self.menu_disabled_bg),
+            ("ui.status_bar_fg", &mut self.status_bar_fg),
+            ("ui.status_bar_bg", &mut self.status_bar_bg),
+            ("ui.prompt_fg", &mut self.prompt_fg),
+            ("ui.prompt_bg", &mut self.prompt_bg),
+            ("ui.prompt_selection_fg", &mut self.prompt_selection_fg),
+            ("ui.prompt_selection_bg", &mut self.prompt_selection_bg),
+            ("ui.popup_border_fg", &mut self.popup_border_fg),
+            ("ui.popup_bg", &mut self.popup_bg),
+            ("ui.popup_selection_bg", &mut self.popup_selection_bg),
+            ("ui.popup_selection_fg", &mut self.popup_selection_fg),
+            ("ui.popup_text_fg", &mut self.popup_text_fg),
+            ("ui.suggestion_bg", &mut self.suggestion_bg),
+            (
+                "ui.suggestion_selected_bg",
+                &mut self.suggestion_selected_bg,
+            ),
+            ("ui.help_bg", &mut self.help_bg),
+            ("ui.help_fg", &mut self.help_fg),
+            ("ui.help_key_fg", &mut self.help_key_fg),
+            ("ui.help_separator_fg", &mut self.help_separator_fg),
+            ("ui.help_indicator_fg", &mut self.help_indicator_fg),
+            ("ui.help_indicator_bg", &mut self.help_indicator_bg),
+            ("ui.inline_code_bg", &mut self.inline_code_bg),
+            ("ui.split_separator_fg", &mut self.split_separator_fg),
+            (
+                "ui.split_separator_hover_fg",
+                &mut self.split_separator_hover_fg,
+            ),
+            ("ui.scrollbar_track_fg", &mut self.scrollbar_track_fg),
+            ("ui.scrollbar_thumb_fg", &mut self.scrollbar_thumb_fg),
+            (
+                "ui.scrollbar_track_hover_fg",
+                &mut self.scrollbar_track_hover_fg,
+            ),
+            (
+                "ui.scrollbar_thumb_hover_fg",
+                &mut self.scrollbar_thumb_hover_fg,
+            ),
+            ("ui.compose_margin_bg", &mut self.compose_margin_bg),
+            ("ui.semantic_highlight_bg", &mut self.semantic_highlight_bg),
+            ("ui.terminal_bg", &mut self.terminal_bg),
+            ("ui.terminal_fg", &mut self.terminal_fg),
+            (
+                "ui.status_warning_indicator_bg",
+                &mut self.status_warning_indicator_bg,
+            ),
+            (
+                "ui.status_warning_indicator_fg",
+                &mut self.status_warning_indicator_fg,
+            ),
+            (
+                "ui.status_error_indicator_bg",
+                &mut self.status_error_indicator_bg,
+            ),
+            (
+                "ui.status_error_indicator_fg",
+                &mut self.status_error_indicator_fg,
+            ),
+            (
+                "ui.status_warning_indicator_hover_bg",
+                &mut self.status_warning_indicator_hover_bg,
+            ),
+            (
+                "ui.status_warning_indicator_hover_fg",
+                &mut self.status_warning_indicator_hover_fg,
+            ),
+            (
+                "ui.status_error_indicator_hover_bg",
+                &mut self.status_error_indicator_hover_bg,
+            ),
+            (
+                "ui.status_error_indicator_hover_fg",
+                &mut self.status_error_indicator_hover_fg,
+            ),
+            ("ui.tab_drop_zone_bg", &mut self.tab_drop_zone_bg),
+            ("ui.tab_drop_zone_border", &mut self.tab_drop_zone_border),
+            ("ui.settings_selected_bg", &mut self.settings_selected_bg),
+            ("ui.settings_selected_fg", &mut self.settings_selected_fg),
+            ("search.match_bg", &mut self.search_match_bg),
+            ("search.match_fg", &mut self.search_match_fg),
+            ("diagnostic.error_fg", &mut self.diagnostic_error_fg),
+            ("diagnostic.error_bg", &mut self.diagnostic_error_bg),
+            ("diagnostic.warning_fg", &mut self.diagnostic_warning_fg),
+            ("diagnostic.warning_bg", &mut self.diagnostic_warning_bg),
+            ("diagnostic.info_fg", &mut self.diagnostic_info_fg),
+            ("diagnostic.info_bg", &mut self.diagnostic_info_bg),
+            ("diagnostic.hint_fg", &mut self.diagnostic_hint_fg),
+            ("diagnostic.hint_bg", &mut self.diagnostic_hint_bg),
+            ("syntax.keyword", &mut self.syntax_keyword),
+            ("syntax.string", &mut self.syntax_string),
+            ("syntax.comment", &mut self.syntax_comment),
+            ("syntax.function", &mut self.syntax_function),
+            ("syntax.type", &mut self.syntax_type),
+            ("syntax.variable", &mut self.syntax_variable),
+            ("syntax.constant", &mut self.syntax_constant),
+            ("syntax.operator", &mut self.syntax_operator),
+        ]
+    }
+
+    /// Set one color by its theme key ("section.field")
+    ///
+    /// Returns false for unknown keys. Any "semantic.*" key is accepted.
+    pub fn set_color(&mut self, key: &str, color: Color) -> bool {
+        if let Some(scope) = key.strip_prefix("semantic.") {
+            self.semantic.insert(scope.to_string(), color);
+            return true;
+        }
+        match self.colors_mut().into_iter().find(|(k, _)| *k == key) {
+            Some((_, slot)) => {
+                *slot = color;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Resolve a theme key to a Color.
     ///
     /// Theme keys use dot notation: "section.field"
@@ -1162,9 +1441,15 @@ impl Theme {
     /// - "editor.selection_bg" -> selection_bg
     /// - "syntax.keyword" -> syntax_keyword
     /// - "diagnostic.error_fg" -> diagnostic_error_fg
+    /// - "semantic.variable.readonly" -> semantic["variable.readonly"]
     ///
     /// Returns None if the key is not recognized.
     pub fn resolve_theme_key(&self, key: &str) -> Option<Color> {
+        // Semantic scopes may contain a dot themselves ("variable.readonly")
+        if let Some(scope) = key.strip_prefix("semantic.") {
+            return self.semantic.get(scope).copied();
+        }
+
         // Parse "section.field" format
         let parts: Vec<&str> = key.split('.').collect();
         if parts.len() != 2 {
@@ -1186,6 +1471,9 @@ impl Theme {
                 "diff_add_bg" => Some(self.diff_add_bg),
                 "diff_remove_bg" => Some(self.diff_remove_bg),
                 "diff_modify_bg" => Some(self.diff_modify_bg),
+                "whitespace_fg" => Some(self.whitespace_fg),
+                "ruler_fg" => Some(self.ruler_fg),
+                "wrap_guide_bg" => Some(self.wrap_guide_bg),
                 _ => None,
             },
             "ui" => match field {
@@ -1277,6 +1565,47 @@ pub fn get_builtin_themes() -> serde_json::Value {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_set_color_by_key() {
+        let mut theme = Theme::load_builtin(THEME_DARK).unwrap();
+        assert!(theme.set_color("editor.bg", Color::Rgb(1, 2, 3)));
+        assert_eq!(theme.editor_bg, Color::Rgb(1, 2, 3));
+        assert_eq!(
+            theme.resolve_theme_key("editor.bg"),
+            Some(Color::Rgb(1, 2, 3))
+        );
+
+        assert!(theme.set_color("syntax.keyword", Color::Red));
+        assert_eq!(theme.syntax_keyword, Color::Red);
+
+        assert!(!theme.set_color("editor.nonexistent", Color::Red));
+        assert!(!theme.set_color("bg", Color::Red));
+
+        assert!(theme.set_color("semantic.variable.readonly", Color::Cyan));
+        assert_eq!(
+            theme.resolve_theme_key("semantic.variable.readonly"),
+            Some(Color::Cyan)
+        );
+        assert_eq!(theme.resolve_theme_key("semantic.unknownType"), None);
+    }
+
+    #[test]
+    fn test_semantic_scopes_round_trip() {
+        let theme = Theme::load_builtin(THEME_DARK).unwrap();
+        assert!(theme.semantic.contains_key("parameter"));
+
+        let value = serde_json::to_value(ThemeFile::from(theme.clone())).unwrap();
+        assert_eq!(
+            value["semantic"]["enumMember"],
+            serde_json::to_value(ColorDef::from(theme.semantic["enumMember"])).unwrap()
+        );
+
+        let mut plain = theme;
+        plain.semantic.clear();
+        let value = serde_json::to_value(ThemeFile::from(plain)).unwrap();
+        assert!(value.get("semantic").is_none());
+    }
+
     #[test]
     fn test_load_builtin_theme() {
         let dark = Theme::load_builtin(THEME_DARK).expect("Dark theme must exist");
@@ -1305,6 +1634,32 @@ mod tests {
         assert_eq!(theme.name, "test");
     }
 
+    #[test]
+    fn test_indexed_color_def() {
+        let def: ColorDef = serde_json::from_str("236").unwrap();
+        assert_eq!(Color::from(def), Color::Indexed(236));
+        assert!(matches!(
+            ColorDef::from(Color::Indexed(236)),
+            ColorDef::Indexed(236)
+        ));
+        assert_eq!(color_to_rgb(Color::Indexed(196)), Some((255, 0, 0)));
+        assert_eq!(color_to_rgb(Color::Indexed(232)), Some((8, 8, 8)));
+    }
+
+    #[test]
+    fn test_theme_fallbacks_round_trip() {
+        let json = r#"{"name":"test","editor":{},"ui":{},"search":{},"diagnostic":{},"syntax":{},
+            "fallbacks":{"16":{"editor.bg":"Black"}}}"#;
+        let theme = Theme::from_json(json).unwrap();
+        assert!(theme.fallbacks.color256.is_empty());
+        assert!(theme.fallbacks.color16.contains_key("editor.bg"));
+
+        let file = ThemeFile::from(theme);
+        let value = serde_json::to_value(&file).unwrap();
+        assert_eq!(value["fallbacks"]["16"]["editor.bg"], "Black");
+        assert!(value["fallbacks"].get("256").is_none());
+    }
+
     #[test]
     fn test_default_reset_color() {
         // Test that "Default" maps to Color::Reset
@@ -1,5 +1,5 @@
 use crate::input::fuzzy::FuzzyMatch;
-use crate::primitives::display_width::str_width;
+use crate::primitives::display_width::{byte_offset_at_visual_column, str_width};
 use crate::view::file_tree::{FileExplorerDecorationCache, FileTreeView, NodeId};
 use crate::view::theme::Theme;
 use ratatui::{
@@ -225,7 +225,7 @@ impl FileExplorerRenderer {
         let indent_width = indent * 2;
         let indicator_width = if node.is_dir() { 2 } else { 2 }; // "▼ " or "  "
         let name_width = str_width(&node.entry.name);
-        let left_side_width = indent_width + indicator_width + name_width;
+        let mut left_side_width = indent_width + indicator_width + name_width;
 
         // Indentation
         if indent > 0 {
@@ -327,8 +327,22 @@ impl FileExplorerRenderer {
 
         let total_right_width = right_indicator_width + error_width;
 
-        // Calculate padding for right-alignment
+        // Where a symlink points, as much of it as fits before the status
         let min_gap = 1;
+        if let Some(target) = &node.entry.symlink_target {
+            let room = content_width.saturating_sub(left_side_width + min_gap + total_right_width);
+            let mut text = format!(" → {}", target.display());
+            if str_width(&text) > room {
+                text.truncate(byte_offset_at_visual_column(&text, room.saturating_sub(1)));
+                text.push('…');
+            }
+            if room > 3 {
+                left_side_width += str_width(&text);
+                spans.push(Span::styled(text, Style::default().fg(theme.line_number_fg)));
+            }
+        }
+
+        // Calculate padding for right-alignment
         let padding = if left_side_width + min_gap + total_right_width < content_width {
             content_width - left_side_width - total_right_width
         } else {
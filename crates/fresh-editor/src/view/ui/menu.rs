@@ -142,7 +142,7 @@ impl MenuContext {
     }
 }
 
-fn is_menu_item_enabled(item: &MenuItem, context: &MenuContext) -> bool {
+pub(crate) fn is_menu_item_enabled(item: &MenuItem, context: &MenuContext) -> bool {
     match item {
         MenuItem::Action { when, .. } => {
             match when.as_deref() {
@@ -154,7 +154,7 @@ fn is_menu_item_enabled(item: &MenuItem, context: &MenuContext) -> bool {
     }
 }
 
-fn is_checkbox_checked(checkbox: &Option<String>, context: &MenuContext) -> bool {
+pub(crate) fn is_checkbox_checked(checkbox: &Option<String>, context: &MenuContext) -> bool {
     match checkbox.as_deref() {
         Some(name) => context.get(name),
         None => false,
@@ -5,6 +5,7 @@
 //! - `menu` - Menu bar rendering
 //! - `tabs` - Tab bar rendering for multiple buffers
 //! - `status_bar` - Status bar and prompt/minibuffer display
+//! - `status_line` - Status bar built from configured segments
 //! - `suggestions` - Autocomplete and command palette UI
 //! - `split_rendering` - Split pane layout and rendering
 //! - `file_explorer` - File tree explorer rendering
@@ -34,6 +35,8 @@ pub mod split_rendering;
 #[cfg(feature = "runtime")]
 pub mod status_bar;
 #[cfg(feature = "runtime")]
+pub mod status_line;
+#[cfg(feature = "runtime")]
 pub mod suggestions;
 #[cfg(feature = "runtime")]
 pub mod tabs;
@@ -4,17 +4,19 @@ use std::collections::BTreeMap;
 
 use crate::app::types::ViewLineMapping;
 use crate::app::BufferMetadata;
+use crate::config::RenderWhitespace;
 use crate::model::buffer::Buffer;
 use crate::model::cursor::SelectionMode;
 use crate::model::event::{BufferId, EventLog, SplitDirection};
 use crate::primitives::ansi::AnsiParser;
 use crate::primitives::ansi_background::AnsiBackground;
-use crate::primitives::display_width::char_width;
+use crate::primitives::display_width::{char_width, ClusterWidth};
+use crate::services::render_profile::{self, Phase};
 use crate::state::{EditorState, ViewMode};
 use crate::view::split::SplitManager;
 use crate::view::ui::tabs::TabsRenderer;
 use crate::view::ui::view_pipeline::{
-    should_show_line_number, LineStart, ViewLine, ViewLineIterator,
+    format_binary_byte, should_show_line_number, LineStart, ViewLine, ViewLineIterator,
 };
 use crate::view::virtual_text::VirtualTextPosition;
 use fresh_core::api::ViewTransformPayload;
@@ -90,8 +92,10 @@ fn push_span_with_map(
     // Push one map entry per visual column (not per character)
     // Double-width characters (CJK, emoji) need 2 entries
     // Zero-width characters (like \u{200b}) get 0 entries - they don't occupy screen space
+    // and neither do the characters joined to a grapheme cluster after its first
+    let mut widths = ClusterWidth::new();
     for ch in text.chars() {
-        let width = char_width(ch);
+        let width = widths.push(ch);
         for _ in 0..width {
             map.push(source);
         }
@@ -146,6 +150,52 @@ fn dim_color_for_tilde(color: Color) -> Color {
     }
 }
 
+/// Fade a text color halfway toward the editor background, for the
+/// paragraphs outside the cursor's paragraph in focus mode.
+fn focus_dim_color(fg: Color, bg: Color) -> Color {
+    match (fg, bg) {
+        (Color::Rgb(fr, fg_, fb), Color::Rgb(br, bg_, bb)) => Color::Rgb(
+            ((fr as u16 + br as u16) / 2) as u8,
+            ((fg_ as u16 + bg_ as u16) / 2) as u8,
+            ((fb as u16 + bb as u16) / 2) as u8,
+        ),
+        _ => dim_color_for_tilde(fg),
+    }
+}
+
+/// Byte range of the paragraph (run of non-blank lines) containing `position`.
+/// Empty when `position` is on a blank line, so every paragraph is dimmed.
+fn focus_paragraph_range(buffer: &mut Buffer, position: usize) -> Range<usize> {
+    /// Bound on lines scanned in each direction, to keep huge paragraphs cheap
+    const MAX_SCAN_LINES: usize = 1000;
+    let is_blank = |line: &str| line.trim().is_empty();
+
+    let mut iter = buffer.line_iterator(position, 80);
+    let line_start = iter.current_position();
+    match iter.next_line() {
+        Some((_, line)) if !is_blank(&line) => {}
+        _ => return line_start..line_start,
+    }
+    let mut end = iter.current_position();
+    for _ in 0..MAX_SCAN_LINES {
+        match iter.next_line() {
+            Some((_, line)) if !is_blank(&line) => end = iter.current_position(),
+            _ => break,
+        }
+    }
+
+    let mut iter = buffer.line_iterator(line_start, 80);
+    let mut start = line_start;
+    for _ in 0..MAX_SCAN_LINES {
+        match iter.prev() {
+            Some((prev_start, line)) if !is_blank(&line) => start = prev_start,
+            _ => break,
+        }
+    }
+
+    start..end
+}
+
 /// Accumulator for building spans - collects characters with the same style
 /// into a single span, flushing when style changes. This is important for
 /// proper rendering of combining characters (like Thai diacritics) which
@@ -154,6 +204,9 @@ struct SpanAccumulator {
     text: String,
     style: Style,
     first_source: Option<usize>,
+    /// Widths by grapheme cluster, kept across flushes so a cluster split
+    /// by a style change still gets the columns of one cluster
+    widths: ClusterWidth,
 }
 
 impl SpanAccumulator {
@@ -162,6 +215,7 @@ impl SpanAccumulator {
             text: String::new(),
             style: Style::default(),
             first_source: None,
+            widths: ClusterWidth::new(),
         }
     }
 
@@ -189,7 +243,7 @@ impl SpanAccumulator {
         self.text.push(ch);
 
         // Update map for this character's visual width
-        let width = char_width(ch);
+        let width = self.widths.push(ch);
         for _ in 0..width {
             map.push(source);
         }
@@ -219,6 +273,49 @@ fn push_debug_tag(spans: &mut Vec<Span<'static>>, map: &mut Vec<Option<usize>>,
     spans.push(Span::styled(text, debug_tag_style()));
 }
 
+/// Which characters of a display line get a whitespace indicator under
+/// `mode`: `·` for a space and `␣` for a non-breaking space (tabs are
+/// already expanded to spaces). The selection mode is decided per character
+/// while drawing, so it marks none here.
+///
+/// `continues_line` is set for a wrapped continuation, which has no
+/// indentation, and `ends_line` when the line's text ends here.
+fn visible_whitespace(
+    text: &str,
+    mode: RenderWhitespace,
+    continues_line: bool,
+    ends_line: bool,
+) -> Vec<bool> {
+    let chars: Vec<char> = text.chars().collect();
+    let is_space = |c: char| c == ' ' || c == '\u{A0}';
+    let content = |c: &char| !is_space(*c) && *c != '\n';
+    let first_content = chars.iter().position(content).unwrap_or(chars.len());
+    let after_content = chars.iter().rposition(content).map_or(0, |i| i + 1);
+    let trailing = |i: usize| ends_line && i >= after_content;
+    let in_run = |i: usize| {
+        (i > 0 && is_space(chars[i - 1])) || chars.get(i + 1).is_some_and(|&c| is_space(c))
+    };
+
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            is_space(c)
+                && match mode {
+                    RenderWhitespace::None | RenderWhitespace::Selection => false,
+                    RenderWhitespace::Trailing => trailing(i),
+                    RenderWhitespace::Boundary => {
+                        c != ' '
+                            || (!continues_line && i < first_content)
+                            || trailing(i)
+                            || in_run(i)
+                    }
+                    RenderWhitespace::All => true,
+                }
+        })
+        .collect()
+}
+
 /// Context for tracking active spans in debug mode
 #[derive(Default)]
 struct DebugSpanTracker {
@@ -354,6 +451,7 @@ struct ViewPreferences {
     compose_width: Option<u16>,
     compose_column_guides: Option<Vec<u16>>,
     view_transform: Option<ViewTransformPayload>,
+    focus_mode: bool,
 }
 
 struct LineRenderInput<'a> {
@@ -716,6 +814,7 @@ impl SplitRenderer {
     /// * `line_wrap` - Whether line wrapping is enabled
     /// * `estimated_line_length` - Estimated average line length for large file line estimation
     /// * `hide_cursor` - Whether to hide the hardware cursor (e.g., when menu is open)
+    /// * `max_content_width` - Text width past which file buffers are centered (0 = off)
     ///
     /// # Returns
     /// * Vec of (split_id, buffer_id, content_rect, scrollbar_rect, thumb_start, thumb_end) for mouse handling
@@ -753,6 +852,9 @@ impl SplitRenderer {
         tab_bar_visible: bool,
         use_terminal_bg: bool,
         session_mode: bool,
+        focus_dim_paragraphs: bool,
+        max_content_width: u16,
+        image_previews: &HashMap<BufferId, crate::view::image_preview::ImagePreview>,
     ) -> (
         Vec<(
             crate::model::event::SplitId,
@@ -792,6 +894,11 @@ impl SplitRenderer {
             let layout = Self::split_layout(split_area, tab_bar_visible);
             let (split_buffers, tab_scroll_offset) =
                 Self::split_buffers_for_tabs(split_view_states.as_deref(), split_id, buffer_id);
+            let (pinned_tabs, tab_groups) = split_view_states
+                .as_deref()
+                .and_then(|view_states| view_states.get(&split_id))
+                .map(|vs| (vs.pinned_buffers.clone(), vs.tab_groups.clone()))
+                .unwrap_or_default();
 
             // Determine hover state for this split's tabs
             let tab_hover_for_split = hovered_tab.and_then(|(hover_buf, hover_split, is_close)| {
@@ -816,6 +923,8 @@ impl SplitRenderer {
                     theme,
                     is_active,
                     tab_scroll_offset,
+                    &pinned_tabs,
+                    &tab_groups,
                     tab_hover_for_split,
                 );
 
@@ -939,6 +1048,59 @@ impl SplitRenderer {
                     continue;
                 }
 
+                // Image previews replace the text with the image and its info
+                if let Some(preview) = image_previews.get(&buffer_id) {
+                    preview.render(frame, layout.content_rect, theme);
+                    split_areas.push((
+                        split_id,
+                        buffer_id,
+                        layout.content_rect,
+                        layout.scrollbar_rect,
+                        0,
+                        0,
+                    ));
+                    view_line_mappings.insert(split_id, Vec::new());
+                    continue;
+                }
+
+                let view_prefs =
+                    Self::resolve_view_preferences(state, split_view_states.as_deref(), split_id);
+
+                // Centered layout: the text and its viewport get a column of
+                // at most `max_content_width`, so mouse handling and popups
+                // line up with it too
+                let centered = max_content_width > 0
+                    && view_prefs.view_mode == ViewMode::Source
+                    && view_prefs.compose_width.is_none()
+                    && buffer_metadata
+                        .get(&buffer_id)
+                        .is_some_and(|metadata| !metadata.is_virtual());
+                let content_rect = if centered {
+                    let column_width =
+                        max_content_width.saturating_add(state.margins.left_total_width() as u16);
+                    let column = Self::calculate_compose_layout(
+                        layout.content_rect,
+                        &view_prefs.view_mode,
+                        Some(column_width),
+                    );
+                    let editor_bg = if use_terminal_bg {
+                        ratatui::style::Color::Reset
+                    } else {
+                        theme.editor_bg
+                    };
+                    Self::render_compose_margins(
+                        frame,
+                        layout.content_rect,
+                        &column,
+                        &view_prefs.view_mode,
+                        theme,
+                        editor_bg,
+                    );
+                    column.render_area
+                } else {
+                    layout.content_rect
+                };
+
                 // Get viewport from SplitViewState (authoritative source)
                 // We need to get it mutably for sync operations
                 // Use as_deref() to get Option<&HashMap> for read-only operations
@@ -950,8 +1112,8 @@ impl SplitRenderer {
                         .map(|vs| vs.viewport.clone())
                         .unwrap_or_else(|| {
                             crate::view::viewport::Viewport::new(
-                                layout.content_rect.width,
-                                layout.content_rect.height,
+                                content_rect.width,
+                                content_rect.height,
                             )
                         });
                 let mut viewport = viewport_clone;
@@ -966,17 +1128,15 @@ impl SplitRenderer {
                     &mut viewport,
                     &mut state.buffer,
                     &state.cursors,
-                    layout.content_rect,
+                    content_rect,
                 );
-                let view_prefs =
-                    Self::resolve_view_preferences(state, split_view_states.as_deref(), split_id);
 
                 let split_view_mappings = Self::render_buffer_in_split(
                     frame,
                     state,
                     &mut viewport,
                     event_log_opt,
-                    layout.content_rect,
+                    content_rect,
                     is_active,
                     theme,
                     ansi_background,
@@ -993,6 +1153,7 @@ impl SplitRenderer {
                     relative_line_numbers,
                     use_terminal_bg,
                     session_mode,
+                    view_prefs.focus_mode && focus_dim_paragraphs,
                 );
 
                 // Store view line mappings for mouse click handling
@@ -1043,7 +1204,7 @@ impl SplitRenderer {
                 split_areas.push((
                     split_id,
                     buffer_id,
-                    layout.content_rect,
+                    content_rect,
                     layout.scrollbar_rect,
                     thumb_start,
                     thumb_end,
@@ -1230,6 +1391,7 @@ impl SplitRenderer {
                         .unwrap_or(source_state.buffer.len());
 
                     // Get syntax highlighting spans from the highlighter
+                    let highlight_profile = render_profile::scope(Phase::Highlight);
                     let highlight_spans = source_state.highlighter.highlight_viewport(
                         &source_state.buffer,
                         top_byte,
@@ -1237,6 +1399,7 @@ impl SplitRenderer {
                         theme,
                         1024, // highlight_context_bytes
                     );
+                    drop(highlight_profile);
 
                     // Create a temporary viewport for building view data
                     let pane_width = pane_widths.get(pane_idx).copied().unwrap_or(80);
@@ -1550,9 +1713,10 @@ impl SplitRenderer {
         let mut rendered = 0usize;
         let mut current_span_text = String::new();
         let mut current_style: Option<Style> = None;
+        let mut widths = ClusterWidth::new();
 
         for (char_idx, ch) in chars.iter().enumerate() {
-            let char_width = char_width(*ch);
+            let char_width = widths.push(*ch);
 
             // Skip characters before left_column
             if col < left_column {
@@ -1848,6 +2012,7 @@ impl SplitRenderer {
                     compose_width: view_state.compose_width,
                     compose_column_guides: view_state.compose_column_guides.clone(),
                     view_transform: view_state.view_transform.clone(),
+                    focus_mode: view_state.focus_mode,
                 };
             }
         }
@@ -1857,6 +2022,7 @@ impl SplitRenderer {
             compose_width: state.compose_width,
             compose_column_guides: state.compose_column_guides.clone(),
             view_transform: state.view_transform.clone(),
+            focus_mode: false,
         }
     }
 
@@ -1966,7 +2132,7 @@ impl SplitRenderer {
         viewport: &crate::view::viewport::Viewport,
         scrollbar_rect: Rect,
         is_active: bool,
-        _theme: &crate::view::theme::Theme,
+        theme: &crate::view::theme::Theme,
         large_file_threshold_bytes: u64,
         total_lines: usize,
         top_line: usize,
@@ -2067,6 +2233,25 @@ impl SplitRenderer {
             frame.render_widget(paragraph, cell_area);
         }
 
+        // Overlay search, diagnostic, line indicator and cursor marks
+        let marks = crate::view::scrollbar_marks::scrollbar_marks(
+            state,
+            theme,
+            height,
+            large_file_threshold_bytes,
+        );
+        for mark in marks {
+            let bg = if mark.row >= thumb_start && mark.row < thumb_end {
+                thumb_color
+            } else {
+                track_color
+            };
+            let cell_area = Rect::new(scrollbar_rect.x, scrollbar_rect.y + mark.row as u16, 1, 1);
+            let paragraph = Paragraph::new(crate::view::scrollbar_marks::MARK_SYMBOL)
+                .style(Style::default().fg(mark.color).bg(bg));
+            frame.render_widget(paragraph, cell_area);
+        }
+
         // Return thumb position for mouse hit testing
         (thumb_start, thumb_end)
     }
@@ -2082,6 +2267,7 @@ impl SplitRenderer {
         content_width: usize,
         gutter_width: usize,
     ) -> ViewData {
+        let _profile = render_profile::scope(Phase::Layout);
         // Check if buffer is binary before building tokens
         let is_binary = state.buffer.is_binary();
         let line_ending = state.buffer.line_ending();
@@ -2108,13 +2294,12 @@ impl SplitRenderer {
         } else {
             MAX_SAFE_LINE_WIDTH
         };
-        tokens = Self::apply_wrapping_transform(tokens, effective_width, gutter_width);
+        tokens = Self::apply_wrapping_transform(tokens, effective_width, gutter_width, is_binary);
 
         // Convert tokens to display lines using the view pipeline
         // Each ViewLine preserves LineStart info for correct line number rendering
         // Use binary mode if the buffer contains binary content
         // Enable ANSI awareness for non-binary content to handle escape sequences correctly
-        let is_binary = state.buffer.is_binary();
         let ansi_aware = !is_binary; // ANSI parsing for normal text files
         let source_lines: Vec<ViewLine> =
             ViewLineIterator::new(&tokens, is_binary, ansi_aware, state.tab_size).collect();
@@ -2579,6 +2764,7 @@ impl SplitRenderer {
         tokens: Vec<fresh_core::api::ViewTokenWire>,
         content_width: usize,
         gutter_width: usize,
+        is_binary: bool,
     ) -> Vec<fresh_core::api::ViewTokenWire> {
         use crate::primitives::visual_layout::visual_width;
         use fresh_core::api::{ViewTokenWire, ViewTokenWireKind};
@@ -2732,9 +2918,9 @@ impl SplitRenderer {
                     wrapped.push(token);
                     current_line_width = 0;
                 }
-                ViewTokenWireKind::BinaryByte(_) => {
-                    // Binary bytes render as <XX> which is 4 characters
-                    let byte_display_width = 4;
+                ViewTokenWireKind::BinaryByte(b) => {
+                    // Binary bytes render as <XX>, control characters in text as ^X
+                    let byte_display_width = format_binary_byte(*b, is_binary).len();
                     if current_line_width + byte_display_width > available_width {
                         wrapped.push(ViewTokenWire {
                             source_offset: None,
@@ -2827,6 +3013,97 @@ impl SplitRenderer {
         }
     }
 
+    /// Dim the text of every rendered row whose source lies outside the
+    /// focused paragraph (focus mode)
+    fn dim_rows_outside_paragraph(
+        frame: &mut Frame,
+        render_area: Rect,
+        gutter_width: usize,
+        view_line_mappings: &[ViewLineMapping],
+        paragraph: &Range<usize>,
+        editor_bg: Color,
+    ) {
+        let content_x = render_area.x.saturating_add(gutter_width as u16);
+        let right = render_area.x + render_area.width;
+        let buf = frame.buffer_mut();
+        for (row, mapping) in view_line_mappings
+            .iter()
+            .enumerate()
+            .take(render_area.height as usize)
+        {
+            let Some(first_byte) = mapping.char_source_bytes.iter().find_map(|b| *b) else {
+                continue;
+            };
+            if paragraph.contains(&first_byte) {
+                continue;
+            }
+            let y = render_area.y + row as u16;
+            for x in content_x..right {
+                let cell = &mut buf[(x, y)];
+                let dimmed = focus_dim_color(cell.fg, editor_bg);
+                cell.set_fg(dimmed);
+            }
+        }
+    }
+
+    /// Draw vertical rulers at the given text columns
+    ///
+    /// Rulers only fill empty cells, so they never hide text.
+    fn render_rulers(
+        frame: &mut Frame,
+        render_area: Rect,
+        gutter_width: usize,
+        left_column: usize,
+        rulers: &[usize],
+        style: Style,
+    ) {
+        let content_x = render_area.x as usize + gutter_width;
+        let right = (render_area.x + render_area.width) as usize;
+        let buf = frame.buffer_mut();
+        for &column in rulers {
+            let Some(x) = column
+                .checked_sub(left_column)
+                .map(|offset| content_x + offset)
+                .filter(|&x| x < right)
+            else {
+                continue;
+            };
+            for y in render_area.y..render_area.y + render_area.height {
+                let cell = &mut buf[(x as u16, y)];
+                if cell.symbol() == " " {
+                    cell.set_symbol("│").set_style(style);
+                }
+            }
+        }
+    }
+
+    /// Shade the text area from `column` to the right edge
+    ///
+    /// Only cells on the plain editor background change, so selections and
+    /// other highlights keep their colors.
+    fn shade_past_column(
+        frame: &mut Frame,
+        render_area: Rect,
+        gutter_width: usize,
+        left_column: usize,
+        column: usize,
+        editor_bg: Color,
+        shade: Color,
+    ) {
+        let content_x = render_area.x as usize + gutter_width;
+        let right = (render_area.x + render_area.width) as usize;
+        let start = content_x + column.saturating_sub(left_column);
+        let buf = frame.buffer_mut();
+        for y in render_area.y..render_area.y + render_area.height {
+            for x in start..right {
+                let cell = &mut buf[(x as u16, y)];
+                if cell.bg == editor_bg {
+                    cell.set_bg(shade);
+                }
+            }
+        }
+    }
+
     fn render_compose_margins(
         frame: &mut Frame,
         area: Rect,
@@ -2960,6 +3237,7 @@ impl SplitRenderer {
             .saturating_add(viewport_size)
             .min(state.buffer.len());
 
+        let highlight_profile = render_profile::scope(Phase::Highlight);
         let highlight_spans = state.highlighter.highlight_viewport(
             &state.buffer,
             highlight_start,
@@ -2967,6 +3245,7 @@ impl SplitRenderer {
             theme,
             highlight_context_bytes,
         );
+        drop(highlight_profile);
 
         // Update reference highlight overlays (debounced, creates overlays that auto-adjust)
         state.reference_highlight_overlay.update(
@@ -3116,6 +3395,7 @@ impl SplitRenderer {
         let mut last_line_end: Option<LastLineEnd> = None;
 
         let is_empty_buffer = state.buffer.is_empty();
+        let is_binary = state.buffer.is_binary();
 
         // Track cursor position during rendering (eliminates duplicate line iteration)
         let mut last_visible_x: u16 = 0;
@@ -3225,6 +3505,24 @@ impl SplitRenderer {
             let mut byte_index = 0; // Byte offset in line_content string
             let mut display_char_idx = 0usize; // Character index in text (for char_source_bytes)
             let mut col_offset = 0usize; // Visual column position
+            let mut col_widths = ClusterWidth::new(); // Widths by grapheme cluster, as in the view line
+
+            // Spaces to draw as indicators (the selection mode is checked per character)
+            let ends_source_line = line_has_newline
+                || line_char_source_bytes
+                    .iter()
+                    .rev()
+                    .flatten()
+                    .next()
+                    .is_some_and(|&bp| bp + 1 >= state.buffer.len());
+            let whitespace_shown = visible_whitespace(
+                &line_content,
+                state.render_whitespace,
+                current_view_line.line_start.is_continuation(),
+                ends_source_line,
+            );
+            // Source byte of the control character whose ^X is being drawn
+            let mut control_char_byte: Option<usize> = None;
 
             // Performance optimization: For very long lines, only process visible characters
             // Calculate the maximum characters we might need to render based on screen width
@@ -3310,6 +3608,20 @@ impl SplitRenderer {
                     // No ANSI in this line - use default style (fast path)
                     Style::default()
                 };
+                let ch_width = col_widths.push(ch);
+
+                // Control characters in text files are drawn as ^X, both
+                // characters mapping to the control character's byte
+                if ch == '^' && !is_binary {
+                    control_char_byte = byte_pos.filter(|&bp| {
+                        state
+                            .buffer
+                            .slice_bytes(bp..bp + 1)
+                            .first()
+                            .is_some_and(|b| b.is_ascii_control())
+                    });
+                }
+                let is_control_char = byte_pos.is_some() && byte_pos == control_char_byte;
 
                 // Performance: skip expensive style calculations for characters beyond visible range
                 // Use visible_char_count (not byte_index) since ANSI codes don't take up visible space
@@ -3389,6 +3701,24 @@ impl SplitRenderer {
                         session_mode,
                     });
 
+                    let is_space = ch == ' ' || ch == '\u{A0}';
+                    let show_whitespace = is_space
+                        && (whitespace_shown
+                            .get(display_char_idx)
+                            .copied()
+                            .unwrap_or(false)
+                            || (state.render_whitespace == RenderWhitespace::Selection
+                                && is_selected));
+                    // The spaces of a tab expansion after the first all map to the tab's byte
+                    let in_tab = display_char_idx > 0
+                        && byte_pos.is_some()
+                        && line_char_source_bytes
+                            .get(display_char_idx - 1)
+                            .copied()
+                            .flatten()
+                            == byte_pos;
+                    let mut is_indicator = is_control_char;
+
                     // Determine display character (tabs already expanded in ViewLineIterator)
                     // Show tab indicator (→) at the start of tab expansions (if enabled for this language)
                     let tab_indicator: String;
@@ -3402,10 +3732,17 @@ impl SplitRenderer {
                         "\\n"
                     } else if ch == '\n' {
                         ""
-                    } else if is_tab_start && state.show_whitespace_tabs {
+                    } else if is_tab_start && (state.show_whitespace_tabs || show_whitespace) {
                         // Visual indicator for tab: show → at the first position
-                        tab_indicator = "→".to_string();
-                        &tab_indicator
+                        is_indicator = true;
+                        "→"
+                    } else if show_whitespace && !in_tab {
+                        is_indicator = true;
+                        if ch == ' ' {
+                            "·"
+                        } else {
+                            "␣"
+                        }
                     } else {
                         tab_indicator = ch.to_string();
                         &tab_indicator
@@ -3459,6 +3796,13 @@ impl SplitRenderer {
                             }
                         }
 
+                        // Whitespace and control character indicators are dimmed
+                        let style = if is_indicator && !is_cursor {
+                            style.fg(theme.whitespace_fg)
+                        } else {
+                            style
+                        };
+
                         // Use accumulator to merge consecutive chars with same style
                         // This is critical for combining characters (Thai diacritics, etc.)
                         for c in display_char.chars() {
@@ -3483,7 +3827,7 @@ impl SplitRenderer {
                     // Zero-width chars don't get map entries, so we need to explicitly record cursor pos
                     if !have_cursor {
                         if let Some(bp) = byte_pos {
-                            if bp == primary_cursor_position && char_width(ch) == 0 {
+                            if bp == primary_cursor_position && ch_width == 0 {
                                 // Account for horizontal scrolling by subtracting left_col
                                 cursor_screen_x = gutter_width as u16
                                     + col_offset.saturating_sub(left_col) as u16;
@@ -3543,7 +3887,6 @@ impl SplitRenderer {
                 display_char_idx += 1; // Increment character index for next lookup
                                        // col_offset tracks visual column position (for indexing into visual_to_char)
                                        // visual_to_char has one entry per visual column, not per character
-                let ch_width = char_width(ch);
                 col_offset += ch_width;
                 visible_char_count += ch_width;
             }
@@ -3956,6 +4299,7 @@ impl SplitRenderer {
         relative_line_numbers: bool,
         use_terminal_bg: bool,
         session_mode: bool,
+        dim_paragraphs: bool,
     ) -> Vec<ViewLineMapping> {
         let _span = tracing::trace_span!("render_buffer_in_split").entered();
 
@@ -4152,6 +4496,19 @@ impl SplitRenderer {
             .style(Style::default().bg(effective_editor_bg));
         frame.render_widget(Paragraph::new(lines).block(editor_block), render_area);
 
+        if dim_paragraphs {
+            let paragraph =
+                focus_paragraph_range(&mut state.buffer, selection.primary_cursor_position);
+            Self::dim_rows_outside_paragraph(
+                frame,
+                render_area,
+                gutter_width,
+                &render_output.view_line_mappings,
+                &paragraph,
+                effective_editor_bg,
+            );
+        }
+
         // Render column guides if present (for tables, etc.)
         if let Some(guides) = compose_column_guides {
             let guide_style = Style::default()
@@ -4176,6 +4533,31 @@ impl SplitRenderer {
             }
         }
 
+        if state.wrap_guide_shading {
+            if let Some(&last_ruler) = state.rulers.iter().max() {
+                Self::shade_past_column(
+                    frame,
+                    render_area,
+                    gutter_width,
+                    viewport.left_column,
+                    last_ruler,
+                    effective_editor_bg,
+                    theme.wrap_guide_bg,
+                );
+            }
+        }
+
+        if !state.rulers.is_empty() {
+            Self::render_rulers(
+                frame,
+                render_area,
+                gutter_width,
+                viewport.left_column,
+                &state.rulers,
+                Style::default().fg(theme.ruler_fg),
+            );
+        }
+
         let buffer_ends_with_newline = if !state.buffer.is_empty() {
             let last_char = state.get_text_range(state.buffer.len() - 1, state.buffer.len());
             last_char == "\n"
@@ -4324,6 +4706,82 @@ mod tests {
     use crate::view::theme::Theme;
     use crate::view::viewport::Viewport;
 
+    #[test]
+    fn test_focus_paragraph_range() {
+        let content = "one\ntwo\n\nthree\n  \nfour";
+        let mut buffer = Buffer::from_str(content, 1024, test_fs());
+
+        // Cursor in the first paragraph covers "one\ntwo\n"
+        assert_eq!(focus_paragraph_range(&mut buffer, 5), 0..8);
+        // Whitespace-only lines separate paragraphs too
+        assert_eq!(focus_paragraph_range(&mut buffer, 10), 9..15);
+        assert_eq!(focus_paragraph_range(&mut buffer, 20), 18..22);
+        // A blank line has no paragraph
+        assert_eq!(focus_paragraph_range(&mut buffer, 8), 8..8);
+    }
+
+    #[test]
+    fn test_focus_dim_color_blends_toward_background() {
+        assert_eq!(
+            focus_dim_color(Color::Rgb(200, 100, 0), Color::Rgb(0, 0, 0)),
+            Color::Rgb(100, 50, 0)
+        );
+    }
+
+    /// The indicators of `visible_whitespace` as a string: `·` where a space
+    /// is drawn, `_` where it isn't
+    fn shown(text: &str, mode: RenderWhitespace, continues: bool, ends: bool) -> String {
+        text.chars()
+            .zip(visible_whitespace(text, mode, continues, ends))
+            .map(|(c, shown)| match (c, shown) {
+                (_, true) => '·',
+                (' ', false) => '_',
+                (c, false) => c,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_visible_whitespace_modes() {
+        let line = "  let a  = b; \n";
+        assert_eq!(
+            shown(line, RenderWhitespace::None, false, true),
+            "__let_a__=_b;_\n"
+        );
+        assert_eq!(
+            shown(line, RenderWhitespace::All, false, true),
+            "··let·a··=·b;·\n"
+        );
+        assert_eq!(
+            shown(line, RenderWhitespace::Trailing, false, true),
+            "__let_a__=_b;·\n"
+        );
+        assert_eq!(
+            shown(line, RenderWhitespace::Boundary, false, true),
+            "··let_a··=_b;·\n"
+        );
+        // Selection is decided while drawing
+        assert_eq!(
+            shown(line, RenderWhitespace::Selection, false, true),
+            "__let_a__=_b;_\n"
+        );
+    }
+
+    #[test]
+    fn test_visible_whitespace_wrapped_segments() {
+        // A continuation has no indentation, and a segment followed by
+        // another has no trailing spaces
+        assert_eq!(shown(" x ", RenderWhitespace::Boundary, true, false), "_x_");
+        assert_eq!(shown("   ", RenderWhitespace::Trailing, false, true), "···");
+    }
+
+    #[test]
+    fn test_visible_whitespace_non_breaking_space() {
+        let line = "a\u{A0}b c";
+        let marks = visible_whitespace(line, RenderWhitespace::Boundary, false, true);
+        assert_eq!(marks, vec![false, true, false, false, false]);
+    }
+
     fn render_output_for(
         content: &str,
         cursor_pos: usize,
@@ -5420,7 +5878,8 @@ mod tests {
         ];
 
         // Apply wrapping with MAX_SAFE_LINE_WIDTH (simulating line_wrap disabled)
-        let wrapped = SplitRenderer::apply_wrapping_transform(tokens, MAX_SAFE_LINE_WIDTH, 0);
+        let wrapped =
+            SplitRenderer::apply_wrapping_transform(tokens, MAX_SAFE_LINE_WIDTH, 0, false);
 
         // Count Break tokens - should have at least 2 breaks for 25K chars at 10K width
         let break_count = wrapped
@@ -5470,7 +5929,8 @@ mod tests {
         ];
 
         // Apply wrapping with MAX_SAFE_LINE_WIDTH (simulating line_wrap disabled)
-        let wrapped = SplitRenderer::apply_wrapping_transform(tokens, MAX_SAFE_LINE_WIDTH, 0);
+        let wrapped =
+            SplitRenderer::apply_wrapping_transform(tokens, MAX_SAFE_LINE_WIDTH, 0, false);
 
         // Should have no Break tokens for short lines
         let break_count = wrapped
@@ -5527,7 +5987,8 @@ mod tests {
         ];
 
         // Apply safety wrapping (simulating line_wrap=false with MAX_SAFE_LINE_WIDTH)
-        let wrapped = SplitRenderer::apply_wrapping_transform(tokens, MAX_SAFE_LINE_WIDTH, 0);
+        let wrapped =
+            SplitRenderer::apply_wrapping_transform(tokens, MAX_SAFE_LINE_WIDTH, 0, false);
 
         // Convert to ViewLines
         let view_lines: Vec<_> = ViewLineIterator::new(&wrapped, false, false, 4).collect();
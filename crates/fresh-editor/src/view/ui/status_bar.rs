@@ -30,6 +30,66 @@ pub struct StatusBarLayout {
     pub message_area: Option<(u16, u16, u16)>,
 }
 
+/// Line and column (0-indexed) of the primary cursor.
+///
+/// Uses the cached line number from the state and only scans back to the
+/// start of the current line for the column.
+pub(super) fn cursor_line_col(state: &mut EditorState) -> (usize, usize) {
+    let cursor = *state.primary_cursor();
+    // Find the start of the line containing the cursor
+    let cursor_iter = state.buffer.line_iterator(cursor.position, 80);
+    let line_start = cursor_iter.current_position();
+    let col = cursor.position.saturating_sub(line_start);
+    (state.primary_cursor_line_number.value(), col)
+}
+
+/// Diagnostic counts by severity, formatted like `E:2 W:1`.
+///
+/// Returns None when the buffer has no diagnostics.
+pub(super) fn diagnostics_summary(state: &EditorState) -> Option<String> {
+    let mut error_count = 0;
+    let mut warning_count = 0;
+    let mut info_count = 0;
+
+    // Use the lsp-diagnostic namespace to identify diagnostic overlays
+    let diagnostic_ns = crate::services::lsp::diagnostics::lsp_diagnostic_namespace();
+    for overlay in state.overlays.all() {
+        if overlay.namespace.as_ref() == Some(&diagnostic_ns) {
+            // Check priority to determine severity
+            // Based on lsp_diagnostics.rs: Error=100, Warning=50, Info=30, Hint=10
+            match overlay.priority {
+                100 => error_count += 1,
+                50 => warning_count += 1,
+                _ => info_count += 1,
+            }
+        }
+    }
+
+    if error_count + warning_count + info_count == 0 {
+        return None;
+    }
+    let mut parts = Vec::new();
+    if error_count > 0 {
+        parts.push(format!("E:{}", error_count));
+    }
+    if warning_count > 0 {
+        parts.push(format!("W:{}", warning_count));
+    }
+    if info_count > 0 {
+        parts.push(format!("I:{}", info_count));
+    }
+    Some(parts.join(" "))
+}
+
+/// Tabs or spaces and the indent width, like `Spaces: 4`
+pub(super) fn indentation_summary(state: &EditorState) -> String {
+    if state.use_tabs {
+        t!("status.indent_tabs", width = state.tab_size).to_string()
+    } else {
+        t!("status.indent_spaces", width = state.indent_width()).to_string()
+    }
+}
+
 /// Status bar hover state for styling clickable indicators
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum StatusBarHover {
@@ -261,6 +321,8 @@ impl StatusBarRenderer {
     /// * `warning_level` - LSP warning level (for coloring LSP indicator)
     /// * `general_warning_count` - Number of general warnings (for badge display)
     /// * `remote_connection` - Optional remote connection info (e.g., "user@host")
+    /// * `pending_auto_saves` - Number of buffers waiting for a delayed auto-save
+    /// * `task_status` - Spinner and progress of running plugin background tasks, and the watch task's result
     /// * `session_name` - Optional session name (for session persistence mode)
     ///
     /// # Returns
@@ -282,6 +344,8 @@ impl StatusBarRenderer {
         general_warning_count: usize,
         hover: StatusBarHover,
         remote_connection: Option<&str>,
+        pending_auto_saves: usize,
+        task_status: Option<&str>,
         session_name: Option<&str>,
     ) -> StatusBarLayout {
         Self::render_status(
@@ -300,6 +364,8 @@ impl StatusBarRenderer {
             general_warning_count,
             hover,
             remote_connection,
+            pending_auto_saves,
+            task_status,
             session_name,
         )
     }
@@ -484,6 +550,8 @@ impl StatusBarRenderer {
         general_warning_count: usize,
         hover: StatusBarHover,
         remote_connection: Option<&str>,
+        pending_auto_saves: usize,
+        task_status: Option<&str>,
         session_name: Option<&str>,
     ) -> StatusBarLayout {
         // Initialize layout tracking
@@ -517,56 +585,12 @@ impl StatusBarRenderer {
             _ => "",
         };
 
-        let cursor = *state.primary_cursor();
-
-        // Get line number and column efficiently using cached values
-        let (line, col) = {
-            // Find the start of the line containing the cursor
-            let cursor_iter = state.buffer.line_iterator(cursor.position, 80);
-            let line_start = cursor_iter.current_position();
-            let col = cursor.position.saturating_sub(line_start);
-
-            // Use cached line number from state
-            let line_num = state.primary_cursor_line_number.value();
-            (line_num, col)
-        };
-
-        // Count diagnostics by severity
-        let diagnostics = state.overlays.all();
-        let mut error_count = 0;
-        let mut warning_count = 0;
-        let mut info_count = 0;
-
-        // Use the lsp-diagnostic namespace to identify diagnostic overlays
-        let diagnostic_ns = crate::services::lsp::diagnostics::lsp_diagnostic_namespace();
-        for overlay in diagnostics {
-            if overlay.namespace.as_ref() == Some(&diagnostic_ns) {
-                // Check priority to determine severity
-                // Based on lsp_diagnostics.rs: Error=100, Warning=50, Info=30, Hint=10
-                match overlay.priority {
-                    100 => error_count += 1,
-                    50 => warning_count += 1,
-                    _ => info_count += 1,
-                }
-            }
-        }
+        let (line, col) = cursor_line_col(state);
 
         // Build diagnostics summary if there are any
-        let diagnostics_summary = if error_count + warning_count + info_count > 0 {
-            let mut parts = Vec::new();
-            if error_count > 0 {
-                parts.push(format!("E:{}", error_count));
-            }
-            if warning_count > 0 {
-                parts.push(format!("W:{}", warning_count));
-            }
-            if info_count > 0 {
-                parts.push(format!("I:{}", info_count));
-            }
-            format!(" | {}", parts.join(" "))
-        } else {
-            String::new()
-        };
+        let diagnostics_summary = diagnostics_summary(state)
+            .map(|summary| format!(" | {}", summary))
+            .unwrap_or_default();
 
         // Build cursor count indicator (only show if multiple cursors)
         let cursor_count_indicator = if state.cursors.count() > 1 {
@@ -575,6 +599,21 @@ impl StatusBarRenderer {
             String::new()
         };
 
+        // Delayed auto-saves still waiting for the user to stop typing
+        let auto_save_indicator = if pending_auto_saves > 0 {
+            format!(
+                " | {}",
+                t!("status.auto_save_pending", count = pending_auto_saves)
+            )
+        } else {
+            String::new()
+        };
+
+        // Plugin background tasks still running
+        let task_indicator = task_status
+            .map(|status| format!(" | {}", status))
+            .unwrap_or_default();
+
         // Build status message parts
         let mut message_parts: Vec<&str> = Vec::new();
         if let Some(msg) = status_message {
@@ -606,7 +645,7 @@ impl StatusBarRenderer {
             .unwrap_or_default();
         let base_status = if state.show_cursors {
             format!(
-                "{session_prefix}{remote_prefix}{filename}{modified} | Ln {}, Col {}{diagnostics_summary}{cursor_count_indicator}",
+                "{session_prefix}{remote_prefix}{filename}{modified} | Ln {}, Col {}{diagnostics_summary}{cursor_count_indicator}{auto_save_indicator}{task_indicator}",
                 line + 1,
                 col + 1
             )
@@ -622,9 +661,13 @@ impl StatusBarRenderer {
         let left_status = format!("{base_status}{chord_display}{message_suffix}");
 
         // Build right-side indicators (these stay fixed on the right)
-        // Order: [Line ending] [Language] [LSP indicator] [warning badge] [update] [Palette]
+        // Order: [Indentation] [Line ending] [Language] [LSP indicator] [warning badge] [update] [Palette]
         // Note: Remote indicator is now on the left side, before the filename
 
+        // Indentation indicator (tabs or spaces and the indent width)
+        let indentation_text = format!(" {} ", indentation_summary(state));
+        let indentation_width = str_width(&indentation_text);
+
         // Line ending indicator (clickable to change format)
         let line_ending_text = format!(" {} ", state.buffer.line_ending().display_name());
         let line_ending_width = str_width(&line_ending_text);
@@ -671,7 +714,7 @@ impl StatusBarRenderer {
         let padded_cmd_palette = format!(" {} ", cmd_palette_indicator);
 
         // Calculate available width and right side width
-        // Right side: [Line ending] [Encoding] [Language] [LSP indicator] [warning badge] [update] [Palette]
+        // Right side: [Indentation] [Line ending] [Encoding] [Language] [LSP indicator] [warning badge] [update] [Palette]
         let available_width = area.width as usize;
         let cmd_palette_width = str_width(&padded_cmd_palette);
         let right_side_width = line_ending_width
@@ -682,6 +725,15 @@ impl StatusBarRenderer {
             + update_width
             + cmd_palette_width;
 
+        // The indentation indicator gives way to the left status
+        let show_indentation =
+            str_width(&left_status) + right_side_width + indentation_width < available_width;
+        let right_side_width = if show_indentation {
+            right_side_width + indentation_width
+        } else {
+            right_side_width
+        };
+
         // Only show command palette indicator if there's enough space (at least 15 chars for minimal display)
         let spans = if available_width >= 15 {
             // Reserve space for right side indicators
@@ -765,6 +817,17 @@ impl StatusBarRenderer {
                 current_col = area.x + (available_width - right_side_width) as u16;
             }
 
+            // Add indentation indicator
+            if show_indentation {
+                spans.push(Span::styled(
+                    indentation_text.clone(),
+                    Style::default()
+                        .fg(theme.status_bar_fg)
+                        .bg(theme.status_bar_bg),
+                ));
+                current_col += indentation_width as u16;
+            }
+
             // Add line ending indicator (clickable to change format)
             {
                 let is_hovering = hover == StatusBarHover::LineEndingIndicator;
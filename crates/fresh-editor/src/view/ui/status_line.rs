@@ -0,0 +1,551 @@
+//! Configurable status line rendering
+//!
+//! Renders the status bar from the segment specs in
+//! [`StatusLineConfig`](crate::config::StatusLineConfig) instead of the
+//! built-in layout. Segments are grouped left, center and right; each one can
+//! override its colors with theme keys.
+
+use std::collections::HashMap;
+
+use super::status_bar::{
+    cursor_line_col, diagnostics_summary, indentation_summary, StatusBarHover, StatusBarLayout,
+};
+use crate::app::WarningLevel;
+use crate::config::StatusLineConfig;
+use crate::primitives::display_width::{char_width, str_width};
+use crate::state::EditorState;
+use crate::view::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use rust_i18n::t;
+
+/// A status line segment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SegmentKind {
+    /// Active editor mode set by plugins (e.g. vi mode)
+    Mode,
+    /// Session name in session persistence mode
+    Session,
+    /// Remote connection (e.g. `[SSH:user@host]`)
+    Remote,
+    /// Buffer display name with modified marker
+    Filename,
+    /// Cursor line and column
+    Position,
+    /// Diagnostic counts for the buffer
+    Diagnostics,
+    /// Cursor count when there are multiple cursors
+    Cursors,
+    /// Number of pending delayed auto-saves
+    AutoSave,
+    /// Spinner and progress of running plugin background tasks
+    Tasks,
+    /// Pending key chord
+    Chord,
+    /// Core and plugin status messages
+    Messages,
+    /// Branch of the git repository containing the working directory
+    GitBranch,
+    /// LSP status indicator
+    Lsp,
+    /// General warning badge
+    Warnings,
+    /// Line ending of the buffer
+    LineEnding,
+    /// Encoding of the buffer
+    Encoding,
+    /// Tabs or spaces and the indent width of the buffer
+    Indentation,
+    /// Language of the buffer
+    Language,
+    /// Available update notice
+    Update,
+    /// Command palette hint
+    Palette,
+    /// Text published by a plugin with `setStatusSegment(id, text)`
+    Plugin(String),
+}
+
+impl SegmentKind {
+    fn from_name(name: &str) -> Option<Self> {
+        if let Some(id) = name.strip_prefix("plugin:") {
+            let id = id.trim();
+            return (!id.is_empty()).then(|| Self::Plugin(id.to_string()));
+        }
+        Some(match name {
+            "mode" => Self::Mode,
+            "session" => Self::Session,
+            "remote" => Self::Remote,
+            "filename" => Self::Filename,
+            "position" => Self::Position,
+            "diagnostics" => Self::Diagnostics,
+            "cursors" => Self::Cursors,
+            "auto_save" => Self::AutoSave,
+            "tasks" => Self::Tasks,
+            "chord" => Self::Chord,
+            "messages" => Self::Messages,
+            "git_branch" => Self::GitBranch,
+            "lsp" => Self::Lsp,
+            "warnings" => Self::Warnings,
+            "line_ending" => Self::LineEnding,
+            "encoding" => Self::Encoding,
+            "indentation" => Self::Indentation,
+            "language" => Self::Language,
+            "update" => Self::Update,
+            "palette" => Self::Palette,
+            _ => return None,
+        })
+    }
+
+    /// Hover state that applies to this segment, for clickable segments
+    fn hover(&self) -> Option<StatusBarHover> {
+        match self {
+            Self::Lsp => Some(StatusBarHover::LspIndicator),
+            Self::Warnings => Some(StatusBarHover::WarningBadge),
+            Self::LineEnding => Some(StatusBarHover::LineEndingIndicator),
+            Self::Encoding => Some(StatusBarHover::EncodingIndicator),
+            Self::Language => Some(StatusBarHover::LanguageIndicator),
+            Self::Messages => Some(StatusBarHover::MessageArea),
+            _ => None,
+        }
+    }
+}
+
+/// A parsed segment spec such as `git_branch(fg=syntax.keyword, bold)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentSpec {
+    pub kind: SegmentKind,
+    /// Theme key for the foreground color
+    pub fg: Option<String>,
+    /// Theme key for the background color
+    pub bg: Option<String>,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl SegmentSpec {
+    /// Parse a segment spec, returning a description of the problem if invalid
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let (name, options) = match spec.find('(') {
+            Some(open) => {
+                let options = spec[open + 1..]
+                    .strip_suffix(')')
+                    .ok_or_else(|| format!("missing ')' in segment '{}'", spec))?;
+                (spec[..open].trim(), Some(options))
+            }
+            None => (spec, None),
+        };
+
+        let kind =
+            SegmentKind::from_name(name).ok_or_else(|| format!("unknown segment '{}'", name))?;
+        let mut parsed = Self {
+            kind,
+            fg: None,
+            bg: None,
+            bold: false,
+            italic: false,
+        };
+
+        for option in options.into_iter().flat_map(|o| o.split(',')) {
+            let option = option.trim();
+            match option.split_once('=') {
+                Some(("fg", key)) => parsed.fg = Some(key.trim().to_string()),
+                Some(("bg", key)) => parsed.bg = Some(key.trim().to_string()),
+                None if option == "bold" => parsed.bold = true,
+                None if option == "italic" => parsed.italic = true,
+                None if option.is_empty() => {}
+                _ => return Err(format!("unknown option '{}' in segment '{}'", option, spec)),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Editor state needed to fill in the segments
+pub struct StatusLineContext<'a> {
+    pub display_name: &'a str,
+    pub status_message: Option<&'a str>,
+    pub plugin_status_message: Option<&'a str>,
+    pub lsp_status: &'a str,
+    pub warning_level: WarningLevel,
+    pub general_warning_count: usize,
+    pub hover: StatusBarHover,
+    pub remote_connection: Option<&'a str>,
+    pub session_name: Option<&'a str>,
+    pub update_available: Option<&'a str>,
+    pub keybindings: &'a crate::input::keybindings::KeybindingResolver,
+    pub chord_state: &'a [(crossterm::event::KeyCode, crossterm::event::KeyModifiers)],
+    pub pending_auto_saves: usize,
+    pub task_status: Option<&'a str>,
+    pub editor_mode: Option<&'a str>,
+    pub git_branch: Option<&'a str>,
+    pub plugin_segments: &'a HashMap<String, String>,
+}
+
+/// A segment ready to be placed on the line
+struct RenderedSegment {
+    kind: SegmentKind,
+    text: String,
+    style: Style,
+}
+
+impl RenderedSegment {
+    fn width(&self) -> usize {
+        str_width(&self.text)
+    }
+}
+
+/// Render the status line described by `config`
+///
+/// Invalid specs and segments with nothing to show are skipped. When space
+/// runs out the right group keeps its rightmost segments, the left group is
+/// truncated with `...`, and the center group is dropped.
+///
+/// # Returns
+/// Layout information with positions of clickable segments
+pub fn render_status_line(
+    frame: &mut Frame,
+    area: Rect,
+    state: &mut EditorState,
+    ctx: &StatusLineContext,
+    config: &StatusLineConfig,
+    theme: &Theme,
+) -> StatusBarLayout {
+    let mut layout = StatusBarLayout::default();
+    let available_width = area.width as usize;
+    let base_style = Style::default()
+        .fg(theme.status_bar_fg)
+        .bg(theme.status_bar_bg);
+
+    let mut right = render_group(&config.right, state, ctx, theme);
+    while group_width(&right) > available_width {
+        right.remove(0);
+    }
+    let right_width = group_width(&right);
+
+    let mut left = render_group(&config.left, state, ctx, theme);
+    truncate_group(&mut left, available_width - right_width);
+    let left_width = group_width(&left);
+
+    let center = render_group(&config.center, state, ctx, theme);
+    let center_width = group_width(&center);
+    let gap = available_width - left_width - right_width;
+    let center_start = (center_width > 0 && center_width <= gap).then(|| {
+        ((available_width - center_width) / 2)
+            .clamp(left_width, available_width - right_width - center_width)
+    });
+
+    let mut spans = Vec::new();
+    let mut col = 0;
+    place_segments(left, area, &mut col, &mut spans, &mut layout);
+    if let Some(start) = center_start {
+        spans.push(Span::styled(" ".repeat(start - col), base_style));
+        col = start;
+        place_segments(center, area, &mut col, &mut spans, &mut layout);
+    }
+    spans.push(Span::styled(
+        " ".repeat(available_width - right_width - col),
+        base_style,
+    ));
+    col = available_width - right_width;
+    place_segments(right, area, &mut col, &mut spans, &mut layout);
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+
+    layout
+}
+
+/// Append segments starting at `col`, recording clickable segment positions
+fn place_segments(
+    segments: Vec<RenderedSegment>,
+    area: Rect,
+    col: &mut usize,
+    spans: &mut Vec<Span<'static>>,
+    layout: &mut StatusBarLayout,
+) {
+    for segment in segments {
+        let width = segment.width();
+        let start = area.x + *col as u16;
+        let bounds = Some((area.y, start, start + width as u16));
+        match segment.kind {
+            SegmentKind::Lsp => layout.lsp_indicator = bounds,
+            SegmentKind::Warnings => layout.warning_badge = bounds,
+            SegmentKind::LineEnding => layout.line_ending_indicator = bounds,
+            SegmentKind::Encoding => layout.encoding_indicator = bounds,
+            SegmentKind::Language => layout.language_indicator = bounds,
+            SegmentKind::Messages => layout.message_area = bounds,
+            _ => {}
+        }
+        spans.push(Span::styled(segment.text, segment.style));
+        *col += width;
+    }
+}
+
+fn group_width(segments: &[RenderedSegment]) -> usize {
+    segments.iter().map(RenderedSegment::width).sum()
+}
+
+/// Drop and truncate segments so the group fits in `max_width` columns
+fn truncate_group(segments: &mut Vec<RenderedSegment>, max_width: usize) {
+    let mut used = 0;
+    for index in 0..segments.len() {
+        let width = segments[index].width();
+        if used + width > max_width {
+            let remaining = max_width - used;
+            segments.truncate(index + 1);
+            let segment = &mut segments[index];
+            segment.text = if remaining > 3 {
+                format!("{}...", take_width(&segment.text, remaining - 3))
+            } else {
+                take_width(&segment.text, remaining)
+            };
+            if segment.text.is_empty() {
+                segments.pop();
+            }
+            return;
+        }
+        used += width;
+    }
+}
+
+/// Longest prefix of `text` that fits in `max_width` columns
+fn take_width(text: &str, max_width: usize) -> String {
+    let mut width = 0;
+    text.chars()
+        .take_while(|ch| {
+            width += char_width(*ch);
+            width <= max_width
+        })
+        .collect()
+}
+
+fn render_group(
+    specs: &[String],
+    state: &mut EditorState,
+    ctx: &StatusLineContext,
+    theme: &Theme,
+) -> Vec<RenderedSegment> {
+    specs
+        .iter()
+        .filter_map(|spec| SegmentSpec::parse(spec).ok())
+        .filter_map(|spec| {
+            let text = segment_text(&spec.kind, state, ctx)?;
+            let style = segment_style(&spec, ctx, theme);
+            Some(RenderedSegment {
+                kind: spec.kind,
+                text: format!(" {} ", text),
+                style,
+            })
+        })
+        .collect()
+}
+
+/// Text of a segment, or None when it has nothing to show
+fn segment_text(
+    kind: &SegmentKind,
+    state: &mut EditorState,
+    ctx: &StatusLineContext,
+) -> Option<String> {
+    let text = match kind {
+        SegmentKind::Mode => ctx.editor_mode?.to_string(),
+        SegmentKind::Session => format!("[{}]", ctx.session_name?),
+        SegmentKind::Remote => format!("[SSH:{}]", ctx.remote_connection?),
+        SegmentKind::Filename => {
+            let modified = if state.buffer.is_modified() {
+                " [+]"
+            } else {
+                ""
+            };
+            format!("{}{}", ctx.display_name, modified)
+        }
+        SegmentKind::Position => {
+            // Virtual buffers with hidden cursors have no meaningful position
+            if !state.show_cursors {
+                return None;
+            }
+            let (line, col) = cursor_line_col(state);
+            format!("Ln {}, Col {}", line + 1, col + 1)
+        }
+        SegmentKind::Diagnostics => diagnostics_summary(state)?,
+        SegmentKind::Cursors => {
+            let count = state.cursors.count();
+            if count <= 1 {
+                return None;
+            }
+            t!("status.cursors", count = count).to_string()
+        }
+        SegmentKind::AutoSave => {
+            if ctx.pending_auto_saves == 0 {
+                return None;
+            }
+            t!("status.auto_save_pending", count = ctx.pending_auto_saves).to_string()
+        }
+        SegmentKind::Tasks => ctx.task_status?.to_string(),
+        SegmentKind::Chord => {
+            if ctx.chord_state.is_empty() {
+                return None;
+            }
+            let chord = ctx
+                .chord_state
+                .iter()
+                .map(|(code, modifiers)| {
+                    crate::input::keybindings::format_keybinding(code, modifiers)
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("[{}]", chord)
+        }
+        SegmentKind::Messages => {
+            let messages: Vec<&str> = [ctx.status_message, ctx.plugin_status_message]
+                .into_iter()
+                .flatten()
+                .filter(|msg| !msg.is_empty())
+                .collect();
+            messages.join(" | ")
+        }
+        SegmentKind::GitBranch => ctx.git_branch?.to_string(),
+        SegmentKind::Lsp => ctx.lsp_status.to_string(),
+        SegmentKind::Warnings => {
+            if ctx.general_warning_count == 0 {
+                return None;
+            }
+            format!("[⚠ {}]", ctx.general_warning_count)
+        }
+        SegmentKind::LineEnding => state.buffer.line_ending().display_name().to_string(),
+        SegmentKind::Encoding => state.buffer.encoding().display_name().to_string(),
+        SegmentKind::Indentation => indentation_summary(state),
+        SegmentKind::Language => state.language.clone(),
+        SegmentKind::Update => {
+            t!("status.update_available", version = ctx.update_available?).to_string()
+        }
+        SegmentKind::Palette => {
+            let shortcut = ctx
+                .keybindings
+                .get_keybinding_for_action(
+                    &crate::input::keybindings::Action::QuickOpen,
+                    crate::input::keybindings::KeyContext::Global,
+                )
+                .unwrap_or_else(|| "?".to_string());
+            t!("status.palette", shortcut = shortcut).to_string()
+        }
+        SegmentKind::Plugin(id) => ctx.plugin_segments.get(id)?.clone(),
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// Style of a segment: the built-in colors for the segment, overridden by
+/// the spec's theme keys unless the segment is hovered
+fn segment_style(spec: &SegmentSpec, ctx: &StatusLineContext, theme: &Theme) -> Style {
+    let hovered = spec.kind.hover() == Some(ctx.hover) && ctx.hover != StatusBarHover::None;
+    let (fg, bg) = match (&spec.kind, hovered) {
+        (SegmentKind::Lsp, _) => match (ctx.warning_level, hovered) {
+            (WarningLevel::Error, true) => (
+                theme.status_error_indicator_hover_fg,
+                theme.status_error_indicator_hover_bg,
+            ),
+            (WarningLevel::Error, false) => (
+                theme.status_error_indicator_fg,
+                theme.status_error_indicator_bg,
+            ),
+            (WarningLevel::Warning, true) => (
+                theme.status_warning_indicator_hover_fg,
+                theme.status_warning_indicator_hover_bg,
+            ),
+            (WarningLevel::Warning, false) => (
+                theme.status_warning_indicator_fg,
+                theme.status_warning_indicator_bg,
+            ),
+            (WarningLevel::None, _) => (theme.status_bar_fg, theme.status_bar_bg),
+        },
+        (SegmentKind::Warnings, true) => (
+            theme.status_warning_indicator_hover_fg,
+            theme.status_warning_indicator_hover_bg,
+        ),
+        (SegmentKind::Warnings, false) => (
+            theme.status_warning_indicator_fg,
+            theme.status_warning_indicator_bg,
+        ),
+        (SegmentKind::Update, _) => (theme.menu_highlight_fg, theme.menu_dropdown_bg),
+        (SegmentKind::Palette, _) => (theme.help_indicator_fg, theme.help_indicator_bg),
+        (SegmentKind::LineEnding | SegmentKind::Encoding | SegmentKind::Language, true) => {
+            (theme.menu_hover_fg, theme.menu_hover_bg)
+        }
+        _ => (theme.status_bar_fg, theme.status_bar_bg),
+    };
+
+    let mut style = Style::default().fg(fg).bg(bg);
+    if !hovered {
+        if let Some(color) = spec.fg.as_deref().and_then(|k| theme.resolve_theme_key(k)) {
+            style = style.fg(color);
+        }
+        if let Some(color) = spec.bg.as_deref().and_then(|k| theme.resolve_theme_key(k)) {
+            style = style.bg(color);
+        }
+    }
+    if spec.bold {
+        style = style.add_modifier(Modifier::BOLD);
+    }
+    if spec.italic {
+        style = style.add_modifier(Modifier::ITALIC);
+    }
+    let underline = match spec.kind {
+        SegmentKind::Lsp => ctx.warning_level != WarningLevel::None,
+        SegmentKind::Messages => false,
+        _ => true,
+    };
+    if hovered && underline {
+        style = style.add_modifier(Modifier::UNDERLINED);
+    }
+    style
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain_segment() {
+        let spec = SegmentSpec::parse(" position ").unwrap();
+        assert_eq!(spec.kind, SegmentKind::Position);
+        assert_eq!(spec.fg, None);
+        assert!(!spec.bold);
+    }
+
+    #[test]
+    fn test_parse_segment_with_options() {
+        let spec =
+            SegmentSpec::parse("git_branch(fg=syntax.keyword, bg=ui.tab_active_bg, bold)").unwrap();
+        assert_eq!(spec.kind, SegmentKind::GitBranch);
+        assert_eq!(spec.fg.as_deref(), Some("syntax.keyword"));
+        assert_eq!(spec.bg.as_deref(), Some("ui.tab_active_bg"));
+        assert!(spec.bold);
+        assert!(!spec.italic);
+    }
+
+    #[test]
+    fn test_parse_plugin_segment() {
+        let spec = SegmentSpec::parse("plugin:clock(italic)").unwrap();
+        assert_eq!(spec.kind, SegmentKind::Plugin("clock".to_string()));
+        assert!(spec.italic);
+        assert!(SegmentSpec::parse("plugin:").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_segments() {
+        assert!(SegmentSpec::parse("nonsense").is_err());
+        assert!(SegmentSpec::parse("position(bold").is_err());
+        assert!(SegmentSpec::parse("position(blink)").is_err());
+    }
+
+    #[test]
+    fn test_take_width() {
+        assert_eq!(take_width("hello", 3), "hel");
+        assert_eq!(take_width("日本語", 5), "日本");
+        assert_eq!(take_width("hi", 10), "hi");
+    }
+}
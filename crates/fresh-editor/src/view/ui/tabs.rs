@@ -4,13 +4,14 @@ use crate::app::BufferMetadata;
 use crate::model::event::BufferId;
 use crate::primitives::display_width::str_width;
 use crate::state::EditorState;
+use crate::view::split::TabGroup;
 use crate::view::ui::layout::point_in_rect;
 use ratatui::layout::Rect;
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Paragraph};
 use ratatui::Frame;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Hit area for a single tab
 #[derive(Debug, Clone)]
@@ -113,6 +114,9 @@ impl TabLayout {
     }
 }
 
+/// Shown in place of the close button on pinned tabs
+const PINNED_MARKER: &str = "• ";
+
 /// Renders the tab bar showing open buffers
 pub struct TabsRenderer;
 
@@ -254,6 +258,8 @@ impl TabsRenderer {
     /// * `active_buffer` - The currently active buffer ID for this split
     /// * `theme` - The active theme for colors
     /// * `is_active_split` - Whether this split is the active one
+    /// * `pinned_buffers` - Pinned tabs (shown with a pin marker instead of a close button)
+    /// * `tab_groups` - Group of each grouped tab (shown as a colored marker)
     /// * `hovered_tab` - Optional (buffer_id, is_close_button) if a tab is being hovered
     ///
     /// # Returns
@@ -270,6 +276,8 @@ impl TabsRenderer {
         theme: &crate::view::theme::Theme,
         is_active_split: bool,
         tab_scroll_offset: usize,
+        pinned_buffers: &HashSet<BufferId>,
+        tab_groups: &HashMap<BufferId, TabGroup>,
         hovered_tab: Option<(BufferId, bool)>, // (buffer_id, is_close_button)
     ) -> TabLayout {
         let mut layout = TabLayout::new(area);
@@ -370,6 +378,12 @@ impl TabsRenderer {
                     .fg(theme.tab_inactive_fg)
                     .bg(theme.tab_inactive_bg)
             };
+            // The preview tab is in italics until it is kept
+            let base_style = if buffer_metadata.get(id).is_some_and(|m| m.preview) {
+                base_style.add_modifier(Modifier::ITALIC)
+            } else {
+                base_style
+            };
 
             // Style for the close button
             let close_style = if is_hovered_close {
@@ -379,23 +393,46 @@ impl TabsRenderer {
                 base_style
             };
 
-            // Build tab content: " {name}{modified}{binary_indicator} "
-            let tab_name_text = format!(" {name}{modified}{binary_indicator} ");
-            let tab_name_width = str_width(&tab_name_text);
-
-            // Close button: "× "
-            let close_text = "× ";
+            // Build tab content: "{group_marker}{name}{modified}{binary_indicator} "
+            // Grouped tabs replace the leading space with a marker in the group color
+            let (group_marker, group_style) = match tab_groups.get(id) {
+                Some(group) => ("▎", base_style.fg(group.color())),
+                None => (" ", base_style),
+            };
+            let tab_name_text = format!("{name}{modified}{binary_indicator} ");
+            let tab_name_width = str_width(group_marker) + str_width(&tab_name_text);
+
+            // Close button: "× ", or the pin marker for pinned tabs (which
+            // can't be closed from the tab bar)
+            let is_pinned = pinned_buffers.contains(id);
+            let (close_text, close_style) = if is_pinned {
+                (PINNED_MARKER, base_style)
+            } else {
+                ("× ", close_style)
+            };
             let close_width = str_width(close_text);
 
             let total_width = tab_name_width + close_width;
 
             let start_pos: usize = all_tab_spans.iter().map(|(_, w)| w).sum();
-            let close_start_pos = start_pos + tab_name_width;
+            // Pinned tabs have an empty close button area
+            let close_start_pos = if is_pinned {
+                start_pos + total_width
+            } else {
+                start_pos + tab_name_width
+            };
             let end_pos = start_pos + total_width;
             tab_ranges.push((start_pos, end_pos, close_start_pos));
 
-            // Add name span
-            all_tab_spans.push((Span::styled(tab_name_text, base_style), tab_name_width));
+            // Add group marker and name spans
+            all_tab_spans.push((
+                Span::styled(group_marker.to_string(), group_style),
+                str_width(group_marker),
+            ));
+            all_tab_spans.push((
+                Span::styled(tab_name_text.clone(), base_style),
+                str_width(&tab_name_text),
+            ));
             // Add close button span (can have different style when hovered)
             all_tab_spans.push((
                 Span::styled(close_text.to_string(), close_style),
@@ -408,7 +445,7 @@ impl TabsRenderer {
         // to account for the separator widths
         let mut final_spans: Vec<(Span<'static>, usize)> = Vec::new();
         let mut separator_offset = 0usize;
-        let spans_per_tab = 2; // name + close button
+        let spans_per_tab = 3; // group marker + name + close button
         for (tab_idx, chunk) in all_tab_spans.chunks(spans_per_tab).enumerate() {
             // Adjust tab_ranges for this tab to account for separators before it
             if separator_offset > 0 {
@@ -647,6 +684,8 @@ impl TabsRenderer {
             theme,
             true, // Legacy behavior: always treat as active
             0,    // Default tab_scroll_offset for legacy render
+            &HashSet::new(),
+            &HashMap::new(),
             None, // No hover state for legacy render
         );
     }
@@ -21,7 +21,7 @@
 //! not reconstructed from flattened text.
 
 use crate::primitives::ansi::AnsiParser;
-use crate::primitives::display_width::char_width;
+use crate::primitives::display_width::ClusterWidth;
 use fresh_core::api::{ViewTokenStyle, ViewTokenWire, ViewTokenWireKind};
 use std::collections::HashSet;
 
@@ -189,6 +189,21 @@ fn format_unprintable_byte(b: u8) -> String {
     format!("<{:02X}>", b)
 }
 
+/// Format a control character in caret notation like "^M" (DEL is "^?")
+fn format_control_char(b: u8) -> String {
+    format!("^{}", (b ^ 0x40) as char)
+}
+
+/// How a `BinaryByte` token is shown: as a code point in binary files, and
+/// control characters in caret notation in text files
+pub(crate) fn format_binary_byte(b: u8, binary_mode: bool) -> String {
+    if !binary_mode && is_unprintable_byte(b) {
+        format_control_char(b)
+    } else {
+        format_unprintable_byte(b)
+    }
+}
+
 impl<'a> Iterator for ViewLineIterator<'a> {
     type Item = ViewLine;
 
@@ -239,6 +254,10 @@ impl<'a> Iterator for ViewLineIterator<'a> {
             }};
         }
 
+        // Widths by grapheme cluster, so that an emoji sequence or a
+        // character with combining marks takes the columns the terminal gives it
+        let mut widths = ClusterWidth::new();
+
         // Process tokens until we hit a line break
         while self.token_idx < self.tokens.len() {
             let token = &self.tokens[self.token_idx];
@@ -256,6 +275,7 @@ impl<'a> Iterator for ViewLineIterator<'a> {
 
                         // In binary mode, render unprintable bytes as code points
                         if self.binary_mode && is_unprintable_byte(b) {
+                            widths.reset();
                             let formatted = format_unprintable_byte(b);
                             for display_ch in formatted.chars() {
                                 add_char!(display_ch, source, token_style.clone(), 1);
@@ -316,6 +336,7 @@ impl<'a> Iterator for ViewLineIterator<'a> {
                         };
 
                         if ch == '\t' {
+                            widths.reset();
                             // Tab expands to spaces - record start position
                             let tab_start_pos = char_source_bytes.len();
                             tab_starts.insert(tab_start_pos);
@@ -350,10 +371,10 @@ impl<'a> Iterator for ViewLineIterator<'a> {
                                 if parser.parse_char(ch).is_none() {
                                     0 // Part of escape sequence, zero width
                                 } else {
-                                    char_width(ch)
+                                    widths.push(ch)
                                 }
                             } else {
-                                char_width(ch)
+                                widths.push(ch)
                             };
                             add_char!(ch, source, token_style.clone(), width);
                         }
@@ -361,6 +382,7 @@ impl<'a> Iterator for ViewLineIterator<'a> {
                     self.token_idx += 1;
                 }
                 ViewTokenWireKind::Space => {
+                    widths.reset();
                     add_char!(' ', token.source_offset, token_style, 1);
                     self.token_idx += 1;
                 }
@@ -388,8 +410,10 @@ impl<'a> Iterator for ViewLineIterator<'a> {
                     break;
                 }
                 ViewTokenWireKind::BinaryByte(b) => {
-                    // Binary byte rendered as <XX> - all 4 chars map to same source byte
-                    let formatted = format_unprintable_byte(*b);
+                    // Binary byte rendered as <XX> (or ^X for a control character
+                    // in a text file) - all chars map to same source byte
+                    widths.reset();
+                    let formatted = format_binary_byte(*b, self.binary_mode);
                     for display_ch in formatted.chars() {
                         add_char!(display_ch, token.source_offset, token_style.clone(), 1);
                     }
@@ -795,6 +819,16 @@ mod tests {
         assert_eq!(format_unprintable_byte(0xFF), "<FF>");
     }
 
+    #[test]
+    fn test_format_binary_byte() {
+        assert_eq!(format_binary_byte(0x0D, false), "^M");
+        assert_eq!(format_binary_byte(0x00, false), "^@");
+        assert_eq!(format_binary_byte(0x1B, false), "^[");
+        assert_eq!(format_binary_byte(0x7F, false), "^?");
+        assert_eq!(format_binary_byte(0x0D, true), "<0D>");
+        assert_eq!(format_binary_byte(0x89, false), "<89>");
+    }
+
     #[test]
     fn test_binary_mode_renders_control_chars() {
         // Text with null byte and control character
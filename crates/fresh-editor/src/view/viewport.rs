@@ -1,6 +1,6 @@
 use crate::model::buffer::Buffer;
 use crate::model::cursor::Cursor;
-use crate::primitives::display_width::{char_width, str_width};
+use crate::primitives::display_width::{str_width, ClusterWidth};
 use crate::primitives::line_wrapping::{char_position_to_segment, wrap_line, WrapConfig};
 use crate::view::ui::view_pipeline::ViewLine;
 /// The viewport - what portion of the buffer is visible
@@ -35,6 +35,10 @@ pub struct Viewport {
     /// When true, horizontal scrolling is disabled
     pub line_wrap_enabled: bool,
 
+    /// Keep the cursor line vertically centered (typewriter scrolling)
+    /// When true, scroll_offset is ignored and the viewport recenters on every sync
+    pub typewriter_scrolling: bool,
+
     /// Whether viewport needs synchronization with cursor positions
     /// When true, ensure_visible needs to be called before rendering
     /// This allows batching multiple cursor movements into a single viewport update
@@ -63,6 +67,7 @@ impl Viewport {
             scroll_offset: 3,
             horizontal_scroll_offset: 5,
             line_wrap_enabled: false,
+            typewriter_scrolling: false,
             needs_sync: false,
             skip_resize_sync: false,
             skip_ensure_visible: false,
@@ -649,11 +654,12 @@ impl Viewport {
                 let line_text = line.text.trim_end_matches('\n');
                 let mut bytes_consumed = 0usize;
                 let mut cursor_visual_col = 0usize;
+                let mut widths = ClusterWidth::new();
                 for ch in line_text.chars() {
                     if bytes_consumed >= cursor_byte_offset {
                         break;
                     }
-                    cursor_visual_col += char_width(ch);
+                    cursor_visual_col += widths.push(ch);
                     bytes_consumed += ch.len_utf8();
                 }
 
@@ -908,8 +914,13 @@ impl Viewport {
 
         // Check if cursor is visible by counting VISUAL ROWS between top_byte and cursor
         // When line wrapping is enabled, we need to count wrapped rows, not logical lines!
-        // Apply scroll_offset to keep cursor away from edges
-        let effective_offset = self.scroll_offset.min(viewport_lines / 2);
+        // Apply scroll_offset to keep cursor away from edges. Typewriter scrolling
+        // uses half the viewport, so the cursor is only "visible" when centered.
+        let effective_offset = if self.typewriter_scrolling {
+            viewport_lines / 2
+        } else {
+            self.scroll_offset.min(viewport_lines / 2)
+        };
 
         let cursor_is_visible = if cursor_line_start < self.top_byte {
             // Cursor is above viewport
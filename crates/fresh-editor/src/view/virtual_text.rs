@@ -310,6 +310,18 @@ impl VirtualTextManager {
         self.texts.clear();
     }
 
+    /// Clear inline virtual texts (BeforeChar/AfterChar), keeping virtual lines
+    pub fn clear_inline(&mut self, marker_list: &mut MarkerList) {
+        self.texts.retain(|_, vtext| {
+            if vtext.position.is_inline() {
+                marker_list.delete(vtext.marker_id);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
     /// Get the number of virtual text entries
     pub fn len(&self) -> usize {
         self.texts.len()
@@ -606,6 +618,38 @@ mod tests {
         assert_eq!(marker_list.marker_count(), 0);
     }
 
+    #[test]
+    fn test_clear_inline_keeps_lines() {
+        let mut marker_list = MarkerList::new();
+        let mut manager = VirtualTextManager::new();
+
+        manager.add(
+            &mut marker_list,
+            10,
+            ": i32".to_string(),
+            hint_style(),
+            VirtualTextPosition::AfterChar,
+            0,
+        );
+        manager.add_line(
+            &mut marker_list,
+            0,
+            "2 references".to_string(),
+            hint_style(),
+            VirtualTextPosition::LineAbove,
+            VirtualTextNamespace::from_string("lsp-code-lens".to_string()),
+            0,
+        );
+
+        manager.clear_inline(&mut marker_list);
+
+        assert_eq!(manager.len(), 1);
+        assert_eq!(marker_list.marker_count(), 1);
+        let lines = manager.query_lines_in_range(&marker_list, 0, 100);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].1.text, "2 references");
+    }
+
     #[test]
     fn test_query_range() {
         let mut marker_list = MarkerList::new();
@@ -22,6 +22,12 @@
 //!
 //! Uses atomic writes: write to temp file, then rename.
 //! This ensures the workspace file is never left in a corrupted state.
+//!
+//! ## Versioning
+//!
+//! Both file formats carry a `version`. Older files are brought up to date with
+//! the migrations in `WORKSPACE_MIGRATIONS` and `FILE_WORKSPACE_MIGRATIONS`
+//! before they are deserialized; newer files are rejected.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -30,6 +36,8 @@ use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::input::input_history::get_data_dir;
+use crate::migration::{self, Migration, MigrationError};
+use crate::view::split::TabGroup;
 
 /// Current workspace file format version
 pub const WORKSPACE_VERSION: u32 = 1;
@@ -37,6 +45,14 @@ pub const WORKSPACE_VERSION: u32 = 1;
 /// Current per-file workspace version
 pub const FILE_WORKSPACE_VERSION: u32 = 1;
 
+/// Workspace file format changes, oldest first.
+/// Add one here whenever `WORKSPACE_VERSION` is incremented.
+pub const WORKSPACE_MIGRATIONS: &[Migration] = &[];
+
+/// Per-file workspace format changes, oldest first.
+/// Add one here whenever `FILE_WORKSPACE_VERSION` is incremented.
+pub const FILE_WORKSPACE_MIGRATIONS: &[Migration] = &[];
+
 /// Persisted workspace state for a working directory
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workspace {
@@ -74,6 +90,10 @@ pub struct Workspace {
     #[serde(default)]
     pub bookmarks: HashMap<char, SerializedBookmark>,
 
+    /// File marks (`A`-`Z` -> file position)
+    #[serde(default)]
+    pub file_marks: HashMap<char, SerializedBookmark>,
+
     /// Open terminal workspaces (for restoration)
     #[serde(default)]
     pub terminals: Vec<SerializedTerminalWorkspace>,
@@ -149,6 +169,46 @@ pub struct SerializedSplitViewState {
     /// Compose width if in compose mode
     #[serde(default)]
     pub compose_width: Option<u16>,
+
+    /// Whether focus mode is on
+    #[serde(default)]
+    pub focus_mode: bool,
+
+    /// Indices into open_tabs of pinned tabs
+    #[serde(default)]
+    pub pinned_tabs: Vec<usize>,
+
+    /// Tab group by index into open_tabs
+    #[serde(default)]
+    pub tab_groups: HashMap<usize, TabGroup>,
+
+    /// Jump list for Navigate Back/Forward
+    #[serde(default)]
+    pub jump_list: SerializedJumpList,
+}
+
+/// A split's jump list; positions in buffers without a file are not saved
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SerializedJumpList {
+    /// Positions, oldest first
+    #[serde(default)]
+    pub entries: Vec<SerializedJump>,
+
+    /// Index into entries of the current position
+    #[serde(default)]
+    pub current: Option<usize>,
+}
+
+/// A position in a jump list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedJump {
+    /// File path (relative to working_dir)
+    pub file_path: PathBuf,
+    /// Cursor byte offset
+    pub position: usize,
+    /// Selection anchor byte offset
+    #[serde(default)]
+    pub anchor: Option<usize>,
 }
 
 /// Per-file state within a split
@@ -382,16 +442,17 @@ impl PersistedFileWorkspace {
             Err(_) => return None,
         };
 
-        let persisted: PersistedFileState = match serde_json::from_str(&content) {
-            Ok(p) => p,
-            Err(_) => return None,
-        };
+        let mut value: serde_json::Value = serde_json::from_str(&content).ok()?;
 
-        // Check version compatibility
-        if persisted.version > FILE_WORKSPACE_VERSION {
-            return None;
-        }
+        // Bring older files up to date; skip files from a newer version
+        migration::migrate(
+            &mut value,
+            FILE_WORKSPACE_MIGRATIONS,
+            FILE_WORKSPACE_VERSION,
+        )
+        .ok()?;
 
+        let persisted: PersistedFileState = serde_json::from_value(value).ok()?;
         Some(persisted.state)
     }
 
@@ -608,27 +669,32 @@ impl From<serde_json::Error> for WorkspaceError {
     }
 }
 
+impl From<MigrationError> for WorkspaceError {
+    fn from(e: MigrationError) -> Self {
+        match e {
+            MigrationError::VersionTooNew {
+                version,
+                max_supported,
+            } => WorkspaceError::VersionTooNew {
+                version,
+                max_supported,
+            },
+            MigrationError::Io { .. } => WorkspaceError::Io(e.into()),
+        }
+    }
+}
+
 impl Workspace {
     /// Load workspace for a working directory (if exists)
     pub fn load(working_dir: &Path) -> Result<Option<Workspace>, WorkspaceError> {
-        let path = get_workspace_path(working_dir)?;
-        tracing::debug!("Looking for workspace at {:?}", path);
+        Self::load_from(&get_workspace_path(working_dir)?, working_dir)
+    }
 
-        if !path.exists() {
-            tracing::debug!("Workspace file does not exist");
+    /// Load a workspace file (if exists) that must belong to `working_dir`
+    pub fn load_from(path: &Path, working_dir: &Path) -> Result<Option<Workspace>, WorkspaceError> {
+        let Some(workspace) = Self::read_file(path)? else {
             return Ok(None);
-        }
-
-        tracing::debug!("Loading workspace from {:?}", path);
-        let content = std::fs::read_to_string(&path)?;
-        let workspace: Workspace = serde_json::from_str(&content)?;
-
-        tracing::debug!(
-            "Loaded workspace: version={}, split_states={}, active_split={}",
-            workspace.version,
-            workspace.split_states.len(),
-            workspace.active_split_id
-        );
+        };
 
         // Validate working_dir matches (canonicalize both for comparison)
         let expected = working_dir
@@ -648,18 +714,41 @@ impl Workspace {
             return Err(WorkspaceError::WorkdirMismatch { expected, found });
         }
 
-        // Check version compatibility
-        if workspace.version > WORKSPACE_VERSION {
-            tracing::warn!(
-                "Workspace version {} is newer than supported {}",
-                workspace.version,
-                WORKSPACE_VERSION
+        Ok(Some(workspace))
+    }
+
+    /// Read a workspace file (if exists) for any working directory
+    pub fn read_file(path: &Path) -> Result<Option<Workspace>, WorkspaceError> {
+        tracing::debug!("Looking for workspace at {:?}", path);
+
+        if !path.exists() {
+            tracing::debug!("Workspace file does not exist");
+            return Ok(None);
+        }
+
+        tracing::debug!("Loading workspace from {:?}", path);
+        let content = std::fs::read_to_string(path)?;
+        let mut value: serde_json::Value = serde_json::from_str(&content)?;
+
+        // Check version compatibility and migrate older formats
+        let report = migration::migrate(&mut value, WORKSPACE_MIGRATIONS, WORKSPACE_VERSION)
+            .inspect_err(|e| tracing::warn!("Not loading workspace {:?}: {}", path, e))?;
+        if !report.applied.is_empty() {
+            tracing::info!(
+                "Migrated workspace from version {} to {}: {}",
+                report.from,
+                report.to,
+                report.applied.join(", ")
             );
-            return Err(WorkspaceError::VersionTooNew {
-                version: workspace.version,
-                max_supported: WORKSPACE_VERSION,
-            });
         }
+        let workspace: Workspace = serde_json::from_value(value)?;
+
+        tracing::debug!(
+            "Loaded workspace: version={}, split_states={}, active_split={}",
+            workspace.version,
+            workspace.split_states.len(),
+            workspace.active_split_id
+        );
 
         Ok(Some(workspace))
     }
@@ -671,7 +760,11 @@ impl Workspace {
     /// 2. Sync to disk (fsync)
     /// 3. Atomically rename to the final path
     pub fn save(&self) -> Result<(), WorkspaceError> {
-        let path = get_workspace_path(&self.working_dir)?;
+        self.save_to(&get_workspace_path(&self.working_dir)?)
+    }
+
+    /// Save workspace to the given file (atomically, like `save`)
+    pub fn save_to(&self, path: &Path) -> Result<(), WorkspaceError> {
         tracing::debug!("Saving workspace to {:?}", path);
 
         // Ensure directory exists
@@ -694,7 +787,7 @@ impl Workspace {
         }
 
         // Atomic rename
-        std::fs::rename(&temp_path, &path)?;
+        std::fs::rename(&temp_path, path)?;
         tracing::info!("Workspace saved to {:?}", path);
 
         Ok(())
@@ -725,6 +818,7 @@ impl Workspace {
             histories: WorkspaceHistories::default(),
             search_options: SearchOptions::default(),
             bookmarks: HashMap::new(),
+            file_marks: HashMap::new(),
             terminals: Vec::new(),
             external_files: Vec::new(),
             saved_at: SystemTime::now()
@@ -976,6 +1070,24 @@ mod tests {
                 tab_scroll_offset: 0,
                 view_mode: SerializedViewMode::Source,
                 compose_width: None,
+                focus_mode: false,
+                pinned_tabs: vec![0],
+                tab_groups: HashMap::from([(1, TabGroup::Blue)]),
+                jump_list: SerializedJumpList {
+                    entries: vec![
+                        SerializedJump {
+                            file_path: PathBuf::from("README.md"),
+                            position: 0,
+                            anchor: None,
+                        },
+                        SerializedJump {
+                            file_path: PathBuf::from("src/lib.rs"),
+                            position: 120,
+                            anchor: Some(110),
+                        },
+                    ],
+                    current: Some(0),
+                },
             },
         );
 
@@ -988,6 +1100,14 @@ mod tests {
             },
         );
 
+        workspace.file_marks.insert(
+            'A',
+            SerializedBookmark {
+                file_path: PathBuf::from("src/lib.rs"),
+                position: 42,
+            },
+        );
+
         // Set search options
         workspace.search_options.case_sensitive = true;
         workspace.search_options.use_regex = true;
@@ -1001,6 +1121,7 @@ mod tests {
         assert_eq!(restored.working_dir, PathBuf::from("/home/user/myproject"));
         assert_eq!(restored.active_split_id, 1);
         assert!(restored.bookmarks.contains_key(&'m'));
+        assert_eq!(restored.file_marks.get(&'A').unwrap().position, 42);
         assert!(restored.search_options.case_sensitive);
         assert!(restored.search_options.use_regex);
 
@@ -1008,6 +1129,11 @@ mod tests {
         let split_state = restored.split_states.get(&1).unwrap();
         assert_eq!(split_state.open_files.len(), 2);
         assert_eq!(split_state.open_files[0], PathBuf::from("README.md"));
+        assert_eq!(split_state.pinned_tabs, vec![0]);
+        assert_eq!(split_state.tab_groups.get(&1), Some(&TabGroup::Blue));
+        assert_eq!(split_state.jump_list.entries.len(), 2);
+        assert_eq!(split_state.jump_list.entries[1].anchor, Some(110));
+        assert_eq!(split_state.jump_list.current, Some(0));
     }
 
     #[test]
@@ -1069,6 +1195,22 @@ mod tests {
         assert_eq!(restored.version, 999);
     }
 
+    #[test]
+    fn test_read_file_rejects_newer_version() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("workspace.json");
+        let mut json_value = serde_json::to_value(Workspace::new(PathBuf::from("/test"))).unwrap();
+        json_value["version"] = serde_json::json!(WORKSPACE_VERSION + 1);
+        std::fs::write(&path, json_value.to_string()).unwrap();
+
+        let err = Workspace::read_file(&path).unwrap_err();
+        assert!(matches!(
+            err,
+            WorkspaceError::VersionTooNew { version, max_supported }
+                if version == WORKSPACE_VERSION + 1 && max_supported == WORKSPACE_VERSION
+        ));
+    }
+
     #[test]
     fn test_empty_workspace_histories() {
         let histories = WorkspaceHistories::default();
@@ -1323,6 +1323,124 @@ done
         std::env::temp_dir().join("fake_lsp_server_no_range.sh")
     }
 
+    /// Spawn a named fake LSP server for multi-server tests
+    ///
+    /// The script is written to `dir` as `fake_lsp_<name>.sh`, so each server
+    /// gets its own command (and label). It logs every method to the file given
+    /// as its first argument and publishes a `<name> problem` diagnostic on
+    /// didOpen/didChange, or clears its diagnostics once a change contains
+    /// `fixed_<name>`. Only advertises hover when `hover` is true.
+    pub fn spawn_named(dir: &std::path::Path, name: &str, hover: bool) -> anyhow::Result<Self> {
+        let (stop_tx, stop_rx) = mpsc::channel();
+
+        let hover_capability = if hover {
+            r#""hoverProvider":true,"#
+        } else {
+            ""
+        };
+        let script = format!(
+            r#"#!/bin/bash
+
+LOG_FILE="$1"
+> "$LOG_FILE"
+
+# Function to read a message
+read_message() {{
+    # Read headers
+    local content_length=0
+    while IFS=: read -r key value; do
+        key=$(echo "$key" | tr -d '\r\n')
+        value=$(echo "$value" | tr -d '\r\n ')
+        if [ "$key" = "Content-Length" ]; then
+            content_length=$value
+        fi
+        # Empty line marks end of headers
+        if [ -z "$key" ]; then
+            break
+        fi
+    done
+
+    # Read content
+    if [ $content_length -gt 0 ]; then
+        dd bs=1 count=$content_length 2>/dev/null
+    fi
+}}
+
+# Function to send a message
+send_message() {{
+    local message="$1"
+    local length=${{#message}}
+    echo -en "Content-Length: $length\r\n\r\n$message"
+}}
+
+# Main loop
+while true; do
+    # Read incoming message
+    msg=$(read_message)
+
+    if [ -z "$msg" ]; then
+        break
+    fi
+
+    # Extract method from JSON
+    method=$(echo "$msg" | grep -o '"method":"[^"]*"' | cut -d'"' -f4)
+    msg_id=$(echo "$msg" | grep -o '"id":[0-9]*' | cut -d':' -f2)
+
+    # Log the method to file
+    if [ -n "$method" ]; then
+        echo "$method" >> "$LOG_FILE"
+    fi
+
+    case "$method" in
+    "initialize")
+        send_message '{{"jsonrpc":"2.0","id":'$msg_id',"result":{{"capabilities":{{{hover_capability}"textDocumentSync":2}}}}}}'
+        ;;
+    "textDocument/hover")
+        send_message '{{"jsonrpc":"2.0","id":'$msg_id',"result":{{"contents":{{"kind":"markdown","value":"Hover from {name}"}}}}}}'
+        ;;
+    "textDocument/didOpen"|"textDocument/didChange")
+        uri=$(echo "$msg" | grep -o '"uri":"[^"]*"' | head -1 | cut -d'"' -f4)
+        if echo "$msg" | grep -q 'fixed_{name}'; then
+            send_message '{{"jsonrpc":"2.0","method":"textDocument/publishDiagnostics","params":{{"uri":"'$uri'","diagnostics":[]}}}}'
+        else
+            send_message '{{"jsonrpc":"2.0","method":"textDocument/publishDiagnostics","params":{{"uri":"'$uri'","diagnostics":[{{"range":{{"start":{{"line":0,"character":0}},"end":{{"line":0,"character":2}}}},"severity":1,"message":"{name} problem"}}]}}}}'
+        fi
+        ;;
+    "shutdown")
+        send_message '{{"jsonrpc":"2.0","id":'$msg_id',"result":null}}'
+        break
+        ;;
+    esac
+done
+"#
+        );
+
+        // Write script next to the test's files
+        let script_path = Self::named_script_path(dir, name);
+        std::fs::write(&script_path, script)?;
+
+        // Make it executable
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&script_path)?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&script_path, perms)?;
+        }
+
+        let handle = Some(thread::spawn(move || {
+            // Wait for stop signal
+            let _ = stop_rx.recv();
+        }));
+
+        Ok(Self { handle, stop_tx })
+    }
+
+    /// Get the path to a named fake LSP server script
+    pub fn named_script_path(dir: &std::path::Path, name: &str) -> std::path::PathBuf {
+        dir.join(format!("fake_lsp_{}.sh", name))
+    }
+
     /// Stop the server
     pub fn stop(&mut self) {
         let _ = self.stop_tx.send(());
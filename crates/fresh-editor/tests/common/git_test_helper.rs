@@ -269,6 +269,14 @@ A sample project for testing.
         copy_plugin(&plugins_dir, "git_log");
     }
 
+    /// Set up git compare plugin by copying it from the project's plugins directory
+    pub fn setup_git_compare_plugin(&self) {
+        let plugins_dir = self.path.join("plugins");
+        fs::create_dir_all(&plugins_dir).expect("Failed to create plugins directory");
+        copy_plugin_lib(&plugins_dir);
+        copy_plugin(&plugins_dir, "git_compare");
+    }
+
     /// Set up git blame plugin by copying it from the project's plugins directory
     pub fn setup_git_blame_plugin(&self) {
         let plugins_dir = self.path.join("plugins");
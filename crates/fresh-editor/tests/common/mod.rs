@@ -14,9 +14,6 @@ pub mod git_test_helper;
 pub mod harness;
 #[cfg(test)]
 #[allow(dead_code)]
-pub mod scrollbar;
-#[cfg(test)]
-#[allow(dead_code)]
 pub mod tracing;
 #[cfg(test)]
 #[allow(dead_code)]
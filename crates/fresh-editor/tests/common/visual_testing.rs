@@ -1,5 +1,6 @@
 // Visual regression testing utilities
 
+use super::harness::EditorTestHarness;
 use ratatui::buffer::Buffer;
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
@@ -92,6 +93,19 @@ impl VisualFlow {
         Ok(())
     }
 
+    /// Render the harness and capture its screen as a step
+    pub fn capture(
+        &mut self,
+        harness: &mut EditorTestHarness,
+        step_name: &str,
+        description: &str,
+    ) -> anyhow::Result<()> {
+        harness.render()?;
+        let cursor_pos = harness.screen_cursor_position();
+        self.step(harness.buffer(), cursor_pos, step_name, description)?;
+        Ok(())
+    }
+
     /// Finalize the flow and write its documentation file
     pub fn finalize(mut self) {
         self.write_documentation_file().ok();
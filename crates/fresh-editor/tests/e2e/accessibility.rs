@@ -0,0 +1,78 @@
+//! E2E tests for accessibility mode announcements
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+
+/// Accessibility mode on, with speech off so announcements are only recorded
+fn accessibility_harness() -> EditorTestHarness {
+    let mut config = Config::default();
+    config.accessibility.enabled = true;
+    config.accessibility.command = Some(Vec::new());
+    EditorTestHarness::with_config(80, 24, config).unwrap()
+}
+
+fn last_announcement(harness: &EditorTestHarness) -> String {
+    harness
+        .editor()
+        .accessibility_announcements()
+        .pop()
+        .unwrap_or_default()
+}
+
+/// Moving between lines reads the new line; opening the menu bar and moving
+/// through it reads the menu and the highlighted item
+#[test]
+fn test_announces_cursor_lines_and_menu_navigation() {
+    let mut harness = accessibility_harness();
+    let _fixture = harness
+        .load_buffer_from_text("fn main() {\n    let x = 1;\n}\n")
+        .unwrap();
+    harness.render().unwrap();
+    let opened = last_announcement(&harness);
+    assert!(opened.contains("test_buffer.txt"), "{}", opened);
+    assert!(opened.contains("fn main() {"), "{}", opened);
+
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    let moved = last_announcement(&harness);
+    assert!(moved.contains("let x = 1;"), "{}", moved);
+    assert!(!moved.contains("test_buffer.txt"), "{}", moved);
+
+    harness
+        .send_key(KeyCode::F(10), KeyModifiers::NONE)
+        .unwrap();
+    let menu = last_announcement(&harness);
+    assert!(menu.contains("File"), "{}", menu);
+    assert!(menu.contains("New File"), "{}", menu);
+
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    assert_eq!(last_announcement(&harness), "Open File...");
+
+    // Nothing changed, nothing said
+    let count = harness.editor().accessibility_announcements().len();
+    harness.render().unwrap();
+    assert_eq!(harness.editor().accessibility_announcements().len(), count);
+}
+
+/// Without accessibility mode, Where Am I shows the description in the
+/// status bar instead
+#[test]
+fn test_where_am_i_without_accessibility_mode() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    let _fixture = harness.load_buffer_from_text("one\ntwo\n").unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Where Am I").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    assert_eq!(
+        harness.editor().get_status_message().map(String::as_str),
+        Some("test_buffer.txt. Line 2: two. Column 1")
+    );
+    assert!(harness.editor().accessibility_announcements().is_empty());
+}
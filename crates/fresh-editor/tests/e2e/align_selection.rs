@@ -0,0 +1,66 @@
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// Run Align By from the command palette with `delimiter`
+fn align_by(harness: &mut EditorTestHarness, delimiter: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains(">command"))
+        .unwrap();
+    harness.type_text("Align By").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    // Replace the offered delimiter
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(delimiter).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// Selected lines line up on the first `=`
+#[test]
+fn test_align_selection_on_equals() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness
+        .type_text("name = \"fresh\"\nversion = \"0.1\"\nx=1")
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    align_by(&mut harness, "=");
+
+    harness.assert_buffer_content("name    = \"fresh\"\nversion = \"0.1\"\nx       = 1");
+
+    // One undo step
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content("name = \"fresh\"\nversion = \"0.1\"\nx=1");
+}
+
+/// A cursor on each line aligns those lines, and `*` aligns every occurrence
+#[test]
+fn test_align_cursor_lines_on_every_comma() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("a,bb,c\naaa,b,cc\nskip,me").unwrap();
+    harness
+        .send_key(KeyCode::Home, KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Down, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        .unwrap();
+
+    align_by(&mut harness, "*,");
+
+    harness.assert_buffer_content("a,   bb, c\naaa, b,  cc\nskip,me");
+}
@@ -1687,3 +1687,101 @@ fn test_bracket_expansion_jsx_component() {
         content
     );
 }
+
+/// Wrapped call arguments line up with the first argument
+#[test]
+fn test_hanging_indent_after_open_paren() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.py");
+    std::fs::write(&file_path, "result = compute(first,").unwrap();
+
+    let mut harness = harness_with_auto_indent();
+    harness.open_file(&file_path).unwrap();
+
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("second").unwrap();
+    harness.render().unwrap();
+
+    harness.assert_buffer_content("result = compute(first,\n                 second");
+}
+
+/// Indent and dedent patterns from the language config drive auto-indent
+#[test]
+fn test_indent_rules_from_language_config() {
+    use fresh::config::{IndentRulesConfig, LanguageConfig};
+
+    let mut config = Config::default();
+    config.editor.auto_indent = true;
+    config.languages.insert(
+        "ruby".to_string(),
+        LanguageConfig {
+            extensions: vec!["rb".to_string()],
+            grammar: "ruby".to_string(),
+            comment_prefix: Some("#".to_string()),
+            indent_rules: Some(IndentRulesConfig {
+                increase_pattern: Some(r"^\s*(def|if|else|do)\b".to_string()),
+                decrease_pattern: Some(r"^\s*(end|else)\b".to_string()),
+                query: None,
+            }),
+            ..Default::default()
+        },
+    );
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.rb");
+    std::fs::write(&file_path, "def greet").unwrap();
+
+    let mut harness = EditorTestHarness::create(
+        80,
+        24,
+        HarnessOptions::new()
+            .with_config(config)
+            .without_empty_plugins_dir(),
+    )
+    .unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_buffer_content("def greet\n    ");
+
+    // Typing `end` moves it out to the level of `def`
+    harness.type_text("end").unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("def greet\nend");
+
+    // Typing more on the line leaves it where it is
+    harness.type_text(" # done").unwrap();
+    harness.assert_buffer_content("def greet\nend # done");
+}
+
+/// A line moved into a block takes the block's indentation, and loses it
+/// when moved back out
+#[test]
+fn test_move_line_reindents() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.rs");
+    std::fs::write(&file_path, "fn main() {\n}\nlet x = 1;\n").unwrap();
+
+    let mut harness = harness_with_auto_indent();
+    harness.open_file(&file_path).unwrap();
+
+    // Last line, "let x = 1;"
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::Up, KeyModifiers::ALT).unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("fn main() {\n    let x = 1;\n}\n");
+
+    harness.send_key(KeyCode::Down, KeyModifiers::ALT).unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("fn main() {\n}\nlet x = 1;\n");
+}
@@ -0,0 +1,137 @@
+//! E2E tests for auto-save policies (afterDelay, onFocusChange, exclusions)
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::{AutoSaveMode, Config};
+use std::time::Duration;
+
+fn auto_save_config(mode: AutoSaveMode, exclude: &[&str]) -> Config {
+    let mut config = Config::default();
+    config.editor.auto_save = mode;
+    config.editor.auto_save_delay_ms = 1000;
+    config.editor.auto_save_exclude = exclude.iter().map(|s| s.to_string()).collect();
+    config
+}
+
+/// afterDelay saves a buffer once edits pause, and each edit restarts the timer
+#[test]
+fn test_auto_save_after_delay_debounces_edits() {
+    let config = auto_save_config(AutoSaveMode::AfterDelay, &[]);
+    let mut harness = EditorTestHarness::with_temp_project_and_config(120, 24, config).unwrap();
+    let file_path = harness.project_dir().unwrap().join("notes.txt");
+    std::fs::write(&file_path, "hello\n").unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.type_text("a").unwrap();
+    harness.process_async_and_render().unwrap();
+    harness.assert_screen_contains("Auto-save: 1 pending");
+
+    // Keep typing before the delay elapses: the save is pushed back
+    harness.advance_time(Duration::from_millis(700));
+    harness.type_text("b").unwrap();
+    harness.process_async_and_render().unwrap();
+    harness.advance_time(Duration::from_millis(700));
+    harness.process_async_and_render().unwrap();
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "hello\n");
+
+    harness.advance_time(Duration::from_millis(400));
+    harness.process_async_and_render().unwrap();
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "abhello\n");
+    assert!(!harness.editor().active_state().buffer.is_modified());
+    harness.assert_screen_not_contains("Auto-save:");
+}
+
+/// Files matching an exclusion glob are left for an explicit save
+#[test]
+fn test_auto_save_skips_excluded_files() {
+    let config = auto_save_config(AutoSaveMode::AfterDelay, &["*.env"]);
+    let mut harness = EditorTestHarness::with_temp_project_and_config(120, 24, config).unwrap();
+    let file_path = harness.project_dir().unwrap().join("prod.env");
+    std::fs::write(&file_path, "KEY=1\n").unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.type_text("#").unwrap();
+    harness.process_async_and_render().unwrap();
+    harness.assert_screen_not_contains("Auto-save:");
+
+    harness.advance_time(Duration::from_secs(5));
+    harness.process_async_and_render().unwrap();
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "KEY=1\n");
+    assert!(harness.editor().active_state().buffer.is_modified());
+}
+
+/// onFocusChange saves the buffer being left when switching buffers
+#[test]
+fn test_auto_save_on_focus_change() {
+    let config = auto_save_config(AutoSaveMode::OnFocusChange, &[]);
+    let mut harness = EditorTestHarness::with_temp_project_and_config(120, 24, config).unwrap();
+    let project_dir = harness.project_dir().unwrap();
+    let first = project_dir.join("first.txt");
+    let second = project_dir.join("second.txt");
+    std::fs::write(&first, "one\n").unwrap();
+    std::fs::write(&second, "two\n").unwrap();
+    harness.open_file(&first).unwrap();
+    harness.open_file(&second).unwrap();
+
+    harness.type_text("2").unwrap();
+    // No save while staying on the buffer, however long we wait
+    harness.advance_time(Duration::from_secs(5));
+    harness.process_async_and_render().unwrap();
+    assert_eq!(std::fs::read_to_string(&second).unwrap(), "two\n");
+
+    harness
+        .send_key(KeyCode::PageUp, KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&second).unwrap(), "2two\n");
+    assert_eq!(std::fs::read_to_string(&first).unwrap(), "one\n");
+}
+
+/// A file changed on disk is not overwritten by auto-save
+#[test]
+fn test_auto_save_does_not_overwrite_external_changes() {
+    let config = auto_save_config(AutoSaveMode::AfterDelay, &[]);
+    let mut harness = EditorTestHarness::with_temp_project_and_config(120, 24, config).unwrap();
+    let file_path = harness.project_dir().unwrap().join("shared.txt");
+    std::fs::write(&file_path, "base\n").unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.type_text("local ").unwrap();
+    harness.process_async_and_render().unwrap();
+
+    // Another process rewrites the file (mtime granularity can be 1s)
+    std::thread::sleep(Duration::from_millis(1100));
+    std::fs::write(&file_path, "external\n").unwrap();
+
+    harness.advance_time(Duration::from_secs(2));
+    harness.process_async_and_render().unwrap();
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "external\n");
+    harness.assert_screen_contains("Auto-save skipped");
+}
+
+/// An auto-save goes through the same bookkeeping as an explicit save, so
+/// the saved version lands in the local history
+#[test]
+fn test_auto_save_records_local_history() {
+    let config = auto_save_config(AutoSaveMode::AfterDelay, &[]);
+    let mut harness = EditorTestHarness::with_temp_project_and_config(120, 24, config).unwrap();
+    let file_path = harness.project_dir().unwrap().join("notes.txt");
+    std::fs::write(&file_path, "hello\n").unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.type_text("a").unwrap();
+    harness.process_async_and_render().unwrap();
+    harness.advance_time(Duration::from_millis(1100));
+    harness.process_async_and_render().unwrap();
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "ahello\n");
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Local History").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Local history (newest first)");
+    harness.assert_screen_not_contains("No local history");
+}
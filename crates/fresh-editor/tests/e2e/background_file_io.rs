@@ -0,0 +1,143 @@
+//! E2E tests for opening and saving large files in the background
+
+use crate::common::harness::{EditorTestHarness, HarnessOptions};
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use fresh::model::event::Event;
+use fresh::services::fs::SlowFsConfig;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// Files of this size or more are opened and saved in the background
+const THRESHOLD: u64 = 1024;
+
+fn large_content() -> String {
+    (0..200).map(|i| format!("line {:03}\n", i)).collect()
+}
+
+fn harness(dir: &TempDir, slow_fs: Option<SlowFsConfig>) -> EditorTestHarness {
+    let mut config = Config::default();
+    config.editor.large_file_threshold_bytes = THRESHOLD;
+    let mut options = HarnessOptions::new()
+        .with_config(config)
+        .with_working_dir(dir.path().to_path_buf())
+        .with_background_file_io();
+    if let Some(slow_fs) = slow_fs {
+        options = options.with_slow_fs(slow_fs);
+    }
+    EditorTestHarness::create(100, 24, options).unwrap()
+}
+
+/// A large file picked in the file browser is read off the main thread and
+/// shown once loaded
+#[test]
+fn test_large_file_opens_in_background() {
+    let dir = TempDir::new().unwrap();
+    let content = large_content();
+    assert!(content.len() as u64 >= THRESHOLD);
+    std::fs::write(dir.path().join("big.log"), &content).unwrap();
+    let mut harness = harness(&dir, None);
+
+    harness
+        .send_key(KeyCode::Char('o'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("big.log"))
+        .unwrap();
+    harness.type_text("big").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness
+        .wait_until(|h| h.screen_to_string().contains("line 000"))
+        .unwrap();
+    harness.assert_screen_contains("line 010");
+}
+
+/// Typing while a large file is being saved is held back until the write
+/// finishes, so the file gets exactly the content from when the save started
+#[test]
+fn test_edits_during_background_save_are_replayed() {
+    let dir = TempDir::new().unwrap();
+    let file_path = dir.path().join("big.log");
+    let content = large_content();
+    std::fs::write(&file_path, &content).unwrap();
+    let slow_fs = SlowFsConfig {
+        write_file_delay: Duration::from_millis(200),
+        other_delay: Duration::from_millis(50),
+        ..SlowFsConfig::none()
+    };
+    let mut harness = harness(&dir, Some(slow_fs));
+    harness.open_file(&file_path).unwrap();
+
+    harness.type_text("a").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_screen_contains("Saving big.log");
+    harness.type_text("b").unwrap();
+    harness.assert_screen_contains("aline 000");
+    harness.assert_screen_not_contains("abline 000");
+
+    harness
+        .wait_until(|h| h.screen_to_string().contains("abline 000"))
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&file_path).unwrap(),
+        format!("a{}", content)
+    );
+    assert!(harness.editor().active_state().buffer.is_modified());
+}
+
+/// An edit that bypasses the action queue while a large file is saved is
+/// kept on top of the saved file, and regions loaded afterwards come from
+/// the new file rather than from stale offsets into the old one
+#[test]
+fn test_edit_during_background_save_of_unloaded_file() {
+    let dir = TempDir::new().unwrap();
+    let file_path = dir.path().join("big.log");
+    // More than one load chunk, so most of the file stays unloaded
+    let content: String = (0..250_000).map(|i| format!("line {:06}\n", i)).collect();
+    std::fs::write(&file_path, &content).unwrap();
+    let slow_fs = SlowFsConfig {
+        write_file_delay: Duration::from_millis(200),
+        other_delay: Duration::from_millis(50),
+        ..SlowFsConfig::none()
+    };
+    let mut harness = harness(&dir, Some(slow_fs));
+    harness.open_file(&file_path).unwrap();
+
+    harness.type_text("a").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert!(harness.editor().active_state().buffer.is_saving());
+
+    // A plugin or LSP edit at the end of the file, far from anything loaded
+    let cursor_id = harness.editor().active_state().cursors.primary_id();
+    let end = harness.editor().active_state().buffer.len();
+    harness
+        .apply_event(Event::Insert {
+            position: end,
+            text: "tail\n".to_string(),
+            cursor_id,
+        })
+        .unwrap();
+    harness.render().unwrap();
+
+    harness
+        .wait_until(|h| !h.editor().active_state().buffer.is_saving())
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&file_path).unwrap(),
+        format!("a{}", content)
+    );
+    let buffer = &mut harness.editor_mut().active_state_mut().buffer;
+    let len = buffer.len();
+    assert_eq!(
+        String::from_utf8(buffer.get_text_range_mut(0, len).unwrap()).unwrap(),
+        format!("a{}tail\n", content)
+    );
+    assert!(harness.editor().active_state().buffer.is_modified());
+}
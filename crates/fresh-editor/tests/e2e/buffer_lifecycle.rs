@@ -505,6 +505,7 @@ fn test_next_buffer_skips_hidden_buffers() {
         show_cursors: true,
         editing_disabled: true,
         hidden_from_tabs: true, // <-- This makes it hidden
+        uri: None,
         request_id: None,
     };
     harness
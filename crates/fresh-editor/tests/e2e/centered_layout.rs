@@ -0,0 +1,62 @@
+//! E2E tests for the centered layout (`editor.max_content_width`)
+
+use crate::common::harness::EditorTestHarness;
+use fresh::config::Config;
+use tempfile::TempDir;
+
+fn harness_with(width: u16, max_content_width: u16) -> (EditorTestHarness, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    std::fs::write(&file_path, "hello world\nsecond line\n").unwrap();
+
+    let mut config = Config::default();
+    config.editor.max_content_width = max_content_width;
+    let mut harness = EditorTestHarness::with_config(width, 24, config).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+    (harness, temp_dir)
+}
+
+/// A wide pane shows the text in a centered column between compose margins,
+/// and clicks land on the character under the mouse
+#[test]
+fn test_wide_pane_centers_text() {
+    let (mut harness, _temp_dir) = harness_with(120, 40);
+
+    let (x, y) = harness.find_text_on_screen("hello").unwrap();
+    assert!(x > 30, "text should be centered, got x={x}");
+    let margin_bg = harness.editor().theme().compose_margin_bg;
+    assert_eq!(harness.get_cell_style(0, y).unwrap().bg, Some(margin_bg));
+
+    harness.mouse_click(x + 6, y).unwrap();
+    assert_eq!(harness.cursor_position(), 6);
+}
+
+/// Panes narrower than the maximum width keep the usual layout
+#[test]
+fn test_narrow_pane_is_unchanged() {
+    let (centered, _dir) = harness_with(60, 80);
+    let (plain, _plain_dir) = harness_with(60, 0);
+    assert_eq!(
+        centered.find_text_on_screen("hello"),
+        plain.find_text_on_screen("hello")
+    );
+}
+
+/// Each split centers its own column
+#[test]
+fn test_splits_are_centered_separately() {
+    let (mut harness, _temp_dir) = harness_with(160, 40);
+    harness.editor_mut().split_pane_vertical();
+    harness.render().unwrap();
+
+    let (_, y) = harness.find_text_on_screen("hello").unwrap();
+    let row = harness.get_screen_row(y as usize);
+    let columns: Vec<usize> = row
+        .match_indices("hello")
+        .map(|(i, _)| row[..i].chars().count())
+        .collect();
+    assert_eq!(columns.len(), 2, "both splits should show the text: {row}");
+    assert!(columns[0] > 10, "left split not centered: {row}");
+    assert!(columns[1] > 90, "right split not centered: {row}");
+}
@@ -435,7 +435,7 @@ fn test_crlf_cut_paste() {
     );
 }
 
-/// Test that CR characters in LF files are shown as <0D>
+/// Test that CR characters in LF files are shown as ^M
 /// In Unix/LF files, \r is unusual and should be visible - even in \r\n sequences
 #[test]
 fn test_cr_shown_in_lf_file() {
@@ -444,7 +444,7 @@ fn test_cr_shown_in_lf_file() {
 
     // Create a test file with LF line endings but containing CR characters
     // The file has more LF than CRLF, so it should be detected as LF
-    // Even the \r\n sequence should show \r as <0D> because this is a Unix file
+    // Even the \r\n sequence should show \r as ^M because this is a Unix file
     let content = "Line1\nHello\rWorld\nLine3\r\nLine4\n";
     std::fs::write(&file_path, content).unwrap();
 
@@ -454,11 +454,11 @@ fn test_cr_shown_in_lf_file() {
 
     let screen = harness.screen_to_string();
 
-    // Both standalone \r and \r in \r\n should be shown as <0D> in LF files
+    // Both standalone \r and \r in \r\n should be shown as ^M in LF files
     // because any \r is unusual in a Unix file
     assert!(
-        screen.contains("<0D>"),
-        "CR characters in LF file should be shown as <0D>, screen: {}",
+        screen.contains("Hello^MWorld") && screen.contains("Line3^M"),
+        "CR characters in LF file should be shown as ^M, screen: {}",
         screen
     );
 
@@ -0,0 +1,54 @@
+//! E2E tests for digraph input
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+}
+
+/// The two characters after Insert Digraph become the character they name,
+/// in either order
+#[test]
+fn test_insert_digraph() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+
+    harness.type_text("caf").unwrap();
+    run_command(&mut harness, "Insert Digraph");
+    harness.type_text("e'").unwrap();
+    harness.type_text(" ").unwrap();
+    run_command(&mut harness, "Insert Digraph");
+    harness.type_text("uE").unwrap();
+    harness.assert_buffer_content("café €");
+
+    // Typing continues normally afterwards
+    harness.type_text("5").unwrap();
+    harness.assert_buffer_content("café €5");
+}
+
+/// An unknown digraph inserts its second character; Escape cancels
+#[test]
+fn test_insert_digraph_unknown_and_cancel() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+
+    run_command(&mut harness, "Insert Digraph");
+    harness.type_text("qq").unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("q");
+    harness.assert_screen_contains("No digraph for qq");
+
+    run_command(&mut harness, "Insert Digraph");
+    harness.type_text("e").unwrap();
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness.type_text(":").unwrap();
+    harness.assert_buffer_content("q:");
+}
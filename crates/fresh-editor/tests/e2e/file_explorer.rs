@@ -760,6 +760,39 @@ fn test_file_explorer_focus_after_delete() {
     // If we can navigate without error, the focus is correctly on file explorer
 }
 
+/// Ctrl+Z in the file explorer puts the last deleted file back
+#[test]
+fn test_file_explorer_undo_delete() {
+    let mut harness = EditorTestHarness::with_temp_project(120, 40).unwrap();
+    let project_root = harness.project_dir().unwrap();
+    let file_path = project_root.join("file1.txt");
+    fs::write(&file_path, "content 1").unwrap();
+
+    harness.editor_mut().focus_file_explorer();
+    harness.wait_for_file_explorer().unwrap();
+    harness.wait_for_file_explorer_item("file1.txt").unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+
+    harness.editor_mut().file_explorer_delete();
+    harness.type_text("y").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    assert!(!file_path.exists());
+    harness.assert_screen_contains("Undo Delete to restore");
+
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "content 1");
+    harness.assert_screen_contains("Restored file1.txt from the trash");
+    harness.wait_for_file_explorer_item("file1.txt").unwrap();
+}
+
 /// Test Feature 1: Enter key on directory toggles expand/collapse
 #[test]
 fn test_enter_toggles_directory() {
@@ -2687,3 +2720,35 @@ fn test_file_explorer_escape_clears_search() {
         "Should remain in FileExplorer context after second Escape"
     );
 }
+
+/// With animations enabled the explorer collapses over a few frames instead
+/// of disappearing at once
+#[test]
+fn test_file_explorer_collapses_when_animated() {
+    use crate::common::harness::HarnessOptions;
+    use std::time::Duration;
+
+    let mut harness = EditorTestHarness::create(
+        120,
+        40,
+        HarnessOptions::new().with_project_root().with_animations(),
+    )
+    .unwrap();
+    let project_root = harness.project_dir().unwrap();
+    fs::write(project_root.join("animated_file.txt"), "content").unwrap();
+
+    harness.editor_mut().toggle_file_explorer();
+    harness
+        .wait_for_screen_contains("animated_file.txt")
+        .unwrap();
+
+    harness.editor_mut().toggle_file_explorer();
+    harness.render().unwrap();
+    assert!(!harness.editor().file_explorer_visible());
+    harness.assert_screen_contains("animated_file.txt");
+
+    harness.advance_time(Duration::from_millis(500));
+    let _ = harness.editor_mut().process_async_messages();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("animated_file.txt");
+}
@@ -0,0 +1,84 @@
+//! E2E tests for focus mode (compose layout, typewriter scrolling, paragraph dimming)
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use tempfile::TempDir;
+
+/// Focus mode centers the text and dims paragraphs other than the cursor's
+#[test]
+fn test_focus_mode_centers_text_and_dims_other_paragraphs() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    std::fs::write(&file_path, "alpha paragraph\n\nbeta paragraph\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+
+    let source_pos = harness.find_text_on_screen("alpha").unwrap();
+    let (alpha_x, alpha_y) = source_pos;
+    let (beta_x, beta_y) = harness.find_text_on_screen("beta").unwrap();
+    assert_eq!(
+        harness.get_cell_style(alpha_x, alpha_y).unwrap().fg,
+        harness.get_cell_style(beta_x, beta_y).unwrap().fg,
+        "paragraphs should share a color outside focus mode"
+    );
+
+    harness.editor_mut().handle_toggle_focus_mode();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Focus mode on");
+
+    let (alpha_x, alpha_y) = harness.find_text_on_screen("alpha").unwrap();
+    let (beta_x, beta_y) = harness.find_text_on_screen("beta").unwrap();
+    // The ~100-column content area with the default 80-column focus width
+    // leaves a margin of about 10 columns on each side
+    assert!(
+        (9..=10).contains(&alpha_x),
+        "text should be centered at the focus width, got x={alpha_x}"
+    );
+    assert_ne!(
+        harness.get_cell_style(alpha_x, alpha_y).unwrap().fg,
+        harness.get_cell_style(beta_x, beta_y).unwrap().fg,
+        "the paragraph without the cursor should be dimmed"
+    );
+
+    harness.editor_mut().handle_toggle_focus_mode();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Focus mode off");
+    assert_eq!(
+        harness.find_text_on_screen("alpha").unwrap(),
+        source_pos,
+        "layout should be restored after leaving focus mode"
+    );
+}
+
+/// Typewriter scrolling keeps the cursor line in the middle of the view
+#[test]
+fn test_focus_mode_typewriter_scrolling() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("long.txt");
+    let content: String = (1..=200).map(|i| format!("line {i}\n")).collect();
+    std::fs::write(&file_path, content).unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.editor_mut().handle_toggle_focus_mode();
+    harness.render().unwrap();
+
+    for _ in 0..40 {
+        harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    }
+    harness.render().unwrap();
+    let (_, centered_y) = harness.screen_cursor_position();
+
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    let (_, next_y) = harness.screen_cursor_position();
+
+    assert_eq!(centered_y, next_y, "cursor row should stay fixed");
+    let screen_mid = harness.terminal_height() as u16 / 2;
+    assert!(
+        centered_y.abs_diff(screen_mid) <= 2,
+        "cursor row {centered_y} should be near the middle ({screen_mid})"
+    );
+}
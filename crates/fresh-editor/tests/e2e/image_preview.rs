@@ -0,0 +1,66 @@
+//! E2E tests for image previews
+//!
+//! The test terminal has no graphics protocol, so previews show the image
+//! info only.
+
+use crate::common::harness::EditorTestHarness;
+use std::fs;
+
+const SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20"><rect width="40" height="20"/></svg>"#;
+
+/// A PNG opens as a preview with its format and dimensions instead of bytes
+#[test]
+fn test_png_opens_as_preview() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 24).unwrap();
+    let file = harness.project_dir().unwrap().join("photo.png");
+    image::RgbaImage::new(30, 20).save(&file).unwrap();
+    harness.open_file(&file).unwrap();
+    harness.render().unwrap();
+
+    let buffer_id = harness.editor().active_buffer();
+    assert!(harness.editor().has_image_preview(buffer_id));
+    harness.assert_screen_contains("PNG · 30 × 20");
+    harness.assert_screen_contains("[image preview]");
+    harness.assert_screen_not_contains("IHDR");
+
+    harness.editor_mut().toggle_image_preview();
+    harness.render().unwrap();
+    assert!(!harness.editor().has_image_preview(buffer_id));
+    harness.assert_screen_not_contains("PNG · 30 × 20");
+}
+
+/// An SVG opens as text; the preview is shown on request and blocks typing
+#[test]
+fn test_svg_preview_on_request() {
+    let mut harness = EditorTestHarness::with_temp_project(120, 24).unwrap();
+    let file = harness.project_dir().unwrap().join("logo.svg");
+    fs::write(&file, SVG).unwrap();
+    harness.open_file(&file).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("<svg");
+
+    harness.editor_mut().toggle_image_preview();
+    harness.render().unwrap();
+    harness.assert_screen_contains("SVG · 40 × 20");
+    harness.assert_screen_not_contains("<svg");
+
+    harness.type_text("x").unwrap();
+    harness.editor_mut().toggle_image_preview();
+    harness.render().unwrap();
+    assert_eq!(harness.get_buffer_content().unwrap(), SVG);
+    assert!(!harness.editor().is_editing_disabled());
+}
+
+/// Toggling a file that isn't an image leaves it alone
+#[test]
+fn test_toggle_on_text_file() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 24).unwrap();
+    let file = harness.project_dir().unwrap().join("notes.txt");
+    fs::write(&file, "hello").unwrap();
+    harness.open_file(&file).unwrap();
+
+    harness.editor_mut().toggle_image_preview();
+    harness.render().unwrap();
+    harness.assert_screen_contains("hello");
+    harness.assert_screen_contains("Not a PNG, JPEG or SVG image");
+}
@@ -0,0 +1,158 @@
+//! Tests for indentation detection on open and the indentation commands:
+//! - Convert Indentation to Spaces / Tabs
+//! - Reindent Buffer
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use tempfile::TempDir;
+
+/// Helper to run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, command_name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.type_text(command_name).unwrap();
+    harness.render().unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// Open `content` as `name` in a fresh harness
+fn open(name: &str, content: &str, config: Config) -> (TempDir, EditorTestHarness) {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join(name);
+    std::fs::write(&file_path, content).unwrap();
+    let mut harness = EditorTestHarness::with_config(160, 24, config).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+    (temp_dir, harness)
+}
+
+/// A file indented with two spaces indents with two spaces, and says so
+#[test]
+fn test_detects_two_space_indentation() {
+    let (_dir, mut harness) = open(
+        "test.rs",
+        "fn main() {\n  if x {\n    y();\n  }\n}\n",
+        Config::default(),
+    );
+    harness.assert_screen_contains("Spaces: 2");
+
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    let content = harness.get_buffer_content().unwrap();
+    assert!(
+        content.ends_with("}\n  "),
+        "Tab should insert the detected two spaces. Got: {:?}",
+        content
+    );
+}
+
+/// A Rust file indented with tabs indents with tabs
+#[test]
+fn test_detects_tab_indentation() {
+    let (_dir, mut harness) = open(
+        "test.rs",
+        "fn main() {\n\tif x {\n\t\ty();\n\t}\n}\n",
+        Config::default(),
+    );
+    harness.assert_screen_contains("Tabs: 4");
+
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    let content = harness.get_buffer_content().unwrap();
+    assert!(
+        content.ends_with("}\n\t"),
+        "Tab should insert a tab in a tab-indented file. Got: {:?}",
+        content
+    );
+}
+
+/// With detection off the configured indentation is used
+#[test]
+fn test_detection_disabled() {
+    let mut config = Config::default();
+    config.editor.detect_indentation = false;
+    let (_dir, harness) = open("test.rs", "fn main() {\n  x();\n}\n", config);
+    harness.assert_screen_contains("Spaces: 4");
+}
+
+/// Converting to tabs turns each level into a tab and keeps alignment
+#[test]
+fn test_convert_indentation_to_tabs() {
+    let (_dir, mut harness) = open(
+        "test.rs",
+        "fn main() {\n    let a = 1;\n    call(a,\n         b);\n    /*\n     * note\n     */\n}\n",
+        Config::default(),
+    );
+
+    run_command(&mut harness, "Convert Indentation to Tabs");
+
+    let content = harness.get_buffer_content().unwrap();
+    assert_eq!(
+        content,
+        "fn main() {\n\tlet a = 1;\n\tcall(a,\n\t\t b);\n\t/*\n     * note\n     */\n}\n"
+    );
+    harness.assert_screen_contains("Tabs: 4");
+
+    // The conversion is a single undo step
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    let content = harness.get_buffer_content().unwrap();
+    assert_eq!(
+        content,
+        "fn main() {\n    let a = 1;\n    call(a,\n         b);\n    /*\n     * note\n     */\n}\n"
+    );
+}
+
+/// Converting to spaces expands leading tabs
+#[test]
+fn test_convert_indentation_to_spaces() {
+    let (_dir, mut harness) = open(
+        "test.rs",
+        "fn main() {\n\tif x {\n\t\ty();\n\t}\n}\n",
+        Config::default(),
+    );
+
+    run_command(&mut harness, "Convert Indentation to Spaces");
+
+    let content = harness.get_buffer_content().unwrap();
+    assert_eq!(content, "fn main() {\n    if x {\n        y();\n    }\n}\n");
+    harness.assert_screen_contains("Spaces: 4");
+}
+
+/// Reindenting changes every level to the width entered
+#[test]
+fn test_reindent_buffer() {
+    let (_dir, mut harness) = open(
+        "test.py",
+        "def f():\n  if x:\n    s = \"\"\"\n  keep\n\"\"\"\n  return s\n",
+        Config::default(),
+    );
+
+    run_command(&mut harness, "Reindent Buffer");
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("4").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    let content = harness.get_buffer_content().unwrap();
+    assert_eq!(
+        content,
+        "def f():\n    if x:\n        s = \"\"\"\n  keep\n\"\"\"\n    return s\n"
+    );
+    harness.assert_screen_contains("Spaces: 4");
+}
@@ -295,6 +295,51 @@ fn test_source_filter_cycle() {
     );
 }
 
+/// Test that actions without a binding are listed and can be bound
+#[test]
+fn test_bind_unbound_action() {
+    let mut harness = EditorTestHarness::new(120, 40).unwrap();
+    open_keybinding_editor(&mut harness);
+
+    // All -> Custom -> Keymap -> Unbound
+    for _ in 0..3 {
+        harness
+            .send_key(KeyCode::Char('s'), KeyModifiers::NONE)
+            .unwrap();
+    }
+    harness.render().unwrap();
+    harness.assert_screen_contains("[Unbound]");
+    harness.assert_screen_contains("unbound");
+
+    // Enter opens the edit dialog with the action filled in
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Edit Keybinding");
+
+    harness
+        .send_key(
+            KeyCode::Char('u'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+        )
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Ctrl+Alt+U");
+
+    // Tab through action and context to the Save button
+    for _ in 0..3 {
+        harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    }
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_not_contains("Edit Keybinding");
+    harness.assert_screen_contains("modified");
+}
+
 // ========================
 // Help overlay
 // ========================
@@ -147,13 +147,14 @@ impl FileSystem for NotOwnerFileSystem {
 
     fn sudo_write(
         &self,
+        program: &str,
         path: &Path,
         data: &[u8],
         mode: u32,
         uid: u32,
         gid: u32,
     ) -> io::Result<()> {
-        self.inner.sudo_write(path, data, mode, uid, gid)
+        self.inner.sudo_write(program, path, data, mode, uid, gid)
     }
 }
 
@@ -482,13 +483,14 @@ impl FileSystem for CrashDuringStreamFileSystem {
 
     fn sudo_write(
         &self,
+        program: &str,
         path: &Path,
         data: &[u8],
         mode: u32,
         uid: u32,
         gid: u32,
     ) -> io::Result<()> {
-        self.inner.sudo_write(path, data, mode, uid, gid)
+        self.inner.sudo_write(program, path, data, mode, uid, gid)
     }
 }
 
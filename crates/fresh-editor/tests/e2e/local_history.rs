@@ -0,0 +1,103 @@
+//! E2E tests for local history of saved files
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+fn save(harness: &mut EditorTestHarness) {
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.process_async_and_render().unwrap();
+}
+
+/// Each save adds a version; restoring an older one replaces the buffer
+#[test]
+fn test_restore_older_version() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 30).unwrap();
+    let file_path = harness.project_dir().unwrap().join("notes.txt");
+    std::fs::write(&file_path, "").unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.type_text("first draft").unwrap();
+    save(&mut harness);
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(" and more").unwrap();
+    save(&mut harness);
+
+    run_command(&mut harness, "Local History");
+    harness.assert_screen_contains("Local history (newest first)");
+
+    // Newest first: move to the older version, then to "Restore this version"
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Compare with current");
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    assert_eq!(harness.get_buffer_content().unwrap(), "first draft");
+    harness.assert_screen_contains("Restored version saved");
+    // Only the buffer changed; the file keeps the newest save until saved again
+    assert_eq!(
+        std::fs::read_to_string(&file_path).unwrap(),
+        "first draft and more"
+    );
+}
+
+/// Comparing opens a diff from the version to the buffer
+#[test]
+fn test_compare_with_current() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 30).unwrap();
+    let file_path = harness.project_dir().unwrap().join("notes.txt");
+    std::fs::write(&file_path, "").unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.type_text("old line").unwrap();
+    save(&mut harness);
+    harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::End, KeyModifiers::SHIFT).unwrap();
+    harness.type_text("new line").unwrap();
+
+    run_command(&mut harness, "Local History");
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("*History: notes.txt @");
+    harness.assert_screen_contains("-old line");
+    harness.assert_screen_contains("+new line");
+}
+
+/// A file that was never saved has no versions
+#[test]
+fn test_no_history_for_unsaved_file() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 30).unwrap();
+    let file_path = harness.project_dir().unwrap().join("notes.txt");
+    std::fs::write(&file_path, "text\n").unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    run_command(&mut harness, "Local History");
+    harness.assert_screen_contains("No local history for notes.txt");
+}
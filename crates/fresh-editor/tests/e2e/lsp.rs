@@ -3270,6 +3270,7 @@ fn test_pull_diagnostics_message_handling() -> anyhow::Result<()> {
     if let Some(bridge) = harness.editor().async_bridge() {
         let _ = bridge.sender().send(AsyncMessage::LspPulledDiagnostics {
             request_id: 1,
+            language: "rust".to_string(),
             uri: uri.as_str().to_string(),
             result_id: Some("test-result-id-123".to_string()),
             diagnostics: vec![diagnostic],
@@ -3316,6 +3317,7 @@ fn test_pull_diagnostics_unchanged_response() -> anyhow::Result<()> {
     if let Some(bridge) = harness.editor().async_bridge() {
         let _ = bridge.sender().send(AsyncMessage::LspPulledDiagnostics {
             request_id: 2,
+            language: "rust".to_string(),
             uri: uri.as_str().to_string(),
             result_id: Some("test-result-id-456".to_string()),
             diagnostics: Vec::new(), // Empty when unchanged
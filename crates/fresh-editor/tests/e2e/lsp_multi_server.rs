@@ -0,0 +1,198 @@
+//! E2E tests for languages served by more than one LSP server
+//!
+//! Each test runs two named fake servers for Rust: `alpha` is the language's
+//! own server and `beta` is listed in `lsp_servers`.
+
+use crate::common::fake_lsp::FakeLspServer;
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::model::event::Event;
+use std::path::{Path, PathBuf};
+
+fn server_config(dir: &Path, name: &str) -> fresh::services::lsp::LspServerConfig {
+    fresh::services::lsp::LspServerConfig {
+        command: FakeLspServer::named_script_path(dir, name)
+            .to_string_lossy()
+            .to_string(),
+        args: vec![log_path(dir, name).to_string_lossy().to_string()],
+        enabled: true,
+        auto_start: true,
+        process_limits: fresh::services::process_limits::ProcessLimits::default(),
+        initialization_options: None,
+    }
+}
+
+fn log_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.log", name))
+}
+
+fn logged_methods(dir: &Path, name: &str) -> Vec<String> {
+    std::fs::read_to_string(log_path(dir, name))
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Open a Rust file served by `alpha` (no hover) and `beta` (hover), and wait
+/// until both servers have seen it.
+fn open_with_two_servers(
+    dir: &Path,
+) -> anyhow::Result<(EditorTestHarness, FakeLspServer, FakeLspServer)> {
+    let alpha = FakeLspServer::spawn_named(dir, "alpha", false)?;
+    let beta = FakeLspServer::spawn_named(dir, "beta", true)?;
+
+    let test_file = dir.join("test.rs");
+    std::fs::write(&test_file, "fn main() {\n    let x = 5;\n}\n")?;
+
+    let mut config = fresh::config::Config::default();
+    config
+        .lsp
+        .insert("rust".to_string(), server_config(dir, "alpha"));
+    config
+        .lsp
+        .insert("beta".to_string(), server_config(dir, "beta"));
+    config.languages.get_mut("rust").unwrap().lsp_servers = vec!["beta".to_string()];
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 30, config, dir.to_path_buf())?;
+    harness.open_file(&test_file)?;
+    harness.render()?;
+
+    harness.wait_until(|_| {
+        ["alpha", "beta"].iter().all(|name| {
+            logged_methods(dir, name)
+                .iter()
+                .any(|m| m == "textDocument/didOpen")
+        })
+    })?;
+
+    Ok((harness, alpha, beta))
+}
+
+/// Diagnostics currently stored for the only open file
+fn stored_diagnostics(harness: &EditorTestHarness) -> Vec<lsp_types::Diagnostic> {
+    harness
+        .editor()
+        .get_stored_diagnostics()
+        .values()
+        .next()
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Insert text at the start of the buffer as a single change
+fn insert_at_start(harness: &mut EditorTestHarness, text: &str) -> anyhow::Result<()> {
+    let cursor_id = harness.editor().active_state().cursors.primary_id();
+    harness.apply_event(Event::Insert {
+        position: 0,
+        text: text.to_string(),
+        cursor_id,
+    })?;
+    harness.render()
+}
+
+/// Hover goes to the additional server, since the primary doesn't provide it
+#[test]
+#[cfg_attr(target_os = "windows", ignore)] // Uses Bash-based fake LSP server
+fn test_request_goes_to_server_with_capability() -> anyhow::Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let dir = temp_dir.path();
+    let (mut harness, _alpha, _beta) = open_with_two_servers(dir)?;
+
+    harness.send_key(KeyCode::Char('k'), KeyModifiers::ALT)?;
+    harness.wait_until(|h| h.screen_to_string().contains("Hover from beta"))?;
+
+    assert!(logged_methods(dir, "beta").contains(&"textDocument/hover".to_string()));
+    assert!(
+        !logged_methods(dir, "alpha").contains(&"textDocument/hover".to_string()),
+        "hover must not be sent to a server without hoverProvider"
+    );
+
+    Ok(())
+}
+
+/// Diagnostics from both servers are shown together, labelled by server
+#[test]
+#[cfg_attr(target_os = "windows", ignore)] // Uses Bash-based fake LSP server
+fn test_diagnostics_from_two_servers_are_merged() -> anyhow::Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let dir = temp_dir.path();
+    let (mut harness, _alpha, _beta) = open_with_two_servers(dir)?;
+
+    harness.wait_until(|h| stored_diagnostics(h).len() == 2)?;
+
+    let mut diagnostics: Vec<(String, Option<String>)> = stored_diagnostics(&harness)
+        .into_iter()
+        .map(|d| (d.message, d.source))
+        .collect();
+    diagnostics.sort();
+    assert_eq!(
+        diagnostics,
+        vec![
+            (
+                "alpha problem".to_string(),
+                Some("fake_lsp_alpha.sh".to_string())
+            ),
+            (
+                "beta problem".to_string(),
+                Some("fake_lsp_beta.sh".to_string())
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+/// A server clearing its diagnostics leaves the other server's in place
+#[test]
+#[cfg_attr(target_os = "windows", ignore)] // Uses Bash-based fake LSP server
+fn test_clearing_one_server_keeps_other_diagnostics() -> anyhow::Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let dir = temp_dir.path();
+    let (mut harness, _alpha, _beta) = open_with_two_servers(dir)?;
+    harness.wait_until(|h| stored_diagnostics(h).len() == 2)?;
+
+    // beta publishes an empty list for this change, alpha republishes its own
+    insert_at_start(&mut harness, "// fixed_beta\n")?;
+    harness.wait_until(|h| stored_diagnostics(h).len() == 1)?;
+
+    let diagnostics = stored_diagnostics(&harness);
+    assert_eq!(diagnostics[0].message, "alpha problem");
+    // Only one server reports now, so no label is added
+    assert_eq!(diagnostics[0].source, None);
+
+    Ok(())
+}
+
+/// didOpen and didChange reach the additional server, not just the primary
+#[test]
+#[cfg_attr(target_os = "windows", ignore)] // Uses Bash-based fake LSP server
+fn test_additional_server_receives_document_sync() -> anyhow::Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let dir = temp_dir.path();
+    let (mut harness, _alpha, _beta) = open_with_two_servers(dir)?;
+
+    insert_at_start(&mut harness, "// edit\n")?;
+    harness.wait_until(|_| {
+        ["alpha", "beta"].iter().all(|name| {
+            logged_methods(dir, name)
+                .iter()
+                .any(|m| m == "textDocument/didChange")
+        })
+    })?;
+
+    for name in ["alpha", "beta"] {
+        let methods = logged_methods(dir, name);
+        let did_open = methods.iter().position(|m| m == "textDocument/didOpen");
+        let did_change = methods.iter().position(|m| m == "textDocument/didChange");
+        assert!(
+            did_open.is_some() && did_open < did_change,
+            "{} should get didOpen before didChange, got {:?}",
+            name,
+            methods
+        );
+    }
+
+    Ok(())
+}
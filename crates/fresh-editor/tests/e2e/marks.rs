@@ -0,0 +1,125 @@
+//! E2E tests for named marks (a-z per buffer, A-Z across files)
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use tempfile::TempDir;
+
+/// Run a command from the command palette that asks for a mark
+fn run_mark_command(harness: &mut EditorTestHarness, command: &str, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.type_text(command).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// A buffer mark set from the command palette follows text inserted above it
+#[test]
+fn test_mark_follows_edits() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("one\n  two\nthree").unwrap();
+    harness.send_key(KeyCode::Up, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+    let marked = harness.cursor_position();
+    run_mark_command(&mut harness, "Set Named Mark", "a");
+    harness.assert_screen_contains("Mark 'a' set");
+
+    harness
+        .send_key(KeyCode::Home, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("zero\n").unwrap();
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+
+    run_mark_command(&mut harness, "Jump to Mark", "a");
+    assert_eq!(harness.cursor_position(), marked + "zero\n".len());
+
+    // The line variant goes to the first non-blank of the line
+    harness.editor_mut().jump_to_mark('a', true);
+    assert_eq!(harness.cursor_position(), "zero\none\n  ".len());
+}
+
+/// Lowercase marks belong to their buffer; uppercase marks open their file
+#[test]
+fn test_file_mark_opens_its_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let a = temp_dir.path().join("a.txt");
+    let b = temp_dir.path().join("b.txt");
+    std::fs::write(&a, "alpha\nbeta\n").unwrap();
+    std::fs::write(&b, "gamma\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.open_file(&a).unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.editor_mut().set_mark('q');
+    harness.editor_mut().set_mark('Q');
+
+    harness.open_file(&b).unwrap();
+    harness.editor_mut().jump_to_mark('q', false);
+    harness.render().unwrap();
+    harness.assert_screen_contains("Mark 'q' not set");
+    harness.assert_buffer_content("gamma\n");
+
+    harness.editor_mut().jump_to_mark('Q', false);
+    harness.assert_buffer_content("alpha\nbeta\n");
+    assert_eq!(harness.cursor_position(), "alpha\n".len());
+
+    // Closing the file keeps the file mark, which reopens it
+    let a_buffer = harness.editor().active_buffer();
+    harness.editor_mut().close_buffer(a_buffer).unwrap();
+    harness.assert_buffer_content("gamma\n");
+    harness.editor_mut().jump_to_mark('Q', false);
+    harness.assert_buffer_content("alpha\nbeta\n");
+    assert_eq!(harness.cursor_position(), "alpha\n".len());
+}
+
+/// File marks are saved with the workspace
+#[test]
+fn test_session_restores_file_marks() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir(&project_dir).unwrap();
+    let a = project_dir.join("a.txt");
+    let b = project_dir.join("b.txt");
+    std::fs::write(&a, "alpha\nbeta\n").unwrap();
+    std::fs::write(&b, "gamma\n").unwrap();
+
+    {
+        let mut harness = EditorTestHarness::with_config_and_working_dir(
+            80,
+            24,
+            Config::default(),
+            project_dir.clone(),
+        )
+        .unwrap();
+        harness.open_file(&a).unwrap();
+        harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+        harness.editor_mut().set_mark('M');
+        harness.open_file(&b).unwrap();
+        harness.editor_mut().save_workspace().unwrap();
+    }
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        80,
+        24,
+        Config::default(),
+        project_dir.clone(),
+    )
+    .unwrap();
+    harness.editor_mut().try_restore_workspace().unwrap();
+    harness.assert_buffer_content("gamma\n");
+    harness.editor_mut().jump_to_mark('M', false);
+    harness.assert_buffer_content("alpha\nbeta\n");
+    assert_eq!(harness.cursor_position(), "alpha\n".len());
+}
@@ -0,0 +1,56 @@
+//! E2E tests for the memory report and the memory use warning
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use std::time::Duration;
+
+/// The report lists open buffers and every subsystem with a total
+#[test]
+fn test_memory_report_lists_buffers_and_subsystems() {
+    let mut harness = EditorTestHarness::with_temp_project(120, 40).unwrap();
+    let file_path = harness.project_dir().unwrap().join("notes.txt");
+    std::fs::write(&file_path, "hello\n".repeat(1000)).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Memory Report").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("*Memory Report*");
+    harness.assert_screen_contains("notes.txt");
+    for row in [
+        "Buffer text",
+        "Undo history",
+        "Syntax highlighting caches",
+        "Plugin runtime",
+        "Workspace index",
+        "Total",
+    ] {
+        harness.assert_screen_contains(row);
+    }
+}
+
+/// Going above `memory_warning_threshold_mb` shows a warning at the next check
+#[test]
+fn test_memory_warning_shown_above_threshold() {
+    let mut config = Config::default();
+    config.editor.memory_warning_threshold_mb = 1;
+    config.editor.large_file_threshold_bytes = 100 * 1024 * 1024;
+    let mut harness = EditorTestHarness::with_temp_project_and_config(160, 24, config).unwrap();
+    let file_path = harness.project_dir().unwrap().join("big.txt");
+    std::fs::write(&file_path, "0123456789abcdef\n".repeat(128 * 1024)).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.process_async_and_render().unwrap();
+    harness.assert_screen_not_contains("warning threshold");
+
+    harness.advance_time(Duration::from_secs(31));
+    harness.process_async_and_render().unwrap();
+    harness.assert_screen_contains("above the 1 MB warning threshold");
+}
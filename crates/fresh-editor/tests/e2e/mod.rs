@@ -1,15 +1,21 @@
+pub mod accessibility;
+pub mod align_selection;
 pub mod ansi_cursor;
 pub mod auto_indent;
 pub mod auto_revert;
+pub mod auto_save;
+pub mod background_file_io;
 pub mod basic;
 pub mod binary_file;
 pub mod block_selection;
 pub mod buffer_lifecycle;
 pub mod buffer_settings_commands;
 pub mod case_conversion;
+pub mod centered_layout;
 pub mod command_palette;
 pub mod crash_repro;
 pub mod crlf_rendering;
+pub mod digraphs;
 pub mod document_model;
 pub mod duplicate_line;
 pub mod emacs_actions;
@@ -18,7 +24,10 @@ pub mod explorer_menu;
 pub mod file_browser;
 pub mod file_explorer;
 pub mod file_permissions;
+pub mod focus_mode;
+pub mod image_preview;
 pub mod indent_dedent;
+pub mod indentation_detection;
 pub mod keybinding_editor;
 pub mod language_features_e2e;
 pub mod large_file_inplace_write_bug;
@@ -27,13 +36,17 @@ pub mod lifecycle;
 pub mod line_wrap_scroll_bugs;
 pub mod line_wrapping;
 pub mod live_grep;
+pub mod local_history;
 pub mod locale;
 pub mod lsp;
 pub mod lsp_config;
+pub mod lsp_multi_server;
 pub mod lsp_order;
 pub mod macros;
 pub mod margin;
 pub mod markdown_compose;
+pub mod marks;
+pub mod memory_report;
 pub mod menu_bar;
 pub mod merge_conflict;
 pub mod mouse;
@@ -43,6 +56,7 @@ pub mod multibyte_characters;
 pub mod multicursor;
 pub mod on_save_actions;
 pub mod open_folder;
+pub mod open_link;
 pub mod paste;
 #[cfg(feature = "plugins")]
 pub mod plugins;
@@ -53,9 +67,14 @@ pub mod position_history_debug;
 pub mod position_history_truncate_debug;
 pub mod prompt;
 pub mod prompt_editing;
+pub mod quickfix;
+pub mod read_only_preview;
 pub mod recovery;
 pub mod remote_fs_test;
+pub mod remote_reconnect;
+pub mod render_whitespace;
 pub mod rendering;
+pub mod rulers;
 pub mod save_as_language_detection;
 pub mod scroll_clearing;
 pub mod scrolling;
@@ -72,6 +91,7 @@ pub mod sort_lines;
 pub mod split_tabs;
 pub mod split_view;
 pub mod split_view_expectations;
+pub mod status_line;
 pub mod stdin_input;
 pub mod sudo_save_prompt;
 #[cfg(unix)]
@@ -79,7 +99,9 @@ pub mod symlinks;
 pub mod tab_config;
 pub mod tab_drag;
 pub mod tab_indent_selection;
+pub mod tab_pinning;
 pub mod tab_scrolling;
+pub mod telemetry_report;
 pub mod terminal;
 pub mod terminal_close;
 pub mod terminal_resize;
@@ -97,4 +119,6 @@ pub mod vi_mode;
 pub mod virtual_lines;
 pub mod visual_regression;
 pub mod warning_indicators;
+pub mod watch_task;
+pub mod word_completion;
 pub mod workspace;
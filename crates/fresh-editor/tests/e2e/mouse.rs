@@ -2060,3 +2060,196 @@ fn test_hover_popup_position_with_file_explorer() {
         screen
     );
 }
+
+/// Test that Ctrl+click adds a cursor and Ctrl+click on it again removes it
+#[test]
+fn test_ctrl_click_toggles_cursor() {
+    use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    let double_click_delay =
+        std::time::Duration::from_millis(harness.config().editor.double_click_time_ms * 2);
+
+    let content = "first line\nsecond line\nthird line\n";
+    let _fixture = harness.load_buffer_from_text(content).unwrap();
+    harness.render().unwrap();
+
+    let (content_first_row, _) = harness.content_area_rows();
+    let row = content_first_row as u16;
+    harness.mouse_click(8, row).unwrap();
+
+    let ctrl_click = |harness: &mut EditorTestHarness, col: u16, row: u16| {
+        for kind in [
+            MouseEventKind::Down(MouseButton::Left),
+            MouseEventKind::Up(MouseButton::Left),
+        ] {
+            harness
+                .send_mouse(MouseEvent {
+                    kind,
+                    column: col,
+                    row,
+                    modifiers: KeyModifiers::CONTROL,
+                })
+                .unwrap();
+        }
+        harness.render().unwrap();
+    };
+
+    ctrl_click(&mut harness, 10, row + 2);
+    assert_eq!(harness.cursor_count(), 2, "Ctrl+click should add a cursor");
+    assert!(
+        !harness.has_selection(),
+        "Ctrl+click should not extend the selection"
+    );
+
+    harness.sleep(double_click_delay);
+    ctrl_click(&mut harness, 10, row + 2);
+    assert_eq!(
+        harness.cursor_count(),
+        1,
+        "Ctrl+click on an existing cursor should remove it"
+    );
+}
+
+/// Test that dragging a selection below the viewport scrolls the buffer
+#[test]
+fn test_drag_selection_auto_scrolls() {
+    use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    let content: String = (1..=200).map(|i| format!("line {}\n", i)).collect();
+    let _fixture = harness.load_buffer_from_text(&content).unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.top_line_number(), 0);
+
+    let (content_first_row, content_last_row) = harness.content_area_rows();
+    let send = |harness: &mut EditorTestHarness, kind: MouseEventKind, row: u16| {
+        harness
+            .send_mouse(MouseEvent {
+                kind,
+                column: 10,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+            .unwrap();
+        harness.render().unwrap();
+    };
+
+    send(
+        &mut harness,
+        MouseEventKind::Down(MouseButton::Left),
+        content_first_row as u16,
+    );
+    // Hold the mouse two rows below the content area for a few events
+    for _ in 0..5 {
+        send(
+            &mut harness,
+            MouseEventKind::Drag(MouseButton::Left),
+            content_last_row as u16 + 2,
+        );
+    }
+    send(
+        &mut harness,
+        MouseEventKind::Up(MouseButton::Left),
+        content_last_row as u16 + 2,
+    );
+
+    assert!(
+        harness.top_line_number() >= 10,
+        "Dragging past the bottom edge should scroll (top line {})",
+        harness.top_line_number()
+    );
+    let range = harness.get_selection_range().expect("selection");
+    assert!(
+        range.start < "line 1\n".len(),
+        "Selection should stay anchored on the first line"
+    );
+    assert!(
+        harness.cursor_position() > harness.top_byte(),
+        "Selection end should follow the scrolled viewport"
+    );
+}
+
+/// Test configurable wheel scroll distance and acceleration
+#[test]
+fn test_mouse_scroll_lines_and_acceleration() {
+    let content: String = (1..=200).map(|i| format!("line {}\n", i)).collect();
+
+    let mut config = fresh::config::Config::default();
+    config.editor.mouse_scroll_lines = 5;
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    let _fixture = harness.load_buffer_from_text(&content).unwrap();
+    harness.render().unwrap();
+    let (content_first_row, _) = harness.content_area_rows();
+    let row = content_first_row as u16 + 1;
+
+    harness.mouse_scroll_down(20, row).unwrap();
+    assert_eq!(harness.top_line_number(), 5);
+
+    // With acceleration, rapid notches scroll further than the base distance
+    let mut config = fresh::config::Config::default();
+    config.editor.mouse_scroll_lines = 1;
+    config.editor.mouse_scroll_acceleration = true;
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    let _fixture = harness.load_buffer_from_text(&content).unwrap();
+    harness.render().unwrap();
+
+    for _ in 0..6 {
+        harness.mouse_scroll_down(20, row).unwrap();
+    }
+    assert!(
+        harness.top_line_number() > 6,
+        "Accelerated scrolling should exceed one line per notch (top line {})",
+        harness.top_line_number()
+    );
+}
+
+/// Test that search matches are marked on the scrollbar and clicking a mark
+/// jumps to the match
+#[test]
+fn test_scrollbar_marks_search_matches() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+
+    let content: String = (1..=100)
+        .map(|i| {
+            if i == 80 {
+                "the needle is here\n".to_string()
+            } else {
+                format!("Line {i} content here\n")
+            }
+        })
+        .collect();
+    let _fixture = harness.load_buffer_from_text(&content).unwrap();
+    harness.render().unwrap();
+
+    let mark_rows = |harness: &EditorTestHarness| -> Vec<u16> {
+        (0..24)
+            .filter(|&row| harness.get_cell(79, row).as_deref() == Some("━"))
+            .collect()
+    };
+    assert!(mark_rows(&harness).is_empty(), "No marks before searching");
+
+    harness
+        .send_key(KeyCode::Char('f'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("needle").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    // Return to the top so the match is off screen
+    harness
+        .send_key(KeyCode::Home, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    assert!(!harness.screen_to_string().contains("needle"));
+
+    let rows = mark_rows(&harness);
+    assert_eq!(rows.len(), 1, "Expected one search mark, got {rows:?}");
+
+    harness.mouse_click(79, rows[0]).unwrap();
+    harness.render().unwrap();
+
+    let needle_pos = content.find("needle").unwrap();
+    assert_eq!(harness.cursor_position(), needle_pos);
+    harness.assert_screen_contains("the needle is here");
+}
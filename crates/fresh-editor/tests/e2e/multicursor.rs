@@ -191,6 +191,65 @@ fn test_add_cursor_next_match() {
     assert_eq!(cursors.iter().count(), 3);
 }
 
+/// Test Select All Occurrences and undoing it in one step
+#[test]
+fn test_select_all_occurrences_undo() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+
+    harness.type_text("foo bar Foo baz foo").unwrap();
+    harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+
+    // With no selection, the word at the cursor and its occurrences are
+    // selected; the search is case-sensitive by default
+    harness.editor_mut().select_all_matches();
+    harness.render().unwrap();
+    let cursors = &harness.editor().active_state().cursors;
+    let mut ranges: Vec<_> = cursors.iter().map(|(_, c)| c.selection_range()).collect();
+    ranges.sort_by_key(|r| r.clone().map(|r| r.start));
+    assert_eq!(ranges, vec![Some(0..3), Some(16..19)]);
+
+    // A single undo removes the cursors and the word selection
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    let cursors = &harness.editor().active_state().cursors;
+    assert_eq!(cursors.count(), 1);
+    assert_eq!(cursors.primary().anchor, None);
+}
+
+/// Test that Skip Occurrence moves the last added cursor instead of adding one
+#[test]
+fn test_skip_occurrence() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+
+    harness.type_text("foo foo foo").unwrap();
+    harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+
+    // Select the word, then add a cursor at the second "foo"
+    harness.editor_mut().add_cursor_at_next_match();
+    harness.editor_mut().add_cursor_at_next_match();
+    let primary = *harness.editor().active_state().cursors.primary();
+    assert_eq!(primary.selection_range(), Some(4..7));
+
+    // Skip the second "foo" for the third
+    harness.editor_mut().skip_to_next_match();
+    harness.render().unwrap();
+    let cursors = &harness.editor().active_state().cursors;
+    assert_eq!(cursors.count(), 2);
+    assert_eq!(cursors.primary().selection_range(), Some(8..11));
+
+    // Undo puts the cursor back on the skipped occurrence
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    let cursors = &harness.editor().active_state().cursors;
+    assert_eq!(cursors.count(), 2);
+    assert_eq!(cursors.primary().selection_range(), Some(4..7));
+}
+
 /// Test adding cursor above with Ctrl+Alt+Up
 #[test]
 fn test_add_cursor_above() {
@@ -41,7 +41,10 @@ fn test_format_on_save() {
             filenames: vec![],
             grammar: "plaintext".to_string(),
             comment_prefix: None,
+            block_comment_start: None,
+            block_comment_end: None,
             auto_indent: false,
+            indent_rules: None,
             highlighter: Default::default(),
             textmate_grammar: None,
             show_whitespace_tabs: true,
@@ -50,6 +53,7 @@ fn test_format_on_save() {
             formatter: Some(formatter),
             format_on_save: true,
             on_save: vec![],
+            lsp_servers: vec![],
         },
     );
 
@@ -104,7 +108,10 @@ fn test_on_save_linter_style() {
             filenames: vec![],
             grammar: "plaintext".to_string(),
             comment_prefix: None,
+            block_comment_start: None,
+            block_comment_end: None,
             auto_indent: false,
+            indent_rules: None,
             highlighter: Default::default(),
             textmate_grammar: None,
             show_whitespace_tabs: true,
@@ -113,6 +120,7 @@ fn test_on_save_linter_style() {
             formatter: None,
             format_on_save: false,
             on_save: vec![action],
+            lsp_servers: vec![],
         },
     );
 
@@ -167,7 +175,10 @@ fn test_on_save_action_failure() {
             filenames: vec![],
             grammar: "plaintext".to_string(),
             comment_prefix: None,
+            block_comment_start: None,
+            block_comment_end: None,
             auto_indent: false,
+            indent_rules: None,
             highlighter: Default::default(),
             textmate_grammar: None,
             show_whitespace_tabs: true,
@@ -176,6 +187,7 @@ fn test_on_save_action_failure() {
             formatter: None,
             format_on_save: false,
             on_save: vec![action],
+            lsp_servers: vec![],
         },
     );
 
@@ -239,7 +251,10 @@ fn test_on_save_file_placeholder() {
             filenames: vec![],
             grammar: "plaintext".to_string(),
             comment_prefix: None,
+            block_comment_start: None,
+            block_comment_end: None,
             auto_indent: false,
+            indent_rules: None,
             highlighter: Default::default(),
             textmate_grammar: None,
             show_whitespace_tabs: true,
@@ -248,6 +263,7 @@ fn test_on_save_file_placeholder() {
             formatter: None,
             format_on_save: false,
             on_save: vec![action],
+            lsp_servers: vec![],
         },
     );
 
@@ -305,7 +321,10 @@ fn test_formatter_stdin_mode() {
             filenames: vec![],
             grammar: "plaintext".to_string(),
             comment_prefix: None,
+            block_comment_start: None,
+            block_comment_end: None,
             auto_indent: false,
+            indent_rules: None,
             highlighter: Default::default(),
             textmate_grammar: None,
             show_whitespace_tabs: true,
@@ -314,6 +333,7 @@ fn test_formatter_stdin_mode() {
             formatter: Some(formatter),
             format_on_save: true,
             on_save: vec![],
+            lsp_servers: vec![],
         },
     );
 
@@ -378,7 +398,10 @@ fn test_on_save_stops_on_failure() {
             filenames: vec![],
             grammar: "plaintext".to_string(),
             comment_prefix: None,
+            block_comment_start: None,
+            block_comment_end: None,
             auto_indent: false,
+            indent_rules: None,
             highlighter: Default::default(),
             textmate_grammar: None,
             show_whitespace_tabs: true,
@@ -387,6 +410,7 @@ fn test_on_save_stops_on_failure() {
             formatter: None,
             format_on_save: false,
             on_save: vec![action1, action2],
+            lsp_servers: vec![],
         },
     );
 
@@ -472,7 +496,10 @@ fn test_formatter_not_found_shows_message() {
             filenames: vec![],
             grammar: "plaintext".to_string(),
             comment_prefix: None,
+            block_comment_start: None,
+            block_comment_end: None,
             auto_indent: false,
+            indent_rules: None,
             highlighter: Default::default(),
             textmate_grammar: None,
             show_whitespace_tabs: true,
@@ -481,6 +508,7 @@ fn test_formatter_not_found_shows_message() {
             formatter: Some(formatter),
             format_on_save: true,
             on_save: vec![],
+            lsp_servers: vec![],
         },
     );
 
@@ -0,0 +1,43 @@
+//! E2E tests for Open Link Under Cursor
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+
+/// A `path:line` under the cursor opens the file at that line
+#[test]
+fn test_open_file_link_at_line() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 24).unwrap();
+    let dir = harness.project_dir().unwrap();
+    fs::write(dir.join("other.txt"), "first\nsecond line\n").unwrap();
+    let file = dir.join("main.txt");
+    fs::write(&file, "see other.txt:2").unwrap();
+    harness.open_file(&file).unwrap();
+
+    harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+    harness.editor_mut().open_link_under_cursor();
+    harness.render().unwrap();
+
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "first\nsecond line\n"
+    );
+    assert_eq!(harness.cursor_position(), 6);
+}
+
+/// Without a link under the cursor nothing is opened
+#[test]
+fn test_no_link_under_cursor() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 24).unwrap();
+    let file = harness.project_dir().unwrap().join("notes.txt");
+    fs::write(&file, "plain words, i.e. nothing").unwrap();
+    harness.open_file(&file).unwrap();
+
+    harness.editor_mut().open_link_under_cursor();
+    harness.render().unwrap();
+    harness.assert_screen_contains("No link under the cursor");
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "plain words, i.e. nothing"
+    );
+}
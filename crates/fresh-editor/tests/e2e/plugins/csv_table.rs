@@ -0,0 +1,169 @@
+//! E2E tests for the CSV/TSV table mode plugin
+
+use crate::common::harness::{copy_plugin, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use std::path::PathBuf;
+
+/// Create a project with the csv_table plugin and a CSV file
+fn setup_project(content: &str) -> (tempfile::TempDir, PathBuf, PathBuf) {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir(&project_root).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    copy_plugin(&plugins_dir, "csv_table");
+
+    let csv_path = project_root.join("data.csv");
+    fs::write(&csv_path, content).unwrap();
+    (temp_dir, project_root, csv_path)
+}
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+}
+
+/// Table mode pads columns to a common width without touching the buffer
+#[test]
+fn test_csv_table_mode_aligns_columns() {
+    let content = "name,qty\nbanana,3\napple,12\n";
+    let (_temp_dir, project_root, csv_path) = setup_project(content);
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&csv_path).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("banana,3");
+
+    run_command(&mut harness, "CSV: Toggle Table Mode");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("name   │ qty"))
+        .unwrap();
+    harness.assert_screen_contains("banana │ 3");
+    harness.assert_screen_contains("apple  │ 12");
+    harness.assert_buffer_content(content);
+
+    run_command(&mut harness, "CSV: Toggle Table Mode");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("banana,3"))
+        .unwrap();
+    harness.assert_no_plugin_errors();
+}
+
+/// Tab moves between cells, and hidden columns drop out of the view
+#[test]
+fn test_csv_table_cell_navigation_and_hiding() {
+    let content = "a,b,c\n1,22,333\n";
+    let (_temp_dir, project_root, csv_path) = setup_project(content);
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&csv_path).unwrap();
+    run_command(&mut harness, "CSV: Toggle Table Mode");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("a │ b  │ c"))
+        .unwrap();
+
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness.wait_until(|h| h.cursor_position() == 2).unwrap();
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness.wait_until(|h| h.cursor_position() == 4).unwrap();
+    // Wraps onto the next row
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness.wait_until(|h| h.cursor_position() == 6).unwrap();
+    harness
+        .send_key(KeyCode::BackTab, KeyModifiers::SHIFT)
+        .unwrap();
+    harness.wait_until(|h| h.cursor_position() == 4).unwrap();
+
+    // Hide column "b" from its header cell
+    harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness.wait_until(|h| h.cursor_position() == 2).unwrap();
+    run_command(&mut harness, "CSV: Hide Column");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("1 │ 333"))
+        .unwrap();
+    harness.assert_buffer_content(content);
+
+    run_command(&mut harness, "CSV: Show All Columns");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("1 │ 22 │ 333"))
+        .unwrap();
+}
+
+/// Sorting reorders the data rows and saving writes plain delimited text
+#[test]
+fn test_csv_table_sort_and_save() {
+    let (_temp_dir, project_root, csv_path) =
+        setup_project("name,qty\nbanana,3\ncherry,100\napple,12\n");
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&csv_path).unwrap();
+    run_command(&mut harness, "CSV: Toggle Table Mode");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("│"))
+        .unwrap();
+
+    // Cursor starts in the "name" column
+    run_command(&mut harness, "CSV: Sort Ascending by Column");
+    harness
+        .wait_for_buffer_content("name,qty\napple,12\nbanana,3\ncherry,100\n")
+        .unwrap();
+
+    // Numeric columns sort by value, not text
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness.wait_until(|h| h.cursor_position() == 5).unwrap();
+    run_command(&mut harness, "CSV: Sort Descending by Column");
+    harness
+        .wait_for_buffer_content("name,qty\ncherry,100\napple,12\nbanana,3\n")
+        .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .wait_until(|_| {
+            fs::read_to_string(&csv_path).unwrap() == "name,qty\ncherry,100\napple,12\nbanana,3\n"
+        })
+        .unwrap();
+    harness.assert_no_plugin_errors();
+}
+
+/// Sorting moves whole records: quoted line breaks stay inside their record,
+/// each record keeps its line ending, and a missing final newline stays missing
+#[test]
+fn test_csv_table_sort_keeps_multiline_records() {
+    let (_temp_dir, project_root, csv_path) =
+        setup_project("name,note\nb,\"two\nlines\"\r\nc,last\na,first");
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&csv_path).unwrap();
+    run_command(&mut harness, "CSV: Toggle Table Mode");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("│"))
+        .unwrap();
+
+    run_command(&mut harness, "CSV: Sort Ascending by Column");
+    harness
+        .wait_for_buffer_content("name,note\na,first\nb,\"two\nlines\"\r\nc,last")
+        .unwrap();
+
+    run_command(&mut harness, "CSV: Sort Descending by Column");
+    harness
+        .wait_for_buffer_content("name,note\nc,last\nb,\"two\nlines\"\r\na,first")
+        .unwrap();
+    harness.assert_no_plugin_errors();
+}
@@ -0,0 +1,59 @@
+//! E2E tests for the directory diff plugin
+
+use crate::common::harness::{copy_plugin, copy_plugin_lib, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+
+/// `fresh --diff OLD NEW` lists the differing files side by side, and Enter
+/// opens one in the diff view
+#[test]
+fn test_directory_diff_listing_and_drill_down() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    copy_plugin(&plugins_dir, "dir_diff");
+
+    let old_dir = temp_dir.path().join("release-1.0");
+    let new_dir = temp_dir.path().join("release-1.1");
+    fs::create_dir_all(old_dir.join("src")).unwrap();
+    fs::create_dir_all(new_dir.join("src")).unwrap();
+    fs::write(old_dir.join("src/changed.txt"), "alpha\nbeta\n").unwrap();
+    fs::write(new_dir.join("src/changed.txt"), "alpha\nBETA CHANGED\n").unwrap();
+    fs::write(old_dir.join("same.txt"), "same\n").unwrap();
+    fs::write(new_dir.join("same.txt"), "same\n").unwrap();
+    fs::write(old_dir.join("removed.txt"), "gone\n").unwrap();
+    fs::write(new_dir.join("added.txt"), "fresh\n").unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 30, Default::default(), project_root)
+            .unwrap();
+    harness
+        .editor_mut()
+        .queue_directory_diff(old_dir.clone(), new_dir.clone());
+    harness.editor_mut().process_pending_file_opens();
+    harness
+        .wait_until(|h| {
+            h.screen_to_string()
+                .contains("1 changed, 1 added, 1 removed, 1 identical")
+        })
+        .unwrap();
+    harness.assert_screen_contains("M src/changed.txt");
+    harness.assert_screen_contains("+ added.txt");
+    harness.assert_screen_contains("- removed.txt");
+    harness.assert_screen_not_contains("same.txt");
+
+    // Rows are sorted by path: added.txt, removed.txt, src/changed.txt
+    for _ in 0..6 {
+        harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    }
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("BETA CHANGED"))
+        .unwrap();
+    harness.assert_screen_contains("beta");
+    harness.assert_no_plugin_errors();
+}
@@ -2348,3 +2348,72 @@ fn test_git_blame_original_buffer_not_decorated() {
         "Original file should NOT have blame headers after closing blame"
     );
 }
+
+// =============================================================================
+// Git Compare Tests
+// =============================================================================
+
+/// Test Compare With Revision diffs the buffer, unsaved changes included,
+/// against a commit picked by its subject
+#[test]
+fn test_compare_with_revision() {
+    let repo = GitTestRepo::new();
+    repo.create_file("notes.txt", "alpha\nbeta\ngamma\n");
+    repo.git_add_all();
+    repo.git_commit("First version");
+    repo.create_file("notes.txt", "alpha\nBETA\ngamma\n");
+    repo.git_add_all();
+    repo.git_commit("Shout beta");
+    repo.setup_git_compare_plugin();
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        120,
+        40,
+        Config::default(),
+        repo.path.clone(),
+    )
+    .unwrap();
+    harness.open_file(&repo.path.join("notes.txt")).unwrap();
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("delta\n").unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text("Compare With Revision").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    // The picker lists the file's commits; filter by subject
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Shout beta"))
+        .unwrap();
+    harness.type_text("First version").unwrap();
+    harness
+        .wait_until(|h| !h.screen_to_string().contains("Shout beta"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness
+        .wait_until(|h| h.screen_to_string().contains("2 hunk(s)"))
+        .unwrap();
+    let screen = harness.screen_to_string();
+    assert!(
+        screen.contains("beta"),
+        "Old side should show the old line:\n{screen}"
+    );
+    assert!(
+        screen.contains("BETA"),
+        "Buffer side should show the new line:\n{screen}"
+    );
+    assert!(
+        screen.contains("delta"),
+        "Buffer side should include unsaved changes:\n{screen}"
+    );
+}
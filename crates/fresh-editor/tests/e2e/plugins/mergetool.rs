@@ -0,0 +1,76 @@
+//! E2E tests for the mergetool plugin
+
+use crate::common::harness::{copy_plugin, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use std::path::PathBuf;
+
+const MERGED: &str = "a\n<<<<<<< ours\nb-local\n=======\nb-remote\n>>>>>>> theirs\nc\n";
+
+/// Start a merge of a file changed on both sides and return the path of
+/// MERGED
+fn start_merge(temp_dir: &tempfile::TempDir) -> (EditorTestHarness, PathBuf) {
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin(&plugins_dir, "mergetool");
+
+    let local = temp_dir.path().join("notes_LOCAL.txt");
+    let base = temp_dir.path().join("notes_BASE.txt");
+    let remote = temp_dir.path().join("notes_REMOTE.txt");
+    let merged = temp_dir.path().join("notes.txt");
+    fs::write(&local, "a\nb-local\nc\n").unwrap();
+    fs::write(&base, "a\nb\nc\n").unwrap();
+    fs::write(&remote, "a\nb-remote\nc\n").unwrap();
+    fs::write(&merged, MERGED).unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 30, Default::default(), project_root)
+            .unwrap();
+    harness
+        .editor_mut()
+        .queue_merge(local, base, remote, merged.clone());
+    harness.editor_mut().process_pending_file_opens();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Conflict 1 of 1"))
+        .unwrap();
+    (harness, merged)
+}
+
+/// Taking REMOTE and saving writes MERGED and exits with status 0
+#[test]
+fn test_mergetool_take_remote_and_save() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let (mut harness, merged) = start_merge(&temp_dir);
+    harness.assert_screen_contains("b-local");
+    harness.assert_screen_contains("b-remote");
+
+    harness
+        .send_key(KeyCode::Char('3'), KeyModifiers::ALT)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("All conflicts resolved"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_until(|h| h.editor().should_quit()).unwrap();
+    assert_eq!(fs::read_to_string(&merged).unwrap(), "a\nb-remote\nc\n");
+    assert_eq!(harness.editor().exit_code(), 0);
+    harness.assert_no_plugin_errors();
+}
+
+/// Aborting leaves MERGED as git wrote it and exits with status 1
+#[test]
+fn test_mergetool_abort() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let (mut harness, merged) = start_merge(&temp_dir);
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_until(|h| h.editor().should_quit()).unwrap();
+    assert_eq!(fs::read_to_string(&merged).unwrap(), MERGED);
+    assert_eq!(harness.editor().exit_code(), 1);
+    harness.assert_no_plugin_errors();
+}
@@ -2,12 +2,18 @@
 //! These tests are only compiled when the "plugins" feature is enabled.
 
 pub mod audit_mode;
+pub mod csv_table;
 pub mod diff_cursor;
+pub mod dir_diff;
 pub mod find_file;
 pub mod git;
 pub mod gutter;
 pub mod language_pack;
 pub mod lsp_find_references;
+pub mod mergetool;
 pub mod package_manager;
+pub mod patch_mode;
 pub mod plugin;
+pub mod review_comments;
+pub mod test_explorer;
 pub mod theme_editor;
@@ -1580,3 +1580,232 @@ globalThis.uninstall_test_hello = function() { editor.setStatus("Hello from unin
         screen
     );
 }
+
+/// Packaged plugins must declare permissions, and the user confirms them on first use.
+/// An "always" answer is persisted in the plugin's config entry.
+#[test]
+fn test_packaged_plugin_permissions_prompt_and_persist() {
+    use fresh::config_io::DirectoryContext;
+    use tempfile::TempDir;
+
+    init_tracing_from_env();
+
+    let temp_dir = TempDir::new().unwrap();
+    let dir_context = DirectoryContext::for_testing(temp_dir.path());
+
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir_all(&project_root).unwrap();
+    let data_path = project_root.join("data.txt");
+    fs::write(&data_path, "secret").unwrap();
+
+    // Install a package that only declares fs-read
+    let package_dir = dir_context
+        .config_dir
+        .join("plugins")
+        .join("packages")
+        .join("perm-probe");
+    fs::create_dir_all(&package_dir).unwrap();
+    fs::write(
+        package_dir.join("package.json"),
+        r#"{
+    "name": "perm-probe",
+    "version": "1.0.0",
+    "type": "plugin",
+    "permissions": ["fs-read"],
+    "fresh": { "entry": "perm_probe.ts" }
+}"#,
+    )
+    .unwrap();
+    fs::write(
+        package_dir.join("perm_probe.ts"),
+        format!(
+            r#"
+const editor = getEditor();
+
+globalThis.perm_probe_read = function(): void {{
+    try {{
+        editor.setStatus("read:" + editor.readFile({path:?}));
+    }} catch (e) {{
+        editor.setStatus("read-error:" + e);
+    }}
+}};
+
+globalThis.perm_probe_spawn = async function(): Promise<void> {{
+    try {{
+        await editor.spawnProcess("true", []);
+        editor.setStatus("spawned");
+    }} catch (e) {{
+        editor.setStatus("spawn-error:" + e);
+    }}
+}};
+
+editor.registerCommand("perm_probe_read", "Probe: Read", "perm_probe_read", null);
+editor.registerCommand("perm_probe_spawn", "Probe: Spawn", "perm_probe_spawn", null);
+"#,
+            path = data_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let config_path = dir_context.config_dir.join("config.json");
+    let mut harness = EditorTestHarness::with_shared_dir_context(
+        160,
+        30,
+        Default::default(),
+        project_root,
+        dir_context,
+    )
+    .unwrap();
+
+    let run_command = |harness: &mut EditorTestHarness, name: &str| {
+        harness
+            .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.wait_for_prompt().unwrap();
+        harness.type_text(name).unwrap();
+        harness
+            .send_key(KeyCode::Enter, KeyModifiers::NONE)
+            .unwrap();
+    };
+
+    // The first read fails while the prompt asks the user
+    run_command(&mut harness, "Probe: Read");
+    harness
+        .wait_until(|h| {
+            h.screen_to_string()
+                .contains("Plugin 'perm_probe' requests 'fs-read' access")
+        })
+        .unwrap();
+    harness.type_text("a").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+    harness
+        .wait_until(|_| fs::read_to_string(&config_path).is_ok_and(|c| c.contains("\"fs-read\"")))
+        .unwrap();
+
+    run_command(&mut harness, "Probe: Read");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("read:secret"))
+        .unwrap();
+
+    // Permissions missing from the manifest are refused without asking
+    run_command(&mut harness, "Probe: Spawn");
+    harness
+        .wait_until(|h| {
+            h.screen_to_string()
+                .contains("did not declare the 'process' permission")
+        })
+        .unwrap();
+}
+
+/// Test that `url@spec` installs the newest tag matching the spec and records
+/// the pin in packages.lock.json.
+#[test]
+#[cfg_attr(windows, ignore)] // file:// URLs don't work reliably on Windows
+fn test_pkg_install_pinned_version_writes_lockfile() {
+    use fresh::config_io::DirectoryContext;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    init_tracing_from_env();
+
+    let temp_dir = TempDir::new().unwrap();
+    let dir_context = DirectoryContext::for_testing(temp_dir.path());
+
+    // A package with three tagged releases
+    let package_repo = GitTestRepo::new();
+    fs::write(
+        package_repo.path.join("main.ts"),
+        "const editor = getEditor();\n",
+    )
+    .unwrap();
+    for version in ["1.0.0", "1.1.0", "2.0.0"] {
+        fs::write(
+            package_repo.path.join("package.json"),
+            format!(
+                r#"{{
+    "name": "pinned-plugin",
+    "version": "{version}",
+    "type": "plugin",
+    "fresh": {{ "entry": "main.ts" }}
+}}"#
+            ),
+        )
+        .unwrap();
+        package_repo.git_add_all();
+        package_repo.git_commit(&format!("Release {version}"));
+        let status = Command::new("git")
+            .args(["tag", &format!("v{version}")])
+            .current_dir(&package_repo.path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    let repo = GitTestRepo::new();
+    repo.setup_typical_project();
+
+    let plugins_dir = repo.path.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    copy_plugin(&plugins_dir, "pkg");
+
+    let packages_dir = dir_context.config_dir.join("plugins").join("packages");
+    fs::create_dir_all(&packages_dir).unwrap();
+
+    let original_dir = repo.change_to_repo_dir();
+    let _guard = DirGuard::new(original_dir);
+
+    let mut harness = EditorTestHarness::with_shared_dir_context(
+        120,
+        35,
+        Default::default(),
+        repo.path.clone(),
+        dir_context.clone(),
+    )
+    .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text("pkg: Install from URL").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Install from URL"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Git URL or local path"))
+        .unwrap();
+
+    // `@1` must resolve to v1.1.0, not the newer v2.0.0
+    let url = format!("file://{}@1", package_repo.path.display());
+    harness.type_text(&url).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    let lockfile_path = dir_context.config_dir.join("packages.lock.json");
+    harness
+        .wait_for_async(|_| lockfile_path.exists(), 15000)
+        .unwrap();
+
+    let manifest =
+        fs::read_to_string(packages_dir.join("pinned-plugin").join("package.json")).unwrap();
+    assert!(
+        manifest.contains("\"version\": \"1.1.0\""),
+        "Expected v1.1.0 to be checked out. Manifest: {}",
+        manifest
+    );
+
+    let lockfile: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&lockfile_path).unwrap()).unwrap();
+    let entry = &lockfile["packages"]["pinned-plugin"];
+    assert_eq!(entry["version"], "1.1.0", "Lockfile: {}", lockfile);
+    assert_eq!(entry["spec"], "1", "Lockfile: {}", lockfile);
+    assert_eq!(entry["commit"].as_str().map(str::len), Some(40));
+}
@@ -0,0 +1,120 @@
+//! E2E tests for the patch mode plugin
+
+use crate::common::harness::{copy_plugin, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use std::path::PathBuf;
+
+const SOURCE: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n";
+
+const PATCH: &str = "\
+diff --git a/numbers.txt b/numbers.txt
+--- a/numbers.txt
++++ b/numbers.txt
+@@ -1,3 +1,3 @@
+ one
+-two
++TWO
+ three
+@@ -8,3 +8,4 @@
+ eight
+ nine
++nine and a half
+ ten
+";
+
+/// Create a project with the patch_mode plugin, a source file and a patch
+/// for it
+fn setup_project() -> (tempfile::TempDir, PathBuf, PathBuf, PathBuf) {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir(&project_root).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    copy_plugin(&plugins_dir, "patch_mode");
+
+    let source_path = project_root.join("numbers.txt");
+    fs::write(&source_path, SOURCE).unwrap();
+    let patch_path = project_root.join("change.patch");
+    fs::write(&patch_path, PATCH).unwrap();
+    (temp_dir, project_root, source_path, patch_path)
+}
+
+/// Alt+A applies only the hunk under the cursor, and Alt+R takes it back
+#[test]
+fn test_patch_apply_and_reverse_hunk() {
+    let (_temp_dir, project_root, source_path, patch_path) = setup_project();
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&patch_path).unwrap();
+    harness.render().unwrap();
+
+    // The second hunk starts on line 9
+    harness
+        .send_key(KeyCode::Char('n'), KeyModifiers::ALT)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('n'), KeyModifiers::ALT)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Hunk 2 of 2"))
+        .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::ALT)
+        .unwrap();
+    harness
+        .wait_until(|_| {
+            fs::read_to_string(&source_path)
+                .unwrap()
+                .contains("nine and a half")
+        })
+        .unwrap();
+    let applied = fs::read_to_string(&source_path).unwrap();
+    assert_eq!(
+        applied,
+        "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nnine and a half\nten\n"
+    );
+
+    // Applying twice is caught instead of duplicating the line
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::ALT)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("already applied"))
+        .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::ALT)
+        .unwrap();
+    harness
+        .wait_until(|_| fs::read_to_string(&source_path).unwrap() == SOURCE)
+        .unwrap();
+    harness.assert_buffer_content(PATCH);
+    harness.assert_no_plugin_errors();
+}
+
+/// Alt+Enter opens the patched file at the line the cursor is on
+#[test]
+fn test_patch_goto_source() {
+    let (_temp_dir, project_root, _source_path, patch_path) = setup_project();
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&patch_path).unwrap();
+    harness.render().unwrap();
+
+    // Down to the "+TWO" line of the first hunk
+    for _ in 0..6 {
+        harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    }
+    harness.send_key(KeyCode::Enter, KeyModifiers::ALT).unwrap();
+    harness
+        .wait_until(|h| h.get_buffer_content().is_some_and(|c| c == SOURCE))
+        .unwrap();
+    // "two" is line 2 of numbers.txt
+    harness.wait_until(|h| h.cursor_position() == 4).unwrap();
+    harness.assert_no_plugin_errors();
+}
@@ -1282,3 +1282,520 @@ editor.debug("Theme overlay test plugin loaded");
     let screen = harness.screen_to_string();
     println!("Final screen:\n{}", screen);
 }
+
+/// Test the text manipulation primitives: getSelections, setSelections,
+/// getText, replaceRange and insertAt
+#[test]
+fn test_plugin_text_manipulation_api() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir(&project_root).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+
+    let test_plugin = r#"
+const editor = getEditor();
+
+globalThis.test_select_words = function(): void {
+    const bufferId = editor.getActiveBufferId();
+    editor.setSelections(bufferId, [
+        { anchor: 0, position: 5 },
+        { anchor: 11, position: 6 },
+    ]);
+};
+
+globalThis.test_upcase_selections = async function(): Promise<void> {
+    const bufferId = editor.getActiveBufferId();
+    const ranges = editor.getSelections()
+        .map(s => ({ start: Math.min(s.anchor, s.position), end: Math.max(s.anchor, s.position) }))
+        .sort((a, b) => b.start - a.start);
+    for (const range of ranges) {
+        const text = await editor.getText(bufferId, range);
+        editor.replaceRange(bufferId, range, text.toUpperCase());
+    }
+    editor.insertAt(bufferId, 0, "> ");
+    editor.setStatus("Upcased " + ranges.length);
+};
+
+editor.registerCommand("Test: Select Words", "Select two words", "test_select_words", null);
+editor.registerCommand("Test: Upcase Selections", "Upcase selections", "test_upcase_selections", null);
+"#;
+    fs::write(plugins_dir.join("test_text_api.ts"), test_plugin).unwrap();
+
+    let fixture = TestFixture::new("test.txt", "hello world foo\n").unwrap();
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(80, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&fixture.path).unwrap();
+    harness.render().unwrap();
+
+    let run_command = |harness: &mut EditorTestHarness, name: &str| {
+        harness
+            .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.wait_for_prompt().unwrap();
+        harness.type_text(name).unwrap();
+        harness
+            .send_key(KeyCode::Enter, KeyModifiers::NONE)
+            .unwrap();
+        harness.wait_for_prompt_closed().unwrap();
+    };
+
+    run_command(&mut harness, "Test: Select Words");
+    harness
+        .wait_until(|h| h.editor().active_state().cursors.count() == 2)
+        .unwrap();
+    let primary = *harness.editor().active_state().cursors.primary();
+    assert_eq!(primary.position, 5);
+    assert_eq!(primary.anchor, Some(0));
+
+    run_command(&mut harness, "Test: Upcase Selections");
+    harness
+        .wait_for_buffer_content("> HELLO WORLD foo\n")
+        .unwrap();
+    harness.assert_no_plugin_errors();
+
+    // insertAt is its own undo step
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content("HELLO WORLD foo\n");
+}
+
+/// Test the edit/save event subscriptions, including edits made from will_save
+#[test]
+fn test_plugin_save_and_change_events() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir(&project_root).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+
+    let test_plugin = r#"
+const editor = getEditor();
+const seen: string[] = [];
+
+globalThis.onWillSave = function(data: { buffer_id: number }): void {
+    seen.push("will_save");
+    editor.insertText(data.buffer_id, 0, "// stamped\n");
+};
+globalThis.onDidSave = function(): void {
+    seen.push("did_save");
+};
+globalThis.onDidChange = function(data: { changes: { start: number; end: number; text: string }[] }): void {
+    for (const c of data.changes) {
+        seen.push(`change ${c.start}-${c.end} '${c.text}'`);
+    }
+};
+globalThis.test_show_events = function(): void {
+    editor.setStatus("Events: " + seen.join(", "));
+};
+
+editor.on("will_save", "onWillSave");
+editor.on("did_save", "onDidSave");
+editor.on("did_change", "onDidChange");
+editor.on("did_change", "onDidChange");
+editor.registerCommand("Test: Show Events", "Show recorded events", "test_show_events", null);
+"#;
+    fs::write(plugins_dir.join("test_events.ts"), test_plugin).unwrap();
+
+    let fixture = TestFixture::new("test.txt", "hello\n").unwrap();
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&fixture.path).unwrap();
+    harness.render().unwrap();
+
+    harness.type_text("X").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    // The will_save edit lands in the file written by this save
+    harness
+        .wait_until(|_| fs::read_to_string(&fixture.path).unwrap() == "// stamped\nXhello\n")
+        .unwrap();
+    harness.assert_buffer_content("// stamped\nXhello\n");
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text("Test: Show Events").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+    harness
+        .wait_until(|h| {
+            h.screen_to_string()
+                .contains("Events: change 0-0 'X', will_save, did_save")
+        })
+        .unwrap();
+    harness.assert_no_plugin_errors();
+}
+
+/// Test plugin-driven UI: quick pick, input box and output buffers
+#[test]
+fn test_plugin_quick_pick_input_box_and_output_buffer() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir(&project_root).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+
+    let test_plugin = r#"
+const editor = getEditor();
+
+globalThis.test_run_tool = async function(): Promise<void> {
+    const picked = await editor.showQuickPick(
+        [
+            { label: "build", description: "Compile the project" },
+            { label: "test", description: "Run the tests", value: "cargo-test" },
+        ],
+        { title: "Task: " }
+    );
+    if (picked === null) {
+        editor.setStatus("Tool cancelled");
+        return;
+    }
+    const args = await editor.showInputBox({ title: "Args: ", value: "--all" });
+    const out = await editor.createOutputBuffer("Tool Output");
+    editor.appendOutput(out, "ran " + picked + "\n");
+    editor.appendOutput(out, "args " + args + "\n");
+};
+
+globalThis.test_clear_tool = async function(): Promise<void> {
+    const out = await editor.createOutputBuffer("Tool Output");
+    editor.clearOutput(out);
+    editor.appendOutput(out, "cleared\n");
+};
+
+editor.registerCommand("Test: Run Tool", "Run a tool", "test_run_tool", null);
+editor.registerCommand("Test: Clear Tool", "Clear tool output", "test_clear_tool", null);
+"#;
+    fs::write(plugins_dir.join("test_ui_api.ts"), test_plugin).unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 24, Default::default(), project_root)
+            .unwrap();
+    harness.render().unwrap();
+
+    let run_command = |harness: &mut EditorTestHarness, name: &str| {
+        harness
+            .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.wait_for_prompt().unwrap();
+        harness.type_text(name).unwrap();
+        harness
+            .send_key(KeyCode::Enter, KeyModifiers::NONE)
+            .unwrap();
+    };
+
+    // Cancelling the quick pick resolves to null
+    run_command(&mut harness, "Test: Run Tool");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Task:"))
+        .unwrap();
+    harness.assert_screen_contains("Compile the project");
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Tool cancelled"))
+        .unwrap();
+
+    // Filter down to "test" and pick it; its value is returned
+    run_command(&mut harness, "Test: Run Tool");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Task:"))
+        .unwrap();
+    harness.type_text("tes").unwrap();
+    harness
+        .wait_until(|h| !h.screen_to_string().contains("Compile the project"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Args:"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness
+        .wait_for_buffer_content("ran cargo-test\nargs --all\n")
+        .unwrap();
+    harness.assert_screen_contains("*Tool Output*");
+    assert!(!harness.editor().active_state().buffer.is_modified());
+
+    // Reopening by name reuses the same buffer
+    run_command(&mut harness, "Test: Clear Tool");
+    harness.wait_for_buffer_content("cleared\n").unwrap();
+    harness.assert_no_plugin_errors();
+}
+
+/// Test editor.runTask: progress in the status bar, results, and cancellation
+#[test]
+fn test_plugin_background_task_progress_and_cancel() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir(&project_root).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+
+    let test_plugin = r#"
+const editor = getEditor();
+
+globalThis.test_quick_task = async function(): Promise<void> {
+    const result = await editor.runTask("Quick", () => ({ answer: 42 })) as { answer: number };
+    editor.setStatus("Task result " + result.answer);
+};
+
+globalThis.test_slow_task = async function(): Promise<void> {
+    try {
+        await editor.runTask("Indexing", async (task) => {
+            task.progress("scanning", 25);
+            while (!task.cancelled) {
+                await editor.delay(20);
+            }
+        });
+        editor.setStatus("Task finished");
+    } catch (e) {
+        editor.setStatus("Task rejected: " + (e instanceof Error ? e.message : String(e)));
+    }
+};
+
+editor.registerCommand("Test: Quick Task", "Run a quick task", "test_quick_task", null);
+editor.registerCommand("Test: Slow Task", "Run a slow task", "test_slow_task", null);
+"#;
+    fs::write(plugins_dir.join("test_tasks.ts"), test_plugin).unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 24, Default::default(), project_root)
+            .unwrap();
+    harness.render().unwrap();
+
+    let run_command = |harness: &mut EditorTestHarness, name: &str| {
+        harness
+            .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.wait_for_prompt().unwrap();
+        harness.type_text(name).unwrap();
+        harness
+            .send_key(KeyCode::Enter, KeyModifiers::NONE)
+            .unwrap();
+    };
+
+    // A finished task hands its result back to the plugin
+    run_command(&mut harness, "Test: Quick Task");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Task result 42"))
+        .unwrap();
+
+    // A running task shows its progress in the status bar
+    run_command(&mut harness, "Test: Slow Task");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Indexing: scanning (25%)"))
+        .unwrap();
+
+    // Cancelling from the task list rejects the plugin's promise
+    run_command(&mut harness, "Show Background Tasks");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Cancel task:"))
+        .unwrap();
+    harness.assert_screen_contains("Indexing");
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| {
+            let screen = h.screen_to_string();
+            screen.contains("Task rejected: Task cancelled") && !screen.contains("(25%)")
+        })
+        .unwrap();
+    harness.assert_no_plugin_errors();
+}
+
+/// Test reloading a plugin from disk, on demand and with hot reload enabled
+#[test]
+fn test_plugin_reload_command_and_hot_reload() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir(&project_root).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+
+    let plugin_path = plugins_dir.join("test_hot.ts");
+    let write_version = |version: u64| {
+        let source = format!(
+            r#"
+const editor = getEditor();
+globalThis.test_hot_version = function(): void {{
+    editor.setStatus("hot version {version}");
+}};
+editor.registerCommand("Test: Hot Version", "Show the plugin version", "test_hot_version", null);
+"#
+        );
+        fs::write(&plugin_path, source).unwrap();
+        // Make sure the change is visible even on coarse mtime filesystems
+        let mtime = std::time::SystemTime::now() + Duration::from_secs(version * 10);
+        fs::File::options()
+            .write(true)
+            .open(&plugin_path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+    };
+    write_version(1);
+
+    let mut config = Config::default();
+    config.editor.plugin_hot_reload = true;
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 24, config, project_root).unwrap();
+    harness.render().unwrap();
+
+    let run_command = |harness: &mut EditorTestHarness, name: &str| {
+        harness
+            .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.wait_for_prompt().unwrap();
+        harness.type_text(name).unwrap();
+        harness
+            .send_key(KeyCode::Enter, KeyModifiers::NONE)
+            .unwrap();
+    };
+
+    run_command(&mut harness, "Test: Hot Version");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("hot version 1"))
+        .unwrap();
+
+    // Reload on demand from the plugin list
+    write_version(2);
+    run_command(&mut harness, "Developer: Reload Plugin");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Reload plugin:"))
+        .unwrap();
+    harness.type_text("test_hot").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Reloaded plugin 'test_hot'"))
+        .unwrap();
+    run_command(&mut harness, "Test: Hot Version");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("hot version 2"))
+        .unwrap();
+
+    // Let the watcher record the reloaded sources, then change them
+    harness.advance_time(Duration::from_secs(5));
+    harness.process_async_and_render().unwrap();
+    write_version(3);
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Reloaded plugin 'test_hot'"))
+        .unwrap();
+
+    // The command is registered once, by the new instance
+    run_command(&mut harness, "Test: Hot Version");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("hot version 3"))
+        .unwrap();
+    harness.assert_no_plugin_errors();
+}
+
+/// Test plugin URI schemes: opening `pr://...` asks the plugin for a virtual
+/// buffer, which reports the URI as its path and is reused when the URI is
+/// opened again
+#[test]
+fn test_plugin_uri_scheme_opens_virtual_buffer() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir(&project_root).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+
+    let test_plugin = r#"
+const editor = getEditor();
+let fetches = 0;
+
+globalThis.onUriOpen = async function(data: { uri: string; line: number | null }): Promise<void> {
+    fetches++;
+    await editor.createVirtualBuffer({
+        name: "main.rs (PR #123)",
+        readOnly: true,
+        uri: data.uri,
+        entries: [{ text: "fn main() {\n    println!(\"from the pull request\");\n}\n" }],
+    });
+};
+globalThis.test_open_pr_file = function(): void {
+    editor.openFile("pr://owner/repo/123/src/main.rs", null, null);
+};
+globalThis.test_show_pr_buffer = function(): void {
+    const id = editor.findBufferByPath("pr://owner/repo/123/src/main.rs");
+    editor.setStatus(`PR buffer ${editor.getBufferPath(id)} fetched ${fetches}`);
+};
+
+editor.registerUriScheme("pr");
+editor.on("uri_open_requested", "onUriOpen");
+editor.registerCommand("Test: Open PR File", "Open a file of a pull request", "test_open_pr_file", null);
+editor.registerCommand("Test: Show PR Buffer", "Show the PR buffer's path", "test_show_pr_buffer", null);
+"#;
+    fs::write(plugins_dir.join("test_uri_scheme.ts"), test_plugin).unwrap();
+
+    let fixture = TestFixture::new("local.txt", "local file\n").unwrap();
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&fixture.path).unwrap();
+    harness.render().unwrap();
+
+    let run_command = |harness: &mut EditorTestHarness, name: &str| {
+        harness
+            .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.wait_for_prompt().unwrap();
+        harness.type_text(name).unwrap();
+        harness
+            .send_key(KeyCode::Enter, KeyModifiers::NONE)
+            .unwrap();
+        harness.wait_for_prompt_closed().unwrap();
+    };
+
+    run_command(&mut harness, "Test: Open PR File");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("from the pull request"))
+        .unwrap();
+
+    // Back to the local file, then open the URI again: the buffer is reused
+    harness.open_file(&fixture.path).unwrap();
+    harness
+        .wait_until(|h| !h.screen_to_string().contains("from the pull request"))
+        .unwrap();
+    run_command(&mut harness, "Test: Open PR File");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("from the pull request"))
+        .unwrap();
+
+    run_command(&mut harness, "Test: Show PR Buffer");
+    harness
+        .wait_until(|h| {
+            h.screen_to_string()
+                .contains("PR buffer pr://owner/repo/123/src/main.rs fetched 1")
+        })
+        .unwrap();
+    harness.assert_no_plugin_errors();
+}
@@ -0,0 +1,119 @@
+//! E2E tests for the review comments plugin
+
+use crate::common::harness::{copy_plugin, copy_plugin_lib, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Create a project with the review_comments plugin and a file to comment on
+fn setup_project(temp_dir: &Path) -> (PathBuf, PathBuf) {
+    let project_root = temp_dir.join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    copy_plugin(&plugins_dir, "review_comments");
+
+    let file = project_root.join("notes.txt");
+    fs::write(&file, "alpha\nbeta\ngamma\n").unwrap();
+    (project_root, file)
+}
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+}
+
+/// A comment on the cursor's line is saved next to the project, exported as
+/// Markdown, and can be resolved
+#[test]
+fn test_review_comment_add_export_and_resolve() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let (project_root, file) = setup_project(temp_dir.path());
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        100,
+        30,
+        Default::default(),
+        project_root.clone(),
+    )
+    .unwrap();
+    harness.open_file(&file).unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+
+    run_command(&mut harness, "Review Comments: Add");
+    harness.wait_for_prompt().unwrap();
+    harness.type_text("Needs a test").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    let store = project_root.join(".review/comments.json");
+    harness
+        .wait_until(|_| fs::read_to_string(&store).is_ok_and(|s| s.contains("Needs a test")))
+        .unwrap();
+    let saved = fs::read_to_string(&store).unwrap();
+    assert!(saved.contains(r#""file": "notes.txt""#));
+    assert!(saved.contains(r#""startLine": 2"#));
+
+    run_command(&mut harness, "Review Comments: Export as Markdown");
+    let export = project_root.join(".review/comments.md");
+    harness.wait_until(|_| export.exists()).unwrap();
+    assert_eq!(
+        fs::read_to_string(&export).unwrap(),
+        "## Review comments\n\n### `notes.txt`\n\n- **L2**: Needs a test\n"
+    );
+
+    run_command(&mut harness, "Review Comments: Toggle Resolved");
+    harness
+        .wait_until(|_| fs::read_to_string(&store).is_ok_and(|s| s.contains(r#""resolved": true"#)))
+        .unwrap();
+    harness.assert_no_plugin_errors();
+}
+
+/// A comment follows its line when lines were added above it
+#[test]
+fn test_review_comment_follows_its_line() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let (project_root, file) = setup_project(temp_dir.path());
+    let store = project_root.join(".review/comments.json");
+    fs::create_dir_all(store.parent().unwrap()).unwrap();
+    fs::write(
+        &store,
+        r#"{
+  "version": 1,
+  "comments": [
+    {
+      "id": "c1",
+      "file": "notes.txt",
+      "startLine": 2,
+      "endLine": 3,
+      "text": "Rename these",
+      "resolved": false,
+      "created": "2026-01-01T00:00:00.000Z",
+      "anchor": "beta"
+    }
+  ]
+}
+"#,
+    )
+    .unwrap();
+    fs::write(&file, "new one\nnew two\nalpha\nbeta\ngamma\n").unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&file).unwrap();
+    harness
+        .wait_until(|_| fs::read_to_string(&store).is_ok_and(|s| s.contains(r#""startLine": 4"#)))
+        .unwrap();
+    assert!(fs::read_to_string(&store)
+        .unwrap()
+        .contains(r#""endLine": 5"#));
+    harness.assert_no_plugin_errors();
+}
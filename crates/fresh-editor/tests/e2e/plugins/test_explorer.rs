@@ -0,0 +1,139 @@
+//! E2E tests for the test explorer plugin
+
+use crate::common::harness::{copy_plugin, copy_plugin_lib, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config_io::DirectoryContext;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Create a project with the test_explorer plugin
+fn setup_project(temp_dir: &Path) -> PathBuf {
+    let project_root = temp_dir.join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    copy_plugin(&plugins_dir, "test_explorer");
+    project_root
+}
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+}
+
+/// Functions marked #[test] in a cargo project are listed by file; other
+/// functions are not
+#[test]
+fn test_explorer_lists_cargo_tests() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = setup_project(temp_dir.path());
+    fs::write(
+        project_root.join("Cargo.toml"),
+        "[package]\nname = \"demo\"\n",
+    )
+    .unwrap();
+    fs::create_dir(project_root.join("src")).unwrap();
+    fs::write(
+        project_root.join("src/lib.rs"),
+        "fn helper() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn adds_numbers() {}\n\n    #[tokio::test]\n    async fn fetches_data() {}\n}\n",
+    )
+    .unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 30, Default::default(), project_root)
+            .unwrap();
+    run_command(&mut harness, "Tests: Show Test Explorer");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("adds_numbers"))
+        .unwrap();
+    harness.assert_screen_contains("lib.rs:");
+    harness.assert_screen_contains("fetches_data");
+    harness.assert_screen_not_contains("helper");
+
+    run_command(&mut harness, "Tests: Rerun Failed Tests");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("No failed tests to rerun"))
+        .unwrap();
+    harness.assert_no_plugin_errors();
+}
+
+/// A framework from a language package runs, and its results are shown in
+/// the panel and the gutter
+#[test]
+fn test_explorer_runs_language_package_framework() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir_context = DirectoryContext::for_testing(temp_dir.path());
+    let package_dir = dir_context.config_dir.join("languages/packages/checklang");
+    fs::create_dir_all(&package_dir).unwrap();
+    fs::write(
+        package_dir.join("package.json"),
+        r#"{
+  "name": "checklang",
+  "type": "language",
+  "fresh": {
+    "test": {
+      "name": "check",
+      "detect": ["run_checks.sh"],
+      "files": "\\.check$",
+      "test": "^check (\\w+)",
+      "runAll": ["sh", "run_checks.sh"],
+      "runTests": ["sh", "run_checks.sh", "${names}"],
+      "passed": "^PASS (\\w+)",
+      "failed": "^FAIL (\\w+)"
+    }
+  }
+}"#,
+    )
+    .unwrap();
+
+    let project_root = setup_project(temp_dir.path());
+    fs::write(
+        project_root.join("run_checks.sh"),
+        "echo 'PASS good'\necho 'FAIL bad'\n",
+    )
+    .unwrap();
+    let suite = project_root.join("suite.check");
+    fs::write(&suite, "check good\ncheck bad\n").unwrap();
+
+    let mut harness = EditorTestHarness::with_shared_dir_context(
+        120,
+        30,
+        Default::default(),
+        project_root,
+        dir_context,
+    )
+    .unwrap();
+    harness.open_file(&suite).unwrap();
+    run_command(&mut harness, "Tests: Run All Tests");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("1 passed, 1 failed"))
+        .unwrap();
+
+    // The gutter marks each test line
+    harness
+        .wait_until(|h| {
+            let screen = h.screen_to_string();
+            screen
+                .lines()
+                .any(|l| l.contains('✓') && l.contains("check good"))
+                && screen
+                    .lines()
+                    .any(|l| l.contains('✗') && l.contains("check bad"))
+        })
+        .unwrap();
+
+    run_command(&mut harness, "Tests: Show Test Explorer");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("✗ bad"))
+        .unwrap();
+    harness.assert_screen_contains("✓ good");
+    harness.assert_no_plugin_errors();
+}
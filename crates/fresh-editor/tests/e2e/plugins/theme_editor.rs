@@ -1816,6 +1816,61 @@ fn test_cursor_position_preserved_after_color_edit() {
     );
 }
 
+/// Test that editing a color previews it on the running editor before saving
+#[test]
+fn test_color_edit_previews_on_running_editor() {
+    init_tracing_from_env();
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir(&project_root).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    copy_plugin(&plugins_dir, "theme_editor");
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 40, Default::default(), project_root)
+            .unwrap();
+    harness.render().unwrap();
+
+    let has_red = |h: &EditorTestHarness| {
+        let mut theme = h.editor().theme().clone();
+        let found = theme
+            .colors_mut()
+            .iter()
+            .any(|(_, color)| **color == Color::Rgb(255, 0, 0));
+        found
+    };
+    assert!(!has_red(&harness));
+
+    // Edit a color of the dark theme
+    open_theme_editor(&mut harness);
+    harness
+        .wait_until(|h| h.screen_to_string().contains("editor"))
+        .unwrap();
+    for _ in 0..5 {
+        harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    }
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("#RRGGBB"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("#FF0000").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    // The running editor shows the edited theme, still unsaved
+    harness.wait_until(|h| has_red(h)).unwrap();
+    assert_eq!(harness.editor().config().theme, "high-contrast".into());
+}
+
 /// Test that cursor is positioned on the value field (not first column) when navigating
 /// When moving to a color field, cursor should be on the value, not at the line start
 #[test]
@@ -250,3 +250,50 @@ fn test_position_history_with_next_prev_buffer() {
     harness.send_key(KeyCode::Left, KeyModifiers::ALT).unwrap();
     harness.assert_buffer_content("Third");
 }
+
+/// Helper: Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// Test that each split navigates through its own jumps
+#[test]
+fn test_position_history_is_per_split() {
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+
+    harness.type_text("Buffer 1").unwrap();
+    for name in ["Buffer 2", "Buffer 3"] {
+        harness
+            .send_key(KeyCode::Char('n'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.type_text(name).unwrap();
+    }
+
+    // The new split starts with a copy of the left split's history
+    run_command(&mut harness, "split vert");
+    for name in ["Buffer 4", "Buffer 5"] {
+        harness
+            .send_key(KeyCode::Char('n'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.type_text(name).unwrap();
+    }
+
+    // Back in the left split, the right split's jumps are not followed
+    run_command(&mut harness, "prev split");
+    harness.assert_buffer_content("Buffer 3");
+    harness.send_key(KeyCode::Left, KeyModifiers::ALT).unwrap();
+    harness.assert_buffer_content("Buffer 2");
+
+    run_command(&mut harness, "next split");
+    harness.assert_buffer_content("Buffer 5");
+    harness.send_key(KeyCode::Left, KeyModifiers::ALT).unwrap();
+    harness.assert_buffer_content("Buffer 4");
+}
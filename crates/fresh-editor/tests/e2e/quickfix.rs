@@ -0,0 +1,80 @@
+//! E2E tests for quickfix list navigation
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use tempfile::TempDir;
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+}
+
+/// The watch task's problems become a quickfix list that F4 and Shift+F4
+/// step through, and the history picker lists it
+#[test]
+#[cfg_attr(not(unix), ignore = "Shell commands require Unix-like environment")]
+fn test_quickfix_steps_through_watch_problems() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().to_path_buf();
+    std::fs::write(project_root.join("a.txt"), "one\ntwo\n").unwrap();
+    std::fs::write(project_root.join("b.txt"), "hello\n").unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 30, Default::default(), project_root)
+            .unwrap();
+
+    run_command(&mut harness, "Toggle Watch Task");
+    harness.wait_for_prompt().unwrap();
+    harness
+        .type_text("printf 'a.txt:2:1: error: first\\nb.txt:1:3: warning: second\\n'; exit 1")
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("watch ✗ 2"))
+        .unwrap();
+
+    harness.send_key(KeyCode::F(4), KeyModifiers::NONE).unwrap();
+    harness.assert_screen_contains("(1 of 2) first");
+    harness.assert_buffer_content("one\ntwo\n");
+    assert_eq!(harness.cursor_position(), 4);
+
+    harness.send_key(KeyCode::F(4), KeyModifiers::NONE).unwrap();
+    harness.assert_screen_contains("(2 of 2) second");
+    harness.assert_buffer_content("hello\n");
+    assert_eq!(harness.cursor_position(), 2);
+
+    harness.send_key(KeyCode::F(4), KeyModifiers::NONE).unwrap();
+    harness.assert_screen_contains("Already at the last quickfix item");
+
+    harness
+        .send_key(KeyCode::F(4), KeyModifiers::SHIFT)
+        .unwrap();
+    harness.assert_screen_contains("(1 of 2) first");
+
+    run_command(&mut harness, "Quickfix: Older List");
+    harness.wait_for_prompt_closed().unwrap();
+    harness.assert_screen_contains("No older quickfix list");
+
+    run_command(&mut harness, "Quickfix: History");
+    harness.wait_for_prompt().unwrap();
+    harness.assert_screen_contains("watch: printf");
+    harness.assert_screen_contains("2 items");
+}
+
+/// Stepping without a quickfix list explains why nothing happens
+#[test]
+fn test_quickfix_next_without_list() {
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.send_key(KeyCode::F(4), KeyModifiers::NONE).unwrap();
+    harness.assert_screen_contains("No quickfix list");
+}
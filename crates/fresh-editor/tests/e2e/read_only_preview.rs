@@ -0,0 +1,91 @@
+//! E2E tests for read-only buffers and preview tabs
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use std::fs;
+
+/// Toggle Read-Only refuses typing until it is toggled off again
+#[test]
+fn test_toggle_read_only_blocks_typing() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 24).unwrap();
+    let file = harness.project_dir().unwrap().join("notes.txt");
+    fs::write(&file, "hello").unwrap();
+    harness.open_file(&file).unwrap();
+
+    harness.editor_mut().toggle_read_only();
+    harness.type_text("x").unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.get_buffer_content().unwrap(), "hello");
+    assert!(harness.get_status_bar().contains("[RO]"));
+
+    harness.editor_mut().toggle_read_only();
+    harness.type_text("x").unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.get_buffer_content().unwrap(), "xhello");
+    assert!(!harness.get_status_bar().contains("[RO]"));
+}
+
+/// A file marked as generated opens read-only, unless auto_read_only is off
+#[test]
+fn test_generated_file_opens_read_only() {
+    let content = "// @generated by build.rs\nfn main() {}\n";
+
+    let mut harness = EditorTestHarness::with_temp_project(120, 24).unwrap();
+    let file = harness.project_dir().unwrap().join("gen.rs");
+    fs::write(&file, content).unwrap();
+    harness.open_file(&file).unwrap();
+    harness.render().unwrap();
+    assert!(harness.editor().is_editing_disabled());
+    harness.assert_screen_contains("read-only: generated file");
+
+    let mut config = Config::default();
+    config.editor.auto_read_only = false;
+    let mut harness = EditorTestHarness::with_temp_project_and_config(100, 24, config).unwrap();
+    let file = harness.project_dir().unwrap().join("gen.rs");
+    fs::write(&file, content).unwrap();
+    harness.open_file(&file).unwrap();
+    assert!(!harness.editor().is_editing_disabled());
+}
+
+/// Single-click the explorer row showing `name`
+fn click_explorer_item(harness: &mut EditorTestHarness, name: &str) {
+    // Clicking anywhere on a row selects it
+    let (_, row) = harness.find_text_on_screen(name).unwrap();
+    harness.mouse_click(2, row).unwrap();
+    harness.render().unwrap();
+}
+
+/// Single-clicking a second file replaces the preview tab of the first,
+/// and an edited preview tab is kept
+#[test]
+fn test_preview_tab_is_replaced_by_next_preview() {
+    let mut config = Config::default();
+    config.editor.preview_tabs = true;
+    let mut harness = EditorTestHarness::with_temp_project_and_config(120, 30, config).unwrap();
+    let project_dir = harness.project_dir().unwrap();
+    fs::write(project_dir.join("alpha.txt"), "alpha").unwrap();
+    fs::write(project_dir.join("beta.txt"), "beta").unwrap();
+    fs::write(project_dir.join("gamma.txt"), "gamma").unwrap();
+
+    harness
+        .send_key(KeyCode::Char('e'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_file_explorer_item("gamma.txt").unwrap();
+
+    click_explorer_item(&mut harness, "alpha.txt");
+    assert!(harness.get_tab_bar().contains("alpha.txt"));
+
+    click_explorer_item(&mut harness, "beta.txt");
+    let tabs = harness.get_tab_bar();
+    assert!(tabs.contains("beta.txt"), "tabs: {tabs}");
+    assert!(!tabs.contains("alpha.txt"), "tabs: {tabs}");
+
+    // Editing keeps the preview tab
+    harness.editor_mut().focus_editor();
+    harness.type_text("x").unwrap();
+    click_explorer_item(&mut harness, "gamma.txt");
+    let tabs = harness.get_tab_bar();
+    assert!(tabs.contains("beta.txt"), "tabs: {tabs}");
+    assert!(tabs.contains("gamma.txt"), "tabs: {tabs}");
+}
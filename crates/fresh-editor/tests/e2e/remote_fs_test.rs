@@ -291,3 +291,90 @@ fn test_remote_large_file_edits_beginning_middle_end() {
         );
     }
 }
+
+/// Local and remote files can be open side by side; each buffer saves to its own filesystem
+#[test]
+fn test_mixed_local_and_remote_buffers() {
+    let Some((fs, remote_dir, _rt)) = create_test_filesystem() else {
+        eprintln!("Skipping test: could not create test filesystem");
+        return;
+    };
+    let remote_fs = Arc::new(fs);
+    let local_dir = tempfile::tempdir().unwrap();
+
+    let local_path = local_dir.path().join("notes.txt");
+    let remote_path = remote_dir.path().join("nginx.conf");
+    std::fs::write(&local_path, "local\n").unwrap();
+    std::fs::write(&remote_path, "remote\n").unwrap();
+
+    // Local primary filesystem with the remote host mounted next to it
+    let mut harness = EditorTestHarness::create(200, 24, HarnessOptions::new()).unwrap();
+    harness.editor_mut().mount_filesystem(remote_fs.clone());
+
+    harness.open_file(&local_path).unwrap();
+    let local_id = harness.editor().active_buffer();
+    harness.assert_screen_not_contains("[SSH:");
+
+    harness
+        .editor_mut()
+        .open_file_with_filesystem(&remote_path, remote_fs.clone())
+        .unwrap();
+    harness.render().unwrap();
+    let remote_id = harness.editor().active_buffer();
+    assert_ne!(local_id, remote_id);
+    harness.assert_screen_contains("[SSH:test@localhost]");
+
+    // Edit and save the remote buffer
+    harness.type_text("# ").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&remote_path).unwrap(), "# remote\n");
+
+    // Switching back to the local buffer drops the SSH indicator
+    harness.editor_mut().switch_buffer(local_id);
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("[SSH:");
+    harness.type_text("more ").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&local_path).unwrap(),
+        "more local\n"
+    );
+
+    // The same path on the remote host is a separate buffer from the local one
+    let shared_id = harness
+        .editor_mut()
+        .open_file_with_filesystem(&local_path, remote_fs)
+        .unwrap();
+    assert_ne!(shared_id, local_id);
+}
+
+/// The file explorer can switch between the local root and mounted hosts
+#[test]
+fn test_file_explorer_switches_between_mounted_roots() {
+    let Some((fs, _remote_dir, _rt)) = create_test_filesystem() else {
+        eprintln!("Skipping test: could not create test filesystem");
+        return;
+    };
+    let mut harness = EditorTestHarness::create(120, 24, HarnessOptions::new()).unwrap();
+
+    harness.editor_mut().file_explorer_next_root();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Only one filesystem is mounted");
+
+    harness.editor_mut().mount_filesystem(Arc::new(fs));
+    harness.editor_mut().show_file_explorer();
+    harness.editor_mut().file_explorer_next_root();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Explorer root: test@localhost");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("[localhost]"))
+        .unwrap();
+
+    harness.editor_mut().file_explorer_next_root();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Explorer root: local");
+}
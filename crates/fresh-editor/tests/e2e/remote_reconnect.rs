@@ -0,0 +1,298 @@
+//! E2E tests for remote connection loss: queued saves and reconnect handling
+
+use crate::common::harness::{EditorTestHarness, HarnessOptions};
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::model::filesystem::{
+    DirEntry, FileMetadata, FilePermissions, FileReader, FileSystem, FileWriter, StdFileSystem,
+};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Local filesystem that pretends to be remote and can simulate a dropped link.
+///
+/// While offline, every operation fails with `NotConnected`, which is what
+/// `RemoteFileSystem` reports when its SSH channel is down.
+struct FlakyRemoteFs {
+    inner: StdFileSystem,
+    online: Arc<AtomicBool>,
+}
+
+impl FlakyRemoteFs {
+    fn check(&self) -> io::Result<()> {
+        if self.online.load(Ordering::SeqCst) {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "Channel closed",
+            ))
+        }
+    }
+}
+
+impl FileSystem for FlakyRemoteFs {
+    fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.check()?;
+        self.inner.read_file(path)
+    }
+    fn read_range(&self, path: &Path, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        self.check()?;
+        self.inner.read_range(path, offset, len)
+    }
+    fn write_file(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.check()?;
+        self.inner.write_file(path, data)
+    }
+    fn create_file(&self, path: &Path) -> io::Result<Box<dyn FileWriter>> {
+        self.check()?;
+        self.inner.create_file(path)
+    }
+    fn open_file(&self, path: &Path) -> io::Result<Box<dyn FileReader>> {
+        self.check()?;
+        self.inner.open_file(path)
+    }
+    fn open_file_for_write(&self, path: &Path) -> io::Result<Box<dyn FileWriter>> {
+        self.check()?;
+        self.inner.open_file_for_write(path)
+    }
+    fn open_file_for_append(&self, path: &Path) -> io::Result<Box<dyn FileWriter>> {
+        self.check()?;
+        self.inner.open_file_for_append(path)
+    }
+    fn set_file_length(&self, path: &Path, len: u64) -> io::Result<()> {
+        self.check()?;
+        self.inner.set_file_length(path, len)
+    }
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check()?;
+        self.inner.rename(from, to)
+    }
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        self.check()?;
+        self.inner.copy(from, to)
+    }
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.check()?;
+        self.inner.remove_file(path)
+    }
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        self.check()?;
+        self.inner.remove_dir(path)
+    }
+    fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+        self.check()?;
+        self.inner.metadata(path)
+    }
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+        self.check()?;
+        self.inner.symlink_metadata(path)
+    }
+    fn is_dir(&self, path: &Path) -> io::Result<bool> {
+        self.check()?;
+        self.inner.is_dir(path)
+    }
+    fn is_file(&self, path: &Path) -> io::Result<bool> {
+        self.check()?;
+        self.inner.is_file(path)
+    }
+    fn set_permissions(&self, path: &Path, permissions: &FilePermissions) -> io::Result<()> {
+        self.check()?;
+        self.inner.set_permissions(path, permissions)
+    }
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
+        self.check()?;
+        self.inner.read_dir(path)
+    }
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.check()?;
+        self.inner.create_dir(path)
+    }
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.check()?;
+        self.inner.create_dir_all(path)
+    }
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.check()?;
+        self.inner.canonicalize(path)
+    }
+    fn current_uid(&self) -> u32 {
+        self.inner.current_uid()
+    }
+    fn remote_connection_info(&self) -> Option<&str> {
+        Some("tester@flaky")
+    }
+    fn is_remote_connected(&self) -> bool {
+        self.online.load(Ordering::SeqCst)
+    }
+    fn sudo_write(
+        &self,
+        program: &str,
+        path: &Path,
+        data: &[u8],
+        mode: u32,
+        uid: u32,
+        gid: u32,
+    ) -> io::Result<()> {
+        self.check()?;
+        self.inner.sudo_write(program, path, data, mode, uid, gid)
+    }
+}
+
+fn flaky_harness(online: Arc<AtomicBool>) -> EditorTestHarness {
+    let fs = Arc::new(FlakyRemoteFs {
+        inner: StdFileSystem,
+        online,
+    });
+    EditorTestHarness::create(200, 24, HarnessOptions::new().with_filesystem(fs)).unwrap()
+}
+
+/// Saving while the link is down queues the save and flushes it after reconnect
+#[test]
+fn test_save_queued_while_offline_and_flushed_on_reconnect() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    std::fs::write(&file_path, "original\n").unwrap();
+
+    let online = Arc::new(AtomicBool::new(true));
+    let mut harness = flaky_harness(online.clone());
+    harness.open_file(&file_path).unwrap();
+    harness.type_text("edited ").unwrap();
+
+    // Drop the link and try to save
+    online.store(false, Ordering::SeqCst);
+    harness.process_async_and_render().unwrap();
+    harness.assert_screen_contains("reconnecting");
+
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_screen_contains("save queued");
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "original\n");
+
+    // Link comes back: the queued save goes through without user action
+    online.store(true, Ordering::SeqCst);
+    harness.process_async_and_render().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&file_path).unwrap(),
+        "edited original\n"
+    );
+    harness.assert_screen_not_contains("reconnecting");
+}
+
+/// If the remote file changed during the outage, the user is asked first
+#[test]
+fn test_queued_save_prompts_when_remote_changed_during_outage() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("config.yml");
+    std::fs::write(&file_path, "a: 1\n").unwrap();
+
+    let online = Arc::new(AtomicBool::new(true));
+    let mut harness = flaky_harness(online.clone());
+    harness.open_file(&file_path).unwrap();
+    harness.type_text("# local\n").unwrap();
+
+    online.store(false, Ordering::SeqCst);
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    // Someone else edits the file on the remote while we're disconnected
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    std::fs::write(&file_path, "a: 2\n").unwrap();
+
+    online.store(true, Ordering::SeqCst);
+    harness.process_async_and_render().unwrap();
+    harness.assert_screen_contains("changed while disconnected");
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "a: 2\n");
+
+    // Overwrite
+    harness.type_text("o").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&file_path).unwrap(),
+        "# local\na: 1\n"
+    );
+}
+
+/// Flushing a queued save on reconnect leaves the user in the buffer they
+/// switched to during the outage
+#[test]
+fn test_reconnect_flush_keeps_active_buffer() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let queued_path = temp_dir.path().join("queued.txt");
+    let other_path = temp_dir.path().join("other.txt");
+    std::fs::write(&queued_path, "original\n").unwrap();
+    std::fs::write(&other_path, "other\n").unwrap();
+
+    let online = Arc::new(AtomicBool::new(true));
+    let mut harness = flaky_harness(online.clone());
+    harness.open_file(&queued_path).unwrap();
+    harness.type_text("edited ").unwrap();
+
+    online.store(false, Ordering::SeqCst);
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_screen_contains("save queued");
+
+    // Move on to another file before the link comes back
+    online.store(true, Ordering::SeqCst);
+    harness.open_file(&other_path).unwrap();
+    let active = harness.editor().active_buffer();
+
+    harness.process_async_and_render().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&queued_path).unwrap(),
+        "edited original\n"
+    );
+    assert_eq!(harness.editor().active_buffer(), active);
+    harness.assert_buffer_content("other\n");
+}
+
+/// Overwriting after a conflict prompt saves the queued buffer, not the
+/// active one
+#[test]
+fn test_reconnect_conflict_overwrite_saves_queued_buffer() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let queued_path = temp_dir.path().join("config.yml");
+    let other_path = temp_dir.path().join("other.txt");
+    std::fs::write(&queued_path, "a: 1\n").unwrap();
+    std::fs::write(&other_path, "other\n").unwrap();
+
+    let online = Arc::new(AtomicBool::new(true));
+    let mut harness = flaky_harness(online.clone());
+    harness.open_file(&queued_path).unwrap();
+    harness.type_text("# local\n").unwrap();
+
+    online.store(false, Ordering::SeqCst);
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    // Someone else edits the file on the remote while we're disconnected
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    std::fs::write(&queued_path, "a: 2\n").unwrap();
+
+    online.store(true, Ordering::SeqCst);
+    harness.open_file(&other_path).unwrap();
+    harness.type_text("x").unwrap();
+    let active = harness.editor().active_buffer();
+
+    harness.process_async_and_render().unwrap();
+    harness.assert_screen_contains("config.yml: Remote file changed");
+
+    harness.type_text("o").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&queued_path).unwrap(),
+        "# local\na: 1\n"
+    );
+    assert_eq!(std::fs::read_to_string(&other_path).unwrap(), "other\n");
+    assert_eq!(harness.editor().active_buffer(), active);
+}
@@ -0,0 +1,117 @@
+//! E2E tests for whitespace indicators and control character rendering
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::{Config, RenderWhitespace};
+
+fn harness_with(mode: RenderWhitespace) -> EditorTestHarness {
+    let mut config = Config::default();
+    config.editor.render_whitespace = mode;
+    EditorTestHarness::with_config(80, 24, config).unwrap()
+}
+
+/// In "all" mode every space is drawn as ·, a tab as → and a non-breaking
+/// space as ␣, all in the theme's whitespace color
+#[test]
+fn test_render_whitespace_all() {
+    let mut harness = harness_with(RenderWhitespace::All);
+    let _fixture = harness.load_buffer_from_text("a  b\tc\u{A0}d \n").unwrap();
+    harness.render().unwrap();
+
+    let (x, y) = harness.find_text_on_screen("a··b→").unwrap();
+    harness.assert_screen_contains("c␣d·");
+
+    let whitespace_fg = harness.editor().theme().whitespace_fg;
+    let dot = harness.get_cell_style(x + 1, y).unwrap();
+    assert_eq!(dot.fg, Some(whitespace_fg));
+    let letter = harness.get_cell_style(x, y).unwrap();
+    assert_ne!(letter.fg, Some(whitespace_fg));
+}
+
+/// "boundary" leaves single spaces between words alone; "trailing" only
+/// marks the end of the line
+#[test]
+fn test_render_whitespace_boundary_and_trailing() {
+    let mut harness = harness_with(RenderWhitespace::Boundary);
+    let _fixture = harness
+        .load_buffer_from_text("  one two  three  \n")
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("··one two··three··");
+
+    let mut harness = harness_with(RenderWhitespace::Trailing);
+    let _fixture = harness
+        .load_buffer_from_text("  one two  three  \n")
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("  one two  three··");
+}
+
+/// "selection" marks only the spaces inside the selection
+#[test]
+fn test_render_whitespace_selection() {
+    let mut harness = harness_with(RenderWhitespace::Selection);
+    let _fixture = harness.load_buffer_from_text("a b c d\n").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("a b c d");
+
+    for _ in 0..4 {
+        harness
+            .send_key(KeyCode::Right, KeyModifiers::SHIFT)
+            .unwrap();
+    }
+    harness.render().unwrap();
+    harness.assert_screen_contains("a·b·c d");
+}
+
+/// Render Whitespace: Cycle steps through the modes
+#[test]
+fn test_cycle_render_whitespace_command() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    let _fixture = harness.load_buffer_from_text("x  y\n").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("x  y");
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text("Render Whitespace: Cycle").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("Render whitespace: boundary");
+    harness.assert_screen_contains("x··y");
+}
+
+/// Control characters in text files are shown in caret notation, and the
+/// cursor steps over them as one character
+#[test]
+fn test_control_characters_in_caret_notation() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    let _fixture = harness.load_buffer_from_text("ab\x0Ccd\n").unwrap();
+    harness.render().unwrap();
+
+    let (x, y) = harness.find_text_on_screen("ab^Lcd").unwrap();
+    let whitespace_fg = harness.editor().theme().whitespace_fg;
+    assert_eq!(
+        harness.get_cell_style(x + 2, y).unwrap().fg,
+        Some(whitespace_fg)
+    );
+    assert_eq!(
+        harness.get_cell_style(x + 3, y).unwrap().fg,
+        Some(whitespace_fg)
+    );
+
+    for _ in 0..3 {
+        harness
+            .send_key(KeyCode::Right, KeyModifiers::NONE)
+            .unwrap();
+    }
+    harness.render().unwrap();
+    assert_eq!(harness.cursor_position(), 3);
+    assert_eq!(harness.screen_cursor_position().0, x + 4);
+}
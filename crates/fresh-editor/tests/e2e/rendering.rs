@@ -590,3 +590,36 @@ fn test_ansi_rgb_color_rendering() {
         );
     }
 }
+
+/// The render profiler overlay shows per-phase timings once a frame has been
+/// measured, and goes away when toggled off
+#[test]
+fn test_render_profiler_overlay_toggles() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut harness = EditorTestHarness::new(100, 30).unwrap();
+    harness.type_text("fn main() {}").unwrap();
+
+    let toggle = |harness: &mut EditorTestHarness| {
+        harness
+            .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.type_text("Toggle Render Profiler").unwrap();
+        harness
+            .send_key(KeyCode::Enter, KeyModifiers::NONE)
+            .unwrap();
+    };
+
+    toggle(&mut harness);
+    harness.render().unwrap();
+    harness.assert_screen_contains("Render profile ─");
+    for phase in ["events", "highlight", "layout", "draw", "total"] {
+        harness.assert_screen_contains(phase);
+    }
+    harness.assert_screen_contains("over 16ms");
+
+    toggle(&mut harness);
+    harness.assert_screen_not_contains("Render profile ─");
+    harness.assert_screen_not_contains("over 16ms");
+    harness.assert_screen_contains("Render profiler OFF");
+}
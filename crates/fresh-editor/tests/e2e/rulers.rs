@@ -0,0 +1,58 @@
+//! E2E tests for rulers and wrap guide shading
+
+use crate::common::harness::EditorTestHarness;
+use fresh::config::Config;
+
+fn harness_with(rulers: Vec<usize>, shading: bool) -> EditorTestHarness {
+    let mut config = Config::default();
+    config.editor.rulers = rulers;
+    config.editor.wrap_guide_shading = shading;
+    EditorTestHarness::with_config(80, 24, config).unwrap()
+}
+
+/// Rulers are drawn in the theme's ruler color in empty cells only
+#[test]
+fn test_rulers_use_theme_color() {
+    let mut harness = harness_with(vec![10], false);
+    let _fixture = harness
+        .load_buffer_from_text("this line is longer than ten\nshort\n")
+        .unwrap();
+    harness.render().unwrap();
+
+    let (x, y) = harness.find_text_on_screen("this line").unwrap();
+    // Text over the ruler column stays visible
+    assert_eq!(harness.get_cell(x + 10, y).as_deref(), Some("i"));
+    assert_eq!(harness.get_cell(x + 10, y + 1).as_deref(), Some("│"));
+    let ruler = harness.get_cell_style(x + 10, y + 1).unwrap();
+    assert_eq!(ruler.fg, Some(harness.editor().theme().ruler_fg));
+
+    // No shading unless enabled
+    let wrap_guide_bg = harness.editor().theme().wrap_guide_bg;
+    let past = harness.get_cell_style(x + 20, y + 1).unwrap();
+    assert_ne!(past.bg, Some(wrap_guide_bg));
+}
+
+/// With shading on, everything from the last ruler onward gets the wrap
+/// guide background
+#[test]
+fn test_wrap_guide_shading_past_last_ruler() {
+    let mut harness = harness_with(vec![20, 10], true);
+    let _fixture = harness
+        .load_buffer_from_text("this line is longer than ten\nshort\n")
+        .unwrap();
+    harness.render().unwrap();
+
+    let (x, y) = harness.find_text_on_screen("this line").unwrap();
+    let wrap_guide_bg = harness.editor().theme().wrap_guide_bg;
+    let bg_at = |harness: &EditorTestHarness, col: u16, row: u16| {
+        harness.get_cell_style(col, row).unwrap().bg
+    };
+
+    // Shading starts at the last (rightmost) ruler, not the first listed
+    assert_ne!(bg_at(&harness, x + 15, y + 1), Some(wrap_guide_bg));
+    assert_eq!(bg_at(&harness, x + 20, y + 1), Some(wrap_guide_bg));
+    assert_eq!(bg_at(&harness, x + 40, y + 1), Some(wrap_guide_bg));
+    // Text past the ruler is shaded too
+    assert_eq!(harness.get_cell(x + 22, y).as_deref(), Some("a"));
+    assert_eq!(bg_at(&harness, x + 22, y), Some(wrap_guide_bg));
+}
@@ -2514,3 +2514,112 @@ fn test_enter_resets_horizontal_scroll() {
 
     println!("\n✓ Enter correctly reset horizontal scroll");
 }
+
+fn animated_harness() -> EditorTestHarness {
+    use crate::common::harness::HarnessOptions;
+    EditorTestHarness::create(80, 24, HarnessOptions::new().with_animations()).unwrap()
+}
+
+/// Page Down scrolls smoothly towards the position the viewport snaps to
+/// without animations
+#[test]
+fn test_page_down_scrolls_smoothly() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use std::time::Duration;
+
+    let content = (0..200)
+        .map(|i| format!("Line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut snapped = EditorTestHarness::new(80, 24).unwrap();
+    let _fixture = snapped.load_buffer_from_text(&content).unwrap();
+    snapped.render().unwrap();
+    snapped
+        .send_key(KeyCode::PageDown, KeyModifiers::NONE)
+        .unwrap();
+    let target_top = snapped.editor().active_viewport().top_byte;
+    assert!(target_top > 0);
+
+    let mut harness = animated_harness();
+    let _fixture = harness.load_buffer_from_text(&content).unwrap();
+    harness.render().unwrap();
+    harness
+        .send_key(KeyCode::PageDown, KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(harness.editor().active_viewport().top_byte, 0);
+    assert_eq!(harness.cursor_position(), snapped.cursor_position());
+
+    harness.advance_time(Duration::from_millis(50));
+    let _ = harness.editor_mut().process_async_messages();
+    harness.render().unwrap();
+    let midway = harness.editor().active_viewport().top_byte;
+    assert!(midway > 0 && midway < target_top, "midway top {midway}");
+
+    harness.advance_time(Duration::from_millis(200));
+    assert!(harness.editor_mut().process_async_messages());
+    harness.render().unwrap();
+    assert_eq!(harness.editor().active_viewport().top_byte, target_top);
+    assert!(!harness.editor_mut().process_async_messages());
+}
+
+/// A key press during a smooth scroll finishes it immediately
+#[test]
+fn test_key_press_finishes_smooth_scroll() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let content = (0..200)
+        .map(|i| format!("Line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut harness = animated_harness();
+    let _fixture = harness.load_buffer_from_text(&content).unwrap();
+    harness.render().unwrap();
+
+    harness
+        .send_key(KeyCode::PageDown, KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(harness.editor().active_viewport().top_byte, 0);
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    assert!(harness.editor().active_viewport().top_byte > 0);
+}
+
+/// Jumping further than a screen flashes the new cursor line, fading back to
+/// the normal background
+#[test]
+fn test_long_jump_flashes_cursor_line() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use std::time::Duration;
+
+    let content = (0..200)
+        .map(|i| format!("Line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut harness = animated_harness();
+    let _fixture = harness.load_buffer_from_text(&content).unwrap();
+    harness.render().unwrap();
+
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    let (_, cursor_y) = harness.screen_cursor_position();
+    let flash_bg = harness.get_cell_style(40, cursor_y).and_then(|s| s.bg);
+
+    harness.advance_time(Duration::from_millis(500));
+    let _ = harness.editor_mut().process_async_messages();
+    harness.render().unwrap();
+    let settled_bg = harness.get_cell_style(40, cursor_y).and_then(|s| s.bg);
+    assert_ne!(flash_bg, settled_bg);
+
+    // Short moves don't flash
+    harness.send_key(KeyCode::Up, KeyModifiers::NONE).unwrap();
+    let (_, cursor_y) = harness.screen_cursor_position();
+    let before = harness.get_cell_style(40, cursor_y).and_then(|s| s.bg);
+    harness.advance_time(Duration::from_millis(500));
+    let _ = harness.editor_mut().process_async_messages();
+    harness.render().unwrap();
+    assert_eq!(
+        harness.get_cell_style(40, cursor_y).and_then(|s| s.bg),
+        before
+    );
+}
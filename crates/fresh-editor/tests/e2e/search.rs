@@ -2435,3 +2435,139 @@ fn test_esc_clears_search_highlights() {
         highlight_count_after
     );
 }
+
+/// Test regex replace with capture groups and a case escape, with the
+/// replacement previewed while it is typed
+#[test]
+fn test_regex_replace_with_capture_groups() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("read_file write_data").unwrap();
+
+    // Replace with Ctrl+R, regex mode with Alt+R
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::ALT)
+        .unwrap();
+    harness.type_text(r"(\w+)_(\w+)").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text(r"$1\u$2").unwrap();
+    harness.render().unwrap();
+
+    // Each visible match shows what it becomes
+    harness.assert_screen_contains("read_file→readFile");
+    harness.assert_screen_contains("write_data→writeData");
+
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("readFile writeData");
+    harness.assert_screen_not_contains("→");
+}
+
+/// Test that interactive replace expands the replacement for each match
+#[test]
+fn test_interactive_regex_replace() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("a1 b22 c3").unwrap();
+    harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+
+    harness
+        .send_key(
+            KeyCode::Char('r'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+        )
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::ALT)
+        .unwrap();
+    harness.type_text(r"\d+").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("<$0>").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.cursor_position(), 1);
+    harness.assert_screen_contains("Replace? (y)es (n)o (a)ll (c)ancel");
+
+    // Replace "1", skip "22", replace the rest
+    harness.type_text("y").unwrap();
+    assert_eq!(harness.cursor_position(), "a<1> b".len());
+    harness.type_text("n").unwrap();
+    harness.type_text("a").unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("a<1> b22 c<3>");
+}
+
+/// Test that structural search lists code matching a pattern, whatever its layout
+#[test]
+fn test_structural_search_lists_matches() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("checks.js");
+    std::fs::write(
+        &file_path,
+        "if (ok) { return 1; }\nif (ready)\n{\n  return 2;\n}\nif (done) { log(); return 3; }\n",
+    )
+    .unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness
+        .editor_mut()
+        .structural_search("if ($a) { return $b; }");
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("checks.js:1:1");
+    harness.assert_screen_contains("checks.js:2:1");
+    harness.assert_screen_not_contains("checks.js:6:1");
+    harness.assert_screen_contains("Found 2 match(es)");
+}
+
+/// Test that structural replace previews the changes, asks, and applies them
+/// as one undo step
+#[test]
+fn test_structural_replace() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("calls.rs");
+    let original = "fn main() {\n    foo(1, 2);\n    foo();\n    bar(3);\n}\n";
+    std::fs::write(&file_path, original).unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness
+        .editor_mut()
+        .structural_replace("foo($$$args)", "baz($$$args)");
+    harness.render().unwrap();
+
+    // The diff is shown before anything changes
+    harness.assert_screen_contains("-foo(1, 2)");
+    harness.assert_screen_contains("+baz(1, 2)");
+    harness.assert_screen_contains("Apply 2 replacement(s)? (y/n)");
+
+    harness.type_text("y").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("fn main() {\n    baz(1, 2);\n    baz();\n    bar(3);\n}\n");
+
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content(original);
+
+    // A replacement that would break the syntax is refused
+    harness
+        .editor_mut()
+        .structural_replace("foo($$$args)", "baz($$$args");
+    harness.render().unwrap();
+    harness.assert_screen_contains("the result would have syntax errors");
+    harness.assert_buffer_content(original);
+}
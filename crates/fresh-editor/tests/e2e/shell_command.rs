@@ -5,6 +5,7 @@
 //! - Output to new buffer vs replace mode
 //! - Selection vs entire buffer
 //! - Command failure handling
+//! - Filter Through Command on a selection
 
 use crate::common::harness::EditorTestHarness;
 use crossterm::event::{KeyCode, KeyModifiers};
@@ -474,3 +475,64 @@ fn test_shell_command_replace_clamps_cursor_when_buffer_shrinks() {
         "Cursor should be clamped to new buffer length"
     );
 }
+
+/// Run Filter Through Command from the command palette with `command`
+fn filter_through_command(harness: &mut EditorTestHarness, command: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text("filter through command").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text(command).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+    harness.render().unwrap();
+}
+
+/// Test Filter Through Command replacing only the selected lines
+#[test]
+#[cfg_attr(not(unix), ignore = "Shell commands require Unix-like environment")]
+fn test_filter_through_command_replaces_selection() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("lines.txt");
+    std::fs::write(&file_path, "cherry\napple\nbanana\nzucchini\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+
+    // Select the first three lines
+    for _ in 0..3 {
+        harness
+            .send_key(KeyCode::Down, KeyModifiers::SHIFT)
+            .unwrap();
+    }
+    filter_through_command(&mut harness, "sort");
+
+    harness.assert_buffer_content("apple\nbanana\ncherry\nzucchini\n");
+}
+
+/// Test that a failing filter leaves the buffer alone and shows its stderr
+#[test]
+#[cfg_attr(not(unix), ignore = "Shell commands require Unix-like environment")]
+fn test_filter_through_command_failure_shows_stderr() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.json");
+    std::fs::write(&file_path, "{not json\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+
+    filter_through_command(&mut harness, "echo 'parse error at line 1' >&2; exit 3");
+
+    harness.assert_buffer_content("{not json\n");
+    harness.assert_screen_contains("parse error at line 1");
+    harness.assert_screen_contains("exit code 3");
+}
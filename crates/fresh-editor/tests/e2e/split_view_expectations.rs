@@ -322,6 +322,56 @@ fn test_split_navigation_circular() {
     assert_eq!(harness.cursor_position(), cursor_third);
 }
 
+/// Test that "Toggle Scroll Lock" scrolls two views of one buffer in parallel,
+/// keeping the distance between them
+#[test]
+fn test_scroll_lock_keeps_split_offset() {
+    let mut harness = EditorTestHarness::new(120, 30).unwrap();
+
+    // Fixed-width lines so top_byte / 9 is the top line
+    let long_text = (0..200)
+        .map(|i| format!("Line {:03}", i))
+        .collect::<Vec<_>>()
+        .join("\n");
+    harness.type_text(&long_text).unwrap();
+    harness
+        .send_key(KeyCode::Home, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.top_byte(), 0);
+
+    // Move the second view further down the file, then lock scrolling
+    split_vertical(&mut harness);
+    for _ in 0..60 {
+        harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    }
+    harness.render().unwrap();
+    let locked_top = harness.top_byte() / 9;
+    assert!(locked_top > 0);
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.type_text("toggle scroll lock").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Scrolling locked");
+
+    harness
+        .send_key(KeyCode::PageDown, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    let scrolled = harness.top_byte() / 9 - locked_top;
+    assert!(scrolled > 0);
+
+    // The first view followed by the same number of lines
+    prev_split(&mut harness);
+    assert_eq!(harness.top_byte() / 9, scrolled);
+}
+
 /// Test that closing a split expands the remaining split
 #[test]
 fn test_close_split_expands_remaining() {
@@ -0,0 +1,67 @@
+//! E2E tests for the configurable status line (segment specs and plugin segments)
+
+use crate::common::harness::EditorTestHarness;
+use fresh::config::Config;
+use fresh::services::plugins::api::PluginCommand;
+use std::time::Duration;
+
+fn status_line_config(left: &[&str], center: &[&str], right: &[&str]) -> Config {
+    let to_vec = |specs: &[&str]| specs.iter().map(|s| s.to_string()).collect();
+    let mut config = Config::default();
+    config.status_line.left = to_vec(left);
+    config.status_line.center = to_vec(center);
+    config.status_line.right = to_vec(right);
+    config
+}
+
+/// Segments are placed in their groups, and the right group is right-aligned
+#[test]
+fn test_status_line_custom_layout() {
+    let config = status_line_config(&["filename", "git_branch(bold)"], &[], &["position"]);
+    let mut harness = EditorTestHarness::with_temp_project_and_config(80, 24, config).unwrap();
+    let project_dir = harness.project_dir().unwrap();
+    std::fs::create_dir_all(project_dir.join(".git")).unwrap();
+    std::fs::write(project_dir.join(".git/HEAD"), "ref: refs/heads/topic\n").unwrap();
+    let file_path = project_dir.join("main.rs");
+    std::fs::write(&file_path, "fn main() {}\n").unwrap();
+    harness.open_file(&file_path).unwrap();
+    // The branch is re-read on the auto-revert poll interval
+    harness.advance_time(Duration::from_secs(5));
+    harness.process_async_and_render().unwrap();
+
+    let status = harness.get_status_bar();
+    assert!(status.starts_with(" main.rs  topic "), "status: {status:?}");
+    assert!(status.ends_with(" Ln 1, Col 1 "), "status: {status:?}");
+    // Segments that aren't configured are not shown
+    assert!(!status.contains("LF"), "status: {status:?}");
+}
+
+/// Plugins publish text for `plugin:<id>` segments; empty text hides them
+#[test]
+fn test_status_line_plugin_segment() {
+    let config = status_line_config(&["filename"], &["plugin:clock"], &["language"]);
+    let mut harness = EditorTestHarness::with_temp_project_and_config(80, 24, config).unwrap();
+    let file_path = harness.project_dir().unwrap().join("notes.txt");
+    std::fs::write(&file_path, "hello\n").unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness
+        .editor_mut()
+        .handle_plugin_command(PluginCommand::SetStatusSegment {
+            id: "clock".to_string(),
+            text: "12:34".to_string(),
+        })
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains(" 12:34 ");
+
+    harness
+        .editor_mut()
+        .handle_plugin_command(PluginCommand::SetStatusSegment {
+            id: "clock".to_string(),
+            text: String::new(),
+        })
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("12:34");
+}
@@ -283,6 +283,9 @@ fn test_save_root_owned_file_shows_sudo_prompt() {
     harness.open_file(&file_path).unwrap();
     harness.render().unwrap();
 
+    // Unwritable files open read-only; make it editable to get to the save
+    harness.editor_mut().toggle_read_only();
+
     // Modify the content (add a space at the beginning)
     harness.type_text(" ").unwrap();
     harness.render().unwrap();
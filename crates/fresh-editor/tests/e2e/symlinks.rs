@@ -10,6 +10,7 @@
 
 use crate::common::harness::{EditorTestHarness, HarnessOptions};
 use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::{Config, OpenSymlinks};
 use std::fs;
 use std::os::unix::fs::symlink;
 
@@ -424,3 +425,95 @@ fn test_symlink_canonicalization_consistency() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Test: Two hard links to one file open in a single buffer, and saving keeps them linked
+#[test]
+fn test_hard_links_share_a_buffer_and_stay_linked() -> anyhow::Result<()> {
+    let mut harness = EditorTestHarness::with_temp_project(100, 30)?;
+    let project_dir = harness.project_dir().unwrap();
+
+    let first = project_dir.join("first.txt");
+    fs::write(&first, "shared")?;
+    let second = project_dir.join("second.txt");
+    fs::hard_link(&first, &second)?;
+
+    harness.open_file(&first)?;
+    harness.open_file(&second)?;
+    harness.render()?;
+
+    let tab_bar = harness.get_tab_bar();
+    assert!(
+        !tab_bar.contains("second.txt"),
+        "Hard links should share one buffer. Tab bar: {}",
+        tab_bar
+    );
+    harness.assert_screen_contains("is a hard link to");
+
+    harness.send_key(KeyCode::End, KeyModifiers::CONTROL)?;
+    harness.type_text(" content")?;
+    harness.send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)?;
+    harness.process_async_and_render()?;
+
+    // Written in place, so the other name sees the change
+    assert_eq!(fs::read_to_string(&second)?, "shared content");
+
+    Ok(())
+}
+
+/// Test: With `open_symlinks` set to "link", the buffer keeps the link's path
+#[test]
+fn test_open_symlinks_link_keeps_link_path() -> anyhow::Result<()> {
+    let mut config = Config::default();
+    config.editor.open_symlinks = OpenSymlinks::Link;
+    let mut harness = EditorTestHarness::with_temp_project_and_config(100, 30, config)?;
+    let project_dir = harness.project_dir().unwrap();
+
+    let real_file = project_dir.join("real.txt");
+    fs::write(&real_file, "real")?;
+    let link = project_dir.join("alias.txt");
+    symlink(&real_file, &link)?;
+
+    harness.open_file(&link)?;
+    harness.render()?;
+    assert!(harness.get_tab_bar().contains("alias.txt"));
+
+    // Opening the target lands on the same buffer
+    harness.editor_mut().open_file(&real_file)?;
+    harness.render()?;
+    assert!(!harness.get_tab_bar().contains("real.txt"));
+
+    harness.send_key(KeyCode::End, KeyModifiers::CONTROL)?;
+    harness.type_text(" edited")?;
+    harness.send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)?;
+    harness.process_async_and_render()?;
+
+    assert_eq!(fs::read_to_string(&real_file)?, "real edited");
+    assert!(fs::symlink_metadata(&link)?.file_type().is_symlink());
+
+    Ok(())
+}
+
+/// Test: Saving through a dangling symlink asks first, since it replaces the link
+#[test]
+fn test_save_through_dangling_symlink_asks() -> anyhow::Result<()> {
+    let mut config = Config::default();
+    config.editor.open_symlinks = OpenSymlinks::Link;
+    let mut harness = EditorTestHarness::with_temp_project_and_config(120, 30, config)?;
+    let project_dir = harness.project_dir().unwrap();
+
+    let link = project_dir.join("dangling.txt");
+    symlink(project_dir.join("missing.txt"), &link)?;
+
+    harness.open_file(&link)?;
+    harness.type_text("text")?;
+    harness.send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)?;
+    harness.render()?;
+    harness.assert_screen_contains("is missing");
+
+    // Declining leaves the link alone
+    harness.send_key(KeyCode::Enter, KeyModifiers::NONE)?;
+    harness.render()?;
+    assert!(fs::symlink_metadata(&link)?.file_type().is_symlink());
+
+    Ok(())
+}
@@ -61,6 +61,17 @@ fn setup_multi_file_harness() -> (EditorTestHarness, TempDir, Vec<std::path::Pat
     (harness, temp_dir, files)
 }
 
+/// Content rect of the given split from the last render
+fn split_rect(harness: &EditorTestHarness, split_id: SplitId) -> ratatui::layout::Rect {
+    harness
+        .editor()
+        .get_split_areas()
+        .iter()
+        .find(|(id, ..)| *id == split_id)
+        .map(|(_, _, rect, ..)| *rect)
+        .expect("split should be laid out")
+}
+
 /// Test that dragging a tab to the right edge creates a vertical split
 #[test]
 fn test_drag_tab_to_right_creates_vertical_split() {
@@ -174,6 +185,13 @@ fn test_drag_tab_to_left_creates_vertical_split() {
         harness.editor().get_split_buffer(new_active_split),
         Some(buffer_id)
     );
+
+    // The new pane is placed left of the pane it was dropped on
+    harness.render().unwrap();
+    assert!(
+        split_rect(&harness, new_active_split).x < split_rect(&harness, source_split_id).x,
+        "New split should be left of the original"
+    );
 }
 
 /// Test that dragging a tab to the top edge creates a horizontal split
@@ -224,6 +242,13 @@ fn test_drag_tab_to_top_creates_horizontal_split() {
         harness.editor().get_split_buffer(new_active_split),
         Some(buffer_id)
     );
+
+    // The new pane is placed above the pane it was dropped on
+    harness.render().unwrap();
+    assert!(
+        split_rect(&harness, new_active_split).y < split_rect(&harness, source_split_id).y,
+        "New split should be above the original"
+    );
 }
 
 /// Test that dragging a tab to the bottom edge creates a horizontal split
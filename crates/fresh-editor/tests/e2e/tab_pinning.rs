@@ -0,0 +1,86 @@
+//! E2E tests for pinned tabs and tab groups
+
+use crate::common::harness::EditorTestHarness;
+use fresh::view::split::TabGroup;
+use tempfile::TempDir;
+
+fn open_three_files(harness: &mut EditorTestHarness, temp_dir: &TempDir) {
+    for name in ["alpha.txt", "beta.txt", "gamma.txt"] {
+        let path = temp_dir.path().join(name);
+        std::fs::write(&path, name).unwrap();
+        harness.open_file(&path).unwrap();
+    }
+    harness.render().unwrap();
+}
+
+/// Pinning a tab moves it to the front, marks it, and protects it from Close Others
+#[test]
+fn test_pinned_tab_survives_close_others() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    open_three_files(&mut harness, &temp_dir);
+
+    let split_id = harness.editor().get_active_split();
+    let tabs = harness.editor().get_split_tabs(split_id);
+    let beta = tabs[tabs.len() - 2];
+    let gamma = tabs[tabs.len() - 1];
+
+    harness.editor_mut().toggle_pin_tab_in_split(beta, split_id);
+    harness.render().unwrap();
+
+    let tabs = harness.editor().get_split_tabs(split_id);
+    assert_eq!(tabs[0], beta, "pinned tab should move to the front");
+
+    let tab_bar = harness.get_tab_bar();
+    assert!(
+        tab_bar.contains("beta.txt •"),
+        "pinned tab should show the pin marker instead of a close button: {tab_bar}"
+    );
+
+    harness
+        .editor_mut()
+        .close_other_tabs_in_split(gamma, split_id);
+    harness.render().unwrap();
+
+    let tabs = harness.editor().get_split_tabs(split_id);
+    assert_eq!(tabs, vec![beta, gamma]);
+    harness.assert_screen_contains("beta.txt");
+    harness.assert_screen_not_contains("alpha.txt");
+}
+
+/// Tabs assigned to the same group are kept next to each other
+#[test]
+fn test_tab_group_keeps_tabs_together() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    open_three_files(&mut harness, &temp_dir);
+
+    let split_id = harness.editor().get_active_split();
+    let tabs = harness.editor().get_split_tabs(split_id);
+    let alpha = tabs[tabs.len() - 3];
+    let beta = tabs[tabs.len() - 2];
+    let gamma = tabs[tabs.len() - 1];
+
+    harness
+        .editor_mut()
+        .set_tab_group_in_split(alpha, split_id, Some(TabGroup::Blue));
+    harness
+        .editor_mut()
+        .set_tab_group_in_split(gamma, split_id, Some(TabGroup::Blue));
+    harness.render().unwrap();
+
+    let tabs = harness.editor().get_split_tabs(split_id);
+    let alpha_pos = tabs.iter().position(|&id| id == alpha).unwrap();
+    assert_eq!(
+        tabs[alpha_pos + 1],
+        gamma,
+        "grouped tabs should be adjacent"
+    );
+    assert_eq!(tabs[alpha_pos + 2], beta);
+
+    let tab_bar = harness.get_tab_bar();
+    assert!(
+        tab_bar.contains("▎alpha.txt"),
+        "grouped tab should show the group marker: {tab_bar}"
+    );
+}
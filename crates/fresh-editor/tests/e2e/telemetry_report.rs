@@ -0,0 +1,28 @@
+//! E2E tests for **Telemetry: Show Collected Data**
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::{Config, TelemetryLevel};
+
+/// The report shows the settings in effect and that nothing is sent without update checks
+#[test]
+fn test_telemetry_report_shows_settings() {
+    let mut config = Config::default();
+    config.telemetry.level = TelemetryLevel::Crashes;
+    config.telemetry.local_only = true;
+    let mut harness = EditorTestHarness::with_temp_project_and_config(120, 30, config).unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Show Collected Data").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("*Telemetry Data*");
+    harness.assert_screen_contains("crashes");
+    harness.assert_screen_contains("(local only)");
+    harness.assert_screen_contains("Nothing: telemetry is only sent with update checks.");
+}
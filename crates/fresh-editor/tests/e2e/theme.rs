@@ -291,3 +291,82 @@ fn test_theme_with_underscore_variant() {
     // Should still load high-contrast theme (accepts both - and _)
     assert_eq!(theme.name, "high-contrast");
 }
+
+#[test]
+fn test_light_dark_theme_pair_follows_appearance() {
+    use fresh::config::Appearance;
+
+    let config: Config =
+        serde_json::from_str(r#"{"theme": {"light": "light", "dark": "dracula"}}"#).unwrap();
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+
+    // Dark until the terminal or OS says otherwise
+    assert_eq!(harness.editor().theme().name, "dracula");
+
+    harness.editor_mut().set_appearance(Appearance::Light);
+    harness.render().unwrap();
+    assert_eq!(harness.editor().theme().name, "light");
+    assert_eq!(
+        harness.editor().theme().editor_bg,
+        Color::Rgb(255, 255, 255)
+    );
+
+    harness.editor_mut().set_appearance(Appearance::Dark);
+    assert_eq!(harness.editor().theme().name, "dracula");
+}
+
+#[test]
+fn test_single_theme_ignores_appearance() {
+    let config = Config {
+        theme: "dark".into(),
+        ..Default::default()
+    };
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+
+    harness
+        .editor_mut()
+        .set_appearance(fresh::config::Appearance::Light);
+    assert_eq!(harness.editor().theme().name, "dark");
+}
+
+#[test]
+fn test_user_theme_reloads_when_file_changes() {
+    use fresh::config_io::DirectoryContext;
+    use std::fs;
+    use std::time::Duration;
+
+    let context_temp = tempfile::TempDir::new().unwrap();
+    let dir_context = DirectoryContext::for_testing(context_temp.path());
+    fs::create_dir_all(dir_context.themes_dir()).unwrap();
+    let theme_path = dir_context.themes_dir().join("hot.json");
+    let theme_json = |bg: &str| {
+        format!(
+            r#"{{"name": "hot", "editor": {{"bg": {bg}}}, "ui": {{}}, "search": {{}}, "diagnostic": {{}}, "syntax": {{}}}}"#
+        )
+    };
+    fs::write(&theme_path, theme_json("[10, 10, 10]")).unwrap();
+
+    let project_temp = tempfile::TempDir::new().unwrap();
+    let config = Config {
+        theme: "hot".into(),
+        ..Default::default()
+    };
+    let poll_interval = Duration::from_millis(config.editor.auto_revert_poll_interval_ms);
+    let mut harness = EditorTestHarness::with_shared_dir_context(
+        80,
+        24,
+        config,
+        project_temp.path().to_path_buf(),
+        dir_context,
+    )
+    .unwrap();
+    assert_eq!(harness.editor().theme().editor_bg, Color::Rgb(10, 10, 10));
+    harness.process_async_and_render().unwrap();
+
+    fs::write(&theme_path, theme_json("[200, 0, 0]")).unwrap();
+    harness.advance_time(poll_interval);
+    harness.process_async_and_render().unwrap();
+
+    assert_eq!(harness.editor().theme().name, "hot");
+    assert_eq!(harness.editor().theme().editor_bg, Color::Rgb(200, 0, 0));
+}
@@ -1,8 +1,10 @@
-//! Tests for Toggle Comment functionality
+//! Tests for Toggle Line Comment and Toggle Block Comment
 //!
 //! Tests that:
 //! - Toggle comment uses language-specific comment prefixes from config
 //! - Selection is preserved after commenting/uncommenting
+//! - Line comments keep indentation aligned and work with multiple cursors
+//! - Block comments use the language's block markers, with fallbacks
 
 use crate::common::harness::{EditorTestHarness, HarnessOptions};
 use crossterm::event::{KeyCode, KeyModifiers};
@@ -42,7 +44,7 @@ fn test_toggle_comment_rust_prefix() {
     harness.render().unwrap();
 
     // Toggle comment on first line
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     let content = harness.get_buffer_content().unwrap();
     assert!(
@@ -66,7 +68,7 @@ fn test_toggle_comment_python_prefix() {
     harness.render().unwrap();
 
     // Toggle comment on first line
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     let content = harness.get_buffer_content().unwrap();
     assert!(
@@ -90,7 +92,7 @@ fn test_toggle_comment_shell_prefix() {
     harness.render().unwrap();
 
     // Toggle comment on first line
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     let content = harness.get_buffer_content().unwrap();
     assert!(
@@ -136,7 +138,7 @@ fn test_toggle_comment_preserves_selection() {
     let selection_len_before = selection_before.end - selection_before.start;
 
     // Toggle comment
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     // Verify content is commented
     let content = harness.get_buffer_content().unwrap();
@@ -198,7 +200,7 @@ fn test_toggle_uncomment_preserves_selection() {
     );
 
     // Toggle comment (uncomment)
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     // Verify content is uncommented
     let content = harness.get_buffer_content().unwrap();
@@ -237,7 +239,7 @@ fn test_toggle_comment_roundtrip_with_selection() {
     harness.render().unwrap();
 
     // Toggle comment (comment all)
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     // Verify content is commented
     let content = harness.get_buffer_content().unwrap();
@@ -256,7 +258,7 @@ fn test_toggle_comment_roundtrip_with_selection() {
     harness.render().unwrap();
 
     // Toggle comment again (uncomment all)
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     // Verify content is back to original
     let content = harness.get_buffer_content().unwrap();
@@ -296,7 +298,7 @@ fn test_toggle_comment_single_line_no_newline() {
     );
 
     // Toggle comment - this was causing infinite loop
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     // Verify content is commented
     let content = harness.get_buffer_content().unwrap();
@@ -325,7 +327,7 @@ fn test_toggle_comment_yaml_prefix() {
     harness.render().unwrap();
 
     // Toggle comment on first line
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     let content = harness.get_buffer_content().unwrap();
     assert!(
@@ -349,7 +351,7 @@ fn test_toggle_comment_yml_prefix() {
     harness.render().unwrap();
 
     // Toggle comment on first line
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     let content = harness.get_buffer_content().unwrap();
     assert!(
@@ -380,7 +382,7 @@ fn test_toggle_comment_selection_at_buffer_end() {
     harness.render().unwrap();
 
     // Toggle comment
-    run_command(&mut harness, "Toggle Comment");
+    run_command(&mut harness, "Toggle Line Comment");
 
     // Verify both lines are commented
     let content = harness.get_buffer_content().unwrap();
@@ -390,3 +392,157 @@ fn test_toggle_comment_selection_at_buffer_end() {
         content
     );
 }
+
+/// Open `content` as `name` in a fresh harness
+fn open(name: &str, content: &str) -> (TempDir, EditorTestHarness) {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join(name);
+    std::fs::write(&file_path, content).unwrap();
+    let mut harness = EditorTestHarness::with_config(80, 24, Config::default()).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+    (temp_dir, harness)
+}
+
+/// Test that the comment prefix goes at the shared indentation of the lines
+#[test]
+fn test_toggle_line_comment_keeps_alignment() {
+    let (_dir, mut harness) = open(
+        "test.rs",
+        "fn main() {\n    if x {\n        y();\n    }\n}\n",
+    );
+
+    // Select the three indented lines
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    for _ in 0..3 {
+        harness
+            .send_key(KeyCode::Down, KeyModifiers::SHIFT)
+            .unwrap();
+    }
+    run_command(&mut harness, "Toggle Line Comment");
+
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "fn main() {\n    // if x {\n    //     y();\n    // }\n}\n"
+    );
+
+    // The selection still covers the lines, so toggling again uncomments
+    run_command(&mut harness, "Toggle Line Comment");
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "fn main() {\n    if x {\n        y();\n    }\n}\n"
+    );
+}
+
+/// Test that a selection mixing commented and plain lines is commented
+#[test]
+fn test_toggle_line_comment_mixed_selection() {
+    let (_dir, mut harness) = open("test.py", "# a = 1\nb = 2\n");
+
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::CONTROL)
+        .unwrap();
+    run_command(&mut harness, "Toggle Line Comment");
+
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "# # a = 1\n# b = 2\n"
+    );
+}
+
+/// Test that every cursor's line is toggled, as one undo step
+#[test]
+fn test_toggle_line_comment_multiple_cursors() {
+    let (_dir, mut harness) = open("test.py", "x = 1\ny = 2\nx = 3\n");
+
+    // Select "x" and add a cursor at the next "x"
+    harness
+        .send_key(KeyCode::Char('d'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('d'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(harness.editor().active_state().cursors.iter().count(), 2);
+
+    run_command(&mut harness, "Toggle Line Comment");
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "# x = 1\ny = 2\n# x = 3\n"
+    );
+
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "x = 1\ny = 2\nx = 3\n"
+    );
+}
+
+/// Test that Toggle Block Comment wraps the line without a selection and
+/// unwraps it again
+#[test]
+fn test_toggle_block_comment_line() {
+    let (_dir, mut harness) = open("test.rs", "fn f() {\n    let x = 1;\n}\n");
+
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    run_command(&mut harness, "Toggle Block Comment");
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "fn f() {\n    /* let x = 1; */\n}\n"
+    );
+
+    run_command(&mut harness, "Toggle Block Comment");
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "fn f() {\n    let x = 1;\n}\n"
+    );
+}
+
+/// Test that Toggle Block Comment wraps just the selection
+#[test]
+fn test_toggle_block_comment_selection() {
+    let (_dir, mut harness) = open("test.c", "int f(int a, int b);\n");
+
+    // Select "int b"
+    for _ in 0..13 {
+        harness
+            .send_key(KeyCode::Right, KeyModifiers::NONE)
+            .unwrap();
+    }
+    for _ in 0..5 {
+        harness
+            .send_key(KeyCode::Right, KeyModifiers::SHIFT)
+            .unwrap();
+    }
+    run_command(&mut harness, "Toggle Block Comment");
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "int f(int a, /* int b */);\n"
+    );
+    harness.assert_screen_contains("Added block comment");
+
+    // The selection grew to cover the markers
+    run_command(&mut harness, "Toggle Block Comment");
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "int f(int a, int b);\n"
+    );
+}
+
+/// Test that each style falls back to the other when a language lacks it
+#[test]
+fn test_toggle_comment_fallbacks() {
+    // Python has no block comments
+    let (_dir, mut harness) = open("test.py", "x = 1\n");
+    run_command(&mut harness, "Toggle Block Comment");
+    assert_eq!(harness.get_buffer_content().unwrap(), "# x = 1\n");
+
+    // Markdown has no line comments
+    let (_dir, mut harness) = open("test.md", "Some text\n");
+    run_command(&mut harness, "Toggle Line Comment");
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "<!-- Some text -->\n"
+    );
+}
@@ -1118,3 +1118,57 @@ fn test_left_arrow_at_long_position_file_loaded() {
          If this is 51, the bug is present: left arrow fell back to code point movement."
     );
 }
+
+/// Grapheme clusters made of several code points (emoji sequences, combining
+/// marks, Hangul jamo) are one cursor stop and take the columns the terminal
+/// draws them with
+#[test]
+fn test_tricky_grapheme_clusters_cursor_and_columns() {
+    let cases = [
+        ("👨\u{200D}👩\u{200D}👧", 2, "ZWJ family"),
+        ("👍\u{1F3FD}", 2, "skin tone modifier"),
+        ("❤\u{FE0F}", 2, "emoji presentation selector"),
+        ("🇯🇵", 2, "flag"),
+        ("e\u{301}", 1, "combining accent"),
+        ("\u{1100}\u{1161}\u{11A8}", 2, "Hangul jamo"),
+        ("漢", 2, "CJK ideograph"),
+    ];
+
+    for (cluster, width, name) in cases {
+        let mut harness = EditorTestHarness::new(80, 24).unwrap();
+        let text = format!("a{cluster}b");
+        harness.type_text(&text).unwrap();
+        harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+        harness.render().unwrap();
+        let (start_x, _) = harness.screen_cursor_position();
+
+        // Past 'a', then past the whole cluster in one step
+        for _ in 0..2 {
+            harness
+                .send_key(KeyCode::Right, KeyModifiers::NONE)
+                .unwrap();
+        }
+        harness.render().unwrap();
+        assert_eq!(
+            harness.cursor_position(),
+            1 + cluster.len(),
+            "{name}: Right should skip the whole cluster"
+        );
+        let (x, _) = harness.screen_cursor_position();
+        assert_eq!(
+            x - start_x,
+            1 + width as u16,
+            "{name}: cursor should be drawn after {width} column(s)"
+        );
+
+        // Left comes back to the start of the cluster
+        harness.send_key(KeyCode::Left, KeyModifiers::NONE).unwrap();
+        assert_eq!(harness.cursor_position(), 1, "{name}: Left");
+
+        // Delete removes the cluster whole
+        harness
+            .send_key(KeyCode::Delete, KeyModifiers::NONE)
+            .unwrap();
+        harness.assert_buffer_content("ab");
+    }
+}
@@ -1,6 +1,7 @@
 //! E2E tests for the update notification UI
 
 use crate::common::harness::EditorTestHarness;
+use fresh::config::{TelemetryConfig, UpdateChannel};
 use fresh::services::release_checker::{
     start_periodic_update_check_with_interval, CURRENT_VERSION,
 };
@@ -61,6 +62,14 @@ fn next_patch_version() -> String {
     }
 }
 
+/// Telemetry written to the test's data directory rather than sent
+fn local_telemetry() -> TelemetryConfig {
+    TelemetryConfig {
+        local_only: true,
+        ..Default::default()
+    }
+}
+
 #[test]
 fn test_update_notification_appears_in_status_bar() {
     // Start a mock server that returns a version higher than current
@@ -78,6 +87,8 @@ fn test_update_notification_appears_in_status_bar() {
         Duration::from_secs(3600),
         time_source,
         temp_dir.path().to_path_buf(),
+        local_telemetry(),
+        UpdateChannel::Stable,
     );
 
     // Inject the checker into the editor
@@ -136,6 +147,8 @@ fn test_update_notification_not_shown_when_current() {
         Duration::from_secs(3600),
         time_source,
         temp_dir.path().to_path_buf(),
+        local_telemetry(),
+        UpdateChannel::Stable,
     );
     harness.editor_mut().set_update_checker(checker);
 
@@ -188,6 +201,8 @@ fn test_update_notification_positioned_near_ctrl_p() {
         Duration::from_secs(3600),
         time_source,
         temp_dir.path().to_path_buf(),
+        local_telemetry(),
+        UpdateChannel::Stable,
     );
     harness.editor_mut().set_update_checker(checker);
 
@@ -11,6 +11,7 @@ use crate::common::fixtures::TestFixture;
 use crate::common::harness::{copy_plugin, copy_plugin_lib, EditorTestHarness};
 use crate::common::tracing::init_tracing_from_env;
 use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::CursorStyle;
 use fresh::input::keybindings::Action::PluginAction;
 use std::fs;
 
@@ -720,6 +721,65 @@ fn test_vi_delete_inner_word() {
     harness.wait_for_buffer_content("hello  test\n").unwrap();
 }
 
+/// `r` waits for a character in vi-replace mode, which has an underline
+/// cursor, then replaces the characters under the cursor
+#[test]
+fn test_vi_replace_char_and_cursor_styles() {
+    let (mut harness, _temp_dir) = vi_mode_harness(80, 24);
+
+    let fixture = TestFixture::new("test.txt", "hello world\n").unwrap();
+    harness.open_file(&fixture.path).unwrap();
+    harness.render().unwrap();
+
+    enable_vi_mode(&mut harness);
+    assert_eq!(harness.editor().cursor_style(), CursorStyle::BlinkingBlock);
+
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.editor().editor_mode() == Some("vi-replace".to_string()))
+        .unwrap();
+    assert_eq!(
+        harness.editor().cursor_style(),
+        CursorStyle::BlinkingUnderline
+    );
+
+    harness
+        .send_key(KeyCode::Char('j'), KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_buffer_content("jello world\n").unwrap();
+    harness
+        .wait_until(|h| h.editor().editor_mode() == Some("vi-normal".to_string()))
+        .unwrap();
+
+    // With a count, `3rx` replaces three characters
+    let pos_before_w = harness.cursor_position();
+    harness
+        .send_key(KeyCode::Char('w'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.cursor_position() > pos_before_w)
+        .unwrap();
+    harness.type_text("3rx").unwrap();
+    harness.wait_for_buffer_content("jello xxxld\n").unwrap();
+    harness
+        .wait_until(|h| h.editor().editor_mode() == Some("vi-normal".to_string()))
+        .unwrap();
+
+    // Insert mode switches the terminal to a bar cursor
+    harness.editor_mut().take_pending_escape_sequences();
+    harness
+        .send_key(KeyCode::Char('i'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.editor().editor_mode() == Some("vi-insert".to_string()))
+        .unwrap();
+    assert_eq!(harness.editor().cursor_style(), CursorStyle::BlinkingBar);
+    let sequences = harness.editor_mut().take_pending_escape_sequences();
+    assert!(sequences.ends_with(CursorStyle::BlinkingBar.to_escape_sequence()));
+}
+
 /// Test 'ci"' changes inside quotes
 #[test]
 fn test_vi_change_inner_quotes() {
@@ -149,13 +149,12 @@ fn long_function() {
     harness.render().unwrap();
 
     // Capture the comprehensive state
-    harness
-        .capture_visual_step(
-            &mut flow,
-            "state_a",
-            "File explorer open + syntax highlighting + diagnostics + multicursors + scrolled",
-        )
-        .unwrap();
+    flow.capture(
+        &mut harness,
+        "state_a",
+        "File explorer open + syntax highlighting + diagnostics + multicursors + scrolled",
+    )
+    .unwrap();
 }
 
 /// Comprehensive visual test B:
@@ -260,13 +259,12 @@ fn helper() {
         .unwrap();
 
     // Capture the comprehensive state
-    harness
-        .capture_visual_step(
-            &mut flow,
-            "state_b",
-            "Split view + command palette open + line wrap off + horizontal scroll",
-        )
-        .unwrap();
+    flow.capture(
+        &mut harness,
+        "state_b",
+        "Split view + command palette open + line wrap off + horizontal scroll",
+    )
+    .unwrap();
 }
 
 // ============================================================================
@@ -0,0 +1,77 @@
+//! E2E tests for the watch task (Toggle Watch Task)
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use tempfile::TempDir;
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+}
+
+/// A failing run shows its problems in the status bar; saving a fix re-runs
+/// the command and the indicator turns to passed
+#[test]
+#[cfg_attr(not(unix), ignore = "Shell commands require Unix-like environment")]
+fn test_watch_task_reruns_on_save() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().to_path_buf();
+    let file_path = project_root.join("notes.txt");
+    std::fs::write(&file_path, "ok\nBAD\n").unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 30, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    run_command(&mut harness, "Toggle Watch Task");
+    harness.wait_for_prompt().unwrap();
+    harness
+        .type_text(
+            "grep -n BAD notes.txt | sed 's/:.*/:1: error: found BAD/; s/^/notes.txt:/'; ! grep -q BAD notes.txt",
+        )
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("watch ✗ 1"))
+        .unwrap();
+
+    // Remove the offending line and save
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness
+        .send_key(KeyCode::Down, KeyModifiers::SHIFT)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Delete, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("watch ✓"))
+        .unwrap();
+    harness.assert_buffer_content("ok\n");
+
+    run_command(&mut harness, "Show Watch Output");
+    harness.wait_for_prompt_closed().unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("$ grep -n BAD"))
+        .unwrap();
+
+    // Toggling again stops the task
+    run_command(&mut harness, "Toggle Watch Task");
+    harness.wait_for_prompt_closed().unwrap();
+    harness
+        .wait_until(|h| !h.screen_to_string().contains("watch ✓"))
+        .unwrap();
+}
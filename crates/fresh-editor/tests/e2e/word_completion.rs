@@ -0,0 +1,69 @@
+//! E2E tests for word completion, the fallback without a language server
+
+use crate::common::harness::{EditorTestHarness, HarnessOptions};
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use tempfile::TempDir;
+
+fn harness(dir: &TempDir) -> EditorTestHarness {
+    let options = HarnessOptions::new()
+        .with_config(Config::default())
+        .with_working_dir(dir.path().to_path_buf())
+        .with_word_completion();
+    EditorTestHarness::create(100, 24, options).unwrap()
+}
+
+/// Typing a prefix offers the words of the open buffers, nearest to the
+/// cursor first, and Enter inserts the selected one
+#[test]
+fn test_word_completion_from_open_buffers() {
+    let dir = TempDir::new().unwrap();
+    let other = dir.path().join("other.txt");
+    let notes = dir.path().join("notes.txt");
+    std::fs::write(&other, "alphabet_soup\n").unwrap();
+    std::fs::write(&notes, "alpha_counter beta\nalpha_value\n").unwrap();
+
+    let mut harness = harness(&dir);
+    harness.open_file(&other).unwrap();
+    harness.open_file(&notes).unwrap();
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("alp").unwrap();
+
+    harness
+        .wait_until(|h| h.screen_to_string().contains("alphabet_soup"))
+        .unwrap();
+    harness.assert_screen_contains("alpha_counter");
+
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_buffer_content("alpha_counter beta\nalpha_value\nalpha_value");
+}
+
+/// Tag names from a ctags `tags` file in the project root are offered too
+#[test]
+fn test_word_completion_from_tags_file() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("tags"),
+        "!_TAG_FILE_FORMAT\t2\t/extended format/\n\
+         parse_config\tsrc/config.rs\t/^fn parse_config() {$/;\"\tf\n",
+    )
+    .unwrap();
+    let file = dir.path().join("main.txt");
+    std::fs::write(&file, "").unwrap();
+
+    let mut harness = harness(&dir);
+    harness.open_file(&file).unwrap();
+    harness.type_text("pars").unwrap();
+
+    harness
+        .wait_until(|h| h.screen_to_string().contains("parse_config"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_buffer_content("parse_config");
+}
@@ -1286,3 +1286,59 @@ fn test_session_restores_file_explorer_hidden_and_gitignored_settings() {
         );
     }
 }
+
+/// Test that the jump list (Navigate Back/Forward) is restored
+#[test]
+fn test_session_restores_jump_list() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir(&project_dir).unwrap();
+
+    let files: Vec<_> = ["a.txt", "b.txt", "c.txt"]
+        .iter()
+        .map(|name| {
+            let path = project_dir.join(name);
+            std::fs::write(&path, format!("Content of {}", name)).unwrap();
+            path
+        })
+        .collect();
+
+    // First session: open the files one after the other
+    {
+        let mut harness = EditorTestHarness::with_config_and_working_dir(
+            80,
+            24,
+            Config::default(),
+            project_dir.clone(),
+        )
+        .unwrap();
+
+        for file in &files {
+            harness.open_file(file).unwrap();
+        }
+        harness.assert_buffer_content("Content of c.txt");
+
+        harness.editor_mut().save_workspace().unwrap();
+    }
+
+    // Second session: navigating back retraces the file switches
+    {
+        let mut harness = EditorTestHarness::with_config_and_working_dir(
+            80,
+            24,
+            Config::default(),
+            project_dir.clone(),
+        )
+        .unwrap();
+
+        harness.editor_mut().try_restore_workspace().unwrap();
+        harness.assert_buffer_content("Content of c.txt");
+
+        harness.send_key(KeyCode::Left, KeyModifiers::ALT).unwrap();
+        harness.assert_buffer_content("Content of b.txt");
+        harness.send_key(KeyCode::Left, KeyModifiers::ALT).unwrap();
+        harness.assert_buffer_content("Content of a.txt");
+        harness.send_key(KeyCode::Right, KeyModifiers::ALT).unwrap();
+        harness.assert_buffer_content("Content of b.txt");
+    }
+}
@@ -23,6 +23,7 @@ fn reproduce_cursor_panic() {
         show_cursors: false, // <--- The trigger: hiding cursors
         editing_disabled: false,
         hidden_from_tabs: false,
+        uri: None,
         request_id: None,
     };
 
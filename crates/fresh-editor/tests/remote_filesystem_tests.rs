@@ -1057,3 +1057,44 @@ fn test_buffer_huge_file_multi_save_cycle_through_remote() {
         }
     }
 }
+
+/// The file explorer browses remote trees lazily, one cached listing per directory
+#[test]
+fn test_file_tree_lazy_expansion_through_remote() {
+    use fresh::services::fs::FsManager;
+    use fresh::view::file_tree::FileTree;
+
+    let Some((fs, temp_dir, rt)) = create_test_filesystem() else {
+        eprintln!("Skipping test: could not create test filesystem");
+        return;
+    };
+
+    let root = temp_dir.path().to_path_buf();
+    std::fs::create_dir(root.join("conf.d")).unwrap();
+    std::fs::write(root.join("conf.d/site.conf"), "server {}").unwrap();
+    std::fs::write(root.join("nginx.conf"), "events {}").unwrap();
+
+    let manager = Arc::new(FsManager::new(Arc::new(fs)));
+    rt.block_on(async {
+        let mut tree = FileTree::new(root.clone(), manager.clone()).await.unwrap();
+        let root_id = tree.root_id();
+        tree.expand_node(root_id).await.unwrap();
+
+        // Only the root is listed; subdirectories load on expansion
+        assert_eq!(tree.node_count(), 3);
+        let file = tree.get_node_by_path(&root.join("nginx.conf")).unwrap();
+        assert_eq!(file.entry.metadata.as_ref().unwrap().size, 9);
+
+        let conf_d = tree.get_node_by_path(&root.join("conf.d")).unwrap().id;
+        tree.expand_node(conf_d).await.unwrap();
+        assert_eq!(tree.node_count(), 4);
+
+        // Re-expanding is served from the cache; refreshing reads the remote again
+        std::fs::write(root.join("conf.d/extra.conf"), "").unwrap();
+        tree.collapse_node(conf_d);
+        tree.expand_node(conf_d).await.unwrap();
+        assert_eq!(tree.node_count(), 4);
+        tree.refresh_node(conf_d).await.unwrap();
+        assert_eq!(tree.node_count(), 5);
+    });
+}
@@ -172,13 +172,14 @@ impl FileSystem for ConfigurableFileSystem {
 
     fn sudo_write(
         &self,
+        program: &str,
         path: &Path,
         data: &[u8],
         mode: u32,
         uid: u32,
         gid: u32,
     ) -> io::Result<()> {
-        self.inner.sudo_write(path, data, mode, uid, gid)
+        self.inner.sudo_write(program, path, data, mode, uid, gid)
     }
 
     fn write_patched(
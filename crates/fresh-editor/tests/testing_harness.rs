@@ -0,0 +1,51 @@
+// Test the public test harness (fresh::testing) the way a plugin author would use it
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::testing::{EditorTestHarness, HarnessOptions};
+use std::fs;
+
+const SHOUT_PLUGIN: &str = r#"
+const editor = getEditor();
+
+globalThis.shout_buffer = async function(): Promise<void> {
+    const bufferId = editor.getActiveBufferId();
+    const text = await editor.getBufferText(bufferId, 0, editor.getBufferLength(bufferId));
+    editor.deleteRange(bufferId, 0, text.length);
+    editor.insertText(bufferId, 0, text.toUpperCase());
+    editor.setStatus("Shouted");
+};
+
+editor.registerCommand("Shout: Uppercase Buffer", "Uppercase the buffer", "shout_buffer", null);
+"#;
+
+#[test]
+fn test_typing_and_screen_assertions() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.assert_screen_contains("[No Name]");
+
+    harness.type_text("hello\nworld").unwrap();
+    harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+    harness.assert_buffer_content("hello\nworld");
+    assert_eq!(harness.cursor_position(), 6);
+    assert_eq!(
+        harness.find_text_on_screen("world").map(|(_, row)| row),
+        Some(3)
+    );
+}
+
+#[test]
+fn test_plugin_command_runs_from_the_palette() {
+    let dir = tempfile::tempdir().unwrap();
+    let plugin = dir.path().join("shout.ts");
+    fs::write(&plugin, SHOUT_PLUGIN).unwrap();
+
+    let mut harness =
+        EditorTestHarness::create(80, 24, HarnessOptions::new().with_plugin(&plugin)).unwrap();
+    let path = harness.open_text("notes.txt", "hello\n").unwrap();
+    assert!(path.starts_with(harness.working_dir()));
+
+    harness.run_command("Shout: Uppercase Buffer").unwrap();
+    harness.wait_for_buffer_content("HELLO\n").unwrap();
+    harness.wait_for_screen_contains("Shouted").unwrap();
+    harness.assert_no_plugin_errors();
+}
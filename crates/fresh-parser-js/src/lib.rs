@@ -148,6 +148,44 @@ pub fn bundle_module(entry_path: &Path) -> Result<String> {
     Ok(output)
 }
 
+/// List the files a module is built from: the entry plus every local module it
+/// imports or re-exports, directly or transitively
+///
+/// Unreadable files and unresolvable imports are skipped, so this never fails;
+/// it's used to watch plugin sources for changes.
+pub fn module_source_files(entry_path: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![entry_path.to_path_buf()];
+
+    while let Some(path) = pending.pop() {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if !visited.insert(canonical) {
+            continue;
+        }
+        let Ok(source) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let parent_dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        files.push(path);
+
+        let (imports, _, reexports) = extract_module_bindings(&source);
+        let sources = imports
+            .iter()
+            .map(|i| &i.source_path)
+            .chain(reexports.iter().map(|r| &r.source_path));
+        for source_path in sources {
+            if source_path.starts_with("./") || source_path.starts_with("../") {
+                if let Ok(resolved) = resolve_import(source_path, &parent_dir) {
+                    pending.push(resolved);
+                }
+            }
+        }
+    }
+
+    files
+}
+
 /// Collect all modules in dependency order (dependencies first)
 fn collect_modules(
     path: &Path,
@@ -798,4 +836,30 @@ const x = foo() + bar();"#;
         assert!(stripped.contains("interface User"));
         assert!(stripped.contains("const x = foo() + bar();"));
     }
+
+    #[test]
+    fn test_module_source_files_follows_local_imports() {
+        let dir = std::env::temp_dir().join(format!("fresh-module-files-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("lib")).unwrap();
+        std::fs::write(
+            dir.join("plugin.ts"),
+            "import { helper } from \"./lib/index.ts\";\nimport { x } from \"fresh\";\nhelper(x);\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("lib/index.ts"),
+            "export { helper } from \"./helper.ts\";\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("lib/helper.ts"), "export function helper() {}\n").unwrap();
+
+        let files = module_source_files(&dir.join("plugin.ts"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<_> = files
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["plugin.ts", "index.ts", "helper.ts"]);
+    }
 }
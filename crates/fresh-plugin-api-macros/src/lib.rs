@@ -624,6 +624,22 @@ interface ProcessHandle<T> extends PromiseLike<T> {
   kill(): Promise<boolean>;
 }
 
+/** Handle passed to a `runTask` job */
+interface TaskHandle {
+  /** Task identifier */
+  readonly id: number;
+  /** True once the user has cancelled the task */
+  readonly cancelled: boolean;
+  /** Show progress in the status bar (percentage is 0-100) */
+  progress(message?: string | null, percentage?: number | null): void;
+}
+
+/** Job run by `runTask`; its return value (or resolved value) is the task result */
+type TaskJob = (task: TaskHandle) => unknown;
+
+/** Theme color: `[r, g, b]` or a named color such as "Red" or "Default" */
+type ThemeColor = [number, number, number] | string;
+
 /** Buffer identifier */
 type BufferId = number;
 
@@ -657,6 +673,9 @@ const BUILTIN_TS_TYPES: &[&str] = &[
     "Array",
     "Promise",
     "ProcessHandle",
+    "TaskHandle",
+    "TaskJob",
+    "ThemeColor",
     "PromiseLike",
     "BufferId",
     "SplitId", // Defined in preamble
@@ -0,0 +1,502 @@
+//! Lua plugin runtime
+//!
+//! Packages whose `package.json` sets `"fresh": {"runtime": "lua"}` are written
+//! in Lua instead of TypeScript. A Lua plugin still gets its own QuickJS context
+//! with the regular `editor` object; the Lua `editor` table forwards every call
+//! to it, so both runtimes share one API surface, the same command and event
+//! registration, permissions and async callbacks.
+//!
+//! Handler names passed to `registerCommand`, `on`, `defineMode`... name global
+//! Lua functions: a JS shim with the same name calls back into Lua. Lua functions
+//! passed directly (e.g. the `runTask` job) become JS functions.
+//!
+//! Lua code runs in coroutines. An API call returning a promise suspends the
+//! coroutine until the promise settles, so async calls read as plain calls:
+//!
+//! ```lua
+//! local text = editor.getBufferText(id, 0, 100)
+//! ```
+//!
+//! The Lua state is sandboxed: no `io`, `os`, `package` or `debug` libraries,
+//! no bytecode loading, and `require` only loads modules from the plugin's
+//! own directory.
+
+use super::quickjs_backend::{format_js_error, js_to_json};
+use anyhow::{anyhow, Result};
+use mlua::serde::{DeserializeOptions, SerializeOptions};
+use mlua::thread::ThreadStatus;
+use mlua::{Lua, LuaOptions, LuaSerdeExt, MultiValue, StdLib, Table, Thread};
+use rquickjs::function::{Rest, This};
+use rquickjs::{Context, Ctx, Function, Object, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Lua global holding the bridge to the JS `editor` object while Lua code runs
+const BRIDGE: &str = "__fresh_bridge";
+
+/// Lua registry key of the table of Lua functions handed to JS
+const HANDLERS: &str = "fresh_handlers";
+
+/// Helpers installed in the plugin's JS context
+const JS_PRELUDE: &str = r#"
+(function() {
+    const pending = new Map();
+    // Resume a suspended Lua coroutine once the promise it waits on settles
+    globalThis._luaAwait = function(promise, waitId) {
+        Promise.resolve(promise).then(
+            function(value) { _luaResume(waitId, true, value); },
+            function(e) { _luaResume(waitId, false, e instanceof Error ? e.message : String(e)); }
+        );
+    };
+    // Promise for a Lua handler call that suspended
+    globalThis._luaDeferred = function(callId) {
+        return new Promise(function(resolve, reject) {
+            pending.set(callId, { resolve: resolve, reject: reject });
+        });
+    };
+    globalThis._luaSettle = function(callId, ok, value) {
+        const p = pending.get(callId);
+        if (!p) return;
+        pending.delete(callId);
+        if (ok) p.resolve(value); else p.reject(new Error(value));
+    };
+    // Expose a global Lua function under the same name for handler lookups
+    globalThis._luaShim = function(name) {
+        if (typeof globalThis[name] !== 'function') {
+            globalThis[name] = function(...args) { return _luaCall(name, ...args); };
+        }
+    };
+    globalThis._luaFunction = function(name) {
+        return function(...args) { return _luaCall(name, ...args); };
+    };
+})();
+"#;
+
+/// Sandbox and `editor` table, run before the plugin's own code
+const LUA_PRELUDE: &str = r##"
+dofile, loadfile = nil, nil
+string.dump = nil
+
+local raw_load = load
+function load(chunk, name, _, ...)
+    if select("#", ...) > 0 then
+        return raw_load(chunk, name, "t", ...)
+    end
+    return raw_load(chunk, name, "t")
+end
+
+local read_module = __fresh_read_module
+__fresh_read_module = nil
+local loaded = {}
+function require(name)
+    if loaded[name] == nil then
+        local source, path = read_module(name)
+        local chunk = assert(raw_load(source, "@" .. path, "t"))
+        local result = chunk(name)
+        if result == nil then result = true end
+        loaded[name] = result
+    end
+    return loaded[name]
+end
+
+local log = __fresh_print
+__fresh_print = nil
+function print(...)
+    local parts = {}
+    for i = 1, select("#", ...) do
+        parts[i] = tostring((select(i, ...)))
+    end
+    log(table.concat(parts, "\t"))
+end
+
+editor = setmetatable({}, {
+    __index = function(t, method)
+        local f = function(...)
+            local value, wait = __fresh_bridge(method, ...)
+            if wait == nil then
+                return value
+            end
+            local ok, result = coroutine.yield(wait)
+            if not ok then
+                error(result, 2)
+            end
+            return result
+        end
+        rawset(t, method, f)
+        return f
+    end,
+})
+"##;
+
+/// A coroutine suspended on a JS promise
+struct Waiting {
+    thread: Thread,
+    /// Deferred JS call waiting for the coroutine's result (none for the top-level chunk)
+    call_id: Option<u64>,
+}
+
+/// How a resumed coroutine stopped
+enum Outcome {
+    Done(mlua::Value),
+    Suspended,
+}
+
+/// Lua state of one plugin
+struct LuaPlugin {
+    lua: Lua,
+    name: String,
+    waiting: RefCell<HashMap<u64, Waiting>>,
+    next_id: Cell<u64>,
+}
+
+/// Load a Lua plugin into its JS context (which already has the `editor` object)
+pub(super) fn load_plugin(context: &Context, plugin_name: &str, path: &Path) -> Result<()> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read plugin {}: {}", path.display(), e))?;
+    let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+    let plugin = Rc::new(
+        LuaPlugin::new(plugin_name, dir)
+            .map_err(|e| anyhow!("Failed to set up Lua for plugin {}: {}", plugin_name, e))?,
+    );
+    let source_name = path.display().to_string();
+
+    context.with(|ctx| {
+        install_js_bridge(&ctx, &plugin).map_err(|e| format_js_error(&ctx, e, &source_name))?;
+
+        let run = || -> mlua::Result<()> {
+            let chunk = plugin
+                .lua
+                .load(source.as_str())
+                .set_name(format!("@{}", source_name))
+                .into_function()?;
+            let thread = plugin.lua.create_thread(chunk)?;
+            plugin.resume(&ctx, thread, MultiValue::new(), None)?;
+            Ok(())
+        };
+        run().map_err(|e| anyhow!("Lua error in {}: {}", source_name, e))
+    })
+}
+
+/// Define the native functions the JS prelude calls back into
+fn install_js_bridge<'js>(ctx: &Ctx<'js>, plugin: &Rc<LuaPlugin>) -> rquickjs::Result<()> {
+    let globals = ctx.globals();
+
+    let call_plugin = Rc::clone(plugin);
+    globals.set(
+        "_luaCall",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'js>, name: String, args: Rest<Value<'js>>| {
+                call_plugin.call_handler(&ctx, &name, args.0)
+            },
+        )?,
+    )?;
+
+    let resume_plugin = Rc::clone(plugin);
+    globals.set(
+        "_luaResume",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'js>, wait_id: f64, ok: bool, value: Value<'js>| {
+                resume_plugin.resume_waiting(&ctx, wait_id as u64, ok, value)
+            },
+        )?,
+    )?;
+
+    ctx.eval::<(), _>(JS_PRELUDE)
+}
+
+fn lua_error_message(plugin_name: &str, e: &mlua::Error) -> String {
+    format!("Lua error in {}: {}", plugin_name, e)
+}
+
+impl LuaPlugin {
+    fn new(name: &str, dir: PathBuf) -> mlua::Result<Self> {
+        let lua = Lua::new_with(
+            StdLib::COROUTINE | StdLib::TABLE | StdLib::STRING | StdLib::UTF8 | StdLib::MATH,
+            LuaOptions::default(),
+        )?;
+        let globals = lua.globals();
+
+        globals.set(
+            "__fresh_read_module",
+            lua.create_function(move |_, module: String| read_module(&dir, &module))?,
+        )?;
+        let plugin_name = name.to_string();
+        globals.set(
+            "__fresh_print",
+            lua.create_function(move |_, line: String| {
+                tracing::info!("{}: {}", plugin_name, line);
+                Ok(())
+            })?,
+        )?;
+        lua.set_named_registry_value(HANDLERS, lua.create_table()?)?;
+        lua.load(LUA_PRELUDE).set_name("=fresh").exec()?;
+
+        Ok(Self {
+            lua,
+            name: name.to_string(),
+            waiting: RefCell::new(HashMap::new()),
+            next_id: Cell::new(1),
+        })
+    }
+
+    fn next_id(&self) -> u64 {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+
+    /// Run `thread` until it finishes or suspends on a promise
+    fn resume(
+        &self,
+        ctx: &Ctx<'_>,
+        thread: Thread,
+        args: MultiValue,
+        call_id: Option<u64>,
+    ) -> mlua::Result<Outcome> {
+        let values = self.lua.scope(|scope| {
+            let bridge =
+                scope.create_function(|_, args: MultiValue| self.call_editor(ctx, args))?;
+            // Restore the outer bridge when Lua code is entered re-entrantly
+            let globals = self.lua.globals();
+            let previous: mlua::Value = globals.get(BRIDGE)?;
+            globals.set(BRIDGE, bridge)?;
+            let result = thread.resume::<MultiValue>(args);
+            globals.set(BRIDGE, previous)?;
+            result
+        })?;
+
+        if thread.status() != ThreadStatus::Resumable {
+            return Ok(Outcome::Done(
+                values.into_iter().next().unwrap_or(mlua::Value::Nil),
+            ));
+        }
+        match values.front() {
+            Some(mlua::Value::Integer(wait_id)) => {
+                self.waiting
+                    .borrow_mut()
+                    .insert(*wait_id as u64, Waiting { thread, call_id });
+                Ok(Outcome::Suspended)
+            }
+            _ => Err(mlua::Error::runtime(
+                "coroutine.yield outside a coroutine created by the plugin",
+            )),
+        }
+    }
+
+    /// `__fresh_bridge(method, ...)`: call `editor[method]` in the JS context
+    ///
+    /// Returns the result, or `nil, wait_id` when the method returned a promise.
+    fn call_editor(
+        &self,
+        ctx: &Ctx<'_>,
+        args: MultiValue,
+    ) -> mlua::Result<(mlua::Value, Option<u64>)> {
+        let js_error =
+            |e: rquickjs::Error| mlua::Error::runtime(format_js_error(ctx, e, &self.name));
+
+        let mut args = args.into_iter();
+        let method = match args.next() {
+            Some(mlua::Value::String(s)) => s.to_str()?.to_string(),
+            _ => return Err(mlua::Error::runtime("editor method name must be a string")),
+        };
+        let editor: Object = ctx.globals().get("editor").map_err(js_error)?;
+        let func: Function = editor
+            .get(method.as_str())
+            .map_err(|_| mlua::Error::runtime(format!("editor.{} is not a function", method)))?;
+
+        let js_args = args
+            .map(|arg| self.lua_to_js(ctx, arg))
+            .collect::<mlua::Result<Vec<_>>>()?;
+        let result: Value = func.call((This(editor), Rest(js_args))).map_err(js_error)?;
+
+        let is_promise = result
+            .as_object()
+            .is_some_and(|obj| obj.get::<_, Function>("then").is_ok());
+        if is_promise {
+            let wait_id = self.next_id();
+            let await_promise: Function = ctx.globals().get("_luaAwait").map_err(js_error)?;
+            await_promise
+                .call::<_, ()>((result, wait_id))
+                .map_err(js_error)?;
+            return Ok((mlua::Value::Nil, Some(wait_id)));
+        }
+        Ok((self.js_to_lua(ctx, result)?, None))
+    }
+
+    /// `_luaCall(name, ...args)`: run a Lua handler
+    ///
+    /// Returns the handler's result, or a promise for it when the handler
+    /// suspended on an async editor call.
+    fn call_handler<'js>(
+        &self,
+        ctx: &Ctx<'js>,
+        name: &str,
+        args: Vec<Value<'js>>,
+    ) -> rquickjs::Result<Value<'js>> {
+        let throw = |e: mlua::Error| {
+            rquickjs::Exception::throw_message(ctx, &lua_error_message(&self.name, &e))
+        };
+
+        let handlers: Table = self.lua.named_registry_value(HANDLERS).map_err(throw)?;
+        let handler = match handlers.get::<mlua::Value>(name).map_err(throw)? {
+            mlua::Value::Function(f) => f,
+            _ => self
+                .lua
+                .globals()
+                .get::<mlua::Function>(name)
+                .map_err(|_| {
+                    throw(mlua::Error::runtime(format!(
+                        "handler '{}' is not a Lua function",
+                        name
+                    )))
+                })?,
+        };
+        let lua_args = args
+            .into_iter()
+            .map(|arg| self.js_to_lua(ctx, arg))
+            .collect::<mlua::Result<MultiValue>>()
+            .map_err(throw)?;
+
+        let thread = self.lua.create_thread(handler).map_err(throw)?;
+        let call_id = self.next_id();
+        match self
+            .resume(ctx, thread, lua_args, Some(call_id))
+            .map_err(throw)?
+        {
+            Outcome::Done(value) => self.lua_to_js(ctx, value).map_err(throw),
+            Outcome::Suspended => {
+                let deferred: Function = ctx.globals().get("_luaDeferred")?;
+                deferred.call((call_id,))
+            }
+        }
+    }
+
+    /// `_luaResume(wait_id, ok, value)`: continue a coroutine whose promise settled
+    fn resume_waiting<'js>(
+        &self,
+        ctx: &Ctx<'js>,
+        wait_id: u64,
+        ok: bool,
+        value: Value<'js>,
+    ) -> rquickjs::Result<()> {
+        let Some(waiting) = self.waiting.borrow_mut().remove(&wait_id) else {
+            return Ok(());
+        };
+        let throw = |e: mlua::Error| {
+            rquickjs::Exception::throw_message(ctx, &lua_error_message(&self.name, &e))
+        };
+
+        let value = self.js_to_lua(ctx, value).map_err(throw)?;
+        let result = self.resume(
+            ctx,
+            waiting.thread,
+            MultiValue::from_iter([mlua::Value::Boolean(ok), value]),
+            waiting.call_id,
+        );
+        let (ok, value) = match result {
+            Ok(Outcome::Suspended) => return Ok(()),
+            Ok(Outcome::Done(value)) => (true, self.lua_to_js(ctx, value).map_err(throw)?),
+            // Without a caller to reject, surface it as an unhandled rejection
+            Err(e) if waiting.call_id.is_none() => return Err(throw(e)),
+            Err(e) => {
+                let message = lua_error_message(&self.name, &e);
+                (
+                    false,
+                    rquickjs::String::from_str(ctx.clone(), &message)?.into(),
+                )
+            }
+        };
+        if let Some(call_id) = waiting.call_id {
+            let settle: Function = ctx.globals().get("_luaSettle")?;
+            settle.call::<_, ()>((call_id, ok, value))?;
+        }
+        Ok(())
+    }
+
+    /// Convert a Lua value to JS
+    ///
+    /// Functions become JS functions calling back into Lua. A string naming a
+    /// global Lua function gets a JS global of the same name, so it can be used
+    /// as a command or event handler name.
+    fn lua_to_js<'js>(&self, ctx: &Ctx<'js>, value: mlua::Value) -> mlua::Result<Value<'js>> {
+        let js_error =
+            |e: rquickjs::Error| mlua::Error::runtime(format_js_error(ctx, e, &self.name));
+        match value {
+            mlua::Value::Nil => Ok(Value::new_undefined(ctx.clone())),
+            mlua::Value::Function(f) => {
+                let name = format!("__lua_fn_{}", self.next_id());
+                let handlers: Table = self.lua.named_registry_value(HANDLERS)?;
+                handlers.set(name.as_str(), f)?;
+                let make: Function = ctx.globals().get("_luaFunction").map_err(js_error)?;
+                make.call((name,)).map_err(js_error)
+            }
+            value => {
+                if let mlua::Value::String(ref s) = value {
+                    let name = s.to_string_lossy();
+                    if let mlua::Value::Function(_) =
+                        self.lua.globals().get::<mlua::Value>(name.as_str())?
+                    {
+                        let shim: Function = ctx.globals().get("_luaShim").map_err(js_error)?;
+                        shim.call::<_, ()>((name.as_str(),)).map_err(js_error)?;
+                    }
+                }
+                let json: serde_json::Value = self.lua.from_value_with(
+                    value,
+                    DeserializeOptions::new()
+                        .encode_empty_tables_as_array(true)
+                        .deny_unsupported_types(false),
+                )?;
+                rquickjs_serde::to_value(ctx.clone(), &json)
+                    .map_err(|e| mlua::Error::runtime(e.to_string()))
+            }
+        }
+    }
+
+    /// Convert a JS value to Lua (as plain data; JS functions are dropped)
+    fn js_to_lua(&self, ctx: &Ctx<'_>, value: Value<'_>) -> mlua::Result<mlua::Value> {
+        let json = js_to_json(ctx, value);
+        self.lua.to_value_with(
+            &json,
+            SerializeOptions::new()
+                .serialize_none_to_null(false)
+                .serialize_unit_to_null(false),
+        )
+    }
+}
+
+/// Source and path of module `name` for `require`, from the plugin's directory
+fn read_module(dir: &Path, name: &str) -> mlua::Result<(String, String)> {
+    let valid = !name.is_empty()
+        && name.split('.').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        });
+    if !valid {
+        return Err(mlua::Error::runtime(format!(
+            "invalid module name '{}'",
+            name
+        )));
+    }
+
+    let relative = name.replace('.', "/");
+    let candidates = [
+        dir.join(format!("{}.lua", relative)),
+        dir.join(&relative).join("init.lua"),
+    ];
+    for path in candidates {
+        if let Ok(source) = std::fs::read_to_string(&path) {
+            return Ok((source, path.display().to_string()));
+        }
+    }
+    Err(mlua::Error::runtime(format!(
+        "module '{}' not found in {}",
+        name,
+        dir.display()
+    )))
+}
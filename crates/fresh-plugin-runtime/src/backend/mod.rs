@@ -1,8 +1,11 @@
 //! Plugin backend abstraction layer
 //!
 //! This module provides the JavaScript runtime backend for executing TypeScript plugins.
-//! Currently implements QuickJS with oxc transpilation.
+//! Currently implements QuickJS with oxc transpilation, plus an optional Lua
+//! runtime that drives the same JavaScript editor API.
 
+#[cfg(feature = "lua")]
+mod lua_backend;
 pub mod quickjs_backend;
 
 pub use quickjs_backend::{
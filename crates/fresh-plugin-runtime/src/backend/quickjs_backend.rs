@@ -86,13 +86,16 @@
 //!
 //! This validates TypeScript syntax and writes `plugins/lib/fresh.d.ts`.
 
+use crate::permissions::{PermissionCheck, PluginPermissions};
 use anyhow::{anyhow, Result};
 use fresh_core::api::{
-    ActionSpec, BufferInfo, CompositeHunk, CreateCompositeBufferOptions, EditorStateSnapshot,
-    JsCallbackId, LanguagePackConfig, LspServerPackConfig, OverlayOptions, PluginCommand,
-    PluginResponse,
+    ActionSpec, BufferInfo, CompositeHunk, CreateCompositeBufferOptions, CursorInfo,
+    EditorStateSnapshot, InputBoxOptions, JsCallbackId, LanguagePackConfig, LspServerPackConfig,
+    OverlayOptions, PluginCommand, PluginResponse, QuickPickItem, QuickPickOptions, QuickfixEntry,
+    TextRange, TextSelection,
 };
 use fresh_core::command::Command;
+use fresh_core::config::PluginPermission;
 use fresh_core::overlay::OverlayNamespace;
 use fresh_core::text_property::TextPropertyEntry;
 use fresh_core::{BufferId, SplitId};
@@ -103,13 +106,16 @@ use fresh_parser_js::{
 use fresh_plugin_api_macros::{plugin_api, plugin_api_impl};
 use rquickjs::{Context, Function, Object, Runtime, Value};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::{mpsc, Arc, RwLock};
 
+/// Built-in actions that write files, and so need the `fs-write` permission
+const FILE_WRITING_ACTIONS: &[&str] = &["save", "save_as"];
+
 /// Convert a QuickJS Value to serde_json::Value
-fn js_to_json(ctx: &rquickjs::Ctx<'_>, val: Value<'_>) -> serde_json::Value {
+pub(super) fn js_to_json(ctx: &rquickjs::Ctx<'_>, val: Value<'_>) -> serde_json::Value {
     use rquickjs::Type;
     match val.type_of() {
         Type::Null | Type::Undefined | Type::Uninitialized => serde_json::Value::Null,
@@ -257,7 +263,7 @@ fn js_value_to_string(ctx: &rquickjs::Ctx<'_>, val: &Value<'_>) -> String {
 }
 
 /// Format a JavaScript error with full details including stack trace
-fn format_js_error(
+pub(super) fn format_js_error(
     ctx: &rquickjs::Ctx<'_>,
     err: rquickjs::Error,
     source_name: &str,
@@ -445,6 +451,12 @@ fn parse_text_property_entry(
     Some(TextPropertyEntry { text, properties })
 }
 
+/// Job function passed to `runTask` (named so the TypeScript signature can describe it)
+type TaskJob<'js> = Function<'js>;
+
+/// Color passed to `setThemeColor` (named so the TypeScript signature can describe it)
+type ThemeColor<'js> = Value<'js>;
+
 /// Pending response senders type alias
 pub type PendingResponses =
     Arc<std::sync::Mutex<HashMap<u64, tokio::sync::oneshot::Sender<PluginResponse>>>>;
@@ -483,9 +495,114 @@ pub struct JsEditorApi {
     callback_contexts: Rc<RefCell<HashMap<u64, String>>>,
     #[qjs(skip_trace)]
     services: Arc<dyn fresh_core::services::PluginServiceBridge>,
+    #[qjs(skip_trace)]
+    permissions: Rc<RefCell<PluginPermissions>>,
+    #[qjs(skip_trace)]
+    cancelled_tasks: Rc<RefCell<HashSet<u64>>>,
     pub plugin_name: String,
 }
 
+impl JsEditorApi {
+    /// Fail a synchronous call unless this plugin may use `permission`
+    ///
+    /// The first call that needs an unanswered permission asks the user; the
+    /// call itself still fails, and later calls follow the user's answer.
+    fn require_permission(
+        &self,
+        ctx: &rquickjs::Ctx<'_>,
+        permission: PluginPermission,
+    ) -> rquickjs::Result<()> {
+        let check = self
+            .permissions
+            .borrow_mut()
+            .check(&self.plugin_name, permission);
+        match check {
+            PermissionCheck::Allowed => Ok(()),
+            PermissionCheck::Pending { first_request } => {
+                if first_request {
+                    self.request_permission(permission);
+                }
+                Err(rquickjs::Exception::throw_message(
+                    ctx,
+                    &format!(
+                        "Plugin '{}' is waiting for the user to allow '{}'",
+                        self.plugin_name, permission
+                    ),
+                ))
+            }
+            PermissionCheck::Denied(message) => {
+                Err(rquickjs::Exception::throw_message(ctx, &message))
+            }
+        }
+    }
+
+    /// Fail a call that opens `path` unless the file is already open or this
+    /// plugin may use `fs-read`, since an opened file can be read back
+    fn require_read_unless_open(
+        &self,
+        ctx: &rquickjs::Ctx<'_>,
+        path: &Path,
+    ) -> rquickjs::Result<()> {
+        let is_open = self
+            .state_snapshot
+            .read()
+            .map(|s| s.buffers.values().any(|b| b.path.as_deref() == Some(path)))
+            .unwrap_or(false);
+        if is_open {
+            return Ok(());
+        }
+        self.require_permission(ctx, PluginPermission::FsRead)
+    }
+
+    /// Send an async command that needs `permission`
+    ///
+    /// While the user hasn't answered, the command is held back and sent (or
+    /// its callback rejected) once they do.
+    fn send_with_permission(
+        &self,
+        ctx: &rquickjs::Ctx<'_>,
+        permission: PluginPermission,
+        callback_id: u64,
+        command: PluginCommand,
+    ) -> rquickjs::Result<()> {
+        let check = self
+            .permissions
+            .borrow_mut()
+            .check(&self.plugin_name, permission);
+        match check {
+            PermissionCheck::Allowed => {
+                let _ = self.command_sender.send(command);
+                Ok(())
+            }
+            PermissionCheck::Pending { first_request } => {
+                self.permissions.borrow_mut().defer(
+                    &self.plugin_name,
+                    permission,
+                    callback_id,
+                    command,
+                );
+                if first_request {
+                    self.request_permission(permission);
+                }
+                Ok(())
+            }
+            PermissionCheck::Denied(message) => {
+                self.callback_contexts.borrow_mut().remove(&callback_id);
+                Err(rquickjs::Exception::throw_message(ctx, &message))
+            }
+        }
+    }
+
+    fn request_permission(&self, permission: PluginPermission) {
+        let _ = self
+            .command_sender
+            .send(PluginCommand::RequestPluginPermission {
+                plugin_name: self.plugin_name.clone(),
+                permission,
+            });
+    }
+}
+
 #[plugin_api_impl]
 #[rquickjs::methods(rename_all = "camelCase")]
 impl JsEditorApi {
@@ -545,6 +662,50 @@ impl JsEditorApi {
             .send(PluginCommand::SetStatus { message: msg });
     }
 
+    /// Set the text shown by the `plugin:<id>` status line segment
+    /// (empty text hides it). Segments are placed via the `status_line` config.
+    pub fn set_status_segment(&self, id: String, text: String) {
+        let _ = self
+            .command_sender
+            .send(PluginCommand::SetStatusSegment { id, text });
+    }
+
+    /// Add a quickfix list (e.g. search results) as the newest in the
+    /// quickfix history; `selected` is the index of the entry the user is at
+    pub fn set_quickfix_list(
+        &self,
+        title: String,
+        entries: Vec<QuickfixEntry>,
+        selected: rquickjs::function::Opt<u32>,
+    ) -> bool {
+        self.command_sender
+            .send(PluginCommand::SetQuickfixList {
+                title,
+                entries,
+                selected: selected.0.map(|i| i as usize),
+            })
+            .is_ok()
+    }
+
+    /// Set a mark at the cursor (`a`-`z` for this buffer, `A`-`Z` for a
+    /// file mark)
+    pub fn set_mark(&self, name: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::SetMark { name })
+            .is_ok()
+    }
+
+    /// Move the cursor to a mark; with `lineStart`, to the first non-blank
+    /// character of its line
+    pub fn jump_to_mark(&self, name: String, line_start: rquickjs::function::Opt<bool>) -> bool {
+        self.command_sender
+            .send(PluginCommand::JumpToMark {
+                name,
+                line_start: line_start.0.unwrap_or(false),
+            })
+            .is_ok()
+    }
+
     // === Clipboard ===
 
     pub fn copy_to_clipboard(&self, text: String) {
@@ -628,9 +789,26 @@ impl JsEditorApi {
     }
 
     /// Execute a built-in action
-    pub fn execute_action(&self, action_name: String) -> bool {
-        self.command_sender
+    ///
+    /// Saving requires the `fs-write` permission for packaged plugins.
+    pub fn execute_action<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        action_name: String,
+    ) -> rquickjs::Result<bool> {
+        if FILE_WRITING_ACTIONS.contains(&action_name.as_str()) {
+            self.require_permission(&ctx, PluginPermission::FsWrite)?;
+        }
+        Ok(self
+            .command_sender
             .send(PluginCommand::ExecuteAction { action_name })
+            .is_ok())
+    }
+
+    /// Set the status the editor process exits with
+    pub fn set_exit_code(&self, code: i32) -> bool {
+        self.command_sender
+            .send(PluginCommand::SetExitCode { code })
             .is_ok()
     }
 
@@ -674,6 +852,27 @@ impl JsEditorApi {
         res
     }
 
+    /// Format a number with the current locale's decimal and grouping
+    /// separators, optionally rounded to `decimals` fractional digits
+    pub fn format_number(&self, value: f64, decimals: rquickjs::function::Opt<u32>) -> String {
+        self.services
+            .format_number(value, decimals.0.map(|d| d as usize))
+    }
+
+    /// Format a date in the current locale
+    /// `format` is "date", "time", "date_time", "long_date", "iso_date",
+    /// "iso_date_time", "unix" or a strftime pattern; `timestamp` is in
+    /// milliseconds like `Date.now()` and defaults to now.
+    /// Returns null for an invalid pattern
+    pub fn format_date(
+        &self,
+        format: String,
+        timestamp: rquickjs::function::Opt<f64>,
+    ) -> Option<String> {
+        self.services
+            .format_date(&format, timestamp.0.map(|ms| ms as i64))
+    }
+
     // === Buffer Queries (additional) ===
 
     /// Get cursor position in active buffer
@@ -780,6 +979,42 @@ impl JsEditorApi {
             .map_err(|e| rquickjs::Error::new_from_js_message("serialize", "", &e.to_string()))
     }
 
+    /// Get all selections in the active buffer, primary first and the rest by position
+    #[plugin_api(ts_return = "TextSelection[]")]
+    pub fn get_selections<'js>(&self, ctx: rquickjs::Ctx<'js>) -> rquickjs::Result<Value<'js>> {
+        fn to_selection(cursor: &CursorInfo) -> TextSelection {
+            let anchor = match &cursor.selection {
+                Some(range) if range.start == cursor.position => range.end,
+                Some(range) => range.start,
+                None => cursor.position,
+            };
+            TextSelection {
+                anchor,
+                position: cursor.position,
+            }
+        }
+
+        let selections: Vec<TextSelection> = if let Ok(s) = self.state_snapshot.read() {
+            let mut others: Vec<&CursorInfo> = s.all_cursors.iter().collect();
+            let primary = s.primary_cursor.as_ref().and_then(|p| {
+                others
+                    .iter()
+                    .position(|c| c.position == p.position && c.selection == p.selection)
+                    .map(|i| others.remove(i))
+            });
+            others.sort_by_key(|c| c.position);
+            primary
+                .into_iter()
+                .chain(others)
+                .map(to_selection)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        rquickjs_serde::to_value(ctx, &selections)
+            .map_err(|e| rquickjs::Error::new_from_js_message("serialize", "", &e.to_string()))
+    }
+
     /// Get viewport info for active buffer
     #[plugin_api(ts_return = "ViewportInfo | null")]
     pub fn get_viewport<'js>(&self, ctx: rquickjs::Ctx<'js>) -> rquickjs::Result<Value<'js>> {
@@ -952,6 +1187,38 @@ impl JsEditorApi {
             .is_ok()
     }
 
+    /// Replace a range of a buffer with new text as a single undo step
+    pub fn replace_range(&self, buffer_id: u32, range: TextRange, text: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::ReplaceRange {
+                buffer_id: BufferId(buffer_id as usize),
+                range: range.start..range.end,
+                text,
+            })
+            .is_ok()
+    }
+
+    /// Insert text at a byte offset in a buffer as a single undo step
+    pub fn insert_at(&self, buffer_id: u32, position: u32, text: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::ReplaceRange {
+                buffer_id: BufferId(buffer_id as usize),
+                range: (position as usize)..(position as usize),
+                text,
+            })
+            .is_ok()
+    }
+
+    /// Replace all cursors in a buffer; the first selection becomes primary
+    pub fn set_selections(&self, buffer_id: u32, selections: Vec<TextSelection>) -> bool {
+        self.command_sender
+            .send(PluginCommand::SetSelections {
+                buffer_id: BufferId(buffer_id as usize),
+                selections,
+            })
+            .is_ok()
+    }
+
     /// Insert text at cursor position in active buffer
     pub fn insert_at_cursor(&self, text: String) -> bool {
         self.command_sender
@@ -962,25 +1229,58 @@ impl JsEditorApi {
     // === File Operations ===
 
     /// Open a file, optionally at a specific line/column
-    pub fn open_file(&self, path: String, line: Option<u32>, column: Option<u32>) -> bool {
-        self.command_sender
+    ///
+    /// Requires the `fs-read` permission for packaged plugins, unless the
+    /// file is already open.
+    pub fn open_file<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        path: String,
+        line: Option<u32>,
+        column: Option<u32>,
+    ) -> rquickjs::Result<bool> {
+        let path = PathBuf::from(path);
+        self.require_read_unless_open(&ctx, &path)?;
+        Ok(self
+            .command_sender
             .send(PluginCommand::OpenFileAtLocation {
-                path: PathBuf::from(path),
+                path,
                 line: line.map(|l| l as usize),
                 column: column.map(|c| c as usize),
             })
-            .is_ok()
+            .is_ok())
     }
 
     /// Open a file in a specific split
-    pub fn open_file_in_split(&self, split_id: u32, path: String, line: u32, column: u32) -> bool {
-        self.command_sender
+    ///
+    /// Requires the `fs-read` permission for packaged plugins, unless the
+    /// file is already open.
+    pub fn open_file_in_split<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        split_id: u32,
+        path: String,
+        line: u32,
+        column: u32,
+    ) -> rquickjs::Result<bool> {
+        let path = PathBuf::from(path);
+        self.require_read_unless_open(&ctx, &path)?;
+        Ok(self
+            .command_sender
             .send(PluginCommand::OpenFileInSplit {
                 split_id: split_id as usize,
-                path: PathBuf::from(path),
+                path,
                 line: Some(line as usize),
                 column: Some(column as usize),
             })
+            .is_ok())
+    }
+
+    /// Claim a URI scheme such as "pr": opening `pr://...` fires the
+    /// `uri_open_requested` hook instead of reading a file
+    pub fn register_uri_scheme(&self, scheme: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::RegisterUriScheme { scheme })
             .is_ok()
     }
 
@@ -1005,21 +1305,36 @@ impl JsEditorApi {
     // === Event Handling ===
 
     /// Subscribe to an editor event
+    ///
+    /// Subscribing the same handler twice is a no-op.
     pub fn on<'js>(&self, _ctx: rquickjs::Ctx<'js>, event_name: String, handler_name: String) {
-        self.event_handlers
-            .borrow_mut()
-            .entry(event_name)
-            .or_default()
-            .push(PluginHandler {
+        let mut handlers = self.event_handlers.borrow_mut();
+        let list = handlers.entry(event_name).or_default();
+        if !list
+            .iter()
+            .any(|h| h.plugin_name == self.plugin_name && h.handler_name == handler_name)
+        {
+            list.push(PluginHandler {
                 plugin_name: self.plugin_name.clone(),
                 handler_name,
             });
+        }
     }
 
     /// Unsubscribe from an event
+    ///
+    /// Only removes this plugin's subscription; other plugins using the same
+    /// handler name keep theirs.
     pub fn off(&self, event_name: String, handler_name: String) {
         if let Some(list) = self.event_handlers.borrow_mut().get_mut(&event_name) {
-            list.retain(|h| h.handler_name != handler_name);
+            list.retain(|h| h.plugin_name != self.plugin_name || h.handler_name != handler_name);
+        }
+    }
+
+    /// Unsubscribe this plugin from every event it listens to
+    pub fn off_all(&self) {
+        for list in self.event_handlers.borrow_mut().values_mut() {
+            list.retain(|h| h.plugin_name != self.plugin_name);
         }
     }
 
@@ -1121,21 +1436,45 @@ impl JsEditorApi {
     // === File System ===
 
     /// Check if file exists
-    pub fn file_exists(&self, path: String) -> bool {
-        Path::new(&path).exists()
+    ///
+    /// Requires the `fs-read` permission for packaged plugins.
+    pub fn file_exists<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        path: String,
+    ) -> rquickjs::Result<bool> {
+        self.require_permission(&ctx, PluginPermission::FsRead)?;
+        Ok(Path::new(&path).exists())
     }
 
     /// Read file contents
-    pub fn read_file(&self, path: String) -> Option<String> {
-        std::fs::read_to_string(&path).ok()
+    ///
+    /// Requires the `fs-read` permission for packaged plugins.
+    pub fn read_file<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        path: String,
+    ) -> rquickjs::Result<Option<String>> {
+        self.require_permission(&ctx, PluginPermission::FsRead)?;
+        Ok(std::fs::read_to_string(&path).ok())
     }
 
     /// Write file contents
-    pub fn write_file(&self, path: String, content: String) -> bool {
-        std::fs::write(&path, content).is_ok()
+    ///
+    /// Requires the `fs-write` permission for packaged plugins.
+    pub fn write_file<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        path: String,
+        content: String,
+    ) -> rquickjs::Result<bool> {
+        self.require_permission(&ctx, PluginPermission::FsWrite)?;
+        Ok(std::fs::write(&path, content).is_ok())
     }
 
     /// Read directory contents (returns array of {name, is_file, is_dir})
+    ///
+    /// Requires the `fs-read` permission for packaged plugins.
     #[plugin_api(ts_return = "DirEntry[]")]
     pub fn read_dir<'js>(
         &self,
@@ -1144,6 +1483,8 @@ impl JsEditorApi {
     ) -> rquickjs::Result<Value<'js>> {
         use fresh_core::api::DirEntry;
 
+        self.require_permission(&ctx, PluginPermission::FsRead)?;
+
         let entries: Vec<DirEntry> = match std::fs::read_dir(&path) {
             Ok(entries) => entries
                 .filter_map(|e| e.ok())
@@ -1261,6 +1602,22 @@ impl JsEditorApi {
             .is_ok()
     }
 
+    /// Change one color of the active theme, e.g. `setThemeColor("editor.bg", [30, 30, 30])`
+    ///
+    /// Colors use the theme file format (`[r, g, b]` or a named color). The
+    /// change is a preview: it lasts until the theme is changed or reloaded.
+    pub fn set_theme_color<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        key: String,
+        color: ThemeColor<'js>,
+    ) -> bool {
+        let color = js_to_json(&ctx, color);
+        self.command_sender
+            .send(PluginCommand::SetThemeColor { key, color })
+            .is_ok()
+    }
+
     /// Get theme schema as JS object
     pub fn get_theme_schema<'js>(&self, ctx: rquickjs::Ctx<'js>) -> rquickjs::Result<Value<'js>> {
         let schema = self.services.get_theme_schema();
@@ -1306,6 +1663,7 @@ impl JsEditorApi {
         ctx: rquickjs::Ctx<'js>,
         path: String,
     ) -> rquickjs::Result<Value<'js>> {
+        self.require_permission(&ctx, PluginPermission::FsRead)?;
         let metadata = std::fs::metadata(&path).ok();
         let stat = metadata.map(|m| {
             serde_json::json!({
@@ -1817,6 +2175,68 @@ impl JsEditorApi {
         id
     }
 
+    /// Show a filterable list of items and wait for a pick (async)
+    /// Returns the picked item's value (or label), or null if cancelled
+    #[plugin_api(async_promise, js_name = "showQuickPick", ts_return = "string | null")]
+    #[qjs(rename = "_showQuickPickStart")]
+    pub fn show_quick_pick_start(
+        &self,
+        _ctx: rquickjs::Ctx<'_>,
+        items: Vec<QuickPickItem>,
+        opts: rquickjs::function::Opt<QuickPickOptions>,
+    ) -> u64 {
+        let id = {
+            let mut id_ref = self.next_request_id.borrow_mut();
+            let id = *id_ref;
+            *id_ref += 1;
+            // Record context for this callback
+            self.callback_contexts
+                .borrow_mut()
+                .insert(id, self.plugin_name.clone());
+            id
+        };
+        let opts = opts.0.unwrap_or_default();
+        let _ = self
+            .command_sender
+            .send(PluginCommand::StartQuickPickAsync {
+                title: opts.title.unwrap_or_default(),
+                items,
+                match_on_description: opts.match_on_description.unwrap_or(false),
+                callback_id: JsCallbackId::new(id),
+            });
+
+        id
+    }
+
+    /// Show a free-text input box and wait for input (async)
+    /// Returns the entered text, or null if cancelled
+    #[plugin_api(async_promise, js_name = "showInputBox", ts_return = "string | null")]
+    #[qjs(rename = "_showInputBoxStart")]
+    pub fn show_input_box_start(
+        &self,
+        _ctx: rquickjs::Ctx<'_>,
+        opts: rquickjs::function::Opt<InputBoxOptions>,
+    ) -> u64 {
+        let id = {
+            let mut id_ref = self.next_request_id.borrow_mut();
+            let id = *id_ref;
+            *id_ref += 1;
+            // Record context for this callback
+            self.callback_contexts
+                .borrow_mut()
+                .insert(id, self.plugin_name.clone());
+            id
+        };
+        let opts = opts.0.unwrap_or_default();
+        let _ = self.command_sender.send(PluginCommand::StartPromptAsync {
+            label: opts.title.unwrap_or_default(),
+            initial_value: opts.value.unwrap_or_default(),
+            callback_id: JsCallbackId::new(id),
+        });
+
+        id
+    }
+
     /// Start an interactive prompt
     pub fn start_prompt(&self, label: String, prompt_type: String) -> bool {
         self.command_sender
@@ -2090,11 +2510,23 @@ impl JsEditorApi {
 
     /// Execute multiple actions in sequence
     ///
-    /// Takes typed ActionSpec array - serde validates field names at runtime
-    pub fn execute_actions(&self, actions: Vec<ActionSpec>) -> bool {
-        self.command_sender
+    /// Takes typed ActionSpec array - serde validates field names at runtime.
+    /// Saving requires the `fs-write` permission for packaged plugins.
+    pub fn execute_actions<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        actions: Vec<ActionSpec>,
+    ) -> rquickjs::Result<bool> {
+        if actions
+            .iter()
+            .any(|a| FILE_WRITING_ACTIONS.contains(&a.action.as_str()))
+        {
+            self.require_permission(&ctx, PluginPermission::FsWrite)?;
+        }
+        Ok(self
+            .command_sender
             .send(PluginCommand::ExecuteActions { actions })
-            .is_ok()
+            .is_ok())
     }
 
     /// Show an action popup
@@ -2234,6 +2666,7 @@ impl JsEditorApi {
                 show_cursors: opts.show_cursors.unwrap_or(true),
                 editing_disabled: opts.editing_disabled.unwrap_or(false),
                 hidden_from_tabs: opts.hidden_from_tabs.unwrap_or(false),
+                uri: opts.uri,
                 request_id: Some(id),
             });
         Ok(id)
@@ -2365,6 +2798,48 @@ impl JsEditorApi {
             .is_ok())
     }
 
+    /// Open a read-only output buffer named `*name*` (async, resolves to the buffer ID)
+    ///
+    /// Reuses the existing buffer if one with the same name is already open.
+    #[plugin_api(async_promise, js_name = "createOutputBuffer", ts_return = "number")]
+    #[qjs(rename = "_createOutputBufferStart")]
+    pub fn create_output_buffer_start(&self, _ctx: rquickjs::Ctx<'_>, name: String) -> u64 {
+        let id = {
+            let mut id_ref = self.next_request_id.borrow_mut();
+            let id = *id_ref;
+            *id_ref += 1;
+            // Record context for this callback
+            self.callback_contexts
+                .borrow_mut()
+                .insert(id, self.plugin_name.clone());
+            id
+        };
+        let _ = self.command_sender.send(PluginCommand::CreateOutputBuffer {
+            name,
+            request_id: id,
+        });
+        id
+    }
+
+    /// Append text to the end of an output buffer
+    pub fn append_output(&self, buffer_id: u32, text: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::AppendOutput {
+                buffer_id: BufferId(buffer_id as usize),
+                text,
+            })
+            .is_ok()
+    }
+
+    /// Remove all text from an output buffer
+    pub fn clear_output(&self, buffer_id: u32) -> bool {
+        self.command_sender
+            .send(PluginCommand::ClearOutput {
+                buffer_id: BufferId(buffer_id as usize),
+            })
+            .is_ok()
+    }
+
     /// Get text properties at cursor position (returns JS array)
     pub fn get_text_properties_at_cursor(
         &self,
@@ -2380,11 +2855,11 @@ impl JsEditorApi {
     #[qjs(rename = "_spawnProcessStart")]
     pub fn spawn_process_start(
         &self,
-        _ctx: rquickjs::Ctx<'_>,
+        ctx: rquickjs::Ctx<'_>,
         command: String,
         args: Vec<String>,
         cwd: rquickjs::function::Opt<String>,
-    ) -> u64 {
+    ) -> rquickjs::Result<u64> {
         let id = {
             let mut id_ref = self.next_request_id.borrow_mut();
             let id = *id_ref;
@@ -2409,13 +2884,18 @@ impl JsEditorApi {
             effective_cwd,
             id
         );
-        let _ = self.command_sender.send(PluginCommand::SpawnProcess {
-            callback_id: JsCallbackId::new(id),
-            command,
-            args,
-            cwd: effective_cwd,
-        });
-        id
+        self.send_with_permission(
+            &ctx,
+            PluginPermission::Process,
+            id,
+            PluginCommand::SpawnProcess {
+                callback_id: JsCallbackId::new(id),
+                command,
+                args,
+                cwd: effective_cwd,
+            },
+        )?;
+        Ok(id)
     }
 
     /// Wait for a process to complete and get its result (async)
@@ -2468,6 +2948,29 @@ impl JsEditorApi {
         id
     }
 
+    /// Get the text of a byte range in a buffer (async, returns request_id)
+    #[plugin_api(async_promise, js_name = "getText", ts_return = "string")]
+    #[qjs(rename = "_getTextStart")]
+    pub fn get_text_start(&self, _ctx: rquickjs::Ctx<'_>, buffer_id: u32, range: TextRange) -> u64 {
+        let id = {
+            let mut id_ref = self.next_request_id.borrow_mut();
+            let id = *id_ref;
+            *id_ref += 1;
+            // Record context for this callback
+            self.callback_contexts
+                .borrow_mut()
+                .insert(id, self.plugin_name.clone());
+            id
+        };
+        let _ = self.command_sender.send(PluginCommand::GetBufferText {
+            buffer_id: BufferId(buffer_id as usize),
+            start: range.start,
+            end: range.end,
+            request_id: id,
+        });
+        id
+    }
+
     /// Delay/sleep (async, returns request_id)
     #[plugin_api(async_promise, js_name = "delay", ts_return = "void")]
     #[qjs(rename = "_delayStart")]
@@ -2489,6 +2992,82 @@ impl JsEditorApi {
         id
     }
 
+    /// Run a long job as a background task (async, resolves with the job's result)
+    ///
+    /// `job` is called with a `TaskHandle` and may return a value or a promise.
+    /// While it runs, the status bar shows a spinner with the task's progress.
+    /// If the user cancels the task the promise rejects, and `task.cancelled`
+    /// becomes true so the job can stop early. The result is passed back as JSON.
+    #[plugin_api(async_promise, js_name = "runTask", ts_return = "unknown")]
+    #[qjs(rename = "_runTaskStart")]
+    pub fn run_task_start<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        name: String,
+        job: TaskJob<'js>,
+    ) -> rquickjs::Result<u64> {
+        let id = {
+            let mut id_ref = self.next_request_id.borrow_mut();
+            let id = *id_ref;
+            *id_ref += 1;
+            // Record context for this callback
+            self.callback_contexts
+                .borrow_mut()
+                .insert(id, self.plugin_name.clone());
+            id
+        };
+        let _ = self.command_sender.send(PluginCommand::StartTask {
+            task_id: JsCallbackId::new(id),
+            plugin_name: self.plugin_name.clone(),
+            name,
+        });
+        let run: Function = ctx.globals().get("_runTask")?;
+        run.call::<_, ()>((id, job))?;
+        Ok(id)
+    }
+
+    /// Report progress for a background task (percentage is 0-100)
+    pub fn report_task_progress(
+        &self,
+        task_id: u64,
+        message: Option<String>,
+        percentage: Option<u32>,
+    ) -> bool {
+        self.command_sender
+            .send(PluginCommand::UpdateTaskProgress {
+                task_id: JsCallbackId::new(task_id),
+                message,
+                percentage: percentage.map(|p| p.min(100)),
+            })
+            .is_ok()
+    }
+
+    /// Check whether the user cancelled a background task
+    pub fn is_task_cancelled(&self, task_id: u64) -> bool {
+        self.cancelled_tasks.borrow().contains(&task_id)
+    }
+
+    /// Report the outcome of a background task's job (called by `runTask`)
+    #[plugin_api(skip)]
+    #[qjs(rename = "_finishTask")]
+    pub fn finish_task<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        task_id: u64,
+        result: Value<'js>,
+        error: Option<String>,
+    ) -> bool {
+        self.cancelled_tasks.borrow_mut().remove(&task_id);
+        let result = (error.is_none()).then(|| js_to_json(&ctx, result));
+        self.command_sender
+            .send(PluginCommand::FinishTask {
+                task_id: JsCallbackId::new(task_id),
+                result,
+                error,
+            })
+            .is_ok()
+    }
+
     /// Send LSP request (async, returns request_id)
     #[plugin_api(async_promise, js_name = "sendLspRequest", ts_return = "unknown")]
     #[qjs(rename = "_sendLspRequestStart")]
@@ -2532,11 +3111,11 @@ impl JsEditorApi {
     #[qjs(rename = "_spawnBackgroundProcessStart")]
     pub fn spawn_background_process_start(
         &self,
-        _ctx: rquickjs::Ctx<'_>,
+        ctx: rquickjs::Ctx<'_>,
         command: String,
         args: Vec<String>,
         cwd: rquickjs::function::Opt<String>,
-    ) -> u64 {
+    ) -> rquickjs::Result<u64> {
         let id = {
             let mut id_ref = self.next_request_id.borrow_mut();
             let id = *id_ref;
@@ -2549,16 +3128,19 @@ impl JsEditorApi {
         };
         // Use id as process_id for simplicity
         let process_id = id;
-        let _ = self
-            .command_sender
-            .send(PluginCommand::SpawnBackgroundProcess {
+        self.send_with_permission(
+            &ctx,
+            PluginPermission::Process,
+            id,
+            PluginCommand::SpawnBackgroundProcess {
                 process_id,
                 command,
                 args,
                 cwd: cwd.0,
                 callback_id: JsCallbackId::new(id),
-            });
-        id
+            },
+        )?;
+        Ok(id)
     }
 
     /// Kill a background process
@@ -2863,6 +3445,10 @@ pub struct QuickJsBackend {
     callback_contexts: Rc<RefCell<HashMap<u64, String>>>,
     /// Bridge for editor services (i18n, theme, etc.)
     pub services: Arc<dyn fresh_core::services::PluginServiceBridge>,
+    /// Sandbox permissions for packaged plugins
+    pub permissions: Rc<RefCell<PluginPermissions>>,
+    /// Background tasks the user cancelled whose job hasn't finished yet
+    cancelled_tasks: Rc<RefCell<HashSet<u64>>>,
 }
 
 impl QuickJsBackend {
@@ -2944,6 +3530,8 @@ impl QuickJsBackend {
             next_request_id,
             callback_contexts,
             services,
+            permissions: Rc::new(RefCell::new(PluginPermissions::new())),
+            cancelled_tasks: Rc::new(RefCell::new(HashSet::new())),
         };
 
         // Initialize main context (for internal utilities if needed)
@@ -2977,6 +3565,8 @@ impl QuickJsBackend {
                 next_request_id: Rc::clone(&next_request_id),
                 callback_contexts: Rc::clone(&self.callback_contexts),
                 services: self.services.clone(),
+                permissions: Rc::clone(&self.permissions),
+                cancelled_tasks: Rc::clone(&self.cancelled_tasks),
                 plugin_name: plugin_name.to_string(),
             };
             let editor = rquickjs::Class::<JsEditorApi>::instance(ctx.clone(), js_api)?;
@@ -3032,6 +3622,23 @@ impl QuickJsBackend {
                     }
                 };
 
+                // Run a background task's job (called from _runTaskStart)
+                globalThis._runTask = function(taskId, job) {
+                    const task = {
+                        id: taskId,
+                        get cancelled() { return editor.isTaskCancelled(taskId); },
+                        progress(message, percentage) {
+                            editor.reportTaskProgress(taskId, message ?? null, percentage == null ? null : Math.round(percentage));
+                        },
+                    };
+                    Promise.resolve()
+                        .then(() => job(task))
+                        .then(
+                            (result) => editor._finishTask(taskId, result, null),
+                            (error) => editor._finishTask(taskId, null, String(error instanceof Error ? error.message : error)),
+                        );
+                };
+
                 // Generic async wrapper decorator
                 // Wraps a function that returns a callbackId into a promise-returning function
                 // Usage: editor.foo = _wrapAsync("_fooStart", "foo");
@@ -3092,6 +3699,7 @@ impl QuickJsBackend {
                 // Apply wrappers to async functions on editor
                 editor.spawnProcess = _wrapAsyncThenable("_spawnProcessStart", "spawnProcess");
                 editor.delay = _wrapAsync("_delayStart", "delay");
+                editor.runTask = _wrapAsync("_runTaskStart", "runTask");
                 editor.createVirtualBuffer = _wrapAsync("_createVirtualBufferStart", "createVirtualBuffer");
                 editor.createVirtualBufferInSplit = _wrapAsync("_createVirtualBufferInSplitStart", "createVirtualBufferInSplit");
                 editor.createVirtualBufferInExistingSplit = _wrapAsync("_createVirtualBufferInExistingSplitStart", "createVirtualBufferInExistingSplit");
@@ -3099,6 +3707,7 @@ impl QuickJsBackend {
                 editor.spawnBackgroundProcess = _wrapAsyncThenable("_spawnBackgroundProcessStart", "spawnBackgroundProcess");
                 editor.spawnProcessWait = _wrapAsync("_spawnProcessWaitStart", "spawnProcessWait");
                 editor.getBufferText = _wrapAsync("_getBufferTextStart", "getBufferText");
+                editor.getText = _wrapAsync("_getTextStart", "getText");
                 editor.createCompositeBuffer = _wrapAsync("_createCompositeBufferStart", "createCompositeBuffer");
                 editor.getHighlights = _wrapAsync("_getHighlightsStart", "getHighlights");
                 editor.loadPlugin = _wrapAsync("_loadPluginStart", "loadPlugin");
@@ -3106,6 +3715,9 @@ impl QuickJsBackend {
                 editor.reloadPlugin = _wrapAsync("_reloadPluginStart", "reloadPlugin");
                 editor.listPlugins = _wrapAsync("_listPluginsStart", "listPlugins");
                 editor.prompt = _wrapAsync("_promptStart", "prompt");
+                editor.showQuickPick = _wrapAsync("_showQuickPickStart", "showQuickPick");
+                editor.showInputBox = _wrapAsync("_showInputBoxStart", "showInputBox");
+                editor.createOutputBuffer = _wrapAsync("_createOutputBufferStart", "createOutputBuffer");
                 editor.getLineStartPosition = _wrapAsync("_getLineStartPositionStart", "getLineStartPosition");
                 editor.getLineEndPosition = _wrapAsync("_getLineEndPositionStart", "getLineEndPosition");
 
@@ -3135,6 +3747,10 @@ impl QuickJsBackend {
         _plugin_source: &str,
     ) -> Result<()> {
         let path_buf = PathBuf::from(path);
+        if path_buf.extension().and_then(|e| e.to_str()) == Some("lua") {
+            return self.load_lua_plugin(&path_buf);
+        }
+
         let source = std::fs::read_to_string(&path_buf)
             .map_err(|e| anyhow!("Failed to read plugin {}: {}", path, e))?;
 
@@ -3181,6 +3797,43 @@ impl QuickJsBackend {
         Ok(())
     }
 
+    /// Load a Lua plugin (packages with `"fresh": {"runtime": "lua"}`)
+    #[cfg(feature = "lua")]
+    fn load_lua_plugin(&mut self, path: &Path) -> Result<()> {
+        let plugin_name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown");
+        let context = self.plugin_context(plugin_name)?;
+        super::lua_backend::load_plugin(&context, plugin_name, path)
+    }
+
+    #[cfg(not(feature = "lua"))]
+    fn load_lua_plugin(&mut self, path: &Path) -> Result<()> {
+        Err(anyhow!(
+            "Cannot load {}: Lua plugins require the `lua` feature",
+            path.display()
+        ))
+    }
+
+    /// Get or create the JS context for a plugin
+    fn plugin_context(&self, plugin_name: &str) -> Result<Context> {
+        let mut contexts = self.plugin_contexts.borrow_mut();
+        if let Some(ctx) = contexts.get(plugin_name) {
+            return Ok(ctx.clone());
+        }
+        let ctx = Context::full(&self.runtime).map_err(|e| {
+            anyhow!(
+                "Failed to create QuickJS context for plugin {}: {}",
+                plugin_name,
+                e
+            )
+        })?;
+        self.setup_context_api(&ctx, plugin_name)?;
+        contexts.insert(plugin_name.to_string(), ctx.clone());
+        Ok(ctx)
+    }
+
     /// Execute JavaScript code in the context
     fn execute_js(&mut self, code: &str, source_name: &str) -> Result<()> {
         // Extract plugin name from path (filename without extension)
@@ -3195,24 +3848,7 @@ impl QuickJsBackend {
             source_name
         );
 
-        // Get or create context for this plugin
-        let context = {
-            let mut contexts = self.plugin_contexts.borrow_mut();
-            if let Some(ctx) = contexts.get(plugin_name) {
-                ctx.clone()
-            } else {
-                let ctx = Context::full(&self.runtime).map_err(|e| {
-                    anyhow!(
-                        "Failed to create QuickJS context for plugin {}: {}",
-                        plugin_name,
-                        e
-                    )
-                })?;
-                self.setup_context_api(&ctx, plugin_name)?;
-                contexts.insert(plugin_name.to_string(), ctx.clone());
-                ctx
-            }
-        };
+        let context = self.plugin_context(plugin_name)?;
 
         // Wrap plugin code in IIFE to prevent TDZ errors and scope pollution
         // This is critical for plugins like vi_mode that declare `const editor = ...`
@@ -3310,6 +3946,31 @@ impl QuickJsBackend {
         Ok(true)
     }
 
+    /// Remove every event handler and action registered by a plugin (used on unload)
+    pub fn remove_plugin_handlers(&self, plugin_name: &str) {
+        let mut handlers = self.event_handlers.borrow_mut();
+        for list in handlers.values_mut() {
+            list.retain(|h| h.plugin_name != plugin_name);
+        }
+        handlers.retain(|_, list| !list.is_empty());
+
+        self.registered_actions
+            .borrow_mut()
+            .retain(|_, h| h.plugin_name != plugin_name);
+    }
+
+    /// Drop a plugin's JS context so a reload starts from fresh globals
+    ///
+    /// Callbacks still pending for the plugin are discarded when they resolve.
+    pub fn remove_plugin_context(&self, plugin_name: &str) {
+        self.plugin_contexts.borrow_mut().remove(plugin_name);
+    }
+
+    /// Bytes allocated by the QuickJS runtime, shared by all plugin contexts
+    pub fn memory_usage(&self) -> usize {
+        self.runtime.memory_usage().malloc_size.max(0) as usize
+    }
+
     /// Check if any handlers are registered for an event
     pub fn has_handlers(&self, event_name: &str) -> bool {
         self.event_handlers
@@ -3574,6 +4235,44 @@ impl QuickJsBackend {
         });
     }
 
+    /// Apply the user's answer to a permission prompt
+    ///
+    /// Commands that were held back waiting for the answer are sent, or their
+    /// callbacks rejected when the permission was denied.
+    pub fn resolve_permission(
+        &mut self,
+        plugin_name: &str,
+        permission: PluginPermission,
+        granted: bool,
+    ) {
+        let released = self
+            .permissions
+            .borrow_mut()
+            .resolve(plugin_name, permission, granted);
+        for (callback_id, command) in released {
+            if granted {
+                let _ = self.command_sender.send(command);
+            } else {
+                self.reject_callback(
+                    JsCallbackId::new(callback_id),
+                    &format!(
+                        "Permission '{}' was denied for plugin '{}'",
+                        permission, plugin_name
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Cancel a background task at the user's request
+    ///
+    /// The `runTask` promise rejects right away; the job keeps running until it
+    /// checks `task.cancelled`.
+    pub fn cancel_task(&mut self, task_id: fresh_core::api::JsCallbackId) {
+        self.cancelled_tasks.borrow_mut().insert(task_id.as_u64());
+        self.reject_callback(task_id, "Task cancelled");
+    }
+
     /// Reject a pending async callback with an error (called from Rust when async op fails)
     pub fn reject_callback(&mut self, callback_id: fresh_core::api::JsCallbackId, error: &str) {
         let id = callback_id.as_u64();
@@ -3723,6 +4422,49 @@ mod tests {
         assert!(backend.has_handlers("test_event"));
     }
 
+    #[test]
+    fn test_event_subscriptions_are_scoped_per_plugin() {
+        let (mut backend, _rx) = create_test_backend();
+
+        for plugin in ["first.js", "second.js"] {
+            backend
+                .execute_js(
+                    r#"
+                const editor = getEditor();
+                globalThis.onSave = () => {};
+                editor.on("did_save", "onSave");
+                editor.on("did_save", "onSave");
+                editor.on("did_change", "onSave");
+            "#,
+                    plugin,
+                )
+                .unwrap();
+        }
+
+        // Subscribing twice doesn't duplicate the handler
+        assert_eq!(backend.event_handlers.borrow()["did_save"].len(), 2);
+
+        // off() only touches the calling plugin's subscription
+        backend
+            .execute_js(r#"getEditor().off("did_save", "onSave");"#, "first.js")
+            .unwrap();
+        let remaining: Vec<String> = backend.event_handlers.borrow()["did_save"]
+            .iter()
+            .map(|h| h.plugin_name.clone())
+            .collect();
+        assert_eq!(remaining, vec!["second".to_string()]);
+
+        backend
+            .execute_js(r#"getEditor().offAll();"#, "second.js")
+            .unwrap();
+        assert!(!backend.has_handlers("did_save"));
+        assert!(backend.has_handlers("did_change"));
+
+        // Unloading drops whatever the plugin still has registered
+        backend.remove_plugin_handlers("first");
+        assert!(!backend.has_handlers("did_change"));
+    }
+
     // ==================== API Tests ====================
 
     #[test]
@@ -4149,6 +4891,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_api_register_uri_scheme() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.registerUriScheme("pr");
+            editor.createVirtualBuffer({
+                name: "main.rs (PR #123)",
+                readOnly: true,
+                uri: "pr://owner/repo/123/src/main.rs"
+            });
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::RegisterUriScheme { scheme } => assert_eq!(scheme, "pr"),
+            cmd => panic!("Expected RegisterUriScheme, got {:?}", cmd),
+        }
+        match rx.try_recv().unwrap() {
+            PluginCommand::CreateVirtualBufferWithContent { uri, read_only, .. } => {
+                assert_eq!(uri.as_deref(), Some("pr://owner/repo/123/src/main.rs"));
+                assert!(read_only);
+            }
+            cmd => panic!("Expected CreateVirtualBufferWithContent, got {:?}", cmd),
+        }
+    }
+
     #[test]
     fn test_api_delete_range() {
         let (mut backend, rx) = create_test_backend();
@@ -4374,97 +5148,431 @@ mod tests {
     }
 
     #[test]
-    fn test_api_get_buffer_text_resolves_callback() {
+    fn test_api_get_text_sends_command() {
         let (mut backend, rx) = create_test_backend();
 
-        // Call getBufferText and set up a handler for when it resolves
         backend
             .execute_js(
                 r#"
             const editor = getEditor();
-            globalThis._resolvedText = null;
-            editor.getBufferText(0, 0, 100).then(text => {
-                globalThis._resolvedText = text;
-            });
+            globalThis._textPromise = editor.getText(3, { start: 4, end: 9 });
         "#,
                 "test.js",
             )
             .unwrap();
 
-        // Get the request_id from the command
-        let request_id = match rx.try_recv().unwrap() {
-            PluginCommand::GetBufferText { request_id, .. } => request_id,
-            cmd => panic!("Expected GetBufferText, got {:?}", cmd),
-        };
+        let cmd = rx.try_recv().unwrap();
+        match cmd {
+            PluginCommand::GetBufferText {
+                buffer_id,
+                start,
+                end,
+                request_id,
+            } => {
+                assert_eq!(buffer_id.0, 3);
+                assert_eq!(start, 4);
+                assert_eq!(end, 9);
+                assert!(request_id > 0);
+            }
+            _ => panic!("Expected GetBufferText, got {:?}", cmd),
+        }
+    }
 
-        // Simulate the editor responding with the text
-        backend.resolve_callback(JsCallbackId::from(request_id), "\"hello world\"");
+    #[test]
+    fn test_api_replace_range_and_insert_at() {
+        let (mut backend, rx) = create_test_backend();
 
-        // Drive the Promise to completion
         backend
-            .plugin_contexts
-            .borrow()
-            .get("test")
-            .unwrap()
-            .clone()
-            .with(|ctx| {
-                run_pending_jobs_checked(&ctx, "test async getText");
-            });
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.replaceRange(1, { start: 2, end: 5 }, "xyz");
+            editor.insertAt(1, 7, "!");
+        "#,
+                "test.js",
+            )
+            .unwrap();
 
-        // Verify the Promise resolved with the text
-        backend
-            .plugin_contexts
-            .borrow()
-            .get("test")
-            .unwrap()
-            .clone()
-            .with(|ctx| {
-                let global = ctx.globals();
-                let result: String = global.get("_resolvedText").unwrap();
-                assert_eq!(result, "hello world");
-            });
+        match rx.try_recv().unwrap() {
+            PluginCommand::ReplaceRange {
+                buffer_id,
+                range,
+                text,
+            } => {
+                assert_eq!(buffer_id.0, 1);
+                assert_eq!(range, 2..5);
+                assert_eq!(text, "xyz");
+            }
+            cmd => panic!("Expected ReplaceRange, got {:?}", cmd),
+        }
+        match rx.try_recv().unwrap() {
+            PluginCommand::ReplaceRange { range, text, .. } => {
+                assert_eq!(range, 7..7);
+                assert_eq!(text, "!");
+            }
+            cmd => panic!("Expected ReplaceRange, got {:?}", cmd),
+        }
     }
 
     #[test]
-    fn test_plugin_translation() {
-        let (mut backend, _rx) = create_test_backend();
+    fn test_api_set_selections() {
+        let (mut backend, rx) = create_test_backend();
 
-        // The t() function should work (returns key if translation not found)
         backend
             .execute_js(
                 r#"
             const editor = getEditor();
-            globalThis._translated = editor.t("test.key");
+            editor.setSelections(2, [
+                { anchor: 10, position: 4 },
+                { anchor: 20, position: 20 },
+            ]);
         "#,
                 "test.js",
             )
             .unwrap();
 
-        backend
-            .plugin_contexts
-            .borrow()
-            .get("test")
-            .unwrap()
-            .clone()
-            .with(|ctx| {
-                let global = ctx.globals();
-                // Without actual translations, it returns the key
-                let result: String = global.get("_translated").unwrap();
-                assert_eq!(result, "test.key");
-            });
+        match rx.try_recv().unwrap() {
+            PluginCommand::SetSelections {
+                buffer_id,
+                selections,
+            } => {
+                assert_eq!(buffer_id.0, 2);
+                assert_eq!(selections.len(), 2);
+                assert_eq!((selections[0].anchor, selections[0].position), (10, 4));
+                assert_eq!((selections[1].anchor, selections[1].position), (20, 20));
+            }
+            cmd => panic!("Expected SetSelections, got {:?}", cmd),
+        }
     }
 
     #[test]
-    fn test_plugin_translation_with_registered_strings() {
-        let (mut backend, _rx) = create_test_backend();
-
-        // Register translations for the test plugin
-        let mut en_strings = std::collections::HashMap::new();
-        en_strings.insert("greeting".to_string(), "Hello, World!".to_string());
-        en_strings.insert("prompt.find_file".to_string(), "Find file: ".to_string());
-
-        let mut strings = std::collections::HashMap::new();
-        strings.insert("en".to_string(), en_strings);
+    fn test_api_get_selections_from_state() {
+        let (tx, _rx) = mpsc::channel();
+        let state_snapshot = Arc::new(RwLock::new(EditorStateSnapshot::new()));
+        {
+            let mut state = state_snapshot.write().unwrap();
+            state.primary_cursor = Some(CursorInfo {
+                position: 30,
+                selection: Some(30..35),
+            });
+            state.all_cursors = vec![
+                CursorInfo {
+                    position: 12,
+                    selection: None,
+                },
+                CursorInfo {
+                    position: 30,
+                    selection: Some(30..35),
+                },
+                CursorInfo {
+                    position: 8,
+                    selection: Some(2..8),
+                },
+            ];
+        }
+
+        let services = Arc::new(fresh_core::services::NoopServiceBridge);
+        let mut backend = QuickJsBackend::with_state(state_snapshot, tx, services).unwrap();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._testResult = JSON.stringify(editor.getSelections());
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let result: String = ctx.globals().get("_testResult").unwrap();
+                assert_eq!(
+                    result,
+                    r#"[{"anchor":35,"position":30},{"anchor":2,"position":8},{"anchor":12,"position":12}]"#
+                );
+            });
+    }
+
+    #[test]
+    fn test_api_show_quick_pick_sends_command() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._pick = editor.showQuickPick(
+                [{ label: "Alpha", description: "first" }, { label: "Beta", value: "b" }],
+                { title: "Pick one: ", matchOnDescription: true }
+            );
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::StartQuickPickAsync {
+                title,
+                items,
+                match_on_description,
+                ..
+            } => {
+                assert_eq!(title, "Pick one: ");
+                assert!(match_on_description);
+                assert_eq!(items.len(), 2);
+                assert_eq!(items[0].label, "Alpha");
+                assert_eq!(items[0].description.as_deref(), Some("first"));
+                assert_eq!(items[1].value.as_deref(), Some("b"));
+            }
+            cmd => panic!("Expected StartQuickPickAsync, got {:?}", cmd),
+        }
+    }
+
+    #[test]
+    fn test_api_set_quickfix_list_sends_command() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.setQuickfixList(
+                "rg todo",
+                [{ file: "src/a.rs", line: 3, column: 5, text: "// todo" }, { file: "b.rs", line: 1 }],
+                1
+            );
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::SetQuickfixList {
+                title,
+                entries,
+                selected,
+            } => {
+                assert_eq!(title, "rg todo");
+                assert_eq!(entries.len(), 2);
+                assert_eq!(entries[0].file, "src/a.rs");
+                assert_eq!(entries[0].column, Some(5));
+                assert_eq!(entries[0].text.as_deref(), Some("// todo"));
+                assert_eq!(entries[1].column, None);
+                assert_eq!(selected, Some(1));
+            }
+            cmd => panic!("Expected SetQuickfixList, got {:?}", cmd),
+        }
+    }
+
+    #[test]
+    fn test_api_marks_send_commands() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.setMark("a");
+            editor.jumpToMark("A");
+            editor.jumpToMark("a", true);
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::SetMark { name } => assert_eq!(name, "a"),
+            cmd => panic!("Expected SetMark, got {:?}", cmd),
+        }
+        match rx.try_recv().unwrap() {
+            PluginCommand::JumpToMark { name, line_start } => {
+                assert_eq!(name, "A");
+                assert!(!line_start);
+            }
+            cmd => panic!("Expected JumpToMark, got {:?}", cmd),
+        }
+        match rx.try_recv().unwrap() {
+            PluginCommand::JumpToMark { line_start, .. } => assert!(line_start),
+            cmd => panic!("Expected JumpToMark, got {:?}", cmd),
+        }
+    }
+
+    #[test]
+    fn test_api_set_exit_code() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.setExitCode(1);
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::SetExitCode { code } => assert_eq!(code, 1),
+            cmd => panic!("Expected SetExitCode, got {:?}", cmd),
+        }
+    }
+
+    #[test]
+    fn test_api_show_input_box_defaults() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._input = editor.showInputBox();
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::StartPromptAsync {
+                label,
+                initial_value,
+                ..
+            } => {
+                assert_eq!(label, "");
+                assert_eq!(initial_value, "");
+            }
+            cmd => panic!("Expected StartPromptAsync, got {:?}", cmd),
+        }
+    }
+
+    #[test]
+    fn test_api_get_buffer_text_resolves_callback() {
+        let (mut backend, rx) = create_test_backend();
+
+        // Call getBufferText and set up a handler for when it resolves
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._resolvedText = null;
+            editor.getBufferText(0, 0, 100).then(text => {
+                globalThis._resolvedText = text;
+            });
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        // Get the request_id from the command
+        let request_id = match rx.try_recv().unwrap() {
+            PluginCommand::GetBufferText { request_id, .. } => request_id,
+            cmd => panic!("Expected GetBufferText, got {:?}", cmd),
+        };
+
+        // Simulate the editor responding with the text
+        backend.resolve_callback(JsCallbackId::from(request_id), "\"hello world\"");
+
+        // Drive the Promise to completion
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                run_pending_jobs_checked(&ctx, "test async getText");
+            });
+
+        // Verify the Promise resolved with the text
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let global = ctx.globals();
+                let result: String = global.get("_resolvedText").unwrap();
+                assert_eq!(result, "hello world");
+            });
+    }
+
+    #[test]
+    fn test_plugin_translation() {
+        let (mut backend, _rx) = create_test_backend();
+
+        // The t() function should work (returns key if translation not found)
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._translated = editor.t("test.key");
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let global = ctx.globals();
+                // Without actual translations, it returns the key
+                let result: String = global.get("_translated").unwrap();
+                assert_eq!(result, "test.key");
+            });
+    }
+
+    #[test]
+    fn test_plugin_locale_formatting() {
+        let (mut backend, _rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._number = editor.formatNumber(1234.5, 2);
+            globalThis._date = editor.formatDate("iso_date", 0);
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let global = ctx.globals();
+                // The test bridge uses the default, locale-less formatting
+                let number: String = global.get("_number").unwrap();
+                assert_eq!(number, "1234.50");
+                let date: Option<String> = global.get("_date").unwrap();
+                assert_eq!(date, None);
+            });
+    }
+
+    #[test]
+    fn test_plugin_translation_with_registered_strings() {
+        let (mut backend, _rx) = create_test_backend();
+
+        // Register translations for the test plugin
+        let mut en_strings = std::collections::HashMap::new();
+        en_strings.insert("greeting".to_string(), "Hello, World!".to_string());
+        en_strings.insert("prompt.find_file".to_string(), "Find file: ".to_string());
+
+        let mut strings = std::collections::HashMap::new();
+        strings.insert("en".to_string(), en_strings);
 
         // Register for "test" plugin
         if let Some(bridge) = backend
@@ -4861,6 +5969,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_api_set_theme_color() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.setThemeColor("editor.bg", [10, 20, 30]);
+            editor.setThemeColor("syntax.keyword", "Red");
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::SetThemeColor { key, color } => {
+                assert_eq!(key, "editor.bg");
+                assert_eq!(color, serde_json::json!([10, 20, 30]));
+            }
+            cmd => panic!("Expected SetThemeColor, got {:?}", cmd),
+        }
+        match rx.try_recv().unwrap() {
+            PluginCommand::SetThemeColor { key, color } => {
+                assert_eq!(key, "syntax.keyword");
+                assert_eq!(color, serde_json::json!("Red"));
+            }
+            cmd => panic!("Expected SetThemeColor, got {:?}", cmd),
+        }
+    }
+
     // ==================== Buffer Operations Tests ====================
 
     #[test]
@@ -5154,6 +6293,137 @@ mod tests {
             });
     }
 
+    #[test]
+    fn test_api_file_exists_requires_fs_read() {
+        let (mut backend, _rx) = create_test_backend();
+        backend
+            .permissions
+            .borrow_mut()
+            .set_declared("test", Some(vec![]));
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            try {
+                editor.fileExists("/");
+                globalThis._error = "";
+            } catch (e) {
+                globalThis._error = String(e);
+            }
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let global = ctx.globals();
+                let error: String = global.get("_error").unwrap();
+                assert!(error.contains("fs-read"), "got: {}", error);
+            });
+    }
+
+    #[test]
+    fn test_api_open_file_requires_fs_read_unless_open() {
+        let (mut backend, rx) = create_test_backend();
+        backend
+            .permissions
+            .borrow_mut()
+            .set_declared("test", Some(vec![]));
+        backend.state_snapshot.write().unwrap().buffers.insert(
+            BufferId(1),
+            BufferInfo {
+                id: BufferId(1),
+                path: Some(PathBuf::from("/open/file.rs")),
+                modified: false,
+                length: 0,
+            },
+        );
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.openFile("/open/file.rs", null, null);
+            try {
+                editor.openFile("/home/user/.ssh/id_rsa", null, null);
+                globalThis._error = "";
+            } catch (e) {
+                globalThis._error = String(e);
+            }
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::OpenFileAtLocation { path, .. } => {
+                assert_eq!(path, PathBuf::from("/open/file.rs"));
+            }
+            cmd => panic!("Expected OpenFileAtLocation, got {:?}", cmd),
+        }
+        assert!(rx.try_recv().is_err(), "unopened file must not be opened");
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let error: String = ctx.globals().get("_error").unwrap();
+                assert!(error.contains("fs-read"), "got: {}", error);
+            });
+    }
+
+    #[test]
+    fn test_api_save_action_requires_fs_write() {
+        let (mut backend, rx) = create_test_backend();
+        backend
+            .permissions
+            .borrow_mut()
+            .set_declared("test", Some(vec![]));
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.executeAction("move_down");
+            try {
+                editor.executeAction("save");
+                globalThis._error = "";
+            } catch (e) {
+                globalThis._error = String(e);
+            }
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::ExecuteAction { action_name } => assert_eq!(action_name, "move_down"),
+            cmd => panic!("Expected ExecuteAction, got {:?}", cmd),
+        }
+        assert!(rx.try_recv().is_err(), "save must not be sent");
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let error: String = ctx.globals().get("_error").unwrap();
+                assert!(error.contains("fs-write"), "got: {}", error);
+            });
+    }
+
     #[test]
     fn test_api_get_cwd() {
         let (mut backend, _rx) = create_test_backend();
@@ -5575,4 +6845,267 @@ mod tests {
                 assert!(error.contains("nonexistent-plugin"));
             });
     }
+
+    #[test]
+    fn test_api_run_task_reports_progress_and_result() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._taskResult = null;
+            editor.runTask("Indexing", (task) => {
+                task.progress("halfway", 49.6);
+                return { files: 3 };
+            }).then(result => {
+                globalThis._taskResult = result.files;
+            });
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let task_id = match rx.try_recv().unwrap() {
+            PluginCommand::StartTask { task_id, name, .. } => {
+                assert_eq!(name, "Indexing");
+                task_id
+            }
+            cmd => panic!("Expected StartTask, got {:?}", cmd),
+        };
+
+        // The job runs as a promise job, not synchronously inside runTask
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                run_pending_jobs_checked(&ctx, "test runTask job");
+            });
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::UpdateTaskProgress {
+                task_id: id,
+                message,
+                percentage,
+            } => {
+                assert_eq!(id, task_id);
+                assert_eq!(message.as_deref(), Some("halfway"));
+                assert_eq!(percentage, Some(50));
+            }
+            cmd => panic!("Expected UpdateTaskProgress, got {:?}", cmd),
+        }
+        match rx.try_recv().unwrap() {
+            PluginCommand::FinishTask {
+                task_id: id,
+                result,
+                error,
+            } => {
+                assert_eq!(id, task_id);
+                assert_eq!(result, Some(serde_json::json!({ "files": 3 })));
+                assert!(error.is_none());
+            }
+            cmd => panic!("Expected FinishTask, got {:?}", cmd),
+        }
+
+        // Simulate the editor handing the result back
+        backend.resolve_callback(task_id, r#"{"files":3}"#);
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                run_pending_jobs_checked(&ctx, "test runTask result");
+                let result: i32 = ctx.globals().get("_taskResult").unwrap();
+                assert_eq!(result, 3);
+            });
+    }
+
+    #[test]
+    fn test_api_run_task_cancel_rejects_and_flags_job() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._task = null;
+            globalThis._taskError = null;
+            editor.runTask("Slow", (task) => {
+                globalThis._task = task;
+                return new Promise(() => {});
+            }).catch(err => {
+                globalThis._taskError = err.message || String(err);
+            });
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let task_id = match rx.try_recv().unwrap() {
+            PluginCommand::StartTask { task_id, .. } => task_id,
+            cmd => panic!("Expected StartTask, got {:?}", cmd),
+        };
+
+        backend.cancel_task(task_id);
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                run_pending_jobs_checked(&ctx, "test runTask cancel");
+                let global = ctx.globals();
+                let error: String = global.get("_taskError").unwrap();
+                assert!(error.contains("Task cancelled"));
+                let cancelled: bool = ctx.eval("globalThis._task.cancelled").unwrap();
+                assert!(cancelled);
+            });
+    }
+
+    /// Write a Lua plugin package into a fresh temp directory and return its entry
+    #[cfg(feature = "lua")]
+    fn write_lua_plugin(test_name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("fresh-lua-{}-{}", test_name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, content) in files {
+            std::fs::write(dir.join(name), content).unwrap();
+        }
+        dir.join(files[0].0)
+    }
+
+    #[cfg(feature = "lua")]
+    fn expect_status(rx: &mpsc::Receiver<PluginCommand>) -> String {
+        loop {
+            match rx.try_recv().unwrap() {
+                PluginCommand::SetStatus { message } => return message,
+                PluginCommand::RegisterCommand { .. } => continue,
+                cmd => panic!("Expected SetStatus, got {:?}", cmd),
+            }
+        }
+    }
+
+    #[cfg(feature = "lua")]
+    #[tokio::test]
+    async fn test_lua_plugin_registers_commands_and_events() {
+        let (mut backend, rx) = create_test_backend();
+        let path = write_lua_plugin(
+            "commands",
+            &[(
+                "greeter.lua",
+                r#"
+            function greet()
+                editor.setStatus("hello from " .. editor.getCwd() .. " lua")
+            end
+            function on_saved(data)
+                editor.setStatus("saved " .. data.path)
+            end
+            editor.registerCommand("Greet", "Say hello", "greet")
+            editor.on("buffer_saved", "on_saved")
+        "#,
+            )],
+        );
+        backend.load_lua_plugin(&path).unwrap();
+
+        match rx.try_recv().unwrap() {
+            PluginCommand::RegisterCommand { command } => {
+                assert_eq!(command.action_name, "greet");
+                assert_eq!(command.plugin_name, "greeter");
+            }
+            cmd => panic!("Expected RegisterCommand, got {:?}", cmd),
+        }
+
+        backend.start_action("greet").unwrap();
+        assert!(expect_status(&rx).starts_with("hello from "));
+
+        backend
+            .emit("buffer_saved", &serde_json::json!({ "path": "/tmp/a.lua" }))
+            .await
+            .unwrap();
+        assert_eq!(expect_status(&rx), "saved /tmp/a.lua");
+    }
+
+    #[cfg(feature = "lua")]
+    #[test]
+    fn test_lua_plugin_async_call_suspends_coroutine() {
+        let (mut backend, rx) = create_test_backend();
+        let path = write_lua_plugin(
+            "async",
+            &[(
+                "reader.lua",
+                r#"
+            function show()
+                local text = editor.getBufferText(0, 0, 5)
+                editor.setStatus("got " .. text)
+            end
+            editor.registerCommand("Show", "Show text", "show")
+        "#,
+            )],
+        );
+        backend.load_lua_plugin(&path).unwrap();
+        backend.start_action("show").unwrap();
+
+        let request_id = loop {
+            match rx.try_recv().unwrap() {
+                PluginCommand::GetBufferText { request_id, .. } => break request_id,
+                PluginCommand::RegisterCommand { .. } => continue,
+                cmd => panic!("Expected GetBufferText, got {:?}", cmd),
+            }
+        };
+        // Nothing happens until the editor answers
+        assert!(rx.try_recv().is_err());
+
+        backend.resolve_callback(JsCallbackId::from(request_id), "\"hello\"");
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("reader")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                run_pending_jobs_checked(&ctx, "test lua async");
+            });
+        assert_eq!(expect_status(&rx), "got hello");
+    }
+
+    #[cfg(feature = "lua")]
+    #[test]
+    fn test_lua_plugin_is_sandboxed() {
+        let (mut backend, rx) = create_test_backend();
+        let path = write_lua_plugin(
+            "sandbox",
+            &[
+                (
+                    "boxed.lua",
+                    r#"
+            local util = require("util")
+            local escaped = pcall(require, "..secret")
+            editor.setStatus(table.concat({
+                tostring(io), tostring(os), tostring(package), tostring(debug),
+                tostring(escaped), util.name,
+            }, ","))
+        "#,
+                ),
+                ("util.lua", r#"return { name = "util" }"#),
+            ],
+        );
+        backend.load_lua_plugin(&path).unwrap();
+        assert_eq!(expect_status(&rx), "nil,nil,nil,nil,false,util");
+    }
+
+    #[cfg(feature = "lua")]
+    #[test]
+    fn test_lua_plugin_load_error_is_reported() {
+        let (mut backend, _rx) = create_test_backend();
+        let path = write_lua_plugin("error", &[("broken.lua", "error('boom')")]);
+        let err = backend.load_lua_plugin(&path).unwrap_err().to_string();
+        assert!(err.contains("boom"), "unexpected error: {}", err);
+    }
 }
@@ -0,0 +1,284 @@
+//! Plugin sandbox permissions
+//!
+//! Plugins shipped as packages (a `package.json` next to the plugin file) must
+//! declare the sensitive capabilities they use in the manifest's `permissions`
+//! array. Plugins without a manifest (bundled and user scripts) are trusted and
+//! never checked.
+//!
+//! The first time a packaged plugin uses a declared permission the editor asks
+//! the user; the answer is remembered for the session, and "always" answers are
+//! persisted in the plugin's config entry.
+
+use fresh_core::api::PluginCommand;
+use fresh_core::config::PluginPermission;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Outcome of a permission check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermissionCheck {
+    /// The call may proceed
+    Allowed,
+    /// The user hasn't answered yet; `first_request` is true for the call that
+    /// should trigger the prompt
+    Pending { first_request: bool },
+    /// The call must fail with this message
+    Denied(String),
+}
+
+/// Permission state for all sandboxed plugins
+#[derive(Debug, Default)]
+pub struct PluginPermissions {
+    /// Declared permissions, keyed by plugin name (only sandboxed plugins)
+    declared: HashMap<String, HashSet<PluginPermission>>,
+    /// Permissions granted by the user (persisted or for this session)
+    granted: HashMap<String, HashSet<PluginPermission>>,
+    /// Permissions the user refused for this session
+    denied: HashMap<String, HashSet<PluginPermission>>,
+    /// Async commands held back until the user answers the prompt
+    deferred: HashMap<(String, PluginPermission), Vec<(u64, PluginCommand)>>,
+    /// Requests that already have a prompt on screen or queued
+    pending: HashSet<(String, PluginPermission)>,
+}
+
+impl PluginPermissions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the permissions a plugin declares, or `None` for a trusted plugin
+    pub fn set_declared(&mut self, plugin_name: &str, declared: Option<Vec<PluginPermission>>) {
+        match declared {
+            Some(perms) => {
+                self.declared
+                    .insert(plugin_name.to_string(), perms.into_iter().collect());
+            }
+            None => {
+                self.declared.remove(plugin_name);
+            }
+        }
+    }
+
+    /// Seed the permissions the user granted permanently (from config)
+    pub fn set_granted(&mut self, plugin_name: &str, granted: &[PluginPermission]) {
+        self.granted
+            .entry(plugin_name.to_string())
+            .or_default()
+            .extend(granted.iter().copied());
+    }
+
+    /// Whether a plugin runs in the sandbox
+    pub fn is_sandboxed(&self, plugin_name: &str) -> bool {
+        self.declared.contains_key(plugin_name)
+    }
+
+    /// Check whether `plugin_name` may use `permission` right now
+    pub fn check(&mut self, plugin_name: &str, permission: PluginPermission) -> PermissionCheck {
+        let Some(declared) = self.declared.get(plugin_name) else {
+            return PermissionCheck::Allowed;
+        };
+        if !declared.contains(&permission) {
+            return PermissionCheck::Denied(format!(
+                "Plugin '{}' did not declare the '{}' permission in its package.json",
+                plugin_name, permission
+            ));
+        }
+        if self
+            .granted
+            .get(plugin_name)
+            .is_some_and(|g| g.contains(&permission))
+        {
+            return PermissionCheck::Allowed;
+        }
+        if self
+            .denied
+            .get(plugin_name)
+            .is_some_and(|d| d.contains(&permission))
+        {
+            return PermissionCheck::Denied(format!(
+                "Permission '{}' was denied for plugin '{}'",
+                permission, plugin_name
+            ));
+        }
+        let first_request = self.pending.insert((plugin_name.to_string(), permission));
+        PermissionCheck::Pending { first_request }
+    }
+
+    /// Hold an async command until the user answers the permission prompt
+    pub fn defer(
+        &mut self,
+        plugin_name: &str,
+        permission: PluginPermission,
+        callback_id: u64,
+        command: PluginCommand,
+    ) {
+        self.deferred
+            .entry((plugin_name.to_string(), permission))
+            .or_default()
+            .push((callback_id, command));
+    }
+
+    /// Record the user's answer and return the commands that were waiting on it
+    pub fn resolve(
+        &mut self,
+        plugin_name: &str,
+        permission: PluginPermission,
+        granted: bool,
+    ) -> Vec<(u64, PluginCommand)> {
+        let key = (plugin_name.to_string(), permission);
+        self.pending.remove(&key);
+        let target = if granted {
+            &mut self.granted
+        } else {
+            &mut self.denied
+        };
+        target
+            .entry(plugin_name.to_string())
+            .or_default()
+            .insert(permission);
+        self.deferred.remove(&key).unwrap_or_default()
+    }
+
+    /// Forget session state for an unloaded plugin
+    ///
+    /// Persisted grants are kept so a reload doesn't prompt again.
+    pub fn forget_session(&mut self, plugin_name: &str) {
+        self.denied.remove(plugin_name);
+        self.pending.retain(|(name, _)| name != plugin_name);
+        self.deferred.retain(|(name, _), _| name != plugin_name);
+    }
+}
+
+/// Read the permissions declared by the package that contains a plugin
+///
+/// Returns `None` when there is no `package.json` next to the plugin, which
+/// marks it as trusted. Unknown permission names are ignored.
+pub fn declared_permissions(plugin_path: &Path) -> Option<Vec<PluginPermission>> {
+    let manifest = plugin_path.parent()?.join("package.json");
+    let content = std::fs::read_to_string(&manifest).ok()?;
+    let json: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(json) => json,
+        Err(e) => {
+            // A broken manifest still marks the plugin as packaged
+            tracing::warn!("Failed to parse {:?}: {}", manifest, e);
+            return Some(Vec::new());
+        }
+    };
+    let declared = json
+        .get("permissions")
+        .and_then(|p| p.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let permission = serde_json::from_value(item.clone()).ok();
+                    if permission.is_none() {
+                        tracing::warn!("Unknown plugin permission {} in {:?}", item, manifest);
+                    }
+                    permission
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(declared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trusted_plugins_are_never_checked() {
+        let mut perms = PluginPermissions::new();
+        assert_eq!(
+            perms.check("bundled", PluginPermission::Process),
+            PermissionCheck::Allowed
+        );
+    }
+
+    #[test]
+    fn undeclared_permission_is_denied() {
+        let mut perms = PluginPermissions::new();
+        perms.set_declared("pkg", Some(vec![PluginPermission::FsRead]));
+        assert!(matches!(
+            perms.check("pkg", PluginPermission::FsWrite),
+            PermissionCheck::Denied(_)
+        ));
+    }
+
+    #[test]
+    fn declared_permission_prompts_once_then_follows_answer() {
+        let mut perms = PluginPermissions::new();
+        perms.set_declared("pkg", Some(vec![PluginPermission::Process]));
+
+        assert_eq!(
+            perms.check("pkg", PluginPermission::Process),
+            PermissionCheck::Pending {
+                first_request: true
+            }
+        );
+        assert_eq!(
+            perms.check("pkg", PluginPermission::Process),
+            PermissionCheck::Pending {
+                first_request: false
+            }
+        );
+
+        perms.defer(
+            "pkg",
+            PluginPermission::Process,
+            7,
+            PluginCommand::SetStatus {
+                message: "held".to_string(),
+            },
+        );
+        let released = perms.resolve("pkg", PluginPermission::Process, true);
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].0, 7);
+        assert_eq!(
+            perms.check("pkg", PluginPermission::Process),
+            PermissionCheck::Allowed
+        );
+    }
+
+    #[test]
+    fn persisted_grants_skip_the_prompt_and_denials_stick() {
+        let mut perms = PluginPermissions::new();
+        perms.set_declared(
+            "pkg",
+            Some(vec![PluginPermission::FsRead, PluginPermission::FsWrite]),
+        );
+        perms.set_granted("pkg", &[PluginPermission::FsRead]);
+        assert_eq!(
+            perms.check("pkg", PluginPermission::FsRead),
+            PermissionCheck::Allowed
+        );
+
+        perms.check("pkg", PluginPermission::FsWrite);
+        perms.resolve("pkg", PluginPermission::FsWrite, false);
+        assert!(matches!(
+            perms.check("pkg", PluginPermission::FsWrite),
+            PermissionCheck::Denied(_)
+        ));
+    }
+
+    #[test]
+    fn declared_permissions_reads_package_manifest() {
+        let dir = std::env::temp_dir().join(format!("fresh-permissions-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let plugin = dir.join("plugin.ts");
+        assert_eq!(declared_permissions(&plugin), None);
+
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"name": "pkg", "permissions": ["fs-read", "process", "teleport"]}"#,
+        )
+        .unwrap();
+        let declared = declared_permissions(&plugin);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            declared,
+            Some(vec![PluginPermission::FsRead, PluginPermission::Process])
+        );
+    }
+}
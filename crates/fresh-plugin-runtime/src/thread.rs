@@ -46,6 +46,18 @@ pub enum PluginRequest {
         error: String,
     },
 
+    /// Cancel a plugin background task
+    CancelTask {
+        task_id: fresh_core::api::JsCallbackId,
+    },
+
+    /// Deliver the user's answer to a plugin permission prompt
+    ResolvePermission {
+        plugin_name: String,
+        permission: fresh_core::config::PluginPermission,
+        granted: bool,
+    },
+
     /// Load all plugins from a directory
     LoadPluginsFromDir {
         dir: PathBuf,
@@ -82,6 +94,13 @@ pub enum PluginRequest {
     /// Run a hook (fire-and-forget, no response needed)
     RunHook { hook_name: String, args: HookArgs },
 
+    /// Run a hook and respond once every handler has returned
+    RunHookBlocking {
+        hook_name: String,
+        args: HookArgs,
+        response: oneshot::Sender<()>,
+    },
+
     /// Check if any handlers are registered for a hook
     HasHookHandlers {
         hook_name: String,
@@ -93,6 +112,9 @@ pub enum PluginRequest {
         response: oneshot::Sender<Vec<TsPluginInfo>>,
     },
 
+    /// Bytes allocated by the JavaScript runtime
+    MemoryUsage { response: oneshot::Sender<usize> },
+
     /// Shutdown the plugin thread
     Shutdown,
 }
@@ -497,6 +519,36 @@ impl PluginThreadHandle {
         }
     }
 
+    /// Run a hook and wait (up to `timeout`) for its handlers to return.
+    ///
+    /// Used for hooks like `will_save` where the editor must not continue until
+    /// plugins have had a chance to queue their commands. Any commands sent by
+    /// the handlers are already in the command channel when this returns.
+    /// Returns false if the plugin thread did not answer in time.
+    pub fn run_hook_blocking(
+        &self,
+        hook_name: &str,
+        args: HookArgs,
+        timeout: std::time::Duration,
+    ) -> bool {
+        let (tx, rx) = oneshot::channel();
+        let Some(sender) = self.request_sender.as_ref() else {
+            return false;
+        };
+        if sender
+            .send(PluginRequest::RunHookBlocking {
+                hook_name: hook_name.to_string(),
+                args,
+                response: tx,
+            })
+            .is_err()
+        {
+            return false;
+        }
+
+        rx.recv_timeout(timeout).is_ok()
+    }
+
     /// Check if any handlers are registered for a hook (blocking)
     pub fn has_hook_handlers(&self, hook_name: &str) -> bool {
         let (tx, rx) = oneshot::channel();
@@ -532,6 +584,19 @@ impl PluginThreadHandle {
         rx.recv().unwrap_or_default()
     }
 
+    /// Bytes allocated by the JavaScript runtime (blocking)
+    ///
+    /// Returns None if the plugin thread isn't running or doesn't answer
+    /// within a second because a plugin is busy.
+    pub fn memory_usage(&self) -> Option<usize> {
+        let (tx, rx) = oneshot::channel();
+        self.request_sender
+            .as_ref()?
+            .send(PluginRequest::MemoryUsage { response: tx })
+            .ok()?;
+        rx.recv_timeout(std::time::Duration::from_secs(1)).ok()
+    }
+
     /// Process pending plugin commands (non-blocking)
     ///
     /// Returns immediately with any pending commands by polling the command queue directly.
@@ -608,6 +673,29 @@ impl PluginThreadHandle {
             let _ = sender.send(PluginRequest::RejectCallback { callback_id, error });
         }
     }
+
+    /// Cancel a plugin background task started with `runTask`
+    pub fn cancel_task(&self, task_id: fresh_core::api::JsCallbackId) {
+        if let Some(sender) = self.request_sender.as_ref() {
+            let _ = sender.send(PluginRequest::CancelTask { task_id });
+        }
+    }
+
+    /// Deliver the user's answer to a plugin permission prompt
+    pub fn resolve_permission(
+        &self,
+        plugin_name: String,
+        permission: fresh_core::config::PluginPermission,
+        granted: bool,
+    ) {
+        if let Some(sender) = self.request_sender.as_ref() {
+            let _ = sender.send(PluginRequest::ResolvePermission {
+                plugin_name,
+                permission,
+                granted,
+            });
+        }
+    }
 }
 
 impl Drop for PluginThreadHandle {
@@ -909,6 +997,19 @@ async fn handle_request(
             }
         }
 
+        PluginRequest::RunHookBlocking {
+            hook_name,
+            args,
+            response,
+        } => {
+            if let Err(e) = run_hook_internal_rc(Rc::clone(&runtime), &hook_name, &args).await {
+                let error_msg = format!("Plugin error in '{}': {}", hook_name, e);
+                tracing::error!("{}", error_msg);
+                runtime.borrow_mut().send_status(error_msg);
+            }
+            let _ = response.send(());
+        }
+
         PluginRequest::HasHookHandlers {
             hook_name,
             response,
@@ -922,6 +1023,10 @@ async fn handle_request(
             let _ = response.send(plugin_list);
         }
 
+        PluginRequest::MemoryUsage { response } => {
+            let _ = response.send(runtime.borrow().memory_usage());
+        }
+
         PluginRequest::ResolveCallback {
             callback_id,
             result_json,
@@ -946,6 +1051,20 @@ async fn handle_request(
             // reject_callback now runs execute_pending_job() internally
         }
 
+        PluginRequest::CancelTask { task_id } => {
+            runtime.borrow_mut().cancel_task(task_id);
+        }
+
+        PluginRequest::ResolvePermission {
+            plugin_name,
+            permission,
+            granted,
+        } => {
+            runtime
+                .borrow_mut()
+                .resolve_permission(&plugin_name, permission, granted);
+        }
+
         PluginRequest::Shutdown => {
             tracing::info!("Plugin thread received shutdown request");
             return true;
@@ -1002,6 +1121,21 @@ async fn load_plugin_internal(
         }
     }
 
+    // Plugins that ship a package.json run sandboxed with their declared permissions
+    let declared = crate::permissions::declared_permissions(path);
+    if let Some(ref perms) = declared {
+        tracing::info!(
+            "Plugin '{}' is sandboxed with permissions {:?}",
+            plugin_name,
+            perms
+        );
+    }
+    runtime
+        .borrow()
+        .permissions
+        .borrow_mut()
+        .set_declared(&plugin_name, declared);
+
     let load_start = std::time::Instant::now();
     runtime
         .borrow_mut()
@@ -1034,6 +1168,25 @@ async fn load_plugin_internal(
     Ok(())
 }
 
+/// Entry file of a Lua plugin package in `dir`
+///
+/// Lua packages set `"fresh": {"runtime": "lua"}` in their package.json; the
+/// entry is `fresh.entry`, defaulting to `<name>.lua`. Other `.lua` files in
+/// the package are modules for `require`, not plugins.
+fn lua_package_entry(dir: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let fresh = manifest.get("fresh")?;
+    if fresh.get("runtime").and_then(|r| r.as_str()) != Some("lua") {
+        return None;
+    }
+    let entry = match fresh.get("entry").and_then(|e| e.as_str()) {
+        Some(entry) => entry.to_string(),
+        None => format!("{}.lua", manifest.get("name")?.as_str()?),
+    };
+    Some(dir.join(entry))
+}
+
 /// Load all plugins from a directory
 async fn load_plugins_from_dir_internal(
     runtime: Rc<RefCell<QuickJsBackend>>,
@@ -1071,6 +1224,14 @@ async fn load_plugins_from_dir_internal(
                 }
             }
 
+            if let Some(path) = lua_package_entry(dir) {
+                if let Err(e) = load_plugin_internal(Rc::clone(&runtime), plugins, &path).await {
+                    let err = format!("Failed to load {:?}: {}", path, e);
+                    tracing::error!("{}", err);
+                    errors.push(err);
+                }
+            }
+
             tracing::debug!(
                 "load_plugins_from_dir_internal: finished loading from {:?}, {} errors",
                 dir,
@@ -1129,6 +1290,14 @@ async fn load_plugins_from_dir_with_config_internal(
                     plugin_files.push((plugin_name, path));
                 }
             }
+            if let Some(path) = lua_package_entry(dir) {
+                let plugin_name = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                plugin_files.push((plugin_name, path));
+            }
         }
         Err(e) => {
             let err = format!("Failed to read plugin directory: {}", e);
@@ -1138,6 +1307,15 @@ async fn load_plugins_from_dir_with_config_internal(
         }
     }
 
+    // Seed permanently granted permissions before any plugin code runs
+    for (plugin_name, config) in plugin_configs {
+        runtime
+            .borrow()
+            .permissions
+            .borrow_mut()
+            .set_granted(plugin_name, &config.granted_permissions);
+    }
+
     // Second pass: build discovered_plugins map and load enabled plugins
     for (plugin_name, path) in plugin_files {
         // Check if we have an existing config for this plugin
@@ -1146,6 +1324,7 @@ async fn load_plugins_from_dir_with_config_internal(
             PluginConfig {
                 enabled: existing_config.enabled,
                 path: Some(path.clone()),
+                granted_permissions: existing_config.granted_permissions.clone(),
             }
         } else {
             // Create new config with default enabled = true
@@ -1204,6 +1383,19 @@ fn unload_plugin_internal(
             .services
             .unregister_commands_by_plugin(name);
 
+        // Drop event subscriptions so a reload doesn't register them twice
+        runtime.borrow().remove_plugin_handlers(name);
+
+        // Start a reload from a clean slate instead of the old module state
+        runtime.borrow().remove_plugin_context(name);
+
+        // Session-only permission answers don't survive an unload
+        runtime
+            .borrow()
+            .permissions
+            .borrow_mut()
+            .forget_session(name);
+
         Ok(())
     } else {
         Err(anyhow!("Plugin '{}' not found", name))
@@ -1258,4 +1450,26 @@ mod tests {
         assert_eq!(parsed["prompt_type"], "search");
         assert_eq!(parsed["input"], "test");
     }
+
+    #[test]
+    fn test_lua_package_entry_requires_lua_runtime() {
+        let dir = std::env::temp_dir().join(format!("fresh-lua-entry-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest = dir.join("package.json");
+
+        std::fs::write(&manifest, r#"{"name": "pkg", "fresh": {}}"#).unwrap();
+        assert_eq!(lua_package_entry(&dir), None);
+
+        std::fs::write(&manifest, r#"{"name": "pkg", "fresh": {"runtime": "lua"}}"#).unwrap();
+        assert_eq!(lua_package_entry(&dir), Some(dir.join("pkg.lua")));
+
+        std::fs::write(
+            &manifest,
+            r#"{"name": "pkg", "fresh": {"runtime": "lua", "entry": "src/main.lua"}}"#,
+        )
+        .unwrap();
+        let entry = lua_package_entry(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(entry, Some(dir.join("src/main.lua")));
+    }
 }
@@ -19,10 +19,10 @@ use fresh_core::api::{
     BufferSavedDiff, CompositeHunk, CompositeLayoutConfig, CompositePaneStyle,
     CompositeSourceConfig, CreateCompositeBufferOptions, CreateVirtualBufferInExistingSplitOptions,
     CreateVirtualBufferInSplitOptions, CreateVirtualBufferOptions, CursorInfo, DirEntry,
-    FormatterPackConfig, JsDiagnostic, JsPosition, JsRange, JsTextPropertyEntry,
-    LanguagePackConfig, LayoutHints, LspServerPackConfig, SpawnResult, TextPropertiesAtCursor,
-    TsHighlightSpan, ViewTokenStyle, ViewTokenWire, ViewTokenWireKind, ViewportInfo,
-    VirtualBufferResult,
+    FormatterPackConfig, InputBoxOptions, JsDiagnostic, JsPosition, JsRange, JsTextPropertyEntry,
+    LanguagePackConfig, LayoutHints, LspServerPackConfig, QuickPickItem, QuickPickOptions,
+    QuickfixEntry, SpawnResult, TextPropertiesAtCursor, TextRange, TextSelection, TsHighlightSpan,
+    ViewTokenStyle, ViewTokenWire, ViewTokenWireKind, ViewportInfo, VirtualBufferResult,
 };
 use fresh_core::command::Suggestion;
 use fresh_core::file_explorer::FileExplorerDecoration;
@@ -42,6 +42,8 @@ fn get_type_decl(type_name: &str) -> Option<String> {
         "ActionSpec" => Some(ActionSpec::decl()),
         "BufferSavedDiff" => Some(BufferSavedDiff::decl()),
         "LayoutHints" => Some(LayoutHints::decl()),
+        "TextRange" => Some(TextRange::decl()),
+        "TextSelection" => Some(TextSelection::decl()),
 
         // Process types
         "SpawnResult" => Some(SpawnResult::decl()),
@@ -64,6 +66,10 @@ fn get_type_decl(type_name: &str) -> Option<String> {
         // UI types (ts-rs renames these with Ts prefix)
         "TsActionPopupAction" | "ActionPopupAction" => Some(ActionPopupAction::decl()),
         "ActionPopupOptions" => Some(ActionPopupOptions::decl()),
+        "QuickPickItem" => Some(QuickPickItem::decl()),
+        "QuickfixEntry" => Some(QuickfixEntry::decl()),
+        "QuickPickOptions" => Some(QuickPickOptions::decl()),
+        "InputBoxOptions" => Some(InputBoxOptions::decl()),
         "TsHighlightSpan" => Some(TsHighlightSpan::decl()),
         "FileExplorerDecoration" => Some(FileExplorerDecoration::decl()),
 
@@ -41,6 +41,7 @@ export default defineConfig({
           { text: "Themes", link: "/features/themes" },
           { text: "Encoding", link: "/features/encoding" },
           { text: "Remote Editing (SSH)", link: "/features/ssh" },
+          { text: "Accessibility", link: "/features/accessibility" },
         ],
       },
       {
@@ -71,6 +72,7 @@ export default defineConfig({
               { text: "Common Patterns", link: "/plugins/development/patterns" },
               { text: "Utilities Library", link: "/plugins/development/utilities" },
               { text: "Internationalization", link: "/plugins/development/i18n" },
+              { text: "Testing", link: "/plugins/development/testing" },
             ],
           },
           {